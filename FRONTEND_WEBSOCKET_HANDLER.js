@@ -72,6 +72,7 @@ class VideoEditorWebSocket {
           this.onMessage({
             role: 'assistant',
             content: data.content,
+            outputs: data.outputs || [],
             timestamp: data.timestamp
           });
         }
@@ -129,7 +130,7 @@ function initializeChat() {
 
   // Handle AI messages (add to chat)
   ws.onMessage = (message) => {
-    addMessageToChat('assistant', message.content, message.timestamp);
+    addMessageToChat('assistant', message.content, message.timestamp, message.outputs);
     hideTypingIndicator();
   };
 
@@ -176,7 +177,7 @@ function initializeChat() {
 }
 
 // Helper: Add message to chat
-function addMessageToChat(role, content, timestamp) {
+function addMessageToChat(role, content, timestamp, outputs = []) {
   const chatContainer = document.querySelector('.chat-messages');
   const messageDiv = document.createElement('div');
   messageDiv.className = `message ${role}`;
@@ -187,6 +188,7 @@ function addMessageToChat(role, content, timestamp) {
     </div>
     <div class="message-content">
       ${formatMarkdown(content)}
+      ${outputs.map(renderOutputCard).join('')}
     </div>
     <div class="message-time">
       ${formatTime(timestamp)}
@@ -197,6 +199,21 @@ function addMessageToChat(role, content, timestamp) {
   chatContainer.scrollTop = chatContainer.scrollHeight;
 }
 
+// Helper: Preview card for an output (poster links to the player)
+function renderOutputCard(output) {
+  const preview = output.poster_url
+    ? `<img src="${output.poster_url}" alt="${output.file_name}" loading="lazy">`
+    : `<div class="output-card-icon">${output.kind === 'audio' ? '🎵' : '🎬'}</div>`;
+
+  return `
+    <div class="output-card">
+      <a href="${output.stream_url}" target="_blank">${preview}</a>
+      <div class="output-card-name">${output.file_name}</div>
+      <a href="${output.download_url}" download>Download</a>
+    </div>
+  `;
+}
+
 // Helper: Show typing indicator
 function showTypingIndicator(message) {
   const indicator = document.querySelector('.typing-indicator');
@@ -0,0 +1,42 @@
+-- Delivery targets (SFTP/FTP/S3) configured per user, plus a receipt for every delivery
+CREATE TABLE IF NOT EXISTS delivery_targets (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    protocol VARCHAR(10) NOT NULL, -- sftp, ftp, s3
+    host VARCHAR(255) NOT NULL, -- server hostname, or bucket name for s3
+    port INTEGER,
+    username VARCHAR(255), -- login, or access key id for s3
+    secret TEXT, -- password, or secret access key for s3
+    remote_path VARCHAR(500) NOT NULL DEFAULT '', -- directory, or key prefix for s3
+    region VARCHAR(50), -- s3 only
+    endpoint_url VARCHAR(500), -- s3-compatible endpoint override (R2, MinIO, ...)
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name),
+    CHECK (protocol IN ('sftp', 'ftp', 's3'))
+);
+
+CREATE TABLE IF NOT EXISTS delivery_receipts (
+    id SERIAL PRIMARY KEY,
+    receipt_uuid VARCHAR(36) NOT NULL UNIQUE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_id INTEGER REFERENCES delivery_targets(id) ON DELETE SET NULL,
+    target_name VARCHAR(100) NOT NULL,
+    local_path VARCHAR(500) NOT NULL,
+    remote_url VARCHAR(1000) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    md5 VARCHAR(32) NOT NULL,
+    verified BOOLEAN NOT NULL DEFAULT FALSE,
+    verification_method VARCHAR(100),
+    status VARCHAR(20) NOT NULL, -- delivered, failed
+    error_message TEXT,
+    started_at TIMESTAMPTZ NOT NULL,
+    delivered_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_targets_user_id ON delivery_targets(user_id);
+CREATE INDEX IF NOT EXISTS idx_delivery_receipts_user_id ON delivery_receipts(user_id);
+CREATE INDEX IF NOT EXISTS idx_delivery_receipts_delivered_at ON delivery_receipts(delivered_at);
@@ -0,0 +1,26 @@
+-- Public, expiring review links for outputs, with timestamped reviewer comments
+CREATE TABLE IF NOT EXISTS review_links (
+    id SERIAL PRIMARY KEY,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    file_path VARCHAR(500) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    allow_comments BOOLEAN NOT NULL DEFAULT TRUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS review_comments (
+    id SERIAL PRIMARY KEY,
+    review_link_id INTEGER NOT NULL REFERENCES review_links(id) ON DELETE CASCADE,
+    author_name VARCHAR(100) NOT NULL,
+    body TEXT NOT NULL,
+    timestamp_seconds DOUBLE PRECISION NOT NULL DEFAULT 0, -- playback position the comment refers to
+    resolved BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_links_user_id ON review_links(user_id);
+CREATE INDEX IF NOT EXISTS idx_review_comments_link ON review_comments(review_link_id, timestamp_seconds);
@@ -0,0 +1,6 @@
+-- Version lineage for output videos: re-rendering an output (same path or using it as input) creates a new version
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS parent_output_id INTEGER REFERENCES output_videos(id) ON DELETE SET NULL;
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS root_output_id INTEGER REFERENCES output_videos(id) ON DELETE SET NULL;
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_output_videos_root_output_id ON output_videos(root_output_id);
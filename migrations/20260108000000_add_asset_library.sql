@@ -0,0 +1,20 @@
+-- Per-user media asset library (logos, intros, music, clips) shared across chat sessions
+CREATE TABLE IF NOT EXISTS library_assets (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL, -- referenced by the agent as library:<name>
+    kind VARCHAR(20) NOT NULL DEFAULT 'other', -- logo, intro, outro, music, clip, image, sfx, other
+    file_path VARCHAR(1024) NOT NULL,
+    file_size BIGINT NOT NULL,
+    mime_type VARCHAR(100),
+    duration_seconds DOUBLE PRECISION,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    description TEXT,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_library_assets_user_id ON library_assets(user_id);
+CREATE INDEX IF NOT EXISTS idx_library_assets_tags ON library_assets USING GIN (tags);
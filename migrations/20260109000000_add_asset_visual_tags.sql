@@ -0,0 +1,21 @@
+-- Vision-model tags for library assets and uploads (one row per file)
+CREATE TABLE IF NOT EXISTS asset_visual_tags (
+    id SERIAL PRIMARY KEY,
+    source VARCHAR(20) NOT NULL, -- library, upload
+    source_id VARCHAR(255) NOT NULL, -- library_assets.id or uploaded_files.id
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    file_path VARCHAR(1024) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, tagged, skipped, failed
+    caption TEXT,
+    objects TEXT[] NOT NULL DEFAULT '{}',
+    scenes TEXT[] NOT NULL DEFAULT '{}',
+    dominant_colors TEXT[] NOT NULL DEFAULT '{}',
+    has_faces BOOLEAN,
+    embedded BOOLEAN NOT NULL DEFAULT FALSE, -- caption + tags stored in the vector index
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    tagged_at TIMESTAMPTZ,
+    UNIQUE (source, source_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_visual_tags_user_id ON asset_visual_tags(user_id);
@@ -0,0 +1,8 @@
+-- Content and perceptual fingerprints for duplicate upload detection
+ALTER TABLE uploaded_files ADD COLUMN IF NOT EXISTS content_hash VARCHAR(64); -- sha256 of the bytes
+ALTER TABLE uploaded_files ADD COLUMN IF NOT EXISTS perceptual_hash VARCHAR(255); -- dHash of sampled frames, hex
+ALTER TABLE uploaded_files ADD COLUMN IF NOT EXISTS duplicate_of VARCHAR(255) REFERENCES uploaded_files(id) ON DELETE SET NULL;
+ALTER TABLE uploaded_files ADD COLUMN IF NOT EXISTS duplicate_kind VARCHAR(20); -- exact, perceptual
+
+CREATE INDEX IF NOT EXISTS idx_uploaded_files_content_hash ON uploaded_files(content_hash);
+CREATE INDEX IF NOT EXISTS idx_uploaded_files_duplicate_of ON uploaded_files(duplicate_of);
@@ -0,0 +1,20 @@
+-- Live RTMP ingest: a stream key per user session, recordings land in the session as uploads
+CREATE TABLE IF NOT EXISTS live_ingests (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id INTEGER NOT NULL REFERENCES chat_sessions(id) ON DELETE CASCADE,
+    stream_key VARCHAR(64) NOT NULL UNIQUE,
+    title VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'idle', -- idle, live, processing, ended, failed, revoked
+    auto_clip BOOLEAN NOT NULL DEFAULT TRUE, -- start a clipping job when the stream ends
+    clip_instructions TEXT,
+    recording_path VARCHAR(1024),
+    uploaded_file_id VARCHAR(255) REFERENCES uploaded_files(id) ON DELETE SET NULL,
+    clip_job_id VARCHAR(255),
+    error_message TEXT,
+    started_at TIMESTAMPTZ,
+    ended_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_live_ingests_user_id ON live_ingests(user_id);
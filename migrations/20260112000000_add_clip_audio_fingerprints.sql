@@ -0,0 +1,2 @@
+-- Audio fingerprints of extracted clips, so later runs can skip clips a channel already published
+ALTER TABLE extracted_clips ADD COLUMN IF NOT EXISTS audio_fingerprint BYTEA;
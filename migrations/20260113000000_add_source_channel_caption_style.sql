@@ -0,0 +1,2 @@
+-- Burned caption style applied to clips cut from each monitored channel
+ALTER TABLE youtube_source_channels ADD COLUMN IF NOT EXISTS caption_style JSONB NOT NULL DEFAULT '{"preset": "bold"}';
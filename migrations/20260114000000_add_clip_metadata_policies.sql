@@ -0,0 +1,10 @@
+-- Owner-defined constraints on AI-generated clip titles, descriptions and hashtags, per destination channel
+CREATE TABLE IF NOT EXISTS clip_metadata_policies (
+    destination_channel_id INTEGER PRIMARY KEY REFERENCES connected_youtube_channels(id) ON DELETE CASCADE,
+    banned_words TEXT[] NOT NULL DEFAULT '{}',
+    mandatory_hashtags TEXT[] NOT NULL DEFAULT '{}',
+    max_title_length INTEGER,
+    tone TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
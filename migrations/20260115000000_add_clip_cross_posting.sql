@@ -0,0 +1,25 @@
+-- Which platforms each destination channel's clips are rendered for and published to
+CREATE TABLE IF NOT EXISTS clip_cross_post_configs (
+    destination_channel_id INTEGER PRIMARY KEY REFERENCES connected_youtube_channels(id) ON DELETE CASCADE,
+    platforms TEXT[] NOT NULL DEFAULT '{youtube_shorts}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per clip per platform: the rendered variant and its publish status
+CREATE TABLE IF NOT EXISTS clip_cross_posts (
+    id SERIAL PRIMARY KEY,
+    extracted_clip_id INTEGER NOT NULL REFERENCES extracted_clips(id) ON DELETE CASCADE,
+    platform VARCHAR(50) NOT NULL,
+    aspect_ratio VARCHAR(10) NOT NULL,
+    rendered_path TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, rendered, published, failed
+    external_id TEXT,
+    external_url TEXT,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (extracted_clip_id, platform)
+);
+
+CREATE INDEX IF NOT EXISTS idx_clip_cross_posts_clip ON clip_cross_posts(extracted_clip_id);
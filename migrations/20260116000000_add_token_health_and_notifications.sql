@@ -0,0 +1,18 @@
+-- OAuth token health for connected channels, checked by the background token monitor
+ALTER TABLE connected_youtube_channels ADD COLUMN IF NOT EXISTS token_status VARCHAR(20) NOT NULL DEFAULT 'healthy'; -- healthy, degraded, revoked
+ALTER TABLE connected_youtube_channels ADD COLUMN IF NOT EXISTS token_error TEXT;
+ALTER TABLE connected_youtube_channels ADD COLUMN IF NOT EXISTS token_checked_at TIMESTAMPTZ;
+
+-- Messages for users about things that need their attention
+CREATE TABLE IF NOT EXISTS notifications (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    data JSONB,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_unread ON notifications(user_id, created_at DESC) WHERE read_at IS NULL;
@@ -0,0 +1,7 @@
+-- Token budgets enforced by the agents (0 = unlimited). Past 80% the agents switch to a cheaper
+-- model and compact old tool output; at 100% they pause with a message instead of calling the model.
+INSERT INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES
+    ('token_budget.session_tokens', '3000000', 'integer', 'Max AI tokens a single chat session may use'),
+    ('token_budget.daily_tokens', '10000000', 'integer', 'Max AI tokens a user may use per UTC day')
+ON CONFLICT (setting_key) DO NOTHING;
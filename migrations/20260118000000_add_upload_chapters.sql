@@ -0,0 +1,25 @@
+-- Opt-in chapter index for long uploads: transcribed, split at topic boundaries and titled
+-- so the agent can find content by topic ("cut the part about onboarding")
+CREATE TABLE IF NOT EXISTS upload_chapter_indexes (
+    uploaded_file_id VARCHAR(255) PRIMARY KEY REFERENCES uploaded_files(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, processing, completed, skipped, failed
+    duration_seconds DOUBLE PRECISION,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS upload_chapters (
+    id SERIAL PRIMARY KEY,
+    uploaded_file_id VARCHAR(255) NOT NULL REFERENCES uploaded_files(id) ON DELETE CASCADE,
+    chapter_index INTEGER NOT NULL,
+    start_seconds DOUBLE PRECISION NOT NULL,
+    end_seconds DOUBLE PRECISION NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    summary TEXT,
+    keywords TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (uploaded_file_id, chapter_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_upload_chapters_file ON upload_chapters(uploaded_file_id);
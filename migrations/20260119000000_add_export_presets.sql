@@ -0,0 +1,24 @@
+-- Named export presets per user, referenced by name from the agent (export_with_preset,
+-- export_for_platform overrides) and from batch render templates
+CREATE TABLE IF NOT EXISTS export_presets (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    container VARCHAR(10) NOT NULL DEFAULT 'mp4', -- mp4, mov, mkv, webm
+    video_codec VARCHAR(20) NOT NULL DEFAULT 'libx264',
+    audio_codec VARCHAR(20) NOT NULL DEFAULT 'aac',
+    crf INTEGER, -- quality-based rate control; ignored when video_bitrate_kbps is set
+    video_bitrate_kbps INTEGER,
+    audio_bitrate_kbps INTEGER,
+    width INTEGER,
+    height INTEGER,
+    fps DOUBLE PRECISION,
+    loudness_target DOUBLE PRECISION, -- integrated loudness in LUFS, e.g. -14
+    burn_captions BOOLEAN NOT NULL DEFAULT FALSE,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_presets_user_id ON export_presets(user_id);
@@ -0,0 +1,2 @@
+-- Machine-readable render report (input hashes, ffmpeg commands, encoders, warnings, environment) per output
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS report_path TEXT;
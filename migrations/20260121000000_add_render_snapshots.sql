@@ -0,0 +1,21 @@
+-- Reproducible render mode: sessions can pin their renders, and pinned outputs can be snapshotted and replayed
+ALTER TABLE chat_sessions ADD COLUMN IF NOT EXISTS reproducible_render BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS render_snapshots (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    output_id INTEGER REFERENCES output_videos(id) ON DELETE SET NULL,
+    name VARCHAR(255) NOT NULL,
+    -- Pinned FFmpeg commands with their input hashes, in execution order
+    manifest JSONB NOT NULL,
+    output_sha256 VARCHAR(64),
+    ffmpeg_version TEXT,
+    -- idle, running, matched, mismatched or failed
+    replay_status VARCHAR(20) NOT NULL DEFAULT 'idle',
+    replay_output TEXT,
+    replay_error TEXT,
+    last_replayed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_render_snapshots_user_id ON render_snapshots(user_id);
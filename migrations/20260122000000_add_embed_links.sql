@@ -0,0 +1,20 @@
+-- Account plan, which gates paid features such as removing VideoSync branding from embeds
+ALTER TABLE users ADD COLUMN IF NOT EXISTS plan VARCHAR(20) NOT NULL DEFAULT 'free';
+
+-- Public embeddable players for recorded outputs
+CREATE TABLE IF NOT EXISTS embed_links (
+    id SERIAL PRIMARY KEY,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    output_id INTEGER NOT NULL REFERENCES output_videos(id) ON DELETE CASCADE,
+    file_path VARCHAR(500) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    hide_branding BOOLEAN NOT NULL DEFAULT FALSE,
+    allowed_domains TEXT, -- space-separated frame-ancestors; NULL allows any site
+    hls_status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, ready, failed
+    view_count INTEGER NOT NULL DEFAULT 0,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_embed_links_user_id ON embed_links(user_id);
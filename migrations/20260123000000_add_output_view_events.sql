@@ -0,0 +1,11 @@
+-- Stream and download events per output, for consumption stats on the dashboard
+CREATE TABLE IF NOT EXISTS output_view_events (
+    id BIGSERIAL PRIMARY KEY,
+    output_id INTEGER NOT NULL REFERENCES output_videos(id) ON DELETE CASCADE,
+    event_type VARCHAR(20) NOT NULL, -- stream, download
+    source VARCHAR(20) NOT NULL DEFAULT 'app', -- app, review, embed
+    country VARCHAR(2), -- ISO 3166-1 alpha-2 from the CDN/proxy geo header; no IPs are stored
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_output_view_events_output ON output_view_events(output_id, created_at);
@@ -0,0 +1,3 @@
+-- Per-session default parameters (resolution, fps, voice, caption style) the agent applies
+-- to tool calls that leave them out, so "from now on everything in 4K" sticks for the session
+ALTER TABLE chat_sessions ADD COLUMN IF NOT EXISTS defaults JSONB NOT NULL DEFAULT '{}'::jsonb;
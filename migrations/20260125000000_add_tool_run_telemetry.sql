@@ -0,0 +1,13 @@
+-- Wall time of each rendering tool run against the size of its input, for render time estimates
+CREATE TABLE IF NOT EXISTS tool_run_telemetry (
+    id BIGSERIAL PRIMARY KEY,
+    tool VARCHAR(100) NOT NULL,
+    input_duration_seconds DOUBLE PRECISION,
+    input_width INTEGER,
+    input_height INTEGER,
+    wall_seconds DOUBLE PRECISION NOT NULL,
+    success BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_run_telemetry_tool ON tool_run_telemetry(tool, created_at DESC);
@@ -0,0 +1,16 @@
+-- Feature flags for gradual rollout of experimental agent tools and workflows.
+-- A flag is on for a user when it is enabled (the kill switch) and the user is targeted:
+-- listed by id, on a listed plan, staff when staff are included, or inside the rollout percentage.
+CREATE TABLE IF NOT EXISTS feature_flags (
+    key VARCHAR(100) PRIMARY KEY,
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    rollout_percent INTEGER NOT NULL DEFAULT 0 CHECK (rollout_percent BETWEEN 0 AND 100),
+    user_ids INTEGER[] NOT NULL DEFAULT '{}',
+    plans TEXT[] NOT NULL DEFAULT '{}',
+    include_staff BOOLEAN NOT NULL DEFAULT FALSE,
+    tools TEXT[] NOT NULL DEFAULT '{}', -- agent tools hidden from users the flag is off for
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
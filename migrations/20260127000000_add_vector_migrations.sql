@@ -0,0 +1,23 @@
+-- Copies of the chat memory vectors from one backend to another (e.g. AstraDB to Qdrant).
+-- The cursor is the source's page token for the next batch, so an interrupted run resumes where it stopped.
+CREATE TABLE IF NOT EXISTS vector_migrations (
+    id SERIAL PRIMARY KEY,
+    source VARCHAR(20) NOT NULL,
+    target VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'running', -- running, completed, failed
+    batch_size INTEGER NOT NULL DEFAULT 100,
+    reembed BOOLEAN NOT NULL DEFAULT FALSE,
+    cursor TEXT,
+    scan_complete BOOLEAN NOT NULL DEFAULT FALSE,
+    source_count BIGINT,
+    target_count BIGINT,
+    copied BIGINT NOT NULL DEFAULT 0,
+    verified BIGINT NOT NULL DEFAULT 0,
+    failed_ids TEXT[] NOT NULL DEFAULT '{}',
+    mismatched_ids TEXT[] NOT NULL DEFAULT '{}',
+    error TEXT,
+    started_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
@@ -0,0 +1,4 @@
+-- Request (or WebSocket turn) each LLM call was made for, to trace a complaint across jobs and agent turns
+ALTER TABLE api_token_usage ADD COLUMN IF NOT EXISTS request_id VARCHAR(128);
+
+CREATE INDEX IF NOT EXISTS idx_api_token_usage_request_id ON api_token_usage(request_id) WHERE request_id IS NOT NULL;
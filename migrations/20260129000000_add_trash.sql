@@ -0,0 +1,6 @@
+-- Soft delete: trashed sessions and outputs are hidden, restorable for 30 days, then purged
+ALTER TABLE chat_sessions ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_chat_sessions_deleted_at ON chat_sessions(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_output_videos_deleted_at ON output_videos(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Archived sessions are kept but left out of the chat lists unless asked for
+ALTER TABLE chat_sessions ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_chat_sessions_archived_at ON chat_sessions(archived_at) WHERE archived_at IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Jobs queued for a future time ("render tonight, publish tomorrow 9am"); the id is the job id they run under
+CREATE TABLE IF NOT EXISTS scheduled_jobs (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_uuid TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL,
+    -- scheduled, started, cancelled
+    status TEXT NOT NULL DEFAULT 'scheduled',
+    started_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_jobs_due ON scheduled_jobs(run_at) WHERE status = 'scheduled';
+CREATE INDEX IF NOT EXISTS idx_scheduled_jobs_user ON scheduled_jobs(user_id, run_at);
@@ -0,0 +1,8 @@
+-- Title, description and tags generated from each output's content after it renders; they pre-fill
+-- YouTube uploads and make the outputs library searchable
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS suggested_title VARCHAR(100);
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS description TEXT;
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS metadata_generated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_output_videos_tags ON output_videos USING GIN (tags);
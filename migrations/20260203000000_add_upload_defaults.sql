@@ -0,0 +1,3 @@
+-- Per-user YouTube upload defaults (channel, privacy, category, tags, description template) that
+-- uploads fall back to for anything the request leaves out
+ALTER TABLE users ADD COLUMN IF NOT EXISTS upload_defaults JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,7 @@
+-- Draft uploads: the video bytes go to YouTube early as a private video, and the title,
+-- description, thumbnail and visibility are set when the draft is finalized
+ALTER TABLE youtube_uploads
+ADD COLUMN IF NOT EXISTS is_draft BOOLEAN NOT NULL DEFAULT false,
+ADD COLUMN IF NOT EXISTS finalized_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_youtube_uploads_open_drafts ON youtube_uploads(user_id) WHERE is_draft AND finalized_at IS NULL;
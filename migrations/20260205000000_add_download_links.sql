@@ -0,0 +1,19 @@
+-- Time-limited, optionally password-protected download links for delivering outputs to clients
+CREATE TABLE IF NOT EXISTS download_links (
+    id SERIAL PRIMARY KEY,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    output_id INTEGER NOT NULL REFERENCES output_videos(id) ON DELETE CASCADE,
+    file_path VARCHAR(500) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    password_hash VARCHAR(255), -- bcrypt; NULL when the link needs no password
+    expires_at TIMESTAMPTZ NOT NULL,
+    max_downloads INTEGER, -- NULL allows any number of downloads until expiry
+    download_count INTEGER NOT NULL DEFAULT 0,
+    last_downloaded_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_links_user_id ON download_links(user_id);
+CREATE INDEX IF NOT EXISTS idx_download_links_output_id ON download_links(output_id);
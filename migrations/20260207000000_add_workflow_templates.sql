@@ -0,0 +1,45 @@
+-- Named job graphs with parameter slots ("podcast to shorts"), defined once and run on any session.
+-- Templates with no user_id are built in and shared by everyone; users add their own next to them.
+CREATE TABLE IF NOT EXISTS workflow_templates (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    parameters JSONB NOT NULL DEFAULT '[]', -- [{"name": "episode", "description": "...", "default": null}]
+    jobs JSONB NOT NULL, -- [{"key": "...", "task": "... {{episode}} ...", "depends_on": [...]}]
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_templates_user_name ON workflow_templates(COALESCE(user_id, 0), lower(name));
+
+INSERT INTO workflow_templates (user_id, name, description, parameters, jobs)
+SELECT NULL, 'podcast-to-shorts',
+    'Cut a podcast episode into captioned vertical shorts with titles and hashtags',
+    '[
+        {"name": "episode", "description": "Path of the episode recording"},
+        {"name": "clip_count", "description": "How many shorts to make", "default": "3"},
+        {"name": "caption_style", "description": "Look of the burned-in captions", "default": "bold"}
+    ]'::jsonb,
+    '[
+        {"key": "highlights", "task": "Transcribe {{episode}} and pick the {{clip_count}} strongest self-contained moments of 20 to 60 seconds. List the start and end time of each with a one-line hook."},
+        {"key": "shorts", "task": "Cut each moment picked above out of {{episode}}, reframe it to 1080x1920 vertical and add {{caption_style}} styled captions. Save them as outputs/short_1.mp4, outputs/short_2.mp4 and so on.", "depends_on": ["highlights"]},
+        {"key": "metadata", "task": "Write a title, a description and hashtags for each short made above, for YouTube Shorts and TikTok.", "depends_on": ["shorts"]}
+    ]'::jsonb
+WHERE NOT EXISTS (SELECT 1 FROM workflow_templates WHERE user_id IS NULL AND name = 'podcast-to-shorts');
+
+INSERT INTO workflow_templates (user_id, name, description, parameters, jobs)
+SELECT NULL, 'footage-to-youtube',
+    'Edit raw footage into a polished video with a thumbnail and upload it to YouTube',
+    '[
+        {"name": "footage", "description": "Path of the raw footage"},
+        {"name": "title", "description": "Title of the YouTube video"},
+        {"name": "privacy", "description": "private, unlisted or public", "default": "private"}
+    ]'::jsonb,
+    '[
+        {"key": "rough_cut", "task": "Analyze {{footage}}, cut out dead air, false starts and shaky or out-of-focus sections, and save the edit as outputs/rough_cut.mp4."},
+        {"key": "polish", "task": "Color correct and stabilize the rough cut made above, normalize its audio and export it with the youtube preset as outputs/youtube_final.mp4.", "depends_on": ["rough_cut"]},
+        {"key": "thumbnail", "task": "Create a thumbnail for the final video made above from its most striking frame, with the text \"{{title}}\".", "depends_on": ["polish"]},
+        {"key": "upload", "task": "Upload the final video made above to YouTube as {{privacy}}, titled \"{{title}}\", with an optimized description and tags and the thumbnail made above.", "depends_on": ["polish", "thumbnail"]}
+    ]'::jsonb
+WHERE NOT EXISTS (SELECT 1 FROM workflow_templates WHERE user_id IS NULL AND name = 'footage-to-youtube');
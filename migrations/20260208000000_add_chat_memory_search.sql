@@ -0,0 +1,16 @@
+-- Keyword side of hybrid memory search. Every chat memory stored in Qdrant is mirrored here under the
+-- same id, so exact terms (filenames, timestamps, codes) that embeddings blur can still be found and
+-- fused with the vector results. Memories stored before this table existed are vector-only.
+CREATE TABLE IF NOT EXISTS chat_memory_entries (
+    id VARCHAR(64) PRIMARY KEY, -- Qdrant point id
+    session_uuid VARCHAR(255) NOT NULL,
+    user_message TEXT NOT NULL,
+    agent_response TEXT NOT NULL,
+    search TSVECTOR GENERATED ALWAYS AS (
+        to_tsvector('simple', user_message || ' ' || agent_response)
+    ) STORED,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_memory_entries_session ON chat_memory_entries(session_uuid, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_chat_memory_entries_search ON chat_memory_entries USING GIN(search);
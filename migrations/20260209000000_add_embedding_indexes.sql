@@ -0,0 +1,26 @@
+-- Versions of the chat memory vector index. Each one is a Qdrant collection filled by a single
+-- embedding model; vectors of different models can't be compared, so a model change builds a new
+-- index by re-embedding the active one's content, and searches stay on the active index until an
+-- admin cuts over to the new one.
+CREATE TABLE IF NOT EXISTS embedding_indexes (
+    id SERIAL PRIMARY KEY,
+    collection VARCHAR(255) NOT NULL UNIQUE,
+    model VARCHAR(50) NOT NULL, -- voyage, gemini, local
+    dimension INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'building', -- building, ready, active, retired, failed
+    source_index_id INTEGER REFERENCES embedding_indexes(id) ON DELETE SET NULL,
+    batch_size INTEGER NOT NULL DEFAULT 100,
+    cursor TEXT,
+    scan_complete BOOLEAN NOT NULL DEFAULT FALSE,
+    source_count BIGINT,
+    reindexed BIGINT NOT NULL DEFAULT 0,
+    failed_ids TEXT[] NOT NULL DEFAULT '{}',
+    error TEXT,
+    started_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    activated_at TIMESTAMPTZ
+);
+
+-- Only one index serves searches at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_embedding_indexes_active ON embedding_indexes(status) WHERE status = 'active';
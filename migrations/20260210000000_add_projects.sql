@@ -0,0 +1,40 @@
+-- Projects: a source video and an ordered edit decision list of tool operations, rendered only on export.
+-- Every edit writes a new revision of the list; the project points at the one it's on, so undo and redo
+-- move that pointer, and revisions past it are the redo stack until the next edit drops them.
+CREATE TABLE IF NOT EXISTS projects (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_uuid VARCHAR(255) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    source_file TEXT NOT NULL,
+    revision INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_projects_session_name ON projects(session_uuid, lower(name));
+CREATE INDEX IF NOT EXISTS idx_projects_user ON projects(user_id, updated_at DESC);
+
+CREATE TABLE IF NOT EXISTS project_revisions (
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    operations JSONB NOT NULL DEFAULT '[]', -- [{"tool": "trim_video", "args": {"start_time": 5, "end_time": 65}, "note": "..."}]
+    change TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, revision)
+);
+
+-- Renders of a revision, one per export preset asked for
+CREATE TABLE IF NOT EXISTS project_exports (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    preset VARCHAR(100),
+    status VARCHAR(20) NOT NULL DEFAULT 'rendering', -- rendering, completed, failed
+    output_path TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_exports_project ON project_exports(project_id, created_at DESC);
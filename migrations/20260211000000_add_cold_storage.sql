@@ -0,0 +1,17 @@
+-- Cold storage: outputs nobody has touched for a while move out of outputs/ into the cold store
+-- (optionally gzipped) and come back on first access. Users can change when that happens.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS storage_policy JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+ALTER TABLE output_videos
+    ADD COLUMN IF NOT EXISTS storage_tier VARCHAR(10) NOT NULL DEFAULT 'hot', -- hot, cold
+    ADD COLUMN IF NOT EXISTS hot_path TEXT, -- where the file sat on disk; it's restored there
+    ADD COLUMN IF NOT EXISTS cold_path TEXT,
+    ADD COLUMN IF NOT EXISTS cold_size BIGINT,
+    ADD COLUMN IF NOT EXISTS cold_compressed BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS cold_sha256 VARCHAR(64), -- of the original file, checked on rehydration
+    ADD COLUMN IF NOT EXISTS cold_file_id VARCHAR(32), -- download/stream file id the output had while hot
+    ADD COLUMN IF NOT EXISTS tiered_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS last_accessed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_output_videos_cold_file_id ON output_videos(cold_file_id) WHERE storage_tier = 'cold';
+CREATE INDEX IF NOT EXISTS idx_output_videos_tier ON output_videos(storage_tier, user_id);
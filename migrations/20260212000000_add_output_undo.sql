@@ -0,0 +1,5 @@
+-- Undo/redo of direct edits in a chat session. Undoing an edit sets `undone_at` on the output it made,
+-- which hides it from the agent until it's redone; the next edit moves whatever is still undone to the trash.
+ALTER TABLE output_videos ADD COLUMN IF NOT EXISTS undone_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_output_videos_session_undone ON output_videos(session_id, undone_at);
@@ -0,0 +1,5 @@
+-- Notification center: every notification is listed (read or not), and warnings that recur are sent once
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS dedupe_key VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, id DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_dedupe ON notifications(user_id, dedupe_key) WHERE dedupe_key IS NOT NULL;
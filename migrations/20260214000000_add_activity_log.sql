@@ -0,0 +1,15 @@
+-- Audit log of what happened in a user's workspace (uploads, renders, publishes, approvals, failures),
+-- read back newest first as their activity feed
+CREATE TABLE IF NOT EXISTS activity_events (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,       -- file_uploaded, job_completed, youtube_published, clip_approved, job_failed, ...
+    category VARCHAR(20) NOT NULL,   -- upload, render, publish, approval or failure
+    summary TEXT NOT NULL,
+    session_id VARCHAR(255),         -- chat session it happened in, when there is one
+    data JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_events_user ON activity_events(user_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_activity_events_user_category ON activity_events(user_id, category, id DESC);
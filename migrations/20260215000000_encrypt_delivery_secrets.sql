@@ -0,0 +1,3 @@
+-- Delivery target secrets are now encrypted by the server before they are stored
+-- (see src/utils/credentials.rs). Rows saved earlier stay plain text until the target is saved again.
+COMMENT ON COLUMN delivery_targets.secret IS 'AES-256-GCM sealed as enc:v1:<base64>; older rows may be plain text';
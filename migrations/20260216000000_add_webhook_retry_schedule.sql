@@ -0,0 +1,7 @@
+-- Webhook retries are driven from the deliveries table so a restart doesn't lose them: a pending
+-- delivery is attempted again once next_attempt_at passes (it is pushed ahead while an attempt runs)
+ALTER TABLE webhook_deliveries ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ;
+
+UPDATE webhook_deliveries SET next_attempt_at = NOW() WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
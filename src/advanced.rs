@@ -1,7 +1,7 @@
 // src/advanced.rs
 
 
-use crate::utils::execute_ffmpeg_command;
+use crate::utils::{execute_ffmpeg_command, gpu};
 use std::process::Command;
 
 pub fn picture_in_picture(
@@ -11,22 +11,34 @@ pub fn picture_in_picture(
     x: &str,
     y: &str,
 ) -> Result<String, String> {
-    let filter = format!("[1:v]scale=iw/4:ih/4 [pip]; [0:v][pip]overlay=x={}:y={}", x, y);
-
-    let mut command = Command::new("ffmpeg");
-    command
-        .arg("-i")
-        .arg(main_video)
-        .arg("-i")
-        .arg(overlay_video)
-        .arg("-filter_complex")
-        .arg(&filter)
-        .arg("-c:a")
-        .arg("copy")
-        .arg("-y")
-        .arg(output_file);
+    let build = |filter: &str, device_args: &[String]| {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(device_args)
+            .arg("-i")
+            .arg(main_video)
+            .arg("-i")
+            .arg(overlay_video)
+            .arg("-filter_complex")
+            .arg(filter)
+            .arg("-map")
+            .arg("[out]")
+            .arg("-map")
+            .arg("0:a?")
+            .arg("-c:a")
+            .arg("copy")
+            .arg("-y")
+            .arg(output_file);
+        command
+    };
 
-    execute_ffmpeg_command(command)
+    let gpu_command = gpu::filters().and_then(|gpu| {
+        let placement = gpu::OverlayPlacement { scale: Some(("iw/4", "ih/4")), x, y, alpha: false };
+        let filter = gpu.overlay_graph("0:v", "1:v", &placement, "out")?;
+        Some(build(&filter, &gpu.device_args()))
+    });
+    let filter = format!("[1:v]scale=iw/4:ih/4 [pip]; [0:v][pip]overlay=x={}:y={}[out]", x, y);
+    gpu::run_with_fallback(gpu_command, || build(&filter, &[]))
 }
 
 pub fn chroma_key(
@@ -37,29 +49,36 @@ pub fn chroma_key(
     similarity: f32,
     blend: f32,
 ) -> Result<String, String> {
+    let build = |filter: &str, device_args: &[String]| {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(device_args)
+            .arg("-i")
+            .arg(background_file)
+            .arg("-i")
+            .arg(input_file)
+            .arg("-filter_complex")
+            .arg(filter)
+            .arg("-map")
+            .arg("[out]")
+            .arg("-map")
+            .arg("0:a?")
+            .arg("-c:a")
+            .arg("copy")
+            .arg("-y")
+            .arg(output_file);
+        command
+    };
+
+    let gpu_command = gpu::filters().and_then(|gpu| {
+        let filter = gpu.chroma_key_graph("0:v", "1:v", color, similarity, blend, "out")?;
+        Some(build(&filter, &gpu.device_args()))
+    });
     let filter = format!(
         "[1:v]colorkey=color={}:similarity={}:blend={}[ckout];[0:v][ckout]overlay[out]",
         color, similarity, blend
     );
-
-    let mut command = Command::new("ffmpeg");
-    command
-        .arg("-i")
-        .arg(background_file)
-        .arg("-i")
-        .arg(input_file)
-        .arg("-filter_complex")
-        .arg(&filter)
-        .arg("-map")
-        .arg("[out]")
-        .arg("-map")
-        .arg("0:a?")
-        .arg("-c:a")
-        .arg("copy")
-        .arg("-y")
-        .arg(output_file);
-
-    execute_ffmpeg_command(command)
+    gpu::run_with_fallback(gpu_command, || build(&filter, &[]))
 }
 
 pub fn split_screen(
@@ -92,4 +111,113 @@ pub fn split_screen(
         .arg(output_file);
 
     execute_ffmpeg_command(command)
-}
\ No newline at end of file
+}
+/// One shot of a video, between two scene changes
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Scene {
+    pub index: usize,
+    pub start: f64,
+    pub end: f64,
+    /// A frame from the middle of the scene, when thumbnails were asked for
+    pub thumbnail: Option<String>,
+}
+
+/// Scene boundaries from scene-change times: the video split at each change, with changes less than
+/// `min_scene_seconds` after the last boundary (flicker, flashes) merged into the scene before
+pub fn scene_boundaries(changes: &[f64], duration: f64, min_scene_seconds: f64) -> Vec<(f64, f64)> {
+    let mut cuts = vec![0.0];
+    for &time in changes {
+        if time - cuts[cuts.len() - 1] >= min_scene_seconds && duration - time >= min_scene_seconds {
+            cuts.push(time);
+        }
+    }
+    cuts.push(duration);
+    cuts.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Split a video into scenes with FFmpeg's scene-change score (`threshold` 0-1; lower finds more,
+/// subtler cuts). With `thumbnail_dir`, a frame from the middle of each scene is saved there as
+/// `scene_001.jpg`, `scene_002.jpg`, ...
+pub fn detect_scenes(
+    input_file: &str,
+    threshold: f64,
+    min_scene_seconds: f64,
+    thumbnail_dir: Option<&str>,
+) -> Result<Vec<Scene>, String> {
+    let duration = crate::core::get_video_duration(input_file)?;
+    let changes = crate::export::scene_changes(input_file, threshold)?;
+    if let Some(dir) = thumbnail_dir {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir, e))?;
+    }
+
+    let mut scenes = Vec::new();
+    for (i, (start, end)) in scene_boundaries(&changes, duration, min_scene_seconds).into_iter().enumerate() {
+        let thumbnail = match thumbnail_dir {
+            Some(dir) => {
+                let path = format!("{}/scene_{:03}.jpg", dir, i + 1);
+                let mut command = Command::new("ffmpeg");
+                command
+                    .arg("-ss")
+                    .arg(format!("{:.3}", (start + end) / 2.0))
+                    .arg("-i")
+                    .arg(input_file)
+                    .arg("-frames:v")
+                    .arg("1")
+                    .arg("-vf")
+                    .arg("scale=320:-2")
+                    .arg("-y")
+                    .arg(&path);
+                execute_ffmpeg_command(command)?;
+                Some(path)
+            }
+            None => None,
+        };
+        scenes.push(Scene { index: i + 1, start, end, thumbnail });
+    }
+    Ok(scenes)
+}
+
+/// Move a cut's start and end to the nearest scene change within `tolerance` seconds, so a clip
+/// doesn't open or close mid-shot. Each end stays put when no change is close enough, or when
+/// snapping it would take the cut outside `min_length`..=`max_length`.
+pub fn snap_to_scenes(start: f64, end: f64, changes: &[f64], tolerance: f64, min_length: f64, max_length: f64) -> (f64, f64) {
+    let nearest = |time: f64| {
+        changes
+            .iter()
+            .copied()
+            .filter(|change| (change - time).abs() <= tolerance)
+            .min_by(|a, b| (a - time).abs().total_cmp(&(b - time).abs()))
+    };
+    let fits = |start: f64, end: f64| end > start && (min_length..=max_length).contains(&(end - start));
+
+    let mut cut = (start, end);
+    if let Some(snapped) = nearest(start).filter(|&s| fits(s, cut.1)) {
+        cut.0 = snapped;
+    }
+    if let Some(snapped) = nearest(end).filter(|&e| fits(cut.0, e)) {
+        cut.1 = snapped;
+    }
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenes_split_at_changes_and_cuts_snap_to_them() {
+        // The flash at 5.3 is too soon after the cut at 5.0; the change at 19.6 leaves too short a tail
+        assert_eq!(
+            scene_boundaries(&[5.0, 5.3, 12.0, 19.6], 20.0, 1.0),
+            [(0.0, 5.0), (5.0, 12.0), (12.0, 20.0)]
+        );
+        assert_eq!(scene_boundaries(&[], 8.0, 1.0), [(0.0, 8.0)]);
+
+        let changes = [10.2, 24.5, 41.0];
+        assert_eq!(snap_to_scenes(11.0, 25.0, &changes, 2.0, 5.0, 60.0), (10.2, 24.5));
+        // Nothing within tolerance of 33: that end stays
+        assert_eq!(snap_to_scenes(11.0, 33.0, &changes, 2.0, 5.0, 60.0), (10.2, 33.0));
+        // Snapping the end to 24.5 would make the clip longer than 14 seconds
+        assert_eq!(snap_to_scenes(11.0, 23.0, &changes, 2.0, 5.0, 14.0), (10.2, 23.0));
+    }
+}
@@ -0,0 +1,35 @@
+// src/agent/llm_provider.rs
+// The model call the agent loops depend on. ClaudeClient is the production implementation; tests
+// swap in a ScriptedProvider (agent/scripted_provider.rs) that replays canned responses.
+use crate::claude_client::{ClaudeClient, ClaudeMessage, ClaudeResponse, ClaudeTool};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// One turn of the conversation: the model's reply to `messages`, optionally with tools available
+    async fn generate_content(
+        &self,
+        messages: Vec<ClaudeMessage>,
+        tools: Option<Vec<ClaudeTool>>,
+        system: Option<String>,
+    ) -> Result<ClaudeResponse, String>;
+
+    /// The same provider answering with another model, e.g. a cheaper one near the token budget
+    fn with_model(&self, model: &str) -> Box<dyn LlmProvider>;
+}
+
+#[async_trait]
+impl LlmProvider for ClaudeClient {
+    async fn generate_content(
+        &self,
+        messages: Vec<ClaudeMessage>,
+        tools: Option<Vec<ClaudeTool>>,
+        system: Option<String>,
+    ) -> Result<ClaudeResponse, String> {
+        ClaudeClient::generate_content(self, messages, tools, system).await
+    }
+
+    fn with_model(&self, model: &str) -> Box<dyn LlmProvider> {
+        Box::new(ClaudeClient::with_model(self, model))
+    }
+}
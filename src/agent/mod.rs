@@ -5,5 +5,13 @@ pub mod simple_claude_agent;
 pub mod tool_executor;
 pub mod react_state;
 pub mod react_agent;
+pub mod llm_provider;
 pub mod video_workflow_state;
 pub mod stateful_agent;
+pub mod slash_commands;
+#[cfg(test)]
+mod tool_harness;
+#[cfg(test)]
+mod tool_golden;
+#[cfg(test)]
+mod scripted_provider;
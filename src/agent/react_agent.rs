@@ -1,21 +1,29 @@
 // ReAct Agent Implementation - Thought → Action → Observation → Reflection
 // Supports user interruption and real-time reasoning updates
 
+#[cfg(test)]
+use super::llm_provider::LlmProvider;
 use super::react_state::{AgentState, AgentContext, UserCommand};
-use super::tool_executor::{execute_tool_claude, execute_tool_gemini};
-use crate::claude_client::{ClaudeClient, ClaudeMessage, ClaudeContent, ContentBlock};
+#[cfg(test)]
+use super::tool_executor::execute_tool_claude;
+use super::tool_executor::execute_tool_gemini;
+#[cfg(test)]
+use crate::claude_client::{ClaudeMessage, ClaudeContent, ContentBlock};
 use crate::gemini_client::{GeminiClient, GenerateContentRequest, Content, Part, Tool, GenerationConfig, ToolConfig, FunctionCallingConfig, FunctionCallingMode};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use serde_json::json;
 
+/// Jobs run SimpleClaudeAgent; this loop is only driven by the scripted ReAct tests (agent/scripted_provider.rs)
+#[cfg(test)]
 pub struct ReActClaudeAgent {
-    client: Arc<ClaudeClient>,
+    client: Arc<dyn LlmProvider>,
     context: AgentContext,
 }
 
+#[cfg(test)]
 impl ReActClaudeAgent {
-    pub fn new(client: Arc<ClaudeClient>, session_id: String, user_request: String) -> Self {
+    pub fn new(client: Arc<dyn LlmProvider>, session_id: String, user_request: String) -> Self {
         Self {
             client,
             context: AgentContext::new(session_id, user_request),
@@ -38,8 +46,11 @@ impl ReActClaudeAgent {
         });
         let _ = progress_tx.send(self.context.current_state.clone());
 
+        // Session files go in the first message rather than the system prompt so the
+        // system prompt stays identical across sessions and can be served from the prompt cache
         let planning_prompt = format!(
-            "Analyze this video editing request and break it down into specific steps:\n\n{}\n\nList the exact tools and sequence needed.",
+            "Current session files: {}\n\nAnalyze this video editing request and break it down into specific steps:\n\n{}\n\nList the exact tools and sequence needed.",
+            self.format_uploaded_files(),
             self.get_user_request()
         );
 
@@ -251,11 +262,8 @@ For each step, you should:
 
 You have access to {} video editing tools. Use them to complete multi-step requests.
 
-Current session files: {}
-
 Always explain your reasoning before acting. Be transparent about your decision-making process.",
-            38,
-            self.format_uploaded_files()
+            38
         )
     }
 
@@ -0,0 +1,276 @@
+// src/agent/scripted_provider.rs
+// A scripted LlmProvider for deterministic tests of the ReAct loop. Each call to generate_content
+// pops the next canned response and records the request, so a test can script a tool-call sequence
+// and then check what the loop dispatched, what it sent back to the model, and the order of the
+// AgentState updates streamed to the WebSocket. Tool calls run under `capture_ffmpeg`, so no media
+// is rendered.
+use super::llm_provider::LlmProvider;
+use super::react_agent::ReActClaudeAgent;
+use super::react_state::{AgentState, UserCommand};
+use crate::claude_client::{ClaudeContent, ClaudeMessage, ClaudeResponse, ClaudeTool, ContentBlock, ResponseContent, Usage};
+use crate::utils::capture_ffmpeg;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// What the loop sent on one call
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub messages: Vec<ClaudeMessage>,
+    pub tools: Option<Vec<ClaudeTool>>,
+}
+
+/// Clones share one script, so a provider switched to another model keeps replaying it
+#[derive(Default, Clone)]
+pub struct ScriptedProvider {
+    turns: Arc<Mutex<VecDeque<Result<ClaudeResponse, String>>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl ScriptedProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reply with plain text (ends the execution loop)
+    pub fn text(self, text: &str) -> Self {
+        self.respond(vec![ResponseContent::Text { text: text.to_string() }])
+    }
+
+    /// Reply with a single tool call
+    pub fn tool_call(self, id: &str, name: &str, input: Value) -> Self {
+        self.respond(vec![ResponseContent::ToolUse { id: id.to_string(), name: name.to_string(), input }])
+    }
+
+    /// Reply with arbitrary content blocks, e.g. text followed by several tool calls
+    pub fn respond(self, content: Vec<ResponseContent>) -> Self {
+        let turn = self.turns.lock().unwrap().len();
+        let stop_reason = if content.iter().any(|c| matches!(c, ResponseContent::ToolUse { .. })) { "tool_use" } else { "end_turn" };
+        self.turns.lock().unwrap().push_back(Ok(ClaudeResponse {
+            id: format!("msg_scripted_{}", turn),
+            model: "scripted".to_string(),
+            role: "assistant".to_string(),
+            content,
+            stop_reason: Some(stop_reason.to_string()),
+            usage: Usage { input_tokens: 0, output_tokens: 0, cache_creation_input_tokens: None, cache_read_input_tokens: None },
+        }));
+        self
+    }
+
+    /// Fail the call the way ClaudeClient reports API errors
+    pub fn error(self, message: &str) -> Self {
+        self.turns.lock().unwrap().push_back(Err(message.to_string()));
+        self
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.turns.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    async fn generate_content(
+        &self,
+        messages: Vec<ClaudeMessage>,
+        tools: Option<Vec<ClaudeTool>>,
+        _system: Option<String>,
+    ) -> Result<ClaudeResponse, String> {
+        self.requests.lock().unwrap().push(RecordedRequest { messages, tools });
+        self.turns
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Err("Script exhausted: the loop asked for more turns than were scripted".to_string()))
+    }
+
+    fn with_model(&self, _model: &str) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+}
+
+struct Run {
+    result: Result<String, String>,
+    /// FFmpeg invocations, in dispatch order
+    commands: Vec<Vec<String>>,
+    /// Phases of the AgentState updates, in the order they were sent
+    phases: Vec<String>,
+    states: Vec<AgentState>,
+}
+
+/// Run the ReAct loop against a script, with `commands` already queued as user input
+async fn run(provider: Arc<ScriptedProvider>, commands: Vec<UserCommand>) -> Run {
+    let mut agent = ReActClaudeAgent::new(provider, "session-test".to_string(), "Trim and rotate input.mp4".to_string());
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let (command_tx, command_rx) = mpsc::unbounded_channel();
+    for command in commands {
+        command_tx.send(command).unwrap();
+    }
+
+    let (result, commands) = capture_ffmpeg(agent.execute_with_state(progress_tx, command_rx)).await;
+
+    let mut states = Vec::new();
+    while let Ok(state) = progress_rx.try_recv() {
+        states.push(state);
+    }
+    let phases = states
+        .iter()
+        .map(|state| serde_json::to_value(state).unwrap()["phase"].as_str().unwrap_or_default().to_string())
+        .collect();
+    Run { result, commands, phases, states }
+}
+
+fn tool_results(message: &ClaudeMessage) -> Vec<(String, String)> {
+    match &message.content {
+        ClaudeContent::Blocks(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::ToolResult { tool_use_id, content, .. } => Some((tool_use_id.clone(), content.clone())),
+                _ => None,
+            })
+            .collect(),
+        ClaudeContent::Text(_) => vec![],
+    }
+}
+
+#[tokio::test]
+async fn dispatches_scripted_tool_calls_in_order() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .text("1. Trim the clip\n2. Rotate it")
+            .tool_call("toolu_1", "trim_video", json!({"input_file": "input.mp4", "output_file": "trimmed.mp4", "start_seconds": 1, "end_seconds": 3}))
+            .tool_call("toolu_2", "rotate_video", json!({"input_file": "trimmed.mp4", "output_file": "rotated.mp4", "degrees": 90}))
+            .text("Trimmed and rotated."),
+    );
+
+    let run = run(provider.clone(), vec![]).await;
+
+    assert_eq!(run.result, Ok("Trimmed and rotated.".to_string()));
+    assert_eq!(provider.remaining(), 0);
+    assert_eq!(run.commands.len(), 2, "one FFmpeg call per tool: {:?}", run.commands);
+    assert!(run.commands[0].iter().any(|arg| arg.ends_with("trimmed.mp4")));
+    assert!(run.commands[1].iter().any(|arg| arg.ends_with("rotated.mp4")));
+
+    let requests = provider.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[0].tools.is_none(), "planning runs without tools");
+    assert!(requests[1..].iter().all(|request| request.tools.is_some()));
+
+    // Each tool result goes back to the model keyed by its tool_use id, after the matching assistant turn
+    let after_first = &requests[2].messages;
+    assert_eq!(after_first.last().unwrap().role, "user");
+    assert_eq!(tool_results(after_first.last().unwrap())[0].0, "toolu_1");
+    let after_second = &requests[3].messages;
+    assert_eq!(after_second.len(), 5);
+    assert_eq!(tool_results(after_second.last().unwrap())[0].0, "toolu_2");
+
+    match run.states.last() {
+        Some(AgentState::Completed { total_steps_executed, .. }) => assert_eq!(*total_steps_executed, 2),
+        other => panic!("expected Completed, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn streams_states_in_react_order() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .text("1. Trim the clip")
+            .tool_call("toolu_1", "trim_video", json!({"input_file": "input.mp4", "output_file": "trimmed.mp4", "start_seconds": 0, "end_seconds": 2}))
+            .text("Done."),
+    );
+
+    let run = run(provider, vec![]).await;
+
+    assert!(run.result.is_ok());
+    assert_eq!(
+        run.phases,
+        ["planning", "planning", "thinking", "executing", "observing", "reflecting", "thinking", "reflecting", "completed"]
+    );
+}
+
+#[tokio::test]
+async fn tool_errors_are_returned_to_the_model() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .text("1. Explode the video")
+            .tool_call("toolu_1", "explode_video", json!({"input_file": "input.mp4"}))
+            .tool_call("toolu_2", "trim_video", json!({"input_file": "input.mp4", "output_file": "trimmed.mp4", "start_seconds": 0, "end_seconds": 2}))
+            .text("That tool doesn't exist, so I trimmed instead."),
+    );
+
+    let run = run(provider.clone(), vec![]).await;
+
+    // The failed call doesn't end the loop; the model sees the error and recovers
+    assert_eq!(run.result, Ok("That tool doesn't exist, so I trimmed instead.".to_string()));
+    let requests = provider.requests();
+    let (id, content) = &tool_results(requests[2].messages.last().unwrap())[0];
+    assert_eq!(id, "toolu_1");
+    assert!(content.starts_with("❌ Unknown tool"), "{}", content);
+    assert_eq!(run.commands.len(), 1);
+}
+
+#[tokio::test]
+async fn provider_errors_fail_the_run() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .text("1. Trim the clip")
+            .error("overloaded_error"),
+    );
+
+    let run = run(provider, vec![]).await;
+
+    assert_eq!(run.result, Err("Claude API Error: overloaded_error".to_string()));
+    assert!(run.commands.is_empty());
+    assert!(!run.phases.contains(&"completed".to_string()));
+}
+
+#[tokio::test]
+async fn planning_errors_fail_before_any_tool_runs() {
+    let provider = Arc::new(ScriptedProvider::new().error("invalid x-api-key"));
+
+    let run = run(provider.clone(), vec![]).await;
+
+    assert_eq!(run.result, Err("Planning failed: invalid x-api-key".to_string()));
+    assert_eq!(provider.requests().len(), 1);
+    assert_eq!(run.phases, ["planning"]);
+}
+
+#[tokio::test]
+async fn cancel_stops_before_the_next_model_call() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .text("1. Trim the clip")
+            .tool_call("toolu_1", "trim_video", json!({"input_file": "input.mp4", "output_file": "trimmed.mp4", "start_seconds": 0, "end_seconds": 2})),
+    );
+
+    let run = run(provider.clone(), vec![UserCommand::Cancel]).await;
+
+    assert_eq!(run.result, Err("Cancelled by user".to_string()));
+    assert_eq!(provider.requests().len(), 1, "only the planning call is made");
+    assert!(run.commands.is_empty());
+}
+
+#[tokio::test]
+async fn new_instructions_are_added_to_the_conversation() {
+    let provider = Arc::new(
+        ScriptedProvider::new()
+            .text("1. Trim the clip")
+            .text("Okay, I'll keep the audio."),
+    );
+
+    let run = run(provider.clone(), vec![UserCommand::NewInstruction("Keep the audio".to_string())]).await;
+
+    assert!(run.result.is_ok());
+    let requests = provider.requests();
+    match &requests[1].messages.last().unwrap().content {
+        ClaudeContent::Text(text) => assert_eq!(text, "NEW INSTRUCTION: Keep the audio"),
+        other => panic!("expected the instruction as text, got {:?}", other),
+    }
+    assert_eq!(run.phases[..3], ["planning", "planning", "interrupted"]);
+}
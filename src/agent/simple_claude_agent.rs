@@ -2,16 +2,19 @@
 // NO Rig framework - direct API calls that actually work
 // Uses comprehensive tool_executor with all 35 tools
 
-use crate::claude_client::{ClaudeClient, ClaudeMessage, ClaudeContent, ContentBlock};
-use crate::agent::tool_executor::{execute_tool_claude_with_context, ToolExecutionContext};
+use crate::agent::llm_provider::LlmProvider;
+use crate::claude_client::{ClaudeMessage, ClaudeContent, ContentBlock};
+use crate::agent::tool_executor::{execute_tool_claude_with_context, with_render_progress, ToolExecutionContext};
+use crate::services::token_budget::{BudgetState, ECONOMY_CLAUDE_MODEL};
+use crate::services::{FeatureFlagService, RenderEstimateService, SessionDefaultsService, TokenBudgetService};
 use std::sync::Arc;
 
 pub struct SimpleClaudeAgent {
-    client: Arc<ClaudeClient>,
+    client: Arc<dyn LlmProvider>,
 }
 
 impl SimpleClaudeAgent {
-    pub fn new(client: Arc<ClaudeClient>) -> Self {
+    pub fn new(client: Arc<dyn LlmProvider>) -> Self {
         Self { client }
     }
 
@@ -36,10 +39,13 @@ impl SimpleClaudeAgent {
             user_id,
             app_state,
         };
-        let tools = crate::claude_client::ClaudeClient::create_video_editing_tools();
+        // Experimental tools stay hidden unless their feature flag is on for this user
+        let hidden_tools = FeatureFlagService::hidden_tools(&exec_context.app_state.db_pool, user_id).await;
+        let mut tools = crate::claude_client::ClaudeClient::create_video_editing_tools();
+        tools.retain(|tool| !hidden_tools.contains(&tool.name));
         let mut messages: Vec<ClaudeMessage> = vec![];
 
-        let system_prompt = r#"You are a professional video editing agent with access to 45+ specialized tools including AUDIO GENERATION. BE CREATIVE AND USE YOUR TOOLS STRATEGICALLY!
+        let mut system_prompt = r#"You are a professional video editing agent with access to 45+ specialized tools including AUDIO GENERATION. BE CREATIVE AND USE YOUR TOOLS STRATEGICALLY!
 
 ## YOUR CAPABILITIES
 
@@ -61,6 +67,7 @@ impl SimpleClaudeAgent {
 ### 2. VIDEO VIEWING & ANALYSIS
 - **view_video**: View any video by retrieving its vectorized embeddings from Qdrant database. This lets you "see" what's in a video without re-processing.
   - CRITICAL: Stock videos from Pexels are auto-vectorized after download, so you CAN view them to verify content before using!
+- **ask_about_video**: Ask a specific question about a video ("when does the logo appear?") and get an answer with [mm:ss] timestamp citations from sampled frames and transcript
 - **analyze_video**: Get technical metadata (duration, resolution, codec, etc.)
 
 ### 2. IMAGE VIEWING & VERIFICATION
@@ -145,7 +152,12 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
 - ❌ DO NOT skip the review step - it ensures quality!
 - ❌ DO NOT present videos without verifying requirements
 - ✅ ALWAYS use review_video, not just view_video
+- 🛠️ When a tool result ends with an `ffmpeg_error` line, apply its remediation (e.g. convert the pixel format, add a soundtrack, fix even dimensions) and retry if `retryable` is true; otherwise explain the problem to the user in plain words
+- ⚙️ When the user asks for something "from now on" (resolution, fps, voice, caption style), call set_session_defaults once instead of repeating it in every call
 - submit_final_answer should be the LAST tool you call"#.to_string();
+        if let Some(note) = SessionDefaultsService::prompt_note(&exec_context.app_state.db_pool, session_id).await {
+            system_prompt.push_str(&note);
+        }
 
         // Add user message
         messages.push(ClaudeMessage {
@@ -156,12 +168,30 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
         let mut iterations = 0;
         let max_iterations = 50; // Safety limit - agent decides when done via submit_final_answer
         let mut final_text = String::new();
+        let mut economy_client: Option<Box<dyn LlmProvider>> = None;
 
         while iterations < max_iterations {
             iterations += 1;
             send_progress(0.0, "🤖 Agent is thinking...");
 
-            let response = self.client.generate_content(
+            // Degrade to a cheaper model and shorter context near the budget, pause once it's spent
+            match TokenBudgetService::check(&exec_context.app_state.db_pool, session_id).await {
+                BudgetState::Exhausted(message) => {
+                    send_progress(0.0, &message);
+                    return Ok(message);
+                }
+                BudgetState::Degraded(reason) => {
+                    if economy_client.is_none() {
+                        send_progress(0.0, &format!("💸 {}", reason));
+                        economy_client = Some(self.client.with_model(ECONOMY_CLAUDE_MODEL));
+                    }
+                    TokenBudgetService::compact_claude_messages(&mut messages);
+                }
+                BudgetState::Normal => {}
+            }
+            let client = economy_client.as_deref().unwrap_or(self.client.as_ref());
+
+            let response = client.generate_content(
                 messages.clone(),
                 Some(tools.clone()),
                 Some(system_prompt.clone()),
@@ -198,8 +228,8 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
                         usage.input_tokens,
                         usage.output_tokens,
                         context_size,
-                        None,
-                        None,
+                        usage.cache_creation_input_tokens,
+                        usage.cache_read_input_tokens,
                     )
                     .await
                     {
@@ -221,7 +251,8 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
                     crate::claude_client::ResponseContent::ToolUse { id, name, input } => {
                         has_tool_calls = true;
                         tracing::info!("🔧 Claude calling: {}", name);
-                        send_progress(0.0, &format!("🔧 {}...", name));
+                        let eta = RenderEstimateService::eta_note(&exec_context.app_state.db_pool, name, input).await;
+                        send_progress(0.0, &format!("🔧 {}...{}", name, eta));
 
                         assistant_blocks.push(ContentBlock::ToolUse {
                             id: id.clone(),
@@ -229,7 +260,12 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
                             input: input.clone(),
                         });
 
-                        let result = execute_tool_claude_with_context(name, input, &exec_context).await;
+                        let result = with_render_progress(
+                            name,
+                            progress_callback.as_ref(),
+                            execute_tool_claude_with_context(name, input, &exec_context),
+                        )
+                        .await;
 
                         // CRITICAL: If this is submit_final_answer, capture its result as the final response and exit
                         if name == "submit_final_answer" && !result.is_empty() {
@@ -3,7 +3,9 @@
 // Uses comprehensive tool_executor with all 34 tools
 
 use crate::gemini_client::{GeminiClient, GenerateContentRequest, Content, Part, Tool, GenerationConfig, ToolConfig, FunctionCallingConfig, FunctionCallingMode};
-use crate::agent::tool_executor::{execute_tool_gemini_with_context, ToolExecutionContext};
+use crate::agent::tool_executor::{execute_tool_gemini_with_context, with_render_progress, ToolExecutionContext};
+use crate::services::token_budget::{BudgetState};
+use crate::services::{FeatureFlagService, RenderEstimateService, SessionDefaultsService, TokenBudgetService};
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -36,7 +38,10 @@ impl SimpleGeminiAgent {
             user_id,
             app_state,
         };
-        let tools = GeminiClient::create_video_editing_tools();
+        // Experimental tools stay hidden unless their feature flag is on for this user
+        let hidden_tools = FeatureFlagService::hidden_tools(&exec_context.app_state.db_pool, user_id).await;
+        let mut tools = GeminiClient::create_video_editing_tools();
+        tools.retain(|tool| !hidden_tools.contains(&tool.name));
         let mut conversation: Vec<Content> = vec![];
 
         // System instruction as first user message
@@ -62,6 +67,7 @@ impl SimpleGeminiAgent {
 ### 2. VIDEO VIEWING & ANALYSIS
 - **view_video**: View any video by retrieving its vectorized embeddings from Qdrant database. This lets you "see" what's in a video without re-processing.
   - CRITICAL: Stock videos from Pexels are auto-vectorized after download, so you CAN view them to verify content before using!
+- **ask_about_video**: Ask a specific question about a video ("when does the logo appear?") and get an answer with [mm:ss] timestamp citations from sampled frames and transcript
 - **analyze_video**: Get technical metadata (duration, resolution, codec, etc.)
 
 ### 2. IMAGE VIEWING & VERIFICATION
@@ -146,7 +152,13 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
 - ❌ DO NOT skip the review step - it ensures quality!
 - ❌ DO NOT present videos without verifying requirements
 - ✅ ALWAYS use review_video, not just view_video
-- submit_final_answer should be the LAST tool you call"#;
+- 🛠️ When a tool result ends with an `ffmpeg_error` line, apply its remediation (e.g. convert the pixel format, add a soundtrack, fix even dimensions) and retry if `retryable` is true; otherwise explain the problem to the user in plain words
+- ⚙️ When the user asks for something "from now on" (resolution, fps, voice, caption style), call set_session_defaults once instead of repeating it in every call
+- submit_final_answer should be the LAST tool you call"#.to_string();
+        let system_instruction = match SessionDefaultsService::prompt_note(&exec_context.app_state.db_pool, session_id).await {
+            Some(note) => system_instruction + &note,
+            None => system_instruction,
+        };
 
         // Add system context + user message
         conversation.push(Content {
@@ -157,11 +169,28 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
         let mut iterations = 0;
         let max_iterations = 50; // Safety limit - agent decides when done via submit_final_answer
         let mut final_text = String::new();
+        let mut degraded = false;
 
         while iterations < max_iterations {
             iterations += 1;
             send_progress(0.0, "🤖 Agent is thinking...");
 
+            // Shorten context near the budget, pause once it's spent
+            match TokenBudgetService::check(&exec_context.app_state.db_pool, session_id).await {
+                BudgetState::Exhausted(message) => {
+                    send_progress(0.0, &message);
+                    return Ok(message);
+                }
+                BudgetState::Degraded(reason) => {
+                    if !degraded {
+                        degraded = true;
+                        send_progress(0.0, &format!("💸 {}", reason));
+                    }
+                    TokenBudgetService::compact_gemini_contents(&mut conversation);
+                }
+                BudgetState::Normal => {}
+            }
+
             let request = GenerateContentRequest {
                 contents: conversation.clone(),
                 tools: Some(vec![Tool { function_declarations: tools.iter().cloned().collect() }]),
@@ -232,9 +261,16 @@ Trimming, merging, splitting, filters, text overlays, color adjustment, audio pr
                         Part::FunctionCall { function_call } => {
                             has_tool_calls = true;
                             tracing::info!("🔧 Gemini calling: {}", function_call.name);
-                            send_progress(0.0, &format!("🔧 {}...", function_call.name));
-
-                            let result = execute_tool_gemini_with_context(&function_call.name, &function_call.args, &exec_context).await;
+                            let args = Value::Object(function_call.args.clone().into_iter().collect());
+                            let eta = RenderEstimateService::eta_note(&exec_context.app_state.db_pool, &function_call.name, &args).await;
+                            send_progress(0.0, &format!("🔧 {}...{}", function_call.name, eta));
+
+                            let result = with_render_progress(
+                                &function_call.name,
+                                progress_callback.as_ref(),
+                                execute_tool_gemini_with_context(&function_call.name, &function_call.args, &exec_context),
+                            )
+                            .await;
 
                             // CRITICAL: If this is submit_final_answer, return immediately
                             if function_call.name == "submit_final_answer" && !result.is_empty() {
@@ -0,0 +1,214 @@
+// src/agent/slash_commands.rs
+// Slash-commands typed in chat (`/trim 0:10 0:30`, `/export youtube`) run one tool directly,
+// without an agent turn. Only exact syntax is taken: anything that doesn't parse (an unknown
+// command, or "/trim the boring part") goes to the agent as usual. Commands work on the file named
+// as their last argument, or else on the session's newest output (or newest uploaded video).
+use serde_json::{json, Map, Value};
+
+/// File extensions a trailing argument can have to be taken as the input file
+const MEDIA_EXTENSIONS: &[&str] = &["mp4", "mov", "mkv", "webm", "avi", "m4v", "mp3", "wav", "m4a", "aac"];
+
+/// Files a command falls back to when none is named
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "mkv", "webm", "avi", "m4v"];
+
+pub const HELP: &str = "Slash-commands run a single edit straight away, on the newest output in this chat \
+(or a file you name at the end, e.g. `/trim 0:10 0:30 intro.mp4`):\n\
+• `/trim <start> <end>` - keep a section (seconds, 1:30, 00:01:30.5, 300f, 25%)\n\
+• `/rotate <90|180|270>`\n\
+• `/flip <horizontal|vertical>`\n\
+• `/speed <factor>` - 0.5 = half speed, 2 = double\n\
+• `/volume <factor>` - 0.5 = half, 2 = double\n\
+• `/compress <high|medium|low>`\n\
+• `/thumbnail <time>`\n\
+• `/audio [mp3|wav|aac]` - extract the soundtrack\n\
+• `/export <preset>` - youtube, instagram, tiktok, ... or one of your export presets\n\
+Anything else is handled by the assistant.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlashCommand {
+    Help,
+    Tool(ToolCommand),
+}
+
+/// A tool call still missing its input and output files
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCommand {
+    pub command: String,
+    pub tool: &'static str,
+    pub args: Map<String, Value>,
+    /// File named in the command, if any
+    pub input: Option<String>,
+    /// Output extension; None keeps the input's
+    pub extension: Option<String>,
+}
+
+impl ToolCommand {
+    /// The complete tool arguments for this input and output
+    pub fn arguments(&self, input_file: &str, output_file: &str) -> Value {
+        let mut args = self.args.clone();
+        args.insert("input_file".to_string(), json!(input_file));
+        args.insert("output_file".to_string(), json!(output_file));
+        Value::Object(args)
+    }
+}
+
+/// Parse a chat message. None means it isn't a command this parser takes; send it to the agent.
+pub fn parse(text: &str) -> Option<SlashCommand> {
+    let text = text.trim();
+    let rest = text.strip_prefix('/')?;
+    let mut words: Vec<&str> = rest.split_whitespace().collect();
+    let command = words.first()?.to_lowercase();
+    if !command.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    words.remove(0);
+
+    if command == "help" && words.is_empty() {
+        return Some(SlashCommand::Help);
+    }
+
+    let input = match words.last() {
+        Some(last) if is_media_file(last) => {
+            let input = last.to_string();
+            words.pop();
+            Some(input)
+        }
+        _ => None,
+    };
+
+    let mut extension = None;
+    let (tool, args) = match (command.as_str(), words.as_slice()) {
+        ("trim", [start, end]) => ("trim_video", json!({ "start_seconds": start, "end_seconds": end })),
+        ("rotate", [degrees]) => {
+            let degrees: u32 = degrees.parse().ok().filter(|d| matches!(d, 90 | 180 | 270))?;
+            ("rotate_video", json!({ "degrees": degrees }))
+        }
+        ("flip", [direction]) => {
+            let direction = match direction.to_lowercase().as_str() {
+                "horizontal" | "h" => "horizontal",
+                "vertical" | "v" => "vertical",
+                _ => return None,
+            };
+            ("flip_video", json!({ "direction": direction }))
+        }
+        ("speed", [factor]) => ("adjust_speed", json!({ "speed_factor": factor_value(factor)? })),
+        ("volume", [factor]) => ("adjust_volume", json!({ "volume_factor": factor_value(factor)? })),
+        ("compress", [quality]) => {
+            let quality = quality.to_lowercase();
+            if !matches!(quality.as_str(), "high" | "medium" | "low") {
+                return None;
+            }
+            ("compress_video", json!({ "quality": quality }))
+        }
+        ("thumbnail", [timestamp]) => {
+            extension = Some("jpg".to_string());
+            ("create_thumbnail", json!({ "timestamp": timestamp }))
+        }
+        ("audio", formats) if formats.len() <= 1 => {
+            let format = formats.first().map(|f| f.to_lowercase()).unwrap_or_else(|| "mp3".to_string());
+            if !matches!(format.as_str(), "mp3" | "wav" | "aac") {
+                return None;
+            }
+            extension = Some(format.clone());
+            ("extract_audio", json!({ "format": format }))
+        }
+        ("export", [preset]) => ("export_with_preset", json!({ "preset": preset.to_lowercase() })),
+        _ => return None,
+    };
+
+    let Value::Object(args) = args else { unreachable!("commands build JSON objects") };
+    Some(SlashCommand::Tool(ToolCommand { command, tool, args, input, extension }))
+}
+
+fn has_extension(path: &str, extensions: &[&str]) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+}
+
+fn is_media_file(word: &str) -> bool {
+    has_extension(word, MEDIA_EXTENSIONS)
+}
+
+pub fn is_video_file(path: &str) -> bool {
+    has_extension(path, VIDEO_EXTENSIONS)
+}
+
+/// "2", "0.5" or "2x"
+fn factor_value(word: &str) -> Option<f64> {
+    word.trim_end_matches(['x', 'X']).parse::<f64>().ok().filter(|f| *f > 0.0 && f.is_finite())
+}
+
+/// `outputs/<stem>_<command>.<ext>`, numbered if that file exists already
+pub fn output_path(input_file: &str, command: &ToolCommand) -> String {
+    let path = std::path::Path::new(input_file);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let extension = command
+        .extension
+        .clone()
+        .or_else(|| path.extension().and_then(|e| e.to_str()).map(str::to_string))
+        .unwrap_or_else(|| "mp4".to_string());
+
+    let mut candidate = format!("outputs/{}_{}.{}", stem, command.command, extension);
+    let mut n = 2;
+    while std::path::Path::new(&candidate).exists() {
+        candidate = format!("outputs/{}_{}_{}.{}", stem, command.command, n, extension);
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(text: &str) -> ToolCommand {
+        match parse(text) {
+            Some(SlashCommand::Tool(command)) => command,
+            other => panic!("{} parsed as {:?}", text, other),
+        }
+    }
+
+    #[test]
+    fn exact_commands_map_to_tool_calls() {
+        let trim = tool("/trim 0:10 0:30");
+        assert_eq!(trim.tool, "trim_video");
+        assert_eq!(trim.input, None);
+        assert_eq!(
+            trim.arguments("uploads/a.mp4", "outputs/a_trim.mp4"),
+            json!({ "input_file": "uploads/a.mp4", "output_file": "outputs/a_trim.mp4", "start_seconds": "0:10", "end_seconds": "0:30" })
+        );
+
+        let export = tool("/export YouTube intro.mov");
+        assert_eq!((export.tool, export.input.as_deref()), ("export_with_preset", Some("intro.mov")));
+        assert_eq!(export.args["preset"], "youtube");
+
+        assert_eq!(tool("/speed 2x").args["speed_factor"], 2.0);
+        assert_eq!(tool("/audio").extension.as_deref(), Some("mp3"));
+        assert_eq!(parse("  /help "), Some(SlashCommand::Help));
+    }
+
+    #[test]
+    fn anything_else_goes_to_the_agent() {
+        for text in [
+            "trim 0:10 0:30",
+            "/trim the boring part",
+            "/rotate 45",
+            "/speed fast",
+            "/frobnicate 3",
+            "/home/user/video.mp4 looks dark",
+            "/",
+        ] {
+            assert_eq!(parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn outputs_are_named_after_the_input() {
+        let thumbnail = tool("/thumbnail 25%");
+        assert!(output_path("uploads/clip.mp4", &thumbnail).starts_with("outputs/clip_thumbnail"));
+        assert!(output_path("uploads/clip.mp4", &thumbnail).ends_with(".jpg"));
+        assert!(output_path("outputs/clip.mov", &tool("/rotate 90")).ends_with("clip_rotate.mov"));
+    }
+}
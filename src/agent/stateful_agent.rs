@@ -4,7 +4,9 @@
 use crate::claude_client::{ClaudeClient, ClaudeMessage, ClaudeContent, ClaudeTool, InputSchema, PropertyDefinition};
 use crate::agent::video_workflow_state::VideoWorkflowManager;
 use crate::agent::conversation_manager::{ConversationManager, ConversationMessage};
-use crate::jobs::video_job;
+use crate::jobs::{video_job, JobPriority};
+use crate::services::token_budget::{BudgetState, DEGRADED_HISTORY_MESSAGES, ECONOMY_CLAUDE_MODEL};
+use crate::services::{MemorySearchService, TokenBudgetService};
 use crate::AppState;
 use std::sync::Arc;
 use std::collections::HashMap;
@@ -41,6 +43,22 @@ impl StatefulClaudeAgent {
         };
 
         send_progress("🔧 Initializing Claude agent (3 control tools + 40+ video editing tools in background job system)...");
+
+        // Pause once the token budget is spent; near it, send less history to a cheaper model
+        let budget = TokenBudgetService::check(&app_state.db_pool, session_id).await;
+        if let BudgetState::Exhausted(message) = budget {
+            send_progress(&message);
+            return Ok(message);
+        }
+        let degraded = matches!(budget, BudgetState::Degraded(_));
+        if let BudgetState::Degraded(reason) = &budget {
+            send_progress(&format!("💸 {}", reason));
+        }
+        let client = if degraded {
+            Arc::new(self.client.with_model(ECONOMY_CLAUDE_MODEL))
+        } else {
+            self.client.clone()
+        };
         let control_tools = Self::create_control_tools();
 
         // Initialize ConversationManager to retrieve and save conversation history
@@ -51,9 +69,9 @@ impl StatefulClaudeAgent {
             tracing::warn!("Failed to initialize conversation schema: {}", e);
         }
 
-        // Retrieve conversation history (last 20 messages)
+        // Retrieve conversation history (last 20 messages, fewer when near the token budget)
         let conversation_history = conversation_manager
-            .get_conversation_history(session_id, Some(20))
+            .get_conversation_history(session_id, Some(if degraded { DEGRADED_HISTORY_MESSAGES } else { 20 }))
             .await
             .unwrap_or_default();
 
@@ -134,7 +152,7 @@ Trust your understanding of natural language to determine user intent:
                 is_first_call = false;
             }
 
-            let response = self.client.generate_content(
+            let response = client.generate_content(
                 conversation_messages.clone(),
                 Some(control_tools.clone()),
                 Some(system_prompt.to_string()),
@@ -168,6 +186,7 @@ Trust your understanding of natural language to determine user intent:
                                 task_description.to_string(),
                                 session_id.to_string(),
                                 agent_type,
+                                JobPriority::High,
                                 app_state.clone(),
                                 job_manager.clone(),
                             ).await;
@@ -230,34 +249,15 @@ Trust your understanding of natural language to determine user intent:
                                 .and_then(|v| v.as_str())
                                 .unwrap_or("");
 
-                            let tool_result = if let Some(ref qdrant_client) = app_state.qdrant_client {
-                                if let Some(ref voyage_embeddings) = app_state.voyage_embeddings {
-                                    match qdrant_client.build_context_for_query_with_voyage(query, session_id, voyage_embeddings).await {
-                                        Ok(context) => {
-                                            if context.is_empty() {
-                                                "No relevant memories found".to_string()
-                                            } else {
-                                                context
-                                            }
-                                        }
-                                        Err(e) => format!("Error searching memory: {}", e)
-                                    }
-                                } else if let Some(ref gemini_client) = app_state.gemini_client {
-                                    match qdrant_client.build_context_for_query_with_gemini(query, session_id, gemini_client).await {
-                                        Ok(context) => {
-                                            if context.is_empty() {
-                                                "No relevant memories found".to_string()
-                                            } else {
-                                                context
-                                            }
-                                        }
-                                        Err(e) => format!("Error searching memory: {}", e)
+                            let tool_result = match MemorySearchService::build_context(&app_state, query, session_id).await {
+                                Ok(context) => {
+                                    if context.is_empty() {
+                                        "No relevant memories found".to_string()
+                                    } else {
+                                        context
                                     }
-                                } else {
-                                    "Memory search unavailable - no embedding client".to_string()
                                 }
-                            } else {
-                                "Memory search unavailable - Qdrant not configured".to_string()
+                                Err(e) => format!("Error searching memory: {}", e)
                             };
 
                             tool_results.push((tool_use_id.clone(), tool_result));
@@ -424,6 +424,17 @@ impl StatefulGeminiAgent {
         };
 
         send_progress("🔧 Initializing Gemini agent (3 control tools + 40+ video editing tools in background job system)...");
+
+        // Pause once the token budget is spent; near it, send less history
+        let budget = TokenBudgetService::check(&app_state.db_pool, session_id).await;
+        if let BudgetState::Exhausted(message) = budget {
+            send_progress(&message);
+            return Ok(message);
+        }
+        let degraded = matches!(budget, BudgetState::Degraded(_));
+        if let BudgetState::Degraded(reason) = &budget {
+            send_progress(&format!("💸 {}", reason));
+        }
         let control_tools = Self::create_control_tools();
 
         // Initialize ConversationManager to retrieve and save conversation history
@@ -434,9 +445,9 @@ impl StatefulGeminiAgent {
             tracing::warn!("Failed to initialize conversation schema: {}", e);
         }
 
-        // Retrieve conversation history (last 20 messages)
+        // Retrieve conversation history (last 20 messages, fewer when near the token budget)
         let conversation_history = conversation_manager
-            .get_conversation_history(session_id, Some(20))
+            .get_conversation_history(session_id, Some(if degraded { DEGRADED_HISTORY_MESSAGES } else { 20 }))
             .await
             .unwrap_or_default();
 
@@ -582,6 +593,7 @@ Trust your understanding of natural language to determine user intent:
                                         task_description.to_string(),
                                         session_id.to_string(),
                                         agent_type,
+                                        JobPriority::High,
                                         app_state.clone(),
                                         job_manager.clone(),
                                     ).await;
@@ -651,53 +663,22 @@ Trust your understanding of natural language to determine user intent:
                                         .and_then(|v| v.as_str())
                                         .unwrap_or("");
 
-                                    let tool_result = if let Some(ref qdrant_client) = app_state.qdrant_client {
-                                        if let Some(ref voyage_embeddings) = app_state.voyage_embeddings {
-                                            match qdrant_client.build_context_for_query_with_voyage(query, session_id, voyage_embeddings).await {
-                                                Ok(context) => {
-                                                    if context.is_empty() {
-                                                        serde_json::json!({
-                                                            "found": false,
-                                                            "message": "No relevant memories found"
-                                                        })
-                                                    } else {
-                                                        serde_json::json!({
-                                                            "found": true,
-                                                            "context": context
-                                                        })
-                                                    }
-                                                }
-                                                Err(e) => serde_json::json!({
-                                                    "error": format!("Error searching memory: {}", e)
+                                    let tool_result = match MemorySearchService::build_context(&app_state, query, session_id).await {
+                                        Ok(context) => {
+                                            if context.is_empty() {
+                                                serde_json::json!({
+                                                    "found": false,
+                                                    "message": "No relevant memories found"
                                                 })
-                                            }
-                                        } else if let Some(ref gemini_client) = app_state.gemini_client {
-                                            match qdrant_client.build_context_for_query_with_gemini(query, session_id, gemini_client).await {
-                                                Ok(context) => {
-                                                    if context.is_empty() {
-                                                        serde_json::json!({
-                                                            "found": false,
-                                                            "message": "No relevant memories found"
-                                                        })
-                                                    } else {
-                                                        serde_json::json!({
-                                                            "found": true,
-                                                            "context": context
-                                                        })
-                                                    }
-                                                }
-                                                Err(e) => serde_json::json!({
-                                                    "error": format!("Error searching memory: {}", e)
+                                            } else {
+                                                serde_json::json!({
+                                                    "found": true,
+                                                    "context": context
                                                 })
                                             }
-                                        } else {
-                                            serde_json::json!({
-                                                "error": "Memory search unavailable - no embedding client"
-                                            })
                                         }
-                                    } else {
-                                        serde_json::json!({
-                                            "error": "Memory search unavailable - Qdrant not configured"
+                                        Err(e) => serde_json::json!({
+                                            "error": format!("Error searching memory: {}", e)
                                         })
                                     };
 
@@ -7,6 +7,7 @@ use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use std::sync::Arc;
 use crate::AppState;
+use crate::utils::file_locks::{self, FileAccess, FileLease};
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use std::time::Duration;
@@ -38,7 +39,7 @@ where
 }
 
 /// Helper function to ensure all output files are in the outputs/ directory
-fn ensure_outputs_directory(file_path: &str) -> String {
+pub(crate) fn ensure_outputs_directory(file_path: &str) -> String {
     // If path is already in outputs/ or starts with outputs/, return as is
     if file_path.starts_with("outputs/") || file_path.starts_with("./outputs/") {
         return file_path.to_string();
@@ -61,16 +62,92 @@ pub struct ToolExecutionContext {
     pub app_state: Arc<AppState>,
 }
 
+/// Receives an agent's progress (0.0-1.0) and a status line
+pub type ProgressCallback = Arc<dyn Fn(f32, &str) + Send + Sync>;
+
+/// Run a tool call with the progress of its long FFmpeg renders ("🔧 resize_video: 42% · ETA 1:23")
+/// sent to the agent's progress callback
+pub async fn with_render_progress<F: std::future::Future>(
+    tool: &str,
+    progress_callback: Option<&ProgressCallback>,
+    future: F,
+) -> F::Output {
+    let Some(callback) = progress_callback.cloned() else {
+        return future.await;
+    };
+    let tool = tool.to_string();
+    let sink: crate::utils::progress::ProgressSink = Arc::new(move |progress| {
+        let fraction = progress.percent.unwrap_or(0.0) / 100.0;
+        callback(fraction as f32, &format!("🔧 {}: {}", tool, progress.describe()));
+    });
+    crate::utils::progress::report_ffmpeg_progress(future, sink).await
+}
+
 /// Execute a tool with full context - saves outputs to DB and vectorizes them
 pub async fn execute_tool_claude_with_context(
     name: &str,
     args: &Value,
     ctx: &ToolExecutionContext,
 ) -> String {
+    // Tools behind a feature flag that is off for this user are refused even if the model calls them
+    if !crate::services::FeatureFlagService::tool_allowed(&ctx.app_state.db_pool, name, ctx.user_id).await {
+        return format!("❌ Error: {} isn't available for your account yet", name);
+    }
+
+    // Library references ("library:<name>") resolve to the asset's path from any session
+    let resolved_args;
+    let args = match resolve_library_references(args, ctx).await {
+        Ok(Some(resolved)) => {
+            resolved_args = resolved;
+            &resolved_args
+        }
+        Ok(None) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    // Session defaults ("everything in 4K with Adam's voice") fill in arguments the call left out
+    let defaulted_args;
+    let args = match crate::services::SessionDefaultsService::apply_to_call(&ctx.app_state.db_pool, &ctx.session_id, name, args).await {
+        Some(defaulted) => {
+            defaulted_args = defaulted;
+            &defaulted_args
+        }
+        None => args,
+    };
+
+    // Time expressions ("00:01:30", "300f", "25%") become seconds before any tool reads them
+    let timed_args;
+    let args = match crate::utils::timecode::normalize_time_arguments(args) {
+        Ok(Some(normalized)) => {
+            timed_args = normalized;
+            &timed_args
+        }
+        Ok(None) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    // Held until the call returns
+    let _files = match lease_files(name, args, ctx).await {
+        Ok(lease) => lease,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
     // Handle special tools that need AppState access
     if name == "view_video" {
         return execute_view_video_with_state_claude(args, ctx).await;
     }
+    if name == "ask_about_video" {
+        return execute_ask_about_video_with_state_claude(args, ctx).await;
+    }
+    if name == "summarize_video" {
+        return summarize_video(
+            args["video_path"].as_str().unwrap_or(""),
+            args["depth"].as_str().unwrap_or("standard"),
+            args["focus"].as_str(),
+            ctx,
+        )
+        .await;
+    }
     if name == "review_video" {
         return execute_review_video_with_state_claude(args, ctx).await;
     }
@@ -92,6 +169,108 @@ pub async fn execute_tool_claude_with_context(
     if name == "set_chat_title" {
         return execute_set_chat_title_with_state_claude(args, ctx).await;
     }
+    if name == "set_session_defaults" {
+        return execute_set_session_defaults_with_state_claude(args, ctx).await;
+    }
+    if name == "upload_to_youtube" {
+        return upload_to_youtube(args, ctx).await;
+    }
+    if name == "remove_fillers" {
+        return execute_remove_fillers_with_state_claude(args, ctx).await;
+    }
+    if name == "select_music" {
+        return execute_select_music_with_state_claude(args, ctx).await;
+    }
+    if name == "censor_profanity" {
+        return execute_censor_profanity_with_state_claude(args, ctx).await;
+    }
+    if name == "transcribe_speakers" {
+        return execute_transcribe_speakers_with_state_claude(args, ctx).await;
+    }
+    if name == "edit_by_speaker" {
+        return execute_edit_by_speaker_with_state_claude(args, ctx).await;
+    }
+    if name == "create_lyric_video" {
+        return execute_create_lyric_video_with_state_claude(args, ctx).await;
+    }
+    if name == "add_styled_captions" {
+        return execute_add_styled_captions_with_state_claude(args, ctx).await;
+    }
+    if name == "generate_captions" {
+        return execute_generate_captions_with_state_claude(args, ctx).await;
+    }
+    if name == "compose_screencast" {
+        return execute_compose_screencast_with_state_claude(args, ctx).await;
+    }
+    if name == "generate_quiz_video" {
+        return execute_generate_quiz_video_with_state_claude(args, ctx).await;
+    }
+    if name == "generate_video_from_article" {
+        return execute_generate_video_from_article_with_state_claude(args, ctx).await;
+    }
+    if name == "generate_talking_head" {
+        return execute_generate_talking_head_with_state_claude(args, ctx).await;
+    }
+    if name == "export_localized" {
+        return execute_export_localized_with_state_claude(args, ctx).await;
+    }
+    if name == "deliver_output" {
+        return execute_deliver_output_with_state_claude(args, ctx).await;
+    }
+    if name == "list_delivery_targets" {
+        return execute_list_delivery_targets_with_state(ctx).await;
+    }
+    if name == "export_with_preset" {
+        return execute_export_with_preset_with_state_claude(args, ctx).await;
+    }
+    if name == "list_export_presets" {
+        return execute_list_export_presets_with_state(ctx).await;
+    }
+    if name == "create_review_link" {
+        return execute_create_review_link_with_state_claude(args, ctx).await;
+    }
+    if name == "compare_versions" {
+        return execute_compare_versions_with_state_claude(args, ctx).await;
+    }
+    if name == "preview_effect_chain" {
+        return execute_preview_effect_chain_with_state_claude(args, ctx).await;
+    }
+    if name == "create_project" {
+        return execute_create_project_with_state_claude(args, ctx).await;
+    }
+    if name == "edit_project" {
+        return execute_edit_project_with_state_claude(args, ctx).await;
+    }
+    if name == "export_project" {
+        return execute_export_project_with_state_claude(args, ctx).await;
+    }
+    if name == "undo_last_edit" {
+        return execute_undo_last_edit_with_state_claude(args, ctx).await;
+    }
+    if name == "redo_edit" {
+        return execute_redo_edit_with_state_claude(args, ctx).await;
+    }
+    if name == "batch_process" {
+        return execute_batch_process_with_state_claude(args, ctx).await;
+    }
+    if name == "rerender_region" {
+        return execute_rerender_region_with_state_claude(args, ctx).await;
+    }
+    if name == "search_library" {
+        return execute_search_library_with_state_claude(args, ctx).await;
+    }
+    if name == "add_to_library" {
+        return execute_add_to_library_with_state_claude(args, ctx).await;
+    }
+    if name == "list_files" {
+        return execute_list_files_with_state_claude(args, ctx).await;
+    }
+    if name == "find_asset" {
+        return execute_find_asset_with_state_claude(args, ctx).await;
+    }
+    if name == "clip_live_stream" {
+        return execute_clip_live_stream_with_state_claude(args, ctx).await;
+    }
 
     // YouTube integration tools (READ-ONLY research tools)
     if name == "optimize_youtube_metadata" {
@@ -110,8 +289,42 @@ pub async fn execute_tool_claude_with_context(
         return execute_search_youtube_channels_with_state_claude(args, ctx).await;
     }
 
-    // Execute the tool first
-    let result = execute_tool_claude(name, args).await;
+    // Re-rendering a recorded output keeps the previous file as an archived version
+    let previous_version = match extract_output_path_from_args(args) {
+        Some(output_path) => archive_before_rerender(&output_path, ctx).await,
+        None => None,
+    };
+    let input_path = extract_input_path_from_args(args);
+
+    // Quick operations take the express lane so they never queue behind long renders
+    let estimate = crate::services::RenderEstimateService::estimate(&ctx.app_state.db_pool, name, args).await;
+    let lane_slot = ctx.app_state.job_manager.acquire_lane(crate::jobs::Lane::for_estimate(estimate.seconds)).await;
+
+    // Execute the tool first, capturing its FFmpeg commands for the render report
+    let started_at = chrono::Utc::now();
+    let started = std::time::Instant::now();
+    let reproducible = session_is_reproducible(ctx).await;
+    let (result, ffmpeg_trace) = crate::utils::trace_ffmpeg(execute_tool_claude(name, args), reproducible).await;
+    let result = with_ffmpeg_failure_feedback(result, &ffmpeg_trace);
+    let render_run = crate::services::render_report::RenderRun {
+        tool: name.to_string(),
+        arguments: args.clone(),
+        started_at,
+        duration_seconds: started.elapsed().as_secs_f64(),
+        ffmpeg: ffmpeg_trace,
+    };
+    drop(lane_slot);
+
+    // Wall time against input size feeds the render time estimator
+    if !render_run.ffmpeg.is_empty() {
+        crate::services::RenderEstimateService::record_in_background(
+            ctx.app_state.db_pool.clone(),
+            name,
+            &render_run.arguments,
+            render_run.duration_seconds,
+            !result.starts_with("❌"),
+        );
+    }
 
     // Auto-vectorize downloaded stock videos from Pexels
     if name == "pexels_download_video" && !result.starts_with("❌") {
@@ -148,7 +361,7 @@ pub async fn execute_tool_claude_with_context(
                     let user_db_id = user_id.unwrap_or(1); // Default to user 1 if not authenticated
 
                     // Save to PostgreSQL
-                    if let Err(e) = crate::services::output_video::OutputVideoService::save_output_video(
+                    let saved = match crate::services::output_video::OutputVideoService::save_output_video(
                         &app_state.db_pool,
                         session_db_id,
                         user_db_id,
@@ -159,22 +372,50 @@ pub async fn execute_tool_claude_with_context(
                         &tool_name,
                         Some("Video created by AI agent"),
                     ).await {
-                        tracing::warn!("Failed to save output video to DB: {}", e);
-                    } else {
-                        tracing::info!("✅ Saved output video to PostgreSQL: {}", output_path);
-                    }
+                        Ok(video) => {
+                            tracing::info!("✅ Saved output video to PostgreSQL: {}", output_path);
+                            match crate::services::EditHistoryService::drop_redo_stack(&app_state.db_pool, session_db_id, video.id).await {
+                                Ok(0) => {}
+                                Ok(dropped) => tracing::info!("↩️ New edit dropped {} undone output(s) to the trash", dropped),
+                                Err(e) => tracing::warn!("Failed to drop the redo stack: {}", e),
+                            }
+                            let parent = record_lineage(&app_state, &video, previous_version, input_path.as_deref()).await;
+                            match crate::services::RenderReportService::record_for_output(&app_state.db_pool, &video, render_run, parent.as_ref()).await {
+                                Ok(report) => tracing::info!("🧾 Render report written: {}", report),
+                                Err(e) => tracing::warn!("Failed to write render report: {}", e),
+                            }
+                            Some(video)
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to save output video to DB: {}", e);
+                            None
+                        }
+                    };
 
                     // Vectorize the output video
-                    if let Err(e) = crate::services::VideoVectorizationService::process_video_for_vectorization(
+                    let content_summary = match crate::services::VideoVectorizationService::process_video_for_vectorization(
                         &output_path,
                         &uuid::Uuid::new_v4().to_string(),
                         &session_id,
                         Some(user_db_id),
                         &app_state,
                     ).await {
-                        tracing::warn!("Failed to vectorize output video: {}", e);
-                    } else {
-                        tracing::info!("✅ Vectorized output video: {}", output_path);
+                        Ok(summary) => {
+                            tracing::info!("✅ Vectorized output video: {}", output_path);
+                            Some(summary)
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to vectorize output video: {}", e);
+                            None
+                        }
+                    };
+
+                    // Title, description and tags for YouTube uploads and library search
+                    if let Some(video) = saved {
+                        match crate::services::OutputMetadataService::generate_for_output(&app_state, &video, content_summary.as_deref()).await {
+                            Ok(metadata) => tracing::info!("🏷️ Generated metadata for {}: {}", output_path, metadata.title),
+                            Err(e) => tracing::warn!("Failed to generate metadata for {}: {}", output_path, e),
+                        }
                     }
                 }
             });
@@ -190,10 +431,71 @@ pub async fn execute_tool_gemini_with_context(
     args: &HashMap<String, Value>,
     ctx: &ToolExecutionContext,
 ) -> String {
+    // Tools behind a feature flag that is off for this user are refused even if the model calls them
+    if !crate::services::FeatureFlagService::tool_allowed(&ctx.app_state.db_pool, name, ctx.user_id).await {
+        return format!("❌ Error: {} isn't available for your account yet", name);
+    }
+
+    // Library references ("library:<name>") resolve to the asset's path from any session
+    let resolved_args: HashMap<String, Value>;
+    let args = match resolve_library_references(&Value::Object(args.clone().into_iter().collect()), ctx).await {
+        Ok(Some(Value::Object(resolved))) => {
+            resolved_args = resolved.into_iter().collect();
+            &resolved_args
+        }
+        Ok(_) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    // Session defaults ("everything in 4K with Adam's voice") fill in arguments the call left out
+    let defaulted_args: HashMap<String, Value>;
+    let args = match crate::services::SessionDefaultsService::apply_to_call(
+        &ctx.app_state.db_pool,
+        &ctx.session_id,
+        name,
+        &Value::Object(args.clone().into_iter().collect()),
+    )
+    .await
+    {
+        Some(Value::Object(defaulted)) => {
+            defaulted_args = defaulted.into_iter().collect();
+            &defaulted_args
+        }
+        _ => args,
+    };
+
+    // Time expressions ("00:01:30", "300f", "25%") become seconds before any tool reads them
+    let timed_args: HashMap<String, Value>;
+    let args = match crate::utils::timecode::normalize_time_arguments(&Value::Object(args.clone().into_iter().collect())) {
+        Ok(Some(Value::Object(normalized))) => {
+            timed_args = normalized.into_iter().collect();
+            &timed_args
+        }
+        Ok(_) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    let _files = match lease_files(name, &Value::Object(args.clone().into_iter().collect()), ctx).await {
+        Ok(lease) => lease,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
     // Handle special tools that need AppState access
     if name == "view_video" {
         return execute_view_video_with_state_gemini(args, ctx).await;
     }
+    if name == "ask_about_video" {
+        return execute_ask_about_video_with_state_gemini(args, ctx).await;
+    }
+    if name == "summarize_video" {
+        return summarize_video(
+            args.get("video_path").and_then(|v| v.as_str()).unwrap_or(""),
+            args.get("depth").and_then(|v| v.as_str()).unwrap_or("standard"),
+            args.get("focus").and_then(|v| v.as_str()),
+            ctx,
+        )
+        .await;
+    }
     if name == "review_video" {
         return execute_review_video_with_state_gemini(args, ctx).await;
     }
@@ -215,6 +517,108 @@ pub async fn execute_tool_gemini_with_context(
     if name == "set_chat_title" {
         return execute_set_chat_title_with_state_gemini(args, ctx).await;
     }
+    if name == "set_session_defaults" {
+        return execute_set_session_defaults_with_state_gemini(args, ctx).await;
+    }
+    if name == "upload_to_youtube" {
+        return upload_to_youtube(&Value::Object(args.clone().into_iter().collect()), ctx).await;
+    }
+    if name == "remove_fillers" {
+        return execute_remove_fillers_with_state_gemini(args, ctx).await;
+    }
+    if name == "select_music" {
+        return execute_select_music_with_state_gemini(args, ctx).await;
+    }
+    if name == "censor_profanity" {
+        return execute_censor_profanity_with_state_gemini(args, ctx).await;
+    }
+    if name == "transcribe_speakers" {
+        return execute_transcribe_speakers_with_state_gemini(args, ctx).await;
+    }
+    if name == "edit_by_speaker" {
+        return execute_edit_by_speaker_with_state_gemini(args, ctx).await;
+    }
+    if name == "create_lyric_video" {
+        return execute_create_lyric_video_with_state_gemini(args, ctx).await;
+    }
+    if name == "add_styled_captions" {
+        return execute_add_styled_captions_with_state_gemini(args, ctx).await;
+    }
+    if name == "generate_captions" {
+        return execute_generate_captions_with_state_gemini(args, ctx).await;
+    }
+    if name == "compose_screencast" {
+        return execute_compose_screencast_with_state_gemini(args, ctx).await;
+    }
+    if name == "generate_quiz_video" {
+        return execute_generate_quiz_video_with_state_gemini(args, ctx).await;
+    }
+    if name == "generate_video_from_article" {
+        return execute_generate_video_from_article_with_state_gemini(args, ctx).await;
+    }
+    if name == "generate_talking_head" {
+        return execute_generate_talking_head_with_state_gemini(args, ctx).await;
+    }
+    if name == "export_localized" {
+        return execute_export_localized_with_state_gemini(args, ctx).await;
+    }
+    if name == "deliver_output" {
+        return execute_deliver_output_with_state_gemini(args, ctx).await;
+    }
+    if name == "list_delivery_targets" {
+        return execute_list_delivery_targets_with_state(ctx).await;
+    }
+    if name == "export_with_preset" {
+        return execute_export_with_preset_with_state_gemini(args, ctx).await;
+    }
+    if name == "list_export_presets" {
+        return execute_list_export_presets_with_state(ctx).await;
+    }
+    if name == "create_review_link" {
+        return execute_create_review_link_with_state_gemini(args, ctx).await;
+    }
+    if name == "compare_versions" {
+        return execute_compare_versions_with_state_gemini(args, ctx).await;
+    }
+    if name == "preview_effect_chain" {
+        return execute_preview_effect_chain_with_state_gemini(args, ctx).await;
+    }
+    if name == "create_project" {
+        return execute_create_project_with_state_gemini(args, ctx).await;
+    }
+    if name == "edit_project" {
+        return execute_edit_project_with_state_gemini(args, ctx).await;
+    }
+    if name == "export_project" {
+        return execute_export_project_with_state_gemini(args, ctx).await;
+    }
+    if name == "undo_last_edit" {
+        return execute_undo_last_edit_with_state_gemini(args, ctx).await;
+    }
+    if name == "redo_edit" {
+        return execute_redo_edit_with_state_gemini(args, ctx).await;
+    }
+    if name == "batch_process" {
+        return execute_batch_process_with_state_gemini(args, ctx).await;
+    }
+    if name == "rerender_region" {
+        return execute_rerender_region_with_state_gemini(args, ctx).await;
+    }
+    if name == "search_library" {
+        return execute_search_library_with_state_gemini(args, ctx).await;
+    }
+    if name == "add_to_library" {
+        return execute_add_to_library_with_state_gemini(args, ctx).await;
+    }
+    if name == "list_files" {
+        return execute_list_files_with_state_gemini(args, ctx).await;
+    }
+    if name == "find_asset" {
+        return execute_find_asset_with_state_gemini(args, ctx).await;
+    }
+    if name == "clip_live_stream" {
+        return execute_clip_live_stream_with_state_gemini(args, ctx).await;
+    }
 
     // YouTube integration tools (READ-ONLY research tools)
     if name == "optimize_youtube_metadata" {
@@ -233,8 +637,43 @@ pub async fn execute_tool_gemini_with_context(
         return execute_search_youtube_channels_with_state_gemini(args, ctx).await;
     }
 
-    // Execute the tool first
-    let result = execute_tool_gemini(name, args).await;
+    // Re-rendering a recorded output keeps the previous file as an archived version
+    let previous_version = match extract_output_path_from_gemini_args(args) {
+        Some(output_path) => archive_before_rerender(&output_path, ctx).await,
+        None => None,
+    };
+    let input_path = extract_input_path_from_gemini_args(args);
+
+    // Quick operations take the express lane so they never queue behind long renders
+    let estimate_args = serde_json::to_value(args).unwrap_or_default();
+    let estimate = crate::services::RenderEstimateService::estimate(&ctx.app_state.db_pool, name, &estimate_args).await;
+    let lane_slot = ctx.app_state.job_manager.acquire_lane(crate::jobs::Lane::for_estimate(estimate.seconds)).await;
+
+    // Execute the tool first, capturing its FFmpeg commands for the render report
+    let started_at = chrono::Utc::now();
+    let started = std::time::Instant::now();
+    let reproducible = session_is_reproducible(ctx).await;
+    let (result, ffmpeg_trace) = crate::utils::trace_ffmpeg(execute_tool_gemini(name, args), reproducible).await;
+    let result = with_ffmpeg_failure_feedback(result, &ffmpeg_trace);
+    let render_run = crate::services::render_report::RenderRun {
+        tool: name.to_string(),
+        arguments: serde_json::to_value(args).unwrap_or_default(),
+        started_at,
+        duration_seconds: started.elapsed().as_secs_f64(),
+        ffmpeg: ffmpeg_trace,
+    };
+    drop(lane_slot);
+
+    // Wall time against input size feeds the render time estimator
+    if !render_run.ffmpeg.is_empty() {
+        crate::services::RenderEstimateService::record_in_background(
+            ctx.app_state.db_pool.clone(),
+            name,
+            &render_run.arguments,
+            render_run.duration_seconds,
+            !result.starts_with("❌"),
+        );
+    }
 
     // Auto-vectorize downloaded stock videos from Pexels
     if name == "pexels_download_video" && !result.starts_with("❌") {
@@ -270,7 +709,7 @@ pub async fn execute_tool_gemini_with_context(
                     let user_db_id = user_id.unwrap_or(1);
 
                     // Save to PostgreSQL
-                    if let Err(e) = crate::services::output_video::OutputVideoService::save_output_video(
+                    let saved = match crate::services::output_video::OutputVideoService::save_output_video(
                         &app_state.db_pool,
                         session_db_id,
                         user_db_id,
@@ -281,22 +720,50 @@ pub async fn execute_tool_gemini_with_context(
                         &tool_name,
                         Some("Video created by AI agent"),
                     ).await {
-                        tracing::warn!("Failed to save output video to DB: {}", e);
-                    } else {
-                        tracing::info!("✅ Saved output video to PostgreSQL: {}", output_path);
-                    }
+                        Ok(video) => {
+                            tracing::info!("✅ Saved output video to PostgreSQL: {}", output_path);
+                            match crate::services::EditHistoryService::drop_redo_stack(&app_state.db_pool, session_db_id, video.id).await {
+                                Ok(0) => {}
+                                Ok(dropped) => tracing::info!("↩️ New edit dropped {} undone output(s) to the trash", dropped),
+                                Err(e) => tracing::warn!("Failed to drop the redo stack: {}", e),
+                            }
+                            let parent = record_lineage(&app_state, &video, previous_version, input_path.as_deref()).await;
+                            match crate::services::RenderReportService::record_for_output(&app_state.db_pool, &video, render_run, parent.as_ref()).await {
+                                Ok(report) => tracing::info!("🧾 Render report written: {}", report),
+                                Err(e) => tracing::warn!("Failed to write render report: {}", e),
+                            }
+                            Some(video)
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to save output video to DB: {}", e);
+                            None
+                        }
+                    };
 
                     // Vectorize the output video
-                    if let Err(e) = crate::services::VideoVectorizationService::process_video_for_vectorization(
+                    let content_summary = match crate::services::VideoVectorizationService::process_video_for_vectorization(
                         &output_path,
                         &uuid::Uuid::new_v4().to_string(),
                         &session_id,
                         Some(user_db_id),
                         &app_state,
                     ).await {
-                        tracing::warn!("Failed to vectorize output video: {}", e);
-                    } else {
-                        tracing::info!("✅ Vectorized output video: {}", output_path);
+                        Ok(summary) => {
+                            tracing::info!("✅ Vectorized output video: {}", output_path);
+                            Some(summary)
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to vectorize output video: {}", e);
+                            None
+                        }
+                    };
+
+                    // Title, description and tags for YouTube uploads and library search
+                    if let Some(video) = saved {
+                        match crate::services::OutputMetadataService::generate_for_output(&app_state, &video, content_summary.as_deref()).await {
+                            Ok(metadata) => tracing::info!("🏷️ Generated metadata for {}: {}", output_path, metadata.title),
+                            Err(e) => tracing::warn!("Failed to generate metadata for {}: {}", output_path, e),
+                        }
                     }
                 }
             });
@@ -306,6 +773,20 @@ pub async fn execute_tool_gemini_with_context(
     result
 }
 
+/// Lease the files a call reads and writes, so edits running at once (a chat command and a
+/// background job) take turns on a file they share instead of corrupting it
+async fn lease_files(name: &str, args: &Value, ctx: &ToolExecutionContext) -> Result<FileLease, String> {
+    let access = FileAccess::of_call(args).map_writes(ensure_outputs_directory);
+    // Files tiered to cold storage come back before the tool reads them (or archives one it overwrites)
+    for path in access.reads.iter().chain(&access.writes) {
+        if let Err(e) = crate::services::StorageTierService::ensure_hot(&ctx.app_state.db_pool, path).await {
+            tracing::warn!("Failed to rehydrate {} for {}: {}", path, name, e);
+        }
+    }
+    let holder = format!("{} in session {}", name, ctx.session_id);
+    file_locks::lease(access, &holder, file_locks::lease_wait()).await
+}
+
 /// Extract output file path from tool arguments
 fn extract_output_path_from_args(args: &Value) -> Option<String> {
     args.get("output_file")
@@ -324,6 +805,111 @@ fn extract_output_path_from_gemini_args(args: &HashMap<String, Value>) -> Option
         .map(|s| s.to_string())
 }
 
+/// Extract input file path from tool arguments
+fn extract_input_path_from_args(args: &Value) -> Option<String> {
+    args.get("input_file")
+        .or_else(|| args.get("input_path"))
+        .or_else(|| args.get("input"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Extract input file path from Gemini-style arguments
+fn extract_input_path_from_gemini_args(args: &HashMap<String, Value>) -> Option<String> {
+    args.get("input_file")
+        .or_else(|| args.get("input_path"))
+        .or_else(|| args.get("input"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Archive the recorded output a tool is about to overwrite; it becomes the new render's parent
+async fn archive_before_rerender(output_path: &str, ctx: &ToolExecutionContext) -> Option<crate::models::file::OutputVideo> {
+    match crate::services::output_video::OutputVideoService::archive_previous_version(&ctx.app_state.db_pool, output_path).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            tracing::warn!("Failed to archive previous version of {}: {}", output_path, e);
+            None
+        }
+    }
+}
+
+/// Link a freshly saved output to the output it was re-rendered from (overwritten or used as input).
+/// Returns that parent, if any.
+async fn record_lineage(
+    app_state: &AppState,
+    video: &crate::models::file::OutputVideo,
+    previous_version: Option<crate::models::file::OutputVideo>,
+    input_path: Option<&str>,
+) -> Option<crate::models::file::OutputVideo> {
+    use crate::services::output_video::OutputVideoService;
+
+    let parent = match (previous_version, input_path) {
+        (Some(previous), _) => Some(previous),
+        (None, Some(input)) => OutputVideoService::find_output_by_any_path(&app_state.db_pool, input).await.ok().flatten(),
+        (None, None) => None,
+    };
+    let parent = parent.filter(|p| p.id != video.id)?;
+
+    match OutputVideoService::link_version(&app_state.db_pool, video.id, &parent).await {
+        Ok(linked) => tracing::info!("🧬 Output {} is v{} (parent: {})", linked.file_path, linked.version, parent.file_path),
+        Err(e) => tracing::warn!("Failed to record output lineage: {}", e),
+    }
+    Some(parent)
+}
+
+/// When a tool's last FFmpeg command failed, append the classified cause as a JSON line the agent can act on
+/// (e.g. convert the pixel format or add a soundtrack, then retry)
+fn with_ffmpeg_failure_feedback(result: String, trace: &[crate::types::FfmpegInvocation]) -> String {
+    let Some(category) = trace.last().filter(|i| !i.success).and_then(|i| i.error_category) else {
+        return result;
+    };
+    let failure = crate::utils::ffmpeg_failure(category, "");
+    let feedback = serde_json::json!({
+        "category": failure.category,
+        "retryable": failure.retryable,
+        "remediation": failure.remediation,
+    });
+    format!("{}\n\n🛠️ ffmpeg_error: {}", result, feedback)
+}
+
+/// The acting user: the context's user, or the owner of the chat session when tools run from a job
+async fn resolve_user_id(ctx: &ToolExecutionContext) -> Option<i32> {
+    if ctx.user_id.is_some() {
+        return ctx.user_id;
+    }
+    sqlx::query_scalar::<_, i32>("SELECT user_id FROM chat_sessions WHERE session_uuid = $1")
+        .bind(&ctx.session_id)
+        .fetch_optional(&ctx.app_state.db_pool)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Whether the session pins its renders so they can be replayed byte-for-byte later
+async fn session_is_reproducible(ctx: &ToolExecutionContext) -> bool {
+    sqlx::query_scalar::<_, bool>("SELECT reproducible_render FROM chat_sessions WHERE session_uuid = $1")
+        .bind(&ctx.session_id)
+        .fetch_optional(&ctx.app_state.db_pool)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
+
+/// Resolve "library:<name>" arguments; Ok(None) when the call has no library references
+async fn resolve_library_references(args: &Value, ctx: &ToolExecutionContext) -> Result<Option<Value>, String> {
+    if !crate::services::LibraryService::has_references(args) {
+        return Ok(None);
+    }
+    let user_id = resolve_user_id(ctx)
+        .await
+        .ok_or_else(|| "library assets require a signed-in user".to_string())?;
+    crate::services::LibraryService::resolve_references(&ctx.app_state.db_pool, user_id, args)
+        .await
+        .map(Some)
+}
+
 /// Get database session ID from UUID session string
 async fn get_session_db_id(session_uuid: &str, app_state: &Arc<AppState>) -> Result<i32, String> {
     sqlx::query_scalar::<_, i32>("SELECT id FROM chat_sessions WHERE session_uuid = $1")
@@ -335,6 +921,29 @@ async fn get_session_db_id(session_uuid: &str, app_state: &Arc<AppState>) -> Res
 
 /// Execute a tool by name with the provided arguments (for Claude - uses Value)
 pub async fn execute_tool_claude(name: &str, args: &Value) -> String {
+    let timed_args;
+    let args = match crate::utils::timecode::normalize_time_arguments(args) {
+        Ok(Some(normalized)) => {
+            timed_args = normalized;
+            &timed_args
+        }
+        Ok(None) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    // Audio and export tools work on the chosen (or default) track of a multi-track input
+    let isolated_args;
+    let _track;
+    let args = match crate::audio::isolate_audio_track(name, args) {
+        Ok(Some((isolated, track))) => {
+            isolated_args = isolated;
+            _track = track;
+            &isolated_args
+        }
+        Ok(None) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
     match name {
         // Core operations
         "trim_video" => execute_trim_video_claude(args),
@@ -348,6 +957,9 @@ pub async fn execute_tool_claude(name: &str, args: &Value) -> String {
         "add_overlay" => execute_add_overlay_claude(args),
         "adjust_color" => execute_adjust_color_claude(args),
         "add_subtitles" => execute_add_subtitles_claude(args),
+        "burn_timecode" => execute_burn_timecode_claude(args),
+        "generate_slate" => execute_generate_slate_claude(args),
+        "preview_safe_zones" => execute_preview_safe_zones_claude(args),
 
         // Transform operations
         "resize_video" => execute_resize_video_claude(args),
@@ -359,22 +971,28 @@ pub async fn execute_tool_claude(name: &str, args: &Value) -> String {
 
         // Audio operations
         "extract_audio" => execute_extract_audio_claude(args),
+        "select_audio_track" => execute_select_audio_track_claude(args),
         "add_audio" => execute_add_audio_claude(args),
         "adjust_volume" => execute_adjust_volume_claude(args),
         "fade_audio" => execute_fade_audio_claude(args),
+        "remove_silence" => execute_remove_silence_claude(args),
 
         // Export operations
         "convert_format" => execute_convert_format_claude(args),
         "compress_video" => execute_compress_video_claude(args),
         "export_for_platform" => execute_export_for_platform_claude(args),
+        "multi_export" => execute_multi_export_claude(args),
         "create_thumbnail" => execute_create_thumbnail_claude(args),
         "extract_frames" => execute_extract_frames_claude(args),
+        "contact_sheet" => execute_contact_sheet_claude(args),
+        "detect_scenes" => execute_detect_scenes_claude(args),
 
         // Advanced operations
         "picture_in_picture" => execute_picture_in_picture_claude(args),
         "chroma_key" => execute_chroma_key_claude(args),
         "split_screen" => execute_split_screen_claude(args),
         "stabilize_video" => execute_stabilize_video_claude(args),
+        "auto_correct" => execute_auto_correct_claude(args),
 
         // AI/Generation tools
         "pexels_search" => execute_pexels_search_claude(args).await,
@@ -404,6 +1022,29 @@ pub async fn execute_tool_claude(name: &str, args: &Value) -> String {
 
 /// Execute a tool by name with the provided arguments (for Gemini - uses HashMap)
 pub async fn execute_tool_gemini(name: &str, args: &HashMap<String, Value>) -> String {
+    let timed_args: HashMap<String, Value>;
+    let args = match crate::utils::timecode::normalize_time_arguments(&Value::Object(args.clone().into_iter().collect())) {
+        Ok(Some(Value::Object(normalized))) => {
+            timed_args = normalized.into_iter().collect();
+            &timed_args
+        }
+        Ok(_) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    // Audio and export tools work on the chosen (or default) track of a multi-track input
+    let isolated_args: HashMap<String, Value>;
+    let _track;
+    let args = match crate::audio::isolate_audio_track(name, &Value::Object(args.clone().into_iter().collect())) {
+        Ok(Some((Value::Object(isolated), track))) => {
+            isolated_args = isolated.into_iter().collect();
+            _track = track;
+            &isolated_args
+        }
+        Ok(_) => args,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
     match name {
         // Core operations
         "trim_video" => execute_trim_video_gemini(args),
@@ -417,6 +1058,9 @@ pub async fn execute_tool_gemini(name: &str, args: &HashMap<String, Value>) -> S
         "add_overlay" => execute_add_overlay_gemini(args),
         "adjust_color" => execute_adjust_color_gemini(args),
         "add_subtitles" => execute_add_subtitles_gemini(args),
+        "burn_timecode" => execute_burn_timecode_gemini(args),
+        "generate_slate" => execute_generate_slate_gemini(args),
+        "preview_safe_zones" => execute_preview_safe_zones_gemini(args),
 
         // Transform operations
         "resize_video" => execute_resize_video_gemini(args),
@@ -428,22 +1072,28 @@ pub async fn execute_tool_gemini(name: &str, args: &HashMap<String, Value>) -> S
 
         // Audio operations
         "extract_audio" => execute_extract_audio_gemini(args),
+        "select_audio_track" => execute_select_audio_track_gemini(args),
         "add_audio" => execute_add_audio_gemini(args),
         "adjust_volume" => execute_adjust_volume_gemini(args),
         "fade_audio" => execute_fade_audio_gemini(args),
+        "remove_silence" => execute_remove_silence_gemini(args),
 
         // Export operations
         "convert_format" => execute_convert_format_gemini(args),
         "compress_video" => execute_compress_video_gemini(args),
         "export_for_platform" => execute_export_for_platform_gemini(args),
+        "multi_export" => execute_multi_export_gemini(args),
         "create_thumbnail" => execute_create_thumbnail_gemini(args),
         "extract_frames" => execute_extract_frames_gemini(args),
+        "contact_sheet" => execute_contact_sheet_gemini(args),
+        "detect_scenes" => execute_detect_scenes_gemini(args),
 
         // Advanced operations
         "picture_in_picture" => execute_picture_in_picture_gemini(args),
         "chroma_key" => execute_chroma_key_gemini(args),
         "split_screen" => execute_split_screen_gemini(args),
         "stabilize_video" => execute_stabilize_video_gemini(args),
+        "auto_correct" => execute_auto_correct_gemini(args),
 
         // AI/Generation tools
         "pexels_search" => execute_pexels_search_gemini(args).await,
@@ -525,13 +1175,49 @@ fn execute_merge_videos_claude(args: &Value) -> String {
 
 fn execute_analyze_video_claude(args: &Value) -> String {
     let input = args["input_file"].as_str().unwrap_or("");
-    match crate::core::analyze_video(input) {
-        Ok(metadata) => serde_json::to_string_pretty(&metadata)
-            .unwrap_or_else(|_| "Failed to serialize metadata".to_string()),
+    match crate::core::probe_media(input) {
+        Ok(info) => describe_media_info(&info),
         Err(e) => e,
     }
 }
 
+/// Probe JSON plus the duration as timecodes, so later time arguments can be given the same way
+fn describe_media_info(info: &crate::types::MediaInfo) -> String {
+    let json = serde_json::to_string_pretty(info).unwrap_or_else(|_| "Failed to serialize metadata".to_string());
+    let smpte = if info.fps() > 0.0 {
+        format!(" (SMPTE {} at {:.3} fps)", crate::utils::timecode::format_frames(info.duration_seconds, info.fps()), info.fps())
+    } else {
+        String::new()
+    };
+    let tracks = if info.audio_streams.len() > 1 {
+        let used = match crate::audio::choose_audio_track(&info.audio_streams, None) {
+            Ok(crate::audio::AudioTrackChoice::Track(track)) => track + 1,
+            _ => 1,
+        };
+        format!(
+            "\n🔊 Audio tracks:\n{}\nAudio and export tools use track {} unless audio_track picks another (a number, language, title or \"mix\")",
+            crate::audio::describe_audio_tracks(&info.audio_streams),
+            used
+        )
+    } else {
+        String::new()
+    };
+    let surround = if info.audio_streams.iter().any(crate::audio::is_surround) {
+        "\n🔈 Surround audio: audio and export tools fold it down to stereo (downmix \"dialog\" boosts the center channel, \"keep\" leaves it)"
+    } else {
+        ""
+    };
+    format!(
+        "{}\n\n⏱️ Duration: {}{}{}{}\n💡 Time arguments accept {}",
+        json,
+        crate::utils::timecode::format(info.duration_seconds),
+        smpte,
+        tracks,
+        surround,
+        crate::utils::timecode::TIME_FORMATS_HINT
+    )
+}
+
 fn execute_split_video_claude(args: &Value) -> String {
     let input = args["input_file"].as_str().unwrap_or("");
     let output_prefix = args["output_prefix"].as_str().unwrap_or("");
@@ -595,13 +1281,87 @@ fn execute_add_subtitles_claude(args: &Value) -> String {
     crate::visual::add_subtitles(input, subtitle_text, &output).unwrap_or_else(|e| e)
 }
 
+fn execute_burn_timecode_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let start = args.get("start_timecode").and_then(|v| v.as_str()).unwrap_or("00:00:00:00");
+    let position = args.get("position").and_then(|v| v.as_str()).unwrap_or("bottom_center");
+    let font_size = args.get("font_size").and_then(|v| v.as_f64()).map(|size| size.round().max(8.0) as u32);
+    let label = args.get("label").and_then(|v| v.as_str());
+    crate::visual::burn_timecode(input, &output, start, position, font_size, label).unwrap_or_else(|e| e)
+}
+
+fn execute_generate_slate_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let project = args["project"].as_str().map(str::trim).unwrap_or("");
+    if project.is_empty() {
+        return "❌ generate_slate needs a project name".to_string();
+    }
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let date = args.get("date").and_then(|v| v.as_str()).filter(|d| !d.trim().is_empty()).unwrap_or(&today);
+    let duration = args.get("duration").and_then(|v| v.as_f64()).unwrap_or(5.0).clamp(1.0, 30.0);
+    crate::visual::generate_slate(
+        input,
+        &output,
+        project,
+        args.get("version").and_then(|v| v.as_str()),
+        date,
+        args.get("notes").and_then(|v| v.as_str()),
+        duration,
+    )
+    .unwrap_or_else(|e| e)
+}
+
+fn execute_preview_safe_zones_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let zones = match crate::visual::safe_zones_for(args.get("platform").and_then(|v| v.as_str()).unwrap_or("all")) {
+        Ok(zones) => zones,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    // Stills default to the middle of the video, where captions usually are
+    let timestamp = args
+        .get("timestamp")
+        .and_then(|v| v.as_f64())
+        .unwrap_or_else(|| crate::core::get_video_duration(input).map(|d| d / 2.0).unwrap_or(0.0));
+
+    match crate::visual::preview_safe_zones(input, &output, &zones, timestamp) {
+        Ok(_) => {
+            let margins: Vec<String> = zones
+                .iter()
+                .map(|z| {
+                    format!(
+                        "- {}: keep clear of the top {:.0}%, bottom {:.0}%, left {:.0}% and right {:.0}%",
+                        z.platform,
+                        z.top * 100.0,
+                        z.bottom * 100.0,
+                        z.left * 100.0,
+                        z.right * 100.0
+                    )
+                })
+                .collect();
+            format!(
+                "✅ Safe-zone proof saved to {}. Shaded areas are covered by platform UI; captions and key text belong inside the outlines.\n{}",
+                output,
+                margins.join("\n")
+            )
+        }
+        Err(e) => e,
+    }
+}
+
 fn execute_resize_video_claude(args: &Value) -> String {
     let input = args["input_file"].as_str().unwrap_or("");
     let output_raw = args["output_file"].as_str().unwrap_or("");
     let output = ensure_outputs_directory(output_raw);
     let width = args["width"].as_u64().unwrap_or(1920) as u32;
     let height = args["height"].as_u64().unwrap_or(1080) as u32;
-    crate::transform::resize_video(input, &output, width, height).unwrap_or_else(|e| e)
+    let fps = args.get("fps").and_then(|v| v.as_f64());
+    crate::transform::resize_video(input, &output, width, height, fps).unwrap_or_else(|e| e)
 }
 
 fn execute_crop_video_claude(args: &Value) -> String {
@@ -657,6 +1417,14 @@ fn execute_extract_audio_claude(args: &Value) -> String {
     crate::audio::extract_audio(input, &output, format).unwrap_or_else(|e| e)
 }
 
+fn execute_select_audio_track_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let selection = crate::audio::audio_track_argument(args.get("audio_track"));
+    crate::audio::select_audio_track(input, &output, selection.as_deref()).unwrap_or_else(|e| e)
+}
+
 fn execute_add_audio_claude(args: &Value) -> String {
     let input = args["input_file"].as_str().unwrap_or("");
     let output_raw = args["output_file"].as_str().unwrap_or("");
@@ -685,6 +1453,66 @@ fn execute_fade_audio_claude(args: &Value) -> String {
     crate::audio::fade_audio(input, &output, fade_in_duration, fade_out_duration, duration).unwrap_or_else(|e| e)
 }
 
+/// Jump-cut a video: drop its silent or low-energy stretches, keeping a little air around the sound
+fn execute_remove_silence_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    if input.is_empty() || output_raw.is_empty() {
+        return "❌ Error: input_file and output_file are required".to_string();
+    }
+    let threshold_db = args.get("threshold_db").and_then(|v| v.as_f64()).unwrap_or(-35.0).clamp(-80.0, -10.0);
+    let min_silence = args.get("min_silence_seconds").and_then(|v| v.as_f64()).unwrap_or(0.6).clamp(0.1, 10.0);
+    let padding = args.get("padding_ms").and_then(|v| v.as_f64()).unwrap_or(150.0).clamp(0.0, 1000.0) / 1000.0;
+    let crossfade = (args.get("crossfade_ms").and_then(|v| v.as_f64()).unwrap_or(30.0) / 1000.0).clamp(0.005, 0.2);
+
+    let media = match crate::core::probe_media(input) {
+        Ok(media) => media,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    if !media.has_audio() {
+        return format!("❌ {} has no audio track, so there's no silence to detect", input);
+    }
+    let duration = match crate::core::get_video_duration(input) {
+        Ok(d) => d,
+        Err(e) => return format!("❌ Failed to read video duration: {}", e),
+    };
+    let silences = match crate::audio::detect_silences(input, threshold_db, min_silence, duration) {
+        Ok(silences) => silences,
+        Err(e) => return format!("❌ Silence detection failed: {}", e),
+    };
+
+    let plan = crate::audio::plan_silence_removal(&silences, duration, padding);
+    if plan.silences_cut == 0 {
+        return format!(
+            "✅ No silent stretches of {:.1}s or more below {:.0} dB - video left unchanged. Raise threshold_db (e.g. -30) or lower min_silence_seconds to cut more.",
+            min_silence, threshold_db
+        );
+    }
+    if plan.keep_segments.is_empty() {
+        return format!("❌ The whole video is below {:.0} dB - nothing would be left. Lower threshold_db (e.g. -45) and retry.", threshold_db);
+    }
+
+    let output = ensure_outputs_directory(output_raw);
+    if let Err(e) = crate::core::concat_segments_with_crossfade(input, &output, &plan.keep_segments, crossfade) {
+        return format!("❌ Failed to render jump cut: {}", e);
+    }
+
+    let new_duration = crate::core::get_video_duration(&output).unwrap_or(plan.original_duration - plan.seconds_saved);
+    let saved = plan.original_duration - new_duration;
+    let percent = if plan.original_duration > 0.0 { saved / plan.original_duration * 100.0 } else { 0.0 };
+    format!(
+        "✅ Removed silence and saved to: {}\n\n✂️ Silent stretches cut: {} (below {:.0} dB for {:.1}s+)\n⏱️ Runtime: {} → {} (saved {:.1}s, {:.1}%)",
+        output,
+        plan.silences_cut,
+        threshold_db,
+        min_silence,
+        crate::utils::format_duration(plan.original_duration),
+        crate::utils::format_duration(new_duration),
+        saved,
+        percent
+    )
+}
+
 fn execute_convert_format_claude(args: &Value) -> String {
     let input = args["input_file"].as_str().unwrap_or("");
     let output_raw = args["output_file"].as_str().unwrap_or("");
@@ -709,25 +1537,206 @@ fn execute_export_for_platform_claude(args: &Value) -> String {
     crate::export::export_for_platform(input, &output, platform).unwrap_or_else(|e| e)
 }
 
-fn execute_create_thumbnail_claude(args: &Value) -> String {
-    let input = args["input_file"].as_str().unwrap_or("");
-    let output_raw = args["output_file"].as_str().unwrap_or("");
-    let output = ensure_outputs_directory(output_raw);
-    let timestamp = args["timestamp"].as_f64().unwrap_or(0.0);
-    // Note: create_thumbnail only takes 3 params (input, output, timestamp) - width/height not supported
-    crate::transform::create_thumbnail(input, &output, timestamp).unwrap_or_else(|e| e)
-}
-
-fn execute_extract_frames_claude(args: &Value) -> String {
+fn execute_multi_export_claude(args: &Value) -> String {
     let input = args["input_file"].as_str().unwrap_or("");
     let output_dir = args["output_dir"].as_str().unwrap_or("");
-    let frame_rate = args.get("frame_rate").and_then(|v| v.as_f64()).unwrap_or(1.0);
-    let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("png");
-    crate::export::extract_frames(input, output_dir, frame_rate, format).unwrap_or_else(|e| e)
+    let renditions: Vec<String> = args["renditions"]
+        .as_array()
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let fps = args["fps"].as_f64();
+    let allow_upscale = args["allow_upscale"].as_bool().unwrap_or(false);
+    multi_export(input, output_dir, &renditions, fps, allow_upscale)
 }
 
-fn execute_picture_in_picture_claude(args: &Value) -> String {
-    let main_video = args["main_video"].as_str().unwrap_or("");
+/// Render every requested rendition in one pass into `outputs/<output_dir>/<stem>_<rendition>.mp4`.
+/// Renditions larger than the source are skipped unless upscaling is allowed.
+fn multi_export(input: &str, output_dir: &str, renditions: &[String], fps: Option<f64>, allow_upscale: bool) -> String {
+    if input.is_empty() || renditions.is_empty() {
+        return "❌ Error: input_file and renditions are required".to_string();
+    }
+    if renditions.len() > 8 {
+        return "❌ Error: at most 8 renditions per export".to_string();
+    }
+    let source = match crate::core::probe_media(input) {
+        Ok(info) => info,
+        Err(e) => return format!("❌ Error: could not read {}: {}", input, e),
+    };
+
+    let stem = std::path::Path::new(input).file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+    let folder = if output_dir.is_empty() { format!("{}_renditions", stem) } else { output_dir.to_string() };
+    let folder = ensure_outputs_directory(&folder);
+    if let Err(e) = std::fs::create_dir_all(&folder) {
+        return format!("❌ Error: could not create {}: {}", folder, e);
+    }
+
+    let mut outputs = Vec::new();
+    let mut skipped = Vec::new();
+    for value in renditions {
+        let rendition = match crate::export::Rendition::parse(value) {
+            Ok(rendition) => rendition,
+            Err(e) => return format!("❌ Error: {}", e),
+        };
+        if outputs.iter().any(|(r, _): &(crate::export::Rendition, String)| r.name == rendition.name) {
+            continue;
+        }
+        // Compare the longer sides so a vertical crop of a landscape source isn't counted as an upscale
+        let source_long = source.width().max(source.height());
+        let target_long = rendition.width.max(rendition.height);
+        if !allow_upscale && source_long > 0 && target_long > source_long {
+            skipped.push(format!("{} ({}x{})", rendition.name, rendition.width, rendition.height));
+            continue;
+        }
+        let output = format!("{}/{}_{}.mp4", folder, stem, rendition.name);
+        outputs.push((rendition, output));
+    }
+    if outputs.is_empty() {
+        return format!(
+            "❌ Error: every rendition is larger than the {}x{} source ({}). Set allow_upscale to render them anyway",
+            source.width(),
+            source.height(),
+            skipped.join(", ")
+        );
+    }
+
+    if let Err(e) = crate::export::multi_export(input, &outputs, fps) {
+        return format!("❌ Error: multi-rendition export failed: {}", e);
+    }
+
+    let mut summary = format!("✅ Exported {} renditions from one decode pass into {}:", outputs.len(), folder);
+    for (rendition, output) in &outputs {
+        summary.push_str(&format!(
+            "\n- {} ({}x{}, {} kbps): {}",
+            rendition.name, rendition.width, rendition.height, rendition.video_bitrate_kbps, output
+        ));
+    }
+    if !skipped.is_empty() {
+        summary.push_str(&format!(
+            "\nSkipped (larger than the {}x{} source): {}",
+            source.width(),
+            source.height(),
+            skipped.join(", ")
+        ));
+    }
+    summary
+}
+
+fn execute_create_thumbnail_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let timestamp = args["timestamp"].as_f64().unwrap_or(0.0);
+    // Note: create_thumbnail only takes 3 params (input, output, timestamp) - width/height not supported
+    crate::transform::create_thumbnail(input, &output, timestamp).unwrap_or_else(|e| e)
+}
+
+fn execute_extract_frames_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_dir = args["output_dir"].as_str().unwrap_or("");
+    let frame_rate = args.get("frame_rate").and_then(|v| v.as_f64()).unwrap_or(1.0);
+    let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("png");
+    crate::export::extract_frames(input, output_dir, frame_rate, format).unwrap_or_else(|e| e)
+}
+
+fn execute_contact_sheet_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    if !matches!(crate::utils::get_file_extension(&output).as_deref(), Some("png" | "jpg" | "jpeg")) {
+        return format!("❌ Error: a contact sheet is an image; save it as .png or .jpg, not {}", output_raw);
+    }
+    let max_frames = args.get("max_frames").and_then(|v| v.as_u64()).unwrap_or(24).clamp(1, 60) as usize;
+    let columns = args.get("columns").and_then(|v| v.as_u64()).unwrap_or(4).max(1) as usize;
+    let threshold = args.get("threshold").and_then(|v| v.as_f64()).unwrap_or(0.3).clamp(0.05, 0.9);
+    let thumb_width = args.get("thumb_width").and_then(|v| v.as_u64()).unwrap_or(320).clamp(160, 640) as u32;
+
+    let duration = match crate::core::get_video_duration(input) {
+        Ok(duration) => duration,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let changes = match crate::export::scene_changes(input, threshold) {
+        Ok(changes) => changes,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let times = crate::export::contact_sheet_times(&changes, duration, max_frames);
+
+    match crate::export::contact_sheet(input, &output, &times, columns, thumb_width) {
+        Ok(_) => {
+            let found = if changes.is_empty() {
+                "No scene changes found, so frames are evenly spaced".to_string()
+            } else {
+                format!("{} scene change(s) found", changes.len())
+            };
+            let listed: Vec<String> = times
+                .iter()
+                .enumerate()
+                .map(|(i, time)| format!("{}. {}", i + 1, crate::utils::format_duration(*time)))
+                .collect();
+            format!(
+                "✅ Contact sheet of {} frames saved to {}. {}.\n{}",
+                times.len(),
+                output,
+                found,
+                listed.join("\n")
+            )
+        }
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+fn execute_detect_scenes_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let threshold = args.get("threshold").and_then(|v| v.as_f64()).unwrap_or(0.3).clamp(0.05, 0.9);
+    let min_scene_seconds = args.get("min_scene_seconds").and_then(|v| v.as_f64()).unwrap_or(1.0).clamp(0.1, 60.0);
+    let thumbnail_dir = args.get("thumbnails").and_then(|v| v.as_bool()).unwrap_or(true).then(|| {
+        match args.get("output_dir").and_then(|v| v.as_str()).filter(|d| !d.is_empty()) {
+            Some(dir) => ensure_outputs_directory(dir.trim_end_matches('/')),
+            None => {
+                let stem = std::path::Path::new(input).file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+                format!("outputs/scenes_{}", stem)
+            }
+        }
+    });
+
+    match crate::advanced::detect_scenes(input, threshold, min_scene_seconds, thumbnail_dir.as_deref()) {
+        Ok(scenes) => {
+            let listed: Vec<String> = scenes
+                .iter()
+                .map(|scene| {
+                    let thumbnail = scene.thumbnail.as_ref().map(|t| format!(" · {}", t)).unwrap_or_default();
+                    format!(
+                        "{}. {} → {} ({:.1}s){}",
+                        scene.index,
+                        crate::utils::format_duration(scene.start),
+                        crate::utils::format_duration(scene.end),
+                        scene.end - scene.start,
+                        thumbnail
+                    )
+                })
+                .collect();
+            let boundaries: Vec<Value> = scenes.iter().map(|s| serde_json::json!([s.start, s.end])).collect();
+            let mut reply = format!(
+                "✅ {} scene(s) in {}\n{}\n\nScene boundaries in seconds: {}",
+                scenes.len(),
+                input,
+                listed.join("\n"),
+                Value::Array(boundaries)
+            );
+            // A planned cut, moved onto the nearest scene changes
+            let planned = (args.get("start").and_then(|v| v.as_f64()), args.get("end").and_then(|v| v.as_f64()));
+            if let (Some(start), Some(end)) = planned {
+                let changes: Vec<f64> = scenes.iter().skip(1).map(|scene| scene.start).collect();
+                let (start, end) = crate::advanced::snap_to_scenes(start, end, &changes, 2.0, 0.0, f64::INFINITY);
+                reply.push_str(&format!("\nCut snapped to scene changes: start {:.3}s, end {:.3}s", start, end));
+            }
+            reply
+        }
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+fn execute_picture_in_picture_claude(args: &Value) -> String {
+    let main_video = args["main_video"].as_str().unwrap_or("");
     let pip_video = args["pip_video"].as_str().unwrap_or("");
     let output_raw = args["output_file"].as_str().unwrap_or("");
     let output = ensure_outputs_directory(output_raw);
@@ -765,6 +1774,36 @@ fn execute_stabilize_video_claude(args: &Value) -> String {
     crate::transform::stabilize_video(input, &output, strength).unwrap_or_else(|e| e)
 }
 
+fn execute_auto_correct_claude(args: &Value) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let strength = args.get("strength").and_then(|v| v.as_f64()).unwrap_or(0.8);
+    format_auto_correct_result(crate::visual::auto_correct(input, &output, strength), &output)
+}
+
+/// Summarize an auto_correct run for the chat, including the split-frame preview path
+fn format_auto_correct_result(
+    result: Result<(crate::types::ColorAnalysis, String, String), String>,
+    output: &str,
+) -> String {
+    match result {
+        Ok((analysis, filter, preview)) => format!(
+            "✅ Auto-corrected video saved to: {}\n\n📊 Measured over {} sampled frames: avg luma {:.1}, 10-90% luma {:.1}-{:.1}, chroma U {:.1} / V {:.1} (128 = neutral)\n🎛️ Applied: {}\n🖼️ Before/after preview (left: original, right: corrected): {}",
+            output,
+            analysis.frames_sampled,
+            analysis.luma_avg,
+            analysis.luma_low,
+            analysis.luma_high,
+            analysis.u_avg,
+            analysis.v_avg,
+            filter,
+            preview
+        ),
+        Err(e) => format!("❌ Auto-correct failed: {}", e),
+    }
+}
+
 async fn execute_pexels_search_claude(args: &Value) -> String {
     let query = args["query"].as_str().unwrap_or("");
     let media_type = args["media_type"].as_str().unwrap_or("videos");
@@ -774,10 +1813,10 @@ async fn execute_pexels_search_claude(args: &Value) -> String {
         return "❌ Error: query is required for Pexels search".to_string();
     }
 
-    // Get Pexels API key from environment
-    let api_key = match std::env::var("PEXELS_API_KEY") {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: PEXELS_API_KEY environment variable not set".to_string(),
+    // Get Pexels API key from the configuration
+    let api_key = match crate::config::get().api_keys.pexels.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Pexels API key not configured (api_keys.pexels / PEXELS_API_KEY)".to_string(),
     };
 
     let pexels_client = crate::pexels_client::PexelsClient::new(api_key);
@@ -836,10 +1875,10 @@ async fn execute_pexels_download_photo_claude(args: &Value) -> String {
 async fn execute_pexels_get_trending_claude(args: &Value) -> String {
     let per_page = args.get("per_page").and_then(|v| v.as_u64()).unwrap_or(15) as i32;
 
-    // Get Pexels API key from environment
-    let api_key = match std::env::var("PEXELS_API_KEY") {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: PEXELS_API_KEY environment variable not set".to_string(),
+    // Get Pexels API key from the configuration
+    let api_key = match crate::config::get().api_keys.pexels.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Pexels API key not configured (api_keys.pexels / PEXELS_API_KEY)".to_string(),
     };
 
     let pexels_client = crate::pexels_client::PexelsClient::new(api_key);
@@ -856,10 +1895,10 @@ async fn execute_pexels_get_trending_claude(args: &Value) -> String {
 async fn execute_pexels_get_curated_claude(args: &Value) -> String {
     let per_page = args.get("per_page").and_then(|v| v.as_u64()).unwrap_or(15) as i32;
 
-    // Get Pexels API key from environment
-    let api_key = match std::env::var("PEXELS_API_KEY") {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: PEXELS_API_KEY environment variable not set".to_string(),
+    // Get Pexels API key from the configuration
+    let api_key = match crate::config::get().api_keys.pexels.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Pexels API key not configured (api_keys.pexels / PEXELS_API_KEY)".to_string(),
     };
 
     let pexels_client = crate::pexels_client::PexelsClient::new(api_key);
@@ -886,10 +1925,10 @@ async fn execute_analyze_image_claude(args: &Value) -> String {
         return format!("❌ Error: Image file not found: {}", image_path);
     }
 
-    // Get Gemini API key from environment
-    let api_key = match std::env::var("GEMINI_API_KEY").or_else(|_| std::env::var("GOOGLE_API_KEY")) {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string(),
+    // Get Gemini API key from the configuration
+    let api_key = match crate::config::get().api_keys.gemini.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string(),
     };
 
     let gemini_client = crate::gemini_client::GeminiClient::new(api_key);
@@ -921,9 +1960,9 @@ async fn execute_generate_text_to_speech_claude(args: &Value) -> String {
     }
 
     // Get Gemini API key
-    let api_key = match std::env::var("GEMINI_API_KEY").or_else(|_| std::env::var("GOOGLE_API_KEY")) {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string(),
+    let api_key = match crate::config::get().api_keys.gemini.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string(),
     };
 
     // Map voice preference to Gemini voice names
@@ -1018,9 +2057,9 @@ async fn execute_generate_video_script_claude(args: &Value) -> String {
     }
 
     // Get Gemini API key
-    let api_key = match std::env::var("GEMINI_API_KEY").or_else(|_| std::env::var("GOOGLE_API_KEY")) {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string(),
+    let api_key = match crate::config::get().api_keys.gemini.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string(),
     };
 
     let gemini_client = crate::gemini_client::GeminiClient::new(api_key);
@@ -1048,7 +2087,8 @@ fn execute_create_blank_video_claude(args: &Value) -> String {
     let width = args["width"].as_u64().unwrap_or(1920) as u32;
     let height = args["height"].as_u64().unwrap_or(1080) as u32;
     let color = args.get("color").and_then(|v| v.as_str()).unwrap_or("black");
-    crate::utils::create_blank_video(&output, duration, width, height, color).unwrap_or_else(|e| e)
+    let fps = args.get("fps").and_then(|v| v.as_f64());
+    crate::utils::create_blank_video(&output, duration, width, height, color, fps).unwrap_or_else(|e| e)
 }
 
 fn execute_submit_final_answer_claude(args: &Value) -> String {
@@ -1112,9 +2152,8 @@ fn execute_merge_videos_gemini(args: &HashMap<String, Value>) -> String {
 
 fn execute_analyze_video_gemini(args: &HashMap<String, Value>) -> String {
     let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
-    match crate::core::analyze_video(input) {
-        Ok(metadata) => serde_json::to_string_pretty(&metadata)
-            .unwrap_or_else(|_| "Failed to serialize metadata".to_string()),
+    match crate::core::probe_media(input) {
+        Ok(info) => describe_media_info(&info),
         Err(e) => e,
     }
 }
@@ -1177,12 +2216,25 @@ fn execute_add_subtitles_gemini(args: &HashMap<String, Value>) -> String {
     crate::visual::add_subtitles(input, subtitle_text, output).unwrap_or_else(|e| e)
 }
 
+fn execute_burn_timecode_gemini(args: &HashMap<String, Value>) -> String {
+    execute_burn_timecode_claude(&serde_json::to_value(args).unwrap_or_default())
+}
+
+fn execute_generate_slate_gemini(args: &HashMap<String, Value>) -> String {
+    execute_generate_slate_claude(&serde_json::to_value(args).unwrap_or_default())
+}
+
+fn execute_preview_safe_zones_gemini(args: &HashMap<String, Value>) -> String {
+    execute_preview_safe_zones_claude(&serde_json::to_value(args).unwrap_or_default())
+}
+
 fn execute_resize_video_gemini(args: &HashMap<String, Value>) -> String {
     let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
     let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
     let width = args.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32;
     let height = args.get("height").and_then(|v| v.as_u64()).unwrap_or(1080) as u32;
-    crate::transform::resize_video(input, &output, width, height).unwrap_or_else(|e| e)
+    let fps = args.get("fps").and_then(|v| v.as_f64());
+    crate::transform::resize_video(input, output, width, height, fps).unwrap_or_else(|e| e)
 }
 
 fn execute_crop_video_gemini(args: &HashMap<String, Value>) -> String {
@@ -1232,6 +2284,14 @@ fn execute_extract_audio_gemini(args: &HashMap<String, Value>) -> String {
     crate::audio::extract_audio(input, &output, format).unwrap_or_else(|e| e)
 }
 
+fn execute_select_audio_track_gemini(args: &HashMap<String, Value>) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output = ensure_outputs_directory(output);
+    let selection = crate::audio::audio_track_argument(args.get("audio_track"));
+    crate::audio::select_audio_track(input, &output, selection.as_deref()).unwrap_or_else(|e| e)
+}
+
 fn execute_add_audio_gemini(args: &HashMap<String, Value>) -> String {
     let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
     let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
@@ -1257,6 +2317,10 @@ fn execute_fade_audio_gemini(args: &HashMap<String, Value>) -> String {
     crate::audio::fade_audio(input, &output, fade_in_duration, fade_out_duration, duration).unwrap_or_else(|e| e)
 }
 
+fn execute_remove_silence_gemini(args: &HashMap<String, Value>) -> String {
+    execute_remove_silence_claude(&serde_json::to_value(args).unwrap_or_default())
+}
+
 fn execute_convert_format_gemini(args: &HashMap<String, Value>) -> String {
     let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
     let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
@@ -1278,6 +2342,19 @@ fn execute_export_for_platform_gemini(args: &HashMap<String, Value>) -> String {
     crate::export::export_for_platform(input, &output, platform).unwrap_or_else(|e| e)
 }
 
+fn execute_multi_export_gemini(args: &HashMap<String, Value>) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output_dir = args.get("output_dir").and_then(|v| v.as_str()).unwrap_or("");
+    let renditions: Vec<String> = args
+        .get("renditions")
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let fps = args.get("fps").and_then(|v| v.as_f64());
+    let allow_upscale = args.get("allow_upscale").and_then(|v| v.as_bool()).unwrap_or(false);
+    multi_export(input, output_dir, &renditions, fps, allow_upscale)
+}
+
 fn execute_create_thumbnail_gemini(args: &HashMap<String, Value>) -> String {
     let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
     let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
@@ -1286,6 +2363,14 @@ fn execute_create_thumbnail_gemini(args: &HashMap<String, Value>) -> String {
     crate::transform::create_thumbnail(input, &output, timestamp).unwrap_or_else(|e| e)
 }
 
+fn execute_contact_sheet_gemini(args: &HashMap<String, Value>) -> String {
+    execute_contact_sheet_claude(&serde_json::to_value(args).unwrap_or_default())
+}
+
+fn execute_detect_scenes_gemini(args: &HashMap<String, Value>) -> String {
+    execute_detect_scenes_claude(&serde_json::to_value(args).unwrap_or_default())
+}
+
 fn execute_extract_frames_gemini(args: &HashMap<String, Value>) -> String {
     let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
     let output_dir = args.get("output_dir").and_then(|v| v.as_str()).unwrap_or("");
@@ -1329,6 +2414,14 @@ fn execute_stabilize_video_gemini(args: &HashMap<String, Value>) -> String {
     crate::transform::stabilize_video(input, &output, strength).unwrap_or_else(|e| e)
 }
 
+fn execute_auto_correct_gemini(args: &HashMap<String, Value>) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output_raw = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output = ensure_outputs_directory(output_raw);
+    let strength = args.get("strength").and_then(|v| v.as_f64()).unwrap_or(0.8);
+    format_auto_correct_result(crate::visual::auto_correct(input, &output, strength), &output)
+}
+
 async fn execute_pexels_search_gemini(args: &HashMap<String, Value>) -> String {
     let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
     let media_type = args.get("media_type").and_then(|v| v.as_str()).unwrap_or("videos");
@@ -1338,10 +2431,10 @@ async fn execute_pexels_search_gemini(args: &HashMap<String, Value>) -> String {
         return "❌ Error: query is required for Pexels search".to_string();
     }
 
-    // Get Pexels API key from environment
-    let api_key = match std::env::var("PEXELS_API_KEY") {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: PEXELS_API_KEY environment variable not set".to_string(),
+    // Get Pexels API key from the configuration
+    let api_key = match crate::config::get().api_keys.pexels.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Pexels API key not configured (api_keys.pexels / PEXELS_API_KEY)".to_string(),
     };
 
     let pexels_client = crate::pexels_client::PexelsClient::new(api_key);
@@ -1402,10 +2495,10 @@ async fn execute_pexels_download_photo_gemini(args: &HashMap<String, Value>) ->
 async fn execute_pexels_get_trending_gemini(args: &HashMap<String, Value>) -> String {
     let per_page = args.get("per_page").and_then(|v| v.as_u64()).unwrap_or(15) as i32;
 
-    // Get Pexels API key from environment
-    let api_key = match std::env::var("PEXELS_API_KEY") {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: PEXELS_API_KEY environment variable not set".to_string(),
+    // Get Pexels API key from the configuration
+    let api_key = match crate::config::get().api_keys.pexels.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Pexels API key not configured (api_keys.pexels / PEXELS_API_KEY)".to_string(),
     };
 
     let pexels_client = crate::pexels_client::PexelsClient::new(api_key);
@@ -1422,10 +2515,10 @@ async fn execute_pexels_get_trending_gemini(args: &HashMap<String, Value>) -> St
 async fn execute_pexels_get_curated_gemini(args: &HashMap<String, Value>) -> String {
     let per_page = args.get("per_page").and_then(|v| v.as_u64()).unwrap_or(15) as i32;
 
-    // Get Pexels API key from environment
-    let api_key = match std::env::var("PEXELS_API_KEY") {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: PEXELS_API_KEY environment variable not set".to_string(),
+    // Get Pexels API key from the configuration
+    let api_key = match crate::config::get().api_keys.pexels.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Pexels API key not configured (api_keys.pexels / PEXELS_API_KEY)".to_string(),
     };
 
     let pexels_client = crate::pexels_client::PexelsClient::new(api_key);
@@ -1452,10 +2545,10 @@ async fn execute_analyze_image_gemini(args: &HashMap<String, Value>) -> String {
         return format!("❌ Error: Image file not found: {}", image_path);
     }
 
-    // Get Gemini API key from environment
-    let api_key = match std::env::var("GEMINI_API_KEY").or_else(|_| std::env::var("GOOGLE_API_KEY")) {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string(),
+    // Get Gemini API key from the configuration
+    let api_key = match crate::config::get().api_keys.gemini.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string(),
     };
 
     let gemini_client = crate::gemini_client::GeminiClient::new(api_key);
@@ -1488,9 +2581,9 @@ async fn execute_generate_text_to_speech_gemini(args: &HashMap<String, Value>) -
     }
 
     // Get Gemini API key
-    let api_key = match std::env::var("GEMINI_API_KEY").or_else(|_| std::env::var("GOOGLE_API_KEY")) {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string(),
+    let api_key = match crate::config::get().api_keys.gemini.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string(),
     };
 
     // Map voice preference to Gemini voice names
@@ -1585,9 +2678,9 @@ async fn execute_generate_video_script_gemini(args: &HashMap<String, Value>) ->
     }
 
     // Get Gemini API key
-    let api_key = match std::env::var("GEMINI_API_KEY").or_else(|_| std::env::var("GOOGLE_API_KEY")) {
-        Ok(key) if !key.is_empty() => key,
-        _ => return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string(),
+    let api_key = match crate::config::get().api_keys.gemini.clone() {
+        Some(key) => key,
+        None => return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string(),
     };
 
     let gemini_client = crate::gemini_client::GeminiClient::new(api_key);
@@ -1614,7 +2707,8 @@ fn execute_create_blank_video_gemini(args: &HashMap<String, Value>) -> String {
     let width = args.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32;
     let height = args.get("height").and_then(|v| v.as_u64()).unwrap_or(1080) as u32;
     let color = args.get("color").and_then(|v| v.as_str()).unwrap_or("black");
-    crate::utils::create_blank_video(output, duration, width, height, color).unwrap_or_else(|e| e)
+    let fps = args.get("fps").and_then(|v| v.as_f64());
+    crate::utils::create_blank_video(output, duration, width, height, color, fps).unwrap_or_else(|e| e)
 }
 
 fn execute_submit_final_answer_gemini(args: &HashMap<String, Value>) -> String {
@@ -1661,12 +2755,11 @@ async fn execute_generate_image_claude(args: &Value) -> String {
         return "❌ Error: prompt and output_file are required".to_string();
     }
 
-    // Get Gemini API key from environment
-    let api_key = std::env::var("GEMINI_API_KEY")
-        .unwrap_or_else(|_| std::env::var("GOOGLE_API_KEY").unwrap_or_default());
+    // Get Gemini API key from the configuration
+    let api_key = crate::config::get().api_keys.gemini.clone().unwrap_or_default();
 
     if api_key.is_empty() {
-        return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string();
+        return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string();
     }
 
     // Create Gemini client for image generation
@@ -1696,12 +2789,11 @@ async fn execute_generate_image_gemini(args: &HashMap<String, Value>) -> String
         return "❌ Error: prompt and output_file are required".to_string();
     }
 
-    // Get Gemini API key from environment
-    let api_key = std::env::var("GEMINI_API_KEY")
-        .unwrap_or_else(|_| std::env::var("GOOGLE_API_KEY").unwrap_or_default());
+    // Get Gemini API key from the configuration
+    let api_key = crate::config::get().api_keys.gemini.clone().unwrap_or_default();
 
     if api_key.is_empty() {
-        return "❌ Error: GEMINI_API_KEY or GOOGLE_API_KEY environment variable not set".to_string();
+        return "❌ Error: Gemini API key not configured (api_keys.gemini / GEMINI_API_KEY)".to_string();
     }
 
     // Create Gemini client for image generation
@@ -2095,11 +3187,129 @@ async fn execute_view_video_with_state_gemini(args: &HashMap<String, Value>, ctx
     }
 }
 
+/// Answer a question about a video from sampled frames and transcript, citing timestamps
+async fn ask_about_video(video_path_input: &str, question: &str, max_frames: usize, ctx: &ToolExecutionContext) -> String {
+    if video_path_input.is_empty() || question.trim().is_empty() {
+        return "❌ Error: video_path and question are required".to_string();
+    }
+
+    // Resolve file path - try as-is first, then try uploads/ and outputs/
+    let candidates = [
+        video_path_input.to_string(),
+        format!("uploads/{}", video_path_input),
+        format!("outputs/{}", video_path_input),
+    ];
+    let mut video_path = None;
+    for candidate in candidates {
+        if tokio::fs::metadata(&candidate).await.is_ok() {
+            video_path = Some(candidate);
+            break;
+        }
+    }
+    let Some(video_path) = video_path else {
+        return format!("❌ Error: Video file not found: {}", video_path_input);
+    };
+
+    match crate::services::VideoQaService::ask(&ctx.app_state, &video_path, question.trim(), max_frames).await {
+        Ok(answer) => format!("🎥 **{}**\n\n{}", video_path, answer),
+        Err(e) => format!("❌ Failed to answer question about {}: {}", video_path, e),
+    }
+}
+
+/// Summarize a recording of any length (map-reduce over transcript chunks)
+async fn summarize_video(video_path_input: &str, depth: &str, focus: Option<&str>, ctx: &ToolExecutionContext) -> String {
+    if video_path_input.is_empty() {
+        return "❌ Error: video_path is required".to_string();
+    }
+    let Some(depth) = crate::services::video_summary::SummaryDepth::parse(depth) else {
+        return format!("❌ Error: Unknown depth '{}'. Use brief, standard or detailed", depth);
+    };
+
+    // Resolve file path - try as-is first, then try uploads/ and outputs/
+    let candidates = [
+        video_path_input.to_string(),
+        format!("uploads/{}", video_path_input),
+        format!("outputs/{}", video_path_input),
+    ];
+    let mut video_path = None;
+    for candidate in candidates {
+        if tokio::fs::metadata(&candidate).await.is_ok() {
+            video_path = Some(candidate);
+            break;
+        }
+    }
+    let Some(video_path) = video_path else {
+        return format!("❌ Error: Video file not found: {}", video_path_input);
+    };
+
+    match crate::services::VideoSummaryService::summarize(&ctx.app_state, &video_path, depth, focus).await {
+        Ok(summary) => summary,
+        Err(e) => format!("❌ Failed to summarize {}: {}", video_path, e),
+    }
+}
+
+/// Ask about a video (Claude version)
+async fn execute_ask_about_video_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let max_frames = args.get("max_frames").and_then(|v| v.as_u64()).map(|v| v as usize);
+    ask_about_video(
+        args["video_path"].as_str().unwrap_or(""),
+        args["question"].as_str().unwrap_or(""),
+        max_frames.unwrap_or(crate::services::video_qa::DEFAULT_MAX_FRAMES),
+        ctx,
+    )
+    .await
+}
+
+/// Ask about a video (Gemini version)
+async fn execute_ask_about_video_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let max_frames = args.get("max_frames").and_then(|v| v.as_f64()).map(|v| v as usize);
+    ask_about_video(
+        args.get("video_path").and_then(|v| v.as_str()).unwrap_or(""),
+        args.get("question").and_then(|v| v.as_str()).unwrap_or(""),
+        max_frames.unwrap_or(crate::services::video_qa::DEFAULT_MAX_FRAMES),
+        ctx,
+    )
+    .await
+}
+
 /// View video placeholder - calls context version
 async fn execute_view_video_gemini(args: &HashMap<String, Value>) -> String {
     format!("❌ Internal error: view_video must be called with context")
 }
 
+/// A/V sync section of a review, and whether the video is within `max_drift_ms`. A check that can't
+/// run (no FFmpeg, unreadable file) is reported but doesn't fail the review.
+async fn review_av_sync(video_path: &str, max_drift_ms: f64) -> (String, bool) {
+    let path = video_path.to_string();
+    let checked = tokio::task::spawn_blocking(move || crate::utils::av_sync::check(&path))
+        .await
+        .unwrap_or_else(|e| Err(format!("A/V sync check panicked: {}", e)));
+
+    let mut section = "**A/V Sync:**\n".to_string();
+    match checked {
+        Ok(report) => {
+            for line in report.describe() {
+                section.push_str(&format!("  • {}\n", line));
+            }
+            let in_sync = !report.exceeds(max_drift_ms);
+            if in_sync {
+                section.push_str(&format!("  ✅ Within {:.0} ms\n\n", max_drift_ms));
+            } else {
+                section.push_str(&format!(
+                    "  ⚠️ Out of sync by about {:.0} ms (limit {:.0} ms). Shift the audio (e.g. adelay or atrim) and re-render\n\n",
+                    report.drift_ms(),
+                    max_drift_ms
+                ));
+            }
+            (section, in_sync)
+        }
+        Err(e) => {
+            section.push_str(&format!("  • Not checked: {}\n\n", e));
+            (section, true)
+        }
+    }
+}
+
 /// Review video against original requirements - WITH AppState (Claude version)
 async fn execute_review_video_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
     let video_path_input = args["video_path"].as_str().unwrap_or("");
@@ -2107,6 +3317,8 @@ async fn execute_review_video_with_state_claude(args: &Value, ctx: &ToolExecutio
     let expected_features = args.get("expected_features").and_then(|v| v.as_array())
         .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
         .unwrap_or_default();
+    let max_drift_ms = args.get("max_av_drift_ms").and_then(|v| v.as_f64())
+        .unwrap_or(crate::utils::av_sync::DEFAULT_MAX_DRIFT_MS);
 
     if video_path_input.is_empty() || original_request.is_empty() {
         return "❌ Error: video_path and original_request are required".to_string();
@@ -2208,13 +3420,19 @@ async fn execute_review_video_with_state_claude(args: &Value, ctx: &ToolExecutio
     review.push_str(&format!("  • Frames analyzed: {}\n", frame_count));
     review.push_str(&format!("  • Vectorization: Complete ✅\n\n"));
 
+    let (sync_section, in_sync) = review_av_sync(&video_path, max_drift_ms).await;
+    review.push_str(&sync_section);
+
     // Calculate pass/fail
     let all_features_found = expected_features.is_empty() || features_found == total_features;
 
     review.push_str("**Review Result:**\n");
-    if all_features_found {
+    if all_features_found && in_sync {
         review.push_str(&format!("✅ **PASS** - All requirements met ({}/{})\n", features_found, total_features));
         review.push_str("This video is ready to present to the user.\n");
+    } else if all_features_found {
+        review.push_str(&format!("⚠️ **FAIL** - Audio and picture are out of sync ({}/{} requirements met)\n", features_found, total_features));
+        review.push_str("**Recommended Action:** Fix the audio offset and review again before presenting the video.\n");
     } else {
         review.push_str(&format!("⚠️ **FAIL** - Missing requirements ({}/{} found)\n", features_found, total_features));
         review.push_str("**Recommended Action:** Re-edit the video to include missing features or explain to user what cannot be achieved.\n");
@@ -2235,6 +3453,8 @@ async fn execute_review_video_with_state_gemini(args: &HashMap<String, Value>, c
     let expected_features = args.get("expected_features").and_then(|v| v.as_array())
         .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
         .unwrap_or_default();
+    let max_drift_ms = args.get("max_av_drift_ms").and_then(|v| v.as_f64())
+        .unwrap_or(crate::utils::av_sync::DEFAULT_MAX_DRIFT_MS);
 
     if video_path_input.is_empty() || original_request.is_empty() {
         return "❌ Error: video_path and original_request are required".to_string();
@@ -2336,13 +3556,19 @@ async fn execute_review_video_with_state_gemini(args: &HashMap<String, Value>, c
     review.push_str(&format!("  • Frames analyzed: {}\n", frame_count));
     review.push_str(&format!("  • Vectorization: Complete ✅\n\n"));
 
+    let (sync_section, in_sync) = review_av_sync(&video_path, max_drift_ms).await;
+    review.push_str(&sync_section);
+
     // Calculate pass/fail
     let all_features_found = expected_features.is_empty() || features_found == total_features;
 
     review.push_str("**Review Result:**\n");
-    if all_features_found {
+    if all_features_found && in_sync {
         review.push_str(&format!("✅ **PASS** - All requirements met ({}/{})\n", features_found, total_features));
         review.push_str("This video is ready to present to the user.\n");
+    } else if all_features_found {
+        review.push_str(&format!("⚠️ **FAIL** - Audio and picture are out of sync ({}/{} requirements met)\n", features_found, total_features));
+        review.push_str("**Recommended Action:** Fix the audio offset and review again before presenting the video.\n");
     } else {
         review.push_str(&format!("⚠️ **FAIL** - Missing requirements ({}/{} found)\n", features_found, total_features));
         review.push_str("**Recommended Action:** Re-edit the video to include missing features or explain to user what cannot be achieved.\n");
@@ -2825,6 +4051,2426 @@ async fn execute_add_voiceover_to_video_with_state_gemini(args: &HashMap<String,
     }
 }
 
+// ============================================================================
+// TRANSCRIPT-DRIVEN EDITING TOOLS
+// ============================================================================
+
+/// Diarized transcripts are cached here so speaker labels stay stable between tool calls
+const TRANSCRIPT_CACHE_DIR: &str = "temp_transcripts";
+
+/// Cache file for a media file's diarized transcript, keyed by path, size and modification time
+async fn transcript_cache_path(input_file: &str) -> Option<String> {
+    use sha2::{Digest, Sha256};
+    let metadata = tokio::fs::metadata(input_file).await.ok()?;
+    let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    let key = Sha256::digest(format!("{}:{}:{}", input_file, metadata.len(), modified).as_bytes());
+    Some(format!("{}/{:x}.json", TRANSCRIPT_CACHE_DIR, key))
+}
+
+/// Extract the audio track and transcribe it with word-level timestamps via Eleven Labs
+pub(crate) async fn transcribe_media_words(
+    input_file: &str,
+    diarize: bool,
+    app_state: &AppState,
+) -> Result<Vec<crate::types::TranscriptWord>, String> {
+    let elevenlabs_client = app_state.elevenlabs_client.as_ref()
+        .ok_or("Eleven Labs client not available. Set ELEVEN_LABS_API_KEY to enable transcription.")?;
+
+    let cache_path = if diarize { transcript_cache_path(input_file).await } else { None };
+    if let Some(cached) = cache_path.as_ref() {
+        if let Ok(bytes) = tokio::fs::read(cached).await {
+            if let Ok(words) = serde_json::from_slice::<Vec<crate::types::TranscriptWord>>(&bytes) {
+                return Ok(words);
+            }
+        }
+    }
+
+    let temp_audio = format!("outputs/temp_transcribe_{}.mp3", uuid::Uuid::new_v4());
+    crate::audio::extract_audio(input_file, &temp_audio, "mp3")?;
+    let audio_bytes = tokio::fs::read(&temp_audio).await
+        .map_err(|e| format!("Failed to read extracted audio: {}", e));
+    let _ = tokio::fs::remove_file(&temp_audio).await;
+
+    let transcript = elevenlabs_client.speech_to_text(audio_bytes?, "audio.mp3", diarize).await
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+
+    let words: Vec<crate::types::TranscriptWord> = transcript.words.into_iter()
+        .filter(|w| w.word_type == "word")
+        .map(|w| crate::types::TranscriptWord {
+            text: w.text,
+            start: w.start,
+            end: w.end,
+            speaker_id: w.speaker_id,
+        })
+        .collect();
+
+    if let Some(cached) = cache_path {
+        let _ = tokio::fs::create_dir_all(TRANSCRIPT_CACHE_DIR).await;
+        if let Ok(json) = serde_json::to_vec(&words) {
+            let _ = tokio::fs::write(&cached, json).await;
+        }
+    }
+    Ok(words)
+}
+
+/// "Speaker 2" for the provider's speaker_1 (labels are shown 1-based)
+fn speaker_label(speaker_id: &str) -> String {
+    match speaker_id.strip_prefix("speaker_").and_then(|n| n.parse::<usize>().ok()) {
+        Some(n) => format!("Speaker {}", n + 1),
+        None => speaker_id.to_string(),
+    }
+}
+
+/// Accept "Speaker 2", "speaker 2", "2" or the raw "speaker_1"
+fn parse_speaker(input: &str) -> Option<String> {
+    let input = input.trim().to_lowercase();
+    if input.starts_with("speaker_") {
+        return Some(input);
+    }
+    let number = input.trim_start_matches("speaker").trim().parse::<usize>().ok()?;
+    (number >= 1).then(|| format!("speaker_{}", number - 1))
+}
+
+/// Speaker-labelled transcript with talk time per speaker
+async fn transcribe_speakers(input: &str, ctx: &ToolExecutionContext) -> String {
+    if input.is_empty() {
+        return "❌ Error: input_file is required".to_string();
+    }
+    let words = match transcribe_media_words(input, true, &ctx.app_state).await {
+        Ok(words) => words,
+        Err(e) => return format!("❌ {}", e),
+    };
+    if words.is_empty() {
+        return "❌ No speech detected in the file".to_string();
+    }
+
+    let turns = crate::audio::speaker_turns(&words);
+    let mut talk_time: Vec<(String, f64, usize)> = Vec::new();
+    for turn in &turns {
+        match talk_time.iter_mut().find(|(id, _, _)| *id == turn.speaker_id) {
+            Some(entry) => {
+                entry.1 += turn.end - turn.start;
+                entry.2 += 1;
+            }
+            None => talk_time.push((turn.speaker_id.clone(), turn.end - turn.start, 1)),
+        }
+    }
+    talk_time.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut result = format!("✅ Transcribed {} with {} speaker(s)\n\n🗣️ Talk time:\n", input, talk_time.len());
+    for (speaker, seconds, count) in &talk_time {
+        result.push_str(&format!("- {}: {:.0}s across {} turns\n", speaker_label(speaker), seconds, count));
+    }
+    result.push_str("\n📝 Transcript:\n");
+    const MAX_TURNS_SHOWN: usize = 200;
+    for turn in turns.iter().take(MAX_TURNS_SHOWN) {
+        let text: String = turn.text.chars().take(300).collect();
+        result.push_str(&format!(
+            "[{:.1}s - {:.1}s] {}: {}{}\n",
+            turn.start,
+            turn.end,
+            speaker_label(&turn.speaker_id),
+            text,
+            if turn.text.chars().count() > 300 { "…" } else { "" }
+        ));
+    }
+    if turns.len() > MAX_TURNS_SHOWN {
+        result.push_str(&format!("... and {} more turns\n", turns.len() - MAX_TURNS_SHOWN));
+    }
+    result.push_str("\nSpeaker labels are stable for this file: use them with edit_by_speaker. Identify named people (e.g. 'Sarah') from introductions in the transcript.");
+    result
+}
+
+/// Options for a per-speaker edit
+struct SpeakerEditOptions<'a> {
+    input: &'a str,
+    output_raw: &'a str,
+    speaker: &'a str,
+    action: &'a str,
+    interruptions_only: bool,
+    max_turn_seconds: f64,
+    padding_ms: f64,
+}
+
+/// Keep only, cut, or mute one speaker's turns (optionally just their short interruptions)
+async fn edit_by_speaker(opts: SpeakerEditOptions<'_>, ctx: &ToolExecutionContext) -> String {
+    if opts.input.is_empty() || opts.output_raw.is_empty() || opts.speaker.is_empty() {
+        return "❌ Error: input_file, output_file and speaker are required".to_string();
+    }
+    if !matches!(opts.action, "keep" | "remove" | "mute") {
+        return format!("❌ Error: unknown action '{}'. Use keep, remove or mute", opts.action);
+    }
+    let Some(speaker_id) = parse_speaker(opts.speaker) else {
+        return format!("❌ Error: unrecognised speaker '{}'. Use a label from transcribe_speakers, e.g. 'Speaker 2'", opts.speaker);
+    };
+    let output = ensure_outputs_directory(opts.output_raw);
+
+    let duration = match crate::core::get_video_duration(opts.input) {
+        Ok(d) => d,
+        Err(e) => return format!("❌ Failed to read duration: {}", e),
+    };
+    let words = match transcribe_media_words(opts.input, true, &ctx.app_state).await {
+        Ok(words) => words,
+        Err(e) => return format!("❌ {}", e),
+    };
+    let turns = crate::audio::speaker_turns(&words);
+
+    let selected: Vec<&crate::types::SpeakerTurn> = turns
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| {
+            t.speaker_id == speaker_id
+                && (!opts.interruptions_only || crate::audio::is_interruption(&turns, *i, opts.max_turn_seconds))
+        })
+        .map(|(_, t)| t)
+        .collect();
+    if selected.is_empty() {
+        let speakers: Vec<String> = turns.iter().map(|t| speaker_label(&t.speaker_id)).fold(Vec::new(), |mut acc, s| {
+            if !acc.contains(&s) {
+                acc.push(s);
+            }
+            acc
+        });
+        return format!(
+            "❌ No {} found for {}. Speakers in this file: {}",
+            if opts.interruptions_only { "interruptions" } else { "turns" },
+            speaker_label(&speaker_id),
+            speakers.join(", ")
+        );
+    }
+
+    let ranges = crate::audio::turn_ranges(&selected, opts.padding_ms / 1000.0, duration);
+    let affected: f64 = ranges.iter().map(|(s, e)| e - s).sum();
+    let rendered = match opts.action {
+        "mute" => crate::audio::mute_ranges(opts.input, &output, &ranges),
+        "keep" => crate::core::concat_segments_with_crossfade(opts.input, &output, &ranges, 0.03),
+        _ => crate::core::concat_segments_with_crossfade(opts.input, &output, &crate::audio::invert_ranges(&ranges, duration), 0.03),
+    };
+    if let Err(e) = rendered {
+        return format!("❌ Failed to render speaker edit: {}", e);
+    }
+
+    let what = if opts.interruptions_only { "interruptions" } else { "turns" };
+    let verb = match opts.action {
+        "keep" => "Kept only",
+        "remove" => "Cut",
+        _ => "Muted",
+    };
+    format!(
+        "✅ {} {}'s {} and saved to: {}\n\n🗣️ {} {} covering {:.1}s of {:.1}s",
+        verb,
+        speaker_label(&speaker_id),
+        what,
+        output,
+        selected.len(),
+        what,
+        affected,
+        duration
+    )
+}
+
+/// Speaker-labelled transcript (Claude version)
+async fn execute_transcribe_speakers_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    transcribe_speakers(args["input_file"].as_str().unwrap_or(""), ctx).await
+}
+
+/// Speaker-labelled transcript (Gemini version)
+async fn execute_transcribe_speakers_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    transcribe_speakers(args.get("input_file").and_then(|v| v.as_str()).unwrap_or(""), ctx).await
+}
+
+/// Per-speaker edit (Claude version)
+async fn execute_edit_by_speaker_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let opts = SpeakerEditOptions {
+        input: args["input_file"].as_str().unwrap_or(""),
+        output_raw: args["output_file"].as_str().unwrap_or(""),
+        speaker: args["speaker"].as_str().unwrap_or(""),
+        action: args.get("action").and_then(|v| v.as_str()).unwrap_or("keep"),
+        interruptions_only: args.get("interruptions_only").and_then(|v| v.as_bool()).unwrap_or(false),
+        max_turn_seconds: args.get("max_turn_seconds").and_then(|v| v.as_f64()).unwrap_or(5.0),
+        padding_ms: args.get("padding_ms").and_then(|v| v.as_f64()).unwrap_or(250.0),
+    };
+    edit_by_speaker(opts, ctx).await
+}
+
+/// Per-speaker edit (Gemini version)
+async fn execute_edit_by_speaker_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let opts = SpeakerEditOptions {
+        input: args.get("input_file").and_then(|v| v.as_str()).unwrap_or(""),
+        output_raw: args.get("output_file").and_then(|v| v.as_str()).unwrap_or(""),
+        speaker: args.get("speaker").and_then(|v| v.as_str()).unwrap_or(""),
+        action: args.get("action").and_then(|v| v.as_str()).unwrap_or("keep"),
+        interruptions_only: args.get("interruptions_only").and_then(|v| v.as_bool()).unwrap_or(false),
+        max_turn_seconds: args.get("max_turn_seconds").and_then(|v| v.as_f64()).unwrap_or(5.0),
+        padding_ms: args.get("padding_ms").and_then(|v| v.as_f64()).unwrap_or(250.0),
+    };
+    edit_by_speaker(opts, ctx).await
+}
+
+/// Cut filler words and shorten long pauses, reporting how much runtime was saved
+async fn remove_fillers(
+    input: &str,
+    output_raw: &str,
+    aggressiveness: &str,
+    crossfade_ms: f64,
+    ctx: &ToolExecutionContext,
+) -> String {
+    if input.is_empty() || output_raw.is_empty() {
+        return "❌ Error: input_file and output_file are required".to_string();
+    }
+    let output = ensure_outputs_directory(output_raw);
+
+    let duration = match crate::core::get_video_duration(input) {
+        Ok(d) => d,
+        Err(e) => return format!("❌ Failed to read video duration: {}", e),
+    };
+    let words = match transcribe_media_words(input, false, &ctx.app_state).await {
+        Ok(words) => words,
+        Err(e) => return format!("❌ {}", e),
+    };
+    if words.is_empty() {
+        return "❌ No speech detected in the video - nothing to remove".to_string();
+    }
+
+    let plan = crate::audio::plan_filler_removal(&words, duration, aggressiveness);
+    if plan.fillers_removed.is_empty() && plan.pauses_shortened == 0 {
+        return format!("✅ No filler words or long pauses found at '{}' aggressiveness - video left unchanged", aggressiveness);
+    }
+
+    let crossfade = (crossfade_ms / 1000.0).clamp(0.005, 0.2);
+    if let Err(e) = crate::core::concat_segments_with_crossfade(input, &output, &plan.keep_segments, crossfade) {
+        return format!("❌ Failed to render trimmed video: {}", e);
+    }
+
+    let new_duration = crate::core::get_video_duration(&output).unwrap_or(plan.original_duration - plan.seconds_saved);
+    let saved = plan.original_duration - new_duration;
+    let percent = if plan.original_duration > 0.0 { saved / plan.original_duration * 100.0 } else { 0.0 };
+    format!(
+        "✅ Removed fillers and saved to: {}\n\n✂️ Fillers cut: {} ({})\n⏸️ Long pauses shortened: {}\n⏱️ Runtime: {} → {} (saved {:.1}s, {:.1}%)",
+        output,
+        plan.fillers_removed.len(),
+        if plan.fillers_removed.is_empty() { "none".to_string() } else { plan.fillers_removed.join(", ") },
+        plan.pauses_shortened,
+        crate::utils::format_duration(plan.original_duration),
+        crate::utils::format_duration(new_duration),
+        saved,
+        percent
+    )
+}
+
+/// Remove filler words and long pauses (Claude version)
+async fn execute_remove_fillers_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    let aggressiveness = args.get("aggressiveness").and_then(|v| v.as_str()).unwrap_or("medium");
+    let crossfade_ms = args.get("crossfade_ms").and_then(|v| v.as_f64()).unwrap_or(30.0);
+    remove_fillers(input, output_raw, aggressiveness, crossfade_ms, ctx).await
+}
+
+/// Remove filler words and long pauses (Gemini version)
+async fn execute_remove_fillers_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output_raw = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    let aggressiveness = args.get("aggressiveness").and_then(|v| v.as_str()).unwrap_or("medium");
+    let crossfade_ms = args.get("crossfade_ms").and_then(|v| v.as_f64()).unwrap_or(30.0);
+    remove_fillers(input, output_raw, aggressiveness, crossfade_ms, ctx).await
+}
+
+/// Options for censoring flagged words
+struct CensorOptions<'a> {
+    input: &'a str,
+    output_raw: &'a str,
+    mode: &'a str,
+    word_list: Vec<String>,
+    padding_ms: f64,
+    bleep_frequency: f64,
+}
+
+/// Find flagged words in the transcript and bleep, mute or cut them, listing each censored timestamp
+async fn censor_profanity(opts: CensorOptions<'_>, ctx: &ToolExecutionContext) -> String {
+    if opts.input.is_empty() || opts.output_raw.is_empty() {
+        return "❌ Error: input_file and output_file are required".to_string();
+    }
+    if !matches!(opts.mode, "bleep" | "mute" | "cut") {
+        return format!("❌ Error: unknown mode '{}'. Use bleep, mute or cut", opts.mode);
+    }
+    let output = ensure_outputs_directory(opts.output_raw);
+
+    let duration = match crate::core::get_video_duration(opts.input) {
+        Ok(d) => d,
+        Err(e) => return format!("❌ Failed to read duration: {}", e),
+    };
+    let words = match transcribe_media_words(opts.input, false, &ctx.app_state).await {
+        Ok(words) => words,
+        Err(e) => return format!("❌ {}", e),
+    };
+    if words.is_empty() {
+        return "❌ No speech detected in the file - nothing to censor".to_string();
+    }
+
+    let flagged = crate::audio::find_flagged_words(&words, &opts.word_list);
+    if flagged.is_empty() {
+        return format!("✅ No flagged words found in {} - file left unchanged", opts.input);
+    }
+
+    let padding = opts.padding_ms.clamp(0.0, 500.0) / 1000.0;
+    let mut ranges: Vec<(f64, f64)> = flagged
+        .iter()
+        .map(|(start, end, _)| ((start - padding).max(0.0), (end + padding).min(duration)))
+        .collect();
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let rendered = match opts.mode {
+        "bleep" => crate::audio::bleep_ranges(opts.input, &output, &merged, opts.bleep_frequency.clamp(200.0, 4000.0), duration),
+        "mute" => crate::audio::mute_ranges(opts.input, &output, &merged),
+        _ => crate::core::concat_segments_with_crossfade(opts.input, &output, &crate::audio::invert_ranges(&merged, duration), 0.03),
+    };
+    if let Err(e) = rendered {
+        return format!("❌ Failed to render censored file: {}", e);
+    }
+
+    let verb = match opts.mode {
+        "bleep" => "Bleeped",
+        "mute" => "Muted",
+        _ => "Cut",
+    };
+    let mut result = format!("✅ {} {} flagged word(s) and saved to: {}\n\n🔇 Censored timestamps:\n", verb, flagged.len(), output);
+    for (start, end, word) in &flagged {
+        result.push_str(&format!(
+            "- {} - {}: \"{}\"\n",
+            crate::utils::format_duration(*start),
+            crate::utils::format_duration(*end),
+            word
+        ));
+    }
+    result
+}
+
+/// Parse the optional word list, accepting an array or a comma-separated string
+fn censor_word_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect(),
+        Some(Value::String(list)) => list.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Censor flagged words (Claude version)
+async fn execute_censor_profanity_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let opts = CensorOptions {
+        input: args["input_file"].as_str().unwrap_or(""),
+        output_raw: args["output_file"].as_str().unwrap_or(""),
+        mode: args.get("mode").and_then(|v| v.as_str()).unwrap_or("bleep"),
+        word_list: censor_word_list(args.get("words")),
+        padding_ms: args.get("padding_ms").and_then(|v| v.as_f64()).unwrap_or(50.0),
+        bleep_frequency: args.get("bleep_frequency").and_then(|v| v.as_f64()).unwrap_or(1000.0),
+    };
+    censor_profanity(opts, ctx).await
+}
+
+/// Censor flagged words (Gemini version)
+async fn execute_censor_profanity_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let opts = CensorOptions {
+        input: args.get("input_file").and_then(|v| v.as_str()).unwrap_or(""),
+        output_raw: args.get("output_file").and_then(|v| v.as_str()).unwrap_or(""),
+        mode: args.get("mode").and_then(|v| v.as_str()).unwrap_or("bleep"),
+        word_list: censor_word_list(args.get("words")),
+        padding_ms: args.get("padding_ms").and_then(|v| v.as_f64()).unwrap_or(50.0),
+        bleep_frequency: args.get("bleep_frequency").and_then(|v| v.as_f64()).unwrap_or(1000.0),
+    };
+    censor_profanity(opts, ctx).await
+}
+
+/// Options for mood-matched background music
+struct SelectMusicOptions<'a> {
+    input: &'a str,
+    output_raw: &'a str,
+    source: &'a str,
+    mood: Option<&'a str>,
+    music_volume: f64,
+}
+
+/// Read the video's mood, pick a library track or generate one, and mix it in with ducking
+async fn select_music(opts: SelectMusicOptions<'_>, ctx: &ToolExecutionContext) -> String {
+    use crate::services::MusicSelectionService;
+
+    if opts.input.is_empty() || opts.output_raw.is_empty() {
+        return "❌ Error: input_file and output_file are required".to_string();
+    }
+    if !matches!(opts.source, "auto" | "library" | "generate") {
+        return format!("❌ Error: unknown source '{}'. Use auto, library or generate", opts.source);
+    }
+    let output = ensure_outputs_directory(opts.output_raw);
+
+    let duration = match crate::core::get_video_duration(opts.input) {
+        Ok(d) => d,
+        Err(e) => return format!("❌ Failed to read video duration: {}", e),
+    };
+    // Videos without speech still get music, just judged as ambient
+    let words = if opts.mood.is_some() {
+        Vec::new()
+    } else {
+        match transcribe_media_words(opts.input, false, &ctx.app_state).await {
+            Ok(words) => words,
+            Err(e) => {
+                tracing::warn!("select_music could not transcribe {}: {}", opts.input, e);
+                Vec::new()
+            }
+        }
+    };
+    let mood = MusicSelectionService::detect_mood(&ctx.app_state, &words, opts.mood).await;
+
+    let mut library_asset = None;
+    if opts.source != "generate" {
+        match ctx.user_id {
+            Some(user_id) => match MusicSelectionService::pick_from_library(&ctx.app_state, user_id, &mood).await {
+                Ok(asset) => library_asset = asset,
+                Err(e) => tracing::warn!("select_music library lookup failed: {}", e),
+            },
+            None if opts.source == "library" => return "❌ Picking from your music library requires being signed in".to_string(),
+            None => {}
+        }
+        if library_asset.is_none() && opts.source == "library" {
+            return format!("❌ No music in your library matches the '{}' mood. Add tracks with kind 'music' or use source 'generate'", mood.mood);
+        }
+    }
+
+    let (music_file, origin) = match library_asset {
+        Some(asset) => (asset.file_path.clone(), format!("library track '{}'", asset.name)),
+        None => {
+            let generated = format!("outputs/temp_music_{}.mp3", uuid::Uuid::new_v4());
+            if let Err(e) = MusicSelectionService::generate(&ctx.app_state, &mood, duration, &generated).await {
+                return format!("❌ {}", e);
+            }
+            (generated, "a generated Eleven Music track".to_string())
+        }
+    };
+
+    let mixed = crate::audio::mix_background_music_ducked(opts.input, &music_file, &output, opts.music_volume.clamp(0.02, 1.0), duration);
+    if music_file.starts_with("outputs/temp_music_") {
+        let _ = tokio::fs::remove_file(&music_file).await;
+    }
+    if let Err(e) = mixed {
+        return format!("❌ Failed to mix background music: {}", e);
+    }
+
+    format!(
+        "✅ Added {} background music and saved to: {}\n\n🎵 Mood: {}{}\n🎼 Source: {}\n🔉 Music ducks under speech and fades out at the end",
+        mood.mood,
+        output,
+        mood.mood,
+        mood.genre.as_deref().map(|g| format!(" ({})", g)).unwrap_or_default(),
+        origin
+    )
+}
+
+/// Mood-matched background music (Claude version)
+async fn execute_select_music_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let opts = SelectMusicOptions {
+        input: args["input_file"].as_str().unwrap_or(""),
+        output_raw: args["output_file"].as_str().unwrap_or(""),
+        source: args.get("source").and_then(|v| v.as_str()).unwrap_or("auto"),
+        mood: args.get("mood").and_then(|v| v.as_str()),
+        music_volume: args.get("music_volume").and_then(|v| v.as_f64()).unwrap_or(0.2),
+    };
+    select_music(opts, ctx).await
+}
+
+/// Mood-matched background music (Gemini version)
+async fn execute_select_music_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let opts = SelectMusicOptions {
+        input: args.get("input_file").and_then(|v| v.as_str()).unwrap_or(""),
+        output_raw: args.get("output_file").and_then(|v| v.as_str()).unwrap_or(""),
+        source: args.get("source").and_then(|v| v.as_str()).unwrap_or("auto"),
+        mood: args.get("mood").and_then(|v| v.as_str()),
+        music_volume: args.get("music_volume").and_then(|v| v.as_f64()).unwrap_or(0.2),
+    };
+    select_music(opts, ctx).await
+}
+
+/// Build a screencast layout from tool arguments, defaulting the frame to the screen recording's size
+fn screencast_layout(args: &Value, screen_file: &str) -> Result<crate::types::ScreencastLayout, String> {
+    let layout = args.get("layout").and_then(|v| v.as_str()).unwrap_or("bubble").to_string();
+    let position = args.get("position").and_then(|v| v.as_str()).unwrap_or("bottom_right").to_string();
+    if !matches!(position.as_str(), "top_left" | "top_right" | "bottom_left" | "bottom_right") {
+        return Err(format!("Unknown position '{}'. Use top_left, top_right, bottom_left or bottom_right", position));
+    }
+    let audio = args.get("audio").and_then(|v| v.as_str()).unwrap_or("mix").to_string();
+    if !matches!(audio.as_str(), "mix" | "screen" | "camera") {
+        return Err(format!("Unknown audio source '{}'. Use mix, screen or camera", audio));
+    }
+
+    // Preset sizes are per layout: bubble diameter vs frame height, pip/column width vs frame width
+    let size = match args.get("size") {
+        Some(Value::Number(n)) => n.as_f64().unwrap_or(0.25),
+        Some(Value::String(preset)) => match (layout.as_str(), preset.as_str()) {
+            (_, fraction) if fraction.parse::<f64>().is_ok() => fraction.parse::<f64>().unwrap_or(0.25),
+            ("side_by_side", "small") => 0.25,
+            ("side_by_side", "large") => 0.4,
+            ("side_by_side", _) => 0.32,
+            (_, "small") => 0.18,
+            (_, "large") => 0.32,
+            _ => 0.25,
+        },
+        _ => if layout == "side_by_side" { 0.32 } else { 0.25 },
+    };
+
+    let screen = crate::core::analyze_video(screen_file)?;
+    Ok(crate::types::ScreencastLayout {
+        layout,
+        position,
+        size,
+        width: args.get("width").and_then(|v| v.as_u64()).map(|w| w as u32).unwrap_or(screen.width.min(1920)),
+        height: args.get("height").and_then(|v| v.as_u64()).map(|h| h as u32).unwrap_or_else(|| {
+            (screen.height as f64 * screen.width.min(1920) as f64 / screen.width.max(1) as f64) as u32
+        }),
+        corner_radius: args.get("corner_radius").and_then(|v| v.as_u64()).unwrap_or(24) as u32,
+        shadow: args.get("shadow").and_then(|v| v.as_bool()).unwrap_or(true),
+        background_color: args.get("background_color").and_then(|v| v.as_str()).unwrap_or("#111111").to_string(),
+        camera_offset: args.get("camera_offset").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        audio,
+    })
+}
+
+/// Compose a screen recording and a camera recording into a tutorial layout (Claude version)
+async fn execute_compose_screencast_with_state_claude(args: &Value, _ctx: &ToolExecutionContext) -> String {
+    let screen_file = args.get("screen_file").and_then(|v| v.as_str()).unwrap_or("");
+    let camera_file = args.get("camera_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output_raw = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    if screen_file.is_empty() || camera_file.is_empty() || output_raw.is_empty() {
+        return "❌ Error: screen_file, camera_file and output_file are required".to_string();
+    }
+    let output = ensure_outputs_directory(output_raw);
+
+    let layout = match screencast_layout(args, screen_file) {
+        Ok(layout) => layout,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let (screen, camera, out, spec) = (screen_file.to_string(), camera_file.to_string(), output.clone(), layout.clone());
+    let rendered = tokio::task::spawn_blocking(move || crate::visual::compose_screencast(&screen, &camera, &out, &spec)).await;
+    match rendered {
+        Ok(Ok(_)) => {
+            let placement = if layout.layout == "side_by_side" {
+                "camera column on the right".to_string()
+            } else {
+                format!("camera {}", layout.position.replace('_', " "))
+            };
+            format!(
+                "✅ Composed {} screencast and saved to: {}\n\n🖥️ Frame: {}x{}\n📷 Layout: {} ({}, size {:.2})\n🔊 Audio: {}",
+                layout.layout.replace('_', " "),
+                output,
+                layout.width,
+                layout.height,
+                layout.layout,
+                placement,
+                layout.size,
+                layout.audio
+            )
+        }
+        Ok(Err(e)) => format!("❌ Failed to compose screencast: {}", e),
+        Err(e) => format!("❌ Screencast task failed: {}", e),
+    }
+}
+
+/// Compose a screen recording and a camera recording into a tutorial layout (Gemini version)
+async fn execute_compose_screencast_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_compose_screencast_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// Options for rendering a karaoke/lyric video
+struct LyricVideoOptions<'a> {
+    audio_file: &'a str,
+    background_file: &'a str,
+    output_raw: &'a str,
+    lyrics: Option<&'a str>,
+    highlight_color: &'a str,
+    text_color: &'a str,
+    font_size: u32,
+    width: u32,
+    height: u32,
+    words_per_line: usize,
+}
+
+/// Render word-synced lyrics over a background, timing words from the vocal transcript
+async fn create_lyric_video(opts: LyricVideoOptions<'_>, ctx: &ToolExecutionContext) -> String {
+    if opts.audio_file.is_empty() || opts.background_file.is_empty() || opts.output_raw.is_empty() {
+        return "❌ Error: audio_file, background_file and output_file are required".to_string();
+    }
+    let output = ensure_outputs_directory(opts.output_raw);
+
+    let duration = match crate::core::get_video_duration(opts.audio_file) {
+        Ok(d) => d,
+        Err(e) => return format!("❌ Failed to read audio duration: {}", e),
+    };
+
+    // Timings always come from the vocals when we can transcribe them
+    let transcript = transcribe_media_words(opts.audio_file, false, &ctx.app_state).await;
+    let (lines, timing_source) = match (opts.lyrics.filter(|l| !l.trim().is_empty()), transcript) {
+        (Some(lyrics), Ok(words)) => (
+            crate::visual::align_lyrics_to_words(lyrics, &words, duration),
+            if words.is_empty() { "evenly spaced" } else { "aligned to vocals" },
+        ),
+        (Some(lyrics), Err(_)) => (crate::visual::align_lyrics_to_words(lyrics, &[], duration), "evenly spaced"),
+        (None, Ok(words)) => (crate::visual::group_words_into_lines(&words, opts.words_per_line), "transcribed"),
+        (None, Err(e)) => return format!("❌ No lyrics provided and transcription failed: {}", e),
+    };
+    if lines.is_empty() {
+        return "❌ No lyric words to display".to_string();
+    }
+
+    let ass_file = match output.rsplit_once('.') {
+        Some((stem, _)) => format!("{}.ass", stem),
+        None => format!("{}.ass", output),
+    };
+    let ass = crate::visual::build_karaoke_ass(&lines, opts.width, opts.height, opts.font_size, opts.text_color, opts.highlight_color);
+    if let Err(e) = tokio::fs::write(&ass_file, ass).await {
+        return format!("❌ Failed to write lyric subtitles: {}", e);
+    }
+
+    match crate::visual::render_lyric_video(opts.background_file, opts.audio_file, &ass_file, &output, opts.width, opts.height) {
+        Ok(_) => format!(
+            "✅ Lyric video saved to: {}\n\n🎤 {} lines, {} words ({})\n📝 Karaoke subtitles: {}",
+            output,
+            lines.len(),
+            lines.iter().map(|l| l.len()).sum::<usize>(),
+            timing_source,
+            ass_file
+        ),
+        Err(e) => format!("❌ Failed to render lyric video: {}", e),
+    }
+}
+
+/// Generate a karaoke/lyric video (Claude version)
+async fn execute_create_lyric_video_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let opts = LyricVideoOptions {
+        audio_file: args["audio_file"].as_str().unwrap_or(""),
+        background_file: args["background_file"].as_str().unwrap_or(""),
+        output_raw: args["output_file"].as_str().unwrap_or(""),
+        lyrics: args.get("lyrics").and_then(|v| v.as_str()),
+        highlight_color: args.get("highlight_color").and_then(|v| v.as_str()).unwrap_or("yellow"),
+        text_color: args.get("text_color").and_then(|v| v.as_str()).unwrap_or("white"),
+        font_size: args.get("font_size").and_then(|v| v.as_u64()).unwrap_or(64) as u32,
+        width: args.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32,
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(1080) as u32,
+        words_per_line: args.get("words_per_line").and_then(|v| v.as_u64()).unwrap_or(6) as usize,
+    };
+    create_lyric_video(opts, ctx).await
+}
+
+/// Generate a karaoke/lyric video (Gemini version)
+async fn execute_create_lyric_video_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let opts = LyricVideoOptions {
+        audio_file: args.get("audio_file").and_then(|v| v.as_str()).unwrap_or(""),
+        background_file: args.get("background_file").and_then(|v| v.as_str()).unwrap_or(""),
+        output_raw: args.get("output_file").and_then(|v| v.as_str()).unwrap_or(""),
+        lyrics: args.get("lyrics").and_then(|v| v.as_str()),
+        highlight_color: args.get("highlight_color").and_then(|v| v.as_str()).unwrap_or("yellow"),
+        text_color: args.get("text_color").and_then(|v| v.as_str()).unwrap_or("white"),
+        font_size: args.get("font_size").and_then(|v| v.as_u64()).unwrap_or(64) as u32,
+        width: args.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32,
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(1080) as u32,
+        words_per_line: args.get("words_per_line").and_then(|v| v.as_u64()).unwrap_or(6) as usize,
+    };
+    create_lyric_video(opts, ctx).await
+}
+
+/// Merge new session defaults, or report the current ones when the call sets nothing
+async fn set_session_defaults(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let update: crate::models::session_defaults::UpdateSessionDefaultsRequest = match serde_json::from_value(args.clone()) {
+        Ok(update) => update,
+        Err(e) => return format!("❌ Error: invalid session defaults: {}", e),
+    };
+    let pool = &ctx.app_state.db_pool;
+    let nothing_set = update.resolution.is_none()
+        && update.width.is_none()
+        && update.height.is_none()
+        && update.fps.is_none()
+        && update.voice.is_none()
+        && update.caption_style.is_none()
+        && update.clear.is_empty();
+    if nothing_set {
+        let defaults = crate::services::SessionDefaultsService::get(pool, &ctx.session_id).await;
+        return format!("✅ Session defaults: {}", crate::services::SessionDefaultsService::describe(&defaults));
+    }
+
+    match crate::services::SessionDefaultsService::update(pool, resolve_user_id(ctx).await, &ctx.session_id, &update).await {
+        Ok(Some(defaults)) => format!(
+            "✅ Session defaults updated: {}\n\nLater tool calls that leave these arguments out will use them.",
+            crate::services::SessionDefaultsService::describe(&defaults)
+        ),
+        Ok(None) => "❌ Error: chat session not found".to_string(),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Set session defaults (Claude version)
+async fn execute_set_session_defaults_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    set_session_defaults(args, ctx).await
+}
+
+/// Set session defaults (Gemini version)
+async fn execute_set_session_defaults_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    set_session_defaults(&Value::Object(args.clone().into_iter().collect()), ctx).await
+}
+
+/// Upload a video to YouTube; whatever the call leaves out comes from the user's upload defaults
+async fn upload_to_youtube(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: uploading needs a signed-in user".to_string();
+    };
+    if !crate::middleware::youtube_access::user_has_access(&ctx.app_state.db_pool, user_id).await {
+        return "❌ Error: YouTube features aren't available for your account yet".to_string();
+    }
+    // Gemini sends numbers as floats
+    let mut args = args.clone();
+    if let Some(channel_id) = args.get("channel_id").and_then(Value::as_f64) {
+        args["channel_id"] = Value::from(channel_id as i64);
+    }
+    let mut request: crate::models::youtube::UploadToYouTubeRequest = match serde_json::from_value(args) {
+        Ok(request) => request,
+        Err(e) => return format!("❌ Error: invalid upload: {}", e),
+    };
+
+    // Resolve file path - try as-is first, then try outputs/ and uploads/
+    let candidates = [
+        request.video_path.clone(),
+        format!("outputs/{}", request.video_path),
+        format!("uploads/{}", request.video_path),
+    ];
+    let mut video_path = None;
+    for candidate in candidates {
+        if tokio::fs::metadata(&candidate).await.is_ok() {
+            video_path = Some(candidate);
+            break;
+        }
+    }
+    let Some(video_path) = video_path else {
+        return format!("❌ Error: Video file not found: {}", request.video_path);
+    };
+    request.video_path = video_path;
+
+    match crate::handlers::youtube::upload_for_user(&ctx.app_state, user_id, &request).await {
+        Ok(result) => format!(
+            "✅ Uploaded \"{}\" to YouTube ({}): {}",
+            result["upload"]["title"].as_str().unwrap_or_default(),
+            result["upload"]["privacy_status"].as_str().unwrap_or_default(),
+            result["upload"]["youtube_url"].as_str().unwrap_or_default()
+        ),
+        Err((_, body)) => format!("❌ Upload failed: {}", body.0["message"].as_str().unwrap_or("unknown error")),
+    }
+}
+
+/// Transcribe a video and burn in word-highlighted captions in one of the clipping caption presets
+async fn add_styled_captions(input: &str, output_raw: &str, preset: &str, ctx: &ToolExecutionContext) -> String {
+    if input.is_empty() || output_raw.is_empty() {
+        return "❌ Error: input_file and output_file are required".to_string();
+    }
+    let style = crate::clipping::models::CaptionStyle { preset: preset.to_string(), ..Default::default() };
+    if let Err(e) = style.validate() {
+        return format!("❌ Error: {}", e);
+    }
+    let output = ensure_outputs_directory(output_raw);
+
+    match crate::clipping::captions::burn_captions(&ctx.app_state, input, &style).await {
+        Ok(Some(captioned)) => {
+            if tokio::fs::rename(&captioned, &output).await.is_err() {
+                if let Err(e) = tokio::fs::copy(&captioned, &output).await {
+                    return format!("❌ Error: failed to save captioned video: {}", e);
+                }
+                let _ = tokio::fs::remove_file(&captioned).await;
+            }
+            format!("✅ Captioned video saved to: {}\n\n💬 Style: {}", output, preset)
+        }
+        Ok(None) => "❌ Error: the 'none' caption style burns no captions; use bold, karaoke, minimal or hype".to_string(),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Burn styled captions (Claude version)
+async fn execute_add_styled_captions_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output = args["output_file"].as_str().unwrap_or("");
+    let preset = args.get("caption_style").and_then(|v| v.as_str()).unwrap_or("bold");
+    add_styled_captions(input, output, preset, ctx).await
+}
+
+/// Burn styled captions (Gemini version)
+async fn execute_add_styled_captions_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    let preset = args.get("caption_style").and_then(|v| v.as_str()).unwrap_or("bold");
+    add_styled_captions(input, output, preset, ctx).await
+}
+
+/// Transcribe a video and burn in animated captions with the look given in `args`
+async fn generate_captions(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output_raw = args["output_file"].as_str().unwrap_or("");
+    if input.is_empty() || output_raw.is_empty() {
+        return "❌ Error: input_file and output_file are required".to_string();
+    }
+    let text = |name: &str, default: &str| args.get(name).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).unwrap_or(default).to_lowercase();
+    let mode = text("mode", "line");
+    let options = crate::types::AnimatedCaptionOptions {
+        words_per_line: args.get("words_per_line").and_then(|v| v.as_f64()).map(|n| n as usize).unwrap_or(if mode == "word" { 1 } else { 3 }).clamp(1, 12),
+        mode,
+        font: args.get("font").and_then(|v| v.as_str()).filter(|v| !v.is_empty()).unwrap_or("DejaVu Sans").to_string(),
+        font_size_percent: args.get("font_size_percent").and_then(|v| v.as_f64()).unwrap_or(7.0).clamp(2.0, 20.0),
+        text_color: text("text_color", "white"),
+        highlight_color: text("highlight_color", "yellow"),
+        highlight_style: text("highlight_style", "color"),
+        position: text("position", "bottom"),
+        uppercase: args.get("uppercase").and_then(|v| v.as_bool()).unwrap_or(true),
+    };
+    let choices = [
+        ("mode", &options.mode, &crate::visual::CAPTION_MODES[..]),
+        ("highlight_style", &options.highlight_style, &crate::visual::CAPTION_HIGHLIGHT_STYLES[..]),
+        ("position", &options.position, &crate::visual::CAPTION_POSITIONS[..]),
+    ];
+    for (name, value, allowed) in choices {
+        if !allowed.contains(&value.as_str()) {
+            return format!("❌ Error: {} must be one of {}, not '{}'", name, allowed.join(", "), value);
+        }
+    }
+
+    let words = match transcribe_media_words(input, false, &ctx.app_state).await {
+        Ok(words) => words,
+        Err(e) => return format!("❌ {}", e),
+    };
+    if words.is_empty() {
+        return "❌ No speech detected in the video - nothing to caption".to_string();
+    }
+
+    let output = ensure_outputs_directory(output_raw);
+    let (input_owned, output_owned, words_count) = (input.to_string(), output.clone(), words.len());
+    let burn_options = options.clone();
+    let burned = tokio::task::spawn_blocking(move || crate::visual::generate_captions(&input_owned, &output_owned, &words, &burn_options)).await;
+    match burned {
+        Ok(Ok(_)) => format!(
+            "✅ Captioned video saved to: {}\n\n💬 {} words, {} · highlight: {} ({}) · {} {} at the {}",
+            output,
+            words_count,
+            if options.mode == "word" { "word by word".to_string() } else { format!("{} words per line", options.words_per_line) },
+            options.highlight_style,
+            options.highlight_color,
+            options.text_color,
+            options.font,
+            options.position
+        ),
+        Ok(Err(e)) => format!("❌ Failed to burn captions: {}", e),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Generate animated captions (Claude version)
+async fn execute_generate_captions_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    generate_captions(args, ctx).await
+}
+
+/// Generate animated captions (Gemini version)
+async fn execute_generate_captions_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    generate_captions(&Value::Object(args.clone().into_iter().collect()), ctx).await
+}
+
+/// Time arguments that mark a point on the timeline, shifted when an edit is re-applied to a region
+const POSITION_ARGUMENTS: [&str; 5] = ["start_seconds", "end_seconds", "start_time", "end_time", "timestamp"];
+
+/// Re-apply one edit to the keyframe-aligned region around [start, end) of a finished video and splice
+/// the result back in; the rest of the video is stream-copied. The edit's times are full-video times.
+async fn rerender_region(input: &str, start: f64, end: f64, edit: &str, output_raw: &str, ctx: &ToolExecutionContext) -> String {
+    if input.is_empty() || edit.is_empty() || output_raw.is_empty() {
+        return "❌ Error: input_file, edit and output_file are required".to_string();
+    }
+    if end <= start {
+        return "❌ Error: end_time must be after start_time".to_string();
+    }
+    let (tool, tool_args) = match parse_preview_step(edit) {
+        Ok(step) => step,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    if tool == "adjust_speed" {
+        return "❌ Error: adjust_speed changes the region's length and can't be spliced; re-render the whole video".to_string();
+    }
+    let output = ensure_outputs_directory(output_raw);
+
+    let run_dir = scratch_dir(&ctx.session_id).join(format!("rerender-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]));
+    if let Err(e) = std::fs::create_dir_all(&run_dir) {
+        return format!("❌ Error: failed to create scratch directory: {}", e);
+    }
+    let segment = run_dir.join("region.mp4").to_string_lossy().to_string();
+    let edited = run_dir.join("region_edited.mp4").to_string_lossy().to_string();
+
+    let (source, cut) = (input.to_string(), segment.clone());
+    let located = tokio::task::spawn_blocking(move || {
+        let info = crate::core::probe_media(&source)?;
+        let keyframes = crate::utils::splice::keyframe_times(&source)?;
+        let region = crate::utils::splice::align_region(&keyframes, start, end);
+        crate::utils::splice::extract_region(&source, region, &cut)?;
+        Ok::<_, String>((info.duration_seconds, region))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    let (total, region) = match located {
+        Ok(located) => located,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&run_dir);
+            return format!("❌ Error: could not cut the region out of {}: {}", input, e);
+        }
+    };
+    let region_length = region.duration(total);
+
+    // Resolve the edit's time expressions against the full video, then move them onto the region
+    let mut edit_args = tool_args;
+    edit_args.insert("input_file".to_string(), Value::String(input.to_string()));
+    if let Ok(Some(Value::Object(normalized))) = crate::utils::timecode::normalize_time_arguments(&Value::Object(edit_args.clone())) {
+        edit_args = normalized;
+    }
+    for key in POSITION_ARGUMENTS {
+        if let Some(seconds) = edit_args.get(key).and_then(|v| v.as_f64()) {
+            edit_args.insert(key.to_string(), serde_json::json!((seconds - region.start).clamp(0.0, region_length)));
+        }
+    }
+    edit_args.insert("input_file".to_string(), Value::String(segment.clone()));
+    edit_args.insert("output_file".to_string(), Value::String(edited.clone()));
+
+    let result = execute_tool_claude(&tool, &Value::Object(edit_args)).await;
+    if result.starts_with("❌") || !std::path::Path::new(&edited).exists() {
+        let _ = std::fs::remove_dir_all(&run_dir);
+        return format!("❌ Error: {} failed on the region: {}", tool, result);
+    }
+
+    let (source, replacement, spliced_output) = (input.to_string(), edited.clone(), output.clone());
+    let spliced = tokio::task::spawn_blocking(move || crate::utils::splice::splice(&source, region, &replacement, &spliced_output))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    let _ = std::fs::remove_dir_all(&run_dir);
+    if let Err(e) = spliced {
+        return format!("❌ Error: failed to splice the re-rendered region: {}", e);
+    }
+
+    format!(
+        "✅ Re-rendered {} → {} with {} and spliced it into: {}\n\n⚡ Only {} of {} was re-encoded (the changed region widened to keyframes); the rest was copied losslessly",
+        crate::utils::timecode::format(region.start),
+        crate::utils::timecode::format(region.end.unwrap_or(total)),
+        tool,
+        output,
+        crate::utils::format_duration(region_length),
+        crate::utils::format_duration(total),
+    )
+}
+
+/// Re-render a changed region (Claude version)
+async fn execute_rerender_region_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let start = args["start_time"].as_f64().unwrap_or(0.0);
+    let end = args["end_time"].as_f64().unwrap_or(0.0);
+    let edit = args["edit"].as_str().unwrap_or("");
+    let output = args["output_file"].as_str().unwrap_or("");
+    rerender_region(input, start, end, edit, output, ctx).await
+}
+
+/// Re-render a changed region (Gemini version)
+async fn execute_rerender_region_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let start = args.get("start_time").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let end = args.get("end_time").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let edit = args.get("edit").and_then(|v| v.as_str()).unwrap_or("");
+    let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    rerender_region(input, start, end, edit, output, ctx).await
+}
+
+/// Run the quiz workflow and summarize the result
+async fn generate_quiz_video(mut config: crate::workflow::quiz_workflow::QuizConfig, ctx: &ToolExecutionContext) -> String {
+    if config.topic.trim().is_empty() || config.output_file.is_empty() {
+        return "❌ Error: topic and output_file are required".to_string();
+    }
+    config.output_file = ensure_outputs_directory(&config.output_file);
+    let thread_id = format!("user-{}:session-{}:quiz", ctx.user_id.unwrap_or(0), ctx.session_id);
+    let output = config.output_file.clone();
+
+    match crate::workflow::quiz_workflow::run_quiz_workflow(ctx.app_state.clone(), config, thread_id).await {
+        Ok(state) if state.is_completed() => {
+            let questions: Vec<crate::types::QuizQuestion> = state.node_outputs.get("write_questions")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+            let music = state.node_outputs.get("assemble")
+                .and_then(|v| v["music"].as_str())
+                .unwrap_or("none")
+                .to_string();
+            let list = questions.iter()
+                .enumerate()
+                .map(|(i, q)| format!("{}. {} → {}", i + 1, q.question, q.options[q.answer_index]))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("✅ Quiz video saved to: {}\n\n❓ {} questions (music: {})\n{}", output, questions.len(), music, list)
+        }
+        Ok(state) => format!(
+            "❌ Quiz workflow did not complete: {}",
+            state.errors.last().map(|e| e.message.clone()).unwrap_or_else(|| "unknown error".to_string())
+        ),
+        Err(e) => format!("❌ Quiz workflow failed: {}", e),
+    }
+}
+
+/// Generate a trivia/quiz video from a topic (Claude version)
+async fn execute_generate_quiz_video_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let config = crate::workflow::quiz_workflow::QuizConfig {
+        topic: args["topic"].as_str().unwrap_or("").to_string(),
+        question_count: args.get("question_count").and_then(|v| v.as_u64()).unwrap_or(5).clamp(1, 20) as usize,
+        countdown_seconds: args.get("countdown_seconds").and_then(|v| v.as_u64()).unwrap_or(5).clamp(1, 30) as u32,
+        reveal_seconds: args.get("reveal_seconds").and_then(|v| v.as_f64()).unwrap_or(3.0),
+        width: args.get("width").and_then(|v| v.as_u64()).unwrap_or(1080) as u32,
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(1920) as u32,
+        background_color: args.get("background_color").and_then(|v| v.as_str()).unwrap_or("#1a1a2e").to_string(),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel").to_string(),
+        music_prompt: args.get("music_prompt").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        output_file: args["output_file"].as_str().unwrap_or("").to_string(),
+    };
+    generate_quiz_video(config, ctx).await
+}
+
+/// Generate a trivia/quiz video from a topic (Gemini version)
+async fn execute_generate_quiz_video_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let config = crate::workflow::quiz_workflow::QuizConfig {
+        topic: args.get("topic").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        question_count: args.get("question_count").and_then(|v| v.as_u64()).unwrap_or(5).clamp(1, 20) as usize,
+        countdown_seconds: args.get("countdown_seconds").and_then(|v| v.as_u64()).unwrap_or(5).clamp(1, 30) as u32,
+        reveal_seconds: args.get("reveal_seconds").and_then(|v| v.as_f64()).unwrap_or(3.0),
+        width: args.get("width").and_then(|v| v.as_u64()).unwrap_or(1080) as u32,
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(1920) as u32,
+        background_color: args.get("background_color").and_then(|v| v.as_str()).unwrap_or("#1a1a2e").to_string(),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel").to_string(),
+        music_prompt: args.get("music_prompt").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        output_file: args.get("output_file").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+    };
+    generate_quiz_video(config, ctx).await
+}
+
+/// Run the article-to-video workflow and summarize the result
+async fn generate_video_from_article(mut config: crate::workflow::article_workflow::ArticleVideoConfig, ctx: &ToolExecutionContext) -> String {
+    if config.url.is_empty() || config.output_file.is_empty() {
+        return "❌ Error: url and output_file are required".to_string();
+    }
+    if !config.url.starts_with("http://") && !config.url.starts_with("https://") {
+        return "❌ Error: url must start with http:// or https://".to_string();
+    }
+    config.output_file = ensure_outputs_directory(&config.output_file);
+    let thread_id = format!("user-{}:session-{}:article", ctx.user_id.unwrap_or(0), ctx.session_id);
+    let output = config.output_file.clone();
+
+    match crate::workflow::article_workflow::run_article_workflow(ctx.app_state.clone(), config, thread_id).await {
+        Ok(state) if state.is_completed() => {
+            let summary = state.node_outputs.get("assemble").cloned().unwrap_or_default();
+            let credits = summary["credits"].as_array()
+                .map(|c| c.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
+                .filter(|c| !c.is_empty())
+                .unwrap_or_else(|| "none".to_string());
+            format!(
+                "✅ Article video saved to: {}\n\n📰 {}\n⏱️ Duration: {:.1}s\n🔗 Source: {}\n🎞️ B-roll credits: {}",
+                output,
+                summary["title"].as_str().unwrap_or(""),
+                summary["duration"].as_f64().unwrap_or(0.0),
+                summary["source_url"].as_str().unwrap_or(""),
+                credits
+            )
+        }
+        Ok(state) => format!(
+            "❌ Article workflow did not complete: {}",
+            state.errors.last().map(|e| e.message.clone()).unwrap_or_else(|| "unknown error".to_string())
+        ),
+        Err(e) => format!("❌ Article workflow failed: {}", e),
+    }
+}
+
+/// Generate a narrated video from an article URL (Claude version)
+async fn execute_generate_video_from_article_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let config = crate::workflow::article_workflow::ArticleVideoConfig {
+        url: args["url"].as_str().unwrap_or("").to_string(),
+        target_duration: args.get("target_duration").and_then(|v| v.as_f64()).unwrap_or(90.0).clamp(60.0, 180.0),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel").to_string(),
+        width: args.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32,
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(1080) as u32,
+        captions: args.get("captions").and_then(|v| v.as_bool()).unwrap_or(true),
+        presenter_image: args.get("presenter_image").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        output_file: args["output_file"].as_str().unwrap_or("").to_string(),
+    };
+    generate_video_from_article(config, ctx).await
+}
+
+/// Generate a narrated video from an article URL (Gemini version)
+async fn execute_generate_video_from_article_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let config = crate::workflow::article_workflow::ArticleVideoConfig {
+        url: args.get("url").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        target_duration: args.get("target_duration").and_then(|v| v.as_f64()).unwrap_or(90.0).clamp(60.0, 180.0),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel").to_string(),
+        width: args.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32,
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(1080) as u32,
+        captions: args.get("captions").and_then(|v| v.as_bool()).unwrap_or(true),
+        presenter_image: args.get("presenter_image").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        output_file: args.get("output_file").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+    };
+    generate_video_from_article(config, ctx).await
+}
+
+/// Options for a talking-head presenter clip
+struct TalkingHeadOptions<'a> {
+    portrait_image: &'a str,
+    script: Option<&'a str>,
+    audio_file: Option<&'a str>,
+    voice: &'a str,
+    output_raw: &'a str,
+}
+
+/// Animate a still portrait into a presenter clip speaking a script (voiced with Eleven Labs when available)
+/// or lip-syncing to existing narration
+async fn generate_talking_head(opts: TalkingHeadOptions<'_>, ctx: &ToolExecutionContext) -> String {
+    if opts.portrait_image.is_empty() || opts.output_raw.is_empty() {
+        return "❌ Error: portrait_image and output_file are required".to_string();
+    }
+    let script = opts.script.map(str::trim).filter(|s| !s.is_empty());
+    let audio_file = opts.audio_file.filter(|a| !a.is_empty());
+    if script.is_none() && audio_file.is_none() {
+        return "❌ Error: provide a script or an audio_file for the presenter to speak".to_string();
+    }
+    let Some(avatar) = ctx.app_state.avatar_client.as_ref() else {
+        return "❌ Talking-avatar provider not available. Set DID_API_KEY to enable talking-head generation.".to_string();
+    };
+    let output = ensure_outputs_directory(opts.output_raw);
+
+    let image_bytes = match tokio::fs::read(opts.portrait_image).await {
+        Ok(bytes) => bytes,
+        Err(e) => return format!("❌ Failed to read portrait image: {}", e),
+    };
+    let image_name = std::path::Path::new(opts.portrait_image)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "portrait.jpg".to_string());
+
+    // Narration: the given audio, else Eleven Labs TTS so the voice matches the rest of the video
+    let mut voice_note = "provider default voice".to_string();
+    let narration = match (audio_file, script, ctx.app_state.elevenlabs_client.as_ref()) {
+        (Some(path), _, _) => match tokio::fs::read(path).await {
+            Ok(bytes) => {
+                voice_note = format!("narration from {}", path);
+                Some(bytes)
+            }
+            Err(e) => return format!("❌ Failed to read audio file: {}", e),
+        },
+        (None, Some(text), Some(elevenlabs)) => {
+            let voice_id = crate::elevenlabs_client::DefaultVoices::get_voice_id_by_name(opts.voice)
+                .unwrap_or(crate::elevenlabs_client::DefaultVoices::RACHEL);
+            match elevenlabs.text_to_speech(text, voice_id, Some("eleven_flash_v2_5"), None, Some("mp3_44100_128")).await {
+                Ok(bytes) => {
+                    voice_note = format!("{} (Eleven Labs)", opts.voice);
+                    Some(bytes)
+                }
+                Err(e) => return format!("❌ Failed to generate narration: {}", e),
+            }
+        }
+        _ => None,
+    };
+
+    let audio = narration.map(|bytes| (bytes, "narration.mp3"));
+    let video = match avatar.generate_talking_head(image_bytes, &image_name, script, audio, None, 300).await {
+        Ok(bytes) => bytes,
+        Err(e) => return format!("❌ {}", e),
+    };
+    if let Err(e) = tokio::fs::write(&output, &video).await {
+        return format!("❌ Failed to save presenter clip: {}", e);
+    }
+
+    let duration = crate::core::get_video_duration(&output).unwrap_or(0.0);
+    format!(
+        "✅ Generated talking-head presenter and saved to: {}\n\n🧑‍🏫 Duration: {:.1}s\n🎙️ Voice: {}\n💡 Composite it over B-roll with add_overlay or use presenter_image in generate_video_from_article",
+        output, duration, voice_note
+    )
+}
+
+/// Talking-head presenter from a portrait (Claude version)
+async fn execute_generate_talking_head_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let opts = TalkingHeadOptions {
+        portrait_image: args["portrait_image"].as_str().unwrap_or(""),
+        script: args.get("script").and_then(|v| v.as_str()),
+        audio_file: args.get("audio_file").and_then(|v| v.as_str()),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel"),
+        output_raw: args["output_file"].as_str().unwrap_or(""),
+    };
+    generate_talking_head(opts, ctx).await
+}
+
+/// Talking-head presenter from a portrait (Gemini version)
+async fn execute_generate_talking_head_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let opts = TalkingHeadOptions {
+        portrait_image: args.get("portrait_image").and_then(|v| v.as_str()).unwrap_or(""),
+        script: args.get("script").and_then(|v| v.as_str()),
+        audio_file: args.get("audio_file").and_then(|v| v.as_str()),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel"),
+        output_raw: args.get("output_file").and_then(|v| v.as_str()).unwrap_or(""),
+    };
+    generate_talking_head(opts, ctx).await
+}
+
+/// Run the localization workflow and summarize each locale's outputs
+async fn export_localized(mut config: crate::workflow::localization_workflow::LocalizationConfig, ctx: &ToolExecutionContext) -> String {
+    if config.input_file.is_empty() || config.languages.is_empty() {
+        return "❌ Error: input_file and languages are required".to_string();
+    }
+    if config.languages.len() > 20 {
+        return "❌ Error: at most 20 languages per export".to_string();
+    }
+    if !std::path::Path::new(&config.input_file).exists() {
+        return format!("❌ Error: input file not found: {}", config.input_file);
+    }
+
+    let folder = if config.output_dir.is_empty() {
+        let stem = std::path::Path::new(&config.input_file).file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+        format!("{}_localized", stem)
+    } else {
+        config.output_dir.clone()
+    };
+    config.output_dir = ensure_outputs_directory(&folder);
+    let output_dir = config.output_dir.clone();
+    let thread_id = format!("user-{}:session-{}:localize", ctx.user_id.unwrap_or(0), ctx.session_id);
+
+    match crate::workflow::localization_workflow::run_localization_workflow(ctx.app_state.clone(), config, thread_id).await {
+        Ok(state) if state.is_completed() => {
+            let outputs: Vec<crate::workflow::localization_workflow::LocaleOutput> = state.node_outputs.get("render_locales")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+            let lines = outputs.iter()
+                .map(|o| match (&o.video_file, &o.error) {
+                    (Some(video), None) => format!(
+                        "  ✓ {}: {} (captions: {}{})",
+                        o.locale, video, o.captions_file, if o.dubbed { ", dubbed" } else { "" }
+                    ),
+                    (_, Some(e)) => format!("  ✗ {}: {}", o.locale, e),
+                    _ => format!("  ✗ {}: no output", o.locale),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                "✅ Localized exports saved to: {}/\n\n🌍 {} locales\n{}\n\n📋 Manifest: {}/locales.json",
+                output_dir,
+                outputs.len(),
+                lines,
+                output_dir
+            )
+        }
+        Ok(state) => format!(
+            "❌ Localization did not complete: {}",
+            state.errors.last().map(|e| e.message.clone()).unwrap_or_else(|| "unknown error".to_string())
+        ),
+        Err(e) => format!("❌ Localization failed: {}", e),
+    }
+}
+
+/// Export per-language variants of an edit (Claude version)
+async fn execute_export_localized_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let config = crate::workflow::localization_workflow::LocalizationConfig {
+        input_file: args["input_file"].as_str().unwrap_or("").to_string(),
+        languages: args.get("languages")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default(),
+        captions_file: args.get("captions_file").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        burn_captions: args.get("burn_captions").and_then(|v| v.as_bool()).unwrap_or(true),
+        dub: args.get("dub").and_then(|v| v.as_bool()).unwrap_or(false),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel").to_string(),
+        title: args.get("title").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        title_duration: args.get("title_duration").and_then(|v| v.as_f64()).unwrap_or(3.0),
+        output_dir: args.get("output_dir").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+    };
+    export_localized(config, ctx).await
+}
+
+/// Export per-language variants of an edit (Gemini version)
+async fn execute_export_localized_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let config = crate::workflow::localization_workflow::LocalizationConfig {
+        input_file: args.get("input_file").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        languages: args.get("languages")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default(),
+        captions_file: args.get("captions_file").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        burn_captions: args.get("burn_captions").and_then(|v| v.as_bool()).unwrap_or(true),
+        dub: args.get("dub").and_then(|v| v.as_bool()).unwrap_or(false),
+        voice: args.get("voice").and_then(|v| v.as_str()).unwrap_or("Rachel").to_string(),
+        title: args.get("title").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        title_duration: args.get("title_duration").and_then(|v| v.as_f64()).unwrap_or(3.0),
+        output_dir: args.get("output_dir").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+    };
+    export_localized(config, ctx).await
+}
+
+/// Directories the agent may send files out of
+const DELIVERABLE_ROOTS: [&str; 2] = ["uploads", "outputs"];
+
+/// Where a relative path the agent named lives inside uploads/ or outputs/. Absolute paths and `..`
+/// are refused, and the canonical path must still sit under one of the roots (no symlinks out).
+fn confine_to_deliverable_roots(file_path: &str) -> Result<(String, std::path::PathBuf), String> {
+    use std::path::{Component, Path};
+
+    let path = Path::new(file_path.trim_start_matches("./"));
+    if path.is_absolute() || path.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return Err(format!("'{}' must be a file in uploads/ or outputs/", file_path));
+    }
+    let relative = path.to_string_lossy().to_string();
+    let candidates = if DELIVERABLE_ROOTS.iter().any(|root| path.starts_with(root)) {
+        vec![relative]
+    } else {
+        DELIVERABLE_ROOTS.iter().map(|root| format!("{}/{}", root, relative)).collect()
+    };
+
+    let roots: Vec<_> = DELIVERABLE_ROOTS.iter().filter_map(|root| std::fs::canonicalize(root).ok()).collect();
+    candidates
+        .into_iter()
+        .filter_map(|candidate| std::fs::canonicalize(&candidate).ok().map(|absolute| (candidate, absolute)))
+        .find(|(_, absolute)| absolute.is_file() && roots.iter().any(|root| absolute.starts_with(root)))
+        .ok_or_else(|| format!("file not found: {}", file_path))
+}
+
+/// Resolve a file to deliver to one of the user's own uploads or outputs
+async fn resolve_deliverable_file(file_path: &str, user_id: i32, ctx: &ToolExecutionContext) -> Result<String, String> {
+    let (relative, absolute) = confine_to_deliverable_roots(file_path)?;
+    let name = absolute.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+
+    // Recorded paths vary between "outputs/x.mp4" and "x.mp4", so match on the file name and compare
+    // canonical paths
+    let recorded: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT file_path FROM output_videos WHERE user_id = $1 AND right(file_path, length($2)) = $2
+        UNION ALL
+        SELECT uf.file_path FROM uploaded_files uf
+        JOIN chat_sessions s ON s.id = uf.session_id
+        WHERE s.user_id = $1 AND right(uf.file_path, length($2)) = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(&name)
+    .fetch_all(&ctx.app_state.db_pool)
+    .await
+    .map_err(|e| format!("failed to look up {}: {}", file_path, e))?;
+
+    let owned = recorded.iter().any(|path| {
+        crate::services::OutputVideoService::path_candidates(path)
+            .iter()
+            .filter_map(|candidate| std::fs::canonicalize(candidate).ok())
+            .any(|candidate| candidate == absolute)
+    });
+    if !owned {
+        return Err(format!("file not found: {}", file_path));
+    }
+    Ok(relative)
+}
+
+/// Push a finished output to one of the user's delivery targets and summarize the receipt
+async fn deliver_output(file_path: &str, target_name: &str, ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: delivery requires a signed-in user".to_string();
+    };
+    if file_path.is_empty() || target_name.is_empty() {
+        return "❌ Error: file_path and target are required".to_string();
+    }
+    let file_path = match resolve_deliverable_file(file_path, user_id, ctx).await {
+        Ok(path) => path,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let file_path = file_path.as_str();
+
+    let target = match crate::services::DeliveryService::get_target_by_name(&ctx.app_state.db_pool, user_id, target_name).await {
+        Ok(Some(target)) => target,
+        Ok(None) => return format!("❌ Error: no delivery target named '{}'. Use list_delivery_targets to see configured targets", target_name),
+        Err(e) => return format!("❌ Error: failed to load delivery target: {}", e),
+    };
+
+    match crate::services::DeliveryService::deliver(&ctx.app_state.db_pool, user_id, &target, file_path).await {
+        Ok(receipt) if receipt.status == "delivered" => format!(
+            "✅ Delivered {} to {} ({})\n\n📍 Remote: {}\n📦 Size: {} bytes\n🔐 SHA-256: {}\n🔐 MD5: {}\n{} Verification: {}\n🧾 Receipt: {}",
+            receipt.local_path,
+            receipt.target_name,
+            target.protocol,
+            receipt.remote_url,
+            receipt.size_bytes,
+            receipt.sha256,
+            receipt.md5,
+            if receipt.verified { "✓" } else { "⚠️" },
+            receipt.verification_method.as_deref().unwrap_or("not verified"),
+            receipt.receipt_uuid
+        ),
+        Ok(receipt) => format!(
+            "❌ Delivery to {} failed: {} (receipt {})",
+            receipt.target_name,
+            receipt.error_message.as_deref().unwrap_or("unknown error"),
+            receipt.receipt_uuid
+        ),
+        Err(e) => format!("❌ Delivery failed: {}", e),
+    }
+}
+
+/// Deliver an output file (Claude version)
+async fn execute_deliver_output_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let file_path = args["file_path"].as_str().unwrap_or("");
+    let target = args["target"].as_str().unwrap_or("");
+    deliver_output(file_path, target, ctx).await
+}
+
+/// Deliver an output file (Gemini version)
+async fn execute_deliver_output_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let file_path = args.get("file_path").and_then(|v| v.as_str()).unwrap_or("");
+    let target = args.get("target").and_then(|v| v.as_str()).unwrap_or("");
+    deliver_output(file_path, target, ctx).await
+}
+
+/// List the user's configured delivery targets
+async fn execute_list_delivery_targets_with_state(ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: delivery targets require a signed-in user".to_string();
+    };
+    match crate::services::DeliveryService::list_targets(&ctx.app_state.db_pool, user_id).await {
+        Ok(targets) if targets.is_empty() => "✅ No delivery targets configured. Add one via POST /api/delivery/targets".to_string(),
+        Ok(targets) => {
+            let lines = targets.iter()
+                .map(|t| format!("  • {} ({}) → {}:{}", t.name, t.protocol, t.host, t.remote_path))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("✅ {} delivery targets:\n{}", targets.len(), lines)
+        }
+        Err(e) => format!("❌ Error: failed to list delivery targets: {}", e),
+    }
+}
+
+/// Render a file with a named export preset (the user's own, or a built-in platform preset)
+async fn export_with_preset(input: &str, output_file: &str, preset: &str, captions_file: Option<&str>, ctx: &ToolExecutionContext) -> String {
+    if input.is_empty() || preset.is_empty() {
+        return "❌ Error: input_file and preset are required".to_string();
+    }
+    if !std::path::Path::new(input).exists() {
+        return format!("❌ Error: file not found: {}", input);
+    }
+
+    let user_id = resolve_user_id(ctx).await;
+    let settings = match crate::services::ExportPresetService::resolve(&ctx.app_state.db_pool, user_id, preset).await {
+        Ok(settings) => settings,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    // The preset decides the container
+    let output = std::path::Path::new(&ensure_outputs_directory(output_file))
+        .with_extension(&settings.container)
+        .to_string_lossy()
+        .to_string();
+
+    // Burned captions come from the given SRT, or from a fresh transcript
+    let mut generated_srt = None;
+    if settings.burn_captions && captions_file.is_none() {
+        let words = match transcribe_media_words(input, false, &ctx.app_state).await {
+            Ok(words) => words,
+            Err(e) => return format!("❌ Error: preset '{}' burns captions but transcription failed: {}", preset, e),
+        };
+        let cues: Vec<(f64, f64, String)> = crate::visual::group_words_into_lines(&words, 8)
+            .into_iter()
+            .filter_map(|line| Some((
+                line.first()?.start,
+                line.last()?.end,
+                line.iter().map(|w| w.text.trim()).collect::<Vec<_>>().join(" "),
+            )))
+            .collect();
+        let srt_file = format!("outputs/temp_export_captions_{}.srt", uuid::Uuid::new_v4());
+        if let Err(e) = tokio::fs::write(&srt_file, crate::utils::build_srt(&cues)).await {
+            return format!("❌ Error: failed to write captions: {}", e);
+        }
+        generated_srt = Some(srt_file);
+    }
+
+    let captions = captions_file.or(generated_srt.as_deref()).filter(|_| settings.burn_captions);
+    let result = crate::export::export_with_settings(input, &output, &settings, captions);
+    if let Some(srt_file) = generated_srt {
+        let _ = tokio::fs::remove_file(&srt_file).await;
+    }
+    match result {
+        Ok(_) => format!("✅ Exported {} with preset '{}'\n\n📁 Output: {}\n🎛️ {}", input, preset, output, describe_export_settings(&settings)),
+        Err(e) => e,
+    }
+}
+
+fn describe_export_settings(settings: &crate::types::ExportSettings) -> String {
+    let mut parts = vec![format!("{} / {} / {}", settings.container, settings.video_codec, settings.audio_codec)];
+    if let Some(crf) = settings.crf {
+        parts.push(format!("CRF {}", crf));
+    }
+    if let Some(kbps) = settings.video_bitrate_kbps {
+        parts.push(format!("{}k video", kbps));
+    }
+    if let (Some(w), Some(h)) = (settings.width, settings.height) {
+        parts.push(format!("{}x{}", w, h));
+    }
+    if let Some(fps) = settings.fps {
+        parts.push(format!("{}fps", fps));
+    }
+    if let Some(lufs) = settings.loudness_target {
+        parts.push(format!("{} LUFS", lufs));
+    }
+    if settings.burn_captions {
+        parts.push("burned captions".to_string());
+    }
+    parts.join(", ")
+}
+
+/// Export with a named preset (Claude version)
+async fn execute_export_with_preset_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let input = args["input_file"].as_str().unwrap_or("");
+    let output = args["output_file"].as_str().unwrap_or("");
+    let preset = args["preset"].as_str().unwrap_or("");
+    let captions = args["captions_file"].as_str().filter(|s| !s.is_empty());
+    export_with_preset(input, output, preset, captions, ctx).await
+}
+
+/// Export with a named preset (Gemini version)
+async fn execute_export_with_preset_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let input = args.get("input_file").and_then(|v| v.as_str()).unwrap_or("");
+    let output = args.get("output_file").and_then(|v| v.as_str()).unwrap_or("");
+    let preset = args.get("preset").and_then(|v| v.as_str()).unwrap_or("");
+    let captions = args.get("captions_file").and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    export_with_preset(input, output, preset, captions, ctx).await
+}
+
+/// List the user's export presets alongside the built-in platform presets
+async fn execute_list_export_presets_with_state(ctx: &ToolExecutionContext) -> String {
+    let builtin = format!("Built-in presets: {}", crate::export::PLATFORM_PRESETS.join(", "));
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return format!("✅ {}", builtin);
+    };
+    match crate::services::ExportPresetService::list_presets(&ctx.app_state.db_pool, user_id).await {
+        Ok(presets) if presets.is_empty() => format!("✅ No custom export presets. {}. Add one via POST /api/export-presets", builtin),
+        Ok(presets) => {
+            let lines = presets.iter()
+                .map(|p| format!("  • {}: {}", p.name, describe_export_settings(&p.settings())))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("✅ {} export presets:\n{}\n\n{}", presets.len(), lines, builtin)
+        }
+        Err(e) => format!("❌ Error: failed to list export presets: {}", e),
+    }
+}
+
+/// Create a public review link for an output and report the shareable URL
+async fn create_review_link(file_path: &str, args: &Value, ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: review links require a signed-in user".to_string();
+    };
+    let output = match crate::services::OutputVideoService::find_output_by_any_path(&ctx.app_state.db_pool, file_path).await {
+        Ok(Some(output)) => output,
+        Ok(None) => return format!("❌ Error: {} is not a recorded output", file_path),
+        Err(e) => return format!("❌ Error: failed to look up {}: {}", file_path, e),
+    };
+    let request = crate::models::review::CreateReviewLinkRequest {
+        output_id: output.id,
+        title: args.get("title").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        expires_in_hours: args.get("expires_in_hours").and_then(|v| v.as_f64()).map(|h| h as i64),
+        allow_comments: args.get("allow_comments").and_then(|v| v.as_bool()),
+    };
+
+    match crate::services::ReviewService::create_link(&ctx.app_state.db_pool, user_id, &request).await {
+        Ok(link) => format!(
+            "✅ Review link created for {}\n\n🔗 URL: /review/{}\n🎬 Title: {}\n💬 Comments: {}\n⏰ Expires: {}",
+            link.file_path,
+            link.token,
+            link.title,
+            if link.allow_comments { "enabled" } else { "disabled" },
+            link.expires_at.format("%Y-%m-%d %H:%M UTC")
+        ),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Create a client review link (Claude version)
+async fn execute_create_review_link_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let file_path = args["file_path"].as_str().unwrap_or("");
+    create_review_link(file_path, args, ctx).await
+}
+
+/// Create a client review link (Gemini version)
+async fn execute_create_review_link_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let file_path = args.get("file_path").and_then(|v| v.as_str()).unwrap_or("");
+    let args = serde_json::to_value(args).unwrap_or_default();
+    create_review_link(file_path, &args, ctx).await
+}
+
+/// Single-input tools that can run inside a scratch preview chain (they all take input_file/output_file)
+const PREVIEWABLE_TOOLS: &[&str] = &[
+    "add_text_overlay", "apply_filter", "add_overlay", "adjust_color", "add_subtitles",
+    "resize_video", "crop_video", "rotate_video", "adjust_speed", "flip_video", "scale_video",
+    "adjust_volume", "fade_audio", "add_audio", "chroma_key", "stabilize_video", "auto_correct",
+];
+
+/// Longest scratch sample, in seconds
+const MAX_PREVIEW_SECONDS: f64 = 10.0;
+
+/// Scratch runs kept per session; older ones are deleted when a new preview starts
+const SCRATCH_RUNS_KEPT: usize = 5;
+
+/// A proposed effect chain to try on a short, low-resolution sample
+struct PreviewChainRequest {
+    input_file: String,
+    steps: Vec<String>,
+    start_time: f64,
+    duration: f64,
+    height: u32,
+}
+
+/// Parse a preview step: a JSON object with the tool name plus that tool's arguments (minus input/output files)
+fn parse_preview_step(step: &str) -> Result<(String, serde_json::Map<String, Value>), String> {
+    let mut args = match serde_json::from_str::<Value>(step) {
+        Ok(Value::Object(args)) => args,
+        _ => return Err(format!("step is not a JSON object: {}", step)),
+    };
+    let tool = args
+        .remove("tool")
+        .and_then(|t| t.as_str().map(|s| s.to_string()))
+        .ok_or_else(|| format!("step has no \"tool\": {}", step))?;
+    if !PREVIEWABLE_TOOLS.contains(&tool.as_str()) {
+        return Err(format!("{} can't run in a preview chain. Previewable tools: {}", tool, PREVIEWABLE_TOOLS.join(", ")));
+    }
+    Ok((tool, args))
+}
+
+/// Session scratch area for experiments: `outputs/scratch/<session>/`, never recorded as outputs
+fn scratch_dir(session_id: &str) -> std::path::PathBuf {
+    let safe: String = session_id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    std::path::PathBuf::from("outputs/scratch").join(safe)
+}
+
+/// Drop all but the newest scratch runs of a session
+fn prune_scratch_runs(dir: &std::path::Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut runs: Vec<(std::time::SystemTime, std::path::PathBuf)> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| (e.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH), e.path()))
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.0));
+    for (_, path) in runs.into_iter().skip(SCRATCH_RUNS_KEPT.saturating_sub(1)) {
+        let _ = std::fs::remove_dir_all(path);
+    }
+}
+
+/// Render a proposed effect chain on a low-res sample so the user can iterate before the full render
+async fn preview_effect_chain(request: PreviewChainRequest, ctx: &ToolExecutionContext) -> String {
+    if !std::path::Path::new(&request.input_file).exists() {
+        return format!("❌ Error: file not found: {}", request.input_file);
+    }
+    if request.steps.is_empty() {
+        return "❌ Error: steps must contain at least one effect".to_string();
+    }
+    let steps = match request.steps.iter().map(|s| parse_preview_step(s)).collect::<Result<Vec<_>, _>>() {
+        Ok(steps) => steps,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let duration = request.duration.clamp(1.0, MAX_PREVIEW_SECONDS);
+    let height = request.height.clamp(144, 720);
+
+    let session_dir = scratch_dir(&ctx.session_id);
+    prune_scratch_runs(&session_dir);
+    let run_dir = session_dir.join(&uuid::Uuid::new_v4().simple().to_string()[..8]);
+    if let Err(e) = std::fs::create_dir_all(&run_dir) {
+        return format!("❌ Error: failed to create scratch directory: {}", e);
+    }
+    let scratch = |name: &str| run_dir.join(name).to_string_lossy().to_string();
+
+    let sample = scratch("sample.mp4");
+    let (input, out, start) = (request.input_file.clone(), sample.clone(), request.start_time);
+    let cut = tokio::task::spawn_blocking(move || crate::visual::cut_preview_sample(&input, &out, start, duration, height))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    if let Err(e) = cut {
+        return format!("❌ Error: failed to cut preview sample: {}", e);
+    }
+
+    let mut current = sample;
+    for (index, (tool, args)) in steps.iter().enumerate() {
+        let output = scratch(&format!("step_{}.mp4", index + 1));
+        let mut args = args.clone();
+        args.insert("input_file".to_string(), Value::String(current.clone()));
+        args.insert("output_file".to_string(), Value::String(output.clone()));
+        let result = execute_tool_claude(tool, &Value::Object(args)).await;
+        if result.starts_with("❌") || !std::path::Path::new(&output).exists() {
+            return format!("❌ Error: preview step {} ({}) failed: {}", index + 1, tool, result);
+        }
+        current = output;
+    }
+
+    let preview = scratch("preview.mp4");
+    let label = format!("PREVIEW · {}p · {:.0}s sample", height / 2 * 2, duration);
+    let (input, out) = (current.clone(), preview.clone());
+    let stamped = tokio::task::spawn_blocking(move || crate::visual::stamp_preview(&input, &out, &label))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    // An unstamped preview is still useful; it stays in the scratch area either way
+    let preview = if stamped.is_ok() { preview } else { current };
+
+    let chain = steps.iter().enumerate().map(|(i, (tool, _))| format!("  {}. {}", i + 1, tool)).collect::<Vec<_>>().join("\n");
+    format!(
+        "✅ PREVIEW ONLY (not a final render) saved to: {}\n\n🧪 {:.0}s sample from {:.1}s at {}p of {}\n🔗 Effect chain:\n{}\n\n💡 Show this to the user. Scratch previews aren't saved as outputs and are cleared as new experiments run. \
+         Once the user approves, apply the same steps to the full-length {} with the regular tools.",
+        preview,
+        duration,
+        request.start_time,
+        height / 2 * 2,
+        request.input_file,
+        chain,
+        request.input_file
+    )
+}
+
+/// Preview an effect chain on a short sample (Claude version)
+async fn execute_preview_effect_chain_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let request = PreviewChainRequest {
+        input_file: args["input_file"].as_str().unwrap_or("").to_string(),
+        steps: args.get("steps")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().map(|s| s.as_str().map(|s| s.to_string()).unwrap_or_else(|| s.to_string())).collect())
+            .unwrap_or_default(),
+        start_time: args.get("start_time").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        duration: args.get("duration").and_then(|v| v.as_f64()).unwrap_or(MAX_PREVIEW_SECONDS),
+        height: args.get("height").and_then(|v| v.as_u64()).unwrap_or(360) as u32,
+    };
+    preview_effect_chain(request, ctx).await
+}
+
+/// Preview an effect chain on a short sample (Gemini version)
+async fn execute_preview_effect_chain_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let request = PreviewChainRequest {
+        input_file: args.get("input_file").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        steps: args.get("steps")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().map(|s| s.as_str().map(|s| s.to_string()).unwrap_or_else(|| s.to_string())).collect())
+            .unwrap_or_default(),
+        start_time: args.get("start_time").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        duration: args.get("duration").and_then(|v| v.as_f64()).unwrap_or(MAX_PREVIEW_SECONDS),
+        height: args.get("height").and_then(|v| v.as_f64()).unwrap_or(360.0) as u32,
+    };
+    preview_effect_chain(request, ctx).await
+}
+
+/// Which two renders to compare: explicit files, or versions from an output's lineage
+struct CompareVersionsRequest {
+    output_id: Option<i32>,
+    version_a: Option<i32>,
+    version_b: Option<i32>,
+    file_a: Option<String>,
+    file_b: Option<String>,
+    mode: String,
+    wipe_period: f64,
+    output_file: String,
+}
+
+/// Render a side-by-side or wipe comparison of two output versions
+async fn compare_versions(request: CompareVersionsRequest, ctx: &ToolExecutionContext) -> String {
+    if !matches!(request.mode.as_str(), "side_by_side" | "wipe") {
+        return format!("❌ Error: unknown mode '{}'. Use side_by_side or wipe", request.mode);
+    }
+
+    let (file_a, file_b, label_a, label_b) = match (request.output_id, request.file_a, request.file_b) {
+        (Some(output_id), _, _) => {
+            let Some(user_id) = resolve_user_id(ctx).await else {
+                return "❌ Error: comparing output versions requires a signed-in user".to_string();
+            };
+            let history = match crate::services::OutputVideoService::get_version_history(&ctx.app_state.db_pool, output_id).await {
+                Ok(history) if !history.is_empty() && history.iter().all(|v| v.user_id == user_id) => history,
+                Ok(_) => return format!("❌ Error: output {} not found", output_id),
+                Err(e) => return format!("❌ Error: failed to load version history: {}", e),
+            };
+            if history.len() < 2 {
+                return format!("❌ Error: output {} has only one version", output_id);
+            }
+
+            let pick = |version: Option<i32>, default: usize| match version {
+                Some(n) => history.iter().find(|v| v.version == n),
+                None => history.get(default),
+            };
+            let (Some(a), Some(b)) = (pick(request.version_a, 0), pick(request.version_b, history.len() - 1)) else {
+                let available = history.iter().map(|v| format!("v{}", v.version)).collect::<Vec<_>>().join(", ");
+                return format!("❌ Error: version not found. Available versions: {}", available);
+            };
+            let on_disk = |path: &str| {
+                let bare = path.trim_start_matches("./").trim_start_matches("outputs/");
+                let candidate = format!("outputs/{}", bare);
+                if std::path::Path::new(&candidate).exists() { candidate } else { path.to_string() }
+            };
+            (on_disk(&a.file_path), on_disk(&b.file_path), format!("v{}", a.version), format!("v{}", b.version))
+        }
+        (None, Some(a), Some(b)) => (a, b, "A".to_string(), "B".to_string()),
+        _ => return "❌ Error: provide output_id, or both file_a and file_b".to_string(),
+    };
+
+    for file in [&file_a, &file_b] {
+        if !std::path::Path::new(file).exists() {
+            return format!("❌ Error: file not found: {}", file);
+        }
+    }
+
+    let output_file = ensure_outputs_directory(&request.output_file);
+    let (mode, wipe_period) = (request.mode.clone(), request.wipe_period);
+    let (a, b, out, la, lb) = (file_a.clone(), file_b.clone(), output_file.clone(), label_a.clone(), label_b.clone());
+    let rendered = tokio::task::spawn_blocking(move || {
+        crate::visual::render_comparison(&a, &b, &out, &mode, (&la, &lb), wipe_period)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+
+    match rendered {
+        Ok(_) => format!(
+            "✅ Comparison video saved to: {}\n\n🆚 {} ({}) vs {} ({})\n🎞️ Mode: {}",
+            output_file, label_a, file_a, label_b, file_b, request.mode.replace('_', "-")
+        ),
+        Err(e) => format!("❌ Comparison render failed: {}", e),
+    }
+}
+
+/// Compare two output versions (Claude version)
+async fn execute_compare_versions_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let request = CompareVersionsRequest {
+        output_id: args.get("output_id").and_then(|v| v.as_i64()).map(|v| v as i32),
+        version_a: args.get("version_a").and_then(|v| v.as_i64()).map(|v| v as i32),
+        version_b: args.get("version_b").and_then(|v| v.as_i64()).map(|v| v as i32),
+        file_a: args.get("file_a").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        file_b: args.get("file_b").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        mode: args.get("mode").and_then(|v| v.as_str()).unwrap_or("side_by_side").to_string(),
+        wipe_period: args.get("wipe_period").and_then(|v| v.as_f64()).unwrap_or(6.0),
+        output_file: args["output_file"].as_str().unwrap_or("comparison.mp4").to_string(),
+    };
+    compare_versions(request, ctx).await
+}
+
+/// Compare two output versions (Gemini version)
+async fn execute_compare_versions_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let request = CompareVersionsRequest {
+        output_id: args.get("output_id").and_then(|v| v.as_f64()).map(|v| v as i32),
+        version_a: args.get("version_a").and_then(|v| v.as_f64()).map(|v| v as i32),
+        version_b: args.get("version_b").and_then(|v| v.as_f64()).map(|v| v as i32),
+        file_a: args.get("file_a").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        file_b: args.get("file_b").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        mode: args.get("mode").and_then(|v| v.as_str()).unwrap_or("side_by_side").to_string(),
+        wipe_period: args.get("wipe_period").and_then(|v| v.as_f64()).unwrap_or(6.0),
+        output_file: args.get("output_file").and_then(|v| v.as_str()).unwrap_or("comparison.mp4").to_string(),
+    };
+    compare_versions(request, ctx).await
+}
+
+/// Undo the session's latest direct edits (Claude version)
+async fn execute_undo_last_edit_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    use crate::services::EditHistoryService;
+
+    let steps = args.get("steps").and_then(|v| v.as_f64()).map(|v| v as i64).unwrap_or(1);
+    let pool = &ctx.app_state.db_pool;
+    let undone = match EditHistoryService::undo(pool, &ctx.session_id, steps).await {
+        Ok(undone) => undone,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let history = EditHistoryService::history(pool, &ctx.session_id).await.unwrap_or_default();
+    let current = history.iter().rfind(|step| step.undone_at.is_none());
+
+    let mut result = format!(
+        "↩️ Undid {}\n",
+        undone.iter().map(|step| format!("{} ({})", step.tool, step.file_path)).collect::<Vec<_>>().join(", ")
+    );
+    match current {
+        Some(step) => result.push_str(&format!("Current output is now {} from {}; continue from it.\n", step.file_path, step.tool)),
+        None => result.push_str("No edited outputs remain; continue from the original upload.\n"),
+    }
+    result.push_str("The undone files are kept until the next edit, so redo_edit can bring them back.\n\n");
+    result.push_str(&EditHistoryService::describe(&history));
+    result
+}
+
+/// Undo the session's latest direct edits (Gemini version)
+async fn execute_undo_last_edit_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_undo_last_edit_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// Bring back the session's most recently undone edits (Claude version)
+async fn execute_redo_edit_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    use crate::services::EditHistoryService;
+
+    let steps = args.get("steps").and_then(|v| v.as_f64()).map(|v| v as i64).unwrap_or(1);
+    let pool = &ctx.app_state.db_pool;
+    let redone = match EditHistoryService::redo(pool, &ctx.session_id, steps).await {
+        Ok(redone) => redone,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let history = EditHistoryService::history(pool, &ctx.session_id).await.unwrap_or_default();
+
+    let mut result = format!(
+        "↪️ Redid {}\n",
+        redone.iter().map(|step| format!("{} ({})", step.tool, step.file_path)).collect::<Vec<_>>().join(", ")
+    );
+    if let Some(step) = redone.last() {
+        result.push_str(&format!("Current output is now {}; continue from it.\n", step.file_path));
+    }
+    result.push('\n');
+    result.push_str(&EditHistoryService::describe(&history));
+    result
+}
+
+/// Bring back the session's most recently undone edits (Gemini version)
+async fn execute_redo_edit_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_redo_edit_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// Apply one edit to many videos as a background job (Claude version)
+async fn execute_batch_process_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    use crate::jobs::batch_process_job::{self, BatchOperation};
+
+    // The arguments come as a JSON object string, or as an object from models that send one
+    let tool_args = match args.get("arguments") {
+        None | Some(Value::Null) => serde_json::Map::new(),
+        Some(Value::Object(map)) => map.clone(),
+        Some(Value::String(text)) if text.trim().is_empty() => serde_json::Map::new(),
+        Some(Value::String(text)) => match serde_json::from_str::<Value>(text) {
+            Ok(Value::Object(map)) => map,
+            _ => return format!("❌ Error: arguments is not a JSON object: {}", text),
+        },
+        Some(other) => return format!("❌ Error: arguments is not a JSON object: {}", other),
+    };
+    let operation = BatchOperation { tool: args["operation"].as_str().unwrap_or("").to_string(), args: tool_args };
+    if let Err(e) = batch_process_job::validate_operation(&operation) {
+        return format!("❌ Error: {}", e);
+    }
+
+    let listed: Vec<String> = args["files"]
+        .as_array()
+        .map(|files| files.iter().filter_map(|f| f.as_str()).map(|f| f.to_string()).collect())
+        .unwrap_or_default();
+    let files = match args["folder"].as_str().filter(|f| !f.trim().is_empty()) {
+        Some(folder) => batch_process_job::folder_videos(folder),
+        None if !listed.is_empty() => Ok(listed),
+        None => batch_process_job::session_videos(&ctx.app_state.db_pool, &ctx.session_id)
+            .await
+            .map_err(|e| format!("Failed to list the chat's videos: {}", e)),
+    };
+    let files = match files {
+        Ok(files) => files,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+
+    let count = files.len();
+    let tool = operation.tool.clone();
+    match batch_process_job::spawn_batch_process_job(
+        ctx.session_id.clone(),
+        resolve_user_id(ctx).await.map(|u| u.to_string()),
+        operation,
+        files,
+        ctx.app_state.job_manager.clone(),
+        ctx.app_state.db_pool.clone(),
+    )
+    .await
+    {
+        Ok(job_id) => format!(
+            "✅ Batch started: {} on {} videos (job {})\n\nProgress is reported per file; when it finishes the outputs and results.zip are listed in the job's output files.",
+            tool, count, job_id
+        ),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Apply one edit to many videos as a background job (Gemini version)
+async fn execute_batch_process_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_batch_process_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// A project of this session by name
+async fn find_session_project(name: &str, ctx: &ToolExecutionContext) -> Result<crate::models::project::Project, String> {
+    crate::services::ProjectService::find_by_name(&ctx.app_state.db_pool, &ctx.session_id, name)
+        .await
+        .map_err(|e| format!("failed to load project '{}': {}", name, e))?
+        .ok_or_else(|| format!("no project named '{}' in this session. Start one with create_project", name))
+}
+
+/// Start a project whose edits are recorded, not rendered, until export (Claude version)
+async fn execute_create_project_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let name = args["name"].as_str().unwrap_or("");
+    let source_file = args["source_file"].as_str().unwrap_or("");
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: projects require a signed-in user".to_string();
+    };
+    match crate::services::ProjectService::create_project(&ctx.app_state.db_pool, user_id, &ctx.session_id, name, source_file).await {
+        Ok(project) => format!(
+            "✅ Project '{}' started on {}\n\nAdd operations with edit_project (action \"add\"); nothing is rendered until export_project.",
+            project.name, project.source_file
+        ),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Start a project (Gemini version)
+async fn execute_create_project_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_create_project_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// Add, remove or move a project operation, undo or redo a change, or show the edit list (Claude version)
+async fn execute_edit_project_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    use crate::services::project::describe_operations;
+    use crate::services::ProjectService;
+
+    let project = match find_session_project(args["project"].as_str().unwrap_or(""), ctx).await {
+        Ok(project) => project,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let pool = &ctx.app_state.db_pool;
+    let position = args.get("position").and_then(|v| v.as_f64()).map(|v| v as usize);
+
+    let (result, done) = match args["action"].as_str().unwrap_or("") {
+        "show" => (ProjectService::operations(pool, &project).await.map(|ops| (project, ops)), "Edit list".to_string()),
+        "add" => {
+            // The arguments come as a JSON object string, or as an object from models that send one
+            let tool_args = match args.get("args") {
+                None | Some(Value::Null) => Ok(serde_json::Map::new()),
+                Some(Value::Object(map)) => Ok(map.clone()),
+                Some(Value::String(text)) if text.trim().is_empty() => Ok(serde_json::Map::new()),
+                Some(Value::String(text)) => match serde_json::from_str::<Value>(text) {
+                    Ok(Value::Object(map)) => Ok(map),
+                    _ => Err(format!("args is not a JSON object: {}", text)),
+                },
+                Some(other) => Err(format!("args is not a JSON object: {}", other)),
+            };
+            let operation = tool_args.map(|tool_args| crate::models::project::ProjectOperation {
+                tool: args["tool"].as_str().unwrap_or("").to_string(),
+                args: tool_args,
+                note: args.get("note").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+            });
+            let done = format!("Added {}", args["tool"].as_str().unwrap_or(""));
+            match operation {
+                Ok(operation) => (ProjectService::add_operation(pool, &project, operation, position).await, done),
+                Err(e) => (Err(e), done),
+            }
+        }
+        "remove" => match position {
+            Some(position) => (ProjectService::remove_operation(pool, &project, position).await, format!("Removed operation {}", position)),
+            None => return "❌ Error: remove needs the position of the operation".to_string(),
+        },
+        "move" => match (position, args.get("to").and_then(|v| v.as_f64()).map(|v| v as usize)) {
+            (Some(from), Some(to)) => (ProjectService::move_operation(pool, &project, from, to).await, format!("Moved operation {} to {}", from, to)),
+            _ => return "❌ Error: move needs position (the operation) and to (where it goes)".to_string(),
+        },
+        "undo" | "redo" => {
+            let action = args["action"].as_str().unwrap_or("");
+            let changed = if action == "undo" {
+                ProjectService::undo(pool, &project).await
+            } else {
+                ProjectService::redo(pool, &project).await
+            };
+            match changed {
+                Ok((project, change)) => {
+                    let verb = if action == "undo" { "Undid" } else { "Redid" };
+                    (ProjectService::operations(pool, &project).await.map(|ops| (project, ops)), format!("{}: {}", verb, change))
+                }
+                Err(e) => (Err(e), String::new()),
+            }
+        }
+        other => return format!("❌ Error: unknown action '{}'. Use add, remove, move, undo, redo or show", other),
+    };
+
+    match result {
+        Ok((project, operations)) => format!(
+            "✅ {} (project '{}', revision {})\n\n🎞️ {} from {}:\n{}",
+            done,
+            project.name,
+            project.revision,
+            if operations.len() == 1 { "1 operation".to_string() } else { format!("{} operations", operations.len()) },
+            project.source_file,
+            describe_operations(&operations)
+        ),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Edit a project (Gemini version)
+async fn execute_edit_project_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_edit_project_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// Render a project's current edit list, optionally with an export preset (Claude version)
+async fn execute_export_project_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    use crate::services::ProjectService;
+
+    let project = match find_session_project(args["project"].as_str().unwrap_or(""), ctx).await {
+        Ok(project) => project,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    let preset = args.get("preset").and_then(|v| v.as_str());
+    let export = match ProjectService::start_export(&ctx.app_state, &project, preset).await {
+        Ok(export) => export,
+        Err(e) => return format!("❌ Error: {}", e),
+    };
+    match ProjectService::render_export(&ctx.app_state, &project, &export).await {
+        Ok(export) => format!(
+            "✅ Exported project '{}' (revision {}){}\n\n📁 Output: {}",
+            project.name,
+            export.revision,
+            export.preset.as_deref().map(|p| format!(" with preset '{}'", p)).unwrap_or_default(),
+            export.output_path.unwrap_or_default()
+        ),
+        Err(e) => format!("❌ Error: export failed: {}", e),
+    }
+}
+
+/// Export a project (Gemini version)
+async fn execute_export_project_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    execute_export_project_with_state_claude(&serde_json::to_value(args).unwrap_or_default(), ctx).await
+}
+
+/// Search the user's asset library and list matching references
+async fn search_library(query: crate::models::library::LibrarySearchQuery, ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: the asset library requires a signed-in user".to_string();
+    };
+
+    match crate::services::LibraryService::search_with_semantics(&ctx.app_state, user_id, &query).await {
+        Ok(assets) if assets.is_empty() => "✅ No matching assets in your library".to_string(),
+        Ok(assets) => {
+            let ids: Vec<String> = assets.iter().map(|a| a.id.to_string()).collect();
+            let visual = crate::services::AssetTaggingService::get_tags_for(&ctx.app_state.db_pool, "library", &ids)
+                .await
+                .unwrap_or_default();
+            let lines = assets.iter()
+                .map(|a| {
+                    let duration = a.duration_seconds.map(|d| format!(", {:.1}s", d)).unwrap_or_default();
+                    let tags = if a.tags.is_empty() { String::new() } else { format!(" [{}]", a.tags.join(", ")) };
+                    let description = a.description.as_deref().map(|d| format!(" - {}", d)).unwrap_or_default();
+                    let seen = visual.get(&a.id.to_string())
+                        .filter(|t| t.status == "tagged")
+                        .map(|t| format!("\n      👁️ {}", t.summary()))
+                        .unwrap_or_default();
+                    format!("  • {} ({}{}){}{}{}", a.reference(), a.kind, duration, tags, description, seen)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                "✅ Found {} library assets:\n{}\n\n💡 Pass a reference like \"{}\" as any file argument",
+                assets.len(),
+                lines,
+                assets[0].reference()
+            )
+        }
+        Err(e) => format!("❌ Error: failed to search library: {}", e),
+    }
+}
+
+/// Search the asset library (Claude version)
+async fn execute_search_library_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let query = crate::models::library::LibrarySearchQuery {
+        q: args.get("query").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        tag: args.get("tag").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        kind: args.get("kind").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        has_faces: args.get("has_faces").and_then(|v| v.as_bool()),
+    };
+    search_library(query, ctx).await
+}
+
+/// Search the asset library (Gemini version)
+async fn execute_search_library_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let query = crate::models::library::LibrarySearchQuery {
+        q: args.get("query").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        tag: args.get("tag").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        kind: args.get("kind").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        has_faces: args.get("has_faces").and_then(|v| v.as_bool()),
+    };
+    search_library(query, ctx).await
+}
+
+/// Save a file to the user's asset library
+async fn add_to_library(file_path: &str, meta: crate::models::library::NewLibraryAsset, ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: the asset library requires a signed-in user".to_string();
+    };
+
+    match crate::services::LibraryService::import_file(&ctx.app_state.db_pool, user_id, file_path, &meta).await {
+        Ok(asset) => {
+            crate::services::AssetTaggingService::spawn_tagging(
+                ctx.app_state.clone(),
+                "library",
+                asset.id.to_string(),
+                Some(user_id),
+                asset.file_path.clone(),
+            );
+            format!(
+                "✅ Saved {} to your library as {} ({})\n\n💡 Use \"{}\" as a file argument in any session",
+                file_path,
+                asset.name,
+                asset.kind,
+                asset.reference()
+            )
+        }
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Save a file to the asset library (Claude version)
+async fn execute_add_to_library_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let meta = crate::models::library::NewLibraryAsset {
+        name: args["name"].as_str().unwrap_or("").to_string(),
+        kind: args.get("kind").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        tags: args.get("tags")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default(),
+        description: args.get("description").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    };
+    add_to_library(args["file_path"].as_str().unwrap_or(""), meta, ctx).await
+}
+
+/// Save a file to the asset library (Gemini version)
+async fn execute_add_to_library_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let meta = crate::models::library::NewLibraryAsset {
+        name: args.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        kind: args.get("kind").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        tags: args.get("tags")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default(),
+        description: args.get("description").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    };
+    add_to_library(args.get("file_path").and_then(|v| v.as_str()).unwrap_or(""), meta, ctx).await
+}
+
+/// List the session's uploads and outputs, filtered and paged
+async fn list_files(query: crate::models::file::SessionFileQuery, ctx: &ToolExecutionContext) -> String {
+    use crate::services::file_discovery::{describe, FileDiscoveryService};
+
+    match FileDiscoveryService::list(&ctx.app_state.db_pool, &ctx.session_id, &query).await {
+        Ok((files, 0)) if files.is_empty() => "✅ No files in this session match".to_string(),
+        Ok((files, total)) => {
+            let offset = query.offset.unwrap_or(0).max(0);
+            let mut reply = format!(
+                "✅ Files {}-{} of {}:\n{}",
+                offset + 1,
+                offset + files.len() as i64,
+                total,
+                files.iter().map(describe).collect::<Vec<_>>().join("\n")
+            );
+            if offset + (files.len() as i64) < total {
+                reply.push_str(&format!("\n\n💡 More files: call list_files again with offset {}", offset + files.len() as i64));
+            }
+            reply
+        }
+        Err(e) => format!("❌ Error: failed to list files: {}", e),
+    }
+}
+
+fn session_file_query(get: impl Fn(&str) -> Option<Value>) -> crate::models::file::SessionFileQuery {
+    crate::models::file::SessionFileQuery {
+        source: get("source").and_then(|v| v.as_str().map(|s| s.to_lowercase())).filter(|s| s != "all"),
+        kind: get("kind").and_then(|v| v.as_str().map(|s| s.to_lowercase())),
+        name: get("name").and_then(|v| v.as_str().map(|s| s.to_string())),
+        limit: get("limit").and_then(|v| v.as_f64()).map(|v| v as i64),
+        offset: get("offset").and_then(|v| v.as_f64()).map(|v| v as i64),
+    }
+}
+
+/// List session files (Claude version)
+async fn execute_list_files_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    list_files(session_file_query(|key| args.get(key).cloned()), ctx).await
+}
+
+/// List session files (Gemini version)
+async fn execute_list_files_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    list_files(session_file_query(|key| args.get(key).cloned()), ctx).await
+}
+
+/// Find session files (and library assets) matching a description
+async fn find_asset(description: &str, limit: usize, ctx: &ToolExecutionContext) -> String {
+    use crate::services::file_discovery::{describe, FileDiscoveryService};
+
+    if description.trim().is_empty() {
+        return "❌ Error: describe the file you're looking for".to_string();
+    }
+    let user_id = resolve_user_id(ctx).await;
+    let files = match FileDiscoveryService::find(&ctx.app_state, &ctx.session_id, user_id, description, limit).await {
+        Ok(files) => files,
+        Err(e) => return format!("❌ Error: failed to search files: {}", e),
+    };
+
+    let mut reply = if files.is_empty() {
+        "✅ No files in this session match that description".to_string()
+    } else {
+        format!(
+            "✅ {} matching files in this session, best first:\n{}",
+            files.len(),
+            files.iter().map(describe).collect::<Vec<_>>().join("\n")
+        )
+    };
+
+    // The user's library is searched too, since assets there work in any session
+    if let Some(user_id) = user_id {
+        let query = crate::models::library::LibrarySearchQuery { q: Some(description.to_string()), ..Default::default() };
+        if let Ok(assets) = crate::services::LibraryService::search_with_semantics(&ctx.app_state, user_id, &query).await {
+            if !assets.is_empty() {
+                let lines = assets.iter().take(5).map(|a| format!("  • {} ({})", a.reference(), a.kind)).collect::<Vec<_>>().join("\n");
+                reply.push_str(&format!("\n\n📚 From your library:\n{}", lines));
+            }
+        }
+    }
+    reply
+}
+
+/// Find session files by description (Claude version)
+async fn execute_find_asset_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let limit = args.get("limit").and_then(|v| v.as_f64()).map(|v| (v as usize).clamp(1, 25)).unwrap_or(10);
+    find_asset(args.get("description").and_then(|v| v.as_str()).unwrap_or(""), limit, ctx).await
+}
+
+/// Find session files by description (Gemini version)
+async fn execute_find_asset_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let limit = args.get("limit").and_then(|v| v.as_f64()).map(|v| (v as usize).clamp(1, 25)).unwrap_or(10);
+    find_asset(args.get("description").and_then(|v| v.as_str()).unwrap_or(""), limit, ctx).await
+}
+
+/// Clip the tail of the live stream running in this session
+async fn clip_live_stream(seconds: f64, output_file: Option<&str>, ctx: &ToolExecutionContext) -> String {
+    let Some(user_id) = resolve_user_id(ctx).await else {
+        return "❌ Error: live clipping requires a signed-in user".to_string();
+    };
+    let ingest = match crate::services::IngestService::find_live_for_session(&ctx.app_state.db_pool, &ctx.session_id).await {
+        Ok(Some(ingest)) if ingest.user_id == user_id => ingest,
+        Ok(_) => return "❌ Error: no live stream is running in this session. Create a stream key with POST /api/ingest and start streaming first".to_string(),
+        Err(e) => return format!("❌ Error: failed to look up live stream: {}", e),
+    };
+
+    match crate::services::IngestService::clip_live(&ctx.app_state.db_pool, &ingest, seconds, output_file).await {
+        Ok(clip) => format!(
+            "✅ Live clip saved to: {}\n\n🔴 Stream: {} (still live)\n⏱️ Duration: {:.1}s",
+            clip.file_path,
+            ingest.title,
+            clip.duration_seconds.unwrap_or(seconds)
+        ),
+        Err(e) => format!("❌ Error: {}", e),
+    }
+}
+
+/// Clip a live stream (Claude version)
+async fn execute_clip_live_stream_with_state_claude(args: &Value, ctx: &ToolExecutionContext) -> String {
+    let seconds = args.get("seconds").and_then(|v| v.as_f64()).unwrap_or(60.0);
+    clip_live_stream(seconds, args.get("output_file").and_then(|v| v.as_str()), ctx).await
+}
+
+/// Clip a live stream (Gemini version)
+async fn execute_clip_live_stream_with_state_gemini(args: &HashMap<String, Value>, ctx: &ToolExecutionContext) -> String {
+    let seconds = args.get("seconds").and_then(|v| v.as_f64()).unwrap_or(60.0);
+    clip_live_stream(seconds, args.get("output_file").and_then(|v| v.as_str()), ctx).await
+}
+
 // ============================================================================
 // CHAT TITLE MANAGEMENT TOOLS
 // ============================================================================
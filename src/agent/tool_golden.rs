@@ -0,0 +1,203 @@
+// src/agent/tool_golden.rs
+// Golden-file tests of the FFmpeg commands each tool builds. Every case runs through the Claude tool
+// executor under `capture_ffmpeg`, so nothing is rendered and no media is needed; the recorded
+// arguments are compared with tests/golden/ffmpeg/<tool>.txt. A filtergraph change shows up as a
+// diff of that file. After an intended change, regenerate the files with
+//     UPDATE_GOLDEN=1 cargo test tool_golden
+// and review the diff like any other change.
+//
+// Tools that probe or measure their input before building the command (merge_videos, multi_export,
+// auto_correct, stabilize_video, ...) need real media and are covered by tool_harness instead.
+use super::tool_executor::execute_tool_claude;
+use crate::utils::capture_ffmpeg;
+use std::path::PathBuf;
+
+/// Parameter matrix per tool: (case label, arguments)
+const MATRIX: &[(&str, &[(&str, &str)])] = &[
+    ("trim_video", &[
+        ("seconds", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "start_seconds": 1.5, "end_seconds": 7}"#),
+        ("timecode", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "start_seconds": "00:00:01.500", "end_seconds": "00:00:07"}"#),
+    ]),
+    ("split_video", &[
+        ("10s segments", r#"{"input_file": "input.mp4", "output_prefix": "part", "segment_duration": 10}"#),
+    ]),
+    ("add_text_overlay", &[
+        ("defaults", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "text": "Hello", "x": 100, "y": 50}"#),
+        ("styled and timed", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "text": "It's 50% off: now", "x": 0, "y": 900, "font_size": 72, "color": "yellow", "start_time": 2, "end_time": 5}"#),
+    ]),
+    ("apply_filter", &[
+        ("grayscale", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "grayscale"}"#),
+        ("sepia", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "sepia"}"#),
+        ("blur half", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "blur", "intensity": 0.5}"#),
+        ("sharpen", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "sharpen"}"#),
+        ("edge", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "edge"}"#),
+        ("emboss", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "emboss"}"#),
+        ("negative", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "filter_type": "negative"}"#),
+    ]),
+    ("add_overlay", &[
+        ("image corner", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "overlay_file": "overlay.png", "x": 20, "y": 20}"#),
+    ]),
+    ("adjust_color", &[
+        ("brightness", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "brightness": 0.2}"#),
+        ("all", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "brightness": -0.1, "contrast": 0.3, "saturation": 0.5, "hue": 45}"#),
+    ]),
+    ("add_subtitles", &[
+        ("srt file", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "subtitle_text": "subs.srt"}"#),
+    ]),
+    ("resize_video", &[
+        ("720p", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "width": 1280, "height": 720}"#),
+        ("720p at 30 fps", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "width": 1280, "height": 720, "fps": 30}"#),
+    ]),
+    ("crop_video", &[
+        ("center square", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "x": 420, "y": 0, "width": 1080, "height": 1080}"#),
+    ]),
+    ("rotate_video", &[
+        ("90", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "degrees": 90}"#),
+        ("180", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "degrees": 180}"#),
+        ("270", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "degrees": 270}"#),
+    ]),
+    ("adjust_speed", &[
+        ("half", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "speed_factor": 0.5}"#),
+        ("double", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "speed_factor": 2.0}"#),
+        ("quadruple", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "speed_factor": 4.0}"#),
+    ]),
+    ("flip_video", &[
+        ("horizontal", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "direction": "horizontal"}"#),
+        ("vertical", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "direction": "vertical"}"#),
+    ]),
+    ("scale_video", &[
+        ("half", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "scale_factor": 0.5}"#),
+        ("double", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "scale_factor": 2.0}"#),
+    ]),
+    ("extract_audio", &[
+        ("mp3", r#"{"input_file": "input.mp4", "output_file": "out.mp3", "format": "mp3"}"#),
+        ("wav", r#"{"input_file": "input.mp4", "output_file": "out.wav", "format": "wav"}"#),
+        ("aac", r#"{"input_file": "input.mp4", "output_file": "out.aac", "format": "aac"}"#),
+    ]),
+    ("add_audio", &[
+        ("replace", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "audio_file": "audio.wav", "replace": true}"#),
+        ("mix", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "audio_file": "audio.wav", "replace": false}"#),
+    ]),
+    ("adjust_volume", &[
+        ("half", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "volume_factor": 0.5}"#),
+        ("boost", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "volume_factor": 2.0}"#),
+    ]),
+    ("fade_audio", &[
+        ("in and out", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "fade_in_duration": 2, "fade_out_duration": 3}"#),
+        ("in only", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "fade_in_duration": 1.5, "fade_out_duration": 0}"#),
+    ]),
+    ("convert_format", &[
+        ("webm", r#"{"input_file": "input.mp4", "output_file": "out.webm", "format": "webm"}"#),
+        ("mov", r#"{"input_file": "input.mp4", "output_file": "out.mov", "format": "mov"}"#),
+        ("avi", r#"{"input_file": "input.mp4", "output_file": "out.avi", "format": "avi"}"#),
+        ("gif", r#"{"input_file": "input.mp4", "output_file": "out.gif", "format": "gif"}"#),
+    ]),
+    ("compress_video", &[
+        ("high", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "quality": "high"}"#),
+        ("medium", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "quality": "medium"}"#),
+        ("low", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "quality": "low"}"#),
+    ]),
+    ("export_for_platform", &[
+        ("youtube", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "platform": "youtube"}"#),
+        ("youtube-4k", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "platform": "youtube-4k"}"#),
+        ("instagram", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "platform": "instagram"}"#),
+        ("tiktok", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "platform": "tiktok"}"#),
+        ("twitter", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "platform": "twitter"}"#),
+        ("facebook", r#"{"input_file": "input.mp4", "output_file": "out.mp4", "platform": "facebook"}"#),
+    ]),
+    ("create_thumbnail", &[
+        ("at 12.5s", r#"{"input_file": "input.mp4", "output_file": "thumb.jpg", "timestamp": 12.5}"#),
+    ]),
+    ("extract_frames", &[
+        ("one per second", r#"{"input_file": "input.mp4", "output_dir": "frames"}"#),
+        ("every 5s as jpg", r#"{"input_file": "input.mp4", "output_dir": "frames", "frame_rate": 0.2, "format": "jpg"}"#),
+    ]),
+    ("picture_in_picture", &[
+        ("top left", r#"{"main_video": "input.mp4", "pip_video": "second.mp4", "output_file": "out.mp4", "x": 20, "y": 20, "scale": 0.3}"#),
+    ]),
+    ("chroma_key", &[
+        ("green", r#"{"input_file": "input.mp4", "background_file": "background.mp4", "output_file": "out.mp4"}"#),
+        ("blue loose", r#"{"input_file": "input.mp4", "background_file": "background.mp4", "output_file": "out.mp4", "key_color": "blue", "similarity": 0.45}"#),
+    ]),
+    ("split_screen", &[
+        ("horizontal", r#"{"video1": "input.mp4", "video2": "second.mp4", "output_file": "out.mp4", "orientation": "horizontal"}"#),
+        ("vertical", r#"{"video1": "input.mp4", "video2": "second.mp4", "output_file": "out.mp4", "orientation": "vertical"}"#),
+    ]),
+    ("create_blank_video", &[
+        ("defaults", r#"{"output_file": "blank.mp4", "duration": 5}"#),
+        ("vertical white at 60 fps", r#"{"output_file": "blank.mp4", "duration": 3, "width": 1080, "height": 1920, "color": "white", "fps": 60}"#),
+    ]),
+];
+
+fn golden_path(tool: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/ffmpeg").join(format!("{}.txt", tool))
+}
+
+/// Every case's arguments and the commands it produced, one argument per line
+async fn render_snapshot(tool: &str, cases: &[(&str, &str)]) -> String {
+    let mut snapshot = String::new();
+    for (label, args) in cases {
+        let args: serde_json::Value =
+            serde_json::from_str(args).unwrap_or_else(|e| panic!("{} / {}: arguments aren't valid JSON: {}", tool, label, e));
+        let (reply, commands) = capture_ffmpeg(execute_tool_claude(tool, &args)).await;
+
+        snapshot.push_str(&format!("=== {}\n{}\n", label, args));
+        if reply.contains("❌") {
+            snapshot.push_str(&format!("error: {}\n", reply.trim()));
+        }
+        for command in commands {
+            snapshot.push_str("ffmpeg\n");
+            for arg in command {
+                snapshot.push_str(&format!("    {}\n", arg));
+            }
+        }
+        snapshot.push('\n');
+    }
+    snapshot
+}
+
+#[tokio::test]
+async fn ffmpeg_commands_match_golden_files() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1");
+    let mut changed = Vec::new();
+
+    for (tool, cases) in MATRIX {
+        let snapshot = render_snapshot(tool, cases).await;
+        let path = golden_path(tool);
+        if update {
+            std::fs::create_dir_all(path.parent().unwrap()).expect("golden folder");
+            std::fs::write(&path, &snapshot).expect("write golden file");
+            continue;
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap_or_default().replace("\r\n", "\n");
+        if expected != snapshot {
+            let first_difference = expected
+                .lines()
+                .zip(snapshot.lines())
+                .enumerate()
+                .find(|(_, (a, b))| a != b)
+                .map(|(line, (a, b))| format!("line {}: expected {:?}, got {:?}", line + 1, a, b))
+                .unwrap_or_else(|| "one file is a prefix of the other".to_string());
+            changed.push(format!("{} ({})", path.display(), first_difference));
+        }
+    }
+
+    assert!(
+        changed.is_empty(),
+        "FFmpeg commands changed; if intended, rerun with UPDATE_GOLDEN=1 and review the diff:\n{}",
+        changed.join("\n")
+    );
+}
+
+#[test]
+fn matrix_covers_distinct_tools() {
+    let mut tools: Vec<&str> = MATRIX.iter().map(|(tool, _)| *tool).collect();
+    tools.sort();
+    let count = tools.len();
+    tools.dedup();
+    assert_eq!(count, tools.len(), "a tool appears twice in MATRIX; merge its cases");
+    for (tool, cases) in MATRIX {
+        assert!(!cases.is_empty(), "{} has no cases", tool);
+    }
+}
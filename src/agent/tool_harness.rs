@@ -0,0 +1,524 @@
+// src/agent/tool_harness.rs
+// End-to-end checks of the tool executor against synthetic media: every tool the agents are offered
+// is either run here through both the Claude and Gemini dispatch paths, with its output probed, or
+// listed with the reason it can't run offline. A new tool fails `every_tool_is_covered` until it
+// is added to one of the tables.
+use super::tool_executor::{execute_tool_claude, execute_tool_gemini};
+use crate::test_support::{ffmpeg_available, Pattern, SyntheticMedia, VideoSpec};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Tools that call a third-party API (stock media, generation, vision)
+const NETWORK_TOOLS: &[&str] = &[
+    "pexels_search",
+    "pexels_download_video",
+    "pexels_download_photo",
+    "pexels_get_trending",
+    "pexels_get_curated",
+    "analyze_image",
+    "generate_text_to_speech",
+    "generate_sound_effect",
+    "generate_music",
+    "add_voiceover_to_video",
+    "generate_video_script",
+    "generate_image",
+    "auto_generate_video",
+    "view_video",
+    "review_video",
+    "view_image",
+];
+
+/// Tools only dispatched with an AppState (database, session or API clients)
+const STATEFUL_TOOLS: &[&str] = &[
+    "add_styled_captions",
+    "generate_captions",
+    "ask_about_video",
+    "summarize_video",
+    "set_chat_title",
+    "set_session_defaults",
+    "upload_to_youtube",
+    "optimize_youtube_metadata",
+    "analyze_youtube_performance",
+    "suggest_content_ideas",
+    "search_youtube_trends",
+    "search_youtube_channels",
+    "remove_fillers",
+    "censor_profanity",
+    "transcribe_speakers",
+    "edit_by_speaker",
+    "select_music",
+    "compose_screencast",
+    "create_lyric_video",
+    "generate_quiz_video",
+    "generate_video_from_article",
+    "generate_talking_head",
+    "export_localized",
+    "deliver_output",
+    "list_delivery_targets",
+    "export_with_preset",
+    "list_export_presets",
+    "create_review_link",
+    "rerender_region",
+    "preview_effect_chain",
+    "create_project",
+    "edit_project",
+    "export_project",
+    "undo_last_edit",
+    "redo_edit",
+    "batch_process",
+    "compare_versions",
+    "search_library",
+    "add_to_library",
+    "list_files",
+    "find_asset",
+    "clip_live_stream",
+];
+
+/// What a tool run must produce
+#[derive(Debug, Clone, Copy)]
+enum Expect {
+    /// A video with these properties (None = not checked)
+    Video { width: Option<u32>, height: Option<u32>, seconds: Option<f64>, audio: Option<bool> },
+    /// An audio-only file
+    Audio { seconds: Option<f64> },
+    /// A still image
+    Image,
+    /// At least this many files whose path starts with the output
+    Files { min: usize },
+    /// The tool's reply contains this text
+    Reply(&'static str),
+}
+
+const ANY_VIDEO: Expect = Expect::Video { width: None, height: None, seconds: None, audio: None };
+const SAME_VIDEO: Expect = Expect::Video { width: Some(320), height: Some(240), seconds: Some(4.0), audio: Some(true) };
+
+/// Tolerance on durations: container rounding plus one AAC frame at each end
+const DURATION_TOLERANCE: f64 = 0.15;
+
+struct ToolCase {
+    tool: &'static str,
+    /// Arguments as JSON. Placeholders: {video} and {video2} (4 s, 320x240, with audio), {green},
+    /// {audio} (4 s sweep), {image} (160x120 PNG), {srt}, {dir} (a scratch folder per provider,
+    /// containing an empty frames/) and {out} (this case's output path without extension, under outputs/)
+    args: &'static str,
+    /// Path the expectation is checked against, with the same placeholders
+    output: &'static str,
+    expect: Expect,
+}
+
+const CASES: &[ToolCase] = &[
+    ToolCase {
+        tool: "trim_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "start_seconds": "1", "end_seconds": "00:00:03.000"}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: Some(2.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "merge_videos",
+        args: r#"{"input_files": ["{video}", "{video2}"], "output_file": "{out}.mp4"}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: Some(8.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "analyze_video",
+        args: r#"{"input_file": "{video}"}"#,
+        output: "",
+        expect: Expect::Reply("Duration"),
+    },
+    ToolCase {
+        tool: "split_video",
+        args: r#"{"input_file": "{video}", "output_prefix": "{dir}/split", "segment_duration": 2}"#,
+        output: "{dir}/split_",
+        expect: Expect::Files { min: 2 },
+    },
+    ToolCase {
+        tool: "add_text_overlay",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "text": "harness", "x": 10, "y": 10, "font_size": 24}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "apply_filter",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "filter_type": "grayscale"}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "add_overlay",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "overlay_file": "{image}", "x": 10, "y": 10}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: None, audio: None },
+    },
+    ToolCase {
+        tool: "adjust_color",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "brightness": 0.1, "contrast": 0.1, "saturation": 0.2}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "add_subtitles",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "subtitle_text": "{srt}"}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "burn_timecode",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "start_timecode": "01:00:00:00", "label": "v1"}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "generate_slate",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "project": "Harness", "version": "v1", "duration": 1}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: Some(5.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "preview_safe_zones",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.png", "platform": "all", "timestamp": 1}"#,
+        output: "{out}.png",
+        expect: Expect::Image,
+    },
+    ToolCase {
+        tool: "resize_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "width": 160, "height": 120}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(160), height: Some(120), seconds: Some(4.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "crop_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "x": 0, "y": 0, "width": 160, "height": 120}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(160), height: Some(120), seconds: Some(4.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "rotate_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "degrees": 90}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(240), height: Some(320), seconds: Some(4.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "adjust_speed",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "speed_factor": 2.0}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: Some(2.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "flip_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "direction": "horizontal"}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "scale_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "scale_factor": 0.5}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(160), height: Some(120), seconds: Some(4.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "extract_audio",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp3", "format": "mp3"}"#,
+        output: "{out}.mp3",
+        expect: Expect::Audio { seconds: Some(4.0) },
+    },
+    ToolCase {
+        tool: "select_audio_track",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "audio_track": "1"}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "add_audio",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "audio_file": "{audio}", "replace": true}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "adjust_volume",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "volume_factor": 0.5}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "fade_audio",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "fade_in_duration": 1, "fade_out_duration": 1}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "remove_silence",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4"}"#,
+        output: "{out}.mp4",
+        expect: Expect::Reply("No silent stretches"),
+    },
+    ToolCase {
+        tool: "convert_format",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mov", "format": "mov"}"#,
+        output: "{out}.mov",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: Some(4.0), audio: None },
+    },
+    ToolCase {
+        tool: "compress_video",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "quality": "low"}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: None, height: None, seconds: Some(4.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "export_for_platform",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "platform": "youtube"}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: None, height: None, seconds: Some(4.0), audio: Some(true) },
+    },
+    ToolCase {
+        tool: "multi_export",
+        args: r#"{"input_file": "{video}", "renditions": ["240x180", "160x120"], "output_dir": "{out}_renditions"}"#,
+        output: "{out}_renditions/",
+        expect: Expect::Files { min: 2 },
+    },
+    ToolCase {
+        tool: "create_thumbnail",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.jpg", "timestamp": "1.5"}"#,
+        output: "{out}.jpg",
+        expect: Expect::Image,
+    },
+    ToolCase {
+        tool: "extract_frames",
+        args: r#"{"input_file": "{video}", "output_dir": "{dir}/frames", "frame_rate": 1, "format": "png"}"#,
+        output: "{dir}/frames/",
+        expect: Expect::Files { min: 3 },
+    },
+    ToolCase {
+        tool: "contact_sheet",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.png", "max_frames": 6, "columns": 3}"#,
+        output: "{out}.png",
+        expect: Expect::Image,
+    },
+    ToolCase {
+        tool: "detect_scenes",
+        args: r#"{"input_file": "{video}", "output_dir": "{out}_scenes"}"#,
+        output: "{out}_scenes/scene_",
+        expect: Expect::Files { min: 1 },
+    },
+    ToolCase {
+        tool: "picture_in_picture",
+        args: r#"{"main_video": "{video}", "pip_video": "{video2}", "output_file": "{out}.mp4", "x": 10, "y": 10, "scale": 0.3}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: None, audio: None },
+    },
+    ToolCase {
+        tool: "chroma_key",
+        args: r#"{"input_file": "{green}", "background_file": "{video}", "output_file": "{out}.mp4", "key_color": "green", "similarity": 0.3}"#,
+        output: "{out}.mp4",
+        expect: ANY_VIDEO,
+    },
+    ToolCase {
+        tool: "split_screen",
+        args: r#"{"video1": "{video}", "video2": "{video2}", "output_file": "{out}.mp4", "orientation": "horizontal"}"#,
+        output: "{out}.mp4",
+        expect: ANY_VIDEO,
+    },
+    ToolCase {
+        tool: "stabilize_video",
+        args: r#"{"input_file": "{video2}", "output_file": "{out}.mp4", "strength": 5}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: None, height: None, seconds: Some(4.0), audio: None },
+    },
+    ToolCase {
+        tool: "auto_correct",
+        args: r#"{"input_file": "{video}", "output_file": "{out}.mp4", "strength": 0.8}"#,
+        output: "{out}.mp4",
+        expect: SAME_VIDEO,
+    },
+    ToolCase {
+        tool: "create_blank_video",
+        args: r#"{"output_file": "{out}.mp4", "duration": 2, "width": 320, "height": 240, "color": "blue"}"#,
+        output: "{out}.mp4",
+        expect: Expect::Video { width: Some(320), height: Some(240), seconds: Some(2.0), audio: None },
+    },
+    ToolCase {
+        tool: "submit_final_answer",
+        args: r#"{"summary": "Harness run finished", "output_files": []}"#,
+        output: "",
+        expect: Expect::Reply("Harness run finished"),
+    },
+];
+
+#[test]
+fn every_tool_is_covered() {
+    let offered: HashSet<String> = crate::claude_client::ClaudeClient::create_video_editing_tools()
+        .into_iter()
+        .map(|tool| tool.name)
+        .collect();
+    let gemini: HashSet<String> = crate::gemini_client::GeminiClient::create_video_editing_tools()
+        .into_iter()
+        .map(|tool| tool.name)
+        .collect();
+
+    let mut classified: HashMap<&str, &str> = HashMap::new();
+    let tables = [
+        ("CASES", CASES.iter().map(|case| case.tool).collect::<Vec<_>>()),
+        ("NETWORK_TOOLS", NETWORK_TOOLS.to_vec()),
+        ("STATEFUL_TOOLS", STATEFUL_TOOLS.to_vec()),
+    ];
+    for (table, tools) in &tables {
+        for tool in tools {
+            if let Some(other) = classified.insert(tool, table) {
+                panic!("{} is listed in both {} and {}", tool, other, table);
+            }
+            assert!(offered.contains(*tool), "{} in {} isn't a tool any more", tool, table);
+        }
+    }
+
+    let mut missing: Vec<&String> = offered.iter().filter(|tool| !classified.contains_key(tool.as_str())).collect();
+    missing.sort();
+    assert!(missing.is_empty(), "tools with no harness case or exclusion: {:?}", missing);
+
+    let mut mismatched: Vec<&String> = offered.symmetric_difference(&gemini).collect();
+    mismatched.sort();
+    assert!(mismatched.is_empty(), "tools offered to only one of Claude and Gemini: {:?}", mismatched);
+}
+
+/// Renders the fixtures, runs every case through both dispatch paths and reports every failure at once
+#[tokio::test]
+async fn tools_against_synthetic_media() {
+    if !ffmpeg_available() {
+        eprintln!("skipping tools_against_synthetic_media: ffmpeg/ffprobe not found");
+        return;
+    }
+    let media = SyntheticMedia::new("tool_harness").expect("fixture folder");
+    let spec = VideoSpec::default();
+    let fixtures: HashMap<&str, String> = HashMap::from([
+        ("{video}", media.video("bars.mp4", Pattern::ColorBars, spec).expect("color bars")),
+        ("{video2}", media.video("pattern.mp4", Pattern::Gradient, spec).expect("test pattern")),
+        ("{green}", media.video("green.mp4", Pattern::GreenScreen, VideoSpec { audio: false, ..spec }).expect("green screen")),
+        ("{audio}", media.tone_sweep("sweep.wav", spec.seconds, 200.0, 2000.0).expect("tone sweep")),
+        ("{image}", media.still("still.png", 160, 120).expect("still")),
+        ("{srt}", media.subtitles("cues.srt", 4).expect("subtitles")),
+    ]);
+    for provider in ["claude", "gemini"] {
+        std::fs::create_dir_all(media.dir().join(provider).join("frames")).expect("scratch folder");
+    }
+    std::fs::create_dir_all("outputs").expect("outputs folder");
+
+    let mut failures = Vec::new();
+    let mut outputs = Vec::new();
+    for case in CASES {
+        for provider in ["claude", "gemini"] {
+            let out = format!("outputs/harness_{}_{}_{}", std::process::id(), provider, case.tool);
+            let scratch = media.dir().join(provider).to_string_lossy().to_string();
+            let fill = |template: &str| {
+                let mut text = template.replace("{out}", &out).replace("{dir}", &scratch);
+                for (placeholder, path) in &fixtures {
+                    text = text.replace(placeholder, path);
+                }
+                text
+            };
+            let args: Value = serde_json::from_str(&fill(case.args))
+                .unwrap_or_else(|e| panic!("{}: arguments aren't valid JSON: {}", case.tool, e));
+            let reply = match provider {
+                "claude" => execute_tool_claude(case.tool, &args).await,
+                _ => {
+                    let args: HashMap<String, Value> = args.as_object().cloned().unwrap_or_default().into_iter().collect();
+                    execute_tool_gemini(case.tool, &args).await
+                }
+            };
+
+            let output = fill(case.output);
+            if let Err(e) = check(case.expect, &reply, &output) {
+                failures.push(format!("{} ({}): {}\n    reply: {}", case.tool, provider, e, reply.lines().next().unwrap_or("")));
+            }
+            outputs.push(output);
+        }
+    }
+
+    for output in outputs.iter().filter(|o| o.starts_with("outputs/")) {
+        if output.ends_with('/') {
+            let _ = std::fs::remove_dir_all(output);
+        } else {
+            let _ = std::fs::remove_file(output);
+        }
+    }
+    assert!(failures.is_empty(), "{} tool run(s) failed:\n{}", failures.len(), failures.join("\n"));
+}
+
+fn check(expect: Expect, reply: &str, output: &str) -> Result<(), String> {
+    if reply.contains("❌") {
+        return Err("the tool reported an error".to_string());
+    }
+    match expect {
+        Expect::Reply(text) => {
+            if !reply.contains(text) {
+                return Err(format!("reply doesn't mention {:?}", text));
+            }
+        }
+        Expect::Files { min } => {
+            let count = matching_files(output);
+            if count < min {
+                return Err(format!("expected at least {} files at {}*, found {}", min, output, count));
+            }
+        }
+        Expect::Image => {
+            let info = crate::core::probe_media(output)?;
+            if !info.has_video() || info.width() == 0 {
+                return Err(format!("{} isn't a readable image", output));
+            }
+        }
+        Expect::Audio { seconds } => {
+            let info = crate::core::probe_media(output)?;
+            if info.has_video() || !info.has_audio() {
+                return Err(format!("{} should be audio only", output));
+            }
+            check_duration(info.duration_seconds, seconds)?;
+        }
+        Expect::Video { width, height, seconds, audio } => {
+            let info = crate::core::probe_media(output)?;
+            if !info.has_video() {
+                return Err(format!("{} has no video stream", output));
+            }
+            if width.is_some_and(|w| w != info.width()) || height.is_some_and(|h| h != info.height()) {
+                return Err(format!(
+                    "expected {}x{}, got {}x{}",
+                    width.map(|w| w.to_string()).unwrap_or("*".into()),
+                    height.map(|h| h.to_string()).unwrap_or("*".into()),
+                    info.width(),
+                    info.height()
+                ));
+            }
+            if audio.is_some_and(|audio| audio != info.has_audio()) {
+                return Err(format!("expected audio: {}, found audio: {}", audio.unwrap_or(false), info.has_audio()));
+            }
+            check_duration(info.duration_seconds, seconds)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_duration(actual: f64, expected: Option<f64>) -> Result<(), String> {
+    match expected {
+        Some(expected) if (actual - expected).abs() > DURATION_TOLERANCE => {
+            Err(format!("expected {:.2}s, got {:.2}s", expected, actual))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Files in the prefix's folder whose path starts with the prefix ("dir/" matches everything in dir)
+fn matching_files(prefix: &str) -> usize {
+    let path = std::path::Path::new(prefix);
+    let (dir, stem) = if prefix.ends_with('/') {
+        (path, String::new())
+    } else {
+        (
+            path.parent().unwrap_or(std::path::Path::new(".")),
+            path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        )
+    };
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(&stem))
+                .count()
+        })
+        .unwrap_or(0)
+}
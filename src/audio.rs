@@ -1,6 +1,7 @@
 // src/audio.rs
 
 
+use crate::types::{AudioInfo, FillerRemovalPlan, SilenceRemovalPlan, SpeakerTurn, TranscriptWord};
 use crate::utils::execute_ffmpeg_command;
 use std::process::Command;
 
@@ -125,4 +126,866 @@ pub fn apply_audio_effect(
         .arg(output_file);
 
     execute_ffmpeg_command(command)
-}
\ No newline at end of file
+}
+
+/// Filler vocabulary and pause thresholds for each aggressiveness level:
+/// (fillers, pause length that triggers shortening, pause length kept)
+fn filler_settings(aggressiveness: &str) -> (Vec<&'static str>, f64, f64) {
+    match aggressiveness {
+        "low" => (vec!["um", "uh", "umm", "uhh"], 1.5, 0.5),
+        "high" => (
+            vec!["um", "uh", "umm", "uhh", "er", "erm", "ah", "hmm", "mm", "like", "basically", "actually", "literally"],
+            0.6,
+            0.25,
+        ),
+        _ => (vec!["um", "uh", "umm", "uhh", "er", "erm", "ah", "hmm", "mm"], 1.0, 0.35),
+    }
+}
+
+/// Work out which ranges of the media to keep so fillers are cut and long pauses shortened.
+/// Word timings come from a word-level transcript; everything outside words is treated as pause.
+pub fn plan_filler_removal(
+    words: &[TranscriptWord],
+    duration: f64,
+    aggressiveness: &str,
+) -> FillerRemovalPlan {
+    let (fillers, pause_threshold, pause_keep) = filler_settings(aggressiveness);
+
+    let mut removed: Vec<(f64, f64)> = Vec::new();
+    let mut fillers_removed = Vec::new();
+    let mut pauses_shortened = 0;
+
+    // Pauses: the gap before the first word, between words, and after the last word
+    let mut previous_end = 0.0;
+    let boundaries = words
+        .iter()
+        .map(|w| (w.start, w.end))
+        .chain(std::iter::once((duration, duration)));
+    for (start, end) in boundaries {
+        let gap = start - previous_end;
+        if gap > pause_threshold {
+            let half_keep = pause_keep / 2.0;
+            removed.push((previous_end + half_keep, start - half_keep));
+            pauses_shortened += 1;
+        }
+        previous_end = f64::max(previous_end, end);
+    }
+
+    // Fillers: drop the word itself
+    for word in words {
+        let normalized: String = word
+            .text
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        if fillers.contains(&normalized.as_str()) {
+            removed.push((word.start, word.end));
+            fillers_removed.push(word.text.clone());
+        }
+    }
+
+    // Merge overlapping removals, then invert into keep ranges
+    removed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in removed {
+        if end <= start {
+            continue;
+        }
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let mut keep_segments = Vec::new();
+    let mut cursor = 0.0;
+    for (start, end) in &merged {
+        if *start > cursor {
+            keep_segments.push((cursor, *start));
+        }
+        cursor = cursor.max(*end);
+    }
+    if cursor < duration {
+        keep_segments.push((cursor, duration));
+    }
+
+    let kept: f64 = keep_segments.iter().map(|(s, e)| e - s).sum();
+    FillerRemovalPlan {
+        keep_segments,
+        fillers_removed,
+        pauses_shortened,
+        original_duration: duration,
+        seconds_saved: (duration - kept).max(0.0),
+    }
+}
+
+/// Silence inside which the same speaker's words still count as one turn
+const TURN_GAP_SECONDS: f64 = 1.5;
+
+/// Group a diarized transcript into speaker turns
+pub fn speaker_turns(words: &[TranscriptWord]) -> Vec<SpeakerTurn> {
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+    for word in words {
+        let speaker = word.speaker_id.clone().unwrap_or_else(|| "unknown".to_string());
+        match turns.last_mut() {
+            Some(turn) if turn.speaker_id == speaker && word.start - turn.end <= TURN_GAP_SECONDS => {
+                turn.end = word.end;
+                turn.text.push(' ');
+                turn.text.push_str(&word.text);
+            }
+            _ => turns.push(SpeakerTurn {
+                speaker_id: speaker,
+                start: word.start,
+                end: word.end,
+                text: word.text.clone(),
+            }),
+        }
+    }
+    turns
+}
+
+/// Short turns that cut in on another speaker: they start while (or right after) someone else
+/// was talking and that speaker carries on afterwards
+pub fn is_interruption(turns: &[SpeakerTurn], index: usize, max_turn_seconds: f64) -> bool {
+    let turn = &turns[index];
+    let (Some(previous), Some(next)) = (index.checked_sub(1).and_then(|i| turns.get(i)), turns.get(index + 1)) else {
+        return false;
+    };
+    turn.end - turn.start <= max_turn_seconds
+        && previous.speaker_id != turn.speaker_id
+        && next.speaker_id == previous.speaker_id
+        && turn.start - previous.end <= 0.5
+}
+
+/// Padded, merged time ranges covered by the selected turns
+pub fn turn_ranges(turns: &[&SpeakerTurn], padding: f64, duration: f64) -> Vec<(f64, f64)> {
+    let mut ranges: Vec<(f64, f64)> = turns
+        .iter()
+        .map(|t| ((t.start - padding).max(0.0), (t.end + padding).min(duration)))
+        .collect();
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// The complement of a set of sorted, merged ranges within 0..duration
+pub fn invert_ranges(ranges: &[(f64, f64)], duration: f64) -> Vec<(f64, f64)> {
+    let mut keep = Vec::new();
+    let mut cursor = 0.0;
+    for (start, end) in ranges {
+        if *start > cursor {
+            keep.push((cursor, *start));
+        }
+        cursor = f64::max(cursor, *end);
+    }
+    if cursor < duration {
+        keep.push((cursor, duration));
+    }
+    keep
+}
+
+/// Stretches of a video's audio quieter than `threshold_db` for at least `min_silence` seconds.
+/// A silence still running at the end of the file closes at `duration`.
+pub fn detect_silences(input_file: &str, threshold_db: f64, min_silence: f64, duration: f64) -> Result<Vec<(f64, f64)>, String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vn")
+        .arg("-af")
+        .arg(format!("silencedetect=noise={}dB:d={},ametadata=print:file=-", threshold_db, min_silence))
+        .arg("-f")
+        .arg("null")
+        .arg("-");
+
+    Ok(silences_from_metadata(&execute_ffmpeg_command(command)?, duration))
+}
+
+/// Pair up the `lavfi.silence_start` / `lavfi.silence_end` values silencedetect prints
+fn silences_from_metadata(printed: &str, duration: f64) -> Vec<(f64, f64)> {
+    let mut silences = Vec::new();
+    let mut open: Option<f64> = None;
+    for line in printed.lines().map(str::trim) {
+        if let Some(start) = line.strip_prefix("lavfi.silence_start=").and_then(|v| v.parse::<f64>().ok()) {
+            open = Some(start.max(0.0));
+        } else if let Some(end) = line.strip_prefix("lavfi.silence_end=").and_then(|v| v.parse::<f64>().ok()) {
+            if let Some(start) = open.take() {
+                silences.push((start, end.min(duration)));
+            }
+        }
+    }
+    if let Some(start) = open {
+        silences.push((start, duration));
+    }
+    silences.retain(|(start, end)| end > start);
+    silences
+}
+
+/// Which ranges to keep so the silences are cut out. `padding` seconds of each silence are kept
+/// next to the sound around it, so words aren't clipped and the cuts don't feel breathless.
+pub fn plan_silence_removal(silences: &[(f64, f64)], duration: f64, padding: f64) -> SilenceRemovalPlan {
+    let removed: Vec<(f64, f64)> = silences
+        .iter()
+        .map(|&(start, end)| {
+            // No padding where the silence runs to either end of the video
+            let start = if start <= 0.0 { 0.0 } else { start + padding };
+            let end = if end >= duration { duration } else { end - padding };
+            (start, end)
+        })
+        .filter(|(start, end)| end > start)
+        .collect();
+
+    let keep_segments = invert_ranges(&removed, duration);
+    let kept: f64 = keep_segments.iter().map(|(s, e)| e - s).sum();
+    SilenceRemovalPlan {
+        keep_segments,
+        silences_cut: removed.len(),
+        original_duration: duration,
+        seconds_saved: (duration - kept).max(0.0),
+    }
+}
+
+/// Silence the audio during the given ranges, leaving the picture untouched
+pub fn mute_ranges(input_file: &str, output_file: &str, ranges: &[(f64, f64)]) -> Result<String, String> {
+    let enable = ranges
+        .iter()
+        .map(|(start, end)| format!("between(t,{:.3},{:.3})", start, end))
+        .collect::<Vec<_>>()
+        .join("+");
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-af")
+        .arg(format!("volume=enable='{}':volume=0", enable))
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Default vocabulary for censor_profanity; matched against the whole word or its stem
+const DEFAULT_PROFANITY: &[&str] = &[
+    "fuck", "shit", "bitch", "bastard", "asshole", "ass", "dick", "cunt", "piss", "damn",
+    "crap", "bollocks", "wanker", "motherfucker", "bullshit", "goddamn", "prick", "twat", "slut", "whore",
+];
+
+/// Inflections of a flagged word that are censored too ("fucking", "shitty", "damned")
+const PROFANITY_SUFFIXES: &[&str] = &["s", "es", "ed", "er", "ers", "ing", "in", "y", "ty", "head", "heads"];
+
+/// Words from the transcript that match the flagged vocabulary, as (start, end, word).
+/// A word matches when it equals a flagged entry or is that entry plus a common inflection,
+/// so "fucking" is caught without flagging "assume" or "dickens".
+pub fn find_flagged_words(words: &[TranscriptWord], word_list: &[String]) -> Vec<(f64, f64, String)> {
+    let flagged: Vec<String> = if word_list.is_empty() {
+        DEFAULT_PROFANITY.iter().map(|w| w.to_string()).collect()
+    } else {
+        word_list.iter().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()).collect()
+    };
+
+    words
+        .iter()
+        .filter(|word| {
+            let normalized: String = word
+                .text
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase();
+            !normalized.is_empty()
+                && flagged
+                    .iter()
+                    .any(|f| match normalized.strip_prefix(f.as_str()) {
+                        Some(rest) => rest.is_empty() || PROFANITY_SUFFIXES.contains(&rest),
+                        None => false,
+                    })
+        })
+        .map(|word| (word.start, word.end, word.text.clone()))
+        .collect()
+}
+
+/// Replace the audio during the given ranges with a sine-tone bleep, leaving the picture untouched
+pub fn bleep_ranges(
+    input_file: &str,
+    output_file: &str,
+    ranges: &[(f64, f64)],
+    frequency: f64,
+    duration: f64,
+) -> Result<String, String> {
+    let enable = ranges
+        .iter()
+        .map(|(start, end)| format!("between(t,{:.3},{:.3})", start, end))
+        .collect::<Vec<_>>()
+        .join("+");
+    let filter = format!(
+        "[0:a]volume=enable='{enable}':volume=0[muted];\
+         sine=frequency={frequency}:sample_rate=48000:duration={duration:.3},volume='if({enable},0.3,0)':eval=frame[tone];\
+         [muted][tone]amix=inputs=2:duration=first:normalize=0[aout]",
+        enable = enable,
+        frequency = frequency,
+        duration = duration,
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("0:v?")
+        .arg("-map")
+        .arg("[aout]")
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Replace a video's audio with a narration track, padding it with silence to the video length
+pub fn replace_audio_padded(
+    video_file: &str,
+    audio_file: &str,
+    output_file: &str,
+) -> Result<String, String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(video_file)
+        .arg("-i")
+        .arg(audio_file)
+        .arg("-filter_complex")
+        .arg("[1:a]aresample=44100,apad[a]")
+        .arg("-map")
+        .arg("0:v")
+        .arg("-map")
+        .arg("[a]")
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-ac")
+        .arg("2")
+        .arg("-shortest")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Mix a looping background music bed under a video's existing audio
+pub fn mix_background_music(
+    video_file: &str,
+    music_file: &str,
+    output_file: &str,
+    music_volume: f64,
+) -> Result<String, String> {
+    let filter = format!(
+        "[1:a]volume={:.2}[music];[0:a][music]amix=inputs=2:duration=first:dropout_transition=0:normalize=0[a]",
+        music_volume
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(video_file)
+        .arg("-stream_loop")
+        .arg("-1")
+        .arg("-i")
+        .arg(music_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("0:v")
+        .arg("-map")
+        .arg("[a]")
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Mix a looping music bed under a video, ducking it with a sidechain compressor whenever
+/// someone speaks and fading it out over the last two seconds
+pub fn mix_background_music_ducked(
+    video_file: &str,
+    music_file: &str,
+    output_file: &str,
+    music_volume: f64,
+    duration: f64,
+) -> Result<String, String> {
+    let fade_start = (duration - 2.0).max(0.0);
+    let filter = format!(
+        "[0:a]asplit=2[voice][key];\
+         [1:a]volume={:.2},afade=t=out:st={:.3}:d=2[music];\
+         [music][key]sidechaincompress=threshold=0.03:ratio=8:attack=20:release=400[ducked];\
+         [voice][ducked]amix=inputs=2:duration=first:dropout_transition=0:normalize=0[a]",
+        music_volume, fade_start
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(video_file)
+        .arg("-stream_loop")
+        .arg("-1")
+        .arg("-i")
+        .arg(music_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("0:v")
+        .arg("-map")
+        .arg("[a]")
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Lay dubbed lines over a video at their cue times, keeping the original audio ducked underneath
+pub fn mix_dub_track(
+    video_file: &str,
+    lines: &[(f64, String)],
+    output_file: &str,
+    original_volume: f64,
+) -> Result<String, String> {
+    if lines.is_empty() {
+        return Err("No dubbed lines to mix".to_string());
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i").arg(video_file);
+    for (_, audio_file) in lines {
+        command.arg("-i").arg(audio_file);
+    }
+
+    let mut filter = format!("[0:a]volume={:.2}[orig];", original_volume);
+    let mut mix_inputs = String::from("[orig]");
+    for (i, (start, _)) in lines.iter().enumerate() {
+        let delay_ms = (start.max(0.0) * 1000.0).round() as u64;
+        filter.push_str(&format!(
+            "[{}:a]aresample=44100,adelay={}|{}[d{}];",
+            i + 1,
+            delay_ms,
+            delay_ms,
+            i
+        ));
+        mix_inputs.push_str(&format!("[d{}]", i));
+    }
+    filter.push_str(&format!(
+        "{}amix=inputs={}:duration=first:dropout_transition=0:normalize=0[a]",
+        mix_inputs,
+        lines.len() + 1
+    ));
+
+    command
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("0:v")
+        .arg("-map")
+        .arg("[a]")
+        .arg("-c:v")
+        .arg("copy")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Tools that take `audio_track` and `downmix` arguments. On inputs with several audio tracks (game +
+/// mic) they get a copy of the input holding only the chosen track, so FFmpeg can't pick the wrong
+/// one; on surround inputs the copy's audio is already folded down to stereo.
+pub const AUDIO_TRACK_TOOLS: &[&str] = &[
+    "extract_audio", "add_audio", "adjust_volume", "fade_audio", "convert_format", "compress_video",
+    "export_for_platform", "multi_export",
+];
+
+/// Which of an input's audio tracks to use
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioTrackChoice {
+    /// Position among the audio tracks, from 0
+    Track(usize),
+    /// All tracks mixed together
+    Mix,
+}
+
+/// Pick a track by `selection`: a track number from 1, a language ("eng"), part of a track title
+/// ("mic") or "mix" for all of them. Without one, the track flagged default is used, else the first.
+pub fn choose_audio_track(tracks: &[AudioInfo], selection: Option<&str>) -> Result<AudioTrackChoice, String> {
+    if tracks.is_empty() {
+        return Err("The input has no audio tracks".to_string());
+    }
+    let Some(selection) = selection.map(str::trim).filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("auto")) else {
+        return Ok(AudioTrackChoice::Track(tracks.iter().position(|t| t.default).unwrap_or(0)));
+    };
+    if selection.eq_ignore_ascii_case("mix") || selection.eq_ignore_ascii_case("all") {
+        return Ok(AudioTrackChoice::Mix);
+    }
+    if let Ok(number) = selection.parse::<usize>() {
+        if number == 0 || number > tracks.len() {
+            return Err(format!(
+                "Audio track {} doesn't exist. The input has:\n{}",
+                number,
+                describe_audio_tracks(tracks)
+            ));
+        }
+        return Ok(AudioTrackChoice::Track(number - 1));
+    }
+    let wanted = selection.to_lowercase();
+    tracks
+        .iter()
+        .position(|t| t.language.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(selection)))
+        .or_else(|| tracks.iter().position(|t| t.title.as_deref().is_some_and(|title| title.to_lowercase().contains(&wanted))))
+        .map(AudioTrackChoice::Track)
+        .ok_or_else(|| format!("No audio track matches \"{}\". The input has:\n{}", selection, describe_audio_tracks(tracks)))
+}
+
+/// How surround (5.1, 7.1) audio becomes stereo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Downmix {
+    /// ITU-R BS.775 fold-down: center and surrounds at -3 dB, LFE dropped
+    Stereo,
+    /// Stereo with the center channel (where dialog sits) at full level and the rest lowered
+    Dialog,
+    /// Leave the channel layout alone
+    Keep,
+}
+
+impl Downmix {
+    pub fn parse(value: Option<&str>) -> Result<Downmix, String> {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("auto") | Some("stereo") => Ok(Downmix::Stereo),
+            Some("dialog") | Some("dialogue") => Ok(Downmix::Dialog),
+            Some("keep") | Some("none") => Ok(Downmix::Keep),
+            Some(other) => Err(format!("Unknown downmix '{}'. Use stereo, dialog or keep", other)),
+        }
+    }
+}
+
+/// Where each channel of a track sits, in FFmpeg's channel order: L/R front, C center, F LFE,
+/// l/r surround (side or back), b back center
+fn channel_positions(track: &AudioInfo) -> Option<&'static str> {
+    let layout = track.channel_layout.as_deref().map(|l| l.trim().to_lowercase());
+    let positions = match layout.as_deref() {
+        Some("2.1") => "LRF",
+        Some("3.0") => "LRC",
+        Some("3.1") => "LRCF",
+        Some("4.0") => "LRCb",
+        Some("quad") | Some("quad(side)") => "LRlr",
+        Some("4.1") => "LRCFb",
+        Some("5.0") | Some("5.0(side)") => "LRClr",
+        Some("5.1") | Some("5.1(side)") => "LRCFlr",
+        Some("6.0") => "LRCblr",
+        Some("6.1") => "LRCFblr",
+        Some("7.1") => "LRCFlrlr",
+        // Unlabelled: FFmpeg's default layout for the channel count
+        _ => match track.channels {
+            3 => "LRC",
+            4 => "LRCb",
+            5 => "LRClr",
+            6 => "LRCFlr",
+            7 => "LRCFblr",
+            8 => "LRCFlrlr",
+            _ => return None,
+        },
+    };
+    (positions.len() == track.channels as usize).then_some(positions)
+}
+
+/// More than two channels
+pub fn is_surround(track: &AudioInfo) -> bool {
+    track.channels > 2
+}
+
+/// A `pan` filter folding a surround track down to stereo; None for mono and stereo tracks, and
+/// for layouts we can't place (FFmpeg's own downmix applies to those). Gains are normalized so the
+/// fold-down can't clip.
+pub fn stereo_downmix_filter(track: &AudioInfo, downmix: Downmix) -> Option<String> {
+    if downmix == Downmix::Keep || !is_surround(track) {
+        return None;
+    }
+    let positions = channel_positions(track)?;
+    let (front, center, surround, back) = match downmix {
+        Downmix::Dialog => (0.707, 1.0, 0.5, 0.354),
+        _ => (1.0, 0.707, 0.707, 0.5),
+    };
+    let side = |own_front: char, own_surround: char| {
+        positions
+            .chars()
+            .enumerate()
+            .filter_map(|(i, position)| {
+                let gain = match position {
+                    p if p == own_front => front,
+                    p if p == own_surround => surround,
+                    'C' => center,
+                    'b' => back,
+                    _ => return None,
+                };
+                Some(format!("{}*c{}", gain, i))
+            })
+            .collect::<Vec<_>>()
+            .join("+")
+    };
+    Some(format!("pan=stereo|FL<{}|FR<{}", side('L', 'l'), side('R', 'r')))
+}
+
+/// The downmix filter for the first audio track of a file, if it is surround
+pub fn downmix_filter_for(input_file: &str, downmix: Downmix) -> Option<String> {
+    let info = crate::core::probe_media(input_file).ok()?;
+    stereo_downmix_filter(info.audio_streams.first()?, downmix)
+}
+
+/// One line per track, numbered the way `audio_track` counts: `2. aac stereo, eng, "Mic" (default)`
+pub fn describe_audio_tracks(tracks: &[AudioInfo]) -> String {
+    tracks
+        .iter()
+        .enumerate()
+        .map(|(i, track)| {
+            let layout = track.channel_layout.clone().unwrap_or_else(|| format!("{} ch", track.channels));
+            let mut line = format!("{}. {} {}", i + 1, track.codec_name, layout);
+            if let Some(language) = &track.language {
+                line.push_str(&format!(", {}", language));
+            }
+            if let Some(title) = &track.title {
+                line.push_str(&format!(", \"{}\"", title));
+            }
+            if track.default {
+                line.push_str(" (default)");
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// FFmpeg arguments copying the video with only the chosen audio track (or the mix of all tracks),
+/// surround tracks folded down to stereo unless `downmix` keeps them
+fn select_audio_track_args(input: &str, output: &str, choice: AudioTrackChoice, tracks: &[AudioInfo], downmix: Downmix, audio_codec: &str) -> Vec<String> {
+    let mut args: Vec<String> = ["-y", "-i", input, "-map", "0:v?"].iter().map(|s| s.to_string()).collect();
+    match choice {
+        AudioTrackChoice::Track(track) => match tracks.get(track).and_then(|t| stereo_downmix_filter(t, downmix)) {
+            Some(pan) => {
+                let filter = format!("[0:a:{}]{}[a]", track, pan);
+                args.extend(["-filter_complex".to_string(), filter, "-map".to_string(), "[a]".to_string()]);
+            }
+            None => args.extend(["-map".to_string(), format!("0:a:{}", track)]),
+        },
+        AudioTrackChoice::Mix => {
+            let mut filters = Vec::new();
+            let mut inputs = String::new();
+            for (i, track) in tracks.iter().enumerate() {
+                match stereo_downmix_filter(track, downmix) {
+                    Some(pan) => {
+                        filters.push(format!("[0:a:{i}]{}[s{i}]", pan, i = i));
+                        inputs.push_str(&format!("[s{}]", i));
+                    }
+                    None => inputs.push_str(&format!("[0:a:{}]", i)),
+                }
+            }
+            filters.push(format!("{}amix=inputs={}:duration=longest:dropout_transition=0:normalize=0[a]", inputs, tracks.len()));
+            args.extend(["-filter_complex".to_string(), filters.join(";"), "-map".to_string(), "[a]".to_string()]);
+        }
+    }
+    args.extend(["-c:v", "copy", "-c:a", audio_codec, output].iter().map(|s| s.to_string()));
+    args
+}
+
+fn run_select_audio_track(input: &str, output: &str, choice: AudioTrackChoice, tracks: &[AudioInfo], downmix: Downmix, audio_codec: &str) -> Result<String, String> {
+    let mut command = Command::new("ffmpeg");
+    command.args(select_audio_track_args(input, output, choice, tracks, downmix, audio_codec));
+    execute_ffmpeg_command(command)
+}
+
+/// Write `output` with the input's video and only the chosen audio track
+pub fn select_audio_track(input_file: &str, output_file: &str, selection: Option<&str>) -> Result<String, String> {
+    let info = crate::core::probe_media(input_file)?;
+    let tracks = &info.audio_streams;
+    let choice = choose_audio_track(tracks, selection)?;
+    run_select_audio_track(input_file, output_file, choice, tracks, Downmix::Keep, "aac")?;
+    let kept = match choice {
+        AudioTrackChoice::Track(track) => format!("audio track {} of {}", track + 1, tracks.len()),
+        AudioTrackChoice::Mix => format!("a mix of all {} audio tracks", tracks.len()),
+    };
+    Ok(format!("✅ Kept {} in {}", kept, output_file))
+}
+
+/// `audio_track` as text; models send track numbers as numbers as often as strings
+pub fn audio_track_argument(value: Option<&serde_json::Value>) -> Option<String> {
+    match value? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Copy of a tool's input holding one audio track; removed when dropped
+#[derive(Debug)]
+pub struct IsolatedAudioTrack(String);
+
+impl Drop for IsolatedAudioTrack {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// For the `AUDIO_TRACK_TOOLS`: when the input has several audio tracks or surround audio, the
+/// arguments with `input_file` swapped for a copy holding only the chosen (or default) track, folded
+/// down to stereo as `downmix` says. None when there is nothing to change; Err when `audio_track`
+/// names a track the input doesn't have or `downmix` isn't a known mode.
+pub fn isolate_audio_track(name: &str, args: &serde_json::Value) -> Result<Option<(serde_json::Value, IsolatedAudioTrack)>, String> {
+    if !AUDIO_TRACK_TOOLS.contains(&name) {
+        return Ok(None);
+    }
+    let Some(input) = args.get("input_file").and_then(|v| v.as_str()).filter(|p| !p.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let selection = audio_track_argument(args.get("audio_track"));
+    let downmix = Downmix::parse(args.get("downmix").and_then(|v| v.as_str()))?;
+    let info = match crate::core::probe_media(input) {
+        Ok(info) => info,
+        // Let the tool report a missing or unreadable input in its own words
+        Err(_) if selection.is_none() => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let tracks = &info.audio_streams;
+    if tracks.is_empty() && selection.is_none() {
+        return Ok(None);
+    }
+    let choice = choose_audio_track(tracks, selection.as_deref())?;
+    let folds_down = match choice {
+        AudioTrackChoice::Track(track) => stereo_downmix_filter(&tracks[track], downmix).is_some(),
+        AudioTrackChoice::Mix => tracks.iter().any(|t| stereo_downmix_filter(t, downmix).is_some()),
+    };
+    if tracks.len() < 2 && !folds_down {
+        return Ok(None);
+    }
+
+    // Lossless audio in Matroska, so the tool's own encode is the only one
+    let copy = IsolatedAudioTrack(crate::utils::create_temp_file(&format!("audio_track_{}", uuid::Uuid::new_v4().simple()), "mkv"));
+    run_select_audio_track(input, &copy.0, choice, tracks, downmix, "flac")?;
+    tracing::info!("🔊 {} uses {:?} of the {} audio tracks in {} ({:?} downmix)", name, choice, tracks.len(), input, downmix);
+
+    let mut isolated = args.clone();
+    isolated["input_file"] = serde_json::Value::String(copy.0.clone());
+    if let Some(map) = isolated.as_object_mut() {
+        map.remove("audio_track");
+        map.remove("downmix");
+    }
+    Ok(Some((isolated, copy)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silences_become_jump_cuts_with_padding() {
+        let printed = "frame:10 pts:4800 pts_time:0.1\nlavfi.silence_start=0\n\
+                       frame:40 pts:19200 pts_time:1.2\nlavfi.silence_end=1.2\nlavfi.silence_duration=1.2\n\
+                       frame:100 pts:48000 pts_time:5\nlavfi.silence_start=5\n\
+                       frame:150 pts:72000 pts_time:7\nlavfi.silence_end=7\nlavfi.silence_duration=2\n\
+                       frame:250 pts:120000 pts_time:9.5\nlavfi.silence_start=9.5\n";
+        let silences = silences_from_metadata(printed, 10.0);
+        assert_eq!(silences, [(0.0, 1.2), (5.0, 7.0), (9.5, 10.0)]);
+
+        // Each cut keeps 0.2 s next to the sound; leading and trailing silence get no padding at the file's ends
+        let plan = plan_silence_removal(&silences, 10.0, 0.2);
+        assert_eq!(plan.silences_cut, 3);
+        assert_eq!(plan.keep_segments.len(), 2);
+        assert!((plan.keep_segments[0].0 - 1.0).abs() < 1e-9 && (plan.keep_segments[0].1 - 5.2).abs() < 1e-9);
+        assert!((plan.keep_segments[1].0 - 6.8).abs() < 1e-9 && (plan.keep_segments[1].1 - 9.7).abs() < 1e-9);
+        assert!((plan.seconds_saved - 2.9).abs() < 1e-9);
+
+        // A silence shorter than the padding on both sides is left alone
+        assert_eq!(plan_silence_removal(&[(3.0, 3.3)], 10.0, 0.2).keep_segments, [(0.0, 10.0)]);
+    }
+
+    fn track(language: &str, title: &str, default: bool) -> AudioInfo {
+        AudioInfo {
+            index: 1,
+            codec_name: "aac".to_string(),
+            sample_rate: 48000,
+            channels: 2,
+            channel_layout: Some("stereo".to_string()),
+            bit_rate: None,
+            duration_seconds: None,
+            start_seconds: None,
+            language: Some(language.to_string()),
+            title: Some(title.to_string()),
+            default,
+        }
+    }
+
+    #[test]
+    fn audio_tracks_are_chosen_by_number_language_or_title() {
+        let tracks = [track("eng", "Game", false), track("jpn", "Mic", true)];
+        assert_eq!(choose_audio_track(&tracks, None), Ok(AudioTrackChoice::Track(1)));
+        assert_eq!(choose_audio_track(&tracks, Some("1")), Ok(AudioTrackChoice::Track(0)));
+        assert_eq!(choose_audio_track(&tracks, Some("ENG")), Ok(AudioTrackChoice::Track(0)));
+        assert_eq!(choose_audio_track(&tracks, Some("mic")), Ok(AudioTrackChoice::Track(1)));
+        assert_eq!(choose_audio_track(&tracks, Some("mix")), Ok(AudioTrackChoice::Mix));
+        let error = choose_audio_track(&tracks, Some("3")).unwrap_err();
+        assert!(error.contains("2. aac stereo, jpn, \"Mic\" (default)"), "{}", error);
+        assert!(choose_audio_track(&[], None).is_err());
+
+        let args = select_audio_track_args("in.mkv", "out.mp4", AudioTrackChoice::Track(1), &tracks, Downmix::Stereo, "aac");
+        assert_eq!(args, ["-y", "-i", "in.mkv", "-map", "0:v?", "-map", "0:a:1", "-c:v", "copy", "-c:a", "aac", "out.mp4"]);
+        let args = select_audio_track_args("in.mkv", "out.mp4", AudioTrackChoice::Mix, &tracks, Downmix::Stereo, "aac");
+        assert!(args.contains(&"[0:a:0][0:a:1]amix=inputs=2:duration=longest:dropout_transition=0:normalize=0[a]".to_string()));
+    }
+
+    #[test]
+    fn surround_tracks_fold_down_to_stereo() {
+        let mut surround = track("eng", "Main", true);
+        surround.channels = 6;
+        surround.channel_layout = Some("5.1(side)".to_string());
+        assert_eq!(
+            stereo_downmix_filter(&surround, Downmix::Stereo).as_deref(),
+            Some("pan=stereo|FL<1*c0+0.707*c2+0.707*c4|FR<1*c1+0.707*c2+0.707*c5")
+        );
+        assert_eq!(
+            stereo_downmix_filter(&surround, Downmix::Dialog).as_deref(),
+            Some("pan=stereo|FL<0.707*c0+1*c2+0.5*c4|FR<0.707*c1+1*c2+0.5*c5")
+        );
+        assert_eq!(stereo_downmix_filter(&surround, Downmix::Keep), None);
+        assert_eq!(stereo_downmix_filter(&track("eng", "Main", true), Downmix::Stereo), None);
+
+        // 7.1 folds both surround pairs into their side; an unlabelled 6-channel track reads as 5.1
+        surround.channels = 8;
+        surround.channel_layout = Some("7.1".to_string());
+        assert!(stereo_downmix_filter(&surround, Downmix::Stereo).unwrap().ends_with("FR<1*c1+0.707*c2+0.707*c5+0.707*c7"));
+        surround.channels = 6;
+        surround.channel_layout = None;
+        assert!(stereo_downmix_filter(&surround, Downmix::Stereo).is_some());
+
+        let args = select_audio_track_args("in.mkv", "out.mkv", AudioTrackChoice::Track(0), &[surround], Downmix::Stereo, "flac");
+        assert!(args.contains(&"[0:a:0]pan=stereo|FL<1*c0+0.707*c2+0.707*c4|FR<1*c1+0.707*c2+0.707*c5[a]".to_string()), "{:?}", args);
+        assert_eq!(Downmix::parse(Some("Dialog")), Ok(Downmix::Dialog));
+        assert!(Downmix::parse(Some("mono")).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+// Talking-avatar API client (D-ID Talks)
+// Turns a still portrait plus a script or narration audio into a lip-synced presenter clip
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone)]
+pub struct AvatarClient {
+    api_key: String,
+    client: Client,
+    base_url: String,
+}
+
+// ============================================================================
+// API REQUEST/RESPONSE STRUCTURES
+// ============================================================================
+
+#[derive(Serialize, Debug)]
+pub struct CreateTalkRequest {
+    pub source_url: String,
+    pub script: TalkScript,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<TalkConfig>,
+}
+
+/// What the avatar says: provider TTS from text, or pre-rendered narration audio
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TalkScript {
+    Text {
+        input: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        provider: Option<TalkVoiceProvider>,
+    },
+    Audio {
+        audio_url: String,
+    },
+}
+
+#[derive(Serialize, Debug)]
+pub struct TalkVoiceProvider {
+    #[serde(rename = "type")]
+    pub provider_type: String,
+    pub voice_id: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TalkConfig {
+    /// Paste the animated face back into the full portrait instead of a tight crop
+    pub stitch: bool,
+    pub result_format: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateTalkResponse {
+    pub id: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TalkStatusResponse {
+    /// created, started, done, error or rejected
+    pub status: String,
+    pub result_url: Option<String>,
+    pub error: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UploadResponse {
+    url: String,
+}
+
+// ============================================================================
+// CLIENT IMPLEMENTATION
+// ============================================================================
+
+impl AvatarClient {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            client: Client::new(),
+            base_url: "https://api.d-id.com".to_string(),
+        }
+    }
+
+    /// Upload a portrait so it can be used as the talk's source image
+    pub async fn upload_image(
+        &self,
+        image_bytes: Vec<u8>,
+        file_name: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.upload("images", "image", image_bytes, file_name).await
+    }
+
+    /// Upload narration audio so the avatar lip-syncs to an existing voiceover
+    pub async fn upload_audio(
+        &self,
+        audio_bytes: Vec<u8>,
+        file_name: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.upload("audios", "audio", audio_bytes, file_name).await
+    }
+
+    async fn upload(
+        &self,
+        endpoint: &str,
+        field: &str,
+        bytes: Vec<u8>,
+        file_name: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/{}", self.base_url, endpoint);
+        let form = reqwest::multipart::Form::new()
+            .part(field.to_string(), reqwest::multipart::Part::bytes(bytes).file_name(file_name.to_string()));
+
+        let response = self.client
+            .post(&url)
+            .header("Authorization", format!("Basic {}", self.api_key))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Avatar {} upload error ({}): {}", field, status, error_text).into());
+        }
+
+        let upload: UploadResponse = response.json().await?;
+        Ok(upload.url)
+    }
+
+    /// Start rendering a talking-head clip (Step 1: Create task)
+    pub async fn create_talk(
+        &self,
+        request: &CreateTalkRequest,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/talks", self.base_url);
+
+        let response = self.client
+            .post(&url)
+            .header("Authorization", format!("Basic {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Avatar Talks API error ({}): {}", status, error_text).into());
+        }
+
+        let created: CreateTalkResponse = response.json().await?;
+        Ok(created.id)
+    }
+
+    /// Check talk rendering status (Step 2: Poll for result)
+    pub async fn get_talk(
+        &self,
+        talk_id: &str,
+    ) -> Result<TalkStatusResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/talks/{}", self.base_url, talk_id);
+
+        let response = self.client
+            .get(&url)
+            .header("Authorization", format!("Basic {}", self.api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Avatar Talk Status API error ({}): {}", status, error_text).into());
+        }
+
+        let talk: TalkStatusResponse = response.json().await?;
+        Ok(talk)
+    }
+
+    /// Generate a presenter clip end to end: upload the portrait (and narration), render, and download the MP4.
+    /// With `audio` the avatar lip-syncs to that narration; otherwise it speaks `text` with the provider voice.
+    pub async fn generate_talking_head(
+        &self,
+        image_bytes: Vec<u8>,
+        image_name: &str,
+        text: Option<&str>,
+        audio: Option<(Vec<u8>, &str)>,
+        voice_id: Option<&str>,
+        max_wait_seconds: u64,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let source_url = self.upload_image(image_bytes, image_name).await?;
+
+        let script = match (audio, text) {
+            (Some((audio_bytes, audio_name)), _) => TalkScript::Audio {
+                audio_url: self.upload_audio(audio_bytes, audio_name).await?,
+            },
+            (None, Some(text)) if !text.trim().is_empty() => TalkScript::Text {
+                input: text.to_string(),
+                provider: voice_id.map(|voice| TalkVoiceProvider {
+                    provider_type: "microsoft".to_string(),
+                    voice_id: voice.to_string(),
+                }),
+            },
+            _ => return Err("A script or narration audio is required".into()),
+        };
+
+        let talk_id = self.create_talk(&CreateTalkRequest {
+            source_url,
+            script,
+            config: Some(TalkConfig { stitch: true, result_format: "mp4".to_string() }),
+        }).await?;
+
+        for _ in 0..(max_wait_seconds / 3).max(1) {
+            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+
+            let talk = self.get_talk(&talk_id).await?;
+            match talk.status.as_str() {
+                "done" => {
+                    let result_url = talk.result_url
+                        .ok_or("Talking head completed but no result URL provided")?;
+                    let response = self.client.get(&result_url).send().await?;
+                    if !response.status().is_success() {
+                        return Err(format!("Failed to download talking head ({})", response.status()).into());
+                    }
+                    return Ok(response.bytes().await?.to_vec());
+                }
+                "error" | "rejected" => {
+                    let detail = talk.error.map(|e| e.to_string()).unwrap_or_else(|| "Unknown error".to_string());
+                    return Err(format!("Talking head generation failed: {}", detail).into());
+                }
+                _ => {}
+            }
+        }
+
+        Err(format!("Talking head generation timed out after {}s", max_wait_seconds).into())
+    }
+}
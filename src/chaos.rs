@@ -0,0 +1,189 @@
+// chaos.rs - Fault injection for third-party API clients
+//
+// The ElevenLabs, Pexels and YouTube clients send every request through `send_with_chaos`. With
+// [chaos] disabled (the default) that is a plain `send()`. When it's enabled, each request to a
+// targeted client is delayed by the configured latency and may then, before reaching the network:
+//   - get a synthetic 429 with a Retry-After header and the provider-style JSON error body,
+//   - get a synthetic 503,
+//   - hang for `timeout_ms` and fail as timed out.
+// The client code can't tell these apart from the real thing, so staging runs exercise the same
+// error handling, retries and job-failure paths production would. Settings start from the config
+// and can be changed at runtime through /api/admin/chaos.
+use crate::config::ChaosConfig;
+use async_trait::async_trait;
+use rand::Rng;
+use reqwest::{RequestBuilder, Response};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// Seconds a synthetic 429 asks the client to wait
+const RETRY_AFTER_SECONDS: u64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChaosTarget {
+    ElevenLabs,
+    Pexels,
+    YouTube,
+}
+
+impl ChaosTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChaosTarget::ElevenLabs => "elevenlabs",
+            ChaosTarget::Pexels => "pexels",
+            ChaosTarget::YouTube => "youtube",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fault {
+    RateLimit,
+    Failure,
+    Timeout,
+}
+
+/// Runtime override of the configured settings (None = use the config)
+fn overridden() -> &'static RwLock<Option<ChaosConfig>> {
+    static OVERRIDE: OnceLock<RwLock<Option<ChaosConfig>>> = OnceLock::new();
+    OVERRIDE.get_or_init(|| RwLock::new(None))
+}
+
+/// The settings in effect, and whether they were changed at runtime
+pub fn settings() -> (ChaosConfig, bool) {
+    match overridden().read().unwrap().clone() {
+        Some(settings) => (settings, true),
+        None => (crate::config::get().chaos.clone(), false),
+    }
+}
+
+/// Replace the settings until the next restart or `reset`
+pub fn set(settings: ChaosConfig) -> Result<(), String> {
+    let problems = settings.problems();
+    if !problems.is_empty() {
+        return Err(problems.join("; "));
+    }
+    if settings.enabled {
+        tracing::warn!("🌪️ Chaos mode enabled at runtime: {:?}", settings);
+    } else {
+        tracing::info!("🌪️ Chaos mode disabled at runtime");
+    }
+    *overridden().write().unwrap() = Some(settings);
+    Ok(())
+}
+
+/// Go back to the configured settings
+pub fn reset() {
+    *overridden().write().unwrap() = None;
+    tracing::info!("🌪️ Chaos settings reset to the configuration");
+}
+
+/// Pick the fault for one request from a uniform sample in [0, 1)
+fn fault_for(settings: &ChaosConfig, sample: f64) -> Option<Fault> {
+    let mut threshold = settings.rate_limit_rate;
+    if sample < threshold {
+        return Some(Fault::RateLimit);
+    }
+    threshold += settings.failure_rate;
+    if sample < threshold {
+        return Some(Fault::Failure);
+    }
+    threshold += settings.timeout_rate;
+    if sample < threshold {
+        return Some(Fault::Timeout);
+    }
+    None
+}
+
+/// The response a provider would send for the fault
+fn synthetic_response(target: ChaosTarget, fault: Fault) -> Response {
+    let (status, message) = match fault {
+        Fault::RateLimit => (429, "Too many requests (injected by chaos mode)"),
+        _ => (503, "Service unavailable (injected by chaos mode)"),
+    };
+    let body = serde_json::json!({
+        "error": { "code": status, "message": message, "service": target.name() }
+    });
+    let mut response = axum::http::Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .header("x-chaos-injected", "true");
+    if fault == Fault::RateLimit {
+        response = response.header("retry-after", RETRY_AFTER_SECONDS.to_string());
+    }
+    Response::from(response.body(body.to_string()).expect("static response parts"))
+}
+
+#[async_trait]
+pub trait ChaosSend {
+    /// `send()`, disturbed as configured for `target` when chaos mode is on
+    async fn send_with_chaos(self, target: ChaosTarget) -> Result<Response, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl ChaosSend for RequestBuilder {
+    async fn send_with_chaos(self, target: ChaosTarget) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
+        let (settings, _) = settings();
+        if !settings.targets(target.name()) {
+            return Ok(self.send().await?);
+        }
+
+        let (delay, fault) = {
+            let mut rng = rand::thread_rng();
+            let jitter = if settings.jitter_ms > 0 { rng.gen_range(0..=settings.jitter_ms) } else { 0 };
+            (settings.latency_ms + jitter, fault_for(&settings, rng.gen::<f64>()))
+        };
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+
+        match fault {
+            None => Ok(self.send().await?),
+            Some(Fault::Timeout) => {
+                tracing::warn!("🌪️ Chaos: {} request hangs for {}ms, then times out", target.name(), settings.timeout_ms);
+                tokio::time::sleep(Duration::from_millis(settings.timeout_ms)).await;
+                Err(format!("{} request timed out after {}ms (injected by chaos mode)", target.name(), settings.timeout_ms).into())
+            }
+            Some(fault) => {
+                tracing::warn!("🌪️ Chaos: injecting {:?} into a {} request", fault, target.name());
+                Ok(synthetic_response(target, fault))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faults_follow_the_configured_shares() {
+        let settings = ChaosConfig { enabled: true, rate_limit_rate: 0.1, failure_rate: 0.2, timeout_rate: 0.3, ..Default::default() };
+        assert_eq!(fault_for(&settings, 0.05), Some(Fault::RateLimit));
+        assert_eq!(fault_for(&settings, 0.25), Some(Fault::Failure));
+        assert_eq!(fault_for(&settings, 0.55), Some(Fault::Timeout));
+        assert_eq!(fault_for(&settings, 0.65), None);
+        assert_eq!(fault_for(&ChaosConfig::default(), 0.0), None);
+    }
+
+    #[test]
+    fn only_listed_services_are_targeted() {
+        let settings = ChaosConfig { enabled: true, services: "pexels, YouTube".to_string(), ..Default::default() };
+        assert!(settings.targets("pexels"));
+        assert!(settings.targets("youtube"));
+        assert!(!settings.targets("elevenlabs"));
+        assert!(!ChaosConfig { enabled: false, ..settings }.targets("pexels"));
+        assert!(ChaosConfig { enabled: true, ..Default::default() }.targets("elevenlabs"));
+    }
+
+    #[tokio::test]
+    async fn rate_limits_look_like_the_real_thing() {
+        let response = synthetic_response(ChaosTarget::Pexels, Fault::RateLimit);
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()["retry-after"], RETRY_AFTER_SECONDS.to_string());
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["service"], "pexels");
+
+        assert_eq!(synthetic_response(ChaosTarget::YouTube, Fault::Failure).status(), 503);
+    }
+}
@@ -93,6 +93,8 @@ pub struct ClaudeTool {
 pub struct InputSchema {
     #[serde(rename = "type")]
     pub schema_type: String,
+    /// Serialized in key order so tool schemas are byte-identical across requests (required for prompt cache hits)
+    #[serde(serialize_with = "serialize_sorted")]
     pub properties: HashMap<String, PropertyDefinition>,
     pub required: Vec<String>,
 }
@@ -131,8 +133,46 @@ pub enum ResponseContent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Usage {
+    /// Uncached input tokens only; cached ones are reported separately below
     pub input_tokens: u32,
     pub output_tokens: u32,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u32>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u32>,
+}
+
+fn serialize_sorted<S: serde::Serializer>(
+    properties: &HashMap<String, PropertyDefinition>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let sorted: std::collections::BTreeMap<_, _> = properties.iter().collect();
+    sorted.serialize(serializer)
+}
+
+/// Mark prompt cache breakpoints on a serialized request: the last tool (caches all tool
+/// schemas), the system prompt, and the final message (caches the conversation so far for
+/// the next turn of an agent loop). Cached prefixes are matched byte-for-byte in the order
+/// tools → system → messages, so anything dynamic belongs in the messages, not the system prompt.
+fn apply_prompt_caching(body: &mut Value) {
+    let breakpoint = json!({ "type": "ephemeral" });
+
+    if let Some(last_tool) = body["tools"].as_array_mut().and_then(|tools| tools.last_mut()) {
+        last_tool["cache_control"] = breakpoint.clone();
+    }
+
+    if let Some(system) = body["system"].as_str() {
+        body["system"] = json!([{ "type": "text", "text": system, "cache_control": breakpoint }]);
+    }
+
+    if let Some(last_message) = body["messages"].as_array_mut().and_then(|messages| messages.last_mut()) {
+        if let Some(text) = last_message["content"].as_str() {
+            last_message["content"] = json!([{ "type": "text", "text": text }]);
+        }
+        if let Some(last_block) = last_message["content"].as_array_mut().and_then(|blocks| blocks.last_mut()) {
+            last_block["cache_control"] = breakpoint;
+        }
+    }
 }
 
 impl ClaudeClient {
@@ -145,6 +185,14 @@ impl ClaudeClient {
         }
     }
 
+    /// Same client talking to a different model
+    pub fn with_model(&self, model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            ..self.clone()
+        }
+    }
+
     pub async fn generate_content(
         &self,
         messages: Vec<ClaudeMessage>,
@@ -173,6 +221,9 @@ impl ClaudeClient {
         tracing::debug!("Claude API Request: {} tools provided", request.tools.as_ref().map(|t| t.len()).unwrap_or(0));
         tracing::debug!("Claude API Request messages count: {}", request.messages.len());
 
+        let mut body = serde_json::to_value(&request).map_err(|e| format!("Failed to serialize request: {}", e))?;
+        apply_prompt_caching(&mut body);
+
         // Configure exponential backoff for retries
         let backoff_config = ExponentialBackoff {
             initial_interval: Duration::from_secs(1),
@@ -191,7 +242,7 @@ impl ClaudeClient {
                 .header("anthropic-version", "2023-06-01")
                 .header("content-type", "application/json")
                 .timeout(Duration::from_secs(120))  // 2-minute timeout per request
-                .json(&request)
+                .json(&body)
                 .send()
                 .await
                 .map_err(|e| {
@@ -228,7 +279,16 @@ impl ClaudeClient {
 
         // Execute with retry
         match retry(backoff_config, operation).await {
-            Ok(response) => Ok(response),
+            Ok(response) => {
+                let response: ClaudeResponse = response;
+                tracing::debug!(
+                    "Claude prompt cache: {} read, {} written, {} uncached input tokens",
+                    response.usage.cache_read_input_tokens.unwrap_or(0),
+                    response.usage.cache_creation_input_tokens.unwrap_or(0),
+                    response.usage.input_tokens
+                );
+                Ok(response)
+            }
             Err(e) => Err(e),
         }
     }
@@ -282,13 +342,13 @@ impl ClaudeClient {
                             items: None,
                         }),
                         ("start_seconds".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Start time in seconds".to_string(),
+                            prop_type: "string".to_string(),
+                            description: "Start time: seconds (12.5), timecode (00:01:12.500), frame number (300f) or percentage of the video (25%)".to_string(),
                             items: None,
                         }),
                         ("end_seconds".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "End time in seconds".to_string(),
+                            prop_type: "string".to_string(),
+                            description: "End time: seconds (12.5), timecode (00:01:12.500), frame number (300f) or percentage of the video (25%)".to_string(),
                             items: None,
                         }),
                     ]),
@@ -405,8 +465,13 @@ impl ClaudeClient {
                             description: "Target height in pixels".to_string(),
                             items: None,
                         }),
+                        ("fps".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output frame rate (optional; keeps the source rate when omitted)".to_string(),
+                            items: None,
+                        }),
                     ]),
-                    required: vec!["input_file".to_string(), "output_file".to_string(), "width".to_string(), "height".to_string()],
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
                 },
             },
             ClaudeTool {
@@ -430,6 +495,16 @@ impl ClaudeClient {
                             description: "Target format (e.g., mp4, avi, mov, webm)".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "format".to_string()],
                 },
@@ -455,6 +530,16 @@ impl ClaudeClient {
                             description: "Volume multiplier (1.0 = original, 0.5 = half, 2.0 = double)".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "volume_factor".to_string()],
                 },
@@ -685,10 +770,45 @@ impl ClaudeClient {
                             description: "Audio format (mp3, wav, aac, etc.)".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "format".to_string()],
                 },
             },
+            ClaudeTool {
+                name: "select_audio_track".to_string(),
+                description: "Keeps one audio track of a file with several (e.g. game audio and microphone), or mixes them all into one. analyze_video lists the tracks".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the video with the chosen audio track".to_string(),
+                            items: None,
+                        }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Track to keep: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks mixed together".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string(), "audio_track".to_string()],
+                },
+            },
             ClaudeTool {
                 name: "add_audio".to_string(),
                 description: "Adds an audio track to a video or replaces existing audio".to_string(),
@@ -715,6 +835,16 @@ impl ClaudeClient {
                             description: "Whether to replace existing audio (true) or mix (false)".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "audio_file".to_string()],
                 },
@@ -745,6 +875,16 @@ impl ClaudeClient {
                             description: "Fade out duration in seconds (0 for no fade out)".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "fade_in_duration".to_string(), "fade_out_duration".to_string()],
                 },
@@ -770,6 +910,16 @@ impl ClaudeClient {
                             description: "Compression quality: 'high', 'medium', 'low'".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "quality".to_string()],
                 },
@@ -795,10 +945,70 @@ impl ClaudeClient {
                             description: "Target platform: 'youtube', 'instagram', 'tiktok', 'twitter', 'facebook'".to_string(),
                             items: None,
                         }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
                     ]),
                     required: vec!["input_file".to_string(), "output_file".to_string(), "platform".to_string()],
                 },
             },
+            ClaudeTool {
+                name: "multi_export".to_string(),
+                description: "Exports several renditions of a video at once (e.g. 4K master, 1080p web, 720p preview, vertical 1080x1920) from a single decode pass, much faster than separate exports".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("renditions".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Renditions to produce: '4k', '1440p', '1080p', '720p', '480p', 'vertical' (1080x1920 crop), 'square' (1080x1080 crop) or a custom '<width>x<height>'".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "A rendition preset name or <width>x<height>".to_string(),
+                                items: None,
+                            })),
+                        }),
+                        ("output_dir".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Folder under outputs/ for the renditions (default: <input name>_renditions)".to_string(),
+                            items: None,
+                        }),
+                        ("fps".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Optional output frame rate for every rendition (default: keep the source frame rate)".to_string(),
+                            items: None,
+                        }),
+                        ("allow_upscale".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Render renditions larger than the source instead of skipping them (default: false)".to_string(),
+                            items: None,
+                        }),
+                        ("audio_track".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Which audio track of a multi-track input to use: a track number from 1, a language (eng), part of the track title (mic), or \"mix\" for all tracks. Defaults to the track flagged default (see analyze_video)".to_string(),
+                            items: None,
+                        }),
+                        ("downmix".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Surround (5.1, 7.1) audio handling: \"stereo\" (default) folds it down to stereo, \"dialog\" does the same with the center channel boosted for clearer speech, \"keep\" leaves the channel layout as it is".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "renditions".to_string()],
+                },
+            },
+
             ClaudeTool {
                 name: "picture_in_picture".to_string(),
                 description: "Creates a picture-in-picture effect with two video sources".to_string(),
@@ -971,8 +1181,8 @@ impl ClaudeClient {
                             items: None,
                         }),
                         ("timestamp".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Time in seconds to capture thumbnail".to_string(),
+                            prop_type: "string".to_string(),
+                            description: "Time to capture the thumbnail: seconds (12.5), timecode (00:01:12.500), frame number (300f) or percentage of the video (25%)".to_string(),
                             items: None,
                         }),
                         ("width".to_string(), PropertyDefinition {
@@ -1065,8 +1275,8 @@ impl ClaudeClient {
                 },
             },
             ClaudeTool {
-                name: "extract_frames".to_string(),
-                description: "Extracts individual frames from a video as image files".to_string(),
+                name: "burn_timecode".to_string(),
+                description: "Burns a running SMPTE timecode (HH:MM:SS:FF) into every frame, counted at the video's exact frame rate, so review notes can cite exact frames".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
@@ -1075,613 +1285,2175 @@ impl ClaudeClient {
                             description: "Path to the input video file".to_string(),
                             items: None,
                         }),
-                        ("output_dir".to_string(), PropertyDefinition {
+                        ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Directory to save extracted frames".to_string(),
-                            items: None,
-                        }),
-                        ("frame_rate".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Extract one frame every N seconds (default: 1)".to_string(),
+                            description: "Path to save the video with the timecode".to_string(),
                             items: None,
                         }),
-                        ("format".to_string(), PropertyDefinition {
+                        ("start_timecode".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Image format for frames (png, jpg, etc.)".to_string(),
+                            description: "Timecode of the first frame, HH:MM:SS:FF (default 00:00:00:00; 01:00:00:00 for a broadcast-style start). Write HH:MM:SS;FF for drop-frame at 29.97 or 59.94 fps".to_string(),
                             items: None,
                         }),
-                    ]),
-                    required: vec!["input_file".to_string(), "output_dir".to_string()],
-                },
-            },
-            ClaudeTool {
-                name: "pexels_search".to_string(),
-                description: "Searches Pexels for stock videos and images based on query".to_string(),
-                input_schema: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: HashMap::from([
-                        ("query".to_string(), PropertyDefinition {
+                        ("position".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Search query for stock content".to_string(),
+                            description: "Where to draw it: bottom_center (default), bottom_left, bottom_right, top_center, top_left or top_right".to_string(),
                             items: None,
                         }),
-                        ("media_type".to_string(), PropertyDefinition {
-                            prop_type: "string".to_string(),
-                            description: "Media type to search: 'videos' or 'photos'".to_string(),
+                        ("font_size".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Text height in pixels (default: 1/20 of the frame height)".to_string(),
                             items: None,
                         }),
-                        ("per_page".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Number of results to return (1-80, default: 15)".to_string(),
+                        ("label".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Text shown before the timecode, e.g. \"v3 REVIEW\"".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["query".to_string(), "media_type".to_string()],
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "analyze_image".to_string(),
-                description: "Analyzes an image and provides detailed description using AI".to_string(),
+                name: "generate_slate".to_string(),
+                description: "Prepends a slate card (project name, version, date and notes) to a video, made at the video's size and frame rate, for review and delivery copies".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("image_path".to_string(), PropertyDefinition {
+                        ("input_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to the image file to analyze".to_string(),
+                            description: "Path to the input video file".to_string(),
                             items: None,
                         }),
-                        ("analysis_type".to_string(), PropertyDefinition {
+                        ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Type of analysis: 'general', 'detailed', 'objects', 'colors'".to_string(),
+                            description: "Path to save the video with the slate in front".to_string(),
                             items: None,
                         }),
-                    ]),
-                    required: vec!["image_path".to_string()],
-                },
-            },
-            ClaudeTool {
-                name: "generate_text_to_speech".to_string(),
-                description: "Generates speech audio from text using Eleven Labs TTS (with Gemini fallback). Supports 17+ premium voices with ultra-low latency (75ms). Perfect for narration, voiceovers, and character voices.".to_string(),
-                input_schema: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: HashMap::from([
-                        ("text".to_string(), PropertyDefinition {
+                        ("project".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Text to convert to speech".to_string(),
+                            description: "Project name, the card's title".to_string(),
                             items: None,
                         }),
-                        ("output_file".to_string(), PropertyDefinition {
+                        ("version".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to save the generated audio file (e.g., 'outputs/narration.mp3')".to_string(),
+                            description: "Version or cut, e.g. \"v3\" or \"Director's cut\"".to_string(),
                             items: None,
                         }),
-                        ("voice".to_string(), PropertyDefinition {
+                        ("date".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Voice name: Rachel (default, young female), Drew (male, news), Clyde (male, veteran), Bella (female, soft), Emily (female, calm), Adam (male, deep), Paul (male, reporter), Domi (female, strong), Elli (female, emotional), Grace (female, young), Matilda (female, warm), Arnold (male, crisp), Callum (male, hoarse), Daniel (male, deep), Ethan (male, young), Liam (male, articulate), Thomas (male, calm)".to_string(),
+                            description: "Date shown on the card (default: today, YYYY-MM-DD)".to_string(),
                             items: None,
                         }),
-                        ("model".to_string(), PropertyDefinition {
+                        ("notes".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Model: 'eleven_flash_v2_5' (75ms latency, default), 'eleven_multilingual_v2' (highest quality), 'eleven_turbo_v2_5' (fast)".to_string(),
+                            description: "Optional notes under the details (up to four short lines)".to_string(),
+                            items: None,
+                        }),
+                        ("duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "How long the slate shows in seconds, 1-30 (default: 5)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["text".to_string(), "output_file".to_string()],
+                    required: vec!["input_file".to_string(), "output_file".to_string(), "project".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "generate_sound_effect".to_string(),
-                description: "Generates custom sound effects from text descriptions using Eleven Labs. Create cinematic sound design, Foley, ambient sounds, impacts, transitions, etc. Duration: 0.5-30 seconds.".to_string(),
+                name: "preview_safe_zones".to_string(),
+                description: "Renders a proof image or video with TikTok, Instagram Reels and YouTube Shorts UI safe-zone guides overlaid, to check captions and text won't be covered by platform UI before exporting".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("description".to_string(), PropertyDefinition {
+                        ("input_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Detailed description of the sound effect (e.g., 'cinematic explosion with rumble', 'door creaking slowly')".to_string(),
+                            description: "Path to the input video file".to_string(),
                             items: None,
                         }),
                         ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to save the sound effect (e.g., 'outputs/explosion.mp3')".to_string(),
+                            description: "Path to save the proof: an image (.png, .jpg) for a single frame, or a video (.mp4) for the whole clip".to_string(),
                             items: None,
                         }),
-                        ("duration_seconds".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Duration in seconds (0.5-30, default: 5)".to_string(),
+                        ("platform".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "tiktok, reels, shorts or all (default: all, with each platform outlined in its own color)".to_string(),
                             items: None,
                         }),
-                        ("prompt_influence".to_string(), PropertyDefinition {
+                        ("timestamp".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "How closely to follow prompt (0-1, default: 0.5). Higher = more precise".to_string(),
+                            description: "Frame to show in an image proof, in seconds (default: the middle of the video)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["description".to_string(), "output_file".to_string()],
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "generate_music".to_string(),
-                description: "Generates studio-grade background music from text prompts using Eleven Music. Create music in any genre, mood, style. Supports custom structure, lyrics, tempo. Commercial use cleared. Duration: 10-300 seconds.".to_string(),
+                name: "add_styled_captions".to_string(),
+                description: "Transcribes a video word by word and burns in animated captions in a style preset (the word being spoken is highlighted). Uses the session's default caption style when none is given.".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("prompt".to_string(), PropertyDefinition {
+                        ("input_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Music description (e.g., 'upbeat electronic dance music 120 BPM', 'peaceful piano meditation', 'epic cinematic orchestral with drums'). Can include genre, mood, instruments, tempo, structure, lyrics.".to_string(),
+                            description: "Path to the video to caption".to_string(),
                             items: None,
                         }),
                         ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to save the music file (e.g., 'outputs/background_music.mp3')".to_string(),
+                            description: "Path for the captioned video".to_string(),
                             items: None,
                         }),
-                        ("duration_seconds".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Music duration in seconds (10-300, default: 30)".to_string(),
+                        ("caption_style".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Caption preset: 'bold', 'karaoke', 'minimal' or 'hype' (default: bold)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["prompt".to_string(), "output_file".to_string()],
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "add_voiceover_to_video".to_string(),
-                description: "Convenience tool that generates voiceover speech and adds it to a video in one step. Combines text-to-speech generation with audio mixing automatically.".to_string(),
+                name: "generate_captions".to_string(),
+                description: "Transcribes a video and burns in animated TikTok-style captions you design: word by word (only the spoken word on screen) or line by line, with the spoken word highlighted by a karaoke fill, a colour change, a highlighter box or a pop. Font, colours, size and position are configurable. Use add_styled_captions for the ready-made presets.".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("input_video".to_string(), PropertyDefinition {
+                        ("input_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to the input video file".to_string(),
+                            description: "Path to the video to caption".to_string(),
                             items: None,
                         }),
-                        ("voiceover_text".to_string(), PropertyDefinition {
+                        ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Text for the voiceover narration".to_string(),
+                            description: "Path for the captioned video".to_string(),
                             items: None,
                         }),
-                        ("output_video".to_string(), PropertyDefinition {
+                        ("mode".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to save the video with voiceover (e.g., 'outputs/narrated_video.mp4')".to_string(),
+                            description: "'line' (a few words at a time, default) or 'word' (one word at a time)".to_string(),
                             items: None,
                         }),
-                        ("voice".to_string(), PropertyDefinition {
+                        ("highlight_style".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Voice name (same as generate_text_to_speech, default: Rachel)".to_string(),
+                            description: "How the spoken word stands out: 'color' (default), 'fill' (karaoke sweep), 'box' (highlighter behind the word), 'pop' (colour plus a scale-up) or 'none'".to_string(),
                             items: None,
                         }),
-                    ]),
-                    required: vec!["input_video".to_string(), "voiceover_text".to_string(), "output_video".to_string()],
-                },
-            },
-            ClaudeTool {
-                name: "generate_video_script".to_string(),
-                description: "Generates a video script based on topic and requirements using AI".to_string(),
-                input_schema: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: HashMap::from([
-                        ("topic".to_string(), PropertyDefinition {
+                        ("font".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Topic or theme for the video script".to_string(),
+                            description: "Font family (default DejaVu Sans)".to_string(),
                             items: None,
                         }),
-                        ("duration".to_string(), PropertyDefinition {
+                        ("font_size_percent".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Target video duration in seconds".to_string(),
+                            description: "Font size as a percentage of the video height (default 7)".to_string(),
                             items: None,
                         }),
-                        ("style".to_string(), PropertyDefinition {
+                        ("text_color".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Script style: 'educational', 'entertainment', 'commercial', 'documentary'".to_string(),
+                            description: "Caption colour: a name like white or yellow, or #RRGGBB (default white)".to_string(),
                             items: None,
                         }),
-                        ("tone".to_string(), PropertyDefinition {
+                        ("highlight_color".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Script tone: 'casual', 'professional', 'humorous', 'serious'".to_string(),
+                            description: "Colour of the spoken word (default yellow)".to_string(),
                             items: None,
                         }),
-                    ]),
-                    required: vec!["topic".to_string(), "duration".to_string()],
+                        ("position".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "'bottom' (default, clear of Shorts/TikTok overlays), 'middle' or 'top'".to_string(),
+                            items: None,
+                        }),
+                        ("words_per_line".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Words shown together in line mode (default 3)".to_string(),
+                            items: None,
+                        }),
+                        ("uppercase".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Show captions in capitals (default true)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "extract_frames".to_string(),
+                description: "Extracts individual frames from a video as image files".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("output_dir".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Directory to save extracted frames".to_string(),
+                            items: None,
+                        }),
+                        ("frame_rate".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Extract one frame every N seconds (default: 1)".to_string(),
+                            items: None,
+                        }),
+                        ("format".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Image format for frames (png, jpg, etc.)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_dir".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "contact_sheet".to_string(),
+                description: "Exports a storyboard: one grid image of frames at the video's scene changes, each labelled with its timestamp. Good for reviewing long footage quickly or sending a client for approval".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the contact sheet image (.png or .jpg)".to_string(),
+                            items: None,
+                        }),
+                        ("max_frames".to_string(), PropertyDefinition {
+                            prop_type: "integer".to_string(),
+                            description: "Most frames on the sheet (default: 24, max: 60); with more scene changes than this, they're thinned evenly".to_string(),
+                            items: None,
+                        }),
+                        ("columns".to_string(), PropertyDefinition {
+                            prop_type: "integer".to_string(),
+                            description: "Frames per row (default: 4)".to_string(),
+                            items: None,
+                        }),
+                        ("threshold".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Scene-change sensitivity from 0.05 to 0.9 (default: 0.3); lower finds subtler cuts".to_string(),
+                            items: None,
+                        }),
+                        ("thumb_width".to_string(), PropertyDefinition {
+                            prop_type: "integer".to_string(),
+                            description: "Width of each frame in pixels, 160-640 (default: 320)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "detect_scenes".to_string(),
+                description: "Finds the scene changes (cuts between shots) in a video and returns each scene's start and end time, with a thumbnail from the middle of each. Use it before trimming, splitting or clipping so cuts land on shot boundaries instead of arbitrary timestamps, or to describe what happens in a video shot by shot".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("threshold".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Scene-change sensitivity from 0.05 to 0.9 (default: 0.3); lower finds subtler cuts".to_string(),
+                            items: None,
+                        }),
+                        ("min_scene_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Shortest scene kept, in seconds (default: 1); quicker changes such as flashes are merged into the scene before".to_string(),
+                            items: None,
+                        }),
+                        ("thumbnails".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Save a thumbnail of each scene (default: true)".to_string(),
+                            items: None,
+                        }),
+                        ("output_dir".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Folder for the thumbnails (default: outputs/scenes_<video name>)".to_string(),
+                            items: None,
+                        }),
+                        ("start".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Start (seconds) of a cut you plan to make; with end, it is returned moved onto the nearest scene changes (within 2 seconds)".to_string(),
+                            items: None,
+                        }),
+                        ("end".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "End (seconds) of the planned cut".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "pexels_search".to_string(),
+                description: "Searches Pexels for stock videos and images based on query".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("query".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Search query for stock content".to_string(),
+                            items: None,
+                        }),
+                        ("media_type".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Media type to search: 'videos' or 'photos'".to_string(),
+                            items: None,
+                        }),
+                        ("per_page".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of results to return (1-80, default: 15)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["query".to_string(), "media_type".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "analyze_image".to_string(),
+                description: "Analyzes an image and provides detailed description using AI".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("image_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the image file to analyze".to_string(),
+                            items: None,
+                        }),
+                        ("analysis_type".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Type of analysis: 'general', 'detailed', 'objects', 'colors'".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["image_path".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "generate_text_to_speech".to_string(),
+                description: "Generates speech audio from text using Eleven Labs TTS (with Gemini fallback). Supports 17+ premium voices with ultra-low latency (75ms). Perfect for narration, voiceovers, and character voices.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("text".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Text to convert to speech".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the generated audio file (e.g., 'outputs/narration.mp3')".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Voice name: Rachel (default, young female), Drew (male, news), Clyde (male, veteran), Bella (female, soft), Emily (female, calm), Adam (male, deep), Paul (male, reporter), Domi (female, strong), Elli (female, emotional), Grace (female, young), Matilda (female, warm), Arnold (male, crisp), Callum (male, hoarse), Daniel (male, deep), Ethan (male, young), Liam (male, articulate), Thomas (male, calm)".to_string(),
+                            items: None,
+                        }),
+                        ("model".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Model: 'eleven_flash_v2_5' (75ms latency, default), 'eleven_multilingual_v2' (highest quality), 'eleven_turbo_v2_5' (fast)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["text".to_string(), "output_file".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "generate_sound_effect".to_string(),
+                description: "Generates custom sound effects from text descriptions using Eleven Labs. Create cinematic sound design, Foley, ambient sounds, impacts, transitions, etc. Duration: 0.5-30 seconds.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("description".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Detailed description of the sound effect (e.g., 'cinematic explosion with rumble', 'door creaking slowly')".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the sound effect (e.g., 'outputs/explosion.mp3')".to_string(),
+                            items: None,
+                        }),
+                        ("duration_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Duration in seconds (0.5-30, default: 5)".to_string(),
+                            items: None,
+                        }),
+                        ("prompt_influence".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "How closely to follow prompt (0-1, default: 0.5). Higher = more precise".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["description".to_string(), "output_file".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "generate_music".to_string(),
+                description: "Generates studio-grade background music from text prompts using Eleven Music. Create music in any genre, mood, style. Supports custom structure, lyrics, tempo. Commercial use cleared. Duration: 10-300 seconds.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("prompt".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Music description (e.g., 'upbeat electronic dance music 120 BPM', 'peaceful piano meditation', 'epic cinematic orchestral with drums'). Can include genre, mood, instruments, tempo, structure, lyrics.".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the music file (e.g., 'outputs/background_music.mp3')".to_string(),
+                            items: None,
+                        }),
+                        ("duration_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Music duration in seconds (10-300, default: 30)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["prompt".to_string(), "output_file".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "add_voiceover_to_video".to_string(),
+                description: "Convenience tool that generates voiceover speech and adds it to a video in one step. Combines text-to-speech generation with audio mixing automatically.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_video".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("voiceover_text".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Text for the voiceover narration".to_string(),
+                            items: None,
+                        }),
+                        ("output_video".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the video with voiceover (e.g., 'outputs/narrated_video.mp4')".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Voice name (same as generate_text_to_speech, default: Rachel)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_video".to_string(), "voiceover_text".to_string(), "output_video".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "generate_video_script".to_string(),
+                description: "Generates a video script based on topic and requirements using AI".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("topic".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Topic or theme for the video script".to_string(),
+                            items: None,
+                        }),
+                        ("duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Target video duration in seconds".to_string(),
+                            items: None,
+                        }),
+                        ("style".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Script style: 'educational', 'entertainment', 'commercial', 'documentary'".to_string(),
+                            items: None,
+                        }),
+                        ("tone".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Script tone: 'casual', 'professional', 'humorous', 'serious'".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["topic".to_string(), "duration".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "create_blank_video".to_string(),
+                description: "Creates a blank video with specified color, duration, and dimensions".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the blank video".to_string(),
+                            items: None,
+                        }),
+                        ("duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Duration of the blank video in seconds".to_string(),
+                            items: None,
+                        }),
+                        ("width".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Video width in pixels".to_string(),
+                            items: None,
+                        }),
+                        ("height".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Video height in pixels".to_string(),
+                            items: None,
+                        }),
+                        ("fps".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Frame rate (optional; default 25)".to_string(),
+                            items: None,
+                        }),
+                        ("color".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Background color (hex code or color name, default: black)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["output_file".to_string(), "duration".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "pexels_download_video".to_string(),
+                description: "Downloads a video from Pexels given the video file URL".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_url".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Pexels video file URL (from pexels_search results)".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Local path to save the downloaded video".to_string(),
+                            items: None,
+                        }),
+                        ("quality".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video quality: 'hd', 'sd', 'low' (optional)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_url".to_string(), "output_file".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "pexels_download_photo".to_string(),
+                description: "Downloads a photo from Pexels given the photo URL".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("photo_url".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Pexels photo URL (from pexels_search results)".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Local path to save the downloaded photo".to_string(),
+                            items: None,
+                        }),
+                        ("size".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Photo size: 'original', 'large', 'medium', 'small' (optional)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["photo_url".to_string(), "output_file".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "pexels_get_trending".to_string(),
+                description: "Gets trending/popular videos from Pexels without needing a search query".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("per_page".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of results to return (1-80, default: 15)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec![],
+                },
+            },
+            ClaudeTool {
+                name: "pexels_get_curated".to_string(),
+                description: "Gets curated/hand-picked photos from Pexels without needing a search query".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("per_page".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of results to return (1-80, default: 15)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec![],
+                },
+            },
+            ClaudeTool {
+                name: "view_video".to_string(),
+                description: "Views/analyzes a video by retrieving its vectorized embeddings from the database. This allows you to 'see' what's in a video without re-processing it. Use this to understand video content, verify edits, or check what a previously generated video contains. Returns detailed frame-by-frame analysis and overall summary.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the video file to view/analyze (e.g., 'outputs/edited_video.mp4')".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_path".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "ask_about_video".to_string(),
+                description: "Answers a question about a video's content with timestamp citations, e.g. 'at what point does the logo appear?' or 'when does the speaker mention pricing?'. Samples frames around relevant moments (found via the video's vectorized frame descriptions and its transcript, plus evenly spaced coverage) and looks at them with a vision model. Use this instead of view_video when you need to locate or verify something specific".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the video file (e.g. 'uploads/interview.mp4')".to_string(),
+                            items: None,
+                        }),
+                        ("question".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "The question to answer about the video".to_string(),
+                            items: None,
+                        }),
+                        ("max_frames".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Frames to look at (default 8, max 16); more is slower but finds brief moments more reliably".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_path".to_string(), "question".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "summarize_video".to_string(),
+                description: "Summarizes a recording of any length, e.g. 'summarize this 3-hour webinar'. Transcribes it, summarizes the transcript in ~10-minute chunks and merges those summaries level by level into an overview with timestamped key points, so long recordings are covered end to end instead of truncated. Use this for summarize/recap/'what is this about' requests; use ask_about_video for specific questions".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the video or audio file (e.g. 'uploads/webinar.mp4')".to_string(),
+                            items: None,
+                        }),
+                        ("depth".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "How much detail: 'brief' (one paragraph), 'standard' (overview, key points and a chapter-level outline; default) or 'detailed' (also a summary of every ~10-minute part)".to_string(),
+                            items: None,
+                        }),
+                        ("focus".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional topic to concentrate on, e.g. 'questions from the audience' or 'pricing'".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_path".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "review_video".to_string(),
+                description: "Reviews an output video to verify it meets the user's original requirements. Use this in the final stage of video editing/generation to confirm quality before presenting to the user. Compares the video's vectorized analysis against the user's request to check if edits were applied correctly, and checks audio/video sync.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the output video to review".to_string(),
+                            items: None,
+                        }),
+                        ("original_request".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "The original user request/requirements to verify against".to_string(),
+                            items: None,
+                        }),
+                        ("expected_features".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "List of expected features that should be present (e.g., ['grayscale filter', 'text overlay', 'trimmed to 10s'])".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "Expected feature".to_string(),
+                                items: None,
+                            })),
+                        }),
+                        ("max_av_drift_ms".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Largest audio/video offset in milliseconds that still passes (default: 100)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_path".to_string(), "original_request".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "view_image".to_string(),
+                description: "Views/analyzes an image file using AI vision. Use this to verify generated images, inspect stock photos from Pexels, or check overlay images before using them in videos. Returns detailed analysis of content, colors, composition, style, and suitability for video use.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("image_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the image file to view/analyze (e.g., 'outputs/generated_logo.png' or 'outputs/stock_photo.jpg')".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["image_path".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "generate_image".to_string(),
+                description: "Generates an image using Google's Imagen AI model based on a text prompt. Use this to create custom images, overlays, backgrounds, or any visual elements needed for video editing.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("prompt".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Detailed text description of the image to generate".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path where the generated image should be saved (e.g., 'outputs/generated_overlay.png')".to_string(),
+                            items: None,
+                        }),
+                        ("width".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Image width in pixels (default: 1024)".to_string(),
+                            items: None,
+                        }),
+                        ("height".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Image height in pixels (default: 1024)".to_string(),
+                            items: None,
+                        }),
+                        ("aspect_ratio".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Aspect ratio: '1:1', '16:9', '9:16', '4:3' (optional, overrides width/height)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["prompt".to_string(), "output_file".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "auto_generate_video".to_string(),
+                description: "Orchestrates automatic video generation from a topic/prompt. This high-level tool searches Pexels for stock footage, generates images, downloads clips, merges them, adds text overlays, music, and exports a complete video. Perfect for creating videos from scratch.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("topic".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Topic or description of the video to create (e.g., 'A motivational video about success')".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path where the final video should be saved".to_string(),
+                            items: None,
+                        }),
+                        ("duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Target video duration in seconds (default: 30)".to_string(),
+                            items: None,
+                        }),
+                        ("style".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video style: 'cinematic', 'minimal', 'energetic', 'calm', 'corporate' (default: 'cinematic')".to_string(),
+                            items: None,
+                        }),
+                        ("include_text_overlays".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Whether to add text overlays with key messages (default: true)".to_string(),
+                            items: None,
+                        }),
+                        ("include_music".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Whether to add background music (default: false)".to_string(),
+                            items: None,
+                        }),
+                        ("num_clips".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of video clips to use from Pexels (default: 3-5 based on duration)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["topic".to_string(), "output_file".to_string()],
+                },
+            },
+            // Chat title management tool
+            ClaudeTool {
+                name: "set_chat_title".to_string(),
+                description: "Sets a descriptive title for the current chat session. Use this to give the conversation a meaningful title based on the user's request or the work being done.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("title".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "A concise, descriptive title for this chat session (max 100 characters)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["title".to_string()],
+                },
+            },
+
+            // =====================================================================
+            // YOUTUBE INTEGRATION TOOLS (READ-ONLY RESEARCH & OPTIMIZATION)
+            // =====================================================================
+
+            ClaudeTool {
+                name: "set_session_defaults".to_string(),
+                description: "Sets defaults that persist for the rest of this chat session, e.g. when the user says 'from now on everything in 4K with Adam's voice'. Tools that take a resolution (width/height), fps, narration voice or caption style use these whenever a call leaves them out. Fields not given keep their current value; call with no fields to read the current defaults.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("resolution".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Default output resolution: '4k', '1440p', '1080p', '720p', '480p' or '<width>x<height>' (e.g. '1080x1920' for vertical)".to_string(),
+                            items: None,
+                        }),
+                        ("fps".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Default output frame rate".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Default narration voice name (e.g. 'Adam', 'Rachel')".to_string(),
+                            items: None,
+                        }),
+                        ("caption_style".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Default caption style: 'bold', 'karaoke', 'minimal', 'hype' or 'none' (no burned captions)".to_string(),
+                            items: None,
+                        }),
+                        ("clear".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Defaults to remove: 'resolution', 'fps', 'voice', 'caption_style' or 'all'".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "Name of a default to clear".to_string(),
+                                items: None,
+                            })),
+                        }),
+                    ]),
+                    required: vec![],
+                },
+            },
+
+            ClaudeTool {
+                name: "upload_to_youtube".to_string(),
+                description: "Uploads a finished video to the user's YouTube channel. Only call this when the user explicitly asks to upload or publish. Leave out anything the user didn't specify: the channel, privacy, category, tags and description come from the user's saved upload defaults, and a missing title or description from the metadata generated when the output rendered. Returns the YouTube URL.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the video to upload (e.g. 'outputs/final.mp4')".to_string(),
+                            items: None,
+                        }),
+                        ("title".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video title; omit to use the generated title".to_string(),
+                            items: None,
+                        }),
+                        ("description".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video description; omit to use the user's description template or the generated description".to_string(),
+                            items: None,
+                        }),
+                        ("privacy_status".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "'public', 'private' or 'unlisted'; omit to use the user's default (private if none)".to_string(),
+                            items: None,
+                        }),
+                        ("channel_id".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Connected channel id; omit to use the user's default channel".to_string(),
+                            items: None,
+                        }),
+                        ("tags".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Tags for this upload, replacing the default and generated tags; omit to use those".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "A tag".to_string(),
+                                items: None,
+                            })),
+                        }),
+                    ]),
+                    required: vec!["video_path".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "optimize_youtube_metadata".to_string(),
+                description: "Analyzes a video file and generates SEO-optimized YouTube metadata (title, description, tags) to maximize discoverability and engagement. Uses AI to understand video content and suggest compelling, keyword-rich metadata. Returns suggestions only - does not upload or modify anything. Parameters: video_path (required) - path to video file, target_audience (optional) - intended audience like 'gaming', 'education', 'vlog', style (optional) - 'clickbait', 'professional', or 'casual'.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the video file to analyze for metadata optimization".to_string(),
+                            items: None,
+                        }),
+                        ("target_audience".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Target audience type: 'gaming', 'education', 'vlog', 'entertainment', 'tech', 'music', etc.".to_string(),
+                            items: None,
+                        }),
+                        ("style".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Metadata style: 'clickbait' (attention-grabbing), 'professional' (formal), 'casual' (conversational)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_path".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "analyze_youtube_performance".to_string(),
+                description: "Fetches analytics data for a YouTube video and provides AI-powered insights on performance, audience engagement, and optimization opportunities. Analyzes views, watch time, likes, comments, shares, and subscriber gain/loss. Identifies strengths and areas for improvement. READ-ONLY tool - does not modify anything. Parameters: video_id (required) - YouTube video ID, date_range (optional) - number of days to analyze (default 30).".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("video_id".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "YouTube video ID (the alphanumeric code from youtube.com/watch?v=VIDEO_ID)".to_string(),
+                            items: None,
+                        }),
+                        ("date_range_days".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of days to analyze (default: 30, max: 365)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["video_id".to_string()],
+                },
+            },
+            ClaudeTool {
+                name: "suggest_content_ideas".to_string(),
+                description: "Analyzes the user's YouTube channel performance and current trending topics to suggest data-driven content ideas that are likely to perform well. Provides 5-10 specific video ideas with rationale based on what's working for the channel and what's trending in the niche. READ-ONLY research tool. Parameters: channel_id (optional) - if not provided, uses user's primary channel, category (optional) - focus area like 'gaming', 'tutorial', 'vlog', num_ideas (optional) - number of ideas to generate (default 5).".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("channel_id".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Internal channel ID from database (optional - if not provided, uses user's first active channel)".to_string(),
+                            items: None,
+                        }),
+                        ("category".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Content category to focus on: 'gaming', 'tech', 'education', 'entertainment', 'music', etc.".to_string(),
+                            items: None,
+                        }),
+                        ("num_ideas".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of content ideas to generate (default: 5, max: 10)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec![],
+                },
+            },
+            ClaudeTool {
+                name: "search_youtube_trends".to_string(),
+                description: "Searches for trending YouTube videos in a specific category or by keyword to understand what content is performing well. Useful for competitive research and identifying content gaps. Returns video titles, view counts, engagement metrics, and channel information. READ-ONLY research tool. Parameters: query (optional) - search keywords, region_code (optional) - two-letter country code like 'US', 'GB', category (optional) - content category, max_results (optional) - max 50.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("query".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Search query/keywords (optional - if not provided, returns general trending)".to_string(),
+                            items: None,
+                        }),
+                        ("region_code".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Two-letter country code (ISO 3166-1 alpha-2): 'US', 'GB', 'CA', 'AU', etc. (default: 'US')".to_string(),
+                            items: None,
+                        }),
+                        ("category".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Content category: 'gaming', 'music', 'education', 'entertainment', 'sports', 'tech'".to_string(),
+                            items: None,
+                        }),
+                        ("max_results".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Maximum number of results to return (default: 10, max: 50)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec![],
+                },
+            },
+            ClaudeTool {
+                name: "search_youtube_channels".to_string(),
+                description: "Searches for YouTube channels by name or keywords. Useful for finding specific creators, competitors, or channels in a particular niche. Returns channel names, descriptions, subscriber counts, and channel IDs. READ-ONLY research tool. Parameters: query (required) - channel name or keywords to search for, max_results (optional) - max 50, order (optional) - 'relevance', 'viewCount', 'videoCount'.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("query".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Channel name or keywords to search for (e.g., 'MrBeast', 'chess tutorials', 'cooking channels')".to_string(),
+                            items: None,
+                        }),
+                        ("max_results".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Maximum number of channels to return (default: 10, max: 50)".to_string(),
+                            items: None,
+                        }),
+                        ("order".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Sort order: 'relevance' (default), 'viewCount', 'videoCount'".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["query".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "auto_correct".to_string(),
+                description: "Automatically fixes exposure, contrast and white balance. Samples frames to measure luma histogram spread and gray-world color cast, applies the correction, and returns a before/after split-frame preview image".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the corrected video".to_string(),
+                            items: None,
+                        }),
+                        ("strength".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "How much of the measured correction to apply (0.0-1.0, default: 0.8)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "remove_fillers".to_string(),
+                description: "Transcribes speech with word-level timing and cuts filler words (um, uh, like...) and long pauses, joining the cuts with micro-crossfades. Reports how much runtime was saved".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the tightened video".to_string(),
+                            items: None,
+                        }),
+                        ("aggressiveness".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "How much to cut: 'low' (only um/uh, pauses over 1.5s), 'medium' (default, common fillers, pauses over 1s), 'high' (also like/basically/actually, pauses over 0.6s)".to_string(),
+                            items: None,
+                        }),
+                        ("crossfade_ms".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Crossfade length at each cut in milliseconds (default: 30)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "remove_silence".to_string(),
+                description: "Remove dead air: detect silent or low-energy stretches of a video's audio and jump-cut them out, keeping a little padding around speech and joining the cuts with micro-crossfades. The classic cleanup for talking-head videos; works without a transcript. Use remove_fillers instead to also cut 'um'/'uh'.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path for the jump-cut video".to_string(),
+                            items: None,
+                        }),
+                        ("threshold_db".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Level below which audio counts as silence, in dB (default -35; -30 cuts more, -45 only near-silence)".to_string(),
+                            items: None,
+                        }),
+                        ("min_silence_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Shortest quiet stretch that gets cut, in seconds (default 0.6)".to_string(),
+                            items: None,
+                        }),
+                        ("padding_ms".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Silence kept on each side of the sound around a cut, in milliseconds (default 150)".to_string(),
+                            items: None,
+                        }),
+                        ("crossfade_ms".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Crossfade at each cut in milliseconds (default 30)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "censor_profanity".to_string(),
+                description: "Finds flagged words (profanity by default, or a custom word list) in the speech using word-level timestamps and bleeps, mutes or cuts them. Returns a report of every censored timestamp".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video or audio file to censor".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path for the censored file".to_string(),
+                            items: None,
+                        }),
+                        ("mode".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "bleep (replace with a tone, default), mute (silence) or cut (remove the word from the video)".to_string(),
+                            items: None,
+                        }),
+                        ("words".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Custom words to censor instead of the built-in profanity list; common inflections are matched too".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "Word to censor".to_string(),
+                                items: None,
+                            })),
+                        }),
+                        ("padding_ms".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Extra time censored around each word in milliseconds (default 50)".to_string(),
+                            items: None,
+                        }),
+                        ("bleep_frequency".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Bleep tone frequency in Hz (default 1000)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "transcribe_speakers".to_string(),
+                description: "Transcribes a video or audio file with speaker diarization and returns a speaker-labelled transcript (Speaker 1, Speaker 2, ...) with timestamps and talk time per speaker. Use this before per-speaker edits and to work out who is who (e.g. which speaker is Sarah)".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video or audio file to transcribe".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "edit_by_speaker".to_string(),
+                description: "Edits a video by speaker using the diarized transcript: keep only one speaker's turns (e.g. a clip of only Sarah's answers), remove them, or mute them. With interruptions_only, only that speaker's short interjections while someone else is talking are affected (e.g. 'mute speaker 2's interruptions')".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Video or audio file to edit".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path for the edited file".to_string(),
+                            items: None,
+                        }),
+                        ("speaker".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Speaker label from transcribe_speakers, e.g. 'Speaker 2'".to_string(),
+                            items: None,
+                        }),
+                        ("action".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "keep (only this speaker, default), remove (cut this speaker) or mute (silence this speaker, keep the picture)".to_string(),
+                            items: None,
+                        }),
+                        ("interruptions_only".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Only affect short turns where this speaker cuts in on someone else (default false)".to_string(),
+                            items: None,
+                        }),
+                        ("max_turn_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Longest turn counted as an interruption (default 5)".to_string(),
+                            items: None,
+                        }),
+                        ("padding_ms".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Extra time kept/cut around each turn in milliseconds (default 250)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string(), "speaker".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "select_music".to_string(),
+                description: "One-step background music: reads the video's transcript to judge its mood, picks a matching track from the user's music library or generates one with Eleven Music at the right length, and mixes it under the video with ducking so speech stays clear".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the input video file".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the video with music".to_string(),
+                            items: None,
+                        }),
+                        ("source".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "auto (library first, otherwise generate; default), library (only the user's music library) or generate (always create a new track)".to_string(),
+                            items: None,
+                        }),
+                        ("mood".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Override the detected mood, e.g. upbeat, calm, inspiring, dramatic, sad, corporate".to_string(),
+                            items: None,
+                        }),
+                        ("music_volume".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Music level before ducking, 0.02-1.0 (default 0.2)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "compose_screencast".to_string(),
+                description: "Composes a screen recording and a webcam recording into a standard tutorial layout: 'bubble' (round webcam in a corner), 'pip' (rounded picture-in-picture window with drop shadow) or 'side_by_side' (screen plus a camera column). Preset positions and sizes, optional sync offset and audio source selection".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("screen_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Screen recording (sets the output length)".to_string(),
+                            items: None,
+                        }),
+                        ("camera_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Webcam/camera recording".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the composed video".to_string(),
+                            items: None,
+                        }),
+                        ("layout".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "bubble (default), pip or side_by_side".to_string(),
+                            items: None,
+                        }),
+                        ("position".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Corner for bubble/pip: top_left, top_right, bottom_left, bottom_right (default)".to_string(),
+                            items: None,
+                        }),
+                        ("size".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Camera size preset: small, medium (default) or large. A number is read as a fraction of the frame (height for bubble, width otherwise)".to_string(),
+                            items: None,
+                        }),
+                        ("shadow".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Drop shadow under the bubble/pip camera (default: true)".to_string(),
+                            items: None,
+                        }),
+                        ("corner_radius".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Corner radius of the pip window in pixels (default: 24)".to_string(),
+                            items: None,
+                        }),
+                        ("camera_offset".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Seconds to delay the camera to sync with the screen; negative trims the camera start (default: 0)".to_string(),
+                            items: None,
+                        }),
+                        ("audio".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Audio source: mix (default), screen or camera".to_string(),
+                            items: None,
+                        }),
+                        ("background_color".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Fill color around the screen when aspect ratios differ (default: #111111)".to_string(),
+                            items: None,
+                        }),
+                        ("width".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output width (default: screen width, max 1920)".to_string(),
+                            items: None,
+                        }),
+                        ("height".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output height (default: matches the screen aspect ratio)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["screen_file".to_string(), "camera_file".to_string(), "output_file".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "create_blank_video".to_string(),
-                description: "Creates a blank video with specified color, duration, and dimensions".to_string(),
+                name: "create_lyric_video".to_string(),
+                description: "Creates a karaoke/lyric video: renders word-synced highlighted lyrics over a background video or image with the audio track as soundtrack. Lyrics are optional - without them the vocals are transcribed. Pairs well with generate_music".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
+                        ("audio_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the song/audio track".to_string(),
+                            items: None,
+                        }),
+                        ("background_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to a background video (looped) or image".to_string(),
+                            items: None,
+                        }),
                         ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to save the blank video".to_string(),
+                            description: "Path to save the lyric video".to_string(),
                             items: None,
                         }),
-                        ("duration".to_string(), PropertyDefinition {
+                        ("lyrics".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional lyrics text, one line per display line. Word timing is aligned to the vocals when possible".to_string(),
+                            items: None,
+                        }),
+                        ("highlight_color".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Color of sung words (name or #RRGGBB, default: yellow)".to_string(),
+                            items: None,
+                        }),
+                        ("text_color".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Color of upcoming words (name or #RRGGBB, default: white)".to_string(),
+                            items: None,
+                        }),
+                        ("font_size".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Duration of the blank video in seconds".to_string(),
+                            description: "Font size in pixels (default: 64)".to_string(),
+                            items: None,
+                        }),
+                        ("width".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output width (default: 1920)".to_string(),
+                            items: None,
+                        }),
+                        ("height".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output height (default: 1080)".to_string(),
+                            items: None,
+                        }),
+                        ("words_per_line".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Max words per line when lyrics come from transcription (default: 6)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["audio_file".to_string(), "background_file".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "generate_quiz_video".to_string(),
+                description: "Generates a complete trivia/quiz video from a topic: writes multiple-choice questions, renders question cards with a countdown timer and answer reveal, narrates each question with text-to-speech and adds optional background music".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("topic".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Quiz topic, e.g. 'world capitals' or '90s movies'".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the quiz video".to_string(),
+                            items: None,
+                        }),
+                        ("question_count".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Number of questions (default: 5, max: 20)".to_string(),
+                            items: None,
+                        }),
+                        ("countdown_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Seconds to think before the answer is revealed (default: 5)".to_string(),
+                            items: None,
+                        }),
+                        ("reveal_seconds".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Seconds the answer stays on screen (default: 3)".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Narration voice name (default: Rachel)".to_string(),
+                            items: None,
+                        }),
+                        ("music_prompt".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional prompt for generated background music, e.g. 'upbeat game show music'".to_string(),
+                            items: None,
+                        }),
+                        ("background_color".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Card background color (name or #RRGGBB, default: #1a1a2e)".to_string(),
+                            items: None,
+                        }),
+                        ("width".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output width (default: 1080)".to_string(),
+                            items: None,
+                        }),
+                        ("height".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output height (default: 1920)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["topic".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "generate_video_from_article".to_string(),
+                description: "Turns a news article or blog post URL into a narrated 1-3 minute video: summarizes the article into a script, generates voiceover, sources matching B-roll from Pexels, burns captions and adds source attribution".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("url".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Article or blog post URL".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the video".to_string(),
+                            items: None,
+                        }),
+                        ("target_duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Target length in seconds (60-180, default: 90)".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Narration voice name (default: Rachel)".to_string(),
+                            items: None,
+                        }),
+                        ("captions".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Burn captions into the video (default: true)".to_string(),
+                            items: None,
+                        }),
+                        ("presenter_image".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional portrait image turned into a talking-head presenter shown picture-in-picture over the B-roll (requires DID_API_KEY)".to_string(),
+                            items: None,
+                        }),
+                        ("width".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output width (default: 1920)".to_string(),
+                            items: None,
+                        }),
+                        ("height".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Output height (default: 1080)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["url".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "generate_talking_head".to_string(),
+                description: "Animates a still portrait image into a lip-synced talking-head presenter clip. The presenter speaks a script (voiced with Eleven Labs when available) or lip-syncs to an existing narration audio file. Use it for faceless educational channels and composite the clip over B-roll".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("portrait_image".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Front-facing portrait image of the presenter".to_string(),
+                            items: None,
+                        }),
+                        ("script".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "What the presenter says (ignored when audio_file is given)".to_string(),
+                            items: None,
+                        }),
+                        ("audio_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Existing narration to lip-sync to instead of a script".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Eleven Labs voice name for the script (default: Rachel)".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the presenter clip (MP4)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["portrait_image".to_string(), "output_file".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "export_localized".to_string(),
+                description: "Exports per-language variants of a finished edit in one step: translates captions (from an SRT or by transcribing the audio), optionally dubs the speech, localizes a title card, and organizes the outputs into one folder per locale".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the master edit".to_string(),
+                            items: None,
+                        }),
+                        ("languages".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Target languages, e.g. ['es', 'fr', 'de'] or ['Spanish', 'Japanese']".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "Language code or name".to_string(),
+                                items: None,
+                            })),
+                        }),
+                        ("captions_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional source SRT. When omitted the audio is transcribed".to_string(),
+                            items: None,
+                        }),
+                        ("burn_captions".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Burn translated captions into each variant (default: true). SRT files are always written".to_string(),
+                            items: None,
+                        }),
+                        ("dub".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Generate dubbed narration in each language over the ducked original audio (default: false)".to_string(),
+                            items: None,
+                        }),
+                        ("voice".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Dubbing voice name (default: Rachel)".to_string(),
+                            items: None,
+                        }),
+                        ("title".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional title card text, translated per locale and shown at the start".to_string(),
+                            items: None,
+                        }),
+                        ("title_duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Seconds the title card stays on screen (default: 3)".to_string(),
+                            items: None,
+                        }),
+                        ("output_dir".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Folder name for the locale folders (default: <input name>_localized)".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "languages".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "deliver_output".to_string(),
+                description: "Delivers a finished output file to one of the user's configured delivery targets (SFTP, FTP or S3 bucket) with checksum verification, and returns a delivery receipt. Use list_delivery_targets first if unsure of the target name".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("file_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path of the output file to deliver (must be in outputs/)".to_string(),
+                            items: None,
+                        }),
+                        ("target".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Name of the delivery target".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["file_path".to_string(), "target".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "list_delivery_targets".to_string(),
+                description: "Lists the user's configured delivery targets (name, protocol, host, remote path)".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::new(),
+                    required: vec![],
+                },
+            },
+
+            ClaudeTool {
+                name: "export_with_preset".to_string(),
+                description: "Render a video with a named export preset: the user's own preset (container, codecs, CRF/bitrate, resolution, loudness target, burned captions) or a built-in platform preset (youtube, instagram, tiktok, twitter, facebook, linkedin). A user preset with a platform's name overrides the built-in one.".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("input_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to the video to export".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Output path; the extension is set from the preset's container".to_string(),
+                            items: None,
+                        }),
+                        ("preset".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Preset name, e.g. 'youtube' or a custom preset from list_export_presets".to_string(),
+                            items: None,
+                        }),
+                        ("captions_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Optional SRT to burn in when the preset burns captions; otherwise the video is transcribed".to_string(),
+                            items: None,
+                        }),
+                    ]),
+                    required: vec!["input_file".to_string(), "output_file".to_string(), "preset".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "list_export_presets".to_string(),
+                description: "List the user's named export presets and the built-in platform presets".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::new(),
+                    required: vec![],
+                },
+            },
+
+            ClaudeTool {
+                name: "create_review_link".to_string(),
+                description: "Creates a public, expiring review link for a finished output so clients without an account can watch it and leave timestamped comments. Returns the shareable URL".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("file_path".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path of the output file to share (must be in outputs/)".to_string(),
                             items: None,
                         }),
-                        ("width".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Video width in pixels".to_string(),
+                        ("title".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Title shown on the review page (defaults to the file name)".to_string(),
                             items: None,
                         }),
-                        ("height".to_string(), PropertyDefinition {
+                        ("expires_in_hours".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Video height in pixels".to_string(),
+                            description: "Hours until the link expires (default 168, max 2160)".to_string(),
                             items: None,
                         }),
-                        ("color".to_string(), PropertyDefinition {
-                            prop_type: "string".to_string(),
-                            description: "Background color (hex code or color name, default: black)".to_string(),
+                        ("allow_comments".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Whether reviewers can comment (default true)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["output_file".to_string(), "duration".to_string(), "width".to_string(), "height".to_string()],
+                    required: vec!["file_path".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "pexels_download_video".to_string(),
-                description: "Downloads a video from Pexels given the video file URL".to_string(),
+                name: "rerender_region".to_string(),
+                description: "Incremental re-render: when only part of a finished video changes (e.g. a new caption at 02:10), re-applies that one edit to just the affected region, aligned to keyframes, and splices it back in. Everything outside the region is copied losslessly, so it is much faster than re-rendering the whole video. Works on H.264/HEVC videos with AAC audio".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("video_url".to_string(), PropertyDefinition {
+                        ("input_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Pexels video file URL (from pexels_search results)".to_string(),
+                            description: "The previously rendered video".to_string(),
                             items: None,
                         }),
-                        ("output_file".to_string(), PropertyDefinition {
+                        ("start_time".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Local path to save the downloaded video".to_string(),
+                            description: "Start of the changed region: seconds, timecode (00:01:12.500), frame number (300f) or percentage of the video (25%)".to_string(),
                             items: None,
                         }),
-                        ("quality".to_string(), PropertyDefinition {
+                        ("end_time".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Video quality: 'hd', 'sd', 'low' (optional)".to_string(),
+                            description: "End of the changed region, in the same formats".to_string(),
+                            items: None,
+                        }),
+                        ("edit".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "The edit to re-apply as a JSON object string with the tool name and its arguments, without input_file/output_file. Times are full-video times, e.g. '{\"tool\": \"add_text_overlay\", \"text\": \"New caption\", \"start_time\": 130, \"end_time\": 134}'".to_string(),
+                            items: None,
+                        }),
+                        ("output_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path to save the updated video".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["video_url".to_string(), "output_file".to_string()],
+                    required: vec!["input_file".to_string(), "start_time".to_string(), "end_time".to_string(), "edit".to_string(), "output_file".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "pexels_download_photo".to_string(),
-                description: "Downloads a photo from Pexels given the photo URL".to_string(),
+                name: "preview_effect_chain".to_string(),
+                description: "Experiment mode: renders a quick low-resolution sample (up to 10 seconds) of a proposed chain of effects so the user can iterate before committing to a full-length render. Output is a scratch file clearly stamped PREVIEW and is never saved as a deliverable; after the user approves, apply the same steps to the full video with the regular tools".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("photo_url".to_string(), PropertyDefinition {
+                        ("input_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Pexels photo URL (from pexels_search results)".to_string(),
+                            description: "Video to sample".to_string(),
                             items: None,
                         }),
-                        ("output_file".to_string(), PropertyDefinition {
+                        ("steps".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Effect chain in order. Each item is a JSON object string with the tool name and its arguments, without input_file/output_file, e.g. '{\"tool\": \"apply_filter\", \"filter_type\": \"vintage\"}'".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "One step as a JSON object string".to_string(),
+                                items: None,
+                            })),
+                        }),
+                        ("start_time".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Local path to save the downloaded photo".to_string(),
+                            description: "Where the sample starts: seconds, timecode (00:01:12.500), frame number (300f) or percentage of the video (25%) (default: 0)".to_string(),
                             items: None,
                         }),
-                        ("size".to_string(), PropertyDefinition {
-                            prop_type: "string".to_string(),
-                            description: "Photo size: 'original', 'large', 'medium', 'small' (optional)".to_string(),
+                        ("duration".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Sample length in seconds (default and max: 10)".to_string(),
                             items: None,
                         }),
-                    ]),
-                    required: vec!["photo_url".to_string(), "output_file".to_string()],
-                },
-            },
-            ClaudeTool {
-                name: "pexels_get_trending".to_string(),
-                description: "Gets trending/popular videos from Pexels without needing a search query".to_string(),
-                input_schema: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: HashMap::from([
-                        ("per_page".to_string(), PropertyDefinition {
+                        ("height".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Number of results to return (1-80, default: 15)".to_string(),
+                            description: "Preview height in pixels (default: 360)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec![],
+                    required: vec!["input_file".to_string(), "steps".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "pexels_get_curated".to_string(),
-                description: "Gets curated/hand-picked photos from Pexels without needing a search query".to_string(),
+                name: "undo_last_edit".to_string(),
+                description: "Undoes the latest edit in this chat (or the last few): the output it made is set aside and the output before it becomes the current one to continue from, without re-uploading. Use it when the user says \"undo that\", \"go back\" or \"that was better before\". The undone files are kept so redo_edit can bring them back until the next edit. Not for projects; use edit_project with action undo there".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("per_page".to_string(), PropertyDefinition {
+                        ("steps".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Number of results to return (1-80, default: 15)".to_string(),
+                            description: "How many edits to undo, newest first (default 1, at most 10)".to_string(),
                             items: None,
                         }),
                     ]),
                     required: vec![],
                 },
             },
+
             ClaudeTool {
-                name: "view_video".to_string(),
-                description: "Views/analyzes a video by retrieving its vectorized embeddings from the database. This allows you to 'see' what's in a video without re-processing it. Use this to understand video content, verify edits, or check what a previously generated video contains. Returns detailed frame-by-frame analysis and overall summary.".to_string(),
+                name: "redo_edit".to_string(),
+                description: "Brings back edits undone with undo_last_edit, most recently undone first; the redone output becomes the current one. Only possible until a new edit is made".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("video_path".to_string(), PropertyDefinition {
-                            prop_type: "string".to_string(),
-                            description: "Path to the video file to view/analyze (e.g., 'outputs/edited_video.mp4')".to_string(),
+                        ("steps".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "How many undone edits to bring back (default 1, at most 10)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["video_path".to_string()],
+                    required: vec![],
                 },
             },
+
             ClaudeTool {
-                name: "review_video".to_string(),
-                description: "Reviews an output video to verify it meets the user's original requirements. Use this in the final stage of video editing/generation to confirm quality before presenting to the user. Compares the video's vectorized analysis against the user's request to check if edits were applied correctly.".to_string(),
+                name: "batch_process".to_string(),
+                description: "Applies one edit to many videos at once as a single background job: a watermark (add_overlay or add_text_overlay), a resize to 1080p (resize_video), a volume change (adjust_volume), compression (compress_video) or any other single-input edit. Runs on every video uploaded to this chat by default, or on the files or folder given. Progress is reported per file, and the results are zipped together (results.zip next to the outputs) when the job finishes. Use it when the user wants the same change on several videos instead of calling the tool once per file".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("video_path".to_string(), PropertyDefinition {
+                        ("operation".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to the output video to review".to_string(),
+                            description: "The tool to apply to each video, e.g. resize_video, add_overlay, adjust_volume, compress_video".to_string(),
                             items: None,
                         }),
-                        ("original_request".to_string(), PropertyDefinition {
+                        ("arguments".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "The original user request/requirements to verify against".to_string(),
+                            description: "The tool's arguments as a JSON object string, without input_file or output_file (set per video), e.g. {\"width\": 1920, \"height\": 1080}".to_string(),
                             items: None,
                         }),
-                        ("expected_features".to_string(), PropertyDefinition {
+                        ("files".to_string(), PropertyDefinition {
                             prop_type: "array".to_string(),
-                            description: "List of expected features that should be present (e.g., ['grayscale filter', 'text overlay', 'trimmed to 10s'])".to_string(),
+                            description: "Videos to process; defaults to every video uploaded to this chat".to_string(),
                             items: Some(Box::new(PropertyDefinition {
                                 prop_type: "string".to_string(),
-                                description: "Expected feature".to_string(),
+                                description: "A video file path".to_string(),
                                 items: None,
                             })),
                         }),
+                        ("folder".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "A folder under uploads/ or outputs/ whose videos are processed instead of the chat's uploads".to_string(),
+                            items: None,
+                        }),
                     ]),
-                    required: vec!["video_path".to_string(), "original_request".to_string()],
+                    required: vec!["operation".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "view_image".to_string(),
-                description: "Views/analyzes an image file using AI vision. Use this to verify generated images, inspect stock photos from Pexels, or check overlay images before using them in videos. Returns detailed analysis of content, colors, composition, style, and suitability for video use.".to_string(),
+                name: "create_project".to_string(),
+                description: "Starts a project on a video: a non-destructive timeline where edits are recorded as an ordered list of operations instead of rendered one by one. Use it when the user wants to iterate on an edit, undo or reorder steps, or export the same edit at several qualities. Build the edit with edit_project and render it with export_project".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("image_path".to_string(), PropertyDefinition {
+                        ("name".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to the image file to view/analyze (e.g., 'outputs/generated_logo.png' or 'outputs/stock_photo.jpg')".to_string(),
+                            description: "Project name, unique in this session (e.g. 'client cut')".to_string(),
+                            items: None,
+                        }),
+                        ("source_file".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Path of the video the edit starts from".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["image_path".to_string()],
+                    required: vec!["name".to_string(), "source_file".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "generate_image".to_string(),
-                description: "Generates an image using Google's Imagen AI model based on a text prompt. Use this to create custom images, overlays, backgrounds, or any visual elements needed for video editing.".to_string(),
+                name: "edit_project".to_string(),
+                description: "Changes a project's edit list without rendering anything: add, remove or move an operation, undo or redo the last change, or show the list. Operations are single-input tools (trim_video, add_text_overlay, apply_filter, add_overlay, adjust_color, add_subtitles, burn_timecode, resize_video, crop_video, rotate_video, adjust_speed, flip_video, scale_video, adjust_volume, fade_audio, add_audio, chroma_key, stabilize_video, auto_correct) applied in order on export".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("prompt".to_string(), PropertyDefinition {
+                        ("project".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Detailed text description of the image to generate".to_string(),
+                            description: "Name of the project".to_string(),
                             items: None,
                         }),
-                        ("output_file".to_string(), PropertyDefinition {
+                        ("action".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path where the generated image should be saved (e.g., 'outputs/generated_overlay.png')".to_string(),
+                            description: "add, remove, move, undo, redo or show".to_string(),
                             items: None,
                         }),
-                        ("width".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Image width in pixels (default: 1024)".to_string(),
+                        ("tool".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "For add: the operation's tool".to_string(),
                             items: None,
                         }),
-                        ("height".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Image height in pixels (default: 1024)".to_string(),
+                        ("args".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "For add: the tool's arguments as a JSON object string, without input_file/output_file, e.g. '{\"start_time\": 5, \"end_time\": 65}'".to_string(),
                             items: None,
                         }),
-                        ("aspect_ratio".to_string(), PropertyDefinition {
+                        ("note".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Aspect ratio: '1:1', '16:9', '9:16', '4:3' (optional, overrides width/height)".to_string(),
+                            description: "For add: why the operation is there, shown in the edit list".to_string(),
+                            items: None,
+                        }),
+                        ("position".to_string(), PropertyDefinition {
+                            prop_type: "integer".to_string(),
+                            description: "1-based position: where add inserts (default: the end), which operation remove drops or move takes".to_string(),
+                            items: None,
+                        }),
+                        ("to".to_string(), PropertyDefinition {
+                            prop_type: "integer".to_string(),
+                            description: "For move: the position the operation ends up at".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["prompt".to_string(), "output_file".to_string()],
+                    required: vec!["project".to_string(), "action".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "auto_generate_video".to_string(),
-                description: "Orchestrates automatic video generation from a topic/prompt. This high-level tool searches Pexels for stock footage, generates images, downloads clips, merges them, adds text overlays, music, and exports a complete video. Perfect for creating videos from scratch.".to_string(),
+                name: "export_project".to_string(),
+                description: "Renders a project's current edit list from its source video, optionally encoded with an export preset (user-defined or built-in platform preset like youtube or tiktok). Exporting the same revision again at another quality reuses the edited master and only re-encodes".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("topic".to_string(), PropertyDefinition {
+                        ("project".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Topic or description of the video to create (e.g., 'A motivational video about success')".to_string(),
+                            description: "Name of the project".to_string(),
+                            items: None,
+                        }),
+                        ("preset".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Export preset to encode with (default: keep the edit's own encoding)".to_string(),
                             items: None,
                         }),
+                    ]),
+                    required: vec!["project".to_string()],
+                },
+            },
+
+            ClaudeTool {
+                name: "compare_versions".to_string(),
+                description: "Renders a comparison video of two versions of an output, either side-by-side or as an animated wipe. Pick versions from an output's history (output_id + version numbers, defaults to first vs latest) or pass two files directly".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
                         ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path where the final video should be saved".to_string(),
+                            description: "Path for the comparison video".to_string(),
                             items: None,
                         }),
-                        ("duration".to_string(), PropertyDefinition {
+                        ("output_id".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Target video duration in seconds (default: 30)".to_string(),
+                            description: "ID of any output in the lineage to compare versions of".to_string(),
                             items: None,
                         }),
-                        ("style".to_string(), PropertyDefinition {
+                        ("version_a".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Version shown on the left / underneath (default: first version)".to_string(),
+                            items: None,
+                        }),
+                        ("version_b".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Version shown on the right / revealed by the wipe (default: latest version)".to_string(),
+                            items: None,
+                        }),
+                        ("file_a".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Video style: 'cinematic', 'minimal', 'energetic', 'calm', 'corporate' (default: 'cinematic')".to_string(),
+                            description: "First video file (instead of output_id)".to_string(),
                             items: None,
                         }),
-                        ("include_text_overlays".to_string(), PropertyDefinition {
-                            prop_type: "boolean".to_string(),
-                            description: "Whether to add text overlays with key messages (default: true)".to_string(),
+                        ("file_b".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Second video file (instead of output_id)".to_string(),
                             items: None,
                         }),
-                        ("include_music".to_string(), PropertyDefinition {
-                            prop_type: "boolean".to_string(),
-                            description: "Whether to add background music (default: false)".to_string(),
+                        ("mode".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "side_by_side (default) or wipe".to_string(),
                             items: None,
                         }),
-                        ("num_clips".to_string(), PropertyDefinition {
+                        ("wipe_period".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Number of video clips to use from Pexels (default: 3-5 based on duration)".to_string(),
+                            description: "Seconds for one full wipe sweep (default 6)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["topic".to_string(), "output_file".to_string()],
+                    required: vec!["output_file".to_string()],
                 },
             },
-            // Chat title management tool
+
             ClaudeTool {
-                name: "set_chat_title".to_string(),
-                description: "Sets a descriptive title for the current chat session. Use this to give the conversation a meaningful title based on the user's request or the work being done.".to_string(),
+                name: "search_library".to_string(),
+                description: "Searches the user's asset library (logos, intros, outros, music, clips, images, sound effects saved across all sessions). Returns library references like library:<name> that can be passed as any file argument".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("title".to_string(), PropertyDefinition {
+                        ("query".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "A concise, descriptive title for this chat session (max 100 characters)".to_string(),
+                            description: "Text to match against asset names, descriptions, tags and automatic vision tags, e.g. 'drone shot over water' (empty lists everything)".to_string(),
+                            items: None,
+                        }),
+                        ("tag".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Only assets with this exact tag".to_string(),
+                            items: None,
+                        }),
+                        ("kind".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Only assets of this kind: logo, intro, outro, music, clip, image, sfx, other".to_string(),
+                            items: None,
+                        }),
+                        ("has_faces".to_string(), PropertyDefinition {
+                            prop_type: "boolean".to_string(),
+                            description: "Only assets where people's faces are (true) or are not (false) visible".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["title".to_string()],
+                    required: vec![],
                 },
             },
 
-            // =====================================================================
-            // YOUTUBE INTEGRATION TOOLS (READ-ONLY RESEARCH & OPTIMIZATION)
-            // =====================================================================
-
             ClaudeTool {
-                name: "optimize_youtube_metadata".to_string(),
-                description: "Analyzes a video file and generates SEO-optimized YouTube metadata (title, description, tags) to maximize discoverability and engagement. Uses AI to understand video content and suggest compelling, keyword-rich metadata. Returns suggestions only - does not upload or modify anything. Parameters: video_path (required) - path to video file, target_audience (optional) - intended audience like 'gaming', 'education', 'vlog', style (optional) - 'clickbait', 'professional', or 'casual'.".to_string(),
+                name: "list_files".to_string(),
+                description: "Lists this session's uploaded files and generated outputs, newest first, with the exact paths to pass to editing tools. Filter by source, kind or name and page with offset; use it whenever the file you need isn't listed in the context".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("video_path".to_string(), PropertyDefinition {
+                        ("source".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Path to the video file to analyze for metadata optimization".to_string(),
+                            description: "upload, output or all (default all)".to_string(),
                             items: None,
                         }),
-                        ("target_audience".to_string(), PropertyDefinition {
+                        ("kind".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Target audience type: 'gaming', 'education', 'vlog', 'entertainment', 'tech', 'music', etc.".to_string(),
+                            description: "Only files of this kind: video, audio, image or document".to_string(),
                             items: None,
                         }),
-                        ("style".to_string(), PropertyDefinition {
+                        ("name".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Metadata style: 'clickbait' (attention-grabbing), 'professional' (formal), 'casual' (conversational)".to_string(),
+                            description: "Only files whose name contains this text".to_string(),
                             items: None,
                         }),
-                    ]),
-                    required: vec!["video_path".to_string()],
-                },
-            },
-            ClaudeTool {
-                name: "analyze_youtube_performance".to_string(),
-                description: "Fetches analytics data for a YouTube video and provides AI-powered insights on performance, audience engagement, and optimization opportunities. Analyzes views, watch time, likes, comments, shares, and subscriber gain/loss. Identifies strengths and areas for improvement. READ-ONLY tool - does not modify anything. Parameters: video_id (required) - YouTube video ID, date_range (optional) - number of days to analyze (default 30).".to_string(),
-                input_schema: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: HashMap::from([
-                        ("video_id".to_string(), PropertyDefinition {
-                            prop_type: "string".to_string(),
-                            description: "YouTube video ID (the alphanumeric code from youtube.com/watch?v=VIDEO_ID)".to_string(),
+                        ("limit".to_string(), PropertyDefinition {
+                            prop_type: "number".to_string(),
+                            description: "Files per page (default 25, max 100)".to_string(),
                             items: None,
                         }),
-                        ("date_range_days".to_string(), PropertyDefinition {
+                        ("offset".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Number of days to analyze (default: 30, max: 365)".to_string(),
+                            description: "Files to skip, for the next page".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["video_id".to_string()],
+                    required: vec![],
                 },
             },
+
             ClaudeTool {
-                name: "suggest_content_ideas".to_string(),
-                description: "Analyzes the user's YouTube channel performance and current trending topics to suggest data-driven content ideas that are likely to perform well. Provides 5-10 specific video ideas with rationale based on what's working for the channel and what's trending in the niche. READ-ONLY research tool. Parameters: channel_id (optional) - if not provided, uses user's primary channel, category (optional) - focus area like 'gaming', 'tutorial', 'vlog', num_ideas (optional) - number of ideas to generate (default 5).".to_string(),
+                name: "find_asset".to_string(),
+                description: "Finds this session's uploads and outputs matching a description, using file names, automatic vision tags and the operations that produced outputs, plus matching assets from the user's library. Returns exact paths to pass to editing tools".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("channel_id".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Internal channel ID from database (optional - if not provided, uses user's first active channel)".to_string(),
-                            items: None,
-                        }),
-                        ("category".to_string(), PropertyDefinition {
+                        ("description".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Content category to focus on: 'gaming', 'tech', 'education', 'entertainment', 'music', etc.".to_string(),
+                            description: "What the file shows or is, e.g. 'drone shot of the beach', 'the trimmed intro', 'podcast audio'".to_string(),
                             items: None,
                         }),
-                        ("num_ideas".to_string(), PropertyDefinition {
+                        ("limit".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Number of content ideas to generate (default: 5, max: 10)".to_string(),
+                            description: "Most files to return (default 10, max 25)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec![],
+                    required: vec!["description".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "search_youtube_trends".to_string(),
-                description: "Searches for trending YouTube videos in a specific category or by keyword to understand what content is performing well. Useful for competitive research and identifying content gaps. Returns video titles, view counts, engagement metrics, and channel information. READ-ONLY research tool. Parameters: query (optional) - search keywords, region_code (optional) - two-letter country code like 'US', 'GB', category (optional) - content category, max_results (optional) - max 50.".to_string(),
+                name: "add_to_library".to_string(),
+                description: "Saves an uploaded file or finished output to the user's asset library so it can be reused from any session as library:<name>".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("query".to_string(), PropertyDefinition {
+                        ("file_path".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Search query/keywords (optional - if not provided, returns general trending)".to_string(),
+                            description: "Path of the file to save (must be in uploads/ or outputs/)".to_string(),
                             items: None,
                         }),
-                        ("region_code".to_string(), PropertyDefinition {
+                        ("name".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Two-letter country code (ISO 3166-1 alpha-2): 'US', 'GB', 'CA', 'AU', etc. (default: 'US')".to_string(),
+                            description: "Unique name for the asset, e.g. 'Brand Logo'".to_string(),
                             items: None,
                         }),
-                        ("category".to_string(), PropertyDefinition {
+                        ("kind".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Content category: 'gaming', 'music', 'education', 'entertainment', 'sports', 'tech'".to_string(),
+                            description: "logo, intro, outro, music, clip, image, sfx or other (detected from the file type if omitted)".to_string(),
                             items: None,
                         }),
-                        ("max_results".to_string(), PropertyDefinition {
-                            prop_type: "number".to_string(),
-                            description: "Maximum number of results to return (default: 10, max: 50)".to_string(),
+                        ("tags".to_string(), PropertyDefinition {
+                            prop_type: "array".to_string(),
+                            description: "Tags for searching".to_string(),
+                            items: Some(Box::new(PropertyDefinition {
+                                prop_type: "string".to_string(),
+                                description: "Tag".to_string(),
+                                items: None,
+                            })),
+                        }),
+                        ("description".to_string(), PropertyDefinition {
+                            prop_type: "string".to_string(),
+                            description: "Short description of the asset".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec![],
+                    required: vec!["file_path".to_string(), "name".to_string()],
                 },
             },
+
             ClaudeTool {
-                name: "search_youtube_channels".to_string(),
-                description: "Searches for YouTube channels by name or keywords. Useful for finding specific creators, competitors, or channels in a particular niche. Returns channel names, descriptions, subscriber counts, and channel IDs. READ-ONLY research tool. Parameters: query (required) - channel name or keywords to search for, max_results (optional) - max 50, order (optional) - 'relevance', 'viewCount', 'videoCount'.".to_string(),
+                name: "clip_live_stream".to_string(),
+                description: "Clips the most recent part of the live RTMP stream currently running in this chat session (e.g. 'clip the last 60 seconds') while the stream continues. The clip is saved to outputs/ and can be published or delivered immediately".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
-                        ("query".to_string(), PropertyDefinition {
-                            prop_type: "string".to_string(),
-                            description: "Channel name or keywords to search for (e.g., 'MrBeast', 'chess tutorials', 'cooking channels')".to_string(),
-                            items: None,
-                        }),
-                        ("max_results".to_string(), PropertyDefinition {
+                        ("seconds".to_string(), PropertyDefinition {
                             prop_type: "number".to_string(),
-                            description: "Maximum number of channels to return (default: 10, max: 50)".to_string(),
+                            description: "How many seconds back from the live edge to clip (1-600)".to_string(),
                             items: None,
                         }),
-                        ("order".to_string(), PropertyDefinition {
+                        ("output_file".to_string(), PropertyDefinition {
                             prop_type: "string".to_string(),
-                            description: "Sort order: 'relevance' (default), 'viewCount', 'videoCount'".to_string(),
+                            description: "Output file name (optional, defaults to live_<id>_<timestamp>.mp4)".to_string(),
                             items: None,
                         }),
                     ]),
-                    required: vec!["query".to_string()],
+                    required: vec!["seconds".to_string()],
                 },
             },
 
@@ -1,6 +1,7 @@
 // AI-powered viral clip identification and extraction
 
-use crate::clipping::models::{ClipCandidate, ClippingConfig, ReviewResult};
+use crate::clipping::{captions, fingerprint};
+use crate::clipping::models::{ClipCandidate, ClipMetadataPolicy, ClippingConfig, ReviewResult};
 use crate::services::VideoVectorizationService;
 use crate::AppState;
 use std::sync::Arc;
@@ -37,26 +38,67 @@ impl AiClipper {
 
         tracing::info!("Found {} clip candidates", clip_candidates.len());
 
+        // Fingerprints of clips this channel already published, plus those accepted in this run
+        let mut known_fingerprints = match config.destination_channel_id {
+            Some(channel_id) => self.load_published_fingerprints(channel_id).await,
+            None => Vec::new(),
+        };
+
+        // Scene changes, so clips start and end on a cut rather than mid-shot; a clip's ends move
+        // at most SCENE_SNAP_SECONDS to land on one
+        const SCENE_THRESHOLD: f64 = 0.3;
+        const SCENE_SNAP_SECONDS: f64 = 2.0;
+        let scene_video = video_path.to_string();
+        let scene_changes = match tokio::task::spawn_blocking(move || crate::export::scene_changes(&scene_video, SCENE_THRESHOLD)).await {
+            Ok(Ok(changes)) => changes,
+            Ok(Err(e)) => {
+                tracing::warn!("Scene detection failed, cutting clips at the AI's timestamps: {}", e);
+                Vec::new()
+            }
+            Err(e) => {
+                tracing::warn!("Scene detection task failed: {}", e);
+                Vec::new()
+            }
+        };
+
         // Step 3: Extract each clip using trim_video
         let mut extracted_clips = Vec::new();
         for (index, candidate) in clip_candidates.iter().enumerate() {
             let clip_path = format!("outputs/clip_{}_{}.mp4", job_id, index + 1);
+            let (start_time, end_time) = crate::advanced::snap_to_scenes(
+                candidate.start_time,
+                candidate.end_time,
+                &scene_changes,
+                SCENE_SNAP_SECONDS,
+                config.min_clip_duration_seconds as f64,
+                config.max_clip_duration_seconds as f64,
+            );
 
             tracing::info!(
                 "Extracting clip {} ({:.1}s - {:.1}s)",
                 index + 1,
-                candidate.start_time,
-                candidate.end_time
+                start_time,
+                end_time
             );
 
             // Use existing trim_video tool
             match crate::core::trim_video(
                 video_path,
                 &clip_path,
-                candidate.start_time,
-                candidate.end_time,
+                start_time,
+                end_time,
             ) {
                 Ok(_) => {
+                    // Skip clips that substantially overlap something already published
+                    let audio_fingerprint = Self::fingerprint_clip(&clip_path).await;
+                    if let Some(fp) = &audio_fingerprint {
+                        if let Some(duplicate_of) = Self::find_duplicate(fp, &known_fingerprints).await {
+                            tracing::warn!("Clip {} duplicates {}, skipping", index + 1, duplicate_of);
+                            let _ = tokio::fs::remove_file(&clip_path).await;
+                            continue;
+                        }
+                    }
+
                     // Vectorize the extracted clip for review
                     if let Err(e) = VideoVectorizationService::process_video_for_vectorization(
                         &clip_path,
@@ -78,18 +120,35 @@ impl AiClipper {
                         continue; // Skip failed clips
                     }
 
+                    // Burn the channel's caption style in before upload; keep the plain clip if that fails
+                    let mut clip_path = clip_path;
+                    if let Some(style) = &config.caption_style {
+                        match captions::burn_captions(&self.app_state, &clip_path, style).await {
+                            Ok(Some(captioned)) => {
+                                let _ = tokio::fs::remove_file(&clip_path).await;
+                                clip_path = captioned;
+                            }
+                            Ok(None) => {}
+                            Err(e) => tracing::warn!("Failed to caption clip {}: {}", index + 1, e),
+                        }
+                    }
+
                     extracted_clips.push(ExtractedClipData {
                         clip_number: (index + 1) as i32,
                         local_clip_path: clip_path,
-                        start_time_seconds: candidate.start_time,
-                        end_time_seconds: candidate.end_time,
-                        duration_seconds: candidate.end_time - candidate.start_time,
+                        start_time_seconds: start_time,
+                        end_time_seconds: end_time,
+                        duration_seconds: end_time - start_time,
                         ai_title: candidate.title.clone(),
                         ai_description: candidate.description.clone(),
                         ai_tags: candidate.tags.clone(),
                         ai_confidence_score: candidate.confidence,
                         viral_factors: candidate.viral_factors.clone(),
+                        audio_fingerprint: audio_fingerprint.as_deref().map(fingerprint::to_bytes),
                     });
+                    if let Some(fp) = audio_fingerprint {
+                        known_fingerprints.push((format!("clip {} of this run", index + 1), fp));
+                    }
                 }
                 Err(e) => {
                     tracing::error!("Failed to extract clip {}: {}", index + 1, e);
@@ -106,6 +165,65 @@ impl AiClipper {
         Ok(extracted_clips)
     }
 
+    /// Audio fingerprint of an extracted clip; None when it has no usable audio
+    async fn fingerprint_clip(clip_path: &str) -> Option<Vec<u32>> {
+        let path = clip_path.to_string();
+        match tokio::task::spawn_blocking(move || fingerprint::compute(&path)).await {
+            Ok(Ok(fp)) => Some(fp),
+            Ok(Err(e)) => {
+                tracing::warn!("Could not fingerprint {}: {}", clip_path, e);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Fingerprint task failed for {}: {}", clip_path, e);
+                None
+            }
+        }
+    }
+
+    /// Label of the first known clip this fingerprint substantially overlaps
+    async fn find_duplicate(fp: &[u32], known: &[(String, Vec<u32>)]) -> Option<String> {
+        let fp = fp.to_vec();
+        let known = known.to_vec();
+        tokio::task::spawn_blocking(move || {
+            known
+                .into_iter()
+                .find(|(_, other)| fingerprint::overlap_ratio(&fp, other) >= fingerprint::DUPLICATE_OVERLAP_RATIO)
+                .map(|(label, _)| label)
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// Fingerprints of clips already published to a destination channel
+    async fn load_published_fingerprints(&self, channel_id: i32) -> Vec<(String, Vec<u32>)> {
+        let rows = sqlx::query_as::<_, (i32, Option<String>, Vec<u8>)>(
+            "SELECT ec.id, ec.ai_title, ec.audio_fingerprint FROM extracted_clips ec
+             JOIN clipping_jobs cj ON ec.clipping_job_id = cj.id
+             JOIN youtube_channel_linkages l ON cj.linkage_id = l.id
+             WHERE l.destination_channel_id = $1 AND ec.upload_status = 'published'
+               AND ec.audio_fingerprint IS NOT NULL",
+        )
+        .bind(channel_id)
+        .fetch_all(&self.app_state.db_pool)
+        .await;
+
+        match rows {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|(id, title, bytes)| {
+                    let label = format!("published clip {} ({})", id, title.unwrap_or_default());
+                    (label, fingerprint::from_bytes(&bytes))
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to load published clip fingerprints: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Get video analysis from Qdrant vectorization
     async fn get_video_analysis(&self, video_path: &str) -> Result<String, String> {
         tracing::info!("Retrieving video analysis from vector database");
@@ -142,7 +260,7 @@ REQUIREMENTS:
 - Each clip must be between {} and {} seconds
 - Focus on: dramatic hooks, surprising moments, emotional peaks, action sequences, plot twists
 - Clips should work as standalone content
-
+{}
 For EACH clip, provide in this exact JSON format:
 [
   {{
@@ -161,14 +279,91 @@ Provide ONLY the JSON array, no other text."#,
             config.clips_per_video,
             video_analysis,
             config.min_clip_duration_seconds,
-            config.max_clip_duration_seconds
+            config.max_clip_duration_seconds,
+            config.metadata_policy.as_ref().map(|p| p.prompt_constraints()).unwrap_or_default()
         );
 
         // Call AI agent (Claude or Gemini based on config)
         let ai_response = self.call_ai_agent(&prompt).await?;
 
         // Parse JSON response
-        self.parse_clip_candidates(&ai_response)
+        let candidates = self.parse_clip_candidates(&ai_response)?;
+        match &config.metadata_policy {
+            Some(policy) => Ok(self.enforce_metadata_policy(candidates, policy).await),
+            None => Ok(candidates),
+        }
+    }
+
+    /// Drop candidates whose metadata still breaks the channel's policy after one rewrite attempt
+    async fn enforce_metadata_policy(&self, candidates: Vec<ClipCandidate>, policy: &ClipMetadataPolicy) -> Vec<ClipCandidate> {
+        let mut compliant = Vec::new();
+        for mut candidate in candidates {
+            policy.apply_fixes(&mut candidate);
+            let violations = policy.violations(&candidate.title, &candidate.description, &candidate.tags);
+            if violations.is_empty() {
+                compliant.push(candidate);
+                continue;
+            }
+
+            if let Err(e) = self.rewrite_metadata(&mut candidate, policy, &violations).await {
+                tracing::warn!("Failed to rewrite metadata for '{}': {}", candidate.title, e);
+            }
+            policy.apply_fixes(&mut candidate);
+            let remaining = policy.violations(&candidate.title, &candidate.description, &candidate.tags);
+            if remaining.is_empty() {
+                compliant.push(candidate);
+            } else {
+                tracing::warn!("Dropping clip '{}': metadata policy violations: {}", candidate.title, remaining.join("; "));
+            }
+        }
+        compliant
+    }
+
+    /// Ask the model to fix a candidate's title/description/tags
+    async fn rewrite_metadata(
+        &self,
+        candidate: &mut ClipCandidate,
+        policy: &ClipMetadataPolicy,
+        violations: &[String],
+    ) -> Result<(), String> {
+        let prompt = format!(
+            r#"Rewrite this YouTube Short's metadata so it follows the channel's rules.
+
+CURRENT METADATA:
+{}
+
+PROBLEMS:
+- {}
+
+RULES:
+{}
+
+Respond with ONLY a JSON object: {{"title": "...", "description": "...", "tags": ["..."]}}"#,
+            serde_json::json!({
+                "title": candidate.title,
+                "description": candidate.description,
+                "tags": candidate.tags,
+            }),
+            violations.join("\n- "),
+            policy.prompt_constraints()
+        );
+        let response = self.call_ai_agent(&prompt).await?;
+
+        let start = response.find('{').ok_or("No JSON object in response")?;
+        let end = response.rfind('}').ok_or("No JSON object in response")?;
+        let rewritten: serde_json::Value = serde_json::from_str(&response[start..=end])
+            .map_err(|e| format!("Invalid JSON: {}", e))?;
+
+        if let Some(title) = rewritten["title"].as_str() {
+            candidate.title = title.to_string();
+        }
+        if let Some(description) = rewritten["description"].as_str() {
+            candidate.description = description.to_string();
+        }
+        if let Some(tags) = rewritten["tags"].as_array() {
+            candidate.tags = tags.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect();
+        }
+        Ok(())
     }
 
     /// Call AI agent with prompt
@@ -320,4 +515,6 @@ pub struct ExtractedClipData {
     pub ai_tags: Vec<String>,
     pub ai_confidence_score: f64,
     pub viral_factors: Vec<String>,
+    /// Serialized audio fingerprint (see clipping::fingerprint)
+    pub audio_fingerprint: Option<Vec<u8>>,
 }
@@ -0,0 +1,179 @@
+// Burned-in captions for clips, styled per source channel
+//
+// Clips are transcribed word by word and rendered as an ASS subtitle track with a
+// karaoke-style fill on the word being spoken, then burned in with the subtitles filter.
+
+use crate::clipping::models::CaptionStyle;
+use crate::types::TranscriptWord;
+use crate::visual::{ass_color, ass_timestamp, group_words_into_lines};
+use crate::AppState;
+
+pub const CAPTION_PRESETS: [&str; 5] = ["bold", "karaoke", "minimal", "hype", "none"];
+
+/// A caption style with preset defaults filled in
+#[derive(Debug, Clone)]
+struct ResolvedCaptionStyle {
+    font: String,
+    font_size_percent: f64,
+    base_color: String,
+    highlight_color: String,
+    outline: u32,
+    emoji_emphasis: bool,
+    uppercase: bool,
+    words_per_line: usize,
+}
+
+impl CaptionStyle {
+    pub fn validate(&self) -> Result<(), String> {
+        if !CAPTION_PRESETS.contains(&self.preset.as_str()) {
+            return Err(format!(
+                "Unknown caption preset '{}'. Use one of: {}",
+                self.preset,
+                CAPTION_PRESETS.join(", ")
+            ));
+        }
+        if let Some(size) = self.font_size_percent {
+            if !(1.0..=20.0).contains(&size) {
+                return Err("font_size_percent must be between 1 and 20".to_string());
+            }
+        }
+        if self.words_per_line == Some(0) {
+            return Err("words_per_line must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Preset defaults overridden by any explicitly set fields; None for the "none" preset
+    fn resolve(&self) -> Option<ResolvedCaptionStyle> {
+        // (size %, base, highlight, outline, emoji, uppercase, words per line)
+        let (size, base, highlight, outline, emoji, uppercase, words) = match self.preset.as_str() {
+            "none" => return None,
+            "karaoke" => (5.5, "white", "cyan", 3, false, false, 5),
+            "minimal" => (4.0, "white", "white", 2, false, false, 7),
+            "hype" => (8.0, "white", "green", 6, true, true, 2),
+            _ => (7.0, "white", "yellow", 5, false, true, 3),
+        };
+        Some(ResolvedCaptionStyle {
+            font: self.font.clone().unwrap_or_else(|| "DejaVu Sans".to_string()),
+            font_size_percent: self.font_size_percent.unwrap_or(size),
+            base_color: self.base_color.clone().unwrap_or_else(|| base.to_string()),
+            highlight_color: self.highlight_color.clone().unwrap_or_else(|| highlight.to_string()),
+            outline,
+            emoji_emphasis: self.emoji_emphasis.unwrap_or(emoji),
+            uppercase: self.uppercase.unwrap_or(uppercase),
+            words_per_line: self.words_per_line.unwrap_or(words),
+        })
+    }
+}
+
+/// Transcribe a clip and burn styled captions into a copy of it.
+/// Returns the captioned file, or None when the style is "none".
+pub async fn burn_captions(app_state: &AppState, clip_path: &str, style: &CaptionStyle) -> Result<Option<String>, String> {
+    let Some(style) = style.resolve() else {
+        return Ok(None);
+    };
+
+    let path = clip_path.to_string();
+    let metadata = tokio::task::spawn_blocking(move || crate::core::analyze_video(&path))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let words = crate::agent::tool_executor::transcribe_media_words(clip_path, false, app_state).await?;
+    if words.is_empty() {
+        return Err("No speech found to caption".to_string());
+    }
+    let lines = group_words_into_lines(&words, style.words_per_line);
+
+    let stem = clip_path.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(clip_path);
+    let ass_file = format!("{}_captions.ass", stem);
+    let output_file = format!("{}_captioned.mp4", stem);
+    tokio::fs::write(&ass_file, build_caption_ass(&lines, metadata.width, metadata.height, &style))
+        .await
+        .map_err(|e| format!("Failed to write captions: {}", e))?;
+
+    let (input, ass, output) = (clip_path.to_string(), ass_file.clone(), output_file.clone());
+    let result = tokio::task::spawn_blocking(move || crate::visual::add_subtitles(&input, &ass, &output))
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = tokio::fs::remove_file(&ass_file).await;
+    result?;
+
+    Ok(Some(output_file))
+}
+
+/// ASS document with one dialogue line per caption line and a \kf fill on each spoken word
+fn build_caption_ass(lines: &[Vec<TranscriptWord>], width: u32, height: u32, style: &ResolvedCaptionStyle) -> String {
+    let font_size = ((height as f64 * style.font_size_percent / 100.0).round() as u32).max(12);
+    let mut ass = format!(
+        "[Script Info]\nScriptType: v4.00+\nPlayResX: {}\nPlayResY: {}\nWrapStyle: 0\n\n\
+[V4+ Styles]\n\
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+Style: Caption,{},{},{},{},&H00000000&,&H80000000&,1,0,0,0,100,100,0,0,1,{},2,2,{},{},{},1\n\n\
+[Events]\n\
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+        width,
+        height,
+        style.font.replace(',', ""),
+        font_size,
+        ass_color(&style.highlight_color), // primary = spoken colour
+        ass_color(&style.base_color),      // secondary = not yet spoken
+        style.outline,
+        width / 12,
+        width / 12,
+        // Sit above the title/description overlay of Shorts-style players
+        height / 5
+    );
+
+    for line in lines {
+        let (Some(first), Some(last)) = (line.first(), line.last()) else {
+            continue;
+        };
+        let mut cursor = first.start;
+        let mut text = String::new();
+        for word in line {
+            let lead_in = ((word.start - cursor) * 100.0).round().max(0.0) as u64;
+            if lead_in > 0 {
+                text.push_str(&format!("{{\\k{}}}", lead_in));
+            }
+            let spoken = ((word.end - word.start.max(cursor)) * 100.0).round().max(1.0) as u64;
+            let mut display = word.text.replace(['{', '}'], "");
+            if style.uppercase {
+                display = display.to_uppercase();
+            }
+            if style.emoji_emphasis {
+                if let Some(emoji) = emphasis_emoji(&word.text) {
+                    display = format!("{} {}", display, emoji);
+                }
+            }
+            text.push_str(&format!("{{\\kf{}}}{} ", spoken, display));
+            cursor = word.end.max(cursor);
+        }
+        ass.push_str(&format!(
+            "Dialogue: 0,{},{},Caption,,0,0,0,,{}\n",
+            ass_timestamp(first.start),
+            ass_timestamp(last.end + 0.2),
+            text.trim_end()
+        ));
+    }
+
+    ass
+}
+
+/// Emoji shown after words that carry the emotional beat of a line
+fn emphasis_emoji(word: &str) -> Option<&'static str> {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+    let emoji = match word.as_str() {
+        "money" | "cash" | "dollars" | "million" | "millions" | "rich" => "💰",
+        "fire" | "hot" | "lit" | "crazy" | "insane" => "🔥",
+        "love" | "heart" | "loved" => "❤️",
+        "funny" | "laugh" | "hilarious" | "lol" => "😂",
+        "win" | "won" | "winner" | "champion" => "🏆",
+        "wow" | "shocked" | "unbelievable" | "mind" => "🤯",
+        "scary" | "scared" | "terrifying" => "😱",
+        "fast" | "speed" | "quick" => "⚡",
+        "dead" | "died" | "kill" => "💀",
+        "secret" | "hidden" => "🤫",
+        _ => return None,
+    };
+    Some(emoji)
+}
@@ -0,0 +1,239 @@
+// Cross-posting matrix: each clip is rendered per platform aspect ratio and published to the
+// platforms its destination channel selected, with status tracked per platform.
+//
+// Only YouTube Shorts has a publishing client today; other platforms stop at "rendered" so the
+// variant is ready to post once their client lands (or by hand).
+
+use crate::clipping::models::{ClipCrossPost, CrossPostConfig};
+use crate::utils::execute_ffmpeg_command;
+use sqlx::PgPool;
+use std::process::Command;
+
+pub struct CrossPostPlatform {
+    pub name: &'static str,
+    pub aspect_ratio: &'static str,
+    pub width: u32,
+    pub height: u32,
+    /// Whether a client exists to publish here automatically
+    pub can_publish: bool,
+}
+
+pub const PLATFORMS: [CrossPostPlatform; 4] = [
+    CrossPostPlatform { name: "youtube_shorts", aspect_ratio: "9:16", width: 1080, height: 1920, can_publish: true },
+    CrossPostPlatform { name: "tiktok", aspect_ratio: "9:16", width: 1080, height: 1920, can_publish: false },
+    CrossPostPlatform { name: "instagram_reels", aspect_ratio: "9:16", width: 1080, height: 1920, can_publish: false },
+    CrossPostPlatform { name: "instagram_feed", aspect_ratio: "4:5", width: 1080, height: 1350, can_publish: false },
+];
+
+pub const DEFAULT_PLATFORMS: [&str; 1] = ["youtube_shorts"];
+
+pub fn platform(name: &str) -> Option<&'static CrossPostPlatform> {
+    PLATFORMS.iter().find(|p| p.name == name)
+}
+
+pub struct CrossPostService;
+
+impl CrossPostService {
+    /// Platforms selected for a destination channel (YouTube Shorts only when unconfigured)
+    pub async fn platforms_for(pool: &PgPool, destination_channel_id: i32) -> Result<Vec<String>, sqlx::Error> {
+        let config = sqlx::query_as::<_, CrossPostConfig>("SELECT * FROM clip_cross_post_configs WHERE destination_channel_id = $1")
+            .bind(destination_channel_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(match config {
+            Some(config) => config.platforms,
+            None => DEFAULT_PLATFORMS.iter().map(|p| p.to_string()).collect(),
+        })
+    }
+
+    pub async fn save_config(pool: &PgPool, destination_channel_id: i32, platforms: &[String]) -> Result<CrossPostConfig, String> {
+        let mut selected: Vec<String> = Vec::new();
+        for name in platforms {
+            let name = name.trim().to_lowercase();
+            if platform(&name).is_none() {
+                let known: Vec<&str> = PLATFORMS.iter().map(|p| p.name).collect();
+                return Err(format!("Unknown platform '{}'. Use any of: {}", name, known.join(", ")));
+            }
+            if !selected.contains(&name) {
+                selected.push(name);
+            }
+        }
+
+        sqlx::query_as::<_, CrossPostConfig>(
+            r#"
+            INSERT INTO clip_cross_post_configs (destination_channel_id, platforms)
+            VALUES ($1, $2)
+            ON CONFLICT (destination_channel_id) DO UPDATE SET platforms = EXCLUDED.platforms, updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(destination_channel_id)
+        .bind(&selected)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save cross-post config: {}", e))
+    }
+
+    pub async fn list_for_clip(pool: &PgPool, clip_id: i32) -> Result<Vec<ClipCrossPost>, sqlx::Error> {
+        sqlx::query_as::<_, ClipCrossPost>("SELECT * FROM clip_cross_posts WHERE extracted_clip_id = $1 ORDER BY id")
+            .bind(clip_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Render the clip for a platform, reusing an existing render of the same dimensions
+    pub async fn render(pool: &PgPool, clip_id: i32, clip_path: &str, platform: &CrossPostPlatform) -> Result<String, String> {
+        let stem = clip_path.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(clip_path);
+        let output = format!("{}_{}x{}.mp4", stem, platform.width, platform.height);
+
+        let result = if tokio::fs::try_exists(&output).await.unwrap_or(false) {
+            Ok(output.clone())
+        } else {
+            let (input, out, width, height) = (clip_path.to_string(), output.clone(), platform.width, platform.height);
+            tokio::task::spawn_blocking(move || render_variant(&input, &out, width, height))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map(|_| output.clone()))
+        };
+
+        let (status, rendered_path, error) = match &result {
+            Ok(path) => ("rendered", Some(path.as_str()), None),
+            Err(e) => ("failed", None, Some(format!("Render failed: {}", e))),
+        };
+        Self::record(pool, clip_id, platform, status, rendered_path, error.as_deref()).await;
+        result
+    }
+
+    pub async fn mark_published(pool: &PgPool, clip_id: i32, platform: &str, external_id: &str, external_url: &str) {
+        if let Err(e) = sqlx::query(
+            "UPDATE clip_cross_posts SET status = 'published', external_id = $3, external_url = $4, error_message = NULL, updated_at = NOW()
+             WHERE extracted_clip_id = $1 AND platform = $2",
+        )
+        .bind(clip_id)
+        .bind(platform)
+        .bind(external_id)
+        .bind(external_url)
+        .execute(pool)
+        .await
+        {
+            tracing::warn!("Failed to record {} publish for clip {}: {}", platform, clip_id, e);
+        }
+    }
+
+    /// Keep the rendered file but note why it wasn't published
+    pub async fn mark_not_published(pool: &PgPool, clip_id: i32, platform: &str, status: &str, reason: &str) {
+        if let Err(e) = sqlx::query(
+            "UPDATE clip_cross_posts SET status = $3, error_message = $4, updated_at = NOW()
+             WHERE extracted_clip_id = $1 AND platform = $2",
+        )
+        .bind(clip_id)
+        .bind(platform)
+        .bind(status)
+        .bind(reason)
+        .execute(pool)
+        .await
+        {
+            tracing::warn!("Failed to record {} status for clip {}: {}", platform, clip_id, e);
+        }
+        Self::notify_review_needed(pool, clip_id, platform, status, reason).await;
+    }
+
+    /// Let the linkage owner know a clip needs them: it's rendered and waiting to be posted by hand,
+    /// or its upload failed and needs a repost. Once per clip, however many platforms it's held on.
+    async fn notify_review_needed(pool: &PgPool, clip_id: i32, platform: &str, status: &str, reason: &str) {
+        let owner = sqlx::query_as::<_, (i32, i32, Option<String>)>(
+            "SELECT l.user_id, ec.clip_number, ec.ai_title FROM extracted_clips ec
+             JOIN clipping_jobs cj ON cj.id = ec.clipping_job_id
+             JOIN youtube_channel_linkages l ON l.id = cj.linkage_id
+             WHERE ec.id = $1",
+        )
+        .bind(clip_id)
+        .fetch_optional(pool)
+        .await;
+        let (user_id, clip_number, title) = match owner {
+            Ok(Some(owner)) => owner,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to find the owner of clip {}: {}", clip_id, e);
+                return;
+            }
+        };
+
+        let name = title.unwrap_or_else(|| format!("Clip {}", clip_number));
+        let message = if status == "failed" {
+            format!("\"{}\" couldn't be posted to {}: {}. Review it and repost when ready.", name, platform, reason)
+        } else {
+            format!("\"{}\" is rendered for {} and waiting for you to review and post it.", name, platform)
+        };
+        let data = serde_json::json!({ "clip_id": clip_id, "platform": platform, "status": status, "reason": reason });
+        if let Err(e) = crate::services::NotificationService::notify_once(
+            pool,
+            user_id,
+            &format!("clip_review:{}", clip_id),
+            "clip_review_needed",
+            "A clip needs your review",
+            &message,
+            Some(data),
+        )
+        .await
+        {
+            tracing::warn!("Failed to notify user {} about clip {}: {}", user_id, clip_id, e);
+        }
+    }
+
+    async fn record(
+        pool: &PgPool,
+        clip_id: i32,
+        platform: &CrossPostPlatform,
+        status: &str,
+        rendered_path: Option<&str>,
+        error: Option<&str>,
+    ) {
+        if let Err(e) = sqlx::query(
+            r#"
+            INSERT INTO clip_cross_posts (extracted_clip_id, platform, aspect_ratio, rendered_path, status, error_message)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (extracted_clip_id, platform) DO UPDATE SET
+                rendered_path = EXCLUDED.rendered_path,
+                status = EXCLUDED.status,
+                error_message = EXCLUDED.error_message,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(clip_id)
+        .bind(platform.name)
+        .bind(platform.aspect_ratio)
+        .bind(rendered_path)
+        .bind(status)
+        .bind(error)
+        .execute(pool)
+        .await
+        {
+            tracing::warn!("Failed to record {} render for clip {}: {}", platform.name, clip_id, e);
+        }
+    }
+}
+
+/// Scale to fill the target frame and centre-crop the overflow
+fn render_variant(input_file: &str, output_file: &str, width: u32, height: u32) -> Result<String, String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vf")
+        .arg(format!(
+            "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1",
+            w = width,
+            h = height
+        ))
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
@@ -0,0 +1,123 @@
+// Audio fingerprints for produced clips, used to suppress near-identical clips across runs
+//
+// Haitsma-Kalker style: audio is decoded to 8kHz mono, split into overlapping frames, and each
+// frame yields 32 bits from the sign of energy differences between adjacent bands over time.
+// Two clips of the same moment share long runs of near-identical frames even when cut differently.
+
+use std::f64::consts::PI;
+use std::process::Command;
+
+const SAMPLE_RATE: usize = 8000;
+const FRAME_SIZE: usize = 2048;
+/// 64ms between frames
+const HOP_SIZE: usize = 512;
+const BANDS: usize = 33;
+const MIN_FREQ: f64 = 300.0;
+const MAX_FREQ: f64 = 2000.0;
+
+/// Frames whose bits differ less than this are considered the same audio
+const MAX_BIT_ERROR_RATE: f64 = 0.35;
+
+/// Share of the shorter clip that must match for two clips to count as duplicates
+pub const DUPLICATE_OVERLAP_RATIO: f64 = 0.5;
+
+/// Fingerprint a clip's audio (blocking; runs ffmpeg)
+pub fn compute(file_path: &str) -> Result<Vec<u32>, String> {
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-i", file_path, "-vn", "-ac", "1", "-ar", &SAMPLE_RATE.to_string(), "-f", "s16le", "-"])
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to decode audio: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let samples: Vec<f64> = output
+        .stdout
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f64 / 32768.0)
+        .collect();
+    if samples.len() < FRAME_SIZE * 2 {
+        return Err("Clip has no usable audio".to_string());
+    }
+    Ok(fingerprint_samples(&samples))
+}
+
+fn fingerprint_samples(samples: &[f64]) -> Vec<u32> {
+    // Log-spaced band edges between MIN_FREQ and MAX_FREQ
+    let edges: Vec<f64> = (0..=BANDS)
+        .map(|i| MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(i as f64 / BANDS as f64))
+        .collect();
+    let window: Vec<f64> = (0..FRAME_SIZE)
+        .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f64 / (FRAME_SIZE - 1) as f64).cos())
+        .collect();
+
+    let mut previous: Option<Vec<f64>> = None;
+    let mut fingerprint = Vec::new();
+    for start in (0..=samples.len() - FRAME_SIZE).step_by(HOP_SIZE) {
+        let frame: Vec<f64> = samples[start..start + FRAME_SIZE].iter().zip(&window).map(|(s, w)| s * w).collect();
+        let energies: Vec<f64> = edges
+            .windows(2)
+            .map(|edge| goertzel(&frame, (edge[0] + edge[1]) / 2.0) + goertzel(&frame, edge[1]))
+            .collect();
+
+        if let Some(prev) = &previous {
+            let mut bits = 0u32;
+            for band in 0..BANDS - 1 {
+                let now = energies[band] - energies[band + 1];
+                let before = prev[band] - prev[band + 1];
+                bits <<= 1;
+                if now - before > 0.0 {
+                    bits |= 1;
+                }
+            }
+            fingerprint.push(bits);
+        }
+        previous = Some(energies);
+    }
+    fingerprint
+}
+
+/// Signal power at one frequency
+fn goertzel(frame: &[f64], frequency: f64) -> f64 {
+    let coefficient = 2.0 * (2.0 * PI * frequency / SAMPLE_RATE as f64).cos();
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for sample in frame {
+        let s0 = sample + coefficient * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    s1 * s1 + s2 * s2 - coefficient * s1 * s2
+}
+
+pub fn to_bytes(fingerprint: &[u32]) -> Vec<u8> {
+    fingerprint.iter().flat_map(|f| f.to_be_bytes()).collect()
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks_exact(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+/// Best fraction of the shorter clip that matches the other at any alignment (0.0 - 1.0)
+pub fn overlap_ratio(a: &[u32], b: &[u32]) -> f64 {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if short.is_empty() {
+        return 0.0;
+    }
+
+    let mut best = 0usize;
+    // Slide the short clip across the long one, allowing partial overlap at either end
+    let min_offset = -(short.len() as isize) + 1;
+    for offset in min_offset..long.len() as isize {
+        let matching = short
+            .iter()
+            .enumerate()
+            .filter_map(|(i, frame)| {
+                let j = offset + i as isize;
+                (j >= 0 && (j as usize) < long.len()).then(|| (frame ^ long[j as usize]).count_ones())
+            })
+            .filter(|errors| (*errors as f64 / 32.0) < MAX_BIT_ERROR_RATE)
+            .count();
+        best = best.max(matching);
+    }
+    best as f64 / short.len() as f64
+}
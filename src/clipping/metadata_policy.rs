@@ -0,0 +1,149 @@
+// Channel owner constraints on AI-generated clip metadata
+//
+// The constraints are added to the generation prompt, then checked again on the generated
+// title/description/tags since models don't reliably follow them.
+
+use crate::clipping::models::{ClipCandidate, ClipMetadataPolicy, UpdateMetadataPolicyRequest};
+use sqlx::PgPool;
+
+/// YouTube's own title limit
+pub const YOUTUBE_MAX_TITLE_LENGTH: usize = 100;
+
+impl ClipMetadataPolicy {
+    pub async fn load(pool: &PgPool, destination_channel_id: i32) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, ClipMetadataPolicy>("SELECT * FROM clip_metadata_policies WHERE destination_channel_id = $1")
+            .bind(destination_channel_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        destination_channel_id: i32,
+        request: &UpdateMetadataPolicyRequest,
+    ) -> Result<Self, String> {
+        if let Some(max) = request.max_title_length {
+            if !(10..=YOUTUBE_MAX_TITLE_LENGTH as i32).contains(&max) {
+                return Err(format!("max_title_length must be between 10 and {}", YOUTUBE_MAX_TITLE_LENGTH));
+            }
+        }
+        let banned_words: Vec<String> = request
+            .banned_words
+            .iter()
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let mandatory_hashtags: Vec<String> = request
+            .mandatory_hashtags
+            .iter()
+            .map(|h| normalize_hashtag(h))
+            .filter(|h| !h.is_empty())
+            .collect();
+
+        sqlx::query_as::<_, ClipMetadataPolicy>(
+            r#"
+            INSERT INTO clip_metadata_policies (destination_channel_id, banned_words, mandatory_hashtags, max_title_length, tone)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (destination_channel_id) DO UPDATE SET
+                banned_words = EXCLUDED.banned_words,
+                mandatory_hashtags = EXCLUDED.mandatory_hashtags,
+                max_title_length = EXCLUDED.max_title_length,
+                tone = EXCLUDED.tone,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(destination_channel_id)
+        .bind(&banned_words)
+        .bind(&mandatory_hashtags)
+        .bind(request.max_title_length)
+        .bind(request.tone.as_deref().map(str::trim).filter(|t| !t.is_empty()))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save metadata policy: {}", e))
+    }
+
+    pub fn max_title_length(&self) -> usize {
+        self.max_title_length
+            .map(|max| max as usize)
+            .unwrap_or(YOUTUBE_MAX_TITLE_LENGTH)
+            .min(YOUTUBE_MAX_TITLE_LENGTH)
+    }
+
+    /// Extra prompt requirements describing this policy
+    pub fn prompt_constraints(&self) -> String {
+        let mut constraints = Vec::new();
+        if let Some(max) = self.max_title_length {
+            constraints.push(format!("- Titles must be at most {} characters", max));
+        }
+        if !self.banned_words.is_empty() {
+            constraints.push(format!(
+                "- Never use these words in titles, descriptions or tags: {}",
+                self.banned_words.join(", ")
+            ));
+        }
+        if !self.mandatory_hashtags.is_empty() {
+            let hashtags: Vec<String> = self.mandatory_hashtags.iter().map(|h| format!("#{}", h)).collect();
+            constraints.push(format!("- Every description must include these hashtags: {}", hashtags.join(" ")));
+        }
+        if let Some(tone) = &self.tone {
+            constraints.push(format!("- Write titles and descriptions in this tone: {}", tone));
+        }
+        constraints.join("\n")
+    }
+
+    /// Add mandatory hashtags the model left out; they are rendered from the tags on upload
+    pub fn apply_fixes(&self, candidate: &mut ClipCandidate) {
+        for hashtag in &self.mandatory_hashtags {
+            if !has_hashtag(&candidate.description, &candidate.tags, hashtag) {
+                candidate.tags.insert(0, hashtag.clone());
+            }
+        }
+    }
+
+    /// Every way the metadata breaks the policy; empty when it complies
+    pub fn violations(&self, title: &str, description: &str, tags: &[String]) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        let title_length = title.chars().count();
+        if title_length > self.max_title_length() {
+            violations.push(format!("title is {} characters (max {})", title_length, self.max_title_length()));
+        }
+
+        for banned in &self.banned_words {
+            let mut fields = vec![("title", title), ("description", description)];
+            fields.extend(tags.iter().map(|t| ("tags", t.as_str())));
+            if let Some((field, _)) = fields.iter().find(|(_, text)| contains_word(text, banned)) {
+                violations.push(format!("{} contains banned word '{}'", field, banned));
+            }
+        }
+
+        for hashtag in &self.mandatory_hashtags {
+            if !has_hashtag(description, tags, hashtag) {
+                violations.push(format!("missing mandatory hashtag #{}", hashtag));
+            }
+        }
+        violations
+    }
+}
+
+fn normalize_hashtag(hashtag: &str) -> String {
+    hashtag.trim().trim_start_matches('#').replace(' ', "")
+}
+
+fn has_hashtag(description: &str, tags: &[String], hashtag: &str) -> bool {
+    tags.iter().any(|t| normalize_hashtag(t).eq_ignore_ascii_case(hashtag))
+        || description
+            .split_whitespace()
+            .any(|w| w.trim_end_matches(|c: char| !c.is_alphanumeric()).eq_ignore_ascii_case(&format!("#{}", hashtag)))
+}
+
+/// Case-insensitive whole-word (or whole-phrase) match
+fn contains_word(text: &str, word: &str) -> bool {
+    let text = text.to_lowercase();
+    text.match_indices(word).any(|(start, matched)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + matched.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
@@ -6,6 +6,10 @@ pub mod ytdlp_client;
 pub mod monitor;
 pub mod ai_clipper;
 pub mod uploader;
+pub mod fingerprint;
+pub mod captions;
+pub mod metadata_policy;
+pub mod cross_post;
 
 // Re-export commonly used types
 pub use models::*;
@@ -2,6 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 
 /// Source channel to monitor (e.g., Mr Beast)
@@ -18,6 +19,43 @@ pub struct SourceChannel {
     pub last_video_checked: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub caption_style: Json<CaptionStyle>,
+}
+
+/// Burned caption style for clips cut from a source channel.
+/// Unset fields fall back to the preset's defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptionStyle {
+    /// "bold", "karaoke", "minimal", "hype" or "none" (no captions)
+    #[serde(default = "default_caption_preset")]
+    pub preset: String,
+    pub font: Option<String>,
+    /// Font size as a percentage of the video height
+    pub font_size_percent: Option<f64>,
+    pub base_color: Option<String>,
+    pub highlight_color: Option<String>,
+    pub emoji_emphasis: Option<bool>,
+    pub uppercase: Option<bool>,
+    pub words_per_line: Option<usize>,
+}
+
+fn default_caption_preset() -> String {
+    "bold".to_string()
+}
+
+impl Default for CaptionStyle {
+    fn default() -> Self {
+        Self {
+            preset: default_caption_preset(),
+            font: None,
+            font_size_percent: None,
+            base_color: None,
+            highlight_color: None,
+            emoji_emphasis: None,
+            uppercase: None,
+            words_per_line: None,
+        }
+    }
 }
 
 /// Linkage between source channel and destination channel
@@ -103,6 +141,60 @@ pub struct PollSchedule {
 pub struct AddSourceChannelRequest {
     pub channel_id: String,
     pub polling_interval_minutes: Option<i32>,
+    pub caption_style: Option<CaptionStyle>,
+}
+
+/// Constraints on generated clip metadata for a destination channel
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ClipMetadataPolicy {
+    pub destination_channel_id: i32,
+    pub banned_words: Vec<String>,
+    /// Stored without the leading '#'
+    pub mandatory_hashtags: Vec<String>,
+    pub max_title_length: Option<i32>,
+    pub tone: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMetadataPolicyRequest {
+    #[serde(default)]
+    pub banned_words: Vec<String>,
+    #[serde(default)]
+    pub mandatory_hashtags: Vec<String>,
+    pub max_title_length: Option<i32>,
+    pub tone: Option<String>,
+}
+
+/// Platforms a destination channel's clips are cross-posted to
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CrossPostConfig {
+    pub destination_channel_id: i32,
+    pub platforms: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCrossPostConfigRequest {
+    pub platforms: Vec<String>,
+}
+
+/// A clip's rendered variant and publish status on one platform
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ClipCrossPost {
+    pub id: i32,
+    pub extracted_clip_id: i32,
+    pub platform: String,
+    pub aspect_ratio: String,
+    pub rendered_path: Option<String>,
+    pub status: String, // pending, rendered, published, failed
+    pub external_id: Option<String>,
+    pub external_url: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -158,6 +250,12 @@ pub struct ClippingConfig {
     pub clips_per_video: i32,
     pub min_clip_duration_seconds: i32,
     pub max_clip_duration_seconds: i32,
+    /// Clips overlapping anything already published to this channel are skipped
+    pub destination_channel_id: Option<i32>,
+    /// Captions burned into each clip before upload; None leaves clips uncaptioned
+    pub caption_style: Option<CaptionStyle>,
+    /// Destination channel's metadata constraints, enforced on every candidate
+    pub metadata_policy: Option<ClipMetadataPolicy>,
 }
 
 /// AI-identified clip candidate
@@ -1,7 +1,10 @@
 // Clip upload manager for posting to YouTube
 
 use crate::clipping::ai_clipper::ExtractedClipData;
+use crate::clipping::metadata_policy::YOUTUBE_MAX_TITLE_LENGTH;
+use crate::clipping::models::ClipMetadataPolicy;
 use crate::models::youtube::ConnectedYouTubeChannel;
+use crate::services::TokenHealthService;
 use crate::youtube_client::YouTubeClient;
 use chrono::Utc;
 use sqlx::PgPool;
@@ -35,6 +38,7 @@ impl ClipUploader {
         clip: &ExtractedClipData,
         clip_db_id: i32,
         destination_channel: &ConnectedYouTubeChannel,
+        metadata_policy: Option<&ClipMetadataPolicy>,
     ) -> Result<YouTubeUploadResult, String> {
         tracing::info!(
             "📤 Uploading clip '{}' to YouTube channel {}",
@@ -46,9 +50,18 @@ impl ClipUploader {
         let access_token = self.ensure_valid_token(destination_channel).await?;
 
         // Step 2: Prepare metadata optimized for YouTube Shorts
-        let title = self.optimize_title(&clip.ai_title);
+        let max_title_length = metadata_policy.map(|p| p.max_title_length()).unwrap_or(YOUTUBE_MAX_TITLE_LENGTH);
+        let title = self.optimize_title(&clip.ai_title, max_title_length);
         let description = self.format_description(&clip.ai_description, &clip.ai_tags);
 
+        // Final check on exactly what will be published
+        if let Some(policy) = metadata_policy {
+            let violations = policy.violations(&title, &description, &clip.ai_tags);
+            if !violations.is_empty() {
+                return Err(format!("Metadata policy violation: {}", violations.join("; ")));
+            }
+        }
+
         tracing::debug!("Title: {}", title);
         tracing::debug!("Description: {}", description);
 
@@ -91,6 +104,13 @@ impl ClipUploader {
         &self,
         channel: &ConnectedYouTubeChannel,
     ) -> Result<String, String> {
+        if channel.token_status == "revoked" {
+            return Err(format!(
+                "YouTube access for {} was revoked; reconnect the channel",
+                channel.channel_name
+            ));
+        }
+
         // Check if token expires within 5 minutes (same logic as existing code)
         let now = Utc::now();
         let expires_soon = channel.token_expiry < now + chrono::Duration::minutes(5);
@@ -106,8 +126,14 @@ impl ClipUploader {
                     &self.oauth_client_id,
                     &self.oauth_client_secret,
                 )
-                .await
-                .map_err(|e| format!("Token refresh failed: {}", e))?;
+                .await;
+            let new_token = match new_token {
+                Ok(token) => token,
+                Err(e) => {
+                    TokenHealthService::record_failure(&self.db_pool, channel, &e.to_string()).await;
+                    return Err(format!("Token refresh failed: {}", e));
+                }
+            };
 
             // Update database with new token
             sqlx::query(
@@ -128,8 +154,8 @@ impl ClipUploader {
         }
     }
 
-    /// Optimize title for YouTube Shorts (max 100 chars)
-    fn optimize_title(&self, title: &str) -> String {
+    /// Optimize title for YouTube Shorts (at most max_length chars)
+    fn optimize_title(&self, title: &str, max_length: usize) -> String {
         let mut optimized = title.trim().to_string();
 
         // Add #Shorts hashtag if not present
@@ -138,8 +164,8 @@ impl ClipUploader {
         }
 
         // Truncate if too long
-        if optimized.len() > 100 {
-            optimized = optimized[..97].to_string();
+        if optimized.chars().count() > max_length {
+            optimized = optimized.chars().take(max_length.saturating_sub(3)).collect();
             optimized.push_str("...");
         }
 
@@ -187,6 +213,7 @@ impl ClipUploader {
         .execute(&self.db_pool)
         .await
         .map_err(|e| format!("Failed to update clip status: {}", e))?;
+        crate::services::ActivityService::record_clip(&self.db_pool, clip_id, "clip_published", Some(url)).await;
 
         Ok(())
     }
@@ -203,6 +230,7 @@ impl ClipUploader {
         .execute(&self.db_pool)
         .await
         .map_err(|e| format!("Failed to mark upload as failed: {}", e))?;
+        crate::services::ActivityService::record_clip(&self.db_pool, clip_id, "clip_upload_failed", Some(error)).await;
 
         Ok(())
     }
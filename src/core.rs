@@ -2,10 +2,56 @@
 
 use crate::types::*;
 use crate::utils::{execute_ffmpeg_command, execute_ffprobe_command};
-use serde_json::Value;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::process::Command;
 
-pub fn analyze_video(file_path: &str) -> Result<VideoMetadata, String> {
+/// Raw ffprobe `-print_format json -show_format -show_streams` output; numbers arrive as strings
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize, Default)]
+struct FfprobeFormat {
+    format_name: Option<String>,
+    duration: Option<String>,
+    size: Option<String>,
+    bit_rate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    index: u32,
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    r_frame_rate: Option<String>,
+    pix_fmt: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+    channel_layout: Option<String>,
+    bit_rate: Option<String>,
+    duration: Option<String>,
+    start_time: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    disposition: HashMap<String, i64>,
+}
+
+/// Parse "30000/1001" style rates; None for 0/0 and missing values
+fn parse_frame_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/')?;
+    let (num, den) = (num.parse::<f64>().ok()?, den.parse::<f64>().ok()?);
+    (den != 0.0 && num > 0.0).then(|| num / den)
+}
+
+/// Probe a media file once and return typed stream information
+pub fn probe_media(file_path: &str) -> Result<MediaInfo, String> {
     let args = &[
         "-v",
         "quiet",
@@ -16,56 +62,62 @@ pub fn analyze_video(file_path: &str) -> Result<VideoMetadata, String> {
         file_path,
     ];
     let ffprobe_output = execute_ffprobe_command(args)?;
-    let json: Value = serde_json::from_str(&ffprobe_output)
+    let probe: FfprobeOutput = serde_json::from_str(&ffprobe_output)
         .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
 
-    let format = &json["format"];
-    let duration_seconds = format["duration"]
-        .as_str()
-        .unwrap_or("0")
-        .parse::<f64>()
-        .unwrap_or(0.0);
-    let file_size_mb = format["size"]
-        .as_str()
-        .unwrap_or("0")
-        .parse::<f64>()
-        .unwrap_or(0.0)
-        / (1024.0 * 1024.0);
-
-    let mut metadata = VideoMetadata {
+    let number = |value: &Option<String>| value.as_deref().and_then(|v| v.parse::<f64>().ok());
+    let integer = |value: &Option<String>| value.as_deref().and_then(|v| v.parse::<u64>().ok());
+    let format = probe.format.unwrap_or_default();
+
+    let mut info = MediaInfo {
         file_path: file_path.to_string(),
-        duration_seconds,
-        width: 0,
-        height: 0,
-        fps: 0.0,
-        has_audio: false,
-        has_video: false,
-        format: format["format_name"].as_str().unwrap_or("unknown").to_string(),
-        file_size_mb,
+        format_name: format.format_name.clone().unwrap_or_else(|| "unknown".to_string()),
+        duration_seconds: number(&format.duration).unwrap_or(0.0),
+        size_bytes: integer(&format.size).unwrap_or(0),
+        bit_rate: integer(&format.bit_rate),
+        streams: Vec::new(),
+        audio_streams: Vec::new(),
     };
 
-    if let Some(streams) = json["streams"].as_array() {
-        for stream in streams {
-            if stream["codec_type"] == "video" {
-                metadata.has_video = true;
-                metadata.width = stream["width"].as_u64().unwrap_or(0) as u32;
-                metadata.height = stream["height"].as_u64().unwrap_or(0) as u32;
-                let fps_str = stream["r_frame_rate"].as_str().unwrap_or("0/1");
-                let parts: Vec<&str> = fps_str.split('/').collect();
-                if parts.len() == 2 {
-                    let num = parts[0].parse::<f64>().unwrap_or(0.0);
-                    let den = parts[1].parse::<f64>().unwrap_or(1.0);
-                    if den != 0.0 {
-                        metadata.fps = num / den;
-                    }
-                }
-            } else if stream["codec_type"] == "audio" {
-                metadata.has_audio = true;
-            }
+    for stream in probe.streams {
+        let language = stream.tags.get("language").cloned();
+        let codec_name = stream.codec_name.clone().unwrap_or_else(|| "unknown".to_string());
+        match stream.codec_type.as_deref().unwrap_or("unknown") {
+            "audio" => info.audio_streams.push(AudioInfo {
+                index: stream.index,
+                codec_name,
+                sample_rate: integer(&stream.sample_rate).unwrap_or(0) as u32,
+                channels: stream.channels.unwrap_or(0),
+                channel_layout: stream.channel_layout,
+                bit_rate: integer(&stream.bit_rate),
+                duration_seconds: number(&stream.duration),
+                start_seconds: number(&stream.start_time),
+                language,
+                title: stream.tags.get("title").cloned(),
+                default: stream.disposition.get("default").copied().unwrap_or(0) == 1,
+            }),
+            codec_type => info.streams.push(StreamInfo {
+                index: stream.index,
+                codec_type: codec_type.to_string(),
+                codec_name,
+                width: stream.width,
+                height: stream.height,
+                fps: stream.r_frame_rate.as_deref().and_then(parse_frame_rate),
+                pix_fmt: stream.pix_fmt,
+                bit_rate: integer(&stream.bit_rate),
+                duration_seconds: number(&stream.duration),
+                start_seconds: number(&stream.start_time),
+                language,
+                attached_pic: stream.disposition.get("attached_pic").copied().unwrap_or(0) == 1,
+            }),
         }
     }
 
-    Ok(metadata)
+    Ok(info)
+}
+
+pub fn analyze_video(file_path: &str) -> Result<VideoMetadata, String> {
+    probe_media(file_path).map(|info| VideoMetadata::from(&info))
 }
 
 pub fn trim_video(
@@ -128,6 +180,99 @@ pub fn merge_videos(input_files: &[String], output_file: &str) -> Result<String,
     result
 }
 
+/// Build a filter_complex that cuts `segments` out of input 0 and joins them with
+/// short video/audio crossfades so cuts don't click or flash.
+pub fn build_segment_crossfade_filter(
+    segments: &[(f64, f64)],
+    crossfade: f64,
+    include_audio: bool,
+) -> String {
+    let mut parts = Vec::new();
+    for (i, (start, end)) in segments.iter().enumerate() {
+        parts.push(format!(
+            "[0:v]trim=start={:.3}:end={:.3},setpts=PTS-STARTPTS[v{}]",
+            start, end, i
+        ));
+        if include_audio {
+            parts.push(format!(
+                "[0:a]atrim=start={:.3}:end={:.3},asetpts=PTS-STARTPTS[a{}]",
+                start, end, i
+            ));
+        }
+    }
+
+    if segments.len() == 1 {
+        parts.push("[v0]null[vout]".to_string());
+        if include_audio {
+            parts.push("[a0]anull[aout]".to_string());
+        }
+        return parts.join(";");
+    }
+
+    // xfade offsets are relative to the running output, which shrinks by one crossfade per join
+    let mut offset = 0.0;
+    let mut video_label = "v0".to_string();
+    let mut audio_label = "a0".to_string();
+    for i in 1..segments.len() {
+        let (prev_start, prev_end) = segments[i - 1];
+        offset += (prev_end - prev_start) - crossfade;
+        let last = i == segments.len() - 1;
+        let next_video = if last { "vout".to_string() } else { format!("vx{}", i) };
+        parts.push(format!(
+            "[{}][v{}]xfade=transition=fade:duration={:.3}:offset={:.3}[{}]",
+            video_label, i, crossfade, offset, next_video
+        ));
+        video_label = next_video;
+
+        if include_audio {
+            let next_audio = if last { "aout".to_string() } else { format!("ax{}", i) };
+            parts.push(format!(
+                "[{}][a{}]acrossfade=d={:.3}[{}]",
+                audio_label, i, crossfade, next_audio
+            ));
+            audio_label = next_audio;
+        }
+    }
+
+    parts.join(";")
+}
+
+/// Keep only the given time ranges of a video, joined with micro-crossfades
+pub fn concat_segments_with_crossfade(
+    input_file: &str,
+    output_file: &str,
+    segments: &[(f64, f64)],
+    crossfade: f64,
+) -> Result<String, String> {
+    // Segments shorter than two crossfades can't be faded in and out cleanly
+    let segments: Vec<(f64, f64)> = segments
+        .iter()
+        .copied()
+        .filter(|(start, end)| end - start > crossfade * 2.0)
+        .collect();
+    if segments.is_empty() {
+        return Err("No segments long enough to keep".to_string());
+    }
+
+    let has_audio = probe_media(input_file)?.has_audio();
+    let filter = build_segment_crossfade_filter(&segments, crossfade, has_audio);
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("[vout]");
+    if has_audio {
+        command.arg("-map").arg("[aout]");
+    }
+    command.arg("-y").arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
 pub fn split_video(
     input_file: &str,
     output_prefix: &str,
@@ -153,13 +298,12 @@ pub fn split_video(
 }
 
 pub fn get_video_duration(file_path: &str) -> Result<f64, String> {
-    let metadata = analyze_video(file_path)?;
-    Ok(metadata.duration_seconds)
+    Ok(probe_media(file_path)?.duration_seconds)
 }
 
 pub fn validate_video_file(file_path: &str) -> Result<bool, String> {
-    match analyze_video(file_path) {
-        Ok(_) => Ok(true),
+    match probe_media(file_path) {
+        Ok(info) => Ok(info.has_video()),
         Err(_) => Ok(false),
     }
 }
\ No newline at end of file
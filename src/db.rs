@@ -1,11 +1,10 @@
 // src/db.rs
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-use std::env;
 use std::time::Duration;
 
 pub async fn create_pool() -> Result<PgPool, sqlx::Error> {
-    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let db_url = crate::config::get().database.url.clone().expect("database.url (DATABASE_URL) must be set");
     let pool = PgPoolOptions::new()
         .max_connections(5)
         .acquire_timeout(Duration::from_secs(30))
@@ -1,6 +1,7 @@
 // Eleven Labs API Client
-// Supports: Text-to-Speech, Sound Effects, Music Generation
+// Supports: Text-to-Speech, Sound Effects, Music Generation, Speech-to-Text
 
+use crate::chaos::{ChaosSend, ChaosTarget};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -87,6 +88,26 @@ pub struct VoicesResponse {
     pub voices: Vec<Voice>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct SpeechToTextResponse {
+    pub text: String,
+    #[serde(default)]
+    pub language_code: Option<String>,
+    #[serde(default)]
+    pub words: Vec<SpeechToTextWord>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SpeechToTextWord {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    #[serde(rename = "type")]
+    pub word_type: String, // "word", "spacing", "audio_event"
+    #[serde(default)]
+    pub speaker_id: Option<String>,
+}
+
 // ============================================================================
 // IMPLEMENTATION
 // ============================================================================
@@ -129,7 +150,7 @@ impl ElevenLabsClient {
             request = request.query(&[("output_format", format)]);
         }
 
-        let response = request.send().await?;
+        let response = request.send_with_chaos(ChaosTarget::ElevenLabs).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -162,7 +183,7 @@ impl ElevenLabsClient {
             .header("xi-api-key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_with_chaos(ChaosTarget::ElevenLabs)
             .await?;
 
         if !response.status().is_success() {
@@ -194,7 +215,7 @@ impl ElevenLabsClient {
             .header("xi-api-key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_with_chaos(ChaosTarget::ElevenLabs)
             .await?;
 
         if !response.status().is_success() {
@@ -217,7 +238,7 @@ impl ElevenLabsClient {
         let response = self.client
             .get(&url)
             .header("xi-api-key", &self.api_key)
-            .send()
+            .send_with_chaos(ChaosTarget::ElevenLabs)
             .await?;
 
         if !response.status().is_success() {
@@ -237,7 +258,7 @@ impl ElevenLabsClient {
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let response = self.client
             .get(audio_url)
-            .send()
+            .send_with_chaos(ChaosTarget::ElevenLabs)
             .await?;
 
         if !response.status().is_success() {
@@ -248,6 +269,67 @@ impl ElevenLabsClient {
         Ok(audio_bytes.to_vec())
     }
 
+    /// Transcribe audio with word-level timestamps (Scribe)
+    pub async fn speech_to_text(
+        &self,
+        audio_bytes: Vec<u8>,
+        file_name: &str,
+        diarize: bool,
+    ) -> Result<SpeechToTextResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/speech-to-text", self.base_url);
+
+        let form = reqwest::multipart::Form::new()
+            .text("model_id", "scribe_v1")
+            .text("timestamps_granularity", "word")
+            .text("diarize", diarize.to_string())
+            .part("file", reqwest::multipart::Part::bytes(audio_bytes).file_name(file_name.to_string()));
+
+        let response = self.client
+            .post(&url)
+            .header("xi-api-key", &self.api_key)
+            .multipart(form)
+            .send_with_chaos(ChaosTarget::ElevenLabs)
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Eleven Labs Speech-to-Text API error ({}): {}", status, error_text).into());
+        }
+
+        let transcript: SpeechToTextResponse = response.json().await?;
+        Ok(transcript)
+    }
+
+    /// Generate music end to end: start the task, poll until it completes, and download the audio
+    pub async fn generate_music(
+        &self,
+        prompt: &str,
+        duration_ms: u32,
+        max_wait_seconds: u64,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let generation_id = self.generate_music_task(prompt, duration_ms).await?;
+
+        for _ in 0..(max_wait_seconds / 2).max(1) {
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+            let status = self.get_music_status(&generation_id).await?;
+            match status.status.as_str() {
+                "completed" => {
+                    let audio_url = status.audio_url
+                        .ok_or("Music generation completed but no audio URL provided")?;
+                    return self.download_music(&audio_url).await;
+                }
+                "failed" => {
+                    return Err(format!("Music generation failed: {}", status.error.unwrap_or_else(|| "Unknown error".to_string())).into());
+                }
+                _ => {}
+            }
+        }
+
+        Err(format!("Music generation timed out after {}s", max_wait_seconds).into())
+    }
+
     /// List all available voices
     pub async fn list_voices(&self) -> Result<Vec<Voice>, Box<dyn std::error::Error + Send + Sync>> {
         let url = format!("{}/voices", self.base_url);
@@ -255,7 +337,7 @@ impl ElevenLabsClient {
         let response = self.client
             .get(&url)
             .header("xi-api-key", &self.api_key)
-            .send()
+            .send_with_chaos(ChaosTarget::ElevenLabs)
             .await?;
 
         if !response.status().is_success() {
@@ -275,7 +357,7 @@ impl ElevenLabsClient {
         let response = self.client
             .get(&url)
             .header("xi-api-key", &self.api_key)
-            .send()
+            .send_with_chaos(ChaosTarget::ElevenLabs)
             .await?;
 
         if !response.status().is_success() {
@@ -1,6 +1,7 @@
 // src/export.rs
 
 
+use crate::types::ExportSettings;
 use crate::utils::execute_ffmpeg_command;
 use std::process::Command;
 
@@ -58,11 +59,10 @@ pub fn export_custom_quality(
     execute_ffmpeg_command(command)
 }
 
-pub fn export_for_platform(
-    input_file: &str,
-    output_file: &str,
-    platform: &str,
-) -> Result<String, String> {
+/// Built-in platform presets; users can override any of them with a named export preset
+pub const PLATFORM_PRESETS: [&str; 6] = ["youtube", "youtube-4k", "instagram", "tiktok", "twitter", "facebook"];
+
+pub fn platform_settings(platform: &str) -> Option<ExportSettings> {
     let (resolution, bitrate, fps) = match platform {
         "youtube" => ((1920, 1080), 8000, 30),
         "youtube-4k" => ((3840, 2160), 35000, 30),
@@ -70,25 +70,216 @@ pub fn export_for_platform(
         "tiktok" => ((1080, 1920), 4000, 30),
         "twitter" => ((1280, 720), 5000, 30),
         "facebook" => ((1920, 1080), 6000, 30),
-        _ => return Err(format!("Unsupported platform: {}", platform)),
+        _ => return None,
     };
 
+    Some(ExportSettings {
+        container: "mp4".to_string(),
+        video_codec: "libx264".to_string(),
+        audio_codec: "aac".to_string(),
+        crf: None,
+        video_bitrate_kbps: Some(bitrate),
+        audio_bitrate_kbps: Some(192),
+        width: Some(resolution.0),
+        height: Some(resolution.1),
+        fps: Some(fps as f64),
+        loudness_target: None,
+        burn_captions: false,
+    })
+}
+
+pub fn export_for_platform(
+    input_file: &str,
+    output_file: &str,
+    platform: &str,
+) -> Result<String, String> {
+    let settings = platform_settings(platform).ok_or_else(|| format!("Unsupported platform: {}", platform))?;
+    export_with_settings(input_file, output_file, &settings, None)
+}
+
+/// Encode with explicit export settings. The frame is letterboxed to the target resolution,
+/// surround audio is folded down to stereo, audio is loudness-normalized when a target is set, and
+/// `captions_file` is burned in if given.
+pub fn export_with_settings(
+    input_file: &str,
+    output_file: &str,
+    settings: &ExportSettings,
+    captions_file: Option<&str>,
+) -> Result<String, String> {
+    let mut video_filters = Vec::new();
+    if let (Some(width), Some(height)) = (settings.width, settings.height) {
+        video_filters.push(format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1",
+            w = width,
+            h = height
+        ));
+    }
+    if let Some(captions) = captions_file {
+        video_filters.push(format!("subtitles={}", captions));
+    }
+
     let mut command = Command::new("ffmpeg");
-    command
-        .arg("-i")
-        .arg(input_file)
-        .arg("-vf")
-        .arg(format!("scale={}:{}", resolution.0, resolution.1))
-        .arg("-r")
-        .arg(fps.to_string())
-        .arg("-b:v")
-        .arg(format!("{}k", bitrate))
-        .arg("-c:a")
-        .arg("aac")
-        .arg("-b:a")
-        .arg("192k")
-        .arg("-y")
-        .arg(output_file);
+    command.arg("-i").arg(input_file);
+
+    if !video_filters.is_empty() {
+        command.arg("-vf").arg(video_filters.join(","));
+    }
+    if let Some(fps) = settings.fps {
+        command.arg("-r").arg(fps.to_string());
+    }
+
+    command.arg("-c:v").arg(&settings.video_codec);
+    if let Some(bitrate) = settings.video_bitrate_kbps {
+        command.arg("-b:v").arg(format!("{}k", bitrate));
+    } else if let Some(crf) = settings.crf {
+        command.arg("-crf").arg(crf.to_string());
+        if settings.video_codec == "libvpx-vp9" {
+            // VP9 needs a zero bitrate cap for constant-quality mode
+            command.arg("-b:v").arg("0");
+        }
+    }
+    if matches!(settings.video_codec.as_str(), "libx264" | "libx265") {
+        command.arg("-pix_fmt").arg("yuv420p");
+    }
+
+    let mut audio_filters = Vec::new();
+    if let Some(pan) = crate::audio::downmix_filter_for(input_file, crate::audio::Downmix::Stereo) {
+        audio_filters.push(pan);
+    }
+    if let Some(target) = settings.loudness_target {
+        audio_filters.push(format!("loudnorm=I={:.1}:TP=-1.5:LRA=11", target));
+    }
+    if !audio_filters.is_empty() {
+        command.arg("-af").arg(audio_filters.join(","));
+    }
+    command.arg("-c:a").arg(&settings.audio_codec);
+    if let Some(bitrate) = settings.audio_bitrate_kbps {
+        command.arg("-b:a").arg(format!("{}k", bitrate));
+    }
+    if settings.container == "mp4" || settings.container == "mov" {
+        command.arg("-movflags").arg("+faststart");
+    }
+
+    command.arg("-y").arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Named renditions for `multi_export`: (name, width, height, video bitrate kbps, crop to fill)
+pub const RENDITION_PRESETS: [(&str, u32, u32, u32, bool); 7] = [
+    ("4k", 3840, 2160, 35000, false),
+    ("1440p", 2560, 1440, 16000, false),
+    ("1080p", 1920, 1080, 8000, false),
+    ("720p", 1280, 720, 4000, false),
+    ("480p", 854, 480, 1500, false),
+    ("vertical", 1080, 1920, 6000, true),
+    ("square", 1080, 1080, 4000, true),
+];
+
+/// One output of a multi-rendition export
+#[derive(Debug, Clone)]
+pub struct Rendition {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub video_bitrate_kbps: u32,
+    /// Crop to fill the frame instead of letterboxing (vertical and square crops)
+    pub crop: bool,
+}
+
+impl Rendition {
+    /// A preset name ("4k", "1080p", "vertical", ...) or a custom "<width>x<height>", letterboxed
+    pub fn parse(value: &str) -> Result<Rendition, String> {
+        let value = value.trim().to_lowercase();
+        if let Some((name, width, height, bitrate, crop)) = RENDITION_PRESETS.iter().find(|(name, ..)| *name == value) {
+            return Ok(Rendition {
+                name: name.to_string(),
+                width: *width,
+                height: *height,
+                video_bitrate_kbps: *bitrate,
+                crop: *crop,
+            });
+        }
+        let (width, height) = value
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?)))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown rendition '{}'. Use {} or <width>x<height>",
+                    value,
+                    RENDITION_PRESETS.iter().map(|(name, ..)| *name).collect::<Vec<_>>().join(", ")
+                )
+            })?;
+        if !(16..=7680).contains(&width) || !(16..=7680).contains(&height) || width % 2 != 0 || height % 2 != 0 {
+            return Err(format!("Rendition {}x{} must be even and between 16 and 7680 pixels", width, height));
+        }
+        // Roughly 8 Mbps per 1080p frame area, scaled to the rendition's size
+        let bitrate = ((width as f64 * height as f64) / (1920.0 * 1080.0) * 8000.0).round().max(500.0) as u32;
+        Ok(Rendition { name: format!("{}x{}", width, height), width, height, video_bitrate_kbps: bitrate, crop: false })
+    }
+
+    fn filter(&self) -> String {
+        if self.crop {
+            format!(
+                "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1",
+                w = self.width,
+                h = self.height
+            )
+        } else {
+            format!(
+                "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1",
+                w = self.width,
+                h = self.height
+            )
+        }
+    }
+}
+
+/// Encode several renditions from a single decode of the input: the decoded video is split once
+/// in the filtergraph and every branch is scaled and encoded to its own file.
+/// `outputs` pairs each rendition with its output path.
+pub fn multi_export(input_file: &str, outputs: &[(Rendition, String)], fps: Option<f64>) -> Result<String, String> {
+    if outputs.is_empty() {
+        return Err("No renditions to export".to_string());
+    }
+
+    let count = outputs.len();
+    let mut graph = format!(
+        "[0:v]split={}{}",
+        count,
+        (0..count).map(|i| format!("[v{}]", i)).collect::<String>()
+    );
+    for (i, (rendition, _)) in outputs.iter().enumerate() {
+        graph.push_str(&format!(";[v{i}]{}[o{i}]", rendition.filter(), i = i));
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i").arg(input_file).arg("-filter_complex").arg(graph);
+    for (i, (rendition, output_file)) in outputs.iter().enumerate() {
+        command
+            .arg("-map")
+            .arg(format!("[o{}]", i))
+            .arg("-map")
+            .arg("0:a?");
+        if let Some(fps) = fps {
+            command.arg("-r").arg(fps.to_string());
+        }
+        command
+            .arg("-c:v")
+            .arg("libx264")
+            .arg("-b:v")
+            .arg(format!("{}k", rendition.video_bitrate_kbps))
+            .arg("-pix_fmt")
+            .arg("yuv420p")
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-b:a")
+            .arg("192k")
+            .arg("-movflags")
+            .arg("+faststart")
+            .arg("-y")
+            .arg(output_file);
+    }
 
     execute_ffmpeg_command(command)
 }
@@ -141,4 +332,126 @@ pub fn extract_frames(
         .arg(output_pattern);
 
     execute_ffmpeg_command(command)
-}
\ No newline at end of file
+}
+/// Times (seconds) where the picture cuts to a new shot: frames whose scene-change score is over
+/// `threshold` (0-1; lower finds more, subtler changes)
+pub fn scene_changes(input_file: &str, threshold: f64) -> Result<Vec<f64>, String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(input_file)
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!("select='gt(scene,{})',metadata=print:file=-", threshold))
+        .arg("-f")
+        .arg("null")
+        .arg("-");
+
+    Ok(pts_times(&execute_ffmpeg_command(command)?))
+}
+
+/// The `pts_time:` of each frame in `metadata=print` output
+fn pts_times(printed: &str) -> Vec<f64> {
+    printed
+        .split_whitespace()
+        .filter_map(|field| field.strip_prefix("pts_time:"))
+        .filter_map(|time| time.parse::<f64>().ok())
+        .collect()
+}
+
+/// Frames a contact sheet shows: the opening frame and each scene change (at least a second apart),
+/// thinned evenly to `max_frames`. A video with no cuts gets evenly spaced frames instead.
+pub fn contact_sheet_times(changes: &[f64], duration: f64, max_frames: usize) -> Vec<f64> {
+    let max_frames = max_frames.max(1);
+    let mut times = vec![0.0];
+    for &time in changes {
+        if time - times[times.len() - 1] >= 1.0 {
+            times.push(time);
+        }
+    }
+    if times.len() == 1 && duration > 0.0 {
+        return (0..max_frames).map(|i| (i as f64 + 0.5) * duration / max_frames as f64).collect();
+    }
+    if times.len() > max_frames {
+        times = (0..max_frames).map(|i| times[i * times.len() / max_frames]).collect();
+    }
+    times
+}
+
+/// A grid image of the frames at `times`, `columns` wide, each `thumb_width` pixels wide and
+/// labelled with its timestamp
+pub fn contact_sheet(
+    input_file: &str,
+    output_file: &str,
+    times: &[f64],
+    columns: usize,
+    thumb_width: u32,
+) -> Result<String, String> {
+    if times.is_empty() {
+        return Err("No frames to put on the contact sheet".to_string());
+    }
+    let columns = columns.clamp(1, times.len());
+    let rows = times.len().div_ceil(columns);
+    let thumb_height = (thumb_width * 9 / 16) & !1;
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSansMono-Bold.ttf";
+
+    let mut command = Command::new("ffmpeg");
+    let mut filter = String::new();
+    for (i, time) in times.iter().enumerate() {
+        command.arg("-ss").arg(format!("{:.3}", time)).arg("-i").arg(input_file);
+        let label = crate::utils::format_duration(*time);
+        let label = label.split('.').next().unwrap_or(&label);
+        filter.push_str(&format!(
+            "[{i}:v]trim=end_frame=1,setpts=PTS-STARTPTS,\
+             scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color=black,setsar=1,\
+             drawtext=fontfile={font}:text='{label}':fontsize={size}:fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=4:x=6:y=h-th-6[f{i}];",
+            i = i,
+            w = thumb_width,
+            h = thumb_height,
+            font = font,
+            label = label.replace(':', "\\:"),
+            size = (thumb_height / 10).max(10),
+        ));
+    }
+    for i in 0..times.len() {
+        filter.push_str(&format!("[f{}]", i));
+    }
+    filter.push_str(&format!(
+        "concat=n={}:v=1:a=0,tile={}x{}:padding=6:margin=6:color=0x202020[sheet]",
+        times.len(),
+        columns,
+        rows
+    ));
+
+    command
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("[sheet]")
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contact_sheets_show_scene_changes_or_even_frames() {
+        let printed = "frame:0    pts:2502    pts_time:2.502\nlavfi.scene_score=0.41\nframe:1    pts:7007    pts_time:7.007\n";
+        assert_eq!(pts_times(printed), [2.502, 7.007]);
+
+        // Changes under a second after the last kept frame are flicker, not new shots
+        assert_eq!(contact_sheet_times(&[0.4, 2.5, 3.0, 7.0], 10.0, 24), [0.0, 2.5, 7.0]);
+        let many: Vec<f64> = (1..100).map(f64::from).collect();
+        let thinned = contact_sheet_times(&many, 100.0, 10);
+        assert_eq!(thinned.len(), 10);
+        assert_eq!(thinned[..3], [0.0, 10.0, 20.0]);
+        assert_eq!(contact_sheet_times(&[], 60.0, 4), [7.5, 22.5, 37.5, 52.5]);
+    }
+}
@@ -0,0 +1,95 @@
+// src/handlers/activity.rs
+//! Activity feed - the user's uploads, renders, publishes, approvals and failures, newest first,
+//! from the workspace audit log. Page back with `before=<next_cursor>`.
+
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::services::activity::CATEGORIES;
+use crate::services::ActivityService;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct ActivityQuery {
+    /// Comma-separated categories to include; all when missing
+    pub category: Option<String>,
+    /// Only events in this chat session
+    pub session_id: Option<String>,
+    /// Only events at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Cursor: page back from this event id
+    pub before: Option<i32>,
+    pub limit: Option<i64>,
+}
+
+pub fn activity_routes() -> Router {
+    Router::new()
+        .route("/api/activity", get(get_activity))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+/// GET /api/activity - newest first, with the cursor for the next page
+async fn get_activity(
+    Query(query): Query<ActivityQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let categories: Vec<String> = query
+        .category
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|category| category.trim().to_string())
+        .filter(|category| !category.is_empty())
+        .collect();
+    if let Some(unknown) = categories.iter().find(|category| !CATEGORIES.contains(&category.as_str())) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "success": false,
+                "error": format!("Unknown category '{}'; expected one of {}", unknown, CATEGORIES.join(", "))
+            })),
+        ));
+    }
+
+    let limit = query.limit.unwrap_or(50);
+    let events = ActivityService::feed(
+        &state.db_pool,
+        user_id(&claims),
+        &categories,
+        query.session_id.as_deref(),
+        query.since,
+        query.before,
+        limit,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to load activity feed: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Failed to load activity" })))
+    })?;
+
+    // A full page means there may be more behind it
+    let next_cursor = (events.len() as i64 == limit.clamp(1, crate::services::activity::MAX_PAGE))
+        .then(|| events.last().map(|event| event.id))
+        .flatten();
+
+    Ok(Json(json!({
+        "success": true,
+        "events": events,
+        "next_cursor": next_cursor,
+    })))
+}
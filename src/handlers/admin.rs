@@ -1,6 +1,9 @@
 use crate::models::{admin::*, auth::*};
 use crate::middleware::admin::{admin_middleware, superuser_middleware};
 use crate::middleware::auth::auth_middleware;
+use crate::models::embedding_index::StartReindexRequest;
+use crate::models::vector_migration::StartVectorMigrationRequest;
+use crate::services::{EmbeddingIndexService, VectorMigrationService};
 use crate::AppState;
 use axum::{
     extract::{Extension, Path, Query},
@@ -33,6 +36,7 @@ pub fn admin_routes() -> Router {
         .route("/api/admin/users", get(admin_users_api))
         .route("/api/admin/users/:id", get(admin_user_api))
         .route("/api/admin/users/:id", put(admin_update_user_api))
+        .route("/api/admin/users/:id/plan", put(admin_set_user_plan))
         .route("/api/admin/users/:id/toggle-active", post(admin_toggle_user_active))
         .route("/api/admin/users/:id/make-staff", post(admin_make_staff))
         .route("/api/admin/users/:id/remove-staff", post(admin_remove_staff))
@@ -47,6 +51,8 @@ pub fn admin_routes() -> Router {
         .route("/api/admin/default-model", post(update_default_model))
         .route("/api/admin/youtube/status", get(get_youtube_feature_status))
         .route("/api/admin/youtube/toggle", post(toggle_youtube_features))
+        .route("/api/admin/jobs/:id/log", get(get_job_failure_log))
+        .route("/api/admin/jobs/:id/priority", put(set_job_priority))
         .layer(axum::middleware::from_fn(admin_middleware))
         .layer(axum::middleware::from_fn(auth_middleware));
     
@@ -54,6 +60,15 @@ pub fn admin_routes() -> Router {
         .route("/api/admin/users/:id/make-superuser", post(admin_make_superuser))
         .route("/api/admin/users/:id/remove-superuser", post(admin_remove_superuser))
         .route("/api/admin/create-superuser", post(create_superuser_api))
+        .route("/api/admin/config", get(get_config_dump))
+        .route("/api/admin/vector-migrations", get(list_vector_migrations).post(start_vector_migration))
+        .route("/api/admin/vector-migrations/:id", get(get_vector_migration))
+        .route("/api/admin/vector-migrations/:id/resume", post(resume_vector_migration))
+        .route("/api/admin/embedding-indexes", get(list_embedding_indexes).post(start_reindex))
+        .route("/api/admin/embedding-indexes/:id", get(get_embedding_index))
+        .route("/api/admin/embedding-indexes/:id/resume", post(resume_reindex))
+        .route("/api/admin/embedding-indexes/:id/cutover", post(cutover_embedding_index))
+        .route("/api/admin/chaos", get(get_chaos_settings).put(update_chaos_settings).delete(reset_chaos_settings))
         .layer(axum::middleware::from_fn(superuser_middleware))
         .layer(axum::middleware::from_fn(auth_middleware));
     
@@ -1101,6 +1116,25 @@ pub async fn admin_update_user_api(Path(_id): Path<i32>) -> Result<(), StatusCod
     Err(StatusCode::NOT_IMPLEMENTED)
 }
 
+/// Move a user between plans (free, pro, business)
+pub async fn admin_set_user_plan(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Json(payload): Json<crate::models::embed::UpdateUserPlanRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let updated = crate::services::EmbedService::set_user_plan(&state.db_pool, id, &payload.plan)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    if !updated {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "User not found" }))));
+    }
+    Ok(Json(json!({
+        "success": true,
+        "plan": payload.plan.trim().to_lowercase()
+    })))
+}
+
 pub async fn admin_toggle_user_active(Path(_id): Path<i32>) -> Result<(), StatusCode> {
     Err(StatusCode::NOT_IMPLEMENTED)
 }
@@ -1633,4 +1667,222 @@ pub async fn toggle_youtube_features(
         "message": format!("YouTube features {}", if payload.enabled { "enabled for all users" } else { "disabled (testing mode)" }),
         "enabled": payload.enabled
     })))
-}
\ No newline at end of file
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Effective configuration with secrets masked, plus the file and env vars it was resolved from
+pub async fn get_config_dump() -> Json<serde_json::Value> {
+    let config = crate::config::get();
+    Json(json!({
+        "success": true,
+        "config": config.redacted(),
+        "sources": crate::config::sources(),
+    }))
+}
+
+// ============================================================================
+// Vector memory migration
+// ============================================================================
+
+pub async fn list_vector_migrations(
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let migrations = VectorMigrationService::list(&state.db_pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "migrations": migrations })))
+}
+
+/// Start copying the chat memory from one vector backend to another in the background
+pub async fn start_vector_migration(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<StartVectorMigrationRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let migration = VectorMigrationService::start(
+        state.clone(),
+        &payload.source,
+        &payload.target,
+        payload.batch_size,
+        payload.reembed.unwrap_or(false),
+        claims.sub.parse::<i32>().unwrap_or(0),
+    )
+    .await
+    .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    tracing::info!("🧠 Vector migration {} ({} → {}) started by {}", migration.id, migration.source, migration.target, claims.email);
+    Ok(Json(json!({ "success": true, "migration": migration })))
+}
+
+pub async fn get_vector_migration(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let migration = VectorMigrationService::get(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(json!({ "success": true, "migration": migration })))
+}
+
+/// Continue a failed or interrupted migration from its last copied page
+pub async fn resume_vector_migration(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let migration = VectorMigrationService::resume(state.clone(), id)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, Json(json!({ "success": false, "error": e }))))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Vector migration not found" }))))?;
+
+    Ok(Json(json!({ "success": true, "migration": migration })))
+}
+
+// ============================================================================
+// Embedding indexes
+// ============================================================================
+
+pub async fn list_embedding_indexes(
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let indexes = EmbeddingIndexService::list(&state.db_pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "active_model": EmbeddingIndexService::model(&state).as_str(), "indexes": indexes })))
+}
+
+/// Start re-embedding the chat memory with another model into a new index in the background
+pub async fn start_reindex(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<StartReindexRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let index = EmbeddingIndexService::start(
+        state.clone(),
+        &payload.model,
+        payload.batch_size,
+        claims.sub.parse::<i32>().unwrap_or(0),
+    )
+    .await
+    .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    tracing::info!("🧠 Re-embedding chat memory with {} into {} started by {}", index.model, index.collection, claims.email);
+    Ok(Json(json!({ "success": true, "index": index })))
+}
+
+pub async fn get_embedding_index(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let index = EmbeddingIndexService::get(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(json!({ "success": true, "index": index })))
+}
+
+/// Continue a failed or interrupted re-embedding from its last checkpoint
+pub async fn resume_reindex(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let index = EmbeddingIndexService::resume(state.clone(), id)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, Json(json!({ "success": false, "error": e }))))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Embedding index not found" }))))?;
+
+    Ok(Json(json!({ "success": true, "index": index })))
+}
+
+/// Switch memory storage and search to a ready index
+pub async fn cutover_embedding_index(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let index = EmbeddingIndexService::cutover(&state, id)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, Json(json!({ "success": false, "error": e }))))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Embedding index not found" }))))?;
+
+    tracing::info!("🧠 Chat memory cut over to {} ({}) by {}", index.collection, index.model, claims.email);
+    Ok(Json(json!({ "success": true, "index": index })))
+}
+
+// ============================================================================
+// Job logs
+// ============================================================================
+
+pub async fn get_job_failure_log(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let job = state
+        .job_manager
+        .get_job(&id)
+        .await
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Job not found" }))))?;
+    let log = job
+        .failure_log
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "No log captured; logs are kept only for failed jobs" }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "job_id": job.id,
+        "job_type": job.job_type,
+        "session_id": job.session_id,
+        "request_id": job.request_id,
+        "status": job.status,
+        "completed_at": job.completed_at,
+        "log": log,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct JobPriorityRequest {
+    pub priority: crate::jobs::JobPriority,
+}
+
+/// Override a job's priority; a job still waiting for a worker moves to its new place in the queue
+pub async fn set_job_priority(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Json(request): Json<JobPriorityRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    if !state.job_manager.set_priority(&id, request.priority).await {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Job not found or already finished" }))));
+    }
+    let status = state.job_manager.get_job_status(&id).await;
+
+    Ok(Json(json!({ "success": true, "job_id": id, "priority": request.priority, "status": status })))
+}
+
+// ============================================================================
+// Chaos mode
+// ============================================================================
+
+pub async fn get_chaos_settings() -> Json<serde_json::Value> {
+    let (chaos, runtime_override) = crate::chaos::settings();
+    Json(json!({ "success": true, "chaos": chaos, "runtime_override": runtime_override }))
+}
+
+pub async fn update_chaos_settings(
+    Json(settings): Json<crate::config::ChaosConfig>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    crate::chaos::set(settings.clone())
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({ "success": true, "chaos": settings, "runtime_override": true })))
+}
+
+pub async fn reset_chaos_settings() -> Json<serde_json::Value> {
+    crate::chaos::reset();
+    get_chaos_settings().await
+}
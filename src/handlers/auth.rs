@@ -252,7 +252,7 @@ async fn login(
 }
 
 fn generate_jwt_token(user: &User) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
-    let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
+    let jwt_secret = crate::config::get().auth.jwt_secret.clone().unwrap_or_else(|| "default_secret".to_string());
     
     let expiration = Utc::now()
         .checked_add_signed(Duration::hours(24))
@@ -400,7 +400,7 @@ async fn verify_token(
 }
 
 pub fn verify_jwt_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
+    let jwt_secret = crate::config::get().auth.jwt_secret.clone().unwrap_or_else(|| "default_secret".to_string());
     
     let token_data = decode::<Claims>(
         token,
@@ -516,8 +516,7 @@ pub async fn initiate_google_oauth(
         "openid",
     ];
 
-    let redirect_uri = std::env::var("GOOGLE_OAUTH_REDIRECT_URI_AUTH")
-        .unwrap_or_else(|_| "http://localhost:3000/api/auth/google/callback".to_string());
+    let redirect_uri = crate::config::get().auth.google_oauth_redirect_uri_auth.clone();
 
     let auth_url = youtube_client::build_google_oauth_url(
         client_id,
@@ -582,8 +581,7 @@ pub async fn google_oauth_callback(
     // Exchange code for tokens
     let client_id = state.google_oauth_client_id.as_ref().unwrap();
     let client_secret = state.google_oauth_client_secret.as_ref().unwrap();
-    let redirect_uri = std::env::var("GOOGLE_OAUTH_REDIRECT_URI_AUTH")
-        .unwrap_or_else(|_| "http://localhost:3000/api/auth/google/callback".to_string());
+    let redirect_uri = crate::config::get().auth.google_oauth_redirect_uri_auth.clone();
 
     let client = reqwest::Client::new();
     let token_response = youtube_client::exchange_code_for_token(
@@ -734,7 +732,7 @@ pub async fn google_oauth_callback(
     };
 
     // Generate JWT token
-    let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+    let jwt_secret = crate::config::get().auth.jwt_secret.clone().expect("auth.jwt_secret (JWT_SECRET) must be set");
 
     let claims = Claims {
         sub: user.id.to_string(),
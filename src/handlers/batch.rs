@@ -0,0 +1,198 @@
+// src/handlers/batch.rs
+//! Bulk personalization endpoints - CSV + render template in, one render job per row out -
+//! and batch operations, one edit applied to every video in a session
+
+use axum::{
+    extract::{multipart::Multipart, DefaultBodyLimit, Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use crate::jobs::batch_process_job::{self, BatchOperation};
+use crate::jobs::batch_render_job::{self, BatchRenderRequest, RenderTemplate};
+use crate::middleware::{auth::auth_middleware, idempotency::idempotency_middleware};
+use crate::models::auth::Claims;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn batch_routes() -> Router {
+    Router::new()
+        .route("/api/batch/render", post(create_batch_render).layer(axum::middleware::from_fn(idempotency_middleware)))
+        .route("/api/batch/process", post(create_batch_process).layer(axum::middleware::from_fn(idempotency_middleware)))
+        .route("/api/batch/:job_id/manifest", get(get_batch_manifest))
+        .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+#[derive(Deserialize)]
+pub struct BatchProcessRequest {
+    pub session_id: String,
+    #[serde(flatten)]
+    pub operation: BatchOperation,
+    /// A subset of the session's videos; all of them when empty
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": message.into() })))
+}
+
+/// POST /api/batch/render - multipart with `csv` (file), `template` (JSON), optional `session_id` and `concurrency`
+async fn create_batch_render(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    mut multipart: Multipart,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let mut csv_text = None;
+    let mut template_text = None;
+    let mut session_id = format!("batch-{}", claims.sub);
+    let mut concurrency = 2;
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let name = field.name().unwrap_or("").to_string();
+        let text = field.text().await.map_err(|e| bad_request(format!("Failed to read field '{}': {}", name, e)))?;
+        match name.as_str() {
+            "csv" => csv_text = Some(text),
+            "template" => template_text = Some(text),
+            "session_id" if !text.trim().is_empty() => session_id = text.trim().to_string(),
+            "concurrency" => concurrency = text.trim().parse().unwrap_or(concurrency),
+            _ => {}
+        }
+    }
+
+    let csv_text = csv_text.ok_or_else(|| bad_request("Missing 'csv' field"))?;
+    let template_text = template_text.ok_or_else(|| bad_request("Missing 'template' field"))?;
+
+    let (headers, rows) = crate::utils::parse_csv(&csv_text).map_err(bad_request)?;
+    let template: RenderTemplate = serde_json::from_str(&template_text)
+        .map_err(|e| bad_request(format!("Invalid template JSON: {}", e)))?;
+    batch_render_job::validate_template(&template, &headers).map_err(bad_request)?;
+
+    let export_settings = match template.export_preset.as_deref() {
+        Some(preset) => {
+            let user_id = claims.sub.parse::<i32>().ok();
+            let settings = crate::services::ExportPresetService::resolve(&state.db_pool, user_id, preset)
+                .await
+                .map_err(bad_request)?;
+            if settings.burn_captions && template.captions_file.is_none() {
+                return Err(bad_request(format!(
+                    "Export preset '{}' burns captions; set the template's captions_file", preset
+                )));
+            }
+            Some(settings)
+        }
+        None => None,
+    };
+
+    let row_count = rows.len();
+    let request = BatchRenderRequest {
+        session_id: session_id.clone(),
+        user_id: Some(claims.sub.clone()),
+        template,
+        export_settings,
+        rows,
+        concurrency,
+    };
+    let job_id = batch_render_job::spawn_batch_render_job(request, state.job_manager.clone(), state.db_pool.clone())
+        .await
+        .map_err(bad_request)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "job_id": job_id,
+        "session_id": session_id,
+        "rows": row_count,
+        "columns": headers,
+        "status_url": format!("/api/jobs/{}/status", job_id),
+        "manifest_url": format!("/api/batch/{}/manifest", job_id),
+    })))
+}
+
+/// POST /api/batch/process - `{ "session_id", "tool", "args", "files"? }`: apply one edit to the session's videos
+async fn create_batch_process(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<BatchProcessRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let internal_error = |e: sqlx::Error| {
+        tracing::error!("Batch process request failed: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Batch process request failed" })))
+    };
+    let owns_session = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(&payload.session_id)
+    .bind(claims.sub.parse::<i32>().unwrap_or(0))
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(internal_error)?
+    .is_some();
+    if !owns_session {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Session not found" }))));
+    }
+    batch_process_job::validate_operation(&payload.operation).map_err(bad_request)?;
+
+    let videos = batch_process_job::session_videos(&state.db_pool, &payload.session_id)
+        .await
+        .map_err(internal_error)?;
+    let files = if payload.files.is_empty() {
+        videos
+    } else {
+        if let Some(other) = payload.files.iter().find(|file| !videos.contains(file)) {
+            return Err(bad_request(format!("{} is not a video in this session", other)));
+        }
+        payload.files
+    };
+
+    let file_count = files.len();
+    let job_id = batch_process_job::spawn_batch_process_job(
+        payload.session_id.clone(),
+        Some(claims.sub.clone()),
+        payload.operation,
+        files,
+        state.job_manager.clone(),
+        state.db_pool.clone(),
+    )
+    .await
+    .map_err(bad_request)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "job_id": job_id,
+        "session_id": payload.session_id,
+        "files": file_count,
+        "status_url": format!("/api/jobs/{}/status", job_id),
+        "manifest_url": format!("/api/batch/{}/manifest", job_id),
+    })))
+}
+
+/// GET /api/batch/:job_id/manifest - combined results once the batch has finished
+async fn get_batch_manifest(
+    Path(job_id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let job = state.job_manager.get_job(&job_id).await.ok_or(StatusCode::NOT_FOUND)?;
+    let is_batch = matches!(job.job_type.as_str(), "batch_render" | "batch_process");
+    if !is_batch || job.user_id.as_deref() != Some(claims.sub.as_str()) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let batch_dir = job.input_data["batch_dir"].as_str().ok_or(StatusCode::NOT_FOUND)?;
+    match tokio::fs::read_to_string(format!("{}/manifest.json", batch_dir)).await {
+        Ok(text) => serde_json::from_str(&text)
+            .map(Json)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR),
+        // Manifest is written when the last row or file finishes
+        Err(_) => Ok(Json(json!({
+            "success": false,
+            "job_id": job_id,
+            "status": job.status,
+            "message": "Batch is still rendering",
+        }))),
+    }
+}
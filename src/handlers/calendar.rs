@@ -0,0 +1,93 @@
+// src/handlers/calendar.rs
+//! Content calendar - scheduled jobs, scheduled YouTube publishes and the clipping pipeline's
+//! planned runs and posts in one time-ordered list, for a calendar UI.
+
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::services::calendar::{DEFAULT_RANGE_DAYS, KINDS, MAX_RANGE_DAYS};
+use crate::services::CalendarService;
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct ScheduleQuery {
+    /// Start of the range; defaults to now
+    pub from: Option<DateTime<Utc>>,
+    /// End of the range; defaults to 30 days after `from`
+    pub to: Option<DateTime<Utc>>,
+    /// Comma-separated entry kinds to include; all when missing
+    pub kinds: Option<String>,
+}
+
+pub fn calendar_routes() -> Router {
+    Router::new()
+        .route("/api/schedule", get(get_schedule))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+/// GET /api/schedule?from=&to=&kinds= - everything lined up in the range, in time order
+async fn get_schedule(
+    Query(query): Query<ScheduleQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let from = query.from.unwrap_or_else(Utc::now);
+    let to = query.to.unwrap_or(from + Duration::days(DEFAULT_RANGE_DAYS));
+    if to <= from {
+        return Err(bad_request("'to' must be after 'from'".to_string()));
+    }
+    if to - from > Duration::days(MAX_RANGE_DAYS) {
+        return Err(bad_request(format!("The range can cover at most {} days", MAX_RANGE_DAYS)));
+    }
+
+    let kinds: Vec<String> = query
+        .kinds
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|kind| kind.trim().to_string())
+        .filter(|kind| !kind.is_empty())
+        .collect();
+    if let Some(unknown) = kinds.iter().find(|kind| !KINDS.contains(&kind.as_str())) {
+        return Err(bad_request(format!("Unknown kind '{}'; expected one of {}", unknown, KINDS.join(", "))));
+    }
+
+    let entries = CalendarService::entries(&state.db_pool, user_id(&claims), from, to, &kinds)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load content calendar: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Failed to load schedule" })))
+        })?;
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for entry in &entries {
+        *counts.entry(entry.kind.as_str()).or_default() += 1;
+    }
+
+    Ok(Json(json!({
+        "success": true,
+        "from": from,
+        "to": to,
+        "counts": counts,
+        "entries": entries,
+    })))
+}
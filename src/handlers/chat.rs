@@ -1,7 +1,10 @@
 // src/handlers/chat.rs
+use crate::agent::slash_commands::{self, SlashCommand};
 use crate::handlers::upload::get_or_create_session;
 use crate::middleware::auth::auth_middleware;
 use crate::middleware::frontend_rate_limit::ai_operation_rate_limit_middleware;
+use crate::qdrant_client::NewChatMemory;
+use crate::services::{EmbeddingIndexService, MemorySearchService, OutputPreviewService};
 use crate::AppState;
 use axum::{
     extract::{
@@ -102,6 +105,12 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
     state.job_manager.register_progress_sender(session_id.clone(), progress_tx).await;
     tracing::info!("📡 Registered progress updates for session: {}", session_id);
 
+    // 🔔 NOTIFICATIONS: The session owner's new notifications are pushed here too (none for anonymous sessions)
+    let mut notification_rx = match get_session_owner(&session_id, &state).await {
+        Some(user_id) => crate::services::NotificationService::subscribe(user_id),
+        None => tokio::sync::mpsc::unbounded_channel().1,
+    };
+
     // 🆕 AGENT PROGRESS: Create separate channel for agent thinking/tool calling updates
     let (agent_progress_tx, mut agent_progress_rx) = tokio::sync::mpsc::unbounded_channel();
 
@@ -130,44 +139,49 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
             Some(Ok(message)) = receiver.next() => {
                 tracing::debug!("📥 Received WebSocket message in session: {}", session_id);
                 if let Message::Text(text) = message {
-                    tracing::info!("💬 Got message in session {}: {}", session_id, text);
-
-            // Build context from vector database if available (prefer Qdrant over AstraDB)
-            let context = if let Some(ref qdrant_client) = state.qdrant_client {
-                // Prefer Voyage embeddings for Claude, fallback to Gemini
-                if let Some(ref voyage_embeddings) = state.voyage_embeddings {
-                    match qdrant_client.build_context_for_query_with_voyage(&text, &session_id, voyage_embeddings).await {
-                        Ok(ctx) => {
-                            if !ctx.is_empty() {
-                                tracing::debug!("Built context from Qdrant with Voyage AI: {} chars", ctx.len());
-                                Some(ctx)
-                            } else {
-                                None
+                    // Each turn gets its own request id, carried into the agent's LLM calls and any job it starts
+                    let turn_id = crate::middleware::request_id::new_id();
+                    tracing::info!(request_id = %turn_id, "💬 Got message in session {}: {}", session_id, text);
+
+                    // Exact slash-commands (`/trim 0:10 0:30`) run their tool directly, without an agent turn
+                    if let Some(command) = crate::agent::slash_commands::parse(&text) {
+                        let (reply, output_files) = crate::middleware::request_id::scope(
+                            turn_id.clone(),
+                            run_slash_command(command, &text, &session_id, &state),
+                        ).await;
+                        let outputs = OutputPreviewService::cards_for_message(&reply, &output_files).await;
+                        let json_response = serde_json::json!({
+                            "type": "message",
+                            "content": reply,
+                            "outputs": outputs,
+                            "request_id": turn_id,
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                        });
+                        if let Ok(json_str) = serde_json::to_string(&json_response) {
+                            if sender.send(Message::Text(json_str)).await.is_err() {
+                                tracing::error!("Failed to send slash-command result to WebSocket");
+                                break;
                             }
                         }
-                        Err(e) => {
-                            tracing::warn!("Failed to build context from Qdrant with Voyage: {}", e);
-                            None
-                        }
+                        continue;
                     }
-                } else if let Some(ref gemini_client) = state.gemini_client {
-                    match qdrant_client.build_context_for_query_with_gemini(&text, &session_id, gemini_client).await {
-                        Ok(ctx) => {
-                            if !ctx.is_empty() {
-                                tracing::debug!("Built context from Qdrant with Gemini: {} chars", ctx.len());
-                                Some(ctx)
-                            } else {
-                                None
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to build context from Qdrant: {}", e);
+
+            // Build context from vector database if available (prefer Qdrant over AstraDB)
+            let context = if state.qdrant_client.is_some() {
+                // Vector similarity fused with keyword matches, so exact filenames aren't missed
+                match MemorySearchService::build_context(&state, &text, &session_id).await {
+                    Ok(ctx) => {
+                        if !ctx.is_empty() {
+                            tracing::debug!("Built context from hybrid memory search: {} chars", ctx.len());
+                            Some(ctx)
+                        } else {
                             None
                         }
                     }
-                } else {
-                    tracing::warn!("No embedding client available for Qdrant");
-                    None
+                    Err(e) => {
+                        tracing::warn!("Failed to build context from Qdrant: {}", e);
+                        None
+                    }
                 }
             } else if let Some(ref vector_db) = state.vector_db {
                 // Fallback to AstraDB
@@ -209,7 +223,32 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
             // Get uploaded files and output videos for this session
             let session_files = get_session_files(&session_id, &state).await.unwrap_or_default();
             let output_videos = get_session_output_videos(&session_id, &state).await.unwrap_or_default();
-            let file_context = build_file_context(&session_files, &output_videos);
+            // Large sessions get a summary; the agent looks the rest up with list_files / find_asset
+            let mut file_context = if session_files.len() + output_videos.len() <= crate::services::file_discovery::INLINE_FILE_LIMIT {
+                build_file_context(&session_files, &output_videos)
+            } else {
+                crate::services::FileDiscoveryService::summary_context(&session_files, &output_videos)
+            };
+
+            // The user's asset library is available in every session
+            if let Some(owner_id) = get_session_owner(&session_id, &state).await {
+                match crate::services::LibraryService::build_library_context(&state.db_pool, owner_id).await {
+                    Ok(library_context) => file_context.push_str(&library_context),
+                    Err(e) => tracing::warn!("Failed to load asset library context: {}", e),
+                }
+            }
+
+            // Undo/redo needs the agent to know which edit is current and what can be brought back
+            match crate::services::EditHistoryService::build_history_context(&state.db_pool, &session_id).await {
+                Ok(history_context) => file_context.push_str(&history_context),
+                Err(e) => tracing::warn!("Failed to load edit history context: {}", e),
+            }
+
+            // Chapter indexes let the agent find content in long recordings by topic
+            match crate::services::ChapteringService::build_chapter_context(&state.db_pool, &session_id).await {
+                Ok(chapter_context) => file_context.push_str(&chapter_context),
+                Err(e) => tracing::warn!("Failed to load chapter context: {}", e),
+            }
             
             if !session_files.is_empty() || !output_videos.is_empty() {
                 tracing::info!("Including {} uploaded file(s) and {} output video(s) in AI context for session {}",
@@ -251,14 +290,14 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                 if let Some(ref claude_client) = state.claude_client {
                     let agent = StatefulClaudeAgent::new(Arc::new(claude_client.clone()));
 
-                    match agent.chat(
+                    match crate::middleware::request_id::scope(turn_id.clone(), agent.chat(
                         &text,
                         &session_id,
                         enhanced_query.clone(),
                         state.clone(),
                         state.job_manager.clone(),
                         Some(agent_progress_tx.clone()),
-                    ).await {
+                    )).await {
                         Ok(resp) => resp,
                         Err(e) => format!("Sorry, I encountered an error: {}", e),
                     }
@@ -269,14 +308,14 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                 if let Some(ref gemini_client) = state.gemini_client {
                     let agent = StatefulGeminiAgent::new(Arc::new(gemini_client.clone()));
 
-                    match agent.chat(
+                    match crate::middleware::request_id::scope(turn_id.clone(), agent.chat(
                         &text,
                         &session_id,
                         enhanced_query.clone(),
                         state.clone(),
                         state.job_manager.clone(),
                         Some(agent_progress_tx.clone()),
-                    ).await {
+                    )).await {
                         Ok(resp) => resp,
                         Err(e) => format!("Sorry, I encountered an error: {}", e),
                     }
@@ -293,10 +332,13 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
 
             // Send AI's response
             if !response.is_empty() {
-
+                // Outputs the reply mentions go along as preview cards (poster, stream and download links)
+                let outputs = OutputPreviewService::cards_for_message(&response, &[]).await;
                 let json_response = serde_json::json!({
                     "type": "message",
                     "content": response,
+                    "outputs": outputs,
+                    "request_id": turn_id,
                     "timestamp": chrono::Utc::now().to_rfc3339(),
                 });
 
@@ -333,7 +375,7 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                 tracing::debug!("📡 Received progress update: {}", progress_update.message);
 
                 // 💾 Save completed job results to conversation history (PostgreSQL + Qdrant)
-                if let crate::jobs::JobStatus::Completed { ref result, .. } = progress_update.status {
+                if let crate::jobs::JobStatus::Completed { ref result, ref output_files, .. } = progress_update.status {
                     if !result.is_empty() {
                         tracing::info!("💾 Saving completed job result to PostgreSQL for session: {}", session_id);
                         let conversation_manager = crate::agent::conversation_manager::ConversationManager::new(state.db_pool.clone());
@@ -362,37 +404,33 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                                 String::new()
                             };
 
-                            if let Some(ref voyage_embeddings) = state.voyage_embeddings {
-                                if let Err(e) = qdrant_client.store_chat_memory_with_voyage(
-                                    &session_id,
-                                    None,
-                                    &user_message,
-                                    result,
-                                    files_referenced.clone(),
-                                    context_data.clone(),
-                                    voyage_embeddings,
-                                ).await {
-                                    tracing::warn!("Failed to store in Qdrant (Voyage): {}", e);
-                                }
-                            } else if let Some(ref gemini_client) = state.gemini_client {
-                                if let Err(e) = qdrant_client.store_chat_memory_with_gemini(
-                                    &session_id,
-                                    None,
-                                    &user_message,
-                                    result,
-                                    files_referenced,
-                                    context_data,
-                                    gemini_client,
-                                ).await {
-                                    tracing::warn!("Failed to store in Qdrant (Gemini): {}", e);
-                                }
+                            let stored = match EmbeddingIndexService::embed(&state, &user_message).await {
+                                Ok(embedding) => qdrant_client.store_chat_memory(
+                                    NewChatMemory {
+                                        session_id: &session_id,
+                                        user_id: None,
+                                        user_message: &user_message,
+                                        agent_response: result,
+                                        files_referenced,
+                                        context: context_data,
+                                    },
+                                    embedding,
+                                ).await.map_err(|e| e.to_string()),
+                                Err(e) => Err(e),
+                            };
+                            match stored {
+                                Ok(memory_id) => MemorySearchService::record(&state.db_pool, &memory_id, &session_id, &user_message, result).await,
+                                Err(e) => tracing::warn!("Failed to store in Qdrant: {}", e),
                             }
                         }
 
                         // 🎯 Send the final result to the user as a regular message
+                        let outputs = OutputPreviewService::cards_for_message(result, output_files).await;
                         let json_response = serde_json::json!({
                             "type": "message",
                             "content": result.clone(),
+                            "outputs": outputs,
+                            "request_id": progress_update.request_id,
                             "timestamp": chrono::Utc::now().to_rfc3339(),
                         });
 
@@ -408,6 +446,7 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                     let json_response = serde_json::json!({
                         "type": "message",
                         "content": format!("❌ {}", error),
+                        "request_id": progress_update.request_id,
                         "timestamp": chrono::Utc::now().to_rfc3339(),
                     });
 
@@ -422,6 +461,7 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                     let json_response = serde_json::json!({
                         "type": "progress",
                         "content": progress_update.message,
+                        "request_id": progress_update.request_id,
                         "timestamp": chrono::Utc::now().to_rfc3339(),
                     });
 
@@ -434,6 +474,22 @@ async fn websocket(stream: WebSocket, state: Arc<AppState>, session_uuid: Option
                 }
             }
 
+            // 🔔 NOTIFICATIONS: Push notifications (job results, clips to review, budget warnings...) as they arrive
+            Some(notification) = notification_rx.recv() => {
+                let json_response = serde_json::json!({
+                    "type": "notification",
+                    "notification": notification,
+                    "timestamp": chrono::Utc::now().to_rfc3339(),
+                });
+
+                if let Ok(json_str) = serde_json::to_string(&json_response) {
+                    if sender.send(Message::Text(json_str)).await.is_err() {
+                        tracing::error!("Failed to send notification to WebSocket");
+                        break;
+                    }
+                }
+            }
+
             // WebSocket closed - both streams ended
             else => {
                 tracing::warn!("❌ WebSocket event loop ended (both streams closed) for session: {}", session_id);
@@ -468,7 +524,7 @@ async fn get_session_output_videos(session_id: &str, state: &AppState) -> Result
     let output_videos = sqlx::query_as::<_, crate::models::file::OutputVideo>(
         "SELECT ov.* FROM output_videos ov 
          JOIN chat_sessions cs ON ov.session_id = cs.id 
-         WHERE cs.session_uuid = $1 AND ov.processing_status = 'completed'
+         WHERE cs.session_uuid = $1 AND ov.processing_status = 'completed' AND ov.deleted_at IS NULL AND ov.undone_at IS NULL
          ORDER BY ov.created_at DESC"
     )
     .bind(session_id)
@@ -478,6 +534,77 @@ async fn get_session_output_videos(session_id: &str, state: &AppState) -> Result
     Ok(output_videos)
 }
 
+/// Run a slash-command's tool and record the exchange in the conversation history; returns the
+/// reply and the files it produced
+async fn run_slash_command(command: SlashCommand, text: &str, session_id: &str, state: &Arc<AppState>) -> (String, Vec<String>) {
+    let command = match command {
+        SlashCommand::Help => return (slash_commands::HELP.to_string(), Vec::new()),
+        SlashCommand::Tool(command) => command,
+    };
+    let Some(input_file) = resolve_command_input(command.input.as_deref(), session_id, state).await else {
+        return (
+            "❌ There's no video in this chat to run that on yet. Upload one, or name the file at the end of the command.".to_string(),
+            Vec::new(),
+        );
+    };
+    let output_file = slash_commands::output_path(&input_file, &command);
+    let args = command.arguments(&input_file, &output_file);
+    tracing::info!("⚡ Slash-command /{} runs {} on {}", command.command, command.tool, input_file);
+
+    let ctx = crate::agent::tool_executor::ToolExecutionContext {
+        session_id: session_id.to_string(),
+        user_id: get_session_owner(session_id, state).await,
+        app_state: state.clone(),
+    };
+    let result = crate::agent::tool_executor::execute_tool_claude_with_context(command.tool, &args, &ctx).await;
+
+    let conversation_manager = crate::agent::conversation_manager::ConversationManager::new(state.db_pool.clone());
+    for message in [
+        crate::agent::conversation_manager::ConversationMessage::new_human(session_id.to_string(), text.to_string()),
+        crate::agent::conversation_manager::ConversationMessage::new_assistant(session_id.to_string(), result.clone()),
+    ] {
+        if let Err(e) = conversation_manager.save_message(&message).await {
+            tracing::error!("Failed to save slash-command exchange: {}", e);
+        }
+    }
+    let output_files = if result.starts_with("❌") { Vec::new() } else { vec![output_file] };
+    (result, output_files)
+}
+
+/// The file a command names (matched against the session's uploads and outputs), or else the
+/// newest output video, or else the newest uploaded video
+async fn resolve_command_input(named: Option<&str>, session_id: &str, state: &AppState) -> Option<String> {
+    let files = get_session_files(session_id, state).await.unwrap_or_default();
+    let outputs = get_session_output_videos(session_id, state).await.unwrap_or_default();
+
+    if let Some(name) = named {
+        let upload = files
+            .iter()
+            .find(|f| f.original_name == name || f.stored_name == name || f.file_path.ends_with(name))
+            .map(|f| f.file_path.clone());
+        let output = outputs
+            .iter()
+            .find(|v| v.file_name == name || v.file_path.ends_with(name))
+            .map(|v| v.file_path.clone());
+        return Some(output.or(upload).unwrap_or_else(|| name.to_string()));
+    }
+
+    outputs
+        .iter()
+        .find(|v| slash_commands::is_video_file(&v.file_path))
+        .map(|v| v.file_path.clone())
+        .or_else(|| files.iter().find(|f| f.file_type == "video").map(|f| f.file_path.clone()))
+}
+
+async fn get_session_owner(session_id: &str, state: &AppState) -> Option<i32> {
+    sqlx::query_scalar::<_, i32>("SELECT user_id FROM chat_sessions WHERE session_uuid = $1")
+        .bind(session_id)
+        .fetch_optional(&state.db_pool)
+        .await
+        .ok()
+        .flatten()
+}
+
 // Build file context string for AI agent
 fn build_file_context(files: &[crate::models::file::UploadedFile], output_videos: &[crate::models::file::OutputVideo]) -> String {
     let mut context = String::new();
@@ -578,9 +705,9 @@ async fn get_chat_history(
     // CRITICAL: Verify that the session belongs to the authenticated user
     let user_id = claims.sub.parse::<i32>().unwrap_or(0);
     
-    // Check if the session belongs to the user
+    // Check if the session belongs to the user (trashed sessions read as missing)
     let session_owner = sqlx::query_scalar::<_, i32>(
-        "SELECT user_id FROM chat_sessions WHERE session_uuid = $1"
+        "SELECT user_id FROM chat_sessions WHERE session_uuid = $1 AND deleted_at IS NULL"
     )
     .bind(&session_id)
     .fetch_optional(&state.db_pool)
@@ -803,7 +930,7 @@ async fn get_recent_chats(
 ) -> Result<axum::response::Json<serde_json::Value>, axum::http::StatusCode> {
     // Get recent chat sessions for the user from the database
     match sqlx::query_as::<_, (i32, String, String, chrono::DateTime<chrono::Utc>)>(
-        "SELECT id, session_uuid, title, created_at FROM chat_sessions WHERE user_id = $1 ORDER BY created_at DESC LIMIT 10"
+        "SELECT id, session_uuid, title, created_at FROM chat_sessions WHERE user_id = $1 AND deleted_at IS NULL AND archived_at IS NULL ORDER BY created_at DESC LIMIT 10"
     )
     .bind(claims.sub.parse::<i32>().unwrap_or(0))
     .fetch_all(&state.db_pool)
@@ -838,6 +965,8 @@ async fn get_recent_chats(
 struct AllChatsQuery {
     page: Option<i64>,
     limit: Option<i64>,
+    /// List archived sessions instead of active ones
+    archived: Option<bool>,
 }
 
 async fn get_all_chats(
@@ -850,12 +979,14 @@ async fn get_all_chats(
     let offset = (page - 1) * limit;
 
     let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let archived = params.archived.unwrap_or(false);
 
     // Get total count
     let total_count: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM chat_sessions WHERE user_id = $1"
+        "SELECT COUNT(*) FROM chat_sessions WHERE user_id = $1 AND deleted_at IS NULL AND (archived_at IS NOT NULL) = $2"
     )
     .bind(user_id)
+    .bind(archived)
     .fetch_one(&state.db_pool)
     .await
     .map_err(|e| {
@@ -867,13 +998,14 @@ async fn get_all_chats(
     let rows = sqlx::query_as::<_, (i32, String, String, chrono::DateTime<chrono::Utc>)>(
         "SELECT cs.id, cs.session_uuid, cs.title, cs.created_at
          FROM chat_sessions cs
-         WHERE cs.user_id = $1
+         WHERE cs.user_id = $1 AND cs.deleted_at IS NULL AND (cs.archived_at IS NOT NULL) = $4
          ORDER BY cs.created_at DESC
          LIMIT $2 OFFSET $3"
     )
     .bind(user_id)
     .bind(limit)
     .bind(offset)
+    .bind(archived)
     .fetch_all(&state.db_pool)
     .await
     .map_err(|e| {
@@ -904,6 +1036,7 @@ async fn get_all_chats(
     Ok(axum::response::Json(serde_json::json!({
         "success": true,
         "chats": chats,
+        "archived": archived,
         "pagination": {
             "page": page,
             "limit": limit,
@@ -7,8 +7,11 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
+use crate::clipping::cross_post::{CrossPostService, PLATFORMS};
 use crate::clipping::models::*;
-use crate::middleware::{auth::auth_middleware, clipping_access::clipping_access_middleware};
+use crate::middleware::{
+    auth::auth_middleware, clipping_access::clipping_access_middleware, idempotency::idempotency_middleware,
+};
 use crate::models::auth::Claims;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
@@ -40,6 +43,15 @@ pub fn clipping_routes() -> Router {
                 .patch(update_linkage)
                 .delete(delete_linkage),
         )
+        // Metadata policy per destination channel
+        .route(
+            "/api/clipping/destination-channels/:id/metadata-policy",
+            get(get_metadata_policy).put(update_metadata_policy),
+        )
+        .route(
+            "/api/clipping/destination-channels/:id/cross-post",
+            get(get_cross_post_config).put(update_cross_post_config),
+        )
         // Clipping job monitoring
         .route("/api/clipping/jobs", get(list_jobs))
         .route("/api/clipping/jobs/:id", get(get_job_status))
@@ -47,7 +59,8 @@ pub fn clipping_routes() -> Router {
         // Extracted clips
         .route("/api/clipping/clips", get(list_clips))
         .route("/api/clipping/clips/:id", get(get_clip_details))
-        .route("/api/clipping/clips/:id/repost", post(repost_clip))
+        .route("/api/clipping/clips/:id/repost", post(repost_clip).layer(axum::middleware::from_fn(idempotency_middleware)))
+        .route("/api/clipping/clips/:id/cross-posts", get(list_clip_cross_posts))
         // All routes protected by clipping access middleware
         .layer(axum::middleware::from_fn(clipping_access_middleware))
         .layer(axum::middleware::from_fn(auth_middleware))
@@ -73,6 +86,11 @@ async fn add_source_channel(
     Extension(state): Extension<Arc<AppState>>,
     Json(payload): Json<AddSourceChannelRequest>,
 ) -> Result<Json<Value>, StatusCode> {
+    let caption_style = payload.caption_style.clone().unwrap_or_default();
+    if caption_style.validate().is_err() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     // Fetch channel info from YouTube API
     let youtube_client = state
         .youtube_client
@@ -102,8 +120,8 @@ async fn add_source_channel(
     // Insert into database
     let source_channel = sqlx::query_as::<_, SourceChannel>(
         "INSERT INTO youtube_source_channels
-         (channel_id, channel_name, channel_thumbnail_url, subscriber_count, polling_interval_minutes)
-         VALUES ($1, $2, $3, $4, $5)
+         (channel_id, channel_name, channel_thumbnail_url, subscriber_count, polling_interval_minutes, caption_style)
+         VALUES ($1, $2, $3, $4, $5, $6)
          RETURNING *",
     )
     .bind(&channel.id.channel_id)
@@ -111,6 +129,7 @@ async fn add_source_channel(
     .bind(thumbnail_url)
     .bind(0i64) // Subscriber count can be fetched separately
     .bind(payload.polling_interval_minutes.unwrap_or(30))
+    .bind(sqlx::types::Json(&caption_style))
     .fetch_one(&state.db_pool)
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -174,6 +193,18 @@ async fn update_source_channel(
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     }
 
+    if !payload["caption_style"].is_null() {
+        let style: CaptionStyle = serde_json::from_value(payload["caption_style"].clone())
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        style.validate().map_err(|_| StatusCode::BAD_REQUEST)?;
+        sqlx::query("UPDATE youtube_source_channels SET caption_style = $1 WHERE id = $2")
+            .bind(sqlx::types::Json(&style))
+            .bind(id)
+            .execute(&state.db_pool)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
     Ok(Json(json!({
         "success": true,
         "message": "Source channel updated"
@@ -196,6 +227,111 @@ async fn remove_source_channel(
     })))
 }
 
+// Metadata Policy Handlers
+
+/// The destination channel, if it belongs to the user
+async fn owns_destination_channel(pool: &PgPool, channel_id: i32, user_id: i32) -> Result<bool, StatusCode> {
+    sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM connected_youtube_channels WHERE id = $1 AND user_id = $2)")
+        .bind(channel_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn get_metadata_policy(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i32>,
+) -> Result<Json<Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    if !owns_destination_channel(&state.db_pool, id, user_id).await? {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let policy = ClipMetadataPolicy::load(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "policy": policy
+    })))
+}
+
+async fn update_metadata_policy(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateMetadataPolicyRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let owned = owns_destination_channel(&state.db_pool, id, user_id)
+        .await
+        .map_err(|status| (status, Json(json!({ "success": false, "error": "Database error" }))))?;
+    if !owned {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Channel not found" }))));
+    }
+
+    let policy = ClipMetadataPolicy::upsert(&state.db_pool, id, &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "policy": policy
+    })))
+}
+
+async fn get_cross_post_config(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i32>,
+) -> Result<Json<Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    if !owns_destination_channel(&state.db_pool, id, user_id).await? {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let platforms = CrossPostService::platforms_for(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let available: Vec<Value> = PLATFORMS
+        .iter()
+        .map(|p| json!({ "platform": p.name, "aspect_ratio": p.aspect_ratio, "auto_publish": p.can_publish }))
+        .collect();
+
+    Ok(Json(json!({
+        "success": true,
+        "platforms": platforms,
+        "available_platforms": available
+    })))
+}
+
+async fn update_cross_post_config(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateCrossPostConfigRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let owned = owns_destination_channel(&state.db_pool, id, user_id)
+        .await
+        .map_err(|status| (status, Json(json!({ "success": false, "error": "Database error" }))))?;
+    if !owned {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Channel not found" }))));
+    }
+
+    let config = CrossPostService::save_config(&state.db_pool, id, &payload.platforms)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "config": config
+    })))
+}
+
 // Channel Linkage Handlers
 
 async fn list_linkages(
@@ -481,6 +617,20 @@ async fn get_clip_details(
     })))
 }
 
+async fn list_clip_cross_posts(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Value>, StatusCode> {
+    let cross_posts = CrossPostService::list_for_clip(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "cross_posts": cross_posts
+    })))
+}
+
 async fn repost_clip(
     Extension(state): Extension<Arc<AppState>>,
     Path(id): Path<i32>,
@@ -495,6 +645,7 @@ async fn repost_clip(
     .execute(&state.db_pool)
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    crate::services::ActivityService::record_clip(&state.db_pool, id, "clip_approved", None).await;
 
     Ok(Json(json!({
         "success": true,
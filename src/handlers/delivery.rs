@@ -0,0 +1,129 @@
+// src/handlers/delivery.rs
+//! Delivery target management and delivery receipts (SFTP/FTP/S3)
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::delivery::{CreateDeliveryTargetRequest, DeliveryTargetResponse};
+use crate::services::DeliveryService;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn delivery_routes() -> Router {
+    Router::new()
+        .route("/api/delivery/targets", get(list_targets).post(create_target))
+        .route("/api/delivery/targets/:id", delete(delete_target))
+        .route("/api/delivery/deliver", post(deliver_output))
+        .route("/api/delivery/receipts", get(list_receipts))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+#[derive(Deserialize)]
+pub struct DeliverRequest {
+    pub file_path: String,
+    pub target: String,
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+async fn list_targets(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let targets = DeliveryService::list_targets(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "targets": targets.into_iter().map(DeliveryTargetResponse::from).collect::<Vec<_>>()
+    })))
+}
+
+/// Create a target, or replace the one with the same name
+async fn create_target(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateDeliveryTargetRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let target = DeliveryService::create_target(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "target": DeliveryTargetResponse::from(target)
+    })))
+}
+
+async fn delete_target(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = DeliveryService::delete_target(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Delivery target removed" })))
+}
+
+/// Push an output file to one of the user's targets and return the receipt
+async fn deliver_output(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<DeliverRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let fail = |status: StatusCode, error: String| (status, Json(json!({ "success": false, "error": error })));
+
+    // Only finished outputs can be delivered
+    let path = std::path::Path::new(&payload.file_path);
+    if !payload.file_path.starts_with("outputs/") || path.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err(fail(StatusCode::BAD_REQUEST, "file_path must be inside outputs/".to_string()));
+    }
+    if !path.exists() {
+        return Err(fail(StatusCode::NOT_FOUND, format!("File not found: {}", payload.file_path)));
+    }
+
+    let uid = user_id(&claims);
+    let target = DeliveryService::get_target_by_name(&state.db_pool, uid, &payload.target)
+        .await
+        .map_err(|e| fail(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| fail(StatusCode::NOT_FOUND, format!("No delivery target named '{}'", payload.target)))?;
+
+    let receipt = DeliveryService::deliver(&state.db_pool, uid, &target, &payload.file_path)
+        .await
+        .map_err(|e| fail(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(json!({
+        "success": receipt.status == "delivered",
+        "receipt": receipt
+    })))
+}
+
+async fn list_receipts(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let receipts = DeliveryService::list_receipts(&state.db_pool, user_id(&claims), 100)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "receipts": receipts
+    })))
+}
@@ -0,0 +1,300 @@
+// src/handlers/download_links.rs
+//! Client delivery links - expiring, optionally password-protected downloads of an output, no account needed
+
+use axum::{
+    extract::{Extension, Form, Path},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Json, Redirect, Response},
+    routing::{delete, get},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::middleware::rate_limit::RateLimiter;
+use crate::models::auth::Claims;
+use crate::models::download_link::{CreateDownloadLinkRequest, DownloadLink, DownloadLinkResponse};
+use crate::services::output_stats::ViewEvent;
+use crate::services::{DownloadLinkService, OutputStatsService};
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, OnceLock};
+use tokio_util::io::ReaderStream;
+
+pub fn download_link_routes() -> Router {
+    // Public routes (the token, and the password if set, are the credential)
+    let public_routes = Router::new()
+        .route("/d/:token", get(download_page))
+        .route("/d/:token/file", get(download_file).post(download_file_with_password))
+        .route("/api/download/:token", get(get_download));
+
+    // Protected routes (link owner)
+    let protected_routes = Router::new()
+        .route("/api/outputs/:id/links", get(list_output_links).post(create_link))
+        .route("/api/download-links", get(list_links))
+        .route("/api/download-links/:id", delete(revoke_link))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
+    public_routes.merge(protected_routes)
+}
+
+/// Password guesses allowed per link in each window before the form is locked
+const PASSWORD_ATTEMPTS_PER_WINDOW: u32 = 10;
+const PASSWORD_ATTEMPT_WINDOW_SECONDS: u64 = 15 * 60;
+
+/// Password attempts per token, so a shared link can't be brute-forced from many IPs
+fn password_attempts() -> &'static RateLimiter {
+    static PASSWORD_ATTEMPTS: OnceLock<RateLimiter> = OnceLock::new();
+    PASSWORD_ATTEMPTS.get_or_init(|| RateLimiter::new(PASSWORD_ATTEMPTS_PER_WINDOW, PASSWORD_ATTEMPT_WINDOW_SECONDS))
+}
+
+#[derive(Deserialize)]
+pub struct DownloadForm {
+    pub password: Option<String>,
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+/// Look up an active link; expired, revoked, used-up and unknown tokens all read as 404
+async fn active_link(state: &AppState, token: &str) -> Result<DownloadLink, StatusCode> {
+    DownloadLinkService::get_active_link(&state.db_pool, token)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn create_link(
+    Path(output_id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateDownloadLinkRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let link = DownloadLinkService::create_link(&state.db_pool, user_id(&claims), output_id, &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "link": DownloadLinkResponse::from(link)
+    })))
+}
+
+async fn list_output_links(
+    Path(output_id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let links = DownloadLinkService::list_links(&state.db_pool, user_id(&claims), Some(output_id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "links": links.into_iter().map(DownloadLinkResponse::from).collect::<Vec<_>>()
+    })))
+}
+
+async fn list_links(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let links = DownloadLinkService::list_links(&state.db_pool, user_id(&claims), None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "links": links.into_iter().map(DownloadLinkResponse::from).collect::<Vec<_>>()
+    })))
+}
+
+async fn revoke_link(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let revoked = DownloadLinkService::revoke_link(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !revoked {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Download link revoked" })))
+}
+
+/// Link details for the download page (never exposes the server-side file path)
+async fn get_download(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<Value>, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    let size_bytes = tokio::fs::metadata(&link.file_path).await.map(|m| m.len()).ok();
+
+    Ok(Json(json!({
+        "success": true,
+        "file_name": link.file_name,
+        "size_bytes": size_bytes,
+        "password_required": link.password_hash.is_some(),
+        "expires_at": link.expires_at,
+        "downloads_remaining": link.downloads_remaining(),
+        "download_url": format!("/d/{}/file", token),
+    })))
+}
+
+/// GET /d/:token/file - direct download for links without a password
+async fn download_file(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    if !DownloadLinkService::check_password(&link, None).await {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    send_file(&state, &link, &headers).await
+}
+
+/// POST /d/:token/file - the download page's form; a wrong password goes back to the page
+async fn download_file_with_password(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    Form(form): Form<DownloadForm>,
+) -> Result<Response, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    if link.password_hash.is_some() {
+        let attempts = password_attempts();
+        if rand::random::<u8>() < 10 {
+            attempts.cleanup_expired();
+        }
+        if !attempts.check_rate_limit(&token) {
+            tracing::warn!("Too many password attempts for download link {}", link.id);
+            return Ok(Redirect::to(&format!("/d/{}?error=attempts", token)).into_response());
+        }
+    }
+    if !DownloadLinkService::check_password(&link, form.password.as_deref()).await {
+        return Ok(Redirect::to(&format!("/d/{}?error=password", token)).into_response());
+    }
+    send_file(&state, &link, &headers).await
+}
+
+async fn send_file(state: &AppState, link: &DownloadLink, headers: &HeaderMap) -> Result<Response, StatusCode> {
+    crate::handlers::output::ensure_hot(state, &link.file_path).await?;
+    let file = tokio::fs::File::open(&link.file_path).await.map_err(|e| {
+        tracing::error!("Failed to open {} for download link {}: {}", link.file_path, link.id, e);
+        StatusCode::NOT_FOUND
+    })?;
+    let size = file.metadata().await.map(|m| m.len()).ok();
+    let egress = crate::handlers::output::open_egress(Some(link.user_id))?;
+    // The last allowed download may have just been taken by a concurrent request
+    if !DownloadLinkService::record_download(&state.db_pool, link.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    OutputStatsService::record(&state.db_pool, link.output_id, ViewEvent::Download, "link", headers);
+
+    let content_type = crate::handlers::output::get_content_type_from_path(std::path::Path::new(&link.file_path));
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", link.file_name.replace('"', "")))
+        .header(header::CACHE_CONTROL, "private, no-store");
+    if let Some(size) = size {
+        response = response.header(header::CONTENT_LENGTH, size);
+    }
+    response
+        .body(axum::body::Body::from_stream(crate::utils::egress::throttle(ReaderStream::new(file), egress)))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// GET /d/:token - landing page with the file details, a password field when needed and the download button
+async fn download_page(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Html<String>, StatusCode> {
+    active_link(&state, &token).await?;
+
+    let html = r###"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex">
+    <title>⬇️ Download - VideoSync</title>
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; background: #0f0f13; color: #e8e8ee; min-height: 100vh; display: flex; align-items: center; justify-content: center; padding: 24px; }
+        .card { background: #17171d; border: 1px solid #26262e; border-radius: 10px; padding: 28px; width: 100%; max-width: 440px; }
+        h1 { font-size: 18px; font-weight: 600; word-break: break-all; margin-bottom: 8px; }
+        .meta { font-size: 13px; color: #8a8a99; margin-bottom: 20px; line-height: 1.6; }
+        form { display: flex; flex-direction: column; gap: 10px; }
+        input { background: #0f0f13; border: 1px solid #2e2e38; color: #e8e8ee; border-radius: 6px; padding: 10px; font: inherit; font-size: 14px; }
+        button { background: #5b6cff; color: #fff; border: none; border-radius: 6px; padding: 11px; font-weight: 600; font-size: 15px; cursor: pointer; }
+        .error { color: #ff6b6b; font-size: 13px; }
+    </style>
+</head>
+<body>
+    <div class="card">
+        <h1 id="name">Loading…</h1>
+        <div class="meta" id="meta"></div>
+        <form id="download-form" method="post" style="display: none">
+            <input id="password" name="password" type="password" placeholder="Password" autocomplete="current-password" style="display: none">
+            <span class="error" id="error"></span>
+            <button type="submit">⬇️ Download</button>
+        </form>
+    </div>
+    <script>
+        const TOKEN = 'DOWNLOAD_TOKEN_PLACEHOLDER';
+
+        function size(bytes) {
+            if (bytes == null) return '';
+            const units = ['B', 'KB', 'MB', 'GB'];
+            let i = 0;
+            while (bytes >= 1024 && i < units.length - 1) { bytes /= 1024; i++; }
+            return bytes.toFixed(i ? 1 : 0) + ' ' + units[i];
+        }
+
+        async function load() {
+            const res = await fetch('/api/download/' + TOKEN);
+            if (!res.ok) {
+                document.getElementById('name').textContent = 'This download link has expired';
+                return;
+            }
+            const info = await res.json();
+            document.title = '⬇️ ' + info.file_name + ' - Download';
+            document.getElementById('name').textContent = info.file_name;
+            const meta = [size(info.size_bytes), 'Link expires ' + new Date(info.expires_at).toLocaleString()];
+            if (info.downloads_remaining != null) meta.push(info.downloads_remaining + ' download(s) left');
+            document.getElementById('meta').textContent = meta.filter(Boolean).join(' · ');
+
+            const form = document.getElementById('download-form');
+            form.action = info.download_url;
+            form.style.display = 'flex';
+            if (info.password_required) {
+                const password = document.getElementById('password');
+                password.style.display = 'block';
+                password.required = true;
+                password.focus();
+            }
+            const error = new URLSearchParams(location.search).get('error');
+            if (error === 'password') {
+                document.getElementById('error').textContent = 'Wrong password';
+            } else if (error === 'attempts') {
+                document.getElementById('error').textContent = 'Too many attempts - try again in 15 minutes';
+            }
+        }
+
+        load();
+    </script>
+</body>
+</html>
+"###;
+
+    // The token matched a stored link, so it is one of our generated hex strings
+    Ok(Html(html.replace("DOWNLOAD_TOKEN_PLACEHOLDER", &token)))
+}
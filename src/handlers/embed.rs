@@ -0,0 +1,221 @@
+// src/handlers/embed.rs
+//! Public embeddable players - creators paste an iframe on their own site and viewers stream straight from VideoSync
+
+use axum::{
+    extract::{Extension, Path},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
+    routing::{delete, get},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::embed::{CreateEmbedLinkRequest, EmbedLink, EmbedLinkResponse};
+use crate::services::embed::embed_hls_dir;
+use crate::services::output_stats::ViewEvent;
+use crate::services::OutputStatsService;
+use crate::services::EmbedService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn embed_routes() -> Router {
+    // Public routes (the token is the credential)
+    let public_routes = Router::new()
+        .route("/embed/:token", get(embed_page))
+        .route("/embed/:token/stream", get(stream_embed_video))
+        .route("/embed/:token/hls/:file", get(serve_hls_file));
+
+    // Protected routes (embed owner)
+    let protected_routes = Router::new()
+        .route("/api/embeds", get(list_embeds).post(create_embed))
+        .route("/api/embeds/:id", delete(revoke_embed))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
+    public_routes.merge(protected_routes)
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+/// Look up an active embed; revoked and unknown tokens both read as 404
+async fn active_link(state: &AppState, token: &str) -> Result<EmbedLink, StatusCode> {
+    EmbedService::get_active_link(&state.db_pool, token)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn create_embed(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateEmbedLinkRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let link = EmbedService::create_link(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "embed": EmbedLinkResponse::from(link)
+    })))
+}
+
+async fn list_embeds(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let links = EmbedService::list_links(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let plan = EmbedService::get_user_plan(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "plan": plan,
+        "embeds": links.into_iter().map(EmbedLinkResponse::from).collect::<Vec<_>>()
+    })))
+}
+
+async fn revoke_embed(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let revoked = EmbedService::revoke_link(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !revoked {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Embed revoked" })))
+}
+
+async fn stream_embed_video(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    crate::handlers::output::ensure_hot(&state, &link.file_path).await?;
+    let egress = crate::handlers::output::open_egress(Some(link.user_id))?;
+    let response = crate::handlers::output::stream_file(std::path::Path::new(&link.file_path), egress).await?;
+    OutputStatsService::record(&state.db_pool, link.output_id, ViewEvent::Stream, "embed", &headers);
+    Ok(response)
+}
+
+/// Playlist and segments of a packaged embed; only flat `index.m3u8` / `segment_NNN.ts` names are served
+async fn serve_hls_file(
+    Path((token, file)): Path<(String, String)>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    let valid_name = file == "index.m3u8"
+        || (file.starts_with("segment_") && file.ends_with(".ts") && file[8..file.len() - 3].chars().all(|c| c.is_ascii_digit()));
+    if link.hls_status != "ready" || !valid_name {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let egress = crate::handlers::output::open_egress(Some(link.user_id))?;
+    let response = crate::handlers::output::stream_file(&embed_hls_dir(&link.token).join(&file), egress).await?;
+    // An HLS playback starts with one playlist fetch
+    if file == "index.m3u8" {
+        OutputStatsService::record(&state.db_pool, link.output_id, ViewEvent::Stream, "embed", &headers);
+    }
+    Ok(response)
+}
+
+/// GET /embed/:token - bare player meant to live inside an iframe on the creator's site
+async fn embed_page(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Response, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    if let Err(e) = EmbedService::record_view(&state.db_pool, link.id).await {
+        tracing::warn!("Failed to record embed view: {}", e);
+    }
+
+    let config = json!({
+        "title": link.title,
+        "mp4_url": format!("/embed/{}/stream", link.token),
+        "hls_url": (link.hls_status == "ready").then(|| format!("/embed/{}/hls/index.m3u8", link.token)),
+        "branding": !link.hide_branding,
+    });
+    // Keep the title from closing the inline <script>
+    let config = config.to_string().replace('<', "\\u003c");
+
+    let html = r###"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex">
+    <title>VideoSync Player</title>
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        html, body { width: 100%; height: 100%; background: #000; overflow: hidden; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; }
+        video { width: 100%; height: 100%; object-fit: contain; background: #000; }
+        .badge { position: absolute; right: 10px; bottom: 48px; background: rgba(15, 15, 19, 0.75); color: #e8e8ee; font-size: 12px; padding: 5px 9px; border-radius: 4px; text-decoration: none; transition: opacity 0.3s; }
+        .badge:hover { background: rgba(91, 108, 255, 0.9); }
+        body.playing .badge { opacity: 0; }
+        body.playing:hover .badge { opacity: 1; }
+    </style>
+</head>
+<body>
+    <video id="player" controls playsinline preload="metadata"></video>
+    <script>
+        const CONFIG = EMBED_CONFIG_PLACEHOLDER;
+        const player = document.getElementById('player');
+        document.title = CONFIG.title;
+        player.setAttribute('aria-label', CONFIG.title);
+
+        if (CONFIG.branding) {
+            const badge = document.createElement('a');
+            badge.className = 'badge';
+            badge.href = '/';
+            badge.target = '_blank';
+            badge.rel = 'noopener';
+            badge.textContent = '🎬 Made with VideoSync';
+            document.body.appendChild(badge);
+        }
+        player.addEventListener('play', () => document.body.classList.add('playing'));
+        player.addEventListener('pause', () => document.body.classList.remove('playing'));
+
+        function progressive() { player.src = CONFIG.mp4_url; }
+
+        if (!CONFIG.hls_url) {
+            progressive();
+        } else if (player.canPlayType('application/vnd.apple.mpegurl')) {
+            // Safari and iOS play HLS natively
+            player.src = CONFIG.hls_url;
+        } else {
+            const script = document.createElement('script');
+            script.src = 'https://cdn.jsdelivr.net/npm/hls.js@1/dist/hls.min.js';
+            script.onload = () => {
+                if (!window.Hls || !Hls.isSupported()) return progressive();
+                const hls = new Hls();
+                hls.on(Hls.Events.ERROR, (_, data) => { if (data.fatal) { hls.destroy(); progressive(); } });
+                hls.loadSource(CONFIG.hls_url);
+                hls.attachMedia(player);
+            };
+            script.onerror = progressive;
+            document.head.appendChild(script);
+        }
+    </script>
+</body>
+</html>
+"###;
+
+    let mut response = Html(html.replace("EMBED_CONFIG_PLACEHOLDER", &config)).into_response();
+    // Only the creator's sites may frame the player when they restricted it
+    let ancestors = link.allowed_domains.as_deref().unwrap_or("*");
+    if let Ok(value) = HeaderValue::from_str(&format!("frame-ancestors {}", ancestors)) {
+        response.headers_mut().insert(header::CONTENT_SECURITY_POLICY, value);
+    }
+    Ok(response)
+}
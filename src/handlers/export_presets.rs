@@ -0,0 +1,103 @@
+// src/handlers/export_presets.rs
+//! Named per-user export presets referenced by the agent and batch renders
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::export_preset::{CreateExportPresetRequest, UpdateExportPresetRequest};
+use crate::services::ExportPresetService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn export_preset_routes() -> Router {
+    Router::new()
+        .route("/api/export-presets", get(list_presets).post(create_preset))
+        .route("/api/export-presets/:id", get(get_preset).patch(update_preset).delete(delete_preset))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+/// The user's presets plus the built-in platform names they can override
+async fn list_presets(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let presets = ExportPresetService::list_presets(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "presets": presets,
+        "builtin_presets": crate::export::PLATFORM_PRESETS
+    })))
+}
+
+/// Create a preset, or replace the one with the same name
+async fn create_preset(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateExportPresetRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let preset = ExportPresetService::create_preset(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(bad_request)?;
+
+    Ok(Json(json!({ "success": true, "preset": preset })))
+}
+
+async fn get_preset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let preset = ExportPresetService::get_preset(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(json!({ "success": true, "preset": preset })))
+}
+
+async fn update_preset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateExportPresetRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let preset = ExportPresetService::update_preset(&state.db_pool, user_id(&claims), id, &payload)
+        .await
+        .map_err(bad_request)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Export preset not found" }))))?;
+
+    Ok(Json(json!({ "success": true, "preset": preset })))
+}
+
+async fn delete_preset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = ExportPresetService::delete_preset(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Export preset removed" })))
+}
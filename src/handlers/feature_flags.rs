@@ -0,0 +1,129 @@
+// src/handlers/feature_flags.rs
+//! Feature flags: each user's evaluated flags, and admin management with an instant kill switch
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use crate::middleware::admin::admin_middleware;
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::feature_flag::UpsertFeatureFlagRequest;
+use crate::services::FeatureFlagService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn feature_flag_routes() -> Router {
+    let user_routes = Router::new()
+        .route("/api/flags", get(my_flags))
+        .route("/api/flags/:key", get(my_flag))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
+    let admin_routes = Router::new()
+        .route("/api/admin/flags", get(list_flags))
+        .route("/api/admin/flags/:key", get(get_flag).put(upsert_flag).delete(delete_flag))
+        .route("/api/admin/flags/:key/kill", post(kill_flag))
+        .layer(axum::middleware::from_fn(admin_middleware))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
+    user_routes.merge(admin_routes)
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+/// Which flags are on for the calling user, so the UI can show or hide experimental features
+async fn my_flags(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let flags = FeatureFlagService::evaluate_all(&state.db_pool, Some(user_id(&claims)))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let flags: serde_json::Map<String, Value> = flags.into_iter().map(|(flag, on)| (flag.key, json!(on))).collect();
+    Ok(Json(json!({ "success": true, "flags": flags })))
+}
+
+/// Whether one flag is on for the calling user; unknown flags are off
+async fn my_flag(
+    Path(key): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Json<Value> {
+    let enabled = FeatureFlagService::is_enabled(&state.db_pool, &key, Some(user_id(&claims))).await;
+    Json(json!({ "success": true, "key": key, "enabled": enabled }))
+}
+
+async fn list_flags(Extension(state): Extension<Arc<AppState>>) -> Result<Json<Value>, StatusCode> {
+    let flags = FeatureFlagService::list(&state.db_pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "flags": flags })))
+}
+
+async fn get_flag(
+    Path(key): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<Value>, StatusCode> {
+    let flag = FeatureFlagService::get(&state.db_pool, &key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(json!({ "success": true, "flag": flag })))
+}
+
+/// Create a flag or change its targeting; fields left out keep their current value
+async fn upsert_flag(
+    Path(key): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpsertFeatureFlagRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let flag = FeatureFlagService::upsert(&state.db_pool, &key, &payload, user_id(&claims))
+        .await
+        .map_err(bad_request)?;
+
+    tracing::info!("🚩 Feature flag {} updated by user {} (enabled: {}, rollout: {}%)", flag.key, user_id(&claims), flag.enabled, flag.rollout_percent);
+    Ok(Json(json!({ "success": true, "flag": flag })))
+}
+
+/// Turn a flag off for everyone; takes effect on the next tool call
+async fn kill_flag(
+    Path(key): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let flag = FeatureFlagService::kill(&state.db_pool, &key, user_id(&claims))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": e.to_string() }))))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Feature flag not found" }))))?;
+
+    tracing::warn!("🚩 Feature flag {} killed by user {}", flag.key, user_id(&claims));
+    Ok(Json(json!({ "success": true, "flag": flag })))
+}
+
+async fn delete_flag(
+    Path(key): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = FeatureFlagService::delete(&state.db_pool, &key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Feature flag removed" })))
+}
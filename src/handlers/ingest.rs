@@ -0,0 +1,216 @@
+// src/handlers/ingest.rs
+//! RTMP live ingest - stream keys per chat session plus the nginx-rtmp callback endpoints
+//!
+//! nginx-rtmp config:
+//!   application live {
+//!       live on;
+//!       record all; record_path /path/to/recordings; record_unique on;
+//!       hls on; hls_path /path/to/recordings/hls; hls_fragment 2s; hls_playlist_length 10m;
+//!       on_publish http://app/api/ingest/rtmp/on_publish?secret=...;
+//!       on_publish_done http://app/api/ingest/rtmp/on_publish_done?secret=...;
+//!       on_record_done http://app/api/ingest/rtmp/on_record_done?secret=...;
+//!   }
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, post},
+    Form, Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::ingest::{CreateLiveIngestRequest, LiveClipRequest, LiveIngest, RtmpCallback};
+use crate::services::ingest::rtmp_publish_url;
+use crate::services::IngestService;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn ingest_routes() -> Router {
+    // Called by the RTMP server, authenticated with RTMP_CALLBACK_SECRET
+    let public_routes = Router::new()
+        .route("/api/ingest/rtmp/on_publish", post(on_publish))
+        .route("/api/ingest/rtmp/on_publish_done", post(on_publish_done))
+        .route("/api/ingest/rtmp/on_record_done", post(on_record_done));
+
+    let protected_routes = Router::new()
+        .route("/api/ingest", post(create_ingest).get(list_ingests))
+        .route("/api/ingest/:id", delete(revoke_ingest))
+        .route("/api/ingest/:id/clip", post(clip_live_stream))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
+    public_routes.merge(protected_routes)
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackAuth {
+    secret: Option<String>,
+}
+
+/// Callbacks are unauthenticated routes, so without a configured secret every one is refused
+fn callback_allowed(auth: &CallbackAuth) -> bool {
+    let Some(expected) = crate::config::get().ingest.callback_secret.as_deref().filter(|s| !s.is_empty()) else {
+        tracing::warn!("Refused an RTMP callback: ingest.callback_secret is not set");
+        return false;
+    };
+    auth.secret.as_deref().is_some_and(|secret| constant_time_eq(secret.as_bytes(), expected.as_bytes()))
+}
+
+/// Compare without returning at the first mismatch, so timing doesn't reveal how much of a guess matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn ingest_json(ingest: &LiveIngest) -> Value {
+    json!({
+        "id": ingest.id,
+        "title": ingest.title,
+        "status": ingest.status,
+        "rtmp_url": rtmp_publish_url(),
+        "stream_key": ingest.stream_key,
+        "auto_clip": ingest.auto_clip,
+        "clip_instructions": ingest.clip_instructions,
+        "recording_path": ingest.recording_path,
+        "uploaded_file_id": ingest.uploaded_file_id,
+        "clip_job_id": ingest.clip_job_id,
+        "error_message": ingest.error_message,
+        "started_at": ingest.started_at,
+        "ended_at": ingest.ended_at,
+        "created_at": ingest.created_at,
+    })
+}
+
+/// POST /api/ingest - create a stream key for a chat session
+async fn create_ingest(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateLiveIngestRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let ingest = IngestService::create(&state.db_pool, user_id, &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({ "success": true, "ingest": ingest_json(&ingest) })))
+}
+
+async fn list_ingests(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let ingests = IngestService::list(&state.db_pool, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "ingests": ingests.iter().map(ingest_json).collect::<Vec<_>>()
+    })))
+}
+
+/// DELETE /api/ingest/:id - revoke the stream key
+async fn revoke_ingest(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let revoked = IngestService::revoke(&state.db_pool, user_id, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !revoked {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Stream key revoked" })))
+}
+
+/// POST /api/ingest/:id/clip - cut the last N seconds of a stream that is still live
+async fn clip_live_stream(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<LiveClipRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let ingest = IngestService::get_owned(&state.db_pool, user_id, id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Database error" }))))?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Stream not found" }))))?;
+
+    let clip = IngestService::clip_live(&state.db_pool, &ingest, payload.seconds, payload.output_file.as_deref())
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "output_id": clip.id,
+        "file_path": clip.file_path,
+        "duration_seconds": clip.duration_seconds,
+    })))
+}
+
+/// nginx-rtmp treats any 2xx as "allow publishing" and anything else as "reject"
+async fn on_publish(
+    Query(auth): Query<CallbackAuth>,
+    Extension(state): Extension<Arc<AppState>>,
+    Form(callback): Form<RtmpCallback>,
+) -> StatusCode {
+    if !callback_allowed(&auth) {
+        return StatusCode::FORBIDDEN;
+    }
+    match IngestService::start_stream(&state.db_pool, &callback.name).await {
+        Ok(Some(ingest)) => {
+            tracing::info!("🔴 Live stream '{}' started from {}", ingest.title, callback.addr.as_deref().unwrap_or("unknown"));
+            StatusCode::OK
+        }
+        Ok(None) => {
+            tracing::warn!("Rejected RTMP publish with unknown or revoked key on app '{}'", callback.app);
+            StatusCode::FORBIDDEN
+        }
+        Err(e) => {
+            tracing::error!("Failed to start live stream: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn on_publish_done(
+    Query(auth): Query<CallbackAuth>,
+    Extension(state): Extension<Arc<AppState>>,
+    Form(callback): Form<RtmpCallback>,
+) -> StatusCode {
+    if !callback_allowed(&auth) {
+        return StatusCode::FORBIDDEN;
+    }
+    match IngestService::end_stream(&state.db_pool, &callback.name).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => {
+            tracing::error!("Failed to end live stream: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn on_record_done(
+    Query(auth): Query<CallbackAuth>,
+    Extension(state): Extension<Arc<AppState>>,
+    Form(callback): Form<RtmpCallback>,
+) -> StatusCode {
+    if !callback_allowed(&auth) {
+        return StatusCode::FORBIDDEN;
+    }
+    let Some(path) = callback.path.as_deref() else {
+        return StatusCode::BAD_REQUEST;
+    };
+    match IngestService::recording_finished(state, &callback.name, path).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => {
+            tracing::warn!("Ignoring {} for '{}': {}", callback.call, callback.name, e);
+            StatusCode::BAD_REQUEST
+        }
+    }
+}
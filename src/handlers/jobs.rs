@@ -1,23 +1,44 @@
 // src/handlers/jobs.rs
-//! Job control endpoints - pause, resume, cancel, status
+//! Job control endpoints - pause, resume, cancel, status, scheduling
 
 use axum::{
     extract::{Extension, Path},
     http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::{get, post},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json,
+    },
+    routing::{delete, get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use crate::AppState;
-use crate::jobs::{JobControl, JobId};
+use crate::jobs::dag::GraphNode;
+use crate::jobs::scheduled_job;
+use crate::jobs::video_job::{self, AgentType};
+use crate::jobs::{JobControl, JobId, JobPriority, SharedJobManager};
+use crate::middleware::{auth::auth_middleware, idempotency::idempotency_middleware};
+use crate::models::auth::Claims;
+use crate::models::scheduled_job::ScheduleJobRequest;
 
 #[derive(Deserialize)]
 pub struct JobControlRequest {
     pub action: String, // "pause", "resume", "cancel"
 }
 
+/// Most jobs one graph may hold
+pub const MAX_GRAPH_JOBS: usize = 20;
+
+#[derive(Deserialize)]
+pub struct JobGraphRequest {
+    pub session_id: String,
+    pub jobs: Vec<GraphNode>,
+    /// Queue priority of every job in the graph (default normal)
+    #[serde(default)]
+    pub priority: JobPriority,
+}
+
 #[derive(Serialize)]
 pub struct JobStatusResponse {
     pub job_id: String,
@@ -45,6 +66,50 @@ pub async fn get_job_status(
     }
 }
 
+/// GET /api/jobs/:job_id/events - Stream job progress as Server-Sent Events
+///
+/// Opens with a `status` event holding the current status, then sends every progress update as a
+/// `progress` event until the job completes, fails or is cancelled.
+pub async fn get_job_events(
+    Path(job_id): Path<JobId>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    job_events(&state.job_manager, job_id).await
+}
+
+/// The SSE stream of one job: its current status, then its progress until it finishes
+async fn job_events(job_manager: &SharedJobManager, job_id: JobId) -> axum::response::Response {
+    // Unknown jobs never get an update, so they must not get a subscription either
+    if job_manager.get_job_status(&job_id).await.is_none() {
+        return (StatusCode::NOT_FOUND, "Job not found").into_response();
+    }
+    // Subscribe before reading the status so no update falls in between
+    let updates = job_manager.subscribe_job(&job_id).await;
+    let Some(status) = job_manager.get_job_status(&job_id).await else {
+        return (StatusCode::NOT_FOUND, "Job not found").into_response();
+    };
+
+    let finished = status.is_final();
+    let snapshot = JobStatusResponse {
+        job_id: job_id.clone(),
+        status,
+        message: "Job status retrieved".to_string(),
+    };
+    let first = Event::default().event("status").json_data(&snapshot);
+
+    let progress = futures::stream::unfold((updates, finished), |(mut updates, finished)| async move {
+        if finished {
+            return None;
+        }
+        let update = updates.recv().await?;
+        let finished = update.status.is_final();
+        Some((Event::default().event("progress").json_data(&update), (updates, finished)))
+    });
+
+    let events = futures::StreamExt::chain(futures::stream::once(async move { first }), progress);
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
 /// POST /api/jobs/:job_id/control - Control job (pause/resume/cancel)
 pub async fn control_job(
     Path(job_id): Path<JobId>,
@@ -60,6 +125,12 @@ pub async fn control_job(
         }
     };
 
+    // A job still waiting for a worker has no control channel yet
+    if matches!(command, JobControl::Cancel) && state.job_manager.cancel_queued(&job_id).await {
+        tracing::info!("Job {} cancelled while queued", job_id);
+        return (StatusCode::OK, format!("Job {} cancelled before it started", job_id)).into_response();
+    }
+
     match state.job_manager.send_control(&job_id, command).await {
         Ok(_) => {
             let message = format!("Job {} action '{}' sent successfully", job_id, request.action);
@@ -91,15 +162,202 @@ pub async fn get_session_jobs(
             "created_at": job.created_at,
             "started_at": job.started_at,
             "completed_at": job.completed_at,
+            "request_id": job.request_id,
+            "graph_id": job.graph_id,
+            "depends_on": job.depends_on,
+            "priority": job.priority,
+        })).collect::<Vec<_>>()
+    });
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// GET /api/jobs/lanes - Busy slots in the express and standard lanes
+pub async fn get_lane_usage(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.job_manager.lane_usage())).into_response()
+}
+
+/// POST /api/jobs/graph - Submit jobs that depend on each other ("voiceover" after "trim")
+pub async fn submit_job_graph(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<JobGraphRequest>,
+) -> impl IntoResponse {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    start_job_graph(&state, user_id, request.session_id, request.jobs, request.priority).await
+}
+
+/// Start a job graph in one of the user's sessions (also used to run workflow templates)
+pub async fn start_job_graph(
+    state: &Arc<AppState>,
+    user_id: i32,
+    session_id: String,
+    jobs: Vec<GraphNode>,
+    priority: JobPriority,
+) -> axum::response::Response {
+    if jobs.is_empty() || jobs.len() > MAX_GRAPH_JOBS {
+        let error = format!("A job graph needs between 1 and {} jobs", MAX_GRAPH_JOBS);
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "success": false, "error": error }))).into_response();
+    }
+
+    let owned = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(&session_id)
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await;
+    match owned {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load session {}: {}", session_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let agent_type = if state.claude_client.is_some() { AgentType::Claude } else { AgentType::Gemini };
+    match video_job::spawn_video_editing_graph(
+        session_id,
+        jobs,
+        agent_type,
+        priority,
+        state.clone(),
+        state.job_manager.clone(),
+    ).await {
+        Ok((graph_id, jobs)) => {
+            let response = serde_json::json!({
+                "success": true,
+                "graph_id": graph_id,
+                "jobs": jobs.iter().map(|(key, job_id)| serde_json::json!({ "key": key, "job_id": job_id })).collect::<Vec<_>>(),
+            });
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "success": false, "error": e }))).into_response(),
+    }
+}
+
+/// GET /api/jobs/graph/:graph_id - Combined progress of a job graph and the status of each job
+pub async fn get_job_graph(
+    Path(graph_id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some((progress, jobs)) = state.job_manager.graph_progress(&graph_id).await else {
+        return (StatusCode::NOT_FOUND, "Job graph not found").into_response();
+    };
+    let response = serde_json::json!({
+        "progress": progress,
+        "jobs": jobs.iter().map(|job| serde_json::json!({
+            "id": job.id,
+            "key": job.input_data.get("graph_key"),
+            "depends_on": job.depends_on,
+            "status": job.status,
+            "started_at": job.started_at,
+            "completed_at": job.completed_at,
         })).collect::<Vec<_>>()
     });
     (StatusCode::OK, Json(response)).into_response()
 }
 
+/// POST /api/jobs/scheduled - Queue a render or YouTube upload to start at `run_at`
+pub async fn create_scheduled_job(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<ScheduleJobRequest>,
+) -> impl IntoResponse {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    match scheduled_job::schedule(&state, user_id, request).await {
+        Ok(scheduled) => (StatusCode::OK, Json(serde_json::json!({ "success": true, "scheduled_job": scheduled }))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "success": false, "error": e }))).into_response(),
+    }
+}
+
+/// GET /api/jobs/scheduled - The user's jobs that haven't started yet, soonest first
+pub async fn list_scheduled_jobs(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    match scheduled_job::list(&state.db_pool, user_id).await {
+        Ok(jobs) => (StatusCode::OK, Json(serde_json::json!({ "success": true, "count": jobs.len(), "scheduled_jobs": jobs }))).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list scheduled jobs for user {}: {}", user_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// DELETE /api/jobs/scheduled/:job_id - Cancel a scheduled job before it starts
+pub async fn cancel_scheduled_job(
+    Path(job_id): Path<JobId>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    match scheduled_job::cancel(&state, user_id, &job_id).await {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({ "success": true, "job_id": job_id }))).into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, "Scheduled job not found or already started").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to cancel scheduled job {}: {}", job_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
 /// Routes for job management
 pub fn job_routes() -> Router {
+    // Graphs and scheduled jobs run work for the session owner, so they need auth
+    let protected_routes = Router::new()
+        .route("/api/jobs/graph", post(submit_job_graph).layer(axum::middleware::from_fn(idempotency_middleware)))
+        .route(
+            "/api/jobs/scheduled",
+            post(create_scheduled_job).get(list_scheduled_jobs).layer(axum::middleware::from_fn(idempotency_middleware)),
+        )
+        .route("/api/jobs/scheduled/:job_id", delete(cancel_scheduled_job))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
     Router::new()
+        .route("/api/jobs/lanes", get(get_lane_usage))
         .route("/api/jobs/:job_id/status", get(get_job_status))
+        .route("/api/jobs/:job_id/events", get(get_job_events))
         .route("/api/jobs/:job_id/control", post(control_job))
         .route("/api/jobs/session/:session_id", get(get_session_jobs))
+        .route("/api/jobs/graph/:graph_id", get(get_job_graph))
+        .merge(protected_routes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{Job, JobManager, JobStatus, ProgressUpdate};
+
+    #[tokio::test]
+    async fn events_for_unknown_jobs_are_404_without_a_subscription() {
+        let job_manager = Arc::new(JobManager::new());
+
+        let response = job_events(&job_manager, "missing".to_string()).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(job_manager.subscriber_count("missing").await, 0);
+    }
+
+    #[tokio::test]
+    async fn events_stream_the_status_then_progress_until_the_job_finishes() {
+        let job_manager = Arc::new(JobManager::new());
+        let job_id = job_manager.create_job(Job::new("session".to_string(), "video_edit".to_string(), serde_json::json!({}))).await;
+
+        let response = job_events(&job_manager, job_id.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(job_manager.subscriber_count(&job_id).await, 1);
+
+        let done = JobStatus::Completed { result: "done".to_string(), output_files: vec![], duration_seconds: 1.0 };
+        job_manager.send_progress("session", ProgressUpdate::new(job_id.clone(), "Finished".to_string(), done)).await;
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let status = body.find("event: status").expect("status event");
+        let progress = body.find("event: progress").expect("progress event");
+        assert!(status < progress, "{}", body);
+        assert!(body.contains("Finished"), "{}", body);
+        assert_eq!(job_manager.subscriber_count(&job_id).await, 0);
+    }
 }
@@ -0,0 +1,213 @@
+// src/handlers/library.rs
+//! Per-user asset library - logos, intros, music and clips reusable from any chat session
+
+use axum::{
+    extract::{multipart::Multipart, DefaultBodyLimit, Extension, Path, Query},
+    http::StatusCode,
+    response::{Json, Response},
+    routing::{get, post},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::library::{
+    AssetVisualTags, ImportLibraryAssetRequest, LibraryAsset, LibrarySearchQuery, NewLibraryAsset, UpdateLibraryAssetRequest,
+};
+use crate::services::{AssetTaggingService, LibraryService};
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn library_routes() -> Router {
+    Router::new()
+        .route("/api/library", get(search_assets).post(upload_asset))
+        .route("/api/library/import", post(import_asset))
+        .route("/api/library/:id", get(get_asset).patch(update_asset).delete(delete_asset))
+        .route("/api/library/:id/file", get(stream_asset))
+        .layer(DefaultBodyLimit::max(100 * 1024 * 1024))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": message.into() })))
+}
+
+fn asset_json(asset: &LibraryAsset, visual_tags: Option<&AssetVisualTags>) -> Value {
+    json!({
+        "id": asset.id,
+        "name": asset.name,
+        "reference": asset.reference(),
+        "kind": asset.kind,
+        "file_path": asset.file_path,
+        "file_size": asset.file_size,
+        "mime_type": asset.mime_type,
+        "duration_seconds": asset.duration_seconds,
+        "tags": asset.tags,
+        "description": asset.description,
+        "use_count": asset.use_count,
+        "visual_tags": visual_tags.map(|t| json!({
+            "status": t.status,
+            "caption": t.caption,
+            "objects": t.objects,
+            "scenes": t.scenes,
+            "dominant_colors": t.dominant_colors,
+            "has_faces": t.has_faces,
+        })),
+        "file_url": format!("/api/library/{}/file", asset.id),
+        "created_at": asset.created_at,
+    })
+}
+
+/// Tag a newly stored asset in the background
+fn queue_tagging(state: &Arc<AppState>, asset: &LibraryAsset) {
+    AssetTaggingService::spawn_tagging(
+        state.clone(),
+        "library",
+        asset.id.to_string(),
+        Some(asset.user_id),
+        asset.file_path.clone(),
+    );
+}
+
+/// GET /api/library?q=&tag=&kind=&has_faces=
+async fn search_assets(
+    Query(query): Query<LibrarySearchQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let assets = LibraryService::search_with_semantics(&state, user_id(&claims), &query)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let ids: Vec<String> = assets.iter().map(|a| a.id.to_string()).collect();
+    let visual_tags = AssetTaggingService::get_tags_for(&state.db_pool, "library", &ids)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "assets": assets.iter().map(|a| asset_json(a, visual_tags.get(&a.id.to_string()))).collect::<Vec<_>>()
+    })))
+}
+
+/// POST /api/library - multipart with `file`, `name`, optional `kind`, `tags` (comma separated) and `description`
+async fn upload_asset(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    mut multipart: Multipart,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let mut file = None;
+    let mut meta = NewLibraryAsset::default();
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let field_name = field.name().unwrap_or("").to_string();
+        if field_name == "file" {
+            let original_name = field.file_name().unwrap_or("asset").to_string();
+            let data = field.bytes().await.map_err(|e| bad_request(format!("Failed to read file: {}", e)))?;
+            file = Some((original_name, data));
+            continue;
+        }
+
+        let text = field.text().await.map_err(|e| bad_request(format!("Failed to read field '{}': {}", field_name, e)))?;
+        match field_name.as_str() {
+            "name" => meta.name = text,
+            "kind" => meta.kind = Some(text),
+            "tags" => meta.tags = text.split(',').map(|t| t.to_string()).collect(),
+            "description" => meta.description = Some(text),
+            _ => {}
+        }
+    }
+
+    let (original_name, data) = file.ok_or_else(|| bad_request("Missing 'file' field"))?;
+    if meta.name.trim().is_empty() {
+        // Default the name to the file stem
+        meta.name = std::path::Path::new(&original_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+
+    let asset = LibraryService::store_upload(&state.db_pool, user_id(&claims), &original_name, &data, &meta)
+        .await
+        .map_err(bad_request)?;
+    queue_tagging(&state, &asset);
+
+    Ok(Json(json!({ "success": true, "asset": asset_json(&asset, None) })))
+}
+
+/// POST /api/library/import - copy an existing upload or output into the library
+async fn import_asset(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ImportLibraryAssetRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let asset = LibraryService::import_file(&state.db_pool, user_id(&claims), &payload.file_path, &payload.asset)
+        .await
+        .map_err(bad_request)?;
+    queue_tagging(&state, &asset);
+
+    Ok(Json(json!({ "success": true, "asset": asset_json(&asset, None) })))
+}
+
+async fn get_asset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let asset = LibraryService::get_asset(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let visual_tags = AssetTaggingService::get_tags(&state.db_pool, "library", &asset.id.to_string())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "asset": asset_json(&asset, visual_tags.as_ref()) })))
+}
+
+async fn update_asset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateLibraryAssetRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let asset = LibraryService::update_asset(&state.db_pool, user_id(&claims), id, &payload)
+        .await
+        .map_err(bad_request)?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Asset not found" }))))?;
+
+    Ok(Json(json!({ "success": true, "asset": asset_json(&asset, None) })))
+}
+
+async fn delete_asset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = LibraryService::delete_asset(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Asset removed from library" })))
+}
+
+/// GET /api/library/:id/file - preview the asset
+async fn stream_asset(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Response, StatusCode> {
+    let asset = LibraryService::get_asset(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let egress = crate::handlers::output::open_egress(Some(user_id(&claims)))?;
+    crate::handlers::output::stream_file(std::path::Path::new(&asset.file_path), egress).await
+}
@@ -10,3 +10,24 @@ pub mod output;
 pub mod jobs; // 🆕 Job control endpoints
 pub mod youtube; // 📺 YouTube integration
 pub mod clipping; // 📹 YouTube clipping feature
+pub mod batch; // 📦 Bulk personalization renders
+pub mod delivery; // 📦 SFTP/FTP/S3 delivery targets
+pub mod review; // 🔗 Public review links with timestamped comments
+pub mod library; // 📚 Per-user media asset library
+pub mod ingest; // 🔴 RTMP live ingest (nginx-rtmp callbacks)
+pub mod export_presets; // 🎛️ Named per-user export presets
+pub mod render_snapshots; // 🔁 Reproducible render snapshots
+pub mod embed; // 📺 Public embeddable players
+pub mod session_defaults; // ⚙️ Per-session default parameters
+pub mod feature_flags; // 🚩 Feature flags and tool rollout
+pub mod trash; // 🗑️ Trash with restore for sessions and outputs
+pub mod sessions; // 🗂️ Bulk session management
+pub mod webhooks; // 🪝 Job completion webhooks
+pub mod download_links; // ⬇️ Expiring client download links
+pub mod workflow_templates; // 🧩 Reusable job graph templates
+pub mod workflows; // 🧩 YAML/JSON workflow definitions
+pub mod projects; // 🎞️ Projects with non-destructive edit history
+pub mod storage; // 🧊 Cold storage tiering
+pub mod notifications; // 🔔 In-app notification center
+pub mod calendar; // 📅 Content calendar
+pub mod activity; // 📰 Workspace activity feed
@@ -0,0 +1,103 @@
+// src/handlers/notifications.rs
+//! Notification center - what needs the user's attention, with read/unread state.
+//! New notifications are also pushed to the user's open chats as `{"type": "notification"}` messages.
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::services::NotificationService;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct ListNotificationsQuery {
+    /// Only notifications not read yet
+    #[serde(default)]
+    pub unread: bool,
+    /// Page back from this notification id
+    pub before: Option<i32>,
+    pub limit: Option<i64>,
+}
+
+pub fn notification_routes() -> Router {
+    Router::new()
+        .route("/api/notifications", get(list_notifications))
+        .route("/api/notifications/read-all", post(mark_all_read))
+        .route("/api/notifications/:id/read", post(mark_read))
+        .route("/api/notifications/:id/unread", post(mark_unread))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+/// GET /api/notifications - newest first, with the unread count
+async fn list_notifications(
+    Query(query): Query<ListNotificationsQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let user_id = user_id(&claims);
+    let limit = query.limit.unwrap_or(50);
+    let notifications = NotificationService::list(&state.db_pool, user_id, query.unread, query.before, limit)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let unread_count = NotificationService::unread_count(&state.db_pool, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "notifications": notifications,
+        "unread_count": unread_count,
+    })))
+}
+
+async fn set_read(state: &AppState, claims: &Claims, id: i32, read: bool) -> Result<Json<Value>, StatusCode> {
+    let user_id = user_id(claims);
+    let notification = NotificationService::set_read(&state.db_pool, user_id, id, read)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let unread_count = NotificationService::unread_count(&state.db_pool, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "notification": notification, "unread_count": unread_count })))
+}
+
+async fn mark_read(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    set_read(&state, &claims, id, true).await
+}
+
+async fn mark_unread(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    set_read(&state, &claims, id, false).await
+}
+
+async fn mark_all_read(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let marked = NotificationService::mark_all_read(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "marked_read": marked, "unread_count": 0 })))
+}
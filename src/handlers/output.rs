@@ -1,13 +1,16 @@
 // src/handlers/output.rs
 use axum::{
     extract::{Path, Extension},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use std::{path::PathBuf, sync::Arc};
 use tokio_util::io::ReaderStream;
+use crate::services::output_stats::ViewEvent;
+use crate::utils::egress::{self, EgressPermit};
+use crate::services::{OutputPreviewService, OutputStatsService, StorageTierService};
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +23,15 @@ pub struct VideoOutputResponse {
     pub stream_url: String,
     pub created_at: String,
     pub content_type: String,
+    /// Poster frame for video outputs, generated on first request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poster_url: Option<String>,
+    /// Probed streams; only filled in by the single-file info endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<crate::types::MediaInfo>,
+    /// "cold" for an output in cold storage; its links rehydrate it on first use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_tier: Option<String>,
 }
 
 #[derive(Serialize)]  
@@ -29,11 +41,25 @@ pub struct VideoOutputListResponse {
 }
 
 pub fn output_routes() -> Router {
+    // Version history is per user, so it sits behind auth
+    let protected_routes = Router::new()
+        .route("/api/outputs/:id/versions", get(list_output_versions))
+        .route("/api/outputs/:id/report", get(download_render_report))
+        .route("/api/outputs/:id/stats", get(get_output_stats))
+        .route("/api/outputs/stats", get(list_output_stats))
+        .route("/api/outputs/:id/metadata", get(get_output_metadata))
+        .route("/api/outputs/:id/transcode", post(transcode_output))
+        .route("/api/outputs/search", get(search_outputs))
+        .route("/api/outputs/:id/rehydrate", post(rehydrate_output))
+        .layer(axum::middleware::from_fn(crate::middleware::auth::auth_middleware));
+
     Router::new()
         .route("/api/outputs/list/:session_id", get(list_session_outputs))
         .route("/api/outputs/download/:file_id", get(download_video_output))
         .route("/api/outputs/stream/:file_id", get(stream_video_output))
         .route("/api/outputs/info/:file_id", get(get_output_info))
+        .route("/api/outputs/poster/:file_id", get(get_output_poster))
+        .merge(protected_routes)
 }
 
 /// List all video outputs for a session
@@ -43,13 +69,11 @@ async fn list_session_outputs(
 ) -> Result<axum::Json<VideoOutputListResponse>, StatusCode> {
     // Get output directory for this session
     let session_output_dir = PathBuf::from("outputs").join(&session_id);
-    
-    if !session_output_dir.exists() {
-        return Ok(axum::Json(VideoOutputListResponse {
-            success: true,
-            outputs: vec![],
-        }));
-    }
+
+    // Trashed outputs stay on disk until they're purged
+    let trashed = crate::services::TrashService::trashed_output_paths(&state.db_pool, &session_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let mut outputs = Vec::new();
     
@@ -58,6 +82,9 @@ async fn list_session_outputs(
         Ok(mut entries) => {
             while let Some(entry) = entries.next_entry().await.unwrap_or(None) {
                 let path = entry.path();
+                if trashed.contains(path.to_string_lossy().as_ref()) {
+                    continue;
+                }
                 if let Some(extension) = path.extension() {
                     let ext_str = extension.to_string_lossy().to_lowercase();
                     if matches!(ext_str.as_str(), "mp4" | "avi" | "mov" | "mkv" | "webm") {
@@ -76,6 +103,9 @@ async fn list_session_outputs(
                                     stream_url: format!("/api/outputs/stream/{}", file_id),
                                     created_at: format_system_time(metadata.created().unwrap_or(std::time::SystemTime::now())),
                                     content_type: get_content_type(&ext_str),
+                                    poster_url: Some(OutputPreviewService::poster_url(&file_id)),
+                                    media: None,
+                                    storage_tier: None,
                                 });
                             }
                         }
@@ -83,12 +113,43 @@ async fn list_session_outputs(
                 }
             }
         }
+        // No directory yet, or only cold outputs left
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
         Err(e) => {
             tracing::error!("Failed to read output directory: {}", e);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     }
 
+    // Tiered outputs are off disk but keep the links they had
+    let cold = sqlx::query_as::<_, (String, String, i64, chrono::DateTime<chrono::Utc>)>(
+        r#"
+        SELECT o.cold_file_id, o.file_name, o.file_size, o.created_at
+        FROM output_videos o
+        JOIN chat_sessions s ON s.id = o.session_id
+        WHERE s.session_uuid = $1 AND o.storage_tier = 'cold' AND o.cold_file_id IS NOT NULL AND o.deleted_at IS NULL
+        "#,
+    )
+    .bind(&session_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    for (file_id, filename, size, created_at) in cold {
+        let extension = std::path::Path::new(&filename).extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        outputs.push(VideoOutputResponse {
+            download_url: format!("/api/outputs/download/{}", file_id),
+            stream_url: format!("/api/outputs/stream/{}", file_id),
+            poster_url: Some(OutputPreviewService::poster_url(&file_id)),
+            file_id,
+            filename,
+            size_bytes: size.max(0) as u64,
+            created_at: format_system_time(created_at.into()),
+            content_type: get_content_type(&extension),
+            media: None,
+            storage_tier: Some("cold".to_string()),
+        });
+    }
+
     // Sort by creation time (newest first)
     outputs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
 
@@ -101,18 +162,21 @@ async fn list_session_outputs(
 /// Download a video output file
 async fn download_video_output(
     Path(file_id): Path<String>,
-    Extension(_state): Extension<Arc<AppState>>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
 ) -> Result<Response, StatusCode> {
-    let file_path = resolve_file_path(&file_id)?;
+    let file_path = resolve_output_path(&state, &file_id).await?;
     
     if !file_path.exists() {
         return Err(StatusCode::NOT_FOUND);
     }
+    let egress = open_egress(output_owner(&state, &file_path).await)?;
+    OutputStatsService::record_for_path(&state.db_pool, &file_path, ViewEvent::Download, "app", &headers);
 
     // Open the file for reading
     match tokio::fs::File::open(&file_path).await {
         Ok(file) => {
-            let stream = ReaderStream::new(file);
+            let stream = egress::throttle(ReaderStream::new(file), egress);
             let filename = file_path.file_name()
                 .and_then(|name| name.to_str())
                 .unwrap_or("video.mp4");
@@ -137,19 +201,46 @@ async fn download_video_output(
 /// Stream a video output file (for browser playback)
 async fn stream_video_output(
     Path(file_id): Path<String>,
-    Extension(_state): Extension<Arc<AppState>>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
 ) -> Result<Response, StatusCode> {
-    let file_path = resolve_file_path(&file_id)?;
-    
+    let file_path = resolve_output_path(&state, &file_id).await?;
+    let egress = open_egress(output_owner(&state, &file_path).await)?;
+    let response = stream_file(&file_path, egress).await?;
+    OutputStatsService::record_for_path(&state.db_pool, &file_path, ViewEvent::Stream, "app", &headers);
+    Ok(response)
+}
+
+/// Count a download of `owner`'s files against their egress limits; 429 when they already have the
+/// maximum open. Files with no recorded owner share one "anonymous" allowance.
+pub(crate) fn open_egress(owner: Option<i32>) -> Result<EgressPermit, StatusCode> {
+    let key = owner.map(|id| format!("user:{}", id)).unwrap_or_else(|| "anonymous".to_string());
+    egress::open(&key).map_err(|e| {
+        tracing::info!("🚦 Download refused: {}", e);
+        StatusCode::TOO_MANY_REQUESTS
+    })
+}
+
+/// Owner of the recorded output at `path`
+async fn output_owner(state: &AppState, path: &std::path::Path) -> Option<i32> {
+    crate::services::OutputVideoService::find_output_by_any_path(&state.db_pool, &path.to_string_lossy())
+        .await
+        .ok()
+        .flatten()
+        .map(|output| output.user_id)
+}
+
+/// Stream a file inline with its video content type (shared with public review links), paced by `egress`
+pub(crate) async fn stream_file(file_path: &std::path::Path, egress: EgressPermit) -> Result<Response, StatusCode> {
     if !file_path.exists() {
         return Err(StatusCode::NOT_FOUND);
     }
 
     // Open the file for streaming
-    match tokio::fs::File::open(&file_path).await {
+    match tokio::fs::File::open(file_path).await {
         Ok(file) => {
-            let stream = ReaderStream::new(file);
-            let content_type = get_content_type_from_path(&file_path);
+            let stream = egress::throttle(ReaderStream::new(file), egress);
+            let content_type = get_content_type_from_path(file_path);
             
             Response::builder()
                 .status(StatusCode::OK)
@@ -166,12 +257,40 @@ async fn stream_video_output(
     }
 }
 
+/// Poster frame of a video output (JPEG), grabbed and cached on first request
+async fn get_output_poster(
+    Path(file_id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Response, StatusCode> {
+    let poster = OutputPreviewService::poster_path(&file_id);
+    if poster.exists() {
+        return poster_response(&poster).await;
+    }
+    let file_path = resolve_output_path(&state, &file_id).await?;
+    let poster = OutputPreviewService::ensure_poster(&file_path, &file_id).await.map_err(|e| {
+        tracing::warn!("Failed to generate poster for {}: {}", file_path.display(), e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+    poster_response(&poster).await
+}
+
+async fn poster_response(poster: &std::path::Path) -> Result<Response, StatusCode> {
+    let bytes = tokio::fs::read(poster).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/jpeg")
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .body(axum::body::Body::from(bytes))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
 /// Get information about a video output file
 async fn get_output_info(
     Path(file_id): Path<String>,
-    Extension(_state): Extension<Arc<AppState>>,
+    Extension(state): Extension<Arc<AppState>>,
 ) -> Result<axum::Json<VideoOutputResponse>, StatusCode> {
-    let file_path = resolve_file_path(&file_id)?;
+    let file_path = resolve_output_path(&state, &file_id).await?;
     
     if !file_path.exists() {
         return Err(StatusCode::NOT_FOUND);
@@ -185,6 +304,11 @@ async fn get_output_info(
                 .to_string();
             
             let content_type = get_content_type_from_path(&file_path);
+            let probe_path = file_path.to_string_lossy().to_string();
+            let media = tokio::task::spawn_blocking(move || crate::core::probe_media(&probe_path).ok())
+                .await
+                .ok()
+                .flatten();
             
             Ok(axum::Json(VideoOutputResponse {
                 file_id: file_id.clone(),
@@ -193,7 +317,10 @@ async fn get_output_info(
                 download_url: format!("/api/outputs/download/{}", file_id),
                 stream_url: format!("/api/outputs/stream/{}", file_id),
                 created_at: format_system_time(metadata.created().unwrap_or(std::time::SystemTime::now())),
+                poster_url: content_type.starts_with("video/").then(|| OutputPreviewService::poster_url(&file_id)),
                 content_type,
+                media,
+                storage_tier: None,
             }))
         }
         Err(e) => {
@@ -203,6 +330,285 @@ async fn get_output_info(
     }
 }
 
+/// Bring an output back from cold storage ahead of use (downloads and streams also do this on their own)
+async fn rehydrate_output(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Result<axum::Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    crate::services::OutputVideoService::get_output_video_by_id(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .filter(|v| v.user_id == user_id && v.deleted_at.is_none())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let path = StorageTierService::rehydrate(&state.db_pool, id).await.map_err(|e| {
+        tracing::error!("Failed to rehydrate output {}: {}", id, e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+
+    Ok(axum::Json(serde_json::json!({ "success": true, "file_path": path.to_string_lossy() })))
+}
+
+/// List every version in an output's lineage (oldest first)
+async fn list_output_versions(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Result<axum::Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let versions = crate::services::OutputVideoService::get_version_history(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if versions.is_empty() || versions.iter().any(|v| v.user_id != user_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let versions: Vec<serde_json::Value> = versions
+        .iter()
+        .map(|v| {
+            // Rows may store the path with or without the outputs/ prefix; ids hash the on-disk path
+            let bare = v.file_path.trim_start_matches("./").trim_start_matches("outputs/");
+            let on_disk = PathBuf::from(format!("outputs/{}", bare));
+            let on_disk = if on_disk.exists() { on_disk } else { PathBuf::from(&v.file_path) };
+            let file_id = generate_file_id(&on_disk);
+            serde_json::json!({
+                "id": v.id,
+                "version": v.version,
+                "parent_output_id": v.parent_output_id,
+                "file_name": v.file_name,
+                "file_path": v.file_path,
+                "file_size": v.file_size,
+                "duration_seconds": v.duration_seconds,
+                "width": v.width,
+                "height": v.height,
+                "tool_used": v.tool_used,
+                "available": on_disk.exists(),
+                "report_url": v.report_path.as_ref().map(|_| format!("/api/outputs/{}/report", v.id)),
+                "download_url": format!("/api/outputs/download/{}", file_id),
+                "stream_url": format!("/api/outputs/stream/{}", file_id),
+                "created_at": v.created_at,
+            })
+        })
+        .collect();
+
+    Ok(axum::Json(serde_json::json!({
+        "success": true,
+        "output_id": id,
+        "root_output_id": versions.first().map(|v| v["id"].clone()),
+        "versions": versions,
+    })))
+}
+
+/// Download the render report (input hashes, FFmpeg commands, encoders, warnings, environment) for an output
+async fn download_render_report(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Result<Response, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let video = crate::services::OutputVideoService::get_output_video_by_id(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .filter(|v| v.user_id == user_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let report_path = video.report_path.ok_or(StatusCode::NOT_FOUND)?;
+    let report = tokio::fs::read(&report_path).await.map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let filename = std::path::Path::new(&report_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("report.json");
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))
+        .body(axum::body::Body::from(report))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Play/download counts and viewer countries for one output
+async fn get_output_stats(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Result<axum::Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    crate::services::OutputVideoService::get_output_video_by_id(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .filter(|v| v.user_id == user_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let stats = OutputStatsService::get_stats(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(axum::Json(serde_json::json!({ "success": true, "stats": stats })))
+}
+
+#[derive(Deserialize)]
+pub struct OutputStatsQuery {
+    pub limit: Option<i64>,
+}
+
+/// Dashboard summary: the user's outputs ranked by how much they are consumed
+async fn list_output_stats(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+    axum::extract::Query(query): axum::extract::Query<OutputStatsQuery>,
+) -> Result<axum::Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let outputs = OutputStatsService::user_summary(&state.db_pool, user_id, query.limit.unwrap_or(50).clamp(1, 500))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(axum::Json(serde_json::json!({
+        "success": true,
+        "total_streams": outputs.iter().map(|o| o.streams).sum::<i64>(),
+        "total_downloads": outputs.iter().map(|o| o.downloads).sum::<i64>(),
+        "outputs": outputs,
+    })))
+}
+
+/// Generated title, description and tags of an output, to pre-fill the YouTube upload form
+async fn get_output_metadata(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Result<axum::Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let video = crate::services::OutputVideoService::get_output_video_by_id(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .filter(|v| v.user_id == user_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(axum::Json(serde_json::json!({
+        "success": true,
+        "output_id": video.id,
+        "video_path": video.file_path,
+        "generated": video.metadata_generated_at.is_some(),
+        "title": video.suggested_title,
+        "description": video.description,
+        "tags": video.tags,
+        "generated_at": video.metadata_generated_at,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct TranscodeQuery {
+    pub profile: Option<String>,
+}
+
+/// A smaller rendition of an output for phones and slow connections. Answers 202 while it is being
+/// transcoded; ask again until it is ready.
+async fn transcode_output(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+    axum::extract::Query(query): axum::extract::Query<TranscodeQuery>,
+) -> Result<Response, StatusCode> {
+    use crate::services::rendition::{RenditionStatus, DEFAULT_PROFILE};
+    use crate::services::{OutputVideoService, RenditionService};
+
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let profile_name = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(profile) = RenditionService::profile(profile_name) else {
+        let body = serde_json::json!({
+            "success": false,
+            "error": format!("Unknown profile '{}'", profile_name),
+            "profiles": RenditionService::profile_names(),
+        });
+        return Ok((StatusCode::BAD_REQUEST, axum::Json(body)).into_response());
+    };
+    let video = OutputVideoService::get_output_video_by_id(&state.db_pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .filter(|v| v.user_id == user_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let source = OutputVideoService::path_candidates(&video.file_path)
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|p| p.is_file())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let status = RenditionService::ensure(&source, video.id, profile).await.map_err(|e| {
+        tracing::warn!("No {} rendition for output {}: {}", profile.name, video.id, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+    let (code, body) = match status {
+        RenditionStatus::Ready { path, original } => {
+            let size_bytes = tokio::fs::metadata(&path).await.map(|m| m.len()).unwrap_or(0);
+            let file_id = file_id_for_path(&path);
+            (StatusCode::OK, serde_json::json!({
+                "success": true,
+                "status": "ready",
+                "original": original,
+                "size_bytes": size_bytes,
+                "stream_url": RenditionService::stream_url(&path),
+                "download_url": format!("/api/outputs/download/{}", file_id),
+            }))
+        }
+        RenditionStatus::Processing => (StatusCode::ACCEPTED, serde_json::json!({ "success": true, "status": "processing" })),
+        RenditionStatus::Failed(error) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, serde_json::json!({ "success": false, "status": "failed", "error": error }))
+        }
+    };
+    let mut body = body;
+    body["output_id"] = serde_json::json!(video.id);
+    body["profile"] = serde_json::json!(profile);
+    Ok((code, axum::Json(body)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct OutputSearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+/// Search the user's outputs by generated title, description and tags (and file name)
+async fn search_outputs(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+    axum::extract::Query(query): axum::extract::Query<OutputSearchQuery>,
+) -> Result<axum::Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if query.q.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let outputs = crate::services::OutputMetadataService::search(&state.db_pool, user_id, &query.q, query.limit.unwrap_or(50).clamp(1, 200))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let outputs: Vec<serde_json::Value> = outputs
+        .iter()
+        .map(|v| {
+            let file_id = file_id_for_path(std::path::Path::new(&v.file_path));
+            serde_json::json!({
+                "id": v.id,
+                "file_name": v.file_name,
+                "file_path": v.file_path,
+                "title": v.suggested_title,
+                "description": v.description,
+                "tags": v.tags,
+                "duration_seconds": v.duration_seconds,
+                "download_url": format!("/api/outputs/download/{}", file_id),
+                "stream_url": format!("/api/outputs/stream/{}", file_id),
+                "poster_url": OutputPreviewService::poster_url(&file_id),
+                "created_at": v.created_at,
+            })
+        })
+        .collect();
+
+    Ok(axum::Json(serde_json::json!({
+        "success": true,
+        "query": query.q,
+        "count": outputs.len(),
+        "outputs": outputs,
+    })))
+}
+
 // Helper functions
 
 fn generate_file_id(path: &PathBuf) -> String {
@@ -214,7 +620,39 @@ fn generate_file_id(path: &PathBuf) -> String {
     format!("{:x}", hasher.finish())
 }
 
-fn resolve_file_path(file_id: &str) -> Result<PathBuf, StatusCode> {
+/// File id of an output path as written by tools (`outputs/x.mp4`, `./x.mp4` or a bare file name),
+/// matching the paths `resolve_file_path` walks
+pub(crate) fn file_id_for_path(path: &std::path::Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => generate_file_id(&path.to_path_buf()),
+        _ => generate_file_id(&PathBuf::from(".").join(path)),
+    }
+}
+
+/// Rehydrate a recorded output path (a review or embed link's file) if it's in cold storage
+pub(crate) async fn ensure_hot(state: &AppState, path: &str) -> Result<(), StatusCode> {
+    StorageTierService::ensure_hot(&state.db_pool, path).await.map_err(|e| {
+        tracing::error!("Failed to rehydrate {}: {}", path, e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })
+}
+
+/// `resolve_file_path`, rehydrating the output first if it has been tiered to cold storage
+pub(crate) async fn resolve_output_path(state: &AppState, file_id: &str) -> Result<PathBuf, StatusCode> {
+    match resolve_file_path(file_id) {
+        Err(StatusCode::NOT_FOUND) => StorageTierService::rehydrate_file_id(&state.db_pool, file_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to rehydrate {}: {}", file_id, e);
+                StatusCode::SERVICE_UNAVAILABLE
+            })?
+            .ok_or(StatusCode::NOT_FOUND),
+        resolved => resolved,
+    }
+}
+
+pub(crate) fn resolve_file_path(file_id: &str) -> Result<PathBuf, StatusCode> {
     // In a production system, you'd want to store file_id -> path mappings in a database
     // For now, we'll scan both project root and outputs directory
 
@@ -266,11 +704,17 @@ fn get_content_type(extension: &str) -> String {
         "mov" => "video/quicktime",
         "mkv" => "video/x-matroska",
         "webm" => "video/webm",
+        "m3u8" => "application/vnd.apple.mpegurl",
+        "ts" => "video/mp2t",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
         _ => "application/octet-stream",
     }.to_string()
 }
 
-fn get_content_type_from_path(path: &PathBuf) -> String {
+pub(crate) fn get_content_type_from_path(path: &std::path::Path) -> String {
     if let Some(extension) = path.extension() {
         get_content_type(&extension.to_string_lossy())
     } else {
@@ -0,0 +1,242 @@
+// src/handlers/projects.rs
+//! Projects - a source video and an edit decision list, edited with undo/redo and rendered on export
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::project::{
+    AddOperationRequest, CreateProjectRequest, ExportProjectRequest, MoveOperationRequest, Project, ProjectOperation,
+};
+use crate::services::ProjectService;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct ListProjectsQuery {
+    pub session_id: Option<String>,
+}
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn project_routes() -> Router {
+    Router::new()
+        .route("/api/projects", get(list_projects).post(create_project))
+        .route("/api/projects/:id", get(get_project).delete(delete_project))
+        .route("/api/projects/:id/operations", post(add_operation))
+        .route("/api/projects/:id/operations/:position", delete(remove_operation))
+        .route("/api/projects/:id/operations/move", post(move_operation))
+        .route("/api/projects/:id/undo", post(undo))
+        .route("/api/projects/:id/redo", post(redo))
+        .route("/api/projects/:id/exports", get(list_exports).post(export_project))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> ApiError {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+fn internal_error(error: impl std::fmt::Display) -> ApiError {
+    tracing::error!("Project request failed: {}", error);
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Project request failed" })))
+}
+
+async fn find_project(state: &AppState, claims: &Claims, id: i32) -> Result<Project, ApiError> {
+    ProjectService::get_project(&state.db_pool, user_id(claims), id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Project not found" }))))
+}
+
+/// A project with its edit list at the current revision
+fn describe(project: &Project, operations: &[ProjectOperation]) -> Value {
+    json!({
+        "success": true,
+        "project": project,
+        "operations": operations,
+    })
+}
+
+async fn list_projects(
+    Query(query): Query<ListProjectsQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let projects = ProjectService::list_projects(&state.db_pool, user_id(&claims), query.session_id.as_deref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "projects": projects })))
+}
+
+async fn create_project(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateProjectRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let owns_session = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(&payload.session_id)
+    .bind(user_id(&claims))
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(internal_error)?
+    .is_some();
+    if !owns_session {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Session not found" }))));
+    }
+
+    let project = ProjectService::create_project(&state.db_pool, user_id(&claims), &payload.session_id, &payload.name, &payload.source_file)
+        .await
+        .map_err(bad_request)?;
+    Ok(Json(describe(&project, &[])))
+}
+
+/// The project, its current edit list and every revision (those past `revision` can be redone)
+async fn get_project(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let operations = ProjectService::operations(&state.db_pool, &project).await.map_err(internal_error)?;
+    let history = ProjectService::history(&state.db_pool, project.id).await.map_err(internal_error)?;
+
+    let mut body = describe(&project, &operations);
+    body["history"] = json!(history
+        .iter()
+        .map(|r| json!({ "revision": r.revision, "change": r.change, "created_at": r.created_at }))
+        .collect::<Vec<_>>());
+    Ok(Json(body))
+}
+
+async fn delete_project(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = ProjectService::delete_project(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true })))
+}
+
+async fn add_operation(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<AddOperationRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let operation = ProjectOperation { tool: payload.tool, args: payload.args, note: payload.note };
+    let (project, operations) = ProjectService::add_operation(&state.db_pool, &project, operation, payload.position)
+        .await
+        .map_err(bad_request)?;
+    Ok(Json(describe(&project, &operations)))
+}
+
+async fn remove_operation(
+    Path((id, position)): Path<(i32, usize)>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let (project, operations) = ProjectService::remove_operation(&state.db_pool, &project, position)
+        .await
+        .map_err(bad_request)?;
+    Ok(Json(describe(&project, &operations)))
+}
+
+async fn move_operation(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<MoveOperationRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let (project, operations) = ProjectService::move_operation(&state.db_pool, &project, payload.from, payload.to)
+        .await
+        .map_err(bad_request)?;
+    Ok(Json(describe(&project, &operations)))
+}
+
+async fn undo(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let (project, undone) = ProjectService::undo(&state.db_pool, &project)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, Json(json!({ "success": false, "error": e }))))?;
+    let operations = ProjectService::operations(&state.db_pool, &project).await.map_err(internal_error)?;
+
+    let mut body = describe(&project, &operations);
+    body["undone"] = json!(undone);
+    Ok(Json(body))
+}
+
+async fn redo(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let (project, redone) = ProjectService::redo(&state.db_pool, &project)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, Json(json!({ "success": false, "error": e }))))?;
+    let operations = ProjectService::operations(&state.db_pool, &project).await.map_err(internal_error)?;
+
+    let mut body = describe(&project, &operations);
+    body["redone"] = json!(redone);
+    Ok(Json(body))
+}
+
+async fn list_exports(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let exports = ProjectService::list_exports(&state.db_pool, project.id).await.map_err(internal_error)?;
+    Ok(Json(json!({ "success": true, "exports": exports })))
+}
+
+/// Render the current revision in the background; poll `GET /api/projects/:id/exports` for the result
+async fn export_project(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    payload: Option<Json<ExportProjectRequest>>,
+) -> Result<Json<Value>, ApiError> {
+    let project = find_project(&state, &claims, id).await?;
+    let request = payload.map(|Json(request)| request).unwrap_or_default();
+    let export = ProjectService::start_export(&state, &project, request.preset.as_deref())
+        .await
+        .map_err(bad_request)?;
+
+    let (render_state, render_export) = (state.clone(), export.clone());
+    tokio::spawn(async move {
+        match ProjectService::render_export(&render_state, &project, &render_export).await {
+            Ok(done) => tracing::info!("🎞️ Project {} exported: {}", project.id, done.output_path.unwrap_or_default()),
+            Err(e) => tracing::warn!("Project {} export {} failed: {}", project.id, render_export.id, e),
+        }
+    });
+
+    Ok(Json(json!({ "success": true, "export": export })))
+}
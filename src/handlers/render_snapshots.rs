@@ -0,0 +1,147 @@
+// src/handlers/render_snapshots.rs
+//! Reproducible render mode: pin a session's renders, snapshot outputs, and replay them later
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post, put},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::render_snapshot::{CreateRenderSnapshotRequest, RenderSnapshot, ReproducibleModeRequest};
+use crate::services::RenderSnapshotService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn render_snapshot_routes() -> Router {
+    Router::new()
+        .route("/api/sessions/:session_uuid/reproducible", put(set_reproducible_mode))
+        .route("/api/render-snapshots", get(list_snapshots).post(create_snapshot))
+        .route("/api/render-snapshots/:id", get(get_snapshot).delete(delete_snapshot))
+        .route("/api/render-snapshots/:id/replay", post(replay_snapshot))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+/// Snapshot without the (potentially large) pinned command manifest
+fn summary(snapshot: &RenderSnapshot) -> Value {
+    json!({
+        "id": snapshot.id,
+        "output_id": snapshot.output_id,
+        "name": snapshot.name,
+        "output_sha256": snapshot.output_sha256,
+        "ffmpeg_version": snapshot.ffmpeg_version,
+        "commands": snapshot.manifest["commands"].as_array().map(|c| c.len()).unwrap_or(0),
+        "replay_status": snapshot.replay_status,
+        "replay_output": snapshot.replay_output,
+        "replay_error": snapshot.replay_error,
+        "last_replayed_at": snapshot.last_replayed_at,
+        "created_at": snapshot.created_at,
+    })
+}
+
+/// Turn reproducible mode on or off for a chat session; later renders in it are pinned
+async fn set_reproducible_mode(
+    Path(session_uuid): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ReproducibleModeRequest>,
+) -> Result<Json<Value>, StatusCode> {
+    let updated = RenderSnapshotService::set_session_mode(&state.db_pool, user_id(&claims), &session_uuid, payload.enabled)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "session_id": session_uuid, "reproducible": payload.enabled })))
+}
+
+async fn list_snapshots(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let snapshots = RenderSnapshotService::list_snapshots(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "snapshots": snapshots.iter().map(summary).collect::<Vec<_>>()
+    })))
+}
+
+/// Freeze an output rendered in reproducible mode
+async fn create_snapshot(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateRenderSnapshotRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let snapshot = RenderSnapshotService::create_snapshot(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(bad_request)?;
+
+    Ok(Json(json!({ "success": true, "snapshot": summary(&snapshot) })))
+}
+
+/// Full snapshot, including the pinned commands and input hashes
+async fn get_snapshot(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let snapshot = RenderSnapshotService::get_snapshot(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(json!({ "success": true, "snapshot": snapshot })))
+}
+
+async fn delete_snapshot(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = RenderSnapshotService::delete_snapshot(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Snapshot removed" })))
+}
+
+/// Re-render a snapshot in the background; poll the snapshot for `replay_status`
+async fn replay_snapshot(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let snapshot = RenderSnapshotService::get_snapshot(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": e.to_string() }))))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Snapshot not found" }))))?;
+
+    RenderSnapshotService::start_replay(&state.db_pool, snapshot)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok((StatusCode::ACCEPTED, Json(json!({
+        "success": true,
+        "snapshot_id": id,
+        "replay_status": "running",
+        "status_url": format!("/api/render-snapshots/{}", id),
+    }))))
+}
@@ -0,0 +1,352 @@
+// src/handlers/review.rs
+//! Public review links - clients without accounts can watch an output and leave timestamped comments
+
+use axum::{
+    extract::{Extension, Path},
+    http::{HeaderMap, StatusCode},
+    response::{Html, Json, Response},
+    routing::{delete, get, patch},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::review::{CreateReviewCommentRequest, CreateReviewLinkRequest, ReviewLink, ReviewLinkResponse};
+use crate::services::output_stats::ViewEvent;
+use crate::services::OutputStatsService;
+use crate::services::ReviewService;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn review_routes() -> Router {
+    // Public routes (the token is the credential)
+    let public_routes = Router::new()
+        .route("/review/:token", get(review_page))
+        .route("/review/:token/stream", get(stream_review_video))
+        .route("/api/review/:token", get(get_review))
+        .route("/api/review/:token/comments", get(list_public_comments).post(add_comment));
+
+    // Protected routes (link owner)
+    let protected_routes = Router::new()
+        .route("/api/review-links", get(list_links).post(create_link))
+        .route("/api/review-links/:id", delete(revoke_link))
+        .route("/api/review-links/:id/comments", get(list_owner_comments))
+        .route("/api/review-comments/:id", patch(update_comment))
+        .layer(axum::middleware::from_fn(auth_middleware));
+
+    public_routes.merge(protected_routes)
+}
+
+#[derive(Deserialize)]
+pub struct UpdateCommentRequest {
+    pub resolved: bool,
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+/// Look up an active link; expired, revoked and unknown tokens all read as 404
+async fn active_link(state: &AppState, token: &str) -> Result<ReviewLink, StatusCode> {
+    ReviewService::get_active_link(&state.db_pool, token)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn create_link(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateReviewLinkRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let link = ReviewService::create_link(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "link": ReviewLinkResponse::from(link)
+    })))
+}
+
+async fn list_links(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let links = ReviewService::list_links(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "links": links.into_iter().map(ReviewLinkResponse::from).collect::<Vec<_>>()
+    })))
+}
+
+async fn revoke_link(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let revoked = ReviewService::revoke_link(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !revoked {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Review link revoked" })))
+}
+
+/// Owner view of a link's comments (works after the link expires)
+async fn list_owner_comments(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let link = ReviewService::get_owned_link(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let comments = ReviewService::list_comments(&state.db_pool, link.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "link": ReviewLinkResponse::from(link),
+        "comments": comments
+    })))
+}
+
+async fn update_comment(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateCommentRequest>,
+) -> Result<Json<Value>, StatusCode> {
+    let updated = ReviewService::set_comment_resolved(&state.db_pool, user_id(&claims), id, payload.resolved)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "resolved": payload.resolved })))
+}
+
+/// Link metadata for the review page (never exposes the server-side file path)
+async fn get_review(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<Value>, StatusCode> {
+    let link = active_link(&state, &token).await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "title": link.title,
+        "allow_comments": link.allow_comments,
+        "expires_at": link.expires_at,
+        "stream_url": format!("/review/{}/stream", token),
+    })))
+}
+
+async fn stream_review_video(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    crate::handlers::output::ensure_hot(&state, &link.file_path).await?;
+    let path = std::path::Path::new(&link.file_path);
+    let egress = crate::handlers::output::open_egress(Some(link.user_id))?;
+    let response = crate::handlers::output::stream_file(path, egress).await?;
+    OutputStatsService::record_for_path(&state.db_pool, path, ViewEvent::Stream, "review", &headers);
+    Ok(response)
+}
+
+async fn list_public_comments(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Json<Value>, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    let comments = ReviewService::list_comments(&state.db_pool, link.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({ "success": true, "comments": comments })))
+}
+
+async fn add_comment(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Json(payload): Json<CreateReviewCommentRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let link = active_link(&state, &token)
+        .await
+        .map_err(|status| (status, Json(json!({ "success": false, "error": "Review link is invalid or has expired" }))))?;
+    let comment = ReviewService::add_comment(&state.db_pool, &link, &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({ "success": true, "comment": comment })))
+}
+
+/// GET /review/:token - player plus comment feed; all content is loaded via the JSON API
+async fn review_page(
+    Path(token): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Result<Html<String>, StatusCode> {
+    let link = active_link(&state, &token).await?;
+    if let Err(e) = ReviewService::record_view(&state.db_pool, link.id).await {
+        tracing::warn!("Failed to record review view: {}", e);
+    }
+
+    let html = r###"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex">
+    <title>🎬 Review - VideoSync</title>
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; background: #0f0f13; color: #e8e8ee; }
+        header { padding: 16px 24px; border-bottom: 1px solid #26262e; display: flex; justify-content: space-between; align-items: center; }
+        header h1 { font-size: 18px; font-weight: 600; }
+        header .expires { font-size: 13px; color: #8a8a99; }
+        main { display: grid; grid-template-columns: minmax(0, 1fr) 360px; gap: 24px; padding: 24px; }
+        video { width: 100%; background: #000; border-radius: 8px; }
+        .panel { background: #17171d; border: 1px solid #26262e; border-radius: 8px; display: flex; flex-direction: column; max-height: calc(100vh - 120px); }
+        .panel h2 { font-size: 15px; padding: 14px 16px; border-bottom: 1px solid #26262e; }
+        #comments { flex: 1; overflow-y: auto; padding: 8px 16px; }
+        .comment { padding: 10px 0; border-bottom: 1px solid #22222a; }
+        .comment .meta { font-size: 12px; color: #8a8a99; margin-bottom: 4px; }
+        .comment .time { color: #7aa2ff; cursor: pointer; font-weight: 600; margin-right: 6px; }
+        .comment.resolved .body { text-decoration: line-through; color: #6b6b78; }
+        .empty { color: #6b6b78; font-size: 13px; padding: 12px 0; }
+        form { padding: 12px 16px; border-top: 1px solid #26262e; display: flex; flex-direction: column; gap: 8px; }
+        input, textarea { background: #0f0f13; border: 1px solid #2e2e38; color: #e8e8ee; border-radius: 6px; padding: 8px; font: inherit; font-size: 14px; }
+        textarea { resize: vertical; min-height: 64px; }
+        button { background: #5b6cff; color: #fff; border: none; border-radius: 6px; padding: 9px; font-weight: 600; cursor: pointer; }
+        .hint { font-size: 12px; color: #8a8a99; }
+        .error { color: #ff6b6b; font-size: 13px; }
+        @media (max-width: 900px) { main { grid-template-columns: 1fr; } }
+    </style>
+</head>
+<body>
+    <header>
+        <h1 id="title">Loading…</h1>
+        <span class="expires" id="expires"></span>
+    </header>
+    <main>
+        <div><video id="player" controls preload="metadata"></video></div>
+        <div class="panel">
+            <h2>💬 Comments</h2>
+            <div id="comments"><div class="empty">No comments yet</div></div>
+            <form id="comment-form">
+                <input id="author" placeholder="Your name" maxlength="100" required>
+                <textarea id="body" placeholder="Leave a comment at the current time…" maxlength="5000" required></textarea>
+                <span class="hint">Commenting at <strong id="at">0:00</strong> (pauses playback while you type)</span>
+                <span class="error" id="error"></span>
+                <button type="submit">Post comment</button>
+            </form>
+        </div>
+    </main>
+    <script>
+        const TOKEN = 'REVIEW_TOKEN_PLACEHOLDER';
+        const player = document.getElementById('player');
+        const list = document.getElementById('comments');
+
+        function fmt(seconds) {
+            const s = Math.floor(seconds);
+            const h = Math.floor(s / 3600), m = Math.floor((s % 3600) / 60), sec = s % 60;
+            const mm = h ? String(m).padStart(2, '0') : m;
+            return (h ? h + ':' : '') + mm + ':' + String(sec).padStart(2, '0');
+        }
+
+        function render(comments) {
+            list.innerHTML = '';
+            if (!comments.length) {
+                list.innerHTML = '<div class="empty">No comments yet</div>';
+                return;
+            }
+            for (const c of comments) {
+                const el = document.createElement('div');
+                el.className = 'comment' + (c.resolved ? ' resolved' : '');
+                const meta = document.createElement('div');
+                meta.className = 'meta';
+                const time = document.createElement('span');
+                time.className = 'time';
+                time.textContent = fmt(c.timestamp_seconds);
+                time.onclick = () => { player.currentTime = c.timestamp_seconds; player.play(); };
+                meta.appendChild(time);
+                meta.appendChild(document.createTextNode(c.author_name + ' · ' + new Date(c.created_at).toLocaleString()));
+                const body = document.createElement('div');
+                body.className = 'body';
+                body.textContent = c.body;
+                el.appendChild(meta);
+                el.appendChild(body);
+                list.appendChild(el);
+            }
+        }
+
+        async function loadComments() {
+            const res = await fetch('/api/review/' + TOKEN + '/comments');
+            if (res.ok) render((await res.json()).comments);
+        }
+
+        async function load() {
+            const res = await fetch('/api/review/' + TOKEN);
+            if (!res.ok) {
+                document.getElementById('title').textContent = 'This review link has expired';
+                return;
+            }
+            const info = await res.json();
+            document.title = '🎬 ' + info.title + ' - Review';
+            document.getElementById('title').textContent = info.title;
+            document.getElementById('expires').textContent = 'Link expires ' + new Date(info.expires_at).toLocaleString();
+            player.src = info.stream_url;
+            if (!info.allow_comments) document.getElementById('comment-form').style.display = 'none';
+            document.getElementById('author').value = localStorage.getItem('review_author') || '';
+            loadComments();
+        }
+
+        player.addEventListener('timeupdate', () => {
+            document.getElementById('at').textContent = fmt(player.currentTime);
+        });
+        document.getElementById('body').addEventListener('focus', () => player.pause());
+
+        document.getElementById('comment-form').addEventListener('submit', async (e) => {
+            e.preventDefault();
+            const author = document.getElementById('author').value.trim();
+            const body = document.getElementById('body').value.trim();
+            const error = document.getElementById('error');
+            error.textContent = '';
+            const res = await fetch('/api/review/' + TOKEN + '/comments', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify({ author_name: author, body, timestamp_seconds: player.currentTime })
+            });
+            const data = await res.json().catch(() => ({}));
+            if (!res.ok || !data.success) {
+                error.textContent = data.error || 'Failed to post comment';
+                return;
+            }
+            localStorage.setItem('review_author', author);
+            document.getElementById('body').value = '';
+            loadComments();
+        });
+
+        load();
+        setInterval(loadComments, 15000);
+    </script>
+</body>
+</html>
+"###;
+
+    // The token matched a stored link, so it is one of our generated hex strings
+    Ok(Html(html.replace("REVIEW_TOKEN_PLACEHOLDER", &token)))
+}
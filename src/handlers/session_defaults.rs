@@ -0,0 +1,59 @@
+// src/handlers/session_defaults.rs
+//! Session-level default parameters the agent applies to tool calls that leave them out
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::session_defaults::UpdateSessionDefaultsRequest;
+use crate::services::SessionDefaultsService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn session_defaults_routes() -> Router {
+    Router::new()
+        .route("/api/sessions/:session_uuid/defaults", get(get_session_defaults).put(update_session_defaults))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+async fn get_session_defaults(
+    Path(session_uuid): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let defaults = SessionDefaultsService::get_for_user(&state.db_pool, user_id(&claims), &session_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(json!({ "success": true, "session_id": session_uuid, "defaults": defaults })))
+}
+
+/// Merge new defaults into the session's; `clear` unsets fields
+async fn update_session_defaults(
+    Path(session_uuid): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateSessionDefaultsRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let defaults = SessionDefaultsService::update(&state.db_pool, Some(user_id(&claims)), &session_uuid, &payload)
+        .await
+        .map_err(bad_request)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Session not found" }))))?;
+
+    Ok(Json(json!({ "success": true, "session_id": session_uuid, "defaults": defaults })))
+}
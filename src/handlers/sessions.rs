@@ -0,0 +1,61 @@
+// src/handlers/sessions.rs
+//! Bulk session management: archive/unarchive/delete many chats, move uploads and outputs between them
+
+use axum::{
+    extract::Extension,
+    http::StatusCode,
+    response::Json,
+    routing::post,
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::session_bulk::{BulkSessionRequest, MoveItemsRequest};
+use crate::services::SessionBulkService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn session_routes() -> Router {
+    Router::new()
+        .route("/api/chat/sessions/bulk", post(bulk_update_sessions))
+        .route("/api/chat/sessions/move", post(move_session_items))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+async fn bulk_update_sessions(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<BulkSessionRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let updated = SessionBulkService::apply(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(bad_request)?;
+
+    Ok(Json(json!({ "success": true, "action": payload.action, "updated": updated })))
+}
+
+async fn move_session_items(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<MoveItemsRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let summary = SessionBulkService::move_items(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(bad_request)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "target_session_id": payload.target_session_id,
+        "files_moved": summary.files_moved,
+        "outputs_moved": summary.outputs_moved
+    })))
+}
@@ -0,0 +1,62 @@
+// src/handlers/storage.rs
+//! Per-user cold storage policy and the outputs currently tiered to cold storage
+
+use axum::{
+    extract::Extension,
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::storage_policy::UpdateStoragePolicyRequest;
+use crate::services::StorageTierService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn storage_routes() -> Router {
+    Router::new()
+        .route("/api/storage/policy", get(get_storage_policy).put(update_storage_policy))
+        .route("/api/storage/cold", get(list_cold_outputs))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+/// The user's overrides and the policy in effect once the server defaults fill the rest
+async fn get_storage_policy(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Json<Value> {
+    let policy = StorageTierService::policy(&state.db_pool, user_id(&claims)).await;
+    Json(json!({ "success": true, "policy": policy, "effective": StorageTierService::effective(&policy) }))
+}
+
+/// Merge settings into the user's policy; `clear` unsets fields
+async fn update_storage_policy(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateStoragePolicyRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let policy = StorageTierService::update_policy(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({ "success": true, "policy": policy, "effective": StorageTierService::effective(&policy) })))
+}
+
+async fn list_cold_outputs(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let outputs = StorageTierService::list_cold(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let bytes_saved: i64 = outputs.iter().map(|o| o.file_size - o.cold_size.unwrap_or(o.file_size)).sum();
+
+    Ok(Json(json!({ "success": true, "outputs": outputs, "bytes_saved": bytes_saved })))
+}
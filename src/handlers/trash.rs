@@ -0,0 +1,114 @@
+// src/handlers/trash.rs
+//! Deleting sessions and outputs moves them to the trash; they can be restored until they're purged
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::services::trash::TRASH_RETENTION_DAYS;
+use crate::services::TrashService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn trash_routes() -> Router {
+    Router::new()
+        .route("/api/trash", get(list_trash))
+        .route("/api/chat/sessions/:session_id", delete(trash_session))
+        .route("/api/chat/sessions/:session_id/restore", post(restore_session))
+        .route("/api/outputs/:id", delete(trash_output))
+        .route("/api/outputs/:id/restore", post(restore_output))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+async fn list_trash(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let items = TrashService::list(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "retention_days": TRASH_RETENTION_DAYS,
+        "items": items
+    })))
+}
+
+/// Move a chat session, with its messages, uploads and outputs, to the trash
+async fn trash_session(
+    Path(session_id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let trashed = TrashService::trash_session(&state.db_pool, user_id(&claims), &session_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !trashed {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({
+        "success": true,
+        "message": format!("Session moved to trash; it can be restored for {} days", TRASH_RETENTION_DAYS)
+    })))
+}
+
+async fn restore_session(
+    Path(session_id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let restored = TrashService::restore_session(&state.db_pool, user_id(&claims), &session_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !restored {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Session restored" })))
+}
+
+async fn trash_output(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let trashed = TrashService::trash_output(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !trashed {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({
+        "success": true,
+        "message": format!("Output moved to trash; it can be restored for {} days", TRASH_RETENTION_DAYS)
+    })))
+}
+
+/// Restore an output (and its session, if that was trashed too)
+async fn restore_output(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let restored = TrashService::restore_output(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !restored {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Output restored" })))
+}
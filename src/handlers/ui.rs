@@ -3449,6 +3449,7 @@ pub async fn chat_interface_with_session_id(session_id: Option<String>) -> Html<
         // ============================================================================
 
         let currentYouTubeVideo = null;
+        let youtubeUploadDefaults = {};
 
         async function openYouTubeUploadModal(videoPath, videoName) {
             currentYouTubeVideo = { path: videoPath, name: videoName };
@@ -3470,10 +3471,13 @@ pub async fn chat_interface_with_session_id(session_id: Option<String>) -> Html<
                     return;
                 }
 
-                const response = await fetch('/api/youtube/channels', {
-                    headers: { 'Authorization': 'Bearer ' + authToken }
-                });
+                const [response, defaultsResponse] = await Promise.all([
+                    fetch('/api/youtube/channels', { headers: { 'Authorization': 'Bearer ' + authToken } }),
+                    fetch('/api/youtube/defaults', { headers: { 'Authorization': 'Bearer ' + authToken } })
+                ]);
                 const data = await response.json();
+                const defaultsData = defaultsResponse.ok ? await defaultsResponse.json() : {};
+                youtubeUploadDefaults = defaultsData.defaults || {};
 
                 if (data.success && data.channels.length > 0) {
                     content.innerHTML = `
@@ -3490,7 +3494,7 @@ pub async fn chat_interface_with_session_id(session_id: Option<String>) -> Html<
                                             '<div style="width: 40px; height: 40px; border-radius: 50%; background: linear-gradient(135deg, #FF0000, #CC0000); display: flex; align-items: center; justify-content: center; color: white; font-size: 1.2rem;">📺</div>'
                                         }
                                         <div style="flex: 1;">
-                                            <div style="font-weight: 600; color: #2c3e50;">${channel.channel_name}</div>
+                                            <div style="font-weight: 600; color: #2c3e50;">${channel.channel_name}${channel.id === youtubeUploadDefaults.channel_id ? ' <span style="font-size: 0.8rem; color: #3b82f6;">⭐ Default</span>' : ''}</div>
                                             <div style="font-size: 0.85rem; color: #6c757d;">
                                                 ${channel.subscriber_count !== null ? channel.subscriber_count.toLocaleString() + ' subscribers' : ''}
                                             </div>
@@ -3535,14 +3539,19 @@ pub async fn chat_interface_with_session_id(session_id: Option<String>) -> Html<
                 return;
             }
 
-            const title = prompt("Enter title for your YouTube video:", currentYouTubeVideo.name.replace('.mp4', ''));
-            if (!title) return;
+            // Blank title/description fall back to the generated metadata and the user's upload defaults
+            const title = prompt("Enter title for your YouTube video (clear it to use the suggested title):", currentYouTubeVideo.name.replace('.mp4', ''));
+            if (title === null) return;
 
-            const description = prompt('Enter description (optional):', 'Created with VideoSync');
-            const privacyStatus = prompt('Privacy status (public/private/unlisted):', 'private');
+            const description = prompt(youtubeUploadDefaults.description_template
+                ? 'Enter description (leave blank to use your description template):'
+                : 'Enter description (optional):', '');
+            let privacyStatus = prompt('Privacy status (public/private/unlisted):', youtubeUploadDefaults.privacy_status || 'private');
+            if (privacyStatus === null) return;
 
             if (!['public', 'private', 'unlisted'].includes(privacyStatus.toLowerCase())) {
                 alert('Invalid privacy status. Using "private"');
+                privacyStatus = 'private';
             }
 
             const modal = document.getElementById('youtubeModalContent');
@@ -3565,11 +3574,9 @@ pub async fn chat_interface_with_session_id(session_id: Option<String>) -> Html<
                     body: JSON.stringify({
                         channel_id: channelId,
                         video_path: currentYouTubeVideo.path,
-                        title: title,
-                        description: description || 'Created with VideoSync',
-                        privacy_status: privacyStatus.toLowerCase(),
-                        category: '22',
-                        tags: ['AI', 'Video Editing', 'VideoSync']
+                        title: title.trim(),
+                        description: description && description.trim() ? description : undefined,
+                        privacy_status: privacyStatus.toLowerCase()
                     })
                 });
 
@@ -1,6 +1,7 @@
-use crate::models::file::{FileUploadResponse, MultipleFileUploadResponse};
-use crate::middleware::auth::auth_middleware;
-use crate::services::VideoVectorizationService;
+use crate::models::file::{FileUploadResponse, MultipleFileUploadResponse, UploadOptions};
+use crate::middleware::{auth::auth_middleware, idempotency::upload_idempotency_middleware};
+use crate::models::auth::Claims;
+use crate::services::{ActivityService, AssetTaggingService, ChapteringService, UploadDedupService, VideoVectorizationService};
 use crate::AppState;
 use sqlx::Row;
 use axum::{
@@ -19,14 +20,20 @@ use uuid::Uuid;
 
 pub fn upload_routes() -> Router {
     let public_routes = Router::new()
-        .route("/upload", post(upload_files))
+        .route("/upload", post(upload_files).layer(axum::middleware::from_fn(upload_idempotency_middleware)))
         .route("/upload/form", axum::routing::get(upload_form))
         .route("/upload/status/:file_id", axum::routing::get(get_upload_status))
-        .route("/upload/session/:session_uuid", post(upload_files_for_session))
+        .route(
+            "/upload/session/:session_uuid",
+            post(upload_files_for_session).layer(axum::middleware::from_fn(upload_idempotency_middleware)),
+        )
         .layer(DefaultBodyLimit::max(100 * 1024 * 1024)); // 100MB limit for file uploads
     
     let protected_routes = Router::new()
         .route("/files/session/:session_uuid", axum::routing::get(get_session_files))
+        .route("/files/duplicates", axum::routing::get(list_duplicate_uploads))
+        .route("/files/:file_id/reuse-original", post(reuse_original_upload))
+        .route("/files/:file_id/chapters", axum::routing::get(get_upload_chapters))
         .layer(axum::middleware::from_fn(auth_middleware));
     
     public_routes.merge(protected_routes)
@@ -201,7 +208,7 @@ pub async fn upload_files(
         
         // Save to database (simplified query to avoid compile-time checks)
         let insert_result = sqlx::query(
-            "INSERT INTO uploaded_files (id, original_name, stored_name, file_path, file_size, file_type, mime_type, upload_status) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+            "INSERT INTO uploaded_files (id, original_name, stored_name, file_path, file_size, file_type, mime_type, upload_status, content_hash) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
         )
         .bind(&file_id)
         .bind(&filename)
@@ -211,22 +218,28 @@ pub async fn upload_files(
         .bind(&file_type)
         .bind(&mime_type)
         .bind("uploaded")
+        .bind(UploadDedupService::content_hash(&data))
         .execute(&state.db_pool)
         .await;
         
         match insert_result {
             Ok(_) => {
                 uploaded_files.push(FileUploadResponse {
-                    id: file_id,
+                    id: file_id.clone(),
                     original_name: filename.clone(),
                     stored_name: unique_filename.clone(),
                     path: file_path.clone(),
                     file_size: data.len() as i64,
-                    file_type,
+                    file_type: file_type.clone(),
                     status: "uploaded".to_string(),
+                    duplicate_of: None,
                 });
                 
                 tracing::info!("Uploaded and stored file: {} -> {}", filename, file_path);
+
+                if matches!(file_type.as_str(), "video" | "image") {
+                    AssetTaggingService::spawn_tagging(state.clone(), "upload", file_id.clone(), None, file_path.clone());
+                }
             }
             Err(e) => {
                 tracing::error!("Failed to save file to database: {}", e);
@@ -256,15 +269,31 @@ pub async fn get_upload_status(
     .fetch_optional(&state.db_pool)
     .await
     {
-        Ok(Some(file)) => Ok(Json(json!({
-            "file_id": file.id,
-            "original_name": file.original_name,
-            "file_type": file.file_type,
-            "file_size": file.file_size,
-            "status": file.upload_status,
-            "created_at": file.created_at,
-            "message": "File found"
-        }))),
+        Ok(Some(file)) => {
+            let visual_tags = AssetTaggingService::get_tags(&state.db_pool, "upload", &file.id)
+                .await
+                .ok()
+                .flatten();
+            let (duplicate_of, duplicate_kind) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+                "SELECT duplicate_of, duplicate_kind FROM uploaded_files WHERE id = $1"
+            )
+            .bind(&file.id)
+            .fetch_one(&state.db_pool)
+            .await
+            .unwrap_or((None, None));
+            Ok(Json(json!({
+                "file_id": file.id,
+                "original_name": file.original_name,
+                "file_type": file.file_type,
+                "file_size": file.file_size,
+                "status": file.upload_status,
+                "created_at": file.created_at,
+                "visual_tags": visual_tags,
+                "duplicate_of": duplicate_of,
+                "duplicate_kind": duplicate_kind,
+                "message": "File found"
+            })))
+        }
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
             tracing::error!("Database error checking file status: {}", e);
@@ -346,6 +375,7 @@ fn detect_mime_type(filename: &str) -> Option<String> {
 // Upload files and associate them with a specific chat session
 pub async fn upload_files_for_session(
     axum::extract::Path(session_uuid): axum::extract::Path<String>,
+    axum::extract::Query(options): axum::extract::Query<UploadOptions>,
     Extension(state): Extension<Arc<AppState>>,
     mut multipart: Multipart,
 ) -> Result<Json<MultipleFileUploadResponse>, StatusCode> {
@@ -368,6 +398,17 @@ pub async fn upload_files_for_session(
         }
     };
 
+    // Owner of the session: vision tags and duplicate detection are scoped per user
+    let session_owner = match session_id {
+        Some(id) => sqlx::query_scalar::<_, i32>("SELECT user_id FROM chat_sessions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.db_pool)
+            .await
+            .ok()
+            .flatten(),
+        None => None,
+    };
+
     while let Some(field) = multipart.next_field().await.map_err(|e| {
         tracing::error!("Failed to parse multipart field: {}", e);
         StatusCode::BAD_REQUEST
@@ -416,6 +457,36 @@ pub async fn upload_files_for_session(
             tracing::warn!("Rejected file '{}' with unsupported file type: {} for session {}", filename, file_type, session_uuid);
             continue;
         }
+
+        // Identical bytes already uploaded by this user: link the existing file instead of storing another copy
+        let content_hash = UploadDedupService::content_hash(&data);
+        let reuse_duplicates = options.on_duplicate.as_deref() != Some("keep");
+        if let (Some(owner), true) = (session_owner, reuse_duplicates) {
+            match UploadDedupService::find_exact_duplicate(&state.db_pool, owner, &content_hash).await {
+                Ok(Some(existing)) => {
+                    let file_id = Uuid::new_v4().to_string();
+                    match UploadDedupService::insert_reused(&state.db_pool, &file_id, session_id, &filename, &existing, &content_hash).await {
+                        Ok(_) => {
+                            tracing::info!("🔁 {} is identical to upload {}, reusing {}", filename, existing.id, existing.file_path);
+                            uploaded_files.push(FileUploadResponse {
+                                id: file_id,
+                                original_name: filename.clone(),
+                                stored_name: existing.stored_name.clone(),
+                                path: existing.file_path.clone(),
+                                file_size: existing.file_size,
+                                file_type: existing.file_type.clone(),
+                                status: "duplicate_reused".to_string(),
+                                duplicate_of: Some(existing.id.clone()),
+                            });
+                            continue;
+                        }
+                        Err(e) => tracing::warn!("Failed to reuse duplicate {}, storing a new copy: {}", existing.id, e),
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Duplicate lookup failed for {}: {}", filename, e),
+            }
+        }
         
         // Write file to disk
         match fs::File::create(&file_path).await {
@@ -432,7 +503,7 @@ pub async fn upload_files_for_session(
         
         // Save to database with session association
         let insert_result = sqlx::query(
-            "INSERT INTO uploaded_files (id, session_id, original_name, stored_name, file_path, file_size, file_type, mime_type, upload_status) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+            "INSERT INTO uploaded_files (id, session_id, original_name, stored_name, file_path, file_size, file_type, mime_type, upload_status, content_hash) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
         )
         .bind(&file_id)
         .bind(session_id)
@@ -443,6 +514,7 @@ pub async fn upload_files_for_session(
         .bind(&file_type)
         .bind(&mime_type)
         .bind("uploaded")
+        .bind(&content_hash)
         .execute(&state.db_pool)
         .await;
         
@@ -456,9 +528,32 @@ pub async fn upload_files_for_session(
                     file_size: data.len() as i64,
                     file_type: file_type.clone(),
                     status: "uploaded".to_string(),
+                    duplicate_of: None,
                 });
                 
                 tracing::info!("Uploaded file for session {}: {} -> {}", session_uuid, filename, file_path);
+
+                if let Some(owner) = session_owner {
+                    ActivityService::record(
+                        &state.db_pool,
+                        owner,
+                        "file_uploaded",
+                        &format!("Uploaded {}", filename),
+                        Some(&session_uuid),
+                        json!({ "file_id": file_id, "file_type": file_type, "file_size": data.len(), "path": file_path }),
+                    )
+                    .await;
+                }
+
+                UploadDedupService::spawn_fingerprint(state.clone(), file_id.clone(), session_owner, file_path.clone(), file_type.clone());
+
+                if matches!(file_type.as_str(), "video" | "image") {
+                    AssetTaggingService::spawn_tagging(state.clone(), "upload", file_id.clone(), session_owner, file_path.clone());
+                }
+
+                if options.chapters == Some(true) && matches!(file_type.as_str(), "video" | "audio") {
+                    ChapteringService::spawn_chaptering(state.clone(), file_id.clone(), file_path.clone());
+                }
                 
                 // Process video files for vectorization
                 if file_type == "video" {
@@ -550,6 +645,73 @@ pub async fn get_session_files(
 }
 
 // Helper function to get or create a chat session
+/// GET /files/duplicates - uploads detected as copies of earlier ones (identical bytes or the same footage re-encoded)
+pub async fn list_duplicate_uploads(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let duplicates = UploadDedupService::list_duplicates(&state.db_pool, user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to list duplicate uploads: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let reclaimable: i64 = duplicates.iter().filter(|d| d.stores_copy).map(|d| d.file_size).sum();
+
+    Ok(Json(json!({
+        "success": true,
+        "duplicates": duplicates,
+        "reclaimable_bytes": reclaimable
+    })))
+}
+
+/// POST /files/:file_id/reuse-original - drop a duplicate's own copy and point it at the original file
+pub async fn reuse_original_upload(
+    axum::extract::Path(file_id): axum::extract::Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let user_id = claims.sub.parse::<i32>()
+        .map_err(|_| (StatusCode::UNAUTHORIZED, Json(json!({ "success": false, "error": "Invalid user" }))))?;
+    let freed = UploadDedupService::reuse_original(&state.db_pool, user_id, &file_id)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "file_id": file_id,
+        "freed_bytes": freed
+    })))
+}
+
+/// GET /files/:file_id/chapters - chapter index of an upload made with ?chapters=true
+pub async fn get_upload_chapters(
+    axum::extract::Path(file_id): axum::extract::Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let user_id = claims.sub.parse::<i32>()
+        .map_err(|_| (StatusCode::UNAUTHORIZED, Json(json!({ "success": false, "error": "Invalid user" }))))?;
+    let found = ChapteringService::get_for_user(&state.db_pool, user_id, &file_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load chapters for {}: {}", file_id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Failed to load chapters" })))
+        })?;
+    let Some((index, chapters)) = found else {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "No chapter index for this file" }))));
+    };
+
+    Ok(Json(json!({
+        "success": true,
+        "status": index.status,
+        "duration_seconds": index.duration_seconds,
+        "error": index.error_message,
+        "chapters": chapters
+    })))
+}
+
 pub async fn get_or_create_session(state: &AppState, session_uuid: &str) -> Result<i32, sqlx::Error> {
     // First try to find existing session
     let session_row = sqlx::query("SELECT id FROM chat_sessions WHERE session_uuid = $1")
@@ -0,0 +1,90 @@
+// src/handlers/webhooks.rs
+//! Webhook registration and delivery logs for job completion notifications
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get},
+    Router,
+};
+use crate::middleware::auth::auth_middleware;
+use crate::models::auth::Claims;
+use crate::models::webhook::{CreateWebhookRequest, WebhookResponse};
+use crate::services::WebhookService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn webhook_routes() -> Router {
+    Router::new()
+        .route("/api/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/api/webhooks/:id", delete(delete_webhook))
+        .route("/api/webhooks/deliveries", get(list_deliveries))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+async fn list_webhooks(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let webhooks = WebhookService::list(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "webhooks": webhooks.into_iter().map(WebhookResponse::from).collect::<Vec<_>>()
+    })))
+}
+
+/// Register a webhook; the response is the only place its secret is shown
+async fn create_webhook(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let webhook = WebhookService::create(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))))?;
+
+    let secret = webhook.secret.clone();
+    Ok(Json(json!({
+        "success": true,
+        "webhook": WebhookResponse::from(webhook),
+        "secret": secret
+    })))
+}
+
+async fn delete_webhook(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = WebhookService::delete(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Webhook removed" })))
+}
+
+async fn list_deliveries(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deliveries = WebhookService::list_deliveries(&state.db_pool, user_id(&claims), 100)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "deliveries": deliveries
+    })))
+}
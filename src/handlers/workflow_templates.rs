@@ -0,0 +1,146 @@
+// src/handlers/workflow_templates.rs
+//! Workflow templates - named job graphs with parameter slots, listed, filled in and run on a session
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use crate::middleware::{auth::auth_middleware, idempotency::idempotency_middleware};
+use crate::models::auth::Claims;
+use crate::models::workflow_template::{
+    CreateWorkflowTemplateRequest, InstantiateTemplateRequest, RunTemplateRequest, WorkflowTemplate,
+};
+use crate::services::WorkflowTemplateService;
+use crate::AppState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+pub fn workflow_template_routes() -> Router {
+    Router::new()
+        .route("/api/workflow-templates", get(list_templates).post(create_template))
+        .route("/api/workflow-templates/:id", get(get_template).delete(delete_template))
+        .route("/api/workflow-templates/:id/instantiate", post(instantiate_template))
+        .route(
+            "/api/workflow-templates/:id/run",
+            post(run_template).layer(axum::middleware::from_fn(idempotency_middleware)),
+        )
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+/// A template with its parameters and jobs spelled out, and whether it's built in
+fn describe(template: &WorkflowTemplate) -> Value {
+    json!({
+        "id": template.id,
+        "name": template.name,
+        "description": template.description,
+        "builtin": template.user_id.is_none(),
+        "parameters": template.parameters,
+        "jobs": template.jobs,
+        "created_at": template.created_at,
+        "updated_at": template.updated_at,
+    })
+}
+
+async fn find_template(state: &AppState, user_id: i32, id: i32) -> Result<WorkflowTemplate, (StatusCode, Json<Value>)> {
+    WorkflowTemplateService::get_template(&state.db_pool, user_id, id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load workflow template {}: {}", id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "success": false, "error": "Failed to load template" })))
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(json!({ "success": false, "error": "Workflow template not found" }))))
+}
+
+/// Built-in templates and the user's own
+async fn list_templates(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let templates = WorkflowTemplateService::list_templates(&state.db_pool, user_id(&claims))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "templates": templates.iter().map(describe).collect::<Vec<_>>()
+    })))
+}
+
+async fn create_template(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateWorkflowTemplateRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let template = WorkflowTemplateService::create_template(&state.db_pool, user_id(&claims), &payload)
+        .await
+        .map_err(bad_request)?;
+
+    Ok(Json(json!({ "success": true, "template": describe(&template) })))
+}
+
+async fn get_template(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let template = find_template(&state, user_id(&claims), id).await?;
+    Ok(Json(json!({ "success": true, "template": describe(&template) })))
+}
+
+async fn delete_template(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let deleted = WorkflowTemplateService::delete_template(&state.db_pool, user_id(&claims), id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(json!({ "success": true, "message": "Workflow template removed" })))
+}
+
+/// The job graph the template makes with these parameters, without running it
+async fn instantiate_template(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<InstantiateTemplateRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let template = find_template(&state, user_id(&claims), id).await?;
+    let jobs = WorkflowTemplateService::instantiate(&template, &payload.params).map_err(bad_request)?;
+    Ok(Json(json!({ "success": true, "template": template.name, "jobs": jobs })))
+}
+
+/// Fill in the template and start its jobs as a job graph in the session
+async fn run_template(
+    Path(id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RunTemplateRequest>,
+) -> Response {
+    let user_id = user_id(&claims);
+    let template = match find_template(&state, user_id, id).await {
+        Ok(template) => template,
+        Err(error) => return error.into_response(),
+    };
+    let jobs = match WorkflowTemplateService::instantiate(&template, &payload.params) {
+        Ok(jobs) => jobs,
+        Err(e) => return bad_request(e).into_response(),
+    };
+    tracing::info!("🧩 Running workflow template '{}' in session {}", template.name, payload.session_id);
+    crate::handlers::jobs::start_job_graph(&state, user_id, payload.session_id, jobs, payload.priority).await
+}
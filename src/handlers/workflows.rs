@@ -0,0 +1,196 @@
+// src/handlers/workflows.rs
+//! Workflow definitions - validate and run YAML/JSON pipelines of tool calls on a session
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use crate::agent::tool_executor::ToolExecutionContext;
+use crate::middleware::{auth::auth_middleware, idempotency::idempotency_middleware};
+use crate::models::auth::Claims;
+use crate::workflow::checkpoint::{Checkpoint, WorkflowCheckpointer};
+use crate::workflow::definition::{run_workflow_definition, WorkflowDefinition};
+use crate::workflow::executor::RESUME_WINDOW_HOURS;
+use crate::workflow::visualize::RunGraph;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct ValidateWorkflowRequest {
+    /// The definition as a JSON object, or as YAML/JSON text
+    pub definition: Value,
+}
+
+#[derive(Deserialize)]
+pub struct RunWorkflowRequest {
+    pub session_id: String,
+    pub definition: Value,
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+pub struct DiagramQuery {
+    /// mermaid (default) or dot
+    pub format: Option<String>,
+}
+
+pub fn workflow_routes() -> Router {
+    Router::new()
+        .route("/api/workflows/validate", post(validate_workflow))
+        .route(
+            "/api/workflows/run",
+            post(run_workflow).layer(axum::middleware::from_fn(idempotency_middleware)),
+        )
+        .route("/api/workflows/:workflow_id", get(get_workflow))
+        .route("/api/workflows/:workflow_id/diagram", get(get_workflow_diagram))
+        .layer(axum::middleware::from_fn(auth_middleware))
+}
+
+fn user_id(claims: &Claims) -> i32 {
+    claims.sub.parse::<i32>().unwrap_or(0)
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": error })))
+}
+
+async fn owns_session(state: &AppState, user_id: i32, session_id: &str) -> Result<bool, StatusCode> {
+    sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map(|found| found.is_some())
+    .map_err(|e| {
+        tracing::error!("Failed to load session {}: {}", session_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Check a definition without running it; errors list every problem found
+async fn validate_workflow(Json(payload): Json<ValidateWorkflowRequest>) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let definition = WorkflowDefinition::from_value(payload.definition).map_err(bad_request)?;
+    Ok(Json(json!({ "success": true, "definition": definition })))
+}
+
+/// Start a definition on a session in the background; poll `GET /api/workflows/:workflow_id` for progress
+async fn run_workflow(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RunWorkflowRequest>,
+) -> Response {
+    let user_id = user_id(&claims);
+    let definition = match WorkflowDefinition::from_value(payload.definition) {
+        Ok(definition) => definition,
+        Err(e) => return bad_request(e).into_response(),
+    };
+    if let Err(e) = definition.resolve_params(&payload.params) {
+        return bad_request(e).into_response();
+    }
+    match owns_session(&state, user_id, &payload.session_id).await {
+        Ok(true) => {}
+        Ok(false) => return (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        Err(status) => return status.into_response(),
+    }
+
+    // An unfinished run of the same definition and parameters picks up where it stopped
+    let resumable = WorkflowCheckpointer::new(state.db_pool.clone())
+        .load_resumable(&payload.session_id, &definition.run_key(&payload.params), RESUME_WINDOW_HOURS)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to look for a workflow run to resume: {}", e);
+            None
+        });
+    let resumed = resumable.is_some();
+    // A resumed run stays on the version it started from unless this one migrates it
+    let version = resumable.as_ref().map(|checkpoint| definition.resumed_version(checkpoint)).unwrap_or(definition.version);
+    let workflow_id = resumable.map(|checkpoint| checkpoint.workflow_id).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    tracing::info!("🧩 Running workflow '{}' ({}) in session {}", definition.name, workflow_id, payload.session_id);
+    let ctx = Arc::new(ToolExecutionContext {
+        session_id: payload.session_id.clone(),
+        user_id: Some(user_id),
+        app_state: state.clone(),
+    });
+    let run_id = workflow_id.clone();
+    tokio::spawn(async move {
+        match run_workflow_definition(&definition, &payload.params, ctx, run_id).await {
+            Ok(finished) => tracing::info!("🧩 Workflow '{}' finished: {:?}", definition.name, finished.status),
+            Err(e) => tracing::error!("❌ Workflow '{}' failed: {}", definition.name, e),
+        }
+    });
+
+    Json(json!({ "success": true, "workflow_id": workflow_id, "resumed": resumed, "version": version })).into_response()
+}
+
+/// The latest checkpoint of a run in one of the user's sessions
+async fn find_run(state: &AppState, claims: &Claims, workflow_id: &str) -> Result<Checkpoint, StatusCode> {
+    let checkpoint = WorkflowCheckpointer::new(state.db_pool.clone())
+        .load_latest(workflow_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load workflow {}: {}", workflow_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !owns_session(state, user_id(claims), &checkpoint.thread_id).await? {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(checkpoint)
+}
+
+/// Progress of a run: its status, the nodes done so far and what each one produced. Definition runs
+/// also get `graph`: every node and edge with the node's status, timings and output.
+async fn get_workflow(
+    Path(workflow_id): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Value>, StatusCode> {
+    let checkpoint = find_run(&state, &claims, &workflow_id).await?;
+    let run = checkpoint.state;
+    Ok(Json(json!({
+        "success": true,
+        "workflow_id": run.workflow_id,
+        "session_id": run.thread_id,
+        "status": run.status,
+        "definition_version": checkpoint.definition_version,
+        "current_node": run.current_node,
+        "completed_nodes": run.completed_nodes,
+        "graph": RunGraph::of_run(&run),
+        "node_outputs": run.node_outputs,
+        "node_attempts": run.node_attempts,
+        "node_timings": run.node_timings,
+        "errors": run.errors,
+        "updated_at": checkpoint.created_at,
+    })))
+}
+
+/// A definition run as a Mermaid flowchart or Graphviz DOT digraph, nodes colored by status
+async fn get_workflow_diagram(
+    Path(workflow_id): Path<String>,
+    Query(query): Query<DiagramQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Response {
+    let checkpoint = match find_run(&state, &claims, &workflow_id).await {
+        Ok(checkpoint) => checkpoint,
+        Err(status) => return status.into_response(),
+    };
+    let Some(graph) = RunGraph::of_run(&checkpoint.state) else {
+        return bad_request("Only runs of workflow definitions can be drawn".to_string()).into_response();
+    };
+    match query.format.as_deref().unwrap_or("mermaid") {
+        "mermaid" => ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], graph.to_mermaid()).into_response(),
+        "dot" => ([(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")], graph.to_dot()).into_response(),
+        other => bad_request(format!("Unknown diagram format '{}'; use mermaid or dot", other)).into_response(),
+    }
+}
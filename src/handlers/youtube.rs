@@ -4,6 +4,7 @@
 use crate::models::youtube::*;
 use crate::youtube_client;
 use crate::middleware::auth::auth_middleware;
+use crate::middleware::idempotency::idempotency_middleware;
 use crate::AppState;
 use axum::{
     extract::{Extension, Path, Query},
@@ -35,8 +36,11 @@ pub fn youtube_routes() -> Router {
         .route("/api/youtube/channels/:id/refresh", post(refresh_channel_token))
 
         // Video upload (protected)
-        .route("/api/youtube/upload", post(upload_video_to_youtube))
+        .route("/api/youtube/upload", post(upload_video_to_youtube).layer(axum::middleware::from_fn(idempotency_middleware)))
         .route("/api/youtube/uploads", get(list_upload_history))
+        .route("/api/youtube/defaults", get(get_upload_defaults).put(update_upload_defaults))
+        .route("/api/youtube/drafts", get(list_draft_uploads).post(create_draft_upload).layer(axum::middleware::from_fn(idempotency_middleware)))
+        .route("/api/youtube/drafts/:upload_id/finalize", post(finalize_draft_upload).layer(axum::middleware::from_fn(idempotency_middleware)))
 
         // Video management (NEW)
         .route("/api/youtube/videos/:video_id", delete(delete_video_from_youtube))
@@ -74,7 +78,7 @@ pub fn youtube_routes() -> Router {
         .route("/api/youtube/captions/:caption_id", delete(delete_caption))
 
         // Resumable uploads (NEW)
-        .route("/api/youtube/upload/resumable", post(initiate_resumable_upload))
+        .route("/api/youtube/upload/resumable", post(initiate_resumable_upload).layer(axum::middleware::from_fn(idempotency_middleware)))
         .route("/api/youtube/upload/resumable/:upload_id/chunk", put(upload_chunk))
         .layer(axum::middleware::from_fn(crate::middleware::youtube_access::youtube_access_middleware))
         .layer(axum::middleware::from_fn(auth_middleware));
@@ -139,8 +143,7 @@ pub async fn initiate_youtube_connection(
         "https://www.googleapis.com/auth/userinfo.profile",
     ];
 
-    let redirect_uri = std::env::var("GOOGLE_OAUTH_REDIRECT_URI")
-        .unwrap_or_else(|_| "http://localhost:3000/youtube/callback".to_string());
+    let redirect_uri = crate::config::get().auth.google_oauth_redirect_uri.clone();
 
     let auth_url = youtube_client::build_google_oauth_url(
         client_id,
@@ -210,8 +213,7 @@ pub async fn youtube_oauth_callback(
     // Exchange code for tokens
     let client_id = state.google_oauth_client_id.as_ref().unwrap();
     let client_secret = state.google_oauth_client_secret.as_ref().unwrap();
-    let redirect_uri = std::env::var("GOOGLE_OAUTH_REDIRECT_URI")
-        .unwrap_or_else(|_| "http://localhost:3000/youtube/callback".to_string());
+    let redirect_uri = crate::config::get().auth.google_oauth_redirect_uri.clone();
 
     let client = reqwest::Client::new();
     let token_response = youtube_client::exchange_code_for_token(
@@ -279,6 +281,8 @@ pub async fn youtube_oauth_callback(
                 subscriber_count = $6,
                 video_count = $7,
                 is_active = true,
+                token_status = 'healthy',
+                token_error = NULL,
                 updated_at = NOW()
             "#
         )
@@ -465,12 +469,20 @@ pub async fn upload_video_to_youtube(
     Json(payload): Json<UploadToYouTubeRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
     let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    upload_for_user(&state, user_id, &payload).await.map(Json)
+}
 
+/// One of the user's active channels, with its access token refreshed if it's about to expire
+async fn connected_channel(
+    state: &AppState,
+    user_id: i32,
+    channel_id: i32,
+) -> Result<ConnectedYouTubeChannel, (StatusCode, Json<serde_json::Value>)> {
     // Get channel and verify ownership
     let mut channel = sqlx::query_as::<_, ConnectedYouTubeChannel>(
         "SELECT * FROM connected_youtube_channels WHERE id = $1 AND user_id = $2 AND is_active = true"
     )
-    .bind(payload.channel_id)
+    .bind(channel_id)
     .bind(user_id)
     .fetch_optional(&state.db_pool)
     .await
@@ -494,16 +506,20 @@ pub async fn upload_video_to_youtube(
         let client_id = state.google_oauth_client_id.as_ref().unwrap();
         let client_secret = state.google_oauth_client_secret.as_ref().unwrap();
 
-        let token_response = youtube.refresh_access_token(
+        let token_response = match youtube.refresh_access_token(
             &channel.refresh_token,
             client_id,
             client_secret,
         )
         .await
-        .map_err(|e| {
-            tracing::error!("Failed to refresh token: {}", e);
-            (StatusCode::UNAUTHORIZED, Json(json!({"success": false, "message": "Token expired. Please reconnect your channel."})))
-        })?;
+        {
+            Ok(token_response) => token_response,
+            Err(e) => {
+                tracing::error!("Failed to refresh token: {}", e);
+                crate::services::TokenHealthService::record_failure(&state.db_pool, &channel, &e.to_string()).await;
+                return Err((StatusCode::UNAUTHORIZED, Json(json!({"success": false, "message": "Token expired. Please reconnect your channel."}))));
+            }
+        };
 
         // Update token in memory and database
         channel.access_token = token_response.access_token.clone();
@@ -516,12 +532,40 @@ pub async fn upload_video_to_youtube(
         )
         .bind(&channel.access_token)
         .bind(channel.token_expiry)
-        .bind(payload.channel_id)
+        .bind(channel_id)
         .execute(&state.db_pool)
         .await
         .ok();
     }
 
+    Ok(channel)
+}
+
+/// Upload a video to one of the user's channels, recording it in youtube_uploads (shared with scheduled
+/// uploads and the agent). Anything the payload leaves out comes from the user's upload defaults.
+pub async fn upload_for_user(
+    state: &AppState,
+    user_id: i32,
+    payload: &UploadToYouTubeRequest,
+) -> Result<serde_json::Value, (StatusCode, Json<serde_json::Value>)> {
+    upload_to_channel(state, user_id, payload, false).await
+}
+
+/// Upload a video, or with `draft` only its bytes: privately, to be finalized later
+async fn upload_to_channel(
+    state: &AppState,
+    user_id: i32,
+    payload: &UploadToYouTubeRequest,
+    draft: bool,
+) -> Result<serde_json::Value, (StatusCode, Json<serde_json::Value>)> {
+    let defaults = crate::services::UploadDefaultsService::get(&state.db_pool, user_id).await;
+    let channel_id = payload.channel_id.or(defaults.channel_id).ok_or_else(|| (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"success": false, "message": "channel_id is required (or set a default channel in /api/youtube/defaults)"}))
+    ))?;
+
+    let channel = connected_channel(state, user_id, channel_id).await?;
+
     // Verify video file exists
     if !std::path::Path::new(&payload.video_path).exists() {
         return Err((
@@ -530,21 +574,47 @@ pub async fn upload_video_to_youtube(
         ));
     }
 
+    // Anything left blank comes from the user's defaults and the metadata generated for the output when it rendered
+    let generated = crate::services::OutputVideoService::find_output_by_any_path(&state.db_pool, &payload.video_path)
+        .await
+        .ok()
+        .flatten()
+        .filter(|output| output.user_id == user_id);
+    let fields = crate::services::UploadDefaultsService::fill(
+        payload,
+        &defaults,
+        generated.as_ref(),
+        &channel.channel_name,
+        chrono::Utc::now().date_naive(),
+    );
+    let title = match fields.title.as_str() {
+        // A draft gets its real title when it's finalized
+        "" if draft => format!(
+            "Draft: {}",
+            std::path::Path::new(&payload.video_path).file_stem().and_then(|s| s.to_str()).unwrap_or("video")
+        ),
+        "" => return Err((StatusCode::BAD_REQUEST, Json(json!({"success": false, "message": "title is required"})))),
+        title => title.to_string(),
+    };
+    let description = fields.description;
+    let privacy_status = if draft { "private".to_string() } else { fields.privacy_status };
+
     // Create upload record
     let upload_id: i32 = sqlx::query_scalar(
         "INSERT INTO youtube_uploads (
             user_id, channel_id, local_video_path, video_title, video_description,
-            video_category, privacy_status, upload_status, created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, 'uploading', NOW(), NOW())
+            video_category, privacy_status, is_draft, upload_status, created_at, updated_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'uploading', NOW(), NOW())
         RETURNING id"
     )
     .bind(user_id)
-    .bind(payload.channel_id)
+    .bind(channel_id)
     .bind(&payload.video_path)
-    .bind(&payload.title)
-    .bind(&payload.description)
-    .bind(payload.category.as_deref().unwrap_or("22"))
-    .bind(&payload.privacy_status)
+    .bind(&title)
+    .bind(&description)
+    .bind(&fields.category)
+    .bind(&privacy_status)
+    .bind(draft)
     .fetch_one(&state.db_pool)
     .await
     .map_err(|_| (
@@ -555,16 +625,16 @@ pub async fn upload_video_to_youtube(
     // Upload to YouTube
     let youtube = state.youtube_client.as_ref().unwrap();
 
-    tracing::info!("📤 Uploading video to YouTube: {} ({})", payload.title, channel.channel_name);
+    tracing::info!("📤 Uploading video to YouTube: {} ({})", title, channel.channel_name);
 
     let upload_result = youtube.upload_video(
         &channel.access_token,
         &payload.video_path,
-        &payload.title,
-        payload.description.as_deref().unwrap_or(""),
-        &payload.privacy_status,
-        payload.category.as_deref(),
-        payload.tags,
+        &title,
+        description.as_deref().unwrap_or(""),
+        &privacy_status,
+        Some(fields.category.as_str()),
+        fields.tags,
     )
     .await;
 
@@ -588,22 +658,45 @@ pub async fn upload_video_to_youtube(
             .ok();
 
             tracing::info!("✅ Video uploaded successfully: {}", youtube_url);
+            crate::services::ActivityService::record(
+                &state.db_pool,
+                user_id,
+                "youtube_published",
+                &format!("\"{}\" was uploaded to {}{}", title, channel.channel_name, if draft { " as a draft" } else { "" }),
+                None,
+                json!({ "upload_id": upload_id, "youtube_url": youtube_url, "privacy_status": privacy_status, "is_draft": draft }),
+            )
+            .await;
 
-            Ok(Json(json!({
+            Ok(json!({
                 "success": true,
-                "message": "Video uploaded to YouTube successfully",
+                "message": if draft {
+                    "Draft uploaded privately; finalize it to set its metadata and visibility"
+                } else {
+                    "Video uploaded to YouTube successfully"
+                },
                 "upload": {
                     "id": upload_id,
                     "youtube_video_id": response.id,
                     "youtube_url": youtube_url,
                     "title": response.snippet.title,
-                    "privacy_status": payload.privacy_status,
+                    "privacy_status": privacy_status,
+                    "is_draft": draft,
                     "published_at": response.snippet.published_at
                 }
-            })))
+            }))
         }
         Err(e) => {
             tracing::error!("❌ Failed to upload video: {}", e);
+            crate::services::ActivityService::record(
+                &state.db_pool,
+                user_id,
+                "youtube_upload_failed",
+                &format!("\"{}\" failed to upload to {}", title, channel.channel_name),
+                None,
+                json!({ "upload_id": upload_id, "error": e.to_string() }),
+            )
+            .await;
 
             // Update upload record with error
             sqlx::query(
@@ -646,6 +739,251 @@ pub async fn list_upload_history(
     })))
 }
 
+/// Get the user's upload defaults
+pub async fn get_upload_defaults(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Json<serde_json::Value> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let defaults = crate::services::UploadDefaultsService::get(&state.db_pool, user_id).await;
+
+    Json(json!({
+        "success": true,
+        "is_set": !defaults.is_empty(),
+        "summary": crate::services::UploadDefaultsService::describe(&defaults),
+        "defaults": defaults,
+        "template_variables": crate::services::upload_defaults::TEMPLATE_VARIABLES
+    }))
+}
+
+/// Merge new upload defaults into the user's; `clear` unsets fields
+pub async fn update_upload_defaults(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+    Json(payload): Json<crate::models::upload_defaults::UpdateUploadDefaultsRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let defaults = crate::services::UploadDefaultsService::update(&state.db_pool, user_id, &payload)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"success": false, "message": e}))))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "summary": crate::services::UploadDefaultsService::describe(&defaults),
+        "defaults": defaults
+    })))
+}
+
+// ============================================================================
+// Draft Uploads
+// ============================================================================
+
+/// Upload the video bytes now as a private draft; title, description, thumbnail and visibility
+/// are set later with the finalize endpoint, so a slow upload doesn't hold up a publish
+///
+/// POST /api/youtube/drafts
+pub async fn create_draft_upload(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+    Json(payload): Json<UploadToYouTubeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    upload_to_channel(&state, user_id, &payload, true).await.map(Json)
+}
+
+/// Drafts that haven't been finalized yet
+///
+/// GET /api/youtube/drafts
+pub async fn list_draft_uploads(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+
+    let drafts = sqlx::query_as::<_, YouTubeUpload>(
+        "SELECT * FROM youtube_uploads
+         WHERE user_id = $1 AND is_draft AND finalized_at IS NULL AND deleted_at IS NULL
+         ORDER BY created_at DESC"
+    )
+    .bind(user_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(json!({
+        "success": true,
+        "drafts": drafts
+    })))
+}
+
+/// Set a draft's final metadata and thumbnail, then make it visible (now, or at `publish_at`)
+///
+/// POST /api/youtube/drafts/:upload_id/finalize
+pub async fn finalize_draft_upload(
+    Path(upload_id): Path<i32>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(claims): Extension<crate::models::auth::Claims>,
+    Json(payload): Json<FinalizeDraftRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let user_id = claims.sub.parse::<i32>().unwrap_or(0);
+    let bad_request = |message: &str| (StatusCode::BAD_REQUEST, Json(json!({"success": false, "message": message})));
+
+    let youtube = state.youtube_client.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"success": false, "message": "YouTube client not initialized"})),
+        )
+    })?;
+
+    let upload = sqlx::query_as::<_, YouTubeUpload>(
+        "SELECT * FROM youtube_uploads WHERE id = $1 AND user_id = $2 AND is_draft AND deleted_at IS NULL"
+    )
+    .bind(upload_id)
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|_| (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"success": false, "message": "Database error"}))
+    ))?
+    .ok_or_else(|| (
+        StatusCode::NOT_FOUND,
+        Json(json!({"success": false, "message": "Draft not found"}))
+    ))?;
+
+    if upload.finalized_at.is_some() {
+        return Err((StatusCode::CONFLICT, Json(json!({"success": false, "message": "Draft is already finalized"}))));
+    }
+    let video_id = match (&upload.youtube_video_id, upload.upload_status.as_deref()) {
+        (Some(video_id), Some("completed")) => video_id.clone(),
+        _ => return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"success": false, "message": "Draft hasn't finished uploading"}))
+        )),
+    };
+
+    // Visibility: what the request asks for, else the user's default; a scheduled publish goes public
+    let defaults = crate::services::UploadDefaultsService::get(&state.db_pool, user_id).await;
+    let privacy_status = match (payload.privacy_status.clone().or(defaults.privacy_status), payload.publish_at) {
+        (Some(privacy), Some(_)) if privacy != "public" => {
+            return Err(bad_request("publish_at makes the video public at that time; leave privacy_status out or set it to public"));
+        }
+        (_, Some(_)) => "public".to_string(),
+        (Some(privacy), None) => privacy,
+        (None, None) => return Err(bad_request("privacy_status is required (or set a default in /api/youtube/defaults)")),
+    };
+    if !crate::services::upload_defaults::PRIVACY_STATUSES.contains(&privacy_status.as_str()) {
+        return Err(bad_request("privacy_status must be public, private or unlisted"));
+    }
+    if payload.publish_at.is_some_and(|at| at <= chrono::Utc::now()) {
+        return Err(bad_request("publish_at must be in the future"));
+    }
+    let title = match payload.title.as_deref().map(str::trim) {
+        Some("") => return Err(bad_request("title must not be empty")),
+        Some(title) => title.to_string(),
+        None => upload.video_title.clone(),
+    };
+
+    let channel = connected_channel(&state, user_id, upload.channel_id).await?;
+    if !channel.granted_scopes.contains("youtube.force-ssl") {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "success": false,
+                "message": "Additional permissions required",
+                "requires_reauth": true,
+                "reconnect_url": "/youtube/connect?reauth=true"
+            })),
+        ));
+    }
+
+    if let Some(thumbnail_path) = &payload.thumbnail_path {
+        let content_type = match std::path::Path::new(thumbnail_path).extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("png") => "image/png",
+            _ => return Err(bad_request("thumbnail_path must be a .jpg or .png image")),
+        };
+        let image_data = tokio::fs::read(thumbnail_path).await.map_err(|_| (
+            StatusCode::NOT_FOUND,
+            Json(json!({"success": false, "message": "Thumbnail file not found"}))
+        ))?;
+        youtube
+            .upload_thumbnail(&channel.access_token, &video_id, image_data, content_type)
+            .await
+            .map_err(|e| (
+                StatusCode::BAD_GATEWAY,
+                Json(json!({"success": false, "message": format!("YouTube API error: {}", e)}))
+            ))?;
+    }
+
+    // Scheduled publishing keeps the video private until publishAt
+    let youtube_privacy = if payload.publish_at.is_some() { "private" } else { privacy_status.as_str() };
+    let publish_at = payload.publish_at.map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    let update_response = youtube
+        .update_video(
+            &channel.access_token,
+            &video_id,
+            Some(&title),
+            payload.description.as_deref(),
+            Some(youtube_privacy),
+            payload.category_id.as_deref(),
+            payload.tags.clone(),
+            publish_at.as_deref(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("YouTube API error finalizing draft {}: {}", upload_id, e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(json!({"success": false, "message": format!("YouTube API error: {}", e)})),
+            )
+        })?;
+
+    sqlx::query(
+        "UPDATE youtube_uploads SET
+         video_title = $1,
+         video_description = COALESCE($2, video_description),
+         privacy_status = $3,
+         video_category = COALESCE($4, video_category),
+         custom_thumbnail_path = COALESCE($5, custom_thumbnail_path),
+         scheduled_publish_at = $6,
+         is_scheduled = $7,
+         finalized_at = NOW(),
+         metadata_updated_at = NOW(),
+         updated_at = NOW()
+         WHERE id = $8"
+    )
+    .bind(&title)
+    .bind(payload.description.as_ref())
+    .bind(youtube_privacy)
+    .bind(payload.category_id.as_ref())
+    .bind(payload.thumbnail_path.as_ref())
+    .bind(payload.publish_at)
+    .bind(payload.publish_at.is_some())
+    .bind(upload_id)
+    .execute(&state.db_pool)
+    .await
+    .ok();
+
+    tracing::info!("✅ Draft {} finalized as {} ({})", upload_id, title, privacy_status);
+
+    Ok(Json(json!({
+        "success": true,
+        "message": match payload.publish_at {
+            Some(_) => "Draft finalized and scheduled to publish",
+            None => "Draft finalized",
+        },
+        "upload": {
+            "id": upload_id,
+            "youtube_video_id": video_id,
+            "youtube_url": upload.youtube_url,
+            "title": update_response.snippet.title,
+            "privacy_status": update_response.status.privacy_status,
+            "publish_at": payload.publish_at
+        }
+    })))
+}
+
 // ============================================================================
 // YouTube Management Page
 // ============================================================================
@@ -1025,6 +1363,7 @@ pub async fn update_video_metadata(
         payload.privacy_status.as_deref(),
         payload.category_id.as_deref(),
         payload.tags.clone(),
+        None,
     )
     .await
     .map_err(|e| {
@@ -3207,6 +3546,15 @@ pub async fn upload_chunk(
         .execute(&state.db_pool)
         .await
         .ok();
+        crate::services::ActivityService::record(
+            &state.db_pool,
+            user_id,
+            "youtube_published",
+            &format!("\"{}\" was uploaded to YouTube", upload.video_title),
+            None,
+            json!({ "upload_id": upload_id, "youtube_url": youtube_url }),
+        )
+        .await;
 
         Ok(Json(json!({
             "success": true,
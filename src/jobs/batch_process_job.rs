@@ -0,0 +1,409 @@
+// src/jobs/batch_process_job.rs
+//! Batch operations - one edit (a watermark overlay, a resize to 1080p, a volume change) applied to
+//! every video in a session or folder, as a single job with per-file progress and a zip of the results
+
+use super::{Job, JobControl, JobId, JobManager, JobPriority, JobStatus, Lane, ProgressUpdate};
+use crate::services::RenderEstimateService;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use crate::middleware::request_id::propagate;
+use crate::utils::processes::track_processes;
+use tracing::Instrument;
+
+/// Files processed at once within a batch
+pub const MAX_BATCH_CONCURRENCY: usize = 4;
+
+/// Largest number of files accepted in one batch
+pub const MAX_BATCH_FILES: usize = 200;
+
+/// Folders a batch may read from
+const BATCH_FOLDER_ROOTS: [&str; 2] = ["uploads", "outputs"];
+
+const VIDEO_EXTENSIONS: [&str; 6] = ["mp4", "mov", "mkv", "webm", "avi", "m4v"];
+
+/// The edit applied to every file; its input and output are filled in per file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOperation {
+    pub tool: String,
+    #[serde(default)]
+    pub args: serde_json::Map<String, Value>,
+}
+
+/// Outcome for one file, written to the batch manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchFileResult {
+    pub index: usize,
+    pub input_file: String,
+    pub status: String,
+    pub output_file: Option<String>,
+    pub error: Option<String>,
+    pub duration_seconds: f64,
+}
+
+/// Single-input tools a batch can apply: the project tools, plus compression
+pub fn is_batch_tool(tool: &str) -> bool {
+    crate::services::project::PROJECT_TOOLS.contains(&tool) || tool == "compress_video"
+}
+
+/// Check an operation before any files are queued
+pub fn validate_operation(operation: &BatchOperation) -> Result<(), String> {
+    if !is_batch_tool(&operation.tool) {
+        return Err(format!(
+            "{} can't be run as a batch. Batch tools: {}, compress_video",
+            operation.tool,
+            crate::services::project::PROJECT_TOOLS.join(", ")
+        ));
+    }
+    if let Some(key) = ["input_file", "output_file"].iter().find(|key| operation.args.contains_key(**key)) {
+        return Err(format!("Leave out {}; each file in the batch is its own input and output", key));
+    }
+    Ok(())
+}
+
+fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| VIDEO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Videos uploaded to a session, oldest first
+pub async fn session_videos(pool: &sqlx::PgPool, session_uuid: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT uf.file_path FROM uploaded_files uf
+        JOIN chat_sessions s ON s.id = uf.session_id
+        WHERE s.session_uuid = $1 AND uf.file_type = 'video'
+        ORDER BY uf.created_at, uf.id
+        "#,
+    )
+    .bind(session_uuid)
+    .fetch_all(pool)
+    .await
+}
+
+/// Videos directly inside a folder under uploads/ or outputs/, by name
+pub fn folder_videos(folder: &str) -> Result<Vec<String>, String> {
+    let folder = folder.trim().trim_end_matches('/');
+    let path = Path::new(folder);
+    let inside_roots = BATCH_FOLDER_ROOTS.iter().any(|root| path.starts_with(root));
+    if !inside_roots || path.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err(format!("Folder must be under {}/", BATCH_FOLDER_ROOTS.join("/ or ")));
+    }
+    let entries = std::fs::read_dir(path).map_err(|e| format!("Failed to read {}: {}", folder, e))?;
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_video(path))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// `<stem>_<tool>.<ext>` for each input, numbered where two inputs share a name
+fn output_names(inputs: &[String], tool: &str) -> Vec<String> {
+    let mut taken = HashSet::new();
+    inputs
+        .iter()
+        .map(|input| {
+            let path = Path::new(input);
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+            let mut name = format!("{}_{}.{}", stem, tool, extension);
+            let mut copy = 1;
+            while !taken.insert(name.clone()) {
+                copy += 1;
+                name = format!("{}_{}_{}.{}", stem, tool, copy, extension);
+            }
+            name
+        })
+        .collect()
+}
+
+/// Spawn a batch operation job; returns its id
+pub async fn spawn_batch_process_job(
+    session_id: String,
+    user_id: Option<String>,
+    operation: BatchOperation,
+    mut files: Vec<String>,
+    job_manager: Arc<JobManager>,
+    pool: sqlx::PgPool,
+) -> Result<JobId, String> {
+    validate_operation(&operation)?;
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    if files.is_empty() {
+        return Err("There are no videos to process".to_string());
+    }
+    if files.len() > MAX_BATCH_FILES {
+        return Err(format!("Batch has {} files, the limit is {}", files.len(), MAX_BATCH_FILES));
+    }
+    if let Some(missing) = files.iter().find(|file| !Path::new(file.as_str()).is_file()) {
+        return Err(format!("File not found: {}", missing));
+    }
+
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let batch_dir = format!("outputs/batch_process_{}", &batch_id[..8]);
+    tokio::fs::create_dir_all(&batch_dir)
+        .await
+        .map_err(|e| format!("Failed to create batch directory: {}", e))?;
+
+    let job_data = serde_json::json!({
+        "batch_dir": batch_dir,
+        "file_count": files.len(),
+        "operation": operation,
+        "files": files,
+    });
+    // Bulk work: interactive edits go ahead of it in the queue
+    let mut job = Job::new(session_id.clone(), "batch_process".to_string(), job_data).with_priority(JobPriority::Low);
+    job.id = batch_id;
+    if let Some(uid) = user_id {
+        job = job.with_user_id(uid);
+    }
+    let job_id = job_manager.create_job(job.clone()).await;
+
+    let (control_tx, control_rx) = mpsc::unbounded_channel();
+    job_manager.register_control_channel(job_id.clone(), control_tx).await;
+
+    let job_context = job.clone();
+    let manager = job_manager.clone();
+    manager.spawn_job(&job_context, async move {
+        let batch_id = job.id.clone();
+        match run_batch(job, operation, files, batch_dir, control_rx, job_manager, pool).await {
+            Ok(summary) => tracing::info!("✅ Batch {} finished: {}", batch_id, summary),
+            Err(e) => tracing::error!("❌ Batch {} failed: {}", batch_id, e),
+        }
+    }).await;
+
+    tracing::info!("🚀 Spawned batch process job: {} for session: {}", job_id, session_id);
+    Ok(job_id)
+}
+
+async fn run_batch(
+    job: Job,
+    operation: BatchOperation,
+    files: Vec<String>,
+    batch_dir: String,
+    mut control_rx: mpsc::UnboundedReceiver<JobControl>,
+    job_manager: Arc<JobManager>,
+    pool: sqlx::PgPool,
+) -> Result<String, String> {
+    let started = std::time::Instant::now();
+    let total = files.len();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    // Cancel stops queued files; the job manager kills the renders already in flight
+    let cancel_flag = cancelled.clone();
+    tokio::spawn(async move {
+        while let Some(command) = control_rx.recv().await {
+            if matches!(command, JobControl::Cancel) {
+                cancel_flag.store(true, Ordering::SeqCst);
+                break;
+            }
+        }
+    });
+
+    report(&job_manager, &job, JobStatus::Running {
+        current_step: format!("Applying {} to {} videos", operation.tool, total),
+        progress_percent: 0.0,
+        steps_completed: 0,
+        total_steps: total,
+    }, format!("📦 Batch {} started: {} videos", operation.tool, total), None).await;
+
+    let names = output_names(&files, &operation.tool);
+    let semaphore = Arc::new(Semaphore::new(MAX_BATCH_CONCURRENCY));
+    let operation = Arc::new(operation);
+    let mut tasks = tokio::task::JoinSet::new();
+    let processes = job_manager.job_processes(&job.id);
+
+    for (index, (input, name)) in files.into_iter().zip(names).enumerate() {
+        let semaphore = semaphore.clone();
+        let cancelled = cancelled.clone();
+        let operation = operation.clone();
+        let job_manager = job_manager.clone();
+        let output = format!("{}/{}", batch_dir, name);
+        let pool = pool.clone();
+        tasks.spawn(propagate(track_processes(async move {
+            let _permit = semaphore.acquire_owned().await.ok()?;
+            if cancelled.load(Ordering::SeqCst) {
+                return None;
+            }
+            Some(process_file(index + 1, input, output, &operation, &job_manager, &pool).await)
+        }, processes.clone())).in_current_span());
+    }
+
+    let mut results: Vec<BatchFileResult> = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        let Ok(Some(result)) = joined else { continue };
+        let completed = results.len() + 1;
+        let icon = if result.status == "completed" { "🎬" } else { "⚠️" };
+        let message = format!("{} Batch progress: {}/{} ({} {})", icon, completed, total, result.input_file, result.status);
+        let details = serde_json::json!({
+            "file": result.input_file,
+            "file_status": result.status,
+            "output_file": result.output_file,
+            "error": result.error,
+        });
+        results.push(result);
+        report(&job_manager, &job, JobStatus::Running {
+            current_step: format!("Processed {}/{}", completed, total),
+            progress_percent: completed as f64 / total as f64 * 100.0,
+            steps_completed: completed,
+            total_steps: total,
+        }, message, Some(details)).await;
+    }
+    results.sort_by_key(|r| r.index);
+
+    let succeeded = results.iter().filter(|r| r.status == "completed").count();
+    let failed = results.len() - succeeded;
+    let skipped = total - results.len();
+
+    let outputs: Vec<String> = results.iter().filter_map(|r| r.output_file.clone()).collect();
+    let zip_path = format!("{}/results.zip", batch_dir);
+    let zip_file = if outputs.is_empty() {
+        None
+    } else {
+        let entries: Vec<(std::path::PathBuf, String)> = outputs
+            .iter()
+            .map(|output| (output.into(), Path::new(output).file_name().unwrap_or_default().to_string_lossy().to_string()))
+            .collect();
+        let target = zip_path.clone();
+        match tokio::task::spawn_blocking(move || crate::utils::zip::write_stored_zip(Path::new(&target), &entries)).await {
+            Ok(Ok(_)) => Some(zip_path),
+            Ok(Err(e)) => {
+                tracing::warn!("Batch {}: failed to zip results: {}", job.id, e);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Batch {}: zip task failed: {}", job.id, e);
+                None
+            }
+        }
+    };
+
+    let manifest_path = format!("{}/manifest.json", batch_dir);
+    let manifest = serde_json::json!({
+        "batch_job_id": job.id,
+        "created_at": job.created_at,
+        "finished_at": chrono::Utc::now(),
+        "batch_dir": batch_dir,
+        "operation": *operation,
+        "total_files": total,
+        "succeeded": succeeded,
+        "failed": failed,
+        "skipped": skipped,
+        "zip_file": zip_file,
+        "files": results,
+    });
+    tokio::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest).unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    let summary = format!("{} succeeded, {} failed, {} skipped", succeeded, failed, skipped);
+    let status = if cancelled.load(Ordering::SeqCst) {
+        JobStatus::Cancelled {
+            cancelled_at_step: format!("Processed {}/{} ({})", results.len(), total, summary),
+        }
+    } else {
+        let mut output_files = outputs;
+        output_files.extend(zip_file.clone());
+        output_files.push(manifest_path.clone());
+        JobStatus::Completed {
+            result: summary.clone(),
+            output_files,
+            duration_seconds: started.elapsed().as_secs_f64(),
+        }
+    };
+    let zipped = zip_file.map(|zip| format!(", zip: {}", zip)).unwrap_or_default();
+    report(&job_manager, &job, status, format!("📦 Batch {} finished: {}{}", operation.tool, summary, zipped), None).await;
+
+    Ok(summary)
+}
+
+/// Apply the operation to one file
+async fn process_file(
+    index: usize,
+    input_file: String,
+    output_file: String,
+    operation: &BatchOperation,
+    job_manager: &JobManager,
+    pool: &sqlx::PgPool,
+) -> BatchFileResult {
+    let started = std::time::Instant::now();
+    // Outputs tiered to cold storage come back before they're read
+    if let Err(e) = crate::services::StorageTierService::ensure_hot(pool, &input_file).await {
+        tracing::warn!("Failed to rehydrate {}: {}", input_file, e);
+    }
+
+    let mut args = operation.args.clone();
+    args.insert("input_file".to_string(), Value::String(input_file.clone()));
+    args.insert("output_file".to_string(), Value::String(output_file.clone()));
+    let args = Value::Object(args);
+
+    let estimate = RenderEstimateService::estimate(pool, &operation.tool, &args).await;
+    let lane_slot = job_manager.acquire_lane(Lane::for_estimate(estimate.seconds)).await;
+    let (result, trace) = crate::utils::trace_ffmpeg(crate::agent::tool_executor::execute_tool_claude(&operation.tool, &args), false).await;
+    drop(lane_slot);
+    let duration_seconds = started.elapsed().as_secs_f64();
+    if !trace.is_empty() {
+        RenderEstimateService::record_in_background(pool.clone(), &operation.tool, &args, duration_seconds, !result.starts_with("❌"));
+    }
+
+    let error = if result.starts_with("❌") {
+        Some(result.trim_start_matches("❌").trim().to_string())
+    } else if !Path::new(&output_file).is_file() {
+        Some(format!("{} finished but {} was not created", operation.tool, output_file))
+    } else {
+        None
+    };
+
+    BatchFileResult {
+        index,
+        input_file,
+        status: if error.is_none() { "completed" } else { "failed" }.to_string(),
+        output_file: error.is_none().then_some(output_file),
+        error,
+        duration_seconds,
+    }
+}
+
+/// Update the job and push the change, with the file it concerns, to the session's WebSocket
+async fn report(job_manager: &JobManager, job: &Job, status: JobStatus, message: String, details: Option<Value>) {
+    job_manager.update_job_status(&job.id, status.clone()).await;
+    let mut update = ProgressUpdate::new(job.id.clone(), message, status);
+    if let Some(details) = details {
+        update = update.with_details(details);
+    }
+    job_manager.send_progress(&job.session_id, update).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_and_output_names_are_checked_per_file() {
+        let resize = BatchOperation { tool: "resize_video".to_string(), args: serde_json::Map::new() };
+        assert!(validate_operation(&resize).is_ok());
+        assert!(validate_operation(&BatchOperation { tool: "compress_video".to_string(), args: serde_json::Map::new() }).is_ok());
+        assert!(validate_operation(&BatchOperation { tool: "merge_videos".to_string(), args: serde_json::Map::new() }).is_err());
+
+        let mut with_input = resize.clone();
+        with_input.args.insert("input_file".to_string(), Value::String("uploads/a.mp4".to_string()));
+        assert!(validate_operation(&with_input).unwrap_err().contains("input_file"));
+
+        let inputs = vec!["uploads/a.mp4".to_string(), "outputs/clips/a.mp4".to_string(), "uploads/b.mov".to_string()];
+        assert_eq!(
+            output_names(&inputs, "resize_video"),
+            vec!["a_resize_video.mp4", "a_resize_video_2.mp4", "b_resize_video.mov"]
+        );
+
+        assert!(folder_videos("../etc").is_err());
+        assert!(folder_videos("uploads/../../etc").is_err());
+    }
+}
@@ -0,0 +1,461 @@
+// src/jobs/batch_render_job.rs
+//! Bulk personalization renders - one templated render per CSV row, fanned out as background jobs
+//! Templates are tool-call timelines whose string arguments may contain `{{column}}` placeholders
+
+use super::{Job, JobControl, JobId, JobManager, JobPriority, JobStatus, Lane, ProgressUpdate};
+use crate::services::RenderEstimateService;
+use crate::types::ExportSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use crate::middleware::request_id::propagate;
+use crate::utils::processes::track_processes;
+use tracing::Instrument;
+
+/// Most renders allowed to run at once for a single batch
+pub const MAX_BATCH_CONCURRENCY: usize = 4;
+
+/// Largest number of rows accepted in one batch
+pub const MAX_BATCH_ROWS: usize = 500;
+
+/// One tool call in a render template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateStep {
+    pub tool: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+/// Timeline template rendered once per row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderTemplate {
+    pub steps: Vec<TemplateStep>,
+    /// Final file produced by the steps (may use placeholders)
+    #[serde(default = "default_template_output")]
+    pub output_file: String,
+    /// Named export preset applied to each row's output once its steps finish
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_preset: Option<String>,
+    /// SRT burned in when the preset burns captions (may use placeholders)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captions_file: Option<String>,
+    /// Pin every row's render (bitexact output, seeded filters, stored inputs) so it can be replayed
+    #[serde(default)]
+    pub reproducible: bool,
+}
+
+fn default_template_output() -> String {
+    "{{batch_dir}}/{{row}}.mp4".to_string()
+}
+
+/// Outcome of one personalized render, written to the batch manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRowResult {
+    pub row: usize,
+    pub job_id: JobId,
+    pub variables: HashMap<String, String>,
+    pub status: String,
+    pub output_file: Option<String>,
+    /// Render report (input hashes, FFmpeg commands, encoders, warnings) next to the output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_file: Option<String>,
+    pub error: Option<String>,
+    pub duration_seconds: f64,
+}
+
+/// A validated batch to queue: the template rendered once per CSV row
+pub struct BatchRenderRequest {
+    pub session_id: String,
+    pub user_id: Option<String>,
+    pub template: RenderTemplate,
+    /// The template's export preset, resolved by the caller
+    pub export_settings: Option<ExportSettings>,
+    pub rows: Vec<HashMap<String, String>>,
+    pub concurrency: usize,
+}
+
+/// What every row of a running batch shares
+struct BatchContext {
+    job: Job,
+    template: RenderTemplate,
+    export_settings: Option<ExportSettings>,
+    batch_dir: String,
+    job_manager: Arc<JobManager>,
+    pool: sqlx::PgPool,
+}
+
+/// Check a template before any rows are queued
+pub fn validate_template(template: &RenderTemplate, headers: &[String]) -> Result<(), String> {
+    if template.steps.is_empty() {
+        return Err("Template has no steps".to_string());
+    }
+    if let Some(step) = template.steps.iter().find(|s| s.tool.trim().is_empty()) {
+        return Err(format!("Template step is missing a tool name: {}", step.args));
+    }
+
+    // Every placeholder must be a CSV column or a built-in variable
+    let placeholder = regex::Regex::new(r"\{\{\s*([A-Za-z0-9_]+)\s*\}\}").unwrap();
+    let text = serde_json::to_string(template).unwrap_or_default();
+    let unknown: Vec<String> = placeholder
+        .captures_iter(&text)
+        .map(|c| c[1].to_string())
+        .filter(|name| !headers.contains(name) && name != "row" && name != "batch_dir")
+        .collect();
+
+    if !unknown.is_empty() {
+        return Err(format!("Template uses placeholders with no matching CSV column: {}", unknown.join(", ")));
+    }
+    Ok(())
+}
+
+/// Spawn a batch render job; returns the parent job id
+pub async fn spawn_batch_render_job(
+    request: BatchRenderRequest,
+    job_manager: Arc<JobManager>,
+    pool: sqlx::PgPool,
+) -> Result<JobId, String> {
+    let BatchRenderRequest { session_id, user_id, template, export_settings, rows, concurrency } = request;
+    if rows.is_empty() {
+        return Err("CSV has no data rows".to_string());
+    }
+    if rows.len() > MAX_BATCH_ROWS {
+        return Err(format!("Batch has {} rows, the limit is {}", rows.len(), MAX_BATCH_ROWS));
+    }
+
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let batch_dir = format!("outputs/batch_{}", &batch_id[..8]);
+    tokio::fs::create_dir_all(&batch_dir)
+        .await
+        .map_err(|e| format!("Failed to create batch directory: {}", e))?;
+
+    let job_data = serde_json::json!({
+        "batch_dir": batch_dir,
+        "row_count": rows.len(),
+        "template": template,
+        "export_settings": export_settings,
+    });
+    // Bulk work: interactive edits go ahead of it in the queue
+    let mut job = Job::new(session_id.clone(), "batch_render".to_string(), job_data).with_priority(JobPriority::Low);
+    job.id = batch_id;
+    if let Some(uid) = user_id {
+        job = job.with_user_id(uid);
+    }
+    let job_id = job_manager.create_job(job.clone()).await;
+
+    let (control_tx, control_rx) = mpsc::unbounded_channel();
+    job_manager.register_control_channel(job_id.clone(), control_tx).await;
+
+    let concurrency = concurrency.clamp(1, MAX_BATCH_CONCURRENCY);
+    let job_context = job.clone();
+    let manager = job_manager.clone();
+    manager.spawn_job(&job_context, async move {
+        let batch_id = job.id.clone();
+        let batch = Arc::new(BatchContext { job, template, export_settings, batch_dir, job_manager, pool });
+        match run_batch(batch, rows, concurrency, control_rx).await {
+            Ok(summary) => tracing::info!("✅ Batch render {} finished: {}", batch_id, summary),
+            Err(e) => tracing::error!("❌ Batch render {} failed: {}", batch_id, e),
+        }
+    }).await;
+
+    tracing::info!("🚀 Spawned batch render job: {} for session: {}", job_id, session_id);
+    Ok(job_id)
+}
+
+async fn run_batch(
+    batch: Arc<BatchContext>,
+    rows: Vec<HashMap<String, String>>,
+    concurrency: usize,
+    mut control_rx: mpsc::UnboundedReceiver<JobControl>,
+) -> Result<String, String> {
+    let BatchContext { job, template, batch_dir, job_manager, pool, .. } = batch.as_ref();
+    let started = std::time::Instant::now();
+    let total = rows.len();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    // Cancel stops queued rows; the job manager kills the renders already in flight
+    let cancel_flag = cancelled.clone();
+    tokio::spawn(async move {
+        while let Some(command) = control_rx.recv().await {
+            if matches!(command, JobControl::Cancel) {
+                cancel_flag.store(true, Ordering::SeqCst);
+                break;
+            }
+        }
+    });
+
+    // Longest renders first (by estimates from past runs), so a slow row doesn't start last and hold up the batch
+    let mut queue = Vec::with_capacity(total);
+    for (index, row) in rows.into_iter().enumerate() {
+        let estimate = estimate_row(pool, template, &row, index + 1, batch_dir).await;
+        queue.push((index, row, estimate));
+    }
+    queue.sort_by(|a, b| b.2.total_cmp(&a.2));
+    let estimates: HashMap<usize, f64> = queue.iter().map(|(index, _, estimate)| (index + 1, *estimate)).collect();
+    let eta = batch_makespan(queue.iter().map(|(_, _, estimate)| *estimate), concurrency);
+
+    report(job_manager, job, JobStatus::Running {
+        current_step: format!("Rendering {} personalized videos", total),
+        progress_percent: 0.0,
+        steps_completed: 0,
+        total_steps: total,
+    }, format!("📦 Batch render started: {} rows (ETA {})", total, RenderEstimateService::format_eta(eta)), Some(eta)).await;
+
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = tokio::task::JoinSet::new();
+    let processes = job_manager.job_processes(&job.id);
+
+    for (index, row, _) in queue {
+        let semaphore = semaphore.clone();
+        let cancelled = cancelled.clone();
+        let batch = batch.clone();
+        tasks.spawn(propagate(track_processes(async move {
+            let _permit = semaphore.acquire_owned().await.ok()?;
+            if cancelled.load(Ordering::SeqCst) {
+                return None;
+            }
+            Some(render_row(&batch, index + 1, row).await)
+        }, processes.clone())).in_current_span());
+    }
+
+    let mut results = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        let Ok(Some(result)) = joined else { continue };
+        results.push(result);
+        let completed = results.len();
+        let eta = remaining_eta(&estimates, &results, concurrency);
+        report(job_manager, job, JobStatus::Running {
+            current_step: format!("Rendered {}/{}", completed, total),
+            progress_percent: completed as f64 / total as f64 * 100.0,
+            steps_completed: completed,
+            total_steps: total,
+        }, format!("🎬 Batch progress: {}/{} (ETA {})", completed, total, RenderEstimateService::format_eta(eta)), Some(eta)).await;
+    }
+    results.sort_by_key(|r| r.row);
+
+    let succeeded = results.iter().filter(|r| r.status == "completed").count();
+    let failed = results.len() - succeeded;
+    let skipped = total - results.len();
+
+    let manifest_path = format!("{}/manifest.json", batch_dir);
+    let manifest = serde_json::json!({
+        "batch_job_id": job.id,
+        "created_at": job.created_at,
+        "finished_at": chrono::Utc::now(),
+        "batch_dir": batch_dir,
+        "total_rows": total,
+        "succeeded": succeeded,
+        "failed": failed,
+        "skipped": skipped,
+        "template": template,
+        "rows": results,
+    });
+    tokio::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest).unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    let summary = format!("{} succeeded, {} failed, {} skipped", succeeded, failed, skipped);
+    let status = if cancelled.load(Ordering::SeqCst) {
+        JobStatus::Cancelled {
+            cancelled_at_step: format!("Rendered {}/{} ({})", results.len(), total, summary),
+        }
+    } else {
+        let mut output_files: Vec<String> = results.iter().filter_map(|r| r.output_file.clone()).collect();
+        output_files.push(manifest_path.clone());
+        JobStatus::Completed {
+            result: summary.clone(),
+            output_files,
+            duration_seconds: started.elapsed().as_secs_f64(),
+        }
+    };
+    report(job_manager, job, status, format!("📦 Batch render finished: {} (manifest: {})", summary, manifest_path), None).await;
+
+    Ok(summary)
+}
+
+/// Render one row as its own child job
+async fn render_row(batch: &BatchContext, row: usize, mut variables: HashMap<String, String>) -> BatchRowResult {
+    let BatchContext { job: parent, template, batch_dir, job_manager, pool, .. } = batch;
+    let export_settings = batch.export_settings.as_ref();
+    let started = std::time::Instant::now();
+    let started_at = chrono::Utc::now();
+    let csv_variables = variables.clone();
+    variables.insert("row".to_string(), row.to_string());
+    variables.insert("batch_dir".to_string(), batch_dir.to_string());
+
+    let mut child = Job::new(
+        parent.session_id.clone(),
+        "batch_render_row".to_string(),
+        serde_json::json!({ "batch_job_id": parent.id, "row": row, "variables": csv_variables }),
+    );
+    if let Some(ref uid) = parent.user_id {
+        child = child.with_user_id(uid.clone());
+    }
+    let child_id = job_manager.create_job(child).await;
+
+    let mut output_file = crate::utils::fill_placeholders(&serde_json::json!(template.output_file), &variables)
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let mut error = None;
+    let mut filled_steps = Vec::with_capacity(template.steps.len());
+    let mut ffmpeg = Vec::new();
+    for (i, step) in template.steps.iter().enumerate() {
+        job_manager.update_job_status(&child_id, JobStatus::Running {
+            current_step: step.tool.clone(),
+            progress_percent: i as f64 / template.steps.len() as f64 * 100.0,
+            steps_completed: i,
+            total_steps: template.steps.len(),
+        }).await;
+
+        let args = crate::utils::fill_placeholders(&step.args, &variables);
+        let estimate = RenderEstimateService::estimate(pool, &step.tool, &args).await;
+        let lane_slot = job_manager.acquire_lane(Lane::for_estimate(estimate.seconds)).await;
+        let step_started = std::time::Instant::now();
+        let (result, trace) = crate::utils::trace_ffmpeg(crate::agent::tool_executor::execute_tool_claude(&step.tool, &args), template.reproducible).await;
+        drop(lane_slot);
+        if !trace.is_empty() {
+            RenderEstimateService::record_in_background(pool.clone(), &step.tool, &args, step_started.elapsed().as_secs_f64(), !result.starts_with("❌"));
+        }
+        filled_steps.push(serde_json::json!({ "tool": step.tool, "args": args }));
+        ffmpeg.extend(trace);
+        if result.starts_with("❌") {
+            error = Some(format!("Step {} ({}) failed: {}", i + 1, step.tool, result.trim_start_matches("❌").trim()));
+            break;
+        }
+    }
+
+    if error.is_none() && !std::path::Path::new(&output_file).exists() {
+        error = Some(format!("Template finished but {} was not created", output_file));
+    }
+
+    if let (None, Some(settings)) = (&error, export_settings) {
+        let captions_file = template.captions_file.as_ref().map(|c| {
+            crate::utils::fill_placeholders(&serde_json::json!(c), &variables)
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        });
+        let (exported, trace) = crate::utils::trace_ffmpeg(apply_export_settings(&output_file, settings, captions_file.as_deref()), template.reproducible).await;
+        ffmpeg.extend(trace);
+        match exported {
+            Ok(exported) => output_file = exported,
+            Err(e) => error = Some(format!("Export preset failed: {}", e.trim_start_matches("❌").trim())),
+        }
+    }
+
+    let duration_seconds = started.elapsed().as_secs_f64();
+    let report_file = match &error {
+        None => {
+            let run = crate::services::render_report::RenderRun {
+                tool: "batch_render".to_string(),
+                arguments: serde_json::json!({
+                    "row": row,
+                    "variables": csv_variables,
+                    "steps": filled_steps,
+                    "export_settings": export_settings,
+                }),
+                started_at,
+                duration_seconds,
+                ffmpeg,
+            };
+            let report = crate::services::RenderReportService::build(run, &output_file, None).await;
+            crate::services::RenderReportService::write(&report, &output_file)
+                .await
+                .map_err(|e| tracing::warn!("Row {}: {}", row, e))
+                .ok()
+        }
+        Some(_) => None,
+    };
+
+    let status = match &error {
+        None => JobStatus::Completed {
+            result: format!("Row {} rendered", row),
+            output_files: vec![output_file.clone()],
+            duration_seconds,
+        },
+        Some(e) => JobStatus::Failed {
+            error: e.clone(),
+            failed_at_step: format!("row {}", row),
+        },
+    };
+    job_manager.update_job_status(&child_id, status).await;
+
+    BatchRowResult {
+        row,
+        job_id: child_id,
+        variables: csv_variables,
+        status: if error.is_none() { "completed" } else { "failed" }.to_string(),
+        output_file: error.is_none().then_some(output_file),
+        report_file,
+        error,
+        duration_seconds,
+    }
+}
+
+/// Re-encode a finished row with the batch's export preset, replacing the step output
+async fn apply_export_settings(output_file: &str, settings: &ExportSettings, captions_file: Option<&str>) -> Result<String, String> {
+    let final_path = std::path::Path::new(output_file).with_extension(&settings.container);
+    let temp_path = final_path.with_extension(format!("export.{}", settings.container));
+    let temp = temp_path.to_string_lossy().to_string();
+
+    let captions = captions_file.filter(|_| settings.burn_captions);
+    crate::export::export_with_settings(output_file, &temp, settings, captions)?;
+    tokio::fs::rename(&temp_path, &final_path)
+        .await
+        .map_err(|e| format!("Failed to move exported file: {}", e))?;
+    if final_path != std::path::Path::new(output_file) {
+        let _ = tokio::fs::remove_file(output_file).await;
+    }
+    Ok(final_path.to_string_lossy().to_string())
+}
+
+/// Predicted wall time of one row's steps, with its placeholders filled
+async fn estimate_row(pool: &sqlx::PgPool, template: &RenderTemplate, row: &HashMap<String, String>, index: usize, batch_dir: &str) -> f64 {
+    let mut variables = row.clone();
+    variables.insert("row".to_string(), index.to_string());
+    variables.insert("batch_dir".to_string(), batch_dir.to_string());
+    let steps: Vec<(String, serde_json::Value)> = template
+        .steps
+        .iter()
+        .map(|step| (step.tool.clone(), crate::utils::fill_placeholders(&step.args, &variables)))
+        .collect();
+    RenderEstimateService::estimate_steps(pool, &steps).await
+}
+
+/// Time to finish renders handed out longest-first to `workers` parallel slots
+fn batch_makespan(estimates: impl Iterator<Item = f64>, workers: usize) -> f64 {
+    let mut slots = vec![0.0_f64; workers.max(1)];
+    for estimate in estimates {
+        if let Some(slot) = slots.iter_mut().min_by(|a, b| a.total_cmp(b)) {
+            *slot += estimate;
+        }
+    }
+    slots.into_iter().fold(0.0, f64::max)
+}
+
+/// Estimated time left, with the estimates of unfinished rows scaled by how the finished ones compared
+fn remaining_eta(estimates: &HashMap<usize, f64>, results: &[BatchRowResult], workers: usize) -> f64 {
+    let estimated_done: f64 = results.iter().filter_map(|r| estimates.get(&r.row)).sum();
+    let actual_done: f64 = results.iter().map(|r| r.duration_seconds).sum();
+    let correction = if estimated_done > 0.0 && actual_done > 0.0 { actual_done / estimated_done } else { 1.0 };
+    let mut remaining: Vec<f64> = estimates
+        .iter()
+        .filter(|(row, _)| !results.iter().any(|r| r.row == **row))
+        .map(|(_, estimate)| estimate * correction)
+        .collect();
+    remaining.sort_by(|a, b| b.total_cmp(a));
+    batch_makespan(remaining.into_iter(), workers)
+}
+
+/// Update the parent job and push the change to the session's WebSocket
+async fn report(job_manager: &JobManager, job: &Job, status: JobStatus, message: String, eta_seconds: Option<f64>) {
+    job_manager.update_job_status(&job.id, status.clone()).await;
+    let mut update = ProgressUpdate::new(job.id.clone(), message, status);
+    if let Some(eta) = eta_seconds {
+        update = update.with_details(serde_json::json!({ "eta_seconds": eta.round() }));
+    }
+    job_manager.send_progress(&job.session_id, update).await;
+}
@@ -2,7 +2,8 @@
 
 use crate::clipping::{
     ai_clipper::{AiClipper, ExtractedClipData},
-    models::{ChannelLinkage, ClippingConfig, ClippingJob},
+    cross_post::{self, CrossPostService},
+    models::{ChannelLinkage, ClipMetadataPolicy, ClippingConfig, ClippingJob, SourceChannel},
     uploader::ClipUploader,
     ytdlp_client::YtDlpClient,
 };
@@ -61,6 +62,11 @@ pub async fn execute_clipping_job(
         clips_per_video: linkage.clips_per_video,
         min_clip_duration_seconds: linkage.min_clip_duration_seconds,
         max_clip_duration_seconds: linkage.max_clip_duration_seconds,
+        destination_channel_id: Some(linkage.destination_channel_id),
+        caption_style: Some(fetch_source_channel(linkage.source_channel_id, &app_state.db_pool).await?.caption_style.0),
+        metadata_policy: ClipMetadataPolicy::load(&app_state.db_pool, linkage.destination_channel_id)
+            .await
+            .map_err(|e| format!("Failed to fetch metadata policy: {}", e))?,
     };
 
     let clips = clipper
@@ -93,25 +99,55 @@ pub async fn execute_clipping_job(
         .ok_or("Google OAuth client secret not configured")?;
 
     let uploader = ClipUploader::new(
-        youtube_client.clone(),
+        Arc::new(youtube_client.clone()),
         app_state.db_pool.clone(),
         oauth_client_id.clone(),
         oauth_client_secret.clone(),
     );
 
+    let platforms = CrossPostService::platforms_for(&app_state.db_pool, linkage.destination_channel_id)
+        .await
+        .map_err(|e| format!("Failed to fetch cross-post config: {}", e))?;
+
     let mut uploaded_count = 0;
-    for (clip, clip_id) in clips.iter().zip(clip_db_ids.iter()) {
-        match uploader.upload_clip(clip, *clip_id, &destination_channel).await {
-            Ok(_) => {
-                uploaded_count += 1;
-                let progress = 70 + (uploaded_count * 30 / clips.len() as i32);
-                update_job_status(job_id, "posting", progress, None, &app_state.db_pool).await?;
+    for (index, (clip, clip_id)) in clips.iter().zip(clip_db_ids.iter()).enumerate() {
+        // Render each selected platform's aspect ratio, publishing where a client exists
+        for name in &platforms {
+            let Some(platform) = cross_post::platform(name) else {
+                continue;
+            };
+            let rendered_path = match CrossPostService::render(&app_state.db_pool, *clip_id, &clip.local_clip_path, platform).await {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::error!("Failed to render clip {} for {}: {}", clip.clip_number, name, e);
+                    continue;
+                }
+            };
+            if !platform.can_publish {
+                let reason = format!("No {} publishing client configured; rendered file is ready to post", name);
+                CrossPostService::mark_not_published(&app_state.db_pool, *clip_id, name, "rendered", &reason).await;
+                continue;
             }
-            Err(e) => {
-                tracing::error!("Failed to upload clip {}: {}", clip.clip_number, e);
-                let _ = uploader.mark_upload_failed(*clip_id, &e).await;
+
+            let variant = ExtractedClipData {
+                local_clip_path: rendered_path,
+                ..clip.clone()
+            };
+            match uploader.upload_clip(&variant, *clip_id, &destination_channel, config.metadata_policy.as_ref()).await {
+                Ok(result) => {
+                    uploaded_count += 1;
+                    CrossPostService::mark_published(&app_state.db_pool, *clip_id, name, &result.video_id, &result.url).await;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to upload clip {}: {}", clip.clip_number, e);
+                    let _ = uploader.mark_upload_failed(*clip_id, &e).await;
+                    CrossPostService::mark_not_published(&app_state.db_pool, *clip_id, name, "failed", &e).await;
+                }
             }
         }
+
+        let progress = 70 + ((index as i32 + 1) * 30 / clips.len() as i32);
+        update_job_status(job_id, "posting", progress, None, &app_state.db_pool).await?;
     }
 
     // Step 6: Mark job as completed
@@ -138,6 +174,38 @@ pub async fn execute_clipping_job(
     ))
 }
 
+/// Run the clipping jobs the channel monitor queued, oldest first. Each job is claimed before it
+/// starts so overlapping runners never pick the same one; a job that errors is marked failed.
+pub async fn run_pending_jobs(app_state: Arc<AppState>) -> usize {
+    let mut ran = 0;
+    loop {
+        let claimed = sqlx::query_scalar::<_, i32>(
+            "UPDATE clipping_jobs SET status = 'downloading', started_at = NOW()
+             WHERE id = (
+                 SELECT id FROM clipping_jobs WHERE status = 'pending'
+                 ORDER BY created_at LIMIT 1 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id",
+        )
+        .fetch_optional(&app_state.db_pool)
+        .await;
+        let job_id = match claimed {
+            Ok(Some(job_id)) => job_id,
+            Ok(None) => return ran,
+            Err(e) => {
+                tracing::error!("Failed to claim a clipping job: {}", e);
+                return ran;
+            }
+        };
+
+        ran += 1;
+        if let Err(e) = execute_clipping_job(job_id, app_state.clone()).await {
+            tracing::error!("❌ Clipping job {} failed: {}", job_id, e);
+            let _ = update_job_status(job_id, "failed", 0, Some(&e), &app_state.db_pool).await;
+        }
+    }
+}
+
 // Helper functions
 
 async fn fetch_job_details(job_id: i32, pool: &PgPool) -> Result<ClippingJob, String> {
@@ -156,6 +224,14 @@ async fn fetch_linkage(linkage_id: i32, pool: &PgPool) -> Result<ChannelLinkage,
         .map_err(|e| format!("Failed to fetch linkage: {}", e))
 }
 
+async fn fetch_source_channel(channel_id: i32, pool: &PgPool) -> Result<SourceChannel, String> {
+    sqlx::query_as::<_, SourceChannel>("SELECT * FROM youtube_source_channels WHERE id = $1")
+        .bind(channel_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch source channel: {}", e))
+}
+
 async fn fetch_destination_channel(
     channel_id: i32,
     pool: &PgPool,
@@ -231,8 +307,8 @@ async fn save_clips_to_database(
             "INSERT INTO extracted_clips
              (clipping_job_id, clip_number, local_clip_path,
               start_time_seconds, end_time_seconds, duration_seconds,
-              ai_title, ai_description, ai_tags, ai_confidence_score, viral_factors)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+              ai_title, ai_description, ai_tags, ai_confidence_score, viral_factors, audio_fingerprint)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
              RETURNING id",
         )
         .bind(job_id)
@@ -246,6 +322,7 @@ async fn save_clips_to_database(
         .bind(&clip.ai_tags)
         .bind(clip.ai_confidence_score)
         .bind(&clip.viral_factors)
+        .bind(&clip.audio_fingerprint)
         .fetch_one(pool)
         .await
         .map_err(|e| format!("Failed to save clip: {}", e))?;
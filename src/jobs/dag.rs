@@ -0,0 +1,227 @@
+// src/jobs/dag.rs
+//! Job dependency graphs: a set of jobs submitted together where a job starts only once every job
+//! it `depends_on` has completed. If a dependency fails or is cancelled, its dependents fail too
+//! (and theirs, down the graph) without running.
+
+use super::{Job, JobId, JobStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::oneshot;
+
+/// One job of a submitted graph; `depends_on` names other nodes by key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub key: String,
+    pub task: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Indices of `nodes` in an order where every node comes after the nodes it depends on.
+/// Fails on duplicate keys, unknown dependencies and cycles.
+pub fn topological_order(nodes: &[GraphNode]) -> Result<Vec<usize>, String> {
+    let mut index = HashMap::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if index.insert(node.key.as_str(), i).is_some() {
+            return Err(format!("Duplicate job key '{}'", node.key));
+        }
+    }
+
+    let mut unmet = vec![0usize; nodes.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for (i, node) in nodes.iter().enumerate() {
+        for dependency in node.depends_on.iter().collect::<HashSet<_>>() {
+            let Some(&d) = index.get(dependency.as_str()) else {
+                return Err(format!("Job '{}' depends on unknown job '{}'", node.key, dependency));
+            };
+            unmet[i] += 1;
+            dependents[d].push(i);
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..nodes.len()).filter(|&i| unmet[i] == 0).collect();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(i) = ready.pop_front() {
+        order.push(i);
+        for &dependent in &dependents[i] {
+            unmet[dependent] -= 1;
+            if unmet[dependent] == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    }
+
+    if order.len() < nodes.len() {
+        let cycle: Vec<&str> = (0..nodes.len()).filter(|&i| unmet[i] > 0).map(|i| nodes[i].key.as_str()).collect();
+        return Err(format!("Dependency cycle between jobs: {}", cycle.join(", ")));
+    }
+    Ok(order)
+}
+
+struct Blocked {
+    remaining: HashSet<JobId>,
+    /// Ok once every dependency completed; Err with the reason when one didn't
+    ready: oneshot::Sender<Result<(), String>>,
+}
+
+/// Jobs waiting on dependencies, released as those finish
+#[derive(Default)]
+pub struct DependencyTracker {
+    blocked: HashMap<JobId, Blocked>,
+}
+
+impl DependencyTracker {
+    /// Hold a job until the `remaining` dependencies finish
+    pub fn block(&mut self, job_id: JobId, remaining: HashSet<JobId>, ready: oneshot::Sender<Result<(), String>>) {
+        self.blocked.insert(job_id, Blocked { remaining, ready });
+    }
+
+    /// Drop a blocked job without releasing it; false if it isn't blocked
+    pub fn remove(&mut self, job_id: &str) -> bool {
+        self.blocked.remove(job_id).is_some()
+    }
+
+    /// A job reached a final state: release dependents that were waiting only on it, and fail the
+    /// dependents of a job that didn't complete
+    pub fn finished(&mut self, job_id: &str, completed: bool) {
+        let waiting: Vec<JobId> = self
+            .blocked
+            .iter()
+            .filter(|(_, blocked)| blocked.remaining.contains(job_id))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for dependent in waiting {
+            let blocked = self.blocked.get_mut(&dependent).expect("collected from the map");
+            blocked.remaining.remove(job_id);
+            if completed && !blocked.remaining.is_empty() {
+                continue;
+            }
+            let blocked = self.blocked.remove(&dependent).expect("collected from the map");
+            let outcome = if completed { Ok(()) } else { Err(format!("Dependency {} did not complete", job_id)) };
+            // A dropped receiver means the job was abandoned; nothing is waiting for it
+            let _ = blocked.ready.send(outcome);
+        }
+    }
+}
+
+/// Combined progress of the jobs in a graph
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GraphProgress {
+    pub graph_id: String,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub running: usize,
+    /// Queued for a worker, blocked on dependencies or scheduled for later
+    pub waiting: usize,
+    pub progress_percent: f64,
+    /// Every job reached a final state
+    pub finished: bool,
+}
+
+pub fn graph_progress(graph_id: &str, jobs: &[&Job]) -> GraphProgress {
+    let mut progress = GraphProgress {
+        graph_id: graph_id.to_string(),
+        total: jobs.len(),
+        completed: 0,
+        failed: 0,
+        cancelled: 0,
+        running: 0,
+        waiting: 0,
+        progress_percent: 0.0,
+        finished: false,
+    };
+
+    // Finished jobs count in full, whatever the outcome, so the total reaches 100%
+    let mut done_percent = 0.0;
+    for job in jobs {
+        match &job.status {
+            JobStatus::Completed { .. } => progress.completed += 1,
+            JobStatus::Failed { .. } => progress.failed += 1,
+            JobStatus::Cancelled { .. } => progress.cancelled += 1,
+            JobStatus::Running { progress_percent, .. } => {
+                progress.running += 1;
+                done_percent += progress_percent.clamp(0.0, 100.0);
+                continue;
+            }
+            JobStatus::Paused { progress_percent, .. } => {
+                progress.running += 1;
+                done_percent += progress_percent.clamp(0.0, 100.0);
+                continue;
+            }
+            JobStatus::Queued { .. } | JobStatus::Blocked { .. } | JobStatus::Scheduled { .. } => {
+                progress.waiting += 1;
+                continue;
+            }
+        }
+        done_percent += 100.0;
+    }
+
+    if !jobs.is_empty() {
+        progress.progress_percent = (done_percent / jobs.len() as f64 * 10.0).round() / 10.0;
+    }
+    progress.finished = progress.completed + progress.failed + progress.cancelled == progress.total;
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(key: &str, depends_on: &[&str]) -> GraphNode {
+        GraphNode {
+            key: key.to_string(),
+            task: format!("{} the video", key),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn orders_jobs_after_their_dependencies() {
+        let nodes = [node("voiceover", &["trim"]), node("export", &["voiceover", "captions"]), node("trim", &[]), node("captions", &["trim"])];
+        let order: Vec<&str> = topological_order(&nodes).unwrap().into_iter().map(|i| nodes[i].key.as_str()).collect();
+        assert_eq!(order, ["trim", "voiceover", "captions", "export"]);
+
+        assert!(topological_order(&[node("a", &["b"]), node("b", &["a"]), node("c", &[])]).unwrap_err().contains("a, b"));
+        assert!(topological_order(&[node("a", &["missing"])]).is_err());
+        assert!(topological_order(&[node("a", &[]), node("a", &[])]).is_err());
+    }
+
+    #[test]
+    fn dependents_start_when_all_dependencies_complete_and_fail_otherwise() {
+        let mut tracker = DependencyTracker::default();
+        let (export_tx, mut export_rx) = oneshot::channel();
+        tracker.block("export".to_string(), ["trim".to_string(), "captions".to_string()].into(), export_tx);
+        let (upload_tx, mut upload_rx) = oneshot::channel();
+        tracker.block("upload".to_string(), ["trim".to_string()].into(), upload_tx);
+
+        tracker.finished("trim", true);
+        assert_eq!(upload_rx.try_recv(), Ok(Ok(())));
+        assert!(export_rx.try_recv().is_err());
+
+        tracker.finished("captions", false);
+        assert!(matches!(export_rx.try_recv(), Ok(Err(reason)) if reason.contains("captions")));
+        assert!(!tracker.remove("export"));
+    }
+
+    #[test]
+    fn aggregate_progress_counts_finished_jobs_in_full() {
+        let job = |status: JobStatus| {
+            let mut job = Job::new("session".to_string(), "video_editing".to_string(), serde_json::json!({}));
+            job.status = status;
+            job
+        };
+        let jobs = [
+            job(JobStatus::Completed { result: String::new(), output_files: vec![], duration_seconds: 1.0 }),
+            job(JobStatus::Running { current_step: "render".to_string(), progress_percent: 50.0, steps_completed: 1, total_steps: 2 }),
+            job(JobStatus::Blocked { waiting_on: vec!["x".to_string()] }),
+            job(JobStatus::Failed { error: "boom".to_string(), failed_at_step: "render".to_string() }),
+        ];
+        let progress = graph_progress("g", &jobs.iter().collect::<Vec<_>>());
+        assert_eq!((progress.completed, progress.running, progress.waiting, progress.failed), (1, 1, 1, 1));
+        assert_eq!(progress.progress_percent, 62.5);
+        assert!(!progress.finished);
+    }
+}
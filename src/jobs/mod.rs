@@ -2,18 +2,78 @@
 //! Background job system for video editing tasks
 //! Enables non-blocking video processing with real-time progress updates via WebSocket
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit, RwLock, Semaphore};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
+use tracing::Instrument;
 
 pub mod video_job;
+pub mod batch_render_job;
+pub mod batch_process_job;
+pub mod worker_pool;
+pub mod dag;
+pub mod scheduled_job;
+pub mod clipping_job;
+
+use crate::utils::processes::ProcessSet;
+use dag::{DependencyTracker, GraphProgress};
+use worker_pool::{Dispatch, WorkerPool};
 
 /// Unique identifier for a background job
 pub type JobId = String;
 
+/// Operations estimated to finish faster than this run in the express lane
+pub const EXPRESS_LANE_THRESHOLD_SECONDS: f64 = 10.0;
+
+/// Operations the express lane runs at once, independent of long renders
+pub const EXPRESS_LANE_SLOTS: usize = 4;
+
+/// Executor an operation runs on: quick ones (thumbnails, frame grabs, remuxes) never wait behind long renders
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Lane {
+    Express,
+    Standard,
+}
+
+impl Lane {
+    pub fn for_estimate(estimated_seconds: f64) -> Self {
+        if estimated_seconds < EXPRESS_LANE_THRESHOLD_SECONDS {
+            Lane::Express
+        } else {
+            Lane::Standard
+        }
+    }
+}
+
+/// Order in which waiting jobs get a worker: interactive edits from chat go ahead of bulk work
+/// (batch renders, stream clipping); jobs of the same priority start in the order they were queued
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Renders the standard lane runs at once: `limits.render_concurrency`, or half the CPU cores
+fn standard_lane_slots() -> usize {
+    crate::config::get()
+        .limits
+        .render_concurrency
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get() / 2).unwrap_or(1).max(1))
+}
+
+/// How long a job may run (`limits.job_timeout_minutes`, 0 = no limit)
+fn job_timeout() -> Option<std::time::Duration> {
+    let minutes = crate::config::get().limits.job_timeout_minutes;
+    (minutes > 0).then(|| std::time::Duration::from_secs(minutes * 60))
+}
+
 /// Job status representing the current state
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "status", rename_all = "lowercase")]
@@ -22,6 +82,14 @@ pub enum JobStatus {
     Queued {
         position: usize,
     },
+    /// Job is waiting for the jobs it depends on to complete
+    Blocked {
+        waiting_on: Vec<JobId>,
+    },
+    /// Job is waiting for its scheduled start time
+    Scheduled {
+        run_at: DateTime<Utc>,
+    },
     /// Job is currently running
     Running {
         current_step: String,
@@ -51,6 +119,13 @@ pub enum JobStatus {
     },
 }
 
+impl JobStatus {
+    /// Completed, failed or cancelled: the job won't change again
+    pub fn is_final(&self) -> bool {
+        matches!(self, JobStatus::Completed { .. } | JobStatus::Failed { .. } | JobStatus::Cancelled { .. })
+    }
+}
+
 /// Progress update message sent to WebSocket
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgressUpdate {
@@ -59,6 +134,8 @@ pub struct ProgressUpdate {
     pub message: String,
     pub status: JobStatus,
     pub details: Option<serde_json::Value>,
+    /// Request the job was started by, for tracing a complaint back through the logs
+    pub request_id: Option<String>,
 }
 
 impl ProgressUpdate {
@@ -69,6 +146,7 @@ impl ProgressUpdate {
             message,
             status,
             details: None,
+            request_id: crate::middleware::request_id::current(),
         }
     }
 
@@ -90,6 +168,15 @@ pub struct Job {
     pub completed_at: Option<DateTime<Utc>>,
     pub status: JobStatus,
     pub input_data: serde_json::Value,
+    /// Request (or WebSocket turn) that started the job
+    pub request_id: Option<String>,
+    /// Log lines captured while the job ran, kept only if it failed
+    pub failure_log: Option<Vec<String>>,
+    /// Jobs that must complete before this one starts
+    pub depends_on: Vec<JobId>,
+    /// Graph the job was submitted in, for aggregate progress
+    pub graph_id: Option<String>,
+    pub priority: JobPriority,
 }
 
 impl Job {
@@ -104,6 +191,11 @@ impl Job {
             completed_at: None,
             status: JobStatus::Queued { position: 0 },
             input_data,
+            request_id: crate::middleware::request_id::current(),
+            failure_log: None,
+            depends_on: Vec::new(),
+            graph_id: None,
+            priority: JobPriority::Normal,
         }
     }
 
@@ -111,6 +203,35 @@ impl Job {
         self.user_id = Some(user_id);
         self
     }
+
+    pub fn with_priority(mut self, priority: JobPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_dependencies(mut self, graph_id: String, depends_on: Vec<JobId>) -> Self {
+        self.graph_id = Some(graph_id);
+        self.depends_on = depends_on;
+        self
+    }
+
+    /// Span to run the job in; everything logged inside it is captured for the job
+    pub fn span(&self) -> tracing::Span {
+        let request_id = self.request_id.as_deref().unwrap_or("-");
+        tracing::info_span!("job", job_id = %self.id, job_type = %self.job_type, request_id = %request_id)
+    }
+
+    /// Run `future` as this job: inside its span and with its request id current, across tokio::spawn
+    pub fn scoped<F: std::future::Future>(&self, future: F) -> impl std::future::Future<Output = F::Output> {
+        let span = self.span();
+        let request_id = self.request_id.clone();
+        async move {
+            match request_id {
+                Some(id) => crate::middleware::request_id::with_id(id, future).instrument(span).await,
+                None => future.instrument(span).await,
+            }
+        }
+    }
 }
 
 /// Control commands for managing jobs
@@ -128,19 +249,254 @@ pub struct JobManager {
     jobs: Arc<RwLock<HashMap<JobId, Job>>>,
     /// Progress senders indexed by session_id (for WebSocket delivery)
     progress_senders: Arc<RwLock<HashMap<String, mpsc::UnboundedSender<ProgressUpdate>>>>,
+    /// Per-job subscribers (Server-Sent Events) indexed by job_id
+    job_subscribers: Arc<RwLock<HashMap<JobId, Vec<mpsc::UnboundedSender<ProgressUpdate>>>>>,
     /// Control channels for each job
     control_channels: Arc<RwLock<HashMap<JobId, mpsc::UnboundedSender<JobControl>>>>,
+    /// FFmpeg processes of running jobs, killed when the job is cancelled
+    processes: Arc<std::sync::Mutex<HashMap<JobId, ProcessSet>>>,
+    /// Slots for long renders
+    standard_lane: Arc<Semaphore>,
+    standard_slots: usize,
+    /// Slots for operations under `EXPRESS_LANE_THRESHOLD_SECONDS`
+    express_lane: Arc<Semaphore>,
+    /// Jobs running and waiting to run (`limits.max_concurrent_jobs`, `limits.max_jobs_per_user`)
+    workers: Arc<std::sync::Mutex<WorkerPool>>,
+    /// Jobs waiting on the jobs they depend on
+    dependencies: Arc<std::sync::Mutex<DependencyTracker>>,
+    /// Set once the database is up; jobs that complete or fail then call their owner's webhooks
+    webhook_pool: std::sync::OnceLock<sqlx::PgPool>,
+}
+
+/// Holds a job's worker slot while it runs; dropping it (even on panic) lets the next job start
+struct WorkerSlot {
+    manager: Arc<JobManager>,
+    user: String,
+}
+
+impl Drop for WorkerSlot {
+    fn drop(&mut self) {
+        let dispatch = self.manager.workers.lock().unwrap_or_else(|e| e.into_inner()).finish(&self.user);
+        if !dispatch.is_empty() {
+            let manager = self.manager.clone();
+            tokio::spawn(async move { manager.announce(dispatch).await });
+        }
+    }
 }
 
 impl JobManager {
     pub fn new() -> Self {
+        let standard_slots = standard_lane_slots();
+        let limits = &crate::config::get().limits;
         Self {
             jobs: Arc::new(RwLock::new(HashMap::new())),
             progress_senders: Arc::new(RwLock::new(HashMap::new())),
+            job_subscribers: Arc::new(RwLock::new(HashMap::new())),
             control_channels: Arc::new(RwLock::new(HashMap::new())),
+            processes: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            standard_lane: Arc::new(Semaphore::new(standard_slots)),
+            standard_slots,
+            express_lane: Arc::new(Semaphore::new(EXPRESS_LANE_SLOTS)),
+            workers: Arc::new(std::sync::Mutex::new(WorkerPool::new(limits.max_concurrent_jobs, limits.max_jobs_per_user))),
+            dependencies: Arc::new(std::sync::Mutex::new(DependencyTracker::default())),
+            webhook_pool: std::sync::OnceLock::new(),
         }
     }
 
+    /// Run a job's work on the worker pool. A job with `depends_on` first waits as
+    /// `Blocked { waiting_on }` until those jobs complete, and fails without running if one of
+    /// them doesn't. It then waits as `Queued { position }`, with the position updated as the
+    /// queue moves, until a slot is free and its user is under the cap. Jobs without a user are
+    /// capped per session.
+    pub async fn spawn_job<F>(self: &Arc<Self>, job: &Job, work: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        if job.depends_on.is_empty() {
+            return self.enqueue(job, work).await;
+        }
+
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let blocked = {
+            // Final statuses are written under the jobs lock, so no dependency can finish unseen here
+            let mut jobs = self.jobs.write().await;
+            let mut remaining = HashSet::new();
+            let mut failed = None;
+            for dependency in &job.depends_on {
+                match jobs.get(dependency).map(|dependency| &dependency.status) {
+                    Some(JobStatus::Completed { .. }) => {}
+                    Some(JobStatus::Failed { .. } | JobStatus::Cancelled { .. }) | None => {
+                        failed = Some(format!("Dependency {} did not complete", dependency));
+                        break;
+                    }
+                    Some(_) => {
+                        remaining.insert(dependency.clone());
+                    }
+                }
+            }
+            match failed {
+                Some(reason) => Err(reason),
+                None if remaining.is_empty() => Ok(None),
+                None => {
+                    let status = JobStatus::Blocked { waiting_on: remaining.iter().cloned().collect() };
+                    if let Some(stored) = jobs.get_mut(&job.id) {
+                        stored.status = status.clone();
+                    }
+                    self.dependencies.lock().unwrap_or_else(|e| e.into_inner()).block(job.id.clone(), remaining, ready_tx);
+                    Ok(Some(status))
+                }
+            }
+        };
+
+        match blocked {
+            Ok(None) => self.enqueue(job, work).await,
+            Ok(Some(status)) => {
+                let message = format!("⏸️ Waiting for {} job(s) this one depends on", job.depends_on.len());
+                self.send_progress(&job.session_id, ProgressUpdate::new(job.id.clone(), message, status)).await;
+
+                let manager = self.clone();
+                let job = job.clone();
+                tokio::spawn(async move {
+                    match ready_rx.await {
+                        Ok(Ok(())) => manager.enqueue(&job, work).await,
+                        Ok(Err(reason)) => manager.fail_blocked(&job, reason).await,
+                        // Cancelled while blocked; `cancel_queued` already reported it
+                        Err(_) => tracing::info!("🚫 Job {} was cancelled while waiting on dependencies", job.id),
+                    }
+                });
+            }
+            Err(reason) => self.fail_blocked(job, reason).await,
+        }
+    }
+
+    /// A dependency didn't complete: fail the job without running it (which fails its own dependents)
+    async fn fail_blocked(&self, job: &Job, reason: String) {
+        tracing::warn!("⛔ Job {} won't run: {}", job.id, reason);
+        let status = JobStatus::Failed { error: reason.clone(), failed_at_step: "waiting for dependencies".to_string() };
+        self.update_job_status(&job.id, status.clone()).await;
+        self.send_progress(&job.session_id, ProgressUpdate::new(job.id.clone(), format!("⛔ Skipped: {}", reason), status)).await;
+    }
+
+    async fn enqueue<F>(self: &Arc<Self>, job: &Job, work: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        // A job released by its dependencies joins the worker queue like any other
+        if let Some(stored) = self.jobs.write().await.get_mut(&job.id) {
+            if matches!(stored.status, JobStatus::Blocked { .. }) {
+                stored.status = JobStatus::Queued { position: 0 };
+            }
+        }
+
+        let user = job.user_id.clone().unwrap_or_else(|| format!("session:{}", job.session_id));
+        let (start_tx, start_rx) = oneshot::channel();
+        let dispatch = self.workers.lock().unwrap_or_else(|e| e.into_inner()).enqueue(job.id.clone(), user.clone(), job.priority, start_tx);
+        self.announce(dispatch).await;
+
+        let manager = self.clone();
+        let job_id = job.id.clone();
+        tokio::spawn(job.scoped(async move {
+            if start_rx.await.is_err() {
+                tracing::info!("🚫 Job {} left the queue before starting", job_id);
+                return;
+            }
+            let _slot = WorkerSlot { manager: manager.clone(), user };
+            let processes = manager.job_processes(&job_id);
+            let work = crate::utils::processes::track_processes(work, processes);
+            match job_timeout() {
+                Some(limit) => {
+                    if tokio::time::timeout(limit, work).await.is_err() {
+                        manager.time_out(&job_id, limit).await;
+                    }
+                }
+                None => work.await,
+            }
+            manager.processes.lock().unwrap_or_else(|e| e.into_inner()).remove(&job_id);
+        }));
+    }
+
+    /// Cancel a job that is still blocked on dependencies or waiting for a worker; false if it's neither
+    pub async fn cancel_queued(&self, job_id: &str) -> bool {
+        let unblocked = self.dependencies.lock().unwrap_or_else(|e| e.into_inner()).remove(job_id);
+        let dispatch = if unblocked {
+            Dispatch::default()
+        } else {
+            match self.workers.lock().unwrap_or_else(|e| e.into_inner()).cancel(job_id) {
+                Some(dispatch) => dispatch,
+                None => return false,
+            }
+        };
+        let status = JobStatus::Cancelled { cancelled_at_step: "queued".to_string() };
+        self.update_job_status(job_id, status.clone()).await;
+        if let Some(job) = self.get_job(job_id).await {
+            self.send_progress(&job.session_id, ProgressUpdate::new(job_id.to_string(), "🛑 Job cancelled before it started".to_string(), status)).await;
+        }
+        self.announce(dispatch).await;
+        true
+    }
+
+    /// Change a job's priority (admin override). A waiting job moves to its new place in the queue;
+    /// false if the job isn't known or has already finished.
+    pub async fn set_priority(&self, job_id: &str, priority: JobPriority) -> bool {
+        match self.jobs.write().await.get_mut(job_id) {
+            Some(job) if !job.status.is_final() => job.priority = priority,
+            _ => return false,
+        }
+        let dispatch = self.workers.lock().unwrap_or_else(|e| e.into_inner()).reprioritize(job_id, priority);
+        if let Some(dispatch) = dispatch {
+            self.announce(dispatch).await;
+        }
+        true
+    }
+
+    /// Tell waiting jobs their new queue position
+    async fn announce(&self, dispatch: Dispatch) {
+        for job_id in &dispatch.started {
+            tracing::info!("▶️ Job {} got a worker slot", job_id);
+        }
+        for (job_id, position) in dispatch.moved {
+            // The job may have started since the dispatch; its own status wins then
+            let session_id = {
+                let mut jobs = self.jobs.write().await;
+                match jobs.get_mut(&job_id) {
+                    Some(job) if matches!(job.status, JobStatus::Queued { .. }) => {
+                        job.status = JobStatus::Queued { position };
+                        job.session_id.clone()
+                    }
+                    _ => continue,
+                }
+            };
+            let message = format!("⏳ Waiting for a free worker (position {} in queue)", position);
+            self.send_progress(&session_id, ProgressUpdate::new(job_id, message, JobStatus::Queued { position })).await;
+        }
+    }
+
+    /// Wait for a slot in a lane; the operation runs while the permit is held
+    pub async fn acquire_lane(&self, lane: Lane) -> OwnedSemaphorePermit {
+        let semaphore = match lane {
+            Lane::Express => self.express_lane.clone(),
+            Lane::Standard => self.standard_lane.clone(),
+        };
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return permit;
+        }
+        tracing::info!("⏳ Waiting for a {:?} lane slot", lane);
+        semaphore.acquire_owned().await.expect("job lane semaphores are never closed")
+    }
+
+    /// Busy and total slots per lane, and running/queued jobs in the worker pool
+    pub fn lane_usage(&self) -> serde_json::Value {
+        let usage = |semaphore: &Semaphore, slots: usize| {
+            serde_json::json!({ "busy": slots - semaphore.available_permits(), "slots": slots })
+        };
+        serde_json::json!({
+            "express": usage(&self.express_lane, EXPRESS_LANE_SLOTS),
+            "standard": usage(&self.standard_lane, self.standard_slots),
+            "express_threshold_seconds": EXPRESS_LANE_THRESHOLD_SECONDS,
+            "workers": self.workers.lock().unwrap_or_else(|e| e.into_inner()).usage(),
+        })
+    }
+
     /// Register a WebSocket sender for a session to receive progress updates
     pub async fn register_progress_sender(
         &self,
@@ -160,8 +516,37 @@ impl JobManager {
         tracing::info!("📡 Unregistered progress sender for session: {}", session_id);
     }
 
-    /// Send progress update to session's WebSocket
-    pub async fn send_progress(&self, session_id: &str, update: ProgressUpdate) {
+    /// Receive every progress update of one job (or graph), whatever session it is sent to
+    pub async fn subscribe_job(&self, job_id: &str) -> mpsc::UnboundedReceiver<ProgressUpdate> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.job_subscribers.write().await.entry(job_id.to_string()).or_default().push(sender);
+        receiver
+    }
+
+    /// How many subscribers are waiting on a job's updates
+    #[cfg(test)]
+    pub async fn subscriber_count(&self, job_id: &str) -> usize {
+        self.job_subscribers.read().await.get(job_id).map_or(0, Vec::len)
+    }
+
+    /// Send progress update to session's WebSocket and the job's subscribers
+    pub async fn send_progress(&self, session_id: &str, mut update: ProgressUpdate) {
+        // Updates sent from tasks outside the job's request scope still carry its request id
+        if update.request_id.is_none() {
+            update.request_id = self.jobs.read().await.get(&update.job_id).and_then(|job| job.request_id.clone());
+        }
+
+        {
+            let mut subscribers = self.job_subscribers.write().await;
+            if let Some(job_subscribers) = subscribers.get_mut(&update.job_id) {
+                // Subscribers that disconnected are dropped here, and all of them once the job is over
+                job_subscribers.retain(|subscriber| subscriber.send(update.clone()).is_ok());
+                if job_subscribers.is_empty() || update.status.is_final() {
+                    subscribers.remove(&update.job_id);
+                }
+            }
+        }
+
         let senders = self.progress_senders.read().await;
         if let Some(sender) = senders.get(session_id) {
             if let Err(e) = sender.send(update.clone()) {
@@ -174,6 +559,11 @@ impl JobManager {
         }
     }
 
+    /// Call users' webhooks, leave them a notification and log the activity when their jobs complete or fail
+    pub fn enable_webhooks(&self, pool: sqlx::PgPool) {
+        let _ = self.webhook_pool.set(pool);
+    }
+
     /// Create and store a new job
     pub async fn create_job(&self, job: Job) -> JobId {
         let job_id = job.id.clone();
@@ -195,10 +585,17 @@ impl JobManager {
         jobs.get(job_id).cloned()
     }
 
-    /// Update job status
+    /// Update job status. A final status releases (or fails) the jobs depending on this one.
     pub async fn update_job_status(&self, job_id: &str, status: JobStatus) {
+        let finished = matches!(status, JobStatus::Completed { .. } | JobStatus::Failed { .. } | JobStatus::Cancelled { .. });
         let mut jobs = self.jobs.write().await;
+        if finished {
+            let completed = matches!(status, JobStatus::Completed { .. });
+            self.dependencies.lock().unwrap_or_else(|e| e.into_inner()).finished(job_id, completed);
+        }
+        let mut notify = None;
         if let Some(job) = jobs.get_mut(job_id) {
+            let was_finished = matches!(job.status, JobStatus::Completed { .. } | JobStatus::Failed { .. } | JobStatus::Cancelled { .. });
             job.status = status.clone();
 
             // Update timestamps
@@ -212,8 +609,67 @@ impl JobManager {
                 _ => {}
             }
 
+            // Keep the captured log for support only when the job failed
+            match &status {
+                JobStatus::Failed { .. } => {
+                    let log = crate::log_files::take_job_log(job_id);
+                    crate::log_files::persist_job_log(job_id, &log);
+                    job.failure_log = Some(log);
+                }
+                JobStatus::Completed { .. } | JobStatus::Cancelled { .. } => {
+                    crate::log_files::take_job_log(job_id);
+                }
+                _ => {}
+            }
+
             tracing::debug!("📊 Updated job {} status: {:?}", job_id, status);
+            if !was_finished && matches!(status, JobStatus::Completed { .. } | JobStatus::Failed { .. }) {
+                notify = Some(job.clone());
+            }
+        }
+
+        let graph = jobs.get(job_id).and_then(|job| job.graph_id.clone().map(|graph_id| (graph_id, job.session_id.clone())));
+        drop(jobs);
+        if let (Some(job), Some(pool)) = (notify, self.webhook_pool.get()) {
+            crate::services::NotificationService::job_finished(pool.clone(), job.clone());
+            crate::services::ActivityService::job_finished(pool.clone(), job.clone());
+            crate::services::WebhookService::job_finished(pool.clone(), job);
+        }
+        if let (true, Some((graph_id, session_id))) = (finished, graph) {
+            self.announce_graph_progress(&graph_id, &session_id).await;
+        }
+    }
+
+    /// Jobs of a graph (oldest first) and their combined progress
+    pub async fn graph_progress(&self, graph_id: &str) -> Option<(GraphProgress, Vec<Job>)> {
+        let jobs = self.jobs.read().await;
+        let mut members: Vec<&Job> = jobs.values().filter(|job| job.graph_id.as_deref() == Some(graph_id)).collect();
+        if members.is_empty() {
+            return None;
         }
+        members.sort_by_key(|job| job.created_at);
+        Some((dag::graph_progress(graph_id, &members), members.into_iter().cloned().collect()))
+    }
+
+    /// Tell the session how far a graph has got, each time one of its jobs finishes
+    async fn announce_graph_progress(&self, graph_id: &str, session_id: &str) {
+        let Some((progress, _)) = self.graph_progress(graph_id).await else {
+            return;
+        };
+        let done = progress.completed + progress.failed + progress.cancelled;
+        let message = if progress.finished {
+            format!("📊 Pipeline finished: {} of {} jobs completed", progress.completed, progress.total)
+        } else {
+            format!("📊 Pipeline: {} of {} jobs done ({:.0}%)", done, progress.total, progress.progress_percent)
+        };
+        let status = JobStatus::Running {
+            current_step: "pipeline".to_string(),
+            progress_percent: progress.progress_percent,
+            steps_completed: done,
+            total_steps: progress.total,
+        };
+        let details = serde_json::json!({ "graph": progress });
+        self.send_progress(session_id, ProgressUpdate::new(graph_id.to_string(), message, status).with_details(details)).await;
     }
 
     /// Get all jobs for a session
@@ -236,15 +692,66 @@ impl JobManager {
         tracing::info!("🎛️ Registered control channel for job: {}", job_id);
     }
 
-    /// Send control command to a job
+    /// Send control command to a job. Cancel also kills the job's running FFmpeg processes and
+    /// reports it `Cancelled` right away, rather than once the job reaches its next step.
     pub async fn send_control(&self, job_id: &str, command: JobControl) -> Result<(), String> {
-        let channels = self.control_channels.read().await;
-        if let Some(sender) = channels.get(job_id) {
-            sender.send(command).map_err(|e| format!("Failed to send control: {}", e))?;
-            Ok(())
-        } else {
-            Err(format!("No control channel for job {}", job_id))
+        let sender = self.control_channels.read().await.get(job_id).cloned()
+            .ok_or_else(|| format!("No control channel for job {}", job_id))?;
+        if matches!(command, JobControl::Cancel) {
+            self.cancel_running(job_id).await;
         }
+        sender.send(command).map_err(|e| format!("Failed to send control: {}", e))
+    }
+
+    /// The processes a running job's FFmpeg commands are registered in. Work a job hands to other
+    /// tasks runs under `track_processes` with this set, so cancelling stops it too.
+    pub fn job_processes(&self, job_id: &str) -> ProcessSet {
+        self.processes.lock().unwrap_or_else(|e| e.into_inner()).entry(job_id.to_string()).or_default().clone()
+    }
+
+    /// Stop a job that ran past `limits.job_timeout_minutes`: kill its FFmpeg processes, tell the tasks
+    /// it handed work to that it's cancelled, and fail it
+    async fn time_out(&self, job_id: &str, limit: std::time::Duration) {
+        let processes = self.processes.lock().unwrap_or_else(|e| e.into_inner()).get(job_id).cloned();
+        let killed = processes.map_or(0, |processes| processes.kill_all());
+        if let Some(sender) = self.control_channels.read().await.get(job_id) {
+            let _ = sender.send(JobControl::Cancel);
+        }
+        let Some(job) = self.get_job(job_id).await.filter(|job| !job.status.is_final()) else {
+            return;
+        };
+        tracing::warn!("⏱️ Job {} ran longer than {} minutes ({} FFmpeg process(es) stopped)", job_id, limit.as_secs() / 60, killed);
+
+        let failed_at_step = match &job.status {
+            JobStatus::Running { current_step, .. } => current_step.clone(),
+            _ => "running".to_string(),
+        };
+        let error = format!("Job ran longer than the {}-minute limit", limit.as_secs() / 60);
+        let status = JobStatus::Failed { error: error.clone(), failed_at_step };
+        self.update_job_status(job_id, status.clone()).await;
+        self.send_progress(&job.session_id, ProgressUpdate::new(job_id.to_string(), format!("⏱️ {}", error), status)).await;
+    }
+
+    /// Kill a running job's FFmpeg processes (their partial outputs are removed) and mark it cancelled
+    async fn cancel_running(&self, job_id: &str) {
+        let Some(job) = self.get_job(job_id).await.filter(|job| !job.status.is_final()) else {
+            return;
+        };
+        let processes = self.processes.lock().unwrap_or_else(|e| e.into_inner()).get(job_id).cloned();
+        let killed = processes.map_or(0, |processes| processes.kill_all());
+        tracing::info!("🛑 Cancelling job {} ({} FFmpeg process(es) stopped)", job_id, killed);
+
+        let cancelled_at_step = match &job.status {
+            JobStatus::Running { current_step, .. } => current_step.clone(),
+            _ => "running".to_string(),
+        };
+        let status = JobStatus::Cancelled { cancelled_at_step };
+        let message = match killed {
+            0 => "🛑 Job cancelled by user".to_string(),
+            n => format!("🛑 Job cancelled by user ({} render(s) stopped)", n),
+        };
+        self.update_job_status(job_id, status.clone()).await;
+        self.send_progress(&job.session_id, ProgressUpdate::new(job_id.to_string(), message, status)).await;
     }
 
     /// Cleanup completed/failed jobs older than specified duration
@@ -268,6 +775,12 @@ impl JobManager {
             jobs.remove(&job_id);
             tracing::debug!("🗑️ Cleaned up old job: {}", job_id);
         }
+        drop(jobs);
+
+        self.job_subscribers.write().await.retain(|_, subscribers| {
+            subscribers.retain(|subscriber| !subscriber.is_closed());
+            !subscribers.is_empty()
+        });
     }
 }
 
@@ -0,0 +1,264 @@
+// src/jobs/scheduled_job.rs
+//! Jobs queued for a future time ("render tonight, publish tomorrow 9am"). Schedules are stored in
+//! `scheduled_jobs` so they survive restarts; until they're due they sit in the job manager as
+//! `Scheduled { run_at }`. The scheduler loop claims due rows and starts each one as a regular job
+//! under the same id, so status, control and progress work as for any other job.
+
+use super::video_job::{self, AgentType};
+use super::{Job, JobManager, JobStatus, ProgressUpdate};
+use crate::models::scheduled_job::{ScheduleJobRequest, ScheduledJob, ScheduledWork};
+use crate::AppState;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// How often the scheduler looks for due jobs
+pub const SCHEDULER_INTERVAL_SECONDS: u64 = 30;
+
+/// Furthest ahead a job can be scheduled
+pub const MAX_SCHEDULE_DAYS: i64 = 90;
+
+/// Check and store a schedule, and show it in the job manager until it's due
+pub async fn schedule(state: &Arc<AppState>, user_id: i32, request: ScheduleJobRequest) -> Result<ScheduledJob, String> {
+    if request.run_at <= Utc::now() {
+        return Err("run_at must be in the future".to_string());
+    }
+    if request.run_at > Utc::now() + Duration::days(MAX_SCHEDULE_DAYS) {
+        return Err(format!("Jobs can be scheduled at most {} days ahead", MAX_SCHEDULE_DAYS));
+    }
+
+    let owns_session = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+    )
+    .bind(&request.session_id)
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .is_some();
+    if !owns_session {
+        return Err("Session not found".to_string());
+    }
+
+    match &request.work {
+        ScheduledWork::VideoEditing { task } if task.trim().is_empty() => {
+            return Err("task must describe the edit to run".to_string());
+        }
+        ScheduledWork::YoutubeUpload { upload } => {
+            let channel_id = match upload.channel_id {
+                Some(channel_id) => channel_id,
+                None => crate::services::UploadDefaultsService::get(&state.db_pool, user_id)
+                    .await
+                    .channel_id
+                    .ok_or("channel_id is required (or set a default channel)")?,
+            };
+            let connected = sqlx::query_scalar::<_, i32>(
+                "SELECT id FROM connected_youtube_channels WHERE id = $1 AND user_id = $2 AND is_active = true"
+            )
+            .bind(channel_id)
+            .bind(user_id)
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+            if !connected {
+                return Err("Channel not found or not connected".to_string());
+            }
+        }
+        _ => {}
+    }
+
+    let payload = serde_json::to_value(&request.work).map_err(|e| e.to_string())?;
+    let job = Job::new(request.session_id.clone(), request.work.kind().to_string(), payload.clone())
+        .with_user_id(user_id.to_string());
+
+    let scheduled = sqlx::query_as::<_, ScheduledJob>(
+        r#"
+        INSERT INTO scheduled_jobs (id, user_id, session_uuid, kind, payload, run_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(&job.id)
+    .bind(user_id)
+    .bind(&request.session_id)
+    .bind(request.work.kind())
+    .bind(&payload)
+    .bind(request.run_at)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(|e| format!("Failed to schedule job: {}", e))?;
+
+    track(&state.job_manager, &scheduled).await;
+    tracing::info!("⏰ User {} scheduled {} job {} for {}", user_id, scheduled.kind, scheduled.id, scheduled.run_at);
+    Ok(scheduled)
+}
+
+/// Put a stored schedule in the job manager as `Scheduled`
+async fn track(job_manager: &JobManager, scheduled: &ScheduledJob) {
+    let mut job = Job::new(scheduled.session_uuid.clone(), scheduled.kind.clone(), scheduled.payload.clone())
+        .with_user_id(scheduled.user_id.to_string());
+    job.id = scheduled.id.clone();
+    job.created_at = scheduled.created_at;
+    job.status = JobStatus::Scheduled { run_at: scheduled.run_at };
+    job_manager.create_job(job).await;
+}
+
+/// The user's jobs still waiting for their time, soonest first
+pub async fn list(pool: &PgPool, user_id: i32) -> Result<Vec<ScheduledJob>, sqlx::Error> {
+    sqlx::query_as::<_, ScheduledJob>(
+        "SELECT * FROM scheduled_jobs WHERE user_id = $1 AND status = 'scheduled' ORDER BY run_at"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Cancel a job that hasn't started yet; false if there's no such pending job
+pub async fn cancel(state: &AppState, user_id: i32, id: &str) -> Result<bool, sqlx::Error> {
+    let session_uuid = sqlx::query_scalar::<_, String>(
+        "UPDATE scheduled_jobs SET status = 'cancelled' WHERE id = $1 AND user_id = $2 AND status = 'scheduled' RETURNING session_uuid"
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await?;
+    let Some(session_uuid) = session_uuid else {
+        return Ok(false);
+    };
+
+    let status = JobStatus::Cancelled { cancelled_at_step: "scheduled".to_string() };
+    state.job_manager.update_job_status(id, status.clone()).await;
+    let update = ProgressUpdate::new(id.to_string(), "🛑 Scheduled job cancelled".to_string(), status);
+    state.job_manager.send_progress(&session_uuid, update).await;
+    Ok(true)
+}
+
+/// Load pending schedules into the job manager (after a restart)
+pub async fn restore(state: &AppState) -> Result<usize, sqlx::Error> {
+    let pending = sqlx::query_as::<_, ScheduledJob>("SELECT * FROM scheduled_jobs WHERE status = 'scheduled'")
+        .fetch_all(&state.db_pool)
+        .await?;
+    for scheduled in &pending {
+        track(&state.job_manager, scheduled).await;
+    }
+    Ok(pending.len())
+}
+
+/// The scheduler loop: reload pending schedules, then start each one as it falls due
+pub async fn run_scheduler(state: Arc<AppState>) {
+    match restore(&state).await {
+        Ok(0) => {}
+        Ok(pending) => tracing::info!("⏰ Restored {} scheduled job(s)", pending),
+        Err(e) => tracing::error!("❌ Failed to restore scheduled jobs: {}", e),
+    }
+    loop {
+        match start_due(&state).await {
+            Ok(0) => {}
+            Ok(started) => tracing::info!("⏰ Started {} scheduled job(s)", started),
+            Err(e) => tracing::error!("❌ Scheduled job check failed: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(SCHEDULER_INTERVAL_SECONDS)).await;
+    }
+}
+
+/// Claim every due schedule and start it; returns how many started
+pub async fn start_due(state: &Arc<AppState>) -> Result<usize, sqlx::Error> {
+    // Claiming in one UPDATE means a schedule starts once even with several instances running
+    let due = sqlx::query_as::<_, ScheduledJob>(
+        r#"
+        UPDATE scheduled_jobs SET status = 'started', started_at = NOW()
+        WHERE status = 'scheduled' AND run_at <= NOW()
+        RETURNING *
+        "#,
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    for scheduled in &due {
+        start(state, scheduled).await;
+    }
+    Ok(due.len())
+}
+
+async fn start(state: &Arc<AppState>, scheduled: &ScheduledJob) {
+    tracing::info!("⏰ Starting scheduled {} job {} (due {})", scheduled.kind, scheduled.id, scheduled.run_at);
+    let work = match serde_json::from_value::<ScheduledWork>(scheduled.payload.clone()) {
+        Ok(work) => work,
+        Err(e) => {
+            let status = JobStatus::Failed { error: format!("Unreadable schedule: {}", e), failed_at_step: "scheduled".to_string() };
+            state.job_manager.update_job_status(&scheduled.id, status).await;
+            return;
+        }
+    };
+
+    match work {
+        ScheduledWork::VideoEditing { task } => {
+            let agent_type = if state.claude_client.is_some() { AgentType::Claude } else { AgentType::Gemini };
+            let job_data = json!({
+                "raw_input": task,
+                "augmented_input": task,
+                "agent_type": format!("{:?}", agent_type),
+                "scheduled_for": scheduled.run_at,
+            });
+            let job = scheduled_as_job(scheduled, job_data);
+            video_job::run_job(job, agent_type, state.clone(), state.job_manager.clone()).await;
+        }
+        ScheduledWork::YoutubeUpload { upload } => {
+            let job = scheduled_as_job(scheduled, scheduled.payload.clone());
+            state.job_manager.create_job(job.clone()).await;
+
+            let state = state.clone();
+            let user_id = scheduled.user_id;
+            let job_context = job.clone();
+            // A blank title is filled in from generated metadata at upload time
+            let label = match upload.title.trim() {
+                "" => upload.video_path.clone(),
+                title => title.to_string(),
+            };
+            state.job_manager.clone().spawn_job(&job_context, async move {
+                let job_manager = &state.job_manager;
+                let started = std::time::Instant::now();
+                let running = JobStatus::Running {
+                    current_step: "Uploading to YouTube".to_string(),
+                    progress_percent: 0.0,
+                    steps_completed: 0,
+                    total_steps: 1,
+                };
+                job_manager.update_job_status(&job.id, running.clone()).await;
+                job_manager
+                    .send_progress(&job.session_id, ProgressUpdate::new(job.id.clone(), format!("📤 Uploading \"{}\" to YouTube", label), running))
+                    .await;
+
+                let (message, status) = match crate::handlers::youtube::upload_for_user(&state, user_id, &upload).await {
+                    Ok(result) => {
+                        let url = result["upload"]["youtube_url"].as_str().unwrap_or_default().to_string();
+                        let message = format!("✅ Scheduled upload of \"{}\" is on YouTube: {}", label, url);
+                        let status = JobStatus::Completed {
+                            result: message.clone(),
+                            output_files: vec![],
+                            duration_seconds: started.elapsed().as_secs_f64(),
+                        };
+                        (message, status)
+                    }
+                    Err((_, body)) => {
+                        let error = body.0["message"].as_str().unwrap_or("Upload failed").to_string();
+                        let message = format!("❌ Scheduled upload of \"{}\" failed: {}", label, error);
+                        (message, JobStatus::Failed { error, failed_at_step: "Uploading to YouTube".to_string() })
+                    }
+                };
+                job_manager.update_job_status(&job.id, status.clone()).await;
+                job_manager.send_progress(&job.session_id, ProgressUpdate::new(job.id.clone(), message, status)).await;
+            }).await;
+        }
+    }
+}
+
+/// The job a schedule runs as: same id and owner, queued from now
+fn scheduled_as_job(scheduled: &ScheduledJob, input_data: serde_json::Value) -> Job {
+    let mut job = Job::new(scheduled.session_uuid.clone(), scheduled.kind.clone(), input_data)
+        .with_user_id(scheduled.user_id.to_string());
+    job.id = scheduled.id.clone();
+    job
+}
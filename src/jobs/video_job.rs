@@ -2,14 +2,20 @@
 //! Video editing job executor - runs AI agents in background with progress updates
 //! Now with LangGraph-style ReAct pattern: Thought → Action → Observation → Reflection
 
-use super::{Job, JobControl, JobId, JobManager, JobStatus, ProgressUpdate};
+use super::dag::{self, GraphNode};
+use super::{Job, JobControl, JobId, JobManager, JobPriority, JobStatus, ProgressUpdate};
 use crate::agent::simple_claude_agent::SimpleClaudeAgent;
 use crate::agent::simple_gemini_agent::SimpleGeminiAgent;
-use crate::agent::react_agent::{ReActClaudeAgent, ReActGeminiAgent};
+use crate::agent::react_agent::ReActGeminiAgent;
 use crate::agent::react_state::{AgentState, UserCommand};
 use crate::agent::conversation_manager::{ConversationManager, ConversationMessage};
+use crate::services::{EmbeddingIndexService, MemorySearchService};
+use crate::qdrant_client::NewChatMemory;
 use crate::AppState;
 use std::sync::Arc;
+use crate::middleware::request_id::propagate;
+use crate::utils::processes::track_processes;
+use tracing::Instrument;
 use tokio::sync::mpsc;
 use serde_json::json;
 
@@ -162,6 +168,12 @@ impl VideoEditingJob {
             }
         };
 
+        // A cancelled job was already reported as such; whatever its agent returned is moot
+        if let Some(JobStatus::Cancelled { .. }) = self.job_manager.get_job_status(&job_id).await {
+            tracing::info!("🛑 Video editing job {} was cancelled", job_id);
+            return Err("Job cancelled by user".to_string());
+        }
+
         // Update final status and save response
         match result {
             Ok(response) => {
@@ -201,30 +213,23 @@ impl VideoEditingJob {
                     let files_referenced = vec![]; 
                     let context_data = std::collections::HashMap::new();
                     
-                    if let Some(ref voyage_embeddings) = self.app_state.voyage_embeddings {
-                        if let Err(e) = qdrant_client.store_chat_memory_with_voyage(
-                            &session_id,
-                            None, 
-                            &raw_input,
-                            &response,
-                            files_referenced,
-                            context_data,
-                            voyage_embeddings,
-                        ).await {
-                            tracing::warn!("Failed to store conversation in Qdrant (Voyage): {}", e);
-                        }
-                    } else if let Some(ref gemini_client) = self.app_state.gemini_client {
-                        if let Err(e) = qdrant_client.store_chat_memory_with_gemini(
-                            &session_id,
-                            None,
-                            &raw_input,
-                            &response,
-                            files_referenced,
-                            context_data,
-                            gemini_client,
-                        ).await {
-                            tracing::warn!("Failed to store conversation in Qdrant (Gemini): {}", e);
-                        }
+                    let stored = match EmbeddingIndexService::embed(&self.app_state, &raw_input).await {
+                        Ok(embedding) => qdrant_client.store_chat_memory(
+                            NewChatMemory {
+                                session_id: &session_id,
+                                user_id: None,
+                                user_message: &raw_input,
+                                agent_response: &response,
+                                files_referenced,
+                                context: context_data,
+                            },
+                            embedding,
+                        ).await.map_err(|e| e.to_string()),
+                        Err(e) => Err(e),
+                    };
+                    match stored {
+                        Ok(memory_id) => MemorySearchService::record(&self.app_state.db_pool, &memory_id, &session_id, &raw_input, &response).await,
+                        Err(e) => tracing::warn!("Failed to store conversation in Qdrant: {}", e),
                     }
                 }
 
@@ -348,9 +353,12 @@ impl VideoEditingJob {
         let session_id_clone = session_id.to_string();
         let app_state_clone = self.app_state.clone();
         let progress_callback_clone = progress_callback.clone();
-        let mut agent_handle = tokio::spawn(async move {
+        let processes = self.job_manager.job_processes(&self.job.id);
+        let mut agent_handle = tokio::spawn(propagate(track_processes(async move {
             agent.execute(&user_input_clone, &session_id_clone, None, app_state_clone, Some(progress_callback_clone)).await
-        });
+        }, processes)).in_current_span());
+        // A job stopped from outside (e.g. for running too long) drops this future; take the agent down with it
+        let _abort_agent = AbortOnDrop(agent_handle.abort_handle());
 
         // Poll for control commands
         loop {
@@ -392,9 +400,12 @@ impl VideoEditingJob {
         let session_id_clone = session_id.to_string();
         let app_state_clone = self.app_state.clone();
         let progress_callback_clone = progress_callback.clone();
-        let mut agent_handle = tokio::spawn(async move {
+        let processes = self.job_manager.job_processes(&self.job.id);
+        let mut agent_handle = tokio::spawn(propagate(track_processes(async move {
             agent.execute(&user_input_clone, &session_id_clone, None, app_state_clone, Some(progress_callback_clone)).await
-        });
+        }, processes)).in_current_span());
+        // A job stopped from outside (e.g. for running too long) drops this future; take the agent down with it
+        let _abort_agent = AbortOnDrop(agent_handle.abort_handle());
 
         // Poll for control commands
         loop {
@@ -427,7 +438,7 @@ impl VideoEditingJob {
         let session_id = self.job.session_id.clone();
 
         // Spawn the actual execution
-        let mut execution_handle = tokio::spawn(executor());
+        let mut execution_handle = tokio::spawn(propagate(executor()).in_current_span());
 
         // Monitor for control commands
         loop {
@@ -517,12 +528,22 @@ impl VideoEditingJob {
     }
 }
 
+/// Aborts a spawned task when dropped
+struct AbortOnDrop(tokio::task::AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
 /// Spawn a video editing job in background
 pub async fn spawn_video_editing_job(
     raw_input: String,
     augmented_input: String,
     session_id: String,
     agent_type: AgentType,
+    priority: JobPriority,
     app_state: Arc<AppState>,
     job_manager: Arc<JobManager>,
 ) -> Result<JobId, String> {
@@ -533,18 +554,83 @@ pub async fn spawn_video_editing_job(
         "agent_type": format!("{:?}", agent_type),
     });
 
-    let job = Job::new(session_id.clone(), "video_editing".to_string(), job_data);
-    let job_id = job.id.clone();
+    let mut job = Job::new(session_id.clone(), "video_editing".to_string(), job_data).with_priority(priority);
+    if let Some(user_id) = session_owner(&session_id, &app_state).await {
+        job = job.with_user_id(user_id.to_string());
+    }
 
-    // Store job in manager
-    let job_id_stored = job_manager.create_job(job.clone()).await;
+    let job_id = run_job(job, agent_type, app_state, job_manager).await;
+    tracing::info!("🚀 Spawned video editing job: {} for session: {}", job_id, session_id);
+    Ok(job_id)
+}
 
-    // Spawn background execution
-    let video_job = VideoEditingJob::new(job, agent_type, app_state, job_manager.clone());
+/// Spawn a graph of video editing jobs, each starting once the jobs it depends on have completed.
+/// A job's prompt gets the results of its dependencies. Returns the graph id and the job id of
+/// each node, in the order they can run.
+pub async fn spawn_video_editing_graph(
+    session_id: String,
+    nodes: Vec<GraphNode>,
+    agent_type: AgentType,
+    priority: JobPriority,
+    app_state: Arc<AppState>,
+    job_manager: Arc<JobManager>,
+) -> Result<(String, Vec<(String, JobId)>), String> {
+    let order = dag::topological_order(&nodes)?;
+    let graph_id = uuid::Uuid::new_v4().to_string();
+    let owner = session_owner(&session_id, &app_state).await;
+
+    let mut job_ids: Vec<(String, JobId)> = Vec::with_capacity(nodes.len());
+    for index in order {
+        let node = &nodes[index];
+        let depends_on = node
+            .depends_on
+            .iter()
+            .filter_map(|key| job_ids.iter().find(|(k, _)| k == key).map(|(_, id)| id.clone()))
+            .collect();
+        let job_data = json!({
+            "raw_input": node.task,
+            "augmented_input": node.task,
+            "agent_type": format!("{:?}", agent_type),
+            "graph_key": node.key,
+        });
+        let mut job = Job::new(session_id.clone(), "video_editing".to_string(), job_data)
+            .with_dependencies(graph_id.clone(), depends_on)
+            .with_priority(priority);
+        if let Some(user_id) = owner {
+            job = job.with_user_id(user_id.to_string());
+        }
+
+        let job_id = run_job(job, agent_type.clone(), app_state.clone(), job_manager.clone()).await;
+        job_ids.push((node.key.clone(), job_id));
+    }
+
+    tracing::info!("🚀 Spawned job graph {} ({} jobs) for session: {}", graph_id, job_ids.len(), session_id);
+    Ok((graph_id, job_ids))
+}
+
+/// The session owner's jobs share one per-user worker cap
+async fn session_owner(session_id: &str, app_state: &AppState) -> Option<i32> {
+    sqlx::query_scalar::<_, i32>("SELECT user_id FROM chat_sessions WHERE session_uuid = $1")
+        .bind(session_id)
+        .fetch_optional(&app_state.db_pool)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Store the job and run it on the worker pool once its dependencies are done and a slot is free
+pub(crate) async fn run_job(job: Job, agent_type: AgentType, app_state: Arc<AppState>, job_manager: Arc<JobManager>) -> JobId {
+    let job_id = job.id.clone();
+    job_manager.create_job(job.clone()).await;
+
+    let job_context = job.clone();
+    let manager = job_manager.clone();
     let job_id_for_spawn = job_id.clone();
 
-    tokio::spawn(async move {
+    job_manager.spawn_job(&job_context, async move {
         tracing::info!("🔥 INSIDE tokio::spawn for job: {}", job_id_for_spawn);
+        let job = with_dependency_results(job, &manager).await;
+        let video_job = VideoEditingJob::new(job, agent_type, app_state, manager);
         match video_job.execute().await {
             Ok(result) => {
                 tracing::info!("✅ Video editing job completed: {}", job_id_for_spawn);
@@ -555,8 +641,27 @@ pub async fn spawn_video_editing_job(
             }
         }
         tracing::info!("🔥 EXITING tokio::spawn for job: {}", job_id_for_spawn);
-    });
+    }).await;
+
+    job_id
+}
+
+/// Put the results of the jobs this one depends on ahead of its task, so it picks up their outputs
+async fn with_dependency_results(mut job: Job, job_manager: &JobManager) -> Job {
+    let mut results = String::new();
+    for dependency in &job.depends_on {
+        if let Some(JobStatus::Completed { result, .. }) = job_manager.get_job_status(dependency).await {
+            results.push_str(&format!("- {}\n", result.trim()));
+        }
+    }
+    if results.is_empty() {
+        return job;
+    }
 
-    tracing::info!("🚀 Spawned video editing job: {} for session: {}", job_id_stored, session_id);
-    Ok(job_id_stored)
+    let task = job.input_data.get("augmented_input").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    job.input_data["augmented_input"] = json!(format!(
+        "[EARLIER PIPELINE STEPS - their outputs are the inputs for this step]\n{}\n[THIS STEP]\n{}",
+        results, task
+    ));
+    job
 }
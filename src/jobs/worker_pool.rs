@@ -0,0 +1,203 @@
+// src/jobs/worker_pool.rs
+//! Admission control for background jobs: at most `slots` jobs run at once, and at most `per_user`
+//! for any one user. Waiting jobs start highest priority first and in FIFO order within a priority,
+//! except that a job whose user is at the cap is passed over so it doesn't hold up other users.
+//! FFmpeg processes inside running jobs are limited separately by the express/standard lanes.
+
+use super::{JobId, JobPriority};
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::oneshot;
+
+struct Waiting {
+    job_id: JobId,
+    user: String,
+    priority: JobPriority,
+    start: oneshot::Sender<()>,
+    /// Last position reported for the job (1 = next to start)
+    position: usize,
+}
+
+/// What a change to the pool did: jobs that were started, and queued jobs whose position changed
+#[derive(Debug, Default, PartialEq)]
+pub struct Dispatch {
+    pub started: Vec<JobId>,
+    pub moved: Vec<(JobId, usize)>,
+}
+
+impl Dispatch {
+    pub fn is_empty(&self) -> bool {
+        self.started.is_empty() && self.moved.is_empty()
+    }
+}
+
+pub struct WorkerPool {
+    slots: usize,
+    per_user: usize,
+    running: HashMap<String, usize>,
+    queue: VecDeque<Waiting>,
+}
+
+impl WorkerPool {
+    pub fn new(slots: usize, per_user: usize) -> Self {
+        Self { slots: slots.max(1), per_user: per_user.max(1), running: HashMap::new(), queue: VecDeque::new() }
+    }
+
+    fn running_total(&self) -> usize {
+        self.running.values().sum()
+    }
+
+    /// Queue a job behind every waiting job of its priority or higher; `start` fires when it gets a slot
+    pub fn enqueue(&mut self, job_id: JobId, user: String, priority: JobPriority, start: oneshot::Sender<()>) -> Dispatch {
+        self.insert(Waiting { job_id, user, priority, start, position: 0 });
+        self.dispatch()
+    }
+
+    fn insert(&mut self, waiting: Waiting) {
+        let index = self.queue.iter().position(|queued| queued.priority < waiting.priority).unwrap_or(self.queue.len());
+        self.queue.insert(index, waiting);
+    }
+
+    /// Move a waiting job to the back of another priority; None if it isn't waiting
+    pub fn reprioritize(&mut self, job_id: &str, priority: JobPriority) -> Option<Dispatch> {
+        let index = self.queue.iter().position(|waiting| waiting.job_id == job_id)?;
+        let mut waiting = self.queue.remove(index).expect("index is in bounds");
+        waiting.priority = priority;
+        self.insert(waiting);
+        Some(self.dispatch())
+    }
+
+    /// Give back a running job's slot
+    pub fn finish(&mut self, user: &str) -> Dispatch {
+        if let Some(count) = self.running.get_mut(user) {
+            *count -= 1;
+            if *count == 0 {
+                self.running.remove(user);
+            }
+        }
+        self.dispatch()
+    }
+
+    /// Take a job out of the queue before it starts; None if it isn't waiting
+    pub fn cancel(&mut self, job_id: &str) -> Option<Dispatch> {
+        let index = self.queue.iter().position(|waiting| waiting.job_id == job_id)?;
+        self.queue.remove(index);
+        Some(self.dispatch())
+    }
+
+    /// Start every waiting job that fits, then renumber the ones still waiting
+    fn dispatch(&mut self) -> Dispatch {
+        let mut dispatch = Dispatch::default();
+        let mut index = 0;
+        while index < self.queue.len() && self.running_total() < self.slots {
+            let user_running = self.running.get(&self.queue[index].user).copied().unwrap_or(0);
+            if user_running >= self.per_user {
+                index += 1;
+                continue;
+            }
+            let waiting = self.queue.remove(index).expect("index is in bounds");
+            // A receiver that's gone means the job was abandoned; it never takes a slot
+            if waiting.start.send(()).is_ok() {
+                *self.running.entry(waiting.user).or_insert(0) += 1;
+                dispatch.started.push(waiting.job_id);
+            }
+        }
+
+        for (index, waiting) in self.queue.iter_mut().enumerate() {
+            if waiting.position != index + 1 {
+                waiting.position = index + 1;
+                dispatch.moved.push((waiting.job_id.clone(), waiting.position));
+            }
+        }
+        dispatch
+    }
+
+    /// Queue position of a waiting job
+    #[cfg(test)]
+    pub fn position(&self, job_id: &str) -> Option<usize> {
+        self.queue.iter().position(|waiting| waiting.job_id == job_id).map(|index| index + 1)
+    }
+
+    pub fn usage(&self) -> serde_json::Value {
+        serde_json::json!({
+            "running": self.running_total(),
+            "queued": self.queue.len(),
+            "slots": self.slots,
+            "per_user": self.per_user,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enqueue(pool: &mut WorkerPool, job_id: &str, user: &str) -> (Dispatch, oneshot::Receiver<()>) {
+        enqueue_with(pool, job_id, user, JobPriority::Normal)
+    }
+
+    fn enqueue_with(pool: &mut WorkerPool, job_id: &str, user: &str, priority: JobPriority) -> (Dispatch, oneshot::Receiver<()>) {
+        let (tx, rx) = oneshot::channel();
+        (pool.enqueue(job_id.to_string(), user.to_string(), priority, tx), rx)
+    }
+
+    #[test]
+    fn queued_jobs_move_up_as_slots_free() {
+        let mut pool = WorkerPool::new(1, 2);
+        let (first, _a) = enqueue(&mut pool, "a", "alice");
+        assert_eq!(first.started, ["a"]);
+        let (_, _b) = enqueue(&mut pool, "b", "bob");
+        let (third, _c) = enqueue(&mut pool, "c", "carol");
+        assert_eq!(third.moved, [("c".to_string(), 2)]);
+
+        let next = pool.finish("alice");
+        assert_eq!(next.started, ["b"]);
+        assert_eq!(next.moved, [("c".to_string(), 1)]);
+        assert_eq!(pool.position("c"), Some(1));
+    }
+
+    #[test]
+    fn users_at_their_cap_do_not_block_others() {
+        let mut pool = WorkerPool::new(3, 1);
+        let (_, _a1) = enqueue(&mut pool, "a1", "alice");
+        let (queued, _a2) = enqueue(&mut pool, "a2", "alice");
+        assert!(queued.started.is_empty());
+        let (second, _b1) = enqueue(&mut pool, "b1", "bob");
+        assert_eq!(second.started, ["b1"]);
+        assert_eq!(pool.position("a2"), Some(1));
+
+        assert_eq!(pool.finish("alice").started, ["a2"]);
+    }
+
+    #[test]
+    fn cancelled_and_abandoned_jobs_give_up_their_place() {
+        let mut pool = WorkerPool::new(1, 1);
+        let (_, _a) = enqueue(&mut pool, "a", "alice");
+        let (_, b) = enqueue(&mut pool, "b", "bob");
+        let (_, _c) = enqueue(&mut pool, "c", "carol");
+        let (_, _d) = enqueue(&mut pool, "d", "dave");
+
+        assert_eq!(pool.cancel("c").unwrap().moved, [("d".to_string(), 2)]);
+        assert!(pool.cancel("c").is_none());
+
+        drop(b);
+        assert_eq!(pool.finish("alice").started, ["d"]);
+    }
+
+    #[test]
+    fn higher_priority_jobs_start_first() {
+        let mut pool = WorkerPool::new(1, 5);
+        let (_, _running) = enqueue(&mut pool, "render", "alice");
+        let (_, _bulk) = enqueue_with(&mut pool, "bulk", "bob", JobPriority::Low);
+        let (_, _normal) = enqueue(&mut pool, "normal", "carol");
+        let (chat, _chat) = enqueue_with(&mut pool, "chat", "dave", JobPriority::High);
+        assert_eq!(chat.moved, [("chat".to_string(), 1), ("normal".to_string(), 2), ("bulk".to_string(), 3)]);
+
+        // Bumped jobs go to the back of their new priority
+        let (_, _second_chat) = enqueue_with(&mut pool, "chat2", "erin", JobPriority::High);
+        pool.reprioritize("bulk", JobPriority::High).unwrap();
+        assert_eq!(pool.position("bulk"), Some(3));
+        assert!(pool.reprioritize("render", JobPriority::Low).is_none());
+
+        assert_eq!(pool.finish("alice").started, ["chat"]);
+    }
+}
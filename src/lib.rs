@@ -1,4 +1,5 @@
 // lib.rs - Main library file that exports all modules
+pub mod config;
 pub mod types;
 pub mod core;
 pub mod audio;
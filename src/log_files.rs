@@ -0,0 +1,319 @@
+// log_files.rs - Rolling JSON log files and per-job log capture
+//
+// With `logging.file_dir` set, every event is also written as a JSON line to <dir>/video_sync.log.
+// The file is rolled daily, hourly, or only by size, and always once it passes `max_file_mb`.
+// Rolled files are renamed to video_sync.<timestamp>.log and gzipped in the background. Only the
+// newest `max_files` are kept.
+//
+// Independently, JobLogLayer keeps the last `job_capture_lines` lines logged inside each job's span
+// (see Job::span). When a job fails, JobManager attaches them to the job, and writes them to
+// <dir>/jobs/<job_id>.log when file logging is on, so support gets the failure in context.
+use crate::config::LoggingConfig;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Name of the active log file; rolled files are video_sync.<timestamp>.log[.gz]
+const LOG_STEM: &str = "video_sync";
+
+pub struct RollingFile {
+    dir: PathBuf,
+    rotation: String,
+    max_bytes: u64,
+    max_files: usize,
+    compress: bool,
+    active: Mutex<Active>,
+}
+
+struct Active {
+    file: File,
+    size: u64,
+    /// Rotation period the file belongs to; a new period starts a new file
+    period: String,
+}
+
+impl RollingFile {
+    pub fn open(config: &LoggingConfig, dir: &str) -> std::io::Result<Self> {
+        let dir = PathBuf::from(dir);
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.log", LOG_STEM));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        // A file left from an earlier run keeps its own period, so it's rolled on the first write if that has passed
+        let modified: DateTime<Utc> = metadata.modified().map(DateTime::from).unwrap_or_else(|_| Utc::now());
+        let rolling = Self {
+            dir,
+            rotation: config.file_rotation.clone(),
+            max_bytes: config.max_file_mb * 1024 * 1024,
+            max_files: config.max_files,
+            compress: config.compress,
+            active: Mutex::new(Active { file, size: metadata.len(), period: String::new() }),
+        };
+        rolling.active.lock().unwrap().period = rolling.period(modified);
+        Ok(rolling)
+    }
+
+    fn active_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.log", LOG_STEM))
+    }
+
+    fn period(&self, at: DateTime<Utc>) -> String {
+        match self.rotation.as_str() {
+            "hourly" => at.format("%Y-%m-%d-%H").to_string(),
+            "daily" => at.format("%Y-%m-%d").to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Rename the active file out of the way and start a fresh one
+    fn roll(&self, active: &mut Active) -> std::io::Result<()> {
+        active.file.flush()?;
+        let stamp = Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+        let mut rolled = self.dir.join(format!("{}.{}.log", LOG_STEM, stamp));
+        let mut n = 1;
+        while rolled.exists() || rolled.with_extension("log.gz").exists() {
+            rolled = self.dir.join(format!("{}.{}-{}.log", LOG_STEM, stamp, n));
+            n += 1;
+        }
+        std::fs::rename(self.active_path(), &rolled)?;
+        active.file = OpenOptions::new().create(true).append(true).open(self.active_path())?;
+        active.size = 0;
+
+        let (dir, compress, max_files) = (self.dir.clone(), self.compress, self.max_files);
+        std::thread::spawn(move || {
+            if compress {
+                if let Err(e) = gzip(&rolled) {
+                    eprintln!("Failed to compress {}: {}", rolled.display(), e);
+                }
+            }
+            prune(&dir, max_files);
+        });
+        Ok(())
+    }
+}
+
+impl Write for &RollingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        let period = self.period(Utc::now());
+        if active.size > 0 && (period != active.period || active.size + buf.len() as u64 > self.max_bytes) {
+            if let Err(e) = self.roll(&mut active) {
+                eprintln!("Failed to roll log file in {}: {}", self.dir.display(), e);
+            }
+        }
+        active.period = period;
+        let written = active.file.write(buf)?;
+        active.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner()).file.flush()
+    }
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for RollingFile {
+    type Writer = &'a RollingFile;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self
+    }
+}
+
+/// Replace a rolled file with a gzipped copy
+fn gzip(path: &Path) -> std::io::Result<()> {
+    let target = path.with_extension("log.gz");
+    let mut input = File::open(path)?;
+    let mut encoder = flate2::write::GzEncoder::new(File::create(&target)?, flate2::Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    std::fs::remove_file(path)
+}
+
+/// Delete the oldest rolled files beyond `keep`
+fn prune(dir: &Path, keep: usize) {
+    let prefix = format!("{}.", LOG_STEM);
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut rolled: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            name.starts_with(&prefix) && (name.ends_with(".log") || name.ends_with(".log.gz")) && name != format!("{}.log", LOG_STEM)
+        })
+        .collect();
+    // Timestamps in the names sort chronologically
+    rolled.sort();
+    let excess = rolled.len().saturating_sub(keep);
+    for path in rolled.into_iter().take(excess) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// ============================================================================
+// Per-job capture
+// ============================================================================
+
+fn captured() -> &'static Mutex<HashMap<String, VecDeque<String>>> {
+    static CAPTURED: OnceLock<Mutex<HashMap<String, VecDeque<String>>>> = OnceLock::new();
+    CAPTURED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Remove and return the lines captured for a job
+pub fn take_job_log(job_id: &str) -> Vec<String> {
+    captured().lock().unwrap().remove(job_id).map(Vec::from).unwrap_or_default()
+}
+
+/// Save a failed job's log next to the log files, when file logging is on
+pub fn persist_job_log(job_id: &str, lines: &[String]) {
+    let Some(dir) = crate::config::get().logging.file_dir.as_deref() else { return };
+    let jobs_dir = Path::new(dir).join("jobs");
+    let result = std::fs::create_dir_all(&jobs_dir)
+        .and_then(|_| std::fs::write(jobs_dir.join(format!("{}.log", job_id)), lines.join("\n") + "\n"));
+    if let Err(e) = result {
+        tracing::warn!("Failed to save log of job {}: {}", job_id, e);
+    }
+}
+
+/// Job id recorded on a `job` span
+struct JobTag(String);
+
+/// Keeps the last `lines` events logged inside each job span
+pub struct JobLogLayer {
+    lines: usize,
+}
+
+impl JobLogLayer {
+    pub fn new(lines: usize) -> Self {
+        Self { lines }
+    }
+}
+
+/// Collects an event's message and fields as `message key=value ...`
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+    job_id: Option<String>,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            "job_id" => self.job_id = Some(value.to_string()),
+            name => {
+                let _ = write!(self.fields, " {}={}", name, value);
+            }
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            "job_id" => self.job_id = Some(format!("{:?}", value).trim_matches('"').to_string()),
+            name => {
+                let _ = write!(self.fields, " {}={:?}", name, value);
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for JobLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(job_id), Some(span)) = (visitor.job_id, ctx.span(id)) {
+            span.extensions_mut().insert(JobTag(job_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else { return };
+        let Some(job_id) = scope
+            .into_iter()
+            .find_map(|span| span.extensions().get::<JobTag>().map(|tag| tag.0.clone()))
+        else {
+            return;
+        };
+
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let line = format!(
+            "{} {:>5} {}: {}{}",
+            Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            metadata.level(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        );
+
+        let mut captured = captured().lock().unwrap_or_else(|e| e.into_inner());
+        let lines = captured.entry(job_id).or_default();
+        if lines.len() == self.lines {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn captures_only_events_inside_job_spans() {
+        let subscriber = tracing_subscriber::registry().with(JobLogLayer::new(2));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("outside any job");
+            let span = tracing::info_span!("job", job_id = "job-capture-test");
+            let _entered = span.enter();
+            tracing::info!("first");
+            tracing::warn!(tool = "trim_video", "second");
+            tracing::error!("third");
+        });
+
+        let lines = take_job_log("job-capture-test");
+        assert_eq!(lines.len(), 2, "only the newest lines are kept: {:?}", lines);
+        assert!(lines[0].contains(" WARN ") && lines[0].ends_with("second tool=trim_video"), "{}", lines[0]);
+        assert!(lines[1].ends_with("third"));
+        assert!(take_job_log("job-capture-test").is_empty());
+    }
+
+    #[test]
+    fn rolls_by_size_and_keeps_the_newest_files() {
+        let dir = std::env::temp_dir().join(format!("video_sync_logs_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = LoggingConfig { file_rotation: "size".to_string(), max_file_mb: 1, max_files: 2, compress: false, ..Default::default() };
+        let rolling = RollingFile::open(&config, dir.to_str().unwrap()).unwrap();
+
+        let line = vec![b'x'; 400 * 1024];
+        for _ in 0..8 {
+            (&rolling).write_all(&line).unwrap();
+        }
+        // Pruning runs in the background
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(names.contains(&"video_sync.log".to_string()));
+        assert!(names.len() <= 3, "{:?}", names);
+        assert!(std::fs::metadata(dir.join("video_sync.log")).unwrap().len() <= 1024 * 1024);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
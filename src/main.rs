@@ -3,11 +3,13 @@ use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 
 mod agent;
+mod config; // ⚙️ Layered configuration: config file + env overrides
 mod db;
 mod gemini_client;
 mod claude_client;
 mod voyage_embeddings;
 mod elevenlabs_client; // 🎙️ Eleven Labs TTS, Sound Effects, Music
+mod avatar_client; // 🧑‍🏫 Talking-head presenter generation
 mod youtube_client; // 📺 YouTube Data API v3 for video uploads
 mod youtube_analytics_client; // 📊 YouTube Analytics API for metrics and insights
 mod handlers;
@@ -30,6 +32,10 @@ mod transform;
 mod advanced;
 mod export;
 mod utils;
+mod chaos; // 🌪️ Fault injection for third-party API clients
+mod log_files; // 🗂️ Rolling JSON log files and per-job log capture
+#[cfg(test)]
+mod test_support; // 🧪 Deterministic synthetic media for tests
 
 // AppState now holds the database connection pool, vector database clients, Claude/Gemini client, Pexels client, job manager, and workflow checkpointer
 pub struct AppState {
@@ -41,6 +47,7 @@ pub struct AppState {
     pub voyage_embeddings: Option<voyage_embeddings::VoyageEmbeddings>,
     pub pexels_client: Option<pexels_client::PexelsClient>,
     pub elevenlabs_client: Option<elevenlabs_client::ElevenLabsClient>, // 🎙️ Audio generation
+    pub avatar_client: Option<avatar_client::AvatarClient>, // 🧑‍🏫 Talking-head presenters
     pub youtube_client: Option<youtube_client::YouTubeClient>, // 📺 YouTube integration
     pub youtube_analytics_client: Option<youtube_analytics_client::YouTubeAnalyticsClient>, // 📊 YouTube Analytics
     pub google_oauth_client_id: Option<String>, // Google OAuth client ID
@@ -54,8 +61,17 @@ async fn main() {
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
 
+    // Resolve the config file and env overrides before anything reads them
+    let config = match config::init() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("❌ Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     // Initialize production-grade logging
-    init_logging().expect("Failed to initialize logging");
+    init_logging(config).expect("Failed to initialize logging");
 
     // Ensure outputs, uploads, and downloads directories exist
     if let Err(e) = std::fs::create_dir_all("outputs") {
@@ -83,9 +99,9 @@ async fn main() {
 
     // Initialize Astra DB client if credentials are provided
     let vector_db = match (
-        std::env::var("ASTRA_DB_API_ENDPOINT").ok(),
-        std::env::var("ASTRA_DB_APPLICATION_TOKEN").ok(),
-        std::env::var("ASTRA_DB_KEYSPACE").ok(),
+        config.vector_db.astra_endpoint.clone(),
+        config.vector_db.astra_token.clone(),
+        config.vector_db.astra_keyspace.clone(),
     ) {
         (Some(endpoint), Some(token), Some(keyspace)) => {
             tracing::info!("Initializing Astra DB connection...");
@@ -111,7 +127,7 @@ async fn main() {
     };
 
     // Initialize Claude client if API key is provided
-    let claude_client = match std::env::var("ANTHROPIC_API_KEY").ok() {
+    let claude_client = match config.api_keys.anthropic.clone() {
         Some(api_key) => {
             tracing::info!("Initializing Claude AI client (Sonnet 4.5)...");
             Some(claude_client::ClaudeClient::new(api_key))
@@ -123,7 +139,7 @@ async fn main() {
     };
 
     // Initialize Voyage embeddings for Claude-compatible embeddings
-    let voyage_embeddings = match std::env::var("VOYAGEAI_API_KEY").ok() {
+    let voyage_embeddings = match config.api_keys.voyage.clone() {
         Some(api_key) => {
             tracing::info!("Initializing Voyage AI embeddings...");
             Some(voyage_embeddings::VoyageEmbeddings::new(api_key))
@@ -136,7 +152,7 @@ async fn main() {
     };
 
     // Initialize Gemini client if API key is provided
-    let gemini_client = match std::env::var("GEMINI_API_KEY").ok() {
+    let gemini_client = match config.api_keys.gemini.clone() {
         Some(api_key) => {
             tracing::info!("Initializing Gemini AI client (2.5 Flash)...");
             Some(gemini_client::GeminiClient::new(api_key))
@@ -148,11 +164,10 @@ async fn main() {
     };
 
     // Initialize Qdrant client if API key is provided  
-    let qdrant_client = match std::env::var("QDRANT_API_KEY").ok() {
+    let qdrant_client = match config.api_keys.qdrant.clone() {
         Some(api_key) => {
             tracing::info!("Initializing Qdrant vector database...");
-            let qdrant_url = std::env::var("QDRANT_URL")
-                .unwrap_or_else(|_| "https://18635ac0-f6b3-43b3-9255-54a553f6c2fb.us-west-1-0.aws.cloud.qdrant.io:6334".to_string());
+            let qdrant_url = config.vector_db.qdrant_url.clone();
             
             match qdrant_client::QdrantClient::new(qdrant_url, Some(api_key)).await {
                 Ok(client) => {
@@ -182,7 +197,7 @@ async fn main() {
     };
 
     // Initialize Pexels client if API key is provided
-    let pexels_client = match std::env::var("PEXELS_API_KEY").ok() {
+    let pexels_client = match config.api_keys.pexels.clone() {
         Some(api_key) => {
             tracing::info!("Initializing Pexels stock media client...");
             Some(pexels_client::PexelsClient::new(api_key))
@@ -195,7 +210,7 @@ async fn main() {
     };
 
     // Initialize Eleven Labs client if API key is provided
-    let elevenlabs_client = match std::env::var("ELEVEN_LABS_API_KEY").ok() {
+    let elevenlabs_client = match config.api_keys.elevenlabs.clone() {
         Some(api_key) if !api_key.is_empty() => {
             tracing::info!("Initializing Eleven Labs audio client (TTS, Sound Effects, Music)...");
             Some(elevenlabs_client::ElevenLabsClient::new(api_key))
@@ -207,8 +222,20 @@ async fn main() {
         }
     };
 
+    // Initialize talking-avatar client if API key is provided
+    let avatar_client = match config.api_keys.did.clone() {
+        Some(api_key) if !api_key.is_empty() => {
+            tracing::info!("Initializing talking-avatar client (D-ID)...");
+            Some(avatar_client::AvatarClient::new(api_key))
+        }
+        _ => {
+            tracing::info!("DID_API_KEY not found. Talking-head presenter generation disabled.");
+            None
+        }
+    };
+
     // Initialize YouTube client if API key is provided
-    let youtube_client = match std::env::var("YOUTUBE_API_KEY").ok() {
+    let youtube_client = match config.api_keys.youtube.clone() {
         Some(api_key) if !api_key.is_empty() => {
             tracing::info!("Initializing YouTube Data API client...");
             Some(youtube_client::YouTubeClient::new(api_key))
@@ -230,8 +257,8 @@ async fn main() {
     };
 
     // Load Google OAuth credentials
-    let google_oauth_client_id = std::env::var("GOOGLE_OAUTH_CLIENT_ID").ok();
-    let google_oauth_client_secret = std::env::var("GOOGLE_OAUTH_CLIENT_SECRET").ok();
+    let google_oauth_client_id = config.auth.google_oauth_client_id.clone();
+    let google_oauth_client_secret = config.auth.google_oauth_client_secret.clone();
 
     if google_oauth_client_id.is_some() && google_oauth_client_secret.is_some() {
         tracing::info!("✅ Google OAuth credentials loaded");
@@ -241,6 +268,7 @@ async fn main() {
 
     // Initialize JobManager for background video editing tasks
     let job_manager = Arc::new(jobs::JobManager::new());
+    job_manager.enable_webhooks(db_pool.clone());
     tracing::info!("🎬 Job manager initialized for background video processing");
 
     // Initialize workflow checkpointer
@@ -262,6 +290,7 @@ async fn main() {
         voyage_embeddings,
         pexels_client,
         elevenlabs_client,
+        avatar_client,
         youtube_client,
         youtube_analytics_client,
         google_oauth_client_id,
@@ -270,6 +299,11 @@ async fn main() {
         workflow_checkpointer,
     });
 
+    // Memories are embedded and searched with the model of the active embedding index
+    if let Err(e) = services::EmbeddingIndexService::load_active(&shared_state).await {
+        tracing::warn!("⚠️ Failed to load the active embedding index: {}", e);
+    }
+
     // Build our application with all routes and shared state
     let app = Router::new()
         .merge(handlers::ui::ui_routes())
@@ -280,18 +314,40 @@ async fn main() {
         .merge(handlers::admin::admin_routes())
         .merge(handlers::background_routes::background_routes())
         .merge(handlers::jobs::job_routes()) // 🆕 Job control endpoints
+        .merge(handlers::workflow_templates::workflow_template_routes()) // 🧩 Workflow templates
+        .merge(handlers::workflows::workflow_routes()) // 🧩 Workflow definitions
+        .merge(handlers::projects::project_routes()) // 🎞️ Projects
         .merge(handlers::youtube::youtube_routes()) // 📺 YouTube integration
         .merge(handlers::clipping::clipping_routes()) // 📹 YouTube clipping feature
+        .merge(handlers::batch::batch_routes()) // 📦 Bulk personalization renders
+        .merge(handlers::delivery::delivery_routes()) // 🚚 SFTP/FTP/S3 delivery
+        .merge(handlers::export_presets::export_preset_routes()) // 🎛️ Export presets
+        .merge(handlers::render_snapshots::render_snapshot_routes()) // 🔁 Reproducible renders
+        .merge(handlers::session_defaults::session_defaults_routes()) // ⚙️ Session defaults
+        .merge(handlers::feature_flags::feature_flag_routes()) // 🚩 Feature flags
+        .merge(handlers::trash::trash_routes()) // 🗑️ Trash
+        .merge(handlers::storage::storage_routes()) // 🧊 Cold storage
+        .merge(handlers::notifications::notification_routes()) // 🔔 Notification center
+        .merge(handlers::calendar::calendar_routes()) // 📅 Content calendar
+        .merge(handlers::activity::activity_routes()) // 📰 Activity feed
+        .merge(handlers::sessions::session_routes()) // 🗂️ Bulk session management
+        .merge(handlers::webhooks::webhook_routes()) // 🪝 Job completion webhooks
+        .merge(handlers::review::review_routes()) // 🔗 Client review links
+        .merge(handlers::embed::embed_routes()) // 📺 Embeddable players
+        .merge(handlers::download_links::download_link_routes()) // ⬇️ Client download links
+        .merge(handlers::library::library_routes()) // 📚 Asset library
+        .merge(handlers::ingest::ingest_routes()) // 🔴 RTMP live ingest
         .route("/api/docs", axum::routing::get(api_documentation))
         .route("/api/status", axum::routing::get(api_status))
         // .layer(axum::middleware::from_fn(middleware::frontend_rate_limit::frontend_rate_limit_middleware))
         // .layer(axum::middleware::from_fn(middleware::rate_limit::rate_limit_middleware))
         .layer(axum::middleware::from_fn(middleware::logging::request_logging_middleware))
+        .layer(axum::middleware::from_fn(middleware::request_id::request_id_middleware))
         .layer(CorsLayer::permissive())
         .layer(Extension(shared_state.clone()));
 
     // Start background polling task for YouTube clipping
-    if shared_state.youtube_client.is_some() {
+    if shared_state.youtube_client.is_some() && config.features.channel_polling {
         let polling_state = shared_state.clone();
         tokio::spawn(async move {
             tracing::info!("📹 Starting YouTube channel polling for clipping...");
@@ -309,16 +365,78 @@ async fn main() {
                     Err(e) => tracing::error!("❌ Channel polling failed: {}", e),
                 }
 
-                // Wait 5 minutes before next poll
-                tokio::time::sleep(tokio::time::Duration::from_secs(300)).await;
+                // Clip the videos this poll found before the next one
+                let ran = jobs::clipping_job::run_pending_jobs(polling_state.clone()).await;
+                if ran > 0 {
+                    tracing::info!("🎬 Ran {} clipping job(s)", ran);
+                }
+
+                // Wait before next poll (5 minutes by default)
+                tokio::time::sleep(tokio::time::Duration::from_secs(config.limits.channel_poll_interval_seconds)).await;
             }
         });
     } else {
-        tracing::warn!("YouTube client not available - clipping polling disabled");
+        tracing::warn!("YouTube client not available or channel polling turned off - clipping polling disabled");
+    }
+
+    // Keep connected channels' OAuth tokens fresh and flag broken connections before a publish hits them
+    if shared_state.youtube_client.is_some() && shared_state.google_oauth_client_id.is_some() && config.features.token_health_monitor {
+        let token_state = shared_state.clone();
+        tokio::spawn(async move {
+            tracing::info!("🔑 Starting YouTube token health monitor...");
+            loop {
+                match services::TokenHealthService::check_all(&token_state).await {
+                    Ok(0) => tracing::debug!("✅ All channel tokens healthy"),
+                    Ok(unhealthy) => tracing::warn!("⚠️ {} channel token(s) need attention", unhealthy),
+                    Err(e) => tracing::error!("❌ Token health check failed: {}", e),
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(services::token_health::CHECK_INTERVAL_SECONDS)).await;
+            }
+        });
+    }
+
+    // Permanently remove sessions and outputs that have been in the trash past the retention period
+    let purge_state = shared_state.clone();
+    tokio::spawn(async move {
+        loop {
+            match services::TrashService::purge_expired(&purge_state).await {
+                Ok(summary) if summary.sessions + summary.outputs + summary.failed == 0 => {}
+                Ok(summary) => tracing::info!(
+                    "🗑️ Trash purge: {} sessions, {} outputs removed, {} to retry",
+                    summary.sessions, summary.outputs, summary.failed
+                ),
+                Err(e) => tracing::error!("❌ Trash purge failed: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(services::trash::PURGE_INTERVAL_SECONDS)).await;
+        }
+    });
+
+    // Move outputs nobody has touched in a while to cold storage; they come back on first access
+    if config.features.storage_tiering {
+        let tier_state = shared_state.clone();
+        tokio::spawn(async move {
+            loop {
+                match services::StorageTierService::tier_idle(&tier_state).await {
+                    Ok(summary) if summary.tiered == 0 => {}
+                    Ok(summary) => tracing::info!(
+                        "🧊 Tiered {} idle output(s) to cold storage, {} bytes saved",
+                        summary.tiered, summary.bytes_saved
+                    ),
+                    Err(e) => tracing::error!("❌ Cold storage sweep failed: {}", e),
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(services::storage_tier::SWEEP_INTERVAL_SECONDS)).await;
+            }
+        });
     }
 
+    // Start renders and uploads that were scheduled for later once they fall due
+    tokio::spawn(jobs::scheduled_job::run_scheduler(shared_state.clone()));
+
+    // Retry webhook deliveries as they fall due, including ones a restart interrupted
+    tokio::spawn(services::WebhookService::run_retries(shared_state.db_pool.clone()));
+
     // Run the server with ConnectInfo to provide socket addresses for rate limiting
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
+    let listener = tokio::net::TcpListener::bind((config.server.host.as_str(), config.server.port))
         .await
         .unwrap();
     tracing::info!("listening on {}", listener.local_addr().unwrap());
@@ -328,12 +446,12 @@ async fn main() {
 }
 
 // Production-grade logging configuration
-fn init_logging() -> Result<(), Box<dyn std::error::Error>> {
+fn init_logging(config: &config::AppConfig) -> Result<(), Box<dyn std::error::Error>> {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, fmt, Layer};
     
-    // Get log level from environment or default to INFO for production
-    let log_level = std::env::var("RUST_LOG")
-        .unwrap_or_else(|_| {
+    // Get log level from the configuration or default to INFO for production
+    let log_level = config.logging.level.clone()
+        .unwrap_or_else(|| {
             if cfg!(debug_assertions) {
                 "debug,video_editor=trace,sqlx=info,reqwest=info,hyper=info,tower=info".to_string()
             } else {
@@ -345,7 +463,7 @@ fn init_logging() -> Result<(), Box<dyn std::error::Error>> {
         .or_else(|_| EnvFilter::try_new(&log_level))?;
     
     // Configure structured logging for production
-    let fmt_layer = if std::env::var("LOG_FORMAT").as_deref() == Ok("json") {
+    let fmt_layer = if config.logging.format == "json" {
         // JSON logging for production (easier for log aggregation)
         fmt::layer()
             .json()
@@ -366,9 +484,29 @@ fn init_logging() -> Result<(), Box<dyn std::error::Error>> {
 .boxed()
     };
     
+    // Rolling JSON files alongside stdout, when a log directory is configured
+    let file_layer = match config.logging.file_dir.as_deref() {
+        Some(dir) => Some(
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_target(true)
+                .with_writer(log_files::RollingFile::open(&config.logging, dir)?)
+                .boxed(),
+        ),
+        None => None,
+    };
+
+    // Recent lines of each job, attached to jobs that fail
+    let job_log_layer = (config.logging.job_capture_lines > 0)
+        .then(|| log_files::JobLogLayer::new(config.logging.job_capture_lines));
+
     tracing_subscriber::registry()
         .with(env_filter)
         .with(fmt_layer)
+        .with(file_layer)
+        .with(job_log_layer)
         .init();
     
     // Log startup information
@@ -376,12 +514,20 @@ fn init_logging() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Version: {}", env!("CARGO_PKG_VERSION"));
     tracing::info!("Build mode: {}", if cfg!(debug_assertions) { "development" } else { "production" });
     tracing::info!("Log level: {}", log_level);
+    if let Some(dir) = &config.logging.file_dir {
+        tracing::info!("🗂️ Writing JSON logs to {}/video_sync.log ({} rotation, {} files kept)", dir, config.logging.file_rotation, config.logging.max_files);
+    }
     
-    // Log environment configuration
-    let gemini_configured = std::env::var("GEMINI_API_KEY").is_ok();
-    let qdrant_configured = std::env::var("QDRANT_API_KEY").is_ok();
-    let astra_configured = std::env::var("ASTRA_DB_API_ENDPOINT").is_ok() && std::env::var("ASTRA_DB_APPLICATION_TOKEN").is_ok();
-    let db_configured = std::env::var("DATABASE_URL").is_ok();
+    // Log where the configuration came from and what it enables
+    let sources = config::sources();
+    tracing::info!("⚙️ Configuration: {} + {} environment override(s)",
+        sources.file.as_deref().unwrap_or("defaults"),
+        sources.env_overrides.len()
+    );
+    let gemini_configured = config.api_keys.gemini.is_some();
+    let qdrant_configured = config.api_keys.qdrant.is_some();
+    let astra_configured = config.vector_db.astra_endpoint.is_some() && config.vector_db.astra_token.is_some();
+    let db_configured = config.database.url.is_some();
     
     tracing::info!("Configuration - Database: {}, Gemini AI: {}, Qdrant: {}, AstraDB: {}", 
         if db_configured { "✅" } else { "❌" },
@@ -410,6 +556,7 @@ async fn api_documentation() -> axum::response::Html<String> {
         .get { background: #28a745; }
         .post { background: #007bff; }
         .delete { background: #dc3545; }
+        .put { background: #fd7e14; }
         .websocket { background: #6f42c1; }
         code { background: #e9ecef; padding: 0.2rem 0.4rem; border-radius: 3px; }
         .section { margin: 2rem 0; }
@@ -459,7 +606,9 @@ async fn api_documentation() -> axum::response::Html<String> {
             <strong>/ws</strong><br>
             Real-time chat with AI video editing agent<br>
             <strong>Usage:</strong> Connect via WebSocket, send text messages, receive AI responses<br>
-            <strong>Features:</strong> Access to 25+ video editing tools, context memory, file references
+            <strong>Features:</strong> Access to 25+ video editing tools, context memory, file references<br>
+            <strong>Slash-commands:</strong> <code>/trim 0:10 0:30</code>, <code>/rotate 90</code>, <code>/flip h</code>, <code>/speed 2</code>, <code>/volume 0.5</code>, <code>/compress medium</code>, <code>/thumbnail 25%</code>, <code>/audio mp3</code>, <code>/export youtube</code> run the tool directly, without an AI turn, on the newest output (or a file named last). <code>/help</code> lists them<br>
+            <strong>Output cards:</strong> <code>message</code> events that produced or mention outputs carry <code>outputs</code>: <code>[{file_id, file_name, kind, size_bytes, download_url, stream_url, poster_url}]</code> for rendering previews
         </div>
         
         <div class="endpoint">
@@ -485,7 +634,8 @@ async fn api_documentation() -> axum::response::Html<String> {
             <span class="method post">POST</span>
             <strong>/upload/session/:session_uuid</strong> 🔒<br>
             Upload files to specific chat session<br>
-            <strong>Body:</strong> multipart/form-data with file(s)
+            <strong>Body:</strong> multipart/form-data with file(s)<br>
+            <strong>Duplicates:</strong> a file identical to one you already uploaded reuses the stored copy (status <code>duplicate_reused</code>); pass <code>?on_duplicate=keep</code> to store it anyway
         </div>
         
         <div class="endpoint">
@@ -495,6 +645,19 @@ async fn api_documentation() -> axum::response::Html<String> {
             <strong>Returns:</strong> Array of file metadata
         </div>
         
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/files/duplicates</strong> 🔒<br>
+            Uploads detected as copies of earlier ones (identical bytes, or the same footage re-encoded/resized via perceptual hash)<br>
+            <strong>Returns:</strong> duplicates with their originals and <code>reclaimable_bytes</code>
+        </div>
+        
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/files/:file_id/reuse-original</strong> 🔒<br>
+            Point a detected duplicate at the original file and delete its redundant copy
+        </div>
+        
         <div class="endpoint">
             <span class="method get">GET</span>
             <strong>/upload/status/:file_id</strong><br>
@@ -507,197 +670,1151 @@ async fn api_documentation() -> axum::response::Html<String> {
             <strong>/upload/form</strong><br>
             HTML upload form for testing<br>
             <strong>Returns:</strong> Interactive file upload interface
+            
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/:id/versions</strong> 🔒<br>
+            Version history of an output (re-renders keep earlier versions as <code>name.vN.ext</code>)<br>
+            <strong>Returns:</strong> Versions oldest first, with parent links and stream/download URLs
         </div>
     </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/:id/versions</strong> 🔒<br>
+            Version history of an output (re-renders keep earlier versions as <code>name.vN.ext</code>)<br>
+            <strong>Returns:</strong> Versions oldest first, with parent links and stream/download URLs
+        </div>
 
-    <div class="section">
-        <h2>🎬 Video Editing Tools (via AI Agent)</h2>
-        <p>The following tools are available through the WebSocket chat interface. Send natural language requests to the AI agent:</p>
-
-        <h3>🎙️ Audio Generation (ElevenLabs)</h3>
-        <ul>
-            <li><strong>generate_text_to_speech</strong> - Generate professional voiceovers with 17+ voices (Rachel, Drew, Adam, Bella, etc.)</li>
-            <li><strong>generate_sound_effect</strong> - Create custom sound effects from text descriptions (0.5-30 seconds)</li>
-            <li><strong>generate_music</strong> - Generate studio-grade background music (10-300 seconds, any genre)</li>
-            <li><strong>add_voiceover_to_video</strong> - One-step tool: generates voiceover + adds to video automatically</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/:id/report</strong> 🔒<br>
+            Download the output's render report (<code>name.ext.report.json</code>)<br>
+            <strong>Returns:</strong> Input hashes, exact FFmpeg commands, encoders, timings, warnings, environment, and what changed since the parent version
+        </div>
 
-        <h3>Core Operations</h3>
-        <ul>
-            <li><strong>trim_video</strong> - Trim video to specific time range</li>
-            <li><strong>merge_videos</strong> - Combine multiple videos</li>
-            <li><strong>split_video</strong> - Split video into segments</li>
-            <li><strong>analyze_video</strong> - Get video metadata and properties</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/:id/stats</strong> 🔒<br>
+            Play and download counts for an output, split by source (app, review, embed, link) and viewer country<br>
+            <strong>Returns:</strong> <code>streams</code>, <code>downloads</code>, <code>last_viewed_at</code>, <code>countries</code>, <code>sources</code>
+        </div>
 
-        <h3>Transform</h3>
-        <ul>
-            <li><strong>resize_video</strong> - Change video dimensions</li>
-            <li><strong>crop_video</strong> - Crop video to specific area</li>
-            <li><strong>rotate_video</strong> - Rotate video by degrees</li>
-            <li><strong>adjust_speed</strong> - Change playback speed</li>
-            <li><strong>flip_video</strong> - Flip horizontal/vertical</li>
-            <li><strong>scale_video</strong> - Scale by factor</li>
-            <li><strong>stabilize_video</strong> - Video stabilization</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/poster/:file_id</strong><br>
+            Poster frame of a video output, grabbed with FFmpeg on first request and cached<br>
+            <strong>Returns:</strong> 480px-wide JPEG
+        </div>
 
-        <h3>Visual Effects</h3>
-        <ul>
-            <li><strong>add_text_overlay</strong> - Add text to video</li>
-            <li><strong>add_overlay</strong> - Add image/video overlay</li>
-            <li><strong>apply_filter</strong> - Apply visual filters</li>
-            <li><strong>adjust_color</strong> - Color correction</li>
-            <li><strong>add_subtitles</strong> - Add subtitle files</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/stats?limit=50</strong> 🔒<br>
+            Dashboard summary: your outputs ranked by plays and downloads, with totals<br>
+            <strong>Note:</strong> Countries come from the CDN/proxy geo header (e.g. <code>CF-IPCountry</code>); IPs are not stored
+        </div>
 
-        <h3>Audio Processing</h3>
-        <ul>
-            <li><strong>extract_audio</strong> - Extract audio track</li>
-            <li><strong>add_audio</strong> - Add background music</li>
-            <li><strong>adjust_volume</strong> - Volume control</li>
-            <li><strong>fade_audio</strong> - Fade in/out effects</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/:id/metadata</strong> 🔒<br>
+            Title, description and tags generated from an output's content after it rendered, to pre-fill a YouTube upload<br>
+            <strong>Note:</strong> YouTube uploads of an output fill a blank <code>title</code> and missing <code>description</code>/<code>tags</code> from these
+        </div>
 
-        <h3>Export & Compression</h3>
-        <ul>
-            <li><strong>convert_format</strong> - Change video format</li>
-            <li><strong>compress_video</strong> - Reduce file size</li>
-            <li><strong>export_for_platform</strong> - Optimize for social media</li>
-            <li><strong>create_thumbnail</strong> - Generate thumbnails</li>
-            <li><strong>extract_frames</strong> - Export individual frames</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/search?q=beach+sunset&limit=50</strong> 🔒<br>
+            Search your outputs by generated title, description and tags (and file name); every word must match
+        </div>
 
-        <h3>Advanced</h3>
-        <ul>
-            <li><strong>picture_in_picture</strong> - PiP effects</li>
-            <li><strong>chroma_key</strong> - Green screen effects</li>
-            <li><strong>split_screen</strong> - Multi-video layouts</li>
-        </ul>
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/outputs/:id/transcode?profile=mobile-480p</strong> 🔒<br>
+            Smaller rendition of an output for phones and slow connections, transcoded once and cached (profiles: mobile-240p, mobile-360p, mobile-480p, mobile-720p)<br>
+            <strong>Returns:</strong> 202 while transcoding, then <code>stream_url</code>; <code>original: true</code> when the output already fits the profile
+        </div>
     </div>
 
     <div class="section">
-        <h2>🌐 Web Interface</h2>
-        
-        <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/</strong><br>
-            Landing page with application overview
-        </div>
-        
+        <h2>🔴 Live Ingest (RTMP)</h2>
+
         <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/login</strong><br>
-            User login page
+            <span class="method post">POST</span>
+            <strong>/api/ingest</strong> 🔒<br>
+            Create a stream key that records into a chat session<br>
+            <strong>Body:</strong> <code>{"session_uuid", "title", "auto_clip": true, "clip_instructions"}</code><br>
+            <strong>Returns:</strong> <code>rtmp_url</code> and <code>stream_key</code> for OBS or any RTMP encoder
         </div>
-        
+
         <div class="endpoint">
             <span class="method get">GET</span>
-            <strong>/signup</strong><br>
-            User registration page
+            <strong>/api/ingest</strong> &nbsp;
+            <span class="method delete">DELETE</span>
+            <strong>/api/ingest/:id</strong> 🔒<br>
+            List your streams (idle, live, processing, ended, failed) or revoke a stream key
         </div>
-        
+
         <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/dashboard</strong><br>
-            User dashboard (requires login)
+            <span class="method post">POST</span>
+            <strong>/api/ingest/:id/clip</strong> 🔒<br>
+            Clip the last N seconds of a stream that is still live, from its HLS segments<br>
+            <strong>Body:</strong> <code>{"seconds": 60, "output_file": "highlight.mp4"}</code>
         </div>
-        
+
         <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/chat</strong><br>
-            Chat interface with AI agent
+            <span class="method post">POST</span>
+            <strong>/api/ingest/rtmp/on_publish</strong>, <strong>on_publish_done</strong>, <strong>on_record_done</strong><br>
+            nginx-rtmp callbacks; append <code>?secret=</code> with <code>RTMP_CALLBACK_SECRET</code>, which must be set (callbacks are refused without it).
+            When a recording finishes it is added to the session's files and, with <code>auto_clip</code>, a clipping job starts immediately
         </div>
     </div>
 
     <div class="section">
-        <h2>🛡️ Admin Panel (Staff/Superuser Only)</h2>
-        
+        <h2>📚 Asset Library</h2>
+
         <div class="endpoint">
             <span class="method get">GET</span>
-            <strong>/admin/login</strong><br>
-            Admin login page
+            <strong>/api/library?q=&amp;tag=&amp;kind=&amp;has_faces=</strong> 🔒<br>
+            Search your library by text, tag and kind (logo, intro, outro, music, clip, image, sfx, other).
+            Text also matches automatic vision tags (objects, scenes, colors), e.g. <code>q=drone shot over water</code>
         </div>
-        
+
         <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/admin/dashboard</strong><br>
-            Admin dashboard with system statistics
+            <span class="method post">POST</span>
+            <strong>/api/library</strong> 🔒<br>
+            Upload an asset<br>
+            <strong>Body:</strong> multipart with <code>file</code>, <code>name</code>, optional <code>kind</code>, <code>tags</code> (comma separated), <code>description</code>
         </div>
-        
-        <h3>User Management</h3>
+
         <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/api/admin/stats</strong> 🔒<br>
-            Get system statistics (users, files, sessions)<br>
-            <strong>Requires:</strong> Admin privileges
+            <span class="method post">POST</span>
+            <strong>/api/library/import</strong> 🔒<br>
+            Save an existing upload or output to the library<br>
+            <strong>Body:</strong> <code>{"file_path": "uploads/...", "name", "kind", "tags": [], "description"}</code>
         </div>
-        
+
         <div class="endpoint">
             <span class="method get">GET</span>
-            <strong>/api/admin/users</strong> 🔒<br>
-            List all users with pagination and search<br>
-            <strong>Query params:</strong> page, limit, search<br>
-            <strong>Requires:</strong> Admin privileges
+            <strong>/api/library/:id</strong> &nbsp;
+            <span class="method patch">PATCH</span> &nbsp;
+            <span class="method delete">DELETE</span> 🔒<br>
+            Read, rename/retag, or remove an asset. <code>GET /api/library/:id/file</code> streams the file
         </div>
-        
-        <h3>Email Whitelist Management</h3>
+
+        <p>In chat, the agent can use any asset from any session by passing <code>library:&lt;name&gt;</code> as a file argument.</p>
+        <p>New library assets and uploaded images/videos are tagged in the background by a vision model; tags appear as <code>visual_tags</code> on assets and in <code>/upload/status/:file_id</code>.</p>
+    </div>
+
+    <div class="section">
+        <h2>📦 Bulk Personalization</h2>
+
         <div class="endpoint">
-            <span class="method get">GET</span>
-            <strong>/api/admin/whitelist/status</strong> 🔒<br>
-            Get whitelist status and email count<br>
-            <strong>Returns:</strong> <code>{"enabled": boolean, "total_emails": number}</code>
+            <span class="method post">POST</span>
+            <strong>/api/batch/render</strong> 🔒<br>
+            Render one personalized video per CSV row as background jobs<br>
+            <strong>Body:</strong> multipart/form-data with <code>csv</code> (file), <code>template</code> (JSON: <code>{"steps": [{"tool": "...", "args": {...}}], "output_file": "{{batch_dir}}/{{row}}.mp4", "export_preset": "name", "captions_file": "..."}</code>), optional <code>session_id</code> and <code>concurrency</code><br>
+            <strong>Placeholders:</strong> <code>{{column}}</code> for any CSV column, plus <code>{{row}}</code> and <code>{{batch_dir}}</code>
         </div>
-        
+
         <div class="endpoint">
             <span class="method post">POST</span>
-            <strong>/api/admin/whitelist/toggle</strong> 🔒<br>
-            Enable/disable email whitelist restriction<br>
-            <strong>Body:</strong> <code>{"enabled": boolean}</code><br>
-            <strong>Note:</strong> When enabled, only whitelisted emails can register/login
+            <strong>/api/batch/process</strong> 🔒<br>
+            Apply one edit (watermark, resize, volume...) to every video uploaded to a session as a single job, with per-file progress over the session's WebSocket<br>
+            <strong>Body:</strong> <code>{"session_id": "...", "tool": "resize_video", "args": {"width": 1920, "height": 1080}, "files": ["uploads/..."]}</code> (<code>files</code> optional, a subset of the session's videos; leave <code>input_file</code>/<code>output_file</code> out of <code>args</code>)<br>
+            <strong>Returns:</strong> <code>job_id</code>; the finished job lists each output plus <code>results.zip</code> and <code>manifest.json</code>
         </div>
-        
+
         <div class="endpoint">
             <span class="method get">GET</span>
-            <strong>/api/admin/whitelist/emails</strong> 🔒<br>
-            List all whitelisted email addresses<br>
-            <strong>Returns:</strong> Array of whitelisted email objects
+            <strong>/api/batch/:job_id/manifest</strong> 🔒<br>
+            Combined results manifest (per-row or per-file status, output file, error)<br>
+            <strong>Returns:</strong> Manifest JSON once the batch has finished
         </div>
-        
+    </div>
+
+    <div class="section">
+        <h2>🚚 Delivery</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/delivery/targets</strong> 🔒<br>
+            List your delivery targets (secrets are never returned)
+        </div>
+
         <div class="endpoint">
             <span class="method post">POST</span>
-            <strong>/api/admin/whitelist/emails</strong> 🔒<br>
-            Add email to whitelist<br>
-            <strong>Body:</strong> <code>{"email": "user@example.com"}</code>
+            <strong>/api/delivery/targets</strong> 🔒<br>
+            Create or replace a delivery target<br>
+            <strong>Body:</strong> <code>{"name", "protocol": "sftp|ftp|s3", "host", "port", "username", "secret", "remote_path", "region", "endpoint_url"}</code><br>
+            For S3, <code>host</code> is the bucket, <code>username</code>/<code>secret</code> are the access key pair
         </div>
-        
+
         <div class="endpoint">
             <span class="method delete">DELETE</span>
-            <strong>/api/admin/whitelist/emails/:id</strong> 🔒<br>
-            Remove email from whitelist<br>
-            <strong>Params:</strong> id (whitelist entry ID)
+            <strong>/api/delivery/targets/:id</strong> 🔒<br>
+            Remove a delivery target
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/delivery/deliver</strong> 🔒<br>
+            Push an output to a target with checksum verification<br>
+            <strong>Body:</strong> <code>{"file_path": "outputs/...", "target": "name"}</code><br>
+            <strong>Returns:</strong> Delivery receipt (size, SHA-256, MD5, verification, remote URL)
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/delivery/receipts</strong> 🔒<br>
+            Your 100 most recent delivery receipts
         </div>
     </div>
 
     <div class="section">
-        <h2>⚙️ System</h2>
-        
+        <h2>🪝 Webhooks</h2>
+
         <div class="endpoint">
             <span class="method get">GET</span>
-            <strong>/api/status</strong><br>
-            API health check and system status
+            <strong>/api/webhooks</strong> 🔒<br>
+            List your webhooks (secrets are never returned)
         </div>
-        
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/webhooks</strong> 🔒<br>
+            Get a signed JSON POST whenever one of your background jobs completes or fails<br>
+            <strong>Body:</strong> <code>{"url": "https://...", "secret": "optional, 16+ chars", "events": ["job.completed", "job.failed"]}</code>; the URL must resolve to a public address<br>
+            <strong>Returns:</strong> The webhook and its <code>secret</code> (shown only here). Each call carries <code>X-VideoSync-Timestamp</code> and <code>X-VideoSync-Signature: sha256=&lt;hex HMAC-SHA256 of "timestamp.body"&gt;</code>; non-2xx answers are retried up to 5 times with backoff
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/webhooks/:id</strong> 🔒<br>
+            Remove a webhook
+        </div>
+
         <div class="endpoint">
             <span class="method get">GET</span>
-            <strong>/api/docs</strong><br>
-            This documentation page
+            <strong>/api/webhooks/deliveries</strong> 🔒<br>
+            Your 100 most recent webhook deliveries (status, attempts, last response code and error)
         </div>
     </div>
 
     <div class="section">
-        <h2>🔧 Rate Limits</h2>
-        <ul>
-            <li><strong>General API:</strong> 100 requests per minute per IP</li>
+        <h2>🎛️ Export Presets</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/export-presets</strong> 🔒<br>
+            List your export presets and the built-in platform preset names
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/export-presets</strong> 🔒<br>
+            Create or replace a named export preset<br>
+            <strong>Body:</strong> <code>{"name", "container": "mp4|mov|mkv|webm", "video_codec", "audio_codec", "crf" | "video_bitrate_kbps", "audio_bitrate_kbps", "width", "height", "fps", "loudness_target", "burn_captions", "description"}</code><br>
+            A preset named after a platform (e.g. <code>youtube</code>) overrides the built-in one
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span> <span class="method patch">PATCH</span> <span class="method delete">DELETE</span>
+            <strong>/api/export-presets/:id</strong> 🔒<br>
+            Read, partially update, or remove a preset. Setting <code>crf</code> clears <code>video_bitrate_kbps</code> and vice versa
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>⚙️ Session Defaults</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span> <span class="method put">PUT</span>
+            <strong>/api/sessions/:session_uuid/defaults</strong> 🔒<br>
+            Resolution, fps, voice and caption style the agent fills into every later tool call that leaves them out (also set by the <code>set_session_defaults</code> tool)<br>
+            <strong>Body:</strong> <code>{"resolution": "4k", "fps": 30, "voice": "Adam", "caption_style": "bold", "clear": ["fps"]}</code>. Fields merge into the current defaults; <code>clear</code> takes field names or <code>"all"</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🗑️ Trash</h2>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/chat/sessions/:session_id</strong> 🔒<br>
+            Move a chat session, with its messages, uploads and outputs, to the trash
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/outputs/:id</strong> 🔒<br>
+            Move one output to the trash
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/trash</strong> 🔒<br>
+            Trashed sessions and outputs with the time each will be purged. Items stay restorable for 30 days, then their files, vectors and records are removed for good
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/chat/sessions/:session_id/restore</strong> 🔒 &nbsp;
+            <span class="method post">POST</span>
+            <strong>/api/outputs/:id/restore</strong> 🔒<br>
+            Take an item out of the trash; restoring an output also restores its session if that was trashed
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🧊 Cold Storage</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/storage/policy</strong> 🔒 &nbsp;
+            <span class="method put">PUT</span>
+            <strong>/api/storage/policy</strong> 🔒<br>
+            When your outputs move to cold storage: after how many untouched days, whether they're gzipped, or never. Unset fields follow the server's <code>[storage]</code> settings<br>
+            <strong>Body:</strong> <code>{"cold_after_days": 60, "compress": true, "enabled": true, "clear": ["compress"]}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/storage/cold</strong> 🔒<br>
+            Outputs currently in cold storage and the bytes compression saved
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/outputs/:id/rehydrate</strong> 🔒<br>
+            Bring an output back from cold storage ahead of time<br>
+            <strong>Note:</strong> Downloads, streams, review and embed links and tools rehydrate a cold output on first use, so its links keep working
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🔔 Notifications</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/notifications</strong> 🔒<br>
+            Things that need your attention, newest first: finished or failed jobs (<code>job_completed</code>, <code>job_failed</code>), clips waiting to be reviewed and posted (<code>clip_review_needed</code>), AI token budget warnings (<code>token_budget_warning</code>, <code>token_budget_exhausted</code>) and YouTube connection problems (<code>youtube_token_unhealthy</code>)<br>
+            <strong>Query:</strong> <code>unread=true</code>, <code>limit</code> (default 50, at most 100), <code>before</code> (a notification id, to page back)<br>
+            <strong>Returns:</strong> <code>notifications</code> and <code>unread_count</code><br>
+            <strong>Push:</strong> new notifications also arrive on the user's open chat WebSockets as <code>{"type": "notification", "notification": {...}}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/notifications/:id/read</strong> 🔒 &nbsp;
+            <span class="method post">POST</span>
+            <strong>/api/notifications/:id/unread</strong> 🔒 &nbsp;
+            <span class="method post">POST</span>
+            <strong>/api/notifications/read-all</strong> 🔒<br>
+            Mark one notification read or unread, or everything read; returns the new <code>unread_count</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>📅 Content Calendar</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/schedule</strong> 🔒<br>
+            Everything lined up in a time range, in time order: scheduled jobs (<code>scheduled_job</code>), YouTube uploads set to publish at a time (<code>youtube_publish</code>), the clipping pipeline's planned polling runs (<code>clipping_run</code>) and clips it posted (<code>clip_post</code>)<br>
+            <strong>Query:</strong> <code>from</code> and <code>to</code> (RFC 3339; default now to 30 days ahead, at most 92 days), <code>kinds</code> (comma-separated, to include only some)<br>
+            <strong>Returns:</strong> <code>entries</code> (<code>kind</code>, <code>at</code>, <code>title</code>, <code>status</code>, <code>source_id</code>, <code>details</code>) and <code>counts</code> per kind
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>📰 Activity Feed</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/activity</strong> 🔒<br>
+            What happened in your workspace, newest first, from its audit log: uploads (<code>upload</code>), finished renders and jobs (<code>render</code>), YouTube uploads and posted clips (<code>publish</code>), clips approved for posting (<code>approval</code>) and anything that failed (<code>failure</code>)<br>
+            <strong>Query:</strong> <code>category</code> (comma-separated), <code>session_id</code>, <code>since</code> (RFC 3339, e.g. when you were last here), <code>limit</code> (default 50, at most 100), <code>before</code> (a cursor)<br>
+            <strong>Returns:</strong> <code>events</code> (<code>kind</code>, <code>category</code>, <code>summary</code>, <code>session_id</code>, <code>data</code>, <code>created_at</code>) and <code>next_cursor</code>, to pass as <code>before</code> for the next page (null on the last page)
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🗂️ Session Management</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/chat/sessions/bulk</strong> 🔒<br>
+            Archive, unarchive or delete (move to the trash) many sessions at once<br>
+            <strong>Body:</strong> <code>{"action": "archive", "session_ids": ["..."], "updated_before": "2026-01-01T00:00:00Z"}</code>. Give either selector or both; at most 1000 ids<br>
+            Archived sessions are left out of <code>/api/chat/recent</code> and <code>/api/chat/all</code>; list them with <code>/api/chat/all?archived=true</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/chat/sessions/move</strong> 🔒<br>
+            Move uploads and outputs into another session<br>
+            <strong>Body:</strong> <code>{"target_session_id": "...", "file_ids": ["..."], "output_ids": [1, 2]}</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>📡 Job Events</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/jobs/:job_id/events</strong><br>
+            Follow a background job without a WebSocket: a Server-Sent Events stream that opens with a <code>status</code> event and then sends each progress update as a <code>progress</code> event<br>
+            <strong>Note:</strong> The stream ends once the job completes, fails or is cancelled. Try it with <code>curl -N /api/jobs/&lt;job_id&gt;/events</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🧩 Job Graphs</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/jobs/graph</strong> 🔒<br>
+            Submit background editing jobs that depend on each other; each starts once everything it <code>depends_on</code> has completed, with their results in its prompt<br>
+            <strong>Body:</strong> <code>{"session_id": "...", "jobs": [{"key": "trim", "task": "Trim the first 10 seconds"}, {"key": "voiceover", "task": "Add a voiceover", "depends_on": ["trim"]}]}</code> (at most 20 jobs, no cycles), optional <code>"priority": "low" | "normal" | "high"</code><br>
+            <strong>Note:</strong> If a job fails or is cancelled, the jobs depending on it fail without running. Waiting jobs get a worker highest priority first: edits started from chat run as high, batch renders and stream clipping as low
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/jobs/graph/:graph_id</strong><br>
+            Combined progress of a graph and the status of each job<br>
+            <strong>Returns:</strong> <code>progress</code> (<code>completed</code>, <code>failed</code>, <code>running</code>, <code>waiting</code>, <code>progress_percent</code>, <code>finished</code>) and <code>jobs</code>. The chat WebSocket also gets a <code>progress</code> event with the same counts whenever a job in the graph finishes
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🧩 Workflow Templates</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/workflow-templates</strong> 🔒<br>
+            Built-in templates (<code>podcast-to-shorts</code>, <code>footage-to-youtube</code>; <code>"builtin": true</code>) and your own, each with its <code>parameters</code> and <code>jobs</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/workflow-templates</strong> 🔒<br>
+            Save a job graph as a template; tasks refer to parameters as <code>{{name}}</code><br>
+            <strong>Body:</strong> <code>{"name": "trailer", "description": "...", "parameters": [{"name": "source", "description": "..."}, {"name": "length", "default": "30"}], "jobs": [{"key": "cut", "task": "Cut a {{length}} second trailer from {{source}}"}]}</code><br>
+            <strong>Note:</strong> A parameter without a <code>default</code> is required. Every slot must be a declared parameter and the jobs must form a valid graph (at most 20 jobs, no cycles)
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/workflow-templates/:id</strong> 🔒<br>
+            One template
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/workflow-templates/:id</strong> 🔒<br>
+            Delete one of your templates (built-in templates can't be deleted)
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/workflow-templates/:id/instantiate</strong> 🔒<br>
+            Preview the job graph a template makes, without running it<br>
+            <strong>Body:</strong> <code>{"params": {"episode": "uploads/ep12.mp4"}}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/workflow-templates/:id/run</strong> 🔒<br>
+            Fill in the template and start it as a job graph in a session; returns the same <code>graph_id</code> and <code>jobs</code> as <code>POST /api/jobs/graph</code><br>
+            <strong>Body:</strong> <code>{"session_id": "...", "params": {"episode": "uploads/ep12.mp4", "clip_count": "5"}}</code>, optional <code>"priority"</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🧩 Workflow Definitions</h2>
+        <p>A custom pipeline written in YAML or JSON: <code>nodes</code> each call one tool with <code>args</code>, <code>edges</code> connect them. Arguments can use <code>{{params.name}}</code> (a declared parameter) and <code>{{nodes.id.field}}</code> (an earlier node's argument such as <code>output_file</code>, its <code>output</code> text or <code>success</code>). An edge runs <code>always</code> (default), or <code>when</code> its node has <code>success</code>, <code>failure</code> or <code>{"output_contains": "..."}</code>. A failed node is retried <code>retries</code> times (default 2), each attempt limited to <code>timeout_seconds</code> (default 300) and spaced by <code>retry_delay_seconds</code> (doubling), then replaced by its <code>fallback</code> node if it names one, e.g. a local voiceover when ElevenLabs is down. Give an edited definition a higher <code>version</code>: unfinished runs stay on the version they started from, unless the new one lists <code>migrations</code> such as <code>{"from": 1, "renamed": {"trim": "cut"}, "rerun": ["vertical"]}</code> to carry them over by node id.</p>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/workflows/validate</strong> 🔒<br>
+            Check a definition without running it; an invalid one gets a 400 listing every problem (unknown tools or arguments, undeclared parameters, missing nodes, unreachable nodes)<br>
+            <strong>Body:</strong> <code>{"definition": "name: shorts\nparams:\n  episode: {}\nnodes:\n  - id: trim\n    tool: trim_video\n    args: {input_file: \"{{params.episode}}\", output_file: trimmed.mp4, start_seconds: 0, end_seconds: 60}"}</code> (YAML or JSON text, or a JSON object)
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/workflows/run</strong> 🔒<br>
+            Run a definition on a session in the background; returns <code>workflow_id</code>. Running the same workflow (by name) with the same parameters again resumes an unfinished run (<code>"resumed": true</code>) on the <code>version</code> it continues with<br>
+            <strong>Body:</strong> <code>{"session_id": "...", "definition": ..., "params": {"episode": "uploads/ep12.mp4"}}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/workflows/:workflow_id</strong> 🔒<br>
+            Progress of a run: <code>status</code>, <code>definition_version</code>, <code>completed_nodes</code>, each node's result in <code>node_outputs</code>, its failed attempts and fallback in <code>node_attempts</code> and its start, finish and duration in <code>node_timings</code>. <code>graph</code> lists every node (with its status: pending, running, retrying, completed, fell_back, failed or skipped) and edge of the definition, for drawing the run
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/workflows/:workflow_id/diagram</strong> 🔒<br>
+            The run as a diagram with nodes colored by status, to render a live execution view<br>
+            <strong>Query:</strong> <code>?format=mermaid</code> (default, a Mermaid flowchart) or <code>?format=dot</code> (Graphviz)
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🎞️ Projects</h2>
+        <p>A project is a source video plus an ordered edit decision list: each operation is a single-input tool (<code>trim_video</code>, <code>adjust_color</code>, <code>crop_video</code>, ...) with its arguments but no <code>input_file</code>/<code>output_file</code>. Edits are recorded as revisions and nothing is rendered until export, so any change can be undone or redone and a revision re-exported at another quality without redoing the edit. The agent works on projects through <code>create_project</code>, <code>edit_project</code> and <code>export_project</code>.</p>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/projects</strong> 🔒<br>
+            Your projects, newest edit first<br>
+            <strong>Query:</strong> <code>?session_id=...</code> to list one session's
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/projects</strong> 🔒<br>
+            Start a project on a video in one of your sessions; names are unique per session<br>
+            <strong>Body:</strong> <code>{"session_id": "...", "name": "client cut", "source_file": "uploads/interview.mp4"}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/projects/:id</strong> 🔒 &nbsp; <span class="method delete">DELETE</span> <strong>/api/projects/:id</strong> 🔒<br>
+            The project with its current <code>operations</code> and <code>history</code> (revisions after <code>revision</code> can be redone), or delete it with its history and exports
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/projects/:id/operations</strong> 🔒<br>
+            Add an operation at a 1-based <code>position</code>, or at the end<br>
+            <strong>Body:</strong> <code>{"tool": "trim_video", "args": {"start_time": 5, "end_time": 65}, "note": "drop the intro", "position": 1}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/projects/:id/operations/:position</strong> 🔒 &nbsp; <span class="method post">POST</span> <strong>/api/projects/:id/operations/move</strong> 🔒<br>
+            Remove an operation, or move one: <code>{"from": 3, "to": 1}</code>. Every edit drops whatever could be redone
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/projects/:id/undo</strong> 🔒 &nbsp; <strong>/api/projects/:id/redo</strong> 🔒<br>
+            Step back or forward one revision; 409 when there is nothing to undo or redo
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/projects/:id/exports</strong> 🔒 &nbsp; <span class="method get">GET</span> <strong>/api/projects/:id/exports</strong> 🔒<br>
+            Render the current revision in the background, encoded with an export preset (yours or a built-in one) when given; list exports with their status and <code>output_path</code>. The edited master of a revision is kept, so exporting it again at another quality only re-encodes<br>
+            <strong>Body:</strong> <code>{"preset": "youtube"}</code> (optional)
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>📺 YouTube Upload Defaults</h2>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/youtube/defaults</strong> 🔒<br>
+            Your upload defaults: channel, privacy, category, tag set and description template, with a one-line <code>summary</code> and <code>is_set</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method put">PUT</span>
+            <strong>/api/youtube/defaults</strong> 🔒<br>
+            Set upload defaults; fields not given keep their value and <code>clear</code> unsets them<br>
+            <strong>Body:</strong> <code>{"channel_id": 1, "privacy_status": "unlisted", "category": "22", "tags": ["cooking"], "description_template": "{title}\n\n{summary}\n\nUploaded {date} to {channel}"}</code> or <code>{"clear": ["tags"]}</code><br>
+            <strong>Note:</strong> Uploads (<code>/api/youtube/upload</code>, scheduled uploads and the agent's <code>upload_to_youtube</code>) use these for anything they leave out; <code>{summary}</code> is the description generated for the output, and default tags are added to its generated tags
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>📝 YouTube Drafts</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/youtube/drafts</strong> 🔒<br>
+            Upload the video bytes early as a private draft while the title, description and thumbnail are still being worked on<br>
+            <strong>Body:</strong> same as <code>/api/youtube/upload</code>; the title may be left blank and privacy is always private
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/youtube/drafts</strong> 🔒<br>
+            Drafts that haven't been finalized yet
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/youtube/drafts/:upload_id/finalize</strong> 🔒<br>
+            Patch a draft's metadata, set its thumbnail and flip its visibility, or schedule it to go public<br>
+            <strong>Body:</strong> <code>{"title": "...", "description": "...", "tags": ["..."], "thumbnail_path": "outputs/thumb.jpg", "privacy_status": "public"}</code> or <code>{"title": "...", "publish_at": "2026-02-05T17:00:00Z"}</code><br>
+            <strong>Note:</strong> Fields left out keep the draft's values; privacy falls back to your upload default
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>⏰ Scheduled Jobs</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/jobs/scheduled</strong> 🔒<br>
+            Queue an editing task or a YouTube upload to start at a later time (up to 90 days ahead); schedules survive restarts<br>
+            <strong>Body:</strong> <code>{"run_at": "2026-02-01T09:00:00Z", "session_id": "...", "kind": "video_editing", "task": "Render the final cut in 4K"}</code> or <code>{"run_at": "...", "session_id": "...", "kind": "youtube_upload", "upload": {"channel_id": 1, "video_path": "outputs/final.mp4", "title": "...", "privacy_status": "public"}}</code><br>
+            <strong>Note:</strong> Until it's due the job shows as <code>scheduled</code> (with <code>run_at</code>) in the session's jobs; it then runs under the same job id
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/jobs/scheduled</strong> 🔒<br>
+            Your scheduled jobs that haven't started yet, soonest first
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/jobs/scheduled/:job_id</strong> 🔒<br>
+            Cancel a scheduled job before it starts
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🔁 Reproducible Renders</h2>
+
+        <div class="endpoint">
+            <span class="method put">PUT</span>
+            <strong>/api/sessions/:session_uuid/reproducible</strong> 🔒<br>
+            Pin every later render in a session: bitexact output, seeded stochastic filters, inputs stored by hash<br>
+            <strong>Body:</strong> <code>{"enabled": true}</code>. Batch templates take <code>"reproducible": true</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span> <span class="method post">POST</span>
+            <strong>/api/render-snapshots</strong> 🔒<br>
+            List snapshots, or freeze a pinned output into one<br>
+            <strong>Body:</strong> <code>{"output_id", "name"}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span> <span class="method delete">DELETE</span>
+            <strong>/api/render-snapshots/:id</strong> 🔒<br>
+            Snapshot with its pinned commands and input hashes, plus the last replay's status
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/render-snapshots/:id/replay</strong> 🔒<br>
+            Re-render from the pinned commands and stored inputs in the background<br>
+            <strong>Returns:</strong> 202; the snapshot's <code>replay_status</code> becomes <code>matched</code>, <code>mismatched</code> or <code>failed</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🔗 Review Links</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/review-links</strong> 🔒<br>
+            Create a public, expiring review link for an output<br>
+            <strong>Body:</strong> <code>{"output_id": 42, "title", "expires_in_hours": 168, "allow_comments": true}</code><br>
+            <strong>Returns:</strong> Link with <code>review_url</code> (<code>/review/:token</code>) to share with clients
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/review-links</strong> 🔒<br>
+            List your review links with view counts and status
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/review-links/:id</strong> 🔒<br>
+            Revoke a review link immediately
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/review-links/:id/comments</strong> 🔒<br>
+            All comments left on a link (available after it expires)
+        </div>
+
+        <div class="endpoint">
+            <span class="method patch">PATCH</span>
+            <strong>/api/review-comments/:id</strong> 🔒<br>
+            Mark a comment resolved<br>
+            <strong>Body:</strong> <code>{"resolved": true}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/review/:token</strong><br>
+            Public review page: streaming player plus timestamped comment feed (no account needed)
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/review/:token/comments</strong> &nbsp;
+            <span class="method post">POST</span>
+            <strong>/api/review/:token/comments</strong><br>
+            Read or add reviewer comments<br>
+            <strong>Body:</strong> <code>{"author_name", "body", "timestamp_seconds"}</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>📺 Embeddable Player</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/embeds</strong> 🔒<br>
+            Publish an output as an embeddable player; it is packaged to HLS in the background<br>
+            <strong>Body:</strong> <code>{"output_id": 42, "title", "hide_branding": false, "allowed_domains": ["https://example.com"]}</code><br>
+            <strong>Returns:</strong> Embed with <code>embed_url</code> (<code>/embed/:token</code>) and a ready-to-paste <code>iframe_html</code>. <code>hide_branding</code> requires a pro or business plan
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/embeds</strong> 🔒<br>
+            List your embeds with view counts and HLS status, plus your current plan
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/embeds/:id</strong> 🔒<br>
+            Revoke an embed; pages embedding it stop playing
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/embed/:token</strong><br>
+            Public player page: HLS (native or hls.js) once packaged, progressive MP4 until then. Framing is limited to <code>allowed_domains</code> when set
+        </div>
+
+        <div class="endpoint">
+            <span class="method put">PUT</span>
+            <strong>/api/admin/users/:id/plan</strong> 🔒 (admin)<br>
+            Set a user's plan<br>
+            <strong>Body:</strong> <code>{"plan": "free" | "pro" | "business"}</code>
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>⬇️ Client Download Links</h2>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/outputs/:id/links</strong> 🔒<br>
+            Create a time-limited download link for an output, to send to a client without an account<br>
+            <strong>Body:</strong> <code>{"expires_in_hours": 72, "password": "optional", "max_downloads": 3}</code> (all optional; at most 30 days)<br>
+            <strong>Returns:</strong> Link with <code>download_url</code> (<code>/d/:token</code>), <code>download_count</code> and <code>is_active</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/outputs/:id/links</strong> 🔒 &nbsp;
+            <span class="method get">GET</span>
+            <strong>/api/download-links</strong> 🔒<br>
+            List the download links of an output, or all of yours, with download counts and status
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/download-links/:id</strong> 🔒<br>
+            Revoke a download link immediately
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/d/:token</strong><br>
+            Public download page: file name, size and expiry, with a password field when the link has one. Expired, revoked and used-up links read as 404
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/d/:token/file</strong> &nbsp;
+            <span class="method post">POST</span>
+            <strong>/d/:token/file</strong><br>
+            Download the file (GET for links without a password; POST a <code>password</code> form field otherwise). Each download is counted<br>
+            <strong>Note:</strong> Downloads and streams of one user's files (here, in the app, and through review and embed links) share <code>limits.max_concurrent_downloads_per_user</code> open at once, beyond which they get 429, and <code>limits.download_mbps_per_user</code> of bandwidth
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🎬 Video Editing Tools (via AI Agent)</h2>
+        <p>The following tools are available through the WebSocket chat interface. Send natural language requests to the AI agent:</p>
+
+        <h3>🎙️ Audio Generation (ElevenLabs)</h3>
+        <ul>
+            <li><strong>generate_text_to_speech</strong> - Generate professional voiceovers with 17+ voices (Rachel, Drew, Adam, Bella, etc.)</li>
+            <li><strong>generate_sound_effect</strong> - Create custom sound effects from text descriptions (0.5-30 seconds)</li>
+            <li><strong>generate_music</strong> - Generate studio-grade background music (10-300 seconds, any genre)</li>
+            <li><strong>select_music</strong> - Pick or generate mood-matched background music and mix it in with ducking</li>
+            <li><strong>generate_talking_head</strong> - Turn a portrait and a script or narration into a talking-head presenter clip (D-ID)</li>
+            <li><strong>add_voiceover_to_video</strong> - One-step tool: generates voiceover + adds to video automatically</li>
+        </ul>
+
+        <h3>Core Operations</h3>
+        <ul>
+            <li><strong>trim_video</strong> - Trim video to specific time range</li>
+            <li><strong>merge_videos</strong> - Combine multiple videos</li>
+            <li><strong>split_video</strong> - Split video into segments</li>
+            <li><strong>analyze_video</strong> - Get video metadata and properties</li>
+        </ul>
+
+        <h3>Transform</h3>
+        <ul>
+            <li><strong>resize_video</strong> - Change video dimensions</li>
+            <li><strong>crop_video</strong> - Crop video to specific area</li>
+            <li><strong>rotate_video</strong> - Rotate video by degrees</li>
+            <li><strong>adjust_speed</strong> - Change playback speed</li>
+            <li><strong>flip_video</strong> - Flip horizontal/vertical</li>
+            <li><strong>scale_video</strong> - Scale by factor</li>
+            <li><strong>stabilize_video</strong> - Video stabilization</li>
+        </ul>
+
+        <h3>Visual Effects</h3>
+        <ul>
+            <li><strong>add_text_overlay</strong> - Add text to video</li>
+            <li><strong>add_overlay</strong> - Add image/video overlay</li>
+            <li><strong>apply_filter</strong> - Apply visual filters</li>
+            <li><strong>adjust_color</strong> - Color correction</li>
+            <li><strong>auto_correct</strong> - Automatic exposure, contrast and white balance fix with before/after preview</li>
+            <li><strong>add_subtitles</strong> - Add subtitle files</li>
+            <li><strong>burn_timecode</strong> - Frame-accurate running SMPTE timecode for review copies</li>
+            <li><strong>generate_slate</strong> - Slate card with project, version and date prepended to a video</li>
+            <li><strong>preview_safe_zones</strong> - Proof image or video with TikTok/Reels/Shorts UI safe-zone guides</li>
+            <li><strong>generate_captions</strong> - Animated word-by-word or line-by-line captions with your own font, colours, position and highlight style</li>
+            <li><strong>add_styled_captions</strong> - Word-highlighted burned captions in a bold, karaoke, minimal or hype style</li>
+            <li><strong>create_lyric_video</strong> - Karaoke-style word-synced lyrics over a background</li>
+            <li><strong>compose_screencast</strong> - Screen + webcam tutorial layouts (corner bubble, rounded picture-in-picture, side-by-side)</li>
+            <li><strong>generate_quiz_video</strong> - Topic-to-trivia video with countdown cards, narration and music</li>
+            <li><strong>generate_video_from_article</strong> - Article URL to narrated, captioned video with B-roll and attribution</li>
+            <li><strong>export_localized</strong> - Per-language variants with translated captions, dubbing and title cards</li>
+            <li><strong>set_session_defaults</strong> - Session-wide default resolution, fps, voice and caption style for later tool calls</li>
+            <li><strong>upload_to_youtube</strong> - Upload an output to YouTube, filling channel, privacy, tags and description from your upload defaults</li>
+            <li><strong>deliver_output</strong> - Push a finished output to an SFTP/FTP/S3 target with checksum receipt</li>
+            <li><strong>list_delivery_targets</strong> - List configured delivery targets</li>
+            <li><strong>export_with_preset</strong> - Render with a named export preset (user-defined or built-in platform)</li>
+            <li><strong>list_export_presets</strong> - List custom and built-in export presets</li>
+            <li><strong>create_review_link</strong> - Public, expiring review page with timestamped client comments</li>
+            <li><strong>ask_about_video</strong> - Ask questions about a video's content and get timestamped answers</li>
+            <li><strong>summarize_video</strong> - Summarize recordings of any length (map-reduce over transcript chunks) at brief, standard or detailed depth</li>
+            <li><strong>rerender_region</strong> - Re-render only a changed region and splice it in losslessly</li>
+            <li><strong>preview_effect_chain</strong> - Experiment mode: low-res 10-second PREVIEW renders of a proposed effect chain in a session scratch area, before the full-length render</li>
+            <li><strong>undo_last_edit</strong> - Undo the latest edit(s) in the chat; the previous output becomes current without re-uploading</li>
+            <li><strong>redo_edit</strong> - Bring back edits undone with undo_last_edit, until the next edit</li>
+            <li><strong>batch_process</strong> - Apply one edit (watermark, resize, volume...) to every video in the chat or a folder as one job, with per-file progress and a zip of the results</li>
+            <li><strong>create_project</strong> - Start a non-destructive project on a video; edits are recorded, not rendered</li>
+            <li><strong>edit_project</strong> - Add, remove or reorder a project's operations, undo/redo changes, or show the edit list</li>
+            <li><strong>export_project</strong> - Render a project's edit list, optionally with an export preset; re-exports at other qualities reuse the edited master</li>
+            <li><strong>compare_versions</strong> - Side-by-side or wipe comparison video of two output versions</li>
+            <li><strong>search_library</strong> - Find assets in your library (reference them as <code>library:&lt;name&gt;</code>)</li>
+            <li><strong>add_to_library</strong> - Save an upload or output to your asset library</li>
+            <li><strong>list_files</strong> - Page through the session's uploads and outputs by kind, name or source</li>
+            <li><strong>find_asset</strong> - Find session files (and library assets) by description, using vision tags and operations</li>
+            <li><strong>clip_live_stream</strong> - Clip the last N seconds of the session's live stream while it keeps running</li>
+        </ul>
+
+        <h3>Audio Processing</h3>
+        <ul>
+            <li><strong>extract_audio</strong> - Extract audio track</li>
+            <li><strong>select_audio_track</strong> - Keep one audio track of a multi-track recording (game + mic) or mix them</li>
+            <li><strong>add_audio</strong> - Add background music</li>
+            <li><strong>adjust_volume</strong> - Volume control</li>
+            <li><strong>fade_audio</strong> - Fade in/out effects</li>
+            <li><strong>remove_fillers</strong> - Cut filler words and long pauses using word-level transcription</li>
+            <li><strong>remove_silence</strong> - Jump-cut dead air: drop silent stretches below a loudness threshold</li>
+            <li><strong>censor_profanity</strong> - Bleep, mute or cut flagged words with a report of censored timestamps</li>
+            <li><strong>transcribe_speakers</strong> - Speaker-labelled transcript with talk time per speaker</li>
+            <li><strong>edit_by_speaker</strong> - Keep, cut or mute one speaker's turns or just their interruptions</li>
+        </ul>
+
+        <h3>Export & Compression</h3>
+        <ul>
+            <li><strong>convert_format</strong> - Change video format</li>
+            <li><strong>compress_video</strong> - Reduce file size</li>
+            <li><strong>export_for_platform</strong> - Optimize for social media</li>
+            <li><strong>multi_export</strong> - Several renditions (4K, 1080p, 720p, vertical) from one decode pass</li>
+            <li><strong>create_thumbnail</strong> - Generate thumbnails</li>
+            <li><strong>extract_frames</strong> - Export individual frames</li>
+            <li><strong>contact_sheet</strong> - Storyboard grid of frames at scene changes, labelled with timestamps</li>
+            <li><strong>detect_scenes</strong> - Scene boundaries (start/end of each shot) with a thumbnail per scene, for cutting on shot changes</li>
+        </ul>
+
+        <h3>Advanced</h3>
+        <ul>
+            <li><strong>picture_in_picture</strong> - PiP effects</li>
+            <li><strong>chroma_key</strong> - Green screen effects</li>
+            <li><strong>split_screen</strong> - Multi-video layouts</li>
+        </ul>
+    </div>
+
+    <div class="section">
+        <h2>🌐 Web Interface</h2>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/</strong><br>
+            Landing page with application overview
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/login</strong><br>
+            User login page
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/signup</strong><br>
+            User registration page
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/dashboard</strong><br>
+            User dashboard (requires login)
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/chat</strong><br>
+            Chat interface with AI agent
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>🛡️ Admin Panel (Staff/Superuser Only)</h2>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/admin/login</strong><br>
+            Admin login page
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/admin/dashboard</strong><br>
+            Admin dashboard with system statistics
+        </div>
+        
+        <h3>User Management</h3>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/stats</strong> 🔒<br>
+            Get system statistics (users, files, sessions)<br>
+            <strong>Requires:</strong> Admin privileges
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/users</strong> 🔒<br>
+            List all users with pagination and search<br>
+            <strong>Query params:</strong> page, limit, search<br>
+            <strong>Requires:</strong> Admin privileges
+        </div>
+        
+        <h3>Email Whitelist Management</h3>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/whitelist/status</strong> 🔒<br>
+            Get whitelist status and email count<br>
+            <strong>Returns:</strong> <code>{"enabled": boolean, "total_emails": number}</code>
+        </div>
+        
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/whitelist/toggle</strong> 🔒<br>
+            Enable/disable email whitelist restriction<br>
+            <strong>Body:</strong> <code>{"enabled": boolean}</code><br>
+            <strong>Note:</strong> When enabled, only whitelisted emails can register/login
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/whitelist/emails</strong> 🔒<br>
+            List all whitelisted email addresses<br>
+            <strong>Returns:</strong> Array of whitelisted email objects
+        </div>
+        
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/whitelist/emails</strong> 🔒<br>
+            Add email to whitelist<br>
+            <strong>Body:</strong> <code>{"email": "user@example.com"}</code>
+        </div>
+        
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/admin/whitelist/emails/:id</strong> 🔒<br>
+            Remove email from whitelist<br>
+            <strong>Params:</strong> id (whitelist entry ID)
+        </div>
+
+        <h3>Feature Flags</h3>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/flags</strong> 🔒<br>
+            Which feature flags are on for the current user, as <code>{"flags": {"key": true}}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/flags/:key</strong> 🔒<br>
+            Whether one feature flag is on for the current user, as <code>{"key", "enabled"}</code>; unknown flags are off
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/flags</strong> 🔒<br>
+            All feature flags with their targeting<br>
+            <strong>Requires:</strong> Staff or superuser privileges
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/flags/:key</strong> 🔒<br>
+            One feature flag with its targeting
+        </div>
+
+        <div class="endpoint">
+            <span class="method put">PUT</span>
+            <strong>/api/admin/flags/:key</strong> 🔒<br>
+            Create a flag or change its targeting; fields left out keep their value. A flag is on for a user when it is enabled and the user is listed, on a listed plan, staff (with <code>include_staff</code>) or inside the rollout percentage. Tools listed in <code>tools</code> are hidden from the agent for everyone else<br>
+            <strong>Body:</strong> <code>{"enabled": true, "rollout_percent": 10, "user_ids": [42], "plans": ["business"], "include_staff": true, "tools": ["multi_export"]}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/flags/:key/kill</strong> 🔒<br>
+            Turn a flag off for everyone immediately, keeping its targeting
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/admin/flags/:key</strong> 🔒<br>
+            Remove a flag (its tools become available to everyone)
+        </div>
+
+        <h3>Job Logs</h3>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/jobs/:id/log</strong> 🔒<br>
+            Log lines captured while a failed job ran (the last <code>logging.job_capture_lines</code>), for support. With file logging on they are also saved to <code>&lt;file_dir&gt;/jobs/&lt;id&gt;.log</code><br>
+            <strong>Requires:</strong> Staff or superuser privileges
+        </div>
+
+        <div class="endpoint">
+            <span class="method put">PUT</span>
+            <strong>/api/admin/jobs/:id/priority</strong> 🔒<br>
+            Override a job's priority with <code>{"priority": "high"}</code>; a job still waiting for a worker moves to the back of its new priority in the queue<br>
+            <strong>Requires:</strong> Staff or superuser privileges
+        </div>
+
+        <h3>Configuration</h3>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/config</strong> 🔒<br>
+            Effective configuration (defaults, then <code>config.toml</code> or <code>$VIDEO_SYNC_CONFIG</code>, then environment overrides) with API keys and secrets masked, plus the file and env vars it came from<br>
+            <strong>Requires:</strong> Superuser privileges
+        </div>
+
+        <h3>Vector Memory Migration</h3>
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/vector-migrations</strong> 🔒<br>
+            Copy every chat memory vector and payload from one configured backend to another in the background. Each batch is read back from the target to verify it, and the source position is checkpointed so the copy can be resumed. With <code>reembed</code>, memories whose vectors don't match the target's dimension are re-embedded from their user message instead of failing<br>
+            <strong>Body:</strong> <code>{"source": "astra", "target": "qdrant", "batch_size": 100, "reembed": true}</code><br>
+            <strong>Requires:</strong> Superuser privileges
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/vector-migrations</strong> 🔒 &nbsp; <strong>/api/admin/vector-migrations/:id</strong> 🔒<br>
+            Migration progress: copied and verified counts, failed and mismatched ids, and the source and target counts once finished
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/vector-migrations/:id/resume</strong> 🔒<br>
+            Continue a failed or interrupted migration from its last copied batch
+        </div>
+
+        <h3>Embedding Indexes</h3>
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/embedding-indexes</strong> 🔒<br>
+            Re-embed the chat memory with another embedding model (<code>voyage</code>, <code>gemini</code> or <code>local</code>) into a new Qdrant collection in the background. Memories keep being stored in and searched from the active index until cutover; the scan position is checkpointed so the build can be resumed<br>
+            <strong>Body:</strong> <code>{"model": "voyage", "batch_size": 100}</code><br>
+            <strong>Requires:</strong> Superuser privileges
+        </div>
+
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/embedding-indexes</strong> 🔒 &nbsp; <strong>/api/admin/embedding-indexes/:id</strong> 🔒<br>
+            Indexes with their model, dimension and status (building, ready, active, retired or failed), re-embedded counts and the ids that couldn't be re-embedded
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/embedding-indexes/:id/resume</strong> 🔒<br>
+            Continue a failed or interrupted build from its last checkpoint
+        </div>
+
+        <div class="endpoint">
+            <span class="method post">POST</span>
+            <strong>/api/admin/embedding-indexes/:id/cutover</strong> 🔒<br>
+            Make a ready index the active one. Memories stored in the old index while it was built are re-embedded first; the old index is retired, not deleted
+        </div>
+
+        <h3>Chaos Mode</h3>
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/admin/chaos</strong> 🔒<br>
+            Fault-injection settings for the ElevenLabs, Pexels and YouTube clients, and whether they were changed at runtime<br>
+            <strong>Requires:</strong> Superuser privileges
+        </div>
+
+        <div class="endpoint">
+            <span class="method put">PUT</span>
+            <strong>/api/admin/chaos</strong> 🔒<br>
+            Change the settings until restart: added latency, and the shares of requests answered with a synthetic 429 (with <code>Retry-After</code>), a 503, or a timeout. For staging only<br>
+            <strong>Body:</strong> <code>{"enabled": true, "services": "elevenlabs,pexels", "latency_ms": 500, "jitter_ms": 250, "rate_limit_rate": 0.2, "failure_rate": 0.1, "timeout_rate": 0.05, "timeout_ms": 30000}</code>
+        </div>
+
+        <div class="endpoint">
+            <span class="method delete">DELETE</span>
+            <strong>/api/admin/chaos</strong> 🔒<br>
+            Drop runtime changes and go back to the <code>[chaos]</code> configuration
+        </div>
+    </div>
+
+    <div class="section">
+        <h2>⚙️ System</h2>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/status</strong><br>
+            API health check and system status
+        </div>
+        
+        <div class="endpoint">
+            <span class="method get">GET</span>
+            <strong>/api/docs</strong><br>
+            This documentation page
+        </div>
+
+        <p><strong>Request IDs:</strong> every response carries an <code>X-Request-Id</code> header (yours, if you send a short URL-safe one, otherwise a new UUID). Chat WebSocket messages and job progress include a <code>request_id</code> too. Quote it when reporting a problem: it tags the request's logs, the jobs it started and their LLM calls.</p>
+        <p><strong>Idempotency keys:</strong> send an <code>Idempotency-Key</code> header (any unique string, e.g. a UUID) with <code>POST /api/jobs/graph</code>, <code>/api/jobs/scheduled</code>, <code>/api/batch/render</code>, <code>/api/batch/process</code>, <code>/api/workflow-templates/:id/run</code>, <code>/api/workflows/run</code>, <code>/api/youtube/upload</code>, <code>/api/youtube/drafts</code> (and <code>/finalize</code>), <code>/api/youtube/upload/resumable</code>, <code>/api/clipping/clips/:id/repost</code>, <code>/upload</code> or <code>/upload/session/:session_uuid</code> (with a bearer token; anonymous uploads aren't keyed) to retry safely: a repeat of the same request within 24 hours returns the original response with <code>Idempotent-Replayed: true</code> instead of starting a second job or upload. Reusing a key for a different request is a 422; retrying while the first is still running is a 409.</p>
+    </div>
+
+    <div class="section">
+        <h2>🔧 Rate Limits</h2>
+        <ul>
+            <li><strong>General API:</strong> 100 requests per minute per IP</li>
             <li><strong>Authentication:</strong> 10 requests per minute per IP</li>
             <li><strong>File Upload:</strong> Limited by file size and count</li>
         </ul>
@@ -745,6 +1862,12 @@ async fn api_status(Extension(state): Extension<Arc<AppState>>) -> axum::respons
     let qdrant_status = if state.qdrant_client.is_some() { "configured" } else { "not_configured" };
     let astra_status = if state.vector_db.is_some() { "configured" } else { "not_configured" };
     let elevenlabs_status = if state.elevenlabs_client.is_some() { "configured" } else { "not_configured" };
+    // Detection runs ffmpeg once per process, so keep it off the async workers
+    let gpu_filters = tokio::task::spawn_blocking(|| utils::gpu::filters().map(|gpu| gpu.backend.device_type()))
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or("cpu");
     
     axum::response::Json(json!({
         "status": "operational",
@@ -765,7 +1888,8 @@ async fn api_status(Extension(state): Extension<Arc<AppState>>) -> axum::respons
             "file_upload": true,
             "websocket_chat": true,
             "rate_limiting": true,
-            "vector_memory": qdrant_status == "configured" || astra_status == "configured"
+            "vector_memory": qdrant_status == "configured" || astra_status == "configured",
+            "gpu_filters": gpu_filters
         },
         "endpoints": {
             "documentation": "/api/docs",
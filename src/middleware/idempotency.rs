@@ -0,0 +1,184 @@
+use crate::handlers::auth::verify_jwt_token;
+use crate::middleware::auth::ClaimsExtractor;
+use crate::services::idempotency::IdempotencyClaim;
+use crate::services::IdempotencyService;
+use crate::AppState;
+use axum::{
+    body::Body,
+    extract::{Extension, Request},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Header a client sets to make retries of a request safe
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Set on responses replayed from an earlier request with the same key
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const MAX_KEY_LEN: usize = 255;
+
+/// Largest request body a keyed request may have (it is buffered to fingerprint it)
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// File uploads are buffered by their handlers anyway, up to the upload routes' body limit
+const MAX_UPLOAD_BODY_BYTES: usize = 100 * 1024 * 1024;
+
+/// A caller-supplied key, if it's printable ASCII and not too long
+fn accepted(value: &HeaderValue) -> Option<String> {
+    let key = value.to_str().ok()?.trim();
+    let valid = !key.is_empty() && key.len() <= MAX_KEY_LEN && key.chars().all(|c| c.is_ascii_graphic());
+    valid.then(|| key.to_string())
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "success": false, "message": message }))).into_response()
+}
+
+/// The signed-in caller: set by `auth_middleware`, or read from the bearer token on the public upload routes
+fn caller_id(request: &Request) -> Option<i32> {
+    let claims = match request.claims() {
+        Some(claims) => claims.clone(),
+        None => {
+            let header = request.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+            verify_jwt_token(header.strip_prefix("Bearer ")?).ok()?
+        }
+    };
+    claims.sub.parse::<i32>().ok()
+}
+
+/// Releases a claimed key if the request is dropped (client gone, handler panicked) before it finished
+struct PendingKey {
+    pool: PgPool,
+    user_id: i32,
+    key: String,
+    finished: bool,
+}
+
+impl Drop for PendingKey {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let (pool, user_id, key) = (self.pool.clone(), self.user_id, std::mem::take(&mut self.key));
+        tokio::spawn(async move {
+            if let Err(e) = IdempotencyService::release(&pool, user_id, &key).await {
+                tracing::warn!("Failed to release idempotency key {}: {}", key, e);
+            }
+        });
+    }
+}
+
+/// Requests that create something (jobs, uploads) may carry an `Idempotency-Key`. The first request
+/// with a key runs and its result is stored; a retry with the same key and body within
+/// `WINDOW_HOURS` gets that result back (marked `Idempotent-Replayed: true`) without running again.
+/// Goes inside `auth_middleware`, as keys are per user. Server errors aren't stored, so they can be retried.
+pub async fn idempotency_middleware(
+    Extension(state): Extension<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    with_idempotency(&state, request, next, MAX_BODY_BYTES).await
+}
+
+/// `idempotency_middleware` for the file upload routes, which are public: keys apply to callers that send
+/// a bearer token (anonymous uploads run as usual) and bodies may be as large as the routes accept
+pub async fn upload_idempotency_middleware(
+    Extension(state): Extension<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    with_idempotency(&state, request, next, MAX_UPLOAD_BODY_BYTES).await
+}
+
+async fn with_idempotency(state: &AppState, request: Request, next: Next, max_body_bytes: usize) -> Response {
+    let Some(value) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    if !matches!(*request.method(), Method::POST | Method::PUT | Method::PATCH) {
+        return next.run(request).await;
+    }
+    let Some(key) = accepted(value) else {
+        return error(StatusCode::BAD_REQUEST, "Idempotency-Key must be 1-255 printable characters");
+    };
+    let Some(user_id) = caller_id(&request) else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, max_body_bytes).await else {
+        return error(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large to use with an Idempotency-Key");
+    };
+    let path = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or_else(|| parts.uri.path());
+    let hash = IdempotencyService::request_hash(parts.method.as_str(), path, &body);
+
+    match IdempotencyService::claim(&state.db_pool, user_id, &key, &hash).await {
+        Ok(IdempotencyClaim::New) => {}
+        Ok(IdempotencyClaim::Replay(stored)) => {
+            tracing::info!("🔁 Replaying {} {} for idempotency key {}", parts.method, path, key);
+            let status = stored.status_code.and_then(|s| StatusCode::from_u16(s as u16).ok()).unwrap_or(StatusCode::OK);
+            let mut response = Response::builder().status(status).header(REPLAYED_HEADER, "true");
+            if let Some(content_type) = stored.content_type {
+                response = response.header(header::CONTENT_TYPE, content_type);
+            }
+            return response
+                .body(Body::from(stored.response_body.unwrap_or_default()))
+                .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+        Ok(IdempotencyClaim::InProgress) => {
+            return error(StatusCode::CONFLICT, "A request with this Idempotency-Key is still being processed");
+        }
+        Ok(IdempotencyClaim::Mismatch) => {
+            return error(StatusCode::UNPROCESSABLE_ENTITY, "This Idempotency-Key was already used for a different request");
+        }
+        Err(e) => {
+            tracing::error!("Failed to check idempotency key {}: {}", key, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let mut pending = PendingKey { pool: state.db_pool.clone(), user_id, key, finished: false };
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let content_type = parts.headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    match IdempotencyService::complete(&state.db_pool, user_id, &pending.key, parts.status.as_u16(), content_type, &body).await {
+        Ok(()) => pending.finished = true,
+        Err(e) => tracing::warn!("Failed to store the result for idempotency key {}: {}", pending.key, e),
+    }
+
+    // Occasionally drop keys past their window
+    if rand::random::<u8>() < 4 {
+        let pool = state.db_pool.clone();
+        tokio::spawn(async move {
+            if let Err(e) = IdempotencyService::purge_expired(&pool).await {
+                tracing::warn!("Failed to purge expired idempotency keys: {}", e);
+            }
+        });
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_short_printable_keys_are_accepted() {
+        assert_eq!(accepted(&HeaderValue::from_static("8e03978e-40d5-43e8-bc93-6894a57f9324")), Some("8e03978e-40d5-43e8-bc93-6894a57f9324".to_string()));
+        assert_eq!(accepted(&HeaderValue::from_static("has spaces")), None);
+        assert_eq!(accepted(&HeaderValue::from_static("  ")), None);
+        assert_eq!(accepted(&HeaderValue::from_str(&"k".repeat(MAX_KEY_LEN + 1)).unwrap()), None);
+    }
+}
@@ -6,6 +6,7 @@ use axum::{
 };
 use std::time::Instant;
 use uuid::Uuid;
+use super::request_id::RequestId;
 
 /// Request logging middleware that adds structured logging for all HTTP requests
 pub async fn request_logging_middleware(
@@ -13,7 +14,10 @@ pub async fn request_logging_middleware(
     next: Next,
 ) -> Result<Response, StatusCode> {
     let start = Instant::now();
-    let request_id = Uuid::new_v4().to_string();
+    // Set by request_id_middleware, which runs first
+    let request_id = req.extensions().get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
     
     // Extract request information before moving req
     let method = req.method().clone();
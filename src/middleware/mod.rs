@@ -1,7 +1,9 @@
 pub mod auth;
 pub mod logging;
+pub mod request_id;
 pub mod rate_limit;
 pub mod admin;
 pub mod frontend_rate_limit;
 pub mod youtube_access;
-pub mod clipping_access;
\ No newline at end of file
+pub mod clipping_access;
+pub mod idempotency;
\ No newline at end of file
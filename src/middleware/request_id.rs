@@ -0,0 +1,104 @@
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::future::Future;
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header a caller can set to choose the request id, echoed on every response
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied id accepted; longer ones are replaced with a fresh id
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Request id of the current request, as a request extension
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Id of the request (or WebSocket turn, or job) the current task is working for
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+pub fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Run `future` with `id` as the current request id and inside a `request` span carrying it
+pub async fn scope<F: Future>(id: String, future: F) -> F::Output {
+    let span = tracing::info_span!("request", request_id = %id);
+    REQUEST_ID.scope(id, future.instrument(span)).await
+}
+
+/// Run `future` with `id` as the current request id, for callers that log in a span of their own
+pub async fn with_id<F: Future>(id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(id, future).await
+}
+
+/// Carry the current request id into a future that will run on another task (tokio::spawn)
+pub fn propagate<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let id = current();
+    async move {
+        match id {
+            Some(id) => REQUEST_ID.scope(id, future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// A caller-supplied id, if it's short and made of URL-safe characters
+fn accepted(value: &HeaderValue) -> Option<String> {
+    let id = value.to_str().ok()?.trim();
+    let valid = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| id.to_string())
+}
+
+/// Give every request an id (the caller's `X-Request-Id`, or a new one) that tags its logs,
+/// the jobs and LLM calls it starts, and is returned in the `X-Request-Id` response header
+pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(accepted)
+        .unwrap_or_else(new_id);
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let mut response = scope(id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_short_url_safe_ids_are_accepted() {
+        assert_eq!(accepted(&HeaderValue::from_static("support-1234_a.b")), Some("support-1234_a.b".to_string()));
+        assert_eq!(accepted(&HeaderValue::from_static("has spaces")), None);
+        assert_eq!(accepted(&HeaderValue::from_static("")), None);
+        assert_eq!(accepted(&HeaderValue::from_str(&"x".repeat(MAX_REQUEST_ID_LEN + 1)).unwrap()), None);
+    }
+
+    #[tokio::test]
+    async fn ids_follow_spawned_tasks() {
+        assert_eq!(current(), None);
+        let seen = scope("req-1".to_string(), async {
+            tokio::spawn(propagate(async { current() })).await.unwrap()
+        })
+        .await;
+        assert_eq!(seen, Some("req-1".to_string()));
+        assert_eq!(tokio::spawn(async { current() }).await.unwrap(), None);
+    }
+}
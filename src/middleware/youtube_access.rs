@@ -95,3 +95,20 @@ pub async fn youtube_access_middleware(
         ))
     }
 }
+
+/// The same access rules for callers without a request (the agent): admins, everyone when the
+/// feature is on, otherwise whitelisted emails
+pub async fn user_has_access(pool: &sqlx::PgPool, user_id: i32) -> bool {
+    let access = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT u.is_staff OR u.is_superuser
+            OR EXISTS(SELECT 1 FROM system_settings WHERE setting_key = 'youtube_features_enabled' AND setting_value = 'true')
+            OR EXISTS(SELECT 1 FROM whitelist_emails w WHERE w.email = u.email)
+        FROM users u WHERE u.id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+    matches!(access, Ok(Some(true)))
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One entry in a user's activity feed
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ActivityEvent {
+    pub id: i32,
+    pub user_id: i32,
+    pub kind: String,
+    /// upload, render, publish, approval or failure
+    pub category: String,
+    pub summary: String,
+    pub session_id: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
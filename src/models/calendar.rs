@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// One thing on the content calendar
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarEntry {
+    /// scheduled_job, youtube_publish, clipping_run or clip_post
+    pub kind: String,
+    pub at: DateTime<Utc>,
+    pub title: String,
+    /// scheduled, started, cancelled, published or planned
+    pub status: String,
+    /// Id of the record behind the entry: the scheduled job, upload, channel linkage or clip
+    pub source_id: String,
+    pub details: serde_json::Value,
+}
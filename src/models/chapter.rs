@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Chaptering status for one upload
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ChapterIndex {
+    pub uploaded_file_id: String,
+    pub status: String,
+    pub duration_seconds: Option<f64>,
+    pub error_message: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Chapter {
+    pub id: i32,
+    pub uploaded_file_id: String,
+    pub chapter_index: i32,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub title: String,
+    pub summary: Option<String>,
+    pub keywords: Vec<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
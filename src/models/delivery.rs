@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DeliveryTarget {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub protocol: String,
+    pub host: String,
+    pub port: Option<i32>,
+    pub username: Option<String>,
+    pub secret: Option<String>,
+    pub remote_path: String,
+    pub region: Option<String>,
+    pub endpoint_url: Option<String>,
+    pub is_active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Delivery target as returned by the API (secret never leaves the server)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeliveryTargetResponse {
+    pub id: i32,
+    pub name: String,
+    pub protocol: String,
+    pub host: String,
+    pub port: Option<i32>,
+    pub username: Option<String>,
+    pub has_secret: bool,
+    pub remote_path: String,
+    pub region: Option<String>,
+    pub endpoint_url: Option<String>,
+    pub is_active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DeliveryTarget> for DeliveryTargetResponse {
+    fn from(target: DeliveryTarget) -> Self {
+        Self {
+            id: target.id,
+            name: target.name,
+            protocol: target.protocol,
+            host: target.host,
+            port: target.port,
+            username: target.username,
+            has_secret: target.secret.as_deref().is_some_and(|s| !s.is_empty()),
+            remote_path: target.remote_path,
+            region: target.region,
+            endpoint_url: target.endpoint_url,
+            is_active: target.is_active,
+            created_at: target.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDeliveryTargetRequest {
+    pub name: String,
+    pub protocol: String,
+    pub host: String,
+    pub port: Option<i32>,
+    pub username: Option<String>,
+    pub secret: Option<String>,
+    pub remote_path: Option<String>,
+    pub region: Option<String>,
+    pub endpoint_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DeliveryReceipt {
+    pub id: i32,
+    pub receipt_uuid: String,
+    pub user_id: i32,
+    pub target_id: Option<i32>,
+    pub target_name: String,
+    pub local_path: String,
+    pub remote_url: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub md5: String,
+    pub verified: bool,
+    pub verification_method: Option<String>,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub delivered_at: chrono::DateTime<chrono::Utc>,
+}
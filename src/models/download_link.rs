@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DownloadLink {
+    pub id: i32,
+    pub token: String,
+    pub user_id: i32,
+    pub output_id: i32,
+    pub file_path: String,
+    /// Name the client's download is saved under
+    pub file_name: String,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// Downloads allowed before the link stops working (None = until it expires)
+    pub max_downloads: Option<i32>,
+    pub download_count: i32,
+    pub last_downloaded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl DownloadLink {
+    pub fn downloads_remaining(&self) -> Option<i32> {
+        self.max_downloads.map(|max| (max - self.download_count).max(0))
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > chrono::Utc::now() && self.downloads_remaining() != Some(0)
+    }
+}
+
+/// Download link as returned to its owner, with the URL to send to the client
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadLinkResponse {
+    pub id: i32,
+    pub output_id: i32,
+    pub file_name: String,
+    pub download_url: String,
+    pub password_protected: bool,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub max_downloads: Option<i32>,
+    pub download_count: i32,
+    pub downloads_remaining: Option<i32>,
+    pub last_downloaded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_active: bool,
+    pub revoked: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DownloadLink> for DownloadLinkResponse {
+    fn from(link: DownloadLink) -> Self {
+        Self {
+            id: link.id,
+            output_id: link.output_id,
+            download_url: format!("/d/{}", link.token),
+            password_protected: link.password_hash.is_some(),
+            is_active: link.is_active(),
+            revoked: link.revoked_at.is_some(),
+            downloads_remaining: link.downloads_remaining(),
+            file_name: link.file_name,
+            expires_at: link.expires_at,
+            max_downloads: link.max_downloads,
+            download_count: link.download_count,
+            last_downloaded_at: link.last_downloaded_at,
+            created_at: link.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDownloadLinkRequest {
+    /// Hours until the link stops working (default 72 = 3 days)
+    pub expires_in_hours: Option<i64>,
+    /// Password the client must enter before downloading
+    pub password: Option<String>,
+    pub max_downloads: Option<i32>,
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct EmbedLink {
+    pub id: i32,
+    pub token: String,
+    pub user_id: i32,
+    pub output_id: i32,
+    pub file_path: String,
+    pub title: String,
+    pub hide_branding: bool,
+    /// Space-separated sites allowed to frame the player (None = any)
+    pub allowed_domains: Option<String>,
+    /// HLS packaging state: pending, ready or failed (the player falls back to progressive MP4)
+    pub hls_status: String,
+    pub view_count: i32,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Embed as returned to its owner, with the player URL and a ready-to-paste iframe
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmbedLinkResponse {
+    pub id: i32,
+    pub output_id: i32,
+    pub title: String,
+    pub embed_url: String,
+    pub iframe_html: String,
+    pub hide_branding: bool,
+    pub allowed_domains: Option<String>,
+    pub hls_status: String,
+    pub is_active: bool,
+    pub view_count: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<EmbedLink> for EmbedLinkResponse {
+    fn from(link: EmbedLink) -> Self {
+        let embed_url = format!("/embed/{}", link.token);
+        Self {
+            id: link.id,
+            output_id: link.output_id,
+            iframe_html: format!(
+                r#"<iframe src="{}" width="640" height="360" frameborder="0" allow="autoplay; fullscreen; picture-in-picture" allowfullscreen></iframe>"#,
+                embed_url
+            ),
+            embed_url,
+            is_active: link.revoked_at.is_none(),
+            title: link.title,
+            hide_branding: link.hide_branding,
+            allowed_domains: link.allowed_domains,
+            hls_status: link.hls_status,
+            view_count: link.view_count,
+            created_at: link.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateEmbedLinkRequest {
+    pub output_id: i32,
+    pub title: Option<String>,
+    /// Remove the "Made with VideoSync" badge (paid plans only)
+    pub hide_branding: Option<bool>,
+    /// Sites allowed to embed the player, e.g. ["https://example.com"] (default: any)
+    pub allowed_domains: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserPlanRequest {
+    pub plan: String,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A version of the chat memory vector index: one Qdrant collection embedded with one model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmbeddingIndex {
+    pub id: i32,
+    pub collection: String,
+    /// "voyage", "gemini" or "local"
+    pub model: String,
+    pub dimension: i32,
+    /// building, ready (waiting for cutover), active, retired or failed
+    pub status: String,
+    /// The index whose content was re-embedded into this one
+    pub source_index_id: Option<i32>,
+    pub batch_size: i32,
+    /// Source page token of the next batch to re-embed (None = from the start)
+    pub cursor: Option<String>,
+    pub scan_complete: bool,
+    pub source_count: Option<i64>,
+    pub reindexed: i64,
+    /// Memories with no text to embed, or whose embedding failed
+    pub failed_ids: Vec<String>,
+    pub error: Option<String>,
+    pub started_by: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub activated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartReindexRequest {
+    /// "voyage", "gemini" or "local"
+    pub model: String,
+    pub batch_size: Option<i32>,
+}
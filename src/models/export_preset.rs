@@ -0,0 +1,80 @@
+use crate::types::ExportSettings;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ExportPreset {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub container: String,
+    pub video_codec: String,
+    pub audio_codec: String,
+    pub crf: Option<i32>,
+    pub video_bitrate_kbps: Option<i32>,
+    pub audio_bitrate_kbps: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub fps: Option<f64>,
+    pub loudness_target: Option<f64>,
+    pub burn_captions: bool,
+    pub description: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ExportPreset {
+    /// Encoding settings this preset renders with
+    pub fn settings(&self) -> ExportSettings {
+        ExportSettings {
+            container: self.container.clone(),
+            video_codec: self.video_codec.clone(),
+            audio_codec: self.audio_codec.clone(),
+            crf: self.crf.map(|v| v as u32),
+            video_bitrate_kbps: self.video_bitrate_kbps.map(|v| v as u32),
+            audio_bitrate_kbps: self.audio_bitrate_kbps.map(|v| v as u32),
+            width: self.width.map(|v| v as u32),
+            height: self.height.map(|v| v as u32),
+            fps: self.fps,
+            loudness_target: self.loudness_target,
+            burn_captions: self.burn_captions,
+        }
+    }
+}
+
+/// Create a preset, or replace the one with the same name
+#[derive(Debug, Deserialize)]
+pub struct CreateExportPresetRequest {
+    pub name: String,
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub crf: Option<i32>,
+    pub video_bitrate_kbps: Option<i32>,
+    pub audio_bitrate_kbps: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub fps: Option<f64>,
+    pub loudness_target: Option<f64>,
+    #[serde(default)]
+    pub burn_captions: bool,
+    pub description: Option<String>,
+}
+
+/// Partial update; omitted fields keep their value
+#[derive(Debug, Deserialize)]
+pub struct UpdateExportPresetRequest {
+    pub name: Option<String>,
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub crf: Option<i32>,
+    pub video_bitrate_kbps: Option<i32>,
+    pub audio_bitrate_kbps: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub fps: Option<f64>,
+    pub loudness_target: Option<f64>,
+    pub burn_captions: Option<bool>,
+    pub description: Option<String>,
+}
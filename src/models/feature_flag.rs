@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeatureFlag {
+    pub key: String,
+    pub description: Option<String>,
+    /// Kill switch: a disabled flag is off for everyone regardless of targeting
+    pub enabled: bool,
+    pub rollout_percent: i32,
+    pub user_ids: Vec<i32>,
+    pub plans: Vec<String>,
+    pub include_staff: bool,
+    /// Agent tools only offered to users the flag is on for
+    pub tools: Vec<String>,
+    pub updated_by: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Who a flag is being evaluated for
+#[derive(Debug, Clone, Default)]
+pub struct FlagSubject {
+    pub user_id: Option<i32>,
+    pub plan: Option<String>,
+    pub is_staff: bool,
+}
+
+/// Create a flag or change its targeting; unset fields keep their current value
+#[derive(Debug, Deserialize, Default)]
+pub struct UpsertFeatureFlagRequest {
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub rollout_percent: Option<i32>,
+    pub user_ids: Option<Vec<i32>>,
+    pub plans: Option<Vec<String>>,
+    pub include_staff: Option<bool>,
+    pub tools: Option<Vec<String>>,
+}
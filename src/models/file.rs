@@ -25,6 +25,34 @@ pub struct FileUploadResponse {
     pub file_size: i64,  // Changed from 'size' to match frontend expectations
     pub file_type: String,
     pub status: String,
+    /// Set when the upload matched a file the user already has
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+/// Query options for session uploads
+#[derive(Debug, Deserialize, Default)]
+pub struct UploadOptions {
+    /// "reuse" (default) links an identical earlier upload instead of storing the bytes again; "keep" always stores
+    pub on_duplicate: Option<String>,
+    /// Build a chapter index for long video/audio recordings (off by default)
+    pub chapters: Option<bool>,
+}
+
+/// An upload flagged as a copy of an earlier one, with the original it matched
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct DuplicateUpload {
+    pub id: String,
+    pub original_name: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub duplicate_kind: Option<String>,
+    pub original_id: String,
+    pub original_file_name: String,
+    pub original_file_path: String,
+    /// Whether this upload still has its own copy on disk
+    pub stores_copy: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -53,8 +81,28 @@ pub struct OutputVideo {
     pub processing_status: String,
     pub tool_used: String,
     pub ai_response_message: Option<String>,
+    /// Output this one was re-rendered from
+    pub parent_output_id: Option<i32>,
+    /// First version in the lineage (None for a v1)
+    pub root_output_id: Option<i32>,
+    pub version: i32,
+    /// Render report written alongside the file (`<file>.report.json`)
+    pub report_path: Option<String>,
+    /// Generated from the content after rendering; pre-fills YouTube uploads
+    pub suggested_title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub metadata_generated_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Set while the output is in the trash
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// "hot" while the file is in outputs/, "cold" once it has moved to cold storage
+    pub storage_tier: String,
+    /// Last download, stream or rehydration
+    pub last_accessed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set while the edit that made the output is undone; redo clears it
+    pub undone_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,4 +118,31 @@ pub struct OutputVideoResponse {
     pub download_url: String,
     pub stream_url: String,
     pub created_at: String,
-}
\ No newline at end of file
+}
+/// An upload or output of a session, as the agent's `list_files` / `find_asset` tools report it
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SessionFile {
+    /// "upload" or "output"
+    pub source: String,
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// video, audio, image, ...
+    pub kind: String,
+    pub size_bytes: i64,
+    pub duration_seconds: Option<f64>,
+    /// Vision caption of an upload, or the operation that produced an output
+    pub detail: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SessionFileQuery {
+    /// "upload" or "output"
+    pub source: Option<String>,
+    pub kind: Option<String>,
+    /// Part of the file name
+    pub name: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, FromRow, Clone)]
+pub struct IdempotencyKey {
+    pub user_id: i32,
+    pub idempotency_key: String,
+    pub request_hash: String,
+    /// None while the first request with this key is still being handled
+    pub status_code: Option<i32>,
+    #[serde(skip_serializing)]
+    pub response_body: Option<Vec<u8>>,
+    pub content_type: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
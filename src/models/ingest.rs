@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LiveIngest {
+    pub id: i32,
+    pub user_id: i32,
+    pub session_id: i32,
+    pub stream_key: String,
+    pub title: String,
+    pub status: String,
+    pub auto_clip: bool,
+    pub clip_instructions: Option<String>,
+    pub recording_path: Option<String>,
+    pub uploaded_file_id: Option<String>,
+    pub clip_job_id: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateLiveIngestRequest {
+    /// Chat session the recording is added to
+    pub session_uuid: String,
+    pub title: Option<String>,
+    /// Start a clipping job as soon as the stream ends (default true)
+    pub auto_clip: Option<bool>,
+    /// Extra guidance for the clipping job, e.g. "focus on the Q&A"
+    pub clip_instructions: Option<String>,
+}
+
+/// Form body nginx-rtmp posts to on_publish / on_publish_done / on_record_done
+#[derive(Debug, Deserialize)]
+pub struct RtmpCallback {
+    #[serde(default)]
+    pub call: String,
+    #[serde(default)]
+    pub app: String,
+    /// The stream name, which is the stream key
+    pub name: String,
+    pub addr: Option<String>,
+    /// Recorded file (on_record_done only)
+    pub path: Option<String>,
+}
+
+/// Clip the most recent part of a live stream
+#[derive(Debug, Deserialize)]
+pub struct LiveClipRequest {
+    /// How far back from the live edge, e.g. 60 for "the last minute"
+    pub seconds: f64,
+    pub output_file: Option<String>,
+}
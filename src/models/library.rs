@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Asset kinds the library groups by
+pub const ASSET_KINDS: [&str; 8] = ["logo", "intro", "outro", "music", "clip", "image", "sfx", "other"];
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LibraryAsset {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub mime_type: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+    pub use_count: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl LibraryAsset {
+    /// How the agent refers to this asset in tool arguments
+    pub fn reference(&self) -> String {
+        format!("library:{}", self.name)
+    }
+}
+
+/// Metadata for a new asset (the file comes from an upload or an existing path)
+#[derive(Debug, Deserialize, Default)]
+pub struct NewLibraryAsset {
+    pub name: String,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+}
+
+/// Add an existing upload or output to the library
+#[derive(Debug, Deserialize)]
+pub struct ImportLibraryAssetRequest {
+    pub file_path: String,
+    #[serde(flatten)]
+    pub asset: NewLibraryAsset,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateLibraryAssetRequest {
+    pub name: Option<String>,
+    pub kind: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct LibrarySearchQuery {
+    /// Matches name, description, tags and vision tags
+    pub q: Option<String>,
+    pub tag: Option<String>,
+    pub kind: Option<String>,
+    /// Only assets whose vision tags found (or didn't find) faces
+    pub has_faces: Option<bool>,
+}
+
+/// Vision-model tags for a library asset or upload
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AssetVisualTags {
+    pub id: i32,
+    pub source: String,
+    pub source_id: String,
+    pub user_id: Option<i32>,
+    pub file_path: String,
+    pub status: String,
+    pub caption: Option<String>,
+    pub objects: Vec<String>,
+    pub scenes: Vec<String>,
+    pub dominant_colors: Vec<String>,
+    pub has_faces: Option<bool>,
+    pub embedded: bool,
+    pub error_message: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub tagged_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl AssetVisualTags {
+    /// One line of text describing the file, used for embeddings and agent context
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(caption) = self.caption.as_deref().filter(|c| !c.is_empty()) {
+            parts.push(caption.trim_end_matches('.').to_string());
+        }
+        if !self.objects.is_empty() {
+            parts.push(format!("objects: {}", self.objects.join(", ")));
+        }
+        if !self.scenes.is_empty() {
+            parts.push(format!("scenes: {}", self.scenes.join(", ")));
+        }
+        if !self.dominant_colors.is_empty() {
+            parts.push(format!("colors: {}", self.dominant_colors.join(", ")));
+        }
+        if let Some(has_faces) = self.has_faces {
+            parts.push(if has_faces { "has faces" } else { "no faces" }.to_string());
+        }
+        parts.join("; ")
+    }
+}
@@ -4,3 +4,27 @@ pub mod auth;
 pub mod chat;
 pub mod file;
 pub mod youtube;
+pub mod delivery;
+pub mod review;
+pub mod library;
+pub mod ingest;
+pub mod notification;
+pub mod chapter;
+pub mod export_preset;
+pub mod render_snapshot;
+pub mod embed;
+pub mod session_defaults;
+pub mod feature_flag;
+pub mod vector_migration;
+pub mod session_bulk;
+pub mod scheduled_job;
+pub mod webhook;
+pub mod upload_defaults;
+pub mod download_link;
+pub mod idempotency_key;
+pub mod workflow_template;
+pub mod embedding_index;
+pub mod project;
+pub mod storage_policy;
+pub mod calendar;
+pub mod activity;
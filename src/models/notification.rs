@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Notification {
+    pub id: i32,
+    pub user_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+    pub read_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
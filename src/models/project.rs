@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+
+/// A source video and the edit decision list applied to it on export
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Project {
+    pub id: i32,
+    pub user_id: i32,
+    pub session_uuid: String,
+    pub name: String,
+    pub source_file: String,
+    /// Revision of the edit list the project is on; undo and redo move it
+    pub revision: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One step of the edit list: a single-input tool and its arguments, without input_file and
+/// output_file, which are chained in on export
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectOperation {
+    pub tool: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// The edit list as it was after one change
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProjectRevision {
+    pub project_id: i32,
+    pub revision: i32,
+    /// `Vec<ProjectOperation>` as JSON
+    pub operations: Value,
+    pub change: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ProjectRevision {
+    pub fn operation_list(&self) -> Result<Vec<ProjectOperation>, String> {
+        serde_json::from_value(self.operations.clone())
+            .map_err(|e| format!("Revision {} has an invalid edit list: {}", self.revision, e))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProjectExport {
+    pub id: i32,
+    pub project_id: i32,
+    pub revision: i32,
+    /// Export preset the render was encoded with; None keeps the edit list's own encoding
+    pub preset: Option<String>,
+    pub status: String,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateProjectRequest {
+    pub session_id: String,
+    pub name: String,
+    pub source_file: String,
+}
+
+/// Add an operation at a 1-based position, or at the end
+#[derive(Debug, Deserialize)]
+pub struct AddOperationRequest {
+    pub tool: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+    pub note: Option<String>,
+    pub position: Option<usize>,
+}
+
+/// Move the operation at one 1-based position to another
+#[derive(Debug, Deserialize)]
+pub struct MoveOperationRequest {
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportProjectRequest {
+    /// A user or built-in export preset ("youtube", "tiktok", ...)
+    pub preset: Option<String>,
+}
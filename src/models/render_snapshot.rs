@@ -0,0 +1,41 @@
+use crate::types::FfmpegInvocation;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct RenderSnapshot {
+    pub id: i32,
+    pub user_id: i32,
+    pub output_id: Option<i32>,
+    pub name: String,
+    pub manifest: serde_json::Value,
+    pub output_sha256: Option<String>,
+    pub ffmpeg_version: Option<String>,
+    /// idle, running, matched, mismatched or failed
+    pub replay_status: String,
+    pub replay_output: Option<String>,
+    pub replay_error: Option<String>,
+    pub last_replayed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// What a snapshot pins: the commands that produced the output, in order, and the hash it must reproduce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub tool: String,
+    pub arguments: serde_json::Value,
+    pub output_path: String,
+    pub output_sha256: Option<String>,
+    pub commands: Vec<FfmpegInvocation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRenderSnapshotRequest {
+    pub output_id: i32,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReproducibleModeRequest {
+    pub enabled: bool,
+}
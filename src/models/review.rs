@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ReviewLink {
+    pub id: i32,
+    pub token: String,
+    pub user_id: i32,
+    pub file_path: String,
+    pub title: String,
+    pub allow_comments: bool,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub view_count: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Review link as returned to its owner, with the shareable URL
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewLinkResponse {
+    pub id: i32,
+    pub title: String,
+    pub file_path: String,
+    pub review_url: String,
+    pub allow_comments: bool,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub is_active: bool,
+    pub view_count: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ReviewLink> for ReviewLinkResponse {
+    fn from(link: ReviewLink) -> Self {
+        Self {
+            id: link.id,
+            is_active: link.revoked_at.is_none() && link.expires_at > chrono::Utc::now(),
+            review_url: format!("/review/{}", link.token),
+            title: link.title,
+            file_path: link.file_path,
+            allow_comments: link.allow_comments,
+            expires_at: link.expires_at,
+            view_count: link.view_count,
+            created_at: link.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReviewLinkRequest {
+    pub output_id: i32,
+    pub title: Option<String>,
+    /// Hours until the link stops working (default 168 = 7 days)
+    pub expires_in_hours: Option<i64>,
+    pub allow_comments: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ReviewComment {
+    pub id: i32,
+    pub review_link_id: i32,
+    pub author_name: String,
+    pub body: String,
+    pub timestamp_seconds: f64,
+    pub resolved: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReviewCommentRequest {
+    pub author_name: String,
+    pub body: String,
+    pub timestamp_seconds: Option<f64>,
+}
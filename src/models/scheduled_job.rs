@@ -0,0 +1,71 @@
+use crate::models::youtube::UploadToYouTubeRequest;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// What a scheduled job does when its time comes
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduledWork {
+    /// Run the editing agent on a task, as a background job in the session
+    VideoEditing { task: String },
+    /// Upload a finished video to one of the user's connected channels
+    YoutubeUpload { upload: UploadToYouTubeRequest },
+}
+
+impl ScheduledWork {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScheduledWork::VideoEditing { .. } => "video_editing",
+            ScheduledWork::YoutubeUpload { .. } => "youtube_upload",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleJobRequest {
+    pub run_at: chrono::DateTime<chrono::Utc>,
+    /// Chat that gets the job's progress and result
+    pub session_id: String,
+    #[serde(flatten)]
+    pub work: ScheduledWork,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ScheduledJob {
+    /// Also the id of the job it runs as
+    pub id: String,
+    pub user_id: i32,
+    pub session_uuid: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub run_at: chrono::DateTime<chrono::Utc>,
+    /// scheduled, started or cancelled
+    pub status: String,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_name_their_work_by_kind() {
+        let request: ScheduleJobRequest = serde_json::from_value(serde_json::json!({
+            "run_at": "2026-02-01T09:00:00Z",
+            "session_id": "abc",
+            "kind": "youtube_upload",
+            "upload": { "channel_id": 3, "video_path": "outputs/final.mp4", "title": "Launch", "privacy_status": "public" }
+        }))
+        .unwrap();
+        assert_eq!(request.work.kind(), "youtube_upload");
+
+        // The stored payload reads back as the same work
+        let payload = serde_json::to_value(&request.work).unwrap();
+        assert_eq!(payload["kind"], "youtube_upload");
+        assert!(matches!(serde_json::from_value(payload).unwrap(), ScheduledWork::YoutubeUpload { upload } if upload.channel_id == Some(3)));
+
+        let edit = serde_json::json!({ "run_at": "2026-02-01T09:00:00Z", "session_id": "abc", "kind": "video_editing", "task": "Render" });
+        assert_eq!(serde_json::from_value::<ScheduleJobRequest>(edit).unwrap().work.kind(), "video_editing");
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkSessionAction {
+    Archive,
+    Unarchive,
+    /// Move to the trash (restorable until purged)
+    Delete,
+}
+
+/// Sessions are selected by id, by last activity, or both (both must match)
+#[derive(Debug, Deserialize)]
+pub struct BulkSessionRequest {
+    pub action: BulkSessionAction,
+    pub session_ids: Option<Vec<String>>,
+    pub updated_before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveItemsRequest {
+    pub target_session_id: String,
+    #[serde(default)]
+    pub file_ids: Vec<String>,
+    #[serde(default)]
+    pub output_ids: Vec<i32>,
+}
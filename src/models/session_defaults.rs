@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Parameters a chat session applies to every tool call that leaves them out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fps: Option<f64>,
+    /// Narration voice name (e.g. "Adam")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+    /// Caption preset: "bold", "karaoke", "minimal", "hype" or "none"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption_style: Option<String>,
+}
+
+impl SessionDefaults {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Partial update: set fields overwrite, `clear` names fields to unset ("all" resets everything)
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateSessionDefaultsRequest {
+    /// "4k", "1440p", "1080p", "720p", "480p" or "<width>x<height>"
+    pub resolution: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    pub voice: Option<String>,
+    pub caption_style: Option<String>,
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// A user's overrides of the server's cold storage settings (`[storage]` in the config)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StoragePolicy {
+    /// false keeps every output hot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Days an output goes untouched before it's tiered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cold_after_days: Option<u32>,
+    /// Gzip outputs as they're tiered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compress: Option<bool>,
+}
+
+/// The policy in effect for a user: their overrides over the server settings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveStoragePolicy {
+    pub enabled: bool,
+    pub cold_after_days: u32,
+    pub compress: bool,
+}
+
+/// Partial update: set fields overwrite, `clear` names fields to unset ("all" resets everything)
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateStoragePolicyRequest {
+    pub enabled: Option<bool>,
+    pub cold_after_days: Option<u32>,
+    pub compress: Option<bool>,
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
+
+/// An output in cold storage
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ColdOutput {
+    pub id: i32,
+    pub file_name: String,
+    pub file_path: String,
+    pub file_size: i64,
+    /// Size in the cold store (smaller than file_size when compressed)
+    pub cold_size: Option<i64>,
+    pub cold_compressed: bool,
+    pub tiered_at: Option<chrono::DateTime<chrono::Utc>>,
+}
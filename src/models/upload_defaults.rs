@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// What a user's YouTube uploads use for anything the upload request leaves out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UploadDefaults {
+    /// One of the user's connected channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<i32>,
+    /// "public", "private" or "unlisted"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy_status: Option<String>,
+    /// YouTube category id (e.g. "22" for People & Blogs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Added to every upload alongside the tags generated for the video
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Description with {title}, {summary}, {channel} and {date} filled in per upload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_template: Option<String>,
+}
+
+impl UploadDefaults {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Partial update: set fields overwrite, `clear` names fields to unset ("all" resets everything)
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateUploadDefaultsRequest {
+    pub channel_id: Option<i32>,
+    pub privacy_status: Option<String>,
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub description_template: Option<String>,
+    #[serde(default)]
+    pub clear: Vec<String>,
+}
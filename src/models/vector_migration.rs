@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VectorMigration {
+    pub id: i32,
+    pub source: String,
+    pub target: String,
+    pub status: String,
+    pub batch_size: i32,
+    /// Re-embed records whose vectors don't fit the target's dimension instead of failing them
+    pub reembed: bool,
+    /// Source page token of the next batch to copy (None = from the start)
+    pub cursor: Option<String>,
+    /// Every source page has been copied; only the final counts remain
+    pub scan_complete: bool,
+    pub source_count: Option<i64>,
+    pub target_count: Option<i64>,
+    pub copied: i64,
+    /// Records read back from the target with the same vector and payload
+    pub verified: i64,
+    pub failed_ids: Vec<String>,
+    pub mismatched_ids: Vec<String>,
+    pub error: Option<String>,
+    pub started_by: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartVectorMigrationRequest {
+    /// "astra" or "qdrant"
+    pub source: String,
+    pub target: String,
+    pub batch_size: Option<i32>,
+    pub reembed: Option<bool>,
+}
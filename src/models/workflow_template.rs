@@ -0,0 +1,67 @@
+use crate::jobs::dag::GraphNode;
+use crate::jobs::JobPriority;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// A named job graph whose tasks contain `{{parameter}}` slots
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WorkflowTemplate {
+    pub id: i32,
+    /// None for the built-in templates everyone sees
+    pub user_id: Option<i32>,
+    pub name: String,
+    pub description: Option<String>,
+    /// `Vec<TemplateParameter>` as JSON
+    pub parameters: serde_json::Value,
+    /// `Vec<GraphNode>` as JSON, tasks still holding their slots
+    pub jobs: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl WorkflowTemplate {
+    pub fn parameter_list(&self) -> Result<Vec<TemplateParameter>, String> {
+        serde_json::from_value(self.parameters.clone()).map_err(|e| format!("Template '{}' has invalid parameters: {}", self.name, e))
+    }
+
+    pub fn job_list(&self) -> Result<Vec<GraphNode>, String> {
+        serde_json::from_value(self.jobs.clone()).map_err(|e| format!("Template '{}' has invalid jobs: {}", self.name, e))
+    }
+}
+
+/// A slot a template's tasks refer to as `{{name}}`; required unless it has a default
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateParameter {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWorkflowTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<TemplateParameter>,
+    pub jobs: Vec<GraphNode>,
+}
+
+/// Values for a template's parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunTemplateRequest {
+    pub session_id: String,
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+    /// Queue priority of every job in the graph (default normal)
+    #[serde(default)]
+    pub priority: JobPriority,
+}
@@ -20,6 +20,9 @@ pub struct ConnectedYouTubeChannel {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub requires_reauth: Option<bool>,  // NEW: OAuth scope migration flag
+    pub token_status: String,  // healthy, degraded, revoked
+    pub token_error: Option<String>,
+    pub token_checked_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,6 +36,8 @@ pub struct ConnectedChannelResponse {
     pub video_count: Option<i64>,
     pub is_active: bool,
     pub connected_at: chrono::DateTime<chrono::Utc>,
+    pub token_status: String,
+    pub token_error: Option<String>,
 }
 
 impl From<ConnectedYouTubeChannel> for ConnectedChannelResponse {
@@ -47,6 +52,8 @@ impl From<ConnectedYouTubeChannel> for ConnectedChannelResponse {
             video_count: channel.video_count,
             is_active: channel.is_active,
             connected_at: channel.created_at,
+            token_status: channel.token_status,
+            token_error: channel.token_error,
         }
     }
 }
@@ -81,15 +88,21 @@ pub struct YouTubeUpload {
     pub bytes_uploaded: Option<i64>,
     pub total_bytes: Option<i64>,
     pub is_resumable: Option<bool>,
+    /// Uploaded privately ahead of time; metadata and visibility come at finalization
+    pub is_draft: bool,
+    pub finalized_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UploadToYouTubeRequest {
-    pub channel_id: i32,
+    /// Falls back to the user's default channel, like the other optional fields
+    pub channel_id: Option<i32>,
     pub video_path: String,
+    /// Blank uses the title generated for the output
+    #[serde(default)]
     pub title: String,
     pub description: Option<String>,
-    pub privacy_status: String, // "public", "private", "unlisted"
+    pub privacy_status: Option<String>, // "public", "private", "unlisted"
     pub category: Option<String>,
     pub tags: Option<Vec<String>>,
 }
@@ -374,6 +387,21 @@ pub struct ScheduleVideoRequest {
     pub publish_at: String,  // ISO 8601 timestamp
 }
 
+/// Final metadata for a draft upload; anything left out keeps the draft's value
+#[derive(Debug, Deserialize)]
+pub struct FinalizeDraftRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub category_id: Option<String>,
+    /// Visibility once finalized; defaults to the user's upload default privacy
+    pub privacy_status: Option<String>,
+    /// Publish at this time instead of now (the video stays private until then)
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Local image to set as the thumbnail (e.g. "outputs/thumbnail.jpg")
+    pub thumbnail_path: Option<String>,
+}
+
 // ============================================================================
 // Resumable Upload Models
 // ============================================================================
@@ -1,4 +1,5 @@
 // src/pexels_client.rs
+use crate::chaos::{ChaosSend, ChaosTarget};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -140,7 +141,7 @@ impl PexelsClient {
             .get(&format!("{}/videos/search", self.base_url))
             .header("Authorization", &self.api_key)
             .query(&params)
-            .send()
+            .send_with_chaos(ChaosTarget::Pexels)
             .await?;
 
         if !response.status().is_success() {
@@ -190,7 +191,7 @@ impl PexelsClient {
             .get(&format!("{}/v1/search", self.base_url))
             .header("Authorization", &self.api_key)
             .query(&params)
-            .send()
+            .send_with_chaos(ChaosTarget::Pexels)
             .await?;
 
         if !response.status().is_success() {
@@ -217,7 +218,7 @@ impl PexelsClient {
         let response = self.client
             .get(&video_file.link)
             .header("Authorization", &self.api_key)
-            .send()
+            .send_with_chaos(ChaosTarget::Pexels)
             .await?;
 
         if !response.status().is_success() {
@@ -262,7 +263,7 @@ impl PexelsClient {
         let response = self.client
             .get(image_url)
             .header("Authorization", &self.api_key)
-            .send()
+            .send_with_chaos(ChaosTarget::Pexels)
             .await?;
 
         if !response.status().is_success() {
@@ -305,7 +306,7 @@ impl PexelsClient {
             .get(&format!("{}/videos/popular", self.base_url))
             .header("Authorization", &self.api_key)
             .query(&params)
-            .send()
+            .send_with_chaos(ChaosTarget::Pexels)
             .await?;
 
         if !response.status().is_success() {
@@ -340,7 +341,7 @@ impl PexelsClient {
             .get(&format!("{}/v1/curated", self.base_url))
             .header("Authorization", &self.api_key)
             .query(&params)
-            .send()
+            .send_with_chaos(ChaosTarget::Pexels)
             .await?;
 
         if !response.status().is_success() {
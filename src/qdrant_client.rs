@@ -1,17 +1,23 @@
 use qdrant_client::qdrant::{
-    CreateCollectionBuilder, CreateFieldIndexCollectionBuilder, Distance, PointStruct, 
-    SearchPointsBuilder, UpsertPointsBuilder, VectorParamsBuilder, FieldType
+    CountPointsBuilder, CreateCollectionBuilder, CreateFieldIndexCollectionBuilder, Distance, GetPointsBuilder,
+    PointStruct, ScrollPointsBuilder, SearchPointsBuilder, UpsertPointsBuilder, VectorParamsBuilder, FieldType
 };
 use qdrant_client::{Qdrant, Payload};
+use crate::services::vector_migration::{VectorMemoryStore, VectorPage, VectorRecord};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
+/// Collection chat memory lived in before embedding indexes were versioned
+pub const DEFAULT_COLLECTION: &str = "chat_memory";
+
 #[derive(Clone)]
 pub struct QdrantClient {
     client: Qdrant,
-    collection_name: String,
+    /// The collection reads and writes go to; switched at runtime when a re-embedded index is cut over
+    collection_name: Arc<RwLock<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +32,16 @@ pub struct ChatMemoryDocument {
     pub files_referenced: Vec<String>,
 }
 
+/// One exchange to remember, before it gets an id and timestamp
+pub struct NewChatMemory<'a> {
+    pub session_id: &'a str,
+    pub user_id: Option<&'a str>,
+    pub user_message: &'a str,
+    pub agent_response: &'a str,
+    pub files_referenced: Vec<String>,
+    pub context: HashMap<String, serde_json::Value>,
+}
+
 impl QdrantClient {
     pub async fn new(
         url: String, 
@@ -41,25 +57,49 @@ impl QdrantClient {
         
         Ok(Self {
             client,
-            collection_name: "chat_memory".to_string(),
+            collection_name: Arc::new(RwLock::new(DEFAULT_COLLECTION.to_string())),
         })
     }
 
-    pub async fn create_collection(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        tracing::info!("Creating Qdrant collection: {}", self.collection_name);
+    /// The collection in use
+    pub fn collection(&self) -> String {
+        self.collection_name.read().unwrap().clone()
+    }
 
-        // Create collection with 1024 dimensions for Voyage AI embeddings (primary)
+    /// Send reads and writes of this client and its clones to another collection
+    pub fn set_collection(&self, name: &str) {
+        *self.collection_name.write().unwrap() = name.to_string();
+    }
+
+    /// A client for another collection on the same connection, leaving this one where it is
+    pub fn for_collection(&self, name: &str) -> Self {
+        Self {
+            client: self.client.clone(),
+            collection_name: Arc::new(RwLock::new(name.to_string())),
+        }
+    }
+
+    pub async fn create_collection(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // 1024 dimensions for Voyage AI embeddings (primary)
         // Note: Gemini embeddings are 768, but Voyage is 1024 and is our primary provider
+        self.create_collection_sized(1024).await
+    }
+
+    /// Create the collection for vectors of `dimension` if it doesn't exist yet
+    pub async fn create_collection_sized(&self, dimension: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let collection_name = self.collection();
+        tracing::info!("Creating Qdrant collection: {}", collection_name);
+
         let result = self.client
             .create_collection(
-                CreateCollectionBuilder::new(&self.collection_name)
-                    .vectors_config(VectorParamsBuilder::new(1024, Distance::Cosine))
+                CreateCollectionBuilder::new(&collection_name)
+                    .vectors_config(VectorParamsBuilder::new(dimension, Distance::Cosine))
             )
             .await;
 
         match result {
             Ok(_) => {
-                tracing::info!("Successfully created Qdrant collection: {}", self.collection_name);
+                tracing::info!("Successfully created Qdrant collection: {}", collection_name);
                 
                 // Create payload field indexes for efficient filtering
                 self.create_payload_indexes().await?;
@@ -69,12 +109,12 @@ impl QdrantClient {
             Err(e) => {
                 let error_msg = e.to_string();
                 if error_msg.contains("already exists") {
-                    tracing::debug!("Qdrant collection '{}' already exists, ensuring indexes exist", self.collection_name);
+                    tracing::debug!("Qdrant collection '{}' already exists, ensuring indexes exist", collection_name);
                     
                     // Still try to create indexes in case they're missing
                     self.create_payload_indexes().await?;
                 } else {
-                    tracing::warn!("Failed to create Qdrant collection '{}': {}", self.collection_name, e);
+                    tracing::warn!("Failed to create Qdrant collection '{}': {}", collection_name, e);
                 }
                 Ok(()) // Collection might already exist, which is fine
             }
@@ -88,7 +128,7 @@ impl QdrantClient {
         let session_id_index = self.client
             .create_field_index(
                 CreateFieldIndexCollectionBuilder::new(
-                    &self.collection_name,
+                    self.collection(),
                     "session_id",
                     FieldType::Keyword,
                 )
@@ -111,7 +151,7 @@ impl QdrantClient {
         let user_id_index = self.client
             .create_field_index(
                 CreateFieldIndexCollectionBuilder::new(
-                    &self.collection_name,
+                    self.collection(),
                     "user_id",
                     FieldType::Keyword,
                 )
@@ -134,7 +174,7 @@ impl QdrantClient {
         let timestamp_index = self.client
             .create_field_index(
                 CreateFieldIndexCollectionBuilder::new(
-                    &self.collection_name,
+                    self.collection(),
                     "timestamp",
                     FieldType::Keyword, // Using Keyword instead of Datetime for compatibility
                 )
@@ -157,28 +197,21 @@ impl QdrantClient {
         Ok(())
     }
 
-    pub async fn store_chat_memory_with_voyage(
+    /// Store a memory under the embedding of its user message, made with the collection's model
+    pub async fn store_chat_memory(
         &self,
-        session_id: &str,
-        user_id: Option<&str>,
-        user_message: &str,
-        agent_response: &str,
-        files_referenced: Vec<String>,
-        context: HashMap<String, serde_json::Value>,
-        voyage_client: &crate::voyage_embeddings::VoyageEmbeddings,
+        memory: NewChatMemory<'_>,
+        embedding: Vec<f32>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Generate embedding using Voyage AI
-        let embedding = voyage_client.generate_single_embedding(user_message.to_string()).await?;
-
         let document = ChatMemoryDocument {
             id: Uuid::new_v4().to_string(),
-            session_id: session_id.to_string(),
-            user_id: user_id.map(|s| s.to_string()),
+            session_id: memory.session_id.to_string(),
+            user_id: memory.user_id.map(|s| s.to_string()),
             timestamp: chrono::Utc::now(),
-            user_message: user_message.to_string(),
-            agent_response: agent_response.to_string(),
-            context,
-            files_referenced,
+            user_message: memory.user_message.to_string(),
+            agent_response: memory.agent_response.to_string(),
+            context: memory.context,
+            files_referenced: memory.files_referenced,
         };
 
         // Create payload from document
@@ -202,61 +235,7 @@ impl QdrantClient {
         // Upsert point to collection
         self.client
             .upsert_points(
-                UpsertPointsBuilder::new(&self.collection_name, vec![point])
-                    .wait(true),
-            )
-            .await?;
-
-        tracing::debug!("Stored chat memory with Voyage embeddings, ID: {}", document.id);
-        Ok(document.id)
-    }
-
-    pub async fn store_chat_memory_with_gemini(
-        &self,
-        session_id: &str,
-        user_id: Option<&str>,
-        user_message: &str,
-        agent_response: &str,
-        files_referenced: Vec<String>,
-        context: HashMap<String, serde_json::Value>,
-        gemini_client: &crate::gemini_client::GeminiClient,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Generate embedding using Gemini
-        let embedding = gemini_client.embed_content(user_message).await?;
-        
-        let document = ChatMemoryDocument {
-            id: Uuid::new_v4().to_string(),
-            session_id: session_id.to_string(),
-            user_id: user_id.map(|s| s.to_string()),
-            timestamp: chrono::Utc::now(),
-            user_message: user_message.to_string(),
-            agent_response: agent_response.to_string(),
-            context,
-            files_referenced,
-        };
-
-        // Create payload from document
-        let payload: Payload = json!({
-            "session_id": document.session_id,
-            "user_id": document.user_id,
-            "timestamp": document.timestamp.to_rfc3339(),
-            "user_message": document.user_message,
-            "agent_response": document.agent_response,
-            "context": document.context,
-            "files_referenced": document.files_referenced
-        }).try_into().unwrap();
-
-        // Create point
-        let point = PointStruct::new(
-            document.id.clone(),
-            embedding,
-            payload
-        );
-
-        // Upsert point to collection
-        self.client
-            .upsert_points(
-                UpsertPointsBuilder::new(&self.collection_name, vec![point])
+                UpsertPointsBuilder::new(self.collection(), vec![point])
                     .wait(true),
             )
             .await?;
@@ -265,107 +244,17 @@ impl QdrantClient {
         Ok(document.id)
     }
 
-    pub async fn search_similar_conversations_with_voyage(
+    /// Memories of a session closest to a query embedded with the collection's model
+    pub async fn search_similar_conversations(
         &self,
-        query: &str,
+        query_embedding: Vec<f32>,
         session_id: &str,
         limit: u32,
-        voyage_client: &crate::voyage_embeddings::VoyageEmbeddings,
     ) -> Result<Vec<ChatMemoryDocument>, Box<dyn std::error::Error + Send + Sync>> {
-        // Generate query embedding using Voyage AI
-        let query_embedding = voyage_client.generate_single_embedding(query.to_string()).await?;
-
         // Search for similar vectors
         let search_result = self.client
             .search_points(
-                SearchPointsBuilder::new(&self.collection_name, query_embedding, limit as u64)
-                    .filter(qdrant_client::qdrant::Filter {
-                        must: vec![qdrant_client::qdrant::Condition {
-                            condition_one_of: Some(
-                                qdrant_client::qdrant::condition::ConditionOneOf::Field(
-                                    qdrant_client::qdrant::FieldCondition {
-                                        key: "session_id".to_string(),
-                                        r#match: Some(qdrant_client::qdrant::Match {
-                                            match_value: Some(
-                                                qdrant_client::qdrant::r#match::MatchValue::Keyword(
-                                                    session_id.to_string()
-                                                )
-                                            ),
-                                        }),
-                                        ..Default::default()
-                                    }
-                                ),
-                            ),
-                        }],
-                        ..Default::default()
-                    })
-                    .with_payload(true)
-            )
-            .await?;
-
-        // Convert search results to documents
-        let mut documents = Vec::new();
-        for scored_point in search_result.result {
-            let payload = scored_point.payload;
-            let point_id = match scored_point.id {
-                Some(id) => match id.point_id_options {
-                    Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) => uuid,
-                    Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) => num.to_string(),
-                    None => continue,
-                },
-                None => continue,
-            };
-
-            let doc = ChatMemoryDocument {
-                id: point_id,
-                session_id: payload.get("session_id")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_default(),
-                user_id: payload.get("user_id")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string()),
-                timestamp: payload.get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                    .unwrap_or_else(chrono::Utc::now),
-                user_message: payload.get("user_message")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_default(),
-                agent_response: payload.get("agent_response")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_default(),
-                context: HashMap::new(),
-                files_referenced: payload.get("files_referenced")
-                    .and_then(|v| {
-                        let json_val: serde_json::Value = serde_json::to_value(v).ok()?;
-                        serde_json::from_value(json_val).ok()
-                    })
-                    .unwrap_or_default(),
-            };
-            documents.push(doc);
-        }
-
-        Ok(documents)
-    }
-
-    pub async fn search_similar_conversations_with_gemini(
-        &self,
-        query: &str,
-        session_id: &str,
-        limit: u32,
-        gemini_client: &crate::gemini_client::GeminiClient,
-    ) -> Result<Vec<ChatMemoryDocument>, Box<dyn std::error::Error + Send + Sync>> {
-        // Generate query embedding
-        let query_embedding = gemini_client.embed_content(query).await?;
-
-        // Search for similar vectors
-        let search_result = self.client
-            .search_points(
-                SearchPointsBuilder::new(&self.collection_name, query_embedding, limit as u64)
+                SearchPointsBuilder::new(self.collection(), query_embedding, limit as u64)
                     .filter(qdrant_client::qdrant::Filter {
                         must: vec![qdrant_client::qdrant::Condition {
                             condition_one_of: Some(
@@ -453,7 +342,7 @@ impl QdrantClient {
         
         let search_result = self.client
             .search_points(
-                SearchPointsBuilder::new(&self.collection_name, zero_vector, limit as u64)
+                SearchPointsBuilder::new(self.collection(), zero_vector, limit as u64)
                     .filter(qdrant_client::qdrant::Filter {
                         must: vec![qdrant_client::qdrant::Condition {
                             condition_one_of: Some(
@@ -534,84 +423,6 @@ impl QdrantClient {
         Ok(documents)
     }
 
-    pub async fn build_context_for_query_with_voyage(
-        &self,
-        query: &str,
-        session_id: &str,
-        voyage_client: &crate::voyage_embeddings::VoyageEmbeddings,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Get recent conversation history
-        let recent_history = self.get_session_history(session_id, 5).await?;
-
-        // Get similar past conversations using Voyage embeddings
-        let similar_conversations = self.search_similar_conversations_with_voyage(query, session_id, 3, voyage_client).await?;
-
-        let mut context = String::new();
-
-        if !recent_history.is_empty() {
-            context.push_str("Recent conversation history:\n");
-            for memory in recent_history.iter().rev() { // Reverse to show chronologically
-                context.push_str(&format!(
-                    "User: {}\nAssistant: {}\n\n",
-                    memory.user_message,
-                    memory.agent_response
-                ));
-            }
-        }
-
-        if !similar_conversations.is_empty() {
-            context.push_str("Similar past conversations:\n");
-            for memory in &similar_conversations {
-                context.push_str(&format!(
-                    "User: {}\nAssistant: {}\n\n",
-                    memory.user_message,
-                    memory.agent_response
-                ));
-            }
-        }
-
-        Ok(context)
-    }
-
-    pub async fn build_context_for_query_with_gemini(
-        &self,
-        query: &str,
-        session_id: &str,
-        gemini_client: &crate::gemini_client::GeminiClient,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Get recent conversation history
-        let recent_history = self.get_session_history(session_id, 5).await?;
-
-        // Get similar past conversations using Gemini embeddings
-        let similar_conversations = self.search_similar_conversations_with_gemini(query, session_id, 3, gemini_client).await?;
-
-        let mut context = String::new();
-
-        if !recent_history.is_empty() {
-            context.push_str("Recent conversation history:\n");
-            for memory in recent_history.iter().rev() { // Reverse to show chronologically
-                context.push_str(&format!(
-                    "User: {}\nAssistant: {}\n\n",
-                    memory.user_message,
-                    memory.agent_response
-                ));
-            }
-        }
-
-        if !similar_conversations.is_empty() {
-            context.push_str("Similar past conversations:\n");
-            for memory in &similar_conversations {
-                context.push_str(&format!(
-                    "User: {}\nAssistant: {}\n\n",
-                    memory.user_message,
-                    memory.agent_response
-                ));
-            }
-        }
-
-        Ok(context)
-    }
-
     /// Upsert a single point into the collection
     pub async fn upsert_point(
         &self,
@@ -635,7 +446,7 @@ impl QdrantClient {
             qdrant_payload,
         );
 
-        let upsert_request = UpsertPointsBuilder::new(&self.collection_name, vec![point])
+        let upsert_request = UpsertPointsBuilder::new(self.collection(), vec![point])
             .wait(true);
 
         self.client.upsert_points(upsert_request).await?;
@@ -651,7 +462,7 @@ impl QdrantClient {
     ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
         use qdrant_client::qdrant::{SearchPointsBuilder, Filter, Condition};
         
-        let mut search_builder = SearchPointsBuilder::new(&self.collection_name, query_vector.to_vec(), limit as u64)
+        let mut search_builder = SearchPointsBuilder::new(self.collection(), query_vector.to_vec(), limit as u64)
             .with_payload(true);
 
         // Apply filter if provided
@@ -707,4 +518,127 @@ impl QdrantClient {
         
         Ok(results)
     }
-}
\ No newline at end of file
+
+    /// Delete every point whose `key` payload field is one of `values`, e.g. all of a session's
+    /// chat memories and video/frame embeddings (`session_id`), or one file's frames (`file_path`)
+    pub async fn delete_points_matching(&self, key: &str, values: Vec<String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        use qdrant_client::qdrant::{Condition, DeletePointsBuilder, Filter};
+
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(self.collection())
+                    .points(Filter::must([Condition::matches(key, values)]))
+                    .wait(true),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Point ids are UUIDs or unsigned integers; ids from other backends must already be one of those
+fn parse_point_id(id: &str) -> Result<qdrant_client::qdrant::PointId, String> {
+    if let Ok(num) = id.parse::<u64>() {
+        return Ok(num.into());
+    }
+    Uuid::parse_str(id)
+        .map(|uuid| uuid.to_string().into())
+        .map_err(|_| format!("'{}' isn't a valid Qdrant point id (UUID or integer)", id))
+}
+
+fn point_id_string(id: Option<qdrant_client::qdrant::PointId>) -> Option<String> {
+    match id?.point_id_options? {
+        qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid) => Some(uuid),
+        qdrant_client::qdrant::point_id::PointIdOptions::Num(num) => Some(num.to_string()),
+    }
+}
+
+fn retrieved_record(point: qdrant_client::qdrant::RetrievedPoint) -> Option<VectorRecord> {
+    use qdrant_client::qdrant::{vector_output::Vector, vectors_output::VectorsOptions};
+
+    let id = point_id_string(point.id)?;
+    let vector = match point.vectors?.vectors_options? {
+        VectorsOptions::Vector(output) => match output.vector {
+            Some(Vector::Dense(dense)) => dense.data,
+            _ => output.data,
+        },
+        VectorsOptions::Vectors(_) => return None,
+    };
+    let payload = point
+        .payload
+        .into_iter()
+        .filter_map(|(key, value)| serde_json::to_value(value).ok().map(|value| (key, value)))
+        .collect();
+    Some(VectorRecord { id, vector, payload })
+}
+
+#[async_trait::async_trait]
+impl VectorMemoryStore for QdrantClient {
+    async fn ensure_collection(&self) -> Result<(), String> {
+        self.create_collection().await.map_err(|e| e.to_string())
+    }
+
+    async fn dimension(&self) -> Result<Option<usize>, String> {
+        use qdrant_client::qdrant::vectors_config::Config;
+
+        let info = self.client.collection_info(self.collection()).await.map_err(|e| e.to_string())?;
+        let config = info
+            .result
+            .and_then(|info| info.config)
+            .and_then(|config| config.params)
+            .and_then(|params| params.vectors_config)
+            .and_then(|vectors| vectors.config);
+        Ok(match config {
+            Some(Config::Params(params)) => Some(params.size as usize),
+            _ => None,
+        })
+    }
+
+    async fn scan(&self, cursor: Option<&str>, limit: usize) -> Result<VectorPage, String> {
+        let mut request = ScrollPointsBuilder::new(self.collection())
+            .limit(limit as u32)
+            .with_payload(true)
+            .with_vectors(true);
+        if let Some(cursor) = cursor {
+            request = request.offset(parse_point_id(cursor)?);
+        }
+        let response = self.client.scroll(request).await.map_err(|e| e.to_string())?;
+        Ok(VectorPage {
+            records: response.result.into_iter().filter_map(retrieved_record).collect(),
+            next: point_id_string(response.next_page_offset),
+        })
+    }
+
+    async fn upsert(&self, records: &[VectorRecord]) -> Result<(), String> {
+        let mut points = Vec::with_capacity(records.len());
+        for record in records {
+            let payload: Payload = serde_json::Value::Object(record.payload.clone())
+                .try_into()
+                .map_err(|e| format!("Invalid payload for {}: {}", record.id, e))?;
+            points.push(PointStruct::new(parse_point_id(&record.id)?, record.vector.clone(), payload));
+        }
+        self.client
+            .upsert_points(UpsertPointsBuilder::new(self.collection(), points).wait(true))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn fetch(&self, ids: &[String]) -> Result<Vec<VectorRecord>, String> {
+        let ids = ids.iter().map(|id| parse_point_id(id)).collect::<Result<Vec<_>, _>>()?;
+        let response = self
+            .client
+            .get_points(GetPointsBuilder::new(self.collection(), ids).with_payload(true).with_vectors(true))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(response.result.into_iter().filter_map(retrieved_record).collect())
+    }
+
+    async fn count(&self) -> Result<u64, String> {
+        let response = self
+            .client
+            .count(CountPointsBuilder::new(self.collection()).exact(true))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(response.result.map(|result| result.count).unwrap_or(0))
+    }
+}
@@ -0,0 +1,171 @@
+// src/services/activity.rs
+//! Workspace audit log: uploads, renders, publishes, approvals and failures are recorded as they
+//! happen and read back newest first as the user's activity feed ("what happened while I was away").
+use crate::jobs::{Job, JobStatus};
+use crate::models::activity::ActivityEvent;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+/// Most events returned by one feed request
+pub const MAX_PAGE: i64 = 100;
+
+/// Feed categories, as filtered on by the API
+pub const CATEGORIES: [&str; 5] = ["upload", "render", "publish", "approval", "failure"];
+
+/// Job types whose work shows up through a parent job or a more specific event
+const QUIET_JOB_TYPES: [&str; 1] = ["batch_render_row"];
+
+/// Feed category an event kind is listed under
+fn category_of(kind: &str) -> &'static str {
+    match kind {
+        _ if kind.ends_with("_failed") => "failure",
+        "file_uploaded" => "upload",
+        "job_completed" => "render",
+        "youtube_published" | "clip_published" => "publish",
+        "clip_approved" => "approval",
+        _ => "other",
+    }
+}
+
+pub struct ActivityService;
+
+impl ActivityService {
+    /// Add an event to the user's audit log. Recording never fails the action it describes, so
+    /// errors are only logged.
+    pub async fn record(pool: &PgPool, user_id: i32, kind: &str, summary: &str, session_id: Option<&str>, data: Value) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO activity_events (user_id, kind, category, summary, session_id, data)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(category_of(kind))
+        .bind(summary)
+        .bind(session_id)
+        .bind(data)
+        .execute(pool)
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to record {} activity for user {}: {}", kind, user_id, e);
+        }
+    }
+
+    /// Record an event about an extracted clip for the user whose channel linkage produced it
+    pub async fn record_clip(pool: &PgPool, clip_id: i32, kind: &str, detail: Option<&str>) {
+        let owner: Option<(i32, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT l.user_id, c.ai_title
+            FROM extracted_clips c
+            JOIN clipping_jobs j ON j.id = c.clipping_job_id
+            JOIN youtube_channel_linkages l ON l.id = j.linkage_id
+            WHERE c.id = $1
+            "#,
+        )
+        .bind(clip_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to look up the owner of clip {}: {}", clip_id, e);
+            None
+        });
+        let Some((user_id, title)) = owner else { return };
+
+        let name = title.unwrap_or_else(|| format!("Clip {}", clip_id));
+        let summary = match kind {
+            "clip_published" => format!("Clip \"{}\" was posted", name),
+            "clip_approved" => format!("Clip \"{}\" was approved for posting", name),
+            "clip_upload_failed" => format!("Clip \"{}\" failed to post", name),
+            _ => format!("Clip \"{}\": {}", name, kind.replace('_', " ")),
+        };
+        Self::record(pool, user_id, kind, &summary, None, json!({ "clip_id": clip_id, "detail": detail })).await;
+    }
+
+    /// Record a job that completed (a render) or failed
+    pub fn job_finished(pool: PgPool, job: Job) {
+        if QUIET_JOB_TYPES.contains(&job.job_type.as_str()) {
+            return;
+        }
+        let Some(user_id) = job.user_id.as_deref().and_then(|id| id.parse::<i32>().ok()) else {
+            return;
+        };
+        let name = job.job_type.replace('_', " ");
+        let (kind, summary, data) = match &job.status {
+            JobStatus::Completed { output_files, duration_seconds, .. } => (
+                "job_completed",
+                format!("{} job finished", capitalize(&name)),
+                json!({ "job_id": job.id, "job_type": job.job_type, "output_files": output_files, "duration_seconds": duration_seconds }),
+            ),
+            JobStatus::Failed { error, failed_at_step } => (
+                "job_failed",
+                format!("{} job failed", capitalize(&name)),
+                json!({ "job_id": job.id, "job_type": job.job_type, "error": error, "failed_at_step": failed_at_step }),
+            ),
+            _ => return,
+        };
+
+        tokio::spawn(async move {
+            Self::record(&pool, user_id, kind, &summary, Some(&job.session_id), data).await;
+        });
+    }
+
+    /// The user's feed, newest first. `before` pages back from an event id; `since` stops at a time,
+    /// e.g. when the user was last here.
+    pub async fn feed(
+        pool: &PgPool,
+        user_id: i32,
+        categories: &[String],
+        session_id: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        before: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<ActivityEvent>, sqlx::Error> {
+        sqlx::query_as::<_, ActivityEvent>(
+            r#"
+            SELECT * FROM activity_events
+            WHERE user_id = $1
+              AND (cardinality($2::TEXT[]) = 0 OR category = ANY($2))
+              AND ($3::TEXT IS NULL OR session_id = $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+              AND ($5::INTEGER IS NULL OR id < $5)
+            ORDER BY id DESC
+            LIMIT $6
+            "#,
+        )
+        .bind(user_id)
+        .bind(categories)
+        .bind(session_id)
+        .bind(since)
+        .bind(before)
+        .bind(limit.clamp(1, MAX_PAGE))
+        .fetch_all(pool)
+        .await
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_kinds_fall_into_feed_categories() {
+        assert_eq!(category_of("file_uploaded"), "upload");
+        assert_eq!(category_of("job_completed"), "render");
+        assert_eq!(category_of("youtube_published"), "publish");
+        assert_eq!(category_of("clip_approved"), "approval");
+        assert_eq!(category_of("job_failed"), "failure");
+        assert_eq!(category_of("youtube_upload_failed"), "failure");
+        assert!(["upload", "render", "publish", "approval", "failure"].iter().all(|c| CATEGORIES.contains(c)));
+        assert_eq!(capitalize("batch process"), "Batch process");
+    }
+}
@@ -0,0 +1,332 @@
+// src/services/asset_tagging.rs
+// Background vision tagging for library assets and uploads: objects, scenes, dominant colors, faces
+// Tags are stored in asset_visual_tags and their summary is embedded in Qdrant for semantic search
+use crate::models::library::AssetVisualTags;
+use crate::AppState;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Frames sampled from a video (as fractions of its duration)
+const VIDEO_SAMPLE_POINTS: [f64; 3] = [0.2, 0.5, 0.8];
+
+const TAGGING_PROMPT: &str = r#"Tag this image for a searchable media library. Respond with ONLY a JSON object:
+{"caption": "one sentence describing the shot, including camera angle if notable (e.g. aerial drone shot)",
+ "objects": ["main objects and subjects, lowercase nouns"],
+ "scenes": ["setting / scene types, e.g. beach, city street, office, underwater, sky"],
+ "dominant_colors": ["2-4 plain color names"],
+ "has_faces": true or false}"#;
+
+/// Tags parsed from one model response
+#[derive(Debug, Default)]
+struct FrameTags {
+    caption: String,
+    objects: Vec<String>,
+    scenes: Vec<String>,
+    dominant_colors: Vec<String>,
+    has_faces: bool,
+}
+
+pub struct AssetTaggingService;
+
+impl AssetTaggingService {
+    /// Queue a file for tagging; runs in the background and never fails the caller
+    pub fn spawn_tagging(state: Arc<AppState>, source: &str, source_id: String, user_id: Option<i32>, file_path: String) {
+        let source = source.to_string();
+        tokio::spawn(async move {
+            match Self::tag_file(&state, &source, &source_id, user_id, &file_path).await {
+                Ok(tags) => tracing::info!("🏷️ Tagged {} {} ({}): {}", source, source_id, tags.status, tags.summary()),
+                Err(e) => tracing::warn!("Vision tagging failed for {} {}: {}", source, source_id, e),
+            }
+        });
+    }
+
+    /// Tag a file now, storing the result (including failures) in asset_visual_tags
+    pub async fn tag_file(
+        state: &Arc<AppState>,
+        source: &str,
+        source_id: &str,
+        user_id: Option<i32>,
+        file_path: &str,
+    ) -> Result<AssetVisualTags, String> {
+        let pool = &state.db_pool;
+        sqlx::query(
+            r#"
+            INSERT INTO asset_visual_tags (source, source_id, user_id, file_path, status)
+            VALUES ($1, $2, $3, $4, 'pending')
+            ON CONFLICT (source, source_id) DO UPDATE SET file_path = EXCLUDED.file_path, status = 'pending', error_message = NULL
+            "#,
+        )
+        .bind(source)
+        .bind(source_id)
+        .bind(user_id)
+        .bind(file_path)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to queue tagging: {}", e))?;
+
+        let frames = match Self::sample_frames(file_path).await {
+            Ok(frames) => frames,
+            Err(e) => return Self::finish(pool, source, source_id, "failed", None, Some(&e)).await,
+        };
+        if frames.is_empty() {
+            // Audio and documents have nothing to look at
+            return Self::finish(pool, source, source_id, "skipped", None, None).await;
+        }
+
+        let Some(gemini_client) = state.gemini_client.as_ref() else {
+            return Self::finish(pool, source, source_id, "failed", None, Some("Gemini client not available")).await;
+        };
+
+        let mut merged = FrameTags::default();
+        let mut captions = Vec::new();
+        for frame in &frames {
+            match gemini_client.analyze_image_bytes(frame, TAGGING_PROMPT).await {
+                Ok(response) => match Self::parse_tags(&response) {
+                    Some(tags) => {
+                        if !tags.caption.is_empty() {
+                            captions.push(tags.caption.clone());
+                        }
+                        Self::merge(&mut merged, tags);
+                    }
+                    None => tracing::warn!("Unparseable tagging response for {}: {}", file_path, response),
+                },
+                Err(e) => tracing::warn!("Vision model call failed for {}: {}", file_path, e),
+            }
+        }
+        if captions.is_empty() && merged.objects.is_empty() {
+            return Self::finish(pool, source, source_id, "failed", None, Some("Vision model returned no usable tags")).await;
+        }
+        // The middle frame's caption is the most representative for videos
+        merged.caption = captions.get(captions.len() / 2).cloned().unwrap_or_default();
+
+        let tags = Self::finish(pool, source, source_id, "tagged", Some(&merged), None).await?;
+        match Self::store_embedding(state, &tags).await {
+            Ok(true) => {
+                let _ = sqlx::query("UPDATE asset_visual_tags SET embedded = TRUE WHERE id = $1")
+                    .bind(tags.id)
+                    .execute(pool)
+                    .await;
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to embed tags for {} {}: {}", source, source_id, e),
+        }
+        Ok(tags)
+    }
+
+    async fn finish(
+        pool: &PgPool,
+        source: &str,
+        source_id: &str,
+        status: &str,
+        tags: Option<&FrameTags>,
+        error: Option<&str>,
+    ) -> Result<AssetVisualTags, String> {
+        let row = sqlx::query_as::<_, AssetVisualTags>(
+            r#"
+            UPDATE asset_visual_tags SET
+                status = $3,
+                caption = $4,
+                objects = $5,
+                scenes = $6,
+                dominant_colors = $7,
+                has_faces = $8,
+                error_message = $9,
+                tagged_at = NOW()
+            WHERE source = $1 AND source_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(source)
+        .bind(source_id)
+        .bind(status)
+        .bind(tags.map(|t| t.caption.clone()).filter(|c| !c.is_empty()))
+        .bind(tags.map(|t| t.objects.clone()).unwrap_or_default())
+        .bind(tags.map(|t| t.scenes.clone()).unwrap_or_default())
+        .bind(tags.map(|t| t.dominant_colors.clone()).unwrap_or_default())
+        .bind(tags.map(|t| t.has_faces))
+        .bind(error)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save tags: {}", e))?;
+
+        match error {
+            Some(e) if status == "failed" => Err(e.to_string()),
+            _ => Ok(row),
+        }
+    }
+
+    /// JPEG/PNG bytes to show the model: the image itself, or frames sampled across a video
+    async fn sample_frames(file_path: &str) -> Result<Vec<Vec<u8>>, String> {
+        let extension = Path::new(file_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "webp" | "gif" => {
+                let bytes = tokio::fs::read(file_path).await.map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+                Ok(vec![bytes])
+            }
+            "mp4" | "mov" | "webm" | "mkv" | "avi" => {
+                let path = file_path.to_string();
+                let duration = tokio::task::spawn_blocking(move || crate::core::analyze_video(&path))
+                    .await
+                    .map_err(|e| e.to_string())??
+                    .duration_seconds;
+
+                let mut frames = Vec::new();
+                for point in VIDEO_SAMPLE_POINTS {
+                    let output = tokio::process::Command::new("ffmpeg")
+                        .arg("-ss")
+                        .arg(format!("{:.3}", duration * point))
+                        .arg("-i")
+                        .arg(file_path)
+                        .arg("-frames:v")
+                        .arg("1")
+                        .arg("-vf")
+                        .arg("scale=640:-2")
+                        .arg("-f")
+                        .arg("image2pipe")
+                        .arg("-vcodec")
+                        .arg("mjpeg")
+                        .arg("-")
+                        .output()
+                        .await
+                        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+                    if output.status.success() && !output.stdout.is_empty() {
+                        frames.push(output.stdout);
+                    }
+                }
+                if frames.is_empty() {
+                    return Err(format!("Could not extract frames from {}", file_path));
+                }
+                Ok(frames)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Pull the JSON object out of a model response (tolerates code fences and surrounding prose)
+    fn parse_tags(response: &str) -> Option<FrameTags> {
+        let start = response.find('{')?;
+        let end = response.rfind('}')?;
+        let parsed: Value = serde_json::from_str(response.get(start..=end)?).ok()?;
+
+        let list = |key: &str| -> Vec<String> {
+            parsed[key]
+                .as_array()
+                .map(|a| a.iter().filter_map(|v| v.as_str()).map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()).collect())
+                .unwrap_or_default()
+        };
+        Some(FrameTags {
+            caption: parsed["caption"].as_str().unwrap_or("").trim().to_string(),
+            objects: list("objects"),
+            scenes: list("scenes"),
+            dominant_colors: list("dominant_colors"),
+            has_faces: parsed["has_faces"].as_bool().unwrap_or(false),
+        })
+    }
+
+    fn merge(into: &mut FrameTags, tags: FrameTags) {
+        for (target, values) in [
+            (&mut into.objects, tags.objects),
+            (&mut into.scenes, tags.scenes),
+            (&mut into.dominant_colors, tags.dominant_colors),
+        ] {
+            for value in values {
+                if !target.contains(&value) {
+                    target.push(value);
+                }
+            }
+        }
+        into.has_faces |= tags.has_faces;
+    }
+
+    /// Deterministic Qdrant point id for a tagged file
+    pub fn point_id(source: &str, source_id: &str) -> String {
+        let digest = Sha256::digest(format!("asset_tags:{}:{}", source, source_id).as_bytes());
+        uuid::Uuid::from_slice(&digest[..16]).unwrap_or_default().to_string()
+    }
+
+    /// Embed the tag summary; Ok(false) when no vector store is configured
+    async fn store_embedding(state: &Arc<AppState>, tags: &AssetVisualTags) -> Result<bool, String> {
+        let (Some(qdrant), Some(gemini)) = (state.qdrant_client.as_ref(), state.gemini_client.as_ref()) else {
+            return Ok(false);
+        };
+
+        let summary = tags.summary();
+        let embedding = gemini.embed_content(&summary).await.map_err(|e| e.to_string())?;
+        let payload = json!({
+            "content_type": "asset_tags",
+            "source": tags.source,
+            "source_id": tags.source_id,
+            "user_id": tags.user_id,
+            "file_path": tags.file_path,
+            "content": summary,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        });
+        qdrant
+            .upsert_point(&Self::point_id(&tags.source, &tags.source_id), &embedding, &payload)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// Semantic search over a user's tagged files; returns (point id, score), best first
+    pub async fn semantic_search(
+        state: &Arc<AppState>,
+        user_id: i32,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, f64)>, String> {
+        let (Some(qdrant), Some(gemini)) = (state.qdrant_client.as_ref(), state.gemini_client.as_ref()) else {
+            return Ok(Vec::new());
+        };
+
+        let embedding = gemini.embed_content(query).await.map_err(|e| e.to_string())?;
+        let filter = json!({
+            "must": [
+                { "key": "content_type", "match": { "value": "asset_tags" } },
+                { "key": "user_id", "match": { "value": user_id } }
+            ]
+        });
+        let hits = qdrant
+            .search_points(&embedding, limit, Some(&filter))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(hits
+            .iter()
+            .filter_map(|hit| Some((hit["id"].as_str()?.to_string(), hit["score"].as_f64()?)))
+            .collect())
+    }
+
+    pub async fn get_tags(pool: &PgPool, source: &str, source_id: &str) -> Result<Option<AssetVisualTags>, sqlx::Error> {
+        sqlx::query_as::<_, AssetVisualTags>(
+            "SELECT * FROM asset_visual_tags WHERE source = $1 AND source_id = $2"
+        )
+        .bind(source)
+        .bind(source_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Tags for several files of one source at once, keyed by source id
+    pub async fn get_tags_for(
+        pool: &PgPool,
+        source: &str,
+        source_ids: &[String],
+    ) -> Result<std::collections::HashMap<String, AssetVisualTags>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AssetVisualTags>(
+            "SELECT * FROM asset_visual_tags WHERE source = $1 AND source_id = ANY($2)"
+        )
+        .bind(source)
+        .bind(source_ids)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|t| (t.source_id.clone(), t)).collect())
+    }
+}
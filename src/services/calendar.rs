@@ -0,0 +1,255 @@
+// src/services/calendar.rs
+//! Content calendar: everything a user has lined up in a time range - scheduled jobs, YouTube
+//! videos set to publish at a time, and the clipping pipeline's planned runs and posted clips.
+use crate::models::calendar::CalendarEntry;
+use crate::models::scheduled_job::ScheduledJob;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+/// Longest range one calendar request can cover
+pub const MAX_RANGE_DAYS: i64 = 92;
+
+/// Range shown when the request doesn't give one
+pub const DEFAULT_RANGE_DAYS: i64 = 30;
+
+/// Planned clipping runs listed per linkage; polls every few minutes would otherwise flood the range
+const MAX_PLANNED_RUNS: usize = 50;
+
+/// Every kind of calendar entry
+pub const KINDS: [&str; 4] = ["scheduled_job", "youtube_publish", "clipping_run", "clip_post"];
+
+#[derive(sqlx::FromRow)]
+struct ScheduledPublish {
+    id: i32,
+    video_title: String,
+    scheduled_publish_at: DateTime<Utc>,
+    privacy_status: Option<String>,
+    youtube_url: Option<String>,
+    is_draft: bool,
+    channel_name: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct ClippingLinkage {
+    id: i32,
+    source_name: String,
+    destination_name: String,
+    next_poll_at: DateTime<Utc>,
+    polling_interval_minutes: i32,
+    clips_per_video: i32,
+}
+
+#[derive(sqlx::FromRow)]
+struct PostedClip {
+    id: i32,
+    ai_title: Option<String>,
+    published_at: DateTime<Utc>,
+    youtube_url: Option<String>,
+    source_video_title: Option<String>,
+    destination_name: String,
+}
+
+pub struct CalendarService;
+
+impl CalendarService {
+    /// The user's calendar between `from` and `to`, in time order; `kinds` limits it to some entry kinds
+    pub async fn entries(
+        pool: &PgPool,
+        user_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        kinds: &[String],
+    ) -> Result<Vec<CalendarEntry>, sqlx::Error> {
+        let wanted = |kind: &str| kinds.is_empty() || kinds.iter().any(|k| k == kind);
+        let mut entries = Vec::new();
+
+        if wanted("scheduled_job") {
+            let jobs = sqlx::query_as::<_, ScheduledJob>(
+                "SELECT * FROM scheduled_jobs WHERE user_id = $1 AND run_at >= $2 AND run_at < $3 ORDER BY run_at"
+            )
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(pool)
+            .await?;
+            entries.extend(jobs.iter().map(scheduled_job_entry));
+        }
+
+        if wanted("youtube_publish") {
+            let publishes = sqlx::query_as::<_, ScheduledPublish>(
+                r#"
+                SELECT u.id, u.video_title, u.scheduled_publish_at, u.privacy_status, u.youtube_url, u.is_draft, c.channel_name
+                FROM youtube_uploads u
+                JOIN connected_youtube_channels c ON c.id = u.channel_id
+                WHERE u.user_id = $1 AND u.deleted_at IS NULL AND u.is_scheduled = TRUE
+                  AND u.scheduled_publish_at >= $2 AND u.scheduled_publish_at < $3
+                ORDER BY u.scheduled_publish_at
+                "#,
+            )
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(pool)
+            .await?;
+            let now = Utc::now();
+            entries.extend(publishes.into_iter().map(|publish| CalendarEntry {
+                kind: "youtube_publish".to_string(),
+                at: publish.scheduled_publish_at,
+                title: format!("Publish \"{}\" on {}", publish.video_title, publish.channel_name),
+                status: if publish.scheduled_publish_at > now { "scheduled" } else { "published" }.to_string(),
+                source_id: publish.id.to_string(),
+                details: json!({
+                    "upload_id": publish.id,
+                    "channel": publish.channel_name,
+                    "privacy_status": publish.privacy_status,
+                    "youtube_url": publish.youtube_url,
+                    "is_draft": publish.is_draft,
+                }),
+            }));
+        }
+
+        if wanted("clipping_run") {
+            let linkages = sqlx::query_as::<_, ClippingLinkage>(
+                r#"
+                SELECT l.id, s.channel_name AS source_name, d.channel_name AS destination_name,
+                       COALESCE(p.next_poll_at, NOW()) AS next_poll_at,
+                       COALESCE(s.polling_interval_minutes, 30) AS polling_interval_minutes,
+                       COALESCE(l.clips_per_video, 2) AS clips_per_video
+                FROM youtube_channel_linkages l
+                JOIN youtube_source_channels s ON s.id = l.source_channel_id
+                JOIN connected_youtube_channels d ON d.id = l.destination_channel_id
+                LEFT JOIN clipping_poll_schedule p ON p.source_channel_id = s.id
+                WHERE l.user_id = $1 AND l.is_active = TRUE AND s.is_active = TRUE
+                "#,
+            )
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+            for linkage in linkages {
+                let interval = linkage.polling_interval_minutes.max(1) as i64;
+                for at in planned_runs(linkage.next_poll_at, interval, from, to, MAX_PLANNED_RUNS) {
+                    entries.push(CalendarEntry {
+                        kind: "clipping_run".to_string(),
+                        at,
+                        title: format!("Clip new {} videos to {}", linkage.source_name, linkage.destination_name),
+                        status: "planned".to_string(),
+                        source_id: linkage.id.to_string(),
+                        details: json!({
+                            "linkage_id": linkage.id,
+                            "source_channel": linkage.source_name,
+                            "destination_channel": linkage.destination_name,
+                            "clips_per_video": linkage.clips_per_video,
+                            "every_minutes": interval,
+                        }),
+                    });
+                }
+            }
+        }
+
+        if wanted("clip_post") {
+            let clips = sqlx::query_as::<_, PostedClip>(
+                r#"
+                SELECT c.id, c.ai_title, c.published_at, c.youtube_url, j.source_video_title, d.channel_name AS destination_name
+                FROM extracted_clips c
+                JOIN clipping_jobs j ON j.id = c.clipping_job_id
+                JOIN youtube_channel_linkages l ON l.id = j.linkage_id
+                JOIN connected_youtube_channels d ON d.id = l.destination_channel_id
+                WHERE l.user_id = $1 AND c.upload_status = 'published'
+                  AND c.published_at >= $2 AND c.published_at < $3
+                ORDER BY c.published_at
+                "#,
+            )
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(pool)
+            .await?;
+            entries.extend(clips.into_iter().map(|clip| CalendarEntry {
+                kind: "clip_post".to_string(),
+                at: clip.published_at,
+                title: format!("Clip \"{}\" posted to {}", clip.ai_title.as_deref().unwrap_or("untitled"), clip.destination_name),
+                status: "published".to_string(),
+                source_id: clip.id.to_string(),
+                details: json!({
+                    "clip_id": clip.id,
+                    "source_video": clip.source_video_title,
+                    "channel": clip.destination_name,
+                    "youtube_url": clip.youtube_url,
+                }),
+            }));
+        }
+
+        entries.sort_by_key(|entry| entry.at);
+        Ok(entries)
+    }
+}
+
+fn scheduled_job_entry(job: &ScheduledJob) -> CalendarEntry {
+    let title = match job.kind.as_str() {
+        "youtube_upload" => {
+            let upload = &job.payload["upload"];
+            let name = upload["title"].as_str().filter(|t| !t.is_empty()).or(upload["video_path"].as_str()).unwrap_or("video");
+            format!("Upload \"{}\" to YouTube", name)
+        }
+        "video_editing" => {
+            let task = job.payload["task"].as_str().unwrap_or("");
+            let short: String = task.chars().take(80).collect();
+            if short.len() < task.len() { format!("Edit: {}…", short) } else { format!("Edit: {}", short) }
+        }
+        other => other.replace('_', " "),
+    };
+    let mut details: Value = json!({
+        "job_id": job.id,
+        "job_kind": job.kind,
+        "session_id": job.session_uuid,
+        "started_at": job.started_at,
+    });
+    if job.kind == "youtube_upload" {
+        details["upload"] = job.payload["upload"].clone();
+    }
+    CalendarEntry {
+        kind: "scheduled_job".to_string(),
+        at: job.run_at,
+        title,
+        status: job.status.clone(),
+        source_id: job.id.clone(),
+        details,
+    }
+}
+
+/// Times a recurring run falls in `from`..`to`, given its next run and interval, at most `max`
+fn planned_runs(next: DateTime<Utc>, every_minutes: i64, from: DateTime<Utc>, to: DateTime<Utc>, max: usize) -> Vec<DateTime<Utc>> {
+    let step = Duration::minutes(every_minutes.max(1));
+    let mut at = next;
+    if at < from {
+        // Skip ahead to the first run inside the range
+        let behind = (from - at).num_minutes() / step.num_minutes();
+        at += step * behind as i32;
+        if at < from {
+            at += step;
+        }
+    }
+    let mut runs = Vec::new();
+    while at < to && runs.len() < max {
+        runs.push(at);
+        at += step;
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn recurring_runs_are_projected_into_the_range() {
+        let at = |h: u32, m: u32| Utc.with_ymd_and_hms(2026, 3, 1, h, m, 0).unwrap();
+        // Next run before the range: the projection starts at the first run inside it
+        assert_eq!(planned_runs(at(8, 0), 30, at(9, 10), at(10, 30), 10), [at(9, 30), at(10, 0)]);
+        assert_eq!(planned_runs(at(12, 0), 60, at(9, 0), at(14, 30), 10), [at(12, 0), at(13, 0), at(14, 0)]);
+        assert_eq!(planned_runs(at(9, 0), 1, at(9, 0), at(23, 0), 5).len(), 5);
+        assert!(planned_runs(at(15, 0), 60, at(9, 0), at(14, 0), 10).is_empty());
+    }
+}
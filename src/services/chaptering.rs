@@ -0,0 +1,421 @@
+// src/services/chaptering.rs
+// Opt-in chapter index for long uploads: the recording is transcribed, split where the vocabulary
+// shifts (TextTiling-style lexical cohesion), cut points are snapped to pauses, and each chapter is
+// titled and summarised. The index is added to the agent's context so edits can target a topic.
+use crate::models::chapter::{Chapter, ChapterIndex};
+use crate::types::TranscriptWord;
+use crate::AppState;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Shorter recordings are skipped - the agent can already see the whole thing
+pub const MIN_DURATION_SECONDS: f64 = 900.0;
+
+/// Transcript is bucketed into blocks of this length for boundary detection
+const BLOCK_SECONDS: f64 = 30.0;
+/// Blocks compared on each side of a candidate boundary
+const WINDOW_BLOCKS: usize = 4;
+/// No chapter shorter than this
+const MIN_CHAPTER_SECONDS: f64 = 180.0;
+/// Roughly one chapter per this many seconds at most
+const SECONDS_PER_MAX_CHAPTER: f64 = 300.0;
+const MAX_CHAPTERS: usize = 40;
+/// Cut points move to the longest pause within this distance
+const SNAP_SECONDS: f64 = 15.0;
+/// Words of each chapter shown to the model when titling
+const EXCERPT_WORDS: usize = 150;
+
+const STOP_WORDS: [&str; 40] = [
+    "that", "this", "with", "have", "from", "they", "were", "been", "will", "would", "there", "their", "what",
+    "about", "which", "when", "your", "just", "like", "into", "then", "than", "them", "some", "could", "know",
+    "yeah", "gonna", "really", "right", "think", "going", "because", "other", "also", "very", "here", "these",
+    "those", "okay",
+];
+
+/// A chapter before it is titled
+#[derive(Debug, Clone)]
+struct Segment {
+    start: f64,
+    end: f64,
+    text: String,
+    keywords: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChapterTitle {
+    title: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+}
+
+pub struct ChapteringService;
+
+impl ChapteringService {
+    /// Queue an upload for chaptering; runs in the background and never fails the caller
+    pub fn spawn_chaptering(state: Arc<AppState>, file_id: String, file_path: String) {
+        tokio::spawn(async move {
+            if let Err(e) = Self::set_status(&state.db_pool, &file_id, "pending", None, None).await {
+                tracing::warn!("Failed to queue chaptering for {}: {}", file_id, e);
+                return;
+            }
+            match Self::build_index(&state, &file_id, &file_path).await {
+                Ok(0) => tracing::info!("📑 Skipped chaptering {} (shorter than {}s)", file_id, MIN_DURATION_SECONDS),
+                Ok(count) => tracing::info!("📑 Indexed {} chapters for upload {}", count, file_id),
+                Err(e) => {
+                    tracing::warn!("Chaptering failed for upload {}: {}", file_id, e);
+                    let _ = Self::set_status(&state.db_pool, &file_id, "failed", None, Some(&e)).await;
+                }
+            }
+        });
+    }
+
+    /// Transcribe, segment and title a recording; returns how many chapters were stored
+    pub async fn build_index(state: &Arc<AppState>, file_id: &str, file_path: &str) -> Result<usize, String> {
+        let pool = &state.db_pool;
+        let path = file_path.to_string();
+        let duration = tokio::task::spawn_blocking(move || crate::core::get_video_duration(&path))
+            .await
+            .map_err(|e| e.to_string())??;
+        if duration < MIN_DURATION_SECONDS {
+            Self::set_status(pool, file_id, "skipped", Some(duration), None).await.map_err(|e| e.to_string())?;
+            return Ok(0);
+        }
+        Self::set_status(pool, file_id, "processing", Some(duration), None).await.map_err(|e| e.to_string())?;
+
+        let words = crate::agent::tool_executor::transcribe_media_words(file_path, false, state).await?;
+        if words.is_empty() {
+            return Err("No speech detected - nothing to chapter".to_string());
+        }
+
+        let segments = segment_transcript(&words, duration);
+        let titles = match state.claude_client.as_ref() {
+            Some(claude) => match title_segments(claude, &segments).await {
+                Ok(titles) => Some(titles),
+                Err(e) => {
+                    tracing::warn!("Chapter titling failed for {}, using keywords: {}", file_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM upload_chapters WHERE uploaded_file_id = $1")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to clear old chapters: {}", e))?;
+        for (index, segment) in segments.iter().enumerate() {
+            let titled = titles.as_ref().and_then(|t| t.get(index));
+            let title = titled
+                .map(|t| t.title.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| fallback_title(index, segment));
+            let keywords = match titled {
+                Some(t) if !t.keywords.is_empty() => t.keywords.iter().map(|k| k.trim().to_lowercase()).collect(),
+                _ => segment.keywords.clone(),
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO upload_chapters (uploaded_file_id, chapter_index, start_seconds, end_seconds, title, summary, keywords)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(file_id)
+            .bind(index as i32 + 1)
+            .bind(segment.start)
+            .bind(segment.end)
+            .bind(title.chars().take(255).collect::<String>())
+            .bind(titled.and_then(|t| t.summary.clone()))
+            .bind(&keywords)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to save chapter: {}", e))?;
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        Self::set_status(pool, file_id, "completed", Some(duration), None).await.map_err(|e| e.to_string())?;
+        Ok(segments.len())
+    }
+
+    async fn set_status(
+        pool: &PgPool,
+        file_id: &str,
+        status: &str,
+        duration: Option<f64>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO upload_chapter_indexes (uploaded_file_id, status, duration_seconds, error_message)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (uploaded_file_id) DO UPDATE SET
+                status = EXCLUDED.status,
+                duration_seconds = COALESCE(EXCLUDED.duration_seconds, upload_chapter_indexes.duration_seconds),
+                error_message = EXCLUDED.error_message,
+                completed_at = CASE WHEN EXCLUDED.status IN ('completed', 'skipped', 'failed') THEN NOW() END
+            "#,
+        )
+        .bind(file_id)
+        .bind(status)
+        .bind(duration)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Chapter status and chapters of an upload the user owns
+    pub async fn get_for_user(
+        pool: &PgPool,
+        user_id: i32,
+        file_id: &str,
+    ) -> Result<Option<(ChapterIndex, Vec<Chapter>)>, sqlx::Error> {
+        let index = sqlx::query_as::<_, ChapterIndex>(
+            r#"
+            SELECT ci.* FROM upload_chapter_indexes ci
+            JOIN uploaded_files uf ON uf.id = ci.uploaded_file_id
+            JOIN chat_sessions cs ON cs.id = uf.session_id
+            WHERE ci.uploaded_file_id = $1 AND cs.user_id = $2
+            "#,
+        )
+        .bind(file_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+        let Some(index) = index else {
+            return Ok(None);
+        };
+
+        let chapters = sqlx::query_as::<_, Chapter>(
+            "SELECT * FROM upload_chapters WHERE uploaded_file_id = $1 ORDER BY chapter_index",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(Some((index, chapters)))
+    }
+
+    /// Chapter indexes of a session's uploads, formatted for the agent's context
+    pub async fn build_chapter_context(pool: &PgPool, session_uuid: &str) -> Result<String, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, String, i32, f64, f64, String, Option<String>)>(
+            r#"
+            SELECT uf.original_name, uf.file_path, ch.chapter_index, ch.start_seconds, ch.end_seconds, ch.title, ch.summary
+            FROM upload_chapters ch
+            JOIN uploaded_files uf ON uf.id = ch.uploaded_file_id
+            JOIN chat_sessions cs ON cs.id = uf.session_id
+            WHERE cs.session_uuid = $1
+            ORDER BY uf.created_at, ch.chapter_index
+            "#,
+        )
+        .bind(session_uuid)
+        .fetch_all(pool)
+        .await?;
+        if rows.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut context = String::from("CHAPTERS OF LONG RECORDINGS (use these times to find or cut content by topic):\n");
+        let mut current_path = String::new();
+        for (name, path, index, start, end, title, summary) in rows {
+            if path != current_path {
+                context.push_str(&format!("\"{}\" ({}):\n", name, path));
+                current_path = path;
+            }
+            context.push_str(&format!(
+                "  {}. [{} - {}] {} (start {:.1}s, end {:.1}s)\n",
+                index,
+                format_timestamp(start),
+                format_timestamp(end),
+                title,
+                start,
+                end
+            ));
+            if let Some(summary) = summary.filter(|s| !s.is_empty()) {
+                context.push_str(&format!("     {}\n", summary));
+            }
+        }
+        context.push('\n');
+        Ok(context)
+    }
+}
+
+/// Split a transcript into chapters at the deepest dips in lexical similarity between neighbouring windows
+fn segment_transcript(words: &[TranscriptWord], duration: f64) -> Vec<Segment> {
+    let block_count = (duration / BLOCK_SECONDS).ceil().max(1.0) as usize;
+    let mut blocks: Vec<HashMap<String, f64>> = vec![HashMap::new(); block_count];
+    for word in words {
+        let block = ((word.start / BLOCK_SECONDS) as usize).min(block_count - 1);
+        if let Some(term) = content_word(&word.text) {
+            *blocks[block].entry(term).or_insert(0.0) += 1.0;
+        }
+    }
+
+    // Similarity across each gap between blocks (gap g sits at the start of block g)
+    let mut similarities = vec![1.0; block_count];
+    for (gap, similarity) in similarities.iter_mut().enumerate().skip(1) {
+        let left = merge_counts(&blocks[gap.saturating_sub(WINDOW_BLOCKS)..gap]);
+        let right = merge_counts(&blocks[gap..(gap + WINDOW_BLOCKS).min(block_count)]);
+        *similarity = cosine(&left, &right);
+    }
+
+    // Depth of each dip relative to the nearest peaks on either side
+    let mut depths: Vec<(usize, f64)> = Vec::new();
+    for gap in 1..block_count {
+        let mut left_peak = similarities[gap];
+        for &s in similarities[1..gap].iter().rev() {
+            if s < left_peak {
+                break;
+            }
+            left_peak = s;
+        }
+        let mut right_peak = similarities[gap];
+        for &s in &similarities[gap + 1..] {
+            if s < right_peak {
+                break;
+            }
+            right_peak = s;
+        }
+        depths.push((gap, (left_peak - similarities[gap]) + (right_peak - similarities[gap])));
+    }
+
+    let mean_depth = depths.iter().map(|(_, d)| d).sum::<f64>() / depths.len().max(1) as f64;
+    let max_chapters = ((duration / SECONDS_PER_MAX_CHAPTER).ceil() as usize).clamp(1, MAX_CHAPTERS);
+    depths.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut boundaries: Vec<f64> = Vec::new();
+    for (gap, depth) in depths {
+        if boundaries.len() + 1 >= max_chapters || depth <= mean_depth {
+            break;
+        }
+        let time = snap_to_pause(words, gap as f64 * BLOCK_SECONDS);
+        let far_enough = time >= MIN_CHAPTER_SECONDS
+            && duration - time >= MIN_CHAPTER_SECONDS
+            && boundaries.iter().all(|b| (b - time).abs() >= MIN_CHAPTER_SECONDS);
+        if far_enough {
+            boundaries.push(time);
+        }
+    }
+    boundaries.sort_by(|a, b| a.total_cmp(b));
+
+    let mut edges = vec![0.0];
+    edges.extend(boundaries);
+    edges.push(duration);
+
+    let overall = merge_counts(&blocks);
+    edges
+        .windows(2)
+        .map(|edge| {
+            let (start, end) = (edge[0], edge[1]);
+            let segment_words: Vec<&TranscriptWord> = words.iter().filter(|w| w.start >= start && w.start < end).collect();
+            let text = segment_words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ");
+            let counts = merge_counts(&blocks[(start / BLOCK_SECONDS) as usize..((end / BLOCK_SECONDS).ceil() as usize).min(block_count)]);
+            Segment { start, end, text, keywords: distinctive_terms(&counts, &overall, 5) }
+        })
+        .collect()
+}
+
+/// Move a cut point to the longest gap between words near it
+fn snap_to_pause(words: &[TranscriptWord], time: f64) -> f64 {
+    words
+        .windows(2)
+        .filter(|pair| (pair[0].end - time).abs() <= SNAP_SECONDS)
+        .max_by(|a, b| (a[1].start - a[0].end).total_cmp(&(b[1].start - b[0].end)))
+        .map(|pair| (pair[0].end + pair[1].start) / 2.0)
+        .unwrap_or(time)
+}
+
+fn content_word(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+    (word.chars().count() > 3 && !STOP_WORDS.contains(&word.as_str())).then_some(word)
+}
+
+fn merge_counts(blocks: &[HashMap<String, f64>]) -> HashMap<String, f64> {
+    let mut merged = HashMap::new();
+    for block in blocks {
+        for (term, count) in block {
+            *merged.entry(term.clone()).or_insert(0.0) += count;
+        }
+    }
+    merged
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(term, x)| b.get(term).map(|y| x * y)).sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 { 0.0 } else { dot / denominator }
+}
+
+/// Terms frequent in this chapter relative to the whole recording
+fn distinctive_terms(counts: &HashMap<String, f64>, overall: &HashMap<String, f64>, limit: usize) -> Vec<String> {
+    let mut scored: Vec<(&String, f64)> = counts
+        .iter()
+        .filter(|(_, count)| **count >= 2.0)
+        .map(|(term, count)| (term, count * count / overall.get(term).copied().unwrap_or(*count)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    scored.into_iter().take(limit).map(|(term, _)| term.clone()).collect()
+}
+
+fn fallback_title(index: usize, segment: &Segment) -> String {
+    if segment.keywords.is_empty() {
+        format!("Part {}", index + 1)
+    } else {
+        format!("Part {}: {}", index + 1, segment.keywords.iter().take(3).cloned().collect::<Vec<_>>().join(", "))
+    }
+}
+
+/// Ask the model for a title, one-sentence summary and keywords per chapter
+async fn title_segments(claude: &crate::claude_client::ClaudeClient, segments: &[Segment]) -> Result<Vec<ChapterTitle>, String> {
+    let excerpts: Vec<serde_json::Value> = segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            let words: Vec<&str> = segment.text.split_whitespace().collect();
+            let middle = words.len() / 2;
+            let mut excerpt = words.iter().take(EXCERPT_WORDS).copied().collect::<Vec<_>>().join(" ");
+            if words.len() > EXCERPT_WORDS * 2 {
+                excerpt.push_str(" ... ");
+                excerpt.push_str(&words[middle..(middle + EXCERPT_WORDS / 2).min(words.len())].join(" "));
+            }
+            serde_json::json!({
+                "chapter": index + 1,
+                "start": format_timestamp(segment.start),
+                "end": format_timestamp(segment.end),
+                "keywords": segment.keywords,
+                "excerpt": excerpt,
+            })
+        })
+        .collect();
+
+    let prompt = format!(
+        "These are consecutive chapters of a long recording, with transcript excerpts. For each chapter write a short \
+         topic title (max 8 words, say what is discussed, no numbering), a one-sentence summary, and 3-6 lowercase keywords \
+         someone might use to refer to it.\n\n\
+         Respond with ONLY a JSON array with exactly {} objects in the same order: \
+         [{{\"title\": \"...\", \"summary\": \"...\", \"keywords\": [\"...\"]}}]\n\n{}",
+        segments.len(),
+        serde_json::to_string(&excerpts).unwrap_or_default()
+    );
+
+    let response = claude.generate_text(&prompt).await?;
+    let start = response.find('[').ok_or("No JSON array in chapter titles")?;
+    let end = response.rfind(']').ok_or("No JSON array in chapter titles")?;
+    let titles: Vec<ChapterTitle> = serde_json::from_str(response.get(start..=end).unwrap_or("[]"))
+        .map_err(|e| format!("Failed to parse chapter titles: {}", e))?;
+    if titles.len() != segments.len() {
+        return Err(format!("Got {} chapter titles, expected {}", titles.len(), segments.len()));
+    }
+    Ok(titles)
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    format!("{}:{:02}:{:02}", total / 3600, (total % 3600) / 60, total % 60)
+}
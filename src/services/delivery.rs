@@ -0,0 +1,382 @@
+// src/services/delivery.rs
+// Pushes finished outputs to client delivery targets (SFTP/FTP via curl, S3 via SigV4 PUT)
+// Every attempt is recorded as a delivery receipt with the file's checksums
+use crate::models::delivery::{CreateDeliveryTargetRequest, DeliveryReceipt, DeliveryTarget};
+use chrono::Utc;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::net::IpAddr;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+pub const SUPPORTED_PROTOCOLS: [&str; 3] = ["sftp", "ftp", "s3"];
+
+/// The only schemes curl may speak for a delivery, whatever a target's URL says
+const CURL_PROTOCOLS: &str = "=sftp,ftp,ftps,https";
+/// `url` has no default port for sftp
+const SFTP_PORT: u16 = 22;
+
+/// Size and checksums of a local file
+#[derive(Debug, Clone)]
+pub struct FileChecksums {
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub md5: String,
+}
+
+/// Result of one transfer before it is written as a receipt
+struct TransferOutcome {
+    remote_url: String,
+    verified: bool,
+    verification_method: String,
+}
+
+pub struct DeliveryService;
+
+impl DeliveryService {
+    /// Stream a file once, computing SHA-256 and MD5 together
+    pub async fn checksum_file(path: &str) -> Result<FileChecksums, String> {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+
+        let mut sha = Sha256::new();
+        let mut md5 = Md5::new();
+        let mut size_bytes = 0u64;
+        let mut buf = vec![0u8; 1024 * 1024];
+        loop {
+            let n = file.read(&mut buf).await.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            if n == 0 {
+                break;
+            }
+            sha.update(&buf[..n]);
+            md5.update(&buf[..n]);
+            size_bytes += n as u64;
+        }
+
+        Ok(FileChecksums {
+            size_bytes,
+            sha256: hex::encode(sha.finalize()),
+            md5: hex::encode(md5.finalize()),
+        })
+    }
+
+    pub async fn list_targets(pool: &PgPool, user_id: i32) -> Result<Vec<DeliveryTarget>, sqlx::Error> {
+        sqlx::query_as::<_, DeliveryTarget>(
+            "SELECT * FROM delivery_targets WHERE user_id = $1 AND is_active = TRUE ORDER BY name"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn get_target_by_name(pool: &PgPool, user_id: i32, name: &str) -> Result<Option<DeliveryTarget>, sqlx::Error> {
+        sqlx::query_as::<_, DeliveryTarget>(
+            "SELECT * FROM delivery_targets WHERE user_id = $1 AND name = $2 AND is_active = TRUE"
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create_target(
+        pool: &PgPool,
+        user_id: i32,
+        request: &CreateDeliveryTargetRequest,
+    ) -> Result<DeliveryTarget, String> {
+        let protocol = request.protocol.to_lowercase();
+        if !SUPPORTED_PROTOCOLS.contains(&protocol.as_str()) {
+            return Err(format!("Unsupported protocol '{}'. Use one of: {}", request.protocol, SUPPORTED_PROTOCOLS.join(", ")));
+        }
+        if request.name.trim().is_empty() || request.host.trim().is_empty() {
+            return Err("name and host are required".to_string());
+        }
+        if protocol == "s3" && (request.username.is_none() || request.secret.is_none() || request.region.is_none()) {
+            return Err("s3 targets need username (access key id), secret (secret access key) and region".to_string());
+        }
+        let host = request.host.trim();
+        if !host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')) {
+            return Err("host must be a bare host name or IPv4 address".to_string());
+        }
+        if let Some(ref endpoint) = request.endpoint_url {
+            let endpoint = reqwest::Url::parse(endpoint).map_err(|e| format!("Invalid endpoint_url: {}", e))?;
+            if endpoint.scheme() != "https" {
+                return Err("endpoint_url must be an https:// URL".to_string());
+            }
+        }
+        let base = base_url(&protocol, host, request.port, request.region.as_deref(), request.endpoint_url.as_deref());
+        let url = reqwest::Url::parse(&base).map_err(|e| format!("Invalid target address {}: {}", base, e))?;
+        crate::services::webhook::resolve_public(&url).await?;
+
+        let secret = request.secret.as_deref().map(crate::utils::credentials::seal).transpose()?;
+
+        sqlx::query_as::<_, DeliveryTarget>(
+            r#"
+            INSERT INTO delivery_targets (user_id, name, protocol, host, port, username, secret, remote_path, region, endpoint_url)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (user_id, name) DO UPDATE SET
+                protocol = EXCLUDED.protocol, host = EXCLUDED.host, port = EXCLUDED.port,
+                username = EXCLUDED.username, secret = EXCLUDED.secret, remote_path = EXCLUDED.remote_path,
+                region = EXCLUDED.region, endpoint_url = EXCLUDED.endpoint_url,
+                is_active = TRUE, updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(request.name.trim())
+        .bind(&protocol)
+        .bind(host)
+        .bind(request.port)
+        .bind(&request.username)
+        .bind(&secret)
+        .bind(request.remote_path.as_deref().unwrap_or("").trim_matches('/'))
+        .bind(&request.region)
+        .bind(&request.endpoint_url)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save delivery target: {}", e))
+    }
+
+    pub async fn delete_target(pool: &PgPool, user_id: i32, target_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM delivery_targets WHERE id = $1 AND user_id = $2")
+            .bind(target_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_receipts(pool: &PgPool, user_id: i32, limit: i64) -> Result<Vec<DeliveryReceipt>, sqlx::Error> {
+        sqlx::query_as::<_, DeliveryReceipt>(
+            "SELECT * FROM delivery_receipts WHERE user_id = $1 ORDER BY delivered_at DESC LIMIT $2"
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Deliver a local file to a target and record the receipt (failed attempts are recorded too)
+    pub async fn deliver(
+        pool: &PgPool,
+        user_id: i32,
+        target: &DeliveryTarget,
+        local_path: &str,
+    ) -> Result<DeliveryReceipt, String> {
+        let started_at = Utc::now();
+        let checksums = Self::checksum_file(local_path).await?;
+        let file_name = Path::new(local_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or("Invalid file name")?
+            .to_string();
+
+        tracing::info!("📦 Delivering {} ({} bytes) to '{}' via {}", local_path, checksums.size_bytes, target.name, target.protocol);
+
+        let outcome = match target.protocol.as_str() {
+            "sftp" | "ftp" => Self::deliver_curl(target, local_path, &file_name, &checksums).await,
+            "s3" => Self::deliver_s3(target, local_path, &file_name, &checksums).await,
+            other => Err(format!("Unsupported protocol '{}'", other)),
+        };
+
+        let (status, remote_url, verified, verification_method, error_message) = match outcome {
+            Ok(o) => ("delivered", o.remote_url, o.verified, Some(o.verification_method), None),
+            Err(e) => ("failed", Self::remote_url(target, &file_name), false, None, Some(e)),
+        };
+
+        sqlx::query_as::<_, DeliveryReceipt>(
+            r#"
+            INSERT INTO delivery_receipts (
+                receipt_uuid, user_id, target_id, target_name, local_path, remote_url, size_bytes,
+                sha256, md5, verified, verification_method, status, error_message, started_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING *
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(target.id)
+        .bind(&target.name)
+        .bind(local_path)
+        .bind(&remote_url)
+        .bind(checksums.size_bytes as i64)
+        .bind(&checksums.sha256)
+        .bind(&checksums.md5)
+        .bind(verified)
+        .bind(verification_method)
+        .bind(status)
+        .bind(error_message)
+        .bind(started_at)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save delivery receipt: {}", e))
+    }
+
+    /// Public location of a delivered file (never includes credentials)
+    fn remote_url(target: &DeliveryTarget, file_name: &str) -> String {
+        let key = if target.remote_path.is_empty() {
+            file_name.to_string()
+        } else {
+            format!("{}/{}", target.remote_path.trim_matches('/'), file_name)
+        };
+        let base = base_url(
+            &target.protocol,
+            &target.host,
+            target.port,
+            target.region.as_deref(),
+            target.endpoint_url.as_deref(),
+        );
+        format!("{}/{}", base, key)
+    }
+
+    /// Run curl against `url` with credentials passed on stdin so they never show up in the process list.
+    /// The host is resolved here and pinned with --resolve, so it can't be re-pointed at an internal address.
+    async fn run_curl(target: &DeliveryTarget, args: &[String], url: &str) -> Result<String, String> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid remote url {}: {}", url, e))?;
+        let addresses = crate::services::webhook::resolve_public(&parsed).await?;
+        let port = parsed.port_or_known_default().unwrap_or(SFTP_PORT);
+        let pinned: Vec<String> = addresses
+            .iter()
+            .map(|address| match address.ip() {
+                IpAddr::V6(ip) => format!("[{}]", ip),
+                ip => ip.to_string(),
+            })
+            .collect();
+        let resolve = format!("{}:{}:{}", parsed.host_str().unwrap_or_default(), port, pinned.join(","));
+
+        let secret = target.secret.as_deref().map(crate::utils::credentials::open).transpose()?;
+        let mut child = Command::new("curl")
+            .arg("--config")
+            .arg("-")
+            .arg("--proto")
+            .arg(CURL_PROTOCOLS)
+            .arg("--resolve")
+            .arg(resolve)
+            .args(args)
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start curl: {}", e))?;
+
+        let mut config = String::new();
+        if let Some(ref username) = target.username {
+            let credentials = format!("{}:{}", username, secret.as_deref().unwrap_or(""));
+            config.push_str(&format!("user = \"{}\"\n", credentials.replace('\\', "\\\\").replace('"', "\\\"")));
+        }
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(config.as_bytes()).await.map_err(|e| format!("Failed to pass credentials to curl: {}", e))?;
+        }
+
+        let output = child.wait_with_output().await.map_err(|e| format!("curl failed: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("curl exited with {}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// SFTP/FTP upload, a `.sha256` sidecar for the receiver, then a remote size check
+    async fn deliver_curl(
+        target: &DeliveryTarget,
+        local_path: &str,
+        file_name: &str,
+        checksums: &FileChecksums,
+    ) -> Result<TransferOutcome, String> {
+        let remote_url = Self::remote_url(target, file_name);
+        let common = vec!["-sS".to_string(), "--fail".to_string(), "--ftp-create-dirs".to_string()];
+
+        let mut upload = common.clone();
+        upload.extend(["-T".to_string(), local_path.to_string()]);
+        Self::run_curl(target, &upload, &remote_url).await?;
+
+        let sidecar = format!("{}.sha256", local_path);
+        tokio::fs::write(&sidecar, format!("{}  {}\n", checksums.sha256, file_name))
+            .await
+            .map_err(|e| format!("Failed to write checksum sidecar: {}", e))?;
+        let mut upload_sidecar = common.clone();
+        upload_sidecar.extend(["-T".to_string(), sidecar.clone()]);
+        let sidecar_result = Self::run_curl(target, &upload_sidecar, &format!("{}.sha256", remote_url)).await;
+        let _ = tokio::fs::remove_file(&sidecar).await;
+        sidecar_result?;
+
+        // curl reports the remote size as Content-Length for --head on FTP and SFTP
+        let mut head = common;
+        head.push("--head".to_string());
+        let headers = Self::run_curl(target, &head, &remote_url).await.unwrap_or_default();
+        let remote_size = headers
+            .lines()
+            .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+            .and_then(|v| v.parse::<u64>().ok());
+
+        let (verified, verification_method) = match remote_size {
+            Some(size) if size == checksums.size_bytes => (true, "remote size match + sha256 sidecar".to_string()),
+            Some(size) => {
+                return Err(format!("Remote size {} does not match local size {}", size, checksums.size_bytes));
+            }
+            None => (false, "sha256 sidecar only (server did not report size)".to_string()),
+        };
+
+        Ok(TransferOutcome { remote_url, verified, verification_method })
+    }
+
+    /// S3 PUT signed with SigV4; S3 rejects the body if it does not match x-amz-content-sha256
+    async fn deliver_s3(
+        target: &DeliveryTarget,
+        local_path: &str,
+        file_name: &str,
+        checksums: &FileChecksums,
+    ) -> Result<TransferOutcome, String> {
+        let remote_url = Self::remote_url(target, file_name);
+        let region = target.region.as_deref().unwrap_or("us-east-1");
+
+        let args = vec![
+            "-sS".to_string(),
+            "--fail".to_string(),
+            "--aws-sigv4".to_string(),
+            format!("aws:amz:{}:s3", region),
+            "-H".to_string(),
+            format!("x-amz-content-sha256: {}", checksums.sha256),
+            "-T".to_string(),
+            local_path.to_string(),
+            "-D".to_string(),
+            "-".to_string(),
+            "-o".to_string(),
+            "/dev/null".to_string(),
+        ];
+        let headers = Self::run_curl(target, &args, &remote_url).await?;
+
+        // Single-part PUT ETags are the MD5 of the body (not true for SSE-KMS buckets)
+        let etag = headers
+            .lines()
+            .find_map(|l| l.to_lowercase().strip_prefix("etag:").map(|v| v.trim().trim_matches('"').to_string()));
+        let verification_method = match etag {
+            Some(ref e) if *e == checksums.md5 => "x-amz-content-sha256 + ETag md5 match",
+            _ => "x-amz-content-sha256 (server-side)",
+        };
+
+        Ok(TransferOutcome {
+            remote_url,
+            verified: true,
+            verification_method: verification_method.to_string(),
+        })
+    }
+}
+
+/// Scheme, host and port (plus the bucket for custom S3 endpoints) a target's files go under
+fn base_url(protocol: &str, host: &str, port: Option<i32>, region: Option<&str>, endpoint_url: Option<&str>) -> String {
+    match protocol {
+        "s3" => match endpoint_url {
+            Some(endpoint) => format!("{}/{}", endpoint.trim_end_matches('/'), host),
+            None => format!("https://{}.s3.{}.amazonaws.com", host, region.unwrap_or("us-east-1")),
+        },
+        protocol => match port {
+            Some(port) => format!("{}://{}:{}", protocol, host, port),
+            None => format!("{}://{}", protocol, host),
+        },
+    }
+}
@@ -0,0 +1,141 @@
+// src/services/download_link.rs
+// Client delivery links: tokenized, expiring downloads of one output, optionally behind a password
+// and a download limit, so deliverables can be sent to people without an account
+use crate::models::download_link::{CreateDownloadLinkRequest, DownloadLink};
+use crate::services::OutputVideoService;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+/// Default link lifetime (3 days)
+pub const DEFAULT_LINK_HOURS: i64 = 24 * 3;
+
+/// Longest lifetime a link can be created with (30 days)
+pub const MAX_LINK_HOURS: i64 = 24 * 30;
+
+pub const MIN_PASSWORD_CHARS: usize = 6;
+
+pub const MAX_DOWNLOAD_LIMIT: i32 = 1000;
+
+pub struct DownloadLinkService;
+
+impl DownloadLinkService {
+    pub async fn create_link(
+        pool: &PgPool,
+        user_id: i32,
+        output_id: i32,
+        request: &CreateDownloadLinkRequest,
+    ) -> Result<DownloadLink, String> {
+        let output = OutputVideoService::get_output_video_by_id(pool, output_id)
+            .await
+            .map_err(|e| format!("Failed to load output: {}", e))?
+            .filter(|o| o.user_id == user_id)
+            .ok_or_else(|| format!("Output {} not found", output_id))?;
+        let file_path = OutputVideoService::path_candidates(&output.file_path)
+            .into_iter()
+            .find(|p| std::path::Path::new(p).is_file())
+            .ok_or_else(|| format!("File not found: {}", output.file_path))?;
+
+        if let Some(max) = request.max_downloads {
+            if !(1..=MAX_DOWNLOAD_LIMIT).contains(&max) {
+                return Err(format!("max_downloads must be between 1 and {}", MAX_DOWNLOAD_LIMIT));
+            }
+        }
+        let password_hash = match request.password.as_deref().filter(|p| !p.is_empty()) {
+            Some(password) if password.chars().count() < MIN_PASSWORD_CHARS => {
+                return Err(format!("password must be at least {} characters", MIN_PASSWORD_CHARS));
+            }
+            Some(password) => Some(
+                bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(|e| format!("Failed to hash password: {}", e))?,
+            ),
+            None => None,
+        };
+        let hours = request.expires_in_hours.unwrap_or(DEFAULT_LINK_HOURS).clamp(1, MAX_LINK_HOURS);
+
+        // Same token shape as review and embed links
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+
+        sqlx::query_as::<_, DownloadLink>(
+            r#"
+            INSERT INTO download_links (token, user_id, output_id, file_path, file_name, password_hash, expires_at, max_downloads)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(&token)
+        .bind(user_id)
+        .bind(output.id)
+        .bind(&file_path)
+        .bind(&output.file_name)
+        .bind(&password_hash)
+        .bind(Utc::now() + Duration::hours(hours))
+        .bind(request.max_downloads)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to create download link: {}", e))
+    }
+
+    /// The user's links, newest first; only those of one output when `output_id` is given
+    pub async fn list_links(pool: &PgPool, user_id: i32, output_id: Option<i32>) -> Result<Vec<DownloadLink>, sqlx::Error> {
+        sqlx::query_as::<_, DownloadLink>(
+            "SELECT * FROM download_links WHERE user_id = $1 AND ($2::INTEGER IS NULL OR output_id = $2) ORDER BY created_at DESC"
+        )
+        .bind(user_id)
+        .bind(output_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn revoke_link(pool: &PgPool, user_id: i32, link_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE download_links SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL"
+        )
+        .bind(link_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resolve a public token; revoked, expired and used-up links resolve to None
+    pub async fn get_active_link(pool: &PgPool, token: &str) -> Result<Option<DownloadLink>, sqlx::Error> {
+        sqlx::query_as::<_, DownloadLink>(
+            r#"
+            SELECT * FROM download_links
+            WHERE token = $1 AND revoked_at IS NULL AND expires_at > NOW()
+              AND (max_downloads IS NULL OR download_count < max_downloads)
+            "#
+        )
+        .bind(token)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether `password` opens the link (always true for links without one).
+    /// bcrypt is deliberately slow, so the hash is checked off the async runtime.
+    pub async fn check_password(link: &DownloadLink, password: Option<&str>) -> bool {
+        match (&link.password_hash, password) {
+            (None, _) => true,
+            (Some(hash), Some(password)) => {
+                let (hash, password) = (hash.clone(), password.to_string());
+                tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash).unwrap_or(false))
+                    .await
+                    .unwrap_or(false)
+            }
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Count a download. False when the link was used up by a download that raced this one.
+    pub async fn record_download(pool: &PgPool, link_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE download_links SET download_count = download_count + 1, last_downloaded_at = NOW()
+            WHERE id = $1 AND (max_downloads IS NULL OR download_count < max_downloads)
+            "#
+        )
+        .bind(link_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,207 @@
+// src/services/edit_history.rs
+//! Undo and redo for the edits the agent makes directly, outside a project. A session's edit
+//! history is its outputs in the order they were made. Undo hides the newest ones from the agent,
+//! leaving their files on disk, and redo brings back the most recently undone. A new edit after an
+//! undo drops the redo stack, and those outputs go to the trash.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Most edits one undo or redo can step over
+pub const MAX_STEPS: i64 = 10;
+
+/// Edits listed in the agent's context and in undo/redo results
+const SHOWN_STEPS: usize = 5;
+
+/// One edit: the output a tool made
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EditStep {
+    pub output_id: i32,
+    pub tool: String,
+    pub file_path: String,
+    pub created_at: DateTime<Utc>,
+    /// Set while the edit is undone
+    pub undone_at: Option<DateTime<Utc>>,
+}
+
+pub struct EditHistoryService;
+
+impl EditHistoryService {
+    /// A session's edits, oldest first, undone ones included
+    pub async fn history(pool: &PgPool, session_uuid: &str) -> Result<Vec<EditStep>, sqlx::Error> {
+        sqlx::query_as::<_, EditStep>(
+            r#"
+            SELECT o.id AS output_id, o.tool_used AS tool, o.file_path, o.created_at, o.undone_at
+            FROM output_videos o
+            JOIN chat_sessions s ON s.id = o.session_id
+            WHERE s.session_uuid = $1 AND o.deleted_at IS NULL AND o.processing_status = 'completed'
+            ORDER BY o.created_at, o.id
+            "#,
+        )
+        .bind(session_uuid)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Undo the newest `steps` edits; returns them, newest first
+    pub async fn undo(pool: &PgPool, session_uuid: &str, steps: i64) -> Result<Vec<EditStep>, String> {
+        Self::step(
+            pool,
+            session_uuid,
+            r#"
+            UPDATE output_videos SET undone_at = NOW()
+            WHERE id IN (
+                SELECT id FROM output_videos
+                WHERE session_id = $1 AND undone_at IS NULL AND deleted_at IS NULL AND processing_status = 'completed'
+                ORDER BY created_at DESC, id DESC
+                LIMIT $2
+            )
+            RETURNING id AS output_id, tool_used AS tool, file_path, created_at, undone_at
+            "#,
+            steps,
+        )
+        .await?
+        .ok_or_else(|| "There's nothing to undo in this session".to_string())
+    }
+
+    /// Redo the `steps` most recently undone edits; returns them, oldest first
+    pub async fn redo(pool: &PgPool, session_uuid: &str, steps: i64) -> Result<Vec<EditStep>, String> {
+        // Edits undone together share `undone_at`, so the oldest of the latest batch comes back first
+        Self::step(
+            pool,
+            session_uuid,
+            r#"
+            UPDATE output_videos SET undone_at = NULL
+            WHERE id IN (
+                SELECT id FROM output_videos
+                WHERE session_id = $1 AND undone_at IS NOT NULL AND deleted_at IS NULL
+                ORDER BY undone_at DESC, created_at, id
+                LIMIT $2
+            )
+            RETURNING id AS output_id, tool_used AS tool, file_path, created_at, undone_at
+            "#,
+            steps,
+        )
+        .await?
+        .ok_or_else(|| "There's nothing to redo in this session".to_string())
+    }
+
+    /// Run an undo or redo update with the session locked, so two at once can't pick the same edits.
+    /// None when it changed nothing.
+    async fn step(pool: &PgPool, session_uuid: &str, update: &str, steps: i64) -> Result<Option<Vec<EditStep>>, String> {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        let session_id = sqlx::query_scalar::<_, i32>(
+            "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND deleted_at IS NULL FOR UPDATE"
+        )
+        .bind(session_uuid)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Session not found".to_string())?;
+
+        let mut changed = sqlx::query_as::<_, EditStep>(update)
+            .bind(session_id)
+            .bind(steps.clamp(1, MAX_STEPS))
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        if changed.is_empty() {
+            return Ok(None);
+        }
+        changed.sort_by_key(|step| (step.created_at, step.output_id));
+        if changed[0].undone_at.is_some() {
+            changed.reverse();
+        }
+        Ok(Some(changed))
+    }
+
+    /// A new edit was made: edits still undone can't be redone any more, so they go to the trash
+    pub async fn drop_redo_stack(pool: &PgPool, session_id: i32, new_output_id: i32) -> Result<u64, sqlx::Error> {
+        sqlx::query(
+            "UPDATE output_videos SET deleted_at = NOW(), undone_at = NULL WHERE session_id = $1 AND undone_at IS NOT NULL AND id <> $2"
+        )
+        .bind(session_id)
+        .bind(new_output_id)
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
+    /// The latest edits with the current one marked, then what redo would bring back
+    pub fn describe(history: &[EditStep]) -> String {
+        let done: Vec<&EditStep> = history.iter().filter(|step| step.undone_at.is_none()).collect();
+        let mut undone: Vec<&EditStep> = history.iter().filter(|step| step.undone_at.is_some()).collect();
+        undone.sort_by_key(|step| (std::cmp::Reverse(step.undone_at), step.created_at, step.output_id));
+
+        let mut text = String::new();
+        if done.is_empty() {
+            text.push_str("No edits left; the session is back to its uploads.\n");
+        } else {
+            let skipped = done.len().saturating_sub(SHOWN_STEPS);
+            if skipped > 0 {
+                text.push_str(&format!("({} earlier edits)\n", skipped));
+            }
+            for (index, step) in done.iter().enumerate().skip(skipped) {
+                let current = if index + 1 == done.len() { "  ← current" } else { "" };
+                text.push_str(&format!("{}. {} → {}{}\n", index + 1, step.tool, step.file_path, current));
+            }
+        }
+        if !undone.is_empty() {
+            text.push_str("Undone (redo_edit brings these back, first one first):\n");
+            for step in undone.iter().take(SHOWN_STEPS) {
+                text.push_str(&format!("- {} → {}\n", step.tool, step.file_path));
+            }
+            if undone.len() > SHOWN_STEPS {
+                text.push_str(&format!("- ... and {} more\n", undone.len() - SHOWN_STEPS));
+            }
+        }
+        text
+    }
+
+    /// The session's edit history for the agent's context; empty when nothing has been edited
+    pub async fn build_history_context(pool: &PgPool, session_uuid: &str) -> Result<String, sqlx::Error> {
+        let history = Self::history(pool, session_uuid).await?;
+        if history.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(format!(
+            "\nEDIT HISTORY (use undo_last_edit / redo_edit when the user says \"undo that\" or \"redo\"):\n{}",
+            Self::describe(&history)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(output_id: i32, tool: &str, minute: u32, undone_minute: Option<u32>) -> EditStep {
+        let at = |m: u32| chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 1, 1, 12, m, 0).unwrap();
+        EditStep {
+            output_id,
+            tool: tool.to_string(),
+            file_path: format!("outputs/{}.mp4", output_id),
+            created_at: at(minute),
+            undone_at: undone_minute.map(at),
+        }
+    }
+
+    #[test]
+    fn history_marks_the_current_edit_and_the_redo_order() {
+        let history = vec![
+            step(1, "trim_video", 0, None),
+            step(2, "add_text_overlay", 1, None),
+            step(3, "adjust_color", 2, Some(10)),
+            step(4, "add_subtitles", 3, Some(9)),
+        ];
+        let text = EditHistoryService::describe(&history);
+        assert!(text.contains("2. add_text_overlay → outputs/2.mp4  ← current"));
+        let (colour, subtitles) = (text.find("adjust_color").unwrap(), text.find("add_subtitles").unwrap());
+        assert!(colour < subtitles, "the edit undone last is redone first:\n{}", text);
+
+        let all_undone = vec![step(1, "trim_video", 0, Some(5))];
+        assert!(EditHistoryService::describe(&all_undone).starts_with("No edits left"));
+    }
+}
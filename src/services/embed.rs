@@ -0,0 +1,230 @@
+// src/services/embed.rs
+// Public embeddable players: tokenized, non-expiring iframes for recorded outputs, packaged to HLS in the background
+use crate::models::embed::{CreateEmbedLinkRequest, EmbedLink};
+use crate::services::OutputVideoService;
+use sqlx::PgPool;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Plans users can be on; the first is the default for new accounts
+pub const PLANS: &[&str] = &["free", "pro", "business"];
+
+/// Plans allowed to remove the "Made with VideoSync" badge
+pub const BRANDING_FREE_PLANS: &[&str] = &["pro", "business"];
+
+/// Segment length for packaged embeds
+const HLS_SEGMENT_SECONDS: u32 = 6;
+
+/// Where an embed's HLS playlist and segments are written
+pub fn embed_hls_dir(token: &str) -> PathBuf {
+    PathBuf::from("outputs/embeds").join(token)
+}
+
+pub struct EmbedService;
+
+impl EmbedService {
+    pub async fn create_link(
+        pool: &PgPool,
+        user_id: i32,
+        request: &CreateEmbedLinkRequest,
+    ) -> Result<EmbedLink, String> {
+        let output = OutputVideoService::get_output_video_by_id(pool, request.output_id)
+            .await
+            .map_err(|e| format!("Failed to load output: {}", e))?
+            .filter(|o| o.user_id == user_id)
+            .ok_or_else(|| format!("Output {} not found", request.output_id))?;
+        let file_path = OutputVideoService::path_candidates(&output.file_path)
+            .into_iter()
+            .find(|p| std::path::Path::new(p).is_file())
+            .ok_or_else(|| format!("File not found: {}", output.file_path))?;
+
+        let hide_branding = request.hide_branding.unwrap_or(false);
+        if hide_branding && !Self::can_hide_branding(pool, user_id).await? {
+            return Err("Removing VideoSync branding requires a pro or business plan".to_string());
+        }
+
+        let allowed_domains = match &request.allowed_domains {
+            Some(domains) if !domains.is_empty() => Some(Self::validate_domains(domains)?),
+            _ => None,
+        };
+        let title = request
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| output.file_name.clone());
+
+        // Same token shape as review links
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+
+        let link = sqlx::query_as::<_, EmbedLink>(
+            r#"
+            INSERT INTO embed_links (token, user_id, output_id, file_path, title, hide_branding, allowed_domains)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(&token)
+        .bind(user_id)
+        .bind(output.id)
+        .bind(&file_path)
+        .bind(&title)
+        .bind(hide_branding)
+        .bind(&allowed_domains)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to create embed: {}", e))?;
+
+        Self::spawn_hls_packaging(pool.clone(), link.clone());
+        Ok(link)
+    }
+
+    pub async fn list_links(pool: &PgPool, user_id: i32) -> Result<Vec<EmbedLink>, sqlx::Error> {
+        sqlx::query_as::<_, EmbedLink>(
+            "SELECT * FROM embed_links WHERE user_id = $1 ORDER BY created_at DESC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Revoke an embed and drop its HLS package; the original output is untouched
+    pub async fn revoke_link(pool: &PgPool, user_id: i32, link_id: i32) -> Result<bool, sqlx::Error> {
+        let token: Option<String> = sqlx::query_scalar(
+            "UPDATE embed_links SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL RETURNING token"
+        )
+        .bind(link_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match token {
+            Some(token) => {
+                let _ = tokio::fs::remove_dir_all(embed_hls_dir(&token)).await;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Resolve a public token; revoked embeds resolve to None
+    pub async fn get_active_link(pool: &PgPool, token: &str) -> Result<Option<EmbedLink>, sqlx::Error> {
+        sqlx::query_as::<_, EmbedLink>(
+            "SELECT * FROM embed_links WHERE token = $1 AND revoked_at IS NULL"
+        )
+        .bind(token)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn record_view(pool: &PgPool, link_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE embed_links SET view_count = view_count + 1 WHERE id = $1")
+            .bind(link_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_user_plan(pool: &PgPool, user_id: i32) -> Result<String, String> {
+        sqlx::query_scalar::<_, String>("SELECT plan FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("Failed to load plan: {}", e))?
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    pub async fn set_user_plan(pool: &PgPool, user_id: i32, plan: &str) -> Result<bool, String> {
+        let plan = plan.trim().to_lowercase();
+        if !PLANS.contains(&plan.as_str()) {
+            return Err(format!("plan must be one of: {}", PLANS.join(", ")));
+        }
+        let result = sqlx::query("UPDATE users SET plan = $2, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .bind(&plan)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to update plan: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Paid plans, and staff accounts, may hide the branding badge
+    async fn can_hide_branding(pool: &PgPool, user_id: i32) -> Result<bool, String> {
+        let row: Option<(String, bool, bool)> =
+            sqlx::query_as("SELECT plan, is_staff, is_superuser FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| format!("Failed to load plan: {}", e))?;
+        Ok(row.is_some_and(|(plan, is_staff, is_superuser)| {
+            is_staff || is_superuser || BRANDING_FREE_PLANS.contains(&plan.as_str())
+        }))
+    }
+
+    /// Normalize allowed sites into a CSP frame-ancestors source list
+    fn validate_domains(domains: &[String]) -> Result<String, String> {
+        let mut sources = Vec::new();
+        for domain in domains {
+            let domain = domain.trim().trim_end_matches('/');
+            let host = domain.trim_start_matches("https://").trim_start_matches("http://");
+            let host_ok = !host.is_empty()
+                && host
+                    .trim_start_matches("*.")
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
+            if !host_ok {
+                return Err(format!("Invalid domain: {}", domain));
+            }
+            let source = if host.len() == domain.len() { format!("https://{}", host) } else { domain.to_string() };
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        Ok(sources.join(" "))
+    }
+
+    /// Package the output as VOD HLS. Stream copy when the codecs allow it, otherwise re-encode to H.264/AAC.
+    fn spawn_hls_packaging(pool: PgPool, link: EmbedLink) {
+        tokio::spawn(async move {
+            let dir = embed_hls_dir(&link.token);
+            let result = tokio::task::spawn_blocking(move || -> Result<(), String> {
+                std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create HLS directory: {}", e))?;
+                let playlist = dir.join("index.m3u8").to_string_lossy().to_string();
+                let segments = dir.join("segment_%03d.ts").to_string_lossy().to_string();
+                let package = |codec_args: &[&str]| {
+                    let mut cmd = Command::new("ffmpeg");
+                    cmd.args(["-y", "-i", &link.file_path]).args(codec_args).args([
+                        "-f", "hls",
+                        "-hls_time", &HLS_SEGMENT_SECONDS.to_string(),
+                        "-hls_playlist_type", "vod",
+                        "-hls_segment_filename", &segments,
+                        &playlist,
+                    ]);
+                    crate::utils::execute_ffmpeg_command(cmd)
+                };
+                package(&["-c", "copy"])
+                    .or_else(|_| package(&["-c:v", "libx264", "-preset", "veryfast", "-crf", "21", "-c:a", "aac", "-b:a", "128k"]))
+                    .map(|_| ())
+            })
+            .await
+            .unwrap_or_else(|e| Err(format!("HLS packaging task panicked: {}", e)));
+
+            let status = match result {
+                Ok(()) => "ready",
+                Err(e) => {
+                    tracing::warn!("HLS packaging failed for embed {}: {}", link.id, e);
+                    "failed"
+                }
+            };
+            if let Err(e) = sqlx::query("UPDATE embed_links SET hls_status = $2 WHERE id = $1")
+                .bind(link.id)
+                .bind(status)
+                .execute(&pool)
+                .await
+            {
+                tracing::warn!("Failed to update embed HLS status: {}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,476 @@
+// src/services/embedding_index.rs
+// Versioned embedding indexes for the chat memory. Vectors from different embedding models can't be
+// compared, so every index (a Qdrant collection) records the model that filled it, and memories are
+// embedded and searched with the active index's model only. Switching models builds a new index in
+// the background: the active one is scanned page by page, each memory re-embedded from its text with
+// the new model, and the source position checkpointed in embedding_indexes so the job can resume.
+// Searches keep using the old index until an admin cuts over, which first copies over whatever was
+// stored since the scan passed it.
+use crate::models::embedding_index::EmbeddingIndex;
+use crate::qdrant_client::{QdrantClient, DEFAULT_COLLECTION};
+use crate::services::vector_migration::{VectorMemoryStore, VectorRecord};
+use crate::AppState;
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// Ids kept per index in failed_ids; counts beyond that are only logged
+const MAX_RECORDED_IDS: i32 = 1000;
+
+const DEFAULT_BATCH_SIZE: i32 = 100;
+const MAX_BATCH_SIZE: i32 = 1000;
+
+/// Dimension of the local hashed embeddings
+const LOCAL_DIMENSION: usize = 384;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmbeddingModel {
+    /// Voyage AI voyage-3
+    Voyage,
+    /// Gemini text-embedding-004
+    Gemini,
+    /// Hashed text vectors computed in process, for running without an embedding API
+    Local,
+}
+
+impl EmbeddingModel {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "voyage" | "voyage-3" => Ok(Self::Voyage),
+            "gemini" | "text-embedding-004" => Ok(Self::Gemini),
+            "local" => Ok(Self::Local),
+            other => Err(format!("Unknown embedding model '{}' (expected voyage, gemini or local)", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Voyage => "voyage",
+            Self::Gemini => "gemini",
+            Self::Local => "local",
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        match self {
+            Self::Voyage => 1024,
+            Self::Gemini => 768,
+            Self::Local => LOCAL_DIMENSION,
+        }
+    }
+
+    /// Whether the model's client is configured
+    pub fn available(&self, state: &AppState) -> bool {
+        match self {
+            Self::Voyage => state.voyage_embeddings.is_some(),
+            Self::Gemini => state.gemini_client.is_some(),
+            Self::Local => true,
+        }
+    }
+
+    pub async fn embed(&self, state: &AppState, text: &str) -> Result<Vec<f32>, String> {
+        let vector = match self {
+            Self::Voyage => {
+                let voyage = state.voyage_embeddings.as_ref().ok_or("Voyage AI isn't configured")?;
+                voyage.generate_single_embedding(text.to_string()).await?
+            }
+            Self::Gemini => {
+                let gemini = state.gemini_client.as_ref().ok_or("Gemini isn't configured")?;
+                gemini.embed_content(text).await.map_err(|e| e.to_string())?
+            }
+            Self::Local => crate::voyage_embeddings::simple_text_embedding(text, LOCAL_DIMENSION),
+        };
+        if vector.len() != self.dimension() {
+            return Err(format!("{} embedding has {} dimensions, expected {}", self.as_str(), vector.len(), self.dimension()));
+        }
+        Ok(vector)
+    }
+
+    /// The model memories were embedded with before indexes were versioned: Voyage when configured,
+    /// else Gemini
+    fn configured_default(state: &AppState) -> Self {
+        if state.voyage_embeddings.is_some() {
+            Self::Voyage
+        } else if state.gemini_client.is_some() {
+            Self::Gemini
+        } else {
+            Self::Local
+        }
+    }
+}
+
+/// Model of the active index, set at startup and on cutover
+fn active_model() -> &'static RwLock<Option<EmbeddingModel>> {
+    static ACTIVE: OnceLock<RwLock<Option<EmbeddingModel>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| RwLock::new(None))
+}
+
+/// Indexes being built by this process, so one can't be resumed while it is still going
+fn running() -> &'static Mutex<HashSet<i32>> {
+    static RUNNING: OnceLock<Mutex<HashSet<i32>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// The text a stored memory was embedded from: a chat memory's user message, or the description of
+/// a vectorized video or frame
+fn embedding_text(payload: &Map<String, Value>) -> Option<&str> {
+    ["user_message", "content"]
+        .iter()
+        .filter_map(|key| payload.get(*key).and_then(|v| v.as_str()))
+        .find(|text| !text.trim().is_empty())
+}
+
+pub struct EmbeddingIndexService;
+
+impl EmbeddingIndexService {
+    pub async fn list(pool: &PgPool) -> Result<Vec<EmbeddingIndex>, sqlx::Error> {
+        sqlx::query_as::<_, EmbeddingIndex>("SELECT * FROM embedding_indexes ORDER BY created_at DESC LIMIT 50")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn get(pool: &PgPool, id: i32) -> Result<Option<EmbeddingIndex>, sqlx::Error> {
+        sqlx::query_as::<_, EmbeddingIndex>("SELECT * FROM embedding_indexes WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    async fn active(pool: &PgPool) -> Result<Option<EmbeddingIndex>, sqlx::Error> {
+        sqlx::query_as::<_, EmbeddingIndex>("SELECT * FROM embedding_indexes WHERE status = 'active'")
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Point Qdrant at the active index. The first time, the collection memories were stored in so
+    /// far becomes the first index, with the model they were embedded with.
+    pub async fn load_active(state: &AppState) -> Result<(), String> {
+        let Some(ref qdrant_client) = state.qdrant_client else {
+            return Ok(());
+        };
+        if Self::active(&state.db_pool).await.map_err(|e| e.to_string())?.is_none() {
+            let model = EmbeddingModel::configured_default(state);
+            sqlx::query(
+                "INSERT INTO embedding_indexes (collection, model, dimension, status, scan_complete, activated_at)
+                 VALUES ($1, $2, $3, 'active', TRUE, NOW())
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(DEFAULT_COLLECTION)
+            .bind(model.as_str())
+            .bind(model.dimension() as i32)
+            .execute(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        let index = Self::active(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "No active embedding index".to_string())?;
+        let model = EmbeddingModel::parse(&index.model)?;
+        if !model.available(state) {
+            tracing::warn!("🧠 The active embedding index uses {}, which isn't configured; memory search will fail until it is", model.as_str());
+        }
+
+        qdrant_client.set_collection(&index.collection);
+        *active_model().write().unwrap() = Some(model);
+        tracing::info!("🧠 Chat memory uses embedding index {} ({}, {} dimensions)", index.collection, model.as_str(), index.dimension);
+        Ok(())
+    }
+
+    /// The model memories are embedded and searched with
+    pub fn model(state: &AppState) -> EmbeddingModel {
+        active_model().read().unwrap().unwrap_or_else(|| EmbeddingModel::configured_default(state))
+    }
+
+    /// Embed a memory or a query with the active index's model
+    pub async fn embed(state: &AppState, text: &str) -> Result<Vec<f32>, String> {
+        Self::model(state).embed(state, text).await
+    }
+
+    /// Record a new index for `model` and start re-embedding the active one into it in the background
+    pub async fn start(
+        state: Arc<AppState>,
+        model: &str,
+        batch_size: Option<i32>,
+        started_by: i32,
+    ) -> Result<EmbeddingIndex, String> {
+        let model = EmbeddingModel::parse(model)?;
+        if state.qdrant_client.is_none() {
+            return Err("Qdrant isn't configured".to_string());
+        }
+        if !model.available(&state) {
+            return Err(format!("The {} embedding model isn't configured", model.as_str()));
+        }
+        let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        if !(1..=MAX_BATCH_SIZE).contains(&batch_size) {
+            return Err(format!("batch_size must be between 1 and {}", MAX_BATCH_SIZE));
+        }
+        let source = Self::active(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "There is no active embedding index to re-embed".to_string())?;
+        let building: Option<i32> = sqlx::query_scalar("SELECT id FROM embedding_indexes WHERE status = 'building'")
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(id) = building {
+            return Err(format!("Embedding index {} is still being built; resume or wait for it first", id));
+        }
+
+        // A newer build replaces one still waiting for cutover
+        sqlx::query("UPDATE embedding_indexes SET status = 'retired', updated_at = NOW() WHERE status = 'ready'")
+            .execute(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let collection = format!("{}_{}_{}", DEFAULT_COLLECTION, model.as_str(), chrono::Utc::now().format("%Y%m%d%H%M%S"));
+        let index = sqlx::query_as::<_, EmbeddingIndex>(
+            "INSERT INTO embedding_indexes (collection, model, dimension, source_index_id, batch_size, started_by)
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+        )
+        .bind(&collection)
+        .bind(model.as_str())
+        .bind(model.dimension() as i32)
+        .bind(source.id)
+        .bind(batch_size)
+        .bind(started_by)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to record embedding index: {}", e))?;
+
+        Self::spawn(state, index.id);
+        Ok(index)
+    }
+
+    /// Continue a failed or interrupted build from its last checkpoint
+    pub async fn resume(state: Arc<AppState>, id: i32) -> Result<Option<EmbeddingIndex>, String> {
+        let Some(index) = Self::get(&state.db_pool, id).await.map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        if !matches!(index.status.as_str(), "building" | "failed") {
+            return Err(format!("This index is {}, not being built", index.status));
+        }
+        if running().lock().unwrap().contains(&id) {
+            return Err("This index is still being built".to_string());
+        }
+
+        let index = sqlx::query_as::<_, EmbeddingIndex>(
+            "UPDATE embedding_indexes SET status = 'building', error = NULL, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Self::spawn(state, id);
+        Ok(Some(index))
+    }
+
+    fn spawn(state: Arc<AppState>, id: i32) {
+        running().lock().unwrap().insert(id);
+        tokio::spawn(async move {
+            let result = Self::build(&state, id).await;
+            running().lock().unwrap().remove(&id);
+            match result {
+                Ok(()) => tracing::info!("🧠 Embedding index {} is ready for cutover", id),
+                Err(e) => {
+                    tracing::error!("🧠 Embedding index {} failed: {}", id, e);
+                    let _ = sqlx::query(
+                        "UPDATE embedding_indexes SET status = 'failed', error = $2, updated_at = NOW() WHERE id = $1",
+                    )
+                    .bind(id)
+                    .bind(&e)
+                    .execute(&state.db_pool)
+                    .await;
+                }
+            }
+        });
+    }
+
+    /// The index's collection and the one it re-embeds
+    async fn stores(state: &AppState, index: &EmbeddingIndex) -> Result<(QdrantClient, QdrantClient), String> {
+        let qdrant_client = state.qdrant_client.as_ref().ok_or("Qdrant isn't configured")?;
+        let source_id = index.source_index_id.ok_or("The index has no source to re-embed")?;
+        let source = Self::get(&state.db_pool, source_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("The source index no longer exists")?;
+        Ok((qdrant_client.for_collection(&source.collection), qdrant_client.for_collection(&index.collection)))
+    }
+
+    async fn build(state: &AppState, id: i32) -> Result<(), String> {
+        let pool = &state.db_pool;
+        let index = Self::get(pool, id).await.map_err(|e| e.to_string())?.ok_or("Index not found")?;
+        let model = EmbeddingModel::parse(&index.model)?;
+        let (source, target) = Self::stores(state, &index).await?;
+
+        target.create_collection_sized(model.dimension() as u64).await.map_err(|e| e.to_string())?;
+        if index.source_count.is_none() {
+            let source_count = source.count().await? as i64;
+            sqlx::query("UPDATE embedding_indexes SET source_count = $2 WHERE id = $1")
+                .bind(id)
+                .bind(source_count)
+                .execute(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let mut cursor = index.cursor.clone();
+        let mut scan_complete = index.scan_complete;
+        while !scan_complete {
+            let page = source.scan(cursor.as_deref(), index.batch_size as usize).await?;
+            let (reindexed, failed_ids) = Self::reembed_batch(state, model, &target, page.records).await?;
+            cursor = page.next;
+            scan_complete = cursor.is_none();
+            Self::checkpoint(pool, id, cursor.as_deref(), scan_complete, reindexed, &failed_ids).await?;
+        }
+
+        sqlx::query("UPDATE embedding_indexes SET status = 'ready', updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Embed each record's text with `model` and write it to the target with its payload. Records
+    /// without text or whose embedding fails are returned as failed; a target error fails the batch
+    /// so it is retried on resume.
+    async fn reembed_batch(
+        state: &AppState,
+        model: EmbeddingModel,
+        target: &QdrantClient,
+        records: Vec<VectorRecord>,
+    ) -> Result<(i64, Vec<String>), String> {
+        let mut failed_ids = Vec::new();
+        let mut ready = Vec::with_capacity(records.len());
+        for mut record in records {
+            let Some(text) = embedding_text(&record.payload) else {
+                failed_ids.push(record.id);
+                continue;
+            };
+            match model.embed(state, text).await {
+                Ok(vector) => {
+                    record.vector = vector;
+                    ready.push(record);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to re-embed memory {}: {}", record.id, e);
+                    failed_ids.push(record.id);
+                }
+            }
+        }
+        if !ready.is_empty() {
+            target.upsert(&ready).await?;
+        }
+        Ok((ready.len() as i64, failed_ids))
+    }
+
+    async fn checkpoint(
+        pool: &PgPool,
+        id: i32,
+        cursor: Option<&str>,
+        scan_complete: bool,
+        reindexed: i64,
+        failed_ids: &[String],
+    ) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE embedding_indexes
+             SET cursor = $2, scan_complete = $3, reindexed = reindexed + $4,
+                 failed_ids = (failed_ids || $5::TEXT[])[1:$6], updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(scan_complete)
+        .bind(reindexed)
+        .bind(failed_ids)
+        .bind(MAX_RECORDED_IDS)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to checkpoint embedding index: {}", e))?;
+
+        if !failed_ids.is_empty() {
+            tracing::warn!("🧠 Embedding index {}: {} memories couldn't be re-embedded in this batch", id, failed_ids.len());
+        }
+        Ok(())
+    }
+
+    /// Make a ready index the one memories are stored in and searched. Memories stored in the old
+    /// index since the build scanned past them are re-embedded first.
+    pub async fn cutover(state: &AppState, id: i32) -> Result<Option<EmbeddingIndex>, String> {
+        let Some(index) = Self::get(&state.db_pool, id).await.map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        if index.status != "ready" {
+            return Err(format!("Only a ready index can be cut over to; this one is {}", index.status));
+        }
+        let model = EmbeddingModel::parse(&index.model)?;
+        if !model.available(state) {
+            return Err(format!("The {} embedding model isn't configured", model.as_str()));
+        }
+
+        let (source, target) = Self::stores(state, &index).await?;
+        let mut caught_up = 0;
+        let mut failed_ids = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = source.scan(cursor.as_deref(), index.batch_size as usize).await?;
+            let ids: Vec<String> = page.records.iter().map(|r| r.id.clone()).collect();
+            let present: HashSet<String> = target.fetch(&ids).await?.into_iter().map(|r| r.id).collect();
+            let missing = page.records.into_iter().filter(|r| !present.contains(&r.id) && !index.failed_ids.contains(&r.id)).collect();
+            let (reindexed, failed) = Self::reembed_batch(state, model, &target, missing).await?;
+            caught_up += reindexed;
+            failed_ids.extend(failed);
+            cursor = page.next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        let mut tx = state.db_pool.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query("UPDATE embedding_indexes SET status = 'retired', updated_at = NOW() WHERE status = 'active'")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        let index = sqlx::query_as::<_, EmbeddingIndex>(
+            "UPDATE embedding_indexes
+             SET status = 'active', reindexed = reindexed + $2, failed_ids = (failed_ids || $3::TEXT[])[1:$4],
+                 activated_at = NOW(), updated_at = NOW()
+             WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(caught_up)
+        .bind(&failed_ids)
+        .bind(MAX_RECORDED_IDS)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        if let Some(ref qdrant_client) = state.qdrant_client {
+            qdrant_client.set_collection(&index.collection);
+        }
+        *active_model().write().unwrap() = Some(model);
+        tracing::info!("🧠 Cut over to embedding index {} ({}), {} memories caught up", index.collection, model.as_str(), caught_up);
+        Ok(Some(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_parse_and_memories_give_their_text() {
+        assert_eq!(EmbeddingModel::parse(" Voyage-3 ").unwrap(), EmbeddingModel::Voyage);
+        assert_eq!(EmbeddingModel::parse("local").unwrap().dimension(), LOCAL_DIMENSION);
+        assert!(EmbeddingModel::parse("ada").unwrap_err().contains("expected voyage, gemini or local"));
+
+        let memory: Map<String, Value> = serde_json::from_value(serde_json::json!({ "user_message": "cut clip_03.mp4", "content": "x" })).unwrap();
+        assert_eq!(embedding_text(&memory), Some("cut clip_03.mp4"));
+        let frame: Map<String, Value> = serde_json::from_value(serde_json::json!({ "user_message": " ", "content": "a red car" })).unwrap();
+        assert_eq!(embedding_text(&frame), Some("a red car"));
+        assert_eq!(embedding_text(&Map::new()), None);
+    }
+}
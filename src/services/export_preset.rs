@@ -0,0 +1,269 @@
+// src/services/export_preset.rs
+// Named per-user export presets (container, codecs, rate control, resolution, loudness, captions).
+// A user preset with a platform's name ("youtube", "tiktok", ...) overrides the built-in one.
+use crate::models::export_preset::{CreateExportPresetRequest, ExportPreset, UpdateExportPresetRequest};
+use crate::types::ExportSettings;
+use sqlx::PgPool;
+
+pub const CONTAINERS: [&str; 4] = ["mp4", "mov", "mkv", "webm"];
+pub const VIDEO_CODECS: [&str; 5] = ["libx264", "libx265", "libvpx-vp9", "libaom-av1", "prores_ks"];
+pub const AUDIO_CODECS: [&str; 4] = ["aac", "libopus", "libmp3lame", "pcm_s16le"];
+
+pub struct ExportPresetService;
+
+impl ExportPresetService {
+    pub async fn list_presets(pool: &PgPool, user_id: i32) -> Result<Vec<ExportPreset>, sqlx::Error> {
+        sqlx::query_as::<_, ExportPreset>("SELECT * FROM export_presets WHERE user_id = $1 ORDER BY name")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn get_preset(pool: &PgPool, user_id: i32, preset_id: i32) -> Result<Option<ExportPreset>, sqlx::Error> {
+        sqlx::query_as::<_, ExportPreset>("SELECT * FROM export_presets WHERE id = $1 AND user_id = $2")
+            .bind(preset_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Names are matched case-insensitively
+    pub async fn get_by_name(pool: &PgPool, user_id: i32, name: &str) -> Result<Option<ExportPreset>, sqlx::Error> {
+        sqlx::query_as::<_, ExportPreset>(
+            "SELECT * FROM export_presets WHERE user_id = $1 AND lower(name) = lower($2)"
+        )
+        .bind(user_id)
+        .bind(name.trim())
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Settings for a preset name: the user's own preset first, then the built-in platform preset
+    pub async fn resolve(pool: &PgPool, user_id: Option<i32>, name: &str) -> Result<ExportSettings, String> {
+        if let Some(user_id) = user_id {
+            let preset = Self::get_by_name(pool, user_id, name)
+                .await
+                .map_err(|e| format!("Failed to look up export preset '{}': {}", name, e))?;
+            if let Some(preset) = preset {
+                return Ok(preset.settings());
+            }
+        }
+        crate::export::platform_settings(&name.trim().to_lowercase()).ok_or_else(|| {
+            format!(
+                "No export preset named '{}'. Built-in presets: {}",
+                name,
+                crate::export::PLATFORM_PRESETS.join(", ")
+            )
+        })
+    }
+
+    pub async fn create_preset(
+        pool: &PgPool,
+        user_id: i32,
+        request: &CreateExportPresetRequest,
+    ) -> Result<ExportPreset, String> {
+        let name = Self::validate_name(&request.name)?;
+        Self::reject_negative(&[request.crf, request.video_bitrate_kbps, request.audio_bitrate_kbps, request.width, request.height])?;
+        let settings = ExportSettings {
+            container: request.container.as_deref().unwrap_or("mp4").trim().to_lowercase(),
+            video_codec: request.video_codec.as_deref().unwrap_or("libx264").trim().to_lowercase(),
+            audio_codec: request.audio_codec.as_deref().unwrap_or("aac").trim().to_lowercase(),
+            crf: request.crf.map(|v| v as u32),
+            video_bitrate_kbps: request.video_bitrate_kbps.map(|v| v as u32),
+            audio_bitrate_kbps: request.audio_bitrate_kbps.map(|v| v as u32),
+            width: request.width.map(|v| v as u32),
+            height: request.height.map(|v| v as u32),
+            fps: request.fps,
+            loudness_target: request.loudness_target,
+            burn_captions: request.burn_captions,
+        };
+        Self::validate_settings(&settings)?;
+        let description = request.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+
+        sqlx::query_as::<_, ExportPreset>(
+            r#"
+            INSERT INTO export_presets (user_id, name, container, video_codec, audio_codec, crf, video_bitrate_kbps,
+                                        audio_bitrate_kbps, width, height, fps, loudness_target, burn_captions, description)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (user_id, name) DO UPDATE SET
+                container = EXCLUDED.container, video_codec = EXCLUDED.video_codec, audio_codec = EXCLUDED.audio_codec,
+                crf = EXCLUDED.crf, video_bitrate_kbps = EXCLUDED.video_bitrate_kbps,
+                audio_bitrate_kbps = EXCLUDED.audio_bitrate_kbps, width = EXCLUDED.width, height = EXCLUDED.height,
+                fps = EXCLUDED.fps, loudness_target = EXCLUDED.loudness_target,
+                burn_captions = EXCLUDED.burn_captions, description = EXCLUDED.description, updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&name)
+        .bind(&settings.container)
+        .bind(&settings.video_codec)
+        .bind(&settings.audio_codec)
+        .bind(request.crf)
+        .bind(request.video_bitrate_kbps)
+        .bind(request.audio_bitrate_kbps)
+        .bind(request.width)
+        .bind(request.height)
+        .bind(request.fps)
+        .bind(request.loudness_target)
+        .bind(request.burn_captions)
+        .bind(description)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save export preset: {}", e))
+    }
+
+    /// Apply a partial update, validating the merged preset before saving
+    pub async fn update_preset(
+        pool: &PgPool,
+        user_id: i32,
+        preset_id: i32,
+        request: &UpdateExportPresetRequest,
+    ) -> Result<Option<ExportPreset>, String> {
+        let Some(mut preset) = Self::get_preset(pool, user_id, preset_id)
+            .await
+            .map_err(|e| format!("Failed to load export preset: {}", e))?
+        else {
+            return Ok(None);
+        };
+        Self::reject_negative(&[request.crf, request.video_bitrate_kbps, request.audio_bitrate_kbps, request.width, request.height])?;
+
+        if let Some(name) = request.name.as_deref() {
+            preset.name = Self::validate_name(name)?;
+        }
+        if let Some(container) = request.container.as_deref() {
+            preset.container = container.trim().to_lowercase();
+        }
+        if let Some(codec) = request.video_codec.as_deref() {
+            preset.video_codec = codec.trim().to_lowercase();
+        }
+        if let Some(codec) = request.audio_codec.as_deref() {
+            preset.audio_codec = codec.trim().to_lowercase();
+        }
+        // Setting one rate-control mode clears the other
+        if request.crf.is_some() {
+            preset.crf = request.crf;
+            preset.video_bitrate_kbps = None;
+        }
+        if request.video_bitrate_kbps.is_some() {
+            preset.video_bitrate_kbps = request.video_bitrate_kbps;
+            preset.crf = None;
+        }
+        preset.audio_bitrate_kbps = request.audio_bitrate_kbps.or(preset.audio_bitrate_kbps);
+        preset.width = request.width.or(preset.width);
+        preset.height = request.height.or(preset.height);
+        preset.fps = request.fps.or(preset.fps);
+        preset.loudness_target = request.loudness_target.or(preset.loudness_target);
+        preset.burn_captions = request.burn_captions.unwrap_or(preset.burn_captions);
+        if let Some(description) = request.description.as_deref() {
+            preset.description = Some(description.trim().to_string()).filter(|d| !d.is_empty());
+        }
+        Self::validate_settings(&preset.settings())?;
+
+        sqlx::query_as::<_, ExportPreset>(
+            r#"
+            UPDATE export_presets SET
+                name = $3, container = $4, video_codec = $5, audio_codec = $6, crf = $7, video_bitrate_kbps = $8,
+                audio_bitrate_kbps = $9, width = $10, height = $11, fps = $12, loudness_target = $13,
+                burn_captions = $14, description = $15, updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(preset_id)
+        .bind(user_id)
+        .bind(&preset.name)
+        .bind(&preset.container)
+        .bind(&preset.video_codec)
+        .bind(&preset.audio_codec)
+        .bind(preset.crf)
+        .bind(preset.video_bitrate_kbps)
+        .bind(preset.audio_bitrate_kbps)
+        .bind(preset.width)
+        .bind(preset.height)
+        .bind(preset.fps)
+        .bind(preset.loudness_target)
+        .bind(preset.burn_captions)
+        .bind(&preset.description)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                "Another export preset already has that name".to_string()
+            }
+            e => format!("Failed to update export preset: {}", e),
+        })
+    }
+
+    pub async fn delete_preset(pool: &PgPool, user_id: i32, preset_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM export_presets WHERE id = $1 AND user_id = $2")
+            .bind(preset_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    fn validate_name(name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() || name.len() > 100 {
+            return Err("Preset name must be 1-100 characters".to_string());
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.')) {
+            return Err("Preset name may only contain letters, numbers, spaces, '-', '_' and '.'".to_string());
+        }
+        Ok(name.to_string())
+    }
+
+    fn reject_negative(values: &[Option<i32>]) -> Result<(), String> {
+        if values.iter().flatten().any(|v| *v < 0) {
+            return Err("crf, bitrates and dimensions cannot be negative".to_string());
+        }
+        Ok(())
+    }
+
+    fn validate_settings(settings: &ExportSettings) -> Result<(), String> {
+        if !CONTAINERS.contains(&settings.container.as_str()) {
+            return Err(format!("Unsupported container '{}'. Use one of: {}", settings.container, CONTAINERS.join(", ")));
+        }
+        if !VIDEO_CODECS.contains(&settings.video_codec.as_str()) {
+            return Err(format!("Unsupported video codec '{}'. Use one of: {}", settings.video_codec, VIDEO_CODECS.join(", ")));
+        }
+        if !AUDIO_CODECS.contains(&settings.audio_codec.as_str()) {
+            return Err(format!("Unsupported audio codec '{}'. Use one of: {}", settings.audio_codec, AUDIO_CODECS.join(", ")));
+        }
+        if settings.container == "webm"
+            && (!matches!(settings.video_codec.as_str(), "libvpx-vp9" | "libaom-av1") || settings.audio_codec != "libopus")
+        {
+            return Err("webm presets need libvpx-vp9 or libaom-av1 video with libopus audio".to_string());
+        }
+        if settings.video_codec == "prores_ks" && settings.container != "mov" {
+            return Err("prores_ks presets must use the mov container".to_string());
+        }
+        if settings.crf.is_some() && settings.video_bitrate_kbps.is_some() {
+            return Err("Set either crf or video_bitrate_kbps, not both".to_string());
+        }
+        if settings.crf.is_some_and(|crf| crf > 63) {
+            return Err("crf must be between 0 and 63".to_string());
+        }
+        if settings.video_bitrate_kbps.is_some_and(|b| !(100..=200_000).contains(&b)) {
+            return Err("video_bitrate_kbps must be between 100 and 200000".to_string());
+        }
+        if settings.audio_bitrate_kbps.is_some_and(|b| !(32..=512).contains(&b)) {
+            return Err("audio_bitrate_kbps must be between 32 and 512".to_string());
+        }
+        match (settings.width, settings.height) {
+            (Some(w), Some(h)) if (16..=7680).contains(&w) && (16..=4320).contains(&h) && w % 2 == 0 && h % 2 == 0 => {}
+            (None, None) => {}
+            (Some(_), Some(_)) => return Err("width and height must be even and at most 7680x4320".to_string()),
+            _ => return Err("Set both width and height, or neither".to_string()),
+        }
+        if settings.fps.is_some_and(|fps| !(1.0..=120.0).contains(&fps)) {
+            return Err("fps must be between 1 and 120".to_string());
+        }
+        if settings.loudness_target.is_some_and(|lufs| !(-70.0..=-5.0).contains(&lufs)) {
+            return Err("loudness_target must be between -70 and -5 LUFS".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+// src/services/feature_flag.rs
+// DB-backed feature flags for rolling experimental agent tools and workflows out to a subset of users.
+// Flags are read on every evaluation (no cache), so turning one off takes effect on the next tool call.
+use crate::models::feature_flag::{FeatureFlag, FlagSubject, UpsertFeatureFlagRequest};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+pub struct FeatureFlagService;
+
+impl FeatureFlagService {
+    pub async fn list(pool: &PgPool) -> Result<Vec<FeatureFlag>, sqlx::Error> {
+        sqlx::query_as::<_, FeatureFlag>("SELECT * FROM feature_flags ORDER BY key")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn get(pool: &PgPool, key: &str) -> Result<Option<FeatureFlag>, sqlx::Error> {
+        sqlx::query_as::<_, FeatureFlag>("SELECT * FROM feature_flags WHERE key = $1")
+            .bind(key)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Plan and staff status of a user, for targeting; anonymous when the user is unknown
+    pub async fn subject(pool: &PgPool, user_id: Option<i32>) -> FlagSubject {
+        let Some(user_id) = user_id else {
+            return FlagSubject::default();
+        };
+        let row = sqlx::query_as::<_, (String, bool)>("SELECT plan, is_staff FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .ok()
+            .flatten();
+        FlagSubject {
+            user_id: Some(user_id),
+            plan: row.as_ref().map(|(plan, _)| plan.clone()),
+            is_staff: row.map(|(_, staff)| staff).unwrap_or(false),
+        }
+    }
+
+    /// Whether a flag is on for a subject: enabled, then targeted by id, plan, staff or rollout bucket
+    pub fn evaluate(flag: &FeatureFlag, subject: &FlagSubject) -> bool {
+        if !flag.enabled {
+            return false;
+        }
+        if flag.rollout_percent >= 100 {
+            return true;
+        }
+        if subject.user_id.is_some_and(|id| flag.user_ids.contains(&id)) {
+            return true;
+        }
+        if subject.plan.as_ref().is_some_and(|plan| flag.plans.iter().any(|p| p.eq_ignore_ascii_case(plan))) {
+            return true;
+        }
+        if flag.include_staff && subject.is_staff {
+            return true;
+        }
+        match subject.user_id {
+            Some(user_id) => (Self::bucket(&flag.key, user_id) as i32) < flag.rollout_percent,
+            None => false,
+        }
+    }
+
+    /// Stable 0-99 bucket of a user for a flag, so raising the percentage only ever adds users
+    fn bucket(key: &str, user_id: i32) -> u32 {
+        let digest = Sha256::digest(format!("{}:{}", key, user_id).as_bytes());
+        u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % 100
+    }
+
+    /// Whether a flag is on for a user; unknown flags are off
+    pub async fn is_enabled(pool: &PgPool, key: &str, user_id: Option<i32>) -> bool {
+        let Ok(Some(flag)) = Self::get(pool, key).await else {
+            return false;
+        };
+        let subject = Self::subject(pool, user_id).await;
+        Self::evaluate(&flag, &subject)
+    }
+
+    /// Every flag's state for a user
+    pub async fn evaluate_all(pool: &PgPool, user_id: Option<i32>) -> Result<Vec<(FeatureFlag, bool)>, sqlx::Error> {
+        let flags = Self::list(pool).await?;
+        let subject = Self::subject(pool, user_id).await;
+        Ok(flags
+            .into_iter()
+            .map(|flag| {
+                let on = Self::evaluate(&flag, &subject);
+                (flag, on)
+            })
+            .collect())
+    }
+
+    /// Tools gated behind flags that are off for this user. A tool named by several flags is
+    /// available when any of them is on. If the flags can't be read, nothing is hidden.
+    pub async fn hidden_tools(pool: &PgPool, user_id: Option<i32>) -> HashSet<String> {
+        let flags = match Self::evaluate_all(pool, user_id).await {
+            Ok(flags) => flags,
+            Err(e) => {
+                tracing::warn!("Failed to load feature flags: {}", e);
+                return HashSet::new();
+            }
+        };
+        let enabled: HashSet<&String> = flags.iter().filter(|(_, on)| *on).flat_map(|(f, _)| f.tools.iter()).collect();
+        flags
+            .iter()
+            .filter(|(_, on)| !*on)
+            .flat_map(|(f, _)| f.tools.iter())
+            .filter(|tool| !enabled.contains(tool))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether a tool is available to a user (not gated, or gated by a flag that is on for them)
+    pub async fn tool_allowed(pool: &PgPool, tool: &str, user_id: Option<i32>) -> bool {
+        !Self::hidden_tools(pool, user_id).await.contains(tool)
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        key: &str,
+        request: &UpsertFeatureFlagRequest,
+        updated_by: i32,
+    ) -> Result<FeatureFlag, String> {
+        let key = key.trim();
+        if key.is_empty() || key.len() > 100 || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.') {
+            return Err("Flag keys are 1-100 characters of letters, digits, '_', '-' or '.'".to_string());
+        }
+        if let Some(percent) = request.rollout_percent {
+            if !(0..=100).contains(&percent) {
+                return Err("rollout_percent must be between 0 and 100".to_string());
+            }
+        }
+        let plans = request.plans.as_ref().map(|plans| plans.iter().map(|p| p.trim().to_lowercase()).collect::<Vec<_>>());
+
+        sqlx::query_as::<_, FeatureFlag>(
+            "INSERT INTO feature_flags (key, description, enabled, rollout_percent, user_ids, plans, include_staff, tools, updated_by)
+             VALUES ($1, $2, COALESCE($3, FALSE), COALESCE($4, 0), COALESCE($5, '{}'), COALESCE($6, '{}'), COALESCE($7, FALSE), COALESCE($8, '{}'), $9)
+             ON CONFLICT (key) DO UPDATE SET
+                description = COALESCE($2, feature_flags.description),
+                enabled = COALESCE($3, feature_flags.enabled),
+                rollout_percent = COALESCE($4, feature_flags.rollout_percent),
+                user_ids = COALESCE($5, feature_flags.user_ids),
+                plans = COALESCE($6, feature_flags.plans),
+                include_staff = COALESCE($7, feature_flags.include_staff),
+                tools = COALESCE($8, feature_flags.tools),
+                updated_by = $9,
+                updated_at = NOW()
+             RETURNING *",
+        )
+        .bind(key)
+        .bind(&request.description)
+        .bind(request.enabled)
+        .bind(request.rollout_percent)
+        .bind(&request.user_ids)
+        .bind(&plans)
+        .bind(request.include_staff)
+        .bind(&request.tools)
+        .bind(updated_by)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save feature flag: {}", e))
+    }
+
+    /// Turn a flag off for everyone immediately, keeping its targeting for when it is re-enabled
+    pub async fn kill(pool: &PgPool, key: &str, updated_by: i32) -> Result<Option<FeatureFlag>, sqlx::Error> {
+        sqlx::query_as::<_, FeatureFlag>(
+            "UPDATE feature_flags SET enabled = FALSE, updated_by = $2, updated_at = NOW() WHERE key = $1 RETURNING *",
+        )
+        .bind(key)
+        .bind(updated_by)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &PgPool, key: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE key = $1").bind(key).execute(pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
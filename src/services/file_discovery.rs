@@ -0,0 +1,220 @@
+// src/services/file_discovery.rs
+//! How the agent finds a session's files. Small sessions get every upload and output listed in the
+//! prompt; past `INLINE_FILE_LIMIT` the prompt only carries a summary and the agent looks files up
+//! with the `list_files` (filter by kind/name/source, paged) and `find_asset` (describe what you
+//! need: names, vision tags, operations and vision-tag embeddings) tools.
+use crate::models::file::{OutputVideo, SessionFile, SessionFileQuery, UploadedFile};
+use crate::services::library::SEMANTIC_MATCH_THRESHOLD;
+use crate::services::{AssetTaggingService, LibraryService};
+use crate::AppState;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Sessions with more uploads and outputs than this get a summary instead of a full listing
+pub const INLINE_FILE_LIMIT: usize = 20;
+
+/// Newest files of each source named in the summary
+const SUMMARY_RECENT_FILES: usize = 5;
+
+pub const DEFAULT_LIST_LIMIT: i64 = 25;
+pub const MAX_LIST_LIMIT: i64 = 100;
+
+/// Uploads and completed outputs of a session as one relation, with the text `find_asset` matches against
+const SESSION_FILES: &str = r#"
+    WITH files AS (
+        SELECT 'upload' AS source, uf.id AS id, uf.original_name AS name, uf.file_path AS path,
+               uf.file_type AS kind, uf.file_size AS size_bytes, NULL::FLOAT8 AS duration_seconds,
+               vt.caption AS detail, uf.created_at,
+               concat_ws(' ', uf.original_name, vt.caption, array_to_string(vt.objects, ' '),
+                         array_to_string(vt.scenes, ' '), array_to_string(vt.dominant_colors, ' ')) AS search_text
+        FROM uploaded_files uf
+        JOIN chat_sessions cs ON cs.id = uf.session_id
+        LEFT JOIN asset_visual_tags vt ON vt.source = 'upload' AND vt.source_id = uf.id
+        WHERE cs.session_uuid = $1
+        UNION ALL
+        SELECT 'output', ov.id::TEXT, ov.file_name, ov.file_path,
+               split_part(ov.mime_type, '/', 1), ov.file_size, ov.duration_seconds,
+               ov.operation_type || ' (' || ov.tool_used || ')', ov.created_at,
+               concat_ws(' ', ov.file_name, ov.operation_type, ov.tool_used, ov.ai_response_message)
+        FROM output_videos ov
+        JOIN chat_sessions cs ON cs.id = ov.session_id
+        WHERE cs.session_uuid = $1 AND ov.processing_status = 'completed' AND ov.deleted_at IS NULL
+    )
+"#;
+
+pub struct FileDiscoveryService;
+
+impl FileDiscoveryService {
+    /// One page of the session's files, newest first, and how many match the filter in total
+    pub async fn list(pool: &PgPool, session_uuid: &str, query: &SessionFileQuery) -> Result<(Vec<SessionFile>, i64), sqlx::Error> {
+        let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let name = query.name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+
+        let filter = r#"
+            WHERE ($2::TEXT IS NULL OR source = $2)
+              AND ($3::TEXT IS NULL OR kind = $3)
+              AND ($4::TEXT IS NULL OR name ILIKE '%' || $4 || '%')
+        "#;
+        let total: i64 = sqlx::query_scalar(&format!("{} SELECT count(*) FROM files {}", SESSION_FILES, filter))
+            .bind(session_uuid)
+            .bind(query.source.as_deref())
+            .bind(query.kind.as_deref())
+            .bind(name)
+            .fetch_one(pool)
+            .await?;
+        let files = sqlx::query_as::<_, SessionFile>(&format!(
+            "{} SELECT source, id, name, path, kind, size_bytes, duration_seconds, detail, created_at FROM files {}
+             ORDER BY created_at DESC LIMIT $5 OFFSET $6",
+            SESSION_FILES, filter
+        ))
+        .bind(session_uuid)
+        .bind(query.source.as_deref())
+        .bind(query.kind.as_deref())
+        .bind(name)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+        Ok((files, total))
+    }
+
+    /// Session files matching a description, best first: each description word found in a file's
+    /// name, vision tags or producing operation is a hit, and uploads whose vision-tag embedding is
+    /// close to the description rank after the text matches
+    pub async fn find(state: &Arc<AppState>, session_uuid: &str, user_id: Option<i32>, description: &str, limit: usize) -> Result<Vec<SessionFile>, sqlx::Error> {
+        let terms = LibraryService::search_terms(description);
+        let mut files = sqlx::query_as::<_, SessionFile>(&format!(
+            "{} SELECT source, id, name, path, kind, size_bytes, duration_seconds, detail, created_at FROM files f
+             CROSS JOIN LATERAL (SELECT count(*) AS hits FROM unnest($2::TEXT[]) term WHERE f.search_text ILIKE '%' || term || '%') m
+             WHERE m.hits > 0
+             ORDER BY m.hits DESC, created_at DESC LIMIT $3",
+            SESSION_FILES
+        ))
+        .bind(session_uuid)
+        .bind(&terms)
+        .bind(limit as i64)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+        let Some(user_id) = user_id.filter(|_| files.len() < limit) else {
+            return Ok(files);
+        };
+        let hits = match AssetTaggingService::semantic_search(state, user_id, description, 20).await {
+            Ok(hits) => hits,
+            Err(e) => {
+                tracing::warn!("Semantic file search failed: {}", e);
+                return Ok(files);
+            }
+        };
+        let scores: HashMap<String, f64> = hits.into_iter().filter(|(_, score)| *score >= SEMANTIC_MATCH_THRESHOLD).collect();
+        if scores.is_empty() {
+            return Ok(files);
+        }
+
+        let uploads = Self::list(&state.db_pool, session_uuid, &SessionFileQuery {
+            source: Some("upload".to_string()),
+            limit: Some(MAX_LIST_LIMIT),
+            ..Default::default()
+        })
+        .await?
+        .0;
+        let mut semantic: Vec<(f64, SessionFile)> = uploads
+            .into_iter()
+            .filter(|upload| !files.iter().any(|f| f.source == "upload" && f.id == upload.id))
+            .filter_map(|upload| Some((*scores.get(&AssetTaggingService::point_id("upload", &upload.id))?, upload)))
+            .collect();
+        semantic.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        files.extend(semantic.into_iter().map(|(_, f)| f).take(limit - files.len()));
+        Ok(files)
+    }
+
+    /// Prompt context for a session with more than `INLINE_FILE_LIMIT` files: counts, the newest few
+    /// of each, and how to find the rest
+    pub fn summary_context(files: &[UploadedFile], outputs: &[OutputVideo]) -> String {
+        let mut kinds: Vec<(&str, usize)> = Vec::new();
+        for kind in files.iter().map(|f| f.file_type.as_str()) {
+            match kinds.iter_mut().find(|(k, _)| *k == kind) {
+                Some((_, count)) => *count += 1,
+                None => kinds.push((kind, 1)),
+            }
+        }
+        let kinds = kinds.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect::<Vec<_>>().join(", ");
+
+        let mut context = format!(
+            "FILES IN THIS CHAT SESSION: {} uploads ({}) and {} generated outputs - too many to list here.\n",
+            files.len(),
+            kinds,
+            outputs.len()
+        );
+        if !files.is_empty() {
+            context.push_str("Newest uploads:\n");
+            for file in files.iter().take(SUMMARY_RECENT_FILES) {
+                context.push_str(&format!("- \"{}\" ({}) - USE THIS PATH: {}\n", file.original_name, file.file_type, file.file_path));
+            }
+        }
+        if !outputs.is_empty() {
+            context.push_str("Newest outputs:\n");
+            for video in outputs.iter().take(SUMMARY_RECENT_FILES) {
+                context.push_str(&format!("- \"{}\" ({}) - USE THIS PATH: {}\n", video.file_name, video.operation_type, video.file_path));
+            }
+        }
+        context.push_str(
+            "To work with any other file, look it up first: list_files filters by kind, name or source (upload/output) \
+             and pages through everything; find_asset finds files by description (e.g. \"the drone shot of the beach\"). \
+             Always pass the exact path they return to editing tools.\n\n",
+        );
+        context
+    }
+}
+
+/// One line per file for the tools' replies
+pub fn describe(file: &SessionFile) -> String {
+    let duration = file.duration_seconds.map(|d| format!(", {:.1}s", d)).unwrap_or_default();
+    let detail = file.detail.as_deref().map(|d| format!(" - {}", d)).unwrap_or_default();
+    format!(
+        "  • {} [{} {}{}, {:.2} MB, {}] PATH: {}{}",
+        file.name,
+        file.source,
+        file.kind,
+        duration,
+        file.size_bytes as f64 / (1024.0 * 1024.0),
+        file.created_at.format("%Y-%m-%d %H:%M"),
+        file.path,
+        detail
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload(index: usize, file_type: &str) -> UploadedFile {
+        UploadedFile {
+            id: format!("id-{}", index),
+            session_id: Some(1),
+            original_name: format!("take_{}.mp4", index),
+            stored_name: format!("uuid_{}.mp4", index),
+            file_path: format!("uploads/uuid_{}.mp4", index),
+            file_size: 1024,
+            file_type: file_type.to_string(),
+            mime_type: None,
+            upload_status: "completed".to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn large_sessions_get_a_summary_pointing_at_the_tools() {
+        let files: Vec<UploadedFile> = (0..200).map(|i| upload(i, if i % 4 == 0 { "audio" } else { "video" })).collect();
+        let context = FileDiscoveryService::summary_context(&files, &[]);
+
+        assert!(context.contains("200 uploads (50 audio, 150 video) and 0 generated outputs"));
+        assert_eq!(context.matches("USE THIS PATH").count(), SUMMARY_RECENT_FILES);
+        assert!(context.contains("uploads/uuid_0.mp4") && !context.contains("uploads/uuid_199.mp4"));
+        assert!(context.contains("list_files") && context.contains("find_asset"));
+        assert!(context.len() < 2000);
+    }
+}
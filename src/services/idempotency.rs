@@ -0,0 +1,137 @@
+// src/services/idempotency.rs
+// Stored results of requests sent with an Idempotency-Key, so a client retrying a job submission or
+// YouTube upload after a network error gets the original result instead of a second job or video
+use crate::models::idempotency_key::IdempotencyKey;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+/// How long a key is remembered; a retry after this runs the request again
+pub const WINDOW_HOURS: i64 = 24;
+
+/// What to do with a request carrying a key
+#[derive(Debug)]
+pub enum IdempotencyClaim {
+    /// First time this key is seen (or it expired): handle the request
+    New,
+    /// Same request as before: answer with its stored result
+    Replay(IdempotencyKey),
+    /// The first request with this key hasn't finished yet
+    InProgress,
+    /// The key was already used for a different request
+    Mismatch,
+}
+
+pub struct IdempotencyService;
+
+impl IdempotencyService {
+    /// Fingerprint of a request, so a key reused for another request can be told apart from a retry
+    pub fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_bytes());
+        hasher.update(b" ");
+        hasher.update(path.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(body);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Claim `key` for a request; only the caller that gets `New` may handle it
+    pub async fn claim(pool: &PgPool, user_id: i32, key: &str, request_hash: &str) -> Result<IdempotencyClaim, sqlx::Error> {
+        sqlx::query(
+            "DELETE FROM idempotency_keys WHERE user_id = $1 AND idempotency_key = $2 AND created_at < NOW() - make_interval(hours => $3)"
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(WINDOW_HOURS as i32)
+        .execute(pool)
+        .await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (user_id, idempotency_key, request_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, idempotency_key) DO NOTHING
+            "#
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(request_hash)
+        .execute(pool)
+        .await?;
+        if inserted.rows_affected() > 0 {
+            return Ok(IdempotencyClaim::New);
+        }
+
+        let existing = sqlx::query_as::<_, IdempotencyKey>(
+            "SELECT * FROM idempotency_keys WHERE user_id = $1 AND idempotency_key = $2"
+        )
+        .bind(user_id)
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+        Ok(match existing {
+            Some(existing) if existing.request_hash != request_hash => IdempotencyClaim::Mismatch,
+            Some(existing) if existing.status_code.is_some() => IdempotencyClaim::Replay(existing),
+            // Released by the first request a moment ago; the client's next retry claims it
+            _ => IdempotencyClaim::InProgress,
+        })
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        user_id: i32,
+        key: &str,
+        status_code: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $3, content_type = $4, response_body = $5, completed_at = NOW()
+            WHERE user_id = $1 AND idempotency_key = $2
+            "#
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(status_code as i32)
+        .bind(content_type)
+        .bind(body)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget a key whose request failed or was abandoned, so a retry runs it again
+    pub async fn release(pool: &PgPool, user_id: i32, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE user_id = $1 AND idempotency_key = $2 AND completed_at IS NULL")
+            .bind(user_id)
+            .bind(key)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn purge_expired(pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE created_at < NOW() - make_interval(hours => $1)")
+            .bind(WINDOW_HOURS as i32)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_hash_the_same_and_other_requests_do_not() {
+        let body = br#"{"session_id":"abc","jobs":[]}"#;
+        let hash = IdempotencyService::request_hash("POST", "/api/jobs/graph", body);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, IdempotencyService::request_hash("POST", "/api/jobs/graph", body));
+        assert_ne!(hash, IdempotencyService::request_hash("POST", "/api/jobs/graph", br#"{"session_id":"xyz","jobs":[]}"#));
+        assert_ne!(hash, IdempotencyService::request_hash("POST", "/api/batch/render", body));
+    }
+}
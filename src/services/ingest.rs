@@ -0,0 +1,401 @@
+// src/services/ingest.rs
+// RTMP live ingest via nginx-rtmp callbacks: stream keys map to a user's chat session,
+// finished recordings are remuxed into uploads/ and can start a clipping job right away.
+// While a stream is live, its HLS segments allow clipping "the last N seconds" on demand.
+use crate::jobs::video_job::{self, AgentType};
+use crate::jobs::JobPriority;
+use crate::models::file::OutputVideo;
+use crate::models::ingest::{CreateLiveIngestRequest, LiveIngest};
+use crate::services::output_video::OutputVideoService;
+use crate::AppState;
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Where nginx-rtmp writes recordings (its `record_path`); callbacks may only reference files in here
+pub fn recordings_dir() -> PathBuf {
+    PathBuf::from(&crate::config::get().storage.recordings_dir)
+}
+
+/// Where nginx-rtmp writes live HLS segments (its `hls_path`), one <stream_key>.m3u8 per stream
+pub fn hls_dir() -> PathBuf {
+    PathBuf::from(&crate::config::get().storage.hls_dir)
+}
+
+/// Longest clip that can be cut from a live stream (10 minutes)
+pub const MAX_LIVE_CLIP_SECONDS: f64 = 600.0;
+
+/// Publish URL shown to streamers (without the stream key)
+pub fn rtmp_publish_url() -> String {
+    crate::config::get().ingest.rtmp_public_url.clone()
+}
+
+pub struct IngestService;
+
+impl IngestService {
+    pub async fn create(pool: &PgPool, user_id: i32, request: &CreateLiveIngestRequest) -> Result<LiveIngest, String> {
+        let session_id = sqlx::query_scalar::<_, i32>(
+            "SELECT id FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2"
+        )
+        .bind(&request.session_uuid)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to look up session: {}", e))?
+        .ok_or_else(|| format!("Chat session {} not found", request.session_uuid))?;
+
+        let title = request
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or("Live stream")
+            .to_string();
+        let stream_key = format!("live_{}", uuid::Uuid::new_v4().simple());
+
+        sqlx::query_as::<_, LiveIngest>(
+            r#"
+            INSERT INTO live_ingests (user_id, session_id, stream_key, title, auto_clip, clip_instructions)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(session_id)
+        .bind(&stream_key)
+        .bind(&title)
+        .bind(request.auto_clip.unwrap_or(true))
+        .bind(request.clip_instructions.as_deref().map(str::trim).filter(|c| !c.is_empty()))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to create ingest: {}", e))
+    }
+
+    pub async fn list(pool: &PgPool, user_id: i32) -> Result<Vec<LiveIngest>, sqlx::Error> {
+        sqlx::query_as::<_, LiveIngest>("SELECT * FROM live_ingests WHERE user_id = $1 ORDER BY created_at DESC")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn revoke(pool: &PgPool, user_id: i32, ingest_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE live_ingests SET status = 'revoked' WHERE id = $1 AND user_id = $2 AND status <> 'revoked'"
+        )
+        .bind(ingest_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_owned(pool: &PgPool, user_id: i32, ingest_id: i32) -> Result<Option<LiveIngest>, sqlx::Error> {
+        sqlx::query_as::<_, LiveIngest>("SELECT * FROM live_ingests WHERE id = $1 AND user_id = $2")
+            .bind(ingest_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// The stream currently live in a chat session, if any
+    pub async fn find_live_for_session(pool: &PgPool, session_uuid: &str) -> Result<Option<LiveIngest>, sqlx::Error> {
+        sqlx::query_as::<_, LiveIngest>(
+            r#"
+            SELECT li.* FROM live_ingests li
+            JOIN chat_sessions cs ON li.session_id = cs.id
+            WHERE cs.session_uuid = $1 AND li.status = 'live'
+            ORDER BY li.started_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(session_uuid)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// on_publish: accept the stream only for a known, unrevoked key that isn't already live
+    pub async fn start_stream(pool: &PgPool, stream_key: &str) -> Result<Option<LiveIngest>, sqlx::Error> {
+        sqlx::query_as::<_, LiveIngest>(
+            r#"
+            UPDATE live_ingests SET status = 'live', started_at = NOW(), ended_at = NULL, error_message = NULL
+            WHERE stream_key = $1 AND status NOT IN ('revoked', 'live')
+            RETURNING *
+            "#,
+        )
+        .bind(stream_key)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// on_publish_done: the broadcaster disconnected; the recording arrives via on_record_done
+    pub async fn end_stream(pool: &PgPool, stream_key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE live_ingests SET status = 'processing', ended_at = NOW() WHERE stream_key = $1 AND status = 'live'")
+            .bind(stream_key)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// on_record_done: move the recording into the session and optionally start clipping, in the background
+    pub async fn recording_finished(state: Arc<AppState>, stream_key: &str, recording_path: &str) -> Result<(), String> {
+        let ingest = sqlx::query_as::<_, LiveIngest>("SELECT * FROM live_ingests WHERE stream_key = $1")
+            .bind(stream_key)
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|e| format!("Failed to load ingest: {}", e))?
+            .ok_or_else(|| format!("Unknown stream key {}", stream_key))?;
+        let recording = Self::validate_recording_path(recording_path)?;
+
+        tokio::spawn(async move {
+            if let Err(e) = Self::ingest_recording(&state, &ingest, &recording).await {
+                tracing::error!("❌ Live ingest {} failed: {}", ingest.id, e);
+                let _ = sqlx::query("UPDATE live_ingests SET status = 'failed', error_message = $2 WHERE id = $1")
+                    .bind(ingest.id)
+                    .bind(&e)
+                    .execute(&state.db_pool)
+                    .await;
+            }
+        });
+        Ok(())
+    }
+
+    fn validate_recording_path(recording_path: &str) -> Result<PathBuf, String> {
+        let root = recordings_dir()
+            .canonicalize()
+            .map_err(|e| format!("Recordings directory unavailable: {}", e))?;
+        let recording = Path::new(recording_path)
+            .canonicalize()
+            .map_err(|e| format!("Recording {} not found: {}", recording_path, e))?;
+        if !recording.starts_with(&root) || !recording.is_file() {
+            return Err(format!("Recording {} is outside {}", recording_path, root.display()));
+        }
+        Ok(recording)
+    }
+
+    async fn ingest_recording(state: &Arc<AppState>, ingest: &LiveIngest, recording: &Path) -> Result<(), String> {
+        // Remux (no re-encode) the FLV recording to a seekable MP4 in uploads/
+        tokio::fs::create_dir_all("uploads")
+            .await
+            .map_err(|e| format!("Failed to create uploads directory: {}", e))?;
+        let file_id = uuid::Uuid::new_v4().to_string();
+        let stored_name = format!("{}_live_{}.mp4", file_id, ingest.id);
+        let file_path = format!("uploads/{}", stored_name);
+
+        let output = tokio::process::Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i")
+            .arg(recording)
+            .arg("-c")
+            .arg("copy")
+            .arg("-movflags")
+            .arg("+faststart")
+            .arg(&file_path)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Remuxing recording failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+        let file_size = tokio::fs::metadata(&file_path).await.map(|m| m.len() as i64).unwrap_or(0);
+
+        sqlx::query(
+            "INSERT INTO uploaded_files (id, session_id, original_name, stored_name, file_path, file_size, file_type, mime_type, upload_status) VALUES ($1, $2, $3, $4, $5, $6, 'video', 'video/mp4', 'uploaded')"
+        )
+        .bind(&file_id)
+        .bind(ingest.session_id)
+        .bind(format!("{}.mp4", ingest.title))
+        .bind(&stored_name)
+        .bind(&file_path)
+        .bind(file_size)
+        .execute(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to register recording: {}", e))?;
+
+        if let Err(e) = tokio::fs::remove_file(recording).await {
+            tracing::warn!("Failed to remove raw recording {}: {}", recording.display(), e);
+        }
+        tracing::info!("🔴 Live stream '{}' recorded to {} ({} bytes)", ingest.title, file_path, file_size);
+        crate::services::ActivityService::record(
+            &state.db_pool,
+            ingest.user_id,
+            "file_uploaded",
+            &format!("Recorded live stream \"{}\"", ingest.title),
+            None,
+            serde_json::json!({ "file_id": file_id, "file_type": "video", "file_size": file_size, "path": file_path, "ingest_id": ingest.id }),
+        )
+        .await;
+
+        let clip_job_id = if ingest.auto_clip {
+            Some(Self::start_clipping(state, ingest, &file_path).await?)
+        } else {
+            None
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE live_ingests SET status = 'ended', recording_path = $2, uploaded_file_id = $3, clip_job_id = $4,
+                ended_at = COALESCE(ended_at, NOW())
+            WHERE id = $1
+            "#,
+        )
+        .bind(ingest.id)
+        .bind(&file_path)
+        .bind(&file_id)
+        .bind(&clip_job_id)
+        .execute(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to update ingest: {}", e))?;
+        Ok(())
+    }
+
+    /// Run the session's editing agent as a background job with a clipping brief
+    async fn start_clipping(state: &Arc<AppState>, ingest: &LiveIngest, file_path: &str) -> Result<String, String> {
+        let session_uuid = sqlx::query_scalar::<_, String>("SELECT session_uuid FROM chat_sessions WHERE id = $1")
+            .bind(ingest.session_id)
+            .fetch_one(&state.db_pool)
+            .await
+            .map_err(|e| format!("Failed to load session: {}", e))?;
+
+        let mut brief = format!(
+            "The live stream \"{}\" just ended and was recorded to {}. Clip it: find the most engaging moments \
+             and export each as its own short clip (15-60 seconds) to outputs/, then summarize what each clip contains.",
+            ingest.title, file_path
+        );
+        if let Some(instructions) = &ingest.clip_instructions {
+            brief.push_str(&format!(" Additional instructions: {}", instructions));
+        }
+
+        let agent_type = if state.claude_client.is_some() { AgentType::Claude } else { AgentType::Gemini };
+        video_job::spawn_video_editing_job(
+            format!("Clip live stream: {}", ingest.title),
+            brief,
+            session_uuid,
+            agent_type,
+            JobPriority::Low,
+            state.clone(),
+            state.job_manager.clone(),
+        )
+        .await
+    }
+
+    /// Cut the last `seconds` of a live stream from its HLS segments into outputs/ and record it as a session output
+    pub async fn clip_live(
+        pool: &PgPool,
+        ingest: &LiveIngest,
+        seconds: f64,
+        output_file: Option<&str>,
+    ) -> Result<OutputVideo, String> {
+        if ingest.status != "live" {
+            return Err(format!("Stream '{}' is not live (status: {})", ingest.title, ingest.status));
+        }
+        if !(1.0..=MAX_LIVE_CLIP_SECONDS).contains(&seconds) {
+            return Err(format!("seconds must be between 1 and {}", MAX_LIVE_CLIP_SECONDS));
+        }
+
+        let playlist_path = hls_dir().join(format!("{}.m3u8", ingest.stream_key));
+        let playlist = tokio::fs::read_to_string(&playlist_path)
+            .await
+            .map_err(|_| format!("No live segments yet for '{}' (is HLS enabled on the RTMP server?)", ingest.title))?;
+        let segment_dir = playlist_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let (segments, available) = Self::trailing_segments(&playlist, &segment_dir, seconds)?;
+
+        // A private playlist of just the needed segments; the oldest one may start before the window
+        let mut clip_playlist = format!(
+            "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:{}\n#EXT-X-MEDIA-SEQUENCE:0\n",
+            segments.iter().map(|(d, _)| d.ceil() as u64).max().unwrap_or(1)
+        );
+        for (duration, path) in &segments {
+            clip_playlist.push_str(&format!("#EXTINF:{:.3},\n{}\n", duration, path.display()));
+        }
+        clip_playlist.push_str("#EXT-X-ENDLIST\n");
+        let temp_playlist = std::env::temp_dir().join(format!("live_clip_{}.m3u8", uuid::Uuid::new_v4().simple()));
+        tokio::fs::write(&temp_playlist, clip_playlist)
+            .await
+            .map_err(|e| format!("Failed to write clip playlist: {}", e))?;
+
+        let output_path = match output_file.map(str::trim).filter(|f| !f.is_empty()) {
+            Some(name) => {
+                let file_name = Path::new(name).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                format!("outputs/{}", file_name)
+            }
+            None => format!("outputs/live_{}_{}.mp4", ingest.id, chrono::Utc::now().format("%Y%m%d_%H%M%S")),
+        };
+        let clip_seconds = seconds.min(available);
+        let skip = (available - clip_seconds).max(0.0);
+
+        let output = tokio::process::Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-allowed_extensions")
+            .arg("ALL")
+            .arg("-i")
+            .arg(&temp_playlist)
+            .arg("-ss")
+            .arg(format!("{:.3}", skip))
+            .arg("-t")
+            .arg(format!("{:.3}", clip_seconds))
+            .arg("-c:v")
+            .arg("libx264")
+            .arg("-preset")
+            .arg("veryfast")
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-movflags")
+            .arg("+faststart")
+            .arg(&output_path)
+            .output()
+            .await;
+        let _ = tokio::fs::remove_file(&temp_playlist).await;
+        let output = output.map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Live clip failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let params = serde_json::json!({ "ingest_id": ingest.id, "seconds": clip_seconds }).to_string();
+        OutputVideoService::save_output_video(
+            pool,
+            ingest.session_id,
+            ingest.user_id,
+            None,
+            &output_path,
+            "live_clip",
+            Some(&params),
+            "clip_live_stream",
+            Some(&format!("Last {:.0}s of live stream \"{}\"", clip_seconds, ingest.title)),
+        )
+        .await
+        .map_err(|e| format!("Failed to save clip: {}", e))
+    }
+
+    /// Newest segments covering at least `seconds` (oldest first), with their total duration
+    fn trailing_segments(playlist: &str, segment_dir: &Path, seconds: f64) -> Result<(Vec<(f64, PathBuf)>, f64), String> {
+        let mut all = Vec::new();
+        let mut pending_duration = None;
+        for line in playlist.lines().map(str::trim) {
+            if let Some(info) = line.strip_prefix("#EXTINF:") {
+                pending_duration = info.split(',').next().and_then(|d| d.trim().parse::<f64>().ok());
+            } else if !line.is_empty() && !line.starts_with('#') {
+                if let Some(duration) = pending_duration.take() {
+                    // Segment names come from our own RTMP server, but never follow them out of its directory
+                    let name = Path::new(line).file_name().ok_or_else(|| format!("Bad segment entry: {}", line))?;
+                    all.push((duration, segment_dir.join(name)));
+                }
+            }
+        }
+        if all.is_empty() {
+            return Err("The live playlist has no segments yet".to_string());
+        }
+
+        let mut selected = Vec::new();
+        let mut total = 0.0;
+        for (duration, path) in all.into_iter().rev() {
+            let absolute = path.canonicalize().map_err(|e| format!("Segment {} unavailable: {}", path.display(), e))?;
+            selected.push((duration, absolute));
+            total += duration;
+            if total >= seconds {
+                break;
+            }
+        }
+        selected.reverse();
+        Ok((selected, total))
+    }
+}
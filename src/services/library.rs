@@ -0,0 +1,467 @@
+// src/services/library.rs
+// Per-user media asset library stored on disk under library/<user_id>/, shared across sessions
+// Tool arguments of the form "library:<name>" are resolved to the asset's file path
+use crate::models::library::{
+    LibraryAsset, LibrarySearchQuery, NewLibraryAsset, UpdateLibraryAssetRequest, ASSET_KINDS,
+};
+use crate::services::AssetTaggingService;
+use crate::AppState;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::sync::Arc;
+
+pub const LIBRARY_DIR: &str = "library";
+
+/// Minimum cosine similarity for a vision-tag embedding to count as a match
+pub(crate) const SEMANTIC_MATCH_THRESHOLD: f64 = 0.6;
+
+/// Assets listed in the agent's prompt; the rest are found with search_library
+const LIBRARY_CONTEXT_LIMIT: usize = 20;
+
+/// Prefix marking a tool argument as a library reference
+pub const LIBRARY_REFERENCE_PREFIX: &str = "library:";
+
+pub struct LibraryService;
+
+impl LibraryService {
+    /// Save uploaded bytes as a new library asset
+    pub async fn store_upload(
+        pool: &PgPool,
+        user_id: i32,
+        original_name: &str,
+        data: &[u8],
+        meta: &NewLibraryAsset,
+    ) -> Result<LibraryAsset, String> {
+        let destination = Self::destination_path(user_id, original_name).await?;
+        tokio::fs::write(&destination, data)
+            .await
+            .map_err(|e| format!("Failed to store asset: {}", e))?;
+        Self::insert_asset(pool, user_id, &destination, meta).await
+    }
+
+    /// Copy an existing upload or output into the library
+    pub async fn import_file(
+        pool: &PgPool,
+        user_id: i32,
+        source_path: &str,
+        meta: &NewLibraryAsset,
+    ) -> Result<LibraryAsset, String> {
+        let source = Path::new(source_path);
+        let allowed = source_path.starts_with("uploads/") || source_path.starts_with("outputs/");
+        if !allowed || source.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err("file_path must be inside uploads/ or outputs/".to_string());
+        }
+        if !source.is_file() {
+            return Err(format!("File not found: {}", source_path));
+        }
+
+        let file_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let destination = Self::destination_path(user_id, &file_name).await?;
+        tokio::fs::copy(source, &destination)
+            .await
+            .map_err(|e| format!("Failed to copy {} into the library: {}", source_path, e))?;
+        Self::insert_asset(pool, user_id, &destination, meta).await
+    }
+
+    async fn destination_path(user_id: i32, original_name: &str) -> Result<String, String> {
+        let dir = format!("{}/{}", LIBRARY_DIR, user_id);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| format!("Failed to create library directory: {}", e))?;
+
+        let safe_name: String = Path::new(original_name)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        Ok(format!("{}/{}_{}", dir, uuid::Uuid::new_v4().simple(), safe_name))
+    }
+
+    async fn insert_asset(
+        pool: &PgPool,
+        user_id: i32,
+        file_path: &str,
+        meta: &NewLibraryAsset,
+    ) -> Result<LibraryAsset, String> {
+        let result = Self::try_insert_asset(pool, user_id, file_path, meta).await;
+        if result.is_err() {
+            // Don't leave orphaned files behind when the metadata is rejected
+            let _ = tokio::fs::remove_file(file_path).await;
+        }
+        result
+    }
+
+    async fn try_insert_asset(
+        pool: &PgPool,
+        user_id: i32,
+        file_path: &str,
+        meta: &NewLibraryAsset,
+    ) -> Result<LibraryAsset, String> {
+        let name = Self::validate_name(&meta.name)?;
+        let extension = Path::new(file_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let kind = match meta.kind.as_deref().map(|k| k.trim().to_lowercase()) {
+            Some(kind) if !kind.is_empty() => Self::validate_kind(&kind)?,
+            _ => Self::detect_kind(&extension).to_string(),
+        };
+        let file_size = tokio::fs::metadata(file_path).await.map(|m| m.len() as i64).unwrap_or(0);
+        let duration = match Self::mime_type(&extension) {
+            Some(mime) if mime.starts_with("video/") || mime.starts_with("audio/") => {
+                crate::core::analyze_video(file_path).ok().map(|m| m.duration_seconds)
+            }
+            _ => None,
+        };
+
+        sqlx::query_as::<_, LibraryAsset>(
+            r#"
+            INSERT INTO library_assets (user_id, name, kind, file_path, file_size, mime_type, duration_seconds, tags, description)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&name)
+        .bind(&kind)
+        .bind(file_path)
+        .bind(file_size)
+        .bind(Self::mime_type(&extension))
+        .bind(duration)
+        .bind(Self::normalize_tags(&meta.tags))
+        .bind(meta.description.as_deref().map(str::trim).filter(|d| !d.is_empty()))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                format!("An asset named '{}' already exists in your library", name)
+            }
+            e => format!("Failed to save asset: {}", e),
+        })
+    }
+
+    /// Search by free text (name, description, tags and vision tags), exact tag, kind and faces.
+    /// Each query word counts as a hit, so "the drone shot over water" ranks assets matching most words first.
+    pub async fn search(
+        pool: &PgPool,
+        user_id: i32,
+        query: &LibrarySearchQuery,
+    ) -> Result<Vec<LibraryAsset>, sqlx::Error> {
+        let terms = query.q.as_deref().map(Self::search_terms).unwrap_or_default();
+        let tag = query.tag.as_deref().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty());
+        let kind = query.kind.as_deref().map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty());
+
+        sqlx::query_as::<_, LibraryAsset>(
+            r#"
+            SELECT la.* FROM library_assets la
+            LEFT JOIN asset_visual_tags vt ON vt.source = 'library' AND vt.source_id = la.id::text
+            CROSS JOIN LATERAL (
+                SELECT count(*) AS hits FROM unnest($2::text[]) term
+                WHERE concat_ws(' ', la.name, la.description, array_to_string(la.tags, ' '), vt.caption,
+                                array_to_string(vt.objects, ' '), array_to_string(vt.scenes, ' '),
+                                array_to_string(vt.dominant_colors, ' ')) ILIKE '%' || term || '%'
+            ) m
+            WHERE la.user_id = $1
+              AND (cardinality($2::text[]) = 0 OR m.hits > 0)
+              AND ($3::text IS NULL OR $3 = ANY(la.tags))
+              AND ($4::text IS NULL OR la.kind = $4)
+              AND ($5::bool IS NULL OR vt.has_faces = $5)
+            ORDER BY m.hits DESC, la.use_count DESC, la.name
+            "#,
+        )
+        .bind(user_id)
+        .bind(&terms)
+        .bind(tag)
+        .bind(kind)
+        .bind(query.has_faces)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Text search plus semantic matches on vision-tag embeddings (when Qdrant is configured)
+    pub async fn search_with_semantics(
+        state: &Arc<AppState>,
+        user_id: i32,
+        query: &LibrarySearchQuery,
+    ) -> Result<Vec<LibraryAsset>, sqlx::Error> {
+        let mut assets = Self::search(&state.db_pool, user_id, query).await?;
+        let Some(text) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) else {
+            return Ok(assets);
+        };
+
+        let hits = match AssetTaggingService::semantic_search(state, user_id, text, 20).await {
+            Ok(hits) => hits,
+            Err(e) => {
+                tracing::warn!("Semantic library search failed: {}", e);
+                return Ok(assets);
+            }
+        };
+        let scores: HashMap<String, f64> = hits.into_iter().filter(|(_, score)| *score >= SEMANTIC_MATCH_THRESHOLD).collect();
+        if scores.is_empty() {
+            return Ok(assets);
+        }
+
+        // Candidates honour the same tag/kind/faces filters, only without the text condition
+        let unfiltered = LibrarySearchQuery { q: None, ..query.clone() };
+        let mut semantic: Vec<(f64, LibraryAsset)> = Self::search(&state.db_pool, user_id, &unfiltered)
+            .await?
+            .into_iter()
+            .filter(|a| !assets.iter().any(|existing| existing.id == a.id))
+            .filter_map(|a| {
+                let score = *scores.get(&AssetTaggingService::point_id("library", &a.id.to_string()))?;
+                Some((score, a))
+            })
+            .collect();
+        semantic.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        assets.extend(semantic.into_iter().map(|(_, a)| a));
+        Ok(assets)
+    }
+
+    /// Lowercased query words worth matching on (drops short words and filler)
+    pub(crate) fn search_terms(q: &str) -> Vec<String> {
+        const STOPWORDS: [&str; 12] = ["the", "and", "with", "over", "from", "that", "this", "into", "shot", "clip", "video", "one"];
+        let mut terms: Vec<String> = q
+            .split(|c: char| !c.is_alphanumeric())
+            .map(|w| w.to_lowercase())
+            .filter(|w| w.len() >= 3 && !STOPWORDS.contains(&w.as_str()))
+            .collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() && !q.trim().is_empty() {
+            terms.push(q.trim().to_lowercase());
+        }
+        terms
+    }
+
+    pub async fn get_asset(pool: &PgPool, user_id: i32, asset_id: i32) -> Result<Option<LibraryAsset>, sqlx::Error> {
+        sqlx::query_as::<_, LibraryAsset>("SELECT * FROM library_assets WHERE id = $1 AND user_id = $2")
+            .bind(asset_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Names are matched case-insensitively
+    pub async fn get_by_name(pool: &PgPool, user_id: i32, name: &str) -> Result<Option<LibraryAsset>, sqlx::Error> {
+        sqlx::query_as::<_, LibraryAsset>(
+            "SELECT * FROM library_assets WHERE user_id = $1 AND lower(name) = lower($2)"
+        )
+        .bind(user_id)
+        .bind(name.trim())
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update_asset(
+        pool: &PgPool,
+        user_id: i32,
+        asset_id: i32,
+        request: &UpdateLibraryAssetRequest,
+    ) -> Result<Option<LibraryAsset>, String> {
+        let name = request.name.as_deref().map(Self::validate_name).transpose()?;
+        let kind = request.kind.as_deref().map(|k| Self::validate_kind(&k.trim().to_lowercase())).transpose()?;
+        let tags = request.tags.as_deref().map(Self::normalize_tags);
+
+        sqlx::query_as::<_, LibraryAsset>(
+            r#"
+            UPDATE library_assets SET
+                name = COALESCE($3, name),
+                kind = COALESCE($4, kind),
+                tags = COALESCE($5, tags),
+                description = COALESCE($6, description),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(asset_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(kind)
+        .bind(tags)
+        .bind(&request.description)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                "Another asset in your library already has that name".to_string()
+            }
+            e => format!("Failed to update asset: {}", e),
+        })
+    }
+
+    /// Delete the asset and its file
+    pub async fn delete_asset(pool: &PgPool, user_id: i32, asset_id: i32) -> Result<bool, sqlx::Error> {
+        let deleted = sqlx::query_scalar::<_, String>(
+            "DELETE FROM library_assets WHERE id = $1 AND user_id = $2 RETURNING file_path"
+        )
+        .bind(asset_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match deleted {
+            Some(file_path) => {
+                sqlx::query("DELETE FROM asset_visual_tags WHERE source = 'library' AND source_id = $1")
+                    .bind(asset_id.to_string())
+                    .execute(pool)
+                    .await?;
+                if let Err(e) = tokio::fs::remove_file(&file_path).await {
+                    tracing::warn!("Failed to remove library file {}: {}", file_path, e);
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Replace every "library:<name>" string in tool arguments with the asset's file path
+    pub async fn resolve_references(pool: &PgPool, user_id: i32, args: &Value) -> Result<Value, String> {
+        let mut names = Vec::new();
+        Self::collect_references(args, &mut names);
+        if names.is_empty() {
+            return Ok(args.clone());
+        }
+
+        let mut paths = HashMap::new();
+        for name in names {
+            let asset = Self::get_by_name(pool, user_id, &name)
+                .await
+                .map_err(|e| format!("Failed to look up library asset '{}': {}", name, e))?
+                .ok_or_else(|| format!("No asset named '{}' in your library", name))?;
+            sqlx::query("UPDATE library_assets SET use_count = use_count + 1 WHERE id = $1")
+                .bind(asset.id)
+                .execute(pool)
+                .await
+                .map_err(|e| format!("Failed to record library use: {}", e))?;
+            paths.insert(name, asset.file_path);
+        }
+
+        Ok(Self::replace_references(args, &paths))
+    }
+
+    /// Whether any string in the arguments is a library reference
+    pub fn has_references(args: &Value) -> bool {
+        let mut names = Vec::new();
+        Self::collect_references(args, &mut names);
+        !names.is_empty()
+    }
+
+    fn collect_references(value: &Value, names: &mut Vec<String>) {
+        match value {
+            Value::String(s) => {
+                if let Some(name) = s.strip_prefix(LIBRARY_REFERENCE_PREFIX) {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| Self::collect_references(v, names)),
+            Value::Object(map) => map.values().for_each(|v| Self::collect_references(v, names)),
+            _ => {}
+        }
+    }
+
+    fn replace_references(value: &Value, paths: &HashMap<String, String>) -> Value {
+        match value {
+            Value::String(s) => s
+                .strip_prefix(LIBRARY_REFERENCE_PREFIX)
+                .and_then(|name| paths.get(name))
+                .map(|path| Value::String(path.clone()))
+                .unwrap_or_else(|| value.clone()),
+            Value::Array(items) => Value::Array(items.iter().map(|v| Self::replace_references(v, paths)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter().map(|(k, v)| (k.clone(), Self::replace_references(v, paths))).collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
+
+    /// Context block listing the user's library for the agent
+    pub async fn build_library_context(pool: &PgPool, user_id: i32) -> Result<String, sqlx::Error> {
+        let assets = Self::search(pool, user_id, &LibrarySearchQuery::default()).await?;
+        if assets.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut context = String::from("USER ASSET LIBRARY (available in every session):\n");
+        for asset in assets.iter().take(LIBRARY_CONTEXT_LIMIT) {
+            context.push_str(&format!("- {} ({})", asset.reference(), asset.kind));
+            if !asset.tags.is_empty() {
+                context.push_str(&format!(" [{}]", asset.tags.join(", ")));
+            }
+            if let Some(description) = &asset.description {
+                context.push_str(&format!(" - {}", description));
+            }
+            context.push('\n');
+        }
+        if assets.len() > LIBRARY_CONTEXT_LIMIT {
+            context.push_str(&format!("...and {} more (use search_library to find them)\n", assets.len() - LIBRARY_CONTEXT_LIMIT));
+        }
+        context.push_str("Pass a library reference like \"library:<name>\" as any file argument and it resolves to the asset's path.\n\n");
+        Ok(context)
+    }
+
+    fn validate_name(name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err("Asset name must be 1-100 characters".to_string());
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.')) {
+            return Err("Asset names may only contain letters, numbers, spaces, '-', '_' and '.'".to_string());
+        }
+        Ok(name.to_string())
+    }
+
+    fn validate_kind(kind: &str) -> Result<String, String> {
+        if ASSET_KINDS.contains(&kind) {
+            Ok(kind.to_string())
+        } else {
+            Err(format!("Unknown asset kind '{}'. Use one of: {}", kind, ASSET_KINDS.join(", ")))
+        }
+    }
+
+    fn normalize_tags(tags: &[String]) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()) {
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+
+    fn detect_kind(extension: &str) -> &'static str {
+        match extension {
+            "png" | "jpg" | "jpeg" | "webp" | "gif" | "svg" => "image",
+            "mp3" | "wav" | "m4a" | "aac" | "ogg" | "flac" => "music",
+            "mp4" | "mov" | "webm" | "mkv" | "avi" => "clip",
+            _ => "other",
+        }
+    }
+
+    fn mime_type(extension: &str) -> Option<&'static str> {
+        Some(match extension {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "webp" => "image/webp",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            "mp3" => "audio/mpeg",
+            "wav" => "audio/wav",
+            "m4a" | "aac" => "audio/aac",
+            "ogg" => "audio/ogg",
+            "flac" => "audio/flac",
+            "mp4" => "video/mp4",
+            "mov" => "video/quicktime",
+            "webm" => "video/webm",
+            "mkv" => "video/x-matroska",
+            "avi" => "video/x-msvideo",
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,240 @@
+// Hybrid memory retrieval for the agents
+// Chat memories live in Qdrant for vector search and are mirrored in chat_memory_entries for keyword
+// search. Vector similarity finds paraphrases but blurs exact terms like "clip_03.mp4" or "00:01:30";
+// Postgres full-text and substring matching catch those. The two rankings are merged with
+// reciprocal-rank fusion, so a memory near the top of either list (or both) makes the context.
+
+use crate::qdrant_client::ChatMemoryDocument;
+use crate::services::embedding_index::EmbeddingIndexService;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+/// Rank damping of reciprocal-rank fusion; 60 is the usual choice and keeps one list's top hit from
+/// drowning out a memory both lists agree on
+const RRF_K: f64 = 60.0;
+
+/// Candidates taken from each search before fusing
+const CANDIDATES_PER_SEARCH: u32 = 10;
+
+/// Fused memories put in the context
+const RELATED_MEMORIES: usize = 5;
+
+/// Recent memories put in the context ahead of the related ones
+const RECENT_MEMORIES: u32 = 5;
+
+/// Exact terms matched per query
+const MAX_EXACT_TERMS: usize = 8;
+
+pub struct MemorySearchService;
+
+impl MemorySearchService {
+    /// Mirror a memory just stored in Qdrant so keyword search can find it. Failures are only
+    /// logged; the memory is still found by vector search.
+    pub async fn record(pool: &PgPool, memory_id: &str, session_uuid: &str, user_message: &str, agent_response: &str) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO chat_memory_entries (id, session_uuid, user_message, agent_response)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(memory_id)
+        .bind(session_uuid)
+        .bind(user_message)
+        .bind(agent_response)
+        .execute(pool)
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to index chat memory {} for keyword search: {}", memory_id, e);
+        }
+    }
+
+    /// Memories of a session matching the query's words or its exact terms, best first. Exact term
+    /// hits rank ahead of full-text relevance.
+    pub async fn keyword_search(
+        pool: &PgPool,
+        session_uuid: &str,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<ChatMemoryDocument>, sqlx::Error> {
+        let terms: Vec<String> = exact_terms(query).iter().map(|term| like_pattern(term)).collect();
+        let rows: Vec<(String, String, String, String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT id, session_uuid, user_message, agent_response, created_at
+            FROM (
+                SELECT m.*,
+                       (SELECT COUNT(*) FROM unnest($3::text[]) AS term
+                        WHERE m.user_message ILIKE term OR m.agent_response ILIKE term) AS exact_hits,
+                       ts_rank(m.search, websearch_to_tsquery('simple', $2)) AS text_rank
+                FROM chat_memory_entries m
+                WHERE m.session_uuid = $1
+            ) ranked
+            WHERE exact_hits > 0 OR search @@ websearch_to_tsquery('simple', $2)
+            ORDER BY exact_hits DESC, text_rank DESC, created_at DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(session_uuid)
+        .bind(query)
+        .bind(&terms)
+        .bind(limit as i64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, session_id, user_message, agent_response, timestamp)| ChatMemoryDocument {
+                id,
+                session_id,
+                user_id: None,
+                timestamp,
+                user_message,
+                agent_response,
+                context: HashMap::new(),
+                files_referenced: Vec::new(),
+            })
+            .collect())
+    }
+
+    /// Memories of a session related to the query: vector and keyword results fused. Either search
+    /// failing or being unavailable leaves the other's results.
+    pub async fn search(state: &AppState, query: &str, session_uuid: &str, limit: usize) -> Vec<ChatMemoryDocument> {
+        let mut rankings = Vec::new();
+
+        if let Some(ref qdrant_client) = state.qdrant_client {
+            let similar = match EmbeddingIndexService::embed(state, query).await {
+                Ok(embedding) => qdrant_client
+                    .search_similar_conversations(embedding, session_uuid, CANDIDATES_PER_SEARCH)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            match similar {
+                Ok(documents) => rankings.push(documents),
+                Err(e) => tracing::warn!("Vector memory search failed for session {}: {}", session_uuid, e),
+            }
+        }
+
+        match Self::keyword_search(&state.db_pool, session_uuid, query, CANDIDATES_PER_SEARCH).await {
+            Ok(documents) => rankings.push(documents),
+            Err(e) => tracing::warn!("Keyword memory search failed for session {}: {}", session_uuid, e),
+        }
+
+        let mut fused = reciprocal_rank_fusion(rankings);
+        fused.truncate(limit);
+        fused
+    }
+
+    /// Context for the agent: the session's recent memories, then related ones from hybrid search
+    pub async fn build_context(state: &AppState, query: &str, session_uuid: &str) -> Result<String, String> {
+        let recent_history = match state.qdrant_client {
+            Some(ref qdrant_client) => qdrant_client
+                .get_session_history(session_uuid, RECENT_MEMORIES)
+                .await
+                .map_err(|e| e.to_string())?,
+            None => Vec::new(),
+        };
+
+        let related: Vec<ChatMemoryDocument> = Self::search(state, query, session_uuid, RELATED_MEMORIES + recent_history.len())
+            .await
+            .into_iter()
+            .filter(|memory| !recent_history.iter().any(|recent| recent.id == memory.id))
+            .take(RELATED_MEMORIES)
+            .collect();
+
+        let mut context = String::new();
+        if !recent_history.is_empty() {
+            context.push_str("Recent conversation history:\n");
+            for memory in recent_history.iter().rev() {
+                context.push_str(&format!("User: {}\nAssistant: {}\n\n", memory.user_message, memory.agent_response));
+            }
+        }
+        if !related.is_empty() {
+            context.push_str("Related past conversations:\n");
+            for memory in &related {
+                context.push_str(&format!("User: {}\nAssistant: {}\n\n", memory.user_message, memory.agent_response));
+            }
+        }
+        Ok(context)
+    }
+}
+
+/// Merge rankings of the same memories: each scores 1 / (RRF_K + rank) in every list it appears in,
+/// summed. Ties keep the order they were first seen in.
+pub fn reciprocal_rank_fusion(rankings: Vec<Vec<ChatMemoryDocument>>) -> Vec<ChatMemoryDocument> {
+    let mut fused: Vec<(f64, ChatMemoryDocument)> = Vec::new();
+    for ranking in rankings {
+        for (rank, document) in ranking.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f64 + 1.0);
+            match fused.iter_mut().find(|(_, seen)| seen.id == document.id) {
+                Some((total, _)) => *total += score,
+                None => fused.push((score, document)),
+            }
+        }
+    }
+    fused.sort_by(|a, b| b.0.total_cmp(&a.0));
+    fused.into_iter().map(|(_, document)| document).collect()
+}
+
+/// Words of a query that should match literally: filenames, paths, timestamps and codes mixing
+/// letters and digits
+fn exact_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query.split_whitespace() {
+        let term = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let separated = term.contains(['.', '_', '/', '\\', ':', '-']);
+        let mixed = term.chars().any(|c| c.is_ascii_digit()) && term.chars().any(char::is_alphabetic);
+        if term.chars().count() >= 3 && (separated || mixed) && !terms.iter().any(|seen| seen.eq_ignore_ascii_case(term)) {
+            terms.push(term.to_string());
+        }
+    }
+    terms.truncate(MAX_EXACT_TERMS);
+    terms
+}
+
+/// An ILIKE pattern matching the term anywhere, with LIKE wildcards in it taken literally
+fn like_pattern(term: &str) -> String {
+    let escaped = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(id: &str) -> ChatMemoryDocument {
+        ChatMemoryDocument {
+            id: id.to_string(),
+            session_id: "session".to_string(),
+            user_id: None,
+            timestamp: Utc::now(),
+            user_message: String::new(),
+            agent_response: String::new(),
+            context: HashMap::new(),
+            files_referenced: Vec::new(),
+        }
+    }
+
+    fn ids(documents: &[ChatMemoryDocument]) -> Vec<&str> {
+        documents.iter().map(|d| d.id.as_str()).collect()
+    }
+
+    #[test]
+    fn fusion_favours_memories_both_searches_found() {
+        let vector = vec![memory("a"), memory("b"), memory("c")];
+        let keyword = vec![memory("filename_hit"), memory("c")];
+        let fused = reciprocal_rank_fusion(vec![vector, keyword]);
+        // "c" is found by both; the keyword-only hit ties with the vector's top one and keeps its place
+        assert_eq!(ids(&fused), ["c", "a", "filename_hit", "b"]);
+        assert!(reciprocal_rank_fusion(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn exact_terms_pick_out_filenames_and_codes() {
+        let terms = exact_terms("what did we do with \"clip_03.mp4\" at 00:01:30, in v2 of the intro? (outputs/final.mp4)");
+        assert_eq!(terms, ["clip_03.mp4", "00:01:30", "outputs/final.mp4"]);
+        assert_eq!(like_pattern("clip_03%.mp4"), "%clip\\_03\\%.mp4%");
+    }
+}
@@ -3,7 +3,87 @@ pub mod output_video;
 pub mod video_vectorization;
 pub mod token_pricing;
 pub mod token_usage;
+pub mod delivery;
+pub mod review;
+pub mod library;
+pub mod asset_tagging;
+pub mod upload_dedup;
+pub mod ingest;
+pub mod notification;
+pub mod token_health;
+pub mod token_budget;
+pub mod video_qa;
+pub mod chaptering;
+pub mod music_selection;
+pub mod export_preset;
+pub mod render_report;
+pub mod render_snapshot;
+pub mod embed;
+pub mod output_stats;
+pub mod session_defaults;
+pub mod render_estimate;
+pub mod feature_flag;
+pub mod vector_migration;
+pub mod trash;
+pub mod session_bulk;
+pub mod output_preview;
+pub mod file_discovery;
+pub mod video_summary;
+pub mod webhook;
+pub mod output_metadata;
+pub mod upload_defaults;
+pub mod download_link;
+pub mod rendition;
+pub mod idempotency;
+pub mod workflow_template;
+pub mod memory_search;
+pub mod embedding_index;
+pub mod project;
+pub mod storage_tier;
+pub mod edit_history;
+pub mod calendar;
+pub mod activity;
 
 pub use output_video::OutputVideoService;
 pub use video_vectorization::VideoVectorizationService;
-pub use token_usage::TokenUsageService;
\ No newline at end of file
+pub use token_usage::TokenUsageService;
+pub use delivery::DeliveryService;
+pub use review::ReviewService;
+pub use library::LibraryService;
+pub use asset_tagging::AssetTaggingService;
+pub use upload_dedup::UploadDedupService;
+pub use ingest::IngestService;
+pub use notification::NotificationService;
+pub use token_health::TokenHealthService;
+pub use token_budget::TokenBudgetService;
+pub use video_qa::VideoQaService;
+pub use chaptering::ChapteringService;
+pub use music_selection::MusicSelectionService;
+pub use export_preset::ExportPresetService;
+pub use render_report::RenderReportService;
+pub use render_snapshot::RenderSnapshotService;
+pub use embed::EmbedService;
+pub use output_stats::OutputStatsService;
+pub use session_defaults::SessionDefaultsService;
+pub use render_estimate::RenderEstimateService;
+pub use feature_flag::FeatureFlagService;
+pub use vector_migration::VectorMigrationService;
+pub use trash::TrashService;
+pub use session_bulk::SessionBulkService;
+pub use output_preview::OutputPreviewService;
+pub use file_discovery::FileDiscoveryService;
+pub use video_summary::VideoSummaryService;
+pub use webhook::WebhookService;
+pub use output_metadata::OutputMetadataService;
+pub use upload_defaults::UploadDefaultsService;
+pub use download_link::DownloadLinkService;
+pub use rendition::RenditionService;
+pub use idempotency::IdempotencyService;
+pub use workflow_template::WorkflowTemplateService;
+pub use memory_search::MemorySearchService;
+pub use embedding_index::EmbeddingIndexService;
+pub use project::ProjectService;
+pub use storage_tier::StorageTierService;
+pub use edit_history::EditHistoryService;
+pub use calendar::CalendarService;
+pub use activity::ActivityService;
\ No newline at end of file
@@ -0,0 +1,161 @@
+// src/services/music_selection.rs
+// Mood-matched background music: the transcript is read for mood, then a track is picked from the
+// user's library (kind "music") or generated with Eleven Music at the video's length
+use crate::models::library::{LibraryAsset, LibrarySearchQuery};
+use crate::services::LibraryService;
+use crate::types::TranscriptWord;
+use crate::AppState;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Words of transcript shown to the model when judging mood
+const TRANSCRIPT_EXCERPT_WORDS: usize = 600;
+/// Eleven Music limits; longer videos loop the generated bed
+const MIN_GENERATED_SECONDS: f64 = 10.0;
+const MAX_GENERATED_SECONDS: f64 = 300.0;
+const GENERATION_TIMEOUT_SECONDS: u64 = 180;
+
+/// Mood keywords used when no model is available to read the transcript
+const MOOD_KEYWORDS: [(&str, &[&str]); 6] = [
+    ("upbeat", &["excited", "amazing", "awesome", "love", "fun", "let's", "great", "happy", "celebrate"]),
+    ("calm", &["relax", "calm", "peace", "breathe", "gentle", "slow", "quiet", "meditation"]),
+    ("inspiring", &["dream", "achieve", "believe", "journey", "success", "future", "change", "goal"]),
+    ("dramatic", &["never", "danger", "fight", "fear", "crisis", "secret", "shocking", "war"]),
+    ("sad", &["miss", "loss", "lost", "sorry", "grief", "alone", "goodbye", "died"]),
+    ("corporate", &["product", "team", "business", "customers", "solution", "platform", "growth", "market"]),
+];
+
+/// What the video sounds like and the music that would suit it
+#[derive(Debug, Clone, Deserialize)]
+pub struct MusicMood {
+    pub mood: String,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub tempo: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Eleven Music prompt describing an instrumental bed
+    #[serde(default)]
+    pub music_prompt: String,
+}
+
+pub struct MusicSelectionService;
+
+impl MusicSelectionService {
+    /// Judge the mood from the transcript (or a user-supplied mood), falling back to keywords
+    pub async fn detect_mood(state: &Arc<AppState>, words: &[TranscriptWord], requested_mood: Option<&str>) -> MusicMood {
+        if let Some(mood) = requested_mood.map(str::trim).filter(|m| !m.is_empty()) {
+            return Self::mood_from_label(mood);
+        }
+        if words.is_empty() {
+            return Self::mood_from_label("ambient");
+        }
+
+        if let Some(claude) = state.claude_client.as_ref() {
+            match Self::ask_for_mood(claude, words).await {
+                Ok(mood) => return mood,
+                Err(e) => tracing::warn!("Mood detection failed, using keywords: {}", e),
+            }
+        }
+        Self::mood_from_keywords(words)
+    }
+
+    /// Best music asset in the user's library for this mood, if any
+    pub async fn pick_from_library(
+        state: &Arc<AppState>,
+        user_id: i32,
+        mood: &MusicMood,
+    ) -> Result<Option<LibraryAsset>, String> {
+        let mut terms = vec![mood.mood.clone()];
+        terms.extend(mood.genre.clone());
+        terms.extend(mood.tempo.clone());
+        terms.extend(mood.keywords.iter().cloned());
+        let query = LibrarySearchQuery {
+            q: Some(terms.join(" ")),
+            kind: Some("music".to_string()),
+            ..Default::default()
+        };
+        let assets = LibraryService::search_with_semantics(state, user_id, &query)
+            .await
+            .map_err(|e| format!("Failed to search music library: {}", e))?;
+        Ok(assets.into_iter().find(|a| std::path::Path::new(&a.file_path).is_file()))
+    }
+
+    /// Generate an instrumental bed with Eleven Music, as long as the video allows
+    pub async fn generate(state: &Arc<AppState>, mood: &MusicMood, duration: f64, output_file: &str) -> Result<(), String> {
+        let elevenlabs = state.elevenlabs_client.as_ref()
+            .ok_or("Eleven Labs client not available. Set ELEVEN_LABS_API_KEY to enable music generation.")?;
+        let seconds = duration.ceil().clamp(MIN_GENERATED_SECONDS, MAX_GENERATED_SECONDS);
+        let bytes = elevenlabs
+            .generate_music(&mood.music_prompt, (seconds * 1000.0) as u32, GENERATION_TIMEOUT_SECONDS)
+            .await
+            .map_err(|e| format!("Music generation failed: {}", e))?;
+        tokio::fs::write(output_file, &bytes)
+            .await
+            .map_err(|e| format!("Failed to save generated music: {}", e))
+    }
+
+    async fn ask_for_mood(claude: &crate::claude_client::ClaudeClient, words: &[TranscriptWord]) -> Result<MusicMood, String> {
+        let excerpt = words
+            .iter()
+            .take(TRANSCRIPT_EXCERPT_WORDS)
+            .map(|w| w.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let prompt = format!(
+            "This is the transcript of a video that needs background music. Judge its overall mood and suggest \
+             music that would sit under the speech without competing with it.\n\n\
+             Respond with ONLY a JSON object: {{\"mood\": \"one word, e.g. upbeat, calm, inspiring, dramatic, sad, corporate\", \
+             \"genre\": \"...\", \"tempo\": \"slow|medium|fast\", \"keywords\": [\"3-5 lowercase words\"], \
+             \"music_prompt\": \"one-sentence description of an instrumental track with no vocals\"}}\n\nTranscript:\n{}",
+            excerpt
+        );
+
+        let response = claude.generate_text(&prompt).await?;
+        let start = response.find('{').ok_or("No JSON object in mood analysis")?;
+        let end = response.rfind('}').ok_or("No JSON object in mood analysis")?;
+        let mut mood: MusicMood = serde_json::from_str(response.get(start..=end).unwrap_or("{}"))
+            .map_err(|e| format!("Failed to parse mood analysis: {}", e))?;
+        mood.mood = mood.mood.trim().to_lowercase();
+        if mood.music_prompt.trim().is_empty() {
+            mood.music_prompt = Self::mood_from_label(&mood.mood).music_prompt;
+        }
+        Ok(mood)
+    }
+
+    fn mood_from_keywords(words: &[TranscriptWord]) -> MusicMood {
+        let text: Vec<String> = words
+            .iter()
+            .map(|w| w.text.chars().filter(|c| c.is_alphanumeric() || *c == '\'').collect::<String>().to_lowercase())
+            .collect();
+        let best = MOOD_KEYWORDS
+            .iter()
+            .map(|(mood, keywords)| (*mood, text.iter().filter(|w| keywords.contains(&w.as_str())).count()))
+            .filter(|(_, hits)| *hits > 0)
+            .max_by_key(|(_, hits)| *hits)
+            .map(|(mood, _)| mood)
+            .unwrap_or("ambient");
+        Self::mood_from_label(best)
+    }
+
+    fn mood_from_label(mood: &str) -> MusicMood {
+        let mood = mood.trim().to_lowercase();
+        let (genre, tempo) = match mood.as_str() {
+            "upbeat" | "happy" | "energetic" => ("pop", "fast"),
+            "calm" | "relaxed" | "peaceful" => ("lo-fi", "slow"),
+            "inspiring" | "uplifting" => ("cinematic", "medium"),
+            "dramatic" | "tense" | "epic" => ("orchestral", "medium"),
+            "sad" | "melancholic" => ("piano", "slow"),
+            "corporate" => ("corporate", "medium"),
+            _ => ("ambient", "slow"),
+        };
+        MusicMood {
+            music_prompt: format!("{} {} instrumental background track, {} tempo, no vocals, suitable under speech", mood, genre, tempo),
+            genre: Some(genre.to_string()),
+            tempo: Some(tempo.to_string()),
+            keywords: vec![genre.to_string()],
+            mood,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+// src/services/notification.rs
+//! In-app notification center: things that need a user's attention (finished jobs, clips waiting to
+//! be posted, token budget warnings, YouTube connection problems), kept until they're read and pushed
+//! live to any chat the user has open.
+use crate::jobs::{Job, JobStatus};
+use crate::models::notification::Notification;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::mpsc;
+
+/// Most notifications returned by one list request
+pub const MAX_PAGE: i64 = 100;
+
+/// Job types that report through a parent job, so they don't notify on their own
+const QUIET_JOB_TYPES: [&str; 1] = ["batch_render_row"];
+
+type Subscribers = HashMap<i32, Vec<mpsc::UnboundedSender<Notification>>>;
+
+fn subscribers() -> &'static Mutex<Subscribers> {
+    static SUBSCRIBERS: OnceLock<Mutex<Subscribers>> = OnceLock::new();
+    SUBSCRIBERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub struct NotificationService;
+
+impl NotificationService {
+    /// Store a notification for a user and push it to their open chats
+    pub async fn notify(
+        pool: &PgPool,
+        user_id: i32,
+        kind: &str,
+        title: &str,
+        message: &str,
+        data: Option<Value>,
+    ) -> Result<Notification, sqlx::Error> {
+        let notification = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (user_id, kind, title, message, data)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(title)
+        .bind(message)
+        .bind(data)
+        .fetch_one(pool)
+        .await?;
+        Self::publish(&notification);
+        Ok(notification)
+    }
+
+    /// Like `notify`, but only the first time for `dedupe_key`; None when it was already sent
+    pub async fn notify_once(
+        pool: &PgPool,
+        user_id: i32,
+        dedupe_key: &str,
+        kind: &str,
+        title: &str,
+        message: &str,
+        data: Option<Value>,
+    ) -> Result<Option<Notification>, sqlx::Error> {
+        let notification = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (user_id, kind, title, message, data, dedupe_key)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id, dedupe_key) WHERE dedupe_key IS NOT NULL DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(title)
+        .bind(message)
+        .bind(data)
+        .bind(dedupe_key)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(ref notification) = notification {
+            Self::publish(notification);
+        }
+        Ok(notification)
+    }
+
+    /// A user's notifications, newest first; `before` pages back from a notification id
+    pub async fn list(
+        pool: &PgPool,
+        user_id: i32,
+        unread_only: bool,
+        before: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, sqlx::Error> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT * FROM notifications
+            WHERE user_id = $1 AND ($2 = FALSE OR read_at IS NULL) AND ($3::INTEGER IS NULL OR id < $3)
+            ORDER BY id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(before)
+        .bind(limit.clamp(1, MAX_PAGE))
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn unread_count(pool: &PgPool, user_id: i32) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Mark one notification read or unread; None when the user has no such notification
+    pub async fn set_read(pool: &PgPool, user_id: i32, id: i32, read: bool) -> Result<Option<Notification>, sqlx::Error> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            UPDATE notifications SET read_at = CASE WHEN $3 THEN COALESCE(read_at, NOW()) ELSE NULL END
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(read)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Mark everything read; returns how many were unread
+    pub async fn mark_all_read(pool: &PgPool, user_id: i32) -> Result<u64, sqlx::Error> {
+        sqlx::query("UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(|result| result.rows_affected())
+    }
+
+    /// Live notifications for one of the user's open chats; dropping the receiver unsubscribes
+    pub fn subscribe(user_id: i32) -> mpsc::UnboundedReceiver<Notification> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut subscribers = subscribers().lock().unwrap_or_else(|e| e.into_inner());
+        let senders = subscribers.entry(user_id).or_default();
+        senders.retain(|sender| !sender.is_closed());
+        senders.push(tx);
+        rx
+    }
+
+    fn publish(notification: &Notification) {
+        let mut subscribers = subscribers().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(senders) = subscribers.get_mut(&notification.user_id) {
+            senders.retain(|sender| sender.send(notification.clone()).is_ok());
+            if senders.is_empty() {
+                subscribers.remove(&notification.user_id);
+            }
+        }
+    }
+
+    /// Tell the job's owner it completed or failed, so they find out even if they weren't watching the chat
+    pub fn job_finished(pool: PgPool, job: Job) {
+        let Some((kind, title, message)) = describe_job(&job) else { return };
+        let Some(user_id) = job.user_id.as_deref().and_then(|id| id.parse::<i32>().ok()) else {
+            return;
+        };
+
+        tokio::spawn(async move {
+            let output_files = match &job.status {
+                JobStatus::Completed { output_files, .. } => output_files.clone(),
+                _ => Vec::new(),
+            };
+            let data = json!({
+                "job_id": job.id,
+                "job_type": job.job_type,
+                "session_id": job.session_id,
+                "output_files": output_files,
+            });
+            if let Err(e) = Self::notify(&pool, user_id, kind, &title, &message, Some(data)).await {
+                tracing::warn!("Failed to notify user {} about job {}: {}", user_id, job.id, e);
+            }
+        });
+    }
+}
+
+/// Kind, title and message for a finished job; None for jobs that don't notify
+fn describe_job(job: &Job) -> Option<(&'static str, String, String)> {
+    if QUIET_JOB_TYPES.contains(&job.job_type.as_str()) {
+        return None;
+    }
+    let name = job.job_type.replace('_', " ");
+    match &job.status {
+        JobStatus::Completed { result, output_files, .. } => {
+            let files = match output_files.len() {
+                0 => String::new(),
+                1 => format!(" Output: {}", output_files[0]),
+                count => format!(" {} output files.", count),
+            };
+            Some(("job_completed", format!("Your {} job finished", name), format!("{}.{}", result.trim_end_matches('.'), files)))
+        }
+        JobStatus::Failed { error, .. } => Some(("job_failed", format!("Your {} job failed", name), error.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finished_jobs_notify_unless_a_parent_reports_for_them() {
+        let mut job = Job::new("session".to_string(), "batch_process".to_string(), json!({}));
+        job.status = JobStatus::Completed {
+            result: "3 succeeded, 0 failed, 0 skipped".to_string(),
+            output_files: vec!["a.mp4".to_string(), "b.mp4".to_string()],
+            duration_seconds: 1.0,
+        };
+        let (kind, title, message) = describe_job(&job).unwrap();
+        assert_eq!(kind, "job_completed");
+        assert_eq!(title, "Your batch process job finished");
+        assert_eq!(message, "3 succeeded, 0 failed, 0 skipped. 2 output files.");
+
+        job.status = JobStatus::Failed { error: "FFmpeg exited with 1".to_string(), failed_at_step: "render".to_string() };
+        assert_eq!(describe_job(&job).unwrap().0, "job_failed");
+
+        job.job_type = "batch_render_row".to_string();
+        assert!(describe_job(&job).is_none());
+    }
+}
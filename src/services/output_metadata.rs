@@ -0,0 +1,169 @@
+// src/services/output_metadata.rs
+// Title, description and tags for each output, written from its content once it has rendered
+// (the visual summary from vectorization, plus what produced it). Stored on the output record, they
+// pre-fill YouTube uploads and are what the outputs library search matches against.
+use crate::models::file::OutputVideo;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// YouTube's limits: 100 characters of title, 5000 of description and 500 of tags in total
+const MAX_TITLE_CHARS: usize = 100;
+const MAX_DESCRIPTION_CHARS: usize = 5000;
+const MAX_TAG_CHARS_TOTAL: usize = 500;
+const MAX_TAGS: usize = 15;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OutputMetadata {
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+pub struct OutputMetadataService;
+
+impl OutputMetadataService {
+    /// Write and store metadata for a freshly rendered output. `content_summary` is what the video
+    /// shows (from vectorization), when known.
+    pub async fn generate_for_output(state: &Arc<AppState>, output: &OutputVideo, content_summary: Option<&str>) -> Result<OutputMetadata, String> {
+        let duration = output.duration_seconds.map(|d| format!("{:.0} seconds", d)).unwrap_or_else(|| "unknown".to_string());
+        let resolution = match (output.width, output.height) {
+            (Some(w), Some(h)) => format!("{}x{}", w, h),
+            _ => "unknown".to_string(),
+        };
+        let prompt = format!(
+            "Write YouTube metadata for a video. File name: {}. Duration: {}. Resolution: {}. Made with: {}.\n\
+             What the video shows: {}\n\n\
+             Respond with ONLY a JSON object: {{\"title\": \"catchy title, max 70 characters\", \
+             \"description\": \"2-4 sentence description of the content\", \"tags\": [\"5-12 lowercase search tags\"]}}",
+            output.file_name,
+            duration,
+            resolution,
+            output.operation_type,
+            content_summary.filter(|s| !s.trim().is_empty()).unwrap_or("(no visual analysis available - go by the file name)"),
+        );
+
+        let response = generate_text(state, &prompt).await?;
+        let start = response.find('{').ok_or("No JSON object in metadata response")?;
+        let end = response.rfind('}').ok_or("No JSON object in metadata response")?;
+        let raw: OutputMetadata = serde_json::from_str(&response[start..=end]).map_err(|e| format!("Failed to parse metadata: {}", e))?;
+        let metadata = sanitize(raw);
+        if metadata.title.is_empty() {
+            return Err("Generated metadata has no title".to_string());
+        }
+
+        Self::store(&state.db_pool, output.id, &metadata).await.map_err(|e| format!("Failed to save metadata: {}", e))?;
+        Ok(metadata)
+    }
+
+    pub async fn store(pool: &PgPool, output_id: i32, metadata: &OutputMetadata) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE output_videos
+            SET suggested_title = $2, description = $3, tags = $4, metadata_generated_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(output_id)
+        .bind(&metadata.title)
+        .bind(&metadata.description)
+        .bind(&metadata.tags)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The user's outputs whose title, description, tags or file name match every word of `query`, newest first
+    pub async fn search(pool: &PgPool, user_id: i32, query: &str, limit: i64) -> Result<Vec<OutputVideo>, sqlx::Error> {
+        let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+        sqlx::query_as::<_, OutputVideo>(
+            r#"
+            SELECT * FROM output_videos ov
+            WHERE ov.user_id = $1 AND ov.deleted_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM unnest($2::TEXT[]) term
+                  WHERE concat_ws(' ', ov.file_name, ov.suggested_title, ov.description, array_to_string(ov.tags, ' '))
+                        NOT ILIKE '%' || term || '%'
+              )
+            ORDER BY ov.created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(&terms)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// Claude when configured, otherwise Gemini
+async fn generate_text(state: &AppState, prompt: &str) -> Result<String, String> {
+    if let Some(claude) = state.claude_client.as_ref() {
+        return claude.generate_text(prompt).await;
+    }
+    let gemini = state.gemini_client.as_ref().ok_or("No AI client available for output metadata")?;
+    let request = crate::gemini_client::GenerateContentRequest {
+        contents: vec![crate::gemini_client::Content {
+            role: Some("user".to_string()),
+            parts: vec![crate::gemini_client::Part::Text { text: prompt.to_string() }],
+        }],
+        tools: None,
+        generation_config: None,
+        tool_config: None,
+    };
+    let response = gemini.generate_content(request).await.map_err(|e| e.to_string())?;
+    response
+        .candidates
+        .first()
+        .and_then(|c| c.content.as_ref())
+        .and_then(|content| content.parts.first())
+        .and_then(|p| match p {
+            crate::gemini_client::Part::Text { text } => Some(text.clone()),
+            _ => None,
+        })
+        .ok_or_else(|| "No text in Gemini response".to_string())
+}
+
+/// Trim to YouTube's limits and drop empty or duplicate tags
+fn sanitize(raw: OutputMetadata) -> OutputMetadata {
+    let title: String = raw.title.trim().trim_matches('"').chars().take(MAX_TITLE_CHARS).collect();
+    let description: String = raw.description.trim().chars().take(MAX_DESCRIPTION_CHARS).collect();
+
+    let mut tags: Vec<String> = Vec::new();
+    let mut total_chars = 0;
+    for tag in raw.tags {
+        let tag = tag.trim().trim_start_matches('#').replace(['<', '>', ','], "").to_lowercase();
+        if tag.is_empty() || tags.contains(&tag) || tags.len() >= MAX_TAGS {
+            continue;
+        }
+        if total_chars + tag.chars().count() > MAX_TAG_CHARS_TOTAL {
+            break;
+        }
+        total_chars += tag.chars().count();
+        tags.push(tag);
+    }
+
+    OutputMetadata { title: title.trim().to_string(), description, tags }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_is_trimmed_to_youtube_limits() {
+        let metadata = sanitize(OutputMetadata {
+            title: format!("\"{}\"", "Sunset ".repeat(30)),
+            description: "  Golden hour over the bay.  ".to_string(),
+            tags: vec!["#Sunset".into(), "sunset".into(), " ".into(), "time<lapse>".into(), "x".repeat(600)],
+        });
+
+        assert!(metadata.title.chars().count() <= MAX_TITLE_CHARS);
+        assert!(metadata.title.starts_with("Sunset") && !metadata.title.ends_with(' '));
+        assert_eq!(metadata.description, "Golden hour over the bay.");
+        assert_eq!(metadata.tags, ["sunset", "timelapse"]);
+    }
+}
@@ -0,0 +1,207 @@
+// src/services/output_preview.rs
+//! Preview cards for outputs shown in chat: file id, links and a poster frame, so the client can
+//! render a card instead of a bare download link. Posters are small JPEG frames grabbed with FFmpeg
+//! the first time a card is built and cached under `outputs/posters/<file_id>.jpg`.
+use crate::handlers::output::{file_id_for_path, resolve_file_path};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const POSTER_DIR: &str = "outputs/posters";
+
+/// Poster width in pixels; the height keeps the aspect ratio
+const POSTER_WIDTH: u32 = 480;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "mkv", "webm", "avi", "m4v"];
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "aac", "m4a", "flac", "ogg"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputCard {
+    pub file_id: String,
+    pub file_name: String,
+    /// "video", "image" or "audio"
+    pub kind: &'static str,
+    pub size_bytes: u64,
+    pub download_url: String,
+    pub stream_url: String,
+    /// None for audio, or when no frame could be grabbed
+    pub poster_url: Option<String>,
+}
+
+pub struct OutputPreviewService;
+
+impl OutputPreviewService {
+    pub fn poster_path(file_id: &str) -> PathBuf {
+        Path::new(POSTER_DIR).join(format!("{}.jpg", file_id))
+    }
+
+    pub fn poster_url(file_id: &str) -> String {
+        format!("/api/outputs/poster/{}", file_id)
+    }
+
+    /// Cards for the outputs a chat message refers to: the given paths (e.g. a job's output files)
+    /// plus any output paths or download links in the text. Files that don't exist are skipped.
+    pub async fn cards_for_message(text: &str, paths: &[String]) -> Vec<OutputCard> {
+        let mut candidates: Vec<PathBuf> = paths.iter().chain(output_paths_in(text).iter()).map(PathBuf::from).collect();
+        for file_id in download_ids_in(text) {
+            let resolved = tokio::task::spawn_blocking(move || resolve_file_path(&file_id).ok()).await.ok().flatten();
+            candidates.extend(resolved);
+        }
+
+        let mut seen = HashSet::new();
+        let mut cards = Vec::new();
+        for path in candidates {
+            let file_id = file_id_for_path(&path);
+            if !seen.insert(file_id.clone()) {
+                continue;
+            }
+            if let Some(card) = Self::card(&path, &file_id).await {
+                cards.push(card);
+            }
+        }
+        cards
+    }
+
+    async fn card(path: &Path, file_id: &str) -> Option<OutputCard> {
+        let kind = media_kind(path)?;
+        let metadata = tokio::fs::metadata(path).await.ok().filter(|m| m.is_file())?;
+        let stream_url = format!("/api/outputs/stream/{}", file_id);
+
+        let poster_url = match kind {
+            "image" => Some(stream_url.clone()),
+            "video" => match Self::ensure_poster(path, file_id).await {
+                Ok(_) => Some(Self::poster_url(file_id)),
+                Err(e) => {
+                    tracing::warn!("No poster for {}: {}", path.display(), e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        Some(OutputCard {
+            file_id: file_id.to_string(),
+            file_name: path.file_name().and_then(|n| n.to_str()).unwrap_or("output").to_string(),
+            kind,
+            size_bytes: metadata.len(),
+            download_url: format!("/api/outputs/download/{}", file_id),
+            stream_url,
+            poster_url,
+        })
+    }
+
+    /// The cached poster for a video, grabbing a frame first if there isn't one yet (or the video
+    /// was re-rendered since)
+    pub async fn ensure_poster(video_path: &Path, file_id: &str) -> Result<PathBuf, String> {
+        let poster = Self::poster_path(file_id);
+        let video_modified = tokio::fs::metadata(video_path).await.and_then(|m| m.modified()).map_err(|e| e.to_string())?;
+        if let Ok(poster_modified) = tokio::fs::metadata(&poster).await.and_then(|m| m.modified()) {
+            if poster_modified >= video_modified {
+                return Ok(poster);
+            }
+        }
+
+        tokio::fs::create_dir_all(POSTER_DIR)
+            .await
+            .map_err(|e| format!("Failed to create poster directory: {}", e))?;
+
+        let input = video_path.to_string_lossy().to_string();
+        let output = poster.clone();
+        tokio::task::spawn_blocking(move || {
+            // A frame a little way in is more representative than a fade-in from black
+            let timestamp = crate::core::probe_media(&input).map(|info| (info.duration_seconds / 10.0).min(3.0)).unwrap_or(0.0);
+            let mut command = Command::new("ffmpeg");
+            command
+                .arg("-ss")
+                .arg(format!("{:.2}", timestamp))
+                .arg("-i")
+                .arg(&input)
+                .arg("-frames:v")
+                .arg("1")
+                .arg("-vf")
+                .arg(format!("scale={}:-2", POSTER_WIDTH))
+                .arg("-q:v")
+                .arg("4")
+                .arg("-y")
+                .arg(&output);
+            crate::utils::execute_ffmpeg_command(command)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        Ok(poster)
+    }
+
+    /// Where the poster of the output at `file_path` is cached (whether or not it exists)
+    pub fn poster_for_file(file_path: &str) -> PathBuf {
+        Self::poster_path(&file_id_for_path(Path::new(file_path)))
+    }
+}
+
+fn media_kind(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        Some("video")
+    } else if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Some("image")
+    } else if AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        Some("audio")
+    } else {
+        None
+    }
+}
+
+/// Words of a message split on whitespace and the markdown/link punctuation around paths
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '`' | '"' | '\'' | '|' | '(' | ')' | '[' | ']' | '<' | '>' | ','))
+        .map(|word| word.trim_end_matches(['.', ':', ';', '!', '?']))
+        .filter(|word| !word.is_empty())
+}
+
+/// Output file paths (`outputs/...` with a media extension) mentioned in a message
+fn output_paths_in(text: &str) -> Vec<String> {
+    words(text)
+        .filter(|word| word.trim_start_matches("./").starts_with("outputs/"))
+        .filter(|word| media_kind(Path::new(word)).is_some())
+        .map(str::to_string)
+        .collect()
+}
+
+/// File ids of `/api/outputs/download/<id>` and `/api/outputs/stream/<id>` links in a message
+fn download_ids_in(text: &str) -> Vec<String> {
+    words(text)
+        .filter_map(|word| {
+            word.strip_prefix("/api/outputs/download/")
+                .or_else(|| word.strip_prefix("/api/outputs/stream/"))
+        })
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_outputs_mentioned_in_a_message() {
+        let text = "✅ Trimmed the intro.\n\n**clip_trim.mp4**\nDownload: `/api/outputs/download/3fa9c1`\n\
+                    Stream: `/api/outputs/stream/3fa9c1`\nYouTube: `outputs/abc/clip_trim.mp4|clip_trim.mp4`\n\
+                    Saved the cover to ./outputs/cover.jpg. The notes are in outputs/notes.txt";
+
+        assert_eq!(output_paths_in(text), ["outputs/abc/clip_trim.mp4", "./outputs/cover.jpg"]);
+        assert_eq!(download_ids_in(text), ["3fa9c1", "3fa9c1"]);
+        assert!(output_paths_in("uploads/raw.mp4 and /api/outputs/download/").is_empty());
+        assert!(download_ids_in("/api/outputs/download/not-an-id").is_empty());
+    }
+
+    #[test]
+    fn only_media_files_get_cards() {
+        assert_eq!(media_kind(Path::new("outputs/a.MOV")), Some("video"));
+        assert_eq!(media_kind(Path::new("outputs/a.png")), Some("image"));
+        assert_eq!(media_kind(Path::new("outputs/a.mp3")), Some("audio"));
+        assert_eq!(media_kind(Path::new("outputs/a.srt")), None);
+    }
+}
@@ -0,0 +1,185 @@
+// src/services/output_stats.rs
+// Play/download counts and viewer geography per output. Country comes from the geo header set by
+// the CDN or proxy in front of the app; client IPs are never stored.
+use crate::services::OutputVideoService;
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+
+/// Geo headers set by common CDNs and proxies, in order of preference
+const COUNTRY_HEADERS: &[&str] = &["cf-ipcountry", "cloudfront-viewer-country", "x-vercel-ip-country", "x-country-code"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewEvent {
+    Stream,
+    Download,
+}
+
+impl ViewEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewEvent::Stream => "stream",
+            ViewEvent::Download => "download",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CountryCount {
+    pub country: String,
+    pub views: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct SourceCount {
+    pub source: String,
+    pub streams: i64,
+    pub downloads: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OutputStats {
+    pub output_id: i32,
+    pub streams: i64,
+    pub downloads: i64,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub countries: Vec<CountryCount>,
+    pub sources: Vec<SourceCount>,
+}
+
+/// One row of the dashboard summary
+#[derive(Debug, Serialize, FromRow)]
+pub struct OutputStatsSummary {
+    pub output_id: i32,
+    pub file_name: String,
+    pub tool_used: String,
+    pub streams: i64,
+    pub downloads: i64,
+    pub countries: i64,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct OutputStatsService;
+
+impl OutputStatsService {
+    /// Record an event without delaying the response; stats are best-effort
+    pub fn record(pool: &PgPool, output_id: i32, event: ViewEvent, source: &'static str, headers: &HeaderMap) {
+        if event == ViewEvent::Stream && !Self::is_new_playback(headers) {
+            return;
+        }
+        let pool = pool.clone();
+        let country = Self::viewer_country(headers);
+        tokio::spawn(async move {
+            if let Err(e) = sqlx::query(
+                "INSERT INTO output_view_events (output_id, event_type, source, country) VALUES ($1, $2, $3, $4)"
+            )
+            .bind(output_id)
+            .bind(event.as_str())
+            .bind(source)
+            .bind(&country)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!("Failed to record output {} event: {}", event.as_str(), e);
+            }
+        });
+    }
+
+    /// Record an event for whichever recorded output lives at `path`; untracked files are ignored
+    pub fn record_for_path(pool: &PgPool, path: &std::path::Path, event: ViewEvent, source: &'static str, headers: &HeaderMap) {
+        if event == ViewEvent::Stream && !Self::is_new_playback(headers) {
+            return;
+        }
+        let pool = pool.clone();
+        let path = path.to_string_lossy().to_string();
+        let headers = headers.clone();
+        tokio::spawn(async move {
+            if let Ok(Some(output)) = OutputVideoService::find_output_by_any_path(&pool, &path).await {
+                Self::record(&pool, output.id, event, source, &headers);
+            }
+        });
+    }
+
+    pub async fn get_stats(pool: &PgPool, output_id: i32) -> Result<OutputStats, sqlx::Error> {
+        let (streams, downloads, last_viewed_at): (i64, i64, Option<DateTime<Utc>>) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE event_type = 'stream'),
+                   COUNT(*) FILTER (WHERE event_type = 'download'),
+                   MAX(created_at)
+            FROM output_view_events WHERE output_id = $1
+            "#,
+        )
+        .bind(output_id)
+        .fetch_one(pool)
+        .await?;
+
+        let countries = sqlx::query_as::<_, CountryCount>(
+            r#"
+            SELECT COALESCE(country, '??') AS country, COUNT(*) AS views
+            FROM output_view_events WHERE output_id = $1
+            GROUP BY 1 ORDER BY views DESC, country
+            "#,
+        )
+        .bind(output_id)
+        .fetch_all(pool)
+        .await?;
+
+        let sources = sqlx::query_as::<_, SourceCount>(
+            r#"
+            SELECT source,
+                   COUNT(*) FILTER (WHERE event_type = 'stream') AS streams,
+                   COUNT(*) FILTER (WHERE event_type = 'download') AS downloads
+            FROM output_view_events WHERE output_id = $1
+            GROUP BY source ORDER BY source
+            "#,
+        )
+        .bind(output_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(OutputStats { output_id, streams, downloads, last_viewed_at, countries, sources })
+    }
+
+    /// Per-output totals for a user's dashboard, most consumed first
+    pub async fn user_summary(pool: &PgPool, user_id: i32, limit: i64) -> Result<Vec<OutputStatsSummary>, sqlx::Error> {
+        sqlx::query_as::<_, OutputStatsSummary>(
+            r#"
+            SELECT o.id AS output_id, o.file_name, o.tool_used,
+                   COUNT(e.id) FILTER (WHERE e.event_type = 'stream') AS streams,
+                   COUNT(e.id) FILTER (WHERE e.event_type = 'download') AS downloads,
+                   COUNT(DISTINCT e.country) AS countries,
+                   MAX(e.created_at) AS last_viewed_at,
+                   o.created_at
+            FROM output_videos o
+            LEFT JOIN output_view_events e ON e.output_id = o.id
+            WHERE o.user_id = $1 AND o.deleted_at IS NULL
+            GROUP BY o.id
+            ORDER BY COUNT(e.id) DESC, o.created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Players issue many range requests per playback; only the first one (no range, or from byte 0) counts
+    fn is_new_playback(headers: &HeaderMap) -> bool {
+        match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+            None => true,
+            Some(range) => range.trim().starts_with("bytes=0-"),
+        }
+    }
+
+    fn viewer_country(headers: &HeaderMap) -> Option<String> {
+        COUNTRY_HEADERS
+            .iter()
+            .filter_map(|name| headers.get(*name).and_then(|v| v.to_str().ok()))
+            .map(|v| v.trim().to_ascii_uppercase())
+            // Cloudflare sends XX for unknown and T1 for Tor
+            .find(|v| v.len() == 2 && v.chars().all(|c| c.is_ascii_uppercase()) && v != "XX")
+    }
+}
@@ -71,13 +71,13 @@ impl OutputVideoService {
         Ok(result)
     }
 
-    /// Get all output videos for a session
+    /// Get all output videos for a session, except those in the trash or undone
     pub async fn get_session_output_videos(
         pool: &PgPool,
         session_id: i32,
     ) -> Result<Vec<OutputVideo>, sqlx::Error> {
         sqlx::query_as::<_, OutputVideo>(
-            "SELECT * FROM output_videos WHERE session_id = $1 ORDER BY created_at DESC"
+            "SELECT * FROM output_videos WHERE session_id = $1 AND deleted_at IS NULL AND undone_at IS NULL ORDER BY created_at DESC"
         )
         .bind(session_id)
         .fetch_all(pool)
@@ -110,6 +110,116 @@ impl OutputVideoService {
         .await
     }
 
+    /// Path lookups tolerate the `outputs/` prefix being present or not, since tools normalize paths
+    pub fn path_candidates(path: &str) -> Vec<String> {
+        let bare = path.trim_start_matches("./").trim_start_matches("outputs/").to_string();
+        vec![path.to_string(), bare.clone(), format!("outputs/{}", bare)]
+    }
+
+    /// Find the recorded output a tool is reading from or writing to
+    pub async fn find_output_by_any_path(
+        pool: &PgPool,
+        path: &str,
+    ) -> Result<Option<OutputVideo>, sqlx::Error> {
+        sqlx::query_as::<_, OutputVideo>(
+            "SELECT * FROM output_videos WHERE file_path = ANY($1) ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(Self::path_candidates(path))
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Before a tool overwrites a recorded output, copy the current file aside as `<name>.v<N>.<ext>`
+    /// and point the existing row at the copy. Returns the archived row, which becomes the new render's parent.
+    pub async fn archive_previous_version(
+        pool: &PgPool,
+        output_path: &str,
+    ) -> Result<Option<OutputVideo>, String> {
+        let Some(previous) = Self::find_output_by_any_path(pool, output_path)
+            .await
+            .map_err(|e| format!("Failed to look up previous output: {}", e))?
+        else {
+            return Ok(None);
+        };
+
+        let Some(current) = Self::path_candidates(output_path).into_iter().find(|p| Path::new(p).is_file()) else {
+            return Ok(None);
+        };
+        let current = Path::new(&current);
+        let stem = current.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+        let ext = current.extension().and_then(|s| s.to_str()).unwrap_or("mp4");
+        let archived = current.with_file_name(format!("{}.v{}.{}", stem, previous.version, ext));
+        let archived = archived.to_string_lossy().to_string();
+
+        tokio::fs::copy(current, &archived)
+            .await
+            .map_err(|e| format!("Failed to archive {}: {}", current.display(), e))?;
+
+        // The render report is overwritten by the new render too, so it moves with the archived file
+        let archived_report = match previous.report_path.as_deref() {
+            Some(report) if Path::new(report).is_file() => {
+                let target = crate::services::RenderReportService::report_path(&archived);
+                tokio::fs::copy(report, &target).await.ok().map(|_| target)
+            }
+            _ => None,
+        };
+
+        sqlx::query_as::<_, OutputVideo>(
+            "UPDATE output_videos SET file_path = $2, file_name = $3, report_path = COALESCE($4, report_path), updated_at = NOW() WHERE id = $1 RETURNING *"
+        )
+        .bind(previous.id)
+        .bind(&archived)
+        .bind(Path::new(&archived).file_name().and_then(|n| n.to_str()).unwrap_or_default())
+        .bind(archived_report)
+        .fetch_one(pool)
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to record archived version: {}", e))
+    }
+
+    /// Attach a new output to its parent's lineage as the next version
+    pub async fn link_version(
+        pool: &PgPool,
+        output_id: i32,
+        parent: &OutputVideo,
+    ) -> Result<OutputVideo, sqlx::Error> {
+        let root_id = parent.root_output_id.unwrap_or(parent.id);
+        sqlx::query_as::<_, OutputVideo>(
+            r#"
+            UPDATE output_videos SET
+                parent_output_id = $2,
+                root_output_id = $3,
+                version = (SELECT COALESCE(MAX(version), 0) + 1 FROM output_videos WHERE id = $3 OR root_output_id = $3),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(output_id)
+        .bind(parent.id)
+        .bind(root_id)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Every version in the lineage of an output, oldest first
+    pub async fn get_version_history(
+        pool: &PgPool,
+        output_id: i32,
+    ) -> Result<Vec<OutputVideo>, sqlx::Error> {
+        sqlx::query_as::<_, OutputVideo>(
+            r#"
+            WITH target AS (SELECT COALESCE(root_output_id, id) AS root_id FROM output_videos WHERE id = $1)
+            SELECT ov.* FROM output_videos ov, target
+            WHERE ov.id = target.root_id OR ov.root_output_id = target.root_id
+            ORDER BY ov.version, ov.created_at
+            "#,
+        )
+        .bind(output_id)
+        .fetch_all(pool)
+        .await
+    }
+
     /// Determine MIME type based on file extension
     fn determine_mime_type(filename: &str) -> String {
         match filename.split('.').last().unwrap_or("").to_lowercase().as_str() {
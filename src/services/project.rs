@@ -0,0 +1,462 @@
+// src/services/project.rs
+// Projects: non-destructive editing. Instead of rendering every edit file-in/file-out, a project keeps
+// its source video and an ordered edit decision list of single-input tool operations. Every change
+// writes a new revision of the list, so undo and redo only move the project's revision pointer, and
+// nothing is rendered until export: the operations are chained from the source into a master for
+// the revision (reused by later exports of it), which is then encoded with the export preset asked for.
+use crate::models::project::{Project, ProjectExport, ProjectOperation, ProjectRevision};
+use crate::services::ExportPresetService;
+use crate::AppState;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::path::Path;
+
+/// Single-input tools an edit list can hold (they all take input_file/output_file)
+pub const PROJECT_TOOLS: &[&str] = &[
+    "trim_video", "add_text_overlay", "apply_filter", "add_overlay", "adjust_color", "add_subtitles",
+    "burn_timecode", "resize_video", "crop_video", "rotate_video", "adjust_speed", "flip_video",
+    "scale_video", "adjust_volume", "fade_audio", "add_audio", "chroma_key", "stabilize_video",
+    "auto_correct",
+];
+
+/// Masters and exports are written under `outputs/projects/<id>/`
+const PROJECT_DIR: &str = "outputs/projects";
+
+pub struct ProjectService;
+
+impl ProjectService {
+    pub async fn create_project(
+        pool: &PgPool,
+        user_id: i32,
+        session_uuid: &str,
+        name: &str,
+        source_file: &str,
+    ) -> Result<Project, String> {
+        let name = name.trim();
+        if name.is_empty() || name.len() > 100 {
+            return Err("Project name must be 1-100 characters".to_string());
+        }
+        if !Path::new(source_file).is_file() {
+            return Err(format!("Source file not found: {}", source_file));
+        }
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        let project = sqlx::query_as::<_, Project>(
+            "INSERT INTO projects (user_id, session_uuid, name, source_file) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(user_id)
+        .bind(session_uuid)
+        .bind(name)
+        .bind(source_file)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                format!("This session already has a project named '{}'", name)
+            }
+            e => format!("Failed to create project: {}", e),
+        })?;
+        sqlx::query("INSERT INTO project_revisions (project_id, revision, operations, change) VALUES ($1, 0, '[]', $2)")
+            .bind(project.id)
+            .bind(format!("Created from {}", source_file))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(project)
+    }
+
+    /// The user's projects, or only those of one session
+    pub async fn list_projects(pool: &PgPool, user_id: i32, session_uuid: Option<&str>) -> Result<Vec<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>(
+            "SELECT * FROM projects WHERE user_id = $1 AND ($2::TEXT IS NULL OR session_uuid = $2) ORDER BY updated_at DESC",
+        )
+        .bind(user_id)
+        .bind(session_uuid)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn get_project(pool: &PgPool, user_id: i32, project_id: i32) -> Result<Option<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = $1 AND user_id = $2")
+            .bind(project_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// A session's project by name, matched case-insensitively
+    pub async fn find_by_name(pool: &PgPool, session_uuid: &str, name: &str) -> Result<Option<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE session_uuid = $1 AND lower(name) = lower($2)")
+            .bind(session_uuid)
+            .bind(name.trim())
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn delete_project(pool: &PgPool, user_id: i32, project_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM projects WHERE id = $1 AND user_id = $2")
+            .bind(project_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every revision up to the redo stack's end, oldest first
+    pub async fn history(pool: &PgPool, project_id: i32) -> Result<Vec<ProjectRevision>, sqlx::Error> {
+        sqlx::query_as::<_, ProjectRevision>("SELECT * FROM project_revisions WHERE project_id = $1 ORDER BY revision")
+            .bind(project_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// The edit list at the project's current revision
+    pub async fn operations(pool: &PgPool, project: &Project) -> Result<Vec<ProjectOperation>, String> {
+        Self::revision(pool, project.id, project.revision)
+            .await?
+            .ok_or_else(|| format!("Project '{}' is missing revision {}", project.name, project.revision))?
+            .operation_list()
+    }
+
+    async fn revision(pool: &PgPool, project_id: i32, revision: i32) -> Result<Option<ProjectRevision>, String> {
+        sqlx::query_as::<_, ProjectRevision>("SELECT * FROM project_revisions WHERE project_id = $1 AND revision = $2")
+            .bind(project_id)
+            .bind(revision)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Apply a change to the current edit list as a new revision. Undone revisions are dropped:
+    /// after an edit there is nothing left to redo.
+    pub async fn edit(
+        pool: &PgPool,
+        project: &Project,
+        change: &str,
+        apply: impl FnOnce(&mut Vec<ProjectOperation>) -> Result<(), String>,
+    ) -> Result<(Project, Vec<ProjectOperation>), String> {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        // Locked so concurrent edits each build on the other's revision
+        let revision: i32 = sqlx::query_scalar("SELECT revision FROM projects WHERE id = $1 FOR UPDATE")
+            .bind(project.id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        let current: Value = sqlx::query_scalar("SELECT operations FROM project_revisions WHERE project_id = $1 AND revision = $2")
+            .bind(project.id)
+            .bind(revision)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut operations: Vec<ProjectOperation> = serde_json::from_value(current).map_err(|e| e.to_string())?;
+        apply(&mut operations)?;
+
+        sqlx::query("DELETE FROM project_revisions WHERE project_id = $1 AND revision > $2")
+            .bind(project.id)
+            .bind(revision)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("INSERT INTO project_revisions (project_id, revision, operations, change) VALUES ($1, $2, $3, $4)")
+            .bind(project.id)
+            .bind(revision + 1)
+            .bind(serde_json::to_value(&operations).unwrap_or_default())
+            .bind(change)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        let project = sqlx::query_as::<_, Project>("UPDATE projects SET revision = $2, updated_at = NOW() WHERE id = $1 RETURNING *")
+            .bind(project.id)
+            .bind(revision + 1)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok((project, operations))
+    }
+
+    pub async fn add_operation(
+        pool: &PgPool,
+        project: &Project,
+        operation: ProjectOperation,
+        position: Option<usize>,
+    ) -> Result<(Project, Vec<ProjectOperation>), String> {
+        validate_operation(&operation)?;
+        let change = format!("Added {}", operation.tool);
+        Self::edit(pool, project, &change, |operations| insert_operation(operations, operation, position)).await
+    }
+
+    pub async fn remove_operation(pool: &PgPool, project: &Project, position: usize) -> Result<(Project, Vec<ProjectOperation>), String> {
+        let change = format!("Removed operation {}", position);
+        Self::edit(pool, project, &change, |operations| remove_operation(operations, position).map(|_| ())).await
+    }
+
+    pub async fn move_operation(pool: &PgPool, project: &Project, from: usize, to: usize) -> Result<(Project, Vec<ProjectOperation>), String> {
+        let change = format!("Moved operation {} to {}", from, to);
+        Self::edit(pool, project, &change, |operations| move_operation(operations, from, to)).await
+    }
+
+    /// Step back one revision; the undone one stays available to redo until the next edit
+    pub async fn undo(pool: &PgPool, project: &Project) -> Result<(Project, String), String> {
+        if project.revision == 0 {
+            return Err("Nothing to undo".to_string());
+        }
+        let undone = Self::revision(pool, project.id, project.revision).await?.map(|r| r.change).unwrap_or_default();
+        Ok((Self::set_revision(pool, project, project.revision - 1).await?, undone))
+    }
+
+    pub async fn redo(pool: &PgPool, project: &Project) -> Result<(Project, String), String> {
+        let redone = Self::revision(pool, project.id, project.revision + 1)
+            .await?
+            .ok_or_else(|| "Nothing to redo".to_string())?
+            .change;
+        Ok((Self::set_revision(pool, project, project.revision + 1).await?, redone))
+    }
+
+    async fn set_revision(pool: &PgPool, project: &Project, revision: i32) -> Result<Project, String> {
+        // Only moves from where the caller saw the project, so two undos can't both step back from it
+        sqlx::query_as::<_, Project>(
+            "UPDATE projects SET revision = $3, updated_at = NOW() WHERE id = $1 AND revision = $2 RETURNING *",
+        )
+        .bind(project.id)
+        .bind(project.revision)
+        .bind(revision)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "The project changed meanwhile; reload it and try again".to_string())
+    }
+
+    pub async fn list_exports(pool: &PgPool, project_id: i32) -> Result<Vec<ProjectExport>, sqlx::Error> {
+        sqlx::query_as::<_, ProjectExport>("SELECT * FROM project_exports WHERE project_id = $1 ORDER BY created_at DESC")
+            .bind(project_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Record an export of the current revision; `render_export` does the work
+    pub async fn start_export(state: &AppState, project: &Project, preset: Option<&str>) -> Result<ProjectExport, String> {
+        let preset = preset.map(str::trim).filter(|p| !p.is_empty());
+        if let Some(preset) = preset {
+            // Fail before queuing anything if the preset doesn't exist
+            ExportPresetService::resolve(&state.db_pool, Some(project.user_id), preset).await?;
+        }
+        sqlx::query_as::<_, ProjectExport>(
+            "INSERT INTO project_exports (project_id, revision, preset) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(project.id)
+        .bind(project.revision)
+        .bind(preset)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to start export: {}", e))
+    }
+
+    /// Render an export and record how it went
+    pub async fn render_export(state: &AppState, project: &Project, export: &ProjectExport) -> Result<ProjectExport, String> {
+        let rendered = Self::render(state, project, export).await;
+        let (status, output_path, error) = match &rendered {
+            Ok(path) => ("completed", Some(path.clone()), None),
+            Err(e) => ("failed", None, Some(e.clone())),
+        };
+        let recorded = sqlx::query_as::<_, ProjectExport>(
+            "UPDATE project_exports SET status = $2, output_path = $3, error = $4, finished_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(export.id)
+        .bind(status)
+        .bind(output_path)
+        .bind(&error)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to record export: {}", e))?;
+        rendered.map(|_| recorded)
+    }
+
+    async fn render(state: &AppState, project: &Project, export: &ProjectExport) -> Result<String, String> {
+        if !Path::new(&project.source_file).is_file() {
+            return Err(format!("Source file not found: {}", project.source_file));
+        }
+        let operations = Self::revision(&state.db_pool, project.id, export.revision)
+            .await?
+            .ok_or_else(|| format!("Revision {} no longer exists", export.revision))?
+            .operation_list()?;
+        let master = Self::render_master(project, &operations).await?;
+        let name = format!("{}_r{}", file_stem(&project.name), export.revision);
+
+        let Some(ref preset) = export.preset else {
+            let output = format!("{}/{}/{}.mp4", PROJECT_DIR, project.id, name);
+            tokio::fs::copy(&master, &output).await.map_err(|e| format!("Failed to write {}: {}", output, e))?;
+            return Ok(output);
+        };
+        let settings = ExportPresetService::resolve(&state.db_pool, Some(project.user_id), preset).await?;
+        let output = format!("{}/{}/{}_{}.{}", PROJECT_DIR, project.id, name, file_stem(preset), settings.container);
+        let (input, out) = (master.clone(), output.clone());
+        tokio::task::spawn_blocking(move || crate::export::export_with_settings(&input, &out, &settings, None))
+            .await
+            .map_err(|e| e.to_string())??;
+        Ok(output)
+    }
+
+    /// The edit list applied to the source. Masters are keyed by the source and operations, so
+    /// exporting a revision again, or one that undo and redo came back to, reuses the render.
+    async fn render_master(project: &Project, operations: &[ProjectOperation]) -> Result<String, String> {
+        let key = edit_list_key(&project.source_file, operations);
+        let dir = format!("{}/{}/masters/{}", PROJECT_DIR, project.id, key);
+        let master = format!("{}/master.mp4", dir);
+        if Path::new(&master).is_file() {
+            return Ok(master);
+        }
+        tokio::fs::create_dir_all(&dir).await.map_err(|e| format!("Failed to create {}: {}", dir, e))?;
+
+        let mut current = project.source_file.clone();
+        for (index, operation) in operations.iter().enumerate() {
+            let output = format!("{}/step_{}.mp4", dir, index + 1);
+            let mut args = operation.args.clone();
+            args.insert("input_file".to_string(), Value::String(current.clone()));
+            args.insert("output_file".to_string(), Value::String(output.clone()));
+            let result = crate::agent::tool_executor::execute_tool_claude(&operation.tool, &Value::Object(args)).await;
+            if result.starts_with("❌") || !Path::new(&output).is_file() {
+                return Err(format!("Operation {} ({}) failed: {}", index + 1, operation.tool, result));
+            }
+            current = output;
+        }
+        tokio::fs::copy(&current, &master).await.map_err(|e| format!("Failed to write {}: {}", master, e))?;
+
+        // Intermediate steps are only needed until the master exists
+        for index in 0..operations.len() {
+            let _ = tokio::fs::remove_file(format!("{}/step_{}.mp4", dir, index + 1)).await;
+        }
+        Ok(master)
+    }
+}
+
+/// An operation must be a project tool whose input and output are left to the edit list
+pub fn validate_operation(operation: &ProjectOperation) -> Result<(), String> {
+    if !PROJECT_TOOLS.contains(&operation.tool.as_str()) {
+        return Err(format!("{} can't be part of a project. Project tools: {}", operation.tool, PROJECT_TOOLS.join(", ")));
+    }
+    if let Some(key) = ["input_file", "output_file"].iter().find(|key| operation.args.contains_key(**key)) {
+        return Err(format!("Leave out {}; project operations are chained from the source on export", key));
+    }
+    Ok(())
+}
+
+/// Insert at a 1-based position (the end when None)
+pub fn insert_operation(operations: &mut Vec<ProjectOperation>, operation: ProjectOperation, position: Option<usize>) -> Result<(), String> {
+    let position = position.unwrap_or(operations.len() + 1);
+    if position == 0 || position > operations.len() + 1 {
+        return Err(format!("Position must be between 1 and {}", operations.len() + 1));
+    }
+    operations.insert(position - 1, operation);
+    Ok(())
+}
+
+pub fn remove_operation(operations: &mut Vec<ProjectOperation>, position: usize) -> Result<ProjectOperation, String> {
+    check_position(operations, position)?;
+    Ok(operations.remove(position - 1))
+}
+
+/// Move the operation at `from` so it ends up at `to` (both 1-based)
+pub fn move_operation(operations: &mut Vec<ProjectOperation>, from: usize, to: usize) -> Result<(), String> {
+    check_position(operations, from)?;
+    check_position(operations, to)?;
+    let operation = operations.remove(from - 1);
+    operations.insert(to - 1, operation);
+    Ok(())
+}
+
+fn check_position(operations: &[ProjectOperation], position: usize) -> Result<(), String> {
+    if operations.is_empty() {
+        return Err("The edit list is empty".to_string());
+    }
+    if position == 0 || position > operations.len() {
+        return Err(format!("Position must be between 1 and {}", operations.len()));
+    }
+    Ok(())
+}
+
+/// Stable key of a source and edit list, for reusing its master
+fn edit_list_key(source_file: &str, operations: &[ProjectOperation]) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(source_file.as_bytes());
+    hasher.update(serde_json::to_vec(operations).unwrap_or_default());
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+/// A name safe to use in a file name
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if stem.is_empty() { "project".to_string() } else { stem }
+}
+
+/// The edit list as numbered lines for the agent and the API
+pub fn describe_operations(operations: &[ProjectOperation]) -> String {
+    if operations.is_empty() {
+        return "  (no operations yet; exporting gives the source unchanged)".to_string();
+    }
+    operations
+        .iter()
+        .enumerate()
+        .map(|(i, op)| {
+            let args = serde_json::to_string(&op.args).unwrap_or_default();
+            match &op.note {
+                Some(note) => format!("  {}. {} {} ({})", i + 1, op.tool, args, note),
+                None => format!("  {}. {} {}", i + 1, op.tool, args),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(tool: &str) -> ProjectOperation {
+        ProjectOperation { tool: tool.to_string(), args: Default::default(), note: None }
+    }
+
+    fn tools(operations: &[ProjectOperation]) -> Vec<&str> {
+        operations.iter().map(|o| o.tool.as_str()).collect()
+    }
+
+    #[test]
+    fn edit_lists_are_appended_removed_and_reordered_by_position() {
+        let mut operations = Vec::new();
+        insert_operation(&mut operations, op("trim_video"), None).unwrap();
+        insert_operation(&mut operations, op("adjust_color"), None).unwrap();
+        insert_operation(&mut operations, op("crop_video"), Some(1)).unwrap();
+        assert_eq!(tools(&operations), ["crop_video", "trim_video", "adjust_color"]);
+
+        move_operation(&mut operations, 1, 3).unwrap();
+        assert_eq!(tools(&operations), ["trim_video", "adjust_color", "crop_video"]);
+        assert_eq!(remove_operation(&mut operations, 2).unwrap().tool, "adjust_color");
+        assert_eq!(tools(&operations), ["trim_video", "crop_video"]);
+
+        assert!(remove_operation(&mut operations, 3).is_err());
+        assert!(insert_operation(&mut operations, op("flip_video"), Some(0)).is_err());
+        assert!(move_operation(&mut Vec::new(), 1, 1).unwrap_err().contains("empty"));
+    }
+
+    #[test]
+    fn operations_must_be_chainable_project_tools() {
+        assert!(validate_operation(&op("trim_video")).is_ok());
+        assert!(validate_operation(&op("merge_videos")).unwrap_err().contains("can't be part of a project"));
+
+        let mut with_output = op("adjust_speed");
+        with_output.args.insert("output_file".to_string(), Value::String("outputs/fast.mp4".to_string()));
+        assert!(validate_operation(&with_output).unwrap_err().contains("output_file"));
+
+        // The same list keys the same master; reordering renders a new one
+        let (a, b) = (vec![op("trim_video"), op("flip_video")], vec![op("flip_video"), op("trim_video")]);
+        assert_eq!(edit_list_key("uploads/a.mp4", &a), edit_list_key("uploads/a.mp4", &a));
+        assert_ne!(edit_list_key("uploads/a.mp4", &a), edit_list_key("uploads/a.mp4", &b));
+        assert_eq!(file_stem("Client Cut v2!"), "client_cut_v2_");
+    }
+}
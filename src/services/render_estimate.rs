@@ -0,0 +1,230 @@
+// src/services/render_estimate.rs
+// Render time estimates learned from telemetry: every rendering tool run records its wall time
+// against the size of its input (duration × resolution), and estimates fit those samples per tool.
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+
+/// Arguments that name a tool's primary input, in order of preference
+const INPUT_ARGUMENTS: [&str; 9] = [
+    "input_file",
+    "input_video",
+    "input_path",
+    "input",
+    "main_video",
+    "video_path",
+    "video1",
+    "screen_file",
+    "audio_file",
+];
+
+/// Most recent runs per tool the model is fitted on
+const MAX_SAMPLES: i64 = 200;
+
+/// Reference frame size work is measured in (1080p)
+const REFERENCE_PIXELS: f64 = 1920.0 * 1080.0;
+
+/// Used before a tool has any history: fixed overhead plus half real time at 1080p
+const DEFAULT_OVERHEAD_SECONDS: f64 = 2.0;
+const DEFAULT_SECONDS_PER_WORK: f64 = 0.5;
+
+/// Size of a tool's input
+#[derive(Debug, Clone, Copy)]
+pub struct InputProfile {
+    pub duration_seconds: f64,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl InputProfile {
+    /// Seconds of 1080p-equivalent media; audio-only inputs count as their duration
+    pub fn work(&self) -> f64 {
+        let pixels = (self.width as f64 * self.height as f64).max(0.0);
+        let scale = if pixels > 0.0 { pixels / REFERENCE_PIXELS } else { 1.0 };
+        self.duration_seconds.max(0.0) * scale
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderEstimate {
+    pub seconds: f64,
+    /// Past runs of the tool the estimate is based on (0 = default rate)
+    pub samples: usize,
+}
+
+/// Per-tool model: wall seconds ≈ overhead + seconds_per_work × work
+#[derive(Debug, Clone, Copy)]
+struct Fit {
+    overhead: f64,
+    seconds_per_work: Option<f64>,
+    /// Average wall time, for calls whose input size is unknown
+    mean_seconds: f64,
+    samples: usize,
+}
+
+#[derive(sqlx::FromRow)]
+struct Sample {
+    input_duration_seconds: Option<f64>,
+    input_width: Option<i32>,
+    input_height: Option<i32>,
+    wall_seconds: f64,
+}
+
+pub struct RenderEstimateService;
+
+impl RenderEstimateService {
+    /// The tool's primary input file, if any
+    pub fn input_path(args: &Value) -> Option<String> {
+        INPUT_ARGUMENTS
+            .iter()
+            .filter_map(|key| args.get(*key).and_then(|v| v.as_str()))
+            .find(|path| std::path::Path::new(path).is_file())
+            .map(|path| path.to_string())
+    }
+
+    pub async fn profile(path: &str) -> Option<InputProfile> {
+        let path = path.to_string();
+        let info = tokio::task::spawn_blocking(move || crate::core::probe_media(&path)).await.ok()?.ok()?;
+        Some(InputProfile {
+            duration_seconds: info.duration_seconds,
+            width: info.width(),
+            height: info.height(),
+        })
+    }
+
+    /// Record a finished tool run without holding up the caller
+    pub fn record_in_background(pool: PgPool, tool: &str, args: &Value, wall_seconds: f64, success: bool) {
+        let (tool, input) = (tool.to_string(), Self::input_path(args));
+        tokio::spawn(async move {
+            let profile = match &input {
+                Some(path) => Self::profile(path).await,
+                None => None,
+            };
+            let inserted = sqlx::query(
+                "INSERT INTO tool_run_telemetry (tool, input_duration_seconds, input_width, input_height, wall_seconds, success)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&tool)
+            .bind(profile.map(|p| p.duration_seconds))
+            .bind(profile.map(|p| p.width as i32))
+            .bind(profile.map(|p| p.height as i32))
+            .bind(wall_seconds)
+            .bind(success)
+            .execute(&pool)
+            .await;
+            if let Err(e) = inserted {
+                tracing::warn!("Failed to record telemetry for {}: {}", tool, e);
+            }
+        });
+    }
+
+    /// Predicted wall time of running `tool` with these arguments
+    pub async fn estimate(pool: &PgPool, tool: &str, args: &Value) -> RenderEstimate {
+        let work = match Self::input_path(args) {
+            Some(path) => Self::profile(&path).await.map(|p| p.work()),
+            None => None,
+        };
+        let fit = Self::fit_for(pool, tool).await;
+        Self::predict(fit, work)
+    }
+
+    /// " (ETA ~45s)" for progress messages, once the tool has past runs to go on
+    pub async fn eta_note(pool: &PgPool, tool: &str, args: &Value) -> String {
+        let estimate = Self::estimate(pool, tool, args).await;
+        if estimate.samples == 0 {
+            return String::new();
+        }
+        format!(" (ETA {})", Self::format_eta(estimate.seconds))
+    }
+
+    /// Predicted wall time of a sequence of tool calls run back to back
+    pub async fn estimate_steps(pool: &PgPool, steps: &[(String, Value)]) -> f64 {
+        let mut total = 0.0;
+        for (tool, args) in steps {
+            total += Self::estimate(pool, tool, args).await.seconds;
+        }
+        total
+    }
+
+    async fn fit_for(pool: &PgPool, tool: &str) -> Option<Fit> {
+        let samples = sqlx::query_as::<_, Sample>(
+            "SELECT input_duration_seconds, input_width, input_height, wall_seconds
+             FROM tool_run_telemetry WHERE tool = $1 AND success
+             ORDER BY created_at DESC LIMIT $2",
+        )
+        .bind(tool)
+        .bind(MAX_SAMPLES)
+        .fetch_all(pool)
+        .await
+        .ok()?;
+
+        let points: Vec<(Option<f64>, f64)> = samples
+            .iter()
+            .map(|s| {
+                let work = s.input_duration_seconds.map(|duration| {
+                    InputProfile {
+                        duration_seconds: duration,
+                        width: s.input_width.unwrap_or(0).max(0) as u32,
+                        height: s.input_height.unwrap_or(0).max(0) as u32,
+                    }
+                    .work()
+                });
+                (work, s.wall_seconds)
+            })
+            .collect();
+        Self::fit(&points)
+    }
+
+    /// Least-squares line through (work, seconds) samples; with too few or identical inputs,
+    /// the median rate through the origin; with no sized inputs, the mean wall time
+    fn fit(points: &[(Option<f64>, f64)]) -> Option<Fit> {
+        if points.is_empty() {
+            return None;
+        }
+        let mean_seconds = points.iter().map(|(_, s)| s).sum::<f64>() / points.len() as f64;
+        let sized: Vec<(f64, f64)> = points.iter().filter_map(|(w, s)| w.filter(|w| *w > 0.0).map(|w| (w, *s))).collect();
+        if sized.is_empty() {
+            return Some(Fit { overhead: mean_seconds, seconds_per_work: None, mean_seconds, samples: points.len() });
+        }
+
+        let n = sized.len() as f64;
+        let mean_work = sized.iter().map(|(w, _)| w).sum::<f64>() / n;
+        let mean_wall = sized.iter().map(|(_, s)| s).sum::<f64>() / n;
+        let variance = sized.iter().map(|(w, _)| (w - mean_work).powi(2)).sum::<f64>();
+        if sized.len() >= 3 && variance > f64::EPSILON {
+            let covariance = sized.iter().map(|(w, s)| (w - mean_work) * (s - mean_wall)).sum::<f64>();
+            let slope = covariance / variance;
+            let overhead = mean_wall - slope * mean_work;
+            // A negative slope or overhead means noise dominates; fall back to the median rate
+            if slope > 0.0 && overhead >= 0.0 {
+                return Some(Fit { overhead, seconds_per_work: Some(slope), mean_seconds, samples: sized.len() });
+            }
+        }
+
+        let mut rates: Vec<f64> = sized.iter().map(|(w, s)| s / w).collect();
+        rates.sort_by(|a, b| a.total_cmp(b));
+        Some(Fit { overhead: 0.0, seconds_per_work: Some(rates[rates.len() / 2]), mean_seconds, samples: sized.len() })
+    }
+
+    fn predict(fit: Option<Fit>, work: Option<f64>) -> RenderEstimate {
+        let seconds = match (fit, work) {
+            (Some(Fit { overhead, seconds_per_work: Some(rate), .. }), Some(work)) => overhead + rate * work,
+            (Some(fit), _) => fit.mean_seconds,
+            (None, work) => DEFAULT_OVERHEAD_SECONDS + DEFAULT_SECONDS_PER_WORK * work.unwrap_or(0.0),
+        };
+        RenderEstimate {
+            seconds: seconds.max(0.5),
+            samples: fit.map(|f| f.samples).unwrap_or(0),
+        }
+    }
+
+    /// "~45s", "~3m 10s", "~1h 05m"
+    pub fn format_eta(seconds: f64) -> String {
+        let total = seconds.round().max(1.0) as u64;
+        match total {
+            0..=59 => format!("~{}s", total),
+            60..=3599 => format!("~{}m {:02}s", total / 60, total % 60),
+            _ => format!("~{}h {:02}m", total / 3600, total % 3600 / 60),
+        }
+    }
+}
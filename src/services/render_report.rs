@@ -0,0 +1,277 @@
+// src/services/render_report.rs
+// Machine-readable render reports written next to each output as `<output>.report.json`:
+// input hashes, the exact FFmpeg commands, encoders, timings, warnings and the environment,
+// plus what changed since the version this output was re-rendered from.
+use crate::models::file::OutputVideo;
+use crate::services::{DeliveryService, OutputVideoService};
+use crate::types::FfmpegInvocation;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::OnceLock;
+
+const REPORT_VERSION: u32 = 1;
+
+/// A tool run as captured while it executed
+#[derive(Debug, Clone)]
+pub struct RenderRun {
+    pub tool: String,
+    pub arguments: Value,
+    pub started_at: DateTime<Utc>,
+    pub duration_seconds: f64,
+    pub ffmpeg: Vec<FfmpegInvocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub duration_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RenderEnvironment {
+    pub app_version: String,
+    pub ffmpeg_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderReport {
+    pub report_version: u32,
+    pub tool: String,
+    pub arguments: Value,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_seconds: f64,
+    pub inputs: Vec<FileFingerprint>,
+    pub output: Option<FileFingerprint>,
+    pub ffmpeg_commands: Vec<FfmpegInvocation>,
+    pub encoders: Vec<String>,
+    pub warnings: Vec<String>,
+    pub environment: RenderEnvironment,
+    /// Every FFmpeg command ran pinned, so the render can be replayed from a snapshot
+    #[serde(default)]
+    pub reproducible: bool,
+    /// Report of the version this output was re-rendered from
+    pub compared_to: Option<String>,
+    pub changes: Vec<String>,
+}
+
+pub struct RenderReportService;
+
+impl RenderReportService {
+    /// Where the report for an output lives
+    pub fn report_path(output_path: &str) -> String {
+        format!("{}.report.json", output_path)
+    }
+
+    /// Build the report for a finished run. Inputs are every argument naming an existing file other than the output.
+    pub async fn build(run: RenderRun, output_path: &str, previous_report: Option<&str>) -> RenderReport {
+        let bare = |p: &str| p.trim_start_matches("./").trim_start_matches("outputs/").to_string();
+        let mut inputs = Vec::new();
+        for path in Self::file_arguments(&run.arguments) {
+            if bare(&path) != bare(output_path) {
+                if let Some(fingerprint) = Self::fingerprint(&path).await {
+                    inputs.push(fingerprint);
+                }
+            }
+        }
+
+        let mut encoders: Vec<String> = Vec::new();
+        let mut warnings: Vec<String> = Vec::new();
+        for invocation in &run.ffmpeg {
+            for encoder in &invocation.encoders {
+                if !encoders.contains(encoder) {
+                    encoders.push(encoder.clone());
+                }
+            }
+            for warning in &invocation.warnings {
+                if !warnings.contains(warning) {
+                    warnings.push(warning.clone());
+                }
+            }
+        }
+        if run.ffmpeg.iter().any(|i| !i.success) {
+            warnings.push("One or more FFmpeg commands failed; the tool recovered with a fallback".to_string());
+        }
+
+        let reproducible = !run.ffmpeg.is_empty() && run.ffmpeg.iter().all(|i| i.pinned);
+        let mut report = RenderReport {
+            report_version: REPORT_VERSION,
+            tool: run.tool,
+            arguments: run.arguments,
+            started_at: run.started_at,
+            finished_at: Utc::now(),
+            duration_seconds: run.duration_seconds,
+            inputs,
+            output: Self::fingerprint(output_path).await,
+            ffmpeg_commands: run.ffmpeg,
+            encoders,
+            warnings,
+            environment: Self::environment().await,
+            reproducible,
+            compared_to: None,
+            changes: Vec::new(),
+        };
+
+        if let Some(previous_path) = previous_report {
+            if let Some(previous) = Self::read(previous_path).await {
+                report.changes = Self::changes(&previous, &report);
+                report.compared_to = Some(previous_path.to_string());
+            }
+        }
+        report
+    }
+
+    pub async fn write(report: &RenderReport, output_path: &str) -> Result<String, String> {
+        let path = Self::report_path(output_path);
+        let json = serde_json::to_string_pretty(report).map_err(|e| format!("Failed to serialize render report: {}", e))?;
+        tokio::fs::write(&path, json)
+            .await
+            .map_err(|e| format!("Failed to write render report {}: {}", path, e))?;
+        Ok(path)
+    }
+
+    pub async fn read(path: &str) -> Option<RenderReport> {
+        let bytes = tokio::fs::read(path).await.ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Write the report for a recorded output and attach it to the output's row
+    pub async fn record_for_output(
+        pool: &PgPool,
+        video: &OutputVideo,
+        run: RenderRun,
+        parent: Option<&OutputVideo>,
+    ) -> Result<String, String> {
+        // Rows may store the path with or without the outputs/ prefix
+        let on_disk = OutputVideoService::path_candidates(&video.file_path)
+            .into_iter()
+            .find(|p| std::path::Path::new(p).is_file())
+            .unwrap_or_else(|| video.file_path.clone());
+        let previous = parent.and_then(|p| p.report_path.as_deref());
+        let report = Self::build(run, &on_disk, previous).await;
+        let path = Self::write(&report, &on_disk).await?;
+
+        sqlx::query("UPDATE output_videos SET report_path = $2, updated_at = NOW() WHERE id = $1")
+            .bind(video.id)
+            .bind(&path)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to attach render report: {}", e))?;
+        Ok(path)
+    }
+
+    /// Human-readable differences that could explain a different render
+    fn changes(previous: &RenderReport, current: &RenderReport) -> Vec<String> {
+        let mut changes = Vec::new();
+        if previous.tool != current.tool {
+            changes.push(format!("tool: {} → {}", previous.tool, current.tool));
+        }
+
+        let (before, after) = (&previous.environment, &current.environment);
+        if before.app_version != after.app_version {
+            changes.push(format!("app version: {} → {}", before.app_version, after.app_version));
+        }
+        if before.ffmpeg_version != after.ffmpeg_version {
+            changes.push(format!(
+                "ffmpeg: {} → {}",
+                before.ffmpeg_version.as_deref().unwrap_or("unknown"),
+                after.ffmpeg_version.as_deref().unwrap_or("unknown")
+            ));
+        }
+        if before.os != after.os || before.arch != after.arch {
+            changes.push(format!("platform: {}/{} → {}/{}", before.os, before.arch, after.os, after.arch));
+        }
+        if previous.encoders != current.encoders {
+            changes.push(format!("encoders: [{}] → [{}]", previous.encoders.join(", "), current.encoders.join(", ")));
+        }
+
+        if let (Value::Object(before), Value::Object(after)) = (&previous.arguments, &current.arguments) {
+            for (key, value) in after {
+                match before.get(key) {
+                    Some(old) if old == value => {}
+                    Some(old) => changes.push(format!("argument {}: {} → {}", key, old, value)),
+                    None => changes.push(format!("argument {} added: {}", key, value)),
+                }
+            }
+            for key in before.keys().filter(|k| !after.contains_key(*k)) {
+                changes.push(format!("argument {} removed", key));
+            }
+        }
+
+        for input in &current.inputs {
+            match previous.inputs.iter().find(|i| i.path == input.path) {
+                Some(old) if old.sha256 != input.sha256 => {
+                    changes.push(format!("input {} content changed ({} → {})", input.path, &old.sha256[..12], &input.sha256[..12]))
+                }
+                Some(_) => {}
+                None => changes.push(format!("new input {}", input.path)),
+            }
+        }
+
+        let commands = |r: &RenderReport| r.ffmpeg_commands.iter().map(|c| c.command.clone()).collect::<Vec<_>>();
+        if commands(previous) != commands(current) {
+            changes.push(format!(
+                "ffmpeg commands differ ({} before, {} now)",
+                previous.ffmpeg_commands.len(),
+                current.ffmpeg_commands.len()
+            ));
+        }
+        changes
+    }
+
+    /// String arguments (including inside arrays) that name existing files
+    fn file_arguments(arguments: &Value) -> Vec<String> {
+        let mut paths = Vec::new();
+        let mut stack = vec![arguments];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::String(s) if std::path::Path::new(s).is_file() && !paths.contains(s) => paths.push(s.clone()),
+                Value::Array(items) => stack.extend(items.iter()),
+                Value::Object(map) => stack.extend(map.values()),
+                _ => {}
+            }
+        }
+        paths.sort();
+        paths
+    }
+
+    async fn fingerprint(path: &str) -> Option<FileFingerprint> {
+        let checksums = DeliveryService::checksum_file(path).await.ok()?;
+        let duration_seconds = crate::core::analyze_video(path).ok().map(|info| info.duration_seconds);
+        Some(FileFingerprint {
+            path: path.to_string(),
+            size_bytes: checksums.size_bytes,
+            sha256: checksums.sha256,
+            duration_seconds,
+        })
+    }
+
+    pub async fn environment() -> RenderEnvironment {
+        static FFMPEG_VERSION: OnceLock<Option<String>> = OnceLock::new();
+        let ffmpeg_version = match FFMPEG_VERSION.get() {
+            Some(version) => version.clone(),
+            None => {
+                let version = tokio::process::Command::new("ffmpeg")
+                    .arg("-version")
+                    .output()
+                    .await
+                    .ok()
+                    .and_then(|o| String::from_utf8_lossy(&o.stdout).lines().next().map(|l| l.trim().to_string()));
+                FFMPEG_VERSION.get_or_init(|| version).clone()
+            }
+        };
+
+        RenderEnvironment {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            ffmpeg_version,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+}
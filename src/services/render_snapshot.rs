@@ -0,0 +1,298 @@
+// src/services/render_snapshot.rs
+// Reproducible renders: a session in reproducible mode pins every FFmpeg command (bitexact output,
+// seeded filters, inputs copied to the content-addressed asset store). A snapshot freezes an output's
+// pinned commands so it can be replayed months later and checked byte-for-byte against the original.
+use crate::models::render_snapshot::{CreateRenderSnapshotRequest, RenderSnapshot, SnapshotManifest};
+use crate::services::{OutputVideoService, RenderReportService};
+use crate::utils::{concat_list_entries, execute_ffmpeg_command, file_sha256, pinned_asset_path};
+use sqlx::PgPool;
+use std::path::Path;
+use std::process::Command;
+
+/// Replays are written under `outputs/snapshots/<id>/replay_<timestamp>/`
+const SNAPSHOT_DIR: &str = "outputs/snapshots";
+
+const FILTER_FLAGS: [&str; 6] = ["-vf", "-af", "-filter:v", "-filter:a", "-filter_complex", "-lavfi"];
+
+/// Outcome of replaying a snapshot
+#[derive(Debug, Clone)]
+pub struct ReplayResult {
+    pub output_path: String,
+    pub matched: bool,
+    pub notes: Vec<String>,
+}
+
+pub struct RenderSnapshotService;
+
+impl RenderSnapshotService {
+    /// Turn reproducible mode on or off for one of the user's chat sessions
+    pub async fn set_session_mode(pool: &PgPool, user_id: i32, session_uuid: &str, enabled: bool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE chat_sessions SET reproducible_render = $3 WHERE session_uuid = $1 AND user_id = $2")
+            .bind(session_uuid)
+            .bind(user_id)
+            .bind(enabled)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Freeze an output rendered in reproducible mode into a replayable snapshot
+    pub async fn create_snapshot(
+        pool: &PgPool,
+        user_id: i32,
+        request: &CreateRenderSnapshotRequest,
+    ) -> Result<RenderSnapshot, String> {
+        let output = OutputVideoService::get_output_video_by_id(pool, request.output_id)
+            .await
+            .map_err(|e| format!("Failed to load output: {}", e))?
+            .filter(|o| o.user_id == user_id)
+            .ok_or("Output not found")?;
+        let report_path = output.report_path.as_deref().ok_or("Output has no render report")?;
+        let report = RenderReportService::read(report_path)
+            .await
+            .ok_or("Output's render report could not be read")?;
+        if !report.reproducible {
+            return Err("Output was not rendered in reproducible mode. Enable it for the session and re-render first".to_string());
+        }
+
+        let commands: Vec<_> = report.ffmpeg_commands.into_iter().filter(|c| c.success).collect();
+        if let Some(missing) = commands
+            .iter()
+            .flat_map(|c| c.inputs.iter())
+            .find(|i| !Path::new(&pinned_asset_path(&i.sha256, &i.path)).is_file())
+        {
+            return Err(format!("Pinned input {} ({}) is missing from the asset store", missing.path, missing.sha256));
+        }
+
+        let manifest = SnapshotManifest {
+            tool: report.tool,
+            arguments: report.arguments,
+            output_path: report.output.as_ref().map(|o| o.path.clone()).unwrap_or_else(|| output.file_path.clone()),
+            output_sha256: report.output.map(|o| o.sha256),
+            commands,
+        };
+        let name = request
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} v{}", output.file_name, output.version));
+
+        sqlx::query_as::<_, RenderSnapshot>(
+            r#"
+            INSERT INTO render_snapshots (user_id, output_id, name, manifest, output_sha256, ffmpeg_version)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(output.id)
+        .bind(&name)
+        .bind(serde_json::to_value(&manifest).unwrap_or_default())
+        .bind(&manifest.output_sha256)
+        .bind(&report.environment.ffmpeg_version)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save snapshot: {}", e))
+    }
+
+    pub async fn list_snapshots(pool: &PgPool, user_id: i32) -> Result<Vec<RenderSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, RenderSnapshot>("SELECT * FROM render_snapshots WHERE user_id = $1 ORDER BY created_at DESC")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn get_snapshot(pool: &PgPool, user_id: i32, snapshot_id: i32) -> Result<Option<RenderSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, RenderSnapshot>("SELECT * FROM render_snapshots WHERE id = $1 AND user_id = $2")
+            .bind(snapshot_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn delete_snapshot(pool: &PgPool, user_id: i32, snapshot_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM render_snapshots WHERE id = $1 AND user_id = $2")
+            .bind(snapshot_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark the snapshot as replaying and re-render it in the background
+    pub async fn start_replay(pool: &PgPool, snapshot: RenderSnapshot) -> Result<(), String> {
+        let claimed = sqlx::query(
+            "UPDATE render_snapshots SET replay_status = 'running', replay_error = NULL WHERE id = $1 AND replay_status <> 'running'"
+        )
+        .bind(snapshot.id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to start replay: {}", e))?;
+        if claimed.rows_affected() == 0 {
+            return Err("A replay of this snapshot is already running".to_string());
+        }
+
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            let (status, output, error) = match Self::replay(&snapshot).await {
+                Ok(result) => {
+                    tracing::info!("🔁 Snapshot {} replayed: matched={}", snapshot.id, result.matched);
+                    let status = if result.matched { "matched" } else { "mismatched" };
+                    let notes = Some(result.notes.join("\n")).filter(|n| !n.is_empty());
+                    (status, Some(result.output_path), notes)
+                }
+                Err(e) => {
+                    tracing::warn!("Snapshot {} replay failed: {}", snapshot.id, e);
+                    ("failed", None, Some(e))
+                }
+            };
+            if let Err(e) = sqlx::query(
+                "UPDATE render_snapshots SET replay_status = $2, replay_output = $3, replay_error = $4, last_replayed_at = NOW() WHERE id = $1"
+            )
+            .bind(snapshot.id)
+            .bind(status)
+            .bind(output)
+            .bind(error)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!("Failed to record replay of snapshot {}: {}", snapshot.id, e);
+            }
+        });
+        Ok(())
+    }
+
+    /// Re-run the pinned commands against the stored inputs and compare every output hash
+    pub async fn replay(snapshot: &RenderSnapshot) -> Result<ReplayResult, String> {
+        let manifest: SnapshotManifest = serde_json::from_value(snapshot.manifest.clone())
+            .map_err(|e| format!("Snapshot manifest is unreadable: {}", e))?;
+        if manifest.commands.is_empty() {
+            return Err("Snapshot has no commands to replay".to_string());
+        }
+
+        let work_dir = format!("{}/{}/replay_{}", SNAPSHOT_DIR, snapshot.id, chrono::Utc::now().format("%Y%m%d%H%M%S"));
+        tokio::fs::create_dir_all(format!("{}/assets", work_dir))
+            .await
+            .map_err(|e| format!("Failed to create replay directory: {}", e))?;
+
+        let mut notes = Vec::new();
+        let environment = RenderReportService::environment().await;
+        if environment.ffmpeg_version != snapshot.ffmpeg_version {
+            notes.push(format!(
+                "ffmpeg differs from the pinned render: {} → {}",
+                snapshot.ffmpeg_version.as_deref().unwrap_or("unknown"),
+                environment.ffmpeg_version.as_deref().unwrap_or("unknown")
+            ));
+        }
+
+        // Original path → replay path; later entries win, since commands may overwrite a path
+        let mut path_map: Vec<(String, String)> = Vec::new();
+        // Paths written by earlier replayed commands, with the hash the original render gave them
+        let mut produced: std::collections::HashMap<String, Option<String>> = std::collections::HashMap::new();
+        let mut matched = true;
+        let mut last_output = None;
+
+        for (i, command) in manifest.commands.iter().enumerate() {
+            for input in &command.inputs {
+                // Use the replayed file unless something outside FFmpeg rewrote that path before this command
+                if produced.get(&input.path).is_some_and(|sha| sha.as_deref() == Some(input.sha256.as_str())) {
+                    continue;
+                }
+                let stored = pinned_asset_path(&input.sha256, &input.path);
+                let restored = format!("{}/assets/{}", work_dir, Path::new(&stored).file_name().and_then(|n| n.to_str()).unwrap_or(&input.sha256));
+                if !Path::new(&restored).exists() {
+                    tokio::fs::copy(&stored, &restored)
+                        .await
+                        .map_err(|e| format!("Pinned input {} ({}) could not be restored: {}", input.path, input.sha256, e))?;
+                }
+                path_map.push((input.path.clone(), restored));
+            }
+
+            let Some(original_output) = command.args.last() else { continue };
+            let file_name = Path::new(original_output).file_name().and_then(|n| n.to_str()).unwrap_or("output");
+            let replay_output = format!("{}/{:02}_{}", work_dir, i + 1, file_name);
+            let args = rewrite_args(&command.args, &path_map, &replay_output)?;
+            path_map.push((original_output.clone(), replay_output.clone()));
+            produced.insert(original_output.clone(), command.output_sha256.clone());
+
+            let mut ffmpeg = Command::new("ffmpeg");
+            ffmpeg.args(&args);
+            tokio::task::spawn_blocking(move || execute_ffmpeg_command(ffmpeg))
+                .await
+                .map_err(|e| format!("Replay step {} panicked: {}", i + 1, e))?
+                .map_err(|e| format!("Replay step {} failed: {}", i + 1, e))?;
+
+            if let Some(expected) = command.output_sha256.as_deref() {
+                let actual = file_sha256(&replay_output)?;
+                if actual != expected {
+                    matched = false;
+                    notes.push(format!("step {} output differs ({} → {})", i + 1, &expected[..12], &actual[..12]));
+                }
+            }
+            last_output = Some((original_output.clone(), replay_output));
+        }
+
+        let (original_output, replay_output) = last_output.ok_or("Snapshot has no commands with outputs")?;
+        // Tools that post-process FFmpeg's output in Rust can only be compared step by step
+        if original_output == manifest.output_path {
+            if let Some(expected) = manifest.output_sha256.as_deref() {
+                if file_sha256(&replay_output)? != expected {
+                    matched = false;
+                }
+            }
+        } else {
+            notes.push(format!("final output {} was produced outside FFmpeg; compared per step only", manifest.output_path));
+        }
+
+        Ok(ReplayResult { output_path: replay_output, matched, notes })
+    }
+}
+
+fn lookup<'a>(path_map: &'a [(String, String)], original: &str) -> Option<&'a str> {
+    path_map.iter().rev().find(|(from, _)| from == original).map(|(_, to)| to.as_str())
+}
+
+/// Point a pinned command at the restored inputs and the replay output
+fn rewrite_args(args: &[String], path_map: &[(String, String)], replay_output: &str) -> Result<Vec<String>, String> {
+    let mut rewritten = Vec::with_capacity(args.len());
+    for (i, arg) in args.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|p| args[p].as_str());
+        let value = if i == args.len() - 1 {
+            replay_output.to_string()
+        } else if previous.is_some_and(|p| FILTER_FLAGS.contains(&p)) {
+            // Filtergraphs name files inline (subtitles=, movie=); longest paths first so prefixes don't clash
+            let mut mappings: Vec<&(String, String)> = path_map.iter().rev().collect();
+            mappings.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+            let mut graph = arg.clone();
+            for (from, to) in mappings {
+                graph = graph.replace(from.as_str(), to);
+            }
+            graph
+        } else if let Some(to) = lookup(path_map, arg) {
+            let is_concat_list = i >= 3 && args[i - 3] == "-f" && args[i - 2] == "concat";
+            if is_concat_list {
+                rewrite_concat_list(to, path_map)?;
+            }
+            to.to_string()
+        } else {
+            arg.clone()
+        };
+        rewritten.push(value);
+    }
+    Ok(rewritten)
+}
+
+/// Rewrite a restored concat list to reference restored files by absolute path
+fn rewrite_concat_list(list_file: &str, path_map: &[(String, String)]) -> Result<(), String> {
+    let lines: Vec<String> = concat_list_entries(list_file)
+        .into_iter()
+        .map(|entry| {
+            let restored = lookup(path_map, &entry).unwrap_or(&entry);
+            let absolute = std::fs::canonicalize(restored).map(|p| p.to_string_lossy().to_string()).unwrap_or_else(|_| restored.to_string());
+            format!("file '{}'", absolute.replace('\'', "'\\''"))
+        })
+        .collect();
+    std::fs::write(list_file, lines.join("\n")).map_err(|e| format!("Failed to rewrite concat list: {}", e))
+}
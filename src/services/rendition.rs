@@ -0,0 +1,227 @@
+// src/services/rendition.rs
+//! Smaller renditions of outputs for playback on phones and slow connections. A rendition is
+//! transcoded in the background the first time a client asks for it and cached under
+//! `outputs/renditions/<output id>_<profile>.mp4` until the output is re-rendered; outputs that
+//! already fit a profile are served as they are.
+use crate::handlers::output::file_id_for_path;
+use crate::types::MediaInfo;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+pub const RENDITION_DIR: &str = "outputs/renditions";
+
+pub const DEFAULT_PROFILE: &str = "mobile-480p";
+
+/// Target of a rendition: H.264/AAC MP4, at most `height` lines and roughly `video_kbps`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TranscodeProfile {
+    pub name: &'static str,
+    pub height: u32,
+    pub video_kbps: u32,
+    pub audio_kbps: u32,
+    pub max_fps: u32,
+}
+
+pub const PROFILES: &[TranscodeProfile] = &[
+    TranscodeProfile { name: "mobile-240p", height: 240, video_kbps: 400, audio_kbps: 64, max_fps: 30 },
+    TranscodeProfile { name: "mobile-360p", height: 360, video_kbps: 700, audio_kbps: 96, max_fps: 30 },
+    TranscodeProfile { name: "mobile-480p", height: 480, video_kbps: 1200, audio_kbps: 96, max_fps: 30 },
+    TranscodeProfile { name: "mobile-720p", height: 720, video_kbps: 2500, audio_kbps: 128, max_fps: 30 },
+];
+
+/// Where a rendition request stands
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenditionStatus {
+    /// Ready to stream; `original` when the output already fits the profile and is served as is
+    Ready { path: PathBuf, original: bool },
+    Processing,
+    Failed(String),
+}
+
+/// Renditions being transcoded by this process (None) or whose last attempt failed (the error)
+fn transcodes() -> &'static Mutex<HashMap<PathBuf, Option<String>>> {
+    static TRANSCODES: OnceLock<Mutex<HashMap<PathBuf, Option<String>>>> = OnceLock::new();
+    TRANSCODES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub struct RenditionService;
+
+impl RenditionService {
+    pub fn profile(name: &str) -> Option<&'static TranscodeProfile> {
+        PROFILES.iter().find(|p| p.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn profile_names() -> Vec<&'static str> {
+        PROFILES.iter().map(|p| p.name).collect()
+    }
+
+    pub fn rendition_path(output_id: i32, profile: &TranscodeProfile) -> PathBuf {
+        Path::new(RENDITION_DIR).join(format!("{}_{}.mp4", output_id, profile.name))
+    }
+
+    /// The rendition of an output if it's cached and current; otherwise start transcoding it (once)
+    /// and report it as processing. A failure is reported once, and the next request tries again.
+    pub async fn ensure(source: &Path, output_id: i32, profile: &'static TranscodeProfile) -> Result<RenditionStatus, String> {
+        let rendition = Self::rendition_path(output_id, profile);
+        let source_modified = tokio::fs::metadata(source).await.and_then(|m| m.modified()).map_err(|e| e.to_string())?;
+        if let Ok(modified) = tokio::fs::metadata(&rendition).await.and_then(|m| m.modified()) {
+            if modified >= source_modified {
+                return Ok(RenditionStatus::Ready { path: rendition, original: false });
+            }
+        }
+
+        {
+            let mut transcodes = transcodes().lock().unwrap_or_else(|e| e.into_inner());
+            match transcodes.get(&rendition) {
+                Some(None) => return Ok(RenditionStatus::Processing),
+                Some(Some(_)) => {
+                    let error = transcodes.remove(&rendition).flatten().unwrap_or_default();
+                    return Ok(RenditionStatus::Failed(error));
+                }
+                None => {}
+            }
+        }
+
+        let probe_path = source.to_string_lossy().to_string();
+        let info = tokio::task::spawn_blocking(move || crate::core::probe_media(&probe_path))
+            .await
+            .map_err(|e| e.to_string())??;
+        let source_fps = match info.video() {
+            Some(video) => video.fps,
+            None => return Err("Only video outputs have renditions".to_string()),
+        };
+        if fits(profile, &info) {
+            return Ok(RenditionStatus::Ready { path: source.to_path_buf(), original: true });
+        }
+        // Another request may have started it while this one was probing
+        if transcodes().lock().unwrap_or_else(|e| e.into_inner()).insert(rendition.clone(), None).is_some() {
+            return Ok(RenditionStatus::Processing);
+        }
+
+        let source = source.to_path_buf();
+        tokio::spawn(async move {
+            let target = rendition.clone();
+            let result = tokio::task::spawn_blocking(move || transcode(&source, &target, profile, source_fps))
+                .await
+                .unwrap_or_else(|e| Err(format!("Transcode task panicked: {}", e)));
+            let mut transcodes = transcodes().lock().unwrap_or_else(|e| e.into_inner());
+            match result {
+                Ok(()) => {
+                    tracing::info!("📱 Rendition {} ready", rendition.display());
+                    transcodes.remove(&rendition);
+                }
+                Err(e) => {
+                    tracing::warn!("Rendition {} failed: {}", rendition.display(), e);
+                    transcodes.insert(rendition, Some(e));
+                }
+            }
+        });
+        Ok(RenditionStatus::Processing)
+    }
+
+    /// Stream URL of a ready rendition (renditions resolve by file id like any output)
+    pub fn stream_url(path: &Path) -> String {
+        format!("/api/outputs/stream/{}", file_id_for_path(path))
+    }
+}
+
+/// Whether the output is already no bigger than the profile, so transcoding would only lose quality
+fn fits(profile: &TranscodeProfile, info: &MediaInfo) -> bool {
+    let Some(video) = info.video() else {
+        return false;
+    };
+    let height_fits = video.height.is_some_and(|h| h <= profile.height);
+    let kbps = info.bit_rate.map(|b| b / 1000).or_else(|| {
+        (info.duration_seconds > 0.0).then(|| (info.size_bytes as f64 * 8.0 / info.duration_seconds / 1000.0) as u64)
+    });
+    let bitrate_fits = kbps.is_some_and(|kbps| kbps <= (profile.video_kbps + profile.audio_kbps) as u64 * 5 / 4);
+    height_fits && bitrate_fits
+}
+
+fn transcode_args(profile: &TranscodeProfile, source_fps: Option<f64>, input: &str, output: &str) -> Vec<String> {
+    let mut filters = format!("scale=-2:'min({},ih)'", profile.height);
+    if source_fps.is_some_and(|fps| fps > profile.max_fps as f64 + 0.5) {
+        filters.push_str(&format!(",fps={}", profile.max_fps));
+    }
+    [
+        "-y", "-i", input,
+        "-vf", &filters,
+        "-c:v", "libx264", "-preset", "veryfast", "-profile:v", "main", "-pix_fmt", "yuv420p",
+        "-b:v", &format!("{}k", profile.video_kbps),
+        "-maxrate", &format!("{}k", profile.video_kbps * 3 / 2),
+        "-bufsize", &format!("{}k", profile.video_kbps * 2),
+        "-c:a", "aac", "-b:a", &format!("{}k", profile.audio_kbps), "-ac", "2",
+        "-movflags", "+faststart",
+        output,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Transcode to a temporary file first, so a half-written rendition is never served
+fn transcode(source: &Path, target: &Path, profile: &TranscodeProfile, source_fps: Option<f64>) -> Result<(), String> {
+    std::fs::create_dir_all(RENDITION_DIR).map_err(|e| format!("Failed to create rendition directory: {}", e))?;
+    let partial = target.with_extension("part.mp4");
+    let mut command = Command::new("ffmpeg");
+    command.args(transcode_args(profile, source_fps, &source.to_string_lossy(), &partial.to_string_lossy()));
+    if let Err(e) = crate::utils::execute_ffmpeg_command(command) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, target).map_err(|e| format!("Failed to save rendition: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StreamInfo;
+
+    fn media(height: u32, bit_rate: u64) -> MediaInfo {
+        MediaInfo {
+            file_path: "outputs/master.mp4".to_string(),
+            format_name: "mov,mp4,m4a,3gp,3g2,mj2".to_string(),
+            duration_seconds: 60.0,
+            size_bytes: bit_rate * 60 / 8,
+            bit_rate: Some(bit_rate),
+            streams: vec![StreamInfo {
+                index: 0,
+                codec_type: "video".to_string(),
+                codec_name: "h264".to_string(),
+                width: Some(height * 16 / 9),
+                height: Some(height),
+                fps: Some(30.0),
+                pix_fmt: Some("yuv420p".to_string()),
+                bit_rate: None,
+                duration_seconds: Some(60.0),
+                start_seconds: Some(0.0),
+                language: None,
+                attached_pic: false,
+            }],
+            audio_streams: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn only_outputs_bigger_than_the_profile_are_transcoded() {
+        let profile = RenditionService::profile("Mobile-480p").unwrap();
+        assert_eq!(profile.height, 480);
+        assert!(RenditionService::profile("mobile-4k").is_none());
+
+        assert!(!fits(profile, &media(2160, 40_000_000)));
+        assert!(!fits(profile, &media(480, 8_000_000)));
+        assert!(fits(profile, &media(360, 900_000)));
+
+        let args = transcode_args(profile, Some(59.94), "outputs/master.mp4", "outputs/renditions/7_mobile-480p.part.mp4");
+        assert!(args.contains(&"scale=-2:'min(480,ih)',fps=30".to_string()));
+        assert!(transcode_args(profile, Some(29.97), "in.mp4", "out.mp4").contains(&"scale=-2:'min(480,ih)'".to_string()));
+        assert_eq!(args.last().unwrap(), "outputs/renditions/7_mobile-480p.part.mp4");
+        assert_eq!(
+            RenditionService::rendition_path(7, profile),
+            PathBuf::from("outputs/renditions/7_mobile-480p.mp4")
+        );
+    }
+}
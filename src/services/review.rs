@@ -0,0 +1,171 @@
+// src/services/review.rs
+// Public review links: tokenized, expiring access to one output plus timestamped reviewer comments
+use crate::models::review::{CreateReviewCommentRequest, CreateReviewLinkRequest, ReviewComment, ReviewLink};
+use crate::services::OutputVideoService;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+/// Default link lifetime (7 days)
+pub const DEFAULT_REVIEW_HOURS: i64 = 24 * 7;
+
+/// Longest lifetime a link can be created with (90 days)
+pub const MAX_REVIEW_HOURS: i64 = 24 * 90;
+
+pub struct ReviewService;
+
+impl ReviewService {
+    pub async fn create_link(
+        pool: &PgPool,
+        user_id: i32,
+        request: &CreateReviewLinkRequest,
+    ) -> Result<ReviewLink, String> {
+        let output = OutputVideoService::get_output_video_by_id(pool, request.output_id)
+            .await
+            .map_err(|e| format!("Failed to load output: {}", e))?
+            .filter(|o| o.user_id == user_id)
+            .ok_or_else(|| format!("Output {} not found", request.output_id))?;
+        let file_path = OutputVideoService::path_candidates(&output.file_path)
+            .into_iter()
+            .find(|p| std::path::Path::new(p).is_file())
+            .ok_or_else(|| format!("File not found: {}", output.file_path))?;
+
+        let hours = request.expires_in_hours.unwrap_or(DEFAULT_REVIEW_HOURS).clamp(1, MAX_REVIEW_HOURS);
+        let title = request
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| output.file_name.clone());
+
+        // Two v4 UUIDs give 244 random bits, far beyond guessable
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+
+        sqlx::query_as::<_, ReviewLink>(
+            r#"
+            INSERT INTO review_links (token, user_id, file_path, title, allow_comments, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(&token)
+        .bind(user_id)
+        .bind(&file_path)
+        .bind(&title)
+        .bind(request.allow_comments.unwrap_or(true))
+        .bind(Utc::now() + Duration::hours(hours))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to create review link: {}", e))
+    }
+
+    pub async fn list_links(pool: &PgPool, user_id: i32) -> Result<Vec<ReviewLink>, sqlx::Error> {
+        sqlx::query_as::<_, ReviewLink>(
+            "SELECT * FROM review_links WHERE user_id = $1 ORDER BY created_at DESC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn get_owned_link(pool: &PgPool, user_id: i32, link_id: i32) -> Result<Option<ReviewLink>, sqlx::Error> {
+        sqlx::query_as::<_, ReviewLink>(
+            "SELECT * FROM review_links WHERE id = $1 AND user_id = $2"
+        )
+        .bind(link_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn revoke_link(pool: &PgPool, user_id: i32, link_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE review_links SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL"
+        )
+        .bind(link_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resolve a public token; revoked and expired links resolve to None
+    pub async fn get_active_link(pool: &PgPool, token: &str) -> Result<Option<ReviewLink>, sqlx::Error> {
+        sqlx::query_as::<_, ReviewLink>(
+            "SELECT * FROM review_links WHERE token = $1 AND revoked_at IS NULL AND expires_at > NOW()"
+        )
+        .bind(token)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn record_view(pool: &PgPool, link_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE review_links SET view_count = view_count + 1 WHERE id = $1")
+            .bind(link_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_comments(pool: &PgPool, link_id: i32) -> Result<Vec<ReviewComment>, sqlx::Error> {
+        sqlx::query_as::<_, ReviewComment>(
+            "SELECT * FROM review_comments WHERE review_link_id = $1 ORDER BY timestamp_seconds, created_at"
+        )
+        .bind(link_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn add_comment(
+        pool: &PgPool,
+        link: &ReviewLink,
+        request: &CreateReviewCommentRequest,
+    ) -> Result<ReviewComment, String> {
+        if !link.allow_comments {
+            return Err("Comments are disabled for this review link".to_string());
+        }
+        let author = request.author_name.trim();
+        let body = request.body.trim();
+        if author.is_empty() || body.is_empty() {
+            return Err("author_name and body are required".to_string());
+        }
+        if author.chars().count() > 100 || body.chars().count() > 5000 {
+            return Err("Comment is too long".to_string());
+        }
+
+        sqlx::query_as::<_, ReviewComment>(
+            r#"
+            INSERT INTO review_comments (review_link_id, author_name, body, timestamp_seconds)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(link.id)
+        .bind(author)
+        .bind(body)
+        .bind(request.timestamp_seconds.unwrap_or(0.0).max(0.0))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save comment: {}", e))
+    }
+
+    pub async fn set_comment_resolved(
+        pool: &PgPool,
+        user_id: i32,
+        comment_id: i32,
+        resolved: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE review_comments SET resolved = $3
+            WHERE id = $1 AND review_link_id IN (SELECT id FROM review_links WHERE user_id = $2)
+            "#,
+        )
+        .bind(comment_id)
+        .bind(user_id)
+        .bind(resolved)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
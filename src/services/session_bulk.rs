@@ -0,0 +1,140 @@
+// src/services/session_bulk.rs
+//! Bulk clean-up of chat sessions: archive, unarchive or trash many at once, and move uploads
+//! and outputs from one session to another.
+use crate::models::session_bulk::{BulkSessionAction, BulkSessionRequest, MoveItemsRequest};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::path::Path;
+
+/// Most sessions one bulk request may name explicitly
+pub const MAX_BULK_SESSIONS: usize = 1000;
+
+#[derive(Debug, Default, Serialize)]
+pub struct MoveSummary {
+    pub files_moved: u64,
+    pub outputs_moved: u64,
+}
+
+pub struct SessionBulkService;
+
+impl SessionBulkService {
+    /// Apply the action to every matching session of the user and return how many changed.
+    /// Sessions already in the requested state (or in the trash) are left alone.
+    pub async fn apply(pool: &PgPool, user_id: i32, request: &BulkSessionRequest) -> Result<u64, String> {
+        if request.session_ids.is_none() && request.updated_before.is_none() {
+            return Err("Select sessions with session_ids or updated_before".to_string());
+        }
+        if request.session_ids.as_ref().is_some_and(|ids| ids.len() > MAX_BULK_SESSIONS) {
+            return Err(format!("At most {} session_ids per request", MAX_BULK_SESSIONS));
+        }
+
+        let change = match request.action {
+            BulkSessionAction::Archive => "archived_at = NOW() WHERE archived_at IS NULL AND",
+            BulkSessionAction::Unarchive => "archived_at = NULL WHERE archived_at IS NOT NULL AND",
+            BulkSessionAction::Delete => "deleted_at = NOW() WHERE",
+        };
+        let result = sqlx::query(&format!(
+            "UPDATE chat_sessions SET {} user_id = $1 AND deleted_at IS NULL
+               AND ($2::TEXT[] IS NULL OR session_uuid = ANY($2))
+               AND ($3::TIMESTAMPTZ IS NULL OR updated_at < $3)",
+            change
+        ))
+        .bind(user_id)
+        .bind(&request.session_ids)
+        .bind(request.updated_before)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to update sessions: {}", e))?;
+
+        tracing::info!("🗂️ User {} bulk {:?}: {} sessions", user_id, request.action, result.rows_affected());
+        Ok(result.rows_affected())
+    }
+
+    /// Move the user's uploads and outputs into another of their sessions. Outputs rendered into
+    /// their session's own directory (`outputs/<session_uuid>/`) move to the target's directory.
+    pub async fn move_items(pool: &PgPool, user_id: i32, request: &MoveItemsRequest) -> Result<MoveSummary, String> {
+        let target = sqlx::query_as::<_, (i32, String)>(
+            "SELECT id, session_uuid FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+        )
+        .bind(&request.target_session_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        let Some((target_id, target_uuid)) = target else {
+            return Err("Target session not found".to_string());
+        };
+
+        let mut summary = MoveSummary::default();
+
+        if !request.file_ids.is_empty() {
+            let result = sqlx::query(
+                r#"
+                UPDATE uploaded_files uf SET session_id = $1, updated_at = NOW()
+                FROM chat_sessions cs
+                WHERE uf.session_id = cs.id AND cs.user_id = $2 AND uf.id = ANY($3) AND uf.session_id <> $1
+                "#,
+            )
+            .bind(target_id)
+            .bind(user_id)
+            .bind(&request.file_ids)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to move files: {}", e))?;
+            summary.files_moved = result.rows_affected();
+        }
+
+        let outputs = sqlx::query_as::<_, (i32, String, String)>(
+            r#"
+            SELECT ov.id, ov.file_path, cs.session_uuid FROM output_videos ov
+            JOIN chat_sessions cs ON cs.id = ov.session_id
+            WHERE ov.user_id = $1 AND ov.id = ANY($2) AND ov.session_id <> $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(&request.output_ids)
+        .bind(target_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for (output_id, file_path, source_uuid) in outputs {
+            let file_path = match Self::relocate(&file_path, &source_uuid, &target_uuid).await {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::warn!("Output {} stays in session {}: {}", output_id, source_uuid, e);
+                    continue;
+                }
+            };
+            sqlx::query("UPDATE output_videos SET session_id = $2, file_path = $3, updated_at = NOW() WHERE id = $1")
+                .bind(output_id)
+                .bind(target_id)
+                .bind(&file_path)
+                .execute(pool)
+                .await
+                .map_err(|e| format!("Failed to move output {}: {}", output_id, e))?;
+            summary.outputs_moved += 1;
+        }
+
+        Ok(summary)
+    }
+
+    /// Move a file out of the source session's output directory into the target's, if it lives there
+    async fn relocate(file_path: &str, source_uuid: &str, target_uuid: &str) -> Result<String, String> {
+        let bare = file_path.trim_start_matches("./").trim_start_matches("outputs/");
+        let Some(name) = bare.strip_prefix(&format!("{}/", source_uuid)) else {
+            return Ok(file_path.to_string());
+        };
+
+        let from = Path::new("outputs").join(source_uuid).join(name);
+        let target_dir = crate::handlers::output::ensure_session_output_directory(target_uuid)
+            .await
+            .map_err(|e| e.to_string())?;
+        let to = target_dir.join(name);
+        if to.exists() {
+            return Err(format!("{} already exists", to.display()));
+        }
+        tokio::fs::rename(&from, &to).await.map_err(|e| format!("Failed to move {}: {}", from.display(), e))?;
+        Ok(to.to_string_lossy().to_string())
+    }
+}
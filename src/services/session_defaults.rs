@@ -0,0 +1,285 @@
+// src/services/session_defaults.rs
+// Session-level default parameters: "from now on everything in 4K with Adam's voice" is stored on
+// the chat session and filled into every later tool call that leaves those arguments out.
+use crate::clipping::captions::CAPTION_PRESETS;
+use crate::models::session_defaults::{SessionDefaults, UpdateSessionDefaultsRequest};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+/// Tools that take an output `width`/`height`
+const RESOLUTION_TOOLS: [&str; 6] = [
+    "resize_video",
+    "create_blank_video",
+    "compose_screencast",
+    "create_lyric_video",
+    "generate_quiz_video",
+    "generate_video_from_article",
+];
+/// Tools that take an output `fps`
+const FPS_TOOLS: [&str; 2] = ["resize_video", "create_blank_video"];
+/// Tools that take a narration `voice`
+const VOICE_TOOLS: [&str; 6] = [
+    "generate_text_to_speech",
+    "add_voiceover_to_video",
+    "generate_quiz_video",
+    "generate_video_from_article",
+    "generate_talking_head",
+    "export_localized",
+];
+/// Tools that burn captions, and the boolean argument that turns them off for the "none" style
+const CAPTION_TOOLS: [(&str, Option<&str>); 3] = [
+    ("add_styled_captions", None),
+    ("generate_video_from_article", Some("captions")),
+    ("export_localized", Some("burn_captions")),
+];
+
+const NAMED_RESOLUTIONS: [(&str, u32, u32); 7] = [
+    ("8k", 7680, 4320),
+    ("4k", 3840, 2160),
+    ("2160p", 3840, 2160),
+    ("1440p", 2560, 1440),
+    ("1080p", 1920, 1080),
+    ("720p", 1280, 720),
+    ("480p", 854, 480),
+];
+
+pub struct SessionDefaultsService;
+
+impl SessionDefaultsService {
+    /// "4k", "1080p" or "1920x1080" (vertical sizes like "1080x1920" work too)
+    pub fn parse_resolution(value: &str) -> Result<(u32, u32), String> {
+        let value = value.trim().to_lowercase();
+        if let Some((_, width, height)) = NAMED_RESOLUTIONS.iter().find(|(name, _, _)| *name == value) {
+            return Ok((*width, *height));
+        }
+        let parsed = value
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?)));
+        match parsed {
+            Some((width, height)) => {
+                Self::validate_size(width, height)?;
+                Ok((width, height))
+            }
+            None => Err(format!(
+                "Unknown resolution '{}'. Use 4k, 1440p, 1080p, 720p, 480p or <width>x<height>",
+                value
+            )),
+        }
+    }
+
+    fn validate_size(width: u32, height: u32) -> Result<(), String> {
+        if !(16..=7680).contains(&width) || !(16..=7680).contains(&height) {
+            return Err("width and height must be between 16 and 7680 pixels".to_string());
+        }
+        if !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err("width and height must be even for H.264 output".to_string());
+        }
+        Ok(())
+    }
+
+    /// Apply an update on top of the current defaults
+    pub fn merge(current: &SessionDefaults, update: &UpdateSessionDefaultsRequest) -> Result<SessionDefaults, String> {
+        let mut merged = current.clone();
+        for field in &update.clear {
+            match field.as_str() {
+                "all" => merged = SessionDefaults::default(),
+                "resolution" | "width" | "height" => {
+                    merged.width = None;
+                    merged.height = None;
+                }
+                "fps" => merged.fps = None,
+                "voice" => merged.voice = None,
+                "caption_style" => merged.caption_style = None,
+                other => {
+                    return Err(format!(
+                        "Unknown default '{}'. Clear one of: resolution, fps, voice, caption_style, all",
+                        other
+                    ))
+                }
+            }
+        }
+
+        match (&update.resolution, update.width, update.height) {
+            (Some(resolution), _, _) => {
+                let (width, height) = Self::parse_resolution(resolution)?;
+                merged.width = Some(width);
+                merged.height = Some(height);
+            }
+            (None, Some(width), Some(height)) => {
+                Self::validate_size(width, height)?;
+                merged.width = Some(width);
+                merged.height = Some(height);
+            }
+            (None, None, None) => {}
+            _ => return Err("Set width and height together, or use resolution".to_string()),
+        }
+
+        if let Some(fps) = update.fps {
+            if !(1.0..=120.0).contains(&fps) {
+                return Err("fps must be between 1 and 120".to_string());
+            }
+            merged.fps = Some(fps);
+        }
+        if let Some(voice) = update.voice.as_deref().map(str::trim) {
+            if voice.is_empty() {
+                return Err("voice must not be empty".to_string());
+            }
+            merged.voice = Some(voice.to_string());
+        }
+        if let Some(style) = update.caption_style.as_deref().map(|s| s.trim().to_lowercase()) {
+            if !CAPTION_PRESETS.contains(&style.as_str()) {
+                return Err(format!(
+                    "Unknown caption style '{}'. Use one of: {}",
+                    style,
+                    CAPTION_PRESETS.join(", ")
+                ));
+            }
+            merged.caption_style = Some(style);
+        }
+        Ok(merged)
+    }
+
+    /// Defaults of a session; empty when the session is unknown
+    pub async fn get(pool: &PgPool, session_uuid: &str) -> SessionDefaults {
+        sqlx::query_scalar::<_, Value>("SELECT defaults FROM chat_sessions WHERE session_uuid = $1")
+            .bind(session_uuid)
+            .fetch_optional(pool)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Defaults of a session the user owns; None when it isn't theirs
+    pub async fn get_for_user(pool: &PgPool, user_id: i32, session_uuid: &str) -> Result<Option<SessionDefaults>, sqlx::Error> {
+        let defaults = sqlx::query_scalar::<_, Value>("SELECT defaults FROM chat_sessions WHERE session_uuid = $1 AND user_id = $2")
+            .bind(session_uuid)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(defaults.map(|v| serde_json::from_value(v).unwrap_or_default()))
+    }
+
+    /// Merge an update into a session's defaults; None when the session isn't found.
+    /// Without a user the session is trusted (agent tool calls).
+    pub async fn update(
+        pool: &PgPool,
+        user_id: Option<i32>,
+        session_uuid: &str,
+        update: &UpdateSessionDefaultsRequest,
+    ) -> Result<Option<SessionDefaults>, String> {
+        let current = sqlx::query_scalar::<_, Value>(
+            "SELECT defaults FROM chat_sessions WHERE session_uuid = $1 AND ($2::INTEGER IS NULL OR user_id = $2)",
+        )
+        .bind(session_uuid)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load session defaults: {}", e))?;
+        let Some(current) = current else {
+            return Ok(None);
+        };
+
+        let current: SessionDefaults = serde_json::from_value(current).unwrap_or_default();
+        let merged = Self::merge(&current, update)?;
+        sqlx::query("UPDATE chat_sessions SET defaults = $2 WHERE session_uuid = $1")
+            .bind(session_uuid)
+            .bind(json!(merged))
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to save session defaults: {}", e))?;
+        Ok(Some(merged))
+    }
+
+    fn affects(tool: &str) -> bool {
+        RESOLUTION_TOOLS.contains(&tool)
+            || FPS_TOOLS.contains(&tool)
+            || VOICE_TOOLS.contains(&tool)
+            || CAPTION_TOOLS.iter().any(|(name, _)| *name == tool)
+    }
+
+    /// Fill a call's missing arguments from the defaults; None when nothing was added
+    pub fn apply(tool: &str, args: &Value, defaults: &SessionDefaults) -> Option<Value> {
+        let Value::Object(original) = args else {
+            return None;
+        };
+        let mut map = original.clone();
+        let mut fill = |key: &str, value: Value| {
+            if map.get(key).is_none_or(Value::is_null) {
+                map.insert(key.to_string(), value);
+            }
+        };
+
+        if RESOLUTION_TOOLS.contains(&tool) {
+            // Only a complete size: a call that sets one side keeps its own aspect
+            if let (Some(width), Some(height)) = (defaults.width, defaults.height) {
+                if !original.contains_key("width") && !original.contains_key("height") {
+                    fill("width", json!(width));
+                    fill("height", json!(height));
+                }
+            }
+        }
+        if let Some(fps) = defaults.fps.filter(|_| FPS_TOOLS.contains(&tool)) {
+            fill("fps", json!(fps));
+        }
+        if let Some(voice) = defaults.voice.as_ref().filter(|_| VOICE_TOOLS.contains(&tool)) {
+            fill("voice", json!(voice));
+        }
+        if let Some(style) = &defaults.caption_style {
+            match CAPTION_TOOLS.iter().find(|(name, _)| *name == tool) {
+                Some((_, Some(toggle))) if style == "none" => fill(toggle, json!(false)),
+                Some((_, None)) => fill("caption_style", json!(style)),
+                _ => {}
+            }
+        }
+
+        (map != *original).then_some(Value::Object(map))
+    }
+
+    /// Look up the session's defaults and apply them, skipping the query for tools they never touch
+    pub async fn apply_to_call(pool: &PgPool, session_uuid: &str, tool: &str, args: &Value) -> Option<Value> {
+        if !Self::affects(tool) {
+            return None;
+        }
+        let defaults = Self::get(pool, session_uuid).await;
+        if defaults.is_empty() {
+            return None;
+        }
+        Self::apply(tool, args, &defaults)
+    }
+
+    /// One-line summary, e.g. "3840x2160, 30 fps, voice Adam, bold captions"
+    pub fn describe(defaults: &SessionDefaults) -> String {
+        let mut parts = Vec::new();
+        if let (Some(width), Some(height)) = (defaults.width, defaults.height) {
+            parts.push(format!("{}x{}", width, height));
+        }
+        if let Some(fps) = defaults.fps {
+            parts.push(format!("{} fps", fps));
+        }
+        if let Some(voice) = &defaults.voice {
+            parts.push(format!("voice {}", voice));
+        }
+        if let Some(style) = &defaults.caption_style {
+            parts.push(if style == "none" { "no captions".to_string() } else { format!("{} captions", style) });
+        }
+        if parts.is_empty() {
+            "none".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    /// System prompt addition telling the agent which defaults are in force
+    pub async fn prompt_note(pool: &PgPool, session_uuid: &str) -> Option<String> {
+        let defaults = Self::get(pool, session_uuid).await;
+        if defaults.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "\n\n## SESSION DEFAULTS\nThe user set these defaults for this session: {}. They are filled into tool calls that leave the arguments out, so omit them unless the user asks for something different for one output. Use set_session_defaults to change them.",
+            Self::describe(&defaults)
+        ))
+    }
+}
@@ -0,0 +1,449 @@
+// src/services/storage_tier.rs
+//! Cold storage tiering. Outputs nobody has downloaded, streamed or edited for `cold_after_days` are
+//! moved out of outputs/ into `storage.cold_dir` (gzipped when compression is on) by a background
+//! sweep. Their rows keep the path they had, so anything that asks for one - a download link, a
+//! review or embed stream, a tool reading it - rehydrates it first and never sees the difference.
+use crate::handlers::output::file_id_for_path;
+use crate::models::storage_policy::{ColdOutput, EffectiveStoragePolicy, StoragePolicy, UpdateStoragePolicyRequest};
+use crate::services::OutputVideoService;
+use crate::utils::file_locks::{self, FileAccess};
+use crate::AppState;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How often the sweep looks for idle outputs
+pub const SWEEP_INTERVAL_SECONDS: u64 = 60 * 60;
+
+/// Outputs tiered per sweep; the rest wait for the next one
+const SWEEP_BATCH: i64 = 50;
+
+const MAX_COLD_AFTER_DAYS: u32 = 3650;
+
+#[derive(Debug, Default, Serialize)]
+pub struct TierSummary {
+    pub tiered: usize,
+    /// Bytes freed in outputs/ minus the bytes the cold copies take
+    pub bytes_saved: i64,
+    /// Outputs left hot because their file was busy, missing or couldn't be copied
+    pub skipped: usize,
+}
+
+/// Row columns a tier change needs
+#[derive(Debug, sqlx::FromRow)]
+struct TierRow {
+    id: i32,
+    user_id: i32,
+    file_name: String,
+    file_path: String,
+    file_size: i64,
+    storage_tier: String,
+    hot_path: Option<String>,
+    cold_path: Option<String>,
+    cold_compressed: bool,
+    cold_sha256: Option<String>,
+}
+
+/// Tier changes take turns, so a rehydration never races the sweep deleting the same hot file
+fn tier_lock() -> &'static tokio::sync::Mutex<()> {
+    static LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| tokio::sync::Mutex::new(()))
+}
+
+pub struct StorageTierService;
+
+impl StorageTierService {
+    /// Apply an update on top of a user's current policy
+    pub fn merge(current: &StoragePolicy, update: &UpdateStoragePolicyRequest) -> Result<StoragePolicy, String> {
+        let mut merged = current.clone();
+        for field in &update.clear {
+            match field.as_str() {
+                "all" => merged = StoragePolicy::default(),
+                "enabled" => merged.enabled = None,
+                "cold_after_days" => merged.cold_after_days = None,
+                "compress" => merged.compress = None,
+                other => {
+                    return Err(format!("Unknown setting '{}'. Clear one of: enabled, cold_after_days, compress, all", other))
+                }
+            }
+        }
+
+        if let Some(days) = update.cold_after_days {
+            if !(1..=MAX_COLD_AFTER_DAYS).contains(&days) {
+                return Err(format!("cold_after_days must be between 1 and {}", MAX_COLD_AFTER_DAYS));
+            }
+            merged.cold_after_days = Some(days);
+        }
+        if update.enabled.is_some() {
+            merged.enabled = update.enabled;
+        }
+        if update.compress.is_some() {
+            merged.compress = update.compress;
+        }
+        Ok(merged)
+    }
+
+    /// A user's overrides over the server's `[storage]` settings
+    pub fn effective(policy: &StoragePolicy) -> EffectiveStoragePolicy {
+        let storage = &crate::config::get().storage;
+        EffectiveStoragePolicy {
+            enabled: policy.enabled.unwrap_or(true),
+            cold_after_days: policy.cold_after_days.unwrap_or(storage.cold_after_days),
+            compress: policy.compress.unwrap_or(storage.cold_compress),
+        }
+    }
+
+    /// A user's storage policy; empty when they haven't changed anything
+    pub async fn policy(pool: &PgPool, user_id: i32) -> StoragePolicy {
+        sqlx::query_scalar::<_, Value>("SELECT storage_policy FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    pub async fn update_policy(pool: &PgPool, user_id: i32, update: &UpdateStoragePolicyRequest) -> Result<StoragePolicy, String> {
+        let merged = Self::merge(&Self::policy(pool, user_id).await, update)?;
+        sqlx::query("UPDATE users SET storage_policy = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(json!(merged))
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to save storage policy: {}", e))?;
+        Ok(merged)
+    }
+
+    pub async fn list_cold(pool: &PgPool, user_id: i32) -> Result<Vec<ColdOutput>, sqlx::Error> {
+        sqlx::query_as::<_, ColdOutput>(
+            r#"
+            SELECT id, file_name, file_path, file_size, cold_size, cold_compressed, tiered_at
+            FROM output_videos
+            WHERE user_id = $1 AND storage_tier = 'cold' AND deleted_at IS NULL
+            ORDER BY tiered_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Where a tiered output is kept: `<cold_dir>/<user>/<output id>_<file name>[.gz]`
+    pub fn cold_path(cold_dir: &str, user_id: i32, output_id: i32, file_name: &str, compressed: bool) -> PathBuf {
+        let name = Path::new(file_name).file_name().and_then(|n| n.to_str()).unwrap_or("output");
+        let name = format!("{}_{}{}", output_id, name, if compressed { ".gz" } else { "" });
+        Path::new(cold_dir).join(user_id.to_string()).join(name)
+    }
+
+    /// Tier every output that has gone untouched past its owner's `cold_after_days`. An output
+    /// is touched by being created, edited, downloaded, streamed or read by a tool.
+    pub async fn tier_idle(state: &AppState) -> Result<TierSummary, sqlx::Error> {
+        let storage = &crate::config::get().storage;
+        let idle = sqlx::query_as::<_, (i32, Value)>(
+            r#"
+            SELECT o.id, u.storage_policy
+            FROM output_videos o
+            JOIN users u ON u.id = o.user_id
+            WHERE o.storage_tier = 'hot'
+              AND o.deleted_at IS NULL
+              AND COALESCE((u.storage_policy->>'enabled')::boolean, TRUE)
+              AND GREATEST(
+                      o.created_at, o.updated_at, o.last_accessed_at,
+                      (SELECT MAX(e.created_at) FROM output_view_events e WHERE e.output_id = o.id)
+                  ) < NOW() - make_interval(days => COALESCE((u.storage_policy->>'cold_after_days')::int, $1))
+              -- A file shared by several rows stays put; tiering it for one would pull it from the others
+              AND NOT EXISTS (SELECT 1 FROM output_videos other WHERE other.file_path = o.file_path AND other.id <> o.id)
+            ORDER BY o.id
+            LIMIT $2
+            "#,
+        )
+        .bind(storage.cold_after_days as i32)
+        .bind(SWEEP_BATCH)
+        .fetch_all(&state.db_pool)
+        .await?;
+
+        let mut summary = TierSummary::default();
+        for (id, policy) in idle {
+            let policy = Self::effective(&serde_json::from_value(policy).unwrap_or_default());
+            match Self::freeze(&state.db_pool, id, policy.compress).await {
+                Ok(saved) => {
+                    summary.tiered += 1;
+                    summary.bytes_saved += saved;
+                }
+                Err(e) => {
+                    tracing::debug!("🧊 Output {} stays hot: {}", id, e);
+                    summary.skipped += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Move one output to cold storage. Returns the bytes saved.
+    async fn freeze(pool: &PgPool, id: i32, compress: bool) -> Result<i64, String> {
+        let row = Self::row(pool, id).await?;
+        let mut on_disk = None;
+        for candidate in OutputVideoService::path_candidates(&row.file_path) {
+            if tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+                on_disk = Some(candidate);
+                break;
+            }
+        }
+        let on_disk = on_disk.ok_or_else(|| format!("{} is not on disk", row.file_path))?;
+
+        // A tool reading or writing the file right now keeps it hot until the next sweep
+        let hot_path = on_disk.trim_start_matches("./").to_string();
+        let access = FileAccess { reads: vec![], writes: vec![hot_path.clone()] };
+        let _lease = file_locks::lease(access, "cold storage tiering", Duration::ZERO).await?;
+
+        let cold = Self::cold_path(&crate::config::get().storage.cold_dir, row.user_id, row.id, &row.file_name, compress);
+        let (source, target) = (PathBuf::from(&on_disk), cold.clone());
+        let (sha256, cold_size) = tokio::task::spawn_blocking(move || copy_to_cold(&source, &target, compress))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to copy {} to cold storage: {}", on_disk, e))?;
+
+        let _tiering = tier_lock().lock().await;
+        let updated = sqlx::query(
+            r#"
+            UPDATE output_videos
+            SET storage_tier = 'cold', hot_path = $2, cold_path = $3, cold_size = $4, cold_compressed = $5,
+                cold_sha256 = $6, cold_file_id = $7, tiered_at = NOW()
+            WHERE id = $1 AND storage_tier = 'hot' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(row.id)
+        .bind(&hot_path)
+        .bind(cold.to_string_lossy().as_ref())
+        .bind(cold_size as i64)
+        .bind(compress)
+        .bind(&sha256)
+        .bind(file_id_for_path(Path::new(&on_disk)))
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+        if updated == 0 {
+            let _ = tokio::fs::remove_file(&cold).await;
+            return Err("output changed while it was being tiered".to_string());
+        }
+        tokio::fs::remove_file(&on_disk).await.map_err(|e| format!("Failed to remove {}: {}", on_disk, e))?;
+
+        tracing::info!("🧊 Tiered output {} to {} ({} → {} bytes)", row.id, cold.display(), row.file_size, cold_size);
+        Ok(row.file_size - cold_size as i64)
+    }
+
+    /// Bring a cold output back to where it was; a hot one is just marked as accessed
+    pub async fn rehydrate(pool: &PgPool, id: i32) -> Result<PathBuf, String> {
+        let _tiering = tier_lock().lock().await;
+        let row = Self::row(pool, id).await?;
+        if row.storage_tier != "cold" {
+            Self::touch(pool, id).await;
+            return Ok(PathBuf::from(row.hot_path.unwrap_or(row.file_path)));
+        }
+        let (Some(cold), Some(sha256)) = (row.cold_path.clone(), row.cold_sha256.clone()) else {
+            return Err(format!("Output {} is cold but has no cold copy recorded", id));
+        };
+        let hot = PathBuf::from(row.hot_path.clone().unwrap_or(row.file_path.clone()));
+
+        let (source, target, compressed) = (PathBuf::from(&cold), hot.clone(), row.cold_compressed);
+        tokio::task::spawn_blocking(move || restore_from_cold(&source, &target, compressed, &sha256))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to rehydrate output {}: {}", id, e))?;
+
+        sqlx::query(
+            r#"
+            UPDATE output_videos
+            SET storage_tier = 'hot', hot_path = NULL, cold_path = NULL, cold_size = NULL, cold_compressed = FALSE,
+                cold_sha256 = NULL, cold_file_id = NULL, tiered_at = NULL, last_accessed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Err(e) = tokio::fs::remove_file(&cold).await {
+            tracing::warn!("Failed to remove cold copy {}: {}", cold, e);
+        }
+
+        tracing::info!("🔥 Rehydrated output {} to {}", id, hot.display());
+        Ok(hot)
+    }
+
+    /// Rehydrate the cold output a download or stream link points at; None when no cold output has that id
+    pub async fn rehydrate_file_id(pool: &PgPool, file_id: &str) -> Result<Option<PathBuf>, String> {
+        let id = sqlx::query_scalar::<_, i32>(
+            "SELECT id FROM output_videos WHERE cold_file_id = $1 AND storage_tier = 'cold' AND deleted_at IS NULL LIMIT 1"
+        )
+        .bind(file_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        match id {
+            Some(id) => Self::rehydrate(pool, id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Make sure a file someone is about to read is on disk: rehydrate it if it's a cold output,
+    /// otherwise count the read as an access so the sweep leaves it alone
+    pub async fn ensure_hot(pool: &PgPool, path: &str) -> Result<(), String> {
+        let candidates = OutputVideoService::path_candidates(path);
+        for candidate in &candidates {
+            if tokio::fs::try_exists(candidate).await.unwrap_or(false) {
+                let _ = sqlx::query(
+                    "UPDATE output_videos SET last_accessed_at = NOW() WHERE file_path = ANY($1) AND storage_tier = 'hot'"
+                )
+                .bind(&candidates)
+                .execute(pool)
+                .await;
+                return Ok(());
+            }
+        }
+
+        let cold = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT id FROM output_videos
+            WHERE storage_tier = 'cold' AND deleted_at IS NULL AND (file_path = ANY($1) OR hot_path = ANY($1))
+            ORDER BY created_at DESC LIMIT 1
+            "#,
+        )
+        .bind(&candidates)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Some(id) = cold {
+            Self::rehydrate(pool, id).await?;
+        }
+        Ok(())
+    }
+
+    async fn touch(pool: &PgPool, id: i32) {
+        let _ = sqlx::query("UPDATE output_videos SET last_accessed_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await;
+    }
+
+    async fn row(pool: &PgPool, id: i32) -> Result<TierRow, String> {
+        sqlx::query_as::<_, TierRow>(
+            r#"
+            SELECT id, user_id, file_name, file_path, file_size, storage_tier, hot_path, cold_path, cold_compressed, cold_sha256
+            FROM output_videos WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Output {} not found", id))
+    }
+}
+
+/// Copy (or gzip) a file into the cold store, hashing the original as it goes. Returns the hash and
+/// the size of the cold copy.
+fn copy_to_cold(source: &Path, target: &Path, compress: bool) -> std::io::Result<(String, u64)> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut input = HashingReader { inner: std::fs::File::open(source)?, hasher: Sha256::new() };
+    let output = std::fs::File::create(target)?;
+    if compress {
+        let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+        std::io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?.sync_all()?;
+    } else {
+        let mut output = output;
+        std::io::copy(&mut input, &mut output)?;
+        output.sync_all()?;
+    }
+    Ok((hex::encode(input.hasher.finalize()), std::fs::metadata(target)?.len()))
+}
+
+/// Restore a cold copy next to its hot path, check it against the original's hash, then move it into place
+fn restore_from_cold(source: &Path, target: &Path, compressed: bool, sha256: &str) -> std::io::Result<()> {
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let partial = target.with_extension("rehydrating");
+    let file = std::fs::File::open(source)?;
+    let mut input: Box<dyn Read> = if compressed { Box::new(flate2::read::GzDecoder::new(file)) } else { Box::new(file) };
+    let mut output = HashingWriter { inner: std::fs::File::create(&partial)?, hasher: Sha256::new() };
+    std::io::copy(&mut input, &mut output)?;
+    output.inner.sync_all()?;
+
+    if hex::encode(output.hasher.finalize()) != sha256 {
+        let _ = std::fs::remove_file(&partial);
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "cold copy doesn't match the original"));
+    }
+    std::fs::rename(&partial, target)
+}
+
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cold_copies_round_trip_and_updates_validate() {
+        let dir = std::env::temp_dir().join(format!("cold_tier_test_{}", std::process::id()));
+        let hot = dir.join("outputs").join("clip.mp4");
+        std::fs::create_dir_all(hot.parent().unwrap()).unwrap();
+        std::fs::write(&hot, b"not really a video, but compressible compressible compressible").unwrap();
+
+        let cold = StorageTierService::cold_path(dir.join("cold").to_str().unwrap(), 7, 42, "clip.mp4", true);
+        assert!(cold.ends_with("7/42_clip.mp4.gz"));
+        let (sha256, _) = copy_to_cold(&hot, &cold, true).unwrap();
+        std::fs::remove_file(&hot).unwrap();
+        restore_from_cold(&cold, &hot, true, &sha256).unwrap();
+        assert_eq!(std::fs::read(&hot).unwrap(), b"not really a video, but compressible compressible compressible");
+        assert!(restore_from_cold(&cold, &hot, true, "0000").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let update = UpdateStoragePolicyRequest { cold_after_days: Some(0), ..Default::default() };
+        assert!(StorageTierService::merge(&StoragePolicy::default(), &update).is_err());
+        let update = UpdateStoragePolicyRequest { cold_after_days: Some(90), compress: Some(true), ..Default::default() };
+        let merged = StorageTierService::merge(&StoragePolicy::default(), &update).unwrap();
+        assert_eq!(merged.cold_after_days, Some(90));
+        let cleared = StorageTierService::merge(&merged, &UpdateStoragePolicyRequest { clear: vec!["all".into()], ..Default::default() });
+        assert_eq!(cleared.unwrap(), StoragePolicy::default());
+    }
+}
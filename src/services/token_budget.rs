@@ -0,0 +1,179 @@
+// Token budget guardrails for the agents
+// Budgets come from system_settings (token_budget.session_tokens / token_budget.daily_tokens, 0 = unlimited)
+// and are checked against recorded api_token_usage before each model call.
+
+use crate::claude_client::{ClaudeContent, ClaudeMessage, ContentBlock};
+use crate::gemini_client::{Content, Part};
+use serde_json::Value;
+use crate::services::NotificationService;
+use sqlx::PgPool;
+
+/// Share of a budget after which agents degrade (cheaper model, compacted context)
+const DEGRADE_AT: f64 = 0.8;
+
+/// Cheaper Claude model used once a budget is nearly spent
+pub const ECONOMY_CLAUDE_MODEL: &str = "claude-haiku-4-5";
+
+/// Tool results older than this many messages are truncated when degraded
+const KEEP_RECENT_MESSAGES: usize = 6;
+const COMPACTED_RESULT_CHARS: usize = 300;
+
+/// Chat history kept when degraded (normally 20 messages)
+pub const DEGRADED_HISTORY_MESSAGES: i32 = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetState {
+    Normal,
+    /// Nearly spent: keep going, but cheaply
+    Degraded(String),
+    /// Spent: stop and tell the user why
+    Exhausted(String),
+}
+
+pub struct TokenBudgetService;
+
+impl TokenBudgetService {
+    /// Where a session (and its owner's day) stands against the configured budgets.
+    /// Fails open: if usage can't be read the agent keeps running normally.
+    pub async fn check(pool: &PgPool, session_uuid: &str) -> BudgetState {
+        match Self::load(pool, session_uuid).await {
+            Ok((state, scope)) => {
+                if let Some(scope) = scope {
+                    Self::notify_owner(pool.clone(), session_uuid.to_string(), state.clone(), scope);
+                }
+                state
+            }
+            Err(e) => {
+                tracing::warn!("Token budget check failed for session {}: {}", session_uuid, e);
+                BudgetState::Normal
+            }
+        }
+    }
+
+    /// The session's budget state and, when degraded or exhausted, which budget ("session" or "daily")
+    async fn load(pool: &PgPool, session_uuid: &str) -> Result<(BudgetState, Option<&'static str>), sqlx::Error> {
+        let session_budget = Self::setting(pool, "token_budget.session_tokens").await?;
+        let daily_budget = Self::setting(pool, "token_budget.daily_tokens").await?;
+        if session_budget == 0 && daily_budget == 0 {
+            return Ok((BudgetState::Normal, None));
+        }
+
+        // Cache writes count like input; cache reads are cheap and excluded
+        let (session_used, daily_used): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE((SELECT SUM(t.total_tokens + COALESCE(t.cache_creation_tokens, 0))
+                          FROM api_token_usage t WHERE t.session_id = s.id), 0)::BIGINT,
+                COALESCE((SELECT SUM(t.total_tokens + COALESCE(t.cache_creation_tokens, 0))
+                          FROM api_token_usage t
+                          WHERE t.user_id = s.user_id AND t.created_at >= date_trunc('day', NOW() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'), 0)::BIGINT
+            FROM chat_sessions s
+            WHERE s.session_uuid = $1
+            "#,
+        )
+        .bind(session_uuid)
+        .fetch_optional(pool)
+        .await?
+        .unwrap_or((0, 0));
+
+        Ok(Self::evaluate(session_used, session_budget, daily_used, daily_budget))
+    }
+
+    fn evaluate(session_used: i64, session_budget: i64, daily_used: i64, daily_budget: i64) -> (BudgetState, Option<&'static str>) {
+        let limits = [
+            ("this session", "session", session_used, session_budget),
+            ("today", "daily", daily_used, daily_budget),
+        ];
+
+        if let Some((scope, key, used, budget)) = limits.iter().find(|(_, _, used, budget)| *budget > 0 && used >= budget) {
+            let state = BudgetState::Exhausted(format!(
+                "⏸️ Paused: the AI token budget for {} is used up ({} of {} tokens). \
+                 Your files and outputs are saved - continue in a new session, tomorrow, or ask an admin to raise the limit.",
+                scope, used, budget
+            ));
+            return (state, Some(*key));
+        }
+        if let Some((scope, key, used, budget)) = limits
+            .iter()
+            .find(|(_, _, used, budget)| *budget > 0 && *used as f64 >= *budget as f64 * DEGRADE_AT)
+        {
+            let state = BudgetState::Degraded(format!(
+                "Token budget for {} is {:.0}% used - switching to economy mode",
+                scope,
+                *used as f64 * 100.0 / *budget as f64
+            ));
+            return (state, Some(*key));
+        }
+        (BudgetState::Normal, None)
+    }
+
+    /// Warn the session's owner once per budget and level: per session for the session budget,
+    /// per day for the daily one
+    fn notify_owner(pool: PgPool, session_uuid: String, state: BudgetState, scope: &'static str) {
+        let (kind, title, message) = match state {
+            BudgetState::Degraded(message) => ("token_budget_warning", "AI token budget nearly used up", message),
+            BudgetState::Exhausted(message) => ("token_budget_exhausted", "AI token budget used up", message),
+            BudgetState::Normal => return,
+        };
+        let period = if scope == "session" { session_uuid.clone() } else { chrono::Utc::now().format("%Y-%m-%d").to_string() };
+        let dedupe_key = format!("{}:{}:{}", kind, scope, period);
+
+        tokio::spawn(async move {
+            let owner: Option<Option<i32>> = sqlx::query_scalar("SELECT user_id FROM chat_sessions WHERE session_uuid = $1")
+                .bind(&session_uuid)
+                .fetch_optional(&pool)
+                .await
+                .unwrap_or(None);
+            let Some(user_id) = owner.flatten() else { return };
+            let data = serde_json::json!({ "session_id": session_uuid, "budget": scope });
+            if let Err(e) = NotificationService::notify_once(&pool, user_id, &dedupe_key, kind, title, &message, Some(data)).await {
+                tracing::warn!("Failed to notify user {} about their token budget: {}", user_id, e);
+            }
+        });
+    }
+
+    async fn setting(pool: &PgPool, key: &str) -> Result<i64, sqlx::Error> {
+        let value: Option<String> = sqlx::query_scalar("SELECT setting_value FROM system_settings WHERE setting_key = $1")
+            .bind(key)
+            .fetch_optional(pool)
+            .await?;
+        Ok(value.and_then(|v| v.trim().parse().ok()).unwrap_or(0))
+    }
+
+    /// Truncate tool results outside the most recent messages (keeps tool_use/tool_result pairing intact)
+    pub fn compact_claude_messages(messages: &mut [ClaudeMessage]) {
+        let cutoff = messages.len().saturating_sub(KEEP_RECENT_MESSAGES);
+        for message in &mut messages[..cutoff] {
+            if let ClaudeContent::Blocks(blocks) = &mut message.content {
+                for block in blocks {
+                    if let ContentBlock::ToolResult { content, .. } = block {
+                        compact(content);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Gemini equivalent of compact_claude_messages
+    pub fn compact_gemini_contents(contents: &mut [Content]) {
+        let cutoff = contents.len().saturating_sub(KEEP_RECENT_MESSAGES);
+        for content in &mut contents[..cutoff] {
+            for part in &mut content.parts {
+                if let Part::FunctionResponse { function_response } = part {
+                    for value in function_response.response.values_mut() {
+                        if let Value::String(text) = value {
+                            compact(text);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn compact(text: &mut String) {
+    if text.chars().count() > COMPACTED_RESULT_CHARS {
+        let kept: String = text.chars().take(COMPACTED_RESULT_CHARS).collect();
+        *text = format!("{}… [truncated to save tokens]", kept);
+    }
+}
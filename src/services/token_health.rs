@@ -0,0 +1,164 @@
+// src/services/token_health.rs
+// Background OAuth token monitoring for connected YouTube channels: refreshes ahead of expiry,
+// validates the rest, and flags + notifies the owner when a connection breaks so it's found
+// before a scheduled publish fails.
+use crate::models::youtube::ConnectedYouTubeChannel;
+use crate::services::NotificationService;
+use crate::youtube_client::YouTubeClient;
+use crate::AppState;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// How often the monitor runs
+pub const CHECK_INTERVAL_SECONDS: u64 = 600;
+
+/// Tokens expiring within this window are refreshed now rather than at publish time
+const REFRESH_AHEAD_MINUTES: i64 = 30;
+
+pub struct TokenHealthService;
+
+impl TokenHealthService {
+    /// Check every active channel's token; returns how many are unhealthy
+    pub async fn check_all(state: &AppState) -> Result<usize, String> {
+        let youtube = state.youtube_client.as_ref().ok_or("YouTube client not available")?;
+        let client_id = state.google_oauth_client_id.as_ref().ok_or("Google OAuth client ID not configured")?;
+        let client_secret = state
+            .google_oauth_client_secret
+            .as_ref()
+            .ok_or("Google OAuth client secret not configured")?;
+
+        let channels = sqlx::query_as::<_, ConnectedYouTubeChannel>(
+            "SELECT * FROM connected_youtube_channels WHERE is_active = true AND token_status <> 'revoked'",
+        )
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to load channels: {}", e))?;
+
+        let mut unhealthy = 0;
+        for channel in &channels {
+            if !Self::check_channel(&state.db_pool, youtube, client_id, client_secret, channel).await {
+                unhealthy += 1;
+            }
+        }
+        Ok(unhealthy)
+    }
+
+    /// Refresh or validate one channel's token; false if it ended up unhealthy
+    async fn check_channel(
+        pool: &PgPool,
+        youtube: &YouTubeClient,
+        client_id: &str,
+        client_secret: &str,
+        channel: &ConnectedYouTubeChannel,
+    ) -> bool {
+        if channel.token_expiry > Utc::now() + Duration::minutes(REFRESH_AHEAD_MINUTES) {
+            match youtube.validate_access_token(&channel.access_token).await {
+                Ok(true) => {
+                    Self::mark_healthy(pool, channel.id).await;
+                    return true;
+                }
+                // Rejected before expiry (e.g. access revoked) - confirm by refreshing
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!("Could not validate token for channel {}: {}", channel.channel_name, e);
+                    return channel.token_status == "healthy";
+                }
+            }
+        }
+
+        match youtube.refresh_access_token(&channel.refresh_token, client_id, client_secret).await {
+            Ok(token) => {
+                let expiry = Utc::now() + Duration::seconds(token.expires_in);
+                if let Err(e) = sqlx::query(
+                    "UPDATE connected_youtube_channels
+                     SET access_token = $1, token_expiry = $2, token_status = 'healthy', token_error = NULL,
+                         token_checked_at = NOW(), updated_at = NOW()
+                     WHERE id = $3",
+                )
+                .bind(&token.access_token)
+                .bind(expiry)
+                .bind(channel.id)
+                .execute(pool)
+                .await
+                {
+                    tracing::error!("Failed to store refreshed token for channel {}: {}", channel.channel_name, e);
+                }
+                tracing::info!("🔄 Proactively refreshed token for channel {}", channel.channel_name);
+                true
+            }
+            Err(e) => {
+                Self::record_failure(pool, channel, &e.to_string()).await;
+                false
+            }
+        }
+    }
+
+    async fn mark_healthy(pool: &PgPool, channel_id: i32) {
+        let _ = sqlx::query(
+            "UPDATE connected_youtube_channels
+             SET token_status = 'healthy', token_error = NULL, token_checked_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(channel_id)
+        .execute(pool)
+        .await;
+    }
+
+    /// Flag a failed refresh and notify the owner when the channel's status changes.
+    /// Google answers invalid_grant when the refresh token was revoked or expired for good.
+    pub async fn record_failure(pool: &PgPool, channel: &ConnectedYouTubeChannel, error: &str) {
+        let status = if error.contains("invalid_grant") { "revoked" } else { "degraded" };
+        tracing::warn!("⚠️ Token for channel {} is {}: {}", channel.channel_name, status, error);
+
+        let previous = sqlx::query_scalar::<_, String>(
+            "UPDATE connected_youtube_channels c
+             SET token_status = $2, token_error = $3, token_checked_at = NOW(),
+                 requires_reauth = (CASE WHEN $2 = 'revoked' THEN true ELSE c.requires_reauth END)
+             FROM connected_youtube_channels old
+             WHERE c.id = $1 AND old.id = c.id
+             RETURNING old.token_status",
+        )
+        .bind(channel.id)
+        .bind(status)
+        .bind(error)
+        .fetch_optional(pool)
+        .await;
+
+        let previous = match previous {
+            Ok(Some(previous)) => previous,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to flag token for channel {}: {}", channel.channel_name, e);
+                return;
+            }
+        };
+        if previous == status {
+            return;
+        }
+
+        let (title, message) = if status == "revoked" {
+            (
+                format!("Reconnect YouTube channel {}", channel.channel_name),
+                format!(
+                    "YouTube access for {} was revoked or expired. Scheduled uploads and clips to this channel will fail until you reconnect it.",
+                    channel.channel_name
+                ),
+            )
+        } else {
+            (
+                format!("Trouble refreshing access to {}", channel.channel_name),
+                format!(
+                    "We couldn't refresh YouTube access for {} and will keep retrying. Reconnect the channel if this persists.",
+                    channel.channel_name
+                ),
+            )
+        };
+        let data = json!({ "channel_id": channel.id, "token_status": status, "error": error });
+        if let Err(e) =
+            NotificationService::notify(pool, channel.user_id, "youtube_token_unhealthy", &title, &message, Some(data)).await
+        {
+            tracing::error!("Failed to notify user {} about channel {}: {}", channel.user_id, channel.channel_name, e);
+        }
+    }
+}
@@ -37,6 +37,17 @@ impl ModelPricing {
         }
     }
 
+    /// Claude Haiku 4.5 - economy model used when a token budget is nearly spent
+    /// Updated: 2026-10-15
+    pub fn claude_haiku_4_5() -> Self {
+        Self {
+            input_price: 1.00,
+            output_price: 5.00,
+            input_price_extended: None,
+            output_price_extended: None,
+        }
+    }
+
     /// Gemini 2.0 Flash - Current model
     /// Updated: 2025-12-12
     /// Source: https://ai.google.dev/gemini-api/docs/pricing
@@ -88,6 +99,18 @@ impl ModelPricing {
             (input_cost + output_cost).round() as i64,
         )
     }
+
+    /// Cost of prompt cache usage in USD cents: cache writes bill at 1.25x the input
+    /// price and cache reads at 0.1x
+    pub fn cache_cost_cents(&self, cache_creation_tokens: u32, cache_read_tokens: u32, context_size: u32) -> i64 {
+        let input_price = match self.input_price_extended {
+            Some(extended) if context_size > 200_000 => extended,
+            _ => self.input_price,
+        };
+        let write_cost = (cache_creation_tokens as f64 / 1_000_000.0) * input_price * 1.25 * 100.0;
+        let read_cost = (cache_read_tokens as f64 / 1_000_000.0) * input_price * 0.1 * 100.0;
+        (write_cost + read_cost).round() as i64
+    }
 }
 
 /// Get pricing for a specific model (tries DB first, falls back to hardcoded)
@@ -169,6 +192,7 @@ fn get_hardcoded_pricing(model_key: &str) -> ModelPricing {
     match model_key {
         "claude-sonnet-4-5" | "claude-sonnet-4.5" => ModelPricing::claude_sonnet_4_5(),
         "claude-3-5-sonnet" | "claude-sonnet-3.5" => ModelPricing::claude_sonnet_3_5(),
+        "claude-haiku-4-5" => ModelPricing::claude_haiku_4_5(),
         "gemini-2.0-flash" | "gemini-2-flash" => ModelPricing::gemini_2_0_flash(),
         "gemini-2.5-flash" => ModelPricing::gemini_2_5_flash(),
         _ => {
@@ -187,6 +211,8 @@ fn get_hardcoded_pricing(model_key: &str) -> ModelPricing {
 fn normalize_model_name(model: &str) -> String {
     if model.contains("claude-sonnet-4") {
         "claude-sonnet-4-5".to_string()
+    } else if model.contains("claude-haiku-4") {
+        "claude-haiku-4-5".to_string()
     } else if model.contains("claude") && model.contains("3.5") || model.contains("3-5") {
         "claude-3-5-sonnet".to_string()
     } else if model.contains("gemini-2.5-flash") || model.contains("gemini-flash-2.5") {
@@ -219,6 +245,16 @@ mod tests {
         assert_eq!(total, 8);
     }
 
+    #[test]
+    fn test_claude_cache_cost_calculation() {
+        let pricing = ModelPricing::claude_sonnet_4_5();
+
+        // 100K written: (100000/1M) * 3.00 * 1.25 * 100 = 37.5¢; 100K read: 3¢
+        assert_eq!(pricing.cache_cost_cents(100_000, 0, 50000), 38);
+        assert_eq!(pricing.cache_cost_cents(0, 100_000, 50000), 3);
+        assert_eq!(pricing.cache_cost_cents(0, 0, 50000), 0);
+    }
+
     #[test]
     fn test_gemini_flash_cost_calculation() {
         let pricing = ModelPricing::gemini_2_0_flash();
@@ -31,6 +31,12 @@ impl TokenUsageService {
             output_tokens,
             context_size,
         );
+        // Cached prompt tokens are billed as input at their own rates
+        let input_cost = input_cost + pricing.cache_cost_cents(
+            cache_creation_tokens.unwrap_or(0),
+            cache_read_tokens.unwrap_or(0),
+            context_size,
+        );
 
         let result: (i32,) = sqlx::query_as(
             r#"
@@ -39,8 +45,8 @@ impl TokenUsageService {
                 provider, model, request_type,
                 input_tokens, output_tokens,
                 input_cost_cents, output_cost_cents,
-                cache_creation_tokens, cache_read_tokens, context_size
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                cache_creation_tokens, cache_read_tokens, context_size, request_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
             RETURNING id
             "#
         )
@@ -58,6 +64,7 @@ impl TokenUsageService {
         .bind(cache_creation_tokens.map(|t| t as i32))
         .bind(cache_read_tokens.map(|t| t as i32))
         .bind(context_size as i32)
+        .bind(crate::middleware::request_id::current())
         .fetch_one(pool)
         .await?;
 
@@ -95,8 +102,8 @@ impl TokenUsageService {
                 session_id, user_id, message_id, job_id,
                 provider, model, request_type,
                 input_tokens, output_tokens,
-                input_cost_cents, output_cost_cents
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                input_cost_cents, output_cost_cents, request_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING id
             "#
         )
@@ -111,6 +118,7 @@ impl TokenUsageService {
         .bind(output_tokens as i32)
         .bind(input_cost)
         .bind(output_cost)
+        .bind(crate::middleware::request_id::current())
         .fetch_one(pool)
         .await?;
 
@@ -0,0 +1,300 @@
+// src/services/trash.rs
+//! Trash for chat sessions and outputs. Deleting only sets `deleted_at`, which hides the item from
+//! listings and the agent's context; for TRASH_RETENTION_DAYS it can be restored. After that the
+//! purge task removes it for good: files on disk, then vectors, then database rows. If the files or
+//! vectors can't be removed the row is kept, so the next run retries instead of orphaning them.
+use crate::services::{OutputPreviewService, OutputVideoService};
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+/// How long trashed items can be restored (matches the privacy policy)
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// How often the purge task looks for expired items
+pub const PURGE_INTERVAL_SECONDS: u64 = 60 * 60;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TrashedItem {
+    /// "session" or "output"
+    pub kind: String,
+    pub id: i32,
+    /// Session UUID (for an output, the session it belongs to)
+    pub session_id: String,
+    pub name: String,
+    pub deleted_at: DateTime<Utc>,
+    /// When the purge task will remove it for good
+    pub purge_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PurgeSummary {
+    pub sessions: usize,
+    pub outputs: usize,
+    /// Items kept for the next run because a file or vector couldn't be removed
+    pub failed: usize,
+}
+
+pub struct TrashService;
+
+impl TrashService {
+    /// Move a session, with everything in it, to the trash
+    pub async fn trash_session(pool: &PgPool, user_id: i32, session_uuid: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE chat_sessions SET deleted_at = NOW() WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NULL"
+        )
+        .bind(session_uuid)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn restore_session(pool: &PgPool, user_id: i32, session_uuid: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE chat_sessions SET deleted_at = NULL WHERE session_uuid = $1 AND user_id = $2 AND deleted_at IS NOT NULL"
+        )
+        .bind(session_uuid)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn trash_output(pool: &PgPool, user_id: i32, output_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE output_videos SET deleted_at = NOW() WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
+        )
+        .bind(output_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Restore an output; if its session is in the trash too, the session comes back with it
+    pub async fn restore_output(pool: &PgPool, user_id: i32, output_id: i32) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let session_id = sqlx::query_scalar::<_, Option<i32>>(
+            "UPDATE output_videos SET deleted_at = NULL WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL RETURNING session_id"
+        )
+        .bind(output_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(session_id) = session_id else {
+            return Ok(false);
+        };
+        sqlx::query("UPDATE chat_sessions SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// The user's trash, most recently deleted first. Outputs of a trashed session are listed
+    /// under the session only.
+    pub async fn list(pool: &PgPool, user_id: i32) -> Result<Vec<TrashedItem>, sqlx::Error> {
+        sqlx::query_as::<_, TrashedItem>(
+            r#"
+            SELECT 'session' AS kind, cs.id, cs.session_uuid AS session_id, cs.title AS name, cs.deleted_at,
+                   cs.deleted_at + make_interval(days => $2) AS purge_at
+            FROM chat_sessions cs
+            WHERE cs.user_id = $1 AND cs.deleted_at IS NOT NULL
+            UNION ALL
+            SELECT 'output' AS kind, ov.id, cs.session_uuid AS session_id, ov.file_name AS name, ov.deleted_at,
+                   ov.deleted_at + make_interval(days => $2) AS purge_at
+            FROM output_videos ov
+            JOIN chat_sessions cs ON cs.id = ov.session_id
+            WHERE ov.user_id = $1 AND ov.deleted_at IS NOT NULL AND cs.deleted_at IS NULL
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(TRASH_RETENTION_DAYS as i32)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Paths (in every stored form) of a session's trashed outputs; all of them when the session itself is trashed
+    pub async fn trashed_output_paths(pool: &PgPool, session_uuid: &str) -> Result<HashSet<String>, sqlx::Error> {
+        let paths = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT ov.file_path FROM output_videos ov
+            JOIN chat_sessions cs ON cs.id = ov.session_id
+            WHERE cs.session_uuid = $1 AND (ov.deleted_at IS NOT NULL OR cs.deleted_at IS NOT NULL)
+            "#,
+        )
+        .bind(session_uuid)
+        .fetch_all(pool)
+        .await?;
+        Ok(paths.iter().flat_map(|path| OutputVideoService::path_candidates(path)).collect())
+    }
+
+    /// Permanently remove everything that has been in the trash longer than the retention period
+    pub async fn purge_expired(state: &AppState) -> Result<PurgeSummary, sqlx::Error> {
+        let cutoff = Utc::now() - Duration::days(TRASH_RETENTION_DAYS);
+        let mut summary = PurgeSummary::default();
+
+        let sessions = sqlx::query_as::<_, (i32, String)>(
+            "SELECT id, session_uuid FROM chat_sessions WHERE deleted_at < $1"
+        )
+        .bind(cutoff)
+        .fetch_all(&state.db_pool)
+        .await?;
+        for (id, session_uuid) in sessions {
+            match Self::purge_session(state, id, &session_uuid).await {
+                Ok(()) => summary.sessions += 1,
+                Err(e) => {
+                    tracing::warn!("🗑️ Failed to purge session {}, will retry: {}", session_uuid, e);
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        // Outputs of purged sessions went with them
+        let outputs = sqlx::query_as::<_, (i32, String, Option<String>, Option<String>)>(
+            "SELECT id, file_path, report_path, cold_path FROM output_videos WHERE deleted_at < $1"
+        )
+        .bind(cutoff)
+        .fetch_all(&state.db_pool)
+        .await?;
+        for (id, file_path, report_path, cold_path) in outputs {
+            match Self::purge_output(state, id, &file_path, report_path.as_deref(), cold_path.as_deref()).await {
+                Ok(()) => summary.outputs += 1,
+                Err(e) => {
+                    tracing::warn!("🗑️ Failed to purge output {}, will retry: {}", id, e);
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn purge_session(state: &AppState, id: i32, session_uuid: &str) -> Result<(), String> {
+        let outputs = sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+            "SELECT file_path, report_path, cold_path FROM output_videos WHERE session_id = $1"
+        )
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for (file_path, report_path, cold_path) in &outputs {
+            remove_output_files(file_path, report_path.as_deref(), cold_path.as_deref()).await?;
+        }
+
+        // Duplicate uploads share one stored file; keep it while another session still uses it
+        let uploads = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT uf.file_path FROM uploaded_files uf
+            WHERE uf.session_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM uploaded_files other
+                  WHERE other.file_path = uf.file_path AND other.session_id IS DISTINCT FROM $1
+              )
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        for path in &uploads {
+            remove_file(path).await?;
+        }
+
+        let output_dir = std::path::Path::new("outputs").join(session_uuid);
+        match tokio::fs::remove_dir_all(&output_dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Failed to remove {}: {}", output_dir.display(), e));
+            }
+            _ => {}
+        }
+
+        if let Some(qdrant) = &state.qdrant_client {
+            qdrant
+                .delete_points_matching("session_id", vec![session_uuid.to_string()])
+                .await
+                .map_err(|e| format!("Failed to delete Qdrant points: {}", e))?;
+        }
+        if let Some(astra) = &state.vector_db {
+            astra.delete_session_memories(session_uuid).await?;
+        }
+
+        // Messages, outputs and usage rows cascade; uploads would only be detached
+        let mut tx = state.db_pool.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM uploaded_files WHERE session_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM chat_memory_entries WHERE session_uuid = $1")
+            .bind(session_uuid)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM projects WHERE session_uuid = $1")
+            .bind(session_uuid)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM chat_sessions WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        tracing::info!("🗑️ Purged session {} ({} outputs, {} uploads)", session_uuid, outputs.len(), uploads.len());
+        Ok(())
+    }
+
+    async fn purge_output(
+        state: &AppState,
+        id: i32,
+        file_path: &str,
+        report_path: Option<&str>,
+        cold_path: Option<&str>,
+    ) -> Result<(), String> {
+        remove_output_files(file_path, report_path, cold_path).await?;
+
+        if let Some(qdrant) = &state.qdrant_client {
+            qdrant
+                .delete_points_matching("file_path", OutputVideoService::path_candidates(file_path))
+                .await
+                .map_err(|e| format!("Failed to delete Qdrant points: {}", e))?;
+        }
+
+        sqlx::query("DELETE FROM output_videos WHERE id = $1")
+            .bind(id)
+            .execute(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        tracing::info!("🗑️ Purged output {} ({})", id, file_path);
+        Ok(())
+    }
+}
+
+/// Remove an output (stored with or without the `outputs/` prefix), its render report and its cold copy
+async fn remove_output_files(file_path: &str, report_path: Option<&str>, cold_path: Option<&str>) -> Result<(), String> {
+    for candidate in OutputVideoService::path_candidates(file_path) {
+        remove_file(&candidate).await?;
+        remove_file(&OutputPreviewService::poster_for_file(&candidate).to_string_lossy()).await?;
+    }
+    for path in report_path.into_iter().chain(cold_path) {
+        remove_file(path).await?;
+    }
+    Ok(())
+}
+
+/// Remove a file; one that's already gone counts as removed
+async fn remove_file(path: &str) -> Result<(), String> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {}: {}", path, e)),
+        _ => Ok(()),
+    }
+}
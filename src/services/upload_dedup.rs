@@ -0,0 +1,279 @@
+// src/services/upload_dedup.rs
+// Duplicate upload detection: sha256 catches byte-identical re-uploads before they hit disk,
+// a perceptual hash of sampled frames catches the same footage re-encoded or resized
+use crate::models::file::{DuplicateUpload, UploadedFile};
+use crate::AppState;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Positions (fractions of duration) sampled for a video's perceptual hash
+const VIDEO_SAMPLE_POINTS: [f64; 5] = [0.1, 0.3, 0.5, 0.7, 0.9];
+
+/// Max differing bits per 64-bit frame hash for two files to count as the same footage
+const MAX_FRAME_DISTANCE: u32 = 8;
+
+const UPLOAD_COLUMNS: &str = "uf.id, uf.session_id, uf.original_name, uf.stored_name, uf.file_path, uf.file_size, uf.file_type, uf.mime_type, uf.upload_status, uf.created_at, uf.updated_at";
+
+pub struct UploadDedupService;
+
+impl UploadDedupService {
+    pub fn content_hash(data: &[u8]) -> String {
+        hex::encode(Sha256::digest(data))
+    }
+
+    /// Earliest upload by this user with identical bytes
+    pub async fn find_exact_duplicate(
+        pool: &PgPool,
+        user_id: i32,
+        content_hash: &str,
+    ) -> Result<Option<UploadedFile>, sqlx::Error> {
+        sqlx::query_as::<_, UploadedFile>(&format!(
+            r#"
+            SELECT {} FROM uploaded_files uf
+            JOIN chat_sessions cs ON uf.session_id = cs.id
+            WHERE cs.user_id = $1 AND uf.content_hash = $2
+            ORDER BY uf.created_at
+            LIMIT 1
+            "#,
+            UPLOAD_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(content_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record a re-upload that reuses an existing file instead of storing another copy
+    pub async fn insert_reused(
+        pool: &PgPool,
+        file_id: &str,
+        session_id: Option<i32>,
+        original_name: &str,
+        existing: &UploadedFile,
+        content_hash: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO uploaded_files (id, session_id, original_name, stored_name, file_path, file_size, file_type, mime_type, upload_status, content_hash, perceptual_hash, duplicate_of, duplicate_kind)
+            SELECT $1, $2, $3, stored_name, file_path, file_size, file_type, mime_type, 'uploaded', $4, perceptual_hash, id, 'exact'
+            FROM uploaded_files WHERE id = $5
+            "#,
+        )
+        .bind(file_id)
+        .bind(session_id)
+        .bind(original_name)
+        .bind(content_hash)
+        .bind(&existing.id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Compute the perceptual hash in the background and flag near-duplicates of the user's earlier uploads
+    pub fn spawn_fingerprint(state: Arc<AppState>, file_id: String, user_id: Option<i32>, file_path: String, file_type: String) {
+        if !matches!(file_type.as_str(), "video" | "image") {
+            return;
+        }
+        tokio::spawn(async move {
+            let hash = match Self::perceptual_hash(&file_path, &file_type).await {
+                Ok(hash) => hash,
+                Err(e) => {
+                    tracing::warn!("Perceptual hash failed for {}: {}", file_path, e);
+                    return;
+                }
+            };
+            if let Err(e) = sqlx::query("UPDATE uploaded_files SET perceptual_hash = $2 WHERE id = $1")
+                .bind(&file_id)
+                .bind(&hash)
+                .execute(&state.db_pool)
+                .await
+            {
+                tracing::warn!("Failed to store perceptual hash for {}: {}", file_id, e);
+                return;
+            }
+
+            let Some(user_id) = user_id else { return };
+            match Self::find_perceptual_duplicate(&state.db_pool, user_id, &file_id, &file_type, &hash).await {
+                Ok(Some(original)) => {
+                    tracing::info!("🔁 Upload {} looks like a re-encode of {} ({})", file_id, original.id, original.original_name);
+                    let _ = sqlx::query(
+                        "UPDATE uploaded_files SET duplicate_of = $2, duplicate_kind = 'perceptual' WHERE id = $1 AND duplicate_of IS NULL"
+                    )
+                    .bind(&file_id)
+                    .bind(&original.id)
+                    .execute(&state.db_pool)
+                    .await;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Duplicate lookup failed for {}: {}", file_id, e),
+            }
+        });
+    }
+
+    /// Closest earlier upload of the same type whose frames all hash within MAX_FRAME_DISTANCE
+    async fn find_perceptual_duplicate(
+        pool: &PgPool,
+        user_id: i32,
+        file_id: &str,
+        file_type: &str,
+        hash: &str,
+    ) -> Result<Option<UploadedFile>, sqlx::Error> {
+        let candidates = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT uf.id, uf.perceptual_hash FROM uploaded_files uf
+            JOIN chat_sessions cs ON uf.session_id = cs.id
+            WHERE cs.user_id = $1 AND uf.id <> $2 AND uf.file_type = $3
+              AND uf.perceptual_hash IS NOT NULL AND uf.duplicate_of IS NULL
+            ORDER BY uf.created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(file_id)
+        .bind(file_type)
+        .fetch_all(pool)
+        .await?;
+
+        let best = candidates
+            .iter()
+            .filter_map(|(id, other)| Self::hash_distance(hash, other).map(|d| (d, id)))
+            .min_by_key(|(distance, _)| *distance);
+        let Some((_, original_id)) = best else {
+            return Ok(None);
+        };
+
+        sqlx::query_as::<_, UploadedFile>(&format!("SELECT {} FROM uploaded_files uf WHERE uf.id = $1", UPLOAD_COLUMNS))
+            .bind(original_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Total differing bits, or None if the hashes are incomparable or any frame is too far apart
+    fn hash_distance(a: &str, b: &str) -> Option<u32> {
+        if a.len() != b.len() || !a.len().is_multiple_of(16) {
+            return None;
+        }
+        let mut total = 0;
+        for i in (0..a.len()).step_by(16) {
+            let x = u64::from_str_radix(&a[i..i + 16], 16).ok()?;
+            let y = u64::from_str_radix(&b[i..i + 16], 16).ok()?;
+            let distance = (x ^ y).count_ones();
+            if distance > MAX_FRAME_DISTANCE {
+                return None;
+            }
+            total += distance;
+        }
+        Some(total)
+    }
+
+    /// 64-bit difference hash per sampled frame (9x8 grayscale, each pixel compared to its right neighbour)
+    async fn perceptual_hash(file_path: &str, file_type: &str) -> Result<String, String> {
+        let seek_points = if file_type == "video" {
+            let path = file_path.to_string();
+            let duration = tokio::task::spawn_blocking(move || crate::core::analyze_video(&path))
+                .await
+                .map_err(|e| e.to_string())??
+                .duration_seconds;
+            VIDEO_SAMPLE_POINTS.iter().map(|p| Some(duration * p)).collect()
+        } else {
+            vec![None]
+        };
+
+        let mut hash = String::new();
+        for seek in seek_points {
+            let mut command = tokio::process::Command::new("ffmpeg");
+            if let Some(seconds) = seek {
+                command.arg("-ss").arg(format!("{:.3}", seconds));
+            }
+            let output = command
+                .arg("-i")
+                .arg(file_path)
+                .arg("-frames:v")
+                .arg("1")
+                .arg("-vf")
+                .arg("scale=9:8,format=gray")
+                .arg("-f")
+                .arg("rawvideo")
+                .arg("-")
+                .output()
+                .await
+                .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+            if !output.status.success() || output.stdout.len() < 72 {
+                return Err(format!("Could not sample a frame from {}", file_path));
+            }
+
+            let pixels = &output.stdout[..72];
+            let mut bits = 0u64;
+            for row in 0..8 {
+                for col in 0..8 {
+                    bits <<= 1;
+                    if pixels[row * 9 + col] < pixels[row * 9 + col + 1] {
+                        bits |= 1;
+                    }
+                }
+            }
+            hash.push_str(&format!("{:016x}", bits));
+        }
+        Ok(hash)
+    }
+
+    /// The user's uploads flagged as duplicates, newest first
+    pub async fn list_duplicates(pool: &PgPool, user_id: i32) -> Result<Vec<DuplicateUpload>, sqlx::Error> {
+        sqlx::query_as::<_, DuplicateUpload>(
+            r#"
+            SELECT uf.id, uf.original_name, uf.file_path, uf.file_size, uf.duplicate_kind,
+                   o.id AS original_id, o.original_name AS original_file_name, o.file_path AS original_file_path,
+                   uf.file_path <> o.file_path AS stores_copy, uf.created_at
+            FROM uploaded_files uf
+            JOIN uploaded_files o ON o.id = uf.duplicate_of
+            JOIN chat_sessions cs ON uf.session_id = cs.id
+            WHERE cs.user_id = $1
+            ORDER BY uf.created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Point a flagged duplicate at its original's file and delete the redundant copy; returns bytes freed
+    pub async fn reuse_original(pool: &PgPool, user_id: i32, file_id: &str) -> Result<i64, String> {
+        let duplicate = Self::list_duplicates(pool, user_id)
+            .await
+            .map_err(|e| format!("Failed to load duplicates: {}", e))?
+            .into_iter()
+            .find(|d| d.id == file_id)
+            .ok_or_else(|| format!("Upload {} is not a detected duplicate", file_id))?;
+        if !duplicate.stores_copy {
+            return Ok(0);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE uploaded_files SET file_path = o.file_path, stored_name = o.stored_name, file_size = o.file_size
+            FROM uploaded_files o
+            WHERE uploaded_files.id = $1 AND o.id = $2
+            "#,
+        )
+        .bind(&duplicate.id)
+        .bind(&duplicate.original_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to reuse original: {}", e))?;
+
+        // Only remove the copy when nothing else (e.g. an exact re-upload of it) still points at it
+        let still_used: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM uploaded_files WHERE file_path = $1")
+            .bind(&duplicate.file_path)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to check file references: {}", e))?;
+        if still_used > 0 {
+            return Ok(0);
+        }
+        if let Err(e) = tokio::fs::remove_file(&duplicate.file_path).await {
+            tracing::warn!("Failed to remove duplicate copy {}: {}", duplicate.file_path, e);
+            return Ok(0);
+        }
+        Ok(duplicate.file_size)
+    }
+}
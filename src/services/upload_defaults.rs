@@ -0,0 +1,284 @@
+// src/services/upload_defaults.rs
+// Per-user YouTube upload defaults: the channel, privacy, category, tag set and description template
+// an upload falls back to for whatever its request leaves out, so the upload flow (and the agent)
+// doesn't have to ask the same questions every time.
+use crate::models::file::OutputVideo;
+use crate::models::upload_defaults::{UpdateUploadDefaultsRequest, UploadDefaults};
+use crate::models::youtube::UploadToYouTubeRequest;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+pub const PRIVACY_STATUSES: [&str; 3] = ["public", "private", "unlisted"];
+/// Variables a description template can use
+pub const TEMPLATE_VARIABLES: [&str; 4] = ["title", "summary", "channel", "date"];
+
+const MAX_TEMPLATE_CHARS: usize = 5000;
+const MAX_DEFAULT_TAGS: usize = 15;
+
+/// Upload fields once the request, the user's defaults and the generated metadata are combined
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadFields {
+    pub title: String,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub privacy_status: String,
+    pub category: String,
+}
+
+pub struct UploadDefaultsService;
+
+impl UploadDefaultsService {
+    /// Apply an update on top of the current defaults (channel ownership is checked by `update`)
+    pub fn merge(current: &UploadDefaults, update: &UpdateUploadDefaultsRequest) -> Result<UploadDefaults, String> {
+        let mut merged = current.clone();
+        for field in &update.clear {
+            match field.as_str() {
+                "all" => merged = UploadDefaults::default(),
+                "channel_id" | "channel" => merged.channel_id = None,
+                "privacy_status" | "privacy" => merged.privacy_status = None,
+                "category" => merged.category = None,
+                "tags" => merged.tags = None,
+                "description_template" | "description" => merged.description_template = None,
+                other => {
+                    return Err(format!(
+                        "Unknown default '{}'. Clear one of: channel_id, privacy_status, category, tags, description_template, all",
+                        other
+                    ))
+                }
+            }
+        }
+
+        if let Some(channel_id) = update.channel_id {
+            merged.channel_id = Some(channel_id);
+        }
+        if let Some(privacy) = update.privacy_status.as_deref().map(|p| p.trim().to_lowercase()) {
+            if !PRIVACY_STATUSES.contains(&privacy.as_str()) {
+                return Err(format!("privacy_status must be one of: {}", PRIVACY_STATUSES.join(", ")));
+            }
+            merged.privacy_status = Some(privacy);
+        }
+        if let Some(category) = update.category.as_deref().map(str::trim) {
+            if category.is_empty() || !category.chars().all(|c| c.is_ascii_digit()) {
+                return Err("category must be a YouTube category id, e.g. \"22\"".to_string());
+            }
+            merged.category = Some(category.to_string());
+        }
+        if let Some(tags) = &update.tags {
+            let mut cleaned: Vec<String> = Vec::new();
+            for tag in tags {
+                let tag = tag.trim().trim_start_matches('#').to_lowercase();
+                if !tag.is_empty() && !cleaned.contains(&tag) {
+                    cleaned.push(tag);
+                }
+            }
+            if cleaned.len() > MAX_DEFAULT_TAGS {
+                return Err(format!("At most {} default tags", MAX_DEFAULT_TAGS));
+            }
+            merged.tags = (!cleaned.is_empty()).then_some(cleaned);
+        }
+        if let Some(template) = update.description_template.as_deref().map(str::trim) {
+            if template.chars().count() > MAX_TEMPLATE_CHARS {
+                return Err(format!("description_template must be at most {} characters", MAX_TEMPLATE_CHARS));
+            }
+            if let Some(unknown) = template_variables(template).find(|v| !TEMPLATE_VARIABLES.contains(v)) {
+                return Err(format!(
+                    "Unknown template variable {{{}}}. Use: {}",
+                    unknown,
+                    TEMPLATE_VARIABLES.iter().map(|v| format!("{{{}}}", v)).collect::<Vec<_>>().join(", ")
+                ));
+            }
+            merged.description_template = (!template.is_empty()).then(|| template.to_string());
+        }
+        Ok(merged)
+    }
+
+    /// A user's upload defaults; empty when none are set
+    pub async fn get(pool: &PgPool, user_id: i32) -> UploadDefaults {
+        sqlx::query_scalar::<_, Value>("SELECT upload_defaults FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Merge an update into the user's defaults and save them
+    pub async fn update(pool: &PgPool, user_id: i32, update: &UpdateUploadDefaultsRequest) -> Result<UploadDefaults, String> {
+        let current = Self::get(pool, user_id).await;
+        let merged = Self::merge(&current, update)?;
+
+        if let Some(channel_id) = update.channel_id {
+            let connected = sqlx::query_scalar::<_, i32>(
+                "SELECT id FROM connected_youtube_channels WHERE id = $1 AND user_id = $2 AND is_active = true"
+            )
+            .bind(channel_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+            if !connected {
+                return Err("Channel not found or not connected".to_string());
+            }
+        }
+
+        sqlx::query("UPDATE users SET upload_defaults = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(json!(merged))
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to save upload defaults: {}", e))?;
+        Ok(merged)
+    }
+
+    /// Combine an upload request with the user's defaults and the metadata generated for the output.
+    /// The request wins; default tags are added to the generated ones; the description template is
+    /// filled with the generated description as {summary}.
+    pub fn fill(
+        request: &UploadToYouTubeRequest,
+        defaults: &UploadDefaults,
+        generated: Option<&OutputVideo>,
+        channel_name: &str,
+        date: chrono::NaiveDate,
+    ) -> UploadFields {
+        let title = match generated.and_then(|o| o.suggested_title.clone()) {
+            Some(suggested) if request.title.trim().is_empty() => suggested,
+            _ => request.title.trim().to_string(),
+        };
+        let summary = generated.and_then(|o| o.description.clone());
+
+        let description = request.description.clone().or_else(|| match &defaults.description_template {
+            Some(template) => Some(render_template(
+                template,
+                &[
+                    ("title", title.as_str()),
+                    ("summary", summary.as_deref().unwrap_or("")),
+                    ("channel", channel_name),
+                    ("date", &date.format("%Y-%m-%d").to_string()),
+                ],
+            )),
+            None => summary.clone(),
+        });
+
+        let tags = request.tags.clone().or_else(|| {
+            let mut tags: Vec<String> = defaults.tags.clone().unwrap_or_default();
+            for tag in generated.map(|o| o.tags.clone()).unwrap_or_default() {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            (!tags.is_empty()).then_some(tags)
+        });
+
+        UploadFields {
+            title,
+            description,
+            tags,
+            privacy_status: request
+                .privacy_status
+                .clone()
+                .or_else(|| defaults.privacy_status.clone())
+                .unwrap_or_else(|| "private".to_string()),
+            category: request
+                .category
+                .clone()
+                .or_else(|| defaults.category.clone())
+                .unwrap_or_else(|| "22".to_string()),
+        }
+    }
+
+    /// One-line summary, e.g. "channel 3, unlisted, category 22, 4 tags, description template"
+    pub fn describe(defaults: &UploadDefaults) -> String {
+        let mut parts = Vec::new();
+        if let Some(channel_id) = defaults.channel_id {
+            parts.push(format!("channel {}", channel_id));
+        }
+        if let Some(privacy) = &defaults.privacy_status {
+            parts.push(privacy.clone());
+        }
+        if let Some(category) = &defaults.category {
+            parts.push(format!("category {}", category));
+        }
+        if let Some(tags) = &defaults.tags {
+            parts.push(format!("tags: {}", tags.join(", ")));
+        }
+        if defaults.description_template.is_some() {
+            parts.push("description template".to_string());
+        }
+        if parts.is_empty() {
+            "none".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Names inside `{...}` in a template
+fn template_variables(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|part| {
+        let (name, _) = part.split_once('}')?;
+        (!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')).then_some(name)
+    })
+}
+
+/// Fill `{name}` variables, dropping the blank lines an empty value leaves behind
+fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = template.to_string();
+    for (name, value) in values {
+        rendered = rendered.replace(&format!("{{{}}}", name), value);
+    }
+    let mut lines: Vec<&str> = Vec::new();
+    for line in rendered.lines() {
+        if line.trim().is_empty() && lines.last().is_none_or(|last| last.trim().is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_fall_back_to_defaults_and_fill_the_template() {
+        let defaults = UploadDefaultsService::merge(
+            &UploadDefaults::default(),
+            &UpdateUploadDefaultsRequest {
+                channel_id: Some(3),
+                privacy_status: Some("Unlisted".into()),
+                tags: Some(vec!["#Cooking".into(), "cooking".into(), "recipes".into()]),
+                description_template: Some("{title}\n\n{summary}\n\nRecorded {date} for {channel}".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(defaults.privacy_status.as_deref(), Some("unlisted"));
+        assert_eq!(defaults.tags, Some(vec!["cooking".to_string(), "recipes".to_string()]));
+        assert!(UploadDefaultsService::merge(&defaults, &UpdateUploadDefaultsRequest {
+            description_template: Some("{views} views".into()),
+            ..Default::default()
+        })
+        .is_err());
+
+        let request: UploadToYouTubeRequest =
+            serde_json::from_value(json!({ "video_path": "outputs/pasta.mp4", "title": "Fresh pasta" })).unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 2, 3).unwrap();
+        let fields = UploadDefaultsService::fill(&request, &defaults, None, "Kitchen", date);
+        assert_eq!(fields.description.as_deref(), Some("Fresh pasta\n\nRecorded 2026-02-03 for Kitchen"));
+        assert_eq!(fields.tags, defaults.tags);
+        assert_eq!(fields.privacy_status, "unlisted");
+        assert_eq!(fields.category, "22");
+
+        let request: UploadToYouTubeRequest = serde_json::from_value(
+            json!({ "video_path": "outputs/pasta.mp4", "title": "Fresh pasta", "privacy_status": "public", "tags": ["pasta"] }),
+        )
+        .unwrap();
+        let fields = UploadDefaultsService::fill(&request, &defaults, None, "Kitchen", date);
+        assert_eq!(fields.privacy_status, "public");
+        assert_eq!(fields.tags, Some(vec!["pasta".to_string()]));
+    }
+}
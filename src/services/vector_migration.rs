@@ -0,0 +1,379 @@
+// src/services/vector_migration.rs
+// Copies the chat memory collection (vectors + payloads) from one vector backend to another.
+// Each backend implements VectorMemoryStore; a migration streams the source page by page, upserts
+// every page into the target, reads it back to verify it, and checkpoints the source's page token
+// in vector_migrations so an interrupted run can be resumed from the last copied page.
+use crate::models::vector_migration::VectorMigration;
+use crate::AppState;
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Ids kept per migration in failed_ids / mismatched_ids; counts beyond that are only logged
+const MAX_RECORDED_IDS: i32 = 1000;
+
+const DEFAULT_BATCH_SIZE: i32 = 100;
+const MAX_BATCH_SIZE: i32 = 1000;
+
+/// One stored memory: its id, embedding and payload (everything else the backend keeps for it)
+#[derive(Debug, Clone)]
+pub struct VectorRecord {
+    pub id: String,
+    pub vector: Vec<f32>,
+    pub payload: Map<String, Value>,
+}
+
+/// A page of a full scan and the token of the page after it (None when this was the last)
+#[derive(Debug, Default)]
+pub struct VectorPage {
+    pub records: Vec<VectorRecord>,
+    pub next: Option<String>,
+}
+
+/// A vector backend the chat memory can be copied out of and into
+#[async_trait]
+pub trait VectorMemoryStore: Send + Sync {
+    /// Create the collection if it doesn't exist yet
+    async fn ensure_collection(&self) -> Result<(), String>;
+    /// Dimension the collection's vectors must have, if the backend reports one
+    async fn dimension(&self) -> Result<Option<usize>, String>;
+    /// A page of every record, starting at `cursor` (None = from the start)
+    async fn scan(&self, cursor: Option<&str>, limit: usize) -> Result<VectorPage, String>;
+    /// Insert or replace records by id
+    async fn upsert(&self, records: &[VectorRecord]) -> Result<(), String>;
+    /// The records with these ids that exist
+    async fn fetch(&self, ids: &[String]) -> Result<Vec<VectorRecord>, String>;
+    async fn count(&self) -> Result<u64, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VectorBackend {
+    Astra,
+    Qdrant,
+}
+
+impl VectorBackend {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "astra" | "astradb" => Ok(Self::Astra),
+            "qdrant" => Ok(Self::Qdrant),
+            "pgvector" => Err("pgvector isn't supported as a vector backend yet".to_string()),
+            other => Err(format!("Unknown vector backend '{}' (expected astra or qdrant)", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Astra => "astra",
+            Self::Qdrant => "qdrant",
+        }
+    }
+
+    /// The configured client for this backend
+    fn store(self, state: &AppState) -> Result<&dyn VectorMemoryStore, String> {
+        match self {
+            Self::Astra => state
+                .vector_db
+                .as_ref()
+                .map(|client| client as &dyn VectorMemoryStore)
+                .ok_or_else(|| "AstraDB isn't configured".to_string()),
+            Self::Qdrant => state
+                .qdrant_client
+                .as_ref()
+                .map(|client| client as &dyn VectorMemoryStore)
+                .ok_or_else(|| "Qdrant isn't configured".to_string()),
+        }
+    }
+}
+
+/// Migrations being run by this process, so one can't be resumed while it is still going
+fn running() -> &'static Mutex<HashSet<i32>> {
+    static RUNNING: OnceLock<Mutex<HashSet<i32>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// What a batch did, for the checkpoint
+#[derive(Default)]
+struct BatchOutcome {
+    copied: i64,
+    verified: i64,
+    failed_ids: Vec<String>,
+    mismatched_ids: Vec<String>,
+}
+
+pub struct VectorMigrationService;
+
+impl VectorMigrationService {
+    pub async fn list(pool: &PgPool) -> Result<Vec<VectorMigration>, sqlx::Error> {
+        sqlx::query_as::<_, VectorMigration>("SELECT * FROM vector_migrations ORDER BY created_at DESC LIMIT 50")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn get(pool: &PgPool, id: i32) -> Result<Option<VectorMigration>, sqlx::Error> {
+        sqlx::query_as::<_, VectorMigration>("SELECT * FROM vector_migrations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Record a new migration and start copying in the background
+    pub async fn start(
+        state: Arc<AppState>,
+        source: &str,
+        target: &str,
+        batch_size: Option<i32>,
+        reembed: bool,
+        started_by: i32,
+    ) -> Result<VectorMigration, String> {
+        let (source, target) = (VectorBackend::parse(source)?, VectorBackend::parse(target)?);
+        if source == target {
+            return Err("Source and target must be different backends".to_string());
+        }
+        source.store(&state)?;
+        target.store(&state)?;
+        let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        if !(1..=MAX_BATCH_SIZE).contains(&batch_size) {
+            return Err(format!("batch_size must be between 1 and {}", MAX_BATCH_SIZE));
+        }
+
+        let migration = sqlx::query_as::<_, VectorMigration>(
+            "INSERT INTO vector_migrations (source, target, batch_size, reembed, started_by)
+             VALUES ($1, $2, $3, $4, $5) RETURNING *",
+        )
+        .bind(source.as_str())
+        .bind(target.as_str())
+        .bind(batch_size)
+        .bind(reembed)
+        .bind(started_by)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| format!("Failed to record vector migration: {}", e))?;
+
+        Self::spawn(state, migration.id);
+        Ok(migration)
+    }
+
+    /// Continue a failed or interrupted migration from its last checkpoint
+    pub async fn resume(state: Arc<AppState>, id: i32) -> Result<Option<VectorMigration>, String> {
+        let Some(migration) = Self::get(&state.db_pool, id).await.map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        if migration.status == "completed" {
+            return Err("This migration has already completed".to_string());
+        }
+        if running().lock().unwrap().contains(&id) {
+            return Err("This migration is still running".to_string());
+        }
+
+        let migration = sqlx::query_as::<_, VectorMigration>(
+            "UPDATE vector_migrations SET status = 'running', error = NULL, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Self::spawn(state, id);
+        Ok(Some(migration))
+    }
+
+    fn spawn(state: Arc<AppState>, id: i32) {
+        running().lock().unwrap().insert(id);
+        tokio::spawn(async move {
+            let result = Self::run(&state, id).await;
+            running().lock().unwrap().remove(&id);
+            match result {
+                Ok(()) => tracing::info!("🧠 Vector migration {} completed", id),
+                Err(e) => {
+                    tracing::error!("🧠 Vector migration {} failed: {}", id, e);
+                    let _ = sqlx::query(
+                        "UPDATE vector_migrations SET status = 'failed', error = $2, updated_at = NOW() WHERE id = $1",
+                    )
+                    .bind(id)
+                    .bind(&e)
+                    .execute(&state.db_pool)
+                    .await;
+                }
+            }
+        });
+    }
+
+    async fn run(state: &AppState, id: i32) -> Result<(), String> {
+        let pool = &state.db_pool;
+        let migration = Self::get(pool, id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Migration not found".to_string())?;
+        let source = VectorBackend::parse(&migration.source)?.store(state)?;
+        let target = VectorBackend::parse(&migration.target)?.store(state)?;
+
+        target.ensure_collection().await?;
+        let dimension = target.dimension().await?;
+        if migration.source_count.is_none() {
+            let source_count = source.count().await? as i64;
+            sqlx::query("UPDATE vector_migrations SET source_count = $2 WHERE id = $1")
+                .bind(id)
+                .bind(source_count)
+                .execute(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let mut cursor = migration.cursor.clone();
+        let mut scan_complete = migration.scan_complete;
+        while !scan_complete {
+            let page = source.scan(cursor.as_deref(), migration.batch_size as usize).await?;
+            let outcome = Self::copy_batch(state, target, page.records, dimension, migration.reembed).await?;
+            cursor = page.next;
+            scan_complete = cursor.is_none();
+            Self::checkpoint(pool, id, cursor.as_deref(), scan_complete, &outcome).await?;
+        }
+
+        let target_count = target.count().await? as i64;
+        sqlx::query(
+            "UPDATE vector_migrations
+             SET status = 'completed', target_count = $2, updated_at = NOW(), completed_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(target_count)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Upsert a page into the target and read it back. Records that can't be written (wrong
+    /// dimension, unusable id) are failed individually; a target error fails the whole batch so it
+    /// is retried on resume.
+    async fn copy_batch(
+        state: &AppState,
+        target: &dyn VectorMemoryStore,
+        records: Vec<VectorRecord>,
+        dimension: Option<usize>,
+        reembed: bool,
+    ) -> Result<BatchOutcome, String> {
+        let mut outcome = BatchOutcome::default();
+        let mut ready = Vec::with_capacity(records.len());
+        for mut record in records {
+            if let Some(dimension) = dimension.filter(|d| *d != record.vector.len()) {
+                if !reembed {
+                    outcome.failed_ids.push(record.id);
+                    continue;
+                }
+                match Self::reembed(state, &record, dimension).await {
+                    Ok(vector) => record.vector = vector,
+                    Err(e) => {
+                        tracing::warn!("Failed to re-embed memory {}: {}", record.id, e);
+                        outcome.failed_ids.push(record.id);
+                        continue;
+                    }
+                }
+            }
+            ready.push(record);
+        }
+        if ready.is_empty() {
+            return Ok(outcome);
+        }
+
+        target.upsert(&ready).await?;
+        outcome.copied = ready.len() as i64;
+
+        let ids: Vec<String> = ready.iter().map(|r| r.id.clone()).collect();
+        let written: HashMap<String, VectorRecord> =
+            target.fetch(&ids).await?.into_iter().map(|r| (r.id.clone(), r)).collect();
+        for record in &ready {
+            match written.get(&record.id) {
+                Some(copy) if Self::same_record(record, copy) => outcome.verified += 1,
+                _ => outcome.mismatched_ids.push(record.id.clone()),
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// A fresh embedding of the memory's user message from the provider matching the dimension
+    async fn reembed(state: &AppState, record: &VectorRecord, dimension: usize) -> Result<Vec<f32>, String> {
+        let text = record
+            .payload
+            .get("user_message")
+            .and_then(|v| v.as_str())
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| "no user_message to embed".to_string())?;
+        let vector = match (&state.voyage_embeddings, &state.gemini_client) {
+            (Some(voyage), _) if dimension == 1024 => voyage.generate_single_embedding(text.to_string()).await?,
+            (_, Some(gemini)) if dimension == 768 => gemini.embed_content(text).await.map_err(|e| e.to_string())?,
+            _ => return Err(format!("no configured embedding provider produces {}-dimensional vectors", dimension)),
+        };
+        if vector.len() != dimension {
+            return Err(format!("embedding has {} dimensions, expected {}", vector.len(), dimension));
+        }
+        Ok(vector)
+    }
+
+    fn same_record(written: &VectorRecord, read: &VectorRecord) -> bool {
+        written.vector.len() == read.vector.len()
+            && written
+                .vector
+                .iter()
+                .zip(&read.vector)
+                .all(|(a, b)| (a - b).abs() <= 1e-5 * a.abs().max(1.0))
+            && written.payload.len() == read.payload.len()
+            && written
+                .payload
+                .iter()
+                .all(|(key, value)| read.payload.get(key).is_some_and(|other| Self::same_value(value, other)))
+    }
+
+    /// JSON equality where 3 and 3.0 match, since backends differ in how they store numbers
+    fn same_value(a: &Value, b: &Value) -> bool {
+        match (a, b) {
+            (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+            (Value::Array(x), Value::Array(y)) => x.len() == y.len() && x.iter().zip(y).all(|(x, y)| Self::same_value(x, y)),
+            (Value::Object(x), Value::Object(y)) => {
+                x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| Self::same_value(v, w)))
+            }
+            _ => a == b,
+        }
+    }
+
+    async fn checkpoint(
+        pool: &PgPool,
+        id: i32,
+        cursor: Option<&str>,
+        scan_complete: bool,
+        outcome: &BatchOutcome,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE vector_migrations
+             SET cursor = $2, scan_complete = $3, copied = copied + $4, verified = verified + $5,
+                 failed_ids = (failed_ids || $6::TEXT[])[1:$8],
+                 mismatched_ids = (mismatched_ids || $7::TEXT[])[1:$8],
+                 updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(scan_complete)
+        .bind(outcome.copied)
+        .bind(outcome.verified)
+        .bind(&outcome.failed_ids)
+        .bind(&outcome.mismatched_ids)
+        .bind(MAX_RECORDED_IDS)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to checkpoint vector migration: {}", e))?;
+
+        if !outcome.failed_ids.is_empty() || !outcome.mismatched_ids.is_empty() {
+            tracing::warn!(
+                "🧠 Vector migration {}: {} records failed, {} didn't verify in this batch",
+                id,
+                outcome.failed_ids.len(),
+                outcome.mismatched_ids.len()
+            );
+        }
+        Ok(())
+    }
+}
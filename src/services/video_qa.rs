@@ -0,0 +1,215 @@
+// src/services/video_qa.rs
+// Question answering over a video: finds the moments likely to matter (vectorized frame
+// descriptions + transcript matches, topped up with evenly spaced samples), shows those frames
+// and the nearby transcript to a vision model, and asks for an answer that cites timestamps.
+use crate::services::VideoVectorizationService;
+use crate::types::TranscriptWord;
+use crate::AppState;
+use std::sync::Arc;
+
+/// Frames shown to the model by default / at most
+pub const DEFAULT_MAX_FRAMES: usize = 8;
+pub const MAX_FRAMES_LIMIT: usize = 16;
+
+/// Moments closer together than this are sampled once
+const MIN_GAP_SECONDS: f64 = 2.0;
+
+/// Transcript included on each side of a sampled frame
+const TRANSCRIPT_WINDOW_SECONDS: f64 = 5.0;
+
+const STOP_WORDS: [&str; 24] = [
+    "what", "when", "where", "which", "who", "whom", "does", "did", "the", "and", "that", "this", "there", "their",
+    "with", "from", "into", "about", "point", "video", "time", "appear", "appears", "show",
+];
+
+/// Why a moment was sampled
+#[derive(Debug, Clone, PartialEq)]
+enum MomentSource {
+    Visual(String),
+    Speech,
+    Coverage,
+}
+
+#[derive(Debug, Clone)]
+struct Moment {
+    seconds: f64,
+    source: MomentSource,
+}
+
+pub struct VideoQaService;
+
+impl VideoQaService {
+    /// Answer a question about a video with [mm:ss] citations
+    pub async fn ask(state: &Arc<AppState>, video_path: &str, question: &str, max_frames: usize) -> Result<String, String> {
+        let gemini_client = state.gemini_client.as_ref().ok_or("Gemini client not available for video Q&A")?;
+        let max_frames = max_frames.clamp(1, MAX_FRAMES_LIMIT);
+
+        let path = video_path.to_string();
+        let duration = tokio::task::spawn_blocking(move || crate::core::get_video_duration(&path))
+            .await
+            .map_err(|e| e.to_string())??;
+
+        // Visual hits from the vector store (only for videos vectorized with file paths)
+        let mut moments = Vec::new();
+        match VideoVectorizationService::search_video_frames(question, video_path, max_frames, state).await {
+            Ok(hits) => moments.extend(hits.iter().filter_map(|hit| {
+                let seconds = hit.get("timestamp_seconds")?.as_f64()?;
+                let description = hit.get("content").and_then(|v| v.as_str()).unwrap_or_default();
+                Some(Moment { seconds, source: MomentSource::Visual(description.to_string()) })
+            })),
+            Err(e) => tracing::warn!("Frame search unavailable for {}: {}", video_path, e),
+        }
+
+        // Spoken mentions of the question's keywords
+        let words = match crate::agent::tool_executor::transcribe_media_words(video_path, false, state).await {
+            Ok(words) => words,
+            Err(e) => {
+                tracing::warn!("No transcript for {}: {}", video_path, e);
+                Vec::new()
+            }
+        };
+        let keywords = keywords(question);
+        moments.extend(
+            words
+                .iter()
+                .filter(|w| keywords.iter().any(|k| normalize(&w.text) == *k))
+                .map(|w| Moment { seconds: w.start, source: MomentSource::Speech }),
+        );
+
+        let moments = select_moments(moments, duration, max_frames);
+
+        let mut frames = Vec::new();
+        let mut sampled = Vec::new();
+        for moment in &moments {
+            match extract_frame(video_path, moment.seconds).await {
+                Ok(bytes) => {
+                    let mut label = format!("Frame at [{}]", format_timestamp(moment.seconds));
+                    if let MomentSource::Visual(description) = &moment.source {
+                        label.push_str(&format!(" (indexed as: {})", truncate(description, 200)));
+                    }
+                    let nearby = transcript_near(&words, moment.seconds);
+                    if !nearby.is_empty() {
+                        label.push_str(&format!("\nSpoken around this frame: \"{}\"", nearby));
+                    }
+                    frames.push((label, bytes));
+                    sampled.push(moment.clone());
+                }
+                Err(e) => tracing::warn!("Could not sample {} at {:.1}s: {}", video_path, moment.seconds, e),
+            }
+        }
+        if frames.is_empty() {
+            return Err(format!("Could not extract any frames from {}", video_path));
+        }
+
+        let prompt = format!(
+            "You are answering a question about a {}-long video using frames sampled from it. \
+             Each frame is labelled with its timestamp and the speech around it.\n\n\
+             Question: {}\n\n\
+             Answer directly. Cite every claim with the timestamp(s) of the frames that support it, \
+             formatted as [mm:ss]. If the frames only narrow it down, give the range between the closest \
+             frames. If the frames don't show the answer, say so rather than guessing.",
+            format_timestamp(duration),
+            question
+        );
+        let answer = gemini_client
+            .analyze_labeled_images(&frames, &prompt)
+            .await
+            .map_err(|e| format!("Vision model call failed: {}", e))?;
+
+        let visual = sampled.iter().filter(|m| matches!(m.source, MomentSource::Visual(_))).count();
+        let speech = sampled.iter().filter(|m| m.source == MomentSource::Speech).count();
+        let timestamps: Vec<String> = sampled.iter().map(|m| format_timestamp(m.seconds)).collect();
+        Ok(format!(
+            "{}\n\n🔎 Looked at {} frames ({} from the visual index, {} from the transcript, {} for coverage): {}",
+            answer.trim(),
+            sampled.len(),
+            visual,
+            speech,
+            sampled.len() - visual - speech,
+            timestamps.join(", ")
+        ))
+    }
+}
+
+/// Pick up to max_frames moments: relevant ones first, then evenly spaced fill, in time order
+fn select_moments(candidates: Vec<Moment>, duration: f64, max_frames: usize) -> Vec<Moment> {
+    let last_frame = (duration - 0.1).max(0.0);
+    let mut selected: Vec<Moment> = Vec::new();
+    let coverage = (0..max_frames).map(|i| Moment {
+        seconds: duration * (i as f64 + 0.5) / max_frames as f64,
+        source: MomentSource::Coverage,
+    });
+
+    for mut moment in candidates.into_iter().chain(coverage) {
+        if selected.len() >= max_frames {
+            break;
+        }
+        moment.seconds = moment.seconds.clamp(0.0, last_frame);
+        if selected.iter().all(|m| (m.seconds - moment.seconds).abs() >= MIN_GAP_SECONDS) {
+            selected.push(moment);
+        }
+    }
+    selected.sort_by(|a, b| a.seconds.total_cmp(&b.seconds));
+    selected
+}
+
+fn keywords(question: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in question.split_whitespace().map(normalize) {
+        if word.chars().count() > 3 && !STOP_WORDS.contains(&word.as_str()) && !keywords.contains(&word) {
+            keywords.push(word);
+        }
+    }
+    keywords
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+fn transcript_near(words: &[TranscriptWord], seconds: f64) -> String {
+    let text: Vec<&str> = words
+        .iter()
+        .filter(|w| w.end >= seconds - TRANSCRIPT_WINDOW_SECONDS && w.start <= seconds + TRANSCRIPT_WINDOW_SECONDS)
+        .map(|w| w.text.as_str())
+        .collect();
+    text.join(" ")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}…", kept)
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    format!("{:02}:{:02}", total / 60, total % 60)
+}
+
+/// One JPEG frame at the given time
+async fn extract_frame(video_path: &str, seconds: f64) -> Result<Vec<u8>, String> {
+    let output = tokio::process::Command::new("ffmpeg")
+        .arg("-ss")
+        .arg(format!("{:.3}", seconds))
+        .arg("-i")
+        .arg(video_path)
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-vf")
+        .arg("scale=768:-2")
+        .arg("-f")
+        .arg("image2pipe")
+        .arg("-vcodec")
+        .arg("mjpeg")
+        .arg("-")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or("no frame decoded").to_string());
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,269 @@
+// src/services/video_summary.rs
+// Summaries of recordings too long for one prompt ("summarize this 3-hour webinar"). The transcript
+// is cut into chunks at pauses and each chunk is summarised on its own (map); the chunk summaries are
+// then merged a few at a time, level by level, until few enough are left for one overview (reduce).
+// The depth picks how much of that hierarchy is shown under the overview.
+use crate::claude_client::ClaudeClient;
+use crate::types::TranscriptWord;
+use crate::AppState;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::sync::Arc;
+
+/// Target length of a transcript chunk; each is summarised in one call
+const CHUNK_SECONDS: f64 = 600.0;
+/// A chunk ends at the first pause this long after reaching `CHUNK_SECONDS`...
+const MIN_PAUSE_SECONDS: f64 = 0.4;
+/// ...or here, if the speaker never pauses
+const MAX_CHUNK_SECONDS: f64 = 750.0;
+/// Summaries merged into one at each reduce level
+const MERGE_FAN_IN: usize = 5;
+/// Chunk summaries requested at once
+const MAP_CONCURRENCY: usize = 4;
+/// Timestamp markers in the transcript shown to the model, so summaries can cite times
+const MARKER_SECONDS: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SummaryDepth {
+    /// One paragraph
+    Brief,
+    /// Overview, key points and a coarse outline
+    Standard,
+    /// Overview, key points and a summary of every chunk
+    Detailed,
+}
+
+impl SummaryDepth {
+    pub fn parse(depth: &str) -> Option<Self> {
+        match depth.trim().to_lowercase().as_str() {
+            "brief" | "short" => Some(Self::Brief),
+            "standard" | "" => Some(Self::Standard),
+            "detailed" | "full" => Some(Self::Detailed),
+            _ => None,
+        }
+    }
+
+    fn overview_instructions(&self) -> &'static str {
+        match self {
+            Self::Brief => "Write one paragraph (at most 120 words) saying what the recording is about and its main takeaways.",
+            Self::Standard | Self::Detailed => {
+                "Write a short overview paragraph, then a 'Key points' list of the 5-10 most important points, \
+                 decisions or takeaways, each with the [h:mm:ss] time it comes up."
+            }
+        }
+    }
+}
+
+/// A stretch of the recording and what happens in it
+#[derive(Debug, Clone)]
+struct Section {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+pub struct VideoSummaryService;
+
+impl VideoSummaryService {
+    /// Summarise a recording of any length; `focus` narrows what the summaries keep (e.g. "pricing questions")
+    pub async fn summarize(state: &Arc<AppState>, video_path: &str, depth: SummaryDepth, focus: Option<&str>) -> Result<String, String> {
+        let claude = state.claude_client.as_ref().ok_or("Claude client not available for summarization")?;
+        let focus = focus.map(str::trim).filter(|f| !f.is_empty());
+
+        let words = crate::agent::tool_executor::transcribe_media_words(video_path, false, state).await?;
+        if words.is_empty() {
+            return Err("No speech detected - nothing to summarize".to_string());
+        }
+
+        let chunks = chunk_transcript(&words);
+        let total = chunks.len();
+        tracing::info!("📝 Summarizing {} in {} chunk(s)", video_path, total);
+
+        // Map: every chunk on its own
+        let chunk_summaries: Vec<Section> = stream::iter(chunks.into_iter().enumerate())
+            .map(|(index, chunk)| summarize_chunk(claude, chunk, index, total, focus))
+            .buffered(MAP_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        // Reduce: merge neighbours until one overview's worth is left
+        let mut levels = vec![chunk_summaries];
+        while levels.last().map_or(0, Vec::len) > MERGE_FAN_IN {
+            let groups = merge_groups(levels.last().expect("levels is never empty"));
+            let merged: Vec<Section> = stream::iter(groups)
+                .map(|group| merge_sections(claude, group, focus))
+                .buffered(MAP_CONCURRENCY)
+                .try_collect()
+                .await?;
+            levels.push(merged);
+        }
+
+        let top = levels.last().expect("levels is never empty");
+        let overview = write_overview(claude, top, depth, focus).await?;
+
+        let duration = words.last().map(|w| w.end).unwrap_or(0.0);
+        let mut summary = format!("📝 **Summary of {}** ({}, {} part(s))\n\n{}\n", video_path, format_timestamp(duration), total, overview.trim());
+        let outline = match depth {
+            SummaryDepth::Brief => None,
+            // The first merge level reads as chapters; short recordings have only the chunks
+            SummaryDepth::Standard => levels.get(1).or(levels.first()),
+            SummaryDepth::Detailed => levels.first(),
+        };
+        if let Some(sections) = outline {
+            summary.push_str("\n**Outline**\n");
+            for section in sections {
+                summary.push_str(&format!(
+                    "[{} - {}] {}\n",
+                    format_timestamp(section.start),
+                    format_timestamp(section.end),
+                    section.text.trim()
+                ));
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// Cut a transcript into chunks of about `CHUNK_SECONDS`, at pauses where possible
+fn chunk_transcript(words: &[TranscriptWord]) -> Vec<Section> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&TranscriptWord> = Vec::new();
+    for word in words {
+        if let (Some(first), Some(last)) = (current.first(), current.last()) {
+            let elapsed = word.start - first.start;
+            let pause = word.start - last.end;
+            if (elapsed >= CHUNK_SECONDS && pause >= MIN_PAUSE_SECONDS) || elapsed >= MAX_CHUNK_SECONDS {
+                chunks.push(timestamped_section(&current));
+                current.clear();
+            }
+        }
+        current.push(word);
+    }
+    if !current.is_empty() {
+        chunks.push(timestamped_section(&current));
+    }
+    chunks
+}
+
+/// The words of a chunk as text, with a [h:mm:ss] marker every `MARKER_SECONDS`
+fn timestamped_section(words: &[&TranscriptWord]) -> Section {
+    let mut text = String::new();
+    let mut next_marker = f64::MIN;
+    for word in words {
+        if word.start >= next_marker {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("[{}] ", format_timestamp(word.start)));
+            next_marker = word.start + MARKER_SECONDS;
+        } else {
+            text.push(' ');
+        }
+        text.push_str(&word.text);
+    }
+    Section {
+        start: words.first().map_or(0.0, |w| w.start),
+        end: words.last().map_or(0.0, |w| w.end),
+        text,
+    }
+}
+
+/// Neighbouring sections in groups of `MERGE_FAN_IN`; a lone leftover joins the group before it
+fn merge_groups(sections: &[Section]) -> Vec<Vec<Section>> {
+    let mut groups: Vec<Vec<Section>> = sections.chunks(MERGE_FAN_IN).map(<[Section]>::to_vec).collect();
+    if groups.len() > 1 && groups.last().is_some_and(|g| g.len() == 1) {
+        let leftover = groups.pop().expect("checked above");
+        groups.last_mut().expect("checked above").extend(leftover);
+    }
+    groups
+}
+
+fn focus_instruction(focus: Option<&str>) -> String {
+    focus.map(|f| format!(" Pay particular attention to: {}.", f)).unwrap_or_default()
+}
+
+async fn summarize_chunk(claude: &ClaudeClient, chunk: Section, index: usize, total: usize, focus: Option<&str>) -> Result<Section, String> {
+    let prompt = format!(
+        "This is part {} of {} of a long recording's transcript ({} - {}). Summarize what is said in this part in \
+         3-6 sentences. Keep names, numbers, decisions and questions raised, and cite the [h:mm:ss] time of important \
+         moments.{} Respond with only the summary.\n\nTranscript:\n{}",
+        index + 1,
+        total,
+        format_timestamp(chunk.start),
+        format_timestamp(chunk.end),
+        focus_instruction(focus),
+        chunk.text
+    );
+    let text = claude.generate_text(&prompt).await.map_err(|e| format!("Failed to summarize part {}: {}", index + 1, e))?;
+    Ok(Section { text, ..chunk })
+}
+
+fn sections_text(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|s| format!("[{} - {}]\n{}", format_timestamp(s.start), format_timestamp(s.end), s.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+async fn merge_sections(claude: &ClaudeClient, group: Vec<Section>, focus: Option<&str>) -> Result<Section, String> {
+    let start = group.first().map_or(0.0, |s| s.start);
+    let end = group.last().map_or(0.0, |s| s.end);
+    let prompt = format!(
+        "These are summaries of consecutive parts of a long recording, covering {} - {}. Combine them into one summary \
+         of 4-8 sentences for the whole stretch, keeping the most important points and their [h:mm:ss] times.{} \
+         Respond with only the summary.\n\n{}",
+        format_timestamp(start),
+        format_timestamp(end),
+        focus_instruction(focus),
+        sections_text(&group)
+    );
+    let text = claude.generate_text(&prompt).await.map_err(|e| format!("Failed to merge summaries: {}", e))?;
+    Ok(Section { start, end, text })
+}
+
+async fn write_overview(claude: &ClaudeClient, sections: &[Section], depth: SummaryDepth, focus: Option<&str>) -> Result<String, String> {
+    let prompt = format!(
+        "These are summaries of the consecutive parts of a recording, in order. {}{} Respond with only the summary, \
+         in markdown.\n\n{}",
+        depth.overview_instructions(),
+        focus_instruction(focus),
+        sections_text(sections)
+    );
+    claude.generate_text(&prompt).await.map_err(|e| format!("Failed to write the overview: {}", e))
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    format!("{}:{:02}:{:02}", total / 3600, (total % 3600) / 60, total % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_transcripts_are_chunked_at_pauses_and_merged_in_levels() {
+        // Three hours of speech, one word every 0.5s with a longer pause every 10 words
+        let words: Vec<TranscriptWord> = (0..21_600)
+            .map(|i| {
+                let start = i as f64 * 0.5;
+                let end = start + if i % 10 == 9 { 0.05 } else { 0.45 };
+                TranscriptWord { text: format!("w{}", i), start, end, speaker_id: None }
+            })
+            .collect();
+
+        let chunks = chunk_transcript(&words);
+        assert_eq!(chunks.len(), 18);
+        assert!(chunks.iter().all(|c| c.end - c.start <= MAX_CHUNK_SECONDS));
+        assert!(chunks.windows(2).all(|pair| pair[1].start > pair[0].end));
+        assert!(chunks[0].text.starts_with("[0:00:00] w0 w1") && chunks[0].text.contains("\n[0:01:00] w120"));
+
+        let level_one = merge_groups(&chunks);
+        assert_eq!(level_one.iter().map(Vec::len).collect::<Vec<_>>(), [5, 5, 5, 3]);
+        let lone = merge_groups(&chunks[..11]);
+        assert_eq!(lone.iter().map(Vec::len).collect::<Vec<_>>(), [5, 6]);
+
+        assert_eq!(SummaryDepth::parse("Detailed"), Some(SummaryDepth::Detailed));
+        assert_eq!(SummaryDepth::parse("everything"), None);
+    }
+}
@@ -1,5 +1,6 @@
 // src/services/video_vectorization.rs
 use crate::gemini_client::GeminiClient;
+use crate::services::embedding_index::EmbeddingIndexService;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -21,6 +22,7 @@ pub struct VideoFrameMetadata {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VideoVectorData {
     pub file_id: String,
+    pub file_path: String,
     pub session_id: String,
     pub user_id: Option<i32>,
     pub frame_metadata: Vec<VideoFrameMetadata>,
@@ -32,14 +34,15 @@ pub struct VideoVectorData {
 pub struct VideoVectorizationService;
 
 impl VideoVectorizationService {
-    /// Extract keyframes from video and generate embeddings for storage in Qdrant
+    /// Extract keyframes from video and generate embeddings for storage in Qdrant.
+    /// Returns the summary of what the video shows.
     pub async fn process_video_for_vectorization(
         video_file_path: &str,
         file_id: &str,
         session_id: &str,
         user_id: Option<i32>,
         state: &Arc<AppState>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         info!("Starting video vectorization for file: {} ({})", video_file_path, file_id);
 
         // Step 1: Extract keyframes from video
@@ -56,8 +59,8 @@ impl VideoVectorizationService {
             None => return Err("Gemini client not available".into()),
         };
         
-        for (frame_number, frame_path) in keyframes.iter().enumerate() {
-            match Self::analyze_frame_with_gemini(frame_path, frame_number as u32, gemini_client).await {
+        for (frame_number, (frame_path, timestamp_seconds)) in keyframes.iter().enumerate() {
+            match Self::analyze_frame_with_gemini(frame_path, frame_number as u32, *timestamp_seconds, gemini_client).await {
                 Ok(metadata) => {
                     frame_metadata.push(metadata);
                 },
@@ -73,6 +76,7 @@ impl VideoVectorizationService {
         // Step 4: Create embeddings and store in Qdrant
         let vector_data = VideoVectorData {
             file_id: file_id.to_string(),
+            file_path: video_file_path.to_string(),
             session_id: session_id.to_string(),
             user_id,
             frame_metadata: frame_metadata.clone(),
@@ -87,14 +91,14 @@ impl VideoVectorizationService {
         let _ = fs::remove_dir_all(&frames_dir).await;
 
         info!("Successfully vectorized video: {} with {} frame embeddings", file_id, frame_metadata.len());
-        Ok(())
+        Ok(video_summary)
     }
 
-    /// Extract keyframes from video using FFmpeg
+    /// Extract keyframes from video using FFmpeg, with each frame's timestamp in seconds
     async fn extract_keyframes(
         video_path: &str,
         output_dir: &str,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<(String, f64)>, Box<dyn std::error::Error + Send + Sync>> {
         info!("Extracting keyframes from video: {}", video_path);
         
         // Use FFmpeg to extract keyframes at 1-second intervals
@@ -104,7 +108,7 @@ impl VideoVectorizationService {
             .arg("-i")
             .arg(video_path)
             .arg("-vf")
-            .arg("select='eq(pict_type,I)',scale=640:360") // Extract I-frames and scale down
+            .arg("select='eq(pict_type,I)',scale=640:360,showinfo") // Extract I-frames, scale down, log their pts_time
             .arg("-vsync")
             .arg("vfr")
             .arg("-q:v")
@@ -117,13 +121,23 @@ impl VideoVectorizationService {
             return Err(format!("FFmpeg failed: {}", String::from_utf8_lossy(&output.stderr)).into());
         }
 
+        // showinfo logs one pts_time per written frame, in output order
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let timestamps: Vec<f64> = stderr
+            .lines()
+            .filter_map(|line| line.split("pts_time:").nth(1))
+            .filter_map(|rest| rest.split_whitespace().next())
+            .filter_map(|t| t.parse().ok())
+            .collect();
+
         // Get list of extracted frames
         let mut frames = Vec::new();
         let mut frame_num = 1;
         loop {
             let frame_path = format!("{}/frame_{:04}.jpg", output_dir, frame_num);
             if tokio::fs::metadata(&frame_path).await.is_ok() {
-                frames.push(frame_path);
+                let timestamp = timestamps.get(frame_num - 1).copied().unwrap_or((frame_num - 1) as f64);
+                frames.push((frame_path, timestamp));
                 frame_num += 1;
             } else {
                 break;
@@ -137,6 +151,7 @@ impl VideoVectorizationService {
     async fn analyze_frame_with_gemini(
         frame_path: &str,
         frame_number: u32,
+        timestamp_seconds: f64,
         gemini_client: &GeminiClient,
     ) -> Result<VideoFrameMetadata, Box<dyn std::error::Error + Send + Sync>> {
         // Read frame data as base64
@@ -160,9 +175,7 @@ impl VideoVectorizationService {
         
         // Parse the AI response to extract structured data
         let (description, visual_features) = Self::parse_frame_analysis(&analysis_result);
-        
-        // Calculate timestamp based on frame number (assuming 1 frame per second for keyframes)
-        let timestamp_seconds = frame_number as f64;
+
 
         Ok(VideoFrameMetadata {
             frame_number,
@@ -263,14 +276,8 @@ impl VideoVectorizationService {
             None => return Err("Qdrant client not available".into()),
         };
 
-        // Generate embeddings for video summary and each frame description
-        let gemini_client = match &state.gemini_client {
-            Some(client) => client,
-            None => return Err("Gemini client not available".into()),
-        };
-
         // 1. Store video-level embedding
-        let video_embedding = Self::generate_text_embedding(&vector_data.video_summary, gemini_client).await?;
+        let video_embedding = Self::generate_text_embedding(&vector_data.video_summary, state).await?;
         
         let video_point_id = format!("video_{}", vector_data.file_id);
         let video_payload = json!({
@@ -289,12 +296,13 @@ impl VideoVectorizationService {
 
         // 2. Store frame-level embeddings
         for frame in &vector_data.frame_metadata {
-            let frame_embedding = Self::generate_text_embedding(&frame.description, gemini_client).await?;
+            let frame_embedding = Self::generate_text_embedding(&frame.description, state).await?;
             
             let frame_point_id = format!("frame_{}_f{}", vector_data.file_id, frame.frame_number);
             let frame_payload = json!({
                 "content_type": "video_frame",
                 "file_id": vector_data.file_id,
+                "file_path": vector_data.file_path,
                 "session_id": vector_data.session_id,
                 "user_id": vector_data.user_id,
                 "content": frame.description,
@@ -311,36 +319,21 @@ impl VideoVectorizationService {
         Ok(())
     }
 
-    /// Generate text embedding using Gemini embedding model
+    /// Generate text embedding with the model of the active embedding index, so video content
+    /// lands in the same vector space as chat memories
     async fn generate_text_embedding(
         text: &str,
-        gemini_client: &GeminiClient,
+        state: &AppState,
     ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
-        // Use the existing embed_content method
-        let embedding = gemini_client.embed_content(text).await?;
+        let embedding = EmbeddingIndexService::embed(state, text).await?;
         Ok(embedding)
     }
 
-    /// Get video duration using FFprobe
+    /// Get video duration from the probed media info
     async fn get_video_duration(video_path: &str) -> Result<f64, Box<dyn std::error::Error + Send + Sync>> {
-        let output = Command::new("ffprobe")
-            .arg("-v")
-            .arg("quiet")
-            .arg("-show_entries")
-            .arg("format=duration")
-            .arg("-of")
-            .arg("csv=p=0")
-            .arg(video_path)
-            .output()?;
-
-        if !output.status.success() {
-            return Err("Failed to get video duration".into());
-        }
-
-        let duration_str = String::from_utf8(output.stdout)?;
-        let duration: f64 = duration_str.trim().parse()?;
-        
-        Ok(duration)
+        let path = video_path.to_string();
+        let info = tokio::task::spawn_blocking(move || crate::core::probe_media(&path)).await??;
+        Ok(info.duration_seconds)
     }
 
     /// Search for similar video content using vector similarity
@@ -350,13 +343,8 @@ impl VideoVectorizationService {
         limit: usize,
         state: &Arc<AppState>,
     ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
-        let gemini_client = match &state.gemini_client {
-            Some(client) => client,
-            None => return Err("Gemini client not available".into()),
-        };
-        
         // Generate embedding for the search query
-        let query_embedding = Self::generate_text_embedding(query, gemini_client).await?;
+        let query_embedding = Self::generate_text_embedding(query, state).await?;
 
         // Search in Qdrant with session filter
         let filter = json!({
@@ -382,6 +370,30 @@ impl VideoVectorizationService {
         Ok(search_results)
     }
 
+    /// Frames of one video most relevant to a query (payloads include timestamp_seconds and content).
+    /// Only finds videos vectorized since frames started recording their file_path.
+    pub async fn search_video_frames(
+        query: &str,
+        video_file_path: &str,
+        limit: usize,
+        state: &Arc<AppState>,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let qdrant_client = match &state.qdrant_client {
+            Some(client) => client,
+            None => return Err("Qdrant client not available".into()),
+        };
+
+        let query_embedding = Self::generate_text_embedding(query, state).await?;
+        let filter = json!({
+            "must": [
+                { "key": "content_type", "match": { "value": "video_frame" } },
+                { "key": "file_path", "match": { "value": video_file_path } }
+            ]
+        });
+
+        qdrant_client.search_points(&query_embedding, limit, Some(&filter)).await
+    }
+
     /// Retrieve video analysis from Qdrant by file path
     /// This allows LLMs to "view" a video by reading its vectorized content
     pub async fn retrieve_video_analysis(
@@ -0,0 +1,475 @@
+// src/services/webhook.rs
+// Per-user webhooks for integrations that can't keep a WebSocket open: when a background job
+// completes or fails, every matching webhook of the job's owner gets a signed JSON POST.
+// Receivers verify `X-VideoSync-Signature: sha256=<hex>`, an HMAC-SHA256 of "<timestamp>.<body>"
+// keyed with the webhook secret (timestamp from `X-VideoSync-Timestamp`). Every event is logged as a
+// webhook delivery, and failed calls are retried with exponential backoff from that table, so retries
+// survive a restart. URLs must resolve to public addresses, checked when a webhook is registered and
+// again on every call, so webhooks can't reach loopback, private or cloud metadata addresses.
+use crate::jobs::{Job, JobStatus};
+use crate::models::webhook::{CreateWebhookRequest, Webhook, WebhookDelivery};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+pub const SUPPORTED_EVENTS: [&str; 2] = ["job.completed", "job.failed"];
+
+/// Webhooks one user can register
+pub const MAX_WEBHOOKS_PER_USER: i64 = 10;
+
+/// Calls per event before the delivery is marked failed
+const MAX_ATTEMPTS: i32 = 5;
+/// Wait before the first retry; doubles after each failed attempt
+const RETRY_BASE_SECONDS: u64 = 10;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+/// How far an attempt in progress pushes its delivery's next attempt, so the retry loop leaves it
+/// alone unless the process dies mid-call
+const ATTEMPT_LEASE_SECONDS: i64 = 120;
+/// How often the retry loop looks for due deliveries
+const RETRY_INTERVAL_SECONDS: u64 = 15;
+/// Deliveries claimed by one pass of the retry loop
+const RETRY_BATCH: i64 = 50;
+
+#[derive(sqlx::FromRow)]
+struct DueDelivery {
+    id: i32,
+    delivery_uuid: String,
+    event: String,
+    job_id: Option<String>,
+    payload: Value,
+    attempts: i32,
+    webhook_id: i32,
+    url: String,
+    secret: String,
+    is_active: bool,
+}
+
+pub struct WebhookService;
+
+impl WebhookService {
+    pub async fn list(pool: &PgPool, user_id: i32) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn create(pool: &PgPool, user_id: i32, request: &CreateWebhookRequest) -> Result<Webhook, String> {
+        let url = reqwest::Url::parse(request.url.trim()).map_err(|e| format!("Invalid url: {}", e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("url must be http or https".to_string());
+        }
+        resolve_public(&url).await?;
+        let events = match &request.events {
+            Some(events) if events.is_empty() => return Err("events must name at least one event".to_string()),
+            Some(events) => {
+                if let Some(unknown) = events.iter().find(|e| !SUPPORTED_EVENTS.contains(&e.as_str())) {
+                    return Err(format!("Unknown event '{}'. Use: {}", unknown, SUPPORTED_EVENTS.join(", ")));
+                }
+                events.clone()
+            }
+            None => SUPPORTED_EVENTS.iter().map(|e| e.to_string()).collect(),
+        };
+        let secret = match request.secret.as_deref().map(str::trim) {
+            Some(secret) if secret.len() < 16 => return Err("secret must be at least 16 characters".to_string()),
+            Some(secret) => secret.to_string(),
+            None => format!("whsec_{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple()),
+        };
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhooks WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if count >= MAX_WEBHOOKS_PER_USER {
+            return Err(format!("You can register at most {} webhooks", MAX_WEBHOOKS_PER_USER));
+        }
+
+        sqlx::query_as::<_, Webhook>(
+            "INSERT INTO webhooks (user_id, url, secret, events) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(user_id)
+        .bind(url.as_str())
+        .bind(&secret)
+        .bind(&events)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to save webhook: {}", e))
+    }
+
+    pub async fn delete(pool: &PgPool, user_id: i32, webhook_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1 AND user_id = $2")
+            .bind(webhook_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_deliveries(pool: &PgPool, user_id: i32, limit: i64) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as::<_, WebhookDelivery>(
+            "SELECT * FROM webhook_deliveries WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2"
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// A job reached `Completed` or `Failed`: call the owner's webhooks in the background
+    pub fn job_finished(pool: PgPool, job: Job) {
+        let event = match job.status {
+            JobStatus::Completed { .. } => "job.completed",
+            JobStatus::Failed { .. } => "job.failed",
+            _ => return,
+        };
+        let Some(user_id) = job.user_id.as_deref().and_then(|id| id.parse::<i32>().ok()) else {
+            return;
+        };
+
+        tokio::spawn(async move {
+            let webhooks = match sqlx::query_as::<_, Webhook>(
+                "SELECT * FROM webhooks WHERE user_id = $1 AND is_active = TRUE AND $2 = ANY(events)"
+            )
+            .bind(user_id)
+            .bind(event)
+            .fetch_all(&pool)
+            .await
+            {
+                Ok(webhooks) => webhooks,
+                Err(e) => {
+                    tracing::warn!("Failed to load webhooks for user {}: {}", user_id, e);
+                    return;
+                }
+            };
+
+            let payload = job_payload(event, &job);
+            for webhook in webhooks {
+                match Self::record(&pool, &webhook, event, &job.id, payload.clone()).await {
+                    Ok(delivery_id) => {
+                        let pool = pool.clone();
+                        tokio::spawn(async move { Self::attempt_logged(&pool, delivery_id).await });
+                    }
+                    Err(e) => tracing::warn!("Webhook {} delivery for job {} not logged: {}", webhook.id, job.id, e),
+                }
+            }
+        });
+    }
+
+    /// Log an event for a webhook as a pending delivery, leased so the first attempt can run right away
+    async fn record(pool: &PgPool, webhook: &Webhook, event: &str, job_id: &str, mut payload: Value) -> Result<i32, sqlx::Error> {
+        let delivery_uuid = uuid::Uuid::new_v4().to_string();
+        payload["delivery_id"] = json!(delivery_uuid);
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO webhook_deliveries (delivery_uuid, webhook_id, user_id, event, job_id, payload, next_attempt_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW() + make_interval(secs => $7))
+            RETURNING id
+            "#,
+        )
+        .bind(&delivery_uuid)
+        .bind(webhook.id)
+        .bind(webhook.user_id)
+        .bind(event)
+        .bind(job_id)
+        .bind(&payload)
+        .bind(ATTEMPT_LEASE_SECONDS as f64)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The retry loop: claim pending deliveries whose next attempt is due, including ones a restart
+    /// interrupted, and call them again
+    pub async fn run_retries(pool: PgPool) {
+        loop {
+            match Self::claim_due(&pool).await {
+                Ok(due) => {
+                    for delivery_id in due {
+                        Self::attempt_logged(&pool, delivery_id).await;
+                    }
+                }
+                Err(e) => tracing::error!("❌ Webhook retry check failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(RETRY_INTERVAL_SECONDS)).await;
+        }
+    }
+
+    /// Claiming in one UPDATE means a delivery is attempted once even with several instances running
+    async fn claim_due(pool: &PgPool) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            UPDATE webhook_deliveries SET next_attempt_at = NOW() + make_interval(secs => $1)
+            WHERE id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id
+            "#,
+        )
+        .bind(ATTEMPT_LEASE_SECONDS as f64)
+        .bind(RETRY_BATCH)
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn attempt_logged(pool: &PgPool, delivery_id: i32) {
+        if let Err(e) = Self::attempt(pool, delivery_id).await {
+            tracing::warn!("Webhook delivery {} attempt not logged: {}", delivery_id, e);
+        }
+    }
+
+    /// Make one call for a pending delivery and record the outcome: delivered on a 2xx, otherwise
+    /// pending with the next attempt scheduled, or failed once the attempts run out
+    async fn attempt(pool: &PgPool, delivery_id: i32) -> Result<(), sqlx::Error> {
+        let Some(delivery) = sqlx::query_as::<_, DueDelivery>(
+            r#"
+            SELECT d.id, d.delivery_uuid, d.event, d.job_id, d.payload, d.attempts,
+                   w.id AS webhook_id, w.url, w.secret, w.is_active
+            FROM webhook_deliveries d
+            JOIN webhooks w ON w.id = d.webhook_id
+            WHERE d.id = $1 AND d.status = 'pending'
+            "#,
+        )
+        .bind(delivery_id)
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let attempt = delivery.attempts + 1;
+        let (response_status, error) = if delivery.is_active {
+            call(&delivery).await
+        } else {
+            (None, Some("Webhook was disabled".to_string()))
+        };
+        let status = match (&error, delivery.is_active) {
+            (None, _) => "delivered",
+            (Some(_), false) => "failed",
+            (Some(_), true) if attempt >= MAX_ATTEMPTS => "failed",
+            (Some(_), true) => "pending",
+        };
+        let retry_in = (status == "pending").then(|| retry_delay(attempt));
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries SET
+                status = $2, attempts = $3, response_status = $4, error_message = $5, last_attempt_at = NOW(),
+                delivered_at = CASE WHEN $2 = 'delivered' THEN NOW() END,
+                next_attempt_at = CASE WHEN $6::FLOAT8 IS NULL THEN NULL ELSE NOW() + make_interval(secs => $6) END
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(status)
+        .bind(attempt)
+        .bind(response_status)
+        .bind(&error)
+        .bind(retry_in.map(|wait| wait as f64))
+        .execute(pool)
+        .await?;
+
+        let job_id = delivery.job_id.as_deref().unwrap_or("-");
+        match (status, error) {
+            ("delivered", _) => tracing::info!("🪝 Webhook {} got {} for job {}", delivery.webhook_id, delivery.event, job_id),
+            (_, Some(e)) if status == "failed" => tracing::warn!(
+                "🪝 Webhook {} gave up on {} for job {} after {} attempts: {}",
+                delivery.webhook_id, delivery.event, job_id, attempt, e
+            ),
+            (_, e) => tracing::debug!(
+                "Webhook {} attempt {} failed ({}), retrying in {}s",
+                delivery.webhook_id, attempt, e.unwrap_or_default(), retry_in.unwrap_or_default()
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// POST a delivery's payload once. The host is resolved and checked again here, and the request is
+/// pinned to the checked addresses with redirects off, so DNS changes can't point it inward.
+async fn call(delivery: &DueDelivery) -> (Option<i32>, Option<String>) {
+    let url = match reqwest::Url::parse(&delivery.url) {
+        Ok(url) => url,
+        Err(e) => return (None, Some(format!("Invalid url: {}", e))),
+    };
+    let addresses = match resolve_public(&url).await {
+        Ok(addresses) => addresses,
+        Err(e) => return (None, Some(e)),
+    };
+    let host = url.host_str().unwrap_or_default();
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(host, &addresses)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return (None, Some(format!("Failed to build HTTP client: {}", e))),
+    };
+
+    let body = delivery.payload.to_string();
+    let timestamp = Utc::now().timestamp().to_string();
+    let response = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "VideoSync-Webhooks/1.0")
+        .header("X-VideoSync-Event", &delivery.event)
+        .header("X-VideoSync-Delivery", &delivery.delivery_uuid)
+        .header("X-VideoSync-Timestamp", &timestamp)
+        .header("X-VideoSync-Signature", format!("sha256={}", sign(&delivery.secret, &timestamp, &body)))
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(r) if r.status().is_success() => (Some(r.status().as_u16() as i32), None),
+        Ok(r) => (Some(r.status().as_u16() as i32), Some(format!("Receiver answered {}", r.status()))),
+        Err(e) => (None, Some(format!("Request failed: {}", e))),
+    }
+}
+
+/// Wait after a failed attempt before the next one; doubles each time
+fn retry_delay(attempt: i32) -> u64 {
+    RETRY_BASE_SECONDS * 2u64.pow(attempt.max(1) as u32 - 1)
+}
+
+/// The addresses a webhook or delivery URL's host resolves to; an error when any of them is internal
+pub(crate) async fn resolve_public(url: &reqwest::Url) -> Result<Vec<SocketAddr>, String> {
+    let host = url.host_str().ok_or_else(|| "url must have a host".to_string())?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    // IPv6 literals come bracketed
+    let literal = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok();
+    let addresses: Vec<SocketAddr> = match literal {
+        Some(ip) => vec![SocketAddr::new(ip, port)],
+        None => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("Can't resolve {}: {}", host, e))?
+            .collect(),
+    };
+    if addresses.is_empty() {
+        return Err(format!("Can't resolve {}", host));
+    }
+    if let Some(internal) = addresses.iter().find(|address| is_internal(address.ip())) {
+        return Err(format!("url must point to a public address; {} resolves to {}", host, internal.ip()));
+    }
+    Ok(addresses)
+}
+
+/// Loopback, private, link-local (incl. cloud metadata), shared, multicast and reserved ranges
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_internal_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_internal_v4(mapped),
+            None => is_internal_v6(ip),
+        },
+    }
+}
+
+fn is_internal_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // shared address space (carrier-grade NAT)
+        || (a == 198 && (18..20).contains(&b)) // benchmarking
+        || a >= 240 // reserved
+}
+
+fn is_internal_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00 // unique local
+        || (first & 0xffc0) == 0xfe80 // link-local
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8) // documentation
+}
+
+/// The JSON body sent for a finished job (`delivery_id` is added per webhook)
+fn job_payload(event: &str, job: &Job) -> Value {
+    json!({
+        "event": event,
+        "created_at": Utc::now(),
+        "job": {
+            "id": job.id,
+            "job_type": job.job_type,
+            "session_id": job.session_id,
+            "graph_id": job.graph_id,
+            "request_id": job.request_id,
+            "created_at": job.created_at,
+            "started_at": job.started_at,
+            "completed_at": job.completed_at,
+            "status": job.status,
+        },
+    })
+}
+
+/// Signature of a call: HMAC-SHA256 of "<timestamp>.<body>"
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    hmac_sha256_hex(secret, &format!("{}.{}", timestamp, body))
+}
+
+fn hmac_sha256_hex(key: &str, message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_cover_the_timestamp_and_body() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256_hex("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let signature = sign("whsec_test", "1767225600", r#"{"event":"job.completed"}"#);
+        assert_eq!(signature, hmac_sha256_hex("whsec_test", r#"1767225600.{"event":"job.completed"}"#));
+        assert_ne!(signature, sign("whsec_test", "1767225601", r#"{"event":"job.completed"}"#));
+        assert_ne!(signature, sign("whsec_other", "1767225600", r#"{"event":"job.completed"}"#));
+
+        let mut job = Job::new("session".to_string(), "video_editing".to_string(), json!({}));
+        job.status = JobStatus::Failed { error: "boom".to_string(), failed_at_step: "render".to_string() };
+        let payload = job_payload("job.failed", &job);
+        assert_eq!(payload["job"]["status"]["status"], "failed");
+        assert_eq!(payload["job"]["status"]["error"], "boom");
+    }
+
+    #[test]
+    fn internal_addresses_are_refused() {
+        for internal in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(is_internal(internal.parse().unwrap()), "{} should be internal", internal);
+        }
+        for public in ["93.184.216.34", "8.8.8.8", "2606:4700::1111"] {
+            assert!(!is_internal(public.parse().unwrap()), "{} should be public", public);
+        }
+        assert_eq!((retry_delay(1), retry_delay(2), retry_delay(4)), (10, 20, 80));
+    }
+
+    #[tokio::test]
+    async fn webhook_urls_must_resolve_to_public_addresses() {
+        let url = |u: &str| reqwest::Url::parse(u).unwrap();
+        assert!(resolve_public(&url("http://169.254.169.254/latest/meta-data")).await.is_err());
+        assert!(resolve_public(&url("http://[::1]:8080/hook")).await.is_err());
+        assert!(resolve_public(&url("https://10.0.0.5/hook")).await.is_err());
+        let public = resolve_public(&url("https://93.184.216.34/hook")).await.unwrap();
+        assert_eq!(public, vec!["93.184.216.34:443".parse().unwrap()]);
+    }
+}
@@ -0,0 +1,198 @@
+// src/services/workflow_template.rs
+// Workflow templates: common pipelines ("podcast to shorts", "raw footage to YouTube upload") stored
+// once as job graphs whose tasks hold `{{parameter}}` slots. Instantiating a template fills the slots
+// and gives a job graph ready for `spawn_video_editing_graph`.
+use crate::handlers::jobs::MAX_GRAPH_JOBS;
+use crate::jobs::dag::{topological_order, GraphNode};
+use crate::models::workflow_template::{CreateWorkflowTemplateRequest, TemplateParameter, WorkflowTemplate};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+
+pub struct WorkflowTemplateService;
+
+impl WorkflowTemplateService {
+    /// Built-in templates first, then the user's own
+    pub async fn list_templates(pool: &PgPool, user_id: i32) -> Result<Vec<WorkflowTemplate>, sqlx::Error> {
+        sqlx::query_as::<_, WorkflowTemplate>(
+            "SELECT * FROM workflow_templates WHERE user_id IS NULL OR user_id = $1 ORDER BY user_id NULLS FIRST, name"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn get_template(pool: &PgPool, user_id: i32, template_id: i32) -> Result<Option<WorkflowTemplate>, sqlx::Error> {
+        sqlx::query_as::<_, WorkflowTemplate>(
+            "SELECT * FROM workflow_templates WHERE id = $1 AND (user_id IS NULL OR user_id = $2)"
+        )
+        .bind(template_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create_template(
+        pool: &PgPool,
+        user_id: i32,
+        request: &CreateWorkflowTemplateRequest,
+    ) -> Result<WorkflowTemplate, String> {
+        let name = request.name.trim();
+        if name.is_empty() || name.len() > 100 {
+            return Err("Template name must be 1-100 characters".to_string());
+        }
+        Self::validate(&request.parameters, &request.jobs)?;
+        let description = request.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+        let parameters = serde_json::to_value(&request.parameters).map_err(|e| e.to_string())?;
+        let jobs = serde_json::to_value(&request.jobs).map_err(|e| e.to_string())?;
+
+        sqlx::query_as::<_, WorkflowTemplate>(
+            r#"
+            INSERT INTO workflow_templates (user_id, name, description, parameters, jobs)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(description)
+        .bind(parameters)
+        .bind(jobs)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => format!("A template named '{}' already exists", name),
+            e => format!("Failed to save workflow template: {}", e),
+        })
+    }
+
+    /// Only the user's own templates can be deleted, not the built-in ones
+    pub async fn delete_template(pool: &PgPool, user_id: i32, template_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM workflow_templates WHERE id = $1 AND user_id = $2")
+            .bind(template_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The template's job graph with every slot filled from `values` or the parameter's default
+    pub fn instantiate(template: &WorkflowTemplate, values: &HashMap<String, String>) -> Result<Vec<GraphNode>, String> {
+        fill_slots(&template.parameter_list()?, &template.job_list()?, values)
+    }
+
+    /// A template must be a valid job graph, and its slots and parameters must match up
+    fn validate(parameters: &[TemplateParameter], jobs: &[GraphNode]) -> Result<(), String> {
+        if jobs.is_empty() || jobs.len() > MAX_GRAPH_JOBS {
+            return Err(format!("A template needs between 1 and {} jobs", MAX_GRAPH_JOBS));
+        }
+        if let Some(job) = jobs.iter().find(|job| job.task.trim().is_empty()) {
+            return Err(format!("Job '{}' has no task", job.key));
+        }
+        topological_order(jobs)?;
+
+        let mut declared = HashSet::new();
+        for parameter in parameters {
+            let valid = !parameter.name.is_empty()
+                && parameter.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                return Err(format!("Parameter name '{}' may only use a-z, 0-9 and _", parameter.name));
+            }
+            if !declared.insert(parameter.name.as_str()) {
+                return Err(format!("Parameter '{}' is declared twice", parameter.name));
+            }
+        }
+        for job in jobs {
+            if let Some(slot) = slots(&job.task).into_iter().find(|slot| !declared.contains(slot)) {
+                return Err(format!("Job '{}' uses {{{{{}}}}}, which isn't a declared parameter", job.key, slot));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Names of the `{{slot}}`s in a task
+fn slots(task: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = task;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        found.push(rest[start + 2..start + 2 + end].trim());
+        rest = &rest[start + 2 + end + 2..];
+    }
+    found
+}
+
+fn fill_slots(parameters: &[TemplateParameter], jobs: &[GraphNode], values: &HashMap<String, String>) -> Result<Vec<GraphNode>, String> {
+    if let Some(unknown) = values.keys().find(|key| !parameters.iter().any(|p| &p.name == *key)) {
+        let names: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+        return Err(format!("Unknown parameter '{}'. This template takes: {}", unknown, names.join(", ")));
+    }
+    let mut filled = HashMap::new();
+    for parameter in parameters {
+        let value = values
+            .get(&parameter.name)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .or_else(|| parameter.default.clone())
+            .ok_or_else(|| format!("Parameter '{}' is required", parameter.name))?;
+        filled.insert(parameter.name.as_str(), value);
+    }
+
+    Ok(jobs
+        .iter()
+        .map(|job| GraphNode { key: job.key.clone(), task: render(&job.task, &filled), depends_on: job.depends_on.clone() })
+        .collect())
+}
+
+/// The task with each `{{slot}}` replaced by its value
+fn render(task: &str, values: &HashMap<&str, String>) -> String {
+    let mut rendered = String::new();
+    let mut rest = task;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let slot_end = start + 2 + end + 2;
+        rendered.push_str(&rest[..start]);
+        match values.get(rest[start + 2..start + 2 + end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..slot_end]),
+        }
+        rest = &rest[slot_end..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_are_filled_from_values_and_defaults() {
+        let parameters = vec![
+            TemplateParameter { name: "episode".into(), description: None, default: None },
+            TemplateParameter { name: "clip_count".into(), description: None, default: Some("3".into()) },
+        ];
+        let jobs = vec![
+            GraphNode { key: "pick".into(), task: "Pick {{clip_count}} moments of {{ episode }}".into(), depends_on: vec![] },
+            GraphNode { key: "cut".into(), task: "Cut them from {{episode}}".into(), depends_on: vec!["pick".into()] },
+        ];
+        assert!(WorkflowTemplateService::validate(&parameters, &jobs).is_ok());
+
+        let values = HashMap::from([("episode".to_string(), "uploads/ep12.mp4".to_string())]);
+        let graph = fill_slots(&parameters, &jobs, &values).unwrap();
+        assert_eq!(graph[0].task, "Pick 3 moments of uploads/ep12.mp4");
+        assert_eq!(graph[1].task, "Cut them from uploads/ep12.mp4");
+        assert_eq!(graph[1].depends_on, ["pick"]);
+
+        assert!(fill_slots(&parameters, &jobs, &HashMap::new()).unwrap_err().contains("'episode' is required"));
+        let typo = HashMap::from([("epsiode".to_string(), "x.mp4".to_string())]);
+        assert!(fill_slots(&parameters, &jobs, &typo).unwrap_err().contains("Unknown parameter 'epsiode'"));
+
+        let undeclared = vec![GraphNode { key: "a".into(), task: "Render {{format}}".into(), depends_on: vec![] }];
+        assert!(WorkflowTemplateService::validate(&parameters, &undeclared).unwrap_err().contains("{{format}}"));
+    }
+}
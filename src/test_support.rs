@@ -0,0 +1,180 @@
+// test_support.rs - Deterministic synthetic media for tests
+//
+// Every fixture is rendered from ffmpeg's lavfi sources with bit-exact flags and a single encoder
+// thread, so the same fixture is byte-identical across runs: SMPTE color bars or a moving test
+// pattern with a burned-in timecode, a green screen with a moving subject, and a sine sweep for audio.
+// Fixtures live in a per-test temporary folder that is removed when the SyntheticMedia is dropped.
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Font the timecode is burned in with (the same default the text tools use); without it the
+/// fixtures are rendered without a timecode
+const TIMECODE_FONT: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+
+/// Whether ffmpeg and ffprobe are on PATH; tests that render media skip themselves otherwise
+pub fn ffmpeg_available() -> bool {
+    ["ffmpeg", "ffprobe"].iter().all(|tool| {
+        Command::new(tool)
+            .arg("-version")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    })
+}
+
+/// Picture content of a synthetic video
+#[derive(Debug, Clone, Copy)]
+pub enum Pattern {
+    /// SMPTE color bars
+    ColorBars,
+    /// Moving gradient test pattern (every frame differs)
+    Gradient,
+    /// Pure green background with a moving grey box, for keying
+    GreenScreen,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VideoSpec {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub seconds: f64,
+    /// Add a 200 Hz → 2 kHz sweep as the audio track
+    pub audio: bool,
+}
+
+impl Default for VideoSpec {
+    fn default() -> Self {
+        Self { width: 320, height: 240, fps: 25, seconds: 4.0, audio: true }
+    }
+}
+
+pub struct SyntheticMedia {
+    dir: PathBuf,
+}
+
+impl SyntheticMedia {
+    /// A fresh fixture folder for one test
+    pub fn new(label: &str) -> Result<Self, String> {
+        let dir = std::env::temp_dir().join(format!("video_sync_{}_{}", label, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of a file in the fixture folder
+    pub fn path(&self, name: &str) -> String {
+        self.dir.join(name).to_string_lossy().to_string()
+    }
+
+    /// Render a video (H.264 + AAC in MP4, a keyframe every second) and return its path
+    pub fn video(&self, name: &str, pattern: Pattern, spec: VideoSpec) -> Result<String, String> {
+        let size = format!("{}x{}", spec.width, spec.height);
+        let source = match pattern {
+            Pattern::ColorBars => format!("smptebars=size={}:rate={}:duration={}", size, spec.fps, spec.seconds),
+            Pattern::Gradient => format!("testsrc2=size={}:rate={}:duration={}", size, spec.fps, spec.seconds),
+            Pattern::GreenScreen => format!("color=c=0x00FF00:size={}:rate={}:duration={}", size, spec.fps, spec.seconds),
+        };
+
+        let mut filters = Vec::new();
+        if let Pattern::GreenScreen = pattern {
+            filters.push("drawbox=x='mod(t*40,iw-ih/3)':y=ih/3:w=ih/3:h=ih/3:color=0x808080:t=fill".to_string());
+        }
+        if Path::new(TIMECODE_FONT).exists() {
+            filters.push(format!(
+                "drawtext=fontfile={}:timecode='00\\:00\\:00\\:00':rate={}:fontsize={}:fontcolor=white:box=1:boxcolor=black@0.6:x=(w-tw)/2:y=h-th-8",
+                TIMECODE_FONT,
+                spec.fps,
+                (spec.height / 10).max(12)
+            ));
+        }
+
+        let output = self.path(name);
+        let mut command = Command::new("ffmpeg");
+        command.args(["-hide_banner", "-loglevel", "error", "-f", "lavfi", "-i"]).arg(&source);
+        if spec.audio {
+            command.args(["-f", "lavfi", "-i"]).arg(Self::sweep_source(spec.seconds, 200.0, 2000.0));
+        }
+        if !filters.is_empty() {
+            command.arg("-vf").arg(filters.join(","));
+        }
+        command.args([
+            "-c:v", "libx264", "-preset", "ultrafast", "-pix_fmt", "yuv420p", "-g", &spec.fps.to_string(), "-threads", "1",
+        ]);
+        if spec.audio {
+            command.args(["-c:a", "aac", "-b:a", "128k", "-ac", "2", "-flags:a", "+bitexact"]);
+        }
+        command
+            .args(["-flags:v", "+bitexact", "-fflags", "+bitexact", "-map_metadata", "-1", "-t"])
+            .arg(spec.seconds.to_string())
+            .args(["-y"])
+            .arg(&output);
+        run(command)?;
+        Ok(output)
+    }
+
+    /// A linear sine sweep between two frequencies as 48 kHz stereo WAV
+    pub fn tone_sweep(&self, name: &str, seconds: f64, from_hz: f64, to_hz: f64) -> Result<String, String> {
+        let output = self.path(name);
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-hide_banner", "-loglevel", "error", "-f", "lavfi", "-i"])
+            .arg(Self::sweep_source(seconds, from_hz, to_hz))
+            .args(["-ac", "2", "-c:a", "pcm_s16le", "-fflags", "+bitexact", "-map_metadata", "-1", "-y"])
+            .arg(&output);
+        run(command)?;
+        Ok(output)
+    }
+
+    /// A single frame of the moving test pattern as PNG
+    pub fn still(&self, name: &str, width: u32, height: u32) -> Result<String, String> {
+        let output = self.path(name);
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-hide_banner", "-loglevel", "error", "-f", "lavfi", "-i"])
+            .arg(format!("testsrc2=size={}x{}:rate=1:duration=1", width, height))
+            .args(["-frames:v", "1", "-fflags", "+bitexact", "-y"])
+            .arg(&output);
+        run(command)?;
+        Ok(output)
+    }
+
+    /// An SRT file with one cue per second
+    pub fn subtitles(&self, name: &str, seconds: u32) -> Result<String, String> {
+        let output = self.path(name);
+        let cues: String = (0..seconds)
+            .map(|i| format!("{}\n00:00:{:02},000 --> 00:00:{:02},900\nCue {}\n\n", i + 1, i, i, i + 1))
+            .collect();
+        std::fs::write(&output, cues).map_err(|e| format!("Failed to write {}: {}", output, e))?;
+        Ok(output)
+    }
+
+    /// aevalsrc for a linear chirp: phase = 2π(f0·t + (f1 − f0)·t² / 2T)
+    fn sweep_source(seconds: f64, from_hz: f64, to_hz: f64) -> String {
+        format!(
+            "aevalsrc=exprs='0.5*sin(2*PI*({f0}*t+{k}*t*t))':s=48000:d={d}",
+            f0 = from_hz,
+            k = (to_hz - from_hz) / (2.0 * seconds),
+            d = seconds
+        )
+    }
+}
+
+impl Drop for SyntheticMedia {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn run(mut command: Command) -> Result<(), String> {
+    let output = command.output().map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr)))
+    }
+}
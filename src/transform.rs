@@ -1,7 +1,7 @@
 // src/transform.rs
 
 
-use crate::utils::execute_ffmpeg_command;
+use crate::utils::{execute_ffmpeg_command, gpu};
 use std::process::Command;
 
 pub fn resize_video(
@@ -9,21 +9,29 @@ pub fn resize_video(
     output_file: &str,
     width: u32,
     height: u32,
+    fps: Option<f64>,
 ) -> Result<String, String> {
-    let filter = format!("scale={}:{}", width, height);
-
-    let mut command = Command::new("ffmpeg");
-    command
-        .arg("-i")
-        .arg(input_file)
-        .arg("-vf")
-        .arg(filter)
-        .arg("-c:a")
-        .arg("copy")
-        .arg("-y")
-        .arg(output_file);
+    let fps_filter = fps.map(|fps| format!(",fps={}", fps)).unwrap_or_default();
+    let build = |filter: String, device_args: &[String]| {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(device_args)
+            .arg("-i")
+            .arg(input_file)
+            .arg("-vf")
+            .arg(filter)
+            .arg("-c:a")
+            .arg("copy")
+            .arg("-y")
+            .arg(output_file);
+        command
+    };
 
-    execute_ffmpeg_command(command)
+    let gpu_command = gpu::filters().and_then(|gpu| {
+        let filter = gpu.scale(&width.to_string(), &height.to_string())?;
+        Some(build(format!("{}{}", filter, fps_filter), &gpu.device_args()))
+    });
+    gpu::run_with_fallback(gpu_command, || build(format!("scale={}:{}{}", width, height, fps_filter), &[]))
 }
 
 pub fn crop_video(
@@ -26,6 +26,106 @@ pub struct VideoMetadata {
     pub file_size_mb: f64,
 }
 
+// Everything ffprobe reports about a media file, parsed once by `core::probe_media`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaInfo {
+    pub file_path: String,
+    pub format_name: String,
+    pub duration_seconds: f64,
+    pub size_bytes: u64,
+    pub bit_rate: Option<u64>,
+    /// Video, subtitle and data streams in file order
+    pub streams: Vec<StreamInfo>,
+    pub audio_streams: Vec<AudioInfo>,
+}
+
+impl MediaInfo {
+    /// First video stream, skipping attached cover art
+    pub fn video(&self) -> Option<&StreamInfo> {
+        self.streams.iter().find(|s| s.codec_type == "video" && !s.attached_pic)
+    }
+
+    pub fn audio(&self) -> Option<&AudioInfo> {
+        self.audio_streams.first()
+    }
+
+    pub fn has_video(&self) -> bool {
+        self.video().is_some()
+    }
+
+    pub fn has_audio(&self) -> bool {
+        !self.audio_streams.is_empty()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.video().and_then(|v| v.width).unwrap_or(0)
+    }
+
+    pub fn height(&self) -> u32 {
+        self.video().and_then(|v| v.height).unwrap_or(0)
+    }
+
+    pub fn fps(&self) -> f64 {
+        self.video().and_then(|v| v.fps).unwrap_or(0.0)
+    }
+}
+
+// A non-audio stream (video, subtitle, data); video-only fields are None elsewhere
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamInfo {
+    pub index: u32,
+    pub codec_type: String,
+    pub codec_name: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    pub pix_fmt: Option<String>,
+    pub bit_rate: Option<u64>,
+    pub duration_seconds: Option<f64>,
+    /// First timestamp of the stream; streams starting at different times play out of sync
+    #[serde(default)]
+    pub start_seconds: Option<f64>,
+    pub language: Option<String>,
+    /// Embedded cover art rather than real video
+    pub attached_pic: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioInfo {
+    pub index: u32,
+    pub codec_name: String,
+    pub sample_rate: u32,
+    pub channels: u32,
+    pub channel_layout: Option<String>,
+    pub bit_rate: Option<u64>,
+    pub duration_seconds: Option<f64>,
+    #[serde(default)]
+    pub start_seconds: Option<f64>,
+    pub language: Option<String>,
+    /// Track name set by the recorder ("Game", "Mic")
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Flagged as the track players pick by default
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl From<&MediaInfo> for VideoMetadata {
+    fn from(info: &MediaInfo) -> Self {
+        Self {
+            file_path: info.file_path.clone(),
+            duration_seconds: info.duration_seconds,
+            width: info.width(),
+            height: info.height(),
+            fps: info.fps(),
+            has_audio: info.has_audio(),
+            has_video: info.has_video(),
+            format: info.format_name.clone(),
+            file_size_mb: info.size_bytes as f64 / (1024.0 * 1024.0),
+        }
+    }
+}
+
 // Core operation parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrimParameters {
@@ -105,6 +205,17 @@ pub struct ColorAdjustParameters {
     pub saturation: f64, // -1.0 to 1.0
 }
 
+// Averaged signalstats measurements from sampled frames (8-bit scale, 0-255)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorAnalysis {
+    pub frames_sampled: usize,
+    pub luma_avg: f64,
+    pub luma_low: f64,  // 10th percentile luma
+    pub luma_high: f64, // 90th percentile luma
+    pub u_avg: f64,     // blue-difference chroma, 128 = neutral
+    pub v_avg: f64,     // red-difference chroma, 128 = neutral
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OverlayParameters {
     pub input_file: String,
@@ -305,6 +416,196 @@ pub struct PlatformExportParameters {
     pub platform: String, // "youtube", "instagram", "tiktok", etc.
 }
 
+// Word-level transcript entry (seconds from start of media)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    pub speaker_id: Option<String>,
+}
+
+// Result of planning which ranges to keep when stripping fillers and long pauses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillerRemovalPlan {
+    pub keep_segments: Vec<(f64, f64)>,
+    pub fillers_removed: Vec<String>,
+    pub pauses_shortened: usize,
+    pub original_duration: f64,
+    pub seconds_saved: f64,
+}
+
+// How generate_captions lays out, reveals and highlights transcript words
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimatedCaptionOptions {
+    // "line" shows a few words at a time, "word" shows only the word being spoken
+    pub mode: String,
+    pub font: String,
+    // Font size as a percentage of the video height
+    pub font_size_percent: f64,
+    pub text_color: String,
+    pub highlight_color: String,
+    // fill (karaoke sweep), color, box, pop or none
+    pub highlight_style: String,
+    // bottom, middle or top
+    pub position: String,
+    pub words_per_line: usize,
+    pub uppercase: bool,
+}
+
+// Result of planning a jump cut that drops a video's silent stretches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SilenceRemovalPlan {
+    pub keep_segments: Vec<(f64, f64)>,
+    pub silences_cut: usize,
+    pub original_duration: f64,
+    pub seconds_saved: f64,
+}
+
+// One uninterrupted stretch of speech by a single diarized speaker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerTurn {
+    pub speaker_id: String,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+// One FFmpeg run captured for a render report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmpegInvocation {
+    pub command: String,
+    pub encoders: Vec<String>,
+    pub duration_seconds: f64,
+    pub success: bool,
+    pub warnings: Vec<String>,
+    /// Run in reproducible mode (bitexact, seeded filters, inputs pinned)
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Inputs stored in the pinned asset store, by content hash
+    #[serde(default)]
+    pub inputs: Vec<PinnedFile>,
+    #[serde(default)]
+    pub output_sha256: Option<String>,
+    /// Classified cause when the command failed
+    #[serde(default)]
+    pub error_category: Option<FfmpegErrorCategory>,
+}
+
+// Common FFmpeg failure causes, each with a known remediation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FfmpegErrorCategory {
+    UnsupportedPixelFormat,
+    MissingAudioStream,
+    MissingVideoStream,
+    CorruptInput,
+    DiskFull,
+    FileNotFound,
+    PermissionDenied,
+    UnknownEncoder,
+    InvalidFilter,
+    OddDimensions,
+    Unknown,
+}
+
+impl FfmpegErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FfmpegErrorCategory::UnsupportedPixelFormat => "unsupported_pixel_format",
+            FfmpegErrorCategory::MissingAudioStream => "missing_audio_stream",
+            FfmpegErrorCategory::MissingVideoStream => "missing_video_stream",
+            FfmpegErrorCategory::CorruptInput => "corrupt_input",
+            FfmpegErrorCategory::DiskFull => "disk_full",
+            FfmpegErrorCategory::FileNotFound => "file_not_found",
+            FfmpegErrorCategory::PermissionDenied => "permission_denied",
+            FfmpegErrorCategory::UnknownEncoder => "unknown_encoder",
+            FfmpegErrorCategory::InvalidFilter => "invalid_filter",
+            FfmpegErrorCategory::OddDimensions => "odd_dimensions",
+            FfmpegErrorCategory::Unknown => "unknown",
+        }
+    }
+}
+
+// A failed FFmpeg run explained for users, with a remediation the agent can act on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmpegFailure {
+    pub category: FfmpegErrorCategory,
+    pub message: String,
+    pub remediation: String,
+    /// Whether re-running a corrected command can succeed without user action
+    pub retryable: bool,
+    /// The stderr lines that identified the failure
+    pub detail: String,
+}
+
+impl std::fmt::Display for FfmpegFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "FFmpeg error [{}]: {}\n💡 Suggested fix: {}\nDetails: {}",
+            self.category.as_str(),
+            self.message,
+            self.remediation,
+            self.detail
+        )
+    }
+}
+
+// A file an FFmpeg command read, identified by content hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedFile {
+    pub path: String,
+    pub sha256: String,
+}
+
+// Encoding settings for an export: a built-in platform preset or a user's named preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSettings {
+    pub container: String,
+    pub video_codec: String,
+    pub audio_codec: String,
+    pub crf: Option<u32>,
+    pub video_bitrate_kbps: Option<u32>,
+    pub audio_bitrate_kbps: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    // Integrated loudness target in LUFS
+    pub loudness_target: Option<f64>,
+    pub burn_captions: bool,
+}
+
+// Screen + webcam tutorial layout for compose_screencast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreencastLayout {
+    // bubble, pip or side_by_side
+    pub layout: String,
+    // top_left, top_right, bottom_left or bottom_right (bubble and pip)
+    pub position: String,
+    // Camera size as a fraction of the output height (bubble) or width (pip and side_by_side)
+    pub size: f64,
+    pub width: u32,
+    pub height: u32,
+    pub corner_radius: u32,
+    pub shadow: bool,
+    pub background_color: String,
+    // Seconds the camera recording is delayed (negative trims its start) to line up with the screen
+    pub camera_offset: f64,
+    // screen, camera or mix
+    pub audio: String,
+}
+
+// Multiple-choice question for quiz/trivia cards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizQuestion {
+    pub question: String,
+    pub options: Vec<String>,
+    pub answer_index: usize,
+}
+
 // Utility functions for OperationResult
 impl OperationResult {
     pub fn success(operation: &str, output_file: &str, duration: f64, message: &str) -> Self {
@@ -1,31 +1,480 @@
 // utils.rs - Pure FFmpeg utility functions (ZERO GStreamer!)
 use std::process::Command;
 
+pub mod gpu;
+pub mod progress;
+pub mod processes;
+pub mod limits;
+pub mod splice;
+pub mod timecode;
+pub mod file_locks;
+pub mod av_sync;
+pub mod egress;
+pub mod zip;
+pub mod credentials;
+
 /// Format duration in HH:MM:SS.mmm format
 pub fn format_duration(seconds: f64) -> String {
-    let hours = (seconds / 3600.0) as u32;
-    let minutes = ((seconds % 3600.0) / 60.0) as u32;
-    let secs = (seconds % 60.0) as u32;
-    let millis = ((seconds % 1.0) * 1000.0) as u32;
-    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+    timecode::format(seconds)
+}
+
+/// Content-addressed store for the inputs of reproducible renders (`<sha256>.<ext>`)
+pub const PINNED_ASSET_DIR: &str = "outputs/render_assets";
+
+/// Seed used for stochastic filters in reproducible renders
+pub const REPRODUCIBLE_SEED: u32 = 1;
+
+/// Filters with randomness and the option that seeds them
+const SEEDED_FILTERS: [(&str, &str); 4] = [("noise", "all_seed"), ("anoisesrc", "seed"), ("random", "seed"), ("aevalsrc", "seed")];
+
+#[derive(Default)]
+struct FfmpegTrace {
+    reproducible: bool,
+    /// Record commands without running them (see `capture_ffmpeg`)
+    dry_run: bool,
+    invocations: Vec<crate::types::FfmpegInvocation>,
+}
+
+tokio::task_local! {
+    /// FFmpeg invocations made by the current task, when it runs under `trace_ffmpeg`
+    static FFMPEG_TRACE: std::cell::RefCell<FfmpegTrace>;
+}
+
+/// Run a future while recording every FFmpeg command it executes through `execute_ffmpeg_command`.
+/// In reproducible mode commands are also pinned: bitexact output, seeded stochastic filters, and
+/// every input copied into `PINNED_ASSET_DIR` so the render can be replayed later.
+/// Work the future hands off to other tasks (`tokio::spawn`) is not captured.
+pub async fn trace_ffmpeg<F: std::future::Future>(future: F, reproducible: bool) -> (F::Output, Vec<crate::types::FfmpegInvocation>) {
+    let trace = FfmpegTrace { reproducible, dry_run: false, invocations: Vec::new() };
+    FFMPEG_TRACE
+        .scope(std::cell::RefCell::new(trace), async {
+            let output = future.await;
+            (output, FFMPEG_TRACE.with(|trace| std::mem::take(&mut trace.borrow_mut().invocations)))
+        })
+        .await
+}
+
+/// Whether the current task renders in reproducible mode (see `trace_ffmpeg`)
+pub fn reproducible_render() -> bool {
+    FFMPEG_TRACE.try_with(|trace| trace.borrow().reproducible).unwrap_or(false)
+}
+
+/// Run a future with `execute_ffmpeg_command` recording each FFmpeg command's arguments instead of
+/// running it (every command "succeeds" with empty output). GPU filters are never used, so the
+/// arguments don't depend on the machine. Used by the golden-file tests of the filtergraph builders.
+#[cfg(test)]
+pub async fn capture_ffmpeg<F: std::future::Future>(future: F) -> (F::Output, Vec<Vec<String>>) {
+    let trace = FfmpegTrace { reproducible: false, dry_run: true, invocations: Vec::new() };
+    FFMPEG_TRACE
+        .scope(std::cell::RefCell::new(trace), async {
+            let output = future.await;
+            let invocations = FFMPEG_TRACE.with(|trace| std::mem::take(&mut trace.borrow_mut().invocations));
+            (output, invocations.into_iter().map(|invocation| invocation.args).collect())
+        })
+        .await
+}
+
+/// Whether the current task only records FFmpeg commands (see `capture_ffmpeg`)
+pub fn dry_run_render() -> bool {
+    FFMPEG_TRACE.try_with(|trace| trace.borrow().dry_run).unwrap_or(false)
 }
 
 /// Execute FFmpeg command with error handling and progress info
 pub fn execute_ffmpeg_command(mut command: Command) -> Result<String, String> {
+    let reproducible = reproducible_render();
+    let mut inputs = Vec::new();
+    if reproducible && command.get_program() == "ffmpeg" {
+        command = pinned_command(&command);
+        inputs = pin_command_inputs(&command)?;
+    }
+
+    if dry_run_render() {
+        let _ = FFMPEG_TRACE.try_with(|trace| {
+            trace.borrow_mut().invocations.push(crate::types::FfmpegInvocation {
+                command: shell_command_line(&command),
+                encoders: Vec::new(),
+                duration_seconds: 0.0,
+                success: true,
+                warnings: Vec::new(),
+                pinned: false,
+                args: command.get_args().map(|a| a.to_string_lossy().to_string()).collect(),
+                inputs: Vec::new(),
+                output_sha256: None,
+                error_category: None,
+            });
+        });
+        return Ok(String::new());
+    }
+
     println!("Executing FFmpeg: {:?}", command);
 
-    let output = command
-        .output()
-        .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?;
+    let processes = processes::current();
+    if processes.as_ref().is_some_and(|p| p.is_cancelled()) {
+        return Err("FFmpeg not started: the job was cancelled".to_string());
+    }
+
+    let limits = limits::ProcessLimits::from_config();
+    let started = std::time::Instant::now();
+    let output = match progress::sink_for(&command) {
+        Some(sink) => progress::run_with_progress(&command, &sink, processes.as_ref(), &limits),
+        None => processes::run_tracked(&command, processes.as_ref(), &limits),
+    }
+    .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let _ = FFMPEG_TRACE.try_with(|trace| {
+        let args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        let output_sha256 = match (reproducible, args.last()) {
+            (true, Some(path)) if output.status.success() => file_sha256(path).ok(),
+            _ => None,
+        };
+        trace.borrow_mut().invocations.push(crate::types::FfmpegInvocation {
+            command: shell_command_line(&command),
+            encoders: ffmpeg_encoders(&command, &stderr),
+            duration_seconds: started.elapsed().as_secs_f64(),
+            success: output.status.success(),
+            warnings: ffmpeg_warnings(&stderr),
+            pinned: reproducible,
+            args,
+            inputs: std::mem::take(&mut inputs),
+            output_sha256,
+            error_category: (!output.status.success()).then(|| classify_ffmpeg_error(&stderr).category),
+        });
+    });
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg error: {}", stderr));
+        return Err(classify_ffmpeg_error(&stderr).to_string());
     }
+    limits.check_output(&command)?;
 
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Rebuild an FFmpeg command with bitexact output and seeded stochastic filters
+fn pinned_command(command: &Command) -> Command {
+    let mut args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+    for i in 1..args.len() {
+        if matches!(args[i - 1].as_str(), "-vf" | "-af" | "-filter:v" | "-filter:a" | "-filter_complex" | "-lavfi") {
+            args[i] = seed_filtergraph(&args[i]);
+        }
+    }
+    if !args.iter().any(|a| a == "+bitexact") {
+        // Output options go right before the output path
+        let at = args.len().saturating_sub(1);
+        let bitexact = ["-fflags", "+bitexact", "-flags:v", "+bitexact", "-flags:a", "+bitexact"].map(String::from);
+        args.splice(at..at, bitexact);
+    }
+
+    let mut pinned = Command::new(command.get_program());
+    pinned.args(&args);
+    if let Some(dir) = command.get_current_dir() {
+        pinned.current_dir(dir);
+    }
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => pinned.env(key, value),
+            None => pinned.env_remove(key),
+        };
+    }
+    pinned
+}
+
+/// Give every stochastic filter in a filtergraph a fixed seed unless it already has one
+pub fn seed_filtergraph(graph: &str) -> String {
+    let labels = regex::Regex::new(r"^\s*(\[[^\]]*\]\s*)*").unwrap();
+    let mut seeded = String::with_capacity(graph.len());
+    // Filters are separated by ',' (chain) and ';' (graph); the separators are kept as-is
+    for segment in graph.split_inclusive([',', ';']) {
+        let (body, separator) = match segment.char_indices().last() {
+            Some((at, c)) if c == ',' || c == ';' => (&segment[..at], &segment[at..]),
+            _ => (segment, ""),
+        };
+        let prefix_len = labels.find(body).map(|m| m.end()).unwrap_or(0);
+        let (prefix, filter) = body.split_at(prefix_len);
+        let name_end = filter.find(['=', '[']).unwrap_or(filter.len());
+        let (name, rest) = filter.split_at(name_end);
+
+        let seed_option = SEEDED_FILTERS.iter().find(|(f, _)| *f == name.trim()).map(|(_, option)| *option);
+        match seed_option {
+            Some(option) if !rest.contains("seed") => {
+                let (options, out_labels) = rest.split_at(rest.find('[').unwrap_or(rest.len()));
+                let options = match options.strip_prefix('=') {
+                    Some(options) => format!("={}:{}={}", options, option, REPRODUCIBLE_SEED),
+                    None => format!("={}={}", option, REPRODUCIBLE_SEED),
+                };
+                seeded.push_str(&format!("{}{}{}{}{}", prefix, name, options, out_labels, separator));
+            }
+            _ => seeded.push_str(segment),
+        }
+    }
+    seeded
+}
+
+/// Files an FFmpeg command reads: `-i` inputs, files named in filtergraphs, and concat list entries
+pub fn command_input_files(args: &[String]) -> Vec<String> {
+    fn push(path: String, files: &mut Vec<String>) {
+        if std::path::Path::new(&path).is_file() && !files.contains(&path) {
+            files.push(path);
+        }
+    }
+    let mut files: Vec<String> = Vec::new();
+    let filter_file = regex::Regex::new(r"(?:subtitles|ass|movie|amovie)=(?:filename=)?'?([^:,;'\[]+)").unwrap();
+
+    for i in 1..args.len() {
+        match args[i - 1].as_str() {
+            "-i" => {
+                let is_concat = i >= 3 && args[i - 3] == "-f" && args[i - 2] == "concat";
+                if is_concat {
+                    for entry in concat_list_entries(&args[i]) {
+                        push(entry, &mut files);
+                    }
+                }
+                push(args[i].clone(), &mut files);
+            }
+            "-vf" | "-af" | "-filter:v" | "-filter:a" | "-filter_complex" | "-lavfi" => {
+                for caps in filter_file.captures_iter(&args[i]) {
+                    push(caps[1].to_string(), &mut files);
+                }
+            }
+            _ => {}
+        }
+    }
+    files
+}
+
+/// Paths listed in an FFmpeg concat demuxer file
+pub fn concat_list_entries(list_file: &str) -> Vec<String> {
+    std::fs::read_to_string(list_file)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("file "))
+        .map(|path| path.trim().trim_matches('\'').to_string())
+        .collect()
+}
+
+/// Copy every input of a command into the pinned asset store
+fn pin_command_inputs(command: &Command) -> Result<Vec<crate::types::PinnedFile>, String> {
+    let args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+    command_input_files(&args)
+        .into_iter()
+        .map(|path| {
+            let sha256 = file_sha256(&path)?;
+            let stored = pinned_asset_path(&sha256, &path);
+            if !std::path::Path::new(&stored).exists() {
+                std::fs::create_dir_all(PINNED_ASSET_DIR).map_err(|e| format!("Failed to create asset store: {}", e))?;
+                std::fs::copy(&path, &stored).map_err(|e| format!("Failed to pin {}: {}", path, e))?;
+            }
+            Ok(crate::types::PinnedFile { path, sha256 })
+        })
+        .collect()
+}
+
+/// Where a pinned input with this hash is stored
+pub fn pinned_asset_path(sha256: &str, original_path: &str) -> String {
+    match std::path::Path::new(original_path).extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}/{}.{}", PINNED_ASSET_DIR, sha256, ext),
+        None => format!("{}/{}", PINNED_ASSET_DIR, sha256),
+    }
+}
+
+/// SHA-256 of a file's contents, hex encoded
+pub fn file_sha256(path: &str) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The command as a copy-pasteable shell line
+pub fn shell_command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=,+@%".contains(c)) {
+                arg.to_string()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Encoders FFmpeg reported in its stream mapping, or the ones the command asked for
+fn ffmpeg_encoders(command: &Command, stderr: &str) -> Vec<String> {
+    let mapping = regex::Regex::new(r"Stream #\d+:\d+ -> #\d+:\d+ \((?:.*-> \S+ \(([^)]+)\)|(copy))\)").unwrap();
+    let mut encoders: Vec<String> = Vec::new();
+    for caps in mapping.captures_iter(stderr) {
+        if let Some(encoder) = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str().to_string()) {
+            if !encoders.contains(&encoder) {
+                encoders.push(encoder);
+            }
+        }
+    }
+
+    if encoders.is_empty() {
+        let args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        for pair in args.windows(2) {
+            let is_codec_flag = matches!(pair[0].as_str(), "-c" | "-c:v" | "-c:a" | "-codec" | "-codec:v" | "-codec:a" | "-vcodec" | "-acodec");
+            if is_codec_flag && !encoders.contains(&pair[1]) {
+                encoders.push(pair[1].clone());
+            }
+        }
+    }
+    encoders
+}
+
+/// Warning lines from FFmpeg's log, deduplicated and capped
+fn ffmpeg_warnings(stderr: &str) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+    for line in stderr.lines().map(str::trim) {
+        let lower = line.to_lowercase();
+        let is_warning = lower.contains("warning") || lower.contains("deprecated") || lower.contains("discarding")
+            || lower.contains("past duration") || lower.contains("non-monotonous");
+        if is_warning && !warnings.iter().any(|w| w == line) {
+            warnings.push(line.to_string());
+        }
+        if warnings.len() >= 20 {
+            break;
+        }
+    }
+    warnings
+}
+
+/// stderr patterns (lowercased) for each failure category, most specific first
+const FFMPEG_ERROR_PATTERNS: &[(crate::types::FfmpegErrorCategory, &[&str])] = {
+    use crate::types::FfmpegErrorCategory::*;
+    &[
+        (DiskFull, &["no space left on device", "disk quota exceeded"]),
+        (PermissionDenied, &["permission denied", "operation not permitted"]),
+        (FileNotFound, &["no such file or directory"]),
+        (OddDimensions, &["not divisible by 2"]),
+        (UnsupportedPixelFormat, &[
+            "incompatible pixel format",
+            "pixel format not supported",
+            "is invalid or not supported",
+            "does not support pixel format",
+            "unsupported pixel format",
+            "impossible to convert between the formats",
+        ]),
+        (UnknownEncoder, &["unknown encoder", "encoder not found", "codec not currently supported in container"]),
+        (MissingAudioStream, &[":a' matches no streams", ":a matches no streams", "does not contain any audio", "no audio stream"]),
+        (MissingVideoStream, &[":v' matches no streams", ":v matches no streams", "does not contain any video", "no video stream"]),
+        (CorruptInput, &[
+            "moov atom not found",
+            "invalid data found when processing input",
+            "error while decoding",
+            "invalid nal unit",
+            "header missing",
+            "corrupt",
+            "truncated",
+        ]),
+        (InvalidFilter, &[
+            "no such filter",
+            "error initializing filter",
+            "error reinitializing filters",
+            "failed to configure output pad",
+            "error parsing filterchain",
+            "invalid stream specifier",
+        ]),
+    ]
+};
+
+/// Map FFmpeg stderr to a failure category with a user-facing explanation and a remediation the agent can apply
+pub fn classify_ffmpeg_error(stderr: &str) -> crate::types::FfmpegFailure {
+    use crate::types::FfmpegErrorCategory::*;
+
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let matched = FFMPEG_ERROR_PATTERNS.iter().find_map(|(category, patterns)| {
+        lines
+            .iter()
+            .find(|line| {
+                let lower = line.to_lowercase();
+                patterns.iter().any(|p| lower.contains(p))
+            })
+            .map(|line| (*category, line.to_string()))
+    });
+    let (category, detail) = matched.unwrap_or_else(|| {
+        // Unclassified: the last lines usually carry the actual error
+        let tail = lines[lines.len().saturating_sub(3)..].join(" | ");
+        (Unknown, tail)
+    });
+    ffmpeg_failure(category, &detail)
+}
+
+/// The explanation and remediation for a failure category
+pub fn ffmpeg_failure(category: crate::types::FfmpegErrorCategory, detail: &str) -> crate::types::FfmpegFailure {
+    use crate::types::FfmpegErrorCategory::*;
+
+    let (message, remediation, retryable) = match category {
+        UnsupportedPixelFormat => (
+            "The video uses a pixel format the chosen encoder or filter can't handle",
+            "Convert to a standard format first (add `format=yuv420p` to the filter chain or `-pix_fmt yuv420p` to the output), e.g. run convert_format before this step",
+            true,
+        ),
+        MissingAudioStream => (
+            "The input has no audio track, but this operation needs one",
+            "Skip the audio step for this file, or give it a soundtrack first with add_audio (music or generated silence) and retry",
+            true,
+        ),
+        MissingVideoStream => (
+            "The input has no video track (it may be an audio-only file)",
+            "Use a video file as input, or create a picture track first (e.g. create_blank_video or an image) and combine it with the audio",
+            true,
+        ),
+        CorruptInput => (
+            "The input file is damaged or incomplete (often an interrupted upload or recording)",
+            "Re-upload or re-download the file; if it still plays partially, re-encode it with convert_format to rebuild its index and retry",
+            true,
+        ),
+        DiskFull => (
+            "The server ran out of disk space while writing the output",
+            "Free up space by deleting old outputs or scratch files, then retry; don't retry automatically",
+            false,
+        ),
+        FileNotFound => (
+            "An input file doesn't exist at the given path",
+            "Check the path (outputs live under outputs/), list the session's files, and retry with the correct one",
+            true,
+        ),
+        PermissionDenied => (
+            "The server isn't allowed to read the input or write the output location",
+            "Write to a path under outputs/ and make sure the input is one of the session's files",
+            true,
+        ),
+        UnknownEncoder => (
+            "The requested codec isn't available in this FFmpeg build or container",
+            "Fall back to a widely supported codec: libx264 + aac in MP4, or libvpx-vp9 + libopus in WebM",
+            true,
+        ),
+        InvalidFilter => (
+            "A filter in the effect chain is invalid or got bad parameters",
+            "Check the effect parameters (ranges, names, timestamps inside the video's duration) and retry with corrected values",
+            true,
+        ),
+        OddDimensions => (
+            "The output width or height is an odd number, which H.264 can't encode",
+            "Round the target size to even numbers (e.g. scale=-2:720 or crop to even width/height) and retry",
+            true,
+        ),
+        Unknown => (
+            "FFmpeg failed for an unrecognized reason",
+            "Inspect the input with analyze_video; try a simpler variant of the operation or re-encode the input with convert_format",
+            true,
+        ),
+    };
+
+    crate::types::FfmpegFailure {
+        category,
+        message: message.to_string(),
+        remediation: remediation.to_string(),
+        retryable,
+        detail: detail.chars().take(500).collect(),
+    }
+}
+
 /// Execute FFprobe for media analysis
 pub fn execute_ffprobe_command(args: &[&str]) -> Result<String, String> {
     let output = Command::new("ffprobe")
@@ -283,47 +732,19 @@ pub fn cleanup_temp_files(files: &[String]) {
 
 /// Convert time in seconds to FFmpeg time format (HH:MM:SS.mmm)
 pub fn seconds_to_ffmpeg_time(seconds: f64) -> String {
-    let hours = (seconds / 3600.0) as u32;
-    let minutes = ((seconds % 3600.0) / 60.0) as u32;
-    let secs = (seconds % 60.0) as u32;
-    let millis = ((seconds % 1.0) * 1000.0) as u32;
-    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+    timecode::format(seconds)
 }
 
-/// Extract specific information from ffprobe output
+/// Extract one field from a file's probed media info
 pub fn get_media_info(file_path: &str, info_type: &str) -> Result<String, String> {
-    let args = match info_type {
-        "duration" => vec![
-            "-v", "quiet",
-            "-show_entries", "format=duration",
-            "-of", "csv=p=0",
-            file_path
-        ],
-        "width" => vec![
-            "-v", "quiet",
-            "-select_streams", "v:0",
-            "-show_entries", "stream=width",
-            "-of", "csv=p=0",
-            file_path
-        ],
-        "height" => vec![
-            "-v", "quiet",
-            "-select_streams", "v:0",
-            "-show_entries", "stream=height",
-            "-of", "csv=p=0",
-            file_path
-        ],
-        "fps" => vec![
-            "-v", "quiet",
-            "-select_streams", "v:0",
-            "-show_entries", "stream=r_frame_rate",
-            "-of", "csv=p=0",
-            file_path
-        ],
-        _ => return Err("Unknown info type".to_string()),
-    };
-
-    execute_ffprobe_command(&args)
+    let info = crate::core::probe_media(file_path)?;
+    match info_type {
+        "duration" => Ok(info.duration_seconds.to_string()),
+        "width" => Ok(info.width().to_string()),
+        "height" => Ok(info.height().to_string()),
+        "fps" => Ok(info.fps().to_string()),
+        _ => Err("Unknown info type".to_string()),
+    }
 }
 
 /// Execute FFmpeg with complex filter graphs
@@ -382,17 +803,259 @@ pub fn create_blank_video(
     width: u32,
     height: u32,
     color: &str,
+    fps: Option<f64>,
 ) -> Result<String, String> {
+    let rate = fps.map(|fps| format!(":r={}", fps)).unwrap_or_default();
     let mut command = Command::new("ffmpeg");
     command
         .arg("-f")
         .arg("lavfi")
         .arg("-i")
-        .arg(format!("color=c={}:s={}x{}:d={}", color, width, height, duration))
+        .arg(format!("color=c={}:s={}x{}:d={}{}", color, width, height, duration, rate))
         .arg("-c:v")
         .arg("libx264")
         .arg("-y")
         .arg(output_file);
 
     execute_ffmpeg_command(command)
-}
\ No newline at end of file
+}
+
+/// Escape text for use inside an FFmpeg drawtext `text='...'` option
+pub fn escape_drawtext(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\'', "\u{2019}")
+        .replace(':', "\\:")
+        .replace('%', "\\%")
+        .replace(',', "\\,")
+}
+
+/// Greedy word wrap so long captions fit on screen (drawtext does not wrap)
+pub fn wrap_text(text: &str, max_chars_per_line: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > max_chars_per_line {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Format seconds as an SRT timestamp (HH:MM:SS,mmm)
+pub fn srt_timestamp(seconds: f64) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        total_ms / 3_600_000,
+        (total_ms / 60_000) % 60,
+        (total_ms / 1000) % 60,
+        total_ms % 1000
+    )
+}
+
+/// Build an SRT document from (start, end, text) cues
+pub fn build_srt(cues: &[(f64, f64, String)]) -> String {
+    cues.iter()
+        .enumerate()
+        .map(|(i, (start, end, text))| {
+            format!("{}\n{} --> {}\n{}\n", i + 1, srt_timestamp(*start), srt_timestamp(*end), text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split narration into short caption cues spread across [start, start + duration] by word count
+pub fn chunk_caption_cues(text: &str, start: f64, duration: f64, words_per_cue: usize) -> Vec<(f64, f64, String)> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() || duration <= 0.0 {
+        return Vec::new();
+    }
+
+    let per_word = duration / words.len() as f64;
+    words
+        .chunks(words_per_cue.max(1))
+        .scan(start, |t, chunk| {
+            let cue_start = *t;
+            *t += per_word * chunk.len() as f64;
+            Some((cue_start, *t, chunk.join(" ")))
+        })
+        .collect()
+}
+
+/// Pull the title and readable body text out of an article's HTML
+pub fn extract_article_text(html: &str) -> (Option<String>, String) {
+    let title = regex::Regex::new(r"(?is)<title[^>]*>(.*?)</title>")
+        .ok()
+        .and_then(|re| re.captures(html).map(|c| decode_html_entities(c[1].trim())))
+        .filter(|t| !t.is_empty());
+
+    // Prefer <article>, then <main>, then the whole body
+    let body = ["article", "main", "body"]
+        .iter()
+        .find_map(|tag| {
+            regex::Regex::new(&format!(r"(?is)<{0}[^>]*>(.*)</{0}>", tag))
+                .ok()
+                .and_then(|re| re.captures(html).map(|c| c[1].to_string()))
+        })
+        .unwrap_or_else(|| html.to_string());
+
+    let noise = regex::Regex::new(r"(?is)<(script|style|nav|header|footer|aside|form|noscript)[^>]*>.*?</(script|style|nav|header|footer|aside|form|noscript)>").unwrap();
+    let cleaned = noise.replace_all(&body, " ");
+
+    // Keep paragraph and heading text only when the page has any
+    let para = regex::Regex::new(r"(?is)<(p|h[1-6]|li)[^>]*>(.*?)</(p|h[1-6]|li)>").unwrap();
+    let tags = regex::Regex::new(r"(?s)<[^>]+>").unwrap();
+    let blocks: Vec<String> = para
+        .captures_iter(&cleaned)
+        .map(|c| decode_html_entities(tags.replace_all(&c[2], "").split_whitespace().collect::<Vec<_>>().join(" ").as_str()))
+        .filter(|b| !b.is_empty())
+        .collect();
+
+    let text = if blocks.is_empty() {
+        decode_html_entities(tags.replace_all(&cleaned, " ").split_whitespace().collect::<Vec<_>>().join(" ").as_str())
+    } else {
+        blocks.join("\n\n")
+    };
+
+    (title, text)
+}
+
+/// Decode the handful of HTML entities that show up in article text
+pub fn decode_html_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&rsquo;", "\u{2019}")
+        .replace("&lsquo;", "\u{2018}")
+        .replace("&ldquo;", "\u{201C}")
+        .replace("&rdquo;", "\u{201D}")
+        .replace("&mdash;", "\u{2014}")
+        .replace("&ndash;", "\u{2013}")
+        .replace("&hellip;", "\u{2026}")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// One parsed CSV data row, keyed by header
+pub type CsvRow = std::collections::HashMap<String, String>;
+
+/// Parse CSV text (RFC 4180 quoting) into a header row and data rows keyed by header
+pub fn parse_csv(text: &str) -> Result<(Vec<String>, Vec<CsvRow>), String> {
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quoted field in CSV".to_string());
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push(record);
+    }
+
+    let mut records = records.into_iter();
+    let headers: Vec<String> = records
+        .next()
+        .ok_or("CSV is empty")?
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    if headers.iter().any(|h| h.is_empty()) {
+        return Err("CSV header contains an empty column name".to_string());
+    }
+
+    let rows = records
+        .enumerate()
+        .map(|(i, values)| {
+            if values.len() != headers.len() {
+                return Err(format!(
+                    "CSV row {} has {} columns, expected {}",
+                    i + 2,
+                    values.len(),
+                    headers.len()
+                ));
+            }
+            Ok(headers.iter().cloned().zip(values.into_iter().map(|v| v.trim().to_string())).collect())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok((headers, rows))
+}
+
+/// Replace `{{name}}` placeholders in every string of a JSON value
+pub fn fill_placeholders(value: &serde_json::Value, vars: &std::collections::HashMap<String, String>) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => {
+            let filled = vars.iter().fold(s.clone(), |acc, (key, val)| {
+                acc.replace(&format!("{{{{{}}}}}", key), val)
+            });
+            serde_json::Value::String(filled)
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|v| fill_placeholders(v, vars)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter().map(|(k, v)| (k.clone(), fill_placeholders(v, vars))).collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Parse an SRT timestamp (HH:MM:SS,mmm) into seconds
+pub fn parse_srt_timestamp(ts: &str) -> Option<f64> {
+    let (hms, ms) = ts.trim().split_once([',', '.']).unwrap_or((ts.trim(), "0"));
+    let parts: Vec<f64> = hms.split(':').map(|p| p.parse().ok()).collect::<Option<Vec<_>>>()?;
+    let ms: f64 = ms.parse().ok()?;
+    match parts.as_slice() {
+        [h, m, s] => Some(h * 3600.0 + m * 60.0 + s + ms / 1000.0),
+        [m, s] => Some(m * 60.0 + s + ms / 1000.0),
+        _ => None,
+    }
+}
+
+/// Parse an SRT document into (start, end, text) cues
+pub fn parse_srt(text: &str) -> Vec<(f64, f64, String)> {
+    text.replace("\r\n", "\n")
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+            let (start, end) = lines.next()?.split_once("-->")?;
+            let body = lines.collect::<Vec<_>>().join("\n");
+            Some((parse_srt_timestamp(start)?, parse_srt_timestamp(end.split_whitespace().next()?)?, body))
+        })
+        .filter(|(_, _, body)| !body.trim().is_empty())
+        .collect()
+}
@@ -0,0 +1,246 @@
+// utils/av_sync.rs - How far a video's sound is out of step with its picture
+//
+// Two checks, cheapest first:
+// - Container timestamps: the audio and video streams should start together. A start offset plays
+//   the whole file out of sync; a length mismatch is only noted, since a track may end early on
+//   purpose.
+// - Activity correlation: sound onsets (speech, hits, beats) tend to land with motion and cuts in the
+//   picture. The rise in loudness and the picture's change score are sampled at the same rate and
+//   cross-correlated, and the best-matching lag estimates the offset. It's a heuristic, so it only
+//   counts when the peak clearly stands out.
+use crate::types::MediaInfo;
+use crate::utils::execute_ffmpeg_command;
+use std::process::Command;
+
+/// Offset past which an output is flagged; viewers start noticing around 100 ms
+pub const DEFAULT_MAX_DRIFT_MS: f64 = 100.0;
+
+/// Sampling step of the activity curves (25 per second)
+const STEP_SECONDS: f64 = 0.04;
+
+/// Largest offset looked for, in steps (one second either way)
+const MAX_LAG_STEPS: usize = 25;
+
+/// Only the opening of long videos is analysed
+const ANALYSIS_SECONDS: u32 = 120;
+
+/// How far the best lag must stand out from the others (in standard deviations) to be trusted
+const MIN_PROMINENCE: f64 = 4.0;
+
+/// Quietest loudness counted, so silence doesn't swamp the onsets
+const FLOOR_DB: f64 = -60.0;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AvSyncReport {
+    /// Audio start minus video start; positive when the sound comes late
+    pub start_offset_ms: Option<f64>,
+    /// Audio length minus video length
+    pub length_difference_ms: Option<f64>,
+    /// Offset estimated from the activity curves; positive when the sound comes late
+    pub estimated_offset_ms: Option<f64>,
+    /// Why there's no estimate, if there isn't one
+    pub notes: Vec<String>,
+}
+
+impl AvSyncReport {
+    /// The larger of the start offset and the estimated offset
+    pub fn drift_ms(&self) -> f64 {
+        [self.start_offset_ms, self.estimated_offset_ms]
+            .into_iter()
+            .flatten()
+            .map(f64::abs)
+            .fold(0.0, f64::max)
+    }
+
+    pub fn exceeds(&self, max_drift_ms: f64) -> bool {
+        self.drift_ms() > max_drift_ms
+    }
+
+    /// One line per finding, for a review
+    pub fn describe(&self) -> Vec<String> {
+        let direction = |ms: f64| if ms > 0.0 { "after" } else { "before" };
+        let mut lines = Vec::new();
+        if let Some(ms) = self.start_offset_ms {
+            lines.push(format!("Container timestamps: audio starts {:.0} ms {} the picture", ms.abs(), direction(ms)));
+        }
+        if let Some(ms) = self.length_difference_ms.filter(|ms| ms.abs() >= 1000.0 * STEP_SECONDS) {
+            let longer = if ms > 0.0 { "longer" } else { "shorter" };
+            lines.push(format!("Audio track is {:.0} ms {} than the picture", ms.abs(), longer));
+        }
+        if let Some(ms) = self.estimated_offset_ms {
+            lines.push(format!("Sound/picture activity: sound lands about {:.0} ms {} the picture", ms.abs(), direction(ms)));
+        }
+        lines.extend(self.notes.iter().cloned());
+        lines
+    }
+}
+
+/// Start offset and length difference of the first audio and video streams
+pub fn container_drift(info: &MediaInfo) -> (Option<f64>, Option<f64>) {
+    let (Some(video), Some(audio)) = (info.video(), info.audio()) else {
+        return (None, None);
+    };
+    let start = match (audio.start_seconds, video.start_seconds) {
+        (Some(a), Some(v)) => Some((a - v) * 1000.0),
+        _ => None,
+    };
+    let length = match (audio.duration_seconds, video.duration_seconds) {
+        (Some(a), Some(v)) => Some((a - v) * 1000.0),
+        _ => None,
+    };
+    (start, length)
+}
+
+/// Check a video's audio against its picture
+pub fn check(input_file: &str) -> Result<AvSyncReport, String> {
+    let info = crate::core::probe_media(input_file)?;
+    if !info.has_video() || !info.has_audio() {
+        return Ok(AvSyncReport {
+            notes: vec!["No audio and video to compare".to_string()],
+            ..Default::default()
+        });
+    }
+    let (start_offset_ms, length_difference_ms) = container_drift(&info);
+    let mut report = AvSyncReport { start_offset_ms, length_difference_ms, ..Default::default() };
+
+    let sound = onsets(&activity(input_file, "a", "lavfi.astats.Overall.RMS_level")?);
+    let picture = onsets(&activity(input_file, "v", "lavfi.scene_score")?);
+    match estimate_offset(&sound, &picture) {
+        Some(offset) => report.estimated_offset_ms = Some(offset * 1000.0),
+        None => report.notes.push(
+            "Sound/picture activity: no clear match between sound onsets and picture changes, so no estimate".to_string(),
+        ),
+    }
+    Ok(report)
+}
+
+/// Loudness ("a") or picture change ("v") of the opening of a video, one value per step
+fn activity(input_file: &str, stream: &str, key: &str) -> Result<Vec<f64>, String> {
+    let filter = if stream == "a" {
+        let samples = (8000.0 * STEP_SECONDS) as u32;
+        format!("aresample=8000,asetnsamples=n={}:p=0,astats=metadata=1:reset=1,ametadata=print:key={}:file=-", samples, key)
+    } else {
+        format!("fps={},scale=160:-2,select='gte(scene,0)',metadata=print:key={}:file=-", 1.0 / STEP_SECONDS, key)
+    };
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-hide_banner")
+        .arg("-t")
+        .arg(ANALYSIS_SECONDS.to_string())
+        .arg("-i")
+        .arg(input_file)
+        .arg("-map")
+        .arg(format!("0:{}:0", stream))
+        .arg(if stream == "a" { "-af" } else { "-vf" })
+        .arg(filter)
+        .arg("-f")
+        .arg("null")
+        .arg("-");
+
+    Ok(printed_series(&execute_ffmpeg_command(command)?, key))
+}
+
+/// Values of `key` in `metadata=print` output, placed by their frame's time at one per step. Steps
+/// without a value repeat the one before.
+fn printed_series(printed: &str, key: &str) -> Vec<f64> {
+    let mut series: Vec<Option<f64>> = Vec::new();
+    let mut time = None;
+    for line in printed.lines() {
+        if let Some(pts) = line.split_whitespace().find_map(|field| field.strip_prefix("pts_time:")) {
+            time = pts.parse::<f64>().ok();
+        } else if let Some(value) = line.trim().strip_prefix(key).and_then(|rest| rest.strip_prefix('=')) {
+            let (Some(time), Ok(value)) = (time, value.parse::<f64>()) else {
+                continue;
+            };
+            let step = (time / STEP_SECONDS).round().max(0.0) as usize;
+            if step >= series.len() {
+                series.resize(step + 1, None);
+            }
+            series[step] = Some(value.max(FLOOR_DB));
+        }
+    }
+    let mut last = 0.0;
+    series
+        .into_iter()
+        .map(|value| {
+            last = value.unwrap_or(last);
+            last
+        })
+        .collect()
+}
+
+/// Where the curve rises (an onset), standardized so curves of different units compare
+fn onsets(series: &[f64]) -> Vec<f64> {
+    let rises: Vec<f64> = series.windows(2).map(|pair| (pair[1] - pair[0]).max(0.0)).collect();
+    standardize(&rises)
+}
+
+fn standardize(values: &[f64]) -> Vec<f64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let deviation = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt();
+    if deviation < 1e-9 {
+        return vec![0.0; values.len()];
+    }
+    values.iter().map(|v| (v - mean) / deviation).collect()
+}
+
+/// Lag (seconds) by which the sound onsets best match the picture's, positive when the sound comes
+/// late; None without enough material or a clear best match
+pub fn estimate_offset(sound: &[f64], picture: &[f64]) -> Option<f64> {
+    let length = sound.len().min(picture.len());
+    if length < 4 * MAX_LAG_STEPS {
+        return None;
+    }
+    let lags: Vec<i64> = (-(MAX_LAG_STEPS as i64)..=MAX_LAG_STEPS as i64).collect();
+    let scores: Vec<f64> = lags
+        .iter()
+        .map(|&lag| {
+            let pairs = (0..length).filter_map(|i| {
+                let j = i as i64 + lag;
+                (0..length as i64).contains(&j).then(|| sound[j as usize] * picture[i])
+            });
+            let (sum, count) = pairs.fold((0.0, 0), |(sum, count), product| (sum + product, count + 1));
+            sum / count.max(1) as f64
+        })
+        .collect();
+
+    let (best, &peak) = scores.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+    let prominence = standardize(&scores)[best];
+    (peak > 0.0 && prominence >= MIN_PROMINENCE).then(|| lags[best] as f64 * STEP_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_are_estimated_from_matching_onsets() {
+        // Picture changes on irregular beats; the sound follows three steps (120 ms) later
+        let mut picture = vec![0.0; 600];
+        for (n, i) in (30..570).step_by(37).enumerate() {
+            picture[i] = 1.0 + (n % 3) as f64;
+        }
+        let mut sound = vec![0.0; 600];
+        sound[3..].copy_from_slice(&picture[..597]);
+        let offset = estimate_offset(&standardize(&sound), &standardize(&picture)).unwrap();
+        assert!((offset - 0.12).abs() < 1e-9, "{}", offset);
+
+        // Flat curves match nothing
+        assert_eq!(estimate_offset(&vec![0.0; 600], &standardize(&picture)), None);
+    }
+
+    #[test]
+    fn printed_metadata_becomes_an_evenly_stepped_series() {
+        let printed = "frame:0    pts:0       pts_time:0\nlavfi.astats.Overall.RMS_level=-20.5\n\
+                       frame:1    pts:640     pts_time:0.08\nlavfi.astats.Overall.RMS_level=-inf\n";
+        assert_eq!(printed_series(printed, "lavfi.astats.Overall.RMS_level"), [-20.5, -20.5, FLOOR_DB]);
+
+        let report = AvSyncReport { start_offset_ms: Some(-40.0), estimated_offset_ms: Some(160.0), ..Default::default() };
+        assert_eq!(report.drift_ms(), 160.0);
+        assert!(report.exceeds(DEFAULT_MAX_DRIFT_MS));
+        assert!(report.describe()[0].contains("audio starts 40 ms before the picture"));
+    }
+}
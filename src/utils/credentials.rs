@@ -0,0 +1,84 @@
+//! Encryption at rest for credentials users give us for third-party servers (delivery target
+//! passwords and secret access keys). Values are sealed with AES-256-GCM under a key derived from
+//! `auth.credentials_key`, falling back to `auth.jwt_secret`, and stored as `enc:v1:<base64>`.
+//! Rows saved before encryption are plain text and read back as they are.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use sha2::{Digest, Sha256};
+
+const PREFIX: &str = "enc:v1:";
+
+fn key_material() -> Result<String, String> {
+    let auth = &crate::config::get().auth;
+    auth.credentials_key
+        .clone()
+        .or_else(|| auth.jwt_secret.clone())
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| "auth.credentials_key (or auth.jwt_secret) must be set to store credentials".to_string())
+}
+
+fn cipher(material: &str) -> LessSafeKey {
+    let key = Sha256::digest(material.as_bytes());
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).expect("SHA-256 output is a valid AES-256 key"))
+}
+
+/// Encrypt a credential for storage
+pub fn seal(plaintext: &str) -> Result<String, String> {
+    seal_with(&key_material()?, plaintext)
+}
+
+/// Decrypt a stored credential; values without the `enc:v1:` prefix are returned unchanged
+pub fn open(stored: &str) -> Result<String, String> {
+    if !stored.starts_with(PREFIX) {
+        return Ok(stored.to_string());
+    }
+    open_with(&key_material()?, stored)
+}
+
+fn seal_with(material: &str, plaintext: &str) -> Result<String, String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new().fill(&mut nonce).map_err(|_| "Failed to generate a nonce".to_string())?;
+    let mut sealed = plaintext.as_bytes().to_vec();
+    cipher(material)
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+        .map_err(|_| "Failed to encrypt credential".to_string())?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&sealed);
+    Ok(format!("{}{}", PREFIX, STANDARD.encode(payload)))
+}
+
+fn open_with(material: &str, stored: &str) -> Result<String, String> {
+    let Some(encoded) = stored.strip_prefix(PREFIX) else {
+        return Ok(stored.to_string());
+    };
+    let mut payload = STANDARD.decode(encoded).map_err(|e| format!("Stored credential is corrupt: {}", e))?;
+    if payload.len() < NONCE_LEN {
+        return Err("Stored credential is corrupt".to_string());
+    }
+    let mut sealed = payload.split_off(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(&payload).map_err(|_| "Stored credential is corrupt".to_string())?;
+    let plaintext = cipher(material)
+        .open_in_place(nonce, Aad::empty(), &mut sealed)
+        .map_err(|_| "Stored credential can't be decrypted; was auth.credentials_key changed?".to_string())?;
+    String::from_utf8(plaintext.to_vec()).map_err(|_| "Stored credential is corrupt".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_round_trip_and_legacy_values_pass_through() {
+        let sealed = seal_with("server key", "hunter2").unwrap();
+        assert!(sealed.starts_with(PREFIX));
+        assert!(!sealed.contains("hunter2"));
+        assert_ne!(sealed, seal_with("server key", "hunter2").unwrap(), "each seal uses a fresh nonce");
+        assert_eq!(open_with("server key", &sealed).unwrap(), "hunter2");
+
+        assert!(open_with("another key", &sealed).is_err());
+        assert_eq!(open_with("server key", "plain old password").unwrap(), "plain old password");
+    }
+}
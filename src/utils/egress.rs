@@ -0,0 +1,154 @@
+//! Egress limits for files sent to clients. Each user (the owner of the files being sent) can have
+//! `limits.max_concurrent_downloads_per_user` downloads open at once, and their downloads together
+//! are paced to `limits.download_mbps_per_user`, so one user pulling a stack of 4K files can't
+//! saturate the NIC and stall renders.
+
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Seconds of a user's bandwidth a download can send in one burst after being idle
+const BURST_SECONDS: f64 = 1.0;
+
+#[derive(Debug)]
+struct UserEgress {
+    active: usize,
+    bucket: TokenBucket,
+}
+
+/// Bytes a user may send right now. Sends can overdraw it; each then waits out its share of the debt,
+/// which spreads the rate over however many downloads are running.
+#[derive(Debug)]
+struct TokenBucket {
+    available: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: f64, now: Instant) -> Self {
+        Self { available: bytes_per_second * BURST_SECONDS, updated: now }
+    }
+
+    /// Take `bytes` and return how long the sender must wait before they're within the rate
+    fn reserve(&mut self, bytes: usize, bytes_per_second: f64, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * bytes_per_second).min(bytes_per_second * BURST_SECONDS);
+        self.updated = now;
+        self.available -= bytes as f64;
+        if self.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.available / bytes_per_second)
+        }
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<String, UserEgress>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, UserEgress>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Bytes per second from `limits.download_mbps_per_user`; None when unlimited
+fn user_rate() -> Option<f64> {
+    let mbps = crate::config::get().limits.download_mbps_per_user;
+    (mbps > 0.0).then(|| mbps * 1_000_000.0 / 8.0)
+}
+
+/// Count a download against `key` unless it already has `max` open (0 = no limit)
+fn try_open(users: &mut HashMap<String, UserEgress>, key: &str, max: usize, rate: f64) -> bool {
+    let user = users
+        .entry(key.to_string())
+        .or_insert_with(|| UserEgress { active: 0, bucket: TokenBucket::new(rate, Instant::now()) });
+    if max > 0 && user.active >= max {
+        return false;
+    }
+    user.active += 1;
+    true
+}
+
+/// One open download; dropping it (when the response body finishes or the client goes away) frees the slot
+#[derive(Debug)]
+pub struct EgressPermit {
+    key: String,
+}
+
+impl Drop for EgressPermit {
+    fn drop(&mut self) {
+        let mut users = registry().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(user) = users.get_mut(&self.key) {
+            user.active = user.active.saturating_sub(1);
+            if user.active == 0 {
+                users.remove(&self.key);
+            }
+        }
+    }
+}
+
+impl EgressPermit {
+    /// Wait until `bytes` more may be sent under the user's bandwidth cap
+    async fn pace(&self, bytes: usize) {
+        let Some(rate) = user_rate() else { return };
+        let wait = {
+            let mut users = registry().lock().unwrap_or_else(|e| e.into_inner());
+            match users.get_mut(&self.key) {
+                Some(user) => user.bucket.reserve(bytes, rate, Instant::now()),
+                None => Duration::ZERO,
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Open a download for `key` (e.g. "user:42"); Err when the user already has the maximum open
+pub fn open(key: &str) -> Result<EgressPermit, String> {
+    let max = crate::config::get().limits.max_concurrent_downloads_per_user;
+    let mut users = registry().lock().unwrap_or_else(|e| e.into_inner());
+    if !try_open(&mut users, key, max, user_rate().unwrap_or(0.0)) {
+        return Err(format!("{} already has {} downloads open", key, max));
+    }
+    Ok(EgressPermit { key: key.to_string() })
+}
+
+/// A file body sent at the pace the permit's user is allowed; the permit is held until it ends
+pub fn throttle<S, B, E>(body: S, permit: EgressPermit) -> impl Stream<Item = Result<B, E>> + Send
+where
+    S: Stream<Item = Result<B, E>> + Send + Unpin + 'static,
+    B: AsRef<[u8]> + Send + 'static,
+    E: Send + 'static,
+{
+    futures::stream::unfold((body, permit), |(mut body, permit)| async move {
+        let chunk = body.next().await?;
+        if let Ok(bytes) = &chunk {
+            permit.pace(bytes.as_ref().len()).await;
+        }
+        Some((chunk, (body, permit)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downloads_are_capped_per_user_and_paced_to_the_rate() {
+        let mut users = HashMap::new();
+        assert!(try_open(&mut users, "user:1", 2, 1000.0));
+        assert!(try_open(&mut users, "user:1", 2, 1000.0));
+        assert!(!try_open(&mut users, "user:1", 2, 1000.0));
+        assert!(try_open(&mut users, "user:2", 2, 1000.0));
+        assert!(try_open(&mut users, "user:1", 0, 1000.0), "0 means no cap");
+
+        // 1000 B/s with a one-second burst: the first 1000 bytes go at once, the next 500 wait half a second
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000.0, start);
+        assert_eq!(bucket.reserve(1000, 1000.0, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(500, 1000.0, start), Duration::from_millis(500));
+        // A second download overdrawing the same bucket waits behind the first
+        assert_eq!(bucket.reserve(500, 1000.0, start), Duration::from_secs(1));
+        // Two seconds later the debt is paid and the bucket has refilled to one burst, no more
+        assert_eq!(bucket.reserve(1000, 1000.0, start + Duration::from_secs(3)), Duration::ZERO);
+    }
+}
@@ -0,0 +1,229 @@
+// utils/file_locks.rs - Leases on the files tool calls read and write
+//
+// Two agent commands running at once (a chat edit and a background job, or two jobs of a graph) can
+// target the same working file: one renders outputs/final.mp4 while the other reads or rewrites it,
+// and both end up with a corrupt file. Each tool call leases its files first. Any number of calls
+// may read a file together, but a call writing it has it to itself. A call that can't get its lease
+// waits up to `limits.file_lock_wait_seconds`, then fails with a message naming the edit in the way.
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Arguments tools read a file from
+const INPUT_KEYS: &[&str] = &[
+    "input_file", "input_path", "input", "video_path", "file_path", "image_path", "audio_file",
+    "background_file", "overlay_file", "pip_video", "portrait_image",
+];
+
+/// Arguments holding several input files
+const INPUT_LIST_KEYS: &[&str] = &["input_files", "video_files"];
+
+/// Arguments tools write a file to
+const OUTPUT_KEYS: &[&str] = &["output_file", "output_path", "output", "output_video"];
+
+/// Who holds a file: the writer, or the readers
+#[derive(Debug, Default)]
+struct FileState {
+    writer: Option<String>,
+    readers: Vec<String>,
+}
+
+struct Registry {
+    files: Mutex<HashMap<String, FileState>>,
+    released: Notify,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry { files: Mutex::new(HashMap::new()), released: Notify::new() })
+}
+
+/// "./outputs/a.mp4" and "outputs/a.mp4" are the same file
+fn normalize(path: &str) -> String {
+    path.trim().trim_start_matches("./").to_string()
+}
+
+/// Files a tool call reads and writes
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileAccess {
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+}
+
+impl FileAccess {
+    /// The files named in a tool call's arguments. A file both read and written (an in-place edit)
+    /// counts as written.
+    pub fn of_call(args: &serde_json::Value) -> Self {
+        let text = |key: &&str| args.get(*key).and_then(|v| v.as_str()).filter(|p| !p.trim().is_empty()).map(normalize);
+        let mut writes: Vec<String> = OUTPUT_KEYS.iter().filter_map(text).collect();
+        writes.sort();
+        writes.dedup();
+
+        let mut reads: Vec<String> = INPUT_KEYS.iter().filter_map(text).collect();
+        for key in INPUT_LIST_KEYS {
+            if let Some(list) = args.get(*key).and_then(|v| v.as_array()) {
+                reads.extend(list.iter().filter_map(|v| v.as_str()).filter(|p| !p.trim().is_empty()).map(normalize));
+            }
+        }
+        reads.retain(|path| !writes.contains(path));
+        reads.sort();
+        reads.dedup();
+        Self { reads, writes }
+    }
+
+    /// Map the written paths to where the tool actually writes them
+    pub fn map_writes(mut self, to_path: impl Fn(&str) -> String) -> Self {
+        self.writes = self.writes.iter().map(|path| normalize(&to_path(path))).collect();
+        self.writes.sort();
+        self.writes.dedup();
+        self.reads.retain(|path| !self.writes.contains(path));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty() && self.writes.is_empty()
+    }
+}
+
+/// Held while a tool call runs; dropping it releases the files and wakes calls waiting for them
+#[derive(Debug)]
+pub struct FileLease {
+    access: FileAccess,
+    holder: String,
+}
+
+impl Drop for FileLease {
+    fn drop(&mut self) {
+        if self.access.is_empty() {
+            return;
+        }
+        let registry = registry();
+        let mut files = registry.files.lock().unwrap_or_else(|e| e.into_inner());
+        for path in &self.access.writes {
+            if let Some(state) = files.get_mut(path) {
+                state.writer = None;
+            }
+        }
+        for path in &self.access.reads {
+            if let Some(state) = files.get_mut(path) {
+                if let Some(index) = state.readers.iter().position(|reader| reader == &self.holder) {
+                    state.readers.remove(index);
+                }
+            }
+        }
+        files.retain(|_, state| state.writer.is_some() || !state.readers.is_empty());
+        drop(files);
+        registry.released.notify_waiters();
+    }
+}
+
+/// Take every file in `access` at once, or none of them; Err describes the first conflict
+fn try_lease(files: &mut HashMap<String, FileState>, access: &FileAccess, holder: &str) -> Result<(), String> {
+    for path in &access.writes {
+        if let Some(state) = files.get(path) {
+            if let Some(writer) = &state.writer {
+                return Err(format!("{} is being written by another edit ({})", path, writer));
+            }
+            if let Some(reader) = state.readers.first() {
+                return Err(format!("{} is being read by another edit ({})", path, reader));
+            }
+        }
+    }
+    for path in &access.reads {
+        if let Some(writer) = files.get(path).and_then(|state| state.writer.as_ref()) {
+            return Err(format!("{} is being written by another edit ({})", path, writer));
+        }
+    }
+
+    for path in &access.writes {
+        files.entry(path.clone()).or_default().writer = Some(holder.to_string());
+    }
+    for path in &access.reads {
+        files.entry(path.clone()).or_default().readers.push(holder.to_string());
+    }
+    Ok(())
+}
+
+/// Lease the files of a tool call for `holder` (e.g. "trim_video in session <id>"), waiting up to
+/// `wait` for other edits to release them
+pub async fn lease(access: FileAccess, holder: &str, wait: Duration) -> Result<FileLease, String> {
+    let registry = registry();
+    let deadline = Instant::now() + wait;
+    let mut waiting = false;
+    loop {
+        // Registered before checking, so a release between the check and the wait isn't missed
+        let released = registry.released.notified();
+        tokio::pin!(released);
+        released.as_mut().enable();
+
+        let conflict = {
+            let mut files = registry.files.lock().unwrap_or_else(|e| e.into_inner());
+            try_lease(&mut files, &access, holder)
+        };
+        match conflict {
+            Ok(()) => return Ok(FileLease { access, holder: holder.to_string() }),
+            Err(conflict) => {
+                if !waiting {
+                    tracing::info!("🔒 {} waits: {}", holder, conflict);
+                    waiting = true;
+                }
+                if tokio::time::timeout_at(deadline, released).await.is_err() {
+                    return Err(format!("{}. Try again once that edit finishes.", conflict));
+                }
+            }
+        }
+    }
+}
+
+/// How long a tool waits for a file in use (`limits.file_lock_wait_seconds`)
+pub fn lease_wait() -> Duration {
+    Duration::from_secs(crate::config::get().limits.file_lock_wait_seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tool_arguments_name_the_files_read_and_written() {
+        let access = FileAccess::of_call(&json!({
+            "input_file": "./uploads/a.mp4",
+            "input_files": ["uploads/b.mp4", "uploads/a.mp4"],
+            "output_file": "final.mp4",
+            "text": "not a path",
+        }))
+        .map_writes(|path| format!("outputs/{}", path));
+        assert_eq!(access.reads, ["uploads/a.mp4", "uploads/b.mp4"]);
+        assert_eq!(access.writes, ["outputs/final.mp4"]);
+
+        let in_place = FileAccess::of_call(&json!({ "input_file": "outputs/x.mp4", "output_file": "outputs/x.mp4" }));
+        assert!(in_place.reads.is_empty());
+        assert!(FileAccess::of_call(&json!({ "title": "My video" })).is_empty());
+    }
+
+    #[tokio::test]
+    async fn writers_exclude_everyone_and_readers_share() {
+        let write = FileAccess { reads: vec!["lock-test/in.mp4".into()], writes: vec!["lock-test/out.mp4".into()] };
+        let read_output = FileAccess { reads: vec!["lock-test/out.mp4".into()], writes: vec![] };
+        let read_input = FileAccess { reads: vec!["lock-test/in.mp4".into()], writes: vec![] };
+
+        let first = lease(write.clone(), "trim_video in session a", Duration::ZERO).await.unwrap();
+        let error = lease(read_output.clone(), "analyze_video in session b", Duration::ZERO).await.unwrap_err();
+        assert!(error.contains("lock-test/out.mp4 is being written by another edit (trim_video in session a)"), "{}", error);
+        let shared = lease(read_input, "analyze_video in session b", Duration::ZERO).await.unwrap();
+
+        // A waiting call gets the file as soon as the writer is done
+        let waiter = tokio::spawn(async move { lease(read_output, "analyze_video in session c", Duration::from_secs(5)).await.is_ok() });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(first);
+        assert!(waiter.await.unwrap());
+
+        let overwrite_input = FileAccess { reads: vec![], writes: vec!["lock-test/in.mp4".into()] };
+        let error = lease(overwrite_input, "trim_video in session d", Duration::ZERO).await.unwrap_err();
+        assert!(error.contains("lock-test/in.mp4 is being read by another edit"), "{}", error);
+        drop(shared);
+    }
+}
@@ -0,0 +1,205 @@
+// utils/gpu.rs - Optional GPU filter paths for scaling, overlay compositing and chroma key
+//
+// When FFmpeg can open a CUDA (or OpenCL) device, the heavy part of a filtergraph runs on it:
+// frames are moved to the device with hwupload, filtered with scale_cuda / overlay_cuda /
+// chromakey_cuda (or overlay_opencl), and brought back with hwdownload before encoding.
+// Every GPU command has a CPU equivalent that runs if the GPU one fails.
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Name the hardware device is registered under with `-init_hw_device`
+const DEVICE_NAME: &str = "gpu";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuBackend {
+    Cuda,
+    OpenCl,
+}
+
+impl GpuBackend {
+    pub fn device_type(self) -> &'static str {
+        match self {
+            GpuBackend::Cuda => "cuda",
+            GpuBackend::OpenCl => "opencl",
+        }
+    }
+
+    fn overlay_filter(self) -> &'static str {
+        match self {
+            GpuBackend::Cuda => "overlay_cuda",
+            GpuBackend::OpenCl => "overlay_opencl",
+        }
+    }
+
+    /// Whether FFmpeg can actually open a device of this type on this machine
+    fn device_works(self) -> bool {
+        Command::new("ffmpeg")
+            .args(["-hide_banner", "-v", "error", "-init_hw_device"])
+            .arg(format!("{}={}", self.device_type(), DEVICE_NAME))
+            .args(["-f", "lavfi", "-i", "color=s=64x64:d=0.1", "-frames:v", "1", "-f", "null", "-"])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// A usable GPU backend and the hardware filters this FFmpeg build has for it
+#[derive(Debug, Clone)]
+pub struct GpuFilters {
+    pub backend: GpuBackend,
+    filters: HashSet<String>,
+}
+
+/// Where and how an overlay is placed by `GpuFilters::overlay_graph`
+#[derive(Debug, Clone, Copy)]
+pub struct OverlayPlacement<'a> {
+    /// Resize the overlay to width/height expressions first
+    pub scale: Option<(&'a str, &'a str)>,
+    pub x: &'a str,
+    pub y: &'a str,
+    /// Keep the overlay's transparency
+    pub alpha: bool,
+}
+
+/// The GPU filter backend, detected once per process. `encoder.gpu_filters` forces "cuda", "opencl"
+/// or "off"; the default tries CUDA, then OpenCL. Reproducible renders and dry runs always stay on the CPU
+/// because GPU filters aren't bitexact across devices and drivers.
+pub fn filters() -> Option<&'static GpuFilters> {
+    static DETECTED: OnceLock<Option<GpuFilters>> = OnceLock::new();
+    if super::reproducible_render() || super::dry_run_render() {
+        return None;
+    }
+    DETECTED.get_or_init(detect).as_ref()
+}
+
+fn detect() -> Option<GpuFilters> {
+    let candidates: &[GpuBackend] = match crate::config::get().encoder.gpu_filters.to_lowercase().as_str() {
+        "off" | "cpu" | "none" => return None,
+        "cuda" => &[GpuBackend::Cuda],
+        "opencl" => &[GpuBackend::OpenCl],
+        _ => &[GpuBackend::Cuda, GpuBackend::OpenCl],
+    };
+
+    let available = ffmpeg_filter_names();
+    for &backend in candidates {
+        let suffix = format!("_{}", backend.device_type());
+        let filters: HashSet<String> = available
+            .iter()
+            .filter(|name| name.ends_with(&suffix) || name.as_str() == "hwupload" || name.as_str() == "hwdownload")
+            .cloned()
+            .collect();
+        let usable = ["hwupload", "hwdownload", backend.overlay_filter()].iter().all(|f| filters.contains(*f));
+        if usable && backend.device_works() {
+            tracing::info!("🚀 GPU filters enabled ({}): {}", backend.device_type(), {
+                let mut names: Vec<&str> = filters.iter().map(String::as_str).collect();
+                names.sort();
+                names.join(", ")
+            });
+            return Some(GpuFilters { backend, filters });
+        }
+    }
+    None
+}
+
+/// Every filter name listed by `ffmpeg -filters`
+fn ffmpeg_filter_names() -> HashSet<String> {
+    let Ok(output) = Command::new("ffmpeg").args(["-hide_banner", "-filters"]).output() else {
+        return HashSet::new();
+    };
+    // Lines look like " ... scale_cuda        V->V       GPU accelerated video resizer"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (_flags, name, io) = (fields.next()?, fields.next()?, fields.next()?);
+            io.contains("->").then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Run the GPU variant of a command when one was built, falling back to the CPU command if it fails
+pub fn run_with_fallback(gpu: Option<Command>, cpu: impl FnOnce() -> Command) -> Result<String, String> {
+    if let Some(command) = gpu {
+        match super::execute_ffmpeg_command(command) {
+            Ok(output) => return Ok(output),
+            Err(e) => tracing::warn!("GPU filter path failed, retrying on the CPU: {}", e),
+        }
+    }
+    super::execute_ffmpeg_command(cpu())
+}
+
+impl GpuFilters {
+    pub fn has(&self, filter: &str) -> bool {
+        self.filters.contains(filter)
+    }
+
+    /// Global options that open the device for `hwupload`; they go before the first `-i`
+    pub fn device_args(&self) -> Vec<String> {
+        vec![
+            "-init_hw_device".to_string(),
+            format!("{}={}", self.backend.device_type(), DEVICE_NAME),
+            "-filter_hw_device".to_string(),
+            DEVICE_NAME.to_string(),
+        ]
+    }
+
+    /// `-vf` chain resizing on the GPU; None when the backend has no scaler
+    pub fn scale(&self, width: &str, height: &str) -> Option<String> {
+        if !self.has("scale_cuda") {
+            return None;
+        }
+        Some(format!("format=nv12,hwupload,scale_cuda={}:{},hwdownload,format=nv12", width, height))
+    }
+
+    /// Filtergraph compositing `[overlay]` onto `[main]` on the GPU as `placement` says, labelled `[out]`
+    /// in system memory. The overlay is resized on the GPU when possible.
+    /// None when the backend can't place the overlay, e.g. OpenCL with expression positions.
+    pub fn overlay_graph(&self, main: &str, overlay: &str, placement: &OverlayPlacement, out: &str) -> Option<String> {
+        let position = self.position(placement.x, placement.y)?;
+        let format = if placement.alpha { "yuva420p" } else { "yuv420p" };
+        // scale_cuda has no alpha formats, so transparent overlays are resized before the upload
+        let overlay_chain = match placement.scale {
+            Some((w, h)) if self.has("scale_cuda") && !placement.alpha => format!("format={},hwupload,scale_cuda={}:{}", format, w, h),
+            Some((w, h)) => format!("scale={}:{},format={},hwupload", w, h, format),
+            None => format!("format={},hwupload", format),
+        };
+        Some(self.composite(main, overlay, &overlay_chain, &position, out))
+    }
+
+    /// Key `color` out of `[foreground]` and composite it over `[background]`, labelled `[out]`.
+    /// CUDA keys with chromakey_cuda when available (YUV distance, so `similarity` reads slightly
+    /// differently from the CPU colorkey); otherwise the key runs on the CPU and only the overlay on the GPU.
+    pub fn chroma_key_graph(&self, background: &str, foreground: &str, color: &str, similarity: f32, blend: f32, out: &str) -> Option<String> {
+        let position = self.position("0", "0")?;
+        let keyed = if self.has("chromakey_cuda") {
+            format!("format=yuv420p,hwupload,chromakey_cuda=color={}:similarity={}:blend={}", color, similarity, blend)
+        } else {
+            format!("colorkey=color={}:similarity={}:blend={},format=yuva420p,hwupload", color, similarity, blend)
+        };
+        Some(self.composite(background, foreground, &keyed, &position, out))
+    }
+
+    /// overlay_cuda takes position expressions; overlay_opencl only plain pixel offsets
+    fn position(&self, x: &str, y: &str) -> Option<String> {
+        match self.backend {
+            GpuBackend::Cuda => Some(format!("x={}:y={}", x, y)),
+            GpuBackend::OpenCl => {
+                let (x, y) = (x.trim().parse::<i64>().ok()?, y.trim().parse::<i64>().ok()?);
+                Some(format!("x={}:y={}", x, y))
+            }
+        }
+    }
+
+    fn composite(&self, main: &str, overlay: &str, overlay_chain: &str, position: &str, out: &str) -> String {
+        format!(
+            "[{main}]format=yuv420p,hwupload[gpu_main];[{overlay}]{chain}[gpu_overlay];[gpu_main][gpu_overlay]{filter}={position},hwdownload,format=yuv420p[{out}]",
+            main = main,
+            overlay = overlay,
+            chain = overlay_chain,
+            filter = self.backend.overlay_filter(),
+            position = position,
+            out = out,
+        )
+    }
+}
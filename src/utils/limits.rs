@@ -0,0 +1,193 @@
+// utils/limits.rs - Resource limits for the FFmpeg processes `execute_ffmpeg_command` starts
+//
+// Each command gets a wall-clock timeout and a cap on the size of the file it writes (`-fs`, checked
+// again once FFmpeg exits), and optionally runs at a lower CPU priority (nice), in the idle I/O class
+// (ionice) and under a memory cap (prlimit), so one corrupt or malicious input can't run FFmpeg
+// forever or fill the disk. The wrappers exec FFmpeg, so the pid that gets tracked and killed is
+// FFmpeg's own. How long a whole job may run is up to the `JobManager`.
+use super::processes::{output_path, signal, KILL_GRACE};
+use std::ffi::OsString;
+use std::io;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::Duration;
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessLimits {
+    pub timeout: Option<Duration>,
+    pub max_output_bytes: Option<u64>,
+    /// nice level, 0 = normal priority
+    pub niceness: u8,
+    pub idle_io: bool,
+    pub memory_bytes: Option<u64>,
+}
+
+impl ProcessLimits {
+    /// Limits from the `[limits]` config
+    pub fn from_config() -> Self {
+        let limits = &crate::config::get().limits;
+        let bytes = |mb: u64| (mb > 0).then_some(mb * MB);
+        Self {
+            timeout: (limits.ffmpeg_timeout_minutes > 0).then(|| Duration::from_secs(limits.ffmpeg_timeout_minutes * 60)),
+            max_output_bytes: bytes(limits.max_output_mb),
+            niceness: limits.ffmpeg_niceness,
+            idle_io: limits.ffmpeg_idle_io,
+            memory_bytes: bytes(limits.ffmpeg_memory_mb),
+        }
+    }
+
+    /// The command to spawn in place of `command`: behind nice, ionice and prlimit as configured, and
+    /// with `-fs` ahead of FFmpeg's output file. Wrappers missing on this machine are left out.
+    pub fn apply(&self, command: &Command) -> Command {
+        let mut args: Vec<OsString> = command.get_args().map(|a| a.to_os_string()).collect();
+        let is_ffmpeg = command.get_program() == "ffmpeg";
+        if let (Some(max), true, Some(_)) = (self.max_output_bytes, is_ffmpeg, output_path(command)) {
+            if !args.iter().any(|a| a == "-fs") {
+                let at = args.len() - 1;
+                args.splice(at..at, ["-fs".into(), max.to_string().into()]);
+            }
+        }
+
+        let mut line: Vec<OsString> = Vec::new();
+        if self.niceness > 0 && available("nice") {
+            line.extend(["nice".into(), "-n".into(), self.niceness.to_string().into()]);
+        }
+        if self.idle_io && available("ionice") {
+            line.extend(["ionice".into(), "-c".into(), "3".into()]);
+        }
+        if let (Some(bytes), true) = (self.memory_bytes, available("prlimit")) {
+            line.extend(["prlimit".into(), format!("--data={}", bytes).into(), "--".into()]);
+        }
+        line.push(command.get_program().to_os_string());
+        line.extend(args);
+
+        let mut limited = Command::new(&line[0]);
+        limited.args(&line[1..]);
+        if let Some(dir) = command.get_current_dir() {
+            limited.current_dir(dir);
+        }
+        for (key, value) in command.get_envs() {
+            match value {
+                Some(value) => limited.env(key, value),
+                None => limited.env_remove(key),
+            };
+        }
+        limited
+    }
+
+    /// Kill `child` if it runs past the timeout; None when there is no timeout
+    pub(crate) fn watch(&self, child: &Child) -> Option<Watchdog> {
+        let timeout = self.timeout?;
+        let pid = child.id();
+        let (done, finished) = mpsc::channel::<()>();
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let flag = timed_out.clone();
+        std::thread::spawn(move || {
+            // The watched command drops its sender once its process has exited
+            if finished.recv_timeout(timeout) != Err(mpsc::RecvTimeoutError::Timeout) {
+                return;
+            }
+            flag.store(true, Ordering::SeqCst);
+            tracing::warn!("⏱️ FFmpeg process {} ran longer than {}s, stopping it", pid, timeout.as_secs());
+            signal(pid, "TERM");
+            if finished.recv_timeout(KILL_GRACE) == Err(mpsc::RecvTimeoutError::Timeout) {
+                signal(pid, "KILL");
+            }
+        });
+        Some(Watchdog { _done: done, timed_out, timeout })
+    }
+
+    /// Fail an output that hit the size cap: FFmpeg stops writing at `-fs` but still exits cleanly
+    pub fn check_output(&self, command: &Command) -> Result<(), String> {
+        let (Some(max), Some(output)) = (self.max_output_bytes, output_path(command)) else {
+            return Ok(());
+        };
+        match std::fs::metadata(&output) {
+            Ok(metadata) if metadata.is_file() && metadata.len() >= max => {
+                let _ = std::fs::remove_file(&output);
+                Err(format!(
+                    "Output {} reached the {} MB size limit (limits.max_output_mb) and was removed",
+                    output,
+                    max / MB
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Watches one process for its timeout; dropping it (after the process was waited on) ends the watch
+pub(crate) struct Watchdog {
+    _done: mpsc::Sender<()>,
+    timed_out: Arc<AtomicBool>,
+    timeout: Duration,
+}
+
+impl Watchdog {
+    /// After the process exited: an error, with the partial output removed, if it was killed for running too long
+    pub(crate) fn check(&self, command: &Command) -> io::Result<()> {
+        if !self.timed_out.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        if let Some(output) = output_path(command) {
+            let _ = std::fs::remove_file(output);
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("stopped after {} minutes (limits.ffmpeg_timeout_minutes)", self.timeout.as_secs() / 60),
+        ))
+    }
+}
+
+/// Whether a wrapper program can be run here, checked once per program
+fn available(program: &'static str) -> bool {
+    static NICE: OnceLock<bool> = OnceLock::new();
+    static IONICE: OnceLock<bool> = OnceLock::new();
+    static PRLIMIT: OnceLock<bool> = OnceLock::new();
+    let cell = match program {
+        "nice" => &NICE,
+        "ionice" => &IONICE,
+        _ => &PRLIMIT,
+    };
+    *cell.get_or_init(|| {
+        let found = Command::new(program).arg("--version").output().is_ok_and(|o| o.status.success());
+        if !found {
+            tracing::warn!("{} is not installed; FFmpeg runs without the limit it applies", program);
+        }
+        found
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffmpeg_commands_get_the_output_cap_and_wrappers() {
+        let mut command = Command::new("ffmpeg");
+        command.args(["-y", "-i", "in.mp4", "-c", "copy", "out.mp4"]);
+
+        let limits = ProcessLimits { max_output_bytes: Some(10 * MB), ..Default::default() };
+        let limited = limits.apply(&command);
+        assert_eq!(limited.get_program(), "ffmpeg");
+        let args: Vec<_> = limited.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(args, ["-y", "-i", "in.mp4", "-c", "copy", "-fs", "10485760", "out.mp4"]);
+
+        let limits = ProcessLimits { niceness: 10, ..Default::default() };
+        let limited = limits.apply(&command);
+        if available("nice") {
+            assert_eq!(limited.get_program(), "nice");
+            let args: Vec<_> = limited.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+            assert_eq!(args[..3], ["-n", "10", "ffmpeg"]);
+        }
+
+        // Probes and stdout pipes write no file to cap
+        let mut probe = Command::new("ffmpeg");
+        probe.args(["-i", "in.mp4", "-f", "null", "-"]);
+        let limits = ProcessLimits { max_output_bytes: Some(MB), ..Default::default() };
+        assert!(!limits.apply(&probe).get_args().any(|a| a == "-fs"));
+    }
+}
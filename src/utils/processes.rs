@@ -0,0 +1,177 @@
+// utils/processes.rs - The FFmpeg processes a job has running, so cancelling it stops them mid-encode
+//
+// While a task runs under `track_processes`, `execute_ffmpeg_command` registers each FFmpeg child it
+// spawns in the task's `ProcessSet`. `ProcessSet::kill_all` sends those processes SIGTERM (SIGKILL
+// after a grace period), and each command removes its partial output once its process is gone.
+// Commands started after that fail straight away.
+use super::limits::ProcessLimits;
+use std::collections::HashMap;
+use std::io;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a process gets to exit on SIGTERM before it is sent SIGKILL
+pub(crate) const KILL_GRACE: Duration = Duration::from_secs(5);
+
+/// Running processes of one job, by pid, with the file each one writes
+#[derive(Clone, Default)]
+pub struct ProcessSet {
+    inner: Arc<Mutex<Processes>>,
+}
+
+#[derive(Default)]
+struct Processes {
+    running: HashMap<u32, Option<String>>,
+    cancelled: bool,
+}
+
+impl ProcessSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Processes> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// `kill_all` was called; no new process should start
+    pub fn is_cancelled(&self) -> bool {
+        self.lock().cancelled
+    }
+
+    pub fn running(&self) -> usize {
+        self.lock().running.len()
+    }
+
+    /// Stop every running process and refuse new ones. Returns how many processes were signalled.
+    pub fn kill_all(&self) -> usize {
+        let pids: Vec<u32> = {
+            let mut processes = self.lock();
+            processes.cancelled = true;
+            processes.running.keys().copied().collect()
+        };
+        for pid in &pids {
+            signal(*pid, "TERM");
+        }
+        if !pids.is_empty() {
+            // A registered pid hasn't been reaped yet, so it can't have been reused by another process
+            let set = self.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(KILL_GRACE);
+                let stubborn: Vec<u32> = set.lock().running.keys().copied().collect();
+                for pid in stubborn {
+                    tracing::warn!("🔪 Process {} ignored SIGTERM, killing it", pid);
+                    signal(pid, "KILL");
+                }
+            });
+        }
+        pids.len()
+    }
+
+    /// Register a spawned child until the returned guard is dropped (after the child was waited on)
+    pub(crate) fn track(&self, child: &Child, command: &Command) -> ProcessGuard {
+        let pid = child.id();
+        let cancelled = {
+            let mut processes = self.lock();
+            processes.running.insert(pid, output_path(command));
+            processes.cancelled
+        };
+        // Cancelled between the check before spawning and now
+        if cancelled {
+            signal(pid, "TERM");
+        }
+        ProcessGuard { set: self.clone(), pid }
+    }
+}
+
+/// Unregisters a process; when its job was cancelled, also deletes the output it left half-written
+pub(crate) struct ProcessGuard {
+    set: ProcessSet,
+    pid: u32,
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        let (output, cancelled) = {
+            let mut processes = self.set.lock();
+            (processes.running.remove(&self.pid).flatten(), processes.cancelled)
+        };
+        if let (true, Some(output)) = (cancelled, output) {
+            if std::fs::remove_file(&output).is_ok() {
+                tracing::info!("🧹 Removed partial output {} of cancelled process {}", output, self.pid);
+            }
+        }
+    }
+}
+
+/// The file a command writes: its last argument, unless that is stdout or a pipe
+pub(crate) fn output_path(command: &Command) -> Option<String> {
+    let output = command.get_args().last()?.to_string_lossy().to_string();
+    (output != "-" && !output.starts_with("pipe:") && !output.starts_with('-')).then_some(output)
+}
+
+pub(crate) fn signal(pid: u32, signal: &str) {
+    match Command::new("kill").arg(format!("-{}", signal)).arg(pid.to_string()).output() {
+        Ok(output) if output.status.success() => tracing::info!("🛑 Sent SIG{} to process {}", signal, pid),
+        Ok(output) => tracing::warn!("Failed to send SIG{} to process {}: {}", signal, pid, String::from_utf8_lossy(&output.stderr).trim()),
+        Err(e) => tracing::warn!("Failed to send SIG{} to process {}: {}", signal, pid, e),
+    }
+}
+
+tokio::task_local! {
+    /// Collects the processes the current task's FFmpeg commands spawn (see `track_processes`)
+    static JOB_PROCESSES: ProcessSet;
+}
+
+/// Run a future with every FFmpeg process it starts through `execute_ffmpeg_command` registered in
+/// `processes`. Like `trace_ffmpeg`, work handed to other tasks is not covered.
+pub async fn track_processes<F: std::future::Future>(future: F, processes: ProcessSet) -> F::Output {
+    JOB_PROCESSES.scope(processes, future).await
+}
+
+/// The current task's process set, if it runs under `track_processes`
+pub(crate) fn current() -> Option<ProcessSet> {
+    JOB_PROCESSES.try_with(|processes| processes.clone()).ok()
+}
+
+/// `Command::output` under `limits`, with the child registered in `processes` (if any) while it runs
+pub(crate) fn run_tracked(command: &Command, processes: Option<&ProcessSet>, limits: &ProcessLimits) -> io::Result<Output> {
+    let child = limits.apply(command).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let _guard = processes.map(|processes| processes.track(&child, command));
+    let watchdog = limits.watch(&child);
+    let output = child.wait_with_output()?;
+    if let Some(watchdog) = watchdog {
+        watchdog.check(command)?;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_all_stops_running_processes_and_removes_their_output() {
+        let output = std::env::temp_dir().join(format!("partial_{}.mp4", uuid::Uuid::new_v4()));
+        std::fs::write(&output, b"half an encode").unwrap();
+
+        let processes = ProcessSet::new();
+        // Stands in for an FFmpeg encode writing `output` (the last argument, `$0` to the shell)
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("exec sleep 30").arg(&output);
+        let runner = processes.clone();
+        let started = std::time::Instant::now();
+        let handle = std::thread::spawn(move || run_tracked(&command, Some(&runner), &ProcessLimits::default()));
+        while processes.running() == 0 && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        processes.kill_all();
+        let _ = handle.join().unwrap();
+        assert!(processes.is_cancelled());
+        assert_eq!(processes.running(), 0);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!output.exists());
+    }
+}
@@ -0,0 +1,304 @@
+// utils/progress.rs - Real render progress from FFmpeg's `-progress pipe:1` output
+//
+// While a task runs under `report_ffmpeg_progress`, `execute_ffmpeg_command` adds `-progress pipe:1` to
+// each FFmpeg command and reads the key=value blocks FFmpeg writes about twice a second. The encoded
+// time (or frame count) against the expected output length gives a true percentage, and the encoding
+// speed an ETA, instead of the step-based jumps a job reports otherwise.
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Commands shorter than this never report, so quick edits don't flood the progress feed
+const REPORT_AFTER: Duration = Duration::from_secs(2);
+/// Least time between two reports of one command
+const REPORT_EVERY: Duration = Duration::from_secs(1);
+
+/// Where a running FFmpeg command is, as parsed from one `-progress` block
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FfmpegProgress {
+    /// Output time encoded so far
+    pub out_time_seconds: f64,
+    pub frame: Option<u64>,
+    /// Expected output length, when it could be worked out from the command
+    pub total_seconds: Option<f64>,
+    /// 0-100, None when the output length is unknown
+    pub percent: Option<f64>,
+    /// Encoding speed as a multiple of real time
+    pub speed: Option<f64>,
+    pub eta_seconds: Option<f64>,
+    /// FFmpeg reported `progress=end`
+    pub finished: bool,
+}
+
+impl FfmpegProgress {
+    /// e.g. "42% · ETA 1:23" or "0:37 rendered"
+    pub fn describe(&self) -> String {
+        let done = match self.percent {
+            Some(percent) => format!("{:.0}%", percent),
+            None => format!("{} rendered", clock(self.out_time_seconds)),
+        };
+        match self.eta_seconds.filter(|_| !self.finished) {
+            Some(eta) => format!("{} · ETA {}", done, clock(eta)),
+            None => done,
+        }
+    }
+}
+
+/// M:SS, or H:MM:SS from an hour up
+fn clock(seconds: f64) -> String {
+    let seconds = seconds.max(0.0).round() as u64;
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
+
+/// What the output should amount to: a duration, or a frame count for `-frames:v` commands
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExpectedLength {
+    pub seconds: Option<f64>,
+    pub frames: Option<u64>,
+}
+
+/// Turns `-progress` lines into `FfmpegProgress`, one per completed block
+pub struct ProgressParser {
+    expected: ExpectedLength,
+    started: Instant,
+    out_time_seconds: f64,
+    frame: Option<u64>,
+    speed: Option<f64>,
+}
+
+impl ProgressParser {
+    pub fn new(expected: ExpectedLength) -> Self {
+        Self { expected, started: Instant::now(), out_time_seconds: 0.0, frame: None, speed: None }
+    }
+
+    /// Feed one line; returns the progress when it closes a block (`progress=continue|end`)
+    pub fn feed_line(&mut self, line: &str) -> Option<FfmpegProgress> {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value.trim();
+        match key {
+            // out_time_ms is in microseconds too (a long-standing FFmpeg quirk)
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(us) = value.parse::<i64>() {
+                    self.out_time_seconds = (us.max(0) as f64) / 1_000_000.0;
+                }
+            }
+            "out_time" => {
+                if let Some(seconds) = parse_clock(value) {
+                    self.out_time_seconds = seconds;
+                }
+            }
+            "frame" => self.frame = value.parse().ok(),
+            "speed" => self.speed = value.trim_end_matches('x').trim().parse().ok().filter(|s: &f64| *s > 0.0),
+            "progress" => return Some(self.snapshot(value == "end", self.started.elapsed().as_secs_f64())),
+            _ => {}
+        }
+        None
+    }
+
+    fn snapshot(&self, finished: bool, elapsed: f64) -> FfmpegProgress {
+        let fraction = match (self.expected.frames, self.frame, self.expected.seconds) {
+            (Some(total), Some(frame), _) if total > 0 => Some(frame as f64 / total as f64),
+            (_, _, Some(total)) if total > 0.0 => Some(self.out_time_seconds / total),
+            _ => None,
+        };
+        let percent = match (finished, fraction) {
+            (true, _) => Some(100.0),
+            // Never claim 100% before FFmpeg says it's done
+            (false, Some(fraction)) => Some((fraction * 100.0).clamp(0.0, 99.9)),
+            (false, None) => None,
+        };
+        // FFmpeg's own speed when it reports one, else encoded time over wall time
+        let speed = self.speed.or_else(|| (elapsed > 0.0 && self.out_time_seconds > 0.0).then(|| self.out_time_seconds / elapsed));
+        let eta_seconds = match (fraction, self.expected.seconds, speed) {
+            (_, Some(total), Some(speed)) if self.expected.frames.is_none() => Some(((total - self.out_time_seconds) / speed).max(0.0)),
+            (Some(fraction), _, _) if fraction > 0.0 => Some(elapsed * (1.0 - fraction.min(1.0)) / fraction),
+            _ => None,
+        };
+
+        FfmpegProgress {
+            out_time_seconds: self.out_time_seconds,
+            frame: self.frame,
+            total_seconds: self.expected.seconds,
+            percent,
+            speed,
+            eta_seconds,
+            finished,
+        }
+    }
+}
+
+/// "00:01:23.456789" to seconds; None for "N/A"
+fn parse_clock(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in value.trim_start_matches('-').split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// How long the output of an FFmpeg command should be: `-frames:v`, `-t`/`-to`, or the probed length
+/// of the first input (the concat demuxer's entries summed) minus any seek
+pub fn expected_length(args: &[String]) -> ExpectedLength {
+    let value_of = |names: &[&str]| {
+        args.windows(2).rfind(|pair| names.contains(&pair[0].as_str())).map(|pair| pair[1].clone())
+    };
+    let frames = value_of(&["-frames:v", "-vframes"]).and_then(|v| v.parse::<u64>().ok());
+    let limit = value_of(&["-t"]).and_then(|v| parse_clock(&v));
+    if let Some(limit) = limit {
+        return ExpectedLength { seconds: Some(limit), frames };
+    }
+
+    let Some(input_at) = args.iter().position(|a| a == "-i") else {
+        return ExpectedLength { seconds: None, frames };
+    };
+    let (before_input, after_input) = args.split_at(input_at);
+    let seek_in = |args: &[String]| {
+        args.windows(2).rfind(|pair| pair[0] == "-ss").and_then(|pair| parse_clock(&pair[1])).unwrap_or(0.0)
+    };
+    // An input seek shortens what there is to read, an output seek what gets written
+    let (input_seek, output_seek) = (seek_in(before_input), seek_in(after_input));
+    let is_concat = before_input.windows(2).any(|pair| pair[0] == "-f" && pair[1] == "concat");
+    let input = match args.get(input_at + 1) {
+        Some(input) => input,
+        None => return ExpectedLength { seconds: None, frames },
+    };
+    let probed = if is_concat {
+        super::concat_list_entries(input)
+            .iter()
+            .map(|entry| crate::core::get_video_duration(entry).ok())
+            .sum::<Option<f64>>()
+    } else {
+        crate::core::get_video_duration(input).ok()
+    };
+
+    let seconds = match value_of(&["-to"]).and_then(|v| parse_clock(&v)) {
+        Some(end) => Some(end - output_seek),
+        None => probed.map(|duration| duration - input_seek - output_seek),
+    }
+    .filter(|seconds| *seconds > 0.0);
+    ExpectedLength { seconds, frames }
+}
+
+pub type ProgressSink = Arc<dyn Fn(&FfmpegProgress) + Send + Sync>;
+
+tokio::task_local! {
+    /// Receives the progress of FFmpeg commands the current task runs (see `report_ffmpeg_progress`)
+    static FFMPEG_PROGRESS: ProgressSink;
+}
+
+/// Run a future with the progress of every long FFmpeg command it executes through
+/// `execute_ffmpeg_command` sent to `sink`. Like `trace_ffmpeg`, work handed to other tasks is not covered.
+pub async fn report_ffmpeg_progress<F: std::future::Future>(future: F, sink: ProgressSink) -> F::Output {
+    FFMPEG_PROGRESS.scope(sink, future).await
+}
+
+/// The current task's progress sink, if this command can report into it: FFmpeg writing to a file
+/// (not to stdout, which `-progress pipe:1` takes over) and not already asked for progress
+pub(crate) fn sink_for(command: &Command) -> Option<ProgressSink> {
+    let sink = FFMPEG_PROGRESS.try_with(|sink| sink.clone()).ok()?;
+    let args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+    let writes_file = args.last().is_some_and(|output| output != "-" && !output.starts_with("pipe:"));
+    (command.get_program() == "ffmpeg" && writes_file && !args.iter().any(|a| a == "-progress")).then_some(sink)
+}
+
+/// Run FFmpeg with `-progress pipe:1`, reporting to `sink` as it goes (and registered in `processes`
+/// while it runs). The returned stdout is empty: everything FFmpeg wrote there was progress.
+pub(crate) fn run_with_progress(
+    command: &Command,
+    sink: &ProgressSink,
+    processes: Option<&super::processes::ProcessSet>,
+    limits: &super::limits::ProcessLimits,
+) -> std::io::Result<Output> {
+    let args: Vec<String> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+    let mut parser = ProgressParser::new(expected_length(&args));
+
+    let mut with_progress = Command::new(command.get_program());
+    with_progress.args(["-progress", "pipe:1", "-nostats"]).args(&args);
+    if let Some(dir) = command.get_current_dir() {
+        with_progress.current_dir(dir);
+    }
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => with_progress.env(key, value),
+            None => with_progress.env_remove(key),
+        };
+    }
+    let mut child = limits.apply(&with_progress).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let _guard = processes.map(|processes| processes.track(&child, &with_progress));
+    let watchdog = limits.watch(&child);
+
+    // Drain stderr on its own thread so a chatty FFmpeg can't block on a full pipe
+    let mut stderr_pipe = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut stderr = Vec::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_end(&mut stderr);
+        }
+        stderr
+    });
+
+    let started = Instant::now();
+    let mut last_report: Option<Instant> = None;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let Some(progress) = parser.feed_line(&line) else {
+                continue;
+            };
+            let due = match last_report {
+                // The final block only matters if the command reported along the way
+                Some(_) if progress.finished => true,
+                Some(last) => last.elapsed() >= REPORT_EVERY,
+                None => !progress.finished && started.elapsed() >= REPORT_AFTER,
+            };
+            if due {
+                sink(&progress);
+                last_report = Some(Instant::now());
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    if let Some(watchdog) = watchdog {
+        watchdog.check(&with_progress)?;
+    }
+    Ok(Output { status, stdout: Vec::new(), stderr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_blocks_become_percentages_and_eta() {
+        let mut parser = ProgressParser::new(ExpectedLength { seconds: Some(120.0), frames: None });
+        let block = ["frame=720", "fps=48.0", "out_time_us=30000000", "out_time=00:00:30.000000", "speed=2.0x"];
+        assert!(block.iter().all(|line| parser.feed_line(line).is_none()));
+
+        let progress = parser.feed_line("progress=continue").unwrap();
+        assert_eq!(progress.frame, Some(720));
+        assert_eq!(progress.percent, Some(25.0));
+        assert_eq!(progress.speed, Some(2.0));
+        assert_eq!(progress.eta_seconds, Some(45.0));
+        assert_eq!(progress.describe(), "25% · ETA 0:45");
+
+        parser.feed_line("out_time_us=125000000");
+        assert_eq!(parser.feed_line("progress=continue").unwrap().percent, Some(99.9));
+        let done = parser.feed_line("progress=end").unwrap();
+        assert!(done.finished);
+        assert_eq!(done.describe(), "100%");
+
+        let mut frames = ProgressParser::new(ExpectedLength { seconds: None, frames: Some(200) });
+        frames.feed_line("frame=50");
+        frames.feed_line("out_time=N/A");
+        assert_eq!(frames.feed_line("progress=continue").unwrap().percent, Some(25.0));
+
+        let args: Vec<String> = ["-y", "-ss", "5", "-i", "missing.mp4", "-t", "00:01:30", "out.mp4"].map(String::from).to_vec();
+        assert_eq!(expected_length(&args), ExpectedLength { seconds: Some(90.0), frames: None });
+    }
+}
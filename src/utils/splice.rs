@@ -0,0 +1,201 @@
+// utils/splice.rs - Re-render one region of a finished video and splice it back in
+//
+// Only the GOPs overlapping a changed region are decoded and re-encoded: the region is widened to the
+// surrounding keyframes, everything before and after it is stream-copied untouched, and the parts are
+// joined with the concat demuxer. The parts go through MPEG-TS so the re-encoded GOPs carry their own
+// parameter sets in band and the joined stream stays decodable across the seams.
+use crate::types::MediaInfo;
+use std::process::Command;
+
+/// A keyframe-aligned region of a video; `end` is None when it runs to the end of the file
+#[derive(Debug, Clone, Copy)]
+pub struct SpliceRegion {
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+impl SpliceRegion {
+    pub fn duration(&self, total: f64) -> f64 {
+        self.end.unwrap_or(total) - self.start
+    }
+}
+
+/// Presentation times of the video keyframes, read from packet flags without decoding
+pub fn keyframe_times(input_file: &str) -> Result<Vec<f64>, String> {
+    let output = super::execute_ffprobe_command(&[
+        "-v",
+        "error",
+        "-select_streams",
+        "v:0",
+        "-show_entries",
+        "packet=pts_time,flags",
+        "-of",
+        "csv=p=0",
+        input_file,
+    ])?;
+    // Lines look like "12.345000,K__"
+    let mut times: Vec<f64> = output
+        .lines()
+        .filter_map(|line| {
+            let (pts, flags) = line.split_once(',')?;
+            flags.starts_with('K').then(|| pts.trim().parse::<f64>().ok()).flatten()
+        })
+        .collect();
+    times.sort_by(|a, b| a.total_cmp(b));
+    times.dedup();
+    Ok(times)
+}
+
+/// Widen [start, end) to the keyframe at or before `start` and the first keyframe at or after `end`
+pub fn align_region(keyframes: &[f64], start: f64, end: f64) -> SpliceRegion {
+    let aligned_start = keyframes.iter().copied().filter(|k| *k <= start + 1e-3).fold(0.0, f64::max);
+    let aligned_end = keyframes.iter().copied().find(|k| *k >= end - 1e-3 && *k > aligned_start);
+    SpliceRegion { start: aligned_start, end: aligned_end }
+}
+
+/// Stream-copy the region out of the video, e.g. as the input of the edit being re-applied
+pub fn extract_region(input_file: &str, region: SpliceRegion, output_file: &str) -> Result<String, String> {
+    let mut command = Command::new("ffmpeg");
+    command.arg("-ss").arg(region.start.to_string()).arg("-i").arg(input_file);
+    if let Some(end) = region.end {
+        command.arg("-t").arg((end - region.start).to_string());
+    }
+    command
+        .args(["-map", "0:v:0", "-map", "0:a:0?", "-c", "copy", "-avoid_negative_ts", "make_zero", "-y"])
+        .arg(output_file);
+    super::execute_ffmpeg_command(command)
+}
+
+/// Encoder options that make a re-rendered region match the source's streams, so the splice can be
+/// stream-copied. Only H.264/HEVC video with AAC (or no) audio can be spliced this way.
+fn conform_args(source: &MediaInfo) -> Result<Vec<String>, String> {
+    let video = source.video().ok_or_else(|| "the video has no video stream".to_string())?;
+    let encoder = match video.codec_name.as_str() {
+        "h264" => "libx264",
+        "hevc" => "libx265",
+        other => return Err(format!("only H.264 and HEVC videos can be spliced, not {}", other)),
+    };
+    let (width, height) = (source.width(), source.height());
+    let mut args = vec![
+        "-vf".to_string(),
+        format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1",
+            w = width,
+            h = height
+        ),
+        "-c:v".to_string(),
+        encoder.to_string(),
+        "-crf".to_string(),
+        "16".to_string(),
+        "-pix_fmt".to_string(),
+        video.pix_fmt.clone().unwrap_or_else(|| "yuv420p".to_string()),
+    ];
+    if let Some(fps) = video.fps.filter(|fps| *fps > 0.0) {
+        args.extend(["-r".to_string(), fps.to_string()]);
+    }
+
+    if let Some(audio) = source.audio() {
+        if audio.codec_name != "aac" {
+            return Err(format!("only AAC audio can be spliced, not {}", audio.codec_name));
+        }
+        args.extend(["-c:a".to_string(), "aac".to_string(), "-b:a".to_string(), "192k".to_string()]);
+        if audio.sample_rate > 0 {
+            args.extend(["-ar".to_string(), audio.sample_rate.to_string()]);
+        }
+        if audio.channels > 0 {
+            args.extend(["-ac".to_string(), audio.channels.to_string()]);
+        }
+    }
+    Ok(args)
+}
+
+/// Replace `region` of `input_file` with `replacement` (the re-rendered region, any codec or size)
+/// and write the result to `output_file`. Only the replacement is encoded; the rest is copied.
+pub fn splice(input_file: &str, region: SpliceRegion, replacement: &str, output_file: &str) -> Result<String, String> {
+    let source = crate::core::probe_media(input_file)?;
+    let conform = conform_args(&source)?;
+
+    if source.has_audio() && !crate::core::probe_media(replacement)?.has_audio() {
+        return Err("the re-rendered region lost its audio track".to_string());
+    }
+
+    let parts = SpliceParts {
+        head: super::create_temp_file("splice_head", "ts"),
+        middle: super::create_temp_file("splice_middle", "ts"),
+        tail: super::create_temp_file("splice_tail", "ts"),
+        list: super::create_temp_file("splice_list", "txt"),
+    };
+    let result = parts.join(input_file, region, replacement, &conform, source.has_audio(), output_file);
+    super::cleanup_temp_files(&[parts.head, parts.middle, parts.tail, parts.list]);
+    result
+}
+
+/// Intermediate files of a splice
+struct SpliceParts {
+    head: String,
+    middle: String,
+    tail: String,
+    list: String,
+}
+
+impl SpliceParts {
+    fn join(
+        &self,
+        input_file: &str,
+        region: SpliceRegion,
+        replacement: &str,
+        conform: &[String],
+        has_audio: bool,
+        output_file: &str,
+    ) -> Result<String, String> {
+        let audio_map = if has_audio { "0:a:0" } else { "0:a:0?" };
+        let mut parts = Vec::new();
+        if region.start > 0.0 {
+            let mut command = Command::new("ffmpeg");
+            command
+                .arg("-i")
+                .arg(input_file)
+                .arg("-t")
+                .arg(region.start.to_string())
+                .args(["-map", "0:v:0", "-map", audio_map, "-c", "copy", "-f", "mpegts", "-y"])
+                .arg(&self.head);
+            super::execute_ffmpeg_command(command)?;
+            parts.push(&self.head);
+        }
+
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-i")
+            .arg(replacement)
+            .args(["-map", "0:v:0", "-map", audio_map])
+            .args(conform)
+            .args(["-f", "mpegts", "-y"])
+            .arg(&self.middle);
+        super::execute_ffmpeg_command(command)?;
+        parts.push(&self.middle);
+
+        if let Some(end) = region.end {
+            let mut command = Command::new("ffmpeg");
+            command
+                .arg("-ss")
+                .arg(end.to_string())
+                .arg("-i")
+                .arg(input_file)
+                .args(["-map", "0:v:0", "-map", audio_map, "-c", "copy", "-f", "mpegts", "-y"])
+                .arg(&self.tail);
+            super::execute_ffmpeg_command(command)?;
+            parts.push(&self.tail);
+        }
+
+        let entries: String = parts.iter().map(|p| format!("file '{}'\n", p)).collect();
+        std::fs::write(&self.list, entries).map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-f", "concat", "-safe", "0", "-i"])
+            .arg(&self.list)
+            .args(["-c", "copy", "-bsf:a", "aac_adtstoasc", "-movflags", "+faststart", "-y"])
+            .arg(output_file);
+        super::execute_ffmpeg_command(command)
+    }
+}
@@ -0,0 +1,320 @@
+// utils/timecode.rs - Time expressions accepted wherever a tool takes a time
+//
+// Besides plain seconds (12.5), tools accept:
+//   - timecodes: "1:12", "00:01:12.500", SMPTE "00:01:12:15" (frames at the input's fps)
+//   - unit strings: "90s", "1m30s", "1.5m", "500ms", "1h2m"
+//   - frame numbers at the input's fps: "300f", "frame 300"
+//   - percentages of the input's duration: "25%"
+use serde_json::Value;
+
+/// Arguments holding a point in time or a length of time, normalized to seconds before tools run
+pub const TIME_ARGUMENTS: &[&str] = &[
+    "start_seconds",
+    "end_seconds",
+    "start_time",
+    "end_time",
+    "timestamp",
+    "duration",
+    "duration_seconds",
+    "segment_duration",
+    "fade_in_duration",
+    "fade_out_duration",
+    "seconds",
+];
+
+/// Appended to the schema description of time arguments
+pub const TIME_FORMATS_HINT: &str = "seconds (12.5), timecode (00:01:12.500), frame number (300f) or percentage of the video (25%)";
+
+/// What frame and percentage expressions are relative to
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeContext {
+    pub fps: Option<f64>,
+    pub duration: Option<f64>,
+}
+
+impl TimeContext {
+    /// Frame rate and duration of a media file
+    pub fn for_media(file_path: &str) -> Result<Self, String> {
+        let info = crate::core::probe_media(file_path)?;
+        Ok(Self {
+            fps: Some(info.fps()).filter(|fps| *fps > 0.0),
+            duration: Some(info.duration_seconds).filter(|d| *d > 0.0),
+        })
+    }
+}
+
+/// Whether an expression can only be resolved against a media file (frames, SMPTE frames or percentages)
+pub fn needs_media(expr: &str) -> bool {
+    let expr = expr.trim().to_lowercase();
+    expr.ends_with('%') || frame_number(&expr).is_some() || expr.split(':').count() == 4
+}
+
+/// Parse a time expression to seconds
+pub fn parse(expr: &str, ctx: &TimeContext) -> Result<f64, String> {
+    let lower = expr.trim().to_lowercase();
+    if lower.is_empty() {
+        return Err("empty time value".to_string());
+    }
+
+    let seconds = if let Some(percent) = lower.strip_suffix('%') {
+        let percent: f64 = percent.trim().parse().map_err(|_| format!("invalid percentage '{}'", expr))?;
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(format!("percentage '{}' must be between 0% and 100%", expr));
+        }
+        let duration = ctx.duration.ok_or_else(|| format!("'{}' needs the input's duration", expr))?;
+        duration * percent / 100.0
+    } else if let Some(frames) = frame_number(&lower) {
+        let frames: f64 = frames.parse().map_err(|_| format!("invalid frame number '{}'", expr))?;
+        frames / fps(ctx, expr)?
+    } else if lower.contains(':') {
+        parse_timecode(&lower, ctx).map_err(|e| format!("invalid timecode '{}': {}", expr, e))?
+    } else if let Ok(seconds) = lower.parse::<f64>() {
+        seconds
+    } else {
+        parse_units(&lower).ok_or_else(|| format!("unrecognized time '{}'; use {}", expr, TIME_FORMATS_HINT))?
+    };
+
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err(format!("time '{}' must be a non-negative value", expr));
+    }
+    Ok(seconds)
+}
+
+/// Format seconds as HH:MM:SS.mmm
+pub fn format(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Format seconds as a SMPTE timecode HH:MM:SS:FF (non-drop-frame)
+pub fn format_frames(seconds: f64, fps: f64) -> String {
+    let rate = fps.round().max(1.0) as u64;
+    let frames = (seconds.max(0.0) * fps).round() as u64;
+    let whole_seconds = frames / rate;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        whole_seconds / 3600,
+        whole_seconds / 60 % 60,
+        whole_seconds % 60,
+        frames % rate
+    )
+}
+
+/// A frame rate as FFmpeg wants it: the exact NTSC fraction for 23.976, 29.97 and 59.94
+pub fn ffmpeg_rate(fps: f64) -> String {
+    for base in [24, 30, 60] {
+        if (fps - base as f64 * 1000.0 / 1001.0).abs() < 0.01 {
+            return format!("{}000/1001", base);
+        }
+    }
+    if (fps - fps.round()).abs() < 0.01 {
+        format!("{}", fps.round())
+    } else {
+        format!("{:.3}", fps)
+    }
+}
+
+/// Check a SMPTE timecode that a counter starts from: HH:MM:SS:FF, or HH:MM:SS;FF for drop-frame
+/// (29.97 and 59.94 fps only). Returns it zero-padded.
+pub fn start_timecode(timecode: &str, fps: f64) -> Result<String, String> {
+    let timecode = timecode.trim();
+    let drop_frame = timecode.contains(';');
+    let parts: Vec<&str> = timecode.split([':', ';']).map(str::trim).collect();
+    let [h, m, s, f] = parts.as_slice() else {
+        return Err(format!("Timecode '{}' should be HH:MM:SS:FF", timecode));
+    };
+    let field = |value: &str| value.parse::<u32>().map_err(|_| format!("Timecode '{}': '{}' is not a number", timecode, value));
+    let (h, m, s, f) = (field(h)?, field(m)?, field(s)?, field(f)?);
+    let rate = fps.round() as u32;
+    if h > 23 || m > 59 || s > 59 || f >= rate {
+        return Err(format!("Timecode '{}' is out of range (hours below 24, frames below {} at {:.3} fps)", timecode, rate, fps));
+    }
+    if drop_frame && !matches!(ffmpeg_rate(fps).as_str(), "30000/1001" | "60000/1001") {
+        return Err(format!("Drop-frame timecode (;) only applies to 29.97 and 59.94 fps, not {:.3}", fps));
+    }
+    Ok(format!("{:02}:{:02}:{:02}{}{:02}", h, m, s, if drop_frame { ';' } else { ':' }, f))
+}
+
+/// Rewrite time-expression strings in a tool's arguments to seconds. The input file is only probed when an
+/// expression needs its frame rate or duration. Ok(None) when nothing needed rewriting.
+pub fn normalize_time_arguments(args: &Value) -> Result<Option<Value>, String> {
+    let Value::Object(map) = args else {
+        return Ok(None);
+    };
+    let expressions: Vec<(&str, &str)> = TIME_ARGUMENTS
+        .iter()
+        .filter_map(|key| map.get(*key).and_then(|v| v.as_str()).map(|s| (*key, s)))
+        .collect();
+    if expressions.is_empty() {
+        return Ok(None);
+    }
+
+    let ctx = if expressions.iter().any(|(_, expr)| needs_media(expr)) {
+        let input = ["input_file", "input_path", "input", "input_video", "video_file"]
+            .iter()
+            .find_map(|key| map.get(*key).and_then(|v| v.as_str()))
+            .ok_or_else(|| "frame and percentage times need an input file".to_string())?;
+        TimeContext::for_media(input)?
+    } else {
+        TimeContext::default()
+    };
+
+    let mut normalized = map.clone();
+    for (key, expr) in expressions {
+        let seconds = parse(expr, &ctx).map_err(|e| format!("{}: {}", key, e))?;
+        normalized.insert(key.to_string(), serde_json::json!(seconds));
+    }
+    Ok(Some(Value::Object(normalized)))
+}
+
+fn fps(ctx: &TimeContext, expr: &str) -> Result<f64, String> {
+    ctx.fps.ok_or_else(|| format!("'{}' needs the input's frame rate", expr))
+}
+
+/// The number in "300f", "f300" or "frame 300"
+fn frame_number(expr: &str) -> Option<&str> {
+    let number = expr
+        .strip_prefix("frame")
+        .or_else(|| expr.strip_suffix("frames"))
+        .or_else(|| expr.strip_suffix('f'))
+        .or_else(|| expr.strip_prefix('f'))?
+        .trim();
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(number)
+}
+
+/// MM:SS(.mmm), HH:MM:SS(.mmm) or HH:MM:SS:FF
+fn parse_timecode(expr: &str, ctx: &TimeContext) -> Result<f64, String> {
+    let parts: Vec<&str> = expr.split(':').map(str::trim).collect();
+    let field = |s: &str| s.parse::<f64>().map_err(|_| format!("'{}' is not a number", s));
+    let sexagesimal = |s: &str| {
+        let value = field(s)?;
+        if value >= 60.0 {
+            Err(format!("'{}' must be below 60", s))
+        } else {
+            Ok(value)
+        }
+    };
+
+    match parts.as_slice() {
+        [m, s] => Ok(field(m)? * 60.0 + sexagesimal(s)?),
+        [h, m, s] => Ok(field(h)? * 3600.0 + sexagesimal(m)? * 60.0 + sexagesimal(s)?),
+        [h, m, s, f] => {
+            let fps = ctx.fps.ok_or_else(|| "SMPTE frames need the input's frame rate".to_string())?;
+            let frames = field(f)?;
+            if frames >= fps.ceil() {
+                return Err(format!("frame {} is beyond {} fps", f, fps));
+            }
+            Ok(field(h)? * 3600.0 + sexagesimal(m)? * 60.0 + sexagesimal(s)? + frames / fps)
+        }
+        _ => Err("expected MM:SS, HH:MM:SS or HH:MM:SS:FF".to_string()),
+    }
+}
+
+/// "90s", "1m30s", "1.5m", "500ms", "1h2m3.5s"
+fn parse_units(expr: &str) -> Option<f64> {
+    let compact: String = expr.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut total = 0.0;
+    let mut rest = compact.as_str();
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        if number_len == 0 {
+            return None;
+        }
+        let value: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(rest.len());
+        let multiplier = match &rest[..unit_len] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        total += value * multiplier;
+        rest = &rest[unit_len..];
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_timecodes_are_checked_against_the_frame_rate() {
+        assert_eq!(start_timecode("1:00:00:00", 25.0), Ok("01:00:00:00".to_string()));
+        assert_eq!(start_timecode("00:59:59;29", 29.97), Ok("00:59:59;29".to_string()));
+        assert!(start_timecode("00:00:00:25", 25.0).unwrap_err().contains("out of range"));
+        assert!(start_timecode("00:00:00;00", 25.0).unwrap_err().contains("Drop-frame"));
+        assert!(start_timecode("00:00:10", 25.0).is_err());
+        assert_eq!(ffmpeg_rate(29.97002997), "30000/1001");
+        assert_eq!(ffmpeg_rate(23.976), "24000/1001");
+        assert_eq!(ffmpeg_rate(25.0), "25");
+    }
+
+    fn close(actual: Result<f64, String>, expected: f64) -> bool {
+        actual.is_ok_and(|seconds| (seconds - expected).abs() < 1e-6)
+    }
+
+    #[test]
+    fn timecodes_parse_to_seconds() {
+        let none = TimeContext::default();
+        assert!(close(parse("00:01:12.500", &none), 72.5));
+        assert!(close(parse("01:00:00.250", &none), 3600.25));
+        assert!(close(parse("1:12", &none), 72.0));
+
+        let pal = TimeContext { fps: Some(25.0), duration: None };
+        assert!(close(parse("00:01:12:15", &pal), 72.6));
+        let ntsc = TimeContext { fps: Some(29.97), duration: None };
+        assert!(close(parse("00:00:10:29", &ntsc), 10.0 + 29.0 / 29.97));
+
+        assert!(parse("00:00:10:15", &none).unwrap_err().contains("frame rate"));
+        assert!(parse("00:00:10:25", &pal).unwrap_err().contains("beyond"));
+        assert!(parse("00:00:10:30", &ntsc).unwrap_err().contains("beyond"));
+        assert!(parse("00:75:00", &none).unwrap_err().contains("below 60"));
+    }
+
+    #[test]
+    fn frames_percentages_and_units_parse_to_seconds() {
+        let ctx = TimeContext { fps: Some(30.0), duration: Some(200.0) };
+        assert!(close(parse("300f", &ctx), 10.0));
+        assert!(close(parse("frame 45", &ctx), 1.5));
+        assert!(close(parse("25%", &ctx), 50.0));
+        assert!(close(parse("1m30s", &ctx), 90.0));
+        assert!(close(parse("1h2m", &ctx), 3720.0));
+        assert!(close(parse("500ms", &ctx), 0.5));
+        assert!(close(parse("12.5", &ctx), 12.5));
+        assert!(close(parse(" 7 ", &ctx), 7.0));
+
+        assert!(parse("300f", &TimeContext::default()).unwrap_err().contains("frame rate"));
+        assert!(parse("25%", &TimeContext::default()).unwrap_err().contains("duration"));
+        assert!(parse("150%", &ctx).unwrap_err().contains("between"));
+        assert!(parse("-5", &ctx).unwrap_err().contains("non-negative"));
+        assert!(parse("-1m", &ctx).is_err());
+        assert!(parse("soon", &ctx).unwrap_err().contains("unrecognized"));
+        assert!(parse("1m30x", &ctx).is_err());
+        assert!(parse("", &ctx).is_err());
+
+        assert!(needs_media("300f") && needs_media("25%") && needs_media("00:00:01:12"));
+        assert!(!needs_media("00:01:12.5") && !needs_media("1m30s") && !needs_media("12"));
+    }
+
+    #[test]
+    fn time_arguments_are_normalized_to_seconds() {
+        let args = serde_json::json!({ "input_file": "a.mp4", "start_time": "1:30", "end_time": "2m", "duration": 5 });
+        let normalized = normalize_time_arguments(&args).unwrap().unwrap();
+        assert_eq!(normalized["start_time"], 90.0);
+        assert_eq!(normalized["end_time"], 120.0);
+        assert_eq!(normalized["duration"], 5);
+
+        assert!(normalize_time_arguments(&serde_json::json!({ "start_time": 3.0 })).unwrap().is_none());
+        assert!(normalize_time_arguments(&serde_json::json!({ "start_time": "300f" })).unwrap_err().contains("input file"));
+        assert!(normalize_time_arguments(&serde_json::json!({ "end_time": "later" })).unwrap_err().starts_with("end_time:"));
+    }
+}
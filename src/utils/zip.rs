@@ -0,0 +1,208 @@
+//! Zip archives of rendered files. Entries are stored, not deflated: video barely compresses and
+//! storing keeps zipping a batch as fast as copying it. Entries and archives past 4 GB use ZIP64.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const ZIP64_LIMIT: u64 = 0xFFFF_FFFF;
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const ZIP64_END: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR: u32 = 0x0706_4b50;
+const END: u32 = 0x0605_4b50;
+/// Names are UTF-8
+const FLAGS: u16 = 0x0800;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u64,
+    offset: u64,
+    zip64: bool,
+}
+
+fn u16le(out: &mut impl Write, value: u16) -> io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+fn u32le(out: &mut impl Write, value: u32) -> io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+fn u64le(out: &mut impl Write, value: u64) -> io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+/// MS-DOS time and date the entries are stamped with
+fn dos_timestamp(now: chrono::DateTime<chrono::Local>) -> (u16, u16) {
+    use chrono::{Datelike, Timelike};
+    let time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+    let date = (((now.year().clamp(1980, 2107) - 1980) as u32) << 9) | (now.month() << 5) | now.day();
+    (time, date as u16)
+}
+
+/// Write `files` (path on disk, name in the archive) to a new zip at `target`; returns its size
+pub fn write_stored_zip(target: &Path, files: &[(std::path::PathBuf, String)]) -> io::Result<u64> {
+    let (time, date) = dos_timestamp(chrono::Local::now());
+    let mut out = BufWriter::new(File::create(target)?);
+    let mut entries = Vec::with_capacity(files.len());
+
+    for (path, name) in files {
+        let mut input = File::open(path)?;
+        let size = input.metadata()?.len();
+        let zip64 = size >= ZIP64_LIMIT;
+        let offset = out.stream_position()?;
+
+        u32le(&mut out, LOCAL_HEADER)?;
+        u16le(&mut out, if zip64 { 45 } else { 20 })?;
+        u16le(&mut out, FLAGS)?;
+        u16le(&mut out, 0)?; // stored
+        u16le(&mut out, time)?;
+        u16le(&mut out, date)?;
+        let crc_at = out.stream_position()?;
+        u32le(&mut out, 0)?; // crc, filled in once the data is written
+        let short_size = if zip64 { ZIP64_LIMIT as u32 } else { size as u32 };
+        u32le(&mut out, short_size)?;
+        u32le(&mut out, short_size)?;
+        u16le(&mut out, name.len() as u16)?;
+        u16le(&mut out, if zip64 { 20 } else { 0 })?;
+        out.write_all(name.as_bytes())?;
+        if zip64 {
+            u16le(&mut out, 0x0001)?;
+            u16le(&mut out, 16)?;
+            u64le(&mut out, size)?;
+            u64le(&mut out, size)?;
+        }
+
+        let mut crc = flate2::Crc::new();
+        let mut buffer = vec![0u8; 1 << 20];
+        let mut copied = 0u64;
+        loop {
+            let read = input.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            crc.update(&buffer[..read]);
+            out.write_all(&buffer[..read])?;
+            copied += read as u64;
+        }
+        if copied != size {
+            return Err(io::Error::other(format!("{} changed while it was being zipped", path.display())));
+        }
+
+        let end = out.stream_position()?;
+        out.seek(SeekFrom::Start(crc_at))?;
+        u32le(&mut out, crc.sum())?;
+        out.seek(SeekFrom::Start(end))?;
+        entries.push(Entry { name: name.clone(), crc: crc.sum(), size, offset, zip64 });
+    }
+
+    let directory_offset = out.stream_position()?;
+    for entry in &entries {
+        let offset_overflows = entry.offset >= ZIP64_LIMIT;
+        let mut extra = Vec::new();
+        if entry.zip64 {
+            u64le(&mut extra, entry.size)?;
+            u64le(&mut extra, entry.size)?;
+        }
+        if offset_overflows {
+            u64le(&mut extra, entry.offset)?;
+        }
+
+        u32le(&mut out, CENTRAL_HEADER)?;
+        let version = if extra.is_empty() { 20 } else { 45 };
+        u16le(&mut out, version)?;
+        u16le(&mut out, version)?;
+        u16le(&mut out, FLAGS)?;
+        u16le(&mut out, 0)?;
+        u16le(&mut out, time)?;
+        u16le(&mut out, date)?;
+        u32le(&mut out, entry.crc)?;
+        let short_size = if entry.zip64 { ZIP64_LIMIT as u32 } else { entry.size as u32 };
+        u32le(&mut out, short_size)?;
+        u32le(&mut out, short_size)?;
+        u16le(&mut out, entry.name.len() as u16)?;
+        u16le(&mut out, if extra.is_empty() { 0 } else { extra.len() as u16 + 4 })?;
+        u16le(&mut out, 0)?; // comment
+        u16le(&mut out, 0)?; // disk
+        u16le(&mut out, 0)?; // internal attributes
+        u32le(&mut out, 0)?; // external attributes
+        u32le(&mut out, if offset_overflows { ZIP64_LIMIT as u32 } else { entry.offset as u32 })?;
+        out.write_all(entry.name.as_bytes())?;
+        if !extra.is_empty() {
+            u16le(&mut out, 0x0001)?;
+            u16le(&mut out, extra.len() as u16)?;
+            out.write_all(&extra)?;
+        }
+    }
+    let directory_end = out.stream_position()?;
+    let directory_size = directory_end - directory_offset;
+
+    let needs_zip64 = entries.len() >= 0xFFFF || directory_offset >= ZIP64_LIMIT || directory_size >= ZIP64_LIMIT;
+    if needs_zip64 {
+        u32le(&mut out, ZIP64_END)?;
+        u64le(&mut out, 44)?;
+        u16le(&mut out, 45)?;
+        u16le(&mut out, 45)?;
+        u32le(&mut out, 0)?;
+        u32le(&mut out, 0)?;
+        u64le(&mut out, entries.len() as u64)?;
+        u64le(&mut out, entries.len() as u64)?;
+        u64le(&mut out, directory_size)?;
+        u64le(&mut out, directory_offset)?;
+
+        u32le(&mut out, ZIP64_LOCATOR)?;
+        u32le(&mut out, 0)?;
+        u64le(&mut out, directory_end)?;
+        u32le(&mut out, 1)?;
+    }
+    let count = entries.len().min(0xFFFF) as u16;
+    u32le(&mut out, END)?;
+    u16le(&mut out, 0)?;
+    u16le(&mut out, 0)?;
+    u16le(&mut out, count)?;
+    u16le(&mut out, count)?;
+    u32le(&mut out, directory_size.min(ZIP64_LIMIT) as u32)?;
+    u32le(&mut out, directory_offset.min(ZIP64_LIMIT) as u32)?;
+    u16le(&mut out, 0)?;
+
+    let size = out.stream_position()?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_zip_lists_each_file_with_its_data() {
+        let dir = std::env::temp_dir().join(format!("zip_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.mp4"), dir.join("b.mp4"));
+        std::fs::write(&a, b"first file").unwrap();
+        std::fs::write(&b, b"second").unwrap();
+        let target = dir.join("results.zip");
+
+        let size = write_stored_zip(&target, &[(a, "a_resized.mp4".to_string()), (b, "b_resized.mp4".to_string())]).unwrap();
+        let bytes = std::fs::read(&target).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(size, bytes.len() as u64);
+
+        // First entry: local header, name, then the stored bytes; crc matches the data
+        assert_eq!(&bytes[..4], &LOCAL_HEADER.to_le_bytes());
+        assert_eq!(&bytes[30..43], b"a_resized.mp4");
+        assert_eq!(&bytes[43..53], b"first file");
+        let mut crc = flate2::Crc::new();
+        crc.update(b"first file");
+        assert_eq!(&bytes[14..18], &crc.sum().to_le_bytes());
+
+        // End record: two entries, and the directory it points at starts with a central header
+        let end = &bytes[bytes.len() - 22..];
+        assert_eq!(&end[..4], &END.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        let directory = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert_eq!(&bytes[directory..directory + 4], &CENTRAL_HEADER.to_le_bytes());
+    }
+}
@@ -2,6 +2,7 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
+use crate::services::vector_migration::{VectorMemoryStore, VectorPage, VectorRecord};
 
 #[derive(Debug, Clone)]
 pub struct AstraDBClient {
@@ -413,4 +414,172 @@ impl AstraDBClient {
         
         Ok(context)
     }
-}
\ No newline at end of file
+}
+
+// Migration support. These go through the Data API command endpoint
+// ({endpoint}/api/json/v1/{keyspace}/{collection}), which pages full scans with pageState.
+impl AstraDBClient {
+    /// Delete every chat memory of a session; deleteMany removes a bounded number per call,
+    /// so it repeats while the API reports more matches
+    pub async fn delete_session_memories(&self, session_id: &str) -> Result<u64, String> {
+        let mut deleted = 0;
+        loop {
+            let body = self
+                .command(&self.command_url(), serde_json::json!({ "deleteMany": { "filter": { "session_id": session_id } } }))
+                .await?;
+            deleted += body.pointer("/status/deletedCount").and_then(|c| c.as_i64()).unwrap_or(0).max(0) as u64;
+            if !body.pointer("/status/moreData").and_then(|m| m.as_bool()).unwrap_or(false) {
+                return Ok(deleted);
+            }
+        }
+    }
+
+    fn command_url(&self) -> String {
+        format!("{}/api/json/v1/{}/{}", self.api_endpoint.trim_end_matches('/'), self.keyspace, self.collection)
+    }
+
+    async fn command(&self, url: &str, body: serde_json::Value) -> Result<serde_json::Value, String> {
+        let response = self
+            .client
+            .post(url)
+            .header("Token", &self.application_token)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("AstraDB request failed: {}", e))?;
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse AstraDB response: {}", e))?;
+        if let Some(errors) = body.get("errors").and_then(|e| e.as_array()).filter(|e| !e.is_empty()) {
+            return Err(format!("AstraDB error: {}", serde_json::Value::Array(errors.clone())));
+        }
+        if !status.is_success() {
+            return Err(format!("AstraDB returned {}: {}", status, body));
+        }
+        Ok(body)
+    }
+
+    /// Split a stored document into its id, vector and payload
+    fn document_record(document: serde_json::Value) -> Option<VectorRecord> {
+        let serde_json::Value::Object(mut payload) = document else {
+            return None;
+        };
+        let id = match payload.remove("_id")? {
+            serde_json::Value::String(id) => id,
+            other => other.to_string(),
+        };
+        let vector = serde_json::from_value(payload.remove("$vector")?).ok()?;
+        Some(VectorRecord { id, vector, payload })
+    }
+
+    fn page_documents(body: &serde_json::Value) -> Vec<VectorRecord> {
+        body.pointer("/data/documents")
+            .and_then(|d| d.as_array())
+            .map(|documents| documents.iter().cloned().filter_map(Self::document_record).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl VectorMemoryStore for AstraDBClient {
+    async fn ensure_collection(&self) -> Result<(), String> {
+        self.create_collection().await.map_err(|e| e.to_string())
+    }
+
+    async fn dimension(&self) -> Result<Option<usize>, String> {
+        let url = format!("{}/api/json/v1/{}", self.api_endpoint.trim_end_matches('/'), self.keyspace);
+        let body = self
+            .command(&url, serde_json::json!({ "findCollections": { "options": { "explain": true } } }))
+            .await?;
+        let dimension = body
+            .pointer("/status/collections")
+            .and_then(|c| c.as_array())
+            .and_then(|collections| collections.iter().find(|c| c["name"] == self.collection.as_str()))
+            .and_then(|c| c.pointer("/options/vector/dimension"))
+            .and_then(|d| d.as_u64());
+        Ok(dimension.map(|d| d as usize))
+    }
+
+    async fn scan(&self, cursor: Option<&str>, _limit: usize) -> Result<VectorPage, String> {
+        // The Data API serves fixed-size pages (20 documents), so the batch size isn't passed on
+        let mut options = serde_json::json!({});
+        if let Some(cursor) = cursor {
+            options["pageState"] = serde_json::Value::String(cursor.to_string());
+        }
+        let body = self
+            .command(
+                &self.command_url(),
+                serde_json::json!({ "find": { "filter": {}, "projection": { "*": 1 }, "options": options } }),
+            )
+            .await?;
+        Ok(VectorPage {
+            records: Self::page_documents(&body),
+            next: body.pointer("/data/nextPageState").and_then(|s| s.as_str()).map(|s| s.to_string()),
+        })
+    }
+
+    async fn upsert(&self, records: &[VectorRecord]) -> Result<(), String> {
+        for record in records {
+            let mut document = record.payload.clone();
+            document.insert("_id".to_string(), serde_json::Value::String(record.id.clone()));
+            document.insert("$vector".to_string(), serde_json::json!(record.vector));
+            self.command(
+                &self.command_url(),
+                serde_json::json!({
+                    "findOneAndReplace": {
+                        "filter": { "_id": record.id },
+                        "replacement": document,
+                        "options": { "upsert": true }
+                    }
+                }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn fetch(&self, ids: &[String]) -> Result<Vec<VectorRecord>, String> {
+        let mut records = Vec::with_capacity(ids.len());
+        // $in takes at most 100 values
+        for chunk in ids.chunks(100) {
+            let mut page_state: Option<String> = None;
+            loop {
+                let mut options = serde_json::json!({});
+                if let Some(state) = &page_state {
+                    options["pageState"] = serde_json::Value::String(state.clone());
+                }
+                let body = self
+                    .command(
+                        &self.command_url(),
+                        serde_json::json!({
+                            "find": { "filter": { "_id": { "$in": chunk } }, "projection": { "*": 1 }, "options": options }
+                        }),
+                    )
+                    .await?;
+                records.extend(Self::page_documents(&body));
+                page_state = body.pointer("/data/nextPageState").and_then(|s| s.as_str()).map(|s| s.to_string());
+                if page_state.is_none() {
+                    break;
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    async fn count(&self) -> Result<u64, String> {
+        let body = self
+            .command(&self.command_url(), serde_json::json!({ "countDocuments": {} }))
+            .await?;
+        // Exact counts stop at 1000; past that, fall back to the estimate
+        if body.pointer("/status/moreData").and_then(|m| m.as_bool()).unwrap_or(false) {
+            let estimate = self
+                .command(&self.command_url(), serde_json::json!({ "estimatedDocumentCount": {} }))
+                .await?;
+            return Ok(estimate.pointer("/status/count").and_then(|c| c.as_u64()).unwrap_or(0));
+        }
+        Ok(body.pointer("/status/count").and_then(|c| c.as_u64()).unwrap_or(0))
+    }
+}
@@ -1,8 +1,11 @@
 // src/visual.rs
 
 
-use crate::utils::execute_ffmpeg_command;
+use crate::core::get_video_duration;
+use crate::types::{AnimatedCaptionOptions, ColorAnalysis, QuizQuestion, ScreencastLayout, TranscriptWord};
+use crate::utils::{execute_ffmpeg_command, gpu};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::process::Command;
 
 pub fn apply_filter(
@@ -71,22 +74,35 @@ pub fn add_overlay(
     x: u32,
     y: u32,
 ) -> Result<String, String> {
-    let filter = format!("overlay={}:{}", x, y);
-
-    let mut command = Command::new("ffmpeg");
-    command
-        .arg("-i")
-        .arg(input_file)
-        .arg("-i")
-        .arg(overlay_file)
-        .arg("-filter_complex")
-        .arg(filter)
-        .arg("-c:a")
-        .arg("copy")
-        .arg("-y")
-        .arg(output_file);
+    let build = |filter: &str, device_args: &[String]| {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(device_args)
+            .arg("-i")
+            .arg(input_file)
+            .arg("-i")
+            .arg(overlay_file)
+            .arg("-filter_complex")
+            .arg(filter)
+            .arg("-map")
+            .arg("[out]")
+            .arg("-map")
+            .arg("0:a?")
+            .arg("-c:a")
+            .arg("copy")
+            .arg("-y")
+            .arg(output_file);
+        command
+    };
 
-    execute_ffmpeg_command(command)
+    let (x, y) = (x.to_string(), y.to_string());
+    let gpu_command = gpu::filters().and_then(|gpu| {
+        let placement = gpu::OverlayPlacement { scale: None, x: &x, y: &y, alpha: true };
+        let filter = gpu.overlay_graph("0:v", "1:v", &placement, "out")?;
+        Some(build(&filter, &gpu.device_args()))
+    });
+    let filter = format!("[0:v][1:v]overlay={}:{}[out]", x, y);
+    gpu::run_with_fallback(gpu_command, || build(&filter, &[]))
 }
 
 pub fn add_subtitles(
@@ -237,4 +253,1287 @@ pub fn apply_filter_chain(
         .arg(output_file);
 
     execute_ffmpeg_command(command)
-}
\ No newline at end of file
+}
+
+/// Sample every Nth frame through signalstats and average the luma/chroma measurements
+pub fn analyze_color_stats(input_file: &str, sample_every_n_frames: u32) -> Result<ColorAnalysis, String> {
+    let filter = format!(
+        "select='not(mod(n\\,{}))',signalstats,metadata=mode=print:file=-",
+        sample_every_n_frames.max(1)
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vf")
+        .arg(filter)
+        .arg("-an")
+        .arg("-f")
+        .arg("null")
+        .arg("-");
+
+    let stats_output = execute_ffmpeg_command(command)?;
+    parse_signalstats(&stats_output)
+}
+
+/// Average the `lavfi.signalstats.*` lines printed by the metadata filter
+pub fn parse_signalstats(stats_output: &str) -> Result<ColorAnalysis, String> {
+    let mut sums: HashMap<&str, (f64, usize)> = HashMap::new();
+
+    for line in stats_output.lines() {
+        let Some(rest) = line.trim().strip_prefix("lavfi.signalstats.") else {
+            continue;
+        };
+        let Some((key, value)) = rest.split_once('=') else {
+            continue;
+        };
+        if let Ok(value) = value.parse::<f64>() {
+            let entry = sums.entry(key).or_insert((0.0, 0));
+            entry.0 += value;
+            entry.1 += 1;
+        }
+    }
+
+    let average = |key: &str| -> Result<(f64, usize), String> {
+        match sums.get(key) {
+            Some((sum, count)) if *count > 0 => Ok((sum / *count as f64, *count)),
+            _ => Err(format!("No {} measurements found in sampled frames", key)),
+        }
+    };
+
+    let (luma_avg, frames_sampled) = average("YAVG")?;
+    Ok(ColorAnalysis {
+        frames_sampled,
+        luma_avg,
+        luma_low: average("YLOW")?.0,
+        luma_high: average("YHIGH")?.0,
+        u_avg: average("UAVG")?.0,
+        v_avg: average("VAVG")?.0,
+    })
+}
+
+/// Build an exposure/contrast/gray-world white balance filter from measured stats
+pub fn build_auto_correct_filter(analysis: &ColorAnalysis, strength: f64) -> String {
+    let strength = strength.clamp(0.0, 1.0);
+
+    // Exposure: pull average luma toward mid-grey
+    let brightness = ((118.0 - analysis.luma_avg) / 255.0 * strength).clamp(-0.3, 0.3);
+
+    // Contrast: stretch or compress the 10th-90th percentile spread toward a healthy range
+    let spread = (analysis.luma_high - analysis.luma_low).max(1.0);
+    let contrast = 1.0 + ((150.0 / spread).clamp(0.8, 1.5) - 1.0) * strength;
+
+    // Gray world: a neutral average has both chroma planes centred at 128
+    let u_dev = (analysis.u_avg - 128.0) / 128.0;
+    let v_dev = (analysis.v_avg - 128.0) / 128.0;
+    let gain = 2.0 * strength;
+    let red = (-v_dev * gain).clamp(-0.4, 0.4);
+    let blue = (-u_dev * gain).clamp(-0.4, 0.4);
+    let green = ((u_dev + v_dev) * 0.5 * gain).clamp(-0.4, 0.4);
+
+    format!(
+        "eq=brightness={:.3}:contrast={:.3},colorbalance=rm={:.3}:gm={:.3}:bm={:.3}",
+        brightness, contrast, red, green, blue
+    )
+}
+
+/// Render a single frame with the original on the left half and the processed file on the right
+pub fn create_split_preview(
+    original_file: &str,
+    processed_file: &str,
+    preview_file: &str,
+    timestamp: f64,
+) -> Result<String, String> {
+    let filter = "[0:v]crop=iw/2:ih:0:0[left];[1:v]crop=iw/2:ih:iw/2:0[right];[left][right]hstack,drawbox=x=iw/2-1:y=0:w=2:h=ih:color=white@0.8:t=fill";
+    let ts = timestamp.max(0.0).to_string();
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-ss")
+        .arg(&ts)
+        .arg("-i")
+        .arg(original_file)
+        .arg("-ss")
+        .arg(&ts)
+        .arg("-i")
+        .arg(processed_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-y")
+        .arg(preview_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Analyze sampled frames and apply exposure, contrast and white balance fixes.
+/// Writes a before/after split-frame preview next to the output file.
+pub fn auto_correct(
+    input_file: &str,
+    output_file: &str,
+    strength: f64,
+) -> Result<(ColorAnalysis, String, String), String> {
+    let analysis = analyze_color_stats(input_file, 30)?;
+    let filter = build_auto_correct_filter(&analysis, strength);
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vf")
+        .arg(&filter)
+        .arg("-c:a")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)?;
+
+    let preview_file = match output_file.rsplit_once('.') {
+        Some((stem, _)) => format!("{}_preview.png", stem),
+        None => format!("{}_preview.png", output_file),
+    };
+    let midpoint = get_video_duration(input_file).unwrap_or(0.0) / 2.0;
+    create_split_preview(input_file, output_file, &preview_file, midpoint)?;
+
+    Ok((analysis, filter, preview_file))
+}
+
+/// Convert a color name or #RRGGBB into ASS subtitle &HBBGGRR& notation
+pub fn ass_color(color: &str) -> String {
+    let hex = match color.to_lowercase().as_str() {
+        "white" => "FFFFFF".to_string(),
+        "black" => "000000".to_string(),
+        "yellow" => "FFD700".to_string(),
+        "red" => "FF3B30".to_string(),
+        "green" => "34C759".to_string(),
+        "blue" => "0A84FF".to_string(),
+        "cyan" => "00E5FF".to_string(),
+        "pink" => "FF2D95".to_string(),
+        "orange" => "FF9500".to_string(),
+        other => other.trim_start_matches('#').to_uppercase(),
+    };
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return "&H00FFFFFF&".to_string();
+    }
+    format!("&H00{}{}{}&", &hex[4..6], &hex[2..4], &hex[0..2])
+}
+
+/// Format seconds as an ASS timestamp (H:MM:SS.cc)
+pub fn ass_timestamp(seconds: f64) -> String {
+    let centis = (seconds.max(0.0) * 100.0).round() as u64;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        centis / 360_000,
+        (centis / 6000) % 60,
+        (centis / 100) % 60,
+        centis % 100
+    )
+}
+
+/// Give lyric words timings. When a transcript of the vocals is available each lyric word
+/// borrows the timing of the proportionally matching transcript word; otherwise the
+/// lyrics are spread evenly across the track.
+pub fn align_lyrics_to_words(
+    lyrics: &str,
+    transcript: &[TranscriptWord],
+    duration: f64,
+) -> Vec<Vec<TranscriptWord>> {
+    let lines: Vec<Vec<&str>> = lyrics
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let total_words: usize = lines.iter().map(|l| l.len()).sum();
+    if total_words == 0 {
+        return Vec::new();
+    }
+
+    let mut index = 0;
+    lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|text| {
+                    let (start, end) = if transcript.is_empty() {
+                        let slot = duration / total_words as f64;
+                        (index as f64 * slot, (index + 1) as f64 * slot)
+                    } else {
+                        let source = &transcript[index * transcript.len() / total_words];
+                        (source.start, source.end)
+                    };
+                    index += 1;
+                    TranscriptWord {
+                        text: text.to_string(),
+                        start,
+                        end,
+                        speaker_id: None,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Group transcript words into display lines, breaking on long gaps or when a line is full
+pub fn group_words_into_lines(words: &[TranscriptWord], max_words_per_line: usize) -> Vec<Vec<TranscriptWord>> {
+    let mut lines: Vec<Vec<TranscriptWord>> = Vec::new();
+    for word in words {
+        let start_new = match lines.last() {
+            Some(line) => {
+                line.len() >= max_words_per_line.max(1)
+                    || line.last().map(|w| word.start - w.end > 1.0).unwrap_or(false)
+            }
+            None => true,
+        };
+        if start_new {
+            lines.push(Vec::new());
+        }
+        if let Some(line) = lines.last_mut() {
+            line.push(word.clone());
+        }
+    }
+    lines
+}
+
+/// Build an ASS subtitle document with per-word karaoke fill (\kf) highlighting
+pub fn build_karaoke_ass(
+    lines: &[Vec<TranscriptWord>],
+    width: u32,
+    height: u32,
+    font_size: u32,
+    base_color: &str,
+    highlight_color: &str,
+) -> String {
+    let mut ass = format!(
+        "[Script Info]\nScriptType: v4.00+\nPlayResX: {}\nPlayResY: {}\nWrapStyle: 0\n\n\
+[V4+ Styles]\n\
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+Style: Karaoke,DejaVu Sans,{},{},{},&H00000000&,&H80000000&,1,0,0,0,100,100,0,0,1,3,1,2,60,60,{},1\n\n\
+[Events]\n\
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+        width,
+        height,
+        font_size,
+        ass_color(highlight_color), // primary = sung colour
+        ass_color(base_color),      // secondary = not yet sung
+        height / 8
+    );
+
+    for line in lines {
+        let (Some(first), Some(last)) = (line.first(), line.last()) else {
+            continue;
+        };
+        // Show the line slightly early so the singer's first word isn't a surprise
+        let line_start = (first.start - 0.3).max(0.0);
+        let mut cursor = line_start;
+        let mut text = String::new();
+        for word in line {
+            let lead_in = ((word.start - cursor) * 100.0).round().max(0.0) as u64;
+            if lead_in > 0 {
+                text.push_str(&format!("{{\\k{}}}", lead_in));
+            }
+            let sung = ((word.end - word.start.max(cursor)) * 100.0).round().max(1.0) as u64;
+            text.push_str(&format!("{{\\kf{}}}{} ", sung, word.text.replace(['{', '}'], "")));
+            cursor = word.end.max(cursor);
+        }
+        ass.push_str(&format!(
+            "Dialogue: 0,{},{},Karaoke,,0,0,0,,{}\n",
+            ass_timestamp(line_start),
+            ass_timestamp(last.end + 0.5),
+            text.trim_end()
+        ));
+    }
+
+    ass
+}
+
+pub const CAPTION_MODES: [&str; 2] = ["line", "word"];
+pub const CAPTION_HIGHLIGHT_STYLES: [&str; 5] = ["fill", "color", "box", "pop", "none"];
+pub const CAPTION_POSITIONS: [&str; 3] = ["bottom", "middle", "top"];
+
+/// ASS override tags that make the word being spoken stand out
+fn caption_highlight_tags(options: &AnimatedCaptionOptions, outline: u32) -> String {
+    let color = ass_color(&options.highlight_color);
+    match options.highlight_style.as_str() {
+        "color" => format!("{{\\c{}}}", color),
+        // A thick outline in the highlight colour reads as a box behind the word
+        "box" => format!("{{\\3c{}\\bord{}}}", color, outline * 3),
+        "pop" => format!("{{\\c{}\\fscx80\\fscy80\\t(0,120,\\fscx118\\fscy118)}}", color),
+        _ => String::new(),
+    }
+}
+
+/// Build an ASS document of animated, TikTok-style captions: word by word (only the spoken word
+/// is on screen) or line by line, with the spoken word highlighted in the chosen style
+pub fn build_animated_caption_ass(words: &[TranscriptWord], width: u32, height: u32, options: &AnimatedCaptionOptions) -> String {
+    let font_size = ((height as f64 * options.font_size_percent / 100.0).round() as u32).max(12);
+    let outline = (font_size / 16).max(2);
+    let (alignment, margin_v) = match options.position.as_str() {
+        "top" => (8, height / 10),
+        "middle" => (5, 0),
+        _ => (2, height / 5),
+    };
+    // With the fill style, \kf sweeps from the secondary colour to the primary one
+    let (primary, secondary) = if options.highlight_style == "fill" {
+        (ass_color(&options.highlight_color), ass_color(&options.text_color))
+    } else {
+        (ass_color(&options.text_color), ass_color(&options.text_color))
+    };
+    let mut ass = format!(
+        "[Script Info]\nScriptType: v4.00+\nPlayResX: {}\nPlayResY: {}\nWrapStyle: 0\n\n\
+[V4+ Styles]\n\
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+Style: Caption,{},{},{},{},&H00000000&,&H80000000&,1,0,0,0,100,100,0,0,1,{},2,{},{},{},{},1\n\n\
+[Events]\n\
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+        width,
+        height,
+        options.font.replace(',', ""),
+        font_size,
+        primary,
+        secondary,
+        outline,
+        alignment,
+        width / 12,
+        width / 12,
+        margin_v
+    );
+
+    let display = |word: &TranscriptWord| {
+        let text = word.text.replace(['{', '}', '\\'], "");
+        if options.uppercase { text.to_uppercase() } else { text }
+    };
+    let highlight = caption_highlight_tags(options, outline);
+    let dialogue = |start: f64, end: f64, text: &str| {
+        format!("Dialogue: 0,{},{},Caption,,0,0,0,,{}\n", ass_timestamp(start), ass_timestamp(end), text.trim_end())
+    };
+
+    if options.mode == "word" {
+        for (i, word) in words.iter().enumerate() {
+            // Hold each word until the next one starts, unless there's a pause
+            let end = words.get(i + 1).map(|next| next.start.min(word.end + 0.5)).unwrap_or(word.end + 0.3);
+            let tags = if options.highlight_style == "fill" { format!("{{\\kf{}}}", ((word.end - word.start) * 100.0).round().max(1.0) as u64) } else { highlight.clone() };
+            ass.push_str(&dialogue(word.start, end.max(word.start + 0.05), &format!("{}{}", tags, display(word))));
+        }
+        return ass;
+    }
+
+    for line in group_words_into_lines(words, options.words_per_line) {
+        let (Some(first), Some(last)) = (line.first(), line.last()) else {
+            continue;
+        };
+        let line_end = last.end + 0.2;
+        match options.highlight_style.as_str() {
+            "fill" => {
+                let mut cursor = first.start;
+                let mut text = String::new();
+                for word in &line {
+                    let lead_in = ((word.start - cursor) * 100.0).round().max(0.0) as u64;
+                    if lead_in > 0 {
+                        text.push_str(&format!("{{\\k{}}}", lead_in));
+                    }
+                    let spoken = ((word.end - word.start.max(cursor)) * 100.0).round().max(1.0) as u64;
+                    text.push_str(&format!("{{\\kf{}}}{} ", spoken, display(word)));
+                    cursor = word.end.max(cursor);
+                }
+                ass.push_str(&dialogue(first.start, line_end, &text));
+            }
+            "none" => {
+                let text: Vec<String> = line.iter().map(display).collect();
+                ass.push_str(&dialogue(first.start, line_end, &text.join(" ")));
+            }
+            _ => {
+                // One event per spoken word: the whole line, with that word restyled
+                for (i, word) in line.iter().enumerate() {
+                    let start = if i == 0 { first.start } else { word.start };
+                    let end = line.get(i + 1).map(|next| next.start).unwrap_or(line_end);
+                    let text: Vec<String> = line
+                        .iter()
+                        .enumerate()
+                        .map(|(j, other)| if j == i { format!("{}{}{{\\r}}", highlight, display(other)) } else { display(other) })
+                        .collect();
+                    ass.push_str(&dialogue(start, end.max(start + 0.05), &text.join(" ")));
+                }
+            }
+        }
+    }
+
+    ass
+}
+
+/// Burn animated captions for a transcript into a video, rendered from an ASS track beside the output
+pub fn generate_captions(
+    input_file: &str,
+    output_file: &str,
+    words: &[TranscriptWord],
+    options: &AnimatedCaptionOptions,
+) -> Result<String, String> {
+    let metadata = crate::core::analyze_video(input_file)?;
+    let stem = output_file.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(output_file);
+    let ass_file = format!("{}_captions.ass", stem);
+    std::fs::write(&ass_file, build_animated_caption_ass(words, metadata.width, metadata.height, options))
+        .map_err(|e| format!("Failed to write captions: {}", e))?;
+
+    let result = add_subtitles(input_file, &ass_file, output_file);
+    let _ = std::fs::remove_file(&ass_file);
+    result
+}
+
+/// Render karaoke-style lyrics over a background video or still image, using the audio track as the soundtrack
+pub fn render_lyric_video(
+    background_file: &str,
+    audio_file: &str,
+    ass_file: &str,
+    output_file: &str,
+    width: u32,
+    height: u32,
+) -> Result<String, String> {
+    let is_image = matches!(
+        crate::utils::get_file_extension(background_file).as_deref(),
+        Some("png") | Some("jpg") | Some("jpeg") | Some("webp") | Some("bmp")
+    );
+    let filter = format!(
+        "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},ass={ass}",
+        w = width,
+        h = height,
+        ass = ass_file
+    );
+
+    let mut command = Command::new("ffmpeg");
+    if is_image {
+        command.arg("-loop").arg("1");
+    } else {
+        command.arg("-stream_loop").arg("-1");
+    }
+    command
+        .arg("-i")
+        .arg(background_file)
+        .arg("-i")
+        .arg(audio_file)
+        .arg("-map")
+        .arg("0:v")
+        .arg("-map")
+        .arg("1:a")
+        .arg("-vf")
+        .arg(filter)
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-shortest")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Render a quiz question card: question and options, a countdown timer, then the
+/// correct answer is highlighted. Includes a silent audio track so cards concat cleanly.
+pub fn render_quiz_card(
+    output_file: &str,
+    card: &QuizQuestion,
+    countdown_seconds: u32,
+    reveal_seconds: f64,
+    width: u32,
+    height: u32,
+    background_color: &str,
+) -> Result<String, String> {
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+    let total = countdown_seconds as f64 + reveal_seconds;
+    let reveal_at = countdown_seconds as f64;
+    let (question, options, answer_index) = (&card.question, &card.options, card.answer_index);
+    let question_size = height / 18;
+    let option_size = height / 24;
+    let mut filters = Vec::new();
+
+    // Question, wrapped and centred near the top
+    let max_chars = (width / (question_size / 2).max(1)) as usize;
+    for (i, line) in crate::utils::wrap_text(question, max_chars).iter().enumerate() {
+        filters.push(format!(
+            "drawtext=fontfile={}:text='{}':fontsize={}:fontcolor=white:x=(w-text_w)/2:y=h*0.12+{}",
+            font,
+            crate::utils::escape_drawtext(line),
+            question_size,
+            i as u32 * question_size * 6 / 5
+        ));
+    }
+
+    // Answer options; the correct one gets a highlight box once the timer runs out
+    for (i, option) in options.iter().enumerate() {
+        let y = format!("h*{:.3}", 0.40 + i as f64 * 0.11);
+        if i == answer_index {
+            filters.push(format!(
+                "drawbox=x=w*0.12:y={}-h*0.02:w=w*0.76:h=h*0.08:color=green@0.7:t=fill:enable='gte(t,{})'",
+                y, reveal_at
+            ));
+        }
+        let label = (b'A' + i as u8) as char;
+        filters.push(format!(
+            "drawtext=fontfile={}:text='{}) {}':fontsize={}:fontcolor=white:x=w*0.15:y={}",
+            font,
+            label,
+            crate::utils::escape_drawtext(option),
+            option_size,
+            y
+        ));
+    }
+
+    // Countdown, then the reveal line
+    filters.push(format!(
+        "drawtext=fontfile={}:text='%{{eif\\:ceil({}-t)\\:d}}':fontsize={}:fontcolor=yellow:x=(w-text_w)/2:y=h*0.85:enable='lt(t,{})'",
+        font, reveal_at, height / 10, reveal_at
+    ));
+    if let Some(answer) = options.get(answer_index) {
+        filters.push(format!(
+            "drawtext=fontfile={}:text='Answer\\: {}':fontsize={}:fontcolor=white:x=(w-text_w)/2:y=h*0.86:enable='gte(t,{})'",
+            font,
+            crate::utils::escape_drawtext(answer),
+            option_size,
+            reveal_at
+        ));
+    }
+    filters.push("fade=t=in:st=0:d=0.4".to_string());
+    filters.push(format!("fade=t=out:st={:.2}:d=0.4", (total - 0.4).max(0.0)));
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-f")
+        .arg("lavfi")
+        .arg("-i")
+        .arg(format!("color=c={}:s={}x{}:d={}:r=30", background_color, width, height, total))
+        .arg("-f")
+        .arg("lavfi")
+        .arg("-i")
+        .arg("anullsrc=r=44100:cl=stereo")
+        .arg("-vf")
+        .arg(filters.join(","))
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-shortest")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Fill exactly `duration` seconds with a B-roll clip or still image, cropped to the output frame.
+/// Short clips loop; stills get a slow zoom so they don't look frozen.
+pub fn fit_broll_to_duration(
+    input_file: &str,
+    output_file: &str,
+    duration: f64,
+    width: u32,
+    height: u32,
+) -> Result<String, String> {
+    let is_image = crate::utils::get_file_extension(input_file)
+        .map(|ext| matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "webp"))
+        .unwrap_or(false);
+
+    let filter = if is_image {
+        // zoompan emits all frames from the single still, so no input looping is needed
+        format!(
+            "scale={w2}:{h2}:force_original_aspect_ratio=increase,crop={w2}:{h2},zoompan=z='min(zoom+0.0008,1.15)':x='iw/2-(iw/zoom/2)':y='ih/2-(ih/zoom/2)':d={frames}:s={w}x{h}:fps=30,setsar=1",
+            w2 = width * 2,
+            h2 = height * 2,
+            frames = (duration * 30.0).ceil() as u64,
+            w = width,
+            h = height
+        )
+    } else {
+        format!(
+            "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},fps=30,setsar=1",
+            w = width,
+            h = height
+        )
+    };
+
+    let mut command = Command::new("ffmpeg");
+    if !is_image {
+        command.arg("-stream_loop").arg("-1");
+    }
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-t")
+        .arg(format!("{:.3}", duration))
+        .arg("-vf")
+        .arg(filter)
+        .arg("-an")
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Show a centered title card (text on a translucent band) over the first `duration` seconds
+pub fn add_title_card(
+    input_file: &str,
+    output_file: &str,
+    title: &str,
+    duration: f64,
+) -> Result<String, String> {
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+    let lines = crate::utils::wrap_text(title, 32);
+    let line_count = lines.len().max(1) as f64;
+
+    // Font and line height scale with the frame so the card works for any aspect ratio
+    let mut filters = vec![format!(
+        "drawbox=x=0:y=ih/2-ih*{half:.3}:w=iw:h=ih*{full:.3}:color=black@0.55:t=fill:enable='lt(t,{d})'",
+        half = (line_count / 14.0 + 0.05) / 2.0,
+        full = line_count / 14.0 + 0.05,
+        d = duration
+    )];
+    for (i, line) in lines.iter().enumerate() {
+        filters.push(format!(
+            "drawtext=fontfile={}:text='{}':fontsize=h/16:fontcolor=white:x=(w-text_w)/2:y=h/2-h*{:.3}+h*{:.3}:enable='lt(t,{})'",
+            font,
+            crate::utils::escape_drawtext(line),
+            line_count / 28.0,
+            i as f64 / 14.0,
+            duration
+        ));
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vf")
+        .arg(filters.join(","))
+        .arg("-c:a")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Composite a talking-head presenter clip as a picture-in-picture in the bottom-right corner.
+/// The presenter is scaled to `size` (fraction of the frame height) and its audio is dropped,
+/// since it lip-syncs to narration the base video already carries.
+pub fn overlay_presenter(
+    input_file: &str,
+    presenter_file: &str,
+    output_file: &str,
+    size: f64,
+) -> Result<String, String> {
+    let meta = crate::core::analyze_video(input_file)?;
+    // Even height keeps libx264 happy
+    let presenter_height = ((meta.height as f64 * size.clamp(0.15, 0.6)) as u32 / 2 * 2).max(2);
+    let filter = format!(
+        "[1:v]scale=-2:{}[p];[0:v][p]overlay=W-w-W*0.03:H-h-H*0.04:eof_action=pass[v]",
+        presenter_height
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-i")
+        .arg(presenter_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("[v]")
+        .arg("-map")
+        .arg("0:a?")
+        .arg("-c:a")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Alpha expression for a rounded-rectangle mask of radius `r` (a circle when r is half the side)
+fn rounded_mask_alpha(r: u32) -> String {
+    format!(
+        "if(gt(abs(X-W/2),W/2-{r})*gt(abs(Y-H/2),H/2-{r}),if(lte(hypot(abs(X-W/2)-(W/2-{r}),abs(Y-H/2)-(H/2-{r})),{r}),255,0),255)",
+        r = r
+    )
+}
+
+/// Compose a screen recording and a camera recording into a tutorial layout:
+/// "bubble" (round webcam in a corner), "pip" (rounded 16:9 camera window in a corner)
+/// or "side_by_side" (screen on the left, cropped camera column on the right).
+/// The screen recording sets the length; the camera is shifted by `camera_offset` to line up.
+pub fn compose_screencast(
+    screen_file: &str,
+    camera_file: &str,
+    output_file: &str,
+    layout: &ScreencastLayout,
+) -> Result<String, String> {
+    let screen = crate::core::analyze_video(screen_file)?;
+    let camera = crate::core::analyze_video(camera_file)?;
+    let even = |v: f64| ((v as u32) / 2 * 2).max(2);
+    let (w, h) = (even(layout.width as f64), even(layout.height as f64));
+    let margin = even(h as f64 * 0.04);
+    let bg = &layout.background_color;
+
+    let base = format!(
+        "[0:v]scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color={bg},setsar=1,format=yuv420p[base]",
+        w = w,
+        h = h,
+        bg = bg
+    );
+
+    let video_filter = match layout.layout.as_str() {
+        "bubble" | "pip" => {
+            let (cw, ch, radius) = if layout.layout == "bubble" {
+                let d = even(h as f64 * layout.size.clamp(0.1, 0.5));
+                (d, d, d / 2)
+            } else {
+                let cw = even(w as f64 * layout.size.clamp(0.1, 0.5));
+                let ch = even(cw as f64 * 9.0 / 16.0);
+                (cw, ch, layout.corner_radius.min(ch / 2))
+            };
+            let x = if layout.position.ends_with("left") { margin } else { w - cw - margin };
+            let y = if layout.position.starts_with("top") { margin } else { h - ch - margin };
+
+            let mut filter = format!(
+                "{base};[1:v]scale={cw}:{ch}:force_original_aspect_ratio=increase,crop={cw}:{ch},setsar=1,format=yuva420p,\
+                 geq=lum='p(X,Y)':cb='cb(X,Y)':cr='cr(X,Y)':a='{alpha}'[cam];",
+                base = base,
+                cw = cw,
+                ch = ch,
+                alpha = rounded_mask_alpha(radius)
+            );
+            if layout.shadow {
+                // A blurred, offset black copy of the masked camera sits underneath it
+                let blur = (ch / 16).clamp(4, 24);
+                let offset = blur / 2;
+                filter.push_str(&format!(
+                    "[cam]split[camfg][camsh];\
+                     [camsh]format=rgba,colorchannelmixer=rr=0:gg=0:bb=0:aa=0.55,pad=iw+{p2}:ih+{p2}:{p}:{p}:color=black@0,format=yuva420p,boxblur={b}:2[shadow];\
+                     [base][shadow]overlay={sx}:{sy}:eof_action=pass[shadowed];\
+                     [shadowed][camfg]overlay={x}:{y}:eof_action=pass[v]",
+                    p = blur * 2,
+                    p2 = blur * 4,
+                    b = blur,
+                    sx = x as i64 + offset as i64 - blur as i64 * 2,
+                    sy = y as i64 + offset as i64 - blur as i64 * 2,
+                    x = x,
+                    y = y
+                ));
+            } else {
+                filter.push_str(&format!("[base][cam]overlay={}:{}:eof_action=pass[v]", x, y));
+            }
+            filter
+        }
+        "side_by_side" => {
+            let cw = even(w as f64 * layout.size.clamp(0.2, 0.5));
+            let sw = w - cw;
+            format!(
+                "[0:v]scale={sw}:{h}:force_original_aspect_ratio=decrease,pad={sw}:{h}:(ow-iw)/2:(oh-ih)/2:color={bg},setsar=1,format=yuv420p[scr];\
+                 [1:v]scale={cw}:{h}:force_original_aspect_ratio=increase,crop={cw}:{h},setsar=1,format=yuv420p[cam];\
+                 [scr][cam]hstack=inputs=2[v]",
+                sw = sw,
+                cw = cw,
+                h = h,
+                bg = bg
+            )
+        }
+        other => return Err(format!("Unknown layout '{}'. Use bubble, pip or side_by_side", other)),
+    };
+
+    let audio_filter = match (layout.audio.as_str(), screen.has_audio, camera.has_audio) {
+        ("mix", true, true) => Some("[0:a][1:a]amix=inputs=2:duration=first:dropout_transition=0:normalize=0[a]"),
+        ("screen", true, _) | ("mix", true, false) => Some("[0:a]anull[a]"),
+        ("camera", _, true) | ("mix", false, true) => Some("[1:a]anull[a]"),
+        _ => None,
+    };
+    let filter = match audio_filter {
+        Some(audio) => format!("{};{}", video_filter, audio),
+        None => video_filter,
+    };
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i").arg(screen_file);
+    if layout.camera_offset > 0.0 {
+        command.arg("-itsoffset").arg(format!("{:.3}", layout.camera_offset));
+    } else if layout.camera_offset < 0.0 {
+        command.arg("-ss").arg(format!("{:.3}", -layout.camera_offset));
+    }
+    command
+        .arg("-i")
+        .arg(camera_file)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("[v]");
+    if audio_filter.is_some() {
+        command.arg("-map").arg("[a]");
+    }
+    command
+        .arg("-t")
+        .arg(format!("{:.3}", screen.duration_seconds))
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Render two versions of a video for comparison.
+/// `mode` is "side_by_side" (both at the same height, stacked horizontally) or
+/// "wipe" (version B revealed over A by a divider sweeping back and forth every `wipe_period` seconds).
+pub fn render_comparison(
+    input_a: &str,
+    input_b: &str,
+    output_file: &str,
+    mode: &str,
+    labels: (&str, &str),
+    wipe_period: f64,
+) -> Result<String, String> {
+    let meta = crate::core::analyze_video(input_a)?;
+    // Even dimensions, capped at 1080p so side-by-side output stays encodable
+    let height = (meta.height.min(1080) / 2 * 2).max(2);
+    let width = ((meta.width as f64 * height as f64 / meta.height.max(1) as f64) as u32 / 2 * 2).max(2);
+
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+    let label = |text: &str, x: &str| {
+        format!(
+            "drawtext=fontfile={}:text='{}':fontsize=h/22:fontcolor=white:box=1:boxcolor=black@0.55:boxborderw=8:x={}:y=h/30",
+            font,
+            crate::utils::escape_drawtext(text),
+            x
+        )
+    };
+
+    let filter = match mode {
+        "side_by_side" => format!(
+            "[0:v]scale=-2:{h},setsar=1,{la}[a];[1:v]scale=-2:{h},setsar=1,{lb}[b];[a][b]hstack=inputs=2:shortest=1[v]",
+            h = height,
+            la = label(labels.0, "w/30"),
+            lb = label(labels.1, "w/30"),
+        ),
+        "wipe" => format!(
+            "[0:v]scale={w}:{h},setsar=1,format=yuv420p[a];[1:v]scale={w}:{h},setsar=1,format=yuv420p[b];\
+             [a][b]blend=all_expr='if(gte(X,W*(0.5-0.45*cos(2*PI*T/{p:.3}))),B,A)':shortest=1,{la},{lb}[v]",
+            w = width,
+            h = height,
+            p = wipe_period.max(1.0),
+            la = label(labels.0, "w/30"),
+            lb = label(labels.1, "w-text_w-w/30"),
+        ),
+        other => return Err(format!("Unknown comparison mode '{}'. Use side_by_side or wipe", other)),
+    };
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_a)
+        .arg("-i")
+        .arg(input_b)
+        .arg("-filter_complex")
+        .arg(filter)
+        .arg("-map")
+        .arg("[v]")
+        .arg("-map")
+        .arg("0:a?")
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-shortest")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Cut a low-resolution sample of `duration` seconds starting at `start_time`, encoded for speed
+/// rather than quality. Used as the input of scratch effect-chain previews.
+pub fn cut_preview_sample(
+    input_file: &str,
+    output_file: &str,
+    start_time: f64,
+    duration: f64,
+    height: u32,
+) -> Result<String, String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-ss")
+        .arg(format!("{:.3}", start_time.max(0.0)))
+        .arg("-i")
+        .arg(input_file)
+        .arg("-t")
+        .arg(format!("{:.3}", duration))
+        .arg("-vf")
+        .arg(format!("scale=-2:{},setsar=1", height / 2 * 2))
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("ultrafast")
+        .arg("-crf")
+        .arg("30")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-b:a")
+        .arg("96k")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Burn a "PREVIEW" banner into a scratch render so it can't be mistaken for a deliverable
+pub fn stamp_preview(input_file: &str, output_file: &str, label: &str) -> Result<String, String> {
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+    let filter = format!(
+        "drawtext=fontfile={}:text='{}':fontsize=h/18:fontcolor=white@0.9:box=1:boxcolor=red@0.6:boxborderw=6:x=w/40:y=h/30",
+        font,
+        crate::utils::escape_drawtext(label)
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vf")
+        .arg(filter)
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("ultrafast")
+        .arg("-crf")
+        .arg("30")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// x and y of a burned-in counter for a named position
+fn counter_position(position: &str) -> Result<(&'static str, &'static str), String> {
+    match position {
+        "" | "bottom_center" => Ok(("(w-tw)/2", "h-th-h/20")),
+        "bottom_left" => Ok(("w/40", "h-th-h/20")),
+        "bottom_right" => Ok(("w-tw-w/40", "h-th-h/20")),
+        "top_center" => Ok(("(w-tw)/2", "h/20")),
+        "top_left" => Ok(("w/40", "h/20")),
+        "top_right" => Ok(("w-tw-w/40", "h/20")),
+        other => Err(format!(
+            "Unknown position '{}'. Use bottom_center, bottom_left, bottom_right, top_center, top_left or top_right",
+            other
+        )),
+    }
+}
+
+/// Burn a running SMPTE timecode into every frame, counted by drawtext frame by frame at the input's
+/// exact frame rate so each frame shows its own number. `label` ("v3 REVIEW") is drawn before it.
+pub fn burn_timecode(
+    input_file: &str,
+    output_file: &str,
+    start_timecode: &str,
+    position: &str,
+    font_size: Option<u32>,
+    label: Option<&str>,
+) -> Result<String, String> {
+    let info = crate::core::probe_media(input_file)?;
+    if info.fps() <= 0.0 {
+        return Err(format!("{} has no video frame rate to count frames by", input_file));
+    }
+    let start = crate::utils::timecode::start_timecode(start_timecode, info.fps())?;
+    let (x, y) = counter_position(position)?;
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSansMono-Bold.ttf";
+
+    let mut filter = format!(
+        "drawtext=fontfile={}:timecode='{}':rate={}",
+        font,
+        start.replace(':', "\\:"),
+        crate::utils::timecode::ffmpeg_rate(info.fps())
+    );
+    if let Some(label) = label.map(str::trim).filter(|l| !l.is_empty()) {
+        filter.push_str(&format!(":text='{}  '", crate::utils::escape_drawtext(label)));
+    }
+    filter.push_str(&format!(
+        ":fontsize={}:fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=8:x={}:y={}",
+        font_size.map(|size| size.to_string()).unwrap_or_else(|| "h/20".to_string()),
+        x,
+        y
+    ));
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-i")
+        .arg(input_file)
+        .arg("-vf")
+        .arg(filter)
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-crf")
+        .arg("18")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-c:a")
+        .arg("copy")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Prepend a slate card (project, version, date, notes) of `duration` seconds. The card is made at
+/// the video's size and frame rate with silence matching its audio, so the two join seamlessly.
+pub fn generate_slate(
+    input_file: &str,
+    output_file: &str,
+    project: &str,
+    version: Option<&str>,
+    date: &str,
+    notes: Option<&str>,
+    duration: f64,
+) -> Result<String, String> {
+    let info = crate::core::probe_media(input_file)?;
+    let (width, height) = (info.width(), info.height());
+    if width == 0 || height == 0 || info.fps() <= 0.0 {
+        return Err(format!("{} has no video stream to put a slate in front of", input_file));
+    }
+    let rate = crate::utils::timecode::ffmpeg_rate(info.fps());
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+    let text = |line: &str, size: &str, color: &str, y: String| {
+        format!(
+            "drawtext=fontfile={}:text='{}':fontsize={}:fontcolor={}:x=(w-text_w)/2:y={}",
+            font,
+            crate::utils::escape_drawtext(line),
+            size,
+            color,
+            y
+        )
+    };
+
+    // Project title above a rule, then the details, then the notes wrapped below
+    let mut card = vec![format!("color=c=0x101418:s={}x{}:r={}:d={:.3}", width, height, rate, duration)];
+    card.push(text(project, "h/12", "white", "h*0.28".to_string()));
+    card.push("drawbox=x=iw*0.15:y=ih*0.42:w=iw*0.7:h=max(2\\,ih/240):color=white@0.5:t=fill".to_string());
+    let mut details = Vec::new();
+    if let Some(version) = version.map(str::trim).filter(|v| !v.is_empty()) {
+        details.push(format!("Version: {}", version));
+    }
+    details.push(format!("Date: {}", date));
+    for (i, line) in details.iter().enumerate() {
+        card.push(text(line, "h/22", "white@0.9", format!("h*{:.3}", 0.48 + i as f64 * 0.07)));
+    }
+    let notes_top = 0.50 + details.len() as f64 * 0.07;
+    for (i, line) in notes.map(|n| crate::utils::wrap_text(n, 48)).unwrap_or_default().iter().take(4).enumerate() {
+        card.push(text(line, "h/30", "white@0.7", format!("h*{:.3}", notes_top + i as f64 * 0.05)));
+    }
+
+    let mut graph = vec![
+        format!("{},format=yuv420p,setsar=1[slate]", card.join(",")),
+        "[0:v]format=yuv420p,setsar=1[main]".to_string(),
+    ];
+    let audio = info.audio().map(|track| track.sample_rate).filter(|rate| *rate > 0);
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i").arg(input_file);
+    match audio {
+        Some(sample_rate) => {
+            graph.push(format!("anullsrc=r={}:cl=stereo,atrim=duration={:.3}[slate_audio]", sample_rate, duration));
+            graph.push(format!("[0:a:0]aformat=sample_rates={}:channel_layouts=stereo[main_audio]", sample_rate));
+            graph.push("[slate][slate_audio][main][main_audio]concat=n=2:v=1:a=1[v][a]".to_string());
+            command.arg("-filter_complex").arg(graph.join(";")).args(["-map", "[v]", "-map", "[a]"]);
+            command.args(["-c:a", "aac", "-b:a", "192k"]);
+        }
+        None => {
+            graph.push("[slate][main]concat=n=2:v=1:a=0[v]".to_string());
+            command.arg("-filter_complex").arg(graph.join(";")).args(["-map", "[v]"]);
+        }
+    }
+    command
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-crf")
+        .arg("18")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg("-y")
+        .arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+/// Parts of a 9:16 frame covered by a platform's UI (captions, buttons, progress bar), as
+/// fractions of the frame: top, bottom, left, right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SafeZone {
+    pub platform: &'static str,
+    pub top: f64,
+    pub bottom: f64,
+    pub left: f64,
+    pub right: f64,
+    /// Guide color in the proof
+    pub color: &'static str,
+}
+
+/// Safe zones of the vertical-video platforms, from their creator guidelines
+pub const SAFE_ZONES: [SafeZone; 3] = [
+    SafeZone { platform: "TikTok", top: 0.08, bottom: 0.23, left: 0.055, right: 0.13, color: "0x25F4EE" },
+    SafeZone { platform: "Instagram Reels", top: 0.14, bottom: 0.35, left: 0.06, right: 0.06, color: "0xE1306C" },
+    SafeZone { platform: "YouTube Shorts", top: 0.10, bottom: 0.25, left: 0.05, right: 0.15, color: "0xFF0000" },
+];
+
+/// The safe zones for "tiktok", "reels" (or "instagram"), "shorts" (or "youtube") or "all"
+pub fn safe_zones_for(platform: &str) -> Result<Vec<SafeZone>, String> {
+    let zone = |name: &str| SAFE_ZONES.iter().copied().find(|z| z.platform == name);
+    match platform.trim().to_lowercase().as_str() {
+        "tiktok" => Ok(zone("TikTok").into_iter().collect()),
+        "reels" | "instagram" | "instagram_reels" => Ok(zone("Instagram Reels").into_iter().collect()),
+        "shorts" | "youtube" | "youtube_shorts" => Ok(zone("YouTube Shorts").into_iter().collect()),
+        "all" => Ok(SAFE_ZONES.to_vec()),
+        other => Err(format!("Unknown platform '{}'. Use tiktok, reels, shorts or all", other)),
+    }
+}
+
+/// Filters drawing the guides on a 9:16 frame: whatever any of `zones` covers is shaded, and each
+/// platform's safe area is outlined and labelled in its color
+pub fn safe_zone_filters(zones: &[SafeZone]) -> Vec<String> {
+    let font = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+    let widest = |edge: fn(&SafeZone) -> f64| zones.iter().map(edge).fold(0.0, f64::max);
+    let (top, bottom, left, right) = (widest(|z| z.top), widest(|z| z.bottom), widest(|z| z.left), widest(|z| z.right));
+
+    let mut filters = vec![
+        format!("drawbox=x=0:y=0:w=iw:h=ih*{:.3}:color=black@0.45:t=fill", top),
+        format!("drawbox=x=0:y=ih*{:.3}:w=iw:h=ih*{:.3}:color=black@0.45:t=fill", 1.0 - bottom, bottom),
+        format!("drawbox=x=0:y=ih*{:.3}:w=iw*{:.3}:h=ih*{:.3}:color=black@0.45:t=fill", top, left, 1.0 - top - bottom),
+        format!(
+            "drawbox=x=iw*{:.3}:y=ih*{:.3}:w=iw*{:.3}:h=ih*{:.3}:color=black@0.45:t=fill",
+            1.0 - right,
+            top,
+            right,
+            1.0 - top - bottom
+        ),
+    ];
+    for (i, zone) in zones.iter().enumerate() {
+        filters.push(format!(
+            "drawbox=x=iw*{:.3}:y=ih*{:.3}:w=iw*{:.3}:h=ih*{:.3}:color={}@0.9:t=4",
+            zone.left,
+            zone.top,
+            1.0 - zone.left - zone.right,
+            1.0 - zone.top - zone.bottom,
+            zone.color
+        ));
+        filters.push(format!(
+            "drawtext=fontfile={}:text='{} safe area':fontsize=h/50:fontcolor={}:x=w*{:.3}+8:y=h*{:.3}+8+{}*h/40",
+            font,
+            zone.platform,
+            zone.color,
+            zone.left,
+            zone.top,
+            i
+        ));
+    }
+    filters
+}
+
+/// Render a proof of `input_file` with platform safe-zone guides: a still at `timestamp` when
+/// `output_file` is an image, else the whole video. The input is fitted into a 9:16 frame the way
+/// the platforms show it, so a landscape video's proof shows its letterbox too.
+pub fn preview_safe_zones(input_file: &str, output_file: &str, zones: &[SafeZone], timestamp: f64) -> Result<String, String> {
+    let mut filters = vec!["scale=1080:1920:force_original_aspect_ratio=decrease,pad=1080:1920:(ow-iw)/2:(oh-ih)/2,setsar=1".to_string()];
+    filters.extend(safe_zone_filters(zones));
+
+    let still = matches!(
+        std::path::Path::new(output_file).extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref(),
+        Some("png" | "jpg" | "jpeg" | "webp")
+    );
+    let mut command = Command::new("ffmpeg");
+    if still {
+        command.arg("-ss").arg(format!("{:.3}", timestamp.max(0.0)));
+    }
+    command.arg("-i").arg(input_file).arg("-vf").arg(filters.join(","));
+    if still {
+        command.arg("-frames:v").arg("1");
+    } else {
+        command
+            .arg("-c:v")
+            .arg("libx264")
+            .arg("-preset")
+            .arg("veryfast")
+            .arg("-crf")
+            .arg("26")
+            .arg("-pix_fmt")
+            .arg("yuv420p")
+            .arg("-c:a")
+            .arg("copy");
+    }
+    command.arg("-y").arg(output_file);
+
+    execute_ffmpeg_command(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start: f64, end: f64) -> TranscriptWord {
+        TranscriptWord { text: text.to_string(), start, end, speaker_id: None }
+    }
+
+    fn options(mode: &str, highlight_style: &str) -> AnimatedCaptionOptions {
+        AnimatedCaptionOptions {
+            mode: mode.to_string(),
+            font: "DejaVu Sans".to_string(),
+            font_size_percent: 7.0,
+            text_color: "white".to_string(),
+            highlight_color: "yellow".to_string(),
+            highlight_style: highlight_style.to_string(),
+            position: "top".to_string(),
+            words_per_line: 3,
+            uppercase: true,
+        }
+    }
+
+    #[test]
+    fn animated_captions_highlight_the_spoken_word() {
+        let words = [word("hello", 0.0, 0.4), word("there", 0.5, 0.9), word("friend", 1.0, 1.5)];
+        let dialogues = |ass: &str| ass.lines().filter(|l| l.starts_with("Dialogue:")).map(str::to_string).collect::<Vec<_>>();
+
+        // Line mode with a colour highlight: one event per word, the line restyled around it
+        let ass = build_animated_caption_ass(&words, 1080, 1920, &options("line", "color"));
+        assert!(ass.contains(",8,90,90,192,1\n"), "top alignment and margin in the style: {}", ass);
+        let events = dialogues(&ass);
+        assert_eq!(events.len(), 3);
+        assert!(events[1].starts_with("Dialogue: 0,0:00:00.50,0:00:01.00,Caption"));
+        assert!(events[1].ends_with("HELLO {\\c&H0000D7FF&}THERE{\\r} FRIEND"));
+
+        // Word mode: only the spoken word is on screen, held until the next one starts
+        let events = dialogues(&build_animated_caption_ass(&words, 1080, 1920, &options("word", "pop")));
+        assert_eq!(events.len(), 3);
+        assert!(events[0].starts_with("Dialogue: 0,0:00:00.00,0:00:00.50,"));
+        assert!(events[0].ends_with("\\t(0,120,\\fscx118\\fscy118)}HELLO"));
+
+        // Fill sweeps the whole line in one event
+        let events = dialogues(&build_animated_caption_ass(&words, 1080, 1920, &options("line", "fill")));
+        assert_eq!(events.len(), 1);
+        assert!(events[0].ends_with("{\\kf40}HELLO {\\k10}{\\kf40}THERE {\\k10}{\\kf50}FRIEND"));
+    }
+}
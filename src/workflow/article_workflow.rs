@@ -0,0 +1,504 @@
+// News/blog-to-video workflow - article → script → voiceover → B-roll → captioned video
+// Productizes the auto_generate_video pieces with narration and source attribution
+
+use super::state::{WorkflowState, StateUpdate, WorkflowStatus};
+use super::graph::{StateGraph, NodeType, NodeFunction, StateGraphBuilder};
+use super::executor::{ExecutorBuilder, ExecutorConfig};
+use super::checkpoint::{resume_key, WorkflowCheckpointer};
+use super::part_path;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Longest article excerpt sent to the script writer
+const MAX_ARTICLE_CHARS: usize = 12_000;
+
+/// Narration speed used to size the script (words per second)
+const WORDS_PER_SECOND: f64 = 2.5;
+
+/// Presenter picture-in-picture height as a fraction of the frame
+const PRESENTER_SIZE: f64 = 0.35;
+
+/// Settings shared by every article workflow node
+#[derive(Debug, Clone)]
+pub struct ArticleVideoConfig {
+    pub url: String,
+    pub target_duration: f64,
+    pub voice: String,
+    pub width: u32,
+    pub height: u32,
+    pub captions: bool,
+    /// Portrait turned into a talking-head presenter over the B-roll
+    pub presenter_image: Option<String>,
+    pub output_file: String,
+}
+
+/// Fetched article text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleContent {
+    pub url: String,
+    pub source: String,
+    pub title: Option<String>,
+    pub text: String,
+}
+
+/// One narrated beat of the video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptSegment {
+    pub narration: String,
+    pub visual_query: String,
+}
+
+/// LLM-written script for the article
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleScript {
+    pub title: String,
+    pub segments: Vec<ScriptSegment>,
+}
+
+/// Narration audio for one segment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NarratedSegment {
+    pub audio_file: String,
+    pub duration: f64,
+}
+
+/// B-roll picked for one segment, with its credit line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrollClip {
+    pub file: String,
+    pub credit: Option<String>,
+}
+
+/// Parse the script JSON, tolerating code fences and surrounding prose
+pub fn parse_article_script(text: &str) -> Result<ArticleScript, String> {
+    let start = text.find('{').ok_or("No JSON object found in script")?;
+    let end = text.rfind('}').ok_or("No JSON object found in script")?;
+    if end < start {
+        return Err("Malformed script".to_string());
+    }
+
+    let mut script: ArticleScript = serde_json::from_str(&text[start..=end])
+        .map_err(|e| format!("Failed to parse script: {}", e))?;
+    script.segments.retain(|s| !s.narration.trim().is_empty());
+
+    if script.segments.is_empty() {
+        return Err("Script has no narration".to_string());
+    }
+    Ok(script)
+}
+
+/// Downloads the article and strips it to readable text
+pub struct FetchArticleNode {
+    config: Arc<ArticleVideoConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for FetchArticleNode {
+    async fn execute(&self, _state: &WorkflowState) -> Result<StateUpdate, String> {
+        tracing::info!("📰 Fetching article: {}", self.config.url);
+
+        let response = reqwest::Client::new()
+            .get(&self.config.url)
+            .header("User-Agent", "Mozilla/5.0 (compatible; VideoSync/1.0)")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch article: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Article request failed with status {}", response.status()));
+        }
+
+        let html = response.text().await
+            .map_err(|e| format!("Failed to read article: {}", e))?;
+        let (title, mut text) = crate::utils::extract_article_text(&html);
+
+        if text.split_whitespace().count() < 50 {
+            return Err("Could not find enough article text on the page".to_string());
+        }
+        if text.len() > MAX_ARTICLE_CHARS {
+            let cut = text.char_indices().nth(MAX_ARTICLE_CHARS).map(|(i, _)| i).unwrap_or(text.len());
+            text.truncate(cut);
+        }
+
+        let source = reqwest::Url::parse(&self.config.url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+            .unwrap_or_else(|| self.config.url.clone());
+
+        let article = ArticleContent { url: self.config.url.clone(), source, title, text };
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Fetched article from {}", article.source))
+            .with_node_output("fetch_article".to_string(), serde_json::to_value(&article).unwrap_or_default()))
+    }
+}
+
+/// Summarizes the article into a narrated script with visual cues
+pub struct WriteScriptNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<ArticleVideoConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for WriteScriptNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let article: ArticleContent = state.node_value("fetch_article")?;
+        let claude = self.app_state.claude_client.as_ref()
+            .ok_or("Claude client not configured")?;
+
+        let target_words = (self.config.target_duration * WORDS_PER_SECOND).round() as usize;
+        let prompt = format!(
+            "Turn this article into a narrated video script of about {} words ({} seconds).\n\
+             Split it into 6-12 segments. Each segment has one or two sentences of narration and a short \
+             stock-footage search query (2-4 concrete visual words) for B-roll that matches it.\n\
+             Stay factual to the article and do not invent details.\n\n\
+             Respond with ONLY JSON in this format:\n\
+             {{\"title\": \"...\", \"segments\": [{{\"narration\": \"...\", \"visual_query\": \"...\"}}]}}\n\n\
+             ARTICLE TITLE: {}\n\nARTICLE:\n{}",
+            target_words,
+            self.config.target_duration.round(),
+            article.title.as_deref().unwrap_or("(untitled)"),
+            article.text
+        );
+
+        let response = claude.generate_text(&prompt).await?;
+        let script = parse_article_script(&response)?;
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Wrote {}-segment script: {}", script.segments.len(), script.title))
+            .with_node_output("write_script".to_string(), serde_json::to_value(&script).unwrap_or_default()))
+    }
+}
+
+/// Generates narration audio for every segment
+pub struct VoiceoverNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<ArticleVideoConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for VoiceoverNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let script: ArticleScript = state.node_value("write_script")?;
+        let elevenlabs = self.app_state.elevenlabs_client.as_ref()
+            .ok_or("ElevenLabs not configured - voiceover is required for article videos")?;
+
+        let voice_id = crate::elevenlabs_client::DefaultVoices::get_voice_id_by_name(&self.config.voice)
+            .unwrap_or(crate::elevenlabs_client::DefaultVoices::RACHEL);
+
+        let mut narrated = Vec::with_capacity(script.segments.len());
+        for (i, segment) in script.segments.iter().enumerate() {
+            let audio = elevenlabs.text_to_speech(&segment.narration, voice_id, Some("eleven_flash_v2_5"), None, Some("mp3_44100_128"))
+                .await
+                .map_err(|e| format!("Voiceover failed for segment {}: {}", i + 1, e))?;
+
+            let audio_file = part_path(&self.config.output_file, &format!("voice{}.mp3", i + 1));
+            tokio::fs::write(&audio_file, &audio).await
+                .map_err(|e| format!("Failed to write voiceover: {}", e))?;
+
+            let duration = crate::core::get_video_duration(&audio_file)?;
+            narrated.push(NarratedSegment { audio_file, duration });
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!(
+                "Narrated {} segments ({:.0}s)",
+                narrated.len(),
+                narrated.iter().map(|n| n.duration).sum::<f64>()
+            ))
+            .with_node_output("voiceover".to_string(), serde_json::to_value(&narrated).unwrap_or_default()))
+    }
+}
+
+/// Animates the presenter portrait to each segment's narration (skipped without a portrait)
+pub struct PresenterNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<ArticleVideoConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for PresenterNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let Some(image) = self.config.presenter_image.as_deref() else {
+            return Ok(StateUpdate::new()
+                .with_node_output("presenter".to_string(), serde_json::json!([])));
+        };
+        let narrated: Vec<NarratedSegment> = state.node_value("voiceover")?;
+        let avatar = self.app_state.avatar_client.as_ref()
+            .ok_or("Talking-avatar provider not configured - set DID_API_KEY to use a presenter")?;
+
+        let image_bytes = tokio::fs::read(image).await
+            .map_err(|e| format!("Failed to read presenter image {}: {}", image, e))?;
+        let image_name = std::path::Path::new(image).file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "presenter.jpg".to_string());
+
+        let mut clips = Vec::with_capacity(narrated.len());
+        for (i, segment) in narrated.iter().enumerate() {
+            let audio = tokio::fs::read(&segment.audio_file).await
+                .map_err(|e| format!("Failed to read narration for segment {}: {}", i + 1, e))?;
+            let video = avatar.generate_talking_head(image_bytes.clone(), &image_name, None, Some((audio, "narration.mp3")), None, 300)
+                .await
+                .map_err(|e| format!("Presenter failed for segment {}: {}", i + 1, e))?;
+
+            let path = part_path(&self.config.output_file, &format!("presenter{}.mp4", i + 1));
+            tokio::fs::write(&path, &video).await
+                .map_err(|e| format!("Failed to write presenter clip: {}", e))?;
+            clips.push(path);
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Rendered presenter for {} segments", clips.len()))
+            .with_node_output("presenter".to_string(), serde_json::to_value(&clips).unwrap_or_default()))
+    }
+}
+
+/// Finds a Pexels clip (or photo) per segment, falling back to a plain background
+pub struct SourceBrollNode {
+    config: Arc<ArticleVideoConfig>,
+}
+
+impl SourceBrollNode {
+    async fn find_clip(&self, pexels: &crate::pexels_client::PexelsClient, query: &str, index: usize) -> Option<BrollClip> {
+        if let Ok(results) = pexels.search_videos(query, Some(3), None, Some(self.config.width.min(1280) as i32), None, Some(4), None).await {
+            for video in &results.videos {
+                // Smallest file that still covers the output width keeps downloads quick
+                let file = video.video_files.iter()
+                    .filter(|f| f.file_type == "video/mp4" && f.width.unwrap_or(0) as u32 >= self.config.width.min(1280))
+                    .min_by_key(|f| f.width.unwrap_or(0))
+                    .or_else(|| video.video_files.iter().max_by_key(|f| f.width.unwrap_or(0)));
+
+                if let Some(file) = file {
+                    let path = part_path(&self.config.output_file, &format!("broll{}.mp4", index + 1));
+                    if pexels.download_video(file, &path).await.is_ok() {
+                        return Some(BrollClip {
+                            file: path,
+                            credit: Some(format!("Video by {} on Pexels", video.user.name)),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Ok(results) = pexels.search_photos(query, Some(1), None, None, None, None).await {
+            if let Some(photo) = results.photos.first() {
+                let path = part_path(&self.config.output_file, &format!("broll{}.jpg", index + 1));
+                if pexels.download_photo(photo, "large", &path).await.is_ok() {
+                    return Some(BrollClip {
+                        file: path,
+                        credit: Some(format!("Photo by {} on Pexels", photo.photographer)),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[async_trait]
+impl NodeFunction for SourceBrollNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let script: ArticleScript = state.node_value("write_script")?;
+        let pexels = crate::config::get().api_keys.pexels.clone().map(crate::pexels_client::PexelsClient::new);
+
+        if pexels.is_none() {
+            tracing::warn!("⚠️ Pexels API key not configured, article video will use plain backgrounds");
+        }
+
+        let mut clips = Vec::with_capacity(script.segments.len());
+        for (i, segment) in script.segments.iter().enumerate() {
+            let found = match &pexels {
+                Some(client) => self.find_clip(client, &segment.visual_query, i).await,
+                None => None,
+            };
+
+            let clip = match found {
+                Some(clip) => clip,
+                None => {
+                    let path = part_path(&self.config.output_file, &format!("broll{}.mp4", i + 1));
+                    crate::utils::create_blank_video(&path, 5.0, self.config.width, self.config.height, "#1a1a2e", None)?;
+                    BrollClip { file: path, credit: None }
+                }
+            };
+            clips.push(clip);
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!(
+                "Sourced B-roll for {} segments ({} from Pexels)",
+                clips.len(),
+                clips.iter().filter(|c| c.credit.is_some()).count()
+            ))
+            .with_node_output("source_broll".to_string(), serde_json::to_value(&clips).unwrap_or_default()))
+    }
+}
+
+/// Cuts B-roll to the narration, joins segments, burns captions and adds attribution
+pub struct AssembleArticleNode {
+    config: Arc<ArticleVideoConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for AssembleArticleNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let article: ArticleContent = state.node_value("fetch_article")?;
+        let script: ArticleScript = state.node_value("write_script")?;
+        let narrated: Vec<NarratedSegment> = state.node_value("voiceover")?;
+        let clips: Vec<BrollClip> = state.node_value("source_broll")?;
+        let presenters: Vec<String> = state.node_value("presenter").unwrap_or_default();
+
+        let mut credits: Vec<String> = clips.iter().filter_map(|c| c.credit.clone()).collect();
+        credits.dedup();
+
+        let config = self.config.clone();
+        let source = article.source.clone();
+        let segments = script.segments.clone();
+
+        let total_duration = tokio::task::spawn_blocking(move || -> Result<f64, String> {
+            let mut parts = Vec::with_capacity(narrated.len());
+            let mut cues = Vec::new();
+            let mut offset = 0.0;
+
+            for (i, ((segment, voice), clip)) in segments.iter().zip(narrated.iter()).zip(clips.iter()).enumerate() {
+                let mut visual = part_path(&config.output_file, &format!("visual{}.mp4", i + 1));
+                crate::visual::fit_broll_to_duration(&clip.file, &visual, voice.duration, config.width, config.height)?;
+                if let Some(presenter) = presenters.get(i) {
+                    let with_presenter = part_path(&config.output_file, &format!("visual{}_presenter.mp4", i + 1));
+                    crate::visual::overlay_presenter(&visual, presenter, &with_presenter, PRESENTER_SIZE)?;
+                    visual = with_presenter;
+                }
+
+                let part = part_path(&config.output_file, &format!("segment{}.mp4", i + 1));
+                crate::audio::replace_audio_padded(&visual, &voice.audio_file, &part)?;
+                parts.push(part);
+
+                cues.extend(crate::utils::chunk_caption_cues(&segment.narration, offset, voice.duration, 7));
+                offset += voice.duration;
+            }
+
+            let joined = part_path(&config.output_file, "joined.mp4");
+            crate::core::merge_videos(&parts, &joined)?;
+
+            let captioned = if config.captions && !cues.is_empty() {
+                let srt_file = part_path(&config.output_file, "captions.srt");
+                std::fs::write(&srt_file, crate::utils::build_srt(&cues))
+                    .map_err(|e| format!("Failed to write captions: {}", e))?;
+                let captioned = part_path(&config.output_file, "captioned.mp4");
+                crate::visual::add_subtitles(&joined, &srt_file, &captioned)?;
+                captioned
+            } else {
+                joined
+            };
+
+            let font_size = (config.height / 40).max(16);
+            crate::visual::add_text_overlay(
+                &captioned,
+                &config.output_file,
+                &crate::utils::escape_drawtext(&format!("Source: {}", source)),
+                "24",
+                "h-th-24",
+                "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+                font_size,
+                "white@0.85",
+                0.0,
+                offset,
+            )?;
+
+            Ok(offset)
+        })
+        .await
+        .map_err(|e| format!("Assembly task failed: {}", e))??;
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Article video saved to {}", self.config.output_file))
+            .with_node_output("assemble".to_string(), serde_json::json!({
+                "output_file": self.config.output_file,
+                "duration": total_duration,
+                "title": script.title,
+                "source_url": article.url,
+                "credits": credits,
+            }))
+            .with_status(WorkflowStatus::Completed))
+    }
+}
+
+/// Build the article-to-video workflow graph
+pub fn build_article_workflow(
+    app_state: Arc<crate::AppState>,
+    config: Arc<ArticleVideoConfig>,
+) -> Result<StateGraph, String> {
+    StateGraphBuilder::new()
+        .add_node(
+            "fetch_article",
+            NodeType::Tool,
+            Arc::new(FetchArticleNode { config: config.clone() }),
+            "Download the article and extract its text"
+        )
+        .add_node(
+            "write_script",
+            NodeType::Agent,
+            Arc::new(WriteScriptNode { app_state: app_state.clone(), config: config.clone() }),
+            "Summarize the article into a segmented script"
+        )
+        .add_node(
+            "voiceover",
+            NodeType::Tool,
+            Arc::new(VoiceoverNode { app_state: app_state.clone(), config: config.clone() }),
+            "Generate narration for each segment"
+        )
+        .add_node(
+            "presenter",
+            NodeType::Tool,
+            Arc::new(PresenterNode { app_state, config: config.clone() }),
+            "Animate the presenter portrait to the narration"
+        )
+        .add_node(
+            "source_broll",
+            NodeType::Tool,
+            Arc::new(SourceBrollNode { config: config.clone() }),
+            "Find stock footage for each segment"
+        )
+        .add_node(
+            "assemble",
+            NodeType::End,
+            Arc::new(AssembleArticleNode { config }),
+            "Cut, caption and attribute the final video"
+        )
+        .set_entry_point("fetch_article")
+        .add_edge("fetch_article", "write_script")
+        .add_edge("write_script", "voiceover")
+        .add_edge("voiceover", "presenter")
+        .add_edge("presenter", "source_broll")
+        .add_edge("source_broll", "assemble")
+        .build()
+}
+
+/// Run the article workflow end to end and return the final state
+pub async fn run_article_workflow(
+    app_state: Arc<crate::AppState>,
+    config: ArticleVideoConfig,
+    thread_id: String,
+) -> Result<WorkflowState, String> {
+    let graph = build_article_workflow(app_state.clone(), Arc::new(config.clone()))?;
+
+    let executor = ExecutorBuilder::new()
+        .with_graph(graph)
+        .with_checkpointer(WorkflowCheckpointer::new(app_state.db_pool.clone()))
+        .with_config(ExecutorConfig {
+            max_iterations: 12,
+            checkpoint_every_n_steps: 1,
+            enable_parallel: false,
+            timeout_seconds: 900,
+        })
+        .build()?;
+
+    let state = WorkflowState::new(
+        uuid::Uuid::new_v4().to_string(),
+        thread_id,
+        format!("Video from article {}", config.url),
+    );
+
+    executor.run_or_resume(state, &resume_key(&config)).await
+}
@@ -1,5 +1,6 @@
 // Checkpointing - Persist and resume workflows (LangGraph-inspired)
-use super::state::WorkflowState;
+use super::definition::VERSION_METADATA_KEY;
+use super::state::{WorkflowState, WorkflowStatus};
 use sqlx::PgPool;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,9 +14,21 @@ pub struct Checkpoint {
     pub thread_id: String,
     pub state: WorkflowState,
     pub version: i32,
+    /// Version of the workflow definition the run started from (definition runs only)
+    pub definition_version: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Run metadata holding the resume key of the request a run was started for
+pub const RESUME_KEY_METADATA_KEY: &str = "resume_key";
+
+/// Identifies what a run was started for (its config), so a retry of the same request can find and
+/// resume it
+pub fn resume_key(request: &impl std::fmt::Debug) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(format!("{:?}", request).as_bytes()))[..16].to_string()
+}
+
 /// Checkpointer - Saves and loads workflow state
 pub struct WorkflowCheckpointer {
     pool: PgPool,
@@ -72,6 +85,11 @@ impl WorkflowCheckpointer {
         .execute(&self.pool)
         .await?;
 
+        // Definition runs stay on the definition version they started from
+        sqlx::query("ALTER TABLE workflow_checkpoints ADD COLUMN IF NOT EXISTS definition_version INTEGER")
+            .execute(&self.pool)
+            .await?;
+
         info!("✅ Workflow checkpoint table setup complete");
         Ok(())
     }
@@ -103,12 +121,13 @@ impl WorkflowCheckpointer {
 
         let state_json = serde_json::to_value(state)
             .map_err(|e| format!("Failed to serialize state: {}", e))?;
+        let definition_version = state.metadata.get(VERSION_METADATA_KEY).and_then(|v| v.parse::<i32>().ok());
 
         sqlx::query(
             r#"
             INSERT INTO workflow_checkpoints
-            (checkpoint_id, workflow_id, thread_id, state, version, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            (checkpoint_id, workflow_id, thread_id, state, version, definition_version, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#,
         )
         .bind(&checkpoint_id)
@@ -116,6 +135,7 @@ impl WorkflowCheckpointer {
         .bind(thread_id)
         .bind(state_json)
         .bind(version)
+        .bind(definition_version)
         .bind(Utc::now())
         .execute(&self.pool)
         .await
@@ -135,7 +155,7 @@ impl WorkflowCheckpointer {
     ) -> Result<Option<Checkpoint>, String> {
         let result = sqlx::query_as::<_, CheckpointRow>(
             r#"
-            SELECT checkpoint_id, workflow_id, thread_id, state, version, created_at
+            SELECT checkpoint_id, workflow_id, thread_id, state, version, definition_version, created_at
             FROM workflow_checkpoints
             WHERE workflow_id = $1
             ORDER BY version DESC
@@ -157,6 +177,7 @@ impl WorkflowCheckpointer {
                 thread_id: row.thread_id,
                 state,
                 version: row.version,
+                definition_version: row.definition_version,
                 created_at: row.created_at,
             }))
         } else {
@@ -171,7 +192,7 @@ impl WorkflowCheckpointer {
     ) -> Result<Option<Checkpoint>, String> {
         let result = sqlx::query_as::<_, CheckpointRow>(
             r#"
-            SELECT checkpoint_id, workflow_id, thread_id, state, version, created_at
+            SELECT checkpoint_id, workflow_id, thread_id, state, version, definition_version, created_at
             FROM workflow_checkpoints
             WHERE checkpoint_id = $1
             "#,
@@ -191,6 +212,7 @@ impl WorkflowCheckpointer {
                 thread_id: row.thread_id,
                 state,
                 version: row.version,
+                definition_version: row.definition_version,
                 created_at: row.created_at,
             }))
         } else {
@@ -205,7 +227,7 @@ impl WorkflowCheckpointer {
     ) -> Result<Option<Checkpoint>, String> {
         let result = sqlx::query_as::<_, CheckpointRow>(
             r#"
-            SELECT checkpoint_id, workflow_id, thread_id, state, version, created_at
+            SELECT checkpoint_id, workflow_id, thread_id, state, version, definition_version, created_at
             FROM workflow_checkpoints
             WHERE thread_id = $1
             ORDER BY version DESC
@@ -227,6 +249,7 @@ impl WorkflowCheckpointer {
                 thread_id: row.thread_id,
                 state,
                 version: row.version,
+                definition_version: row.definition_version,
                 created_at: row.created_at,
             }))
         } else {
@@ -234,6 +257,51 @@ impl WorkflowCheckpointer {
         }
     }
 
+    /// Latest checkpoint of a run on `thread_id` started for `resume_key` in the last `max_age_hours`,
+    /// unless that run completed or was cancelled
+    pub async fn load_resumable(
+        &self,
+        thread_id: &str,
+        resume_key: &str,
+        max_age_hours: i64,
+    ) -> Result<Option<Checkpoint>, String> {
+        let result = sqlx::query_as::<_, CheckpointRow>(
+            r#"
+            SELECT checkpoint_id, workflow_id, thread_id, state, version, definition_version, created_at
+            FROM workflow_checkpoints
+            WHERE thread_id = $1 AND state->'metadata'->>$4 = $2 AND created_at > $3
+            ORDER BY created_at DESC, version DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(thread_id)
+        .bind(resume_key)
+        .bind(Utc::now() - chrono::Duration::hours(max_age_hours))
+        .bind(RESUME_KEY_METADATA_KEY)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to load checkpoint: {}", e))?;
+
+        let Some(row) = result else {
+            return Ok(None);
+        };
+        let state: WorkflowState = serde_json::from_value(row.state)
+            .map_err(|e| format!("Failed to deserialize state: {}", e))?;
+        if matches!(state.status, WorkflowStatus::Completed | WorkflowStatus::Cancelled) {
+            return Ok(None);
+        }
+
+        Ok(Some(Checkpoint {
+            checkpoint_id: row.checkpoint_id,
+            workflow_id: row.workflow_id,
+            thread_id: row.thread_id,
+            state,
+            version: row.version,
+            definition_version: row.definition_version,
+            created_at: row.created_at,
+        }))
+    }
+
     /// List all checkpoints for a workflow (time-travel debugging)
     pub async fn list_checkpoints(
         &self,
@@ -241,7 +309,7 @@ impl WorkflowCheckpointer {
     ) -> Result<Vec<Checkpoint>, String> {
         let rows = sqlx::query_as::<_, CheckpointRow>(
             r#"
-            SELECT checkpoint_id, workflow_id, thread_id, state, version, created_at
+            SELECT checkpoint_id, workflow_id, thread_id, state, version, definition_version, created_at
             FROM workflow_checkpoints
             WHERE workflow_id = $1
             ORDER BY version ASC
@@ -263,6 +331,7 @@ impl WorkflowCheckpointer {
                     thread_id: row.thread_id,
                     state,
                     version: row.version,
+                    definition_version: row.definition_version,
                     created_at: row.created_at,
                 })
             })
@@ -296,5 +365,6 @@ struct CheckpointRow {
     thread_id: String,
     state: serde_json::Value,
     version: i32,
+    definition_version: Option<i32>,
     created_at: DateTime<Utc>,
 }
@@ -0,0 +1,857 @@
+// Workflow definitions - custom pipelines in YAML or JSON instead of Rust
+//
+// A definition names tools as nodes and connects them with edges:
+//
+//   name: shorts-from-episode
+//   params:
+//     episode: { description: "Path of the recording" }
+//     height: { default: 1920 }
+//   nodes:
+//     - id: trim
+//       tool: trim_video
+//       args: { input_file: "{{params.episode}}", output_file: "outputs/trimmed.mp4", start_seconds: 0, end_seconds: 60 }
+//     - id: vertical
+//       tool: resize_video
+//       args: { input_file: "{{nodes.trim.output_file}}", output_file: "outputs/vertical.mp4", width: 1080, height: "{{params.height}}" }
+//   edges:
+//     - { from: trim, to: vertical }
+//
+// `{{params.<name>}}` is filled in when the graph is built; `{{nodes.<id>.<field>}}` when the node
+// runs, from an earlier node's arguments (`output_file`, ...), its `output` text or its `success`.
+// Edges run `always` (the default), or only on the `success` or `failure` of their `from` node, or
+// when its output contains a text; the first matching edge is taken and a node with none ends the run.
+//
+// A failed node is retried (`retries`, default 2, each attempt limited to `timeout_seconds`), then
+// replaced by its `fallback` node if it has one:
+//
+//     - id: voice
+//       tool: generate_text_to_speech
+//       retries: 1
+//       fallback: local_voice
+//
+// The fallback's result stands in for the failed node's, in `{{nodes.voice...}}` and on its edges.
+//
+// Re-running a definition with the same name and parameters resumes its unfinished run. A run stays
+// on the `version` it started from, even if the definition was edited since, unless the new version
+// declares how to carry such runs over:
+//
+//   version: 2
+//   migrations:
+//     - from: 1
+//       renamed: { trim: cut }    # old node id -> new node id, keeping its result
+//       rerun: [vertical]         # finished nodes to run again
+
+use super::checkpoint::{resume_key, Checkpoint, WorkflowCheckpointer, RESUME_KEY_METADATA_KEY};
+use super::executor::{ExecutorBuilder, ExecutorConfig, RESUME_WINDOW_HOURS};
+use super::graph::{NodeFunction, NodePolicy, NodeType, StateGraph, StateGraphBuilder};
+use super::state::{StateUpdate, WorkflowState};
+use crate::agent::tool_executor::ToolExecutionContext;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+/// Most nodes one definition may hold
+pub const MAX_NODES: usize = 50;
+
+/// Run metadata holding the definition a run was started from, as JSON
+pub const DEFINITION_METADATA_KEY: &str = "definition";
+
+/// Run metadata holding the version of that definition
+pub const VERSION_METADATA_KEY: &str = "definition_version";
+
+/// Most retries one node may ask for
+pub const MAX_RETRIES: usize = 10;
+
+/// Longest time limit one attempt of a node may ask for
+pub const MAX_TIMEOUT_SECONDS: u64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkflowDefinition {
+    pub name: String,
+    /// Bumped when the definition changes; unfinished runs keep the version they started from
+    #[serde(default = "first_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub params: BTreeMap<String, ParamDefinition>,
+    /// First node to run (default: the first in `nodes`)
+    #[serde(default)]
+    pub entry: Option<String>,
+    pub nodes: Vec<NodeDefinition>,
+    #[serde(default)]
+    pub edges: Vec<EdgeDefinition>,
+    /// How unfinished runs of earlier versions carry over to this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<RunMigration>,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+/// A parameter; required unless it has a default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParamDefinition {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: Option<Value>,
+}
+
+/// One tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeDefinition {
+    pub id: String,
+    /// A tool name from the agent's tool set (trim_video, add_subtitles, ...)
+    pub tool: String,
+    #[serde(default)]
+    pub args: serde_json::Map<String, Value>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Attempts after a failed one (default 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<usize>,
+    /// Time limit of each attempt (default 300)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// Wait before the first retry, doubled before each one after it (default 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_delay_seconds: Option<u64>,
+    /// Node run instead once the retries are used up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
+
+impl NodeDefinition {
+    fn policy(&self) -> NodePolicy {
+        let defaults = NodePolicy::default();
+        NodePolicy {
+            max_retries: self.retries.unwrap_or(defaults.max_retries),
+            timeout_seconds: self.timeout_seconds.unwrap_or(defaults.timeout_seconds),
+            retry_delay_seconds: self.retry_delay_seconds.unwrap_or(defaults.retry_delay_seconds),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EdgeDefinition {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub when: EdgeCondition,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeCondition {
+    #[default]
+    Always,
+    Success,
+    Failure,
+    /// The node's output contains this text
+    OutputContains(String),
+}
+
+impl EdgeCondition {
+    /// Whether the edge is taken, given what the `from` node recorded in `node_outputs`
+    fn matches(&self, result: Option<&Value>) -> bool {
+        let succeeded = result.and_then(|r| r["success"].as_bool()).unwrap_or(false);
+        match self {
+            EdgeCondition::Always => true,
+            EdgeCondition::Success => succeeded,
+            EdgeCondition::Failure => !succeeded,
+            EdgeCondition::OutputContains(text) => {
+                result.and_then(|r| r["output"].as_str()).is_some_and(|output| output.contains(text.as_str()))
+            }
+        }
+    }
+}
+
+/// Carries an unfinished run of an earlier version over to this one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunMigration {
+    /// The version the runs started from
+    pub from: u32,
+    /// Old node id -> new node id, for nodes that were renamed; their results carry over
+    #[serde(default)]
+    pub renamed: BTreeMap<String, String>,
+    /// Finished nodes (by their new id) to run again; the run continues from the first of them
+    #[serde(default)]
+    pub rerun: Vec<String>,
+}
+
+impl RunMigration {
+    /// The state of a run of version `from`, moved onto `to`: renamed nodes keep their results under
+    /// their new ids, removed and rerun nodes lose theirs. The run continues from the first rerun
+    /// node it had finished, else where it stopped, else after the last finished node still there.
+    pub fn apply(&self, mut state: WorkflowState, to: &WorkflowDefinition) -> WorkflowState {
+        let rename = |id: &str| self.renamed.get(id).cloned().unwrap_or_else(|| id.to_string());
+        let exists = |id: &str| to.nodes.iter().any(|node| node.id == id);
+        let keep = |id: &str| exists(id) && !self.rerun.iter().any(|rerun| rerun == id);
+
+        let rerun_from = state.completed_nodes.iter().map(|id| rename(id)).find(|id| self.rerun.contains(id));
+        let current = rename(&state.current_node);
+        state.completed_nodes = state.completed_nodes.iter().map(|id| rename(id)).filter(|id| keep(id)).collect();
+        state.current_node = match rerun_from {
+            Some(node) => node,
+            None if exists(&current) => current,
+            None => state.completed_nodes.last().cloned().unwrap_or_else(|| "start".to_string()),
+        };
+
+        state.node_outputs = std::mem::take(&mut state.node_outputs)
+            .into_iter()
+            .map(|(id, output)| (rename(&id), output))
+            .filter(|(id, _)| keep(id))
+            .collect();
+        state.node_attempts = std::mem::take(&mut state.node_attempts)
+            .into_iter()
+            .map(|(id, mut attempts)| {
+                attempts.fell_back_to = attempts.fell_back_to.map(|fallback| rename(&fallback));
+                (rename(&id), attempts)
+            })
+            .filter(|(id, _)| keep(id))
+            .collect();
+        state.node_timings = std::mem::take(&mut state.node_timings)
+            .into_iter()
+            .map(|(id, timing)| (rename(&id), timing))
+            .filter(|(id, _)| keep(id))
+            .collect();
+
+        to.record_in(&mut state);
+        state.metadata.insert("migrated_from_version".to_string(), self.from.to_string());
+        state
+    }
+}
+
+/// A `{{...}}` reference in an argument
+#[derive(Debug, PartialEq)]
+enum Placeholder<'a> {
+    Param(&'a str),
+    Node(&'a str, &'a str),
+    Invalid(&'a str),
+}
+
+fn placeholder(expression: &str) -> Placeholder<'_> {
+    let parts: Vec<&str> = expression.split('.').map(str::trim).collect();
+    match parts.as_slice() {
+        ["params", name] if !name.is_empty() => Placeholder::Param(name),
+        ["nodes", id, field] if !id.is_empty() && !field.is_empty() => Placeholder::Node(id, field),
+        _ => Placeholder::Invalid(expression.trim()),
+    }
+}
+
+/// The `{{...}}` expressions in a text
+fn expressions(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        found.push(&rest[start + 2..start + 2 + end]);
+        rest = &rest[start + 2 + end + 2..];
+    }
+    found
+}
+
+/// Replace the expressions `lookup` knows, leaving the others for later. A text that is a single
+/// expression takes the value's own type, so `"{{params.height}}"` can fill a numeric argument.
+fn render(value: &Value, lookup: &dyn Fn(&str) -> Option<Value>) -> Value {
+    match value {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            if trimmed.starts_with("{{") && trimmed.ends_with("}}") && expressions(trimmed).len() == 1 {
+                if let Some(found) = lookup(&trimmed[2..trimmed.len() - 2]) {
+                    return found;
+                }
+            }
+            let mut rendered = String::new();
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(end) = rest[start + 2..].find("}}") else {
+                    break;
+                };
+                let slot_end = start + 2 + end + 2;
+                rendered.push_str(&rest[..start]);
+                match lookup(&rest[start + 2..start + 2 + end]) {
+                    Some(found) => rendered.push_str(&as_text(&found)),
+                    None => rendered.push_str(&rest[start..slot_end]),
+                }
+                rest = &rest[slot_end..];
+            }
+            rendered.push_str(rest);
+            Value::String(rendered)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| render(item, lookup)).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), render(v, lookup))).collect()),
+        other => other.clone(),
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Every string inside a value
+fn strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(text) => vec![text.as_str()],
+        Value::Array(items) => items.iter().flat_map(strings).collect(),
+        Value::Object(map) => map.values().flat_map(strings).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Argument names each tool accepts and requires, from the tool schemas the agent is offered
+fn tool_schemas() -> HashMap<String, (HashSet<String>, Vec<String>)> {
+    crate::claude_client::ClaudeClient::create_video_editing_tools()
+        .into_iter()
+        .map(|tool| (tool.name, (tool.input_schema.properties.into_keys().collect(), tool.input_schema.required)))
+        .collect()
+}
+
+impl WorkflowDefinition {
+    /// Parse and validate a definition written in YAML or JSON (told apart by a leading `{`)
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let definition: Self = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| format!("Invalid workflow JSON: {}", e))?
+        } else {
+            serde_yaml::from_str(text).map_err(|e| format!("Invalid workflow YAML: {}", e))?
+        };
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Parse and validate a definition sent as a JSON object, or as YAML/JSON text in a string
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        if let Value::String(text) = value {
+            return Self::parse(&text);
+        }
+        let definition: Self = serde_json::from_value(value).map_err(|e| format!("Invalid workflow definition: {}", e))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// The node a run starts at
+    pub fn entry_node(&self) -> Option<&str> {
+        self.entry.as_deref().or_else(|| self.nodes.first().map(|n| n.id.as_str()))
+    }
+
+    /// The definition a run was started from, if it was started from one
+    pub fn of_run(state: &WorkflowState) -> Option<Self> {
+        serde_json::from_str(state.metadata.get(DEFINITION_METADATA_KEY)?).ok()
+    }
+
+    /// Keep this definition and its version with a run, for resuming and drawing it
+    fn record_in(&self, state: &mut WorkflowState) {
+        if let Ok(json) = serde_json::to_string(self) {
+            state.metadata.insert(DEFINITION_METADATA_KEY.to_string(), json);
+        }
+        state.metadata.insert(VERSION_METADATA_KEY.to_string(), self.version.to_string());
+    }
+
+    /// The migration this version declares for runs of `version`, unless that's this version
+    pub fn migration_from(&self, version: u32) -> Option<&RunMigration> {
+        self.migrations.iter().find(|migration| migration.from == version && version != self.version)
+    }
+
+    /// The version an unfinished run continues on: the one it started from, unless this version
+    /// migrates runs of it
+    pub fn resumed_version(&self, checkpoint: &Checkpoint) -> u32 {
+        let started = started_version(checkpoint);
+        if self.migration_from(started).is_some() {
+            self.version
+        } else {
+            started
+        }
+    }
+
+    /// Check the whole definition and report every problem found, not just the first
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if self.name.trim().is_empty() {
+            problems.push("the workflow needs a name".to_string());
+        }
+        if self.version == 0 {
+            problems.push("versions start at 1".to_string());
+        }
+        if self.nodes.is_empty() || self.nodes.len() > MAX_NODES {
+            problems.push(format!("a workflow needs between 1 and {} nodes", MAX_NODES));
+        }
+        for name in self.params.keys() {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                problems.push(format!("parameter '{}': names may only use letters, digits and _", name));
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for node in &self.nodes {
+            if node.id.is_empty() || !node.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                problems.push(format!("node '{}': ids may only use letters, digits, _ and -", node.id));
+            }
+            if !ids.insert(node.id.as_str()) {
+                problems.push(format!("node '{}' is defined twice", node.id));
+            }
+        }
+
+        let schemas = tool_schemas();
+        for node in &self.nodes {
+            let Some((accepted, required)) = schemas.get(&node.tool) else {
+                let similar: Vec<&str> = schemas
+                    .keys()
+                    .filter(|name| name.contains(node.tool.as_str()) || node.tool.contains(name.as_str()))
+                    .map(String::as_str)
+                    .collect();
+                let hint = if similar.is_empty() { String::new() } else { format!(" (did you mean {}?)", similar.join(" or ")) };
+                problems.push(format!("node '{}': unknown tool '{}'{}", node.id, node.tool, hint));
+                continue;
+            };
+            for arg in node.args.keys().filter(|arg| !accepted.contains(*arg)) {
+                let mut names: Vec<&str> = accepted.iter().map(String::as_str).collect();
+                names.sort();
+                problems.push(format!("node '{}': {} has no argument '{}' (it takes {})", node.id, node.tool, arg, names.join(", ")));
+            }
+            for arg in required.iter().filter(|arg| !node.args.contains_key(*arg)) {
+                problems.push(format!("node '{}': {} needs the argument '{}'", node.id, node.tool, arg));
+            }
+            for text in node.args.values().flat_map(strings) {
+                for expression in expressions(text) {
+                    match placeholder(expression) {
+                        Placeholder::Param(name) if !self.params.contains_key(name) => {
+                            problems.push(format!("node '{}': {{{{params.{}}}}} isn't a declared parameter", node.id, name));
+                        }
+                        Placeholder::Node(id, _) if id == node.id => {
+                            problems.push(format!("node '{}': {{{{nodes.{}...}}}} refers to the node itself", node.id, id));
+                        }
+                        Placeholder::Node(id, _) if !ids.contains(id) => {
+                            problems.push(format!("node '{}': {{{{nodes.{}...}}}} refers to an unknown node", node.id, id));
+                        }
+                        Placeholder::Invalid(expression) => problems.push(format!(
+                            "node '{}': {{{{{}}}}} should be {{{{params.<name>}}}} or {{{{nodes.<id>.<field>}}}}",
+                            node.id, expression
+                        )),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        for node in &self.nodes {
+            if node.retries.is_some_and(|retries| retries > MAX_RETRIES) {
+                problems.push(format!("node '{}': retries can be at most {}", node.id, MAX_RETRIES));
+            }
+            if node.timeout_seconds.is_some_and(|seconds| seconds == 0 || seconds > MAX_TIMEOUT_SECONDS) {
+                problems.push(format!("node '{}': timeout_seconds must be between 1 and {}", node.id, MAX_TIMEOUT_SECONDS));
+            }
+            if node.retry_delay_seconds.is_some_and(|seconds| seconds > MAX_TIMEOUT_SECONDS) {
+                problems.push(format!("node '{}': retry_delay_seconds can be at most {}", node.id, MAX_TIMEOUT_SECONDS));
+            }
+            match node.fallback.as_deref() {
+                Some(fallback) if fallback == node.id => problems.push(format!("node '{}' can't be its own fallback", node.id)),
+                Some(fallback) if !ids.contains(fallback) => {
+                    problems.push(format!("node '{}': there is no fallback node '{}'", node.id, fallback))
+                }
+                _ => {}
+            }
+        }
+
+        let mut migrated = HashSet::new();
+        for migration in &self.migrations {
+            if migration.from >= self.version {
+                problems.push(format!("migration from version {}: only earlier versions than {} can be migrated", migration.from, self.version));
+            }
+            if !migrated.insert(migration.from) {
+                problems.push(format!("version {} has more than one migration", migration.from));
+            }
+            for (old, new) in &migration.renamed {
+                if !ids.contains(new.as_str()) {
+                    problems.push(format!("migration from version {}: '{}' is renamed to '{}', which isn't a node", migration.from, old, new));
+                }
+            }
+            for id in migration.rerun.iter().filter(|id| !ids.contains(id.as_str())) {
+                problems.push(format!("migration from version {}: there is no node '{}' to rerun", migration.from, id));
+            }
+        }
+
+        let mut outgoing: HashMap<&str, Vec<&EdgeDefinition>> = HashMap::new();
+        for edge in &self.edges {
+            for end in [&edge.from, &edge.to] {
+                if !ids.contains(end.as_str()) {
+                    problems.push(format!("edge {} -> {}: there is no node '{}'", edge.from, edge.to, end));
+                }
+            }
+            outgoing.entry(edge.from.as_str()).or_default().push(edge);
+        }
+        for (from, edges) in &outgoing {
+            if edges.len() > 1 && edges.iter().any(|e| e.when == EdgeCondition::Always) {
+                problems.push(format!(
+                    "node '{}' has an unconditional edge and other edges; give each edge a `when` (success, failure or output_contains)",
+                    from
+                ));
+            }
+        }
+
+        match self.entry_node() {
+            Some(entry) if !ids.contains(entry) => problems.push(format!("the entry node '{}' doesn't exist", entry)),
+            Some(entry) => {
+                let mut reached = HashSet::from([entry]);
+                let mut queue = VecDeque::from([entry]);
+                let fallbacks: HashMap<&str, &str> = self
+                    .nodes
+                    .iter()
+                    .filter_map(|n| Some((n.id.as_str(), n.fallback.as_deref()?)))
+                    .collect();
+                while let Some(id) = queue.pop_front() {
+                    let targets = outgoing.get(id).into_iter().flatten().map(|edge| edge.to.as_str());
+                    for target in targets.chain(fallbacks.get(id).copied()) {
+                        if reached.insert(target) {
+                            queue.push_back(target);
+                        }
+                    }
+                }
+                for node in self.nodes.iter().filter(|n| !reached.contains(n.id.as_str())) {
+                    problems.push(format!("node '{}' can't be reached from the entry node '{}'", node.id, entry));
+                }
+            }
+            None => {}
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Workflow '{}' is invalid:\n- {}", self.name, problems.join("\n- ")))
+        }
+    }
+
+    /// Parameter values for a run: `values` over the defaults; fails on missing and unknown ones
+    pub fn resolve_params(&self, values: &HashMap<String, Value>) -> Result<HashMap<String, Value>, String> {
+        if let Some(unknown) = values.keys().find(|key| !self.params.contains_key(*key)) {
+            let names: Vec<&str> = self.params.keys().map(String::as_str).collect();
+            return Err(format!("Unknown parameter '{}'. This workflow takes: {}", unknown, names.join(", ")));
+        }
+        let mut resolved = HashMap::new();
+        for (name, param) in &self.params {
+            let value = values
+                .get(name)
+                .filter(|v| !v.is_null())
+                .or(param.default.as_ref())
+                .ok_or_else(|| format!("Parameter '{}' is required", name))?;
+            resolved.insert(name.clone(), value.clone());
+        }
+        Ok(resolved)
+    }
+
+    /// Identifies runs of this workflow (by name, whatever its version) with these parameters, to
+    /// find one to resume
+    pub fn run_key(&self, params: &HashMap<String, Value>) -> String {
+        let sorted: BTreeMap<_, _> = params.iter().collect();
+        resume_key(&(&self.name, sorted))
+    }
+
+    /// The graph of this definition with `params` filled in; its nodes run their tools in `ctx`
+    pub fn build_graph(&self, params: &HashMap<String, Value>, ctx: Arc<ToolExecutionContext>) -> Result<StateGraph, String> {
+        let params = self.resolve_params(params)?;
+        let lookup_param = |expression: &str| match placeholder(expression) {
+            Placeholder::Param(name) => params.get(name).cloned(),
+            _ => None,
+        };
+
+        let mut builder = StateGraphBuilder::new();
+        for node in &self.nodes {
+            let tool = ToolNode {
+                id: node.id.clone(),
+                tool: node.tool.clone(),
+                args: render(&Value::Object(node.args.clone()), &lookup_param),
+                handles_failure: self.edges.iter().any(|edge| {
+                    edge.from == node.id && matches!(edge.when, EdgeCondition::Failure | EdgeCondition::OutputContains(_))
+                }),
+                ctx: ctx.clone(),
+            };
+            let description = node.description.clone().unwrap_or_else(|| format!("Run {}", node.tool));
+            builder = builder
+                .add_node(&node.id, NodeType::Tool, Arc::new(tool), &description)
+                .with_policy(&node.id, node.policy());
+        }
+        builder = builder.set_entry_point(self.entry_node().unwrap_or_default());
+
+        let mut outgoing: BTreeMap<&str, Vec<EdgeDefinition>> = BTreeMap::new();
+        for edge in &self.edges {
+            outgoing.entry(edge.from.as_str()).or_default().push(edge.clone());
+        }
+        for (from, edges) in outgoing {
+            if let [edge] = edges.as_slice() {
+                if edge.when == EdgeCondition::Always {
+                    builder = builder.add_edge(from, &edge.to);
+                    continue;
+                }
+            }
+            let node = from.to_string();
+            builder = builder.add_conditional_edge(
+                from,
+                Arc::new(move |state: &WorkflowState| {
+                    let result = node_result(state, &node);
+                    edges.iter().find(|edge| edge.when.matches(result)).map(|edge| edge.to.clone())
+                }),
+            );
+        }
+        builder.build()
+    }
+}
+
+/// What a node recorded in `node_outputs`, or what its fallback did if it fell back
+fn node_result<'a>(state: &'a WorkflowState, id: &str) -> Option<&'a Value> {
+    state.node_outputs.get(id).or_else(|| {
+        let fallback = state.node_attempts.get(id)?.fell_back_to.as_deref()?;
+        state.node_outputs.get(fallback)
+    })
+}
+
+/// Runs one tool with its arguments filled in from the parameters and earlier nodes
+struct ToolNode {
+    id: String,
+    tool: String,
+    args: Value,
+    /// An edge routes on this node's failure, so a failed tool doesn't fail the run
+    handles_failure: bool,
+    ctx: Arc<ToolExecutionContext>,
+}
+
+#[async_trait]
+impl NodeFunction for ToolNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let lookup_node = |expression: &str| match placeholder(expression) {
+            Placeholder::Node(id, field) => {
+                let result = node_result(state, id)?;
+                result.get(field).or_else(|| result["args"].get(field)).cloned()
+            }
+            _ => None,
+        };
+        let args = render(&self.args, &lookup_node);
+        if let Some(missing) = strings(&args).into_iter().flat_map(expressions).next() {
+            return Err(format!("Node '{}' uses {{{{{}}}}}, which no earlier node has produced", self.id, missing));
+        }
+
+        tracing::info!("🧩 Workflow node '{}' runs {}", self.id, self.tool);
+        let output = crate::agent::tool_executor::execute_tool_claude_with_context(&self.tool, &args, &self.ctx).await;
+        // Same test the tool executor uses before saving a tool's output
+        let success = !output.starts_with("❌") && !output.starts_with("Error");
+        if !success && !self.handles_failure {
+            return Err(format!("Node '{}' ({}) failed: {}", self.id, self.tool, output));
+        }
+
+        // Outputs land under outputs/ whatever path was given, so later nodes get the real path
+        let mut recorded = args.clone();
+        for key in ["output_file", "output_path"] {
+            if let Some(path) = recorded.get(key).and_then(|v| v.as_str()).map(crate::agent::tool_executor::ensure_outputs_directory) {
+                recorded[key] = Value::String(path);
+            }
+        }
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("{}: {}", self.id, output))
+            .with_node_output(
+                self.id.clone(),
+                serde_json::json!({ "tool": self.tool, "success": success, "output": output, "args": recorded }),
+            ))
+    }
+}
+
+/// Version of the definition a checkpointed run started from
+fn started_version(checkpoint: &Checkpoint) -> u32 {
+    checkpoint
+        .definition_version
+        .and_then(|version| u32::try_from(version).ok())
+        .or_else(|| WorkflowDefinition::of_run(&checkpoint.state).map(|definition| definition.version))
+        .unwrap_or_else(first_version)
+}
+
+/// Run a definition end to end in the session of `ctx` and return the final state. An unfinished
+/// run of the same workflow with the same parameters in the session is resumed instead, under its
+/// own workflow id, on the version it started from or migrated to this one.
+pub async fn run_workflow_definition(
+    definition: &WorkflowDefinition,
+    params: &HashMap<String, Value>,
+    ctx: Arc<ToolExecutionContext>,
+    workflow_id: String,
+) -> Result<WorkflowState, String> {
+    let (app_state, session_id) = (ctx.app_state.clone(), ctx.session_id.clone());
+    let run_key = definition.run_key(params);
+    let resumable = WorkflowCheckpointer::new(app_state.db_pool.clone())
+        .load_resumable(&session_id, &run_key, RESUME_WINDOW_HOURS)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to look for a workflow run to resume: {}", e);
+            None
+        });
+
+    let (definition, resumed) = match resumable {
+        Some(checkpoint) => {
+            let started = started_version(&checkpoint);
+            if let Some(migration) = definition.migration_from(started) {
+                tracing::info!("🧩 Migrating run {} of '{}' from version {} to {}", checkpoint.workflow_id, definition.name, started, definition.version);
+                (definition.clone(), Some(migration.apply(checkpoint.state, definition)))
+            } else if let Some(pinned) = WorkflowDefinition::of_run(&checkpoint.state) {
+                if pinned.version != definition.version {
+                    tracing::info!("🧩 Run {} of '{}' stays on version {} (version {} has no migration from it)",
+                        checkpoint.workflow_id, definition.name, pinned.version, definition.version);
+                }
+                (pinned, Some(checkpoint.state))
+            } else {
+                // Started before definitions were kept with their runs
+                (definition.clone(), Some(checkpoint.state))
+            }
+        }
+        None => (definition.clone(), None),
+    };
+
+    let graph = definition.build_graph(params, ctx)?;
+    let executor = ExecutorBuilder::new()
+        .with_graph(graph)
+        .with_checkpointer(WorkflowCheckpointer::new(app_state.db_pool.clone()))
+        .with_config(ExecutorConfig {
+            max_iterations: definition.nodes.iter().map(|n| n.policy().max_retries + 1).sum::<usize>() * 4,
+            checkpoint_every_n_steps: 1,
+            enable_parallel: false,
+            timeout_seconds: 3600,
+        })
+        .build()?;
+
+    if let Some(state) = resumed {
+        return executor.resume(state).await;
+    }
+    let mut state = WorkflowState::new(workflow_id, session_id, format!("Workflow {}", definition.name));
+    definition.record_in(&mut state);
+    state.metadata.insert(RESUME_KEY_METADATA_KEY.to_string(), run_key);
+    executor.run(state).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORTS: &str = r#"
+name: shorts
+params:
+  episode: { description: "Path of the recording" }
+  height: { default: 1920 }
+nodes:
+  - id: trim
+    tool: trim_video
+    args: { input_file: "{{params.episode}}", output_file: "outputs/trimmed.mp4", start_seconds: 0, end_seconds: 60 }
+  - id: vertical
+    tool: resize_video
+    args: { input_file: "{{nodes.trim.output_file}}", output_file: "outputs/vertical.mp4", width: 1080, height: "{{params.height}}" }
+  - id: report
+    tool: analyze_video
+    args: { input_file: "{{nodes.trim.input_file}}" }
+edges:
+  - { from: trim, to: vertical, when: success }
+  - { from: trim, to: report, when: failure }
+"#;
+
+    #[test]
+    fn yaml_and_json_definitions_load_and_fill_their_parameters() {
+        let definition = WorkflowDefinition::parse(SHORTS).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(definition.entry_node(), Some("trim"));
+        let json = serde_json::to_string(&definition).unwrap();
+        assert_eq!(WorkflowDefinition::parse(&json).unwrap().nodes.len(), 3);
+
+        let params = definition.resolve_params(&HashMap::from([("episode".to_string(), Value::from("uploads/ep1.mp4"))])).unwrap();
+        let lookup = |expression: &str| match placeholder(expression) {
+            Placeholder::Param(name) => params.get(name).cloned(),
+            _ => None,
+        };
+        let args = render(&Value::Object(definition.nodes[1].args.clone()), &lookup);
+        assert_eq!(args["height"], 1920);
+        assert_eq!(args["input_file"], "{{nodes.trim.output_file}}");
+        assert!(definition.resolve_params(&HashMap::new()).unwrap_err().contains("'episode' is required"));
+
+        let failed = serde_json::json!({ "success": false, "output": "❌ Error: no such file" });
+        assert!(EdgeCondition::Failure.matches(Some(&failed)));
+        assert!(!EdgeCondition::Success.matches(Some(&failed)));
+        assert!(EdgeCondition::OutputContains("no such file".into()).matches(Some(&failed)));
+    }
+
+    #[test]
+    fn validation_reports_every_problem() {
+        let broken = SHORTS
+            .replace("tool: trim_video", "tool: trim")
+            .replace("{{params.height}}", "{{params.heigth}}")
+            .replace("output_file: \"outputs/vertical.mp4\", width: 1080,", "widht: 1080,")
+            .replace("{ from: trim, to: report, when: failure }", "{ from: trim, to: reprot }");
+        let error = WorkflowDefinition::parse(&broken).unwrap_err();
+        for expected in [
+            "unknown tool 'trim' (did you mean trim_video",
+            "{{params.heigth}} isn't a declared parameter",
+            "resize_video has no argument 'widht'",
+            "resize_video needs the argument 'output_file'",
+            "there is no node 'reprot'",
+            "node 'trim' has an unconditional edge and other edges",
+            "node 'report' can't be reached",
+        ] {
+            assert!(error.contains(expected), "missing {:?} in:\n{}", expected, error);
+        }
+
+        // A node reached only as a fallback is reachable
+        let with_fallback = SHORTS.replace(
+            "    tool: trim_video\n",
+            "    tool: trim_video\n    retries: 1\n    fallback: report\n",
+        ).replace("  - { from: trim, to: report, when: failure }\n", "");
+        let definition = WorkflowDefinition::parse(&with_fallback).unwrap_or_else(|e| panic!("{}", e));
+        assert_eq!(definition.nodes[0].policy().fallback.as_deref(), Some("report"));
+        let error = WorkflowDefinition::parse(&with_fallback.replace("retries: 1\n    fallback: report", "retries: 99\n    fallback: trim"))
+            .unwrap_err();
+        assert!(error.contains("'trim' can't be its own fallback"), "{}", error);
+        assert!(error.contains("retries can be at most 10"), "{}", error);
+
+        let typo = WorkflowDefinition::parse("name: x\nnodes: []\nedgse: []").unwrap_err();
+        assert!(typo.contains("unknown field `edgse`"), "{}", typo);
+    }
+
+    #[test]
+    fn runs_of_earlier_versions_are_migrated_by_node_id() {
+        // Version 2 renames "trim" to "cut" and changes how "vertical" resizes
+        let v2 = SHORTS
+            .replace("name: shorts\n", "name: shorts\nversion: 2\nmigrations:\n  - { from: 1, renamed: { trim: cut }, rerun: [vertical] }\n")
+            .replace("id: trim", "id: cut")
+            .replace("nodes.trim.", "nodes.cut.")
+            .replace("from: trim", "from: cut");
+        let v2 = WorkflowDefinition::parse(&v2).unwrap_or_else(|e| panic!("{}", e));
+        assert!(v2.migration_from(1).is_some() && v2.migration_from(2).is_none());
+        assert_eq!(v2.run_key(&HashMap::new()), WorkflowDefinition::parse(SHORTS).unwrap().run_key(&HashMap::new()));
+
+        // Version 1 stopped after both nodes finished
+        let mut state = WorkflowState::new("wf".into(), "session".into(), "".into());
+        state.current_node = "vertical".into();
+        state.completed_nodes = vec!["trim".into(), "vertical".into()];
+        state.node_outputs.insert("trim".into(), serde_json::json!({ "success": true }));
+        state.node_outputs.insert("vertical".into(), serde_json::json!({ "success": true }));
+
+        let migrated = v2.migrations[0].apply(state, &v2);
+        assert_eq!(migrated.completed_nodes, ["cut"]);
+        assert_eq!(migrated.current_node, "vertical");
+        assert!(migrated.node_outputs.contains_key("cut") && !migrated.node_outputs.contains_key("vertical"));
+        assert_eq!(migrated.metadata.get(VERSION_METADATA_KEY).map(String::as_str), Some("2"));
+        assert_eq!(WorkflowDefinition::of_run(&migrated).unwrap().version, 2);
+
+        let error = WorkflowDefinition::parse(&SHORTS.replace(
+            "name: shorts\n",
+            "name: shorts\nversion: 2\nmigrations:\n  - { from: 2, renamed: { trim: cut }, rerun: [crop] }\n",
+        ))
+        .unwrap_err();
+        assert!(error.contains("only earlier versions than 2"), "{}", error);
+        assert!(error.contains("renamed to 'cut', which isn't a node"), "{}", error);
+        assert!(error.contains("no node 'crop' to rerun"), "{}", error);
+    }
+}
@@ -1,12 +1,15 @@
 // Executor - Runs the workflow graph with checkpointing and retries
-use super::state::{WorkflowState, StateUpdate, WorkflowStatus, WorkflowError};
-use super::graph::{StateGraph, NodeType};
-use super::checkpoint::WorkflowCheckpointer;
+use super::state::{WorkflowState, StateUpdate, WorkflowStatus, WorkflowError, NodeTiming};
+use super::graph::{StateGraph, NodePolicy};
+use super::checkpoint::{WorkflowCheckpointer, RESUME_KEY_METADATA_KEY};
 use tokio::time::{timeout, Duration};
 use tracing::{info, warn, error};
 use futures::future::join_all;
 use chrono::Utc;
 
+/// How old an unfinished run may be and still be resumed instead of started over
+pub const RESUME_WINDOW_HOURS: i64 = 24;
+
 /// Workflow executor config
 pub struct ExecutorConfig {
     pub max_iterations: usize,
@@ -26,6 +29,12 @@ impl Default for ExecutorConfig {
     }
 }
 
+/// Wait before retry number `failures`: the policy's delay, doubled for each retry before it
+fn retry_delay(policy: &NodePolicy, failures: usize) -> Duration {
+    let doublings = failures.saturating_sub(1).min(6) as u32;
+    Duration::from_secs(policy.retry_delay_seconds.saturating_mul(1 << doublings))
+}
+
 /// Workflow executor
 pub struct WorkflowExecutor {
     graph: StateGraph,
@@ -55,11 +64,21 @@ impl WorkflowExecutor {
         info!("🚀 Starting workflow execution: {}", state.workflow_id);
         state.status = WorkflowStatus::Running;
 
-        // Start from entry point or resume from checkpoint
+        // Start from entry point or resume from checkpoint. A checkpoint saved after its current node
+        // finished continues with the node after it.
         let mut current_node = if state.current_node == "start" {
             self.graph.get_entry_point()
                 .ok_or("No entry point")?
                 .clone()
+        } else if state.completed_nodes.last() == Some(&state.current_node) {
+            match self.next_nodes(&state.current_node, &state).first() {
+                Some(next) => next.clone(),
+                None => {
+                    info!("🏁 Every step of workflow {} had already finished", state.workflow_id);
+                    state.status = WorkflowStatus::Completed;
+                    return Ok(state);
+                }
+            }
         } else {
             state.current_node.clone()
         };
@@ -91,9 +110,19 @@ impl WorkflowExecutor {
 
             // Update state with current node
             state.current_node = current_node.clone();
+            state.node_timings.insert(current_node.clone(), NodeTiming::started());
+            // Runs checkpointed every step also record each node as it starts, so a watcher sees
+            // which one is running
+            if self.config.checkpoint_every_n_steps == 1 {
+                self.save_checkpoint(&state).await;
+            }
 
             // Execute node with timeout and retry
-            let update = match self.execute_node_with_retry(node, &state).await {
+            let result = self.execute_node_with_retry(node, &state).await;
+            if let Some(timing) = state.node_timings.get_mut(&current_node) {
+                timing.finish();
+            }
+            let update = match result {
                 Ok(update) => update,
                 Err(e) => {
                     error!("❌ Node '{}' failed: {}", current_node, e);
@@ -106,11 +135,31 @@ impl WorkflowExecutor {
                     });
                     state.error_count += 1;
 
-                    // Check if should retry
-                    if state.should_retry(node.max_retries) {
+                    let attempts = state.node_attempts.entry(current_node.clone()).or_default();
+                    attempts.failures += 1;
+                    attempts.last_error = Some(e);
+                    let failures = attempts.failures;
+                    let policy = &node.policy;
+
+                    // Retry, then fall back, then give up; each step is checkpointed so a resumed
+                    // run doesn't start the count over
+                    if failures <= policy.max_retries {
                         state.status = WorkflowStatus::Retrying;
-                        warn!("🔄 Retrying node '{}' (attempt {}/{})",
-                            current_node, state.error_count, node.max_retries);
+                        let delay = retry_delay(policy, failures);
+                        warn!("🔄 Retrying node '{}' in {}s (attempt {}/{})",
+                            current_node, delay.as_secs(), failures + 1, policy.max_retries + 1);
+                        self.save_checkpoint(&state).await;
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    } else if let Some(fallback) = policy.fallback.clone() {
+                        warn!("↪️ Node '{}' failed {} time(s), falling back to '{}'",
+                            current_node, failures, fallback);
+                        if let Some(attempts) = state.node_attempts.get_mut(&current_node) {
+                            attempts.fell_back_to = Some(fallback.clone());
+                        }
+                        state.status = WorkflowStatus::Running;
+                        self.save_checkpoint(&state).await;
+                        current_node = fallback;
                         continue;
                     } else {
                         state.status = WorkflowStatus::Failed;
@@ -121,19 +170,11 @@ impl WorkflowExecutor {
 
             // Apply state update
             state.apply_update(update);
+            state.completed_nodes.push(current_node.clone());
 
             // Checkpoint periodically
             if iteration % self.config.checkpoint_every_n_steps == 0 {
-                if let Some(ref checkpointer) = self.checkpointer {
-                    match checkpointer.save(&state.workflow_id, &state.thread_id, &state).await {
-                        Ok(checkpoint_id) => {
-                            info!("💾 Checkpoint saved at step {}: {}", iteration, checkpoint_id);
-                        }
-                        Err(e) => {
-                            warn!("⚠️ Failed to save checkpoint: {}", e);
-                        }
-                    }
-                }
+                self.save_checkpoint(&state).await;
             }
 
             // Check for terminal states
@@ -154,7 +195,7 @@ impl WorkflowExecutor {
             }
 
             // Get next node(s)
-            let next_nodes = self.graph.get_next_nodes(&current_node, &state);
+            let next_nodes = self.next_nodes(&current_node, &state);
 
             if next_nodes.is_empty() {
                 info!("🏁 Reached end node");
@@ -203,19 +244,71 @@ impl WorkflowExecutor {
         self.run(checkpoint.state).await
     }
 
-    /// Execute node with retry logic
+    /// Run `state`, unless the same request (`resume_key`) already has an unfinished run on the
+    /// thread (interrupted by a crash or a failed step): that run then continues after its last
+    /// completed step, with the outputs of the steps before it
+    pub async fn run_or_resume(&self, mut state: WorkflowState, resume_key: &str) -> Result<WorkflowState, String> {
+        if let Some(ref checkpointer) = self.checkpointer {
+            match checkpointer.load_resumable(&state.thread_id, resume_key, RESUME_WINDOW_HOURS).await {
+                Ok(Some(checkpoint)) => return self.resume(checkpoint.state).await,
+                Ok(None) => {}
+                Err(e) => warn!("⚠️ Failed to look for a run to resume: {}", e),
+            }
+        }
+        state.metadata.insert(RESUME_KEY_METADATA_KEY.to_string(), resume_key.to_string());
+        self.run(state).await
+    }
+
+    /// Continue an unfinished run from its last checkpointed state
+    pub async fn resume(&self, mut resumed: WorkflowState) -> Result<WorkflowState, String> {
+        info!("🔄 Resuming workflow {} after {} completed step(s) (last: {})",
+            resumed.workflow_id,
+            resumed.completed_nodes.len(),
+            resumed.completed_nodes.last().map(String::as_str).unwrap_or("none"));
+        resumed.error_count = 0;
+        // A run that gave up gets a fresh set of attempts at the node it stopped on
+        if matches!(resumed.status, WorkflowStatus::Failed) {
+            resumed.node_attempts.remove(&resumed.current_node);
+        }
+        self.run(resumed).await
+    }
+
+    /// Nodes after `node`. A fallback without edges of its own continues where the node it stands
+    /// in for would have.
+    fn next_nodes(&self, node: &str, state: &WorkflowState) -> Vec<String> {
+        let next = self.graph.get_next_nodes(node, state);
+        match state.fallback_for(node) {
+            Some(failed) if next.is_empty() => self.graph.get_next_nodes(failed, state),
+            _ => next,
+        }
+    }
+
+    async fn save_checkpoint(&self, state: &WorkflowState) {
+        if let Some(ref checkpointer) = self.checkpointer {
+            match checkpointer.save(&state.workflow_id, &state.thread_id, state).await {
+                Ok(checkpoint_id) => {
+                    info!("💾 Checkpoint saved at node '{}': {}", state.current_node, checkpoint_id);
+                }
+                Err(e) => {
+                    warn!("⚠️ Failed to save checkpoint: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Execute node with its timeout
     async fn execute_node_with_retry(
         &self,
         node: &super::graph::Node,
         state: &WorkflowState,
     ) -> Result<StateUpdate, String> {
-        let node_timeout = Duration::from_secs(node.timeout_seconds);
+        let node_timeout = Duration::from_secs(node.policy.timeout_seconds);
 
         match timeout(node_timeout, node.function.execute(state)).await {
             Ok(Ok(update)) => Ok(update),
             Ok(Err(e)) => Err(e),
             Err(_) => Err(format!("Node '{}' timed out after {}s",
-                node.id, node.timeout_seconds)),
+                node.id, node.policy.timeout_seconds)),
         }
     }
 
@@ -238,6 +331,7 @@ impl WorkflowExecutor {
             match node.function.execute(&state).await {
                 Ok(update) => {
                     state.apply_update(update);
+                    state.completed_nodes.push(node_id.clone());
                 }
                 Err(e) => {
                     warn!("⚠️ Node {} failed: {}", node_id, e);
@@ -375,3 +469,102 @@ impl Default for ExecutorBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::graph::{NodeFunction, NodeType, StateGraphBuilder};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    /// Records its name as its output; the last step completes the workflow
+    struct Step {
+        name: &'static str,
+        last: bool,
+    }
+
+    #[async_trait]
+    impl NodeFunction for Step {
+        async fn execute(&self, _state: &WorkflowState) -> Result<StateUpdate, String> {
+            let update = StateUpdate::new().with_node_output(self.name.to_string(), serde_json::json!(true));
+            Ok(if self.last { update.with_status(WorkflowStatus::Completed) } else { update })
+        }
+    }
+
+    #[tokio::test]
+    async fn resumed_runs_continue_after_the_last_completed_step() {
+        let node = |name, last| Arc::new(Step { name, last }) as Arc<dyn NodeFunction>;
+        let graph = StateGraphBuilder::new()
+            .add_node("download", NodeType::Tool, node("download", false), "")
+            .add_node("render", NodeType::Tool, node("render", false), "")
+            .add_node("assemble", NodeType::End, node("assemble", true), "")
+            .set_entry_point("download")
+            .add_edge("download", "render")
+            .add_edge("render", "assemble")
+            .build()
+            .unwrap();
+        let executor = ExecutorBuilder::new().with_graph(graph).build().unwrap();
+
+        // As checkpointed right after "download" finished, before a crash
+        let mut state = WorkflowState::new("wf".to_string(), "thread".to_string(), "make it".to_string());
+        state.current_node = "download".to_string();
+        state.completed_nodes = vec!["download".to_string()];
+
+        let state = executor.run(state).await.unwrap();
+        assert!(matches!(state.status, WorkflowStatus::Completed));
+        assert_eq!(state.completed_nodes, ["download", "render", "assemble"]);
+        assert!(!state.node_outputs.contains_key("download"));
+    }
+
+    /// Always fails, like a TTS service that's down
+    struct Broken;
+
+    #[async_trait]
+    impl NodeFunction for Broken {
+        async fn execute(&self, _state: &WorkflowState) -> Result<StateUpdate, String> {
+            Err("service unavailable".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_nodes_retry_then_fall_back() {
+        let node = |name, last| Arc::new(Step { name, last }) as Arc<dyn NodeFunction>;
+        let graph = StateGraphBuilder::new()
+            .add_node("cloud_tts", NodeType::Tool, Arc::new(Broken), "")
+            .add_node("local_tts", NodeType::Tool, node("local_tts", false), "")
+            .add_node("mix", NodeType::End, node("mix", true), "")
+            .set_entry_point("cloud_tts")
+            .add_edge("cloud_tts", "mix")
+            .with_policy("cloud_tts", NodePolicy { max_retries: 1, fallback: Some("local_tts".into()), ..Default::default() })
+            .build()
+            .unwrap();
+        let executor = ExecutorBuilder::new().with_graph(graph).build().unwrap();
+
+        let state = WorkflowState::new("wf".to_string(), "thread".to_string(), "narrate it".to_string());
+        let state = executor.run(state).await.unwrap();
+        assert!(matches!(state.status, WorkflowStatus::Completed));
+        assert_eq!(state.completed_nodes, ["local_tts", "mix"]);
+        let attempts = &state.node_attempts["cloud_tts"];
+        assert_eq!(attempts.failures, 2);
+        assert_eq!(attempts.fell_back_to.as_deref(), Some("local_tts"));
+
+        // Without a fallback the run fails once the retries are used up
+        let graph = StateGraphBuilder::new()
+            .add_node("cloud_tts", NodeType::Tool, Arc::new(Broken), "")
+            .set_entry_point("cloud_tts")
+            .with_policy("cloud_tts", NodePolicy { max_retries: 0, ..Default::default() })
+            .build()
+            .unwrap();
+        let executor = ExecutorBuilder::new().with_graph(graph).build().unwrap();
+        let state = executor.run(WorkflowState::new("wf".into(), "thread".into(), "".into())).await.unwrap();
+        assert!(matches!(state.status, WorkflowStatus::Failed));
+        assert_eq!(state.node_attempts["cloud_tts"].failures, 1);
+
+        let missing = StateGraphBuilder::new()
+            .add_node("cloud_tts", NodeType::Tool, Arc::new(Broken), "")
+            .set_entry_point("cloud_tts")
+            .with_policy("cloud_tts", NodePolicy { fallback: Some("say".into()), ..Default::default() })
+            .build();
+        assert!(missing.err().unwrap().contains("non-existent node: say"));
+    }
+}
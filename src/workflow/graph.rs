@@ -54,14 +54,38 @@ impl std::fmt::Debug for EdgeType {
     }
 }
 
+/// What the executor does when a node fails
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodePolicy {
+    /// Attempts after the first failed one
+    pub max_retries: usize,
+    /// Time limit of each attempt
+    pub timeout_seconds: u64,
+    /// Wait before the first retry, doubled before each one after it
+    pub retry_delay_seconds: u64,
+    /// Node run in place of this one once its retries are used up (e.g. a local TTS tool standing in
+    /// for ElevenLabs). Without edges of its own it continues along this node's edges.
+    pub fallback: Option<String>,
+}
+
+impl Default for NodePolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            timeout_seconds: 300,
+            retry_delay_seconds: 0,
+            fallback: None,
+        }
+    }
+}
+
 /// Graph node
 pub struct Node {
     pub id: String,
     pub node_type: NodeType,
     pub function: Arc<dyn NodeFunction>,
     pub description: String,
-    pub max_retries: usize,
-    pub timeout_seconds: u64,
+    pub policy: NodePolicy,
 }
 
 /// StateGraph - The workflow graph
@@ -107,8 +131,7 @@ impl StateGraph {
             node_type,
             function,
             description,
-            max_retries: 3,
-            timeout_seconds: 300,
+            policy: NodePolicy::default(),
         };
 
         self.nodes.insert(id, node);
@@ -146,6 +169,18 @@ impl StateGraph {
         self
     }
 
+    /// Set the retry, timeout and fallback policy of a node added before
+    pub fn set_node_policy(&mut self, node_id: &str, policy: NodePolicy) -> &mut Self {
+        if self.compiled {
+            panic!("Cannot modify compiled graph");
+        }
+        match self.nodes.get_mut(node_id) {
+            Some(node) => node.policy = policy,
+            None => tracing::warn!("Policy for non-existent node: {}", node_id),
+        }
+        self
+    }
+
     /// Set entry point
     pub fn set_entry_point(&mut self, node_id: String) -> &mut Self {
         if self.compiled {
@@ -155,6 +190,15 @@ impl StateGraph {
         self
     }
 
+    /// Load a graph from a YAML/JSON workflow definition (see `workflow::definition`)
+    pub fn from_definition(
+        definition: &super::definition::WorkflowDefinition,
+        params: &HashMap<String, serde_json::Value>,
+        ctx: Arc<crate::agent::tool_executor::ToolExecutionContext>,
+    ) -> Result<Self, String> {
+        definition.build_graph(params, ctx)
+    }
+
     /// Compile graph (validate and optimize)
     pub fn compile(&mut self) -> Result<(), String> {
         // Validate entry point exists
@@ -192,6 +236,18 @@ impl StateGraph {
             }
         }
 
+        // Validate fallbacks point to other existing nodes
+        for node in self.nodes.values() {
+            if let Some(fallback) = &node.policy.fallback {
+                if fallback == &node.id {
+                    return Err(format!("Node '{}' can't be its own fallback", node.id));
+                }
+                if !self.nodes.contains_key(fallback) {
+                    return Err(format!("Fallback of '{}' is a non-existent node: {}", node.id, fallback));
+                }
+            }
+        }
+
         // Detect cycles (simple DFS check)
         if self.has_cycles() {
             tracing::warn!("Graph contains cycles - this is allowed but may loop indefinitely");
@@ -340,6 +396,11 @@ impl StateGraphBuilder {
         self
     }
 
+    pub fn with_policy(mut self, node_id: &str, policy: NodePolicy) -> Self {
+        self.graph.set_node_policy(node_id, policy);
+        self
+    }
+
     pub fn build(mut self) -> Result<StateGraph, String> {
         self.graph.compile()?;
         Ok(self.graph)
@@ -0,0 +1,366 @@
+// Localization matrix workflow - one master edit → per-language captioned/dubbed variants
+// Outputs are organized as <output_dir>/<locale>/<name>_<locale>.{mp4,srt}
+
+use super::state::{WorkflowState, StateUpdate, WorkflowStatus};
+use super::graph::{StateGraph, NodeType, NodeFunction, StateGraphBuilder};
+use super::executor::{ExecutorBuilder, ExecutorConfig};
+use super::checkpoint::{resume_key, WorkflowCheckpointer};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Settings shared by every localization node
+#[derive(Debug, Clone)]
+pub struct LocalizationConfig {
+    pub input_file: String,
+    pub languages: Vec<String>,
+    pub captions_file: Option<String>,
+    pub burn_captions: bool,
+    pub dub: bool,
+    pub voice: String,
+    pub title: Option<String>,
+    pub title_duration: f64,
+    pub output_dir: String,
+}
+
+impl LocalizationConfig {
+    fn base_name(&self) -> String {
+        std::path::Path::new(&self.input_file)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("video")
+            .to_string()
+    }
+
+    fn locale_path(&self, locale: &str, ext: &str) -> String {
+        format!("{}/{}/{}_{}.{}", self.output_dir, locale, self.base_name(), locale, ext)
+    }
+}
+
+/// A timed caption line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptionCue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Translated captions and title for one locale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleTranslation {
+    pub locale: String,
+    pub cues: Vec<CaptionCue>,
+    pub title: Option<String>,
+}
+
+/// Files produced for one locale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleOutput {
+    pub locale: String,
+    pub video_file: Option<String>,
+    pub captions_file: String,
+    pub dubbed: bool,
+    pub error: Option<String>,
+}
+
+/// Turn a language code or name into a safe directory name ("pt-BR" stays, "Brazilian Portuguese" → "Brazilian_Portuguese")
+pub fn locale_slug(locale: &str) -> String {
+    locale.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+/// Loads source captions from an SRT file or transcribes the master's audio
+pub struct SourceCaptionsNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<LocalizationConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for SourceCaptionsNode {
+    async fn execute(&self, _state: &WorkflowState) -> Result<StateUpdate, String> {
+        let cues: Vec<CaptionCue> = match &self.config.captions_file {
+            Some(path) => {
+                let text = tokio::fs::read_to_string(path).await
+                    .map_err(|e| format!("Failed to read captions file: {}", e))?;
+                crate::utils::parse_srt(&text)
+                    .into_iter()
+                    .map(|(start, end, text)| CaptionCue { start, end, text })
+                    .collect()
+            }
+            None => {
+                let words = crate::agent::tool_executor::transcribe_media_words(&self.config.input_file, false, &self.app_state).await?;
+                crate::visual::group_words_into_lines(&words, 8)
+                    .into_iter()
+                    .filter_map(|line| Some(CaptionCue {
+                        start: line.first()?.start,
+                        end: line.last()?.end,
+                        text: line.iter().map(|w| w.text.trim()).collect::<Vec<_>>().join(" "),
+                    }))
+                    .collect()
+            }
+        };
+
+        if cues.is_empty() && self.config.title.is_none() {
+            return Err("Nothing to localize: no captions found and no title given".to_string());
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Loaded {} source caption cues", cues.len()))
+            .with_node_output("source_captions".to_string(), serde_json::to_value(&cues).unwrap_or_default()))
+    }
+}
+
+/// Translates captions and the title card into every target language
+pub struct TranslateNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<LocalizationConfig>,
+}
+
+impl TranslateNode {
+    async fn translate(&self, claude: &crate::claude_client::ClaudeClient, lines: &[String], language: &str) -> Result<Vec<String>, String> {
+        let prompt = format!(
+            "Translate each string in this JSON array into {}. These are video captions and a title, so keep \
+             translations concise and natural for on-screen reading. Keep the array order and length exactly the same.\n\n\
+             Respond with ONLY the translated JSON array of strings.\n\n{}",
+            language,
+            serde_json::to_string(lines).unwrap_or_default()
+        );
+
+        let response = claude.generate_text(&prompt).await?;
+        let start = response.find('[').ok_or("No JSON array in translation")?;
+        let end = response.rfind(']').ok_or("No JSON array in translation")?;
+        let translated: Vec<String> = serde_json::from_str(response.get(start..=end).unwrap_or("[]"))
+            .map_err(|e| format!("Failed to parse {} translation: {}", language, e))?;
+
+        if translated.len() != lines.len() {
+            return Err(format!("{} translation returned {} lines, expected {}", language, translated.len(), lines.len()));
+        }
+        Ok(translated)
+    }
+}
+
+#[async_trait]
+impl NodeFunction for TranslateNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let cues: Vec<CaptionCue> = state.node_value("source_captions")?;
+        let claude = self.app_state.claude_client.as_ref()
+            .ok_or("Claude client not configured")?;
+
+        // Title rides along as the last line so each language is one request
+        let mut lines: Vec<String> = cues.iter().map(|c| c.text.clone()).collect();
+        if let Some(ref title) = self.config.title {
+            lines.push(title.clone());
+        }
+
+        let mut translations = Vec::with_capacity(self.config.languages.len());
+        for language in &self.config.languages {
+            tracing::info!("🌍 Translating {} lines into {}", lines.len(), language);
+            let mut translated = self.translate(claude, &lines, language).await?;
+            let title = self.config.title.as_ref().and_then(|_| translated.pop());
+
+            translations.push(LocaleTranslation {
+                locale: locale_slug(language),
+                cues: cues.iter()
+                    .zip(translated)
+                    .map(|(cue, text)| CaptionCue { start: cue.start, end: cue.end, text })
+                    .collect(),
+                title,
+            });
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Translated into {} languages", translations.len()))
+            .with_node_output("translate".to_string(), serde_json::to_value(&translations).unwrap_or_default()))
+    }
+}
+
+/// Writes captions, dubs and renders each locale's variant
+pub struct RenderLocalesNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<LocalizationConfig>,
+}
+
+impl RenderLocalesNode {
+    async fn dub_lines(&self, translation: &LocaleTranslation) -> Result<Vec<(f64, String)>, String> {
+        let elevenlabs = self.app_state.elevenlabs_client.as_ref()
+            .ok_or("ElevenLabs not configured - dubbing unavailable")?;
+        let voice_id = crate::elevenlabs_client::DefaultVoices::get_voice_id_by_name(&self.config.voice)
+            .unwrap_or(crate::elevenlabs_client::DefaultVoices::RACHEL);
+
+        let mut lines = Vec::with_capacity(translation.cues.len());
+        for (i, cue) in translation.cues.iter().enumerate() {
+            let audio = elevenlabs.text_to_speech(&cue.text, voice_id, Some("eleven_multilingual_v2"), None, Some("mp3_44100_128"))
+                .await
+                .map_err(|e| format!("Dubbing failed for line {}: {}", i + 1, e))?;
+            let path = format!("{}/{}/dub_{:03}.mp3", self.config.output_dir, translation.locale, i + 1);
+            tokio::fs::write(&path, &audio).await
+                .map_err(|e| format!("Failed to write dub line: {}", e))?;
+            lines.push((cue.start, path));
+        }
+        Ok(lines)
+    }
+
+    async fn render_locale(&self, translation: &LocaleTranslation) -> Result<LocaleOutput, String> {
+        let locale_dir = format!("{}/{}", self.config.output_dir, translation.locale);
+        tokio::fs::create_dir_all(&locale_dir).await
+            .map_err(|e| format!("Failed to create {}: {}", locale_dir, e))?;
+
+        let srt_file = self.config.locale_path(&translation.locale, "srt");
+        let cues: Vec<(f64, f64, String)> = translation.cues.iter()
+            .map(|c| (c.start, c.end, c.text.clone()))
+            .collect();
+        tokio::fs::write(&srt_file, crate::utils::build_srt(&cues)).await
+            .map_err(|e| format!("Failed to write captions: {}", e))?;
+
+        let dub_lines = if self.config.dub && !translation.cues.is_empty() {
+            Some(self.dub_lines(translation).await?)
+        } else {
+            None
+        };
+
+        // Each stage reads the previous stage's file; the last one writes the locale's video
+        let mut current = self.config.input_file.clone();
+        let mut stage = 0;
+        let mut next_path = || {
+            stage += 1;
+            format!("{}/stage{}.mp4", locale_dir, stage)
+        };
+
+        if let Some(ref lines) = dub_lines {
+            let out = next_path();
+            crate::audio::mix_dub_track(&current, lines, &out, 0.2)?;
+            current = out;
+        }
+        if self.config.burn_captions && !cues.is_empty() {
+            let out = next_path();
+            crate::visual::add_subtitles(&current, &srt_file, &out)?;
+            current = out;
+        }
+        if let Some(ref title) = translation.title {
+            let out = next_path();
+            crate::visual::add_title_card(&current, &out, title, self.config.title_duration)?;
+            current = out;
+        }
+
+        let video_file = self.config.locale_path(&translation.locale, "mp4");
+        if current == self.config.input_file {
+            tokio::fs::copy(&current, &video_file).await
+                .map_err(|e| format!("Failed to copy master: {}", e))?;
+        } else {
+            tokio::fs::rename(&current, &video_file).await
+                .map_err(|e| format!("Failed to move {} variant: {}", translation.locale, e))?;
+        }
+
+        Ok(LocaleOutput {
+            locale: translation.locale.clone(),
+            video_file: Some(video_file),
+            captions_file: srt_file,
+            dubbed: dub_lines.is_some(),
+            error: None,
+        })
+    }
+}
+
+#[async_trait]
+impl NodeFunction for RenderLocalesNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let translations: Vec<LocaleTranslation> = state.node_value("translate")?;
+
+        // One failed locale should not sink the others
+        let mut outputs = Vec::with_capacity(translations.len());
+        for translation in &translations {
+            let output = match self.render_locale(translation).await {
+                Ok(output) => output,
+                Err(e) => {
+                    tracing::warn!("⚠️ Locale {} failed: {}", translation.locale, e);
+                    LocaleOutput {
+                        locale: translation.locale.clone(),
+                        video_file: None,
+                        captions_file: self.config.locale_path(&translation.locale, "srt"),
+                        dubbed: false,
+                        error: Some(e),
+                    }
+                }
+            };
+            outputs.push(output);
+        }
+
+        let manifest: HashMap<String, &LocaleOutput> = outputs.iter().map(|o| (o.locale.clone(), o)).collect();
+        let manifest_path = format!("{}/locales.json", self.config.output_dir);
+        tokio::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest).unwrap_or_default()).await
+            .map_err(|e| format!("Failed to write locale manifest: {}", e))?;
+
+        let succeeded = outputs.iter().filter(|o| o.error.is_none()).count();
+        if succeeded == 0 {
+            return Err("Every locale failed to render".to_string());
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Rendered {}/{} locales", succeeded, outputs.len()))
+            .with_node_output("render_locales".to_string(), serde_json::to_value(&outputs).unwrap_or_default())
+            .with_status(WorkflowStatus::Completed))
+    }
+}
+
+/// Build the localization workflow graph
+pub fn build_localization_workflow(
+    app_state: Arc<crate::AppState>,
+    config: Arc<LocalizationConfig>,
+) -> Result<StateGraph, String> {
+    StateGraphBuilder::new()
+        .add_node(
+            "source_captions",
+            NodeType::Tool,
+            Arc::new(SourceCaptionsNode { app_state: app_state.clone(), config: config.clone() }),
+            "Load or transcribe the master captions"
+        )
+        .add_node(
+            "translate",
+            NodeType::Agent,
+            Arc::new(TranslateNode { app_state: app_state.clone(), config: config.clone() }),
+            "Translate captions and title per language"
+        )
+        .add_node(
+            "render_locales",
+            NodeType::End,
+            Arc::new(RenderLocalesNode { app_state, config }),
+            "Dub, caption and title each locale"
+        )
+        .set_entry_point("source_captions")
+        .add_edge("source_captions", "translate")
+        .add_edge("translate", "render_locales")
+        .build()
+}
+
+/// Run the localization workflow end to end and return the final state
+pub async fn run_localization_workflow(
+    app_state: Arc<crate::AppState>,
+    config: LocalizationConfig,
+    thread_id: String,
+) -> Result<WorkflowState, String> {
+    let graph = build_localization_workflow(app_state.clone(), Arc::new(config.clone()))?;
+
+    let executor = ExecutorBuilder::new()
+        .with_graph(graph)
+        .with_checkpointer(WorkflowCheckpointer::new(app_state.db_pool.clone()))
+        .with_config(ExecutorConfig {
+            max_iterations: 10,
+            checkpoint_every_n_steps: 1,
+            enable_parallel: false,
+            timeout_seconds: 1800,
+        })
+        .build()?;
+
+    let state = WorkflowState::new(
+        uuid::Uuid::new_v4().to_string(),
+        thread_id,
+        format!("Localize {} into {}", config.input_file, config.languages.join(", ")),
+    );
+
+    executor.run_or_resume(state, &resume_key(&config)).await
+}
@@ -5,3 +5,16 @@ pub mod checkpoint;
 pub mod executor;
 pub mod router;
 pub mod video_workflow;
+pub mod quiz_workflow;
+pub mod article_workflow;
+pub mod localization_workflow;
+pub mod definition;
+pub mod visualize;
+
+/// Path for an intermediate file next to a workflow's final output
+pub fn part_path(output_file: &str, suffix: &str) -> String {
+    match output_file.rsplit_once('.') {
+        Some((stem, _)) => format!("{}_{}", stem, suffix),
+        None => format!("{}_{}", output_file, suffix),
+    }
+}
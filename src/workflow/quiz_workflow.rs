@@ -0,0 +1,300 @@
+// Quiz/trivia video workflow - questions → cards → narration → assembly
+// Each stage is a graph node so long runs can be checkpointed and resumed
+
+use super::state::{WorkflowState, StateUpdate, WorkflowStatus};
+use super::graph::{StateGraph, NodeType, NodeFunction, StateGraphBuilder};
+use super::executor::{ExecutorBuilder, ExecutorConfig};
+use super::checkpoint::{resume_key, WorkflowCheckpointer};
+use super::part_path;
+use crate::types::QuizQuestion;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Settings shared by every quiz workflow node
+#[derive(Debug, Clone)]
+pub struct QuizConfig {
+    pub topic: String,
+    pub question_count: usize,
+    pub countdown_seconds: u32,
+    pub reveal_seconds: f64,
+    pub width: u32,
+    pub height: u32,
+    pub background_color: String,
+    pub voice: String,
+    pub music_prompt: Option<String>,
+    pub output_file: String,
+}
+
+/// Parse the LLM's question list, tolerating code fences and surrounding prose
+pub fn parse_quiz_questions(text: &str) -> Result<Vec<QuizQuestion>, String> {
+    let start = text.find('[').ok_or("No JSON array found in question list")?;
+    let end = text.rfind(']').ok_or("No JSON array found in question list")?;
+    if end < start {
+        return Err("Malformed question list".to_string());
+    }
+
+    let questions: Vec<QuizQuestion> = serde_json::from_str(&text[start..=end])
+        .map_err(|e| format!("Failed to parse questions: {}", e))?;
+
+    let valid: Vec<QuizQuestion> = questions
+        .into_iter()
+        .filter(|q| !q.question.trim().is_empty() && q.options.len() >= 2 && q.answer_index < q.options.len())
+        .collect();
+
+    if valid.is_empty() {
+        return Err("No valid questions in question list".to_string());
+    }
+    Ok(valid)
+}
+
+fn output_list(state: &WorkflowState, node: &str) -> Vec<String> {
+    state.node_outputs.get(node)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn questions_from_state(state: &WorkflowState) -> Result<Vec<QuizQuestion>, String> {
+    state.node_outputs.get("write_questions")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .ok_or_else(|| "Questions have not been written yet".to_string())
+}
+
+/// Writes the questions with Claude
+pub struct WriteQuestionsNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<QuizConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for WriteQuestionsNode {
+    async fn execute(&self, _state: &WorkflowState) -> Result<StateUpdate, String> {
+        tracing::info!("❓ Writing {} quiz questions about '{}'", self.config.question_count, self.config.topic);
+
+        let claude = self.app_state.claude_client.as_ref()
+            .ok_or("Claude client not configured")?;
+
+        let prompt = format!(
+            "Write {} multiple-choice trivia questions about: {}\n\n\
+             Each question must have 3 or 4 short options (under 40 characters) and exactly one correct answer. \
+             Keep questions under 120 characters.\n\n\
+             Respond with ONLY a JSON array in this format:\n\
+             [{{\"question\": \"...\", \"options\": [\"...\", \"...\", \"...\"], \"answer_index\": 0}}]",
+            self.config.question_count, self.config.topic
+        );
+
+        let response = claude.generate_text(&prompt).await?;
+        let mut questions = parse_quiz_questions(&response)?;
+        questions.truncate(self.config.question_count);
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Wrote {} questions", questions.len()))
+            .with_node_output("write_questions".to_string(), serde_json::to_value(&questions).unwrap_or_default()))
+    }
+}
+
+/// Renders one silent card per question
+pub struct RenderCardsNode {
+    config: Arc<QuizConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for RenderCardsNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let questions = questions_from_state(state)?;
+        let config = self.config.clone();
+
+        let cards = tokio::task::spawn_blocking(move || {
+            questions.iter().enumerate().map(|(i, q)| {
+                let card = part_path(&config.output_file, &format!("card{}.mp4", i + 1));
+                crate::visual::render_quiz_card(
+                    &card,
+                    q,
+                    config.countdown_seconds,
+                    config.reveal_seconds,
+                    config.width,
+                    config.height,
+                    &config.background_color,
+                )?;
+                Ok(card)
+            }).collect::<Result<Vec<String>, String>>()
+        })
+        .await
+        .map_err(|e| format!("Card rendering task failed: {}", e))??;
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Rendered {} quiz cards", cards.len()))
+            .with_node_output("render_cards".to_string(), serde_json::json!(cards)))
+    }
+}
+
+/// Voices each question over its card (cards stay silent without ElevenLabs)
+pub struct NarrateNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<QuizConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for NarrateNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let questions = questions_from_state(state)?;
+        let cards = output_list(state, "render_cards");
+
+        let Some(ref elevenlabs) = self.app_state.elevenlabs_client else {
+            tracing::warn!("⚠️ ElevenLabs not configured, quiz cards will not be narrated");
+            return Ok(StateUpdate::new()
+                .with_node_output("narrate".to_string(), serde_json::json!(cards)));
+        };
+
+        let voice_id = crate::elevenlabs_client::DefaultVoices::get_voice_id_by_name(&self.config.voice)
+            .unwrap_or(crate::elevenlabs_client::DefaultVoices::RACHEL);
+
+        let mut narrated = Vec::with_capacity(cards.len());
+        for (i, (card, question)) in cards.iter().zip(questions.iter()).enumerate() {
+            let script = format!(
+                "{} {}",
+                question.question,
+                question.options.iter()
+                    .enumerate()
+                    .map(|(j, o)| format!("{}: {}.", (b'A' + j as u8) as char, o))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+
+            let audio = elevenlabs.text_to_speech(&script, voice_id, Some("eleven_flash_v2_5"), None, Some("mp3_44100_128"))
+                .await
+                .map_err(|e| format!("Narration failed for question {}: {}", i + 1, e))?;
+
+            let audio_file = part_path(&self.config.output_file, &format!("voice{}.mp3", i + 1));
+            tokio::fs::write(&audio_file, &audio).await
+                .map_err(|e| format!("Failed to write narration: {}", e))?;
+
+            let voiced = part_path(&self.config.output_file, &format!("card{}_voiced.mp4", i + 1));
+            crate::audio::replace_audio_padded(card, &audio_file, &voiced)?;
+            narrated.push(voiced);
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Narrated {} quiz cards", narrated.len()))
+            .with_node_output("narrate".to_string(), serde_json::json!(narrated)))
+    }
+}
+
+/// Joins the cards and lays optional background music underneath
+pub struct AssembleNode {
+    app_state: Arc<crate::AppState>,
+    config: Arc<QuizConfig>,
+}
+
+#[async_trait]
+impl NodeFunction for AssembleNode {
+    async fn execute(&self, state: &WorkflowState) -> Result<StateUpdate, String> {
+        let cards = output_list(state, "narrate");
+        if cards.is_empty() {
+            return Err("No quiz cards to assemble".to_string());
+        }
+
+        let output = self.config.output_file.clone();
+        let joined = part_path(&self.config.output_file, "joined.mp4");
+        crate::core::merge_videos(&cards, &joined)?;
+
+        let mut music_note = "none";
+        let music = match (&self.config.music_prompt, &self.app_state.elevenlabs_client) {
+            (Some(prompt), Some(elevenlabs)) => {
+                let duration = crate::core::get_video_duration(&joined)?;
+                match elevenlabs.generate_music(prompt, (duration * 1000.0) as u32, 120).await {
+                    Ok(bytes) => Some(bytes),
+                    Err(e) => {
+                        tracing::warn!("⚠️ Background music generation failed: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(bytes) = music {
+            let music_file = part_path(&self.config.output_file, "music.mp3");
+            tokio::fs::write(&music_file, &bytes).await
+                .map_err(|e| format!("Failed to write music: {}", e))?;
+            crate::audio::mix_background_music(&joined, &music_file, &output, 0.15)?;
+            music_note = "generated";
+        } else {
+            tokio::fs::rename(&joined, &output).await
+                .map_err(|e| format!("Failed to move quiz video: {}", e))?;
+        }
+
+        Ok(StateUpdate::new()
+            .with_message("assistant", format!("Quiz video saved to {}", output))
+            .with_node_output("assemble".to_string(), serde_json::json!({
+                "output_file": output,
+                "cards": cards.len(),
+                "music": music_note,
+            }))
+            .with_status(WorkflowStatus::Completed))
+    }
+}
+
+/// Build the quiz workflow graph
+pub fn build_quiz_workflow(
+    app_state: Arc<crate::AppState>,
+    config: Arc<QuizConfig>,
+) -> Result<StateGraph, String> {
+    StateGraphBuilder::new()
+        .add_node(
+            "write_questions",
+            NodeType::Agent,
+            Arc::new(WriteQuestionsNode { app_state: app_state.clone(), config: config.clone() }),
+            "Write multiple-choice questions for the topic"
+        )
+        .add_node(
+            "render_cards",
+            NodeType::Tool,
+            Arc::new(RenderCardsNode { config: config.clone() }),
+            "Render a countdown/reveal card per question"
+        )
+        .add_node(
+            "narrate",
+            NodeType::Tool,
+            Arc::new(NarrateNode { app_state: app_state.clone(), config: config.clone() }),
+            "Narrate each question with text-to-speech"
+        )
+        .add_node(
+            "assemble",
+            NodeType::End,
+            Arc::new(AssembleNode { app_state, config }),
+            "Join cards and add background music"
+        )
+        .set_entry_point("write_questions")
+        .add_edge("write_questions", "render_cards")
+        .add_edge("render_cards", "narrate")
+        .add_edge("narrate", "assemble")
+        .build()
+}
+
+/// Run the quiz workflow end to end and return the final state
+pub async fn run_quiz_workflow(
+    app_state: Arc<crate::AppState>,
+    config: QuizConfig,
+    thread_id: String,
+) -> Result<WorkflowState, String> {
+    let graph = build_quiz_workflow(app_state.clone(), Arc::new(config.clone()))?;
+
+    let executor = ExecutorBuilder::new()
+        .with_graph(graph)
+        .with_checkpointer(WorkflowCheckpointer::new(app_state.db_pool.clone()))
+        .with_config(ExecutorConfig {
+            max_iterations: 10,
+            checkpoint_every_n_steps: 1,
+            enable_parallel: false,
+            timeout_seconds: 900,
+        })
+        .build()?;
+
+    let state = WorkflowState::new(
+        uuid::Uuid::new_v4().to_string(),
+        thread_id,
+        format!("Quiz video about {}", config.topic),
+    );
+
+    executor.run_or_resume(state, &resume_key(&config)).await
+}
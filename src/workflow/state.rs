@@ -30,6 +30,10 @@ pub struct WorkflowState {
     /// Current node in the graph
     pub current_node: String,
 
+    /// Nodes that finished, in order (the step cursor); a resumed run continues after the last one
+    #[serde(default)]
+    pub completed_nodes: Vec<String>,
+
     /// Conversation messages (appended, not replaced)
     pub messages: Vec<StateMessage>,
 
@@ -46,6 +50,14 @@ pub struct WorkflowState {
     pub errors: Vec<WorkflowError>,
     pub error_count: usize,
 
+    /// Failed attempts per node and the fallbacks taken, checkpointed so a resumed run keeps count
+    #[serde(default)]
+    pub node_attempts: HashMap<String, NodeAttempts>,
+
+    /// When each node last ran
+    #[serde(default)]
+    pub node_timings: HashMap<String, NodeTiming>,
+
     /// Metadata (user_id, session_id, etc.)
     pub metadata: HashMap<String, String>,
 
@@ -98,6 +110,35 @@ pub struct FileReference {
     pub created_by_node: String,
 }
 
+/// When a node's last attempt started and finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTiming {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+}
+
+impl NodeTiming {
+    pub fn started() -> Self {
+        Self { started_at: Utc::now(), finished_at: None, duration_ms: None }
+    }
+
+    pub fn finish(&mut self) {
+        let now = Utc::now();
+        self.duration_ms = Some((now - self.started_at).num_milliseconds().max(0) as u64);
+        self.finished_at = Some(now);
+    }
+}
+
+/// How a node's attempts went so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeAttempts {
+    pub failures: usize,
+    pub last_error: Option<String>,
+    /// Node that ran in its place once its retries were used up
+    pub fell_back_to: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowError {
     pub node: String,
@@ -115,6 +156,7 @@ impl WorkflowState {
             workflow_id,
             thread_id,
             current_node: "start".to_string(),
+            completed_nodes: Vec::new(),
             messages: vec![StateMessage {
                 role: "user".to_string(),
                 content: user_input,
@@ -126,6 +168,8 @@ impl WorkflowState {
             files: HashMap::new(),
             errors: Vec::new(),
             error_count: 0,
+            node_attempts: HashMap::new(),
+            node_timings: HashMap::new(),
             metadata: HashMap::new(),
             created_at: now,
             updated_at: now,
@@ -196,6 +240,21 @@ impl WorkflowState {
         self.error_count > 0 && self.error_count < max_retries
     }
 
+    /// A node's stored output read back as `T`
+    pub fn node_value<T: serde::de::DeserializeOwned>(&self, node: &str) -> Result<T, String> {
+        self.node_outputs.get(node)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .ok_or_else(|| format!("Missing output from '{}' step", node))
+    }
+
+    /// The failed node that `node` ran in place of, if it's a fallback that was taken
+    pub fn fallback_for(&self, node: &str) -> Option<&str> {
+        self.node_attempts
+            .iter()
+            .find(|(_, attempts)| attempts.fell_back_to.as_deref() == Some(node))
+            .map(|(failed, _)| failed.as_str())
+    }
+
     /// Get last user message
     pub fn get_last_user_message(&self) -> Option<&StateMessage> {
         self.messages.iter().rev().find(|m| m.role == "user")
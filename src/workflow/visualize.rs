@@ -0,0 +1,324 @@
+// Run diagrams - a workflow definition run drawn from its checkpoints
+//
+// A run keeps the definition it started from in its metadata. Together with the checkpointed state
+// that gives each node a status, its timings and its output, as JSON for the dashboard or as
+// Mermaid/DOT text for a live execution diagram.
+
+use super::definition::{EdgeCondition, WorkflowDefinition};
+use super::state::{WorkflowState, WorkflowStatus};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    Pending,
+    Running,
+    Retrying,
+    Completed,
+    /// Ran out of retries and its fallback ran instead
+    FellBack,
+    Failed,
+    /// The run ended without reaching it
+    Skipped,
+}
+
+impl NodeStatus {
+    fn name(self) -> &'static str {
+        match self {
+            NodeStatus::Pending => "pending",
+            NodeStatus::Running => "running",
+            NodeStatus::Retrying => "retrying",
+            NodeStatus::Completed => "completed",
+            NodeStatus::FellBack => "fell_back",
+            NodeStatus::Failed => "failed",
+            NodeStatus::Skipped => "skipped",
+        }
+    }
+
+    /// Fill and stroke colors in diagrams
+    fn colors(self) -> (&'static str, &'static str) {
+        match self {
+            NodeStatus::Pending => ("#eeeeee", "#9e9e9e"),
+            NodeStatus::Running => ("#bbdefb", "#1565c0"),
+            NodeStatus::Retrying => ("#ffe0b2", "#ef6c00"),
+            NodeStatus::Completed => ("#c8e6c9", "#2e7d32"),
+            NodeStatus::FellBack => ("#fff9c4", "#f9a825"),
+            NodeStatus::Failed => ("#ffcdd2", "#c62828"),
+            NodeStatus::Skipped => ("#fafafa", "#bdbdbd"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunNode {
+    pub id: String,
+    pub tool: String,
+    pub description: Option<String>,
+    pub status: NodeStatus,
+    pub failed_attempts: usize,
+    pub last_error: Option<String>,
+    pub fell_back_to: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+    pub output: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunEdge {
+    pub from: String,
+    pub to: String,
+    /// When the edge is taken: empty for always, "success", "failure", "output contains ..." or "fallback"
+    pub label: String,
+    pub fallback: bool,
+}
+
+/// A definition run's graph with where each node stands
+#[derive(Debug, Clone, Serialize)]
+pub struct RunGraph {
+    pub name: String,
+    pub entry: Option<String>,
+    pub nodes: Vec<RunNode>,
+    pub edges: Vec<RunEdge>,
+}
+
+impl RunGraph {
+    /// The graph of a run started from a workflow definition; None for other runs
+    pub fn of_run(state: &WorkflowState) -> Option<Self> {
+        Some(Self::new(&WorkflowDefinition::of_run(state)?, state))
+    }
+
+    pub fn new(definition: &WorkflowDefinition, state: &WorkflowState) -> Self {
+        let nodes = definition
+            .nodes
+            .iter()
+            .map(|node| {
+                let attempts = state.node_attempts.get(&node.id);
+                let timing = state.node_timings.get(&node.id);
+                RunNode {
+                    id: node.id.clone(),
+                    tool: node.tool.clone(),
+                    description: node.description.clone(),
+                    status: node_status(&node.id, state),
+                    failed_attempts: attempts.map(|a| a.failures).unwrap_or(0),
+                    last_error: attempts.and_then(|a| a.last_error.clone()),
+                    fell_back_to: attempts.and_then(|a| a.fell_back_to.clone()),
+                    started_at: timing.map(|t| t.started_at),
+                    finished_at: timing.and_then(|t| t.finished_at),
+                    duration_ms: timing.and_then(|t| t.duration_ms),
+                    output: state.node_outputs.get(&node.id).cloned(),
+                }
+            })
+            .collect();
+
+        let mut edges: Vec<RunEdge> = definition
+            .edges
+            .iter()
+            .map(|edge| RunEdge {
+                from: edge.from.clone(),
+                to: edge.to.clone(),
+                label: match &edge.when {
+                    EdgeCondition::Always => String::new(),
+                    EdgeCondition::Success => "success".to_string(),
+                    EdgeCondition::Failure => "failure".to_string(),
+                    EdgeCondition::OutputContains(text) => format!("output contains '{}'", text),
+                },
+                fallback: false,
+            })
+            .collect();
+        edges.extend(definition.nodes.iter().filter_map(|node| {
+            Some(RunEdge {
+                from: node.id.clone(),
+                to: node.fallback.clone()?,
+                label: "fallback".to_string(),
+                fallback: true,
+            })
+        }));
+
+        Self {
+            name: definition.name.clone(),
+            entry: definition.entry_node().map(str::to_string),
+            nodes,
+            edges,
+        }
+    }
+
+    /// A Mermaid flowchart, nodes colored by status
+    pub fn to_mermaid(&self) -> String {
+        let mut lines = vec!["flowchart TD".to_string()];
+        for (i, node) in self.nodes.iter().enumerate() {
+            let mut label = format!("{}<br/>{}", node.id, node.tool);
+            if let Some(ms) = node.duration_ms {
+                label.push_str(&format!("<br/>{}", seconds(ms)));
+            }
+            lines.push(format!("    n{}[\"{}\"]:::{}", i, mermaid_text(&label), node.status.name()));
+        }
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (self.index_of(&edge.from), self.index_of(&edge.to)) else {
+                continue;
+            };
+            let arrow = if edge.fallback { "-.->" } else { "-->" };
+            if edge.label.is_empty() {
+                lines.push(format!("    n{} {} n{}", from, arrow, to));
+            } else {
+                lines.push(format!("    n{} {}|\"{}\"| n{}", from, arrow, mermaid_text(&edge.label), to));
+            }
+        }
+        for status in self.statuses() {
+            let (fill, stroke) = status.colors();
+            lines.push(format!("    classDef {} fill:{},stroke:{}", status.name(), fill, stroke));
+        }
+        lines.join("\n")
+    }
+
+    /// A Graphviz DOT digraph, nodes colored by status
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec![
+            format!("digraph \"{}\" {{", dot_text(&self.name)),
+            "    rankdir=TB;".to_string(),
+            "    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];".to_string(),
+        ];
+        for node in &self.nodes {
+            let mut label = format!("{}\\n{}", dot_text(&node.id), dot_text(&node.tool));
+            if let Some(ms) = node.duration_ms {
+                label.push_str(&format!("\\n{}", seconds(ms)));
+            }
+            let (fill, stroke) = node.status.colors();
+            lines.push(format!(
+                "    \"{}\" [label=\"{}\", fillcolor=\"{}\", color=\"{}\", tooltip=\"{}\"];",
+                dot_text(&node.id),
+                label,
+                fill,
+                stroke,
+                node.status.name()
+            ));
+        }
+        for edge in &self.edges {
+            let mut attributes = Vec::new();
+            if !edge.label.is_empty() {
+                attributes.push(format!("label=\"{}\"", dot_text(&edge.label)));
+            }
+            if edge.fallback {
+                attributes.push("style=dashed".to_string());
+            }
+            let attributes = if attributes.is_empty() { String::new() } else { format!(" [{}]", attributes.join(", ")) };
+            lines.push(format!("    \"{}\" -> \"{}\"{};", dot_text(&edge.from), dot_text(&edge.to), attributes));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    fn index_of(&self, id: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.id == id)
+    }
+
+    /// The statuses in use, for their class definitions
+    fn statuses(&self) -> Vec<NodeStatus> {
+        let mut statuses: Vec<NodeStatus> = Vec::new();
+        for node in &self.nodes {
+            if !statuses.contains(&node.status) {
+                statuses.push(node.status);
+            }
+        }
+        statuses
+    }
+}
+
+/// Where a node stands in the run as last checkpointed
+fn node_status(id: &str, state: &WorkflowState) -> NodeStatus {
+    let current = state.current_node == id;
+    if state.node_attempts.get(id).is_some_and(|a| a.fell_back_to.is_some()) {
+        NodeStatus::FellBack
+    } else if state.completed_nodes.iter().any(|node| node == id) {
+        NodeStatus::Completed
+    } else if current {
+        match state.status {
+            WorkflowStatus::Retrying => NodeStatus::Retrying,
+            WorkflowStatus::Failed | WorkflowStatus::Cancelled => NodeStatus::Failed,
+            _ => NodeStatus::Running,
+        }
+    } else if matches!(state.status, WorkflowStatus::Completed | WorkflowStatus::Failed | WorkflowStatus::Cancelled) {
+        NodeStatus::Skipped
+    } else {
+        NodeStatus::Pending
+    }
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+/// Text safe inside a quoted Mermaid label
+fn mermaid_text(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// Text safe inside a quoted DOT string
+fn dot_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::definition::DEFINITION_METADATA_KEY;
+    use super::super::state::{NodeAttempts, NodeTiming};
+
+    #[test]
+    fn runs_are_drawn_with_each_node_status() {
+        let definition = WorkflowDefinition::parse(
+            r#"
+name: voiced
+nodes:
+  - id: voice
+    tool: generate_text_to_speech
+    args: { text: "Hello", output_file: "outputs/voice.mp3" }
+    retries: 1
+    fallback: local_voice
+  - id: local_voice
+    tool: generate_text_to_speech
+    args: { text: "Hello", output_file: "outputs/voice.mp3" }
+  - id: mix
+    tool: add_audio
+    args: { input_file: "uploads/a.mp4", audio_file: "{{nodes.voice.output_file}}", output_file: "outputs/final.mp4" }
+edges:
+  - { from: voice, to: mix }
+"#,
+        )
+        .unwrap_or_else(|e| panic!("{}", e));
+
+        // Checkpointed while "mix" runs, after the voiceover fell back to the local one
+        let mut state = WorkflowState::new("wf".into(), "session".into(), "".into());
+        state.metadata.insert(DEFINITION_METADATA_KEY.into(), serde_json::to_string(&definition).unwrap());
+        state.status = WorkflowStatus::Running;
+        state.current_node = "mix".into();
+        state.completed_nodes = vec!["local_voice".into()];
+        state.node_attempts.insert(
+            "voice".into(),
+            NodeAttempts { failures: 2, last_error: Some("quota exceeded".into()), fell_back_to: Some("local_voice".into()) },
+        );
+        let mut timing = NodeTiming::started();
+        timing.finish();
+        state.node_timings.insert("local_voice".into(), timing);
+
+        let graph = RunGraph::of_run(&state).unwrap();
+        let statuses: Vec<NodeStatus> = graph.nodes.iter().map(|n| n.status).collect();
+        assert_eq!(statuses, [NodeStatus::FellBack, NodeStatus::Completed, NodeStatus::Running]);
+        assert_eq!(graph.nodes[0].failed_attempts, 2);
+        assert!(graph.nodes[1].duration_ms.is_some());
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD"));
+        assert!(mermaid.contains("n0 --> n2"), "{}", mermaid);
+        assert!(mermaid.contains("n0 -.->|\"fallback\"| n1"), "{}", mermaid);
+        assert!(mermaid.contains(":::running") && mermaid.contains("classDef fell_back"), "{}", mermaid);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"voice\" -> \"local_voice\" [label=\"fallback\", style=dashed];"), "{}", dot);
+        assert!(dot.trim_end().ends_with('}'));
+
+        assert!(RunGraph::of_run(&WorkflowState::new("wf".into(), "s".into(), "".into())).is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::chaos::{ChaosSend, ChaosTarget};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -54,7 +55,7 @@ impl YouTubeAnalyticsClient {
                 ("sort", "-estimatedMinutesWatched".to_string()),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -101,7 +102,7 @@ impl YouTubeAnalyticsClient {
                 ("metrics", metrics.to_string()),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -181,7 +182,7 @@ impl YouTubeAnalyticsClient {
                 ("sort", "-views".to_string()),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -214,7 +215,7 @@ impl YouTubeAnalyticsClient {
                 ("sort", "-views".to_string()),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -248,7 +249,7 @@ impl YouTubeAnalyticsClient {
                 ("sort", "-viewerPercentage".to_string()),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
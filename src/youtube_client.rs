@@ -1,6 +1,7 @@
 // YouTube Data API v3 client for video uploads and channel management
 // Docs: https://developers.google.com/youtube/v3
 
+use crate::chaos::{ChaosSend, ChaosTarget};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -127,7 +128,7 @@ impl YouTubeClient {
                 ("mine", "true"),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -226,7 +227,7 @@ impl YouTubeClient {
             ])
             .header("Authorization", format!("Bearer {}", access_token))
             .multipart(form)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -261,7 +262,7 @@ impl YouTubeClient {
         let response = self.client
             .post(url)
             .json(&params)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -273,6 +274,26 @@ impl YouTubeClient {
         Ok(token_response)
     }
 
+    /// Check whether Google still accepts an access token
+    pub async fn validate_access_token(
+        &self,
+        access_token: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let response = self.client
+            .get("https://oauth2.googleapis.com/tokeninfo")
+            .query(&[("access_token", access_token)])
+            .send_with_chaos(ChaosTarget::YouTube)
+            .await?;
+
+        if response.status().is_success() {
+            return Ok(true);
+        }
+        if response.status().is_client_error() {
+            return Ok(false);
+        }
+        Err(format!("Token validation failed with status {}", response.status()).into())
+    }
+
     // ========================================================================
     // Video Management Methods
     // ========================================================================
@@ -294,7 +315,7 @@ impl YouTubeClient {
             .delete(url)
             .query(&[("id", video_id)])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -307,7 +328,7 @@ impl YouTubeClient {
         Ok(())
     }
 
-    /// Update video metadata (title, description, privacy, tags)
+    /// Update video metadata (title, description, privacy, tags, scheduled publish time)
     ///
     /// Required scope: https://www.googleapis.com/auth/youtube.force-ssl
     pub async fn update_video(
@@ -319,6 +340,7 @@ impl YouTubeClient {
         privacy_status: Option<&str>,
         category_id: Option<&str>,
         tags: Option<Vec<String>>,
+        publish_at: Option<&str>,
     ) -> Result<VideoUpdateResponse, Box<dyn std::error::Error + Send + Sync>> {
         let url = "https://www.googleapis.com/youtube/v3/videos";
 
@@ -343,6 +365,10 @@ impl YouTubeClient {
         if let Some(p) = privacy_status {
             status["privacyStatus"] = json!(p);
         }
+        // YouTube only schedules private videos; they go public at publishAt
+        if let Some(at) = publish_at {
+            status["publishAt"] = json!(at);
+        }
 
         let body = json!({
             "id": video_id,
@@ -357,7 +383,7 @@ impl YouTubeClient {
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -398,7 +424,7 @@ impl YouTubeClient {
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", content_type)
             .body(image_data)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -448,7 +474,7 @@ impl YouTubeClient {
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -479,7 +505,7 @@ impl YouTubeClient {
                 ("maxResults", "50"),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -528,7 +554,7 @@ impl YouTubeClient {
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -555,7 +581,7 @@ impl YouTubeClient {
             .delete(url)
             .query(&[("id", playlist_id)])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -603,7 +629,7 @@ impl YouTubeClient {
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -628,7 +654,7 @@ impl YouTubeClient {
             .delete(url)
             .query(&[("id", playlist_item_id)])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -675,7 +701,7 @@ impl YouTubeClient {
             request = request.query(&[("key", &self.api_key)]);
         }
 
-        let response = request.send().await?;
+        let response = request.send_with_chaos(ChaosTarget::YouTube).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -718,7 +744,7 @@ impl YouTubeClient {
             request = request.query(&[("key", &self.api_key)]);
         }
 
-        let response = request.send().await?;
+        let response = request.send_with_chaos(ChaosTarget::YouTube).await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -757,7 +783,7 @@ impl YouTubeClient {
             .client
             .get(url)
             .query(&query_params)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -787,7 +813,7 @@ impl YouTubeClient {
                 ("maxResults", &max_results.to_string()),
                 ("key", &self.api_key),
             ])
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -815,7 +841,7 @@ impl YouTubeClient {
                 ("id", video_id),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -852,7 +878,7 @@ impl YouTubeClient {
                 ("order", "time"),  // Most recent first
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -887,7 +913,7 @@ impl YouTubeClient {
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -914,7 +940,7 @@ impl YouTubeClient {
             .delete(url)
             .query(&[("id", comment_id)])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -947,7 +973,7 @@ impl YouTubeClient {
                 ("videoId", video_id),
             ])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -1007,7 +1033,7 @@ impl YouTubeClient {
             ])
             .header("Authorization", format!("Bearer {}", access_token))
             .multipart(form)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -1037,7 +1063,7 @@ impl YouTubeClient {
             .delete(url)
             .query(&[("id", caption_id)])
             .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -1097,7 +1123,7 @@ impl YouTubeClient {
             .header("X-Upload-Content-Length", file_size.to_string())
             .header("X-Upload-Content-Type", "video/*")
             .json(&metadata)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         if !response.status().is_success() {
@@ -1146,7 +1172,7 @@ impl YouTubeClient {
             .header("Content-Range", content_range)
             .header("Content-Type", "video/*")
             .body(chunk_data)
-            .send()
+            .send_with_chaos(ChaosTarget::YouTube)
             .await?;
 
         let status = response.status();
@@ -1232,7 +1258,7 @@ pub async fn exchange_code_for_token(
     let response = client
         .post(url)
         .json(&params)
-        .send()
+        .send_with_chaos(ChaosTarget::YouTube)
         .await?;
 
     if !response.status().is_success() {
@@ -1263,7 +1289,7 @@ pub async fn get_google_user_info(
     let response = client
         .get(url)
         .header("Authorization", format!("Bearer {}", access_token))
-        .send()
+        .send_with_chaos(ChaosTarget::YouTube)
         .await?;
 
     if !response.status().is_success() {